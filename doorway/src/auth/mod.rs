@@ -5,13 +5,17 @@
 //! - API key authentication (for backward compatibility)
 //! - Permission levels for operation authorization
 //! - Password hashing with Argon2
+//! - WebAuthn passkey ceremonies for passwordless login
 
 pub mod api_key;
 pub mod jwt;
 pub mod password;
 pub mod permissions;
+pub mod policy;
+pub mod webauthn;
 
 pub use api_key::ApiKeyValidator;
 pub use jwt::{extract_token_from_header, Claims, JwtValidator, TokenInput, TokenValidationResult};
 pub use password::{hash_password, verify_password};
 pub use permissions::{get_required_permission, is_operation_allowed, PermissionLevel};
+pub use webauthn::{RelyingParty, WebAuthnError};
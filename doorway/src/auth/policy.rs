@@ -0,0 +1,431 @@
+//! Per-zome-function authorization policies
+//!
+//! Operators restrict zome functions without code changes by writing an
+//! ordered list of allow/deny rules, loaded from `ZOME_POLICY_FILE` and
+//! replaceable at runtime through `PUT /admin/zome-policy`:
+//!
+//! ```json
+//! { "rules": [
+//!   { "effect": "allow", "functions": ["delete_path"], "agents": ["uhCAk..."] },
+//!   { "effect": "deny", "functions": ["delete_path"] },
+//!   { "effect": "deny", "functions": ["create_*", "update_*"], "scopes": ["public"] },
+//!   { "effect": "deny", "zomes": ["content_store"], "functions": ["create_content"],
+//!     "when": ["input.reach == \"commons\""], "scopes": ["authenticated"] }
+//! ] }
+//! ```
+//!
+//! Rules are checked in order and the first one matching a call decides it;
+//! a call no rule matches is allowed. A field a rule leaves out matches
+//! anything:
+//!
+//! - `zomes`, `functions`: names, or prefixes ending in `*`
+//! - `scopes`: the caller's permission level (`public`, `authenticated`,
+//!   `admin`), or `steward` for callers managing their own keys
+//! - `agents`: agent pubkeys, from the caller's token or the call's provenance
+//! - `when`: conditions on the call's input that must all hold, written
+//!   `input.<path> == <json>` or `input.<path> != <json>`
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+use super::{Claims, PermissionLevel};
+use crate::config::Args;
+
+/// Scope held by callers who manage their own keys
+pub const STEWARD_SCOPE: &str = "steward";
+
+/// What a matching rule does to a call
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Effect {
+    Allow,
+    Deny,
+}
+
+/// One allow/deny rule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PolicyRule {
+    pub effect: Effect,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub zomes: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub functions: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scopes: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub agents: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub when: Vec<Condition>,
+    /// Operator's note, echoed in denials
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// An operator's ordered rules
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PolicySet {
+    #[serde(default)]
+    pub rules: Vec<PolicyRule>,
+}
+
+/// A condition on the call's input: `input.<path> == <json>` or `!=`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Condition {
+    path: Vec<String>,
+    negated: bool,
+    value: JsonValue,
+}
+
+/// Who is making a call
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Caller {
+    pub level: PermissionLevel,
+    pub steward: bool,
+    pub agent_pub_key: Option<String>,
+}
+
+/// A zome call to authorize
+#[derive(Debug, Clone, Copy)]
+pub struct ZomeCall<'a> {
+    pub zome_name: &'a str,
+    pub fn_name: &'a str,
+    /// Agent the call is signed by, when known
+    pub provenance: Option<&'a str>,
+    pub input: &'a JsonValue,
+}
+
+/// A call refused by a deny rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Denied {
+    /// Index of the deciding rule
+    pub rule: usize,
+    pub description: Option<String>,
+}
+
+impl fmt::Display for Denied {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.description {
+            Some(description) => write!(f, "Denied by policy rule {}: {description}", self.rule),
+            None => write!(f, "Denied by policy rule {}", self.rule),
+        }
+    }
+}
+
+impl FromStr for Condition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // The first operator splits, so values may contain either
+        let (at, negated) = match (s.find("=="), s.find("!=")) {
+            (Some(eq), Some(ne)) if ne < eq => (ne, true),
+            (Some(eq), _) => (eq, false),
+            (None, Some(ne)) => (ne, true),
+            (None, None) => return Err(format!("Condition '{s}' needs == or !=")),
+        };
+        let (lhs, rhs) = (&s[..at], &s[at + 2..]);
+
+        let mut segments = lhs.trim().split('.');
+        if segments.next() != Some("input") {
+            return Err(format!("Condition '{s}' must test a field of input"));
+        }
+        let path: Vec<String> = segments.map(str::to_string).collect();
+        if path.iter().any(String::is_empty) {
+            return Err(format!("Condition '{s}' has an empty field name"));
+        }
+        let value = serde_json::from_str(rhs.trim())
+            .map_err(|e| format!("Condition '{s}' must compare with a JSON value: {e}"))?;
+        Ok(Self {
+            path,
+            negated,
+            value,
+        })
+    }
+}
+
+impl TryFrom<String> for Condition {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lhs = String::from("input");
+        for segment in &self.path {
+            lhs.push('.');
+            lhs.push_str(segment);
+        }
+        let op = if self.negated { "!=" } else { "==" };
+        write!(f, "{lhs} {op} {}", self.value)
+    }
+}
+
+impl From<Condition> for String {
+    fn from(condition: Condition) -> Self {
+        condition.to_string()
+    }
+}
+
+impl Condition {
+    /// Whether the input satisfies the condition. A missing field equals
+    /// nothing, so `!=` holds for it.
+    pub fn holds(&self, input: &JsonValue) -> bool {
+        let found = self
+            .path
+            .iter()
+            .try_fold(input, |value, segment| match value {
+                JsonValue::Object(map) => map.get(segment),
+                JsonValue::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+                _ => None,
+            });
+        (found == Some(&self.value)) != self.negated
+    }
+}
+
+impl Caller {
+    pub fn from_claims(claims: Option<&Claims>) -> Self {
+        match claims {
+            Some(claims) => Self {
+                level: claims.permission_level,
+                steward: claims.is_steward,
+                agent_pub_key: Some(claims.agent_pub_key.clone()),
+            },
+            None => Self::default(),
+        }
+    }
+
+    fn has_scope(&self, scope: &str) -> bool {
+        if scope == STEWARD_SCOPE {
+            return self.steward;
+        }
+        scope.eq_ignore_ascii_case(&self.level.to_string())
+    }
+}
+
+/// Name, or prefix when ending in `*`
+fn name_matches(pattern: &str, name: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => pattern == name,
+    }
+}
+
+impl PolicyRule {
+    pub fn matches(&self, caller: &Caller, call: &ZomeCall<'_>) -> bool {
+        let names = |patterns: &[String], name: &str| {
+            patterns.is_empty() || patterns.iter().any(|p| name_matches(p, name))
+        };
+        let agent = |agent: &String| {
+            caller.agent_pub_key.as_deref() == Some(agent.as_str())
+                || call.provenance == Some(agent.as_str())
+        };
+
+        names(&self.zomes, call.zome_name)
+            && names(&self.functions, call.fn_name)
+            && (self.scopes.is_empty() || self.scopes.iter().any(|s| caller.has_scope(s)))
+            && (self.agents.is_empty() || self.agents.iter().any(agent))
+            && self.when.iter().all(|c| c.holds(call.input))
+    }
+}
+
+impl PolicySet {
+    /// Parse and check an operator's policy document
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let set: Self = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        set.validate()?;
+        Ok(set)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        const SCOPES: [&str; 4] = ["public", "authenticated", "admin", STEWARD_SCOPE];
+        for (i, rule) in self.rules.iter().enumerate() {
+            let mut names = rule.zomes.iter().chain(&rule.functions);
+            if let Some(name) = names.find(|n| n.is_empty() || n.trim() != n.as_str()) {
+                return Err(format!("Rule {i}: invalid name '{name}'"));
+            }
+            if let Some(scope) = rule.scopes.iter().find(|s| !SCOPES.contains(&s.as_str())) {
+                return Err(format!(
+                    "Rule {i}: unknown scope '{scope}' (expected one of {})",
+                    SCOPES.join(", ")
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// First matching rule decides; calls no rule matches are allowed
+    pub fn check(&self, caller: &Caller, call: &ZomeCall<'_>) -> Result<(), Denied> {
+        match self
+            .rules
+            .iter()
+            .enumerate()
+            .find(|(_, rule)| rule.matches(caller, call))
+        {
+            Some((i, rule)) if rule.effect == Effect::Deny => Err(Denied {
+                rule: i,
+                description: rule.description.clone(),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The operator's live policy, shared by all connections
+#[derive(Debug)]
+pub struct PolicyStore {
+    set: RwLock<Arc<PolicySet>>,
+    path: Option<PathBuf>,
+    denied: AtomicU64,
+}
+
+impl PolicyStore {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            set: RwLock::new(Arc::new(PolicySet::default())),
+            path,
+            denied: AtomicU64::new(0),
+        }
+    }
+
+    pub fn from_args(args: &Args) -> Self {
+        Self::new(args.zome_policy_file.as_ref().map(PathBuf::from))
+    }
+
+    /// Load the policy file, if one is configured and exists
+    pub fn load(&self) -> Result<usize, String> {
+        let Some(path) = self.path.as_ref().filter(|p| p.exists()) else {
+            return Ok(0);
+        };
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        let set = PolicySet::parse(&json).map_err(|e| format!("{}: {e}", path.display()))?;
+        let rules = set.rules.len();
+        *self.set.write().unwrap() = Arc::new(set);
+        Ok(rules)
+    }
+
+    pub fn current(&self) -> Arc<PolicySet> {
+        Arc::clone(&self.set.read().unwrap())
+    }
+
+    /// Swap in a new policy, saving it to the policy file first so it
+    /// survives restarts
+    pub fn replace(&self, set: PolicySet) -> Result<(), String> {
+        set.validate()?;
+        if let Some(ref path) = self.path {
+            let json = serde_json::to_string_pretty(&set).map_err(|e| e.to_string())?;
+            std::fs::write(path, json)
+                .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+        }
+        *self.set.write().unwrap() = Arc::new(set);
+        Ok(())
+    }
+
+    pub fn check(&self, caller: &Caller, call: &ZomeCall<'_>) -> Result<(), Denied> {
+        let result = self.current().check(caller, call);
+        if result.is_err() {
+            self.denied.fetch_add(1, Ordering::Relaxed);
+        }
+        result
+    }
+
+    /// Calls denied since startup
+    pub fn denied_count(&self) -> u64 {
+        self.denied.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn call<'a>(fn_name: &'a str, input: &'a JsonValue) -> ZomeCall<'a> {
+        ZomeCall {
+            zome_name: "content_store",
+            fn_name,
+            provenance: None,
+            input,
+        }
+    }
+
+    fn caller(level: PermissionLevel, agent: &str) -> Caller {
+        Caller {
+            level,
+            steward: false,
+            agent_pub_key: Some(agent.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_steward_allow_list() {
+        let set = PolicySet::parse(
+            r#"{ "rules": [
+                { "effect": "allow", "functions": ["delete_path"], "agents": ["uhCAkSteward"] },
+                { "effect": "deny", "functions": ["delete_path"], "description": "stewards only" }
+            ] }"#,
+        )
+        .unwrap();
+        let input = json!("path-1");
+
+        let steward = caller(PermissionLevel::Authenticated, "uhCAkSteward");
+        let other = caller(PermissionLevel::Admin, "uhCAkOther");
+        assert!(set.check(&steward, &call("delete_path", &input)).is_ok());
+        let denied = set.check(&other, &call("delete_path", &input)).unwrap_err();
+        assert_eq!(denied.rule, 1);
+        assert_eq!(denied.to_string(), "Denied by policy rule 1: stewards only");
+        assert!(set.check(&other, &call("get_path", &input)).is_ok());
+    }
+
+    #[test]
+    fn test_scopes_prefixes_and_conditions() {
+        let set = PolicySet::parse(
+            r#"{ "rules": [
+                { "effect": "deny", "functions": ["create_*"], "scopes": ["public"] },
+                { "effect": "deny", "functions": ["create_content"],
+                  "when": ["input.reach == \"commons\"", "input.meta.draft != true"] }
+            ] }"#,
+        )
+        .unwrap();
+        let user = caller(PermissionLevel::Authenticated, "uhCAkUser");
+        let draft = json!({ "reach": "commons", "meta": { "draft": true } });
+        let published = json!({ "reach": "commons" });
+        let private = json!({ "reach": "private" });
+
+        assert!(set
+            .check(&Caller::default(), &call("create_path", &private))
+            .is_err());
+        assert!(set.check(&user, &call("create_path", &private)).is_ok());
+        assert!(set.check(&user, &call("create_content", &draft)).is_ok());
+        assert!(set
+            .check(&user, &call("create_content", &published))
+            .is_err());
+        assert!(set.check(&user, &call("create_content", &private)).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_policies() {
+        assert!(PolicySet::parse(r#"{"rules": [{"effect": "maybe"}]}"#).is_err());
+        assert!(
+            PolicySet::parse(r#"{"rules": [{"effect": "deny", "scopes": ["root"]}]}"#).is_err()
+        );
+        assert!("reach == 1".parse::<Condition>().is_err());
+        assert!("input.reach ~ 1".parse::<Condition>().is_err());
+        assert!("input.reach == commons".parse::<Condition>().is_err());
+        assert!("input.note == \"a!=b\""
+            .parse::<Condition>()
+            .unwrap()
+            .holds(&json!({"note": "a!=b"})));
+
+        let condition: Condition = "input.tags.0 != \"x\"".parse().unwrap();
+        assert_eq!(condition.to_string(), "input.tags.0 != \"x\"");
+    }
+}
@@ -0,0 +1,686 @@
+//! WebAuthn passkeys
+//!
+//! Verifies the browser side of the two WebAuthn ceremonies so users can
+//! sign in without a password:
+//!
+//! - **Registration** (`navigator.credentials.create`): a signed-in user adds
+//!   a passkey. The authenticator returns a new credential ID and public key.
+//! - **Assertion** (`navigator.credentials.get`): the passkey signs the
+//!   login challenge; the signature is checked against the stored key.
+//!
+//! Doorways ask for `none` attestation: a passkey is trusted as the user's
+//! own device, so attestation statements aren't checked. ES256 (P-256) and
+//! EdDSA (Ed25519) keys are accepted, which covers platform authenticators
+//! and security keys.
+//!
+//! The relying party ID (`WEBAUTHN_RP_ID`) is the domain passkeys are bound
+//! to; client data must come from one of `WEBAUTHN_ORIGINS`. Passkeys are
+//! disabled unless `WEBAUTHN_RP_ID` is set.
+
+use base64::prelude::*;
+use ed25519_dalek::Verifier as _;
+use rand::RngCore;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::config::Args;
+
+/// Random bytes in a challenge
+const CHALLENGE_BYTES: usize = 32;
+
+/// Deepest CBOR nesting accepted
+const MAX_CBOR_DEPTH: usize = 8;
+
+/// Authenticator data flags
+const FLAG_USER_PRESENT: u8 = 0x01;
+const FLAG_USER_VERIFIED: u8 = 0x04;
+const FLAG_BACKUP_ELIGIBLE: u8 = 0x08;
+const FLAG_ATTESTED_CREDENTIAL: u8 = 0x40;
+
+/// COSE algorithm identifiers
+pub const COSE_ES256: i64 = -7;
+pub const COSE_EDDSA: i64 = -8;
+
+/// WebAuthn verification errors
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum WebAuthnError {
+    #[error("Malformed {0}")]
+    Malformed(&'static str),
+
+    #[error("Client data is for {0}, not this ceremony")]
+    WrongCeremony(String),
+
+    #[error("Challenge does not match")]
+    ChallengeMismatch,
+
+    #[error("Origin {0} is not allowed")]
+    OriginNotAllowed(String),
+
+    #[error("Passkey is for another site")]
+    RpIdMismatch,
+
+    #[error("User presence was not confirmed")]
+    UserNotPresent,
+
+    #[error("User verification is required")]
+    UserNotVerified,
+
+    #[error("Unsupported key algorithm")]
+    UnsupportedAlgorithm,
+
+    #[error("Invalid signature")]
+    InvalidSignature,
+
+    #[error("Signature counter went backwards; the passkey may be cloned")]
+    CounterRegressed,
+
+    #[error("Invalid WebAuthn config: {0}")]
+    Config(String),
+}
+
+/// Collected client data (`clientDataJSON`)
+#[derive(Debug, Deserialize)]
+struct ClientData {
+    #[serde(rename = "type")]
+    kind: String,
+    challenge: String,
+    origin: String,
+}
+
+/// A credential created during registration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisteredCredential {
+    pub credential_id: Vec<u8>,
+    /// COSE public key as sent by the authenticator
+    pub public_key: Vec<u8>,
+    pub sign_count: u32,
+    pub aaguid: [u8; 16],
+    pub backup_eligible: bool,
+}
+
+/// Fresh random challenge (base64url)
+pub fn new_challenge() -> String {
+    let mut bytes = [0u8; CHALLENGE_BYTES];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    BASE64_URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Decode a base64url field as browsers send it (padding tolerated)
+pub fn decode_b64url(value: &str, what: &'static str) -> Result<Vec<u8>, WebAuthnError> {
+    BASE64_URL_SAFE_NO_PAD
+        .decode(value.trim_end_matches('='))
+        .map_err(|_| WebAuthnError::Malformed(what))
+}
+
+/// Challenge echoed in `clientDataJSON`, to find the pending ceremony
+pub fn client_challenge(client_data_json: &[u8]) -> Result<String, WebAuthnError> {
+    serde_json::from_slice::<ClientData>(client_data_json)
+        .map(|data| data.challenge)
+        .map_err(|_| WebAuthnError::Malformed("client data"))
+}
+
+/// The site passkeys are registered for
+#[derive(Debug, Clone)]
+pub struct RelyingParty {
+    id: String,
+    name: String,
+    origins: Vec<String>,
+    require_user_verification: bool,
+}
+
+impl RelyingParty {
+    /// Relying party as configured, `None` when `WEBAUTHN_RP_ID` is unset
+    pub fn from_args(args: &Args) -> Result<Option<Self>, WebAuthnError> {
+        let Some(id) = args.webauthn_rp_id.as_deref().filter(|id| !id.is_empty()) else {
+            return Ok(None);
+        };
+        let origins = if args.webauthn_origins.is_empty() {
+            vec![format!("https://{id}")]
+        } else {
+            args.webauthn_origins.clone()
+        };
+        Self::new(id, &args.webauthn_rp_name, origins)
+            .map(|rp| rp.with_user_verification(args.webauthn_require_user_verification))
+            .map(Some)
+    }
+
+    pub fn new(id: &str, name: &str, origins: Vec<String>) -> Result<Self, WebAuthnError> {
+        let id = id.trim().to_lowercase();
+        if id.contains('/') || id.contains(':') {
+            return Err(WebAuthnError::Config(format!(
+                "WEBAUTHN_RP_ID must be a bare domain, not {id}"
+            )));
+        }
+        let origins: Vec<String> = origins
+            .iter()
+            .map(|o| o.trim().trim_end_matches('/').to_string())
+            .filter(|o| !o.is_empty())
+            .collect();
+        if origins.is_empty() {
+            return Err(WebAuthnError::Config("No WEBAUTHN_ORIGINS".to_string()));
+        }
+        Ok(Self {
+            id,
+            name: name.to_string(),
+            origins,
+            require_user_verification: false,
+        })
+    }
+
+    /// Refuse assertions where the authenticator didn't verify the user
+    /// (PIN or biometric), not just their presence
+    pub fn with_user_verification(mut self, required: bool) -> Self {
+        self.require_user_verification = required;
+        self
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// `userVerification` to request from browsers
+    pub fn user_verification(&self) -> &'static str {
+        if self.require_user_verification {
+            "required"
+        } else {
+            "preferred"
+        }
+    }
+
+    fn check_client_data(
+        &self,
+        client_data_json: &[u8],
+        kind: &str,
+        challenge: &str,
+    ) -> Result<(), WebAuthnError> {
+        let data: ClientData = serde_json::from_slice(client_data_json)
+            .map_err(|_| WebAuthnError::Malformed("client data"))?;
+        if data.kind != kind {
+            return Err(WebAuthnError::WrongCeremony(data.kind));
+        }
+        if data.challenge.trim_end_matches('=') != challenge {
+            return Err(WebAuthnError::ChallengeMismatch);
+        }
+        if !self.origins.contains(&data.origin) {
+            return Err(WebAuthnError::OriginNotAllowed(data.origin));
+        }
+        Ok(())
+    }
+
+    fn check_auth_data(&self, auth_data: &AuthenticatorData) -> Result<(), WebAuthnError> {
+        if auth_data.rp_id_hash != Sha256::digest(self.id.as_bytes()).as_slice() {
+            return Err(WebAuthnError::RpIdMismatch);
+        }
+        if auth_data.flags & FLAG_USER_PRESENT == 0 {
+            return Err(WebAuthnError::UserNotPresent);
+        }
+        if self.require_user_verification && auth_data.flags & FLAG_USER_VERIFIED == 0 {
+            return Err(WebAuthnError::UserNotVerified);
+        }
+        Ok(())
+    }
+
+    /// Verify a registration response for `challenge`
+    pub fn verify_registration(
+        &self,
+        challenge: &str,
+        client_data_json: &[u8],
+        attestation_object: &[u8],
+    ) -> Result<RegisteredCredential, WebAuthnError> {
+        self.check_client_data(client_data_json, "webauthn.create", challenge)?;
+
+        let attestation = CborReader::new(attestation_object).value(0)?;
+        let auth_data = attestation
+            .entry_text("authData")
+            .and_then(Cbor::as_bytes)
+            .ok_or(WebAuthnError::Malformed("attestation object"))?;
+        let auth_data = AuthenticatorData::parse(auth_data)?;
+        self.check_auth_data(&auth_data)?;
+
+        let credential = auth_data
+            .credential
+            .ok_or(WebAuthnError::Malformed("attested credential data"))?;
+        // Reject keys we couldn't check a signature with later
+        PublicKey::from_cose(&credential.public_key)?;
+        Ok(RegisteredCredential {
+            credential_id: credential.id,
+            public_key: credential.public_key,
+            sign_count: auth_data.sign_count,
+            aaguid: credential.aaguid,
+            backup_eligible: auth_data.flags & FLAG_BACKUP_ELIGIBLE != 0,
+        })
+    }
+
+    /// Verify an assertion for `challenge` by a stored passkey
+    ///
+    /// Returns the new signature counter to store.
+    pub fn verify_assertion(
+        &self,
+        challenge: &str,
+        public_key: &[u8],
+        stored_sign_count: u32,
+        client_data_json: &[u8],
+        authenticator_data: &[u8],
+        signature: &[u8],
+    ) -> Result<u32, WebAuthnError> {
+        self.check_client_data(client_data_json, "webauthn.get", challenge)?;
+        let auth_data = AuthenticatorData::parse(authenticator_data)?;
+        self.check_auth_data(&auth_data)?;
+
+        let mut signed = authenticator_data.to_vec();
+        signed.extend_from_slice(&Sha256::digest(client_data_json));
+        PublicKey::from_cose(public_key)?.verify(&signed, signature)?;
+
+        // Synced passkeys always report 0; otherwise the counter must grow
+        let counted = auth_data.sign_count > 0 || stored_sign_count > 0;
+        if counted && auth_data.sign_count <= stored_sign_count {
+            return Err(WebAuthnError::CounterRegressed);
+        }
+        Ok(auth_data.sign_count)
+    }
+}
+
+/// Credential data attached to authenticator data at registration
+#[derive(Debug)]
+struct AttestedCredential {
+    aaguid: [u8; 16],
+    id: Vec<u8>,
+    public_key: Vec<u8>,
+}
+
+/// Parsed `authenticatorData`
+#[derive(Debug)]
+struct AuthenticatorData {
+    rp_id_hash: [u8; 32],
+    flags: u8,
+    sign_count: u32,
+    credential: Option<AttestedCredential>,
+}
+
+impl AuthenticatorData {
+    fn parse(data: &[u8]) -> Result<Self, WebAuthnError> {
+        let mut reader = CborReader::new(data);
+        let malformed = |_| WebAuthnError::Malformed("authenticator data");
+        let rp_id_hash: [u8; 32] = reader.take(32)?.try_into().map_err(malformed)?;
+        let flags = reader.take(1)?[0];
+        let sign_count = u32::from_be_bytes(reader.take(4)?.try_into().map_err(malformed)?);
+
+        let credential = if flags & FLAG_ATTESTED_CREDENTIAL != 0 {
+            let aaguid: [u8; 16] = reader.take(16)?.try_into().map_err(malformed)?;
+            let id_len = u16::from_be_bytes(reader.take(2)?.try_into().map_err(malformed)?);
+            let id = reader.take(id_len as usize)?.to_vec();
+            let key_start = reader.pos;
+            reader.value(0)?;
+            Some(AttestedCredential {
+                aaguid,
+                id,
+                public_key: data[key_start..reader.pos].to_vec(),
+            })
+        } else {
+            None
+        };
+        Ok(Self {
+            rp_id_hash,
+            flags,
+            sign_count,
+            credential,
+        })
+    }
+}
+
+/// A passkey's public key
+enum PublicKey {
+    Es256(p256::ecdsa::VerifyingKey),
+    Ed25519(ed25519_dalek::VerifyingKey),
+}
+
+impl PublicKey {
+    fn from_cose(cose: &[u8]) -> Result<Self, WebAuthnError> {
+        let key = CborReader::new(cose).value(0)?;
+        let int = |label| key.entry_int(label).and_then(Cbor::as_int);
+        let bytes = |label| key.entry_int(label).and_then(Cbor::as_bytes);
+        let malformed = || WebAuthnError::Malformed("public key");
+
+        match (int(1), int(3)) {
+            // EC2 key on P-256
+            (Some(2), Some(COSE_ES256)) if int(-1) == Some(1) => {
+                let (x, y) = (
+                    bytes(-2).ok_or_else(malformed)?,
+                    bytes(-3).ok_or_else(malformed)?,
+                );
+                if x.len() != 32 || y.len() != 32 {
+                    return Err(malformed());
+                }
+                let point = p256::EncodedPoint::from_affine_coordinates(
+                    x.as_slice().into(),
+                    y.as_slice().into(),
+                    false,
+                );
+                p256::ecdsa::VerifyingKey::from_encoded_point(&point)
+                    .map(Self::Es256)
+                    .map_err(|_| malformed())
+            }
+            // OKP key on Ed25519
+            (Some(1), Some(COSE_EDDSA)) if int(-1) == Some(6) => {
+                let x: [u8; 32] = bytes(-2)
+                    .and_then(|x| x.as_slice().try_into().ok())
+                    .ok_or_else(malformed)?;
+                ed25519_dalek::VerifyingKey::from_bytes(&x)
+                    .map(Self::Ed25519)
+                    .map_err(|_| malformed())
+            }
+            _ => Err(WebAuthnError::UnsupportedAlgorithm),
+        }
+    }
+
+    fn verify(&self, message: &[u8], signature: &[u8]) -> Result<(), WebAuthnError> {
+        let verified = match self {
+            Self::Es256(key) => p256::ecdsa::Signature::from_der(signature)
+                .is_ok_and(|sig| key.verify(message, &sig).is_ok()),
+            Self::Ed25519(key) => ed25519_dalek::Signature::from_slice(signature)
+                .is_ok_and(|sig| key.verify(message, &sig).is_ok()),
+        };
+        verified
+            .then_some(())
+            .ok_or(WebAuthnError::InvalidSignature)
+    }
+}
+
+// =============================================================================
+// CBOR
+// =============================================================================
+
+/// The CBOR subset authenticators produce (definite lengths, no floats)
+#[derive(Debug, Clone, PartialEq)]
+enum Cbor {
+    Int(i64),
+    Bytes(Vec<u8>),
+    Text(String),
+    Array(Vec<Cbor>),
+    Map(Vec<(Cbor, Cbor)>),
+    Bool(bool),
+    Null,
+}
+
+impl Cbor {
+    fn as_int(&self) -> Option<i64> {
+        match self {
+            Self::Int(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    fn as_bytes(&self) -> Option<&Vec<u8>> {
+        match self {
+            Self::Bytes(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    fn entry(&self, key: &Cbor) -> Option<&Cbor> {
+        match self {
+            Self::Map(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn entry_int(&self, key: i64) -> Option<&Cbor> {
+        self.entry(&Cbor::Int(key))
+    }
+
+    fn entry_text(&self, key: &str) -> Option<&Cbor> {
+        self.entry(&Cbor::Text(key.to_string()))
+    }
+}
+
+struct CborReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> CborReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], WebAuthnError> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|end| *end <= self.data.len())
+            .ok_or(WebAuthnError::Malformed("CBOR (truncated)"))?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    /// A length that can't be larger than what is left to read
+    fn length(&self, arg: u64) -> Result<usize, WebAuthnError> {
+        usize::try_from(arg)
+            .ok()
+            .filter(|len| *len <= self.data.len() - self.pos)
+            .ok_or(WebAuthnError::Malformed("CBOR (length)"))
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Cbor, WebAuthnError> {
+        if depth > MAX_CBOR_DEPTH {
+            return Err(WebAuthnError::Malformed("CBOR (too deep)"));
+        }
+        let initial = self.take(1)?[0];
+        let (major, info) = (initial >> 5, initial & 0x1f);
+        let arg = match info {
+            0..=23 => u64::from(info),
+            24 => u64::from(self.take(1)?[0]),
+            25 => u64::from(u16::from_be_bytes([self.take(1)?[0], self.take(1)?[0]])),
+            26 => u64::from(u32::from_be_bytes(
+                self.take(4)?.try_into().unwrap_or_default(),
+            )),
+            27 => u64::from_be_bytes(self.take(8)?.try_into().unwrap_or_default()),
+            _ => return Err(WebAuthnError::Malformed("CBOR (indefinite length)")),
+        };
+        let int = |arg: u64| i64::try_from(arg).map_err(|_| WebAuthnError::Malformed("CBOR (int)"));
+
+        match major {
+            0 => Ok(Cbor::Int(int(arg)?)),
+            1 => Ok(Cbor::Int(-1 - int(arg)?)),
+            2 => Ok(Cbor::Bytes(self.take(self.length(arg)?)?.to_vec())),
+            3 => String::from_utf8(self.take(self.length(arg)?)?.to_vec())
+                .map(Cbor::Text)
+                .map_err(|_| WebAuthnError::Malformed("CBOR (text)")),
+            4 => {
+                let len = self.length(arg)?;
+                let mut items = Vec::with_capacity(len);
+                for _ in 0..len {
+                    items.push(self.value(depth + 1)?);
+                }
+                Ok(Cbor::Array(items))
+            }
+            5 => {
+                let len = self.length(arg)?;
+                let mut entries = Vec::with_capacity(len);
+                for _ in 0..len {
+                    let key = self.value(depth + 1)?;
+                    entries.push((key, self.value(depth + 1)?));
+                }
+                Ok(Cbor::Map(entries))
+            }
+            // Tags don't change how the value is read
+            6 => self.value(depth + 1),
+            _ => match (info, arg) {
+                (20, _) => Ok(Cbor::Bool(false)),
+                (21, _) => Ok(Cbor::Bool(true)),
+                (22, _) | (23, _) => Ok(Cbor::Null),
+                _ => Err(WebAuthnError::Malformed("CBOR (float or simple value)")),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use p256::ecdsa::signature::Signer as _;
+
+    const RP_ID: &str = "doorway.example";
+    const ORIGIN: &str = "https://doorway.example";
+
+    fn rp() -> RelyingParty {
+        RelyingParty::new(RP_ID, "Elohim", vec![ORIGIN.to_string()]).unwrap()
+    }
+
+    fn client_data(kind: &str, challenge: &str) -> Vec<u8> {
+        serde_json::json!({ "type": kind, "challenge": challenge, "origin": ORIGIN })
+            .to_string()
+            .into_bytes()
+    }
+
+    /// CBOR head for a major type and length/value below 256
+    fn head(major: u8, arg: usize) -> Vec<u8> {
+        if arg < 24 {
+            vec![major << 5 | arg as u8]
+        } else {
+            vec![major << 5 | 24, arg as u8]
+        }
+    }
+
+    fn cbor_bytes(bytes: &[u8]) -> Vec<u8> {
+        [head(2, bytes.len()), bytes.to_vec()].concat()
+    }
+
+    fn cose_es256(key: &p256::ecdsa::VerifyingKey) -> Vec<u8> {
+        let point = key.to_encoded_point(false);
+        let mut cose = head(5, 5);
+        cose.extend([0x01, 0x02]); // kty: EC2
+        cose.extend([0x03, 0x26]); // alg: -7
+        cose.extend([0x20, 0x01]); // crv: P-256
+        cose.push(0x21); // x
+        cose.extend(cbor_bytes(point.x().unwrap()));
+        cose.push(0x22); // y
+        cose.extend(cbor_bytes(point.y().unwrap()));
+        cose
+    }
+
+    fn auth_data(flags: u8, sign_count: u32, credential: Option<(&[u8], &[u8])>) -> Vec<u8> {
+        let mut data = Sha256::digest(RP_ID.as_bytes()).to_vec();
+        data.push(flags);
+        data.extend(sign_count.to_be_bytes());
+        if let Some((id, cose)) = credential {
+            data.extend([7u8; 16]);
+            data.extend((id.len() as u16).to_be_bytes());
+            data.extend(id);
+            data.extend(cose);
+        }
+        data
+    }
+
+    fn attestation_object(auth_data: &[u8]) -> Vec<u8> {
+        let text = |s: &str| [head(3, s.len()), s.as_bytes().to_vec()].concat();
+        let mut object = head(5, 3);
+        object.extend(text("fmt"));
+        object.extend(text("none"));
+        object.extend(text("attStmt"));
+        object.extend(head(5, 0));
+        object.extend(text("authData"));
+        object.extend(cbor_bytes(auth_data));
+        object
+    }
+
+    #[test]
+    fn test_register_then_sign_in_with_es256() {
+        let rp = rp();
+        let signing_key = p256::ecdsa::SigningKey::from_slice(&[9u8; 32]).unwrap();
+        let cose = cose_es256(signing_key.verifying_key());
+
+        let challenge = new_challenge();
+        let data = auth_data(
+            FLAG_USER_PRESENT | FLAG_ATTESTED_CREDENTIAL | FLAG_BACKUP_ELIGIBLE,
+            0,
+            Some((b"cred-1", &cose)),
+        );
+        let client = client_data("webauthn.create", &challenge);
+        assert_eq!(client_challenge(&client).unwrap(), challenge);
+        let credential = rp
+            .verify_registration(&challenge, &client, &attestation_object(&data))
+            .unwrap();
+        assert_eq!(credential.credential_id, b"cred-1");
+        assert_eq!(credential.public_key, cose);
+        assert!(credential.backup_eligible);
+
+        let challenge = new_challenge();
+        let client = client_data("webauthn.get", &challenge);
+        let data = auth_data(FLAG_USER_PRESENT, 5, None);
+        let mut signed = data.clone();
+        signed.extend(Sha256::digest(&client));
+        let signature: p256::ecdsa::Signature = signing_key.sign(&signed);
+        let signature = signature.to_der();
+        let verify = |stored: u32, challenge: &str| {
+            rp.verify_assertion(
+                challenge,
+                &cose,
+                stored,
+                &client,
+                &data,
+                signature.as_bytes(),
+            )
+        };
+        assert_eq!(verify(0, &challenge), Ok(5));
+        assert_eq!(verify(5, &challenge), Err(WebAuthnError::CounterRegressed));
+        assert_eq!(
+            verify(0, &new_challenge()),
+            Err(WebAuthnError::ChallengeMismatch)
+        );
+
+        let mut forged = signature.as_bytes().to_vec();
+        let last = forged.len() - 1;
+        forged[last] ^= 1;
+        assert_eq!(
+            rp.verify_assertion(&challenge, &cose, 0, &client, &data, &forged),
+            Err(WebAuthnError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn test_ceremony_checks() {
+        let rp = rp().with_user_verification(true);
+        let challenge = new_challenge();
+        let client = client_data("webauthn.get", &challenge);
+        assert_eq!(
+            rp.verify_registration(&challenge, &client, &[]),
+            Err(WebAuthnError::WrongCeremony("webauthn.get".to_string()))
+        );
+
+        let other_site =
+            RelyingParty::new("elsewhere.example", "x", vec![ORIGIN.to_string()]).unwrap();
+        let data = auth_data(FLAG_USER_PRESENT, 1, None);
+        assert_eq!(
+            other_site.verify_assertion(&challenge, &[], 0, &client, &data, &[]),
+            Err(WebAuthnError::RpIdMismatch)
+        );
+        assert_eq!(
+            rp.verify_assertion(&challenge, &[], 0, &client, &data, &[]),
+            Err(WebAuthnError::UserNotVerified)
+        );
+        assert!(RelyingParty::new("https://doorway.example", "x", vec![]).is_err());
+    }
+
+    #[test]
+    fn test_cbor_reader_rejects_hostile_input() {
+        // Array claiming more items than there are bytes
+        assert!(CborReader::new(&[0x9a, 0xff, 0xff, 0xff, 0xff])
+            .value(0)
+            .is_err());
+        // Indefinite-length map
+        assert!(CborReader::new(&[0xbf, 0xff]).value(0).is_err());
+        // Nesting beyond the limit
+        assert!(CborReader::new(&[0x81; 16]).value(0).is_err());
+        assert_eq!(
+            CborReader::new(&[0xa1, 0x20, 0xf5]).value(0),
+            Ok(Cbor::Map(vec![(Cbor::Int(-1), Cbor::Bool(true))]))
+        );
+    }
+}
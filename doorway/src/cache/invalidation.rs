@@ -0,0 +1,242 @@
+//! Signal-driven cache invalidation
+//!
+//! Doorway only sees the writes that pass through it, but DNAs also change
+//! from local apps and other doorways. Zomes emit a `CacheSignal` carrying
+//! `source_fn` on every write; the signal subscriber forwards it here and the
+//! DNA's own `invalidated_by` rules decide which cached reads to evict.
+//!
+//! Rules marked `keyed_by_id` lose only the written entity's entry; every
+//! other dependent function is evicted wholesale.
+//!
+//! Writes made through doorway also have their input at hand. Rules that name
+//! an input field of the write (`create_relationship.source_id`) lose only the
+//! cached calls whose input referenced the written IDs; see
+//! [`apply_write_invalidation`].
+
+use std::sync::Arc;
+
+use serde_json::Value;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use super::keys::arg_refs;
+use super::rules::InvalidationTarget;
+use super::{CacheRuleStore, ContentCache};
+
+/// A write reported by a zome signal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheInvalidation {
+    /// Zome function that performed the write
+    pub source_fn: String,
+    /// Document type written (e.g., "PathStep")
+    pub doc_type: String,
+    /// Entity written, or "*" for bulk writes
+    pub doc_id: String,
+}
+
+/// Evict cached responses made stale by a write. Returns entries removed.
+pub fn apply_invalidation(
+    cache: &ContentCache,
+    rules: &CacheRuleStore,
+    invalidation: &CacheInvalidation,
+) -> usize {
+    let mut removed = 0;
+
+    for (dna_hash, rule) in rules.signal_invalidations(&invalidation.source_fn) {
+        removed += if rule.keyed_by_id && invalidation.doc_id != "*" {
+            cache.invalidate_entity(&dna_hash, &rule.fn_name, &invalidation.doc_id)
+        } else {
+            cache.invalidate_dna_function(&dna_hash, &rule.fn_name)
+        };
+    }
+
+    removed
+}
+
+/// Evict a target's cached calls made stale by a write with `input`.
+/// Returns entries removed.
+///
+/// Falls back to the whole function when the target has no argument paths,
+/// or the write input doesn't hold IDs at them.
+pub fn apply_write_invalidation(
+    cache: &ContentCache,
+    target: &InvalidationTarget,
+    input: &Value,
+) -> usize {
+    if target.arg_paths.is_empty() {
+        return cache.invalidate_dna_function(&target.dna_hash, &target.fn_name);
+    }
+    match arg_refs(input, &target.arg_paths) {
+        Some(ids) => cache.invalidate_referencing(&target.dna_hash, &target.fn_name, &ids),
+        None => cache.invalidate_dna_function(&target.dna_hash, &target.fn_name),
+    }
+}
+
+/// Spawn the task applying signal invalidations to the response cache.
+pub fn spawn_invalidation_task(
+    mut rx: broadcast::Receiver<CacheInvalidation>,
+    cache: Arc<ContentCache>,
+    rules: Arc<CacheRuleStore>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!("Signal-driven cache invalidation started");
+
+        loop {
+            match rx.recv().await {
+                Ok(invalidation) => {
+                    let removed = apply_invalidation(&cache, &rules, &invalidation);
+                    debug!(
+                        source_fn = %invalidation.source_fn,
+                        doc_type = %invalidation.doc_type,
+                        doc_id = %invalidation.doc_id,
+                        removed,
+                        "Applied cache invalidation signal"
+                    );
+                }
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    // Missed writes could leave stale entries; drop everything
+                    warn!(
+                        missed = n,
+                        "Cache invalidation lagged, clearing response cache"
+                    );
+                    cache.clear();
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    info!("Cache invalidation channel closed");
+                    break;
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::{CacheKey, CacheRule};
+    use std::time::Duration;
+
+    fn rule(fn_name: &str, invalidated_by: &str, keyed_by_id: bool) -> CacheRule {
+        CacheRule {
+            fn_name: fn_name.into(),
+            cacheable: true,
+            ttl_secs: 900,
+            public: true,
+            reach_field: None,
+            reach_value: None,
+            invalidated_by: vec![invalidated_by.into()],
+            bridge_invalidated_by: vec![],
+            keyed_by_id,
+            key_fields: vec![],
+        }
+    }
+
+    fn setup() -> (ContentCache, CacheRuleStore) {
+        let cache = ContentCache::with_defaults();
+        let rules = CacheRuleStore::new();
+        rules.set_dna_rules(
+            "dna",
+            vec![
+                rule("get_step_by_id", "update_step", true),
+                rule("get_path_with_steps", "update_step", false),
+            ],
+        );
+
+        let ttl = Duration::from_secs(300);
+        for (fn_name, args) in [
+            ("get_step_by_id", "\"step-1\""),
+            ("get_step_by_id", "\"step-2\""),
+            ("get_path_with_steps", "\"path-1\""),
+            ("get_content", "\"c\""),
+        ] {
+            let key = CacheKey::new("dna", "content_store", fn_name, args).to_storage_key();
+            cache.set(&key, b"{}".to_vec(), "application/json", ttl);
+        }
+
+        (cache, rules)
+    }
+
+    #[test]
+    fn test_entity_write_evicts_entity_and_dependents() {
+        let (cache, rules) = setup();
+
+        let removed = apply_invalidation(
+            &cache,
+            &rules,
+            &CacheInvalidation {
+                source_fn: "update_step".into(),
+                doc_type: "PathStep".into(),
+                doc_id: "step-1".into(),
+            },
+        );
+
+        // step-1 lookup plus the path listing; step-2 and content stay cached
+        assert_eq!(removed, 2);
+        assert_eq!(cache.stats().entries, 2);
+    }
+
+    #[test]
+    fn test_bulk_write_evicts_whole_function() {
+        let (cache, rules) = setup();
+
+        let removed = apply_invalidation(
+            &cache,
+            &rules,
+            &CacheInvalidation {
+                source_fn: "update_step".into(),
+                doc_type: "PathStep".into(),
+                doc_id: "*".into(),
+            },
+        );
+
+        assert_eq!(removed, 3);
+        assert_eq!(cache.stats().entries, 1);
+    }
+
+    #[test]
+    fn test_write_evicts_only_referencing_entries() {
+        let cache = ContentCache::with_defaults();
+        let ttl = Duration::from_secs(300);
+        for (source_id, refs) in [("c1", vec!["c1"]), ("c2", vec!["c2"]), ("-", vec![])] {
+            let key = CacheKey::new("dna", "content_store", "get_relationships", source_id)
+                .to_storage_key();
+            let refs = refs.into_iter().map(String::from).collect();
+            cache.set_with_refs(&key, b"[]".to_vec(), "application/json", ttl, refs);
+        }
+
+        let target = InvalidationTarget {
+            dna_hash: "dna".into(),
+            fn_name: "get_relationships".into(),
+            arg_paths: vec!["source_id".into()],
+        };
+
+        // c1's listing and the unreferenced entry go; c2's listing stays
+        let write = serde_json::json!({"source_id": "c1", "target_id": "c9"});
+        assert_eq!(apply_write_invalidation(&cache, &target, &write), 2);
+        assert_eq!(cache.stats().entries, 1);
+
+        // No ID at the path: the whole function goes
+        let write = serde_json::json!({"target_id": "c9"});
+        assert_eq!(apply_write_invalidation(&cache, &target, &write), 1);
+        assert_eq!(cache.stats().entries, 0);
+    }
+
+    #[test]
+    fn test_unknown_source_fn_is_noop() {
+        let (cache, rules) = setup();
+
+        let removed = apply_invalidation(
+            &cache,
+            &rules,
+            &CacheInvalidation {
+                source_fn: "something_else".into(),
+                doc_type: "Thing".into(),
+                doc_id: "x".into(),
+            },
+        );
+
+        assert_eq!(removed, 0);
+        assert_eq!(cache.stats().entries, 4);
+    }
+}
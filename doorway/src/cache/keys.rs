@@ -1,7 +1,17 @@
 //! Cache key definitions
 //!
 //! Generic cache keys for Holochain zome calls.
+//!
+//! Inputs are hashed in canonical form (sorted object keys, null fields
+//! dropped) so the same logical call always lands on the same entry, however
+//! the client ordered or padded its arguments. Rules can narrow the key further
+//! to a few input fields with `key_fields`.
+//!
+//! Since keys only carry a hash of the input, cached responses also record the
+//! string values their input names ([`input_refs`]); argument-based
+//! invalidation matches those against the IDs a write names ([`arg_refs`]).
 
+use serde_json::Value;
 use std::fmt;
 
 /// Cache key for a zome function call
@@ -18,6 +28,9 @@ pub struct CacheKey {
     /// Reach level of the content (for reach-aware caching)
     /// None = reach not applicable, Some("commons"|"regional"|"bioregional"|etc) = reach-specific cache
     pub reach: Option<String>,
+    /// Negotiated locale chain (for translated responses)
+    /// None = not localized, Some("pt-BR,pt,en") = one cache entry per chain
+    pub locale: Option<String>,
 }
 
 impl CacheKey {
@@ -40,9 +53,25 @@ impl CacheKey {
             fn_name: fn_name.to_string(),
             args_hash,
             reach: None,
+            locale: None,
         }
     }
 
+    /// Create a cache key from a JSON input in canonical form
+    ///
+    /// With `key_fields`, only those (dotted path) fields of the input
+    /// contribute to the key.
+    pub fn for_input(
+        dna_hash: &str,
+        zome: &str,
+        fn_name: &str,
+        input: &Value,
+        key_fields: &[String],
+    ) -> Self {
+        let args = canonical_args(&key_args(input, key_fields));
+        Self::new(dna_hash, zome, fn_name, &args)
+    }
+
     /// Create from components with pre-computed args hash
     pub fn with_args_hash(dna_hash: &str, zome: &str, fn_name: &str, args_hash: &str) -> Self {
         Self {
@@ -51,6 +80,7 @@ impl CacheKey {
             fn_name: fn_name.to_string(),
             args_hash: args_hash.to_string(),
             reach: None,
+            locale: None,
         }
     }
 
@@ -61,10 +91,23 @@ impl CacheKey {
         key
     }
 
+    /// Create a cache key for a localized response
+    pub fn with_locale(
+        dna_hash: &str,
+        zome: &str,
+        fn_name: &str,
+        args: &str,
+        locale: &str,
+    ) -> Self {
+        let mut key = Self::new(dna_hash, zome, fn_name, args);
+        key.locale = Some(locale.to_string());
+        key
+    }
+
     /// Convert to storage key string
-    /// Format: dna:zome:fn:args_hash or dna:zome:fn:args_hash:reach
+    /// Format: dna:zome:fn:args_hash, optionally followed by :reach and :lang=locale
     pub fn to_storage_key(&self) -> String {
-        match &self.reach {
+        let mut key = match &self.reach {
             Some(reach) => format!(
                 "{}:{}:{}:{}:{}",
                 self.dna_hash, self.zome, self.fn_name, self.args_hash, reach
@@ -73,7 +116,12 @@ impl CacheKey {
                 "{}:{}:{}:{}",
                 self.dna_hash, self.zome, self.fn_name, self.args_hash
             ),
+        };
+        if let Some(ref locale) = self.locale {
+            key.push_str(":lang=");
+            key.push_str(locale);
         }
+        key
     }
 
     /// Create a pattern for invalidating all calls to a function
@@ -82,6 +130,109 @@ impl CacheKey {
     }
 }
 
+/// Serialize zome input canonically: object keys sorted, null fields dropped
+///
+/// Array order is kept, since it is usually meaningful to the zome.
+pub fn canonical_args(value: &Value) -> String {
+    let mut out = String::new();
+    write_canonical(value, &mut out);
+    out
+}
+
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(map) => {
+            let mut fields: Vec<(&String, &Value)> =
+                map.iter().filter(|(_, v)| !v.is_null()).collect();
+            fields.sort_by(|a, b| a.0.cmp(b.0));
+
+            out.push('{');
+            for (i, (key, field)) in fields.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(field, out);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+/// Project an input onto a rule's key fields (dotted paths)
+///
+/// Non-object inputs, and rules without key fields, key on the whole input.
+/// Missing fields are left out, so they key the same as null ones.
+pub fn key_args(input: &Value, key_fields: &[String]) -> Value {
+    if key_fields.is_empty() || !input.is_object() {
+        return input.clone();
+    }
+
+    let mut projected = serde_json::Map::new();
+    for path in key_fields {
+        let found = path
+            .split('.')
+            .try_fold(input, |value, segment| value.get(segment));
+        if let Some(value) = found {
+            projected.insert(path.clone(), value.clone());
+        }
+    }
+    Value::Object(projected)
+}
+
+/// Entity IDs an input names: its string values, narrowed to the key fields
+pub fn input_refs(input: &Value, key_fields: &[String]) -> Vec<String> {
+    let mut refs = Vec::new();
+    collect_strings(&key_args(input, key_fields), &mut refs);
+    refs.sort();
+    refs.dedup();
+    refs
+}
+
+fn collect_strings(value: &Value, refs: &mut Vec<String>) {
+    match value {
+        Value::String(s) => refs.push(s.clone()),
+        Value::Array(items) => items.iter().for_each(|item| collect_strings(item, refs)),
+        Value::Object(map) => map.values().for_each(|item| collect_strings(item, refs)),
+        _ => {}
+    }
+}
+
+/// Entity IDs a write names at `paths` (dotted): strings or arrays of strings
+///
+/// None when a path is missing or holds anything else, since the write's
+/// reach can't be told.
+pub fn arg_refs(input: &Value, paths: &[String]) -> Option<Vec<String>> {
+    let mut refs = Vec::new();
+    for path in paths {
+        match path
+            .split('.')
+            .try_fold(input, |value, segment| value.get(segment))?
+        {
+            Value::String(id) => refs.push(id.clone()),
+            Value::Array(ids) => {
+                for id in ids {
+                    refs.push(id.as_str()?.to_string());
+                }
+            }
+            _ => return None,
+        }
+    }
+    Some(refs)
+}
+
 impl fmt::Display for CacheKey {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -168,4 +319,80 @@ mod tests {
 
         assert_ne!(key1.to_storage_key(), key2.to_storage_key());
     }
+
+    #[test]
+    fn test_canonical_args_ignores_key_order_and_nulls() {
+        let a = serde_json::json!({"id": "x", "filter": {"reach": "commons", "tag": null}});
+        let b = serde_json::json!({"filter": {"reach": "commons"}, "id": "x", "limit": null});
+        assert_eq!(canonical_args(&a), canonical_args(&b));
+        assert_eq!(
+            canonical_args(&a),
+            r#"{"filter":{"reach":"commons"},"id":"x"}"#
+        );
+
+        // Array order still matters
+        let c = serde_json::json!({"ids": ["a", "b"]});
+        let d = serde_json::json!({"ids": ["b", "a"]});
+        assert_ne!(canonical_args(&c), canonical_args(&d));
+    }
+
+    #[test]
+    fn test_for_input_with_key_fields() {
+        let fields = vec!["content_id".to_string(), "opts.locale".to_string()];
+        let a = serde_json::json!({"content_id": "c1", "trace": "1", "opts": {"locale": "es"}});
+        let b = serde_json::json!({"opts": {"locale": "es", "debug": true}, "content_id": "c1"});
+        let c = serde_json::json!({"content_id": "c2", "opts": {"locale": "es"}});
+
+        let key_a = CacheKey::for_input("dna", "zome", "fn", &a, &fields);
+        let key_b = CacheKey::for_input("dna", "zome", "fn", &b, &fields);
+        let key_c = CacheKey::for_input("dna", "zome", "fn", &c, &fields);
+        assert_eq!(key_a, key_b);
+        assert_ne!(key_a, key_c);
+
+        // Without key fields every field counts
+        assert_ne!(
+            CacheKey::for_input("dna", "zome", "fn", &a, &[]),
+            CacheKey::for_input("dna", "zome", "fn", &b, &[])
+        );
+    }
+
+    #[test]
+    fn test_for_input_matches_entity_keys() {
+        // ID-keyed invalidation hashes the JSON-encoded ID; canonical form
+        // of a bare string must not change it
+        let id = serde_json::json!("step-1");
+        assert_eq!(
+            CacheKey::for_input("dna", "zome", "fn", &id, &[]),
+            CacheKey::new("dna", "zome", "fn", "\"step-1\"")
+        );
+    }
+
+    #[test]
+    fn test_input_and_arg_refs() {
+        let input = serde_json::json!({"source_id": "c1", "kinds": ["prereq"], "limit": 5});
+        assert_eq!(input_refs(&input, &[]), vec!["c1", "prereq"]);
+        assert_eq!(input_refs(&input, &["source_id".to_string()]), vec!["c1"]);
+
+        let write = serde_json::json!({"source_id": "c1", "target": {"ids": ["c2", "c3"]}});
+        assert_eq!(
+            arg_refs(&write, &["source_id".to_string(), "target.ids".to_string()]),
+            Some(vec!["c1".to_string(), "c2".to_string(), "c3".to_string()])
+        );
+        assert_eq!(arg_refs(&write, &["target_id".to_string()]), None);
+        assert_eq!(arg_refs(&input, &["limit".to_string()]), None);
+    }
+
+    #[test]
+    fn test_cache_key_with_locale() {
+        let args = r#"{"id":"x"}"#;
+        let es = CacheKey::with_locale("dna", "zome", "fn", args, "es,en");
+        let pt = CacheKey::with_locale("dna", "zome", "fn", args, "pt-BR,pt");
+
+        assert!(es.to_storage_key().ends_with(":lang=es,en"));
+        assert_ne!(es.to_storage_key(), pt.to_storage_key());
+        // Localized entries are still cleared with the function's pattern
+        assert!(es
+            .to_storage_key()
+            .starts_with(&CacheKey::invalidation_pattern("dna", "zome", "fn")));
+    }
 }
@@ -8,6 +8,7 @@
 //!
 //! - **CDN-style read caching**: Cache blob/shard reads for external clients
 //! - **Request coalescing**: Dedupe multiple clients requesting same content
+//!   (see [`single_flight`] for zome calls)
 //! - **DDoS protection**: Rate limiting, auth for external requests
 //! - **NOT write batching**: That's the agent's job via holochain-cache-core
 //!
@@ -20,6 +21,11 @@
 //!
 //! See [`rules`] module for the protocol specification.
 //!
+//! ## Signal Invalidation
+//!
+//! The [`invalidation`] module evicts cached responses when a zome signals a
+//! write, so writes that bypass this doorway don't leave stale reads behind.
+//!
 //! ## Content Resolution
 //!
 //! The [`resolution`] module provides tiered content source routing:
@@ -35,13 +41,27 @@
 //! - Geographic routing hints
 //!
 //! This COMPLEMENTS agent-side `holochain-cache-core` - it does NOT replace it.
+//!
+//! ## Rule Statistics
+//!
+//! The [`rule_stats`] module counts hits, misses, stale serves and
+//! invalidations per cache rule for `/admin/cache/stats`.
+//!
+//! ## Snapshots
+//!
+//! The [`snapshot`] module dumps the response cache to a file and loads it
+//! into another instance, so blue-green deploys start with a warm cache.
 
 pub mod access_control;
 pub mod delivery_relay;
+pub mod invalidation;
 pub mod keys;
 pub mod reach_aware_serving;
 pub mod resolution;
+pub mod rule_stats;
 pub mod rules;
+pub mod single_flight;
+pub mod snapshot;
 pub mod store;
 pub mod tiered;
 
@@ -49,13 +69,21 @@ pub use access_control::{
     can_serve_at_reach, geographic_distance, prioritize_sources, CustodianSource, RequesterContext,
 };
 pub use delivery_relay::{CoalescedRequest, DeliveryRelay, DeliveryRelayConfig};
+pub use invalidation::{
+    apply_invalidation, apply_write_invalidation, spawn_invalidation_task, CacheInvalidation,
+};
 pub use keys::CacheKey;
 pub use reach_aware_serving::{
     create_reach_aware_cache_key, extract_reach_from_response, extract_requester_context,
     should_serve_response,
 };
 pub use resolution::{DoorwayResolver, ResolutionResult, ResolutionStats};
-pub use rules::{CacheRule, CacheRuleStore, DefaultRules, DnaRules, CACHE_RULES_FN};
+pub use rule_stats::{RuleStats, RuleStatsSnapshot};
+pub use rules::{
+    CacheRule, CacheRuleStore, DefaultCachePolicy, DefaultRules, DnaRules, InvalidationTarget,
+    RoleCachePolicy, CACHE_RULES_FN,
+};
+pub use single_flight::{SingleFlight, SingleFlightStats};
 pub use store::{CacheEntry, ContentCache};
 pub use tiered::{
     spawn_tiered_cleanup_task, BlobMetadata, CacheError, CaptionMetadata, TierStats,
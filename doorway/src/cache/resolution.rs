@@ -40,6 +40,7 @@ use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
 use crate::projection::ProjectionStore;
+use crate::proxy::admission::CallPriority;
 use crate::types::{DoorwayError, Result};
 use crate::worker::{RequesterIdentity, WorkerPool, ZomeCallBuilder, ZomeCallConfig};
 
@@ -271,12 +272,19 @@ impl DoorwayResolver {
                 "Falling back to conductor"
             );
 
+            // Anonymous reads give way while the conductor is under pressure
+            let priority = if requester.is_some() {
+                CallPriority::Normal
+            } else {
+                CallPriority::Low
+            };
+
             // Build the zome call for __doorway_get with identity
             let builder = ZomeCallBuilder::new(config.clone());
             let payload = builder.build_doorway_get(content_type, id, requester)?;
 
             // Send to conductor via worker pool
-            match pool.request(payload).await {
+            match pool.request_with_priority(payload, priority).await {
                 Ok(response) => {
                     // Parse the response
                     match builder.parse_response::<serde_json::Value>(&response) {
@@ -310,6 +318,7 @@ impl DoorwayResolver {
                         }
                     }
                 }
+                Err(e @ DoorwayError::Overloaded(_)) => return Err(e),
                 Err(e) => {
                     warn!(
                         content_type = content_type,
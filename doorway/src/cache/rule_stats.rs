@@ -0,0 +1,258 @@
+//! Per-rule cache statistics
+//!
+//! The response cache attributes every lookup, store and invalidation to the
+//! (DNA, function) pair in its storage key, so operators can see which cache
+//! rules pay off and which TTLs need adjusting. Keys without that shape
+//! (blob hashes) are not counted.
+//!
+//! Counters are cumulative since startup. The
+//! [rollup worker](crate::worker::cache_stats) persists the difference
+//! between consecutive snapshots.
+
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Counters for one (dna_hash, fn_name)
+#[derive(Debug, Default)]
+struct RuleCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    stale_serves: AtomicU64,
+    invalidations: AtomicU64,
+    stored: AtomicU64,
+    stored_bytes: AtomicU64,
+}
+
+/// Point-in-time numbers for one rule
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleStatsSnapshot {
+    pub dna_hash: String,
+    pub fn_name: String,
+    pub hits: u64,
+    pub misses: u64,
+    /// Expired entries served because the fresh call failed
+    pub stale_serves: u64,
+    /// Entries removed by invalidation (not by TTL or eviction)
+    pub invalidations: u64,
+    /// Responses written to the cache
+    pub stored: u64,
+    pub stored_bytes: u64,
+}
+
+impl RuleStatsSnapshot {
+    /// Share of lookups answered from the cache (0.0 - 1.0)
+    pub fn hit_ratio(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+
+    /// Mean size of stored responses in bytes
+    pub fn avg_payload_bytes(&self) -> u64 {
+        self.stored_bytes.checked_div(self.stored).unwrap_or(0)
+    }
+
+    /// Counts accumulated since `earlier` (a snapshot of the same rule)
+    pub fn since(&self, earlier: &Self) -> Self {
+        Self {
+            dna_hash: self.dna_hash.clone(),
+            fn_name: self.fn_name.clone(),
+            hits: self.hits.saturating_sub(earlier.hits),
+            misses: self.misses.saturating_sub(earlier.misses),
+            stale_serves: self.stale_serves.saturating_sub(earlier.stale_serves),
+            invalidations: self.invalidations.saturating_sub(earlier.invalidations),
+            stored: self.stored.saturating_sub(earlier.stored),
+            stored_bytes: self.stored_bytes.saturating_sub(earlier.stored_bytes),
+        }
+    }
+
+    /// Add another window's counts for the same rule
+    pub fn merge(&mut self, other: &Self) {
+        self.hits += other.hits;
+        self.misses += other.misses;
+        self.stale_serves += other.stale_serves;
+        self.invalidations += other.invalidations;
+        self.stored += other.stored;
+        self.stored_bytes += other.stored_bytes;
+    }
+
+    /// Whether nothing was counted
+    pub fn is_empty(&self) -> bool {
+        self.hits == 0
+            && self.misses == 0
+            && self.stale_serves == 0
+            && self.invalidations == 0
+            && self.stored == 0
+    }
+}
+
+/// Cache counters per (dna_hash, fn_name)
+pub struct RuleStats {
+    counters: DashMap<(String, String), RuleCounters>,
+    since: DateTime<Utc>,
+}
+
+impl RuleStats {
+    pub fn new() -> Self {
+        Self {
+            counters: DashMap::new(),
+            since: Utc::now(),
+        }
+    }
+
+    /// When counting started
+    pub fn since(&self) -> DateTime<Utc> {
+        self.since
+    }
+
+    pub fn record_hit(&self, storage_key: &str) {
+        self.add(storage_key, |c| {
+            c.hits.fetch_add(1, Ordering::Relaxed);
+        });
+    }
+
+    pub fn record_miss(&self, storage_key: &str) {
+        self.add(storage_key, |c| {
+            c.misses.fetch_add(1, Ordering::Relaxed);
+        });
+    }
+
+    pub fn record_stale_serve(&self, storage_key: &str) {
+        self.add(storage_key, |c| {
+            c.stale_serves.fetch_add(1, Ordering::Relaxed);
+        });
+    }
+
+    pub fn record_invalidation(&self, storage_key: &str) {
+        self.add(storage_key, |c| {
+            c.invalidations.fetch_add(1, Ordering::Relaxed);
+        });
+    }
+
+    pub fn record_store(&self, storage_key: &str, bytes: usize) {
+        self.add(storage_key, |c| {
+            c.stored.fetch_add(1, Ordering::Relaxed);
+            c.stored_bytes.fetch_add(bytes as u64, Ordering::Relaxed);
+        });
+    }
+
+    /// Current counters for every rule seen so far
+    pub fn snapshot(&self) -> Vec<RuleStatsSnapshot> {
+        self.counters
+            .iter()
+            .map(|entry| {
+                let ((dna_hash, fn_name), c) = entry.pair();
+                RuleStatsSnapshot {
+                    dna_hash: dna_hash.clone(),
+                    fn_name: fn_name.clone(),
+                    hits: c.hits.load(Ordering::Relaxed),
+                    misses: c.misses.load(Ordering::Relaxed),
+                    stale_serves: c.stale_serves.load(Ordering::Relaxed),
+                    invalidations: c.invalidations.load(Ordering::Relaxed),
+                    stored: c.stored.load(Ordering::Relaxed),
+                    stored_bytes: c.stored_bytes.load(Ordering::Relaxed),
+                }
+            })
+            .collect()
+    }
+
+    fn add(&self, storage_key: &str, count: impl FnOnce(&RuleCounters)) {
+        let Some((dna_hash, fn_name)) = rule_of(storage_key) else {
+            return;
+        };
+        let key = (dna_hash.to_string(), fn_name.to_string());
+        // Read lock for the common case; the shard write lock only on first use
+        if let Some(counters) = self.counters.get(&key) {
+            count(counters.value());
+            return;
+        }
+        count(self.counters.entry(key).or_default().value());
+    }
+}
+
+impl Default for RuleStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// (dna_hash, fn_name) of a zome call storage key (`dna:zome:fn:args...`)
+pub fn rule_of(storage_key: &str) -> Option<(&str, &str)> {
+    let mut parts = storage_key.splitn(4, ':');
+    let dna_hash = parts.next()?;
+    let fn_name = parts.nth(1)?;
+    parts.next()?;
+    Some((dna_hash, fn_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rule_of() {
+        assert_eq!(
+            rule_of("dna:content_store:get_content:abc:commons"),
+            Some(("dna", "get_content"))
+        );
+        assert_eq!(rule_of("dna:zome:fn"), None);
+        assert_eq!(rule_of("sha256-deadbeef"), None);
+    }
+
+    #[test]
+    fn test_counts_per_rule() {
+        let stats = RuleStats::new();
+        stats.record_miss("dna:zome:get_a:x");
+        stats.record_store("dna:zome:get_a:x", 100);
+        stats.record_hit("dna:zome:get_a:x");
+        stats.record_hit("dna:zome:get_a:y:lang=es");
+        stats.record_store("dna:zome:get_a:y:lang=es", 300);
+        stats.record_invalidation("dna:zome:get_b:z");
+        stats.record_hit("blobhash");
+
+        let mut snapshot = stats.snapshot();
+        snapshot.sort_by(|a, b| a.fn_name.cmp(&b.fn_name));
+        assert_eq!(snapshot.len(), 2);
+
+        let get_a = &snapshot[0];
+        assert_eq!((get_a.hits, get_a.misses), (2, 1));
+        assert!((get_a.hit_ratio() - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(get_a.avg_payload_bytes(), 200);
+        assert_eq!(snapshot[1].invalidations, 1);
+        assert_eq!(snapshot[1].hit_ratio(), 0.0);
+    }
+
+    #[test]
+    fn test_since_and_merge() {
+        let earlier = RuleStatsSnapshot {
+            hits: 5,
+            misses: 2,
+            stored: 2,
+            stored_bytes: 40,
+            ..Default::default()
+        };
+        let later = RuleStatsSnapshot {
+            hits: 9,
+            misses: 3,
+            stored: 3,
+            stored_bytes: 100,
+            ..Default::default()
+        };
+
+        let mut window = later.since(&earlier);
+        assert_eq!(
+            (window.hits, window.misses, window.stored_bytes),
+            (4, 1, 60)
+        );
+        assert!(!window.is_empty());
+
+        window.merge(&earlier);
+        assert_eq!(window.hits, 9);
+        assert!(later.since(&later).is_empty());
+    }
+}
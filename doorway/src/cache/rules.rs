@@ -21,11 +21,30 @@
 //!     ])
 //! }
 //! ```
+//!
+//! ## Default Policy
+//!
+//! Functions a DNA doesn't declare fall back to the operator's
+//! [`DefaultCachePolicy`], loaded from `CACHE_POLICY_FILE`:
+//!
+//! ```json
+//! { "ttl_secs": 300, "public": false, "cacheable_prefixes": ["get_", "list_"],
+//!   "deny": ["get_my_*", "get_session"],
+//!   "roles": { "imagodei": { "ttl_secs": 60, "deny": ["get_recovery_*"] } } }
+//! ```
+//!
+//! `deny` entries are names, or prefixes ending in `*`. A denied function is
+//! never cached, even when its DNA declares a rule for it.
 
 use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
 use std::time::Duration;
 
+use super::keys::input_refs;
+use super::CacheKey;
+
 // Re-export the shared CacheRule type from doorway-client
 pub use doorway_client::{CacheRule, CacheRuleBuilder, CACHE_RULES_FN};
 
@@ -35,6 +54,9 @@ pub trait CacheRuleExt {
     fn ttl(&self) -> Duration;
 
     /// Check if this rule allows public access based on response data
+    ///
+    /// The reach field may be a dotted path into the response; through an
+    /// array (`items.content.reach`), every element must have the reach.
     fn is_public_response(&self, response: &serde_json::Value) -> bool;
 }
 
@@ -51,40 +73,143 @@ impl CacheRuleExt for CacheRule {
 
         // Check reach field if specified
         if let (Some(field), Some(required_value)) = (&self.reach_field, &self.reach_value) {
-            if let Some(actual_value) = response.get(field).and_then(|v| v.as_str()) {
-                return actual_value == required_value;
-            }
+            let path: Vec<&str> = field.split('.').collect();
+            return reach_matches(response, &path, required_value);
         }
 
         false
     }
 }
 
+/// Whether the value at a dotted path equals `required`, for every element
+/// of any array along the way
+fn reach_matches(value: &serde_json::Value, path: &[&str], required: &str) -> bool {
+    match (value, path.split_first()) {
+        (serde_json::Value::Array(items), _) => {
+            items.iter().all(|item| reach_matches(item, path, required))
+        }
+        (_, Some((key, rest))) => value
+            .get(key)
+            .is_some_and(|inner| reach_matches(inner, rest, required)),
+        (_, None) => value.as_str() == Some(required),
+    }
+}
+
 /// Default rules applied when a DNA doesn't implement __doorway_cache_rules
 #[derive(Debug, Clone)]
 pub struct DefaultRules;
 
 impl DefaultRules {
-    /// Convention-based rules for common patterns
+    /// Convention-based rules for common patterns (built-in policy)
     pub fn for_function(fn_name: &str) -> Option<CacheRule> {
-        // get_* functions are cacheable by default
-        if fn_name.starts_with("get_") || fn_name.starts_with("list_") {
-            Some(CacheRule {
-                fn_name: fn_name.to_string(),
-                cacheable: true,
-                ttl_secs: 300, // 5 minutes for lists/gets
-                public: false, // Require auth by default
-                reach_field: None,
-                reach_value: None,
-                invalidated_by: vec![],
-            })
-        } else {
-            // create_*, update_*, delete_* are not cacheable
-            None
+        DefaultCachePolicy::default().rule_for(None, fn_name)
+    }
+}
+
+/// Operator policy for functions a DNA doesn't declare rules for
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DefaultCachePolicy {
+    /// TTL of convention rules
+    pub ttl_secs: u64,
+    /// Whether convention rules are served without auth
+    pub public: bool,
+    /// Undeclared functions with these prefixes are cacheable
+    pub cacheable_prefixes: Vec<String>,
+    /// Functions never cached, declared or not (names, or prefixes ending in `*`)
+    pub deny: Vec<String>,
+    /// Overrides by DNA role name
+    pub roles: HashMap<String, RoleCachePolicy>,
+}
+
+/// Per-role override of [`DefaultCachePolicy`]; unset fields inherit
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RoleCachePolicy {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl_secs: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cacheable_prefixes: Option<Vec<String>>,
+    /// Denied in this role, on top of the global deny-list
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub deny: Vec<String>,
+}
+
+impl Default for DefaultCachePolicy {
+    fn default() -> Self {
+        Self {
+            ttl_secs: 300, // 5 minutes for lists/gets
+            public: false, // Require auth by default
+            cacheable_prefixes: vec!["get_".to_string(), "list_".to_string()],
+            deny: vec![],
+            roles: HashMap::new(),
         }
     }
 }
 
+/// Name, or prefix when ending in `*`
+fn name_matches(pattern: &str, name: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => pattern == name,
+    }
+}
+
+impl DefaultCachePolicy {
+    /// Parse a policy document
+    pub fn parse(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| format!("Invalid cache policy: {e}"))
+    }
+
+    /// Load a policy file
+    pub fn load(path: &str) -> Result<Self, String> {
+        let json =
+            std::fs::read_to_string(path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+        Self::parse(&json).map_err(|e| format!("{path}: {e}"))
+    }
+
+    /// Whether `fn_name` must never be cached in `role`
+    pub fn is_denied(&self, role: Option<&str>, fn_name: &str) -> bool {
+        let role_deny = role
+            .and_then(|role| self.roles.get(role))
+            .map(|policy| policy.deny.as_slice())
+            .unwrap_or_default();
+        self.deny
+            .iter()
+            .chain(role_deny)
+            .any(|pattern| name_matches(pattern, fn_name))
+    }
+
+    /// Convention rule for an undeclared function, if it is cacheable
+    pub fn rule_for(&self, role: Option<&str>, fn_name: &str) -> Option<CacheRule> {
+        if self.is_denied(role, fn_name) {
+            return None;
+        }
+        let role_policy = role.and_then(|role| self.roles.get(role));
+        let prefixes = role_policy
+            .and_then(|policy| policy.cacheable_prefixes.as_ref())
+            .unwrap_or(&self.cacheable_prefixes);
+        // create_*, update_*, delete_* are not cacheable
+        if !prefixes
+            .iter()
+            .any(|prefix| fn_name.starts_with(prefix.as_str()))
+        {
+            return None;
+        }
+
+        let mut rule = CacheRule::new(fn_name);
+        rule.ttl_secs = role_policy
+            .and_then(|policy| policy.ttl_secs)
+            .unwrap_or(self.ttl_secs);
+        rule.public = role_policy
+            .and_then(|policy| policy.public)
+            .unwrap_or(self.public);
+        Some(rule)
+    }
+}
+
 /// Stored rules for a DNA
 #[derive(Debug, Clone)]
 pub struct DnaRules {
@@ -99,6 +224,33 @@ pub struct DnaRules {
 
     /// Reverse index: function -> functions it invalidates
     pub invalidation_map: HashMap<String, Vec<String>>,
+
+    /// Reverse index: "role:fn" in another DNA -> functions it invalidates here
+    pub bridge_invalidation_map: HashMap<String, Vec<String>>,
+
+    /// (invalidator, function) -> write input paths naming the entities the
+    /// write affects. Pairs not listed are invalidated wholesale.
+    pub invalidation_args: HashMap<(String, String), Vec<String>>,
+}
+
+/// A cached function made stale by a write
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct InvalidationTarget {
+    pub dna_hash: String,
+    pub fn_name: String,
+    /// Write input paths naming the affected entities; empty evicts every
+    /// cached call of the function
+    pub arg_paths: Vec<String>,
+}
+
+/// Split an `invalidated_by` entry into the writing function and, for
+/// argument-based entries like `create_relationship.source_id`, the path of
+/// the write input naming the affected entity
+fn parse_invalidator(invalidator: &str) -> (&str, Option<&str>) {
+    match invalidator.split_once('.') {
+        Some((source, path)) => (source, Some(path)),
+        None => (invalidator, None),
+    }
 }
 
 impl DnaRules {
@@ -109,6 +261,8 @@ impl DnaRules {
             rules: HashMap::new(),
             discovered: false,
             invalidation_map: HashMap::new(),
+            bridge_invalidation_map: HashMap::new(),
+            invalidation_args: HashMap::new(),
         }
     }
 
@@ -116,14 +270,35 @@ impl DnaRules {
     pub fn from_rules(dna_hash: &str, rules: Vec<CacheRule>) -> Self {
         let mut rule_map = HashMap::new();
         let mut invalidation_map: HashMap<String, Vec<String>> = HashMap::new();
+        let mut bridge_invalidation_map: HashMap<String, Vec<String>> = HashMap::new();
+        // None once any entry for the pair is wholesale
+        let mut invalidation_args: HashMap<(String, String), Option<Vec<String>>> = HashMap::new();
 
         for rule in rules {
-            // Build reverse invalidation map
-            for invalidator in &rule.invalidated_by {
-                invalidation_map
-                    .entry(invalidator.clone())
-                    .or_default()
-                    .push(rule.fn_name.clone());
+            let local = rule.invalidated_by.iter().map(|i| (i, false));
+            // Writes in other DNAs are tracked separately, keyed by role
+            let bridged = rule.bridge_invalidated_by.iter().map(|i| (i, true));
+
+            // Build reverse invalidation maps
+            for (invalidator, is_bridge) in local.chain(bridged) {
+                let (source, path) = parse_invalidator(invalidator);
+                let map = if is_bridge {
+                    &mut bridge_invalidation_map
+                } else {
+                    &mut invalidation_map
+                };
+                let targets = map.entry(source.to_string()).or_default();
+                if !targets.contains(&rule.fn_name) {
+                    targets.push(rule.fn_name.clone());
+                }
+
+                let args = invalidation_args
+                    .entry((source.to_string(), rule.fn_name.clone()))
+                    .or_insert_with(|| Some(vec![]));
+                match (args.as_mut(), path) {
+                    (Some(paths), Some(path)) => paths.push(path.to_string()),
+                    _ => *args = None,
+                }
             }
 
             rule_map.insert(rule.fn_name.clone(), rule);
@@ -134,6 +309,11 @@ impl DnaRules {
             rules: rule_map,
             discovered: true,
             invalidation_map,
+            bridge_invalidation_map,
+            invalidation_args: invalidation_args
+                .into_iter()
+                .filter_map(|(pair, paths)| Some((pair, paths?)))
+                .collect(),
         }
     }
 
@@ -152,12 +332,35 @@ impl DnaRules {
             .cloned()
             .unwrap_or_default()
     }
+
+    /// Get functions that should be invalidated when a function in another
+    /// DNA role is called
+    pub fn get_bridge_invalidations(&self, role_name: &str, fn_name: &str) -> Vec<String> {
+        self.bridge_invalidation_map
+            .get(&format!("{role_name}:{fn_name}"))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Write input paths naming the entities of `target` a write by
+    /// `invalidator` (a function, or "role:fn" for bridges) affects; empty
+    /// when the write invalidates the target wholesale
+    pub fn get_invalidation_args(&self, invalidator: &str, target: &str) -> Vec<String> {
+        self.invalidation_args
+            .get(&(invalidator.to_string(), target.to_string()))
+            .cloned()
+            .unwrap_or_default()
+    }
 }
 
 /// Store for cache rules across all DNAs
 pub struct CacheRuleStore {
     /// Rules indexed by DNA hash
     rules: DashMap<String, DnaRules>,
+    /// Policy for undeclared functions
+    policy: RwLock<Arc<DefaultCachePolicy>>,
+    /// Role name by DNA hash, for per-role policy overrides
+    roles: DashMap<String, String>,
 }
 
 impl CacheRuleStore {
@@ -165,9 +368,34 @@ impl CacheRuleStore {
     pub fn new() -> Self {
         Self {
             rules: DashMap::new(),
+            policy: RwLock::new(Arc::new(DefaultCachePolicy::default())),
+            roles: DashMap::new(),
         }
     }
 
+    /// Current policy for undeclared functions
+    pub fn policy(&self) -> Arc<DefaultCachePolicy> {
+        Arc::clone(&self.policy.read().unwrap())
+    }
+
+    /// Swap in the operator's policy for undeclared functions
+    pub fn set_policy(&self, policy: DefaultCachePolicy) {
+        *self.policy.write().unwrap() = Arc::new(policy);
+    }
+
+    /// Record which role a DNA plays, for per-role policy overrides
+    pub fn set_role(&self, dna_hash: &str, role_name: &str) {
+        self.roles
+            .insert(dna_hash.to_string(), role_name.to_string());
+    }
+
+    /// Whether the operator's deny-list forbids caching `fn_name` in `dna_hash`
+    pub fn is_denied(&self, dna_hash: &str, fn_name: &str) -> bool {
+        let role = self.roles.get(dna_hash);
+        self.policy()
+            .is_denied(role.as_deref().map(String::as_str), fn_name)
+    }
+
     /// Get rules for a DNA (creates empty entry if not exists)
     pub fn get_dna_rules(&self, dna_hash: &str) -> DnaRules {
         self.rules
@@ -199,8 +427,135 @@ impl CacheRuleStore {
     }
 
     /// Get rule for a specific function
+    ///
+    /// The DNA's declared rule wins over the default policy; the deny-list
+    /// wins over both.
     pub fn get_rule(&self, dna_hash: &str, fn_name: &str) -> Option<CacheRule> {
-        self.get_dna_rules(dna_hash).get_rule(fn_name)
+        let role = self.roles.get(dna_hash).map(|role| role.clone());
+        let policy = self.policy();
+        if policy.is_denied(role.as_deref(), fn_name) {
+            return None;
+        }
+        self.rules
+            .get(dna_hash)
+            .and_then(|dna_rules| dna_rules.rules.get(fn_name).cloned())
+            .or_else(|| policy.rule_for(role.as_deref(), fn_name))
+    }
+
+    /// Cache key for a call, in canonical form and narrowed to the rule's
+    /// `key_fields` when it declares any
+    pub fn cache_key<I: Serialize>(
+        &self,
+        dna_hash: &str,
+        zome: &str,
+        fn_name: &str,
+        input: &I,
+    ) -> CacheKey {
+        let input = serde_json::to_value(input).unwrap_or_default();
+        let key_fields = self
+            .get_rule(dna_hash, fn_name)
+            .map(|rule| rule.key_fields)
+            .unwrap_or_default();
+        CacheKey::for_input(dna_hash, zome, fn_name, &input, &key_fields)
+    }
+
+    /// Entity IDs a call's input names, to store with its cached response
+    /// for argument-based invalidation
+    pub fn cache_refs<I: Serialize>(
+        &self,
+        dna_hash: &str,
+        fn_name: &str,
+        input: &I,
+    ) -> Vec<String> {
+        let input = serde_json::to_value(input).unwrap_or_default();
+        let key_fields = self
+            .get_rule(dna_hash, fn_name)
+            .map(|rule| rule.key_fields)
+            .unwrap_or_default();
+        input_refs(&input, &key_fields)
+    }
+
+    /// Rules declared by DNAs (not convention defaults), as (dna_hash, rule)
+    pub fn declared_rules(&self) -> Vec<(String, CacheRule)> {
+        self.rules
+            .iter()
+            .flat_map(|entry| {
+                let dna_hash = entry.dna_hash.clone();
+                entry
+                    .rules
+                    .values()
+                    .map(move |rule| (dna_hash.clone(), rule.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Cached rules made stale by a write signal from `source_fn`, as
+    /// (dna_hash, rule) pairs.
+    ///
+    /// Signals don't say which DNA they came from, so every DNA's local
+    /// `invalidated_by` rules and any bridge rule naming the function are
+    /// included. Over-invalidating an unrelated DNA only costs a cache miss.
+    pub fn signal_invalidations(&self, source_fn: &str) -> Vec<(String, CacheRule)> {
+        let bridge_suffix = format!(":{source_fn}");
+        let mut targets = Vec::new();
+
+        for entry in self.rules.iter() {
+            let dna_rules = entry.value();
+            let mut fns = dna_rules.get_invalidations(source_fn);
+            for (invalidator, bridged) in &dna_rules.bridge_invalidation_map {
+                if invalidator.ends_with(&bridge_suffix) {
+                    fns.extend(bridged.iter().cloned());
+                }
+            }
+            fns.sort();
+            fns.dedup();
+
+            for fn_name in fns {
+                if let Some(rule) = dna_rules.get_rule(&fn_name) {
+                    targets.push((dna_rules.dna_hash.clone(), rule));
+                }
+            }
+        }
+
+        targets
+    }
+
+    /// Cached functions to drop after `fn_name` was called on `dna_hash`
+    /// (role `role_name`).
+    ///
+    /// Includes the calling DNA's own `invalidated_by` rules plus any other
+    /// DNA whose rules depend on this role through a bridge call. Targets
+    /// declared with argument paths carry them for partial invalidation.
+    pub fn write_invalidations(
+        &self,
+        role_name: &str,
+        dna_hash: &str,
+        fn_name: &str,
+    ) -> Vec<InvalidationTarget> {
+        let bridge_invalidator = format!("{role_name}:{fn_name}");
+        let mut targets = Vec::new();
+
+        for entry in self.rules.iter() {
+            let dna_rules = entry.value();
+            let (invalidator, fns) = if dna_rules.dna_hash == dna_hash {
+                (fn_name, dna_rules.get_invalidations(fn_name))
+            } else {
+                (
+                    bridge_invalidator.as_str(),
+                    dna_rules.get_bridge_invalidations(role_name, fn_name),
+                )
+            };
+            for target in fns {
+                targets.push(InvalidationTarget {
+                    dna_hash: dna_rules.dna_hash.clone(),
+                    arg_paths: dna_rules.get_invalidation_args(invalidator, &target),
+                    fn_name: target,
+                });
+            }
+        }
+
+        targets
     }
 }
 
@@ -237,6 +592,9 @@ mod tests {
             reach_field: Some("reach".into()),
             reach_value: Some("commons".into()),
             invalidated_by: vec![],
+            bridge_invalidated_by: vec![],
+            keyed_by_id: false,
+            key_fields: vec![],
         };
 
         let public_response = serde_json::json!({"reach": "commons", "title": "Test"});
@@ -246,6 +604,27 @@ mod tests {
         assert!(!rule.is_public_response(&private_response));
     }
 
+    #[test]
+    fn test_is_public_response_nested_items() {
+        let rule = CacheRuleBuilder::new("query_content")
+            .reach_based("items.content.reach", "commons")
+            .build();
+
+        let commons = serde_json::json!({"items": [
+            {"content": {"id": "a", "reach": "commons"}},
+            {"content": {"id": "b", "reach": "commons"}}
+        ]});
+        let mixed = serde_json::json!({"items": [
+            {"content": {"id": "a", "reach": "commons"}},
+            {"content": {"id": "b", "reach": "private"}}
+        ]});
+
+        assert!(rule.is_public_response(&commons));
+        assert!(!rule.is_public_response(&mixed));
+        assert!(rule.is_public_response(&serde_json::json!({"items": []})));
+        assert!(!rule.is_public_response(&serde_json::json!({"total_count": 0})));
+    }
+
     #[test]
     fn test_explicit_public() {
         let rule = CacheRule {
@@ -256,6 +635,9 @@ mod tests {
             reach_field: None,
             reach_value: None,
             invalidated_by: vec![],
+            bridge_invalidated_by: vec![],
+            keyed_by_id: false,
+            key_fields: vec![],
         };
 
         // Any response is public when public=true
@@ -272,6 +654,84 @@ mod tests {
         assert!(DefaultRules::for_function("random_function").is_none());
     }
 
+    #[test]
+    fn test_default_policy_role_overrides() {
+        let policy = DefaultCachePolicy::parse(
+            r#"{
+                "ttl_secs": 120,
+                "cacheable_prefixes": ["get_", "list_", "query_"],
+                "deny": ["get_session"],
+                "roles": {
+                    "imagodei": { "ttl_secs": 30, "public": false, "deny": ["get_my_*"] },
+                    "lamad": { "public": true, "cacheable_prefixes": ["get_"] }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let rule = policy.rule_for(None, "query_content").unwrap();
+        assert_eq!((rule.ttl_secs, rule.public), (120, false));
+
+        let rule = policy.rule_for(Some("imagodei"), "get_agent").unwrap();
+        assert_eq!(rule.ttl_secs, 30);
+        assert!(policy
+            .rule_for(Some("imagodei"), "get_my_mastery")
+            .is_none());
+        // Role deny-lists don't leak into other roles
+        assert!(policy.rule_for(Some("lamad"), "get_my_mastery").is_some());
+
+        let rule = policy.rule_for(Some("lamad"), "get_path").unwrap();
+        assert!(rule.public);
+        assert!(policy.rule_for(Some("lamad"), "list_paths").is_none());
+
+        assert!(policy.rule_for(Some("lamad"), "get_session").is_none());
+        assert!(policy.rule_for(None, "create_content").is_none());
+
+        assert!(DefaultCachePolicy::parse(r#"{"ttl_secs": "soon"}"#).is_err());
+    }
+
+    #[test]
+    fn test_rule_store_deny_list_beats_declared_rules() {
+        let store = CacheRuleStore::new();
+        store.set_dna_rules(
+            "imagodei_dna",
+            vec![CacheRuleBuilder::new("get_my_mastery").ttl_1h().build()],
+        );
+        assert!(store.get_rule("imagodei_dna", "get_my_mastery").is_some());
+
+        let mut policy = DefaultCachePolicy::default();
+        policy.roles.insert(
+            "imagodei".to_string(),
+            RoleCachePolicy {
+                ttl_secs: Some(45),
+                deny: vec!["get_my_*".to_string()],
+                ..Default::default()
+            },
+        );
+        store.set_policy(policy);
+
+        // Role unknown: only global settings apply
+        assert!(store.get_rule("imagodei_dna", "get_my_mastery").is_some());
+        assert_eq!(
+            store
+                .get_rule("imagodei_dna", "get_agent")
+                .unwrap()
+                .ttl_secs,
+            300
+        );
+
+        store.set_role("imagodei_dna", "imagodei");
+        assert!(store.get_rule("imagodei_dna", "get_my_mastery").is_none());
+        assert!(store.is_denied("imagodei_dna", "get_my_mastery"));
+        assert_eq!(
+            store
+                .get_rule("imagodei_dna", "get_agent")
+                .unwrap()
+                .ttl_secs,
+            45
+        );
+    }
+
     #[test]
     fn test_dna_rules_invalidation_map() {
         let rules = vec![
@@ -283,6 +743,9 @@ mod tests {
                 reach_field: None,
                 reach_value: None,
                 invalidated_by: vec!["create_content".into(), "update_content".into()],
+                bridge_invalidated_by: vec![],
+                keyed_by_id: false,
+                key_fields: vec![],
             },
             CacheRule {
                 fn_name: "list_content".into(),
@@ -292,6 +755,9 @@ mod tests {
                 reach_field: None,
                 reach_value: None,
                 invalidated_by: vec!["create_content".into(), "delete_content".into()],
+                bridge_invalidated_by: vec![],
+                keyed_by_id: false,
+                key_fields: vec![],
             },
         ];
 
@@ -308,6 +774,214 @@ mod tests {
         assert!(!invalidated.contains(&"list_content".to_string()));
     }
 
+    #[test]
+    fn test_dna_rules_bridge_invalidation_map() {
+        let rules = vec![CacheRule {
+            fn_name: "get_path_with_mastery".into(),
+            cacheable: true,
+            ttl_secs: 300,
+            public: false,
+            reach_field: None,
+            reach_value: None,
+            invalidated_by: vec!["update_path".into()],
+            bridge_invalidated_by: vec!["imagodei:upsert_mastery".into()],
+            keyed_by_id: false,
+            key_fields: vec![],
+        }];
+
+        let dna_rules = DnaRules::from_rules("lamad_dna", rules);
+
+        assert_eq!(
+            dna_rules.get_bridge_invalidations("imagodei", "upsert_mastery"),
+            vec!["get_path_with_mastery".to_string()]
+        );
+        // Same function name in a different role is unrelated
+        assert!(dna_rules
+            .get_bridge_invalidations("other", "upsert_mastery")
+            .is_empty());
+        // Bridge invalidators don't leak into the local map
+        assert!(dna_rules.get_invalidations("upsert_mastery").is_empty());
+    }
+
+    #[test]
+    fn test_rule_store_write_invalidations() {
+        let store = CacheRuleStore::new();
+        store.set_dna_rules(
+            "lamad_dna",
+            vec![CacheRule {
+                fn_name: "get_path_with_mastery".into(),
+                cacheable: true,
+                ttl_secs: 300,
+                public: false,
+                reach_field: None,
+                reach_value: None,
+                invalidated_by: vec!["update_path".into()],
+                bridge_invalidated_by: vec!["imagodei:upsert_mastery".into()],
+                keyed_by_id: false,
+                key_fields: vec![],
+            }],
+        );
+        store.set_dna_rules(
+            "imagodei_dna",
+            vec![CacheRule {
+                fn_name: "get_my_mastery".into(),
+                cacheable: true,
+                ttl_secs: 60,
+                public: false,
+                reach_field: None,
+                reach_value: None,
+                invalidated_by: vec!["upsert_mastery".into()],
+                bridge_invalidated_by: vec![],
+                keyed_by_id: false,
+                key_fields: vec![],
+            }],
+        );
+
+        let pairs = |targets: Vec<InvalidationTarget>| {
+            let mut pairs: Vec<(String, String)> = targets
+                .into_iter()
+                .map(|target| (target.dna_hash, target.fn_name))
+                .collect();
+            pairs.sort();
+            pairs
+        };
+
+        // An imagodei write invalidates its own reads and the bridged lamad read
+        let targets = store.write_invalidations("imagodei", "imagodei_dna", "upsert_mastery");
+        assert_eq!(
+            pairs(targets),
+            vec![
+                ("imagodei_dna".to_string(), "get_my_mastery".to_string()),
+                ("lamad_dna".to_string(), "get_path_with_mastery".to_string()),
+            ]
+        );
+
+        // A local lamad write only touches lamad
+        let targets = store.write_invalidations("lamad", "lamad_dna", "update_path");
+        assert_eq!(
+            pairs(targets),
+            vec![("lamad_dna".to_string(), "get_path_with_mastery".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_argument_invalidators() {
+        let store = CacheRuleStore::new();
+        store.set_dna_rules(
+            "lamad_dna",
+            vec![
+                CacheRuleBuilder::new("get_relationships")
+                    .invalidated_by(vec![
+                        "create_relationship.source_id",
+                        "create_relationship.target_id",
+                        "delete_relationship",
+                    ])
+                    .build(),
+                // A bare entry for the same write wins over its argument entry
+                CacheRuleBuilder::new("get_graph")
+                    .invalidated_by(vec!["create_relationship.source_id", "create_relationship"])
+                    .build(),
+            ],
+        );
+
+        let mut targets = store.write_invalidations("lamad", "lamad_dna", "create_relationship");
+        targets.sort();
+        assert_eq!(targets.len(), 2);
+        assert_eq!(targets[0].fn_name, "get_graph");
+        assert!(targets[0].arg_paths.is_empty());
+        assert_eq!(targets[1].fn_name, "get_relationships");
+        assert_eq!(targets[1].arg_paths, vec!["source_id", "target_id"]);
+
+        let targets = store.write_invalidations("lamad", "lamad_dna", "delete_relationship");
+        assert!(targets[0].arg_paths.is_empty());
+
+        // Signals still find the rule by the bare function name
+        assert_eq!(store.signal_invalidations("create_relationship").len(), 2);
+    }
+
+    #[test]
+    fn test_rule_store_signal_invalidations() {
+        let store = CacheRuleStore::new();
+        store.set_dna_rules(
+            "lamad_dna",
+            vec![
+                CacheRule {
+                    fn_name: "get_step_by_id".into(),
+                    cacheable: true,
+                    ttl_secs: 900,
+                    public: true,
+                    reach_field: None,
+                    reach_value: None,
+                    invalidated_by: vec!["update_step".into()],
+                    bridge_invalidated_by: vec![],
+                    keyed_by_id: true,
+                    key_fields: vec![],
+                },
+                CacheRule {
+                    fn_name: "get_path_with_steps".into(),
+                    cacheable: true,
+                    ttl_secs: 900,
+                    public: true,
+                    reach_field: None,
+                    reach_value: None,
+                    invalidated_by: vec!["update_step".into()],
+                    bridge_invalidated_by: vec!["imagodei:upsert_mastery".into()],
+                    keyed_by_id: false,
+                    key_fields: vec![],
+                },
+            ],
+        );
+
+        let mut targets: Vec<(String, String, bool)> = store
+            .signal_invalidations("update_step")
+            .into_iter()
+            .map(|(dna, rule)| (dna, rule.fn_name, rule.keyed_by_id))
+            .collect();
+        targets.sort();
+        assert_eq!(
+            targets,
+            vec![
+                (
+                    "lamad_dna".to_string(),
+                    "get_path_with_steps".to_string(),
+                    false
+                ),
+                ("lamad_dna".to_string(), "get_step_by_id".to_string(), true),
+            ]
+        );
+
+        // Bridge rules match on function name when the signal has no role
+        let targets = store.signal_invalidations("upsert_mastery");
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].1.fn_name, "get_path_with_steps");
+
+        assert!(store.signal_invalidations("unrelated_fn").is_empty());
+    }
+
+    #[test]
+    fn test_rule_store_cache_key_uses_key_fields() {
+        let store = CacheRuleStore::new();
+        store.set_dna_rules(
+            "dna",
+            vec![CacheRuleBuilder::new("get_content")
+                .key_on(vec!["content_id"])
+                .build()],
+        );
+
+        let a = serde_json::json!({"content_id": "c1", "include_related": true});
+        let b = serde_json::json!({"content_id": "c1"});
+        assert_eq!(
+            store.cache_key("dna", "content_store", "get_content", &a),
+            store.cache_key("dna", "content_store", "get_content", &b)
+        );
+
+        // Convention rules key on the whole (canonical) input
+        assert_ne!(
+            store.cache_key("dna", "content_store", "get_other", &a),
+            store.cache_key("dna", "content_store", "get_other", &b)
+        );
+    }
+
     #[test]
     fn test_rule_store() {
         let store = CacheRuleStore::new();
@@ -326,6 +1000,9 @@ mod tests {
                 reach_field: None,
                 reach_value: None,
                 invalidated_by: vec![],
+                bridge_invalidated_by: vec![],
+                keyed_by_id: false,
+                key_fields: vec![],
             }],
         );
 
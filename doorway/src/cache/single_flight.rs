@@ -0,0 +1,220 @@
+//! Single-flight coalescing of identical in-flight calls
+//!
+//! On a cold cache, a burst of clients asking for the same thing (every
+//! learner opening the path list after a deploy) would each miss and each
+//! call the conductor. [`SingleFlight`] lets the first caller for a key run
+//! the call while later callers for the same key wait and share its result,
+//! so the conductor sees one call per key however many clients are waiting.
+//!
+//! Keys are cache storage keys ([`CacheKey`](super::CacheKey)), so calls
+//! coalesce exactly when their responses would share a cache entry. Only
+//! calls already in flight are shared: the next call after one completes
+//! runs again (or is served by the response cache).
+//!
+//! If the caller running the call is cancelled, one of the waiters takes
+//! over and runs it instead.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tokio::sync::watch;
+
+/// Calls run and calls that shared another caller's result
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct SingleFlightStats {
+    pub in_flight: usize,
+    pub calls: u64,
+    pub coalesced: u64,
+}
+
+struct Flight<T> {
+    id: u64,
+    result: watch::Receiver<Option<T>>,
+}
+
+/// In-flight calls by key
+pub struct SingleFlight<T> {
+    flights: Mutex<HashMap<String, Flight<T>>>,
+    next_id: AtomicU64,
+    calls: AtomicU64,
+    coalesced: AtomicU64,
+}
+
+/// Whether a caller runs the call or waits for the one in flight
+enum Seat<T> {
+    Run(watch::Sender<Option<T>>, u64),
+    Wait(watch::Receiver<Option<T>>),
+}
+
+/// Removes a flight when its caller finishes or is cancelled
+struct Landing<'a, T> {
+    flights: &'a Mutex<HashMap<String, Flight<T>>>,
+    key: &'a str,
+    id: u64,
+}
+
+impl<T> Drop for Landing<'_, T> {
+    fn drop(&mut self) {
+        let mut flights = self.flights.lock().unwrap();
+        if flights.get(self.key).is_some_and(|f| f.id == self.id) {
+            flights.remove(self.key);
+        }
+    }
+}
+
+impl<T: Clone> SingleFlight<T> {
+    pub fn new() -> Self {
+        Self {
+            flights: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(0),
+            calls: AtomicU64::new(0),
+            coalesced: AtomicU64::new(0),
+        }
+    }
+
+    /// Run `call` for `key`, or wait for the identical call already in flight
+    pub async fn run<F, Fut>(&self, key: &str, call: F) -> T
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = T>,
+    {
+        let mut call = Some(call);
+        loop {
+            let seat = {
+                let mut flights = self.flights.lock().unwrap();
+                match flights.get(key) {
+                    Some(flight) => Seat::Wait(flight.result.clone()),
+                    None => {
+                        let (sender, result) = watch::channel(None);
+                        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+                        flights.insert(key.to_string(), Flight { id, result });
+                        Seat::Run(sender, id)
+                    }
+                }
+            };
+            let (sender, id) = match seat {
+                Seat::Run(sender, id) => (sender, id),
+                Seat::Wait(waiting) => {
+                    if let Some(result) = Self::wait(waiting).await {
+                        self.coalesced.fetch_add(1, Ordering::Relaxed);
+                        return result;
+                    }
+                    // The caller running it was cancelled; try again
+                    continue;
+                }
+            };
+
+            let _landing = Landing {
+                flights: &self.flights,
+                key,
+                id,
+            };
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            let call = call.take().expect("single-flight call runs once");
+            let result = call().await;
+            let _ = sender.send(Some(result.clone()));
+            return result;
+        }
+    }
+
+    async fn wait(mut result: watch::Receiver<Option<T>>) -> Option<T> {
+        result
+            .wait_for(Option::is_some)
+            .await
+            .ok()
+            .and_then(|result| result.clone())
+    }
+
+    pub fn stats(&self) -> SingleFlightStats {
+        SingleFlightStats {
+            in_flight: self.flights.lock().unwrap().len(),
+            calls: self.calls.load(Ordering::Relaxed),
+            coalesced: self.coalesced.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl<T: Clone> Default for SingleFlight<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_identical_calls_share_one_upstream_call() {
+        let flights = Arc::new(SingleFlight::<Result<u32, String>>::new());
+        let upstream = Arc::new(AtomicUsize::new(0));
+
+        let mut waiters = Vec::new();
+        for _ in 0..50 {
+            let flights = Arc::clone(&flights);
+            let upstream = Arc::clone(&upstream);
+            waiters.push(tokio::spawn(async move {
+                flights
+                    .run("dna:content_store:get_all_paths:def", || async {
+                        upstream.fetch_add(1, Ordering::SeqCst);
+                        tokio::time::sleep(Duration::from_millis(50)).await;
+                        Ok(7)
+                    })
+                    .await
+            }));
+        }
+        for waiter in waiters {
+            assert_eq!(waiter.await.unwrap(), Ok(7));
+        }
+
+        assert_eq!(upstream.load(Ordering::SeqCst), 1);
+        let stats = flights.stats();
+        assert_eq!((stats.calls, stats.coalesced, stats.in_flight), (1, 49, 0));
+    }
+
+    #[tokio::test]
+    async fn test_different_keys_and_later_calls_run_separately() {
+        let flights = SingleFlight::<u32>::new();
+        assert_eq!(flights.run("a", || async { 1 }).await, 1);
+        assert_eq!(flights.run("a", || async { 2 }).await, 2);
+        let (a, b) = tokio::join!(
+            flights.run("a", || async { 3 }),
+            flights.run("b", || async { 4 })
+        );
+        assert_eq!((a, b), (3, 4));
+        assert_eq!(flights.stats().coalesced, 0);
+    }
+
+    #[tokio::test]
+    async fn test_waiter_takes_over_when_caller_is_cancelled() {
+        let flights = Arc::new(SingleFlight::<u32>::new());
+
+        let leader = {
+            let flights = Arc::clone(&flights);
+            tokio::spawn(async move {
+                flights
+                    .run("key", || async {
+                        tokio::time::sleep(Duration::from_secs(60)).await;
+                        1
+                    })
+                    .await
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        let waiter = {
+            let flights = Arc::clone(&flights);
+            tokio::spawn(async move { flights.run("key", || async { 2 }).await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        leader.abort();
+
+        assert_eq!(waiter.await.unwrap(), 2);
+        assert_eq!(flights.stats().in_flight, 0);
+    }
+}
@@ -0,0 +1,319 @@
+//! Cache snapshots for blue-green deploys
+//!
+//! Serializes the hot response cache so a freshly deployed doorway can start
+//! warm instead of stampeding the conductor. A snapshot is newline-delimited
+//! JSON: a header line, then one line per entry with its remaining TTL.
+//! Loading subtracts the time since the dump, so entries never outlive the
+//! TTL they were cached with.
+//!
+//! Snapshots are taken and loaded through `GET /admin/cache/dump` and
+//! `POST /admin/cache/load`, or `doorway cache dump|load` from the CLI.
+
+use base64::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use thiserror::Error;
+use tracing::info;
+
+use super::{CacheEntry, ContentCache};
+
+/// Format tag on the header line
+pub const SNAPSHOT_FORMAT: &str = "doorway-cache-v1";
+
+/// Content-Type of a snapshot
+pub const SNAPSHOT_CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// Snapshot errors
+#[derive(Debug, Error)]
+pub enum SnapshotError {
+    #[error("Snapshot is empty")]
+    Empty,
+
+    #[error("Unsupported snapshot format: {0}")]
+    UnsupportedFormat(String),
+
+    #[error("Malformed snapshot line {line}: {reason}")]
+    Malformed { line: usize, reason: String },
+}
+
+/// First line of a snapshot
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SnapshotHeader {
+    pub format: String,
+    /// Unix seconds when the snapshot was taken
+    pub dumped_at: i64,
+    pub entries: usize,
+}
+
+/// One cached response
+#[derive(Debug, Serialize, Deserialize)]
+struct SnapshotEntry {
+    key: String,
+    /// Base64 response bytes
+    data: String,
+    content_type: String,
+    /// Remaining TTL at dump time
+    ttl_secs: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    reach: Option<String>,
+    cache_priority: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    bandwidth_class: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    geographic_affinity: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    refs: Vec<String>,
+}
+
+impl SnapshotEntry {
+    fn from_entry(key: String, entry: CacheEntry) -> Self {
+        Self {
+            key,
+            data: BASE64_STANDARD.encode(&entry.data),
+            ttl_secs: entry.remaining_ttl_secs(),
+            content_type: entry.content_type,
+            reach: entry.reach,
+            cache_priority: entry.cache_priority,
+            bandwidth_class: entry.bandwidth_class,
+            geographic_affinity: entry.geographic_affinity,
+            refs: entry.refs,
+        }
+    }
+}
+
+/// Outcome of loading a snapshot
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoadSummary {
+    /// Entries added to the cache
+    pub loaded: usize,
+    /// Entries whose TTL ran out since the dump
+    pub expired: usize,
+    /// Entries already cached here
+    pub skipped: usize,
+}
+
+/// Serialize all live entries
+pub fn dump(cache: &ContentCache, now_unix: i64) -> Vec<u8> {
+    let entries = cache.live_entries();
+    let header = SnapshotHeader {
+        format: SNAPSHOT_FORMAT.to_string(),
+        dumped_at: now_unix,
+        entries: entries.len(),
+    };
+
+    let mut out = serde_json::to_vec(&header).unwrap_or_default();
+    out.push(b'\n');
+    for (key, entry) in entries {
+        let line = SnapshotEntry::from_entry(key, entry);
+        if let Ok(json) = serde_json::to_vec(&line) {
+            out.extend_from_slice(&json);
+            out.push(b'\n');
+        }
+    }
+    out
+}
+
+/// Load a snapshot into the cache
+///
+/// The whole snapshot is validated before anything is cached, so a truncated
+/// transfer loads nothing.
+pub fn load(
+    cache: &ContentCache,
+    snapshot: &[u8],
+    now_unix: i64,
+) -> Result<LoadSummary, SnapshotError> {
+    let mut lines = snapshot
+        .split(|b| *b == b'\n')
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty());
+
+    let (_, header_line) = lines.next().ok_or(SnapshotError::Empty)?;
+    let header: SnapshotHeader =
+        serde_json::from_slice(header_line).map_err(|e| SnapshotError::Malformed {
+            line: 1,
+            reason: e.to_string(),
+        })?;
+    if header.format != SNAPSHOT_FORMAT {
+        return Err(SnapshotError::UnsupportedFormat(header.format));
+    }
+
+    let mut entries = Vec::with_capacity(header.entries);
+    for (index, line) in lines {
+        let malformed = |reason: String| SnapshotError::Malformed {
+            line: index + 1,
+            reason,
+        };
+        let entry: SnapshotEntry =
+            serde_json::from_slice(line).map_err(|e| malformed(e.to_string()))?;
+        let data = BASE64_STANDARD
+            .decode(&entry.data)
+            .map_err(|e| malformed(e.to_string()))?;
+        entries.push((entry, data));
+    }
+    if entries.len() != header.entries {
+        return Err(SnapshotError::Malformed {
+            line: entries.len() + 1,
+            reason: format!(
+                "expected {} entries, found {}",
+                header.entries,
+                entries.len()
+            ),
+        });
+    }
+
+    let elapsed = now_unix.saturating_sub(header.dumped_at).max(0) as u64;
+    let mut summary = LoadSummary::default();
+    for (entry, data) in entries {
+        let ttl_secs = entry.ttl_secs.saturating_sub(elapsed);
+        if ttl_secs == 0 {
+            summary.expired += 1;
+            continue;
+        }
+        let ttl = Duration::from_secs(ttl_secs);
+        let mut cached = CacheEntry::new(data, ttl, &entry.content_type);
+        cached.reach = entry.reach;
+        cached.cache_priority = entry.cache_priority.min(100);
+        cached.bandwidth_class = entry.bandwidth_class;
+        cached.geographic_affinity = entry.geographic_affinity;
+        cached.refs = entry.refs;
+
+        if cache.insert_entry(&entry.key, cached) {
+            summary.loaded += 1;
+        } else {
+            summary.skipped += 1;
+        }
+    }
+
+    info!(
+        loaded = summary.loaded,
+        expired = summary.expired,
+        skipped = summary.skipped,
+        "Cache snapshot loaded"
+    );
+    Ok(summary)
+}
+
+/// Fetch a running doorway's snapshot (`GET /admin/cache/dump`)
+pub async fn fetch_snapshot(
+    client: &reqwest::Client,
+    doorway_url: &str,
+    token: &str,
+) -> Result<bytes::Bytes, String> {
+    let url = format!("{}/admin/cache/dump", doorway_url.trim_end_matches('/'));
+    let response = client
+        .get(&url)
+        .bearer_auth(token)
+        .send()
+        .await
+        .map_err(|e| format!("{url}: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!("{url}: HTTP {}", response.status()));
+    }
+    response.bytes().await.map_err(|e| format!("{url}: {e}"))
+}
+
+/// Load a snapshot into a running doorway (`POST /admin/cache/load`)
+pub async fn push_snapshot(
+    client: &reqwest::Client,
+    doorway_url: &str,
+    token: &str,
+    snapshot: bytes::Bytes,
+) -> Result<LoadSummary, String> {
+    let url = format!("{}/admin/cache/load", doorway_url.trim_end_matches('/'));
+    let response = client
+        .post(&url)
+        .bearer_auth(token)
+        .header("Content-Type", SNAPSHOT_CONTENT_TYPE)
+        .body(snapshot)
+        .send()
+        .await
+        .map_err(|e| format!("{url}: {e}"))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let detail = response.text().await.unwrap_or_default();
+        return Err(format!("{url}: HTTP {status} {detail}"));
+    }
+    response
+        .json::<LoadSummary>()
+        .await
+        .map_err(|e| format!("{url}: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn warm_cache() -> ContentCache {
+        let cache = ContentCache::with_defaults();
+        cache.set(
+            "dna:content_store:get_content:abc",
+            b"{\"id\":\"manifesto\"}".to_vec(),
+            "application/json",
+            Duration::from_secs(300),
+        );
+        cache.set(
+            "dna:content_store:get_all_paths:def",
+            b"[]".to_vec(),
+            "application/json",
+            Duration::from_secs(30),
+        );
+        cache
+    }
+
+    #[test]
+    fn test_dump_and_load() {
+        let snapshot = dump(&warm_cache(), 1_000);
+        let fresh = ContentCache::with_defaults();
+
+        let summary = load(&fresh, &snapshot, 1_010).unwrap();
+        assert_eq!(summary.loaded, 2);
+        let entry = fresh.get("dna:content_store:get_content:abc").unwrap();
+        assert_eq!(entry.data, b"{\"id\":\"manifesto\"}");
+        assert!(entry.remaining_ttl_secs() <= 290);
+    }
+
+    #[test]
+    fn test_load_drops_expired_entries() {
+        let snapshot = dump(&warm_cache(), 1_000);
+        let fresh = ContentCache::with_defaults();
+
+        let summary = load(&fresh, &snapshot, 1_060).unwrap();
+        assert_eq!(summary.loaded, 1);
+        assert_eq!(summary.expired, 1);
+        assert!(fresh.get("dna:content_store:get_all_paths:def").is_none());
+    }
+
+    #[test]
+    fn test_load_keeps_existing_entries() {
+        let snapshot = dump(&warm_cache(), 1_000);
+        let target = ContentCache::with_defaults();
+        target.set(
+            "dna:content_store:get_all_paths:def",
+            b"[\"newer\"]".to_vec(),
+            "application/json",
+            Duration::from_secs(60),
+        );
+
+        let summary = load(&target, &snapshot, 1_000).unwrap();
+        assert_eq!(summary.skipped, 1);
+        let entry = target.get("dna:content_store:get_all_paths:def").unwrap();
+        assert_eq!(entry.data, b"[\"newer\"]");
+    }
+
+    #[test]
+    fn test_truncated_snapshot_loads_nothing() {
+        let snapshot = dump(&warm_cache(), 1_000);
+        let cut = snapshot.len() - 20;
+        let fresh = ContentCache::with_defaults();
+
+        assert!(matches!(
+            load(&fresh, &snapshot[..cut], 1_000),
+            Err(SnapshotError::Malformed { .. })
+        ));
+        assert_eq!(fresh.stats().entries, 0);
+        assert!(matches!(
+            load(&fresh, b"", 1_000),
+            Err(SnapshotError::Empty)
+        ));
+    }
+}
@@ -10,7 +10,8 @@
 //! - `get_range()` - Get byte range for HTTP 206 Partial Content
 //! - `blob_size()` - Get blob size without loading data
 
-use super::CacheConfig;
+use super::rule_stats::RuleStats;
+use super::{CacheConfig, CacheKey};
 use bytes::Bytes;
 use dashmap::DashMap;
 use futures::stream::{self, Stream};
@@ -53,6 +54,8 @@ pub struct CacheEntry {
     pub bandwidth_class: Option<String>,
     /// Geographic affinity hint for source prioritization
     pub geographic_affinity: Option<String>,
+    /// Entity IDs the cached call's input named (argument-based invalidation)
+    pub refs: Vec<String>,
 }
 
 impl CacheEntry {
@@ -70,6 +73,7 @@ impl CacheEntry {
             cache_priority: 50, // Default priority
             bandwidth_class: None,
             geographic_affinity: None,
+            refs: Vec::new(),
         }
     }
 
@@ -95,6 +99,7 @@ impl CacheEntry {
             cache_priority: cache_priority.clamp(0, 100),
             bandwidth_class: bandwidth_class.map(|s| s.to_string()),
             geographic_affinity: geographic_affinity.map(|s| s.to_string()),
+            refs: Vec::new(),
         }
     }
 
@@ -157,6 +162,8 @@ pub struct ContentCache {
     misses: AtomicU64,
     /// Eviction counter
     evictions: AtomicU64,
+    /// Counters per (dna_hash, fn_name)
+    rule_stats: RuleStats,
 }
 
 impl ContentCache {
@@ -179,6 +186,7 @@ impl ContentCache {
             hits: AtomicU64::new(0),
             misses: AtomicU64::new(0),
             evictions: AtomicU64::new(0),
+            rule_stats: RuleStats::new(),
         }
     }
 
@@ -188,23 +196,38 @@ impl ContentCache {
     }
 
     /// Get an entry from the cache by storage key
+    ///
+    /// Expired entries are a miss but stay until the next [`cleanup`](Self::cleanup),
+    /// so [`get_stale`](Self::get_stale) can fall back to them.
     pub fn get(&self, storage_key: &str) -> Option<CacheEntry> {
         if let Some(entry) = self.entries.get(storage_key) {
             if !entry.is_expired() {
                 self.hits.fetch_add(1, Ordering::Relaxed);
+                self.rule_stats.record_hit(storage_key);
                 debug!(key = storage_key, "Cache hit");
                 return Some(entry.clone());
             }
-            // Entry expired, remove it
-            drop(entry); // Release the reference before removing
-            self.entries.remove(storage_key);
         }
 
         self.misses.fetch_add(1, Ordering::Relaxed);
+        self.rule_stats.record_miss(storage_key);
         debug!(key = storage_key, "Cache miss");
         None
     }
 
+    /// Get an entry even if it has expired
+    ///
+    /// For serving the last known response when refreshing it failed (e.g.
+    /// the conductor is unavailable).
+    pub fn get_stale(&self, storage_key: &str) -> Option<CacheEntry> {
+        let entry = self.entries.get(storage_key)?.clone();
+        if entry.is_expired() {
+            self.rule_stats.record_stale_serve(storage_key);
+            debug!(key = storage_key, "Serving stale cache entry");
+        }
+        Some(entry)
+    }
+
     /// Check if an ETag matches the cached entry
     pub fn check_etag(&self, storage_key: &str, etag: &str) -> Option<bool> {
         self.entries.get(storage_key).map(|entry| {
@@ -218,7 +241,21 @@ impl ContentCache {
 
     /// Store an entry in the cache with explicit TTL
     pub fn set(&self, storage_key: &str, data: Vec<u8>, content_type: &str, ttl: Duration) {
-        let entry = CacheEntry::new(data, ttl, content_type);
+        self.set_with_refs(storage_key, data, content_type, ttl, Vec::new());
+    }
+
+    /// Set a zome call response along with the entity IDs its input named
+    pub fn set_with_refs(
+        &self,
+        storage_key: &str,
+        data: Vec<u8>,
+        content_type: &str,
+        ttl: Duration,
+        refs: Vec<String>,
+    ) {
+        self.rule_stats.record_store(storage_key, data.len());
+        let mut entry = CacheEntry::new(data, ttl, content_type);
+        entry.refs = refs;
         debug!(key = storage_key, ttl_secs = ttl.as_secs(), "Cache set");
         self.entries.insert(storage_key.to_string(), entry);
 
@@ -243,6 +280,7 @@ impl ContentCache {
         let count = keys_to_remove.len();
         for key in keys_to_remove {
             self.entries.remove(&key);
+            self.rule_stats.record_invalidation(&key);
         }
 
         if count > 0 {
@@ -265,6 +303,109 @@ impl ContentCache {
         self.invalidate_pattern(&format!("{dna_hash}:{zome}:{fn_name}:"))
     }
 
+    /// Invalidate a function's entries in a DNA regardless of zome
+    ///
+    /// Cache rules are declared per DNA, not per zome, so bridge invalidation
+    /// only knows the DNA and function name.
+    pub fn invalidate_dna_function(&self, dna_hash: &str, fn_name: &str) -> usize {
+        let keys_to_remove: Vec<String> = self
+            .entries
+            .iter()
+            .filter(|entry| {
+                let mut parts = entry.key().splitn(4, ':');
+                parts.next() == Some(dna_hash) && parts.nth(1) == Some(fn_name)
+            })
+            .map(|entry| entry.key().clone())
+            .collect();
+
+        let count = keys_to_remove.len();
+        for key in keys_to_remove {
+            self.entries.remove(&key);
+            self.rule_stats.record_invalidation(&key);
+        }
+
+        if count > 0 {
+            debug!(
+                dna_hash = dna_hash,
+                fn_name = fn_name,
+                count = count,
+                "Invalidated cache entries"
+            );
+        }
+        count
+    }
+
+    /// Invalidate one entity's cached response for a function keyed by ID
+    ///
+    /// Matches entries whose args were the JSON-encoded entity ID, in any
+    /// zome and at any reach level.
+    pub fn invalidate_entity(&self, dna_hash: &str, fn_name: &str, entity_id: &str) -> usize {
+        let args = serde_json::to_string(entity_id).unwrap_or_default();
+        let args_hash = CacheKey::new(dna_hash, "", fn_name, &args).args_hash;
+
+        let keys_to_remove: Vec<String> = self
+            .entries
+            .iter()
+            .filter(|entry| {
+                let parts: Vec<&str> = entry.key().split(':').collect();
+                parts.len() >= 4
+                    && parts[0] == dna_hash
+                    && parts[2] == fn_name
+                    && parts[3] == args_hash
+            })
+            .map(|entry| entry.key().clone())
+            .collect();
+
+        let count = keys_to_remove.len();
+        for key in keys_to_remove {
+            self.entries.remove(&key);
+            self.rule_stats.record_invalidation(&key);
+        }
+
+        if count > 0 {
+            debug!(
+                fn_name = fn_name,
+                entity_id = entity_id,
+                count = count,
+                "Invalidated cache entries for entity"
+            );
+        }
+        count
+    }
+
+    /// Invalidate a function's entries whose input named any of `ids`
+    ///
+    /// Entries stored without refs can't be told apart and are evicted too.
+    pub fn invalidate_referencing(&self, dna_hash: &str, fn_name: &str, ids: &[String]) -> usize {
+        let keys_to_remove: Vec<String> = self
+            .entries
+            .iter()
+            .filter(|entry| {
+                let mut parts = entry.key().splitn(4, ':');
+                parts.next() == Some(dna_hash)
+                    && parts.nth(1) == Some(fn_name)
+                    && (entry.refs.is_empty() || entry.refs.iter().any(|r| ids.contains(r)))
+            })
+            .map(|entry| entry.key().clone())
+            .collect();
+
+        let count = keys_to_remove.len();
+        for key in keys_to_remove {
+            self.entries.remove(&key);
+            self.rule_stats.record_invalidation(&key);
+        }
+
+        if count > 0 {
+            debug!(
+                fn_name = fn_name,
+                ids = ?ids,
+                count = count,
+                "Invalidated cache entries referencing entities"
+            );
+        }
+        count
+    }
+
     /// Clear all entries
     pub fn clear(&self) {
         self.entries.clear();
@@ -291,6 +432,32 @@ impl ContentCache {
         count
     }
 
+    /// Unexpired entries with their storage keys (for cache export)
+    pub fn live_entries(&self) -> Vec<(String, CacheEntry)> {
+        self.entries
+            .iter()
+            .filter(|entry| !entry.is_expired())
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect()
+    }
+
+    /// Store a prepared entry (for cache import)
+    ///
+    /// Entries already cached under the key are kept, as they are at least as
+    /// fresh as an imported copy.
+    pub fn insert_entry(&self, storage_key: &str, entry: CacheEntry) -> bool {
+        if self
+            .entries
+            .get(storage_key)
+            .is_some_and(|existing| !existing.is_expired())
+        {
+            return false;
+        }
+        self.entries.insert(storage_key.to_string(), entry);
+        self.maybe_evict();
+        true
+    }
+
     /// Get cache statistics
     pub fn stats(&self) -> CacheStats {
         CacheStats {
@@ -301,6 +468,11 @@ impl ContentCache {
         }
     }
 
+    /// Per-rule hit, miss and invalidation counters
+    pub fn rule_stats(&self) -> &RuleStats {
+        &self.rule_stats
+    }
+
     /// Get configuration
     pub fn config(&self) -> &CacheConfig {
         &self.config
@@ -659,6 +831,37 @@ mod tests {
         assert!(cache.get(key).is_none());
     }
 
+    #[test]
+    fn test_stale_entry_and_rule_stats() {
+        let cache = ContentCache::with_defaults();
+        let key = "dna:zome:get_thing:args";
+
+        cache.set(
+            key,
+            b"old".to_vec(),
+            "application/json",
+            Duration::from_millis(10),
+        );
+        assert!(cache.get(key).is_some());
+        std::thread::sleep(Duration::from_millis(20));
+
+        // Expired: a miss, but still available as a fallback until cleanup
+        assert!(cache.get(key).is_none());
+        assert_eq!(cache.get_stale(key).unwrap().data, b"old");
+        assert_eq!(cache.invalidate_dna_function("dna", "get_thing"), 1);
+
+        let stats = cache.rule_stats().snapshot();
+        assert_eq!(stats.len(), 1);
+        assert_eq!(
+            (stats[0].hits, stats[0].misses, stats[0].stale_serves),
+            (1, 1, 1)
+        );
+        assert_eq!(stats[0].invalidations, 1);
+        assert_eq!(stats[0].avg_payload_bytes(), 3);
+
+        assert!(cache.get_stale(key).is_none());
+    }
+
     #[test]
     fn test_invalidate_pattern() {
         let cache = ContentCache::with_defaults();
@@ -708,6 +911,54 @@ mod tests {
         assert_eq!(cache.stats().entries, 1);
     }
 
+    #[test]
+    fn test_invalidate_dna_function() {
+        let cache = ContentCache::with_defaults();
+        let ttl = Duration::from_secs(300);
+
+        cache.set("dna:z1:get_path:a", b"1".to_vec(), "application/json", ttl);
+        cache.set("dna:z2:get_path:b", b"2".to_vec(), "application/json", ttl);
+        cache.set(
+            "dna:z1:get_path_list:c",
+            b"3".to_vec(),
+            "application/json",
+            ttl,
+        );
+        cache.set(
+            "other:z1:get_path:d",
+            b"4".to_vec(),
+            "application/json",
+            ttl,
+        );
+
+        let removed = cache.invalidate_dna_function("dna", "get_path");
+        assert_eq!(removed, 2);
+        assert_eq!(cache.stats().entries, 2);
+    }
+
+    #[test]
+    fn test_invalidate_entity() {
+        let cache = ContentCache::with_defaults();
+        let ttl = Duration::from_secs(300);
+
+        let step_1 = CacheKey::new("dna", "zome", "get_step_by_id", "\"step-1\"");
+        let step_1_commons =
+            CacheKey::with_reach("dna", "zome", "get_step_by_id", "\"step-1\"", "commons");
+        let step_2 = CacheKey::new("dna", "zome", "get_step_by_id", "\"step-2\"");
+        for key in [&step_1, &step_1_commons, &step_2] {
+            cache.set(
+                &key.to_storage_key(),
+                b"{}".to_vec(),
+                "application/json",
+                ttl,
+            );
+        }
+
+        let removed = cache.invalidate_entity("dna", "get_step_by_id", "step-1");
+        assert_eq!(removed, 2);
+        assert!(cache.get(&step_2.to_storage_key()).is_some());
+    }
+
     #[test]
     fn test_invalidate_dna() {
         let cache = ContentCache::with_defaults();
@@ -3,8 +3,9 @@
 //! CLI arguments and environment variable handling using clap.
 //! Pattern adapted from holo-host/rust/holo-gateway/src/lib.rs
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use uuid::Uuid;
 
 /// Doorway - WebSocket gateway for Elohim Holochain
@@ -58,6 +59,36 @@ pub struct Args {
     #[arg(long, env = "MONGODB_DB", default_value = "doorway")]
     pub mongodb_db: String,
 
+    /// MongoDB replica set name (overrides `replicaSet` in the URI)
+    #[arg(long, env = "MONGODB_REPLICA_SET")]
+    pub mongodb_replica_set: Option<String>,
+
+    /// Read preference for projection and cache reads (primary, primary_preferred,
+    /// secondary, secondary_preferred, nearest); writes always go to the primary
+    #[arg(long, env = "MONGODB_READ_PREFERENCE", default_value = "secondary_preferred")]
+    pub mongodb_read_preference: String,
+
+    /// Connections kept open to each MongoDB server
+    #[arg(long, env = "MONGODB_MIN_POOL_SIZE", default_value = "0")]
+    pub mongodb_min_pool_size: u32,
+
+    /// Most connections open to each MongoDB server
+    #[arg(long, env = "MONGODB_MAX_POOL_SIZE", default_value = "10")]
+    pub mongodb_max_pool_size: u32,
+
+    /// Retries of a write that fails while the replica set elects a primary
+    #[arg(long, env = "MONGODB_WRITE_RETRIES", default_value = "3")]
+    pub mongodb_write_retries: u32,
+
+    /// Projection query results cached in-process, per document type, as
+    /// DocType=ttl_secs (comma-separated; empty disables the L1)
+    #[arg(long, env = "L1_CACHE_RULES", default_value = "LearningPath=60")]
+    pub l1_cache_rules: String,
+
+    /// Most query results held in the L1 cache
+    #[arg(long, env = "L1_CACHE_CAPACITY", default_value = "1000")]
+    pub l1_cache_capacity: usize,
+
     /// JWT secret for token signing (required in production)
     #[arg(long, env = "JWT_SECRET")]
     pub jwt_secret: Option<String>,
@@ -66,6 +97,28 @@ pub struct Args {
     #[arg(long, env = "JWT_EXPIRY_SECONDS", default_value = "3600")]
     pub jwt_expiry_seconds: u64,
 
+    /// WebAuthn relying party ID: the domain passkeys are bound to (e.g.
+    /// `elohim.host`); passkey login is disabled if unset
+    #[arg(long, env = "WEBAUTHN_RP_ID")]
+    pub webauthn_rp_id: Option<String>,
+
+    /// Site name shown by authenticators when creating a passkey
+    #[arg(long, env = "WEBAUTHN_RP_NAME", default_value = "Elohim")]
+    pub webauthn_rp_name: String,
+
+    /// Origins passkey ceremonies may run on (defaults to
+    /// `https://{WEBAUTHN_RP_ID}`)
+    #[arg(long, env = "WEBAUTHN_ORIGINS", value_delimiter = ',')]
+    pub webauthn_origins: Vec<String>,
+
+    /// Refuse passkeys that only prove presence, not a PIN or biometric
+    #[arg(
+        long,
+        env = "WEBAUTHN_REQUIRE_USER_VERIFICATION",
+        default_value = "false"
+    )]
+    pub webauthn_require_user_verification: bool,
+
     /// API key for authenticated access (optional, for backward compat)
     #[arg(long, env = "API_KEY_AUTHENTICATED")]
     pub api_key_authenticated: Option<String>,
@@ -74,6 +127,34 @@ pub struct Args {
     #[arg(long, env = "API_KEY_ADMIN")]
     pub api_key_admin: Option<String>,
 
+    /// Operator API keys whose requests are served from the staging cells
+    /// instead of production (see `server::staging`); disabled if unset
+    #[arg(long, env = "STAGING_API_KEYS", value_delimiter = ',')]
+    pub staging_api_keys: Vec<String>,
+
+    /// Suffix of the parallel role names staging cells are installed under
+    #[arg(
+        long,
+        env = "STAGING_ROLE_SUFFIX",
+        default_value = "-staging",
+        allow_hyphen_values = true
+    )]
+    pub staging_role_suffix: String,
+
+    /// elohim-storage bound to the staging cells (staging imports refused if unset)
+    #[arg(long, env = "STAGING_STORAGE_URL")]
+    pub staging_storage_url: Option<String>,
+
+    /// JSON file of allow/deny rules for zome calls made through the app
+    /// proxy; replaced at runtime via PUT /admin/zome-policy
+    #[arg(long, env = "ZOME_POLICY_FILE")]
+    pub zome_policy_file: Option<String>,
+
+    /// JSON file of the cache policy for functions a DNA declares no rule
+    /// for: TTL, auth, cacheable prefixes, per-role overrides and a deny-list
+    #[arg(long, env = "CACHE_POLICY_FILE")]
+    pub cache_policy_file: Option<String>,
+
     /// Log level (trace, debug, info, warn, error)
     #[arg(long, env = "LOG_LEVEL", default_value = "info")]
     pub log_level: String,
@@ -86,6 +167,20 @@ pub struct Args {
     #[arg(long, env = "WORKER_COUNT", default_value = "4")]
     pub worker_count: usize,
 
+    /// Conductor calls in flight at which cacheable reads are shed, and
+    /// at half of which they are delayed (0 disables admission control)
+    #[arg(long, env = "ADMISSION_MAX_IN_FLIGHT", default_value = "256")]
+    pub admission_max_in_flight: usize,
+
+    /// Share of the last minute's conductor calls timing out at which
+    /// cacheable reads are shed, and at half of which they are delayed
+    #[arg(long, env = "ADMISSION_TIMEOUT_RATE", default_value = "0.2")]
+    pub admission_timeout_rate: f64,
+
+    /// How long cacheable reads wait under elevated conductor pressure
+    #[arg(long, env = "ADMISSION_DELAY_MS", default_value = "250")]
+    pub admission_delay_ms: u64,
+
     /// Enable bootstrap service for agent discovery
     #[arg(long, env = "BOOTSTRAP_ENABLED", default_value = "true")]
     pub bootstrap_enabled: bool,
@@ -114,6 +209,15 @@ pub struct Args {
     #[arg(long, env = "REGION")]
     pub region: Option<String>,
 
+    /// Where this doorway is, as "latitude,longitude", for sending clients
+    /// to the nearest doorway
+    #[arg(long, env = "REGION_LOCATION")]
+    pub region_location: Option<String>,
+
+    /// Interval between presence announcements to doorways in other regions
+    #[arg(long, env = "REGION_PRESENCE_INTERVAL_SECS", default_value = "15")]
+    pub region_presence_interval_secs: u64,
+
     /// Doorway identifier for federation (e.g., "alpha-elohim-host")
     /// Used in JWT claims to identify token issuer
     #[arg(long, env = "DOORWAY_ID")]
@@ -184,6 +288,566 @@ pub struct Args {
     /// Number of StatefulSet replicas for P2P peer enumeration
     #[arg(long, env = "STATEFULSET_REPLICAS")]
     pub statefulset_replicas: Option<u32>,
+
+    /// How often to sweep custodian commitments for missed beneficiary check-ins
+    /// Overdue dead man's switches are triggered automatically; 0 disables the sweep
+    #[arg(long, env = "DEAD_MANS_SWITCH_INTERVAL_SECS", default_value = "3600")]
+    pub dead_mans_switch_interval_secs: u64,
+
+    /// How long ranked "what to learn next" suggestions stay fresh per agent
+    /// Recently active agents are re-ranked in the background on this interval; 0 disables
+    #[arg(long, env = "RECOMMENDATION_REFRESH_SECS", default_value = "900")]
+    pub recommendation_refresh_secs: u64,
+
+    /// Interval for rolling up anonymized per-path learning analytics (0 disables)
+    #[arg(long, env = "ANALYTICS_ROLLUP_INTERVAL_SECS", default_value = "21600")]
+    pub analytics_rollup_interval_secs: u64,
+
+    /// Interval for the content health sweep (stale content, broken blob URLs,
+    /// archived relationships); 0 disables
+    #[arg(long, env = "CONTENT_HEALTH_INTERVAL_SECS", default_value = "86400")]
+    pub content_health_interval_secs: u64,
+
+    /// Months without an update before content is flagged stale (0 disables)
+    #[arg(long, env = "CONTENT_STALE_MONTHS", default_value = "12")]
+    pub content_stale_months: u32,
+
+    /// Elasticsearch/OpenSearch URL to mirror content and paths into (disabled if unset)
+    /// Basic auth may be embedded in the URL
+    #[arg(long, env = "SEARCH_EXPORT_URL")]
+    pub search_export_url: Option<String>,
+
+    /// API key for the search cluster (sent as `Authorization: ApiKey ..`)
+    #[arg(long, env = "SEARCH_EXPORT_API_KEY")]
+    pub search_export_api_key: Option<String>,
+
+    /// Prefix for exported index names ({prefix}-content-commons, ...)
+    #[arg(long, env = "SEARCH_EXPORT_INDEX_PREFIX", default_value = "elohim")]
+    pub search_export_index_prefix: String,
+
+    /// Where to publish domain events: `nats://host:4222` or a Kafka REST
+    /// Proxy URL (disabled if unset)
+    #[arg(long, env = "EVENT_EXPORT_URL")]
+    pub event_export_url: Option<String>,
+
+    /// Kafka topic, or NATS subject prefix ({topic}.content.created, ...)
+    #[arg(long, env = "EVENT_EXPORT_TOPIC", default_value = "elohim.events")]
+    pub event_export_topic: String,
+
+    /// Comma-separated event types to export (e.g. "content.created,path.completed");
+    /// all when empty
+    #[arg(long, env = "EVENT_EXPORT_TYPES", value_delimiter = ',')]
+    pub event_export_types: Vec<String>,
+
+    /// S3-compatible endpoint to mirror public blobs to as download fallbacks
+    /// (e.g. https://s3.us-east-1.amazonaws.com); disabled if unset
+    #[arg(long, env = "BLOB_MIRROR_S3_ENDPOINT")]
+    pub blob_mirror_s3_endpoint: Option<String>,
+
+    /// Bucket receiving mirrored blobs (must be publicly readable)
+    #[arg(long, env = "BLOB_MIRROR_S3_BUCKET")]
+    pub blob_mirror_s3_bucket: Option<String>,
+
+    /// Region used for request signing
+    #[arg(long, env = "BLOB_MIRROR_S3_REGION", default_value = "us-east-1")]
+    pub blob_mirror_s3_region: String,
+
+    /// Access key ID for the bucket
+    #[arg(long, env = "BLOB_MIRROR_S3_ACCESS_KEY")]
+    pub blob_mirror_s3_access_key: Option<String>,
+
+    /// Secret access key for the bucket
+    #[arg(long, env = "BLOB_MIRROR_S3_SECRET_KEY")]
+    pub blob_mirror_s3_secret_key: Option<String>,
+
+    /// Public base URL for mirrored blobs, e.g. a CDN in front of the bucket
+    /// (defaults to {endpoint}/{bucket})
+    #[arg(long, env = "BLOB_MIRROR_PUBLIC_URL")]
+    pub blob_mirror_public_url: Option<String>,
+
+    /// Interval between blob mirroring passes (0 disables)
+    #[arg(long, env = "BLOB_MIRROR_INTERVAL_SECS", default_value = "3600")]
+    pub blob_mirror_interval_secs: u64,
+
+    /// Blobs at least this large (in MB) get torrent metadata with this
+    /// doorway as web seed; needs STORAGE_URL and DOORWAY_URL (0 disables)
+    #[arg(long, env = "TORRENT_MIN_SIZE_MB", default_value = "100")]
+    pub torrent_min_size_mb: u64,
+
+    /// HMAC key for signed media URLs; when set, blobs of non-commons or
+    /// non-redistributable content are only served through signed URLs
+    #[arg(long, env = "MEDIA_URL_SECRET")]
+    pub media_url_secret: Option<String>,
+
+    /// Lifetime of signed media URLs in seconds
+    #[arg(long, env = "MEDIA_URL_TTL_SECS", default_value = "900")]
+    pub media_url_ttl_secs: u64,
+
+    /// Partner sites allowed to embed learning paths in an iframe, as
+    /// `origin=shared secret` (secret used to sign embed tokens); embedding
+    /// is disabled if unset
+    #[arg(long, env = "EMBED_PARTNERS", value_delimiter = ',')]
+    pub embed_partners: Vec<String>,
+
+    /// Longest lifetime an embed token may have, in seconds
+    #[arg(long, env = "EMBED_TOKEN_MAX_TTL_SECS", default_value = "3600")]
+    pub embed_token_max_ttl_secs: u64,
+
+    /// HMAC key for guest tokens; when set, landing pages may start
+    /// anonymous read-only sessions (`POST /guest/session`)
+    #[arg(long, env = "GUEST_TOKEN_SECRET")]
+    pub guest_token_secret: Option<String>,
+
+    /// Lifetime of guest tokens in seconds
+    #[arg(long, env = "GUEST_TOKEN_TTL_SECS", default_value = "900")]
+    pub guest_token_ttl_secs: u64,
+
+    /// Guest sessions any one client IP may start per minute
+    #[arg(long, env = "GUEST_SESSIONS_PER_MINUTE", default_value = "5")]
+    pub guest_sessions_per_minute: usize,
+
+    /// Reads a guest session may make per minute
+    #[arg(long, env = "GUEST_READS_PER_MINUTE", default_value = "60")]
+    pub guest_reads_per_minute: usize,
+
+    /// HMAC key for calendar feed tokens; when set, learners get a feed URL
+    /// their calendar app can subscribe to (`GET /me/calendar.ics?token=`)
+    #[arg(long, env = "CALENDAR_FEED_SECRET")]
+    pub calendar_feed_secret: Option<String>,
+
+    /// Require an invite code (`invite_code`) to register; a matching
+    /// admin bootstrap key is let through without one
+    #[arg(long, env = "INVITE_ONLY", default_value = "false")]
+    pub invite_only: bool,
+
+    /// Invitations a member may hold, revoked ones aside (0 = no cap;
+    /// admins are never capped)
+    #[arg(long, env = "INVITES_PER_MEMBER", default_value = "0")]
+    pub invites_per_member: u32,
+
+    /// Transcoder API (ffmpeg sidecar or external service) that new video
+    /// blobs are submitted to for adaptive renditions; disabled if unset
+    #[arg(long, env = "TRANSCODER_URL")]
+    pub transcoder_url: Option<String>,
+
+    /// Bearer token for the transcoder API
+    #[arg(long, env = "TRANSCODER_API_KEY")]
+    pub transcoder_api_key: Option<String>,
+
+    /// Renditions requested from the transcoder
+    #[arg(
+        long,
+        env = "TRANSCODE_RENDITIONS",
+        value_delimiter = ',',
+        default_value = "480p,720p,1080p"
+    )]
+    pub transcode_renditions: Vec<String>,
+
+    /// Interval between transcoding passes (submit new videos, poll jobs)
+    #[arg(long, env = "TRANSCODE_INTERVAL_SECS", default_value = "300")]
+    pub transcode_interval_secs: u64,
+
+    /// Interval between sitemap rebuilds; needs DOORWAY_URL and the
+    /// projection store (0 disables)
+    #[arg(long, env = "SITEMAP_INTERVAL_SECS", default_value = "3600")]
+    pub sitemap_interval_secs: u64,
+
+    /// Base64 32-byte Ed25519 seed learners' Open Badges exports are signed
+    /// with; published as `#badge-issuer` in the DID document. Needs
+    /// DOORWAY_URL (export disabled if unset)
+    #[arg(long, env = "BADGE_ISSUER_KEY")]
+    pub badge_issuer_key: Option<String>,
+
+    /// Headless Chromium renderer (Gotenberg's HTML route) that prints
+    /// public paths to PDF; `/paths/{id}/export.pdf` is disabled if unset
+    #[arg(long, env = "PATH_RENDERER_URL")]
+    pub path_renderer_url: Option<String>,
+
+    /// Rendered path PDFs kept in memory
+    #[arg(long, env = "PATH_RENDER_CACHE_SIZE", default_value = "50")]
+    pub path_render_cache_size: usize,
+
+    /// Machine-translation provider (`libretranslate` or `deepl`) used to
+    /// draft missing translations for steward review; disabled if unset
+    #[arg(long, env = "MACHINE_TRANSLATION_PROVIDER")]
+    pub machine_translation_provider: Option<String>,
+
+    /// Provider API base URL (required for LibreTranslate; DeepL defaults
+    /// to its public API)
+    #[arg(long, env = "MACHINE_TRANSLATION_URL")]
+    pub machine_translation_url: Option<String>,
+
+    /// Provider API key
+    #[arg(long, env = "MACHINE_TRANSLATION_API_KEY")]
+    pub machine_translation_api_key: Option<String>,
+
+    /// Language content is authored in (never machine-translated into)
+    #[arg(long, env = "MACHINE_TRANSLATION_SOURCE_LOCALE", default_value = "en")]
+    pub machine_translation_source_locale: String,
+
+    /// Locales to draft translations for (comma-separated; empty allows any)
+    #[arg(long, env = "MACHINE_TRANSLATION_LOCALES", value_delimiter = ',')]
+    pub machine_translation_locales: Vec<String>,
+
+    /// Interval between machine-translation passes
+    #[arg(long, env = "MACHINE_TRANSLATION_INTERVAL_SECS", default_value = "60")]
+    pub machine_translation_interval_secs: u64,
+
+    /// Embedding provider (`openai`, `local` for an OpenAI-compatible local
+    /// server such as text-embeddings-inference with an ONNX model, or
+    /// `ollama`); semantic related-content is disabled if unset
+    #[arg(long, env = "EMBEDDING_PROVIDER")]
+    pub embedding_provider: Option<String>,
+
+    /// Provider API base URL (defaults per provider)
+    #[arg(long, env = "EMBEDDING_URL")]
+    pub embedding_url: Option<String>,
+
+    /// Provider API key
+    #[arg(long, env = "EMBEDDING_API_KEY")]
+    pub embedding_api_key: Option<String>,
+
+    /// Embedding model (defaults per provider)
+    #[arg(long, env = "EMBEDDING_MODEL")]
+    pub embedding_model: Option<String>,
+
+    /// Interval between embedding passes
+    #[arg(long, env = "EMBEDDING_INTERVAL_SECS", default_value = "3600")]
+    pub embedding_interval_secs: u64,
+
+    /// Cosine similarity from which content pairs are suggested to stewards
+    /// as related (above 1 disables suggestions)
+    #[arg(long, env = "EMBEDDING_SUGGEST_THRESHOLD", default_value = "0.85")]
+    pub embedding_suggest_threshold: f32,
+
+    /// OpenAI-compatible chat completions URL used to draft quiz questions
+    /// for the question bank (steward approval required); disabled if unset
+    #[arg(long, env = "QUESTION_GENERATION_URL")]
+    pub question_generation_url: Option<String>,
+
+    /// API key for the question generation endpoint
+    #[arg(long, env = "QUESTION_GENERATION_API_KEY")]
+    pub question_generation_api_key: Option<String>,
+
+    /// Model used to draft questions
+    #[arg(long, env = "QUESTION_GENERATION_MODEL", default_value = "gpt-4o-mini")]
+    pub question_generation_model: String,
+
+    /// Questions drafted per content node
+    #[arg(long, env = "QUESTION_GENERATION_PER_CONTENT", default_value = "3")]
+    pub question_generation_per_content: usize,
+
+    /// Interval between question generation passes
+    #[arg(long, env = "QUESTION_GENERATION_INTERVAL_SECS", default_value = "3600")]
+    pub question_generation_interval_secs: u64,
+
+    /// Similarity at which imported content is flagged as a suspected
+    /// duplicate of existing content (0 disables the check)
+    #[arg(long, env = "IMPORT_DUPLICATE_THRESHOLD", default_value = "0.8")]
+    pub import_duplicate_threshold: f64,
+
+    /// Path to a JSON file of custom import validation rules (see
+    /// `services::import_validation`)
+    #[arg(long, env = "IMPORT_RULES")]
+    pub import_rules: Option<String>,
+
+    /// Dynamic libraries implementing import validation rules
+    #[arg(long, env = "IMPORT_RULE_PLUGINS", value_delimiter = ',')]
+    pub import_rule_plugins: Vec<String>,
+
+    /// Import batches per minute any one caller may queue before it is
+    /// throttled (0 disables import abuse detection)
+    #[arg(long, env = "IMPORT_ABUSE_MAX_PER_MINUTE", default_value = "50")]
+    pub import_abuse_max_per_minute: usize,
+
+    /// How many times its own baseline batch rate or size a caller may
+    /// reach before it is throttled
+    #[arg(long, env = "IMPORT_ABUSE_DEVIATION", default_value = "5.0")]
+    pub import_abuse_deviation: f64,
+
+    /// How long a caller with anomalous imports is throttled
+    #[arg(long, env = "IMPORT_ABUSE_THROTTLE_SECS", default_value = "300")]
+    pub import_abuse_throttle_secs: u64,
+
+    /// Human ids or agent keys notified when an import caller is throttled
+    #[arg(long, env = "IMPORT_ABUSE_ALERT_RECIPIENTS", value_delimiter = ',')]
+    pub import_abuse_alert_recipients: Vec<String>,
+
+    /// OpenAI-compatible chat completions URL for the conversational tutor
+    /// (`POST /tutor/chat`); disabled if unset
+    #[arg(long, env = "TUTOR_URL")]
+    pub tutor_url: Option<String>,
+
+    /// Operator API key for the tutor endpoint
+    #[arg(long, env = "TUTOR_API_KEY")]
+    pub tutor_api_key: Option<String>,
+
+    /// Model used for tutor chats
+    #[arg(long, env = "TUTOR_MODEL", default_value = "gpt-4o-mini")]
+    pub tutor_model: String,
+
+    /// Tokens the tutor may spend per operator API key each calendar month
+    /// (0 = unlimited)
+    #[arg(long, env = "TUTOR_MONTHLY_TOKEN_BUDGET", default_value = "2000000")]
+    pub tutor_monthly_token_budget: u64,
+
+    /// File of blocked words and phrases for write moderation, one per line
+    /// (`#` comments)
+    #[arg(long, env = "MODERATION_WORD_LIST")]
+    pub moderation_word_list: Option<String>,
+
+    /// OpenAI-compatible moderation API URL (`POST {url}/v1/moderations`)
+    #[arg(long, env = "MODERATION_API_URL")]
+    pub moderation_api_url: Option<String>,
+
+    /// API key for the moderation endpoint
+    #[arg(long, env = "MODERATION_API_KEY")]
+    pub moderation_api_key: Option<String>,
+
+    /// Moderation API category score (0-1) that holds a write for review even
+    /// when the provider doesn't flag it; governance can change it
+    #[arg(long, env = "MODERATION_SCORE_THRESHOLD")]
+    pub moderation_score_threshold: Option<f64>,
+
+    /// Interval for re-reading approved `doorway_setting` governance states;
+    /// 0 disables the governance executor
+    #[arg(long, env = "GOVERNANCE_REFRESH_SECS", default_value = "300")]
+    pub governance_refresh_secs: u64,
+
+    /// How long a task dispatched to an elohim agent may stay unfinished
+    /// before it is marked timed out
+    #[arg(long, env = "ELOHIM_TASK_TIMEOUT_SECS", default_value = "900")]
+    pub elohim_task_timeout_secs: u64,
+
+    /// Interval for suggesting matches between Shefa service requests and
+    /// offers (0 disables)
+    #[arg(long, env = "SERVICE_MATCHING_INTERVAL_SECS", default_value = "1800")]
+    pub service_matching_interval_secs: u64,
+
+    /// Interval for reminding attendees of upcoming learning sessions
+    /// (needs MongoDB; 0 disables)
+    #[arg(long, env = "SESSION_REMINDER_INTERVAL_SECS", default_value = "300")]
+    pub session_reminder_interval_secs: u64,
+
+    /// How far ahead of a learning session its reminder goes out, in minutes
+    /// (at most a week)
+    #[arg(long, env = "SESSION_REMINDER_LEAD_MINS", default_value = "60")]
+    pub session_reminder_lead_mins: u64,
+
+    /// Human IDs allowed to assign, decide and pay out Shefa insurance claims
+    /// (admins always can)
+    #[arg(long, env = "CLAIM_ADJUSTERS", value_delimiter = ',')]
+    pub claim_adjusters: Vec<String>,
+
+    /// Interval for recording insurance mutual solvency snapshots (0 disables)
+    #[arg(long, env = "SOLVENCY_SNAPSHOT_INTERVAL_SECS", default_value = "86400")]
+    pub solvency_snapshot_interval_secs: u64,
+
+    /// Interval for enriching external path steps with oEmbed/Open Graph
+    /// metadata and page snapshots (snapshots need STORAGE_URL; 0 disables)
+    #[arg(long, env = "EXTERNAL_RESOURCE_INTERVAL_SECS", default_value = "600")]
+    pub external_resource_interval_secs: u64,
+
+    /// Trusted settlement keys for token payments to premium gates, as
+    /// `network=base64 Ed25519 key` (e.g. `hrea=...`); disabled if unset
+    #[arg(long, env = "TOKEN_SETTLEMENT_SIGNERS", value_delimiter = ',')]
+    pub token_settlement_signers: Vec<String>,
+
+    /// Account token payments must be made to (required with
+    /// TOKEN_SETTLEMENT_SIGNERS)
+    #[arg(long, env = "TOKEN_SETTLEMENT_RECEIVER")]
+    pub token_settlement_receiver: Option<String>,
+
+    /// Oldest token payment accepted for an access grant, in seconds
+    #[arg(long, env = "TOKEN_SETTLEMENT_MAX_AGE_SECS", default_value = "86400")]
+    pub token_settlement_max_age_secs: u64,
+
+    /// Doorways of other communities to federate with in both directions,
+    /// as `doorway_id|url|base64 Ed25519 key`; disabled if unset
+    #[arg(long, env = "RECIPROCAL_PEERS", value_delimiter = ',')]
+    pub reciprocal_peers: Vec<String>,
+
+    /// Base64 32-byte Ed25519 seed this doorway signs reciprocal calls with
+    /// Peers pin its public key (published in /.well-known/doorway-keys)
+    #[arg(long, env = "RECIPROCAL_SIGNING_KEY")]
+    pub reciprocal_signing_key: Option<String>,
+
+    /// Public read-only content_store functions reciprocal peers may call
+    #[arg(
+        long,
+        env = "RECIPROCAL_EXPORTED_FNS",
+        value_delimiter = ',',
+        default_value = "get_content_by_id,get_content_summary_by_id,get_content_by_type,get_content_by_tag,get_content_by_license,get_all_paths,get_path_with_steps,get_path_overview"
+    )]
+    pub reciprocal_exported_fns: Vec<String>,
+
+    /// PEM client certificate and key presented to peers whose ingress requires mTLS
+    #[arg(long, env = "RECIPROCAL_CLIENT_CERT")]
+    pub reciprocal_client_cert: Option<String>,
+
+    /// Upstream systems trusted to sign import manifests, as
+    /// `source=base64 Ed25519 key`; signed imports are refused if unset
+    #[arg(long, env = "IMPORT_SIGNERS", value_delimiter = ',')]
+    pub import_signers: Vec<String>,
+
+    /// Size of the capped conductor signal journal in bytes (0 disables it)
+    #[arg(long, env = "SIGNAL_JOURNAL_MAX_BYTES", default_value = "536870912")]
+    pub signal_journal_max_bytes: u64,
+
+    /// Largest JSON request body accepted (API writes, zome calls)
+    #[arg(long, env = "MAX_JSON_BODY_BYTES", default_value = "4194304")]
+    pub max_json_body_bytes: usize,
+
+    /// Largest bulk import or seed blob request body accepted
+    #[arg(long, env = "MAX_IMPORT_BODY_BYTES", default_value = "67108864")]
+    pub max_import_body_bytes: usize,
+
+    /// Largest WebSocket frame or message proxied to the conductor
+    #[arg(long, env = "MAX_WS_FRAME_BYTES", default_value = "16777216")]
+    pub max_ws_frame_bytes: usize,
+
+    /// Zome responses queued per app WebSocket client before it is
+    /// disconnected as too slow
+    #[arg(long, env = "WS_RESPONSE_QUEUE", default_value = "1024")]
+    pub ws_response_queue: usize,
+
+    /// Signals queued per app WebSocket client (oldest dropped beyond this)
+    #[arg(long, env = "WS_SIGNAL_QUEUE", default_value = "256")]
+    pub ws_signal_queue: usize,
+
+    /// Other notices queued per app WebSocket client (newest dropped beyond this)
+    #[arg(long, env = "WS_NOTICE_QUEUE", default_value = "64")]
+    pub ws_notice_queue: usize,
+
+    /// Seconds an app WebSocket client may take to accept a message before
+    /// it is disconnected
+    #[arg(long, env = "WS_SEND_TIMEOUT_SECS", default_value = "10")]
+    pub ws_send_timeout_secs: u64,
+
+    /// Seconds between heartbeat pings to app WebSocket clients (0 disables)
+    #[arg(long, env = "WS_PING_INTERVAL_SECS", default_value = "30")]
+    pub ws_ping_interval_secs: u64,
+
+    /// Seconds an app WebSocket client may stay silent before its
+    /// connection is reaped
+    #[arg(long, env = "WS_LIVENESS_TIMEOUT_SECS", default_value = "90")]
+    pub ws_liveness_timeout_secs: u64,
+
+    /// Members a presence room (`GET /presence`) may hold (0 disables presence)
+    #[arg(long, env = "PRESENCE_MAX_ROOM_SIZE", default_value = "200")]
+    pub presence_max_room_size: usize,
+
+    /// Positions per second relayed from one presence member (0 = no limit)
+    #[arg(long, env = "PRESENCE_POSITIONS_PER_SEC", default_value = "5")]
+    pub presence_positions_per_sec: u32,
+
+    /// Seconds an app WebSocket call is held while the conductor connection
+    /// is being re-established (0 disables reconnecting)
+    #[arg(long, env = "CONDUCTOR_RECONNECT_HOLD_SECS", default_value = "30")]
+    pub conductor_reconnect_hold_secs: u64,
+
+    /// Calls held per app WebSocket connection while reconnecting
+    #[arg(long, env = "CONDUCTOR_RECONNECT_QUEUE", default_value = "64")]
+    pub conductor_reconnect_queue: usize,
+
+    /// Zome calls slower than this many milliseconds are logged and fed to
+    /// the query advisor (0 disables both)
+    #[arg(long, env = "SLOW_QUERY_THRESHOLD_MS", default_value = "500")]
+    pub slow_query_threshold_ms: u64,
+
+    /// Interval between query advisor reports in seconds
+    #[arg(long, env = "QUERY_ADVISOR_INTERVAL_SECS", default_value = "3600")]
+    pub query_advisor_interval_secs: u64,
+
+    /// Interval for persisting per-rule cache statistics to MongoDB (0 disables)
+    #[arg(long, env = "CACHE_STATS_ROLLUP_INTERVAL_SECS", default_value = "3600")]
+    pub cache_stats_rollup_interval_secs: u64,
+
+    /// Interval for adding views of steward-marked content to their daily
+    /// rollups in MongoDB (0 disables content access logging)
+    #[arg(long, env = "CONTENT_ACCESS_FLUSH_SECS", default_value = "60")]
+    pub content_access_flush_secs: u64,
+
+    /// Path to a JSON file of retention policies (archive stale content,
+    /// purge erased users' projections, expire import batches)
+    #[arg(long, env = "RETENTION_POLICIES")]
+    pub retention_policies: Option<String>,
+
+    /// Interval between retention policy runs in seconds (0 = only when an
+    /// admin runs them)
+    #[arg(long, env = "RETENTION_INTERVAL_SECS", default_value = "86400")]
+    pub retention_interval_secs: u64,
+
+    /// One-off command to run instead of the gateway
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// One-off commands
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Export public paths and commons content as a static bundle
+    /// (JSON + HTML) for CDN hosting or offline distribution
+    ExportSite(ExportSiteArgs),
+
+    /// Dump or load the response cache of running doorways, so a new
+    /// deployment starts warm
+    Cache(CacheArgs),
+}
+
+/// Options for `doorway export-site`
+#[derive(Parser, Debug, Clone)]
+pub struct ExportSiteArgs {
+    /// Directory to write the bundle to
+    #[arg(long, default_value = "site")]
+    pub out: PathBuf,
+
+    /// Also copy blob bytes into the bundle (`media/`), verified against
+    /// their hashes; otherwise the media manifest only lists URLs
+    #[arg(long)]
+    pub include_media: bool,
+}
+
+/// Options for `doorway cache`
+#[derive(Parser, Debug, Clone)]
+pub struct CacheArgs {
+    /// Admin JWT accepted by the doorways involved
+    #[arg(long, env = "DOORWAY_ADMIN_TOKEN")]
+    pub token: String,
+
+    #[command(subcommand)]
+    pub action: CacheAction,
+}
+
+/// `doorway cache` operations
+#[derive(Subcommand, Debug, Clone)]
+pub enum CacheAction {
+    /// Write a running doorway's cache to a snapshot file
+    Dump {
+        /// Doorway to dump
+        #[arg(long, default_value = "http://127.0.0.1:8080")]
+        from: String,
+
+        /// Snapshot file to write
+        #[arg(long, default_value = "doorway-cache.ndjson")]
+        out: PathBuf,
+    },
+
+    /// Load a snapshot into a running doorway, from a file or streamed
+    /// straight from another doorway
+    Load {
+        /// Doorway to warm
+        #[arg(long, default_value = "http://127.0.0.1:8080")]
+        to: String,
+
+        /// Snapshot file to load
+        #[arg(long, required_unless_present = "from", conflicts_with = "from")]
+        file: Option<PathBuf>,
+
+        /// Doorway to copy the cache from
+        #[arg(long)]
+        from: Option<String>,
+    },
 }
 
 /// NATS connection configuration
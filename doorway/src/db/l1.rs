@@ -0,0 +1,388 @@
+//! In-process L1 cache in front of MongoDB
+//!
+//! Projection queries (`GET /api/v1/cache/{type}`, feeds, path listings) go
+//! to MongoDB on every request, and a few hot keys - every learning path,
+//! the `get_all_paths` class - carry most of that load. [`L1Cache`] keeps
+//! those results in a bounded LRU inside each doorway process, with MongoDB
+//! as the shared L2.
+//!
+//! Caching is opt-in per rule (`L1_CACHE_RULES="LearningPath=300,Content=60"`):
+//! each rule names a projected document type and how many seconds its query
+//! results may be served from L1. A write to a type evicts that type's
+//! entries, and the eviction is published on NATS so the L1s of the other
+//! doorways sharing the database stay coherent.
+
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::nats::NatsClient;
+
+/// NATS subject carrying L1 evictions between doorways
+pub const L1_INVALIDATION_SUBJECT: &str = "DOORWAY.l1.invalidate";
+
+/// Tag that evicts every entry
+const ALL_TAGS: &str = "*";
+
+/// An opted-in document type and how long its results stay in L1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct L1Rule {
+    pub doc_type: String,
+    pub ttl: Duration,
+}
+
+/// Parse `L1_CACHE_RULES` (`DocType=ttl_secs`, comma-separated)
+pub fn parse_l1_rules(spec: &str) -> Result<Vec<L1Rule>, String> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|rule| !rule.is_empty())
+        .map(|rule| {
+            let (doc_type, ttl) = rule
+                .split_once('=')
+                .ok_or_else(|| format!("L1 cache rule '{rule}' must be DocType=ttl_secs"))?;
+            let ttl: u64 = ttl
+                .trim()
+                .parse()
+                .ok()
+                .filter(|ttl| *ttl > 0)
+                .ok_or_else(|| format!("L1 cache rule '{rule}' needs a TTL of at least 1s"))?;
+            Ok(L1Rule {
+                doc_type: doc_type.trim().to_string(),
+                ttl: Duration::from_secs(ttl),
+            })
+        })
+        .collect()
+}
+
+/// L1 size and hit counters, as reported by `GET /status`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct L1Stats {
+    pub capacity: usize,
+    pub entries: usize,
+    /// Lookups served from L1
+    pub l1_hits: u64,
+    /// Lookups of opted-in keys that went to MongoDB
+    pub l2_hits: u64,
+    pub l1_hit_ratio: f64,
+    /// Entries dropped to stay within capacity
+    pub evictions: u64,
+    /// Entries dropped by writes, here or on another doorway
+    pub invalidations: u64,
+}
+
+/// Eviction message published on NATS
+#[derive(Debug, Serialize, Deserialize)]
+struct L1Invalidation {
+    origin: String,
+    tag: String,
+}
+
+struct Entry<V> {
+    value: V,
+    tag: String,
+    expires_at: Instant,
+    tick: u64,
+}
+
+/// Entries plus their use order (oldest tick first)
+struct Lru<V> {
+    entries: HashMap<String, Entry<V>>,
+    order: BTreeMap<u64, String>,
+    next_tick: u64,
+}
+
+impl<V> Lru<V> {
+    fn touch(&mut self, key: &str) {
+        let tick = self.next_tick;
+        self.next_tick += 1;
+        if let Some(entry) = self.entries.get_mut(key) {
+            self.order.remove(&entry.tick);
+            entry.tick = tick;
+            self.order.insert(tick, key.to_string());
+        }
+    }
+
+    fn remove(&mut self, key: &str) -> Option<Entry<V>> {
+        let entry = self.entries.remove(key)?;
+        self.order.remove(&entry.tick);
+        Some(entry)
+    }
+}
+
+/// Bounded, TTL-aware LRU of query results, tagged by document type
+pub struct L1Cache<V> {
+    capacity: usize,
+    rules: HashMap<String, Duration>,
+    lru: Mutex<Lru<V>>,
+    l1_hits: AtomicU64,
+    l2_hits: AtomicU64,
+    evictions: AtomicU64,
+    invalidations: AtomicU64,
+    /// Local evictions, for publishing to the other doorways
+    invalidation_tx: broadcast::Sender<String>,
+}
+
+impl<V: Clone> L1Cache<V> {
+    pub fn new(capacity: usize, rules: Vec<L1Rule>) -> Self {
+        let (invalidation_tx, _) = broadcast::channel(256);
+        Self {
+            capacity: capacity.max(1),
+            rules: rules
+                .into_iter()
+                .map(|rule| (rule.doc_type, rule.ttl))
+                .collect(),
+            lru: Mutex::new(Lru {
+                entries: HashMap::new(),
+                order: BTreeMap::new(),
+                next_tick: 0,
+            }),
+            l1_hits: AtomicU64::new(0),
+            l2_hits: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+            invalidations: AtomicU64::new(0),
+            invalidation_tx,
+        }
+    }
+
+    /// Whether a document type opted in to L1
+    pub fn covers(&self, tag: &str) -> bool {
+        self.rules.contains_key(tag)
+    }
+
+    /// Cached value, if present and fresh
+    pub fn get(&self, key: &str) -> Option<V> {
+        let mut lru = self.lru.lock().unwrap();
+        let fresh = lru.entries.get(key)?.expires_at > Instant::now();
+        if !fresh {
+            lru.remove(key);
+            return None;
+        }
+        lru.touch(key);
+        self.l1_hits.fetch_add(1, Ordering::Relaxed);
+        lru.entries.get(key).map(|entry| entry.value.clone())
+    }
+
+    /// Count a lookup that L1 couldn't serve
+    pub fn record_l2_hit(&self) {
+        self.l2_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Cache a value under its document type's rule (ignored without one)
+    pub fn insert(&self, key: &str, tag: &str, value: V) {
+        let Some(ttl) = self.rules.get(tag) else {
+            return;
+        };
+        let mut lru = self.lru.lock().unwrap();
+        lru.remove(key);
+        let tick = lru.next_tick;
+        lru.next_tick += 1;
+        lru.entries.insert(
+            key.to_string(),
+            Entry {
+                value,
+                tag: tag.to_string(),
+                expires_at: Instant::now() + *ttl,
+                tick,
+            },
+        );
+        lru.order.insert(tick, key.to_string());
+
+        while lru.entries.len() > self.capacity {
+            let Some((_, oldest)) = lru.order.pop_first() else {
+                break;
+            };
+            lru.entries.remove(&oldest);
+            self.evictions.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn evict_tag(&self, tag: &str) -> usize {
+        let mut lru = self.lru.lock().unwrap();
+        let keys: Vec<String> = lru
+            .entries
+            .iter()
+            .filter(|(_, entry)| tag == ALL_TAGS || entry.tag == tag)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in &keys {
+            lru.remove(key);
+        }
+        self.invalidations
+            .fetch_add(keys.len() as u64, Ordering::Relaxed);
+        keys.len()
+    }
+
+    /// Evict a document type's entries after a write here, and tell the
+    /// other doorways to do the same
+    pub fn invalidate(&self, tag: &str) -> usize {
+        if tag != ALL_TAGS && !self.covers(tag) {
+            return 0;
+        }
+        let _ = self.invalidation_tx.send(tag.to_string());
+        self.evict_tag(tag)
+    }
+
+    /// Evict entries after a write reported by another doorway
+    pub fn apply_remote_invalidation(&self, tag: &str) -> usize {
+        self.evict_tag(tag)
+    }
+
+    /// Local evictions as they happen
+    pub fn subscribe_invalidations(&self) -> broadcast::Receiver<String> {
+        self.invalidation_tx.subscribe()
+    }
+
+    pub fn stats(&self) -> L1Stats {
+        let l1_hits = self.l1_hits.load(Ordering::Relaxed);
+        let l2_hits = self.l2_hits.load(Ordering::Relaxed);
+        let lookups = l1_hits + l2_hits;
+        L1Stats {
+            capacity: self.capacity,
+            entries: self.lru.lock().unwrap().entries.len(),
+            l1_hits,
+            l2_hits,
+            l1_hit_ratio: if lookups == 0 {
+                0.0
+            } else {
+                l1_hits as f64 / lookups as f64
+            },
+            evictions: self.evictions.load(Ordering::Relaxed),
+            invalidations: self.invalidations.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Spawn the task keeping this doorway's L1 coherent with the others:
+/// publishes local evictions on NATS and applies theirs
+pub fn spawn_l1_sync_task<V>(
+    l1: Arc<L1Cache<V>>,
+    nats: NatsClient,
+    node_id: String,
+) -> JoinHandle<()>
+where
+    V: Clone + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut remote = match nats.subscribe(L1_INVALIDATION_SUBJECT).await {
+            Ok(subscriber) => subscriber,
+            Err(e) => {
+                warn!(error = %e, "L1 sync could not subscribe, L1 may serve stale results");
+                return;
+            }
+        };
+        let mut local = l1.subscribe_invalidations();
+        info!(subject = L1_INVALIDATION_SUBJECT, "L1 cache sync started");
+
+        loop {
+            tokio::select! {
+                tag = local.recv() => {
+                    let tag = match tag {
+                        Ok(tag) => tag,
+                        // Missed evictions: have every doorway drop everything
+                        Err(broadcast::error::RecvError::Lagged(_)) => ALL_TAGS.to_string(),
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    };
+                    let message = L1Invalidation { origin: node_id.clone(), tag };
+                    let payload = serde_json::to_vec(&message).unwrap_or_default();
+                    if let Err(e) = nats.publish(L1_INVALIDATION_SUBJECT, payload.into()).await {
+                        warn!(error = %e, "Failed to publish L1 invalidation");
+                    }
+                }
+                message = remote.next() => {
+                    let Some(message) = message else {
+                        warn!("L1 invalidation subscription closed");
+                        break;
+                    };
+                    match serde_json::from_slice::<L1Invalidation>(&message.payload) {
+                        Ok(invalidation) if invalidation.origin != node_id => {
+                            let removed = l1.apply_remote_invalidation(&invalidation.tag);
+                            debug!(
+                                origin = %invalidation.origin,
+                                tag = %invalidation.tag,
+                                removed,
+                                "Applied remote L1 invalidation"
+                            );
+                        }
+                        Ok(_) => {}
+                        Err(e) => warn!(error = %e, "Invalid L1 invalidation message"),
+                    }
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(capacity: usize) -> L1Cache<u32> {
+        L1Cache::new(
+            capacity,
+            parse_l1_rules("LearningPath=60, Content=60").unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_parse_l1_rules() {
+        let rules = parse_l1_rules("LearningPath=300,Content=60").unwrap();
+        assert_eq!(rules[0].doc_type, "LearningPath");
+        assert_eq!(rules[1].ttl, Duration::from_secs(60));
+        assert!(parse_l1_rules("").unwrap().is_empty());
+        assert!(parse_l1_rules("LearningPath").is_err());
+        assert!(parse_l1_rules("LearningPath=0").is_err());
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        let l1 = cache(2);
+        l1.insert("a", "Content", 1);
+        l1.insert("b", "Content", 2);
+        assert_eq!(l1.get("a"), Some(1));
+        l1.insert("c", "Content", 3);
+
+        // "b" was least recently used
+        assert_eq!(l1.get("b"), None);
+        assert_eq!(l1.get("a"), Some(1));
+        assert_eq!(l1.get("c"), Some(3));
+        assert_eq!(l1.stats().evictions, 1);
+    }
+
+    #[test]
+    fn test_opt_in_and_invalidation() {
+        let l1 = cache(10);
+        l1.insert("paths", "LearningPath", 1);
+        l1.insert("content", "Content", 2);
+        l1.insert("steps", "PathStep", 3);
+        assert_eq!(l1.get("steps"), None);
+
+        let mut published = l1.subscribe_invalidations();
+        assert_eq!(l1.invalidate("LearningPath"), 1);
+        assert_eq!(published.try_recv().unwrap(), "LearningPath");
+        assert_eq!(l1.get("paths"), None);
+        assert_eq!(l1.get("content"), Some(2));
+
+        assert_eq!(l1.apply_remote_invalidation("*"), 1);
+        assert!(published.try_recv().is_err());
+        assert_eq!(l1.stats().entries, 0);
+    }
+
+    #[test]
+    fn test_hit_ratio() {
+        let l1 = cache(10);
+        l1.record_l2_hit();
+        l1.insert("paths", "LearningPath", 1);
+        l1.get("paths");
+        l1.get("paths");
+        l1.get("paths");
+        let stats = l1.stats();
+        assert_eq!((stats.l1_hits, stats.l2_hits), (3, 1));
+        assert_eq!(stats.l1_hit_ratio, 0.75);
+    }
+}
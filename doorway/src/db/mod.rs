@@ -1,10 +1,13 @@
 //! Database layer for Doorway
 //!
-//! Provides MongoDB storage for users, API keys, and host registry.
+//! Provides MongoDB storage for users, API keys, and host registry, with an
+//! in-process [`l1`] cache in front of hot projection queries.
 //! Pattern adapted from holo-host/rust/util_libs/db
 
+pub mod l1;
 pub mod mongo;
 pub mod schemas;
 
-pub use mongo::{MongoClient, MongoCollection};
+pub use l1::{L1Cache, L1Rule, L1Stats};
+pub use mongo::{MongoClient, MongoCollection, MongoOptions, ReadRouting};
 pub use schemas::{ApiKeyDoc, HostDoc, Metadata, UserDoc};
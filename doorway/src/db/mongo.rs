@@ -1,20 +1,162 @@
 //! MongoDB client and collection wrapper
 //!
 //! Pattern adapted from holo-host/rust/util_libs/db/src/mongodb
+//!
+//! Against a replica set, writes always go to the primary while read-heavy
+//! paths (projections, cache lookups) use [`MongoClient::read_database`],
+//! which follows the configured read preference and may read from a
+//! secondary. Writes that fail while the set elects a new primary
+//! (`NotWritablePrimary` and friends) are retried with backoff.
 
 use bson::{doc, oid::ObjectId, DateTime, Document};
 use mongodb::{
-    options::{IndexOptions, UpdateModifications},
+    error::{Error as MongoError, ErrorKind, WriteFailure},
+    options::{
+        ClientOptions, DatabaseOptions, IndexOptions, ReadPreference, SelectionCriteria,
+        UpdateModifications,
+    },
     results::UpdateResult,
-    Client, Collection, IndexModel,
+    Client, Collection, Database, IndexModel,
 };
 use serde::{de::DeserializeOwned, Serialize};
 use std::fmt::Debug;
-use tracing::{error, info};
+use std::future::IntoFuture;
+use std::time::Duration;
+use tracing::{error, info, warn};
 
+use crate::config::Args;
 use crate::db::schemas::Metadata;
 use crate::types::DoorwayError;
 
+/// Server selection and connect timeout, so an unreachable MongoDB fails fast
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Delay before the first write retry; doubles on each further attempt
+const RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Server error codes seen while a replica set has no writable primary
+const FAILOVER_CODES: [i32; 7] = [
+    10107, // NotWritablePrimary
+    13435, // NotPrimaryNoSecondaryOk
+    13436, // NotPrimaryOrSecondary
+    189,   // PrimarySteppedDown
+    91,    // ShutdownInProgress
+    11600, // InterruptedAtShutdown
+    11602, // InterruptedDueToReplStateChange
+];
+
+/// Where reads that tolerate slight staleness are sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReadRouting {
+    Primary,
+    PrimaryPreferred,
+    Secondary,
+    /// Secondaries when available, the primary otherwise (also fine on a
+    /// standalone server)
+    #[default]
+    SecondaryPreferred,
+    Nearest,
+}
+
+impl ReadRouting {
+    /// Parse a read preference name (`secondary_preferred` or `secondaryPreferred`)
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().replace('_', "").to_ascii_lowercase().as_str() {
+            "primary" => Some(Self::Primary),
+            "primarypreferred" => Some(Self::PrimaryPreferred),
+            "secondary" => Some(Self::Secondary),
+            "secondarypreferred" => Some(Self::SecondaryPreferred),
+            "nearest" => Some(Self::Nearest),
+            _ => None,
+        }
+    }
+
+    fn selection_criteria(self) -> SelectionCriteria {
+        let preference = match self {
+            Self::Primary => ReadPreference::Primary,
+            Self::PrimaryPreferred => ReadPreference::PrimaryPreferred { options: None },
+            Self::Secondary => ReadPreference::Secondary { options: None },
+            Self::SecondaryPreferred => ReadPreference::SecondaryPreferred { options: None },
+            Self::Nearest => ReadPreference::Nearest { options: None },
+        };
+        SelectionCriteria::ReadPreference(preference)
+    }
+}
+
+/// Connection settings beyond the URI
+#[derive(Debug, Clone, Default)]
+pub struct MongoOptions {
+    /// Replica set name (overrides `replicaSet` in the URI)
+    pub replica_set: Option<String>,
+    /// Routing of reads made through [`MongoClient::read_database`]
+    pub read_routing: ReadRouting,
+    pub min_pool_size: Option<u32>,
+    pub max_pool_size: Option<u32>,
+    /// Retries of a write that failed during a failover
+    pub write_retries: u32,
+}
+
+impl MongoOptions {
+    pub fn from_args(args: &Args) -> Result<Self, String> {
+        let read_routing = ReadRouting::parse(&args.mongodb_read_preference).ok_or_else(|| {
+            format!(
+                "Unknown MongoDB read preference '{}'",
+                args.mongodb_read_preference
+            )
+        })?;
+        Ok(Self {
+            replica_set: args.mongodb_replica_set.clone(),
+            read_routing,
+            min_pool_size: Some(args.mongodb_min_pool_size),
+            max_pool_size: Some(args.mongodb_max_pool_size),
+            write_retries: args.mongodb_write_retries,
+        })
+    }
+}
+
+/// Whether a server code means the replica set is between primaries
+fn is_failover_code(code: i32) -> bool {
+    FAILOVER_CODES.contains(&code)
+}
+
+/// Whether a failed write is worth retrying once a new primary is elected
+pub fn is_transient_write_error(e: &MongoError) -> bool {
+    if e.contains_label("RetryableWriteError") {
+        return true;
+    }
+    let code = match e.kind.as_ref() {
+        ErrorKind::Command(command) => Some(command.code),
+        ErrorKind::Write(WriteFailure::WriteConcernError(concern)) => Some(concern.code),
+        _ => None,
+    };
+    code.is_some_and(is_failover_code)
+}
+
+/// Run a write, retrying transient failover errors up to `retries` times
+pub async fn retry_write<T, F, Fut>(retries: u32, mut op: F) -> Result<T, MongoError>
+where
+    F: FnMut() -> Fut,
+    Fut: IntoFuture<Output = Result<T, MongoError>>,
+{
+    let mut attempt = 0;
+    loop {
+        match op().await {
+            Err(e) if attempt < retries && is_transient_write_error(&e) => {
+                let delay = RETRY_BACKOFF * 2u32.pow(attempt);
+                attempt += 1;
+                warn!(
+                    attempt,
+                    delay_ms = delay.as_millis() as u64,
+                    error = %e,
+                    "MongoDB write failed during failover, retrying"
+                );
+                tokio::time::sleep(delay).await;
+            }
+            result => return result,
+        }
+    }
+}
+
 /// Trait for schemas that provide index definitions
 pub trait IntoIndexes {
     fn into_indices() -> Vec<(Document, Option<IndexOptions>)>;
@@ -30,22 +172,38 @@ pub trait MutMetadata {
 pub struct MongoClient {
     client: Client,
     db_name: String,
+    read_routing: ReadRouting,
+    write_retries: u32,
 }
 
 impl MongoClient {
-    /// Create a new MongoDB client
+    /// Create a new MongoDB client with default options
     pub async fn new(uri: &str, db_name: &str) -> Result<Self, DoorwayError> {
-        info!("Connecting to MongoDB at {}", uri);
+        Self::with_options(uri, db_name, MongoOptions::default()).await
+    }
 
-        // Use serverSelectionTimeoutMS to avoid hanging on unreachable MongoDB
-        let timeout_uri = if uri.contains('?') {
-            format!("{uri}&serverSelectionTimeoutMS=3000&connectTimeoutMS=3000")
-        } else {
-            format!("{uri}?serverSelectionTimeoutMS=3000&connectTimeoutMS=3000")
-        };
+    /// Create a new MongoDB client
+    pub async fn with_options(
+        uri: &str,
+        db_name: &str,
+        options: MongoOptions,
+    ) -> Result<Self, DoorwayError> {
+        info!("Connecting to MongoDB at {}", uri);
 
-        let client = Client::with_uri_str(&timeout_uri)
+        let mut client_options = ClientOptions::parse(uri)
             .await
+            .map_err(|e| DoorwayError::Database(format!("Invalid MongoDB URI: {e}")))?;
+
+        // Avoid hanging on unreachable MongoDB
+        client_options.server_selection_timeout = Some(CONNECT_TIMEOUT);
+        client_options.connect_timeout = Some(CONNECT_TIMEOUT);
+        if options.replica_set.is_some() {
+            client_options.repl_set_name = options.replica_set.clone();
+        }
+        client_options.min_pool_size = options.min_pool_size;
+        client_options.max_pool_size = options.max_pool_size;
+
+        let client = Client::with_options(client_options)
             .map_err(|e| DoorwayError::Database(format!("Failed to connect to MongoDB: {e}")))?;
 
         // Verify connection with timeout
@@ -55,11 +213,16 @@ impl MongoClient {
             .await
             .map_err(|e| DoorwayError::Database(format!("MongoDB ping failed: {e}")))?;
 
-        info!("Connected to MongoDB database '{}'", db_name);
+        info!(
+            "Connected to MongoDB database '{}' (reads: {:?}, pool {:?}-{:?})",
+            db_name, options.read_routing, options.min_pool_size, options.max_pool_size
+        );
 
         Ok(Self {
             client,
             db_name: db_name.to_string(),
+            read_routing: options.read_routing,
+            write_retries: options.write_retries,
         })
     }
 
@@ -68,7 +231,27 @@ impl MongoClient {
     where
         T: Serialize + DeserializeOwned + Unpin + Send + Sync + Default + IntoIndexes + MutMetadata,
     {
-        MongoCollection::new(&self.client, &self.db_name, name).await
+        let collection = MongoCollection::new(&self.client, &self.db_name, name).await?;
+        Ok(collection.with_write_retries(self.write_retries))
+    }
+
+    /// The database, reading from and writing to the primary
+    pub fn database(&self) -> Database {
+        self.client.database(&self.db_name)
+    }
+
+    /// The database for reads that tolerate slight staleness, routed by the
+    /// configured read preference (writes still go to the primary)
+    pub fn read_database(&self) -> Database {
+        let options = DatabaseOptions::builder()
+            .selection_criteria(self.read_routing.selection_criteria())
+            .build();
+        self.client.database_with_options(&self.db_name, options)
+    }
+
+    /// Retries granted to writes failing during a failover
+    pub fn write_retries(&self) -> u32 {
+        self.write_retries
     }
 
     /// Get the raw MongoDB client
@@ -89,6 +272,7 @@ where
     T: Serialize + DeserializeOwned + Unpin + Send + Sync,
 {
     inner: Collection<T>,
+    write_retries: u32,
 }
 
 impl<T> MongoCollection<T>
@@ -102,7 +286,10 @@ where
         collection_name: &str,
     ) -> Result<Self, DoorwayError> {
         let collection = client.database(db_name).collection::<T>(collection_name);
-        let mongo_collection = MongoCollection { inner: collection };
+        let mongo_collection = MongoCollection {
+            inner: collection,
+            write_retries: 0,
+        };
 
         // Apply indexes
         mongo_collection.apply_indexes().await?;
@@ -110,6 +297,12 @@ where
         Ok(mongo_collection)
     }
 
+    /// Retry writes failing during a failover up to `retries` times
+    pub fn with_write_retries(mut self, retries: u32) -> Self {
+        self.write_retries = retries;
+        self
+    }
+
     /// Apply schema-defined indexes
     async fn apply_indexes(&self) -> Result<(), DoorwayError> {
         let schema_indices = T::into_indices();
@@ -138,9 +331,7 @@ where
         metadata.created_at = Some(DateTime::now());
         metadata.updated_at = Some(DateTime::now());
 
-        let result = self
-            .inner
-            .insert_one(item)
+        let result = retry_write(self.write_retries, || self.inner.insert_one(&item))
             .await
             .map_err(|e| DoorwayError::Database(format!("Insert failed: {e}")))?;
 
@@ -201,10 +392,11 @@ where
         // Add updated_at to the update
         let modifications = update.into();
 
-        self.inner
-            .update_one(filter, modifications)
-            .await
-            .map_err(|e| DoorwayError::Database(format!("Update failed: {e}")))
+        retry_write(self.write_retries, || {
+            self.inner.update_one(filter.clone(), modifications.clone())
+        })
+        .await
+        .map_err(|e| DoorwayError::Database(format!("Update failed: {e}")))
     }
 
     /// Soft delete a document
@@ -230,4 +422,27 @@ where
 mod tests {
     // Integration tests would require a running MongoDB instance
     // See docker-compose.dev.yml for local testing
+
+    use super::*;
+
+    #[test]
+    fn test_read_routing_parse() {
+        assert_eq!(
+            ReadRouting::parse("secondary_preferred"),
+            Some(ReadRouting::SecondaryPreferred)
+        );
+        assert_eq!(
+            ReadRouting::parse("primaryPreferred"),
+            Some(ReadRouting::PrimaryPreferred)
+        );
+        assert_eq!(ReadRouting::parse("Nearest"), Some(ReadRouting::Nearest));
+        assert_eq!(ReadRouting::parse("tertiary"), None);
+    }
+
+    #[test]
+    fn test_failover_codes() {
+        assert!(is_failover_code(10107));
+        assert!(is_failover_code(189));
+        assert!(!is_failover_code(11000)); // DuplicateKey
+    }
 }
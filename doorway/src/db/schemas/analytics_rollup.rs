@@ -0,0 +1,173 @@
+//! Path Analytics Rollup Schema
+//!
+//! Anonymized per-path learning aggregates written by the analytics worker.
+//! One document per path, replaced on every rollup. Documents hold counts
+//! and averages only; no agent or human identifiers are stored.
+
+use bson::{doc, oid::ObjectId, Document};
+use chrono::{DateTime, Utc};
+use mongodb::options::IndexOptions;
+use serde::{Deserialize, Serialize};
+
+use super::metadata::Metadata;
+use crate::db::mongo::{IntoIndexes, MutMetadata};
+
+/// Collection name for path analytics rollups
+pub const ANALYTICS_ROLLUP_COLLECTION: &str = "analytics_path_rollups";
+
+/// Completion funnel entry for one step
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct FunnelStepRollup {
+    pub step_index: u32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub step_title: Option<String>,
+
+    /// Authored time estimate for the step
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_minutes: Option<u32>,
+
+    /// Learners who completed the step
+    #[serde(default)]
+    pub completions: u32,
+
+    /// completions / learners started
+    #[serde(default)]
+    pub completion_rate: f64,
+}
+
+/// Path analytics rollup document
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct PathAnalyticsRollupDoc {
+    /// MongoDB document ID
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    /// Standard metadata (created_at, updated_at, is_deleted)
+    #[serde(default)]
+    pub metadata: Metadata,
+
+    #[serde(default)]
+    pub path_id: String,
+
+    #[serde(default)]
+    pub path_title: String,
+
+    /// Learners who started the path
+    #[serde(default)]
+    pub learners_started: u32,
+
+    #[serde(default)]
+    pub learners_completed: u32,
+
+    /// True when the cohort was too small to report details; only
+    /// `learners_started` is meaningful then
+    #[serde(default)]
+    pub suppressed: bool,
+
+    #[serde(default)]
+    pub funnel: Vec<FunnelStepRollup>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avg_estimated_minutes_per_step: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avg_actual_minutes_per_step: Option<f64>,
+
+    /// Completed challenges bucketed by score in tenths (0.0-0.1, ..., 0.9-1.0)
+    #[serde(default)]
+    pub challenge_score_histogram: Vec<u32>,
+
+    #[serde(default)]
+    pub challenges_completed: u32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub computed_at: Option<DateTime<Utc>>,
+}
+
+impl PathAnalyticsRollupDoc {
+    /// Share of starters who finished the path
+    pub fn completion_rate(&self) -> f64 {
+        if self.learners_started == 0 {
+            0.0
+        } else {
+            self.learners_completed as f64 / self.learners_started as f64
+        }
+    }
+
+    /// Drop everything but the cohort size
+    pub fn suppress(&mut self) {
+        self.suppressed = true;
+        self.learners_completed = 0;
+        self.funnel.clear();
+        self.avg_actual_minutes_per_step = None;
+        self.challenge_score_histogram.clear();
+        self.challenges_completed = 0;
+    }
+}
+
+impl IntoIndexes for PathAnalyticsRollupDoc {
+    fn into_indices() -> Vec<(Document, Option<IndexOptions>)> {
+        vec![
+            // One rollup per path
+            (
+                doc! { "path_id": 1 },
+                Some(
+                    IndexOptions::builder()
+                        .unique(true)
+                        .name("path_id_unique".to_string())
+                        .build(),
+                ),
+            ),
+        ]
+    }
+}
+
+impl MutMetadata for PathAnalyticsRollupDoc {
+    fn mut_metadata(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_completion_rate() {
+        let mut rollup = PathAnalyticsRollupDoc::default();
+        assert_eq!(rollup.completion_rate(), 0.0);
+
+        rollup.learners_started = 8;
+        rollup.learners_completed = 2;
+        assert_eq!(rollup.completion_rate(), 0.25);
+    }
+
+    #[test]
+    fn test_suppress_keeps_only_cohort_size() {
+        let mut rollup = PathAnalyticsRollupDoc {
+            learners_started: 3,
+            learners_completed: 1,
+            funnel: vec![FunnelStepRollup {
+                step_index: 0,
+                completions: 3,
+                ..Default::default()
+            }],
+            avg_estimated_minutes_per_step: Some(10.0),
+            avg_actual_minutes_per_step: Some(12.5),
+            challenge_score_histogram: vec![0, 0, 0, 0, 0, 0, 0, 1, 0, 0],
+            challenges_completed: 1,
+            ..Default::default()
+        };
+        rollup.suppress();
+
+        assert!(rollup.suppressed);
+        assert_eq!(rollup.learners_started, 3);
+        assert_eq!(rollup.learners_completed, 0);
+        assert!(rollup.funnel.is_empty());
+        assert!(rollup.avg_actual_minutes_per_step.is_none());
+        assert!(rollup.challenge_score_histogram.is_empty());
+        // Authored estimates aren't learner data
+        assert_eq!(rollup.avg_estimated_minutes_per_step, Some(10.0));
+    }
+}
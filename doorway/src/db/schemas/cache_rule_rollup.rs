@@ -0,0 +1,71 @@
+//! Cache Rule Rollup Schema
+//!
+//! Per-rule response cache counters for one rollup window, written by the
+//! [cache stats worker](crate::worker::cache_stats). Rules with no activity
+//! in a window get no document. `metadata.created_at` is when the window
+//! closed.
+
+use bson::{doc, oid::ObjectId, Document};
+use chrono::{DateTime, Utc};
+use mongodb::options::IndexOptions;
+use serde::{Deserialize, Serialize};
+
+use super::metadata::Metadata;
+use crate::cache::RuleStatsSnapshot;
+use crate::db::mongo::{IntoIndexes, MutMetadata};
+
+/// Collection name for cache rule rollups
+pub const CACHE_RULE_ROLLUP_COLLECTION: &str = "cache_rule_rollups";
+
+/// Cache rule rollup document
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct CacheRuleRollupDoc {
+    /// MongoDB document ID
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    /// Standard metadata (created_at, updated_at, is_deleted)
+    #[serde(default)]
+    pub metadata: Metadata,
+
+    /// Counters accumulated during the window
+    #[serde(flatten)]
+    pub stats: RuleStatsSnapshot,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_start: Option<DateTime<Utc>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_end: Option<DateTime<Utc>>,
+}
+
+impl IntoIndexes for CacheRuleRollupDoc {
+    fn into_indices() -> Vec<(Document, Option<IndexOptions>)> {
+        vec![
+            // Recent windows across all rules
+            (
+                doc! { "metadata.created_at": -1 },
+                Some(
+                    IndexOptions::builder()
+                        .name("created_at_index".to_string())
+                        .build(),
+                ),
+            ),
+            // One rule's history
+            (
+                doc! { "dna_hash": 1, "fn_name": 1, "metadata.created_at": -1 },
+                Some(
+                    IndexOptions::builder()
+                        .name("rule_window_index".to_string())
+                        .build(),
+                ),
+            ),
+        ]
+    }
+}
+
+impl MutMetadata for CacheRuleRollupDoc {
+    fn mut_metadata(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+}
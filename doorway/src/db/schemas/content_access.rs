@@ -0,0 +1,203 @@
+//! Content Access Schemas
+//!
+//! Opt-in reach metrics for [content access logging](crate::services::content_access).
+//! Stewards mark content with a tracking document; views of marked content
+//! are counted into one rollup document per content node and UTC day.
+//! Nothing about the viewer is stored: a view only adds to the day's count
+//! and to the bucket its referrer falls in.
+
+use bson::{doc, oid::ObjectId, Document};
+use mongodb::options::IndexOptions;
+use serde::{Deserialize, Serialize};
+
+use super::metadata::Metadata;
+use crate::db::mongo::{IntoIndexes, MutMetadata};
+
+/// Collection name for access tracking marks
+pub const CONTENT_ACCESS_TRACKING_COLLECTION: &str = "content_access_tracking";
+
+/// Collection name for daily access rollups
+pub const CONTENT_ACCESS_ROLLUP_COLLECTION: &str = "content_access_rollups";
+
+/// Where a view came from, coarsely
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ReferrerBucket {
+    /// No referrer sent
+    Direct,
+    /// This doorway or its apps
+    Internal,
+    /// A web search engine
+    Search,
+    /// A social network or forum
+    Social,
+    Other,
+}
+
+impl ReferrerBucket {
+    pub const ALL: [Self; 5] = [
+        Self::Direct,
+        Self::Internal,
+        Self::Search,
+        Self::Social,
+        Self::Other,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Direct => "direct",
+            Self::Internal => "internal",
+            Self::Search => "search",
+            Self::Social => "social",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// Views per referrer bucket
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReferrerCounts {
+    #[serde(default)]
+    pub direct: u64,
+    #[serde(default)]
+    pub internal: u64,
+    #[serde(default)]
+    pub search: u64,
+    #[serde(default)]
+    pub social: u64,
+    #[serde(default)]
+    pub other: u64,
+}
+
+impl ReferrerCounts {
+    pub fn get(&self, bucket: ReferrerBucket) -> u64 {
+        match bucket {
+            ReferrerBucket::Direct => self.direct,
+            ReferrerBucket::Internal => self.internal,
+            ReferrerBucket::Search => self.search,
+            ReferrerBucket::Social => self.social,
+            ReferrerBucket::Other => self.other,
+        }
+    }
+
+    pub fn add(&mut self, bucket: ReferrerBucket, views: u64) {
+        let count = match bucket {
+            ReferrerBucket::Direct => &mut self.direct,
+            ReferrerBucket::Internal => &mut self.internal,
+            ReferrerBucket::Search => &mut self.search,
+            ReferrerBucket::Social => &mut self.social,
+            ReferrerBucket::Other => &mut self.other,
+        };
+        *count = count.saturating_add(views);
+    }
+
+    pub fn merge(&mut self, other: &ReferrerCounts) {
+        for bucket in ReferrerBucket::ALL {
+            self.add(bucket, other.get(bucket));
+        }
+    }
+
+    /// Views over all buckets
+    pub fn total(&self) -> u64 {
+        ReferrerBucket::ALL.iter().map(|b| self.get(*b)).sum()
+    }
+}
+
+/// A steward's mark enabling access logging for one content node
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ContentAccessTrackingDoc {
+    /// MongoDB document ID
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    /// Standard metadata (created_at, updated_at, is_deleted)
+    #[serde(default)]
+    pub metadata: Metadata,
+
+    #[serde(default)]
+    pub content_id: String,
+
+    #[serde(default)]
+    pub track_access: bool,
+
+    /// Steward who last changed the mark
+    #[serde(default)]
+    pub marked_by: String,
+}
+
+impl IntoIndexes for ContentAccessTrackingDoc {
+    fn into_indices() -> Vec<(Document, Option<IndexOptions>)> {
+        vec![(
+            doc! { "content_id": 1 },
+            Some(
+                IndexOptions::builder()
+                    .unique(true)
+                    .name("content_id_unique".to_string())
+                    .build(),
+            ),
+        )]
+    }
+}
+
+impl MutMetadata for ContentAccessTrackingDoc {
+    fn mut_metadata(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+}
+
+/// Views of one content node on one day
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ContentAccessRollupDoc {
+    /// MongoDB document ID
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    /// Standard metadata (created_at, updated_at, is_deleted)
+    #[serde(default)]
+    pub metadata: Metadata,
+
+    #[serde(default)]
+    pub content_id: String,
+
+    /// UTC day as `YYYY-MM-DD`
+    #[serde(default)]
+    pub day: String,
+
+    #[serde(default)]
+    pub views: u64,
+
+    #[serde(default)]
+    pub referrers: ReferrerCounts,
+}
+
+impl IntoIndexes for ContentAccessRollupDoc {
+    fn into_indices() -> Vec<(Document, Option<IndexOptions>)> {
+        vec![
+            // One rollup per content node and day
+            (
+                doc! { "content_id": 1, "day": 1 },
+                Some(
+                    IndexOptions::builder()
+                        .unique(true)
+                        .name("content_day_unique".to_string())
+                        .build(),
+                ),
+            ),
+            // Every tracked node over a span of days
+            (
+                doc! { "day": -1 },
+                Some(
+                    IndexOptions::builder()
+                        .name("day_index".to_string())
+                        .build(),
+                ),
+            ),
+        ]
+    }
+}
+
+impl MutMetadata for ContentAccessRollupDoc {
+    fn mut_metadata(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+}
@@ -0,0 +1,108 @@
+//! Content Embedding Schema
+//!
+//! Vector embeddings of content written by the
+//! [embedding worker](crate::worker::embeddings). One document per content
+//! node and model. On Atlas (or any deployment with search indexes) the
+//! `embedding` field is covered by a `vectorSearch` index; elsewhere
+//! similarity is computed in doorway.
+
+use bson::{doc, oid::ObjectId, Document};
+use chrono::{DateTime, Utc};
+use mongodb::options::IndexOptions;
+use serde::{Deserialize, Serialize};
+
+use super::metadata::Metadata;
+use crate::db::mongo::{IntoIndexes, MutMetadata};
+
+/// Collection name for content embeddings
+pub const CONTENT_EMBEDDING_COLLECTION: &str = "content_embeddings";
+
+/// Name of the Atlas vector search index on `embedding`
+pub const CONTENT_EMBEDDING_VECTOR_INDEX: &str = "content_embedding_vector";
+
+/// Content embedding document
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ContentEmbeddingDoc {
+    /// MongoDB document ID
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    /// Standard metadata (created_at, updated_at, is_deleted)
+    #[serde(default)]
+    pub metadata: Metadata,
+
+    #[serde(default)]
+    pub content_id: String,
+
+    #[serde(default)]
+    pub title: String,
+
+    #[serde(default)]
+    pub content_type: String,
+
+    #[serde(default)]
+    pub reach: String,
+
+    /// Embedding model that produced the vector (vectors from different
+    /// models are never compared)
+    #[serde(default)]
+    pub model: String,
+
+    #[serde(default)]
+    pub embedding: Vec<f32>,
+
+    /// SHA-256 of the embedded text; unchanged text is not re-embedded
+    #[serde(default)]
+    pub text_hash: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embedded_at: Option<DateTime<Utc>>,
+}
+
+impl IntoIndexes for ContentEmbeddingDoc {
+    fn into_indices() -> Vec<(Document, Option<IndexOptions>)> {
+        vec![
+            // One embedding per content node and model
+            (
+                doc! { "content_id": 1, "model": 1 },
+                Some(
+                    IndexOptions::builder()
+                        .unique(true)
+                        .name("content_model_unique".to_string())
+                        .build(),
+                ),
+            ),
+            // Candidate scans for in-process similarity
+            (
+                doc! { "model": 1, "reach": 1 },
+                Some(
+                    IndexOptions::builder()
+                        .name("model_reach_index".to_string())
+                        .build(),
+                ),
+            ),
+        ]
+    }
+}
+
+impl MutMetadata for ContentEmbeddingDoc {
+    fn mut_metadata(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+}
+
+/// Definition for the Atlas `vectorSearch` index
+pub fn vector_index_definition(dimensions: usize) -> Document {
+    doc! {
+        "fields": [
+            {
+                "type": "vector",
+                "path": "embedding",
+                "numDimensions": dimensions as i64,
+                "similarity": "cosine",
+            },
+            { "type": "filter", "path": "model" },
+            { "type": "filter", "path": "reach" },
+        ]
+    }
+}
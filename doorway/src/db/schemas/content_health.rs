@@ -0,0 +1,190 @@
+//! Content Health Report Schema
+//!
+//! Maintenance findings from the content health sweep: content that hasn't
+//! been updated in a long time, blobs whose fallback URLs no longer answer,
+//! and relationships pointing at archived nodes. One document per content
+//! node with open issues; healthy content has no document.
+
+use bson::{doc, oid::ObjectId, Document};
+use chrono::{DateTime, Utc};
+use mongodb::options::IndexOptions;
+use serde::{Deserialize, Serialize};
+
+use super::metadata::Metadata;
+use crate::db::mongo::{IntoIndexes, MutMetadata};
+
+/// Collection name for content health reports
+pub const CONTENT_HEALTH_COLLECTION: &str = "content_health_reports";
+
+/// Kind of maintenance problem
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentHealthIssueKind {
+    /// Not updated within the staleness window
+    #[default]
+    Stale,
+    /// A blob fallback URL failed to respond
+    BrokenFallbackUrl,
+    /// A relationship or related node is archived
+    ArchivedRelationship,
+}
+
+impl ContentHealthIssueKind {
+    /// Parse the snake_case name used in queries
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "stale" => Some(Self::Stale),
+            "broken_fallback_url" => Some(Self::BrokenFallbackUrl),
+            "archived_relationship" => Some(Self::ArchivedRelationship),
+            _ => None,
+        }
+    }
+
+    /// What a steward should do about it
+    pub fn suggestion(self) -> &'static str {
+        match self {
+            Self::Stale => "Review the content and refresh or re-publish it",
+            Self::BrokenFallbackUrl => "Replace the URL or re-seed the blob to a custodian",
+            Self::ArchivedRelationship => "Relink to the successor or remove the relationship",
+        }
+    }
+}
+
+/// A single finding
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct ContentHealthIssue {
+    pub kind: ContentHealthIssueKind,
+
+    /// Human-readable description
+    #[serde(default)]
+    pub detail: String,
+
+    /// URL or content id the issue is about
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+
+    #[serde(default)]
+    pub suggestion: String,
+}
+
+impl ContentHealthIssue {
+    pub fn new(kind: ContentHealthIssueKind, detail: String, target: Option<String>) -> Self {
+        Self {
+            kind,
+            detail,
+            target,
+            suggestion: kind.suggestion().to_string(),
+        }
+    }
+}
+
+/// Content health report document
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ContentHealthReportDoc {
+    /// MongoDB document ID
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    /// Standard metadata (created_at, updated_at, is_deleted)
+    #[serde(default)]
+    pub metadata: Metadata,
+
+    #[serde(default)]
+    pub content_id: String,
+
+    #[serde(default)]
+    pub title: String,
+
+    #[serde(default)]
+    pub content_type: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author_id: Option<String>,
+
+    /// When the content entry was last written
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_updated_at: Option<DateTime<Utc>>,
+
+    #[serde(default)]
+    pub issues: Vec<ContentHealthIssue>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checked_at: Option<DateTime<Utc>>,
+}
+
+impl ContentHealthReportDoc {
+    /// Whether the report contains an issue of `kind`
+    pub fn has_issue(&self, kind: ContentHealthIssueKind) -> bool {
+        self.issues.iter().any(|issue| issue.kind == kind)
+    }
+}
+
+impl IntoIndexes for ContentHealthReportDoc {
+    fn into_indices() -> Vec<(Document, Option<IndexOptions>)> {
+        vec![
+            // One report per content node
+            (
+                doc! { "content_id": 1 },
+                Some(
+                    IndexOptions::builder()
+                        .unique(true)
+                        .name("content_id_unique".to_string())
+                        .build(),
+                ),
+            ),
+            // Triage by issue kind
+            (
+                doc! { "issues.kind": 1 },
+                Some(
+                    IndexOptions::builder()
+                        .name("issue_kind_index".to_string())
+                        .build(),
+                ),
+            ),
+        ]
+    }
+}
+
+impl MutMetadata for ContentHealthReportDoc {
+    fn mut_metadata(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_issue_kind_round_trip() {
+        for kind in [
+            ContentHealthIssueKind::Stale,
+            ContentHealthIssueKind::BrokenFallbackUrl,
+            ContentHealthIssueKind::ArchivedRelationship,
+        ] {
+            let json = serde_json::to_string(&kind).unwrap();
+            assert_eq!(
+                ContentHealthIssueKind::parse(json.trim_matches('"')),
+                Some(kind)
+            );
+        }
+        assert_eq!(ContentHealthIssueKind::parse("unknown"), None);
+    }
+
+    #[test]
+    fn test_issue_carries_suggestion() {
+        let issue = ContentHealthIssue::new(
+            ContentHealthIssueKind::BrokenFallbackUrl,
+            "HTTP 404".to_string(),
+            Some("https://cdn.example/blob".to_string()),
+        );
+        assert!(!issue.suggestion.is_empty());
+
+        let report = ContentHealthReportDoc {
+            issues: vec![issue],
+            ..Default::default()
+        };
+        assert!(report.has_issue(ContentHealthIssueKind::BrokenFallbackUrl));
+        assert!(!report.has_issue(ContentHealthIssueKind::Stale));
+    }
+}
@@ -0,0 +1,195 @@
+//! Elohim Task Schema
+//!
+//! Work the doorway has forwarded to an elohim agent registered for a scope
+//! (moderation reviews, translations, tutoring). The agent reports progress
+//! over NATS and the [status listener](crate::worker::elohim_tasks) keeps
+//! these documents current.
+
+use bson::{doc, oid::ObjectId, Document};
+use chrono::{DateTime, Utc};
+use mongodb::options::IndexOptions;
+use serde::{Deserialize, Serialize};
+
+use super::metadata::Metadata;
+use crate::db::mongo::{IntoIndexes, MutMetadata};
+
+/// Collection name for elohim tasks
+pub const ELOHIM_TASK_COLLECTION: &str = "elohim_tasks";
+
+/// What an elohim is asked to do
+///
+/// Names match the capabilities elohim agents register with
+/// (`ELOHIM_CAPABILITIES` in the imagodei integrity zome).
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ElohimTaskKind {
+    #[default]
+    ModerationReview,
+    Translation,
+    Tutoring,
+}
+
+impl ElohimTaskKind {
+    /// Parse the snake_case name used in requests
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "moderation_review" => Some(Self::ModerationReview),
+            "translation" => Some(Self::Translation),
+            "tutoring" => Some(Self::Tutoring),
+            _ => None,
+        }
+    }
+
+    /// Capability name an elohim must have registered
+    pub fn capability(self) -> &'static str {
+        match self {
+            Self::ModerationReview => "moderation_review",
+            Self::Translation => "translation",
+            Self::Tutoring => "tutoring",
+        }
+    }
+}
+
+/// Progress of a dispatched task
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ElohimTaskStatus {
+    /// Published to the elohim's endpoint, not acknowledged yet
+    #[default]
+    Dispatched,
+    /// The elohim acknowledged the task and is working on it
+    Accepted,
+    Completed,
+    Failed,
+    /// No final status arrived before the task timeout
+    TimedOut,
+}
+
+impl ElohimTaskStatus {
+    /// Whether no further updates are expected
+    pub fn is_final(self) -> bool {
+        matches!(self, Self::Completed | Self::Failed | Self::TimedOut)
+    }
+}
+
+/// Elohim task document
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ElohimTaskDoc {
+    /// MongoDB document ID
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    /// Standard metadata (created_at, updated_at, is_deleted)
+    #[serde(default)]
+    pub metadata: Metadata,
+
+    /// Task ID shared with the elohim (UUID)
+    #[serde(default)]
+    pub task_id: String,
+
+    #[serde(default)]
+    pub kind: ElohimTaskKind,
+
+    /// Scope the task was dispatched for
+    #[serde(default)]
+    pub scope: String,
+
+    /// Registered elohim the task went to
+    #[serde(default)]
+    pub elohim_id: String,
+
+    /// NATS subject the task was published on
+    #[serde(default)]
+    pub endpoint: String,
+
+    /// Task input as JSON
+    #[serde(default)]
+    pub payload_json: String,
+
+    /// Human who asked for the task
+    #[serde(default)]
+    pub requested_by: String,
+
+    #[serde(default)]
+    pub status: ElohimTaskStatus,
+
+    /// Result reported by the elohim, as JSON
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result_json: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl IntoIndexes for ElohimTaskDoc {
+    fn into_indices() -> Vec<(Document, Option<IndexOptions>)> {
+        vec![
+            (
+                doc! { "task_id": 1 },
+                Some(
+                    IndexOptions::builder()
+                        .unique(true)
+                        .name("task_id_index".to_string())
+                        .build(),
+                ),
+            ),
+            // Timing out tasks that never finished
+            (
+                doc! { "status": 1, "metadata.created_at": 1 },
+                Some(
+                    IndexOptions::builder()
+                        .name("status_created_index".to_string())
+                        .build(),
+                ),
+            ),
+            (
+                doc! { "requested_by": 1, "metadata.created_at": -1 },
+                Some(
+                    IndexOptions::builder()
+                        .name("requester_created_index".to_string())
+                        .build(),
+                ),
+            ),
+        ]
+    }
+}
+
+impl MutMetadata for ElohimTaskDoc {
+    fn mut_metadata(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kind_matches_capability_names() {
+        for kind in [
+            ElohimTaskKind::ModerationReview,
+            ElohimTaskKind::Translation,
+            ElohimTaskKind::Tutoring,
+        ] {
+            let json = serde_json::to_string(&kind).unwrap();
+            assert_eq!(json.trim_matches('"'), kind.capability());
+            assert_eq!(ElohimTaskKind::parse(kind.capability()), Some(kind));
+        }
+        assert_eq!(ElohimTaskKind::parse("summarize"), None);
+    }
+
+    #[test]
+    fn test_final_statuses() {
+        assert!(!ElohimTaskStatus::Dispatched.is_final());
+        assert!(!ElohimTaskStatus::Accepted.is_final());
+        assert!(ElohimTaskStatus::Completed.is_final());
+        assert!(ElohimTaskStatus::TimedOut.is_final());
+        assert_eq!(
+            serde_json::to_string(&ElohimTaskStatus::TimedOut).unwrap(),
+            "\"timed_out\""
+        );
+    }
+}
@@ -0,0 +1,120 @@
+//! Identity Link Schema
+//!
+//! Accounts merged into another account. A person who signed up twice (or
+//! set up an API key or passkey on a second account) links the extra
+//! account into the one they keep; from then on its credentials sign in as
+//! the kept identity and agent key. Each link records what it moved so it
+//! can be undone.
+
+use bson::{doc, oid::ObjectId, DateTime, Document};
+use mongodb::options::IndexOptions;
+use serde::{Deserialize, Serialize};
+
+use super::metadata::Metadata;
+use crate::db::mongo::{IntoIndexes, MutMetadata};
+
+/// Collection name for identity links
+pub const IDENTITY_LINK_COLLECTION: &str = "identity_links";
+
+/// Credential that proved control of the linked account
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkProof {
+    #[default]
+    Password,
+    Passkey,
+    ApiKey,
+}
+
+/// An account merged into a primary account
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct IdentityLinkDoc {
+    /// MongoDB document ID
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    /// Standard metadata (created_at, updated_at, is_deleted)
+    #[serde(default)]
+    pub metadata: Metadata,
+
+    /// User document of the account kept
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub primary_user_id: Option<ObjectId>,
+
+    /// Identifier of the account kept
+    #[serde(default)]
+    pub primary_identifier: String,
+
+    #[serde(default)]
+    pub primary_human_id: String,
+
+    #[serde(default)]
+    pub primary_agent_pub_key: String,
+
+    /// User document of the linked account
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<ObjectId>,
+
+    /// Identifier of the linked account
+    #[serde(default)]
+    pub identifier: String,
+
+    /// Human ID the linked account had
+    #[serde(default)]
+    pub human_id: String,
+
+    /// Agent key the linked account had; no longer issued in tokens
+    #[serde(default)]
+    pub agent_pub_key: String,
+
+    /// Conductor hosting the linked account's agent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conductor_id: Option<String>,
+
+    #[serde(default)]
+    pub proof: LinkProof,
+
+    /// Passkeys moved to the primary account (credential IDs)
+    #[serde(default)]
+    pub passkey_ids: Vec<String>,
+
+    /// API keys moved to the primary account
+    #[serde(default)]
+    pub api_key_ids: Vec<ObjectId>,
+
+    /// When the link was undone
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unlinked_at: Option<DateTime>,
+}
+
+impl IntoIndexes for IdentityLinkDoc {
+    fn into_indices() -> Vec<(Document, Option<IndexOptions>)> {
+        vec![
+            // An account is linked into at most one other at a time
+            (
+                doc! { "identifier": 1 },
+                Some(
+                    IndexOptions::builder()
+                        .unique(true)
+                        .partial_filter_expression(doc! { "metadata.is_deleted": false })
+                        .name("identifier_active_unique".to_string())
+                        .build(),
+                ),
+            ),
+            (
+                doc! { "primary_identifier": 1 },
+                Some(
+                    IndexOptions::builder()
+                        .name("primary_identifier_index".to_string())
+                        .build(),
+                ),
+            ),
+        ]
+    }
+}
+
+impl MutMetadata for IdentityLinkDoc {
+    fn mut_metadata(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+}
@@ -1,18 +1,74 @@
 //! Database schemas for Doorway
 //!
-//! Defines MongoDB document structures for users, API keys, hosts, and OAuth.
+//! Defines MongoDB document structures for users, passkeys and WebAuthn
+//! challenges, accounts linked into another account, API keys, hosts, OAuth,
+//! emergency recovery sagas, learning analytics rollups, cache rule rollups,
+//! content health reports, content access marks and daily rollups,
+//! content embeddings, relationship suggestions,
+//! tutor usage, the moderation queue, notifications, tasks dispatched to
+//! elohim agents, the journal of received conductor signals, retention
+//! policy audit records and provisioned operators.
 
+mod analytics_rollup;
 mod api_key;
+mod cache_rule_rollup;
+mod content_access;
+mod content_embedding;
+mod content_health;
+mod elohim_task;
 mod host;
+mod identity_link;
 mod metadata;
+mod moderation_item;
+mod notification;
 mod oauth_session;
+mod operator;
+mod passkey;
+mod recovery_saga;
+mod relationship_suggestion;
+mod retention_audit;
+mod signal_journal;
+mod tutor_usage;
 mod user;
 
+pub use analytics_rollup::{FunnelStepRollup, PathAnalyticsRollupDoc, ANALYTICS_ROLLUP_COLLECTION};
 pub use api_key::{ApiKeyDoc, API_KEY_COLLECTION};
+pub use cache_rule_rollup::{CacheRuleRollupDoc, CACHE_RULE_ROLLUP_COLLECTION};
+pub use content_access::{
+    ContentAccessRollupDoc, ContentAccessTrackingDoc, ReferrerBucket, ReferrerCounts,
+    CONTENT_ACCESS_ROLLUP_COLLECTION, CONTENT_ACCESS_TRACKING_COLLECTION,
+};
+pub use content_embedding::{
+    vector_index_definition, ContentEmbeddingDoc, CONTENT_EMBEDDING_COLLECTION,
+    CONTENT_EMBEDDING_VECTOR_INDEX,
+};
+pub use content_health::{
+    ContentHealthIssue, ContentHealthIssueKind, ContentHealthReportDoc, CONTENT_HEALTH_COLLECTION,
+};
+pub use elohim_task::{ElohimTaskDoc, ElohimTaskKind, ElohimTaskStatus, ELOHIM_TASK_COLLECTION};
 pub use host::{HostDoc, HostStatus, HOST_COLLECTION};
+pub use identity_link::{IdentityLinkDoc, LinkProof, IDENTITY_LINK_COLLECTION};
 pub use metadata::Metadata;
+pub use moderation_item::{
+    ModerationItemDoc, ModerationKind, ModerationStatus, MODERATION_QUEUE_COLLECTION,
+};
+pub use notification::{NotificationDoc, NOTIFICATION_COLLECTION};
 pub use oauth_session::{
     get_registered_clients, validate_redirect_uri, OAuthClient, OAuthSessionDoc,
     OAUTH_SESSION_COLLECTION,
 };
+pub use operator::{OperatorDoc, OperatorStatus, OperatorStep, StepStatus, OPERATOR_COLLECTION};
+pub use passkey::{
+    PasskeyDoc, WebAuthnCeremony, WebAuthnChallengeDoc, PASSKEY_COLLECTION,
+    WEBAUTHN_CHALLENGE_COLLECTION,
+};
+pub use recovery_saga::{
+    RecoveryContentProgress, RecoverySagaDoc, RecoveryStep, RECOVERY_SAGA_COLLECTION,
+};
+pub use relationship_suggestion::{
+    RelationshipSuggestionDoc, SuggestionStatus, RELATIONSHIP_SUGGESTION_COLLECTION,
+};
+pub use retention_audit::{RetentionAuditDoc, RETENTION_AUDIT_COLLECTION};
+pub use signal_journal::{JournaledSignalKind, SignalJournalDoc, SIGNAL_JOURNAL_COLLECTION};
+pub use tutor_usage::{TutorUsageDoc, TUTOR_USAGE_COLLECTION};
 pub use user::{CustodialKeyMaterial, UserDoc, UserQuota, UserUsage, USER_COLLECTION};
@@ -0,0 +1,196 @@
+//! Moderation Queue Schema
+//!
+//! Items waiting for a steward: writes held back by the
+//! [moderation filter](crate::services::moderation), content reported by
+//! users, and content whose attestation was revoked. Quarantined writes keep
+//! the zome call they were submitted as, so approving one makes the
+//! original write.
+
+use bson::{doc, oid::ObjectId, Document};
+use chrono::{DateTime, Utc};
+use mongodb::options::IndexOptions;
+use serde::{Deserialize, Serialize};
+
+use super::metadata::Metadata;
+use crate::db::mongo::{IntoIndexes, MutMetadata};
+
+/// Collection name for the moderation queue
+pub const MODERATION_QUEUE_COLLECTION: &str = "moderation_queue";
+
+/// Why an item is in the queue
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ModerationKind {
+    /// A write the filter held back; not on the DHT yet
+    #[default]
+    QuarantinedWrite,
+    /// Published content reported by users
+    Report,
+    /// Published content that lost an attestation
+    AttestationRevoked,
+}
+
+impl ModerationKind {
+    /// Whether the item is about content already on the DHT
+    pub fn is_published(self) -> bool {
+        !matches!(self, Self::QuarantinedWrite)
+    }
+}
+
+/// Review state of a queue item
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ModerationStatus {
+    #[default]
+    Pending,
+    /// Written (quarantined writes) or left as is (published content)
+    Approved,
+    /// Dropped (quarantined writes) or moved to private reach (published content)
+    #[serde(alias = "rejected")]
+    Hidden,
+    /// Sent back to the author with a note
+    ChangesRequested,
+}
+
+impl ModerationStatus {
+    /// Parse the snake_case name used in queries
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(Self::Pending),
+            "approved" => Some(Self::Approved),
+            "hidden" => Some(Self::Hidden),
+            "changes_requested" => Some(Self::ChangesRequested),
+            _ => None,
+        }
+    }
+}
+
+/// Moderation queue document
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ModerationItemDoc {
+    /// MongoDB document ID
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    /// Standard metadata (created_at, updated_at, is_deleted)
+    #[serde(default)]
+    pub metadata: Metadata,
+
+    #[serde(default)]
+    pub kind: ModerationKind,
+
+    /// Content the item is about (reports and revocations)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_id: Option<String>,
+
+    /// content_store function a quarantined write was submitted to
+    #[serde(default)]
+    pub zome_fn: String,
+
+    /// Zome call input as JSON, replayed when a quarantined write is approved
+    #[serde(default)]
+    pub payload_json: String,
+
+    /// Why it was queued (`word:<term>`, `api:<category>`, `report:<reason>`, ...)
+    #[serde(default)]
+    pub reasons: Vec<String>,
+
+    /// Reporter comments or revocation details
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<String>,
+
+    /// Where the item came from (`api`, `import:<batch_id>`, `report`, `attestation`)
+    #[serde(default)]
+    pub source: String,
+
+    /// Human who submitted a quarantined write, if known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub submitted_by: Option<String>,
+
+    /// Humans who reported the content
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reporters: Vec<String>,
+
+    /// Who is told about the outcome (human id or agent key)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+
+    #[serde(default)]
+    pub status: ModerationStatus,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reviewed_by: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reviewed_at: Option<DateTime<Utc>>,
+
+    /// Steward's note to the author
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub review_note: Option<String>,
+}
+
+impl IntoIndexes for ModerationItemDoc {
+    fn into_indices() -> Vec<(Document, Option<IndexOptions>)> {
+        vec![
+            // Review queue, oldest first
+            (
+                doc! { "status": 1, "metadata.created_at": 1 },
+                Some(
+                    IndexOptions::builder()
+                        .name("status_created_index".to_string())
+                        .build(),
+                ),
+            ),
+            // Folding repeat reports into the open item
+            (
+                doc! { "content_id": 1, "kind": 1, "status": 1 },
+                Some(
+                    IndexOptions::builder()
+                        .name("content_kind_status_index".to_string())
+                        .build(),
+                ),
+            ),
+        ]
+    }
+}
+
+impl MutMetadata for ModerationItemDoc {
+    fn mut_metadata(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_round_trip() {
+        for status in [
+            ModerationStatus::Pending,
+            ModerationStatus::Approved,
+            ModerationStatus::Hidden,
+            ModerationStatus::ChangesRequested,
+        ] {
+            let json = serde_json::to_string(&status).unwrap();
+            assert_eq!(
+                ModerationStatus::parse(json.trim_matches('"')),
+                Some(status)
+            );
+        }
+        assert_eq!(ModerationStatus::parse("flagged"), None);
+
+        let legacy: ModerationStatus = serde_json::from_str("\"rejected\"").unwrap();
+        assert_eq!(legacy, ModerationStatus::Hidden);
+    }
+
+    #[test]
+    fn test_kind_serialization() {
+        assert_eq!(
+            serde_json::to_string(&ModerationKind::AttestationRevoked).unwrap(),
+            "\"attestation_revoked\""
+        );
+        assert!(!ModerationKind::QuarantinedWrite.is_published());
+        assert!(ModerationKind::Report.is_published());
+    }
+}
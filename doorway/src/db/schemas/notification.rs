@@ -0,0 +1,67 @@
+//! Notification Schema
+//!
+//! Messages for a human from the doorway, such as the outcome of a
+//! moderation review of their content. Addressed by human id or by agent
+//! key, since published content only records its author's agent.
+
+use bson::{doc, oid::ObjectId, Document};
+use mongodb::options::IndexOptions;
+use serde::{Deserialize, Serialize};
+
+use super::metadata::Metadata;
+use crate::db::mongo::{IntoIndexes, MutMetadata};
+
+/// Collection name for notifications
+pub const NOTIFICATION_COLLECTION: &str = "notifications";
+
+/// Notification document
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct NotificationDoc {
+    /// MongoDB document ID
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    /// Standard metadata (created_at, updated_at, is_deleted)
+    #[serde(default)]
+    pub metadata: Metadata,
+
+    /// Human id or agent key of the recipient
+    #[serde(default)]
+    pub recipient: String,
+
+    /// What the notification is about (e.g. `moderation`)
+    #[serde(default)]
+    pub kind: String,
+
+    #[serde(default)]
+    pub title: String,
+
+    #[serde(default)]
+    pub message: String,
+
+    /// Content (or learning session) the notification refers to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_id: Option<String>,
+
+    #[serde(default)]
+    pub read: bool,
+}
+
+impl IntoIndexes for NotificationDoc {
+    fn into_indices() -> Vec<(Document, Option<IndexOptions>)> {
+        vec![(
+            doc! { "recipient": 1, "metadata.created_at": -1 },
+            Some(
+                IndexOptions::builder()
+                    .name("recipient_created_index".to_string())
+                    .build(),
+            ),
+        )]
+    }
+}
+
+impl MutMetadata for NotificationDoc {
+    fn mut_metadata(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+}
@@ -0,0 +1,122 @@
+//! Operator Schema
+//!
+//! One document per tenant provisioned through
+//! [operator onboarding](crate::services::operator_onboarding): the names
+//! reserved for it and the outcome of each provisioning step, so a partly
+//! provisioned operator can be finished by provisioning it again.
+
+use bson::{doc, oid::ObjectId, Document};
+use mongodb::options::IndexOptions;
+use serde::{Deserialize, Serialize};
+
+use super::metadata::Metadata;
+use crate::db::mongo::{IntoIndexes, MutMetadata};
+
+/// Collection name for operators
+pub const OPERATOR_COLLECTION: &str = "operators";
+
+/// Overall provisioning state
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OperatorStatus {
+    /// Some step failed; provisioning again retries it
+    #[default]
+    Partial,
+    /// Every requested step succeeded
+    Ready,
+}
+
+/// Outcome of one provisioning step
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    #[default]
+    Done,
+    /// Not requested, or the service it needs isn't configured here
+    Skipped,
+    Failed,
+}
+
+/// One provisioning step as last run
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct OperatorStep {
+    /// `record`, `api_keys`, `cache_namespace`, `nats`, `collections` or `happ`
+    pub step: String,
+    pub status: StepStatus,
+    /// What was created, or why the step was skipped or failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// Operator (tenant) document
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct OperatorDoc {
+    /// MongoDB document ID
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    /// Standard metadata (created_at, updated_at, is_deleted)
+    #[serde(default)]
+    pub metadata: Metadata,
+
+    /// Slug naming everything provisioned for the operator
+    #[serde(default)]
+    pub operator_id: String,
+
+    #[serde(default)]
+    pub name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contact_email: Option<String>,
+
+    #[serde(default)]
+    pub status: OperatorStatus,
+
+    /// Response cache key prefix
+    #[serde(default)]
+    pub cache_namespace: String,
+
+    /// JetStream stream holding the operator's subjects
+    #[serde(default)]
+    pub nats_stream: String,
+
+    #[serde(default)]
+    pub nats_subjects: Vec<String>,
+
+    /// Operator-scoped MongoDB collections
+    #[serde(default)]
+    pub collections: Vec<String>,
+
+    /// Installed hApp, when one was requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub installed_app_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conductor_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent_pub_key: Option<String>,
+
+    #[serde(default)]
+    pub steps: Vec<OperatorStep>,
+}
+
+impl IntoIndexes for OperatorDoc {
+    fn into_indices() -> Vec<(Document, Option<IndexOptions>)> {
+        vec![(
+            doc! { "operator_id": 1 },
+            Some(
+                IndexOptions::builder()
+                    .unique(true)
+                    .name("operator_id_unique".to_string())
+                    .build(),
+            ),
+        )]
+    }
+}
+
+impl MutMetadata for OperatorDoc {
+    fn mut_metadata(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+}
@@ -0,0 +1,223 @@
+//! Passkey Schemas
+//!
+//! WebAuthn credentials registered by users, one document per device, and
+//! the short-lived challenges of registration and login ceremonies. A
+//! challenge is deleted when it is answered; unanswered ones expire
+//! through a TTL index.
+
+use bson::{doc, oid::ObjectId, DateTime, Document};
+use mongodb::options::IndexOptions;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use super::metadata::Metadata;
+use crate::db::mongo::{IntoIndexes, MutMetadata};
+
+/// Collection name for registered passkeys
+pub const PASSKEY_COLLECTION: &str = "passkeys";
+
+/// Collection name for pending WebAuthn challenges
+pub const WEBAUTHN_CHALLENGE_COLLECTION: &str = "webauthn_challenges";
+
+/// A passkey registered by a user on one device
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct PasskeyDoc {
+    /// MongoDB document ID
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    /// Standard metadata (created_at, updated_at, is_deleted)
+    #[serde(default)]
+    pub metadata: Metadata,
+
+    /// Credential ID chosen by the authenticator (base64url)
+    #[serde(default)]
+    pub credential_id: String,
+
+    /// User identifier the passkey signs in as
+    #[serde(default)]
+    pub identifier: String,
+
+    /// Holochain human ID of the user
+    #[serde(default)]
+    pub human_id: String,
+
+    /// COSE public key (base64url)
+    #[serde(default)]
+    pub public_key: String,
+
+    /// Signature counter last reported by the authenticator
+    #[serde(default)]
+    pub sign_count: u32,
+
+    /// Authenticator model (hex AAGUID; all zeros when not disclosed)
+    #[serde(default)]
+    pub aaguid: String,
+
+    /// Name the user gave the device
+    #[serde(default)]
+    pub device_name: String,
+
+    /// Transports the browser reported (`internal`, `hybrid`, `usb`, ..)
+    #[serde(default)]
+    pub transports: Vec<String>,
+
+    /// Whether the passkey may be synced to other devices
+    #[serde(default)]
+    pub backup_eligible: bool,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_used_at: Option<DateTime>,
+}
+
+impl IntoIndexes for PasskeyDoc {
+    fn into_indices() -> Vec<(Document, Option<IndexOptions>)> {
+        vec![
+            (
+                doc! { "credential_id": 1 },
+                Some(
+                    IndexOptions::builder()
+                        .unique(true)
+                        .name("credential_id_unique".to_string())
+                        .build(),
+                ),
+            ),
+            // A user's passkeys, for management and login hints
+            (
+                doc! { "identifier": 1 },
+                Some(
+                    IndexOptions::builder()
+                        .name("identifier_index".to_string())
+                        .build(),
+                ),
+            ),
+        ]
+    }
+}
+
+impl MutMetadata for PasskeyDoc {
+    fn mut_metadata(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+}
+
+/// Which ceremony a challenge was issued for
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WebAuthnCeremony {
+    /// Adding a passkey to a signed-in user
+    #[default]
+    Registration,
+    /// Signing in with a passkey
+    Authentication,
+    /// Proving control of an account to link it into another
+    Linking,
+}
+
+impl WebAuthnCeremony {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Registration => "registration",
+            Self::Authentication => "authentication",
+            Self::Linking => "linking",
+        }
+    }
+}
+
+/// A challenge waiting for its ceremony to complete
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct WebAuthnChallengeDoc {
+    /// MongoDB document ID
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    /// Standard metadata (created_at, updated_at, is_deleted)
+    #[serde(default)]
+    pub metadata: Metadata,
+
+    /// Random challenge (base64url), as echoed in the client data
+    #[serde(default)]
+    pub challenge: String,
+
+    #[serde(default)]
+    pub ceremony: WebAuthnCeremony,
+
+    /// User the challenge was issued to; unset for a login that lets the
+    /// authenticator pick the account
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identifier: Option<String>,
+
+    #[serde(default = "DateTime::now")]
+    pub expires_at: DateTime,
+}
+
+impl WebAuthnChallengeDoc {
+    /// Create a challenge that expires after `ttl`.
+    pub fn new(
+        challenge: String,
+        ceremony: WebAuthnCeremony,
+        identifier: Option<String>,
+        ttl: Duration,
+    ) -> Self {
+        Self {
+            id: None,
+            metadata: Metadata::new(),
+            challenge,
+            ceremony,
+            identifier,
+            expires_at: DateTime::from_millis(
+                DateTime::now().timestamp_millis() + ttl.as_millis() as i64,
+            ),
+        }
+    }
+
+    /// Check if the challenge may still be answered.
+    ///
+    /// The TTL monitor only runs about once a minute.
+    pub fn is_valid(&self) -> bool {
+        DateTime::now() < self.expires_at
+    }
+}
+
+impl Default for WebAuthnChallengeDoc {
+    fn default() -> Self {
+        Self::new(
+            String::new(),
+            WebAuthnCeremony::default(),
+            None,
+            Duration::ZERO,
+        )
+    }
+}
+
+impl IntoIndexes for WebAuthnChallengeDoc {
+    fn into_indices() -> Vec<(Document, Option<IndexOptions>)> {
+        vec![
+            (
+                doc! { "challenge": 1 },
+                Some(
+                    IndexOptions::builder()
+                        .unique(true)
+                        .name("challenge_unique".to_string())
+                        .build(),
+                ),
+            ),
+            // TTL index for automatic expiration cleanup
+            (
+                doc! { "expires_at": 1 },
+                Some(
+                    IndexOptions::builder()
+                        .expire_after(Duration::from_secs(0))
+                        .name("expires_at_ttl".to_string())
+                        .build(),
+                ),
+            ),
+        ]
+    }
+}
+
+impl MutMetadata for WebAuthnChallengeDoc {
+    fn mut_metadata(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+}
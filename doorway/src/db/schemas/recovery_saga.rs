@@ -0,0 +1,222 @@
+//! Recovery Saga Schema
+//!
+//! Tracks doorway-coordinated emergency recovery of a custodian commitment.
+//! One document per commitment; each step is persisted before the next runs
+//! so an interrupted recovery resumes where it left off.
+
+use bson::{doc, oid::ObjectId, Document};
+use chrono::{DateTime, Utc};
+use mongodb::options::IndexOptions;
+use serde::{Deserialize, Serialize};
+
+use super::metadata::Metadata;
+use crate::db::mongo::{IntoIndexes, MutMetadata};
+
+/// Collection name for recovery sagas
+pub const RECOVERY_SAGA_COLLECTION: &str = "recovery_sagas";
+
+/// Step of the emergency recovery protocol
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RecoveryStep {
+    /// Checking which emergency trigger applies and firing it
+    #[default]
+    ValidatingTrigger,
+    /// Waiting for M-of-N custodians to approve
+    AwaitingConsensus,
+    /// Waiting for enough custodian shards of every content item
+    GatheringShards,
+    /// Reassembling content from shards
+    Reconstructing,
+    /// All content reconstructed
+    Completed,
+    /// Recovery cannot proceed (see `last_error`)
+    Failed,
+}
+
+impl RecoveryStep {
+    /// Whether the saga has finished, successfully or not
+    pub fn is_terminal(self) -> bool {
+        matches!(self, RecoveryStep::Completed | RecoveryStep::Failed)
+    }
+}
+
+/// Per-content progress through shard gathering and reconstruction
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct RecoveryContentProgress {
+    pub content_id: String,
+
+    #[serde(default)]
+    pub shards_available: u32,
+
+    #[serde(default)]
+    pub shards_required: u32,
+
+    /// Custodians known to hold shards of this content
+    #[serde(default)]
+    pub custodian_agent_ids: Vec<String>,
+
+    /// verified|unverified|partial once reconstruction was attempted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verification_status: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Recovery saga document
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct RecoverySagaDoc {
+    /// MongoDB document ID
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    /// Standard metadata (created_at, updated_at, is_deleted)
+    #[serde(default)]
+    pub metadata: Metadata,
+
+    /// Custodian commitment being recovered
+    #[serde(default)]
+    pub commitment_id: String,
+
+    /// Human who started the recovery
+    #[serde(default)]
+    pub requested_by: String,
+
+    /// Reason given for the emergency
+    #[serde(default)]
+    pub reason: String,
+
+    #[serde(default)]
+    pub step: RecoveryStep,
+
+    /// Trigger that activated (or is activating) the commitment
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trigger_type: Option<String>,
+
+    #[serde(default)]
+    pub content: Vec<RecoveryContentProgress>,
+
+    /// Latest M-of-N tally as returned by check_consensus_status
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub consensus_json: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+
+    /// Number of times the saga has been driven
+    #[serde(default)]
+    pub attempts: u32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl RecoverySagaDoc {
+    /// Start a new saga at the trigger validation step
+    pub fn new(commitment_id: String, requested_by: String, reason: String, content_ids: Vec<String>) -> Self {
+        Self {
+            id: None,
+            metadata: Metadata::new(),
+            commitment_id,
+            requested_by,
+            reason,
+            step: RecoveryStep::ValidatingTrigger,
+            trigger_type: None,
+            content: content_ids
+                .into_iter()
+                .map(|content_id| RecoveryContentProgress {
+                    content_id,
+                    ..Default::default()
+                })
+                .collect(),
+            consensus_json: None,
+            last_error: None,
+            attempts: 0,
+            completed_at: None,
+        }
+    }
+
+    /// Move to a step, clearing any error from the previous one
+    pub fn advance(&mut self, step: RecoveryStep) {
+        self.step = step;
+        self.last_error = None;
+        if step == RecoveryStep::Completed {
+            self.completed_at = Some(Utc::now());
+        }
+    }
+
+    /// Stop the saga with an error
+    pub fn fail(&mut self, error: impl Into<String>) {
+        self.step = RecoveryStep::Failed;
+        self.last_error = Some(error.into());
+    }
+}
+
+impl IntoIndexes for RecoverySagaDoc {
+    fn into_indices() -> Vec<(Document, Option<IndexOptions>)> {
+        vec![
+            // One saga per commitment
+            (
+                doc! { "commitment_id": 1 },
+                Some(
+                    IndexOptions::builder()
+                        .unique(true)
+                        .name("commitment_id_unique".to_string())
+                        .build(),
+                ),
+            ),
+            // Lookup of a human's recoveries
+            (
+                doc! { "requested_by": 1 },
+                Some(
+                    IndexOptions::builder()
+                        .name("requested_by_index".to_string())
+                        .build(),
+                ),
+            ),
+        ]
+    }
+}
+
+impl MutMetadata for RecoverySagaDoc {
+    fn mut_metadata(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_saga_starts_at_trigger_validation() {
+        let saga = RecoverySagaDoc::new(
+            "commitment-1".to_string(),
+            "human-1".to_string(),
+            "Passed away".to_string(),
+            vec!["a".to_string(), "b".to_string()],
+        );
+        assert_eq!(saga.step, RecoveryStep::ValidatingTrigger);
+        assert_eq!(saga.content.len(), 2);
+        assert!(!saga.step.is_terminal());
+    }
+
+    #[test]
+    fn test_step_serializes_snake_case() {
+        let json = serde_json::to_string(&RecoveryStep::AwaitingConsensus).unwrap();
+        assert_eq!(json, "\"awaiting_consensus\"");
+    }
+
+    #[test]
+    fn test_fail_and_advance() {
+        let mut saga = RecoverySagaDoc::default();
+        saga.fail("boom");
+        assert!(saga.step.is_terminal());
+        assert_eq!(saga.last_error.as_deref(), Some("boom"));
+
+        saga.advance(RecoveryStep::Completed);
+        assert!(saga.last_error.is_none());
+        assert!(saga.completed_at.is_some());
+    }
+}
@@ -0,0 +1,159 @@
+//! Relationship Suggestion Schema
+//!
+//! Relationships proposed by the [embedding worker](crate::worker::embeddings)
+//! for content that reads alike. Suggestions wait for a steward; approving
+//! one creates a `RELATES_TO` relationship with `inference_source: "semantic"`
+//! in the content DNA.
+
+use bson::{doc, oid::ObjectId, Document};
+use chrono::{DateTime, Utc};
+use mongodb::options::IndexOptions;
+use serde::{Deserialize, Serialize};
+
+use super::metadata::Metadata;
+use crate::db::mongo::{IntoIndexes, MutMetadata};
+
+/// Collection name for relationship suggestions
+pub const RELATIONSHIP_SUGGESTION_COLLECTION: &str = "relationship_suggestions";
+
+/// Review state of a suggestion
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SuggestionStatus {
+    #[default]
+    Pending,
+    Approved,
+    Rejected,
+}
+
+impl SuggestionStatus {
+    /// Parse the snake_case name used in queries
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(Self::Pending),
+            "approved" => Some(Self::Approved),
+            "rejected" => Some(Self::Rejected),
+            _ => None,
+        }
+    }
+}
+
+/// Relationship suggestion document
+///
+/// The pair is stored with `source_id < target_id` so each pair is
+/// suggested once, whichever side was embedded first.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct RelationshipSuggestionDoc {
+    /// MongoDB document ID
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    /// Standard metadata (created_at, updated_at, is_deleted)
+    #[serde(default)]
+    pub metadata: Metadata,
+
+    #[serde(default)]
+    pub source_id: String,
+
+    #[serde(default)]
+    pub source_title: String,
+
+    #[serde(default)]
+    pub target_id: String,
+
+    #[serde(default)]
+    pub target_title: String,
+
+    /// Cosine similarity of the two embeddings (becomes the confidence)
+    #[serde(default)]
+    pub similarity: f64,
+
+    #[serde(default)]
+    pub model: String,
+
+    #[serde(default)]
+    pub status: SuggestionStatus,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reviewed_by: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reviewed_at: Option<DateTime<Utc>>,
+}
+
+impl RelationshipSuggestionDoc {
+    /// Order a pair the way it is stored
+    pub fn ordered_pair<'a>(a: &'a str, b: &'a str) -> (&'a str, &'a str) {
+        if a <= b {
+            (a, b)
+        } else {
+            (b, a)
+        }
+    }
+}
+
+impl IntoIndexes for RelationshipSuggestionDoc {
+    fn into_indices() -> Vec<(Document, Option<IndexOptions>)> {
+        vec![
+            // One suggestion per pair, kept after review so rejected pairs
+            // aren't suggested again
+            (
+                doc! { "source_id": 1, "target_id": 1 },
+                Some(
+                    IndexOptions::builder()
+                        .unique(true)
+                        .name("pair_unique".to_string())
+                        .build(),
+                ),
+            ),
+            // Review queue
+            (
+                doc! { "status": 1, "similarity": -1 },
+                Some(
+                    IndexOptions::builder()
+                        .name("status_similarity_index".to_string())
+                        .build(),
+                ),
+            ),
+        ]
+    }
+}
+
+impl MutMetadata for RelationshipSuggestionDoc {
+    fn mut_metadata(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_round_trip() {
+        for status in [
+            SuggestionStatus::Pending,
+            SuggestionStatus::Approved,
+            SuggestionStatus::Rejected,
+        ] {
+            let json = serde_json::to_string(&status).unwrap();
+            assert_eq!(
+                SuggestionStatus::parse(json.trim_matches('"')),
+                Some(status)
+            );
+        }
+        assert_eq!(SuggestionStatus::parse("maybe"), None);
+    }
+
+    #[test]
+    fn test_ordered_pair() {
+        assert_eq!(
+            RelationshipSuggestionDoc::ordered_pair("b", "a"),
+            ("a", "b")
+        );
+        assert_eq!(
+            RelationshipSuggestionDoc::ordered_pair("a", "b"),
+            ("a", "b")
+        );
+    }
+}
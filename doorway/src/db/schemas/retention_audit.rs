@@ -0,0 +1,85 @@
+//! Retention Audit Schema
+//!
+//! One document per run of a [retention policy](crate::worker::retention),
+//! scheduled or requested by an admin, dry runs included. Operators can show
+//! what each policy archived, tombstoned or expired, and when.
+
+use bson::{doc, oid::ObjectId, Document};
+use chrono::{DateTime, Utc};
+use mongodb::options::IndexOptions;
+use serde::{Deserialize, Serialize};
+
+use super::metadata::Metadata;
+use crate::db::mongo::{IntoIndexes, MutMetadata};
+
+/// Collection name for retention audit records
+pub const RETENTION_AUDIT_COLLECTION: &str = "retention_audit";
+
+/// Retention run audit document
+///
+/// `metadata.created_at` is when the run started.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct RetentionAuditDoc {
+    /// MongoDB document ID
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    /// Standard metadata (created_at, updated_at, is_deleted)
+    #[serde(default)]
+    pub metadata: Metadata,
+
+    #[serde(default)]
+    pub policy_id: String,
+
+    /// Policy action (archive_stale_content, purge_erased_users, ...)
+    #[serde(default)]
+    pub action: String,
+
+    /// Whether the run only previewed its matches
+    #[serde(default)]
+    pub dry_run: bool,
+
+    /// Documents older than this were in scope
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cutoff: Option<DateTime<Utc>>,
+
+    /// Documents matching the policy
+    #[serde(default)]
+    pub matched: u64,
+
+    /// Documents archived, tombstoned or deleted (0 for dry runs)
+    #[serde(default)]
+    pub affected: u64,
+
+    /// Some of the matched document ids
+    #[serde(default)]
+    pub sample_ids: Vec<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl IntoIndexes for RetentionAuditDoc {
+    fn into_indices() -> Vec<(Document, Option<IndexOptions>)> {
+        vec![
+            // Runs of one policy, newest first
+            (
+                doc! { "policy_id": 1, "metadata.created_at": -1 },
+                Some(
+                    IndexOptions::builder()
+                        .name("policy_runs_index".to_string())
+                        .build(),
+                ),
+            ),
+        ]
+    }
+}
+
+impl MutMetadata for RetentionAuditDoc {
+    fn mut_metadata(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+}
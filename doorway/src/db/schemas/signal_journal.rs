@@ -0,0 +1,105 @@
+//! Signal Journal Schema
+//!
+//! Every conductor signal the subscriber receives, in arrival order. The
+//! [journal](crate::worker::signal_journal) lets projections and the response
+//! cache be rebuilt after a bug by replaying signals instead of re-crawling
+//! the DHT. The collection is capped, so the oldest signals age out.
+
+use bson::{doc, oid::ObjectId, Document};
+use mongodb::options::IndexOptions;
+use serde::{Deserialize, Serialize};
+
+use super::metadata::Metadata;
+use crate::db::mongo::{IntoIndexes, MutMetadata};
+
+/// Collection name for the signal journal
+pub const SIGNAL_JOURNAL_COLLECTION: &str = "signal_journal";
+
+/// Which subscriber stream a signal came from
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JournaledSignalKind {
+    /// A ProjectionSignal for the projection engine
+    #[default]
+    Projection,
+    /// A zome write reported for cache invalidation
+    CacheInvalidation,
+}
+
+impl JournaledSignalKind {
+    /// Parse the snake_case name used in requests
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "projection" => Some(Self::Projection),
+            "cache_invalidation" => Some(Self::CacheInvalidation),
+            _ => None,
+        }
+    }
+}
+
+/// Journaled signal document
+///
+/// `metadata.created_at` is when the signal was received.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct SignalJournalDoc {
+    /// MongoDB document ID
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    /// Standard metadata (created_at, updated_at, is_deleted)
+    #[serde(default)]
+    pub metadata: Metadata,
+
+    /// Position in the journal, increasing with arrival
+    #[serde(default)]
+    pub offset: i64,
+
+    #[serde(default)]
+    pub kind: JournaledSignalKind,
+
+    /// Document type the signal is about
+    #[serde(default)]
+    pub doc_type: String,
+
+    /// Document id, or "*" for bulk writes
+    #[serde(default)]
+    pub doc_id: String,
+
+    /// Zome function that performed the write (cache invalidations)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_fn: Option<String>,
+
+    /// The full ProjectionSignal as JSON (projection signals)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload_json: Option<String>,
+}
+
+impl IntoIndexes for SignalJournalDoc {
+    fn into_indices() -> Vec<(Document, Option<IndexOptions>)> {
+        vec![
+            (
+                doc! { "offset": 1 },
+                Some(
+                    IndexOptions::builder()
+                        .name("offset_index".to_string())
+                        .unique(true)
+                        .build(),
+                ),
+            ),
+            (
+                doc! { "metadata.created_at": 1 },
+                Some(
+                    IndexOptions::builder()
+                        .name("received_at_index".to_string())
+                        .build(),
+                ),
+            ),
+        ]
+    }
+}
+
+impl MutMetadata for SignalJournalDoc {
+    fn mut_metadata(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+}
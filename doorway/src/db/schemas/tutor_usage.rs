@@ -0,0 +1,72 @@
+//! Tutor Usage Schema
+//!
+//! Monthly token spend of the [conversational tutor](crate::services::tutor),
+//! one document per operator API key and calendar month. The key itself is
+//! never stored, only a fingerprint of it.
+
+use bson::{doc, oid::ObjectId, Document};
+use mongodb::options::IndexOptions;
+use serde::{Deserialize, Serialize};
+
+use super::metadata::Metadata;
+use crate::db::mongo::{IntoIndexes, MutMetadata};
+
+/// Collection name for tutor usage
+pub const TUTOR_USAGE_COLLECTION: &str = "tutor_usage";
+
+/// Token usage for one operator key in one month
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct TutorUsageDoc {
+    /// MongoDB document ID
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+
+    /// Standard metadata (created_at, updated_at, is_deleted)
+    #[serde(default)]
+    pub metadata: Metadata,
+
+    /// Fingerprint of the provider API key the spend is billed to
+    #[serde(default)]
+    pub operator_key: String,
+
+    /// Budget period as `YYYY-MM` (UTC)
+    #[serde(default)]
+    pub period: String,
+
+    #[serde(default)]
+    pub prompt_tokens: u64,
+
+    #[serde(default)]
+    pub completion_tokens: u64,
+
+    /// Chats proxied
+    #[serde(default)]
+    pub requests: u64,
+}
+
+impl TutorUsageDoc {
+    /// Tokens counted against the budget
+    pub fn total_tokens(&self) -> u64 {
+        self.prompt_tokens.saturating_add(self.completion_tokens)
+    }
+}
+
+impl IntoIndexes for TutorUsageDoc {
+    fn into_indices() -> Vec<(Document, Option<IndexOptions>)> {
+        vec![(
+            doc! { "operator_key": 1, "period": 1 },
+            Some(
+                IndexOptions::builder()
+                    .unique(true)
+                    .name("operator_period_unique".to_string())
+                    .build(),
+            ),
+        )]
+    }
+}
+
+impl MutMetadata for TutorUsageDoc {
+    fn mut_metadata(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+}
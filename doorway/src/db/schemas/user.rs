@@ -199,6 +199,15 @@ pub struct UserDoc {
     /// None for legacy users or dev mode registrations.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub conductor_id: Option<String>,
+
+    /// Identifier of the account this one was linked into.
+    /// Logging in here signs in as that account instead.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub linked_to: Option<String>,
+
+    /// Human id of the member whose invite code this account signed up with.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invited_by: Option<String>,
 }
 
 fn default_identifier_type() -> String {
@@ -243,6 +252,8 @@ impl UserDoc {
             is_steward: false,
             stewardship_at: None,
             conductor_id: None,
+            linked_to: None,
+            invited_by: None,
         }
     }
 
@@ -8,23 +8,24 @@ use tracing::{error, info, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use doorway::{
+    cache::{spawn_invalidation_task, DefaultCachePolicy},
     conductor::{
         admin_client::AdminClient, ConductorInfo, ConductorPoolMap, ConductorRegistry,
         ConductorRouter,
     },
-    config::Args,
-    db::MongoClient,
+    config::{Args, CacheAction, CacheArgs, Command},
+    db::{MongoClient, MongoOptions},
     nats::NatsClient,
     orchestrator::{Orchestrator, OrchestratorConfig, OrchestratorState},
     projection::{
         spawn_engine_task, spawn_subscriber, EngineConfig, ProjectionEngine, SubscriberConfig,
     },
-    server,
+    proxy, server,
     services::{
         self, register_local_storage, spawn_discovery_task, DiscoveryConfig,
         StorageRegistrationConfig,
     },
-    worker::{PoolConfig, WorkerPool},
+    worker::{self, PoolConfig, WorkerPool},
 };
 
 #[tokio::main]
@@ -45,6 +46,47 @@ async fn main() -> anyhow::Result<()> {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
+    // One-off commands run instead of the gateway (no JWT/NATS/MongoDB needed)
+    if let Some(Command::ExportSite(ref export)) = args.command {
+        let admin_url = args.admin_url().to_string();
+        let app_url = derive_app_url(&args.conductor_url, args.app_port_min);
+        let zome_caller = services::ZomeCaller::new(&admin_url, &app_url, &args.installed_app_id);
+        let exporter = services::site_export::SiteExporter::new(
+            &zome_caller,
+            services::site_export::SiteExportConfig {
+                out_dir: export.out.clone(),
+                include_media: export.include_media,
+                storage_url: args.storage_url.clone(),
+            },
+        );
+        match exporter.export().await {
+            Ok(summary) => {
+                info!(
+                    out = %export.out.display(),
+                    paths = summary.paths,
+                    content = summary.content,
+                    media = summary.media,
+                    media_downloaded = summary.media_downloaded,
+                    media_failed = summary.media_failed,
+                    withheld_by_license = summary.withheld_by_license,
+                    "Static site exported"
+                );
+                return Ok(());
+            }
+            Err(e) => {
+                error!("Site export failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+    if let Some(Command::Cache(ref cache_args)) = args.command {
+        if let Err(e) = run_cache_command(cache_args).await {
+            error!("Cache command failed: {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     // Validate configuration
     if let Err(e) = args.validate() {
         error!("Configuration error: {}", e);
@@ -84,7 +126,16 @@ async fn main() -> anyhow::Result<()> {
     info!("======================================");
 
     // Connect to MongoDB (optional in dev mode)
-    let mongo = match MongoClient::new(&args.mongodb_uri, &args.mongodb_db).await {
+    let mongo_options = match MongoOptions::from_args(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            error!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    let mongo = match MongoClient::with_options(&args.mongodb_uri, &args.mongodb_db, mongo_options)
+        .await
+    {
         Ok(client) => {
             info!("MongoDB connected successfully");
             Some(client)
@@ -130,6 +181,9 @@ async fn main() -> anyhow::Result<()> {
     // The browser app needs admin commands (generate_agent_pub_key, list_apps, etc.)
     // which MUST go to the admin interface, not the app interface.
 
+    // Cacheable reads give way to writes while the conductor is under pressure
+    let admission = proxy::admission::AdmissionPolicy::from_args(&args).map(Arc::new);
+
     // APP pool - for zome calls
     let worker_app_url = derive_app_url(&args.conductor_url, args.app_port_min);
     let app_pool = match WorkerPool::new(PoolConfig {
@@ -145,7 +199,7 @@ async fn main() -> anyhow::Result<()> {
                 "App worker pool started with {} workers (app interface: {})",
                 args.worker_count, worker_app_url
             );
-            Some(Arc::new(p))
+            Some(Arc::new(p.with_admission(admission.clone())))
         }
         Err(e) => {
             if args.dev_mode {
@@ -215,6 +269,7 @@ async fn main() -> anyhow::Result<()> {
         server::AppState::with_services(args.clone(), mongo, nats)
     };
     state.orchestrator = orchestrator_state.clone();
+    state.admission = admission;
 
     // Create single-connection ImportClient for import operations
     // Uses ONE connection to app interface to avoid overwhelming conductor during batch imports
@@ -335,6 +390,424 @@ async fn main() -> anyhow::Result<()> {
         );
     }
 
+    // Recommendation engine: ranks "what to learn next" per agent
+    if args.recommendation_refresh_secs > 0 {
+        if let Some(zome_caller) = state.zome_caller.clone() {
+            let engine = worker::recommendations::RecommendationEngine::new(
+                zome_caller,
+                state.projection.clone(),
+                std::time::Duration::from_secs(args.recommendation_refresh_secs),
+            );
+            state.recommendations = Some(Arc::new(engine));
+        }
+    }
+
+    // Torrent metadata: let learners share large blobs, web-seeded from here
+    if args.torrent_min_size_mb > 0 {
+        if let (Some(storage_url), Some(doorway_url)) =
+            (args.storage_url.clone(), args.doorway_url.clone())
+        {
+            let generator =
+                worker::torrent::TorrentGenerator::new(worker::torrent::TorrentConfig {
+                    storage_url,
+                    doorway_url,
+                    min_size_bytes: args.torrent_min_size_mb * 1024 * 1024,
+                });
+            state.torrents = Some(Arc::new(generator));
+            info!(
+                "Torrent metadata enabled for blobs >= {} MB",
+                args.torrent_min_size_mb
+            );
+        }
+    }
+
+    // Sitemaps: index commons content and public paths for search engines
+    if args.sitemap_interval_secs > 0 {
+        if let (Some(projection), Some(doorway_url)) =
+            (state.projection.clone(), args.doorway_url.clone())
+        {
+            state.sitemaps = Some(Arc::new(worker::sitemap::SitemapGenerator::new(
+                doorway_url,
+                projection,
+            )));
+        }
+    }
+
+    // Open Badges export: learners' attestations signed as this doorway's did:web
+    if let Some(ref seed) = args.badge_issuer_key {
+        match worker::badge_export::BadgeIssuer::parse_key(seed) {
+            Ok(signing_key) => {
+                if let (Some(zome_caller), Some(doorway_url)) =
+                    (state.zome_caller.clone(), args.doorway_url.clone())
+                {
+                    let issuer = worker::badge_export::BadgeIssuer::new(
+                        doorway::routes::identity::derive_doorway_did(&state),
+                        doorway::routes::verify::ISSUER_NAME.to_string(),
+                        doorway_url,
+                        signing_key,
+                    );
+                    state.badge_exports = Some(Arc::new(
+                        worker::badge_export::BadgeExporter::new(issuer, zome_caller),
+                    ));
+                    info!("Open Badges export enabled");
+                }
+            }
+            Err(e) => warn!("{}; Open Badges export disabled", e),
+        }
+    }
+
+    // Printable path PDFs via a headless renderer
+    if let (Some(url), Some(zome_caller)) =
+        (args.path_renderer_url.clone(), state.zome_caller.clone())
+    {
+        state.path_renderer = Some(Arc::new(worker::path_render::PathRenderer::new(
+            worker::path_render::PathRenderConfig {
+                url,
+                cache_size: args.path_render_cache_size,
+                doorway_url: args.doorway_url.clone(),
+            },
+            zome_caller,
+        )));
+        info!("Path PDF export enabled");
+    }
+
+    // Import duplicate check against projected content
+    if args.import_duplicate_threshold > 0.0 {
+        if let Some(projection) = state.projection.clone() {
+            state.duplicate_detector = Some(Arc::new(
+                services::duplicate_detection::DuplicateDetector::new(
+                    projection,
+                    args.import_duplicate_threshold.min(1.0),
+                ),
+            ));
+        }
+    }
+
+    // Machine translation: draft missing translations for steward review
+    if let Some(ref name) = args.machine_translation_provider {
+        match worker::machine_translation::Provider::parse(name) {
+            Some(provider) => {
+                let url = args.machine_translation_url.clone().or_else(|| {
+                    provider.default_url(args.machine_translation_api_key.as_deref())
+                });
+                match url {
+                    Some(url) => {
+                        state.machine_translation = Some(Arc::new(
+                            worker::machine_translation::MachineTranslator::new(
+                                worker::machine_translation::MachineTranslationConfig {
+                                    provider,
+                                    url,
+                                    api_key: args.machine_translation_api_key.clone(),
+                                    source_locale: args.machine_translation_source_locale.clone(),
+                                    target_locales: args.machine_translation_locales.clone(),
+                                },
+                            ),
+                        ));
+                    }
+                    None => warn!(
+                        "MACHINE_TRANSLATION_URL is required for {}; machine translation disabled",
+                        provider.name()
+                    ),
+                }
+            }
+            None => warn!(
+                "Unknown machine translation provider '{}'; machine translation disabled",
+                name
+            ),
+        }
+    }
+
+    // Embeddings: semantic related-content and relationship suggestions
+    let mut embedder = None;
+    if let Some(ref name) = args.embedding_provider {
+        match (worker::embeddings::Provider::parse(name), state.mongo.clone()) {
+            (Some(provider), Some(mongo)) => {
+                let config = worker::embeddings::EmbeddingConfig {
+                    provider,
+                    url: args
+                        .embedding_url
+                        .clone()
+                        .unwrap_or_else(|| provider.default_url().to_string()),
+                    api_key: args.embedding_api_key.clone(),
+                    model: args
+                        .embedding_model
+                        .clone()
+                        .unwrap_or_else(|| provider.default_model().to_string()),
+                    suggest_threshold: args.embedding_suggest_threshold,
+                };
+                state.semantic = Some(Arc::new(worker::embeddings::SemanticIndex::new(
+                    mongo,
+                    config.model.clone(),
+                )));
+                embedder = Some(worker::embeddings::Embedder::new(config));
+            }
+            (Some(_), None) => {
+                warn!("Embeddings need MongoDB; semantic related-content disabled")
+            }
+            (None, _) => warn!(
+                "Unknown embedding provider '{}'; semantic related-content disabled",
+                name
+            ),
+        }
+    }
+
+    // Operator values for governed settings; approved governance states override them
+    state.governance.configure(
+        worker::governance::TUTOR_MONTHLY_TOKENS,
+        args.tutor_monthly_token_budget as f64,
+    );
+    if let Some(threshold) = args.moderation_score_threshold {
+        state
+            .governance
+            .configure(worker::governance::MODERATION_SCORE_THRESHOLD, threshold);
+    }
+
+    // Conversational tutor (budget kept in MongoDB when available)
+    if let Some(ref url) = args.tutor_url {
+        state.tutor = Some(Arc::new(services::tutor::TutorService::new(
+            services::tutor::TutorConfig {
+                url: url.clone(),
+                api_key: args.tutor_api_key.clone(),
+                model: args.tutor_model.clone(),
+                monthly_token_budget: args.tutor_monthly_token_budget,
+            },
+            state.mongo.clone(),
+            Arc::clone(&state.governance),
+        )));
+        info!(
+            "Tutor enabled: {} using {} (monthly budget {} tokens)",
+            url, args.tutor_model, args.tutor_monthly_token_budget
+        );
+    }
+
+    // Moderation: word list and/or moderation API screening, steward queue in MongoDB
+    let word_list = match args.moderation_word_list {
+        Some(ref path) => match std::fs::read_to_string(path) {
+            Ok(text) => services::moderation::parse_word_list(&text),
+            Err(e) => {
+                warn!("Failed to read moderation word list {}: {}", path, e);
+                Vec::new()
+            }
+        },
+        None => Vec::new(),
+    };
+    let moderation_config = services::moderation::ModerationConfig {
+        word_list,
+        api_url: args.moderation_api_url.clone(),
+        api_key: args.moderation_api_key.clone(),
+    };
+    match state.mongo.clone() {
+        Some(mongo) => {
+            if moderation_config.is_enabled() {
+                info!(
+                    "Moderation enabled: {} blocked terms, API {}",
+                    moderation_config.word_list.len(),
+                    moderation_config.api_url.as_deref().unwrap_or("not configured")
+                );
+            }
+            // The queue also takes user reports and attestation revocations
+            state.moderation = Some(Arc::new(services::moderation::ModerationService::new(
+                moderation_config,
+                mongo,
+                Arc::clone(&state.governance),
+            )));
+        }
+        None if moderation_config.is_enabled() => {
+            warn!("Moderation needs MongoDB for its review queue; moderation disabled")
+        }
+        None => {}
+    }
+
+    // Reciprocal federation with other communities' doorways
+    match services::reciprocal_federation::ReciprocalFederation::from_args(&args) {
+        Ok(Some(reciprocal)) => {
+            info!(
+                "Reciprocal federation enabled: {} peer(s), {} shared function(s)",
+                reciprocal.peers().count(),
+                reciprocal.exported_fns().len()
+            );
+            state.reciprocal = Some(Arc::new(reciprocal));
+        }
+        Ok(None) => {}
+        Err(e) => warn!("Reciprocal federation disabled: {}", e),
+    }
+
+    // Upstream systems allowed to sign import manifests
+    match services::import_provenance::ImportSigners::from_args(&args) {
+        Ok(Some(signers)) => {
+            info!("Signed imports enabled: {} trusted signer(s)", signers.len());
+            state.import_signers = Some(Arc::new(signers));
+        }
+        Ok(None) => {}
+        Err(e) => warn!("Signed imports disabled: {}", e),
+    }
+
+    // Signed URLs for gated and non-commons media
+    match services::media_urls::MediaUrlSigner::from_args(&args) {
+        Ok(Some(signer)) => {
+            info!(
+                "Signed media URLs enabled (valid {}s)",
+                signer.ttl().as_secs()
+            );
+            state.media_urls = Some(Arc::new(signer));
+        }
+        Ok(None) => {}
+        Err(e) => warn!("Signed media URLs disabled: {}", e),
+    }
+
+    // Partner sites allowed to embed learning paths
+    match services::embed::EmbedPolicy::from_args(&args) {
+        Ok(Some(policy)) => {
+            info!(
+                "Path embedding enabled for: {}",
+                policy.origins().collect::<Vec<_>>().join(", ")
+            );
+            state.embed = Some(Arc::new(policy));
+        }
+        Ok(None) => {}
+        Err(e) => warn!("Path embedding disabled: {}", e),
+    }
+
+    // Anonymous read-only sessions for landing pages
+    match services::guest_sessions::GuestSessions::from_args(&args) {
+        Ok(Some(guests)) => {
+            info!(
+                "Guest sessions enabled ({}s tokens, {} reads/min)",
+                guests.ttl().as_secs(),
+                args.guest_reads_per_minute
+            );
+            state.guest_sessions = Some(Arc::new(guests));
+        }
+        Ok(None) => {}
+        Err(e) => warn!("Guest sessions disabled: {}", e),
+    }
+
+    // Subscribable calendar feeds of learning sessions
+    match services::calendar::CalendarFeeds::from_args(&args) {
+        Ok(Some(feeds)) => {
+            info!("Calendar feeds enabled");
+            state.calendar_feeds = Some(Arc::new(feeds));
+        }
+        Ok(None) => {}
+        Err(e) => warn!("Calendar feeds disabled: {}", e),
+    }
+
+    // Operator-defined import validation rules
+    match services::import_validation::ImportValidator::from_args(&args) {
+        Ok(Some(validator)) => {
+            info!("Import validation rules: {}", validator.rule_names().join(", "));
+            state.import_validator = Some(Arc::new(validator));
+        }
+        Ok(None) => {}
+        Err(e) => warn!("Import validation disabled: {}", e),
+    }
+
+    // Per-caller import baselines, throttling runaway import scripts
+    if let Some(config) = services::import_abuse::ImportAbuseConfig::from_args(&args) {
+        info!(
+            "Import abuse detection enabled ({} batches/min, {}x baseline)",
+            config.max_per_minute, config.deviation
+        );
+        state.import_abuse = Some(Arc::new(services::import_abuse::ImportAbuseDetector::new(
+            config,
+            state.mongo.clone(),
+        )));
+    }
+
+    // Region awareness: presence shared with doorways in other regions
+    match services::regions::RegionDirectory::from_args(&args) {
+        Ok(Some(directory)) => {
+            info!("Region awareness enabled: {}", directory.region());
+            state.regions = Some(Arc::new(directory));
+        }
+        Ok(None) => {}
+        Err(e) => warn!("Region awareness disabled: {}", e),
+    }
+
+    // Opt-in view counts for content stewards mark `track_access`
+    if args.content_access_flush_secs > 0 {
+        if let Some(mongo) = state.mongo.clone() {
+            let log = Arc::new(services::content_access::ContentAccessLog::new(
+                mongo,
+                args.doorway_url.as_deref(),
+            ));
+            let _content_access = services::content_access::spawn_content_access_task(
+                Arc::clone(&log),
+                std::time::Duration::from_secs(args.content_access_flush_secs),
+            );
+            state.content_access = Some(log);
+        }
+    }
+
+    // Conductor signal journal for replaying projections
+    if let Some(mongo) = state.mongo.clone().filter(|_| args.signal_journal_max_bytes > 0) {
+        match worker::signal_journal::SignalJournal::open(&mongo, args.signal_journal_max_bytes)
+            .await
+        {
+            Ok(journal) => {
+                info!("Signal journal enabled ({} bytes)", args.signal_journal_max_bytes);
+                state.signal_journal = Some(Arc::new(journal));
+            }
+            Err(e) => warn!("Signal journal disabled: {}", e),
+        }
+    }
+
+    // Slow query log feeding the cache-rule tuning advisor
+    if args.slow_query_threshold_ms > 0 {
+        state.query_advisor = Some(Arc::new(worker::query_advisor::QueryAdvisor::new(
+            std::time::Duration::from_millis(args.slow_query_threshold_ms),
+        )));
+        info!("Slow query log enabled: threshold {}ms", args.slow_query_threshold_ms);
+    }
+
+    // Retention policies over projections the doorway holds
+    if let Some(ref path) = args.retention_policies {
+        let policies = std::fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| worker::retention::parse_policies(&text));
+        match (policies, state.mongo.clone(), state.projection.clone()) {
+            (Ok(policies), Some(mongo), Some(projection)) => {
+                match worker::retention::RetentionEngine::new(mongo, projection, policies).await {
+                    Ok(engine) => {
+                        info!("Retention enabled: {} policies", engine.policies().len());
+                        state.retention = Some(Arc::new(engine));
+                    }
+                    Err(e) => warn!("Retention disabled: {}", e),
+                }
+            }
+            (Err(e), _, _) => warn!("Retention disabled: {} ({})", e, path),
+            _ => warn!("Retention disabled: requires MongoDB and the projection store"),
+        }
+    }
+
+    // Operator's zome call authorization policy; refuse to start on a bad one
+    match state.zome_policy.load() {
+        Ok(0) => {}
+        Ok(rules) => info!("Zome policy loaded: {} rules", rules),
+        Err(e) => {
+            error!("Invalid zome policy: {}", e);
+            std::process::exit(1);
+        }
+    }
+
+    // Operator's default cache policy; same refusal on a bad file
+    if let Some(ref path) = args.cache_policy_file {
+        match DefaultCachePolicy::load(path) {
+            Ok(policy) => {
+                info!(
+                    "Cache policy loaded: {} denied, {} role overrides",
+                    policy.deny.len(),
+                    policy.roles.len()
+                );
+                state.cache_rules.set_policy(policy);
+            }
+            Err(e) => {
+                error!("Invalid cache policy: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
     // Set up P2P status polling from elohim-storage (if STORAGE_URL configured)
     if let Some(ref storage_url) = state.args.storage_url {
         let p2p_health = state.p2p_health.clone();
@@ -384,11 +857,22 @@ async fn main() -> anyhow::Result<()> {
                 ..DiscoveryConfig::default()
             };
 
-            let _discovery_handle = spawn_discovery_task(
+            let discovery_handle = spawn_discovery_task(
                 discovery_config,
                 Arc::clone(&state.zome_configs),
                 Arc::clone(import_config_store),
             );
+
+            // Per-role cache policy overrides need each DNA's role
+            let zome_configs = Arc::clone(&state.zome_configs);
+            let cache_rules = Arc::clone(&state.cache_rules);
+            tokio::spawn(async move {
+                if discovery_handle.await.is_ok() {
+                    for entry in zome_configs.iter() {
+                        cache_rules.set_role(entry.key(), &entry.role_name);
+                    }
+                }
+            });
             info!(
                 "Zome capability discovery started (admin: {}, import routes will be available after discovery completes)",
                 admin_url
@@ -407,6 +891,9 @@ async fn main() -> anyhow::Result<()> {
     //   projection_writer=false → reads from shared MongoDB, no subscriber (read replica mode)
     //
     // In dev mode, the signal subscriber is always disabled (app interface requires auth).
+    let mut governance_signals = None;
+    let mut advisor_signals = None;
+    let mut relay_signals = None;
     let _projection_handle = if let Some(ref projection_store) = state.projection {
         if args.dev_mode || !args.projection_writer {
             if !args.projection_writer {
@@ -415,6 +902,10 @@ async fn main() -> anyhow::Result<()> {
                 info!("Projection engine started (dev mode: signal subscriber disabled, app interface requires auth)");
             }
 
+            if args.event_export_url.is_some() {
+                warn!("EVENT_EXPORT_URL set but write signals aren't subscribed here; event export disabled");
+            }
+
             // Create engine without signals (it will still work for manual queries)
             let engine = Arc::new(ProjectionEngine::new(
                 projection_store.clone(),
@@ -453,6 +944,52 @@ async fn main() -> anyhow::Result<()> {
             let signal_rx = subscriber.subscribe();
             let engine_handle = spawn_engine_task(engine, signal_rx);
 
+            // Zome write signals evict stale entries from the response cache
+            spawn_invalidation_task(
+                subscriber.subscribe_cache_invalidations(),
+                Arc::clone(&state.cache),
+                Arc::clone(&state.cache_rules),
+            );
+
+            // Revoked attestations go to the steward moderation queue
+            if let Some(ref moderation) = state.moderation {
+                services::moderation::spawn_revocation_task(
+                    subscriber.subscribe_cache_invalidations(),
+                    Arc::clone(moderation),
+                );
+            }
+
+            // Journal every signal so projections can be replayed later
+            if let Some(ref journal) = state.signal_journal {
+                worker::signal_journal::spawn_signal_journal_task(
+                    Arc::clone(journal),
+                    subscriber.subscribe(),
+                    subscriber.subscribe_cache_invalidations(),
+                );
+            }
+
+            // Domain events go to the operator's Kafka or NATS
+            if let Some(config) = worker::event_export::EventExportConfig::from_args(&args) {
+                info!("Event export enabled: {}", config.topic);
+                worker::event_export::spawn_event_export_task(
+                    config,
+                    subscriber.subscribe_cache_invalidations(),
+                );
+            }
+
+            // Governance changes apply without waiting for the next refresh
+            governance_signals = Some(subscriber.subscribe_cache_invalidations());
+
+            // Invalidations from write signals count towards the query advisor
+            if state.query_advisor.is_some() {
+                advisor_signals = Some(subscriber.subscribe_cache_invalidations());
+            }
+
+            // Write signals evict stale reads in the other regions too
+            if state.regions.is_some() {
+                relay_signals = Some(subscriber.subscribe_cache_invalidations());
+            }
+
             info!("Projection engine started (writer mode)");
             Some((subscriber_handle, engine_handle))
         }
@@ -574,6 +1111,367 @@ async fn main() -> anyhow::Result<()> {
         }
     }
 
+    // Dead man's switch: periodically trigger custodian commitments whose
+    // beneficiary has stopped checking in
+    if args.dead_mans_switch_interval_secs > 0 {
+        if let Some(zome_caller) = state.zome_caller.clone() {
+            let _dead_mans_switch = worker::dead_mans_switch::spawn_dead_mans_switch_task(
+                std::time::Duration::from_secs(args.dead_mans_switch_interval_secs),
+                zome_caller,
+            );
+            info!(
+                "Dead man's switch sweep enabled: every {}s",
+                args.dead_mans_switch_interval_secs
+            );
+        }
+    }
+
+    // Recommendations: re-rank recently active learners before their cache expires
+    if let Some(engine) = state.recommendations.clone() {
+        let _recommendations = worker::recommendations::spawn_recommendation_task(
+            std::time::Duration::from_secs(args.recommendation_refresh_secs),
+            engine,
+        );
+        info!(
+            "Recommendation refresh enabled: every {}s",
+            args.recommendation_refresh_secs
+        );
+    }
+
+    // Analytics: roll up anonymized path aggregates for operators
+    if args.analytics_rollup_interval_secs > 0 {
+        if let (Some(zome_caller), Some(mongo)) = (state.zome_caller.clone(), state.mongo.clone())
+        {
+            let _analytics = worker::analytics::spawn_analytics_rollup_task(
+                std::time::Duration::from_secs(args.analytics_rollup_interval_secs),
+                zome_caller,
+                mongo,
+            );
+            info!(
+                "Analytics rollup enabled: every {}s",
+                args.analytics_rollup_interval_secs
+            );
+        }
+    }
+
+    // Content health: flag maintenance work for stewards
+    if args.content_health_interval_secs > 0 {
+        if let (Some(zome_caller), Some(mongo)) = (state.zome_caller.clone(), state.mongo.clone())
+        {
+            let _content_health = worker::content_health::spawn_content_health_task(
+                std::time::Duration::from_secs(args.content_health_interval_secs),
+                zome_caller,
+                mongo,
+                args.content_stale_months,
+            );
+            info!(
+                "Content health sweep enabled: every {}s",
+                args.content_health_interval_secs
+            );
+        }
+    }
+
+    // Governance: apply approved doorway settings (rate limits, moderation
+    // thresholds, recognition shares) to runtime configuration
+    if args.governance_refresh_secs > 0 {
+        if let Some(zome_caller) = state.zome_caller.clone() {
+            let _governance = worker::governance::spawn_governance_task(
+                std::time::Duration::from_secs(args.governance_refresh_secs),
+                zome_caller,
+                Arc::clone(&state.governance),
+                governance_signals,
+            );
+            info!(
+                "Governance executor enabled: every {}s",
+                args.governance_refresh_secs
+            );
+        }
+    }
+
+    // Query advisor: turn each window of zome call timings into a report
+    if let Some(advisor) = state.query_advisor.clone() {
+        let _query_advisor = worker::query_advisor::spawn_query_advisor_task(
+            advisor,
+            Arc::clone(&state.cache_rules),
+            std::time::Duration::from_secs(args.query_advisor_interval_secs.max(60)),
+            advisor_signals,
+        );
+        info!(
+            "Query advisor enabled: every {}s",
+            args.query_advisor_interval_secs
+        );
+    }
+
+    // Cache stats: persist per-rule hit/miss counters for /admin/cache/stats
+    if args.cache_stats_rollup_interval_secs > 0 {
+        if let Some(mongo) = state.mongo.clone() {
+            let _cache_stats = worker::cache_stats::spawn_cache_stats_rollup_task(
+                std::time::Duration::from_secs(args.cache_stats_rollup_interval_secs),
+                Arc::clone(&state.cache),
+                mongo,
+            );
+            info!(
+                "Cache rule rollup enabled: every {}s",
+                args.cache_stats_rollup_interval_secs
+            );
+        }
+    }
+
+    // Retention: run every policy on a timer (admins can also run them)
+    if args.retention_interval_secs > 0 {
+        if let Some(engine) = state.retention.clone() {
+            let _retention = worker::retention::spawn_retention_task(
+                engine,
+                std::time::Duration::from_secs(args.retention_interval_secs),
+            );
+            info!("Retention worker enabled: every {}s", args.retention_interval_secs);
+        }
+    }
+
+    // L1 cache: share evictions with the other doorways over NATS
+    if let (Some(l1), Some(nats)) = (
+        state.projection.as_ref().and_then(|p| p.l1().cloned()),
+        state.nats.clone(),
+    ) {
+        let _l1_sync = doorway::db::l1::spawn_l1_sync_task(l1, nats, args.node_id.to_string());
+    }
+
+    // Service matching: suggest offers that fit active Shefa requests
+    if args.service_matching_interval_secs > 0 {
+        if let Some(zome_caller) = state.zome_caller.clone() {
+            let _service_matching = worker::service_matching::spawn_service_matching_task(
+                std::time::Duration::from_secs(args.service_matching_interval_secs),
+                zome_caller,
+                state.mongo.clone(),
+            );
+            info!(
+                "Service matching enabled: every {}s",
+                args.service_matching_interval_secs
+            );
+        }
+    }
+
+    // Session reminders: notify attendees of upcoming learning sessions
+    if args.session_reminder_interval_secs > 0 {
+        if let (Some(zome_caller), Some(mongo)) = (state.zome_caller.clone(), state.mongo.clone()) {
+            let _session_reminders = worker::session_reminders::spawn_session_reminder_task(
+                std::time::Duration::from_secs(args.session_reminder_interval_secs),
+                std::time::Duration::from_secs(args.session_reminder_lead_mins * 60),
+                zome_caller,
+                mongo,
+            );
+            info!(
+                "Session reminders enabled: every {}s, {} minutes ahead",
+                args.session_reminder_interval_secs, args.session_reminder_lead_mins
+            );
+        }
+    }
+
+    // Solvency: record insurance mutual pool health for members
+    if args.solvency_snapshot_interval_secs > 0 {
+        if let Some(zome_caller) = state.zome_caller.clone() {
+            let _solvency = worker::solvency::spawn_solvency_task(
+                std::time::Duration::from_secs(args.solvency_snapshot_interval_secs),
+                zome_caller,
+            );
+            info!(
+                "Solvency snapshots enabled: every {}s",
+                args.solvency_snapshot_interval_secs
+            );
+        }
+    }
+
+    // External resources: fetch metadata and snapshots for external steps
+    if args.external_resource_interval_secs > 0 {
+        if let Some(zome_caller) = state.zome_caller.clone() {
+            let _external_resources = worker::external_resources::spawn_external_resource_task(
+                std::time::Duration::from_secs(args.external_resource_interval_secs),
+                zome_caller,
+                worker::external_resources::ExternalResourceEnricher::new(
+                    args.storage_url.clone(),
+                ),
+            );
+            info!(
+                "External resource enrichment enabled: every {}s",
+                args.external_resource_interval_secs
+            );
+        }
+    }
+
+    // Elohim tasks: record progress reported by elohim agents and time out
+    // tasks they never finish
+    if let (Some(nats), Some(mongo)) = (state.nats.clone(), state.mongo.clone()) {
+        let _elohim_tasks = worker::elohim_tasks::spawn_elohim_task_tracker(
+            nats,
+            mongo,
+            std::time::Duration::from_secs(args.elohim_task_timeout_secs),
+        );
+        info!(
+            "Elohim task tracking enabled: timeout {}s",
+            args.elohim_task_timeout_secs
+        );
+    }
+
+    // Regions: announce this doorway, track the others, and share cache
+    // invalidations so every region evicts what any region saw written
+    if let (Some(directory), Some(nats)) = (state.regions.clone(), state.nats.clone()) {
+        let health_state = Arc::clone(&state);
+        let _presence =
+            worker::regions::spawn_region_presence_task(directory, nats.clone(), move || {
+                doorway::routes::region_hint::doorway_health(&health_state)
+            });
+        let _relay = worker::regions::spawn_invalidation_relay(
+            nats,
+            args.node_id.to_string(),
+            relay_signals,
+            Arc::clone(&state.cache),
+            Arc::clone(&state.cache_rules),
+        );
+    } else if state.regions.is_some() {
+        warn!("Region awareness needs NATS; no presence or global invalidation");
+    }
+
+    // Search export: mirror projected content and paths into an external cluster
+    if let Some(url) = args.search_export_url.clone() {
+        if let Some(projection) = state.projection.clone() {
+            let _search_export = worker::search_export::spawn_search_export_task(
+                worker::search_export::SearchExportConfig {
+                    url,
+                    api_key: args.search_export_api_key.clone(),
+                    index_prefix: args.search_export_index_prefix.clone(),
+                    batch_size: 500,
+                    flush_interval: std::time::Duration::from_secs(2),
+                },
+                projection,
+            );
+            info!("Search export enabled: {}", args.search_export_index_prefix);
+        } else {
+            warn!(
+                "SEARCH_EXPORT_URL set but projection store is unavailable; search export disabled"
+            );
+        }
+    }
+
+    // Blob mirror: copy public blobs to S3 as download fallbacks
+    if let (Some(endpoint), Some(bucket), Some(access_key), Some(secret_key)) = (
+        args.blob_mirror_s3_endpoint.clone(),
+        args.blob_mirror_s3_bucket.clone(),
+        args.blob_mirror_s3_access_key.clone(),
+        args.blob_mirror_s3_secret_key.clone(),
+    ) {
+        if args.blob_mirror_interval_secs > 0 {
+            if let Some(zome_caller) = state.zome_caller.clone() {
+                let _blob_mirror = worker::blob_mirror::spawn_blob_mirror_task(
+                    std::time::Duration::from_secs(args.blob_mirror_interval_secs),
+                    zome_caller,
+                    worker::blob_mirror::BlobMirrorConfig {
+                        endpoint,
+                        bucket: bucket.clone(),
+                        region: args.blob_mirror_s3_region.clone(),
+                        access_key,
+                        secret_key,
+                        public_base_url: args.blob_mirror_public_url.clone(),
+                        storage_url: args.storage_url.clone(),
+                    },
+                );
+                info!(
+                    "Blob mirror enabled: bucket {} every {}s",
+                    bucket, args.blob_mirror_interval_secs
+                );
+            }
+        }
+    }
+
+    // Sitemaps: rebuild periodically from the projection store
+    if let Some(generator) = state.sitemaps.clone() {
+        let _sitemap = worker::sitemap::spawn_sitemap_task(
+            std::time::Duration::from_secs(args.sitemap_interval_secs),
+            generator,
+        );
+        info!(
+            "Sitemap generation enabled: every {}s",
+            args.sitemap_interval_secs
+        );
+    }
+
+    // Transcoding: turn new video blobs into adaptive renditions
+    if let Some(url) = args.transcoder_url.clone() {
+        if args.transcode_interval_secs > 0 {
+            if let Some(zome_caller) = state.zome_caller.clone() {
+                let _transcode = worker::transcode::spawn_transcode_task(
+                    std::time::Duration::from_secs(args.transcode_interval_secs),
+                    zome_caller,
+                    worker::transcode::TranscodeConfig {
+                        url: url.clone(),
+                        api_key: args.transcoder_api_key.clone(),
+                        renditions: args.transcode_renditions.clone(),
+                        storage_url: args.storage_url.clone(),
+                    },
+                );
+                info!(
+                    "Transcoding enabled: {} every {}s",
+                    url, args.transcode_interval_secs
+                );
+            }
+        }
+    }
+
+    // Machine translation: work through queued translation requests
+    if let (Some(translator), Some(zome_caller)) =
+        (state.machine_translation.clone(), state.zome_caller.clone())
+    {
+        let _machine_translation = worker::machine_translation::spawn_machine_translation_task(
+            std::time::Duration::from_secs(args.machine_translation_interval_secs.max(1)),
+            zome_caller,
+            translator,
+        );
+        info!(
+            "Machine translation enabled: {} every {}s",
+            args.machine_translation_provider.as_deref().unwrap_or_default(),
+            args.machine_translation_interval_secs
+        );
+    }
+
+    // Embeddings: embed new and changed commons content
+    if let (Some(embedder), Some(index), Some(zome_caller)) =
+        (embedder, state.semantic.clone(), state.zome_caller.clone())
+    {
+        let _embeddings = worker::embeddings::spawn_embedding_task(
+            std::time::Duration::from_secs(args.embedding_interval_secs.max(1)),
+            zome_caller,
+            embedder,
+            index,
+        );
+        info!(
+            "Embeddings enabled: {} every {}s",
+            args.embedding_provider.as_deref().unwrap_or_default(),
+            args.embedding_interval_secs
+        );
+    }
+
+    // Question generation: draft quiz questions for steward review
+    if let Some(url) = args.question_generation_url.clone() {
+        if let Some(zome_caller) = state.zome_caller.clone() {
+            let _question_generation =
+                worker::question_generation::spawn_question_generation_task(
+                    std::time::Duration::from_secs(args.question_generation_interval_secs.max(1)),
+                    zome_caller,
+                    worker::question_generation::QuestionGenerator::new(
+                        worker::question_generation::QuestionGenerationConfig {
+                            url: url.clone(),
+                            api_key: args.question_generation_api_key.clone(),
+                            model: args.question_generation_model.clone(),
+                            questions_per_content: args.question_generation_per_content.max(1),
+                        },
+                    ),
+                );
+            info!(
+                "Question generation enabled: {} ({}) every {}s",
+                url, args.question_generation_model, args.question_generation_interval_secs
+            );
+        }
+    }
+
     // Run the server
     if let Err(e) = server::run(state).await {
         error!("Server error: {:?}", e);
@@ -702,3 +1600,46 @@ fn derive_app_url(conductor_url: &str, app_port: u16) -> String {
     // Fallback: just use the default
     format!("ws://localhost:{app_port}")
 }
+
+/// Run `doorway cache dump|load` against running doorways
+async fn run_cache_command(cache_args: &CacheArgs) -> Result<(), String> {
+    use doorway::cache::snapshot::{fetch_snapshot, push_snapshot};
+
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(600))
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    match cache_args.action {
+        CacheAction::Dump { ref from, ref out } => {
+            let snapshot = fetch_snapshot(&client, from, &cache_args.token).await?;
+            tokio::fs::write(out, &snapshot)
+                .await
+                .map_err(|e| format!("{}: {}", out.display(), e))?;
+            info!(out = %out.display(), bytes = snapshot.len(), "Cache snapshot written");
+        }
+        CacheAction::Load {
+            ref to,
+            ref file,
+            ref from,
+        } => {
+            let snapshot = match (file, from) {
+                (Some(file), _) => tokio::fs::read(file)
+                    .await
+                    .map(Into::into)
+                    .map_err(|e| format!("{}: {}", file.display(), e))?,
+                (None, Some(from)) => fetch_snapshot(&client, from, &cache_args.token).await?,
+                (None, None) => return Err("give --file or --from".to_string()),
+            };
+            let summary = push_snapshot(&client, to, &cache_args.token, snapshot).await?;
+            info!(
+                to = %to,
+                loaded = summary.loaded,
+                expired = summary.expired,
+                skipped = summary.skipped,
+                "Cache snapshot loaded"
+            );
+        }
+    }
+    Ok(())
+}
@@ -1,11 +1,16 @@
 //! NATS message types for Holochain WebSocket proxying
 //!
 //! Defines the request/response messages used for routing WebSocket
-//! connections through NATS to backend Holochain hosts.
+//! connections through NATS to backend Holochain hosts, the messages
+//! exchanged with elohim agents when the doorway dispatches tasks to them,
+//! and the presence and cache invalidations doorways in several regions
+//! share (see [`regions`](crate::services::regions)).
 
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::db::schemas::{ElohimTaskKind, ElohimTaskStatus};
+
 /// Subject prefix for Holochain WebSocket requests
 pub const HC_WS_SUBJECT_PREFIX: &str = "HC.WS";
 
@@ -243,6 +248,100 @@ impl HostHeartbeat {
     }
 }
 
+/// Subject prefix doorways announce their presence on, followed by the region
+pub const DOORWAY_PRESENCE_SUBJECT_PREFIX: &str = "DOORWAY.PRESENCE";
+
+/// Subject doorways share cache invalidations on
+pub const CACHE_INVALIDATION_SUBJECT: &str = "DOORWAY.CACHE.INVALIDATE";
+
+/// Latitude and longitude in degrees
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GeoPoint {
+    pub lat: f64,
+    pub lon: f64,
+}
+
+/// A doorway announcing itself to the others
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DoorwayPresence {
+    pub node_id: String,
+    pub region: String,
+    /// Public URL clients can be sent to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<GeoPoint>,
+    /// Conductor connected and not under high pressure
+    pub healthy: bool,
+    /// Conductor calls queued or in flight
+    #[serde(default)]
+    pub in_flight: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+impl DoorwayPresence {
+    /// Subject a region's doorways announce themselves on
+    pub fn subject(region: &str) -> String {
+        format!("{DOORWAY_PRESENCE_SUBJECT_PREFIX}.{region}")
+    }
+}
+
+/// A write one doorway saw, for the others to evict from their caches
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheInvalidationMessage {
+    /// Node that saw the write
+    pub origin: String,
+    pub source_fn: String,
+    pub doc_type: String,
+    pub doc_id: String,
+}
+
+/// Subject prefix elohim agents report task progress on
+pub const ELOHIM_TASK_STATUS_SUBJECT_PREFIX: &str = "ELOHIM.TASK.STATUS";
+
+/// Task published to an elohim agent's registered endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElohimTaskMessage {
+    pub task_id: String,
+    pub kind: ElohimTaskKind,
+    pub scope: String,
+    /// Task input, passed through from the requester
+    pub payload: serde_json::Value,
+    /// Where to publish [`ElohimTaskUpdate`]s for this task
+    pub status_subject: String,
+}
+
+impl ElohimTaskMessage {
+    /// Create a task message with a generated ID
+    pub fn new(kind: ElohimTaskKind, scope: String, payload: serde_json::Value) -> Self {
+        let task_id = Uuid::new_v4().to_string();
+        Self {
+            status_subject: Self::status_subject(&task_id),
+            task_id,
+            kind,
+            scope,
+            payload,
+        }
+    }
+
+    /// Subject progress for a task is reported on
+    pub fn status_subject(task_id: &str) -> String {
+        format!("{ELOHIM_TASK_STATUS_SUBJECT_PREFIX}.{task_id}")
+    }
+}
+
+/// Progress report from an elohim agent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElohimTaskUpdate {
+    pub task_id: String,
+    pub status: ElohimTaskStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
 // Base64 encoding helpers using the base64 crate
 fn base64_encode(data: &[u8]) -> String {
     use base64::{engine::general_purpose::STANDARD, Engine};
@@ -320,4 +419,27 @@ mod tests {
         let decoded = req.decode_payload().unwrap();
         assert_eq!(original_payload, decoded);
     }
+
+    #[test]
+    fn test_elohim_task_status_subject() {
+        let task = ElohimTaskMessage::new(
+            ElohimTaskKind::Translation,
+            "global".to_string(),
+            serde_json::json!({ "content_id": "c-1" }),
+        );
+        assert_eq!(
+            task.status_subject,
+            format!("ELOHIM.TASK.STATUS.{}", task.task_id)
+        );
+    }
+
+    #[test]
+    fn test_elohim_task_update_parse() {
+        let update: ElohimTaskUpdate = serde_json::from_str(
+            r#"{"task_id":"t-1","status":"completed","result":{"title":"Hola"}}"#,
+        )
+        .unwrap();
+        assert_eq!(update.status, ElohimTaskStatus::Completed);
+        assert!(update.error.is_none());
+    }
 }
@@ -10,5 +10,5 @@ pub mod routing;
 
 pub use client::NatsClient;
 pub use gateway::{GatewayConfig, GatewayPublisher, SessionGateway};
-pub use messages::{HcWsRequest, HcWsResponse};
+pub use messages::{ElohimTaskMessage, ElohimTaskUpdate, HcWsRequest, HcWsResponse};
 pub use routing::HostRouter;
@@ -3,6 +3,7 @@
 //! Defines the structure for documents stored in the projection layer.
 
 use bson::{doc, DateTime, Document};
+use chrono::Utc;
 use mongodb::options::IndexOptions;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
@@ -180,6 +181,54 @@ impl ProjectedDocument {
             .map(|word| word.to_lowercase())
             .collect()
     }
+
+    /// When the underlying entry was created and last updated.
+    ///
+    /// Prefers the entry's own `created_at`/`updated_at` over projection
+    /// times, which move whenever the document is re-projected.
+    pub fn entry_times(&self) -> (chrono::DateTime<Utc>, chrono::DateTime<Utc>) {
+        let created = self
+            .data
+            .get("created_at")
+            .and_then(parse_entry_timestamp)
+            .unwrap_or_else(|| self.created_at.to_chrono());
+        let updated = self
+            .data
+            .get("updated_at")
+            .and_then(parse_entry_timestamp)
+            .or_else(|| self.metadata.updated_at.map(|t| t.to_chrono()))
+            .unwrap_or(created);
+        (created, updated.max(created))
+    }
+}
+
+/// Parse an entry timestamp: RFC 3339, optionally wrapped as `Timestamp(..)`
+/// (Holochain's debug format), or microseconds since the epoch.
+pub fn parse_entry_timestamp(value: &JsonValue) -> Option<chrono::DateTime<Utc>> {
+    if let Some(micros) = value.as_i64() {
+        return chrono::DateTime::from_timestamp_micros(micros);
+    }
+    let text = value.as_str()?.trim();
+    let text = text
+        .strip_prefix("Timestamp(")
+        .and_then(|t| t.strip_suffix(')'))
+        .unwrap_or(text);
+    chrono::DateTime::parse_from_rfc3339(text)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// RFC 3339 in UTC to the second (`2025-03-01T12:00:00Z`)
+pub fn rfc3339(time: chrono::DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+/// RFC 3339 form of an entry timestamp, or the original text when it
+/// doesn't parse
+pub fn entry_timestamp_rfc3339(value: &str) -> String {
+    parse_entry_timestamp(&JsonValue::String(value.to_string()))
+        .map(rfc3339)
+        .unwrap_or_else(|| value.to_string())
 }
 
 impl IntoIndexes for ProjectedDocument {
@@ -266,6 +315,12 @@ impl ProjectionQuery {
         self
     }
 
+    /// Add a custom BSON filter
+    pub fn with_filter(mut self, filter: Document) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
     /// Add limit
     pub fn with_limit(mut self, limit: i64) -> Self {
         self.limit = Some(limit);
@@ -332,6 +387,36 @@ mod tests {
         assert_eq!(doc.mongo_id, Some("Content:content-123".to_string()));
     }
 
+    #[test]
+    fn test_parse_entry_timestamp() {
+        let expected = chrono::DateTime::parse_from_rfc3339("2025-03-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let parse = |v: JsonValue| parse_entry_timestamp(&v);
+        assert_eq!(
+            parse(serde_json::json!("2025-03-01T12:00:00Z")),
+            Some(expected)
+        );
+        assert_eq!(
+            parse(serde_json::json!("Timestamp(2025-03-01T12:00:00.000000Z)")),
+            Some(expected)
+        );
+        assert_eq!(
+            parse(serde_json::json!(expected.timestamp_micros())),
+            Some(expected)
+        );
+        assert_eq!(parse(serde_json::json!("yesterday")), None);
+    }
+
+    #[test]
+    fn test_entry_timestamp_rfc3339() {
+        assert_eq!(
+            entry_timestamp_rfc3339("Timestamp(2025-03-01T12:00:00.000000Z)"),
+            "2025-03-01T12:00:00Z"
+        );
+        assert_eq!(entry_timestamp_rfc3339("yesterday"), "yesterday");
+    }
+
     #[test]
     fn test_search_token_extraction() {
         let tokens = ProjectedDocument::extract_search_tokens("The quick brown fox jumps");
@@ -0,0 +1,417 @@
+//! Content graph index
+//!
+//! Adjacency lists over projected `Relationship` documents, so graph
+//! exploration runs in doorway instead of walking links in the WASM zome.
+//! The index is kept current by [`ProjectionStore`](super::ProjectionStore):
+//! relationship upserts add edges, invalidations remove them.
+//!
+//! Traversal is breadth-first from a root content id, bounded by depth and
+//! node count, and returns D3-friendly `nodes`/`edges` arrays.
+
+use std::collections::{HashSet, VecDeque};
+use std::sync::RwLock;
+use std::time::Instant;
+
+use dashmap::DashMap;
+use serde::Serialize;
+
+use super::document::ProjectedDocument;
+
+/// Projected document type holding relationships
+pub const RELATIONSHIP_DOC_TYPE: &str = "Relationship";
+
+/// Deepest traversal a client may request
+pub const MAX_GRAPH_DEPTH: u32 = 4;
+
+/// Node budget per traversal; expansion stops once reached
+pub const MAX_GRAPH_NODES: usize = 500;
+
+/// Which edges to follow from a node
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GraphDirection {
+    /// source → target
+    Outgoing,
+    /// target → source
+    Incoming,
+    /// Both ways
+    #[default]
+    Both,
+}
+
+impl GraphDirection {
+    /// Parse from query string value
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "out" | "outgoing" => Some(Self::Outgoing),
+            "in" | "incoming" => Some(Self::Incoming),
+            "both" => Some(Self::Both),
+            _ => None,
+        }
+    }
+}
+
+/// A relationship edge
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GraphEdge {
+    /// Relationship id
+    pub id: String,
+    pub source: String,
+    pub target: String,
+    pub relationship_type: String,
+    pub confidence: f64,
+}
+
+impl GraphEdge {
+    /// Build an edge from a projected Relationship document
+    pub fn from_document(doc: &ProjectedDocument) -> Option<Self> {
+        if doc.doc_type != RELATIONSHIP_DOC_TYPE {
+            return None;
+        }
+        let data = &doc.data;
+        Some(Self {
+            id: doc.doc_id.clone(),
+            source: data.get("source_id")?.as_str()?.to_string(),
+            target: data.get("target_id")?.as_str()?.to_string(),
+            relationship_type: data
+                .get("relationship_type")
+                .and_then(|v| v.as_str())
+                .unwrap_or("RELATES_TO")
+                .to_string(),
+            confidence: data
+                .get("confidence")
+                .and_then(|v| v.as_f64())
+                .unwrap_or(1.0),
+        })
+    }
+}
+
+/// A node reached during traversal
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphNode {
+    pub id: String,
+    /// Hops from the root
+    pub depth: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+}
+
+/// Traversal options
+#[derive(Debug, Clone)]
+pub struct GraphOptions {
+    /// Maximum hops from the root (clamped to [`MAX_GRAPH_DEPTH`])
+    pub depth: u32,
+    /// Relationship types to follow; `None` follows all
+    pub types: Option<HashSet<String>>,
+    pub direction: GraphDirection,
+}
+
+impl Default for GraphOptions {
+    fn default() -> Self {
+        Self {
+            depth: 1,
+            types: None,
+            direction: GraphDirection::Both,
+        }
+    }
+}
+
+/// Traversal result, shaped for D3 force layouts
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphTraversal {
+    pub root: String,
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+    /// True when the node budget cut the expansion short
+    pub truncated: bool,
+}
+
+/// In-memory adjacency index over relationships
+#[derive(Default)]
+pub struct ContentGraph {
+    /// Relationship id → edge
+    edges: DashMap<String, GraphEdge>,
+    /// Content id → relationship ids where it is the source
+    outgoing: DashMap<String, HashSet<String>>,
+    /// Content id → relationship ids where it is the target
+    incoming: DashMap<String, HashSet<String>>,
+    /// When the index was last rebuilt from the projection
+    loaded_at: RwLock<Option<Instant>>,
+}
+
+impl ContentGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add or replace an edge
+    pub fn upsert(&self, edge: GraphEdge) {
+        self.remove(&edge.id);
+        self.outgoing
+            .entry(edge.source.clone())
+            .or_default()
+            .insert(edge.id.clone());
+        self.incoming
+            .entry(edge.target.clone())
+            .or_default()
+            .insert(edge.id.clone());
+        self.edges.insert(edge.id.clone(), edge);
+    }
+
+    /// Remove an edge by relationship id
+    pub fn remove(&self, relationship_id: &str) -> bool {
+        let Some((_, edge)) = self.edges.remove(relationship_id) else {
+            return false;
+        };
+        if let Some(mut ids) = self.outgoing.get_mut(&edge.source) {
+            ids.remove(relationship_id);
+        }
+        if let Some(mut ids) = self.incoming.get_mut(&edge.target) {
+            ids.remove(relationship_id);
+        }
+        self.outgoing
+            .remove_if(&edge.source, |_, ids| ids.is_empty());
+        self.incoming
+            .remove_if(&edge.target, |_, ids| ids.is_empty());
+        true
+    }
+
+    /// Drop every edge
+    pub fn clear(&self) {
+        self.edges.clear();
+        self.outgoing.clear();
+        self.incoming.clear();
+        if let Ok(mut loaded_at) = self.loaded_at.write() {
+            *loaded_at = None;
+        }
+    }
+
+    /// Replace the index with a full set of relationship documents
+    pub fn rebuild(&self, docs: &[ProjectedDocument]) {
+        self.clear();
+        for edge in docs.iter().filter_map(GraphEdge::from_document) {
+            self.upsert(edge);
+        }
+        if let Ok(mut loaded_at) = self.loaded_at.write() {
+            *loaded_at = Some(Instant::now());
+        }
+    }
+
+    /// Seconds since the last rebuild, `None` if never loaded
+    pub fn age_secs(&self) -> Option<u64> {
+        self.loaded_at
+            .read()
+            .ok()
+            .and_then(|at| at.map(|at| at.elapsed().as_secs()))
+    }
+
+    /// Number of edges indexed
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Edges adjacent to a node that match the options
+    fn neighbours(&self, node: &str, options: &GraphOptions) -> Vec<GraphEdge> {
+        let mut ids: Vec<String> = Vec::new();
+        if options.direction != GraphDirection::Incoming {
+            if let Some(out) = self.outgoing.get(node) {
+                ids.extend(out.iter().cloned());
+            }
+        }
+        if options.direction != GraphDirection::Outgoing {
+            if let Some(inc) = self.incoming.get(node) {
+                ids.extend(inc.iter().cloned());
+            }
+        }
+        // Stable output regardless of set iteration order
+        ids.sort();
+        ids.dedup();
+
+        ids.iter()
+            .filter_map(|id| self.edges.get(id).map(|e| e.clone()))
+            .filter(|edge| match options.types {
+                Some(ref types) => types.contains(&edge.relationship_type),
+                None => true,
+            })
+            .collect()
+    }
+
+    /// Breadth-first expansion from `root`
+    ///
+    /// Every node appears once, at its shortest depth; every edge appears
+    /// once even when reached from both ends.
+    pub fn traverse(&self, root: &str, options: &GraphOptions) -> GraphTraversal {
+        let max_depth = options.depth.min(MAX_GRAPH_DEPTH);
+
+        let mut visited: HashSet<String> = HashSet::from([root.to_string()]);
+        let mut seen_edges: HashSet<String> = HashSet::new();
+        let mut nodes = vec![GraphNode {
+            id: root.to_string(),
+            depth: 0,
+            title: None,
+            content_type: None,
+        }];
+        let mut edges = Vec::new();
+        let mut truncated = false;
+        let mut queue = VecDeque::from([(root.to_string(), 0u32)]);
+
+        'bfs: while let Some((node, depth)) = queue.pop_front() {
+            if depth >= max_depth {
+                continue;
+            }
+
+            for edge in self.neighbours(&node, options) {
+                let other = if edge.source == node {
+                    edge.target.clone()
+                } else {
+                    edge.source.clone()
+                };
+
+                if !visited.contains(&other) {
+                    if nodes.len() >= MAX_GRAPH_NODES {
+                        truncated = true;
+                        break 'bfs;
+                    }
+                    visited.insert(other.clone());
+                    nodes.push(GraphNode {
+                        id: other.clone(),
+                        depth: depth + 1,
+                        title: None,
+                        content_type: None,
+                    });
+                    queue.push_back((other, depth + 1));
+                }
+
+                if seen_edges.insert(edge.id.clone()) {
+                    edges.push(edge);
+                }
+            }
+        }
+
+        GraphTraversal {
+            root: root.to_string(),
+            nodes,
+            edges,
+            truncated,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edge(id: &str, source: &str, target: &str, rel: &str) -> GraphEdge {
+        GraphEdge {
+            id: id.into(),
+            source: source.into(),
+            target: target.into(),
+            relationship_type: rel.into(),
+            confidence: 1.0,
+        }
+    }
+
+    /// a → b → c → d, plus x → a and a cycle c → a
+    fn sample() -> ContentGraph {
+        let graph = ContentGraph::new();
+        graph.upsert(edge("r1", "a", "b", "RELATES_TO"));
+        graph.upsert(edge("r2", "b", "c", "DEPENDS_ON"));
+        graph.upsert(edge("r3", "c", "d", "RELATES_TO"));
+        graph.upsert(edge("r4", "x", "a", "RELATES_TO"));
+        graph.upsert(edge("r5", "c", "a", "RELATES_TO"));
+        graph
+    }
+
+    fn ids(traversal: &GraphTraversal) -> Vec<&str> {
+        let mut ids: Vec<&str> = traversal.nodes.iter().map(|n| n.id.as_str()).collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn test_depth_limits_expansion() {
+        let graph = sample();
+        let options = GraphOptions {
+            depth: 1,
+            direction: GraphDirection::Outgoing,
+            ..Default::default()
+        };
+        let result = graph.traverse("a", &options);
+        assert_eq!(ids(&result), vec!["a", "b"]);
+
+        let options = GraphOptions {
+            depth: 3,
+            direction: GraphDirection::Outgoing,
+            ..Default::default()
+        };
+        let result = graph.traverse("a", &options);
+        assert_eq!(ids(&result), vec!["a", "b", "c", "d"]);
+        assert_eq!(result.nodes.iter().find(|n| n.id == "d").unwrap().depth, 3);
+    }
+
+    #[test]
+    fn test_direction() {
+        let graph = sample();
+        let incoming = GraphOptions {
+            depth: 1,
+            direction: GraphDirection::Incoming,
+            ..Default::default()
+        };
+        assert_eq!(ids(&graph.traverse("a", &incoming)), vec!["a", "c", "x"]);
+
+        let both = GraphOptions::default();
+        assert_eq!(ids(&graph.traverse("a", &both)), vec!["a", "b", "c", "x"]);
+    }
+
+    #[test]
+    fn test_type_filter() {
+        let graph = sample();
+        let options = GraphOptions {
+            depth: 4,
+            types: Some(HashSet::from(["DEPENDS_ON".to_string()])),
+            direction: GraphDirection::Both,
+        };
+        assert_eq!(ids(&graph.traverse("b", &options)), vec!["b", "c"]);
+    }
+
+    #[test]
+    fn test_cycles_deduplicate_nodes_and_edges() {
+        let graph = sample();
+        let options = GraphOptions {
+            depth: 4,
+            ..Default::default()
+        };
+        let result = graph.traverse("a", &options);
+        assert_eq!(result.nodes.len(), 5);
+        assert_eq!(result.edges.len(), 5);
+        assert!(!result.truncated);
+    }
+
+    #[test]
+    fn test_remove_and_rebuild() {
+        let graph = sample();
+        assert!(graph.remove("r1"));
+        assert!(!graph.remove("r1"));
+        assert_eq!(graph.edge_count(), 4);
+
+        let doc = ProjectedDocument::new(
+            RELATIONSHIP_DOC_TYPE,
+            "r9",
+            "uhCkk...",
+            "uhCAk...",
+            serde_json::json!({
+                "source_id": "m",
+                "target_id": "n",
+                "relationship_type": "CONTAINS",
+                "confidence": 0.5
+            }),
+        );
+        graph.rebuild(&[doc]);
+        assert_eq!(graph.edge_count(), 1);
+        assert!(graph.age_secs().is_some());
+        let result = graph.traverse("n", &GraphOptions::default());
+        assert_eq!(result.edges[0].relationship_type, "CONTAINS");
+        assert_eq!(result.edges[0].confidence, 0.5);
+    }
+}
@@ -38,12 +38,14 @@ pub mod app_auth;
 pub mod collections;
 pub mod document;
 pub mod engine;
+pub mod graph;
 pub mod store;
 pub mod subscriber;
 
 // Re-export main types
 pub use document::{ProjectedDocument, ProjectionQuery};
 pub use engine::{spawn_engine_task, EngineConfig, ProjectionEngine, ProjectionSignal};
+pub use graph::{ContentGraph, GraphDirection, GraphOptions, GraphTraversal};
 pub use store::{ProjectionConfig, ProjectionStore};
 pub use subscriber::{
     spawn_subscriber, ContentServerRegistration, SignalSubscriber, SubscriberConfig,
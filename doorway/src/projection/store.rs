@@ -2,18 +2,26 @@
 //!
 //! Combines an in-memory hot cache with MongoDB for persistence.
 //! Reads check hot cache first, then MongoDB. Writes update both.
+//! MongoDB reads follow the configured read preference, so on a replica set
+//! they may come from a secondary; the hot cache covers fresh writes.
 
 use bson::{doc, DateTime};
 use dashmap::DashMap;
 use futures_util::StreamExt;
 use mongodb::options::FindOptions;
+use std::sync::Arc;
 use tokio::sync::broadcast;
 use tracing::{debug, error, info, warn};
 
-use crate::db::MongoClient;
+use crate::db::mongo::retry_write;
+use crate::db::{L1Cache, L1Stats, MongoClient};
 use crate::types::DoorwayError;
 
 use super::document::{ProjectedDocument, ProjectionQuery};
+use super::graph::{ContentGraph, GraphEdge, GraphOptions, GraphTraversal, RELATIONSHIP_DOC_TYPE};
+
+/// Upper bound on relationships loaded when rebuilding the content graph
+const MAX_GRAPH_RELATIONSHIPS: i64 = 100_000;
 
 /// Hot cache entry for projected documents
 #[derive(Debug, Clone)]
@@ -63,6 +71,7 @@ impl Default for ProjectionConfig {
 /// Architecture:
 /// - Hot cache (DashMap) for fast reads
 /// - MongoDB for persistence and queries
+/// - Optional L1 for hot query results (see [`crate::db::l1`])
 /// - Broadcast channel for real-time updates
 pub struct ProjectionStore {
     /// In-memory hot cache
@@ -76,6 +85,12 @@ pub struct ProjectionStore {
 
     /// Broadcast sender for projection updates
     update_tx: broadcast::Sender<ProjectedDocument>,
+
+    /// Adjacency index over projected relationships
+    graph: ContentGraph,
+
+    /// In-process cache of hot query results (opted-in document types)
+    l1: Option<Arc<L1Cache<Arc<Vec<ProjectedDocument>>>>>,
 }
 
 impl ProjectionStore {
@@ -96,6 +111,8 @@ impl ProjectionStore {
             mongo: Some(mongo),
             config,
             update_tx,
+            graph: ContentGraph::new(),
+            l1: None,
         })
     }
 
@@ -110,7 +127,42 @@ impl ProjectionStore {
             mongo: None,
             config,
             update_tx,
+            graph: ContentGraph::new(),
+            l1: None,
+        }
+    }
+
+    /// Serve opted-in query results from an in-process L1
+    pub fn with_l1(mut self, l1: Arc<L1Cache<Arc<Vec<ProjectedDocument>>>>) -> Self {
+        self.l1 = Some(l1);
+        self
+    }
+
+    /// The L1 in front of MongoDB queries, if enabled
+    pub fn l1(&self) -> Option<&Arc<L1Cache<Arc<Vec<ProjectedDocument>>>>> {
+        self.l1.as_ref()
+    }
+
+    pub fn l1_stats(&self) -> Option<L1Stats> {
+        self.l1.as_ref().map(|l1| l1.stats())
+    }
+
+    /// L1 key of a query whose document type opted in
+    fn l1_key(&self, query: &ProjectionQuery) -> Option<String> {
+        let doc_type = query.doc_type.as_deref()?;
+        if !self.l1.as_ref()?.covers(doc_type) {
+            return None;
         }
+        Some(format!(
+            "{doc_type}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}",
+            query.author,
+            query.doc_ids,
+            query.search,
+            query.filter,
+            query.limit,
+            query.skip,
+            query.sort
+        ))
     }
 
     /// Ensure MongoDB indexes exist
@@ -184,7 +236,7 @@ impl ProjectionStore {
         mongo: &MongoClient,
         mongo_id: &str,
     ) -> Result<Option<ProjectedDocument>, DoorwayError> {
-        let db = mongo.inner().database(mongo.db_name());
+        let db = mongo.read_database();
         let collection = db.collection::<ProjectedDocument>(&self.config.collection_name);
 
         collection
@@ -211,6 +263,13 @@ impl ProjectionStore {
         self.hot_cache
             .insert(cache_key, HotCacheEntry::new(doc.clone()));
         self.evict_if_needed();
+        if let Some(ref l1) = self.l1 {
+            l1.invalidate(&doc.doc_type);
+        }
+
+        if let Some(edge) = GraphEdge::from_document(&doc) {
+            self.graph.upsert(edge);
+        }
 
         // Broadcast update
         let _ = self.update_tx.send(doc);
@@ -237,11 +296,13 @@ impl ProjectionStore {
             .upsert(true)
             .build();
 
-        collection
-            .replace_one(doc! { "_id": &mongo_id }, doc)
-            .with_options(options)
-            .await
-            .map_err(|e| DoorwayError::Database(format!("Upsert failed: {e}")))?;
+        retry_write(mongo.write_retries(), || {
+            collection
+                .replace_one(doc! { "_id": &mongo_id }, doc)
+                .with_options(options.clone())
+        })
+        .await
+        .map_err(|e| DoorwayError::Database(format!("Upsert failed: {e}")))?;
 
         debug!("Projected document upserted: {}", mongo_id);
         Ok(())
@@ -249,7 +310,8 @@ impl ProjectionStore {
 
     /// Query projected documents
     ///
-    /// Always queries MongoDB (hot cache is for single-document lookups).
+    /// Queries MongoDB (hot cache is for single-document lookups), unless
+    /// the document type opted in to L1 and the result is cached there.
     pub async fn query(
         &self,
         query: ProjectionQuery,
@@ -259,7 +321,16 @@ impl ProjectionStore {
             return Ok(self.query_hot_cache(&query));
         };
 
-        let db = mongo.inner().database(mongo.db_name());
+        let l1_key = self.l1_key(&query);
+        if let (Some(l1), Some(key)) = (&self.l1, &l1_key) {
+            if let Some(results) = l1.get(key) {
+                return Ok(results.as_ref().clone());
+            }
+            l1.record_l2_hit();
+        }
+        let doc_type = query.doc_type.clone().unwrap_or_default();
+
+        let db = mongo.read_database();
         let collection = db.collection::<ProjectedDocument>(&self.config.collection_name);
 
         let filter = query.to_filter();
@@ -298,6 +369,10 @@ impl ProjectionStore {
             .collect()
             .await;
 
+        if let (Some(l1), Some(key)) = (&self.l1, &l1_key) {
+            l1.insert(key, &doc_type, Arc::new(results.clone()));
+        }
+
         Ok(results)
     }
 
@@ -370,6 +445,19 @@ impl ProjectionStore {
             count += 1;
         }
 
+        if let (Some(l1), Some((doc_type, _))) = (&self.l1, pattern.split_once(':')) {
+            l1.invalidate(doc_type);
+        }
+
+        // Keep the content graph in step with relationship removals
+        if let Some(target) = pattern.strip_prefix("Relationship:") {
+            if target == "*" {
+                self.graph.clear();
+            } else {
+                self.graph.remove(target);
+            }
+        }
+
         // Soft-delete in MongoDB
         if let Some(ref mongo) = self.mongo {
             let db = mongo.inner().database(mongo.db_name());
@@ -406,6 +494,75 @@ impl ProjectionStore {
         Ok(count)
     }
 
+    /// Content graph index, rebuilt from the projection when stale
+    ///
+    /// The index follows relationship writes projected here, and is rebuilt
+    /// when first used or once older than the hot cache TTL, so replicas that
+    /// don't project signals still converge.
+    pub async fn content_graph_index(&self) -> Result<&ContentGraph, DoorwayError> {
+        let stale = match self.graph.age_secs() {
+            Some(age) => age > self.config.hot_cache_ttl_secs,
+            None => true,
+        };
+        if stale {
+            let relationships = self
+                .query(
+                    ProjectionQuery::by_type(RELATIONSHIP_DOC_TYPE)
+                        .with_limit(MAX_GRAPH_RELATIONSHIPS),
+                )
+                .await?;
+            self.graph.rebuild(&relationships);
+            debug!(
+                "Content graph rebuilt with {} relationships",
+                self.graph.edge_count()
+            );
+        }
+        Ok(&self.graph)
+    }
+
+    /// Traverse the content graph breadth-first from `root`
+    ///
+    /// Nodes are annotated with title and type from projected Content.
+    pub async fn content_graph(
+        &self,
+        root: &str,
+        options: &GraphOptions,
+    ) -> Result<GraphTraversal, DoorwayError> {
+        let mut traversal = self.content_graph_index().await?.traverse(root, options);
+
+        let ids: Vec<String> = traversal.nodes.iter().map(|n| n.id.clone()).collect();
+        let limit = ids.len() as i64;
+        let content = self
+            .query(ProjectionQuery {
+                doc_type: Some("Content".to_string()),
+                doc_ids: Some(ids),
+                limit: Some(limit),
+                ..Default::default()
+            })
+            .await?;
+
+        for node in traversal.nodes.iter_mut() {
+            // Titles of restricted content stay out of the public graph
+            let Some(doc) = content.iter().find(|d| {
+                d.doc_id == node.id && matches!(d.reach.as_deref(), None | Some("commons"))
+            }) else {
+                continue;
+            };
+            node.title = doc
+                .data
+                .get("title")
+                .and_then(|v| v.as_str())
+                .map(String::from);
+            node.content_type = doc
+                .data
+                .get("content_type")
+                .and_then(|v| v.as_str())
+                .map(String::from);
+        }
+
+        Ok(traversal)
+    }
+
     /// Subscribe to projection updates
     pub fn subscribe(&self) -> broadcast::Receiver<ProjectedDocument> {
         self.update_tx.subscribe()
@@ -489,7 +646,7 @@ impl ProjectionStore {
 
         // Fall back to MongoDB
         if let Some(ref mongo) = self.mongo {
-            let db = mongo.inner().database(mongo.db_name());
+            let db = mongo.read_database();
             let collection = db.collection::<ProjectedDocument>(&self.config.collection_name);
 
             let filter = doc! {
@@ -562,6 +719,11 @@ impl ProjectionStore {
     pub fn has_mongodb(&self) -> bool {
         self.mongo.is_some()
     }
+
+    /// MongoDB collection holding the projections
+    pub fn collection_name(&self) -> &str {
+        &self.config.collection_name
+    }
 }
 
 /// Hot cache statistics
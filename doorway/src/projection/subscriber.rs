@@ -27,6 +27,7 @@ use tracing::{debug, error, info, warn};
 
 use super::app_auth::{issue_app_token, AppAuthToken};
 use super::engine::ProjectionSignal;
+use crate::cache::CacheInvalidation;
 
 // =============================================================================
 // CacheSignal Support - for warm_cache and doorway-client signals
@@ -47,6 +48,9 @@ pub struct CacheSignal {
     pub public: bool,
     #[serde(default)]
     pub reach: Option<String>,
+    /// Zome function whose write produced this signal
+    #[serde(default)]
+    pub source_fn: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, PartialEq)]
@@ -109,6 +113,15 @@ pub enum InfrastructureSignal {
 }
 
 impl CacheSignal {
+    /// Response cache invalidation for signals that report a write
+    pub fn to_cache_invalidation(&self) -> Option<CacheInvalidation> {
+        self.source_fn.as_ref().map(|source_fn| CacheInvalidation {
+            source_fn: source_fn.clone(),
+            doc_type: self.doc_type.clone(),
+            doc_id: self.doc_id.clone(),
+        })
+    }
+
     /// Convert CacheSignal to ProjectionSignal format
     ///
     /// For cache warming, we don't have action_hash/author from the original
@@ -242,6 +255,8 @@ pub struct SignalSubscriber {
     signal_tx: broadcast::Sender<ProjectionSignal>,
     /// Channel to send content server registrations (blob routing)
     blob_registry_tx: broadcast::Sender<ContentServerRegistration>,
+    /// Channel to send write notifications (response cache eviction)
+    cache_invalidation_tx: broadcast::Sender<CacheInvalidation>,
     /// Shutdown signal
     shutdown_tx: broadcast::Sender<()>,
 }
@@ -251,12 +266,14 @@ impl SignalSubscriber {
     pub fn new(config: SubscriberConfig) -> Self {
         let (signal_tx, _) = broadcast::channel(1000);
         let (blob_registry_tx, _) = broadcast::channel(1000);
+        let (cache_invalidation_tx, _) = broadcast::channel(1000);
         let (shutdown_tx, _) = broadcast::channel(1);
 
         Self {
             config,
             signal_tx,
             blob_registry_tx,
+            cache_invalidation_tx,
             shutdown_tx,
         }
     }
@@ -271,6 +288,11 @@ impl SignalSubscriber {
         self.blob_registry_tx.subscribe()
     }
 
+    /// Get a receiver for zome write notifications (→ ContentCache eviction)
+    pub fn subscribe_cache_invalidations(&self) -> broadcast::Receiver<CacheInvalidation> {
+        self.cache_invalidation_tx.subscribe()
+    }
+
     /// Get a shutdown receiver
     pub fn shutdown_receiver(&self) -> broadcast::Receiver<()> {
         self.shutdown_tx.subscribe()
@@ -604,6 +626,12 @@ impl SignalSubscriber {
         // { "namespace": "doorway", "payload": { CacheSignal } }
         if let Ok(doorway_signal) = serde_json::from_value::<DoorwaySignal>(value.clone()) {
             if doorway_signal.namespace == "doorway" {
+                // Writes evict cached zome responses; ignore send errors
+                // (no receiver when the response cache isn't wired up)
+                if let Some(invalidation) = doorway_signal.payload.to_cache_invalidation() {
+                    let _ = self.cache_invalidation_tx.send(invalidation);
+                }
+
                 let signal = doorway_signal.payload.to_projection_signal();
                 info!(
                     doc_type = signal.doc_type,
@@ -845,6 +873,39 @@ mod tests {
         subscriber.process_signal_value(&json);
     }
 
+    #[test]
+    fn test_write_signal_forwards_cache_invalidation() {
+        let subscriber = SignalSubscriber::new(SubscriberConfig::default());
+        let mut rx = subscriber.subscribe_cache_invalidations();
+
+        let json = serde_json::json!({
+            "namespace": "doorway",
+            "payload": {
+                "signal_type": "invalidate",
+                "doc_type": "PathStep",
+                "doc_id": "step-1",
+                "source_fn": "update_step"
+            }
+        });
+        subscriber.process_signal_value(&json);
+
+        let invalidation = rx.try_recv().unwrap();
+        assert_eq!(invalidation.source_fn, "update_step");
+        assert_eq!(invalidation.doc_id, "step-1");
+
+        // Signals without a source function don't evict anything
+        let json = serde_json::json!({
+            "namespace": "doorway",
+            "payload": {
+                "signal_type": "upsert",
+                "doc_type": "Content",
+                "doc_id": "manifesto"
+            }
+        });
+        subscriber.process_signal_value(&json);
+        assert!(rx.try_recv().is_err());
+    }
+
     #[test]
     fn test_cache_signal_to_projection_signal() {
         let cache_signal = CacheSignal {
@@ -857,6 +918,7 @@ mod tests {
             ttl_secs: Some(3600),
             public: true,
             reach: Some("commons".to_string()),
+            source_fn: None,
         };
 
         let projection_signal = cache_signal.to_projection_signal();
@@ -879,6 +941,7 @@ mod tests {
             ttl_secs: None,
             public: false,
             reach: None,
+            source_fn: None,
         };
 
         let projection_signal = cache_signal.to_projection_signal();
@@ -0,0 +1,313 @@
+//! Admission control for conductor calls
+//!
+//! Keeps an overloaded conductor serving writes and signed-in learners
+//! instead of degrading for everyone at once. The [worker pool](crate::worker::WorkerPool)
+//! reports its [`ConductorLoad`]: calls in flight (queued or awaiting the
+//! conductor) and the share of calls that timed out over the last minute.
+//! From those the policy judges the conductor's [`Pressure`]:
+//!
+//! - **Normal**: every call goes through
+//! - **Elevated**: in flight at half of `ADMISSION_MAX_IN_FLIGHT`, or
+//!   timeouts at half of `ADMISSION_TIMEOUT_RATE`; low-priority calls wait
+//!   `ADMISSION_DELAY_MS` before going through
+//! - **High**: either limit reached; low-priority calls are shed with
+//!   `503 Service Unavailable` (a delayed call still facing high pressure is
+//!   shed too)
+//!
+//! Low priority means anonymous cacheable reads: conductor fallbacks of the
+//! cache API and cacheable content_store reads made without a signed-in
+//! human. Writes and calls made for a signed-in human are never held back.
+//! Delays and sheds are counted in `GET /status`.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::config::Args;
+use crate::types::{DoorwayError, Result};
+
+/// Span timeouts are counted over
+const OUTCOME_WINDOW: Duration = Duration::from_secs(60);
+
+/// Buckets the window is kept in
+const OUTCOME_BUCKETS: usize = 6;
+
+/// Calls in the window before the timeout rate is trusted
+const MIN_SAMPLES: u64 = 20;
+
+/// How far a call may be held back
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallPriority {
+    /// Cacheable reads, delayed or shed under pressure
+    Low,
+    /// Writes and signed-in users' calls, always admitted
+    Normal,
+}
+
+/// Conductor pressure as judged from its load
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Pressure {
+    Normal,
+    Elevated,
+    High,
+}
+
+/// What to do with a call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Admission {
+    Admit,
+    Delay(Duration),
+    Shed,
+}
+
+/// Load indicators of a conductor connection pool
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct ConductorLoad {
+    /// Calls queued or awaiting the conductor
+    pub in_flight: usize,
+    /// Share of the last minute's calls that timed out
+    pub timeout_rate: f64,
+    /// Calls completed in the last minute
+    pub recent_calls: u64,
+}
+
+/// Completed and timed-out calls over the last minute
+#[derive(Debug)]
+pub struct CallOutcomes {
+    buckets: Mutex<[(Instant, u64, u64); OUTCOME_BUCKETS]>,
+}
+
+impl Default for CallOutcomes {
+    fn default() -> Self {
+        Self::new(Instant::now())
+    }
+}
+
+impl CallOutcomes {
+    fn new(now: Instant) -> Self {
+        Self {
+            buckets: Mutex::new([(now, 0, 0); OUTCOME_BUCKETS]),
+        }
+    }
+
+    fn bucket_span() -> Duration {
+        OUTCOME_WINDOW / OUTCOME_BUCKETS as u32
+    }
+
+    /// Count a completed call
+    pub fn record(&self, timed_out: bool, now: Instant) {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let newest = buckets
+            .iter_mut()
+            .max_by_key(|(start, _, _)| *start)
+            .expect("window has buckets");
+        if now.duration_since(newest.0) < Self::bucket_span() {
+            newest.1 += 1;
+            newest.2 += u64::from(timed_out);
+            return;
+        }
+        let oldest = buckets
+            .iter_mut()
+            .min_by_key(|(start, _, _)| *start)
+            .expect("window has buckets");
+        *oldest = (now, 1, u64::from(timed_out));
+    }
+
+    /// Calls and timeouts still inside the window
+    pub fn totals(&self, now: Instant) -> (u64, u64) {
+        let buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        buckets
+            .iter()
+            .filter(|(start, _, _)| now.duration_since(*start) < OUTCOME_WINDOW)
+            .fold((0, 0), |(calls, timeouts), (_, c, t)| {
+                (calls + c, timeouts + t)
+            })
+    }
+}
+
+/// Pressure thresholds and counters shared by all conductor calls
+#[derive(Debug)]
+pub struct AdmissionPolicy {
+    max_in_flight: usize,
+    timeout_rate: f64,
+    delay: Duration,
+    delayed: AtomicU64,
+    shed: AtomicU64,
+}
+
+/// Admission as reported by `GET /status`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct AdmissionStats {
+    pub pressure: Pressure,
+    pub load: ConductorLoad,
+    pub max_in_flight: usize,
+    pub max_timeout_rate: f64,
+    pub delayed_reads: u64,
+    pub shed_reads: u64,
+}
+
+impl AdmissionPolicy {
+    pub fn new(max_in_flight: usize, timeout_rate: f64, delay: Duration) -> Self {
+        Self {
+            max_in_flight: max_in_flight.max(1),
+            timeout_rate: timeout_rate.clamp(0.01, 1.0),
+            delay,
+            delayed: AtomicU64::new(0),
+            shed: AtomicU64::new(0),
+        }
+    }
+
+    /// Policy as configured, `None` when admission control is off
+    pub fn from_args(args: &Args) -> Option<Self> {
+        (args.admission_max_in_flight > 0).then(|| {
+            Self::new(
+                args.admission_max_in_flight,
+                args.admission_timeout_rate,
+                Duration::from_millis(args.admission_delay_ms),
+            )
+        })
+    }
+
+    /// Pressure the conductor is under
+    pub fn pressure(&self, load: &ConductorLoad) -> Pressure {
+        let timeout_rate = if load.recent_calls >= MIN_SAMPLES {
+            load.timeout_rate
+        } else {
+            0.0
+        };
+        if load.in_flight >= self.max_in_flight || timeout_rate >= self.timeout_rate {
+            Pressure::High
+        } else if load.in_flight * 2 >= self.max_in_flight
+            || timeout_rate * 2.0 >= self.timeout_rate
+        {
+            Pressure::Elevated
+        } else {
+            Pressure::Normal
+        }
+    }
+
+    /// Decide on a call given the current load
+    pub fn decide(&self, load: &ConductorLoad, priority: CallPriority) -> Admission {
+        if priority == CallPriority::Normal {
+            return Admission::Admit;
+        }
+        match self.pressure(load) {
+            Pressure::Normal => Admission::Admit,
+            Pressure::Elevated => Admission::Delay(self.delay),
+            Pressure::High => Admission::Shed,
+        }
+    }
+
+    /// Wait for a call's turn, or refuse it
+    ///
+    /// `load` is read again after a delay, so a call held back while
+    /// pressure builds is shed rather than piled on.
+    pub async fn admit(
+        &self,
+        load: impl Fn() -> ConductorLoad,
+        priority: CallPriority,
+    ) -> Result<()> {
+        match self.decide(&load(), priority) {
+            Admission::Admit => return Ok(()),
+            Admission::Delay(delay) => {
+                self.delayed.fetch_add(1, Ordering::Relaxed);
+                tokio::time::sleep(delay).await;
+                if self.pressure(&load()) < Pressure::High {
+                    return Ok(());
+                }
+            }
+            Admission::Shed => {}
+        }
+        self.shed.fetch_add(1, Ordering::Relaxed);
+        Err(DoorwayError::Overloaded(
+            "Conductor under heavy load, retry shortly".into(),
+        ))
+    }
+
+    pub fn stats(&self, load: ConductorLoad) -> AdmissionStats {
+        AdmissionStats {
+            pressure: self.pressure(&load),
+            load,
+            max_in_flight: self.max_in_flight,
+            max_timeout_rate: self.timeout_rate,
+            delayed_reads: self.delayed.load(Ordering::Relaxed),
+            shed_reads: self.shed.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(in_flight: usize, timeout_rate: f64, recent_calls: u64) -> ConductorLoad {
+        ConductorLoad {
+            in_flight,
+            timeout_rate,
+            recent_calls,
+        }
+    }
+
+    #[test]
+    fn test_low_priority_held_back_under_pressure() {
+        let policy = AdmissionPolicy::new(100, 0.2, Duration::from_millis(250));
+        let delay = Admission::Delay(Duration::from_millis(250));
+        let low = |in_flight, timeout_rate, recent_calls| {
+            policy.decide(
+                &load(in_flight, timeout_rate, recent_calls),
+                CallPriority::Low,
+            )
+        };
+
+        assert_eq!(low(10, 0.0, 500), Admission::Admit);
+        assert_eq!(low(60, 0.0, 500), delay);
+        assert_eq!(low(10, 0.12, 500), delay);
+        assert_eq!(low(100, 0.0, 500), Admission::Shed);
+        assert_eq!(low(10, 0.5, 500), Admission::Shed);
+        // A handful of timeouts isn't a rate yet
+        assert_eq!(low(10, 0.5, 4), Admission::Admit);
+
+        // Writes and signed-in users are never held back
+        assert_eq!(
+            policy.decide(&load(500, 1.0, 500), CallPriority::Normal),
+            Admission::Admit
+        );
+    }
+
+    #[test]
+    fn test_outcomes_age_out_of_window() {
+        let start = Instant::now();
+        let outcomes = CallOutcomes::new(start);
+        for i in 0..10 {
+            outcomes.record(i % 2 == 0, start + Duration::from_secs(i));
+        }
+        outcomes.record(false, start + Duration::from_secs(30));
+        assert_eq!(outcomes.totals(start + Duration::from_secs(30)), (11, 5));
+
+        // The first ten calls have left the window
+        assert_eq!(outcomes.totals(start + Duration::from_secs(65)), (1, 0));
+    }
+
+    #[tokio::test]
+    async fn test_shed_reads_are_counted() {
+        let policy = AdmissionPolicy::new(10, 0.2, Duration::from_millis(1));
+        assert!(policy
+            .admit(|| load(10, 0.0, 0), CallPriority::Low)
+            .await
+            .is_err());
+        assert!(policy
+            .admit(|| load(6, 0.0, 0), CallPriority::Low)
+            .await
+            .is_ok());
+        assert!(policy
+            .admit(|| load(10, 0.0, 0), CallPriority::Normal)
+            .await
+            .is_ok());
+
+        let stats = policy.stats(load(0, 0.0, 0));
+        assert_eq!((stats.delayed_reads, stats.shed_reads), (1, 1));
+        assert_eq!(stats.pressure, Pressure::Normal);
+    }
+}
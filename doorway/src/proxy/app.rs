@@ -1,30 +1,117 @@
 //! App interface proxy
 //!
 //! Simple passthrough WebSocket proxy for app interfaces.
-//! No message filtering needed - app interfaces handle their own auth.
+//! App interfaces handle their own auth; the only filtering is the
+//! operator's zome policy, applied by a [`CallGuard`].
+//! Messages to the client go through a prioritized [`OutboundQueue`], so
+//! signal floods can't hold back zome responses or exhaust memory, and a
+//! [heartbeat](crate::proxy::heartbeat) reaps clients that silently vanished.
+//! If the conductor connection drops, the proxy [reconnects](crate::proxy::reconnect)
+//! while holding the client's new calls.
 
 use futures_util::{SinkExt, StreamExt};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
 use tokio_tungstenite::{
     connect_async_with_config,
     tungstenite::{http::Request, protocol::Message},
+    MaybeTlsStream, WebSocketStream,
 };
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
+use crate::proxy::call_guard::CallGuard;
+use crate::proxy::heartbeat::{HeartbeatPolicy, Liveness, HEARTBEAT_PAYLOAD};
+use crate::proxy::outbound::{OutboundPolicy, OutboundQueue};
+use crate::proxy::reconnect::{self, HeldCalls, ReconnectPolicy};
 use crate::types::{DoorwayError, Result};
 
 type HyperWebSocket =
     hyper_tungstenite::WebSocketStream<hyper_util::rt::TokioIo<hyper::upgrade::Upgraded>>;
 
+type ConductorWebSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Client frames waiting to be written to the conductor
+const UPSTREAM_BUFFER: usize = 64;
+
+/// Open a connection to the conductor app interface
+async fn connect_app_interface(
+    app_url: &str,
+    conductor_host: &str,
+    port: u16,
+) -> Result<ConductorWebSocket> {
+    let request = Request::builder()
+        .uri(app_url)
+        .header("Host", format!("{conductor_host}:{port}"))
+        .header("Origin", "http://localhost")
+        .header("Connection", "Upgrade")
+        .header("Upgrade", "websocket")
+        .header("Sec-WebSocket-Version", "13")
+        .header(
+            "Sec-WebSocket-Key",
+            tokio_tungstenite::tungstenite::handshake::client::generate_key(),
+        )
+        .body(())
+        .map_err(|e| DoorwayError::Holochain(format!("Failed to build request: {e}")))?;
+
+    let (conductor_ws, _) = connect_async_with_config(request, None, false)
+        .await
+        .map_err(|e| DoorwayError::Holochain(format!("Failed to connect to app interface: {e}")))?;
+    Ok(conductor_ws)
+}
+
+/// Answer a call that can't be sent; false when the client fell too far behind
+fn refuse_call(
+    queue: &OutboundQueue,
+    outbound: &OutboundPolicy,
+    reconnect: &ReconnectPolicy,
+    call: &Message,
+) -> bool {
+    reconnect.record_expired();
+    let Some(response) = reconnect::unavailable_response(call) else {
+        return true;
+    };
+    if queue.push(response).is_err() {
+        outbound.record_slow_client();
+        return false;
+    }
+    true
+}
+
+/// Hold a client frame during an outage; false when the client fell too far behind
+fn hold_call(
+    held: &mut HeldCalls,
+    message: Message,
+    queue: &OutboundQueue,
+    outbound: &OutboundPolicy,
+    reconnect: &ReconnectPolicy,
+) -> bool {
+    // Pings and pongs lose their meaning once the conductor is back
+    if !matches!(message, Message::Binary(_) | Message::Text(_)) {
+        return true;
+    }
+    match held.push(message, Instant::now()) {
+        Ok(()) => true,
+        Err(call) => refuse_call(queue, outbound, reconnect, &call),
+    }
+}
+
 /// Run the app proxy between client and conductor app interface.
 ///
 /// `conductor_host` is the hostname of the conductor (e.g. "elohim-edgenode-alpha")
 /// extracted from CONDUCTOR_URL. Falls back to "localhost" for local dev.
+#[allow(clippy::too_many_arguments)]
 pub async fn run_proxy(
     client_ws: HyperWebSocket,
     port: u16,
     origin: Option<String>,
     query: Option<String>,
     conductor_host: &str,
+    outbound: Arc<OutboundPolicy>,
+    heartbeat: Arc<HeartbeatPolicy>,
+    reconnect: Arc<ReconnectPolicy>,
+    guard: CallGuard,
 ) -> Result<()> {
     // Build app interface URL using the conductor host (not hardcoded localhost)
     // Strip Doorway-specific params (apiKey) but keep conductor params
@@ -41,110 +128,266 @@ pub async fn run_proxy(
 
     info!("Creating app proxy to {} (origin: {:?})", app_url, origin);
 
-    // Connect to conductor app interface with proper headers
-    let request = Request::builder()
-        .uri(&app_url)
-        .header("Host", format!("{conductor_host}:{port}"))
-        .header("Origin", "http://localhost")
-        .header("Connection", "Upgrade")
-        .header("Upgrade", "websocket")
-        .header("Sec-WebSocket-Version", "13")
-        .header(
-            "Sec-WebSocket-Key",
-            tokio_tungstenite::tungstenite::handshake::client::generate_key(),
-        )
-        .body(())
-        .map_err(|e| DoorwayError::Holochain(format!("Failed to build request: {e}")))?;
-
-    let (conductor_ws, _) = connect_async_with_config(request, None, false)
-        .await
-        .map_err(|e| DoorwayError::Holochain(format!("Failed to connect to app interface: {e}")))?;
+    let conductor_ws = connect_app_interface(&app_url, conductor_host, port).await?;
 
     info!("Connected to app interface on port {}", port);
 
-    // Split both connections
     let (mut client_sink, mut client_stream) = client_ws.split();
-    let (mut conductor_sink, mut conductor_stream) = conductor_ws.split();
+    let (upstream_tx, mut upstream_rx) = mpsc::channel::<Message>(UPSTREAM_BUFFER);
+    let queue = OutboundQueue::new(Arc::clone(&outbound));
+    let liveness = Liveness::new();
 
-    // Bidirectional passthrough - no filtering for app interfaces
+    // Client frames go to the conductor connection through `upstream_tx`,
+    // less zome calls the policy denies
     let client_to_conductor = async {
         while let Some(msg) = client_stream.next().await {
-            match msg {
+            if msg.is_ok() {
+                liveness.touch();
+            }
+            let forward = match msg {
                 Ok(Message::Binary(data)) => {
-                    if let Err(e) = conductor_sink.send(Message::Binary(data)).await {
-                        error!("Failed to send to app interface: {}", e);
-                        break;
+                    if let Some(refusal) = guard.check(&data) {
+                        if queue.push(Message::Binary(refusal)).is_err() {
+                            outbound.record_slow_client();
+                            break;
+                        }
+                        continue;
                     }
+                    Message::Binary(data)
                 }
-                Ok(Message::Text(text)) => {
-                    if let Err(e) = conductor_sink.send(Message::Text(text)).await {
-                        error!("Failed to send text to app interface: {}", e);
-                        break;
-                    }
-                }
-                Ok(Message::Ping(data)) => {
-                    let _ = conductor_sink.send(Message::Ping(data)).await;
-                }
-                Ok(Message::Pong(data)) => {
-                    let _ = conductor_sink.send(Message::Pong(data)).await;
-                }
+                // Answers to our own heartbeat stay here
+                Ok(Message::Pong(data)) if data == HEARTBEAT_PAYLOAD => continue,
+                Ok(Message::Frame(_)) => continue,
                 Ok(Message::Close(frame)) => {
                     info!("Client closed app connection: {:?}", frame);
-                    let _ = conductor_sink.send(Message::Close(frame)).await;
+                    let _ = upstream_tx.send(Message::Close(frame)).await;
                     break;
                 }
-                Ok(Message::Frame(_)) => {}
+                Ok(msg) => msg,
                 Err(e) => {
                     error!("Client app WebSocket error: {}", e);
                     break;
                 }
+            };
+            if upstream_tx.send(forward).await.is_err() {
+                break;
             }
         }
     };
 
-    let conductor_to_client = async {
-        while let Some(msg) = conductor_stream.next().await {
-            match msg {
-                Ok(Message::Binary(data)) => {
-                    if let Err(e) = client_sink.send(Message::Binary(data)).await {
-                        error!("Failed to send to app client: {}", e);
-                        break;
+    // Own the conductor connection: write client frames, queue conductor
+    // messages and reconnect when it drops. False when the client fell too
+    // far behind
+    let conductor_link = async {
+        let mut conductor_ws = conductor_ws;
+        let mut authenticate: Option<Message> = None;
+        loop {
+            let (mut conductor_sink, mut conductor_stream) = conductor_ws.split();
+
+            // Passthrough until the connection drops
+            loop {
+                tokio::select! {
+                    msg = upstream_rx.recv() => {
+                        let Some(msg) = msg else { return true };
+                        if authenticate.is_none() && reconnect::is_authenticate(&msg) {
+                            authenticate = Some(msg.clone());
+                        }
+                        let closing = matches!(msg, Message::Close(_));
+                        if let Err(e) = conductor_sink.send(msg).await {
+                            error!("Failed to send to app interface: {}", e);
+                            break;
+                        }
+                        if closing {
+                            return true;
+                        }
                     }
+                    msg = conductor_stream.next() => match msg {
+                        Some(Ok(Message::Frame(_))) => {}
+                        Some(Ok(Message::Close(frame))) if reconnect.is_enabled() => {
+                            info!("App interface closed connection: {:?}", frame);
+                            break;
+                        }
+                        Some(Ok(msg)) => {
+                            let closing = matches!(msg, Message::Close(_));
+                            if closing {
+                                info!("App interface closed connection: {:?}", msg);
+                            }
+                            if queue.push(msg).is_err() {
+                                warn!("App client is not reading responses, disconnecting");
+                                outbound.record_slow_client();
+                                return false;
+                            }
+                            if closing {
+                                return true;
+                            }
+                        }
+                        Some(Err(e)) => {
+                            error!("App interface WebSocket error: {}", e);
+                            break;
+                        }
+                        None => break,
+                    }
+                }
+            }
+            drop((conductor_sink, conductor_stream));
+            if !reconnect.is_enabled() {
+                return true;
+            }
+
+            // Reconnect with backoff, holding calls made meanwhile
+            reconnect.record_drop();
+            let outage = Instant::now();
+            let mut held = reconnect.held_calls();
+            let mut attempt = 0;
+            conductor_ws = loop {
+                let delay = reconnect.delay(attempt);
+                attempt += 1;
+                warn!(
+                    "App interface on port {} unavailable, reconnect attempt {} in {:?}",
+                    port, attempt, delay
+                );
+                if queue
+                    .push(reconnect::degraded_frame(attempt, delay))
+                    .is_err()
+                {
+                    outbound.record_slow_client();
+                    return false;
                 }
-                Ok(Message::Text(text)) => {
-                    if let Err(e) = client_sink.send(Message::Text(text)).await {
-                        error!("Failed to send text to app client: {}", e);
-                        break;
+
+                let retry = tokio::time::sleep(delay);
+                tokio::pin!(retry);
+                loop {
+                    tokio::select! {
+                        _ = &mut retry => break,
+                        msg = upstream_rx.recv() => {
+                            let Some(msg) = msg else { return true };
+                            if matches!(msg, Message::Close(_)) {
+                                return true;
+                            }
+                            if authenticate.is_none() && reconnect::is_authenticate(&msg) {
+                                authenticate = Some(msg);
+                            } else if !hold_call(&mut held, msg, &queue, &outbound, &reconnect) {
+                                return false;
+                            }
+                        }
+                    }
+                    for call in held.expire(Instant::now()) {
+                        if !refuse_call(&queue, &outbound, &reconnect, &call) {
+                            return false;
+                        }
                     }
                 }
-                Ok(Message::Ping(data)) => {
-                    let _ = client_sink.send(Message::Ping(data)).await;
+
+                match connect_app_interface(&app_url, conductor_host, port).await {
+                    Ok(ws) => break ws,
+                    Err(e) => debug!("Reconnect to app interface failed: {}", e),
                 }
-                Ok(Message::Pong(data)) => {
-                    let _ = client_sink.send(Message::Pong(data)).await;
+            };
+
+            // Re-authenticate, then send what the client asked for meanwhile
+            let mut replay: Vec<Message> = authenticate.iter().cloned().collect();
+            let replayed = held.len();
+            replay.extend(held.drain());
+            for msg in replay {
+                if let Err(e) = conductor_ws.send(msg).await {
+                    error!("Failed to replay to app interface: {}", e);
+                    break;
                 }
-                Ok(Message::Close(frame)) => {
-                    info!("App interface closed connection: {:?}", frame);
-                    let _ = client_sink.send(Message::Close(frame)).await;
+            }
+            reconnect.record_recovery(replayed);
+            info!(
+                "Reconnected to app interface on port {} after {:?} ({} calls replayed)",
+                port,
+                outage.elapsed(),
+                replayed
+            );
+            let recovered = reconnect::recovered_frame(attempt, outage.elapsed(), replayed);
+            if queue.push(recovered).is_err() {
+                outbound.record_slow_client();
+                return false;
+            }
+        }
+    };
+
+    // Write queued messages to the client, highest priority first
+    let client_writer = async {
+        while let Some(msg) = queue.pop().await {
+            let closing = matches!(msg, Message::Close(_));
+            match tokio::time::timeout(outbound.send_timeout(), client_sink.send(msg)).await {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => {
+                    error!("Failed to send to app client: {}", e);
                     break;
                 }
-                Ok(Message::Frame(_)) => {}
-                Err(e) => {
-                    error!("App interface WebSocket error: {}", e);
+                Err(_) => {
+                    warn!(
+                        "App client took over {:?} to accept a message, disconnecting",
+                        outbound.send_timeout()
+                    );
+                    outbound.record_slow_client();
                     break;
                 }
             }
+            if closing {
+                break;
+            }
+        }
+    };
+    tokio::pin!(client_writer);
+
+    let outbound_done = async {
+        tokio::select! {
+            drain = conductor_link => {
+                // Let the client have what's already queued
+                queue.close();
+                if drain {
+                    (&mut client_writer).await;
+                }
+            }
+            _ = &mut client_writer => {}
+        }
+    };
+
+    // Ping the client and reap it once it has been silent too long
+    let heartbeat_loop = async {
+        if !heartbeat.is_enabled() {
+            return std::future::pending().await;
+        }
+        let mut ticker = tokio::time::interval(heartbeat.interval());
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            if liveness.is_dead(heartbeat.timeout(), Instant::now()) {
+                warn!(
+                    "App client silent for over {:?}, reaping connection",
+                    heartbeat.timeout()
+                );
+                heartbeat.record_reap();
+                break;
+            }
+            if queue
+                .push(Message::Ping(HEARTBEAT_PAYLOAD.to_vec()))
+                .is_err()
+            {
+                outbound.record_slow_client();
+                break;
+            }
+            heartbeat.record_ping();
         }
     };
 
-    // Run both directions concurrently
+    // Run both directions concurrently; dropping the conductor socket on
+    // return releases its app connection
     tokio::select! {
         _ = client_to_conductor => {
             debug!("App client->conductor stream ended");
         }
-        _ = conductor_to_client => {
+        _ = outbound_done => {
             debug!("App conductor->client stream ended");
         }
+        _ = heartbeat_loop => {
+            debug!("App client reaped by heartbeat");
+        }
     }
 
     info!("App proxy connection closed (port {})", port);
@@ -0,0 +1,205 @@
+//! Zome call authorization for app connections
+//!
+//! Checks every `call_zome` request a client sends through the app proxy
+//! against the operator's [zome policy](crate::auth::policy). Denied calls
+//! never reach the conductor; the client gets an error response for the
+//! request instead. Anything that isn't a recognizable zome call passes
+//! through, as before.
+
+use base64::Engine;
+use rmpv::Value;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::auth::policy::{Caller, PolicyStore, ZomeCall};
+use crate::proxy::holochain::{encode_error_response, get_field, parse_message, to_json};
+
+/// Error type reported to the client for denied calls
+pub const UNAUTHORIZED_ERROR: &str = "zome_call_unauthorized";
+
+/// Policy check for one client connection
+pub struct CallGuard {
+    policy: Arc<PolicyStore>,
+    caller: Caller,
+}
+
+/// The parts of a `call_zome` request a policy looks at
+#[derive(Debug, PartialEq)]
+struct CallZome {
+    id: u64,
+    zome_name: String,
+    fn_name: String,
+    provenance: Option<String>,
+    input: serde_json::Value,
+}
+
+impl CallGuard {
+    pub fn new(policy: Arc<PolicyStore>, caller: Caller) -> Self {
+        Self { policy, caller }
+    }
+
+    /// Check a client frame; returns the response to send back when the
+    /// call is denied
+    pub fn check(&self, data: &[u8]) -> Option<Vec<u8>> {
+        let call = parse_call_zome(data)?;
+        let denied = self
+            .policy
+            .check(
+                &self.caller,
+                &ZomeCall {
+                    zome_name: &call.zome_name,
+                    fn_name: &call.fn_name,
+                    provenance: call.provenance.as_deref(),
+                    input: &call.input,
+                },
+            )
+            .err()?;
+        warn!(
+            zome = %call.zome_name,
+            fn_name = %call.fn_name,
+            agent = ?self.caller.agent_pub_key,
+            rule = denied.rule,
+            "Zome call denied by policy"
+        );
+        Some(encode_error_response(
+            call.id,
+            UNAUTHORIZED_ERROR,
+            &format!("{}/{}: {denied}", call.zome_name, call.fn_name),
+        ))
+    }
+}
+
+/// Decode a `call_zome` request, either with its fields inline or signed
+/// as serialized `bytes`
+fn parse_call_zome(data: &[u8]) -> Option<CallZome> {
+    let parsed = parse_message(data).ok()?;
+    if parsed.operation != "call_zome" {
+        return None;
+    }
+    let signed_params;
+    let mut params = match &parsed.data {
+        Value::Map(map) => map,
+        _ => return None,
+    };
+    if let Some(Value::Binary(bytes)) = get_field(params, "bytes") {
+        signed_params = rmpv::decode::read_value(&mut bytes.as_slice()).ok()?;
+        params = match &signed_params {
+            Value::Map(map) => map,
+            _ => return None,
+        };
+    }
+
+    let string = |key: &str| get_field(params, key)?.as_str().map(str::to_string);
+    let input = match get_field(params, "payload") {
+        Some(Value::Binary(payload)) => rmpv::decode::read_value(&mut payload.as_slice())
+            .map(|value| to_json(&value))
+            .unwrap_or_default(),
+        _ => serde_json::Value::Null,
+    };
+    let provenance = match get_field(params, "provenance") {
+        Some(Value::Binary(key)) => Some(format!(
+            "u{}",
+            base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(key)
+        )),
+        _ => None,
+    };
+
+    Some(CallZome {
+        id: parsed.id?,
+        zome_name: string("zome_name")?,
+        fn_name: string("fn_name")?,
+        provenance,
+        input,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::policy::PolicySet;
+    use crate::auth::PermissionLevel;
+
+    fn encode(value: &Value) -> Vec<u8> {
+        let mut buf = Vec::new();
+        rmpv::encode::write_value(&mut buf, value).unwrap();
+        buf
+    }
+
+    fn map(entries: Vec<(&str, Value)>) -> Value {
+        Value::Map(
+            entries
+                .into_iter()
+                .map(|(k, v)| (Value::String(k.into()), v))
+                .collect(),
+        )
+    }
+
+    /// A signed call_zome request as @holochain/client sends it
+    fn call_zome(id: u64, fn_name: &str, reach: &str) -> Vec<u8> {
+        let payload = encode(&map(vec![("reach", Value::String(reach.into()))]));
+        let params = encode(&map(vec![
+            ("provenance", Value::Binary(vec![7; 39])),
+            ("zome_name", Value::String("content_store".into())),
+            ("fn_name", Value::String(fn_name.into())),
+            ("payload", Value::Binary(payload)),
+        ]));
+        let request = encode(&map(vec![
+            ("type", Value::String("call_zome".into())),
+            (
+                "value",
+                map(vec![
+                    ("bytes", Value::Binary(params)),
+                    ("signature", Value::Binary(vec![0; 64])),
+                ]),
+            ),
+        ]));
+        encode(&map(vec![
+            ("id", Value::from(id)),
+            ("type", Value::String("request".into())),
+            ("data", Value::Binary(request)),
+        ]))
+    }
+
+    #[test]
+    fn test_parse_signed_call_zome() {
+        let call = parse_call_zome(&call_zome(4, "create_content", "commons")).unwrap();
+        assert_eq!(call.id, 4);
+        assert_eq!(call.fn_name, "create_content");
+        assert_eq!(call.input["reach"], "commons");
+        assert!(call.provenance.unwrap().starts_with("uBwcH"));
+    }
+
+    #[test]
+    fn test_denied_call_gets_error_response() {
+        let policy = Arc::new(PolicyStore::new(None));
+        policy
+            .replace(
+                PolicySet::parse(
+                    r#"{"rules": [{"effect": "deny", "functions": ["create_content"],
+                        "when": ["input.reach == \"commons\""]}]}"#,
+                )
+                .unwrap(),
+            )
+            .unwrap();
+        let guard = CallGuard::new(
+            Arc::clone(&policy),
+            Caller {
+                level: PermissionLevel::Authenticated,
+                ..Default::default()
+            },
+        );
+
+        assert!(guard
+            .check(&call_zome(1, "create_content", "private"))
+            .is_none());
+        let response = guard
+            .check(&call_zome(2, "create_content", "commons"))
+            .unwrap();
+        let Value::Map(envelope) = rmpv::decode::read_value(&mut response.as_slice()).unwrap()
+        else {
+            panic!("Expected map");
+        };
+        assert_eq!(get_field(&envelope, "id").and_then(Value::as_u64), Some(2));
+        assert_eq!(policy.denied_count(), 1);
+    }
+}
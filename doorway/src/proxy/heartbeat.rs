@@ -0,0 +1,140 @@
+//! Client heartbeat and dead-connection reaping
+//!
+//! Mobile clients often vanish without a close frame, leaving half-open
+//! connections that hold a conductor app connection each. The app proxy pings
+//! every client every `WS_PING_INTERVAL_SECS`; any frame from the client
+//! counts as a sign of life. A client silent for `WS_LIVENESS_TIMEOUT_SECS`
+//! is reaped: both sockets are dropped, releasing the conductor connection.
+//! Reaps are counted in `GET /status`.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::config::Args;
+
+/// Payload of the doorway's own pings; matching pongs aren't forwarded
+pub const HEARTBEAT_PAYLOAD: &[u8] = b"doorway-heartbeat";
+
+/// Ping interval, liveness timeout and reap counters
+#[derive(Debug)]
+pub struct HeartbeatPolicy {
+    interval: Duration,
+    timeout: Duration,
+    pings_sent: AtomicU64,
+    reaped: AtomicU64,
+}
+
+/// Heartbeat settings and reaps as reported by `GET /status`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct HeartbeatStats {
+    pub enabled: bool,
+    pub ping_interval_secs: u64,
+    pub liveness_timeout_secs: u64,
+    pub pings_sent: u64,
+    pub connections_reaped: u64,
+}
+
+impl HeartbeatPolicy {
+    /// A zero interval disables the heartbeat. The timeout is at least two
+    /// intervals, so one lost pong doesn't reap a live client.
+    pub fn new(interval: Duration, timeout: Duration) -> Self {
+        Self {
+            interval,
+            timeout: timeout.max(interval * 2),
+            pings_sent: AtomicU64::new(0),
+            reaped: AtomicU64::new(0),
+        }
+    }
+
+    pub fn from_args(args: &Args) -> Self {
+        Self::new(
+            Duration::from_secs(args.ws_ping_interval_secs),
+            Duration::from_secs(args.ws_liveness_timeout_secs),
+        )
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.interval.is_zero()
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    pub fn record_ping(&self) {
+        self.pings_sent.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_reap(&self) {
+        self.reaped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn stats(&self) -> HeartbeatStats {
+        HeartbeatStats {
+            enabled: self.is_enabled(),
+            ping_interval_secs: self.interval.as_secs(),
+            liveness_timeout_secs: self.timeout.as_secs(),
+            pings_sent: self.pings_sent.load(Ordering::Relaxed),
+            connections_reaped: self.reaped.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// When a connection's client was last heard from
+#[derive(Debug)]
+pub struct Liveness {
+    last_seen: Mutex<Instant>,
+}
+
+impl Liveness {
+    pub fn new() -> Self {
+        Self {
+            last_seen: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Record a frame from the client
+    pub fn touch(&self) {
+        *self.last_seen.lock().unwrap() = Instant::now();
+    }
+
+    /// Whether the client has been silent for longer than `timeout`
+    pub fn is_dead(&self, timeout: Duration, now: Instant) -> bool {
+        now.duration_since(*self.last_seen.lock().unwrap()) > timeout
+    }
+}
+
+impl Default for Liveness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timeout_covers_two_intervals() {
+        let policy = HeartbeatPolicy::new(Duration::from_secs(30), Duration::from_secs(45));
+        assert_eq!(policy.timeout(), Duration::from_secs(60));
+        assert!(!HeartbeatPolicy::new(Duration::ZERO, Duration::from_secs(90)).is_enabled());
+    }
+
+    #[test]
+    fn test_liveness() {
+        let liveness = Liveness::new();
+        let timeout = Duration::from_secs(60);
+        let later = Instant::now() + Duration::from_secs(61);
+        assert!(!liveness.is_dead(timeout, Instant::now()));
+        assert!(liveness.is_dead(timeout, later));
+        liveness.touch();
+        assert!(!liveness.is_dead(timeout, Instant::now()));
+    }
+}
@@ -14,6 +14,8 @@ use crate::types::DoorwayError;
 /// Parsed Holochain admin message
 #[derive(Debug, Clone)]
 pub struct ParsedMessage {
+    /// Request ID from the client envelope, for correlating a response
+    pub id: Option<u64>,
     /// The operation type (e.g., "list_apps", "install_app")
     pub operation: String,
     /// The operation data (may be Nil for operations without data)
@@ -67,8 +69,17 @@ fn try_parse_client_envelope(
 
             if let Value::Map(ref inner_map) = inner {
                 if let Some(operation) = get_string_field(inner_map, "type") {
-                    let data = get_field(inner_map, "data").cloned().unwrap_or(Value::Nil);
-                    return Ok(Some(ParsedMessage { operation, data }));
+                    // Newer conductors carry the request body in "value"
+                    let data = get_field(inner_map, "data")
+                        .or_else(|| get_field(inner_map, "value"))
+                        .cloned()
+                        .unwrap_or(Value::Nil);
+                    let id = get_field(map, "id").and_then(Value::as_u64);
+                    return Ok(Some(ParsedMessage {
+                        id,
+                        operation,
+                        data,
+                    }));
                 }
             }
         }
@@ -86,7 +97,11 @@ fn try_parse_direct_message(map: &[(Value, Value)]) -> Result<Option<ParsedMessa
         }
 
         let data = get_field(map, "data").cloned().unwrap_or(Value::Nil);
-        return Ok(Some(ParsedMessage { operation, data }));
+        return Ok(Some(ParsedMessage {
+            id: None,
+            operation,
+            data,
+        }));
     }
 
     Ok(None)
@@ -107,7 +122,7 @@ fn get_string_field(map: &[(Value, Value)], key: &str) -> Option<String> {
 }
 
 /// Get a field from a MessagePack map
-fn get_field<'a>(map: &'a [(Value, Value)], key: &str) -> Option<&'a Value> {
+pub fn get_field<'a>(map: &'a [(Value, Value)], key: &str) -> Option<&'a Value> {
     for (k, v) in map {
         if let Value::String(k_str) = k {
             if k_str.as_str() == Some(key) {
@@ -136,6 +151,70 @@ pub fn encode_error(message: &str) -> Vec<u8> {
     buf
 }
 
+/// Encode a refusal of request `id` as a conductor error response, so
+/// @holochain/client rejects the pending call instead of timing out
+pub fn encode_error_response(id: u64, error_type: &str, message: &str) -> Vec<u8> {
+    let error = Value::Map(vec![
+        (Value::String("type".into()), Value::String("error".into())),
+        (
+            Value::String("value".into()),
+            Value::Map(vec![
+                (
+                    Value::String("type".into()),
+                    Value::String(error_type.into()),
+                ),
+                (Value::String("value".into()), Value::String(message.into())),
+            ]),
+        ),
+    ]);
+    let mut inner = Vec::new();
+    rmpv::encode::write_value(&mut inner, &error).unwrap_or_default();
+
+    let envelope = Value::Map(vec![
+        (
+            Value::String("type".into()),
+            Value::String("response".into()),
+        ),
+        (Value::String("id".into()), Value::from(id)),
+        (Value::String("data".into()), Value::Binary(inner)),
+    ]);
+    let mut buf = Vec::new();
+    rmpv::encode::write_value(&mut buf, &envelope).unwrap_or_default();
+    buf
+}
+
+/// Convert a MessagePack value to JSON. Binary becomes an array of bytes;
+/// non-string map keys are stringified.
+pub fn to_json(value: &Value) -> serde_json::Value {
+    use serde_json::Value as Json;
+    match value {
+        Value::Nil | Value::Ext(..) => Json::Null,
+        Value::Boolean(b) => Json::Bool(*b),
+        Value::Integer(i) => i
+            .as_i64()
+            .map(Json::from)
+            .or_else(|| i.as_u64().map(Json::from))
+            .unwrap_or(Json::Null),
+        Value::F32(f) => Json::from(*f as f64),
+        Value::F64(f) => Json::from(*f),
+        Value::String(s) => Json::String(s.as_str().unwrap_or_default().to_string()),
+        Value::Binary(bytes) => Json::from(bytes.clone()),
+        Value::Array(items) => Json::Array(items.iter().map(to_json).collect()),
+        Value::Map(entries) => Json::Object(
+            entries
+                .iter()
+                .map(|(k, v)| {
+                    let key = match k {
+                        Value::String(s) => s.as_str().unwrap_or_default().to_string(),
+                        other => other.to_string(),
+                    };
+                    (key, to_json(v))
+                })
+                .collect(),
+        ),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,7 +1,13 @@
 //! WebSocket proxy implementations for Doorway
 
 pub mod admin;
+pub mod admission;
 pub mod app;
+pub mod call_guard;
+pub mod heartbeat;
 pub mod holochain;
 pub mod nats;
+pub mod outbound;
 pub mod pool;
+pub mod presence;
+pub mod reconnect;
@@ -0,0 +1,282 @@
+//! Prioritized outbound queues for proxied WebSocket connections
+//!
+//! Messages from the conductor to a client are queued per connection in
+//! three lanes and written highest priority first:
+//!
+//! 1. **Responses** to the client's own zome calls (and control frames)
+//! 2. **Signals** the client subscribed to
+//! 3. **Notices**: anything else, such as broadcast notices
+//!
+//! Each lane is bounded. A signal flood drops the oldest queued signals, and
+//! notices beyond the limit are dropped as they arrive. Responses are never
+//! dropped: a client whose response lane fills up, or that can't take a
+//! message within `WS_SEND_TIMEOUT_SECS`, is disconnected as a slow consumer
+//! instead of letting its queue grow without bound. Drops and disconnects are
+//! counted in `GET /status`.
+
+use rmpv::ValueRef;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio_tungstenite::tungstenite::protocol::Message;
+
+use crate::config::Args;
+
+/// Outbound lane of a message, highest priority first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    Response = 0,
+    Signal = 1,
+    Notice = 2,
+}
+
+impl Priority {
+    /// Lane of a message from the conductor
+    ///
+    /// Holochain wraps everything it sends in a `{type, id?, data}` envelope;
+    /// only the envelope's `type` is read.
+    pub fn of(message: &Message) -> Self {
+        let data = match message {
+            Message::Binary(data) => data,
+            Message::Text(_) => return Self::Notice,
+            // Ping, pong and close keep their place among responses
+            _ => return Self::Response,
+        };
+        let Ok(ValueRef::Map(envelope)) = rmpv::decode::read_value_ref(&mut &data[..]) else {
+            return Self::Notice;
+        };
+        let kind = envelope.iter().find_map(|(key, value)| match (key, value) {
+            (ValueRef::String(key), ValueRef::String(value)) if key.as_str() == Some("type") => {
+                value.as_str()
+            }
+            _ => None,
+        });
+        match kind {
+            Some("response") | Some("error") => Self::Response,
+            Some("signal") => Self::Signal,
+            _ => Self::Notice,
+        }
+    }
+}
+
+/// Outbound queue sizes, send timeout and counters shared by all connections
+#[derive(Debug)]
+pub struct OutboundPolicy {
+    capacities: [usize; 3],
+    send_timeout: Duration,
+    dropped_signals: AtomicU64,
+    dropped_notices: AtomicU64,
+    slow_clients: AtomicU64,
+}
+
+/// Drops and disconnects as reported by `GET /status`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct OutboundStats {
+    pub response_queue: usize,
+    pub signal_queue: usize,
+    pub notice_queue: usize,
+    pub send_timeout_secs: u64,
+    pub dropped_signals: u64,
+    pub dropped_notices: u64,
+    pub slow_clients_disconnected: u64,
+}
+
+impl OutboundPolicy {
+    pub fn new(
+        response_queue: usize,
+        signal_queue: usize,
+        notice_queue: usize,
+        send_timeout: Duration,
+    ) -> Self {
+        Self {
+            capacities: [response_queue.max(1), signal_queue.max(1), notice_queue],
+            send_timeout,
+            dropped_signals: AtomicU64::new(0),
+            dropped_notices: AtomicU64::new(0),
+            slow_clients: AtomicU64::new(0),
+        }
+    }
+
+    pub fn from_args(args: &Args) -> Self {
+        Self::new(
+            args.ws_response_queue,
+            args.ws_signal_queue,
+            args.ws_notice_queue,
+            Duration::from_secs(args.ws_send_timeout_secs.max(1)),
+        )
+    }
+
+    /// Longest a client may take to accept one message
+    pub fn send_timeout(&self) -> Duration {
+        self.send_timeout
+    }
+
+    /// Count a client disconnected for not keeping up
+    pub fn record_slow_client(&self) {
+        self.slow_clients.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn stats(&self) -> OutboundStats {
+        OutboundStats {
+            response_queue: self.capacities[Priority::Response as usize],
+            signal_queue: self.capacities[Priority::Signal as usize],
+            notice_queue: self.capacities[Priority::Notice as usize],
+            send_timeout_secs: self.send_timeout.as_secs(),
+            dropped_signals: self.dropped_signals.load(Ordering::Relaxed),
+            dropped_notices: self.dropped_notices.load(Ordering::Relaxed),
+            slow_clients_disconnected: self.slow_clients.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// The client stopped reading: its response lane is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlowClient;
+
+struct Lanes {
+    lanes: [VecDeque<Message>; 3],
+    closed: bool,
+}
+
+/// One connection's outbound queue
+pub struct OutboundQueue {
+    lanes: Mutex<Lanes>,
+    ready: Notify,
+    policy: Arc<OutboundPolicy>,
+}
+
+impl OutboundQueue {
+    pub fn new(policy: Arc<OutboundPolicy>) -> Self {
+        Self {
+            lanes: Mutex::new(Lanes {
+                lanes: Default::default(),
+                closed: false,
+            }),
+            ready: Notify::new(),
+            policy,
+        }
+    }
+
+    /// Queue a message for the client, applying its lane's drop policy
+    pub fn push(&self, message: Message) -> Result<(), SlowClient> {
+        let priority = Priority::of(&message);
+        let capacity = self.policy.capacities[priority as usize];
+        {
+            let mut lanes = self.lanes.lock().unwrap();
+            let lane = &mut lanes.lanes[priority as usize];
+            if lane.len() >= capacity {
+                match priority {
+                    Priority::Response => return Err(SlowClient),
+                    Priority::Signal => {
+                        lane.pop_front();
+                        self.policy.dropped_signals.fetch_add(1, Ordering::Relaxed);
+                    }
+                    Priority::Notice => {
+                        self.policy.dropped_notices.fetch_add(1, Ordering::Relaxed);
+                        return Ok(());
+                    }
+                }
+            }
+            lane.push_back(message);
+        }
+        self.ready.notify_one();
+        Ok(())
+    }
+
+    /// Next message by priority, if any is queued
+    pub fn try_pop(&self) -> Option<Message> {
+        let mut lanes = self.lanes.lock().unwrap();
+        lanes.lanes.iter_mut().find_map(VecDeque::pop_front)
+    }
+
+    /// Wait for the next message; None once closed and drained
+    pub async fn pop(&self) -> Option<Message> {
+        loop {
+            if let Some(message) = self.try_pop() {
+                return Some(message);
+            }
+            if self.lanes.lock().unwrap().closed {
+                return None;
+            }
+            self.ready.notified().await;
+        }
+    }
+
+    /// No more messages will be queued
+    pub fn close(&self) {
+        self.lanes.lock().unwrap().closed = true;
+        self.ready.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmpv::Value;
+
+    fn envelope(kind: &str, id: u64) -> Message {
+        let value = Value::Map(vec![
+            (Value::String("type".into()), Value::String(kind.into())),
+            (Value::String("id".into()), Value::from(id)),
+            (Value::String("data".into()), Value::Binary(vec![0; 8])),
+        ]);
+        let mut buf = Vec::new();
+        rmpv::encode::write_value(&mut buf, &value).unwrap();
+        Message::Binary(buf)
+    }
+
+    fn queue(responses: usize, signals: usize, notices: usize) -> OutboundQueue {
+        OutboundQueue::new(Arc::new(OutboundPolicy::new(
+            responses,
+            signals,
+            notices,
+            Duration::from_secs(10),
+        )))
+    }
+
+    #[test]
+    fn test_priority_of() {
+        assert_eq!(Priority::of(&envelope("response", 1)), Priority::Response);
+        assert_eq!(Priority::of(&envelope("signal", 0)), Priority::Signal);
+        assert_eq!(Priority::of(&envelope("notice", 0)), Priority::Notice);
+        assert_eq!(Priority::of(&Message::Ping(vec![])), Priority::Response);
+        assert_eq!(Priority::of(&Message::Binary(vec![0xc1])), Priority::Notice);
+    }
+
+    #[test]
+    fn test_responses_jump_the_queue() {
+        let queue = queue(4, 4, 4);
+        queue.push(envelope("signal", 0)).unwrap();
+        queue.push(envelope("notice", 0)).unwrap();
+        queue.push(envelope("response", 7)).unwrap();
+
+        assert_eq!(queue.try_pop(), Some(envelope("response", 7)));
+        assert_eq!(queue.try_pop(), Some(envelope("signal", 0)));
+        assert_eq!(queue.try_pop(), Some(envelope("notice", 0)));
+        assert_eq!(queue.try_pop(), None);
+    }
+
+    #[test]
+    fn test_drop_policies() {
+        let queue = queue(1, 2, 1);
+        for id in 1..=3 {
+            queue.push(envelope("signal", id)).unwrap();
+        }
+        queue.push(envelope("notice", 1)).unwrap();
+        queue.push(envelope("notice", 2)).unwrap();
+
+        // Oldest signal and newest notice dropped
+        assert_eq!(queue.try_pop(), Some(envelope("signal", 2)));
+        assert_eq!(queue.try_pop(), Some(envelope("signal", 3)));
+        assert_eq!(queue.try_pop(), Some(envelope("notice", 1)));
+
+        queue.push(envelope("response", 1)).unwrap();
+        assert_eq!(queue.push(envelope("response", 2)), Err(SlowClient));
+
+        let stats = queue.policy.stats();
+        assert_eq!((stats.dropped_signals, stats.dropped_notices), (1, 1));
+    }
+}
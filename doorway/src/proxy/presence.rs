@@ -0,0 +1,511 @@
+//! Presence and co-learning rooms
+//!
+//! Learners viewing the same path, or the same step of a path, share a room.
+//! A client joins over `GET /presence` (WebSocket) and the doorway tells the
+//! room when members join, leave, or move: enough for "3 others are studying
+//! this chapter" and for following each other through a study session.
+//!
+//! Rooms live in this doorway's memory only. Nothing is written to the DHT,
+//! and members connected to different doorways don't see each other.
+//! Members are known by a per-connection id and the display name they choose
+//! to send, if any.
+//!
+//! ## Messages
+//!
+//! Client to doorway:
+//!
+//! - `{"type":"join","path_id":"...","step":"...","name":"..."}` - `step` and
+//!   `name` are optional; joining again moves to the new room
+//! - `{"type":"position","data":{...}}` - cursor or position, relayed as is
+//! - `{"type":"leave"}`
+//!
+//! Doorway to client: `joined` (the room's members), `join`, `leave`,
+//! `position` and `error`.
+//!
+//! Rooms hold at most `PRESENCE_MAX_ROOM_SIZE` members, and each member's
+//! positions are relayed at most `PRESENCE_POSITIONS_PER_SEC` times a second;
+//! faster ones are dropped.
+
+use dashmap::DashMap;
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tokio::sync::broadcast;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::debug;
+
+use crate::config::Args;
+
+/// Events a room buffers for a slow member before it starts skipping
+const ROOM_CHANNEL_CAPACITY: usize = 64;
+
+/// Longest display name kept, in characters
+const MAX_NAME_CHARS: usize = 64;
+
+/// Largest position payload relayed, in bytes of JSON
+const MAX_POSITION_BYTES: usize = 512;
+
+/// Largest client message read, in bytes
+const MAX_MESSAGE_BYTES: usize = 2 * 1024;
+
+type HyperWebSocket =
+    hyper_tungstenite::WebSocketStream<hyper_util::rt::TokioIo<hyper::upgrade::Upgraded>>;
+
+/// Presence errors
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum PresenceError {
+    #[error("Room is full ({0} members)")]
+    RoomFull(usize),
+    #[error("Invalid room: {0}")]
+    InvalidRoom(String),
+    #[error("Position data is limited to {0} bytes")]
+    PositionTooLarge(usize),
+}
+
+/// A path, or one step of it
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct RoomKey {
+    pub path_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub step: Option<String>,
+}
+
+impl RoomKey {
+    pub fn new(path_id: &str, step: Option<&str>) -> Result<Self, PresenceError> {
+        let path_id = path_id.trim();
+        if path_id.is_empty() || path_id.len() > 256 {
+            return Err(PresenceError::InvalidRoom(
+                "path_id must be 1-256 bytes".into(),
+            ));
+        }
+        let step = step.map(str::trim).filter(|s| !s.is_empty());
+        if step.is_some_and(|s| s.len() > 256) {
+            return Err(PresenceError::InvalidRoom(
+                "step must be at most 256 bytes".into(),
+            ));
+        }
+        Ok(Self {
+            path_id: path_id.to_string(),
+            step: step.map(str::to_string),
+        })
+    }
+}
+
+/// A member as the room sees them
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Member {
+    pub id: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// What the doorway sends to room members
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PresenceEvent {
+    /// Sent to a member on joining
+    Joined {
+        room: RoomKey,
+        you: u64,
+        members: Vec<Member>,
+    },
+    Join {
+        member: Member,
+    },
+    Leave {
+        member_id: u64,
+    },
+    Position {
+        member_id: u64,
+        data: serde_json::Value,
+    },
+    Error {
+        message: String,
+    },
+}
+
+impl PresenceEvent {
+    /// The member the event is about; members don't hear their own events
+    fn member_id(&self) -> Option<u64> {
+        match self {
+            Self::Join { member } => Some(member.id),
+            Self::Leave { member_id } | Self::Position { member_id, .. } => Some(*member_id),
+            Self::Joined { .. } | Self::Error { .. } => None,
+        }
+    }
+}
+
+/// What clients send
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientMessage {
+    Join {
+        path_id: String,
+        #[serde(default)]
+        step: Option<String>,
+        #[serde(default)]
+        name: Option<String>,
+    },
+    Position {
+        data: serde_json::Value,
+    },
+    Leave,
+}
+
+struct Room {
+    tx: broadcast::Sender<PresenceEvent>,
+    members: HashMap<u64, Member>,
+}
+
+/// A member's place in a room
+pub struct Membership {
+    pub member_id: u64,
+    pub room: RoomKey,
+    pub members: Vec<Member>,
+    pub events: broadcast::Receiver<PresenceEvent>,
+}
+
+/// Room sizes as reported by `GET /status`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct PresenceStats {
+    pub enabled: bool,
+    pub rooms: usize,
+    pub members: usize,
+    pub max_room_size: usize,
+}
+
+/// Rooms on this doorway
+pub struct PresenceHub {
+    rooms: DashMap<RoomKey, Room>,
+    max_room_size: usize,
+    position_gap: Duration,
+    next_member: AtomicU64,
+}
+
+impl PresenceHub {
+    /// A zero room size disables presence. A zero rate relays every position.
+    pub fn new(max_room_size: usize, positions_per_sec: u32) -> Self {
+        let position_gap = if positions_per_sec == 0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs(1) / positions_per_sec
+        };
+        Self {
+            rooms: DashMap::new(),
+            max_room_size,
+            position_gap,
+            next_member: AtomicU64::new(1),
+        }
+    }
+
+    pub fn from_args(args: &Args) -> Self {
+        Self::new(args.presence_max_room_size, args.presence_positions_per_sec)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.max_room_size > 0
+    }
+
+    /// Shortest time between two relayed positions of one member
+    pub fn position_gap(&self) -> Duration {
+        self.position_gap
+    }
+
+    /// Join a room, telling its members
+    pub fn join(&self, room: RoomKey, name: Option<&str>) -> Result<Membership, PresenceError> {
+        let name = name
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+            .map(|n| n.chars().take(MAX_NAME_CHARS).collect::<String>());
+        let mut entry = self.rooms.entry(room.clone()).or_insert_with(|| Room {
+            tx: broadcast::channel(ROOM_CHANNEL_CAPACITY).0,
+            members: HashMap::new(),
+        });
+        if entry.members.len() >= self.max_room_size {
+            return Err(PresenceError::RoomFull(self.max_room_size));
+        }
+
+        let member = Member {
+            id: self.next_member.fetch_add(1, Ordering::Relaxed),
+            name,
+        };
+        let events = entry.tx.subscribe();
+        entry.members.insert(member.id, member.clone());
+        let _ = entry.tx.send(PresenceEvent::Join {
+            member: member.clone(),
+        });
+        let mut members: Vec<Member> = entry.members.values().cloned().collect();
+        members.sort_by_key(|m| m.id);
+        Ok(Membership {
+            member_id: member.id,
+            room,
+            members,
+            events,
+        })
+    }
+
+    /// Leave a room, telling its members; the last one out closes it
+    pub fn leave(&self, room: &RoomKey, member_id: u64) {
+        let emptied = match self.rooms.get_mut(room) {
+            Some(mut entry) => {
+                if entry.members.remove(&member_id).is_some() {
+                    let _ = entry.tx.send(PresenceEvent::Leave { member_id });
+                }
+                entry.members.is_empty()
+            }
+            None => false,
+        };
+        if emptied {
+            self.rooms
+                .remove_if(room, |_, entry| entry.members.is_empty());
+        }
+    }
+
+    /// Relay a member's position to the room
+    pub fn position(
+        &self,
+        room: &RoomKey,
+        member_id: u64,
+        data: serde_json::Value,
+    ) -> Result<(), PresenceError> {
+        if serde_json::to_vec(&data).map_or(true, |b| b.len() > MAX_POSITION_BYTES) {
+            return Err(PresenceError::PositionTooLarge(MAX_POSITION_BYTES));
+        }
+        if let Some(entry) = self.rooms.get(room) {
+            if entry.members.contains_key(&member_id) {
+                let _ = entry.tx.send(PresenceEvent::Position { member_id, data });
+            }
+        }
+        Ok(())
+    }
+
+    /// Members in a room
+    pub fn count(&self, room: &RoomKey) -> usize {
+        self.rooms.get(room).map_or(0, |entry| entry.members.len())
+    }
+
+    pub fn stats(&self) -> PresenceStats {
+        let mut stats = PresenceStats {
+            enabled: self.is_enabled(),
+            max_room_size: self.max_room_size,
+            ..Default::default()
+        };
+        for entry in self.rooms.iter() {
+            stats.rooms += 1;
+            stats.members += entry.members.len();
+        }
+        stats
+    }
+}
+
+async fn send_event(
+    ws_write: &mut futures_util::stream::SplitSink<HyperWebSocket, Message>,
+    event: &PresenceEvent,
+) -> bool {
+    match serde_json::to_string(event) {
+        Ok(json) => ws_write.send(Message::Text(json)).await.is_ok(),
+        Err(_) => true,
+    }
+}
+
+fn error_event(message: impl ToString) -> PresenceEvent {
+    PresenceEvent::Error {
+        message: message.to_string(),
+    }
+}
+
+/// Act on a client message, returning the reply to send, if any
+fn handle_message(
+    hub: &PresenceHub,
+    membership: &mut Option<Membership>,
+    last_position: &mut Option<Instant>,
+    message: ClientMessage,
+) -> Option<PresenceEvent> {
+    match message {
+        ClientMessage::Join {
+            path_id,
+            step,
+            name,
+        } => {
+            if let Some(m) = membership.take() {
+                hub.leave(&m.room, m.member_id);
+            }
+            let joined = RoomKey::new(&path_id, step.as_deref())
+                .and_then(|room| hub.join(room, name.as_deref()));
+            match joined {
+                Ok(m) => {
+                    let reply = PresenceEvent::Joined {
+                        room: m.room.clone(),
+                        you: m.member_id,
+                        members: m.members.clone(),
+                    };
+                    *membership = Some(m);
+                    Some(reply)
+                }
+                Err(e) => Some(error_event(e)),
+            }
+        }
+        ClientMessage::Position { data } => {
+            let Some(m) = membership.as_ref() else {
+                return Some(error_event("Join a room first"));
+            };
+            let now = Instant::now();
+            if last_position.is_some_and(|at| now.duration_since(at) < hub.position_gap()) {
+                return None;
+            }
+            *last_position = Some(now);
+            hub.position(&m.room, m.member_id, data)
+                .err()
+                .map(error_event)
+        }
+        ClientMessage::Leave => {
+            if let Some(m) = membership.take() {
+                hub.leave(&m.room, m.member_id);
+            }
+            None
+        }
+    }
+}
+
+/// Serve one presence client until it disconnects
+pub async fn run_presence(ws: HyperWebSocket, hub: Arc<PresenceHub>) {
+    let (mut ws_write, mut ws_read) = ws.split();
+    let mut membership: Option<Membership> = None;
+    let mut last_position: Option<Instant> = None;
+
+    loop {
+        tokio::select! {
+            event = async {
+                match membership.as_mut() {
+                    Some(m) => m.events.recv().await,
+                    None => std::future::pending().await,
+                }
+            } => {
+                match event {
+                    Ok(event) => {
+                        let own = membership.as_ref().map(|m| m.member_id);
+                        if event.member_id() != own && !send_event(&mut ws_write, &event).await {
+                            break;
+                        }
+                    }
+                    // Missed positions are stale anyway
+                    Err(broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(broadcast::error::RecvError::Closed) => membership = None,
+                }
+            }
+
+            msg = ws_read.next() => {
+                let text = match msg {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_))) | None | Some(Err(_)) => break,
+                    Some(Ok(_)) => continue,
+                };
+                if text.len() > MAX_MESSAGE_BYTES {
+                    let event = error_event(format!(
+                        "Messages are limited to {MAX_MESSAGE_BYTES} bytes"
+                    ));
+                    if !send_event(&mut ws_write, &event).await {
+                        break;
+                    }
+                    continue;
+                }
+                let reply = match serde_json::from_str::<ClientMessage>(&text) {
+                    Ok(message) => {
+                        handle_message(&hub, &mut membership, &mut last_position, message)
+                    }
+                    Err(e) => Some(error_event(format!("Invalid message: {e}"))),
+                };
+                if let Some(reply) = reply {
+                    if !send_event(&mut ws_write, &reply).await {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(m) = membership {
+        hub.leave(&m.room, m.member_id);
+    }
+    debug!("Presence client disconnected");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chapter() -> RoomKey {
+        RoomKey::new("path-1", Some("chapter-3")).unwrap()
+    }
+
+    #[test]
+    fn test_join_leave_and_position() {
+        let hub = PresenceHub::new(2, 5);
+        let mut first = hub.join(chapter(), Some("Ada")).unwrap();
+        let second = hub.join(chapter(), None).unwrap();
+        assert_eq!(hub.count(&chapter()), 2);
+        assert_eq!(second.members.len(), 2);
+        assert_eq!(
+            hub.join(chapter(), None).err(),
+            Some(PresenceError::RoomFull(2))
+        );
+
+        // The first member hears their own join, then the second's
+        assert_eq!(
+            first.events.try_recv().unwrap().member_id(),
+            Some(first.member_id)
+        );
+        assert_eq!(
+            first.events.try_recv().unwrap(),
+            PresenceEvent::Join {
+                member: Member {
+                    id: second.member_id,
+                    name: None
+                }
+            }
+        );
+
+        hub.position(
+            &chapter(),
+            second.member_id,
+            serde_json::json!({"offset": 0.4}),
+        )
+        .unwrap();
+        assert!(matches!(
+            first.events.try_recv().unwrap(),
+            PresenceEvent::Position { member_id, .. } if member_id == second.member_id
+        ));
+        let oversized = serde_json::json!("x".repeat(MAX_POSITION_BYTES));
+        assert_eq!(
+            hub.position(&chapter(), second.member_id, oversized),
+            Err(PresenceError::PositionTooLarge(MAX_POSITION_BYTES))
+        );
+
+        hub.leave(&chapter(), second.member_id);
+        assert_eq!(
+            first.events.try_recv().unwrap(),
+            PresenceEvent::Leave {
+                member_id: second.member_id
+            }
+        );
+        hub.leave(&chapter(), first.member_id);
+        assert_eq!(hub.count(&chapter()), 0);
+        assert_eq!(hub.stats().rooms, 0);
+    }
+
+    #[test]
+    fn test_room_key() {
+        let path = RoomKey::new(" path-1 ", Some("  ")).unwrap();
+        assert_eq!(path, RoomKey::new("path-1", None).unwrap());
+        assert_ne!(path, chapter());
+        assert!(RoomKey::new("", None).is_err());
+        assert_eq!(
+            serde_json::to_string(&PresenceEvent::Leave { member_id: 7 }).unwrap(),
+            r#"{"type":"leave","member_id":7}"#
+        );
+    }
+}
@@ -0,0 +1,337 @@
+//! Conductor reconnect for app connections
+//!
+//! When the app proxy's connection to the conductor drops (conductor restart,
+//! network blip), the client connection is kept open and the proxy reconnects
+//! with exponential backoff. Calls the client makes meanwhile are held in a
+//! bounded queue and sent once the conductor is back; a call held longer than
+//! `CONDUCTOR_RECONNECT_HOLD_SECS`, or that doesn't fit in the queue, is
+//! answered with an `upstream_unavailable` error instead. The client's
+//! `authenticate` frame is replayed on every new connection.
+//!
+//! Clients are told with JSON text frames:
+//!
+//! ```text
+//! {"type":"upstream_degraded","attempt":1,"retry_in_ms":250}
+//! {"type":"upstream_recovered","attempts":3,"downtime_ms":2210,"replayed_calls":2}
+//! ```
+//!
+//! Calls already sent when the connection dropped are not replayed, since
+//! they may have run; the client sees `upstream_degraded` and its own call
+//! timeout. Drops, recoveries and held calls are counted in `GET /status`.
+//! A hold of 0 disables reconnecting: the client connection closes with the
+//! conductor's, as before.
+
+use rand::Rng;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio_tungstenite::tungstenite::protocol::Message;
+
+use crate::config::Args;
+use crate::proxy::holochain::{encode_error_response, parse_message};
+
+/// Error type reported to the client for calls that couldn't be sent
+pub const UPSTREAM_UNAVAILABLE_ERROR: &str = "upstream_unavailable";
+
+/// First reconnect delay
+const INITIAL_BACKOFF: Duration = Duration::from_millis(250);
+
+/// Longest delay between reconnect attempts
+const MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+/// Hold window, queue size and reconnect counters shared by all connections
+#[derive(Debug)]
+pub struct ReconnectPolicy {
+    hold: Duration,
+    queue: usize,
+    drops: AtomicU64,
+    recoveries: AtomicU64,
+    calls_replayed: AtomicU64,
+    calls_expired: AtomicU64,
+}
+
+/// Reconnect settings and counters as reported by `GET /status`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct ReconnectStats {
+    pub enabled: bool,
+    pub hold_secs: u64,
+    pub queue: usize,
+    pub upstream_drops: u64,
+    pub upstream_recoveries: u64,
+    pub calls_replayed: u64,
+    pub calls_expired: u64,
+}
+
+impl ReconnectPolicy {
+    pub fn new(hold: Duration, queue: usize) -> Self {
+        Self {
+            hold,
+            queue: queue.max(1),
+            drops: AtomicU64::new(0),
+            recoveries: AtomicU64::new(0),
+            calls_replayed: AtomicU64::new(0),
+            calls_expired: AtomicU64::new(0),
+        }
+    }
+
+    pub fn from_args(args: &Args) -> Self {
+        Self::new(
+            Duration::from_secs(args.conductor_reconnect_hold_secs),
+            args.conductor_reconnect_queue,
+        )
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.hold.is_zero()
+    }
+
+    /// Longest a call is held while the conductor is away
+    pub fn hold(&self) -> Duration {
+        self.hold
+    }
+
+    /// Base delay before reconnect attempt `attempt` (0-based)
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        INITIAL_BACKOFF
+            .saturating_mul(1 << attempt.min(16))
+            .min(MAX_BACKOFF)
+    }
+
+    /// Backoff with jitter, so clients of a restarted conductor don't all
+    /// reconnect at once
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let base = self.backoff(attempt);
+        base / 2 + base.mul_f64(rand::thread_rng().gen_range(0.0..0.5))
+    }
+
+    /// A call queue for one outage
+    pub fn held_calls(&self) -> HeldCalls {
+        HeldCalls {
+            calls: VecDeque::new(),
+            capacity: self.queue,
+            hold: self.hold,
+        }
+    }
+
+    pub fn record_drop(&self) {
+        self.drops.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_recovery(&self, replayed: usize) {
+        self.recoveries.fetch_add(1, Ordering::Relaxed);
+        self.calls_replayed
+            .fetch_add(replayed as u64, Ordering::Relaxed);
+    }
+
+    pub fn record_expired(&self) {
+        self.calls_expired.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn stats(&self) -> ReconnectStats {
+        ReconnectStats {
+            enabled: self.is_enabled(),
+            hold_secs: self.hold.as_secs(),
+            queue: self.queue,
+            upstream_drops: self.drops.load(Ordering::Relaxed),
+            upstream_recoveries: self.recoveries.load(Ordering::Relaxed),
+            calls_replayed: self.calls_replayed.load(Ordering::Relaxed),
+            calls_expired: self.calls_expired.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Calls a client made while the conductor was away
+#[derive(Debug)]
+pub struct HeldCalls {
+    calls: VecDeque<(Instant, Message)>,
+    capacity: usize,
+    hold: Duration,
+}
+
+impl HeldCalls {
+    /// Hold a call; gives it back when the queue is full
+    pub fn push(&mut self, message: Message, now: Instant) -> Result<(), Message> {
+        if self.calls.len() >= self.capacity {
+            return Err(message);
+        }
+        self.calls.push_back((now, message));
+        Ok(())
+    }
+
+    /// Remove calls held longer than the hold window
+    pub fn expire(&mut self, now: Instant) -> Vec<Message> {
+        let mut expired = Vec::new();
+        while let Some((held_at, _)) = self.calls.front() {
+            if now.duration_since(*held_at) <= self.hold {
+                break;
+            }
+            if let Some((_, message)) = self.calls.pop_front() {
+                expired.push(message);
+            }
+        }
+        expired
+    }
+
+    pub fn len(&self) -> usize {
+        self.calls.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    /// Held calls in the order they were made
+    pub fn drain(&mut self) -> Vec<Message> {
+        self.calls.drain(..).map(|(_, message)| message).collect()
+    }
+}
+
+/// Whether a client frame is the app interface `authenticate` message
+pub fn is_authenticate(message: &Message) -> bool {
+    match message {
+        Message::Binary(data) => {
+            parse_message(data).is_ok_and(|parsed| parsed.operation == "authenticate")
+        }
+        _ => false,
+    }
+}
+
+/// Error response for a call that couldn't be sent, if it's a request
+pub fn unavailable_response(message: &Message) -> Option<Message> {
+    let Message::Binary(data) = message else {
+        return None;
+    };
+    let id = parse_message(data).ok()?.id?;
+    Some(Message::Binary(encode_error_response(
+        id,
+        UPSTREAM_UNAVAILABLE_ERROR,
+        "Conductor unavailable, call was not sent",
+    )))
+}
+
+/// Status frame telling the client the conductor connection dropped
+pub fn degraded_frame(attempt: u32, retry_in: Duration) -> Message {
+    Message::Text(
+        serde_json::json!({
+            "type": "upstream_degraded",
+            "attempt": attempt,
+            "retry_in_ms": retry_in.as_millis() as u64,
+        })
+        .to_string(),
+    )
+}
+
+/// Status frame telling the client the conductor connection is back
+pub fn recovered_frame(attempts: u32, downtime: Duration, replayed_calls: usize) -> Message {
+    Message::Text(
+        serde_json::json!({
+            "type": "upstream_recovered",
+            "attempts": attempts,
+            "downtime_ms": downtime.as_millis() as u64,
+            "replayed_calls": replayed_calls,
+        })
+        .to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmpv::Value;
+
+    fn request(id: u64) -> Message {
+        let mut inner = Vec::new();
+        let call = Value::Map(vec![(
+            Value::String("type".into()),
+            Value::String("call_zome".into()),
+        )]);
+        rmpv::encode::write_value(&mut inner, &call).unwrap();
+        let envelope = Value::Map(vec![
+            (
+                Value::String("type".into()),
+                Value::String("request".into()),
+            ),
+            (Value::String("id".into()), Value::from(id)),
+            (Value::String("data".into()), Value::Binary(inner)),
+        ]);
+        let mut buf = Vec::new();
+        rmpv::encode::write_value(&mut buf, &envelope).unwrap();
+        Message::Binary(buf)
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let policy = ReconnectPolicy::new(Duration::from_secs(30), 64);
+        assert_eq!(policy.backoff(0), Duration::from_millis(250));
+        assert_eq!(policy.backoff(2), Duration::from_secs(1));
+        assert_eq!(policy.backoff(10), MAX_BACKOFF);
+        assert_eq!(policy.backoff(u32::MAX), MAX_BACKOFF);
+        for attempt in 0..6 {
+            let delay = policy.delay(attempt);
+            assert!(delay >= policy.backoff(attempt) / 2 && delay <= policy.backoff(attempt));
+        }
+        assert!(!ReconnectPolicy::new(Duration::ZERO, 64).is_enabled());
+    }
+
+    #[test]
+    fn test_held_calls_bounded_and_expire() {
+        let policy = ReconnectPolicy::new(Duration::from_secs(5), 2);
+        let mut held = policy.held_calls();
+        let start = Instant::now();
+        held.push(request(1), start).unwrap();
+        held.push(request(2), start + Duration::from_secs(3))
+            .unwrap();
+        assert_eq!(held.push(request(3), start), Err(request(3)));
+
+        let expired = held.expire(start + Duration::from_secs(6));
+        assert_eq!(expired, vec![request(1)]);
+        assert_eq!(held.drain(), vec![request(2)]);
+        assert!(held.is_empty());
+    }
+
+    #[test]
+    fn test_unavailable_response_answers_request() {
+        let Some(Message::Binary(response)) = unavailable_response(&request(42)) else {
+            panic!("expected an error response");
+        };
+        let parsed = rmpv::decode::read_value(&mut response.as_slice()).unwrap();
+        let Value::Map(envelope) = parsed else {
+            panic!("expected an envelope");
+        };
+        assert!(envelope.contains(&(Value::String("id".into()), Value::from(42u64))));
+        assert!(unavailable_response(&Message::Text("hello".into())).is_none());
+    }
+
+    #[test]
+    fn test_is_authenticate() {
+        let authenticate = Value::Map(vec![
+            (
+                Value::String("type".into()),
+                Value::String("authenticate".into()),
+            ),
+            (Value::String("data".into()), Value::Binary(vec![1, 2, 3])),
+        ]);
+        let mut buf = Vec::new();
+        rmpv::encode::write_value(&mut buf, &authenticate).unwrap();
+        assert!(is_authenticate(&Message::Binary(buf)));
+        assert!(!is_authenticate(&request(1)));
+    }
+
+    #[test]
+    fn test_status_frames() {
+        let Message::Text(degraded) = degraded_frame(1, Duration::from_millis(250)) else {
+            panic!("expected a text frame");
+        };
+        let degraded: serde_json::Value = serde_json::from_str(&degraded).unwrap();
+        assert_eq!(degraded["type"], "upstream_degraded");
+        assert_eq!(degraded["retry_in_ms"], 250);
+
+        let Message::Text(recovered) = recovered_frame(3, Duration::from_secs(2), 4) else {
+            panic!("expected a text frame");
+        };
+        let recovered: serde_json::Value = serde_json::from_str(&recovered).unwrap();
+        assert_eq!(recovered["type"], "upstream_recovered");
+        assert_eq!(recovered["replayed_calls"], 4);
+    }
+}
@@ -1117,6 +1117,7 @@ async fn handle_delete_user(
             doc! {
                 "$set": {
                     "metadata.is_deleted": true,
+                    "metadata.deleted_at": DateTime::now(),
                     "metadata.updated_at": DateTime::now(),
                     "is_active": false
                 }
@@ -0,0 +1,209 @@
+//! Learning Analytics API
+//!
+//! Operator-only read access to the anonymized per-path rollups produced by
+//! the [analytics worker](crate::worker::analytics). Only aggregates are
+//! served; small cohorts arrive already suppressed.
+//!
+//! ## Routes
+//!
+//! - `GET /analytics/paths` - Summary of every path (cohort, completion rate)
+//! - `GET /analytics/paths/{path_id}` - Funnel, step timing and score distribution
+//!
+//! Both require an admin token.
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use http_body_util::Full;
+use hyper::{Response, StatusCode};
+use serde::Serialize;
+use std::sync::Arc;
+use tracing::warn;
+
+use super::api::{error_response, json_response};
+use super::auth_helpers::require_admin;
+use crate::db::schemas::{FunnelStepRollup, PathAnalyticsRollupDoc, ANALYTICS_ROLLUP_COLLECTION};
+use crate::server::AppState;
+
+/// Which analytics resource a path addresses
+#[derive(Debug, PartialEq, Eq)]
+enum AnalyticsRoute<'a> {
+    Paths,
+    Path(&'a str),
+}
+
+fn parse_analytics_path(path: &str) -> Option<AnalyticsRoute<'_>> {
+    let rest = path.strip_prefix("/analytics/paths")?.trim_end_matches('/');
+    match rest.strip_prefix('/') {
+        None if rest.is_empty() => Some(AnalyticsRoute::Paths),
+        Some(path_id) if !path_id.is_empty() && !path_id.contains('/') => {
+            Some(AnalyticsRoute::Path(path_id))
+        }
+        _ => None,
+    }
+}
+
+/// Rollup as served to operators
+#[derive(Debug, Serialize)]
+struct PathRollupResponse<'a> {
+    path_id: &'a str,
+    path_title: &'a str,
+    learners_started: u32,
+    learners_completed: u32,
+    completion_rate: f64,
+    suppressed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    funnel: Option<&'a [FunnelStepRollup]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    avg_estimated_minutes_per_step: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    avg_actual_minutes_per_step: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    challenge_score_histogram: Option<&'a [u32]>,
+    challenges_completed: u32,
+    computed_at: Option<DateTime<Utc>>,
+}
+
+impl<'a> PathRollupResponse<'a> {
+    /// `detailed` includes the funnel and score histogram
+    fn new(rollup: &'a PathAnalyticsRollupDoc, detailed: bool) -> Self {
+        Self {
+            path_id: &rollup.path_id,
+            path_title: &rollup.path_title,
+            learners_started: rollup.learners_started,
+            learners_completed: rollup.learners_completed,
+            completion_rate: rollup.completion_rate(),
+            suppressed: rollup.suppressed,
+            funnel: detailed.then_some(rollup.funnel.as_slice()),
+            avg_estimated_minutes_per_step: rollup.avg_estimated_minutes_per_step,
+            avg_actual_minutes_per_step: rollup.avg_actual_minutes_per_step,
+            challenge_score_histogram: detailed
+                .then_some(rollup.challenge_score_histogram.as_slice()),
+            challenges_completed: rollup.challenges_completed,
+            computed_at: rollup.computed_at,
+        }
+    }
+}
+
+/// Handle GET /analytics/*
+pub async fn handle_analytics_request(
+    state: Arc<AppState>,
+    path: &str,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    if let Err(response) = require_admin(&state, auth_header.as_deref()) {
+        return response;
+    }
+
+    let Some(route) = parse_analytics_path(path) else {
+        return error_response(StatusCode::NOT_FOUND, "Not found", "NOT_FOUND");
+    };
+
+    let Some(ref mongo) = state.mongo else {
+        return error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Analytics storage not available",
+            "DATABASE_UNAVAILABLE",
+        );
+    };
+
+    let collection = match mongo
+        .collection::<PathAnalyticsRollupDoc>(ANALYTICS_ROLLUP_COLLECTION)
+        .await
+    {
+        Ok(collection) => collection,
+        Err(e) => {
+            warn!(error = %e, "Analytics collection unavailable");
+            return error_response(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Analytics storage not available",
+                "DATABASE_UNAVAILABLE",
+            );
+        }
+    };
+
+    match route {
+        AnalyticsRoute::Paths => match collection.find_many(bson::doc! {}).await {
+            Ok(mut rollups) => {
+                rollups.sort_by(|a, b| b.learners_started.cmp(&a.learners_started));
+                let paths: Vec<PathRollupResponse> = rollups
+                    .iter()
+                    .map(|rollup| PathRollupResponse::new(rollup, false))
+                    .collect();
+                json_response(
+                    serde_json::to_vec(&serde_json::json!({ "paths": paths })).unwrap_or_default(),
+                )
+            }
+            Err(e) => {
+                warn!(error = %e, "Failed to list analytics rollups");
+                error_response(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Failed to load analytics",
+                    "DATABASE_ERROR",
+                )
+            }
+        },
+        AnalyticsRoute::Path(path_id) => {
+            match collection.find_one(bson::doc! { "path_id": path_id }).await {
+                Ok(Some(rollup)) => json_response(
+                    serde_json::to_vec(&PathRollupResponse::new(&rollup, true)).unwrap_or_default(),
+                ),
+                Ok(None) => error_response(
+                    StatusCode::NOT_FOUND,
+                    "No analytics for this path yet",
+                    "NOT_FOUND",
+                ),
+                Err(e) => {
+                    warn!(path_id, error = %e, "Failed to load analytics rollup");
+                    error_response(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "Failed to load analytics",
+                        "DATABASE_ERROR",
+                    )
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_analytics_path() {
+        assert_eq!(
+            parse_analytics_path("/analytics/paths"),
+            Some(AnalyticsRoute::Paths)
+        );
+        assert_eq!(
+            parse_analytics_path("/analytics/paths/"),
+            Some(AnalyticsRoute::Paths)
+        );
+        assert_eq!(
+            parse_analytics_path("/analytics/paths/governance-101"),
+            Some(AnalyticsRoute::Path("governance-101"))
+        );
+        assert_eq!(parse_analytics_path("/analytics/paths/a/b"), None);
+        assert_eq!(parse_analytics_path("/analytics/learners"), None);
+    }
+
+    #[test]
+    fn test_summary_omits_details() {
+        let rollup = PathAnalyticsRollupDoc {
+            path_id: "p".to_string(),
+            learners_started: 10,
+            learners_completed: 4,
+            funnel: vec![FunnelStepRollup::default()],
+            challenge_score_histogram: vec![0; 10],
+            ..Default::default()
+        };
+
+        let summary = serde_json::to_value(PathRollupResponse::new(&rollup, false)).unwrap();
+        assert!(summary.get("funnel").is_none());
+        assert!(summary.get("challenge_score_histogram").is_none());
+        assert_eq!(summary["completion_rate"], 0.4);
+
+        let detail = serde_json::to_value(PathRollupResponse::new(&rollup, true)).unwrap();
+        assert_eq!(detail["funnel"].as_array().unwrap().len(), 1);
+    }
+}
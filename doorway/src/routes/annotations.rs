@@ -0,0 +1,371 @@
+//! Annotations
+//!
+//! Highlights and comments on ranges of content, as W3C Web Annotations (see
+//! [`web_annotation`](crate::services::web_annotation)). Annotations are
+//! private, public, or `cohort`: made while studying a path and shared with
+//! the learners and facilitator the author shares that path with.
+//!
+//! ## Routes
+//!
+//! - `POST /annotations` - Create from a Web Annotation; answers with it, `id` filled in
+//! - `GET /annotations?content_id=&path_id=` - An `AnnotationPage` of what the caller
+//!   sees on a content node; with `path_id`, the cohort layer for that path too
+//! - `GET /annotations/{id}` - One annotation the caller can see
+//! - `PUT /annotations/{id}` - Replace the body, target selector or visibility of your own
+//! - `DELETE /annotations/{id}` - Delete your own
+
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::Incoming;
+use hyper::{Request, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use super::api::error_response;
+use super::auth_helpers::require_user;
+use super::zome_helpers::call_content_store_for;
+use crate::server::AppState;
+use crate::services::web_annotation::{
+    annotation_page, parse_web_annotation, to_web_annotation, AnnotationOutput, ParsedAnnotation,
+    ANNO_CONTENT_TYPE,
+};
+
+/// Largest request body accepted
+const MAX_BODY_BYTES: usize = 16 * 1024;
+
+/// Parse `/annotations/{id}`
+pub fn parse_annotation_path(path: &str) -> Option<&str> {
+    let id = path.strip_prefix("/annotations/")?;
+    (!id.is_empty() && !id.contains('/')).then_some(id)
+}
+
+/// Must match CreateAnnotationInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct CreateAnnotationInput<'a> {
+    author_id: &'a str,
+    content_id: String,
+    path_id: Option<String>,
+    selector_json: String,
+    motivation: String,
+    body: String,
+    reply_to: Option<String>,
+    visibility: String,
+}
+
+/// Must match UpdateAnnotationInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct UpdateAnnotationInput<'a> {
+    annotation_id: &'a str,
+    author_id: &'a str,
+    body: Option<String>,
+    selector_json: Option<String>,
+    visibility: Option<String>,
+}
+
+/// Must match DeleteAnnotationInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct DeleteAnnotationInput<'a> {
+    annotation_id: &'a str,
+    author_id: &'a str,
+}
+
+/// Must match GetAnnotationInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct GetAnnotationInput<'a> {
+    annotation_id: &'a str,
+    viewer_id: &'a str,
+}
+
+/// Must match GetAnnotationsInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct GetAnnotationsInput<'a> {
+    content_id: String,
+    viewer_id: &'a str,
+    path_id: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ListParams {
+    content_id: Option<String>,
+    path_id: Option<String>,
+}
+
+/// Base of annotation and content IRIs
+fn iri_base(state: &AppState) -> &str {
+    state.args.doorway_url.as_deref().unwrap_or("")
+}
+
+fn annotation_response(status: StatusCode, body: &Value) -> Response<Full<Bytes>> {
+    Response::builder()
+        .status(status)
+        .header("Content-Type", ANNO_CONTENT_TYPE)
+        .body(Full::new(Bytes::from(
+            serde_json::to_vec(body).unwrap_or_default(),
+        )))
+        .unwrap()
+}
+
+/// An annotation from a zome answer, as a Web Annotation
+fn web_annotation_of(state: &AppState, data: Option<Value>) -> Option<Value> {
+    let output: AnnotationOutput = serde_json::from_value(data?).ok()?;
+    Some(to_web_annotation(&output.annotation, iri_base(state)))
+}
+
+/// Read and parse a Web Annotation request body
+#[allow(clippy::result_large_err)]
+async fn read_annotation(
+    req: Request<Incoming>,
+) -> Result<ParsedAnnotation, Response<Full<Bytes>>> {
+    let body = match Limited::new(req.into_body(), MAX_BODY_BYTES)
+        .collect()
+        .await
+    {
+        Ok(collected) => collected.to_bytes(),
+        Err(_) => {
+            return Err(error_response(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                &format!("Requests are limited to {MAX_BODY_BYTES} bytes"),
+                "TOO_LARGE",
+            ))
+        }
+    };
+    let annotation: Value = serde_json::from_slice(&body).map_err(|e| {
+        error_response(
+            StatusCode::BAD_REQUEST,
+            &format!("Invalid request: {e}"),
+            "INVALID_JSON",
+        )
+    })?;
+    parse_web_annotation(&annotation).map_err(|e| {
+        error_response(
+            StatusCode::BAD_REQUEST,
+            &e.to_string(),
+            "INVALID_ANNOTATION",
+        )
+    })
+}
+
+/// Handle POST /annotations
+pub async fn handle_create_annotation(
+    req: Request<Incoming>,
+    state: Arc<AppState>,
+) -> Response<Full<Bytes>> {
+    let auth_header = req
+        .headers()
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string());
+    let claims = match require_user(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+    let parsed = match read_annotation(req).await {
+        Ok(parsed) => parsed,
+        Err(response) => return response,
+    };
+
+    let input = CreateAnnotationInput {
+        author_id: &claims.human_id,
+        content_id: parsed.content_id,
+        path_id: parsed.path_id,
+        selector_json: parsed.selector_json,
+        motivation: parsed.motivation,
+        body: parsed.body,
+        reply_to: parsed.reply_to,
+        visibility: parsed.visibility.unwrap_or_else(|| "private".to_string()),
+    };
+    match call_content_store_for(&state, "create_annotation", &input, Some(&claims)).await {
+        Ok(data) => match web_annotation_of(&state, data) {
+            Some(web) => {
+                info!(author = %claims.human_id, visibility = %input.visibility, "Annotation created");
+                annotation_response(StatusCode::CREATED, &web)
+            }
+            None => error_response(
+                StatusCode::BAD_GATEWAY,
+                "Unexpected zome answer",
+                "ZOME_ERROR",
+            ),
+        },
+        Err(e) => {
+            warn!(author = %claims.human_id, error = ?e, "Failed to create annotation");
+            error_response(
+                StatusCode::BAD_REQUEST,
+                "Annotation not created (unknown content, path or annotation replied to, or invalid motivation or visibility)",
+                "ANNOTATE_FAILED",
+            )
+        }
+    }
+}
+
+/// Handle GET /annotations
+pub async fn handle_list_annotations(
+    state: Arc<AppState>,
+    query: Option<&str>,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    let claims = match require_user(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+    let params: ListParams = serde_urlencoded::from_str(query.unwrap_or("")).unwrap_or_default();
+    let Some(content_id) = params.content_id.filter(|id| !id.is_empty()) else {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "content_id is required",
+            "MISSING_CONTENT_ID",
+        );
+    };
+
+    let input = GetAnnotationsInput {
+        content_id,
+        viewer_id: &claims.human_id,
+        path_id: params.path_id.filter(|id| !id.is_empty()),
+    };
+    match call_content_store_for(&state, "get_annotations_for_content", &input, Some(&claims)).await
+    {
+        Ok(data) => {
+            let outputs: Vec<AnnotationOutput> = data
+                .and_then(|value| serde_json::from_value(value).ok())
+                .unwrap_or_default();
+            let annotations: Vec<_> = outputs.into_iter().map(|o| o.annotation).collect();
+            annotation_response(
+                StatusCode::OK,
+                &annotation_page(&annotations, iri_base(&state)),
+            )
+        }
+        Err(e) => {
+            warn!(error = ?e, "Failed to load annotations");
+            error_response(StatusCode::BAD_GATEWAY, "Lookup failed", "ZOME_ERROR")
+        }
+    }
+}
+
+/// Handle GET /annotations/{id}
+pub async fn handle_get_annotation(
+    state: Arc<AppState>,
+    annotation_id: &str,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    let claims = match require_user(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let input = GetAnnotationInput {
+        annotation_id,
+        viewer_id: &claims.human_id,
+    };
+    match call_content_store_for(&state, "get_annotation", &input, Some(&claims)).await {
+        Ok(data) => match web_annotation_of(&state, data) {
+            Some(web) => annotation_response(StatusCode::OK, &web),
+            None => error_response(StatusCode::NOT_FOUND, "Annotation not found", "NOT_FOUND"),
+        },
+        Err(e) => {
+            warn!(annotation_id = %annotation_id, error = ?e, "Failed to load annotation");
+            error_response(StatusCode::BAD_GATEWAY, "Lookup failed", "ZOME_ERROR")
+        }
+    }
+}
+
+/// Handle PUT /annotations/{id}
+pub async fn handle_update_annotation(
+    req: Request<Incoming>,
+    state: Arc<AppState>,
+    annotation_id: String,
+) -> Response<Full<Bytes>> {
+    let auth_header = req
+        .headers()
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string());
+    let claims = match require_user(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+    let parsed = match read_annotation(req).await {
+        Ok(parsed) => parsed,
+        Err(response) => return response,
+    };
+
+    // A reply's target is what it answers, which can't change
+    let is_reply = parsed.reply_to.is_some();
+    let input = UpdateAnnotationInput {
+        annotation_id: &annotation_id,
+        author_id: &claims.human_id,
+        body: Some(parsed.body),
+        selector_json: (!is_reply).then_some(parsed.selector_json),
+        visibility: parsed.visibility.filter(|_| !is_reply),
+    };
+    match call_content_store_for(&state, "update_annotation", &input, Some(&claims)).await {
+        Ok(data) => match web_annotation_of(&state, data) {
+            Some(web) => {
+                info!(annotation_id = %annotation_id, "Annotation updated");
+                annotation_response(StatusCode::OK, &web)
+            }
+            None => error_response(
+                StatusCode::BAD_GATEWAY,
+                "Unexpected zome answer",
+                "ZOME_ERROR",
+            ),
+        },
+        Err(e) => {
+            info!(annotation_id = %annotation_id, error = ?e, "Annotation not updated");
+            error_response(
+                StatusCode::CONFLICT,
+                "Annotation not updated (not found, not yours, or invalid visibility)",
+                "UPDATE_FAILED",
+            )
+        }
+    }
+}
+
+/// Handle DELETE /annotations/{id}
+pub async fn handle_delete_annotation(
+    state: Arc<AppState>,
+    annotation_id: &str,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    let claims = match require_user(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let input = DeleteAnnotationInput {
+        annotation_id,
+        author_id: &claims.human_id,
+    };
+    match call_content_store_for(&state, "delete_annotation", &input, Some(&claims)).await {
+        Ok(_) => {
+            info!(annotation_id = %annotation_id, "Annotation deleted");
+            Response::builder()
+                .status(StatusCode::NO_CONTENT)
+                .body(Full::new(Bytes::new()))
+                .unwrap()
+        }
+        Err(e) => {
+            info!(annotation_id = %annotation_id, error = ?e, "Annotation not deleted");
+            error_response(
+                StatusCode::CONFLICT,
+                "Annotation not deleted (not found, or not yours)",
+                "DELETE_FAILED",
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_annotation_path() {
+        assert_eq!(
+            parse_annotation_path("/annotations/annotation-uhCAk-1"),
+            Some("annotation-uhCAk-1")
+        );
+        assert_eq!(parse_annotation_path("/annotations/"), None);
+        assert_eq!(parse_annotation_path("/annotations/a/b"), None);
+    }
+}
@@ -6,7 +6,21 @@
 //! ## Routes
 //!
 //! - `GET /api/v1/cache/{type}/{id}` - Get cached document by type and ID
-//! - `GET /api/v1/cache/{type}` - Query cached documents by type
+//! - `GET /api/v1/cache/{type}?search=&limit=&cursor=` - Query cached documents
+//!   by type, optionally matching search terms
+//!
+//! Both take `?fields=` to return only some fields of each document (see
+//! [`fields`](super::fields)).
+//!
+//! A resolved `Content` document counts as a view for [content access
+//! logging](crate::services::content_access) when a steward tracks it.
+//!
+//! [Guest sessions](super::guest) only get `commons` and `public` documents
+//! from the projection; their misses never reach the conductor.
+//!
+//! Collection queries are paged like every listing (see
+//! [`pagination`](super::pagination)), but keep their bare-array body for
+//! existing clients: the cursors travel in the `Link` header only.
 //!
 //! ## Architecture
 //!
@@ -23,8 +37,11 @@
 //! Access control happens in the DNA layer which has the full context
 //! of reach levels, governance rules, and identity relationships.
 
+use bson::{doc, Document};
 use bytes::Bytes;
+use chrono::Utc;
 use http_body_util::Full;
+use hyper::header::{HeaderValue, RETRY_AFTER};
 use hyper::{Response, StatusCode};
 use serde::Serialize;
 use std::collections::HashMap;
@@ -32,8 +49,13 @@ use std::net::IpAddr;
 use std::sync::Arc;
 use tracing::{debug, info, warn};
 
+use super::content_body::{is_public, PUBLIC_REACH};
+use super::fields::FieldSelection;
+use super::guest::guest_read;
+use super::pagination::{array_page_response, Page, PageRequest};
 use crate::projection::ProjectionQuery;
-use crate::server::AppState;
+use crate::server::{staging, AppState};
+use crate::types::DoorwayError;
 use crate::worker::RequesterIdentity;
 
 /// API error response
@@ -70,7 +92,7 @@ impl<'a> CacheRoute<'a> {
 }
 
 /// Build a JSON error response
-fn error_response(status: StatusCode, message: &str, code: &'static str) -> Response<Full<Bytes>> {
+pub(crate) fn error_response(status: StatusCode, message: &str, code: &'static str) -> Response<Full<Bytes>> {
     let error = ApiError {
         error: message.to_string(),
         code,
@@ -91,8 +113,24 @@ fn error_response(status: StatusCode, message: &str, code: &'static str) -> Resp
         })
 }
 
+/// Build the `503` for a call shed while the conductor is under pressure,
+/// asking the client to retry shortly
+pub(crate) fn overloaded_response(message: &str) -> Response<Full<Bytes>> {
+    let mut response = error_response(StatusCode::SERVICE_UNAVAILABLE, message, "OVERLOADED");
+    response
+        .headers_mut()
+        .insert(RETRY_AFTER, HeaderValue::from_static("1"));
+    response
+}
+
+/// Build the response for a feature this doorway isn't set up for; `message`
+/// says what it needs
+pub(crate) fn not_enabled_response(status: StatusCode, message: &str) -> Response<Full<Bytes>> {
+    error_response(status, message, "NOT_ENABLED")
+}
+
 /// Build successful JSON response
-fn json_response(data: Vec<u8>) -> Response<Full<Bytes>> {
+pub(crate) fn json_response(data: Vec<u8>) -> Response<Full<Bytes>> {
     Response::builder()
         .status(StatusCode::OK)
         .header("Content-Type", "application/json")
@@ -104,21 +142,25 @@ fn json_response(data: Vec<u8>) -> Response<Full<Bytes>> {
         .unwrap()
 }
 
-/// Parse query string into key-value map
-fn parse_query_params(query: &str) -> HashMap<String, String> {
-    if query.is_empty() {
-        return HashMap::new();
-    }
+/// Build a JSON response meant for one signed-in human only
+///
+/// Shared caches must not hand it to another caller, hence `Vary: Authorization`
+/// alongside the caller's `Cache-Control` (`private, ...`).
+pub(crate) fn private_json_response(body: &serde_json::Value, cache_control: &str) -> Response<Full<Bytes>> {
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", "application/json")
+        .header("Cache-Control", cache_control)
+        .header("Vary", "Authorization")
+        .body(Full::new(Bytes::from(serde_json::to_vec(body).unwrap_or_default())))
+        .unwrap()
+}
 
-    query
-        .split('&')
-        .filter_map(|pair| {
-            let mut parts = pair.splitn(2, '=');
-            let key = parts.next()?;
-            let value = parts.next().unwrap_or("");
-            Some((key.to_string(), value.to_string()))
-        })
-        .collect()
+/// Parse query string into key-value map
+///
+/// Values are percent-decoded, so search terms arrive as typed.
+pub(crate) fn parse_query_params(query: &str) -> HashMap<String, String> {
+    serde_urlencoded::from_str(query).unwrap_or_default()
 }
 
 /// Parse requester identity from auth header
@@ -152,6 +194,39 @@ fn parse_requester_identity(auth_header: Option<&str>) -> Option<RequesterIdenti
     }
 }
 
+/// Projection filter matching what [`is_public`] allows
+fn public_reach_filter() -> Document {
+    let public = PUBLIC_REACH.to_vec();
+    doc! { "$or": [
+        { "reach": { "$in": &public } },
+        { "reach": null, "data.reach": { "$in": &public } },
+    ] }
+}
+
+/// Respond with a resolved document, counting views of tracked content
+fn document_response(
+    state: &AppState,
+    doc_type: &str,
+    id: &str,
+    data: serde_json::Value,
+    fields: Option<&FieldSelection>,
+    referer: Option<&str>,
+) -> Response<Full<Bytes>> {
+    if doc_type == "Content" {
+        if let Some(ref access) = state.content_access {
+            access.record(id, referer, Utc::now());
+        }
+    }
+
+    // Return whatever the DNA returned - no interpretation
+    let data = match fields {
+        Some(fields) => fields.apply_to_content(&data),
+        None => data,
+    };
+    let response = serde_json::to_vec(&data).unwrap_or_default();
+    json_response(response)
+}
+
 /// Handle GET /api/v1/cache/{type}/{id} or /api/v1/cache/{type}
 ///
 /// Thin HTTP gateway. Doorway does NOT interpret response bodies or enforce
@@ -165,6 +240,7 @@ pub async fn handle_api_request(
     query: Option<&str>,
     _remote_addr: Option<IpAddr>,
     auth_header: Option<String>,
+    referer: Option<String>,
 ) -> Response<Full<Bytes>> {
     // Parse cache route
     let route = match CacheRoute::parse(path) {
@@ -178,6 +254,25 @@ pub async fn handle_api_request(
         }
     };
 
+    // The projection only mirrors production
+    if staging::is_active() {
+        return error_response(
+            StatusCode::CONFLICT,
+            "The cache API serves production data and is not available to staging requests",
+            "STAGING_UNAVAILABLE",
+        );
+    }
+
+    let fields = match FieldSelection::from_query(query) {
+        Ok(fields) => fields,
+        Err(msg) => return error_response(StatusCode::BAD_REQUEST, &msg, "INVALID_QUERY"),
+    };
+
+    let guest = match guest_read(&state, auth_header.as_deref()) {
+        Ok(guest) => guest,
+        Err(response) => return response,
+    };
+
     // Parse requester identity from auth header (passed to DNA for access control)
     let requester = parse_requester_identity(auth_header.as_deref());
 
@@ -192,6 +287,35 @@ pub async fn handle_api_request(
     // Doorway is type-agnostic: passes type string through to projection/conductor
     // Access control happens in the DNA layer, not here
     if let Some(id) = route.doc_id {
+        if guest {
+            let doc = match &state.projection {
+                Some(projection) => projection.get(route.doc_type, id).await,
+                None => None,
+            };
+            return match doc.filter(is_public) {
+                Some(doc) => {
+                    debug!(
+                        doc_type = route.doc_type,
+                        id = id,
+                        "Guest read from projection"
+                    );
+                    document_response(
+                        &state,
+                        route.doc_type,
+                        id,
+                        doc.data,
+                        fields.as_ref(),
+                        referer.as_deref(),
+                    )
+                }
+                None => error_response(
+                    StatusCode::NOT_FOUND,
+                    &format!("Not found: {}/{}", route.doc_type, id),
+                    "NOT_FOUND",
+                ),
+            };
+        }
+
         // Generic resolution for any type (tiered: projection → conductor)
         // Identity passed through for DNA-level access control
         let result = state
@@ -208,11 +332,16 @@ pub async fn handle_api_request(
                     duration_ms = resolution.duration_ms,
                     "Document resolved"
                 );
-
-                // Return whatever the DNA returned - no interpretation
-                let response = serde_json::to_vec(&resolution.data).unwrap_or_default();
-                json_response(response)
+                document_response(
+                    &state,
+                    route.doc_type,
+                    id,
+                    resolution.data,
+                    fields.as_ref(),
+                    referer.as_deref(),
+                )
             }
+            Err(DoorwayError::Overloaded(msg)) => overloaded_response(&msg),
             Err(e) => {
                 debug!(doc_type = route.doc_type, id = id, error = ?e, "Resolution failed");
                 // TODO: Distinguish NotFound vs AccessDenied from conductor errors
@@ -237,23 +366,36 @@ pub async fn handle_api_request(
         }
     };
 
+    let page = match PageRequest::from_query(query, 100, 1000) {
+        Ok(page) => page,
+        Err(msg) => return error_response(StatusCode::BAD_REQUEST, &msg, "INVALID_QUERY"),
+    };
     let params = parse_query_params(query.unwrap_or(""));
-    let limit = params
-        .get("limit")
-        .and_then(|v| v.parse::<i64>().ok())
-        .unwrap_or(100)
-        .min(1000);
 
     // TODO: Pass requester identity for access-filtered queries
-    let proj_query = ProjectionQuery::by_type(route.doc_type).with_limit(limit);
+    let mut proj_query = ProjectionQuery::by_type(route.doc_type)
+        .with_limit(page.probe_limit() as i64)
+        .with_skip(page.offset as u64);
+    if guest {
+        proj_query = proj_query.with_filter(public_reach_filter());
+    }
+    if let Some(search) = params.get("search").filter(|s| !s.trim().is_empty()) {
+        proj_query = proj_query.with_search(search.as_str());
+    }
 
     match projection.query(proj_query).await {
-        Ok(docs) => {
+        Ok(mut docs) => {
             // Return whatever projection returned - no filtering here
             // Access control should happen at projection query level
-            let data: Vec<_> = docs.iter().map(|doc| &doc.data).collect();
-            let response = serde_json::to_vec(&data).unwrap_or_default();
-            json_response(response)
+            if guest {
+                // The in-memory store ignores custom filters
+                docs.retain(is_public);
+            }
+            let data: Vec<_> = match fields {
+                Some(ref fields) => docs.iter().map(|doc| fields.apply(&doc.data)).collect(),
+                None => docs.into_iter().map(|doc| doc.data).collect(),
+            };
+            array_page_response(&Page::from_probe(data, page))
         }
         Err(e) => {
             warn!("Projection query failed: {}", e);
@@ -309,4 +451,25 @@ mod tests {
         let resp = error_response(StatusCode::NOT_FOUND, "Test error", "TEST_ERROR");
         assert_eq!(resp.status(), StatusCode::NOT_FOUND);
     }
+
+    #[test]
+    fn test_overloaded_response() {
+        let resp = overloaded_response("Conductor under pressure");
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(resp.headers().get(RETRY_AFTER).unwrap(), "1");
+    }
+
+    #[test]
+    fn test_not_enabled_response() {
+        let resp = not_enabled_response(StatusCode::SERVICE_UNAVAILABLE, "Requires MongoDB");
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[test]
+    fn test_private_json_response() {
+        let resp = private_json_response(&serde_json::json!([]), "private, no-store");
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers().get("Cache-Control").unwrap(), "private, no-store");
+        assert_eq!(resp.headers().get("Vary").unwrap(), "Authorization");
+    }
 }
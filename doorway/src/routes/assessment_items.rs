@@ -0,0 +1,157 @@
+//! Question Bank Review API
+//!
+//! Steward review of unpublished assessment items, including the drafts
+//! written by the [question generator](crate::worker::question_generation).
+//! Mastery challenges only use published items.
+//!
+//! ## Routes
+//!
+//! - `GET /steward/assessment-items` - Items awaiting review, oldest first
+//! - `POST /steward/assessment-items/{id}/approve` - Publish an item
+//! - `POST /steward/assessment-items/{id}/reject` - Remove an item from the bank
+//!
+//! Requires a steward or admin token.
+
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::{Response, StatusCode};
+use serde::Serialize;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use super::api::{error_response, json_response};
+use super::auth_helpers::require_steward;
+use super::zome_helpers::call_content_store_for;
+use crate::server::AppState;
+
+/// Review decision on an item
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReviewAction {
+    Approve,
+    Reject,
+}
+
+impl ReviewAction {
+    fn zome_fn(self) -> &'static str {
+        match self {
+            Self::Approve => "publish_assessment_item",
+            Self::Reject => "reject_assessment_item",
+        }
+    }
+}
+
+/// Parse `/steward/assessment-items/{id}/{approve|reject}`
+pub fn parse_review_path(path: &str) -> Option<(&str, ReviewAction)> {
+    let rest = path.strip_prefix("/steward/assessment-items/")?;
+    let (id, action) = rest.rsplit_once('/')?;
+    let action = match action {
+        "approve" => ReviewAction::Approve,
+        "reject" => ReviewAction::Reject,
+        _ => return None,
+    };
+    (!id.is_empty() && !id.contains('/')).then_some((id, action))
+}
+
+/// Must match ReviewAssessmentItemInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct ReviewAssessmentItemInput {
+    item_id: String,
+    reviewer_id: Option<String>,
+}
+
+/// Handle GET /steward/assessment-items
+pub async fn handle_pending_assessment_items(
+    state: Arc<AppState>,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    let claims = match require_steward(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    match call_content_store_for(&state, "get_pending_assessment_items", &(), Some(&claims)).await {
+        Ok(data) => {
+            let data = data.unwrap_or_else(|| serde_json::json!([]));
+            Response::builder()
+                .status(StatusCode::OK)
+                .header("Content-Type", "application/json")
+                .header("Cache-Control", "private, no-store")
+                .body(Full::new(Bytes::from(
+                    serde_json::to_vec(&data).unwrap_or_default(),
+                )))
+                .unwrap()
+        }
+        Err(e) => {
+            warn!(error = ?e, "Failed to list pending assessment items");
+            error_response(StatusCode::BAD_GATEWAY, "Lookup failed", "ZOME_ERROR")
+        }
+    }
+}
+
+/// Handle POST /steward/assessment-items/{id}/{approve|reject}
+pub async fn handle_review_assessment_item(
+    state: Arc<AppState>,
+    item_id: &str,
+    action: ReviewAction,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    let claims = match require_steward(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let input = ReviewAssessmentItemInput {
+        item_id: urlencoding::decode(item_id)
+            .map(|id| id.into_owned())
+            .unwrap_or_else(|_| item_id.to_string()),
+        reviewer_id: Some(claims.human_id.clone()),
+    };
+    // Goes through the pool so cached question bank reads are invalidated
+    match call_content_store_for(&state, action.zome_fn(), &input, Some(&claims)).await {
+        Ok(data) => {
+            info!(
+                item_id = %input.item_id,
+                action = ?action,
+                reviewer = %claims.human_id,
+                "Assessment item reviewed"
+            );
+            json_response(
+                serde_json::to_vec(&data.unwrap_or(serde_json::Value::Null)).unwrap_or_default(),
+            )
+        }
+        Err(e) => {
+            warn!(item_id = %input.item_id, error = ?e, "Failed to review assessment item");
+            error_response(
+                StatusCode::BAD_GATEWAY,
+                "Failed to review item (is it still awaiting review?)",
+                "ZOME_ERROR",
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_review_path() {
+        assert_eq!(
+            parse_review_path("/steward/assessment-items/assessment-intro-17/approve"),
+            Some(("assessment-intro-17", ReviewAction::Approve))
+        );
+        assert_eq!(
+            parse_review_path("/steward/assessment-items/assessment-intro-17/reject"),
+            Some(("assessment-intro-17", ReviewAction::Reject))
+        );
+        assert_eq!(
+            parse_review_path("/steward/assessment-items/x/publish"),
+            None
+        );
+        assert_eq!(
+            parse_review_path("/steward/assessment-items/a/b/approve"),
+            None
+        );
+        assert_eq!(parse_review_path("/steward/assessment-items"), None);
+    }
+}
@@ -0,0 +1,85 @@
+//! Auth Helpers - Bearer token checks for HTTP route handlers
+//!
+//! Each check validates the `Authorization` header against the doorway's JWT
+//! settings and returns the caller's claims, or the error response to send.
+
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::{Response, StatusCode};
+
+use super::api::error_response;
+use crate::auth::{extract_token_from_header, Claims, JwtValidator, PermissionLevel};
+use crate::server::AppState;
+
+/// Validate the bearer token
+#[allow(clippy::result_large_err)]
+pub(crate) fn require_user(
+    state: &AppState,
+    auth_header: Option<&str>,
+) -> Result<Claims, Response<Full<Bytes>>> {
+    let token = extract_token_from_header(auth_header)
+        .ok_or_else(|| error_response(StatusCode::UNAUTHORIZED, "No token provided", "NO_TOKEN"))?;
+
+    let jwt = if state.args.dev_mode {
+        JwtValidator::new_dev()
+    } else {
+        let secret = state.args.jwt_secret.clone().ok_or_else(|| {
+            error_response(
+                StatusCode::NOT_IMPLEMENTED,
+                "Authentication not enabled (missing JWT_SECRET)",
+                "NOT_ENABLED",
+            )
+        })?;
+        JwtValidator::new(secret, state.args.jwt_expiry_seconds).map_err(|e| {
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                &format!("JWT configuration error: {e}"),
+                "CONFIG_ERROR",
+            )
+        })?
+    };
+
+    let result = jwt.verify_token(token);
+    match result.claims {
+        Some(claims) if result.valid => Ok(claims),
+        _ => Err(error_response(
+            StatusCode::UNAUTHORIZED,
+            result.error.as_deref().unwrap_or("Invalid token"),
+            "INVALID_TOKEN",
+        )),
+    }
+}
+
+/// Validate the bearer token and require admin access
+#[allow(clippy::result_large_err)]
+pub(crate) fn require_admin(
+    state: &AppState,
+    auth_header: Option<&str>,
+) -> Result<Claims, Response<Full<Bytes>>> {
+    let claims = require_user(state, auth_header)?;
+    if claims.permission_level < PermissionLevel::Admin {
+        return Err(error_response(
+            StatusCode::FORBIDDEN,
+            "Admin permission required",
+            "FORBIDDEN",
+        ));
+    }
+    Ok(claims)
+}
+
+/// Validate the bearer token and require steward or admin access
+#[allow(clippy::result_large_err)]
+pub(crate) fn require_steward(
+    state: &AppState,
+    auth_header: Option<&str>,
+) -> Result<Claims, Response<Full<Bytes>>> {
+    let claims = require_user(state, auth_header)?;
+    if !claims.is_steward && claims.permission_level < PermissionLevel::Admin {
+        return Err(error_response(
+            StatusCode::FORBIDDEN,
+            "Steward permission required",
+            "FORBIDDEN",
+        ));
+    }
+    Ok(claims)
+}
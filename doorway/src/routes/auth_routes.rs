@@ -6,6 +6,8 @@
 //! - POST /auth/logout   - Invalidate token (optional, client-side mainly)
 //! - POST /auth/refresh  - Refresh an expiring token
 //! - GET  /auth/me       - Get current user info from token
+//! - /auth/passkeys/*    - Passkey registration and passwordless login (see [`super::passkeys`])
+//! - /auth/links/*       - Linking other accounts into the signed-in one (see [`super::identity_links`])
 //!
 //! Ported from admin-proxy/src/auth-routes.ts
 
@@ -28,11 +30,13 @@ use crate::db::schemas::{
     OAUTH_SESSION_COLLECTION, USER_COLLECTION,
 };
 use crate::routes::zome_helpers::{call_create_human, get_agent_pub_key, CreateHumanInput};
+use crate::routes::{identity_links, invitations, passkeys};
 use crate::server::AppState;
+use crate::services::identity_links::resolve_linked_user;
 use crate::types::DoorwayError;
 use rand::Rng;
 
-type BoxBody = http_body_util::combinators::BoxBody<Bytes, hyper::Error>;
+pub(super) type BoxBody = http_body_util::combinators::BoxBody<Bytes, hyper::Error>;
 
 // =============================================================================
 // Request/Response Types
@@ -71,6 +75,9 @@ pub struct RegisterRequest {
     /// Must match the API_KEY_ADMIN environment variable.
     #[serde(default)]
     pub admin_bootstrap_key: Option<String>,
+    /// Invite code from an existing member (required when INVITE_ONLY is set)
+    #[serde(default)]
+    pub invite_code: Option<String>,
 }
 
 fn default_profile_reach() -> String {
@@ -502,14 +509,14 @@ pub struct StewardshipConfirmedResponse {
 // Response Helpers
 // =============================================================================
 
-fn json_response<T: Serialize>(status: StatusCode, body: &T) -> Response<BoxBody> {
+pub(super) fn json_response<T: Serialize>(status: StatusCode, body: &T) -> Response<BoxBody> {
     let json = serde_json::to_string(body).unwrap_or_else(|_| "{}".to_string());
 
     Response::builder()
         .status(status)
         .header("Content-Type", "application/json")
         .header("Access-Control-Allow-Origin", "*")
-        .header("Access-Control-Allow-Methods", "GET, POST, OPTIONS")
+        .header("Access-Control-Allow-Methods", "GET, POST, DELETE, OPTIONS")
         .header(
             "Access-Control-Allow-Headers",
             "Content-Type, Authorization",
@@ -522,7 +529,7 @@ fn cors_preflight() -> Response<BoxBody> {
     Response::builder()
         .status(StatusCode::NO_CONTENT)
         .header("Access-Control-Allow-Origin", "*")
-        .header("Access-Control-Allow-Methods", "GET, POST, OPTIONS")
+        .header("Access-Control-Allow-Methods", "GET, POST, DELETE, OPTIONS")
         .header(
             "Access-Control-Allow-Headers",
             "Content-Type, Authorization",
@@ -544,7 +551,7 @@ fn empty_body() -> BoxBody {
         .boxed()
 }
 
-async fn parse_json_body<T: for<'de> Deserialize<'de>>(
+pub(super) async fn parse_json_body<T: for<'de> Deserialize<'de>>(
     req: Request<hyper::body::Incoming>,
 ) -> Result<T, DoorwayError> {
     let body = req
@@ -560,7 +567,7 @@ async fn parse_json_body<T: for<'de> Deserialize<'de>>(
     serde_json::from_slice(&bytes).map_err(|e| DoorwayError::Http(format!("Invalid JSON: {e}")))
 }
 
-fn get_auth_header(req: &Request<hyper::body::Incoming>) -> Option<&str> {
+pub(super) fn get_auth_header(req: &Request<hyper::body::Incoming>) -> Option<&str> {
     req.headers()
         .get(hyper::header::AUTHORIZATION)
         .and_then(|v| v.to_str().ok())
@@ -609,6 +616,63 @@ async fn handle_register(
         );
     }
 
+    // Validate password strength (minimum 8 characters)
+    if body.password.len() < 8 {
+        return json_response(
+            StatusCode::BAD_REQUEST,
+            &ErrorResponse {
+                error: "Password must be at least 8 characters".into(),
+                code: Some("WEAK_PASSWORD".into()),
+            },
+        );
+    }
+
+    // An invite code, when given, must still be usable; with INVITE_ONLY one
+    // is required, unless the registration bootstraps an admin
+    let invite_code = body
+        .invite_code
+        .as_deref()
+        .map(str::trim)
+        .filter(|code| !code.is_empty());
+    let admin_bootstrap = matches!(
+        (&body.admin_bootstrap_key, &state.args.api_key_admin),
+        (Some(key), Some(admin_key)) if !admin_key.is_empty() && key == admin_key
+    );
+    match invite_code {
+        None if state.args.invite_only && !admin_bootstrap => {
+            return json_response(
+                StatusCode::FORBIDDEN,
+                &ErrorResponse {
+                    error: "An invite code is required to register".into(),
+                    code: Some("INVITE_REQUIRED".into()),
+                },
+            )
+        }
+        None => {}
+        Some(code) => match invitations::invite_code_usable(&state, code).await {
+            Ok(true) => {}
+            Ok(false) => {
+                return json_response(
+                    StatusCode::FORBIDDEN,
+                    &ErrorResponse {
+                        error: "Invalid or expired invite code".into(),
+                        code: Some("INVITE_INVALID".into()),
+                    },
+                )
+            }
+            Err(e) => {
+                warn!("Failed to check invite code: {}", e);
+                return json_response(
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    &ErrorResponse {
+                        error: "Invite codes can't be checked right now".into(),
+                        code: Some("INVITE_UNAVAILABLE".into()),
+                    },
+                );
+            }
+        },
+    }
+
     // Determine display name for registration
     let display_name = if body.display_name.is_empty() {
         body.identifier
@@ -620,97 +684,71 @@ async fn handle_register(
         body.display_name.clone()
     };
 
-    // For doorway-hosted registration, create identity via imagodei zome
-    let (human_id, agent_pub_key, profile) =
-        if body.human_id.is_empty() || body.agent_pub_key.is_empty() {
-            // Generate UUID for human_id
-            let generated_human_id = uuid::Uuid::new_v4().to_string();
-
-            // Try to call imagodei zome (only if conductor is connected)
-            let zome_result = call_create_human(
-                &state,
-                CreateHumanInput {
-                    id: generated_human_id.clone(),
-                    display_name: display_name.clone(),
-                    bio: body.bio.clone(),
-                    affinities: body.affinities.clone(),
-                    profile_reach: body.profile_reach.clone(),
-                    location: body.location.clone(),
-                },
-            )
-            .await;
+    // Get JWT validator
+    let jwt = match get_jwt_validator(&state) {
+        Ok(j) => j,
+        Err(resp) => return resp,
+    };
 
-            match zome_result {
-                Ok(human_output) => {
-                    // Get agent_pub_key from discovered zome config
-                    let agent_key = match get_agent_pub_key(&state) {
-                        Ok(k) => k,
-                        Err(e) => {
-                            warn!("Failed to get agent_pub_key: {}", e);
-                            return json_response(
-                                StatusCode::INTERNAL_SERVER_ERROR,
-                                &ErrorResponse {
-                                    error: "Failed to get agent identity".into(),
-                                    code: Some("AGENT_KEY_ERROR".into()),
-                                },
-                            );
-                        }
-                    };
+    // Production flow uses MongoDB; dev mode without it skips storing the user
+    let collection = if state.args.dev_mode && state.mongo.is_none() {
+        None
+    } else {
+        let mongo = match &state.mongo {
+            Some(m) => m,
+            None => {
+                return json_response(
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    &ErrorResponse {
+                        error: "Database not available".into(),
+                        code: Some("DB_UNAVAILABLE".into()),
+                    },
+                )
+            }
+        };
 
-                    info!(
-                        "Created Holochain identity via imagodei zome: {} (display_name={})",
-                        human_output.human.id, display_name
-                    );
+        // Get users collection
+        match mongo.collection::<UserDoc>(USER_COLLECTION).await {
+            Ok(c) => Some(c),
+            Err(e) => {
+                return json_response(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    &ErrorResponse {
+                        error: format!("Database error: {e}"),
+                        code: Some("DB_ERROR".into()),
+                    },
+                )
+            }
+        }
+    };
 
-                    let profile = HumanProfileResponse {
-                        id: human_output.human.id.clone(),
-                        display_name: human_output.human.display_name,
-                        bio: human_output.human.bio,
-                        affinities: human_output.human.affinities,
-                        profile_reach: human_output.human.profile_reach,
-                        location: human_output.human.location,
-                        created_at: human_output.human.created_at,
-                        updated_at: human_output.human.updated_at,
-                    };
-
-                    (human_output.human.id, agent_key, Some(profile))
-                }
-                Err(e) => {
-                    // Zome call failed - check if we should fall back to placeholder (dev mode)
-                    if state.args.dev_mode {
-                        warn!("Imagodei zome unavailable, using dev fallback: {}", e);
-                        // Generate deterministic IDs for dev mode
-                        use sha2::{Digest, Sha256};
-                        let mut hasher = Sha256::new();
-                        hasher.update(body.identifier.as_bytes());
-                        hasher.update(b"human_id_salt");
-                        let hash = hasher.finalize();
-                        let human_id = format!("uhCHk{}", hex::encode(&hash[..20]));
-
-                        let mut hasher2 = Sha256::new();
-                        hasher2.update(body.identifier.as_bytes());
-                        hasher2.update(b"agent_pub_key_salt");
-                        let hash2 = hasher2.finalize();
-                        let agent_pub_key = format!("uhCAk{}", hex::encode(&hash2[..20]));
-
-                        (human_id, agent_pub_key, None)
-                    } else {
-                        // Production mode - fail if zome unavailable
-                        warn!("Failed to create identity via imagodei zome: {}", e);
-                        return json_response(
-                            StatusCode::SERVICE_UNAVAILABLE,
-                            &ErrorResponse {
-                                error: format!("Failed to create Holochain identity: {e}"),
-                                code: Some("IDENTITY_CREATION_FAILED".into()),
-                            },
-                        );
-                    }
-                }
+    // Check if identifier already exists
+    if let Some(ref collection) = collection {
+        match collection
+            .find_one(doc! { "identifier": &body.identifier })
+            .await
+        {
+            Ok(Some(_)) => {
+                return json_response(
+                    StatusCode::CONFLICT,
+                    &ErrorResponse {
+                        error: "An account with this identifier already exists".into(),
+                        code: Some("USER_EXISTS".into()),
+                    },
+                )
             }
-        } else {
-            // human_id and agent_pub_key provided (legacy/external registration)
-            (body.human_id.clone(), body.agent_pub_key.clone(), None)
-        };
+            Ok(None) => {} // Good, doesn't exist
+            Err(e) => {
+                return json_response(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    &ErrorResponse {
+                        error: format!("Database error: {e}"),
+                        code: Some("DB_ERROR".into()),
+                    },
+                )
+            }
+        }
+    }
 
     // Attempt agent provisioning on a conductor (non-fatal)
     let provisioned = if let Some(registry) = &state.conductor_registry {
@@ -742,25 +780,160 @@ async fn handle_register(
         None
     };
 
-    // Validate password strength (minimum 8 characters)
-    if body.password.len() < 8 {
-        return json_response(
-            StatusCode::BAD_REQUEST,
-            &ErrorResponse {
-                error: "Password must be at least 8 characters".into(),
-                code: Some("WEAK_PASSWORD".into()),
+    // Generate custodial key material
+    let custodial_key_service = CustodialKeyService::new();
+    let custodial_key = match custodial_key_service.generate_key_material(&body.password) {
+        Ok(key) => key,
+        Err(e) => {
+            warn!("Failed to generate custodial key: {}", e);
+            return json_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                &ErrorResponse {
+                    error: "Failed to generate identity key".into(),
+                    code: Some("KEY_GEN_ERROR".into()),
+                },
+            );
+        }
+    };
+
+    // Use conductor-generated key if provisioned, otherwise use custodial key
+    let actual_agent_pub_key = if let Some(ref p) = provisioned {
+        p.agent_pub_key.clone()
+    } else {
+        custodial_key.public_key.clone()
+    };
+
+    // The human id is settled before the identity exists, so the invite code
+    // can be redeemed for it first: a code another registration got to in the
+    // meantime stops this one before anything is created
+    let hosted_identity = body.human_id.is_empty() || body.agent_pub_key.is_empty();
+    let new_human_id = if hosted_identity {
+        uuid::Uuid::new_v4().to_string()
+    } else {
+        body.human_id.clone()
+    };
+    let invited_by = match invite_code {
+        Some(code) => {
+            match invitations::redeem_invite_code(
+                &state,
+                code,
+                &new_human_id,
+                &actual_agent_pub_key,
+            )
+            .await
+            {
+                Ok(inviter) => {
+                    info!(
+                        "Registration of {} used an invite from {}",
+                        body.identifier, inviter
+                    );
+                    Some(inviter)
+                }
+                Err(e) => {
+                    warn!("Invite code not redeemed for {}: {}", body.identifier, e);
+                    return json_response(
+                        StatusCode::CONFLICT,
+                        &ErrorResponse {
+                            error: "Invite code could not be used".into(),
+                            code: Some("INVITE_INVALID".into()),
+                        },
+                    );
+                }
+            }
+        }
+        None => None,
+    };
+
+    // For doorway-hosted registration, create identity via imagodei zome
+    let (human_id, agent_pub_key, profile) = if hosted_identity {
+        // Try to call imagodei zome (only if conductor is connected)
+        let zome_result = call_create_human(
+            &state,
+            CreateHumanInput {
+                id: new_human_id.clone(),
+                display_name: display_name.clone(),
+                bio: body.bio.clone(),
+                affinities: body.affinities.clone(),
+                profile_reach: body.profile_reach.clone(),
+                location: body.location.clone(),
             },
-        );
-    }
+        )
+        .await;
 
-    // Get JWT validator
-    let jwt = match get_jwt_validator(&state) {
-        Ok(j) => j,
-        Err(resp) => return resp,
+        match zome_result {
+            Ok(human_output) => {
+                // Get agent_pub_key from discovered zome config
+                let agent_key = match get_agent_pub_key(&state) {
+                    Ok(k) => k,
+                    Err(e) => {
+                        warn!("Failed to get agent_pub_key: {}", e);
+                        return json_response(
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                            &ErrorResponse {
+                                error: "Failed to get agent identity".into(),
+                                code: Some("AGENT_KEY_ERROR".into()),
+                            },
+                        );
+                    }
+                };
+
+                info!(
+                    "Created Holochain identity via imagodei zome: {} (display_name={})",
+                    human_output.human.id, display_name
+                );
+
+                let profile = HumanProfileResponse {
+                    id: human_output.human.id.clone(),
+                    display_name: human_output.human.display_name,
+                    bio: human_output.human.bio,
+                    affinities: human_output.human.affinities,
+                    profile_reach: human_output.human.profile_reach,
+                    location: human_output.human.location,
+                    created_at: human_output.human.created_at,
+                    updated_at: human_output.human.updated_at,
+                };
+
+                (human_output.human.id, agent_key, Some(profile))
+            }
+            Err(e) => {
+                // Zome call failed - check if we should fall back to placeholder (dev mode)
+                if state.args.dev_mode {
+                    warn!("Imagodei zome unavailable, using dev fallback: {}", e);
+                    // Generate deterministic IDs for dev mode
+                    use sha2::{Digest, Sha256};
+                    let mut hasher = Sha256::new();
+                    hasher.update(body.identifier.as_bytes());
+                    hasher.update(b"human_id_salt");
+                    let hash = hasher.finalize();
+                    let human_id = format!("uhCHk{}", hex::encode(&hash[..20]));
+
+                    let mut hasher2 = Sha256::new();
+                    hasher2.update(body.identifier.as_bytes());
+                    hasher2.update(b"agent_pub_key_salt");
+                    let hash2 = hasher2.finalize();
+                    let agent_pub_key = format!("uhCAk{}", hex::encode(&hash2[..20]));
+
+                    (human_id, agent_pub_key, None)
+                } else {
+                    // Production mode - fail if zome unavailable
+                    warn!("Failed to create identity via imagodei zome: {}", e);
+                    return json_response(
+                        StatusCode::SERVICE_UNAVAILABLE,
+                        &ErrorResponse {
+                            error: format!("Failed to create Holochain identity: {e}"),
+                            code: Some("IDENTITY_CREATION_FAILED".into()),
+                        },
+                    );
+                }
+            }
+        }
+    } else {
+        // human_id and agent_pub_key provided (legacy/external registration)
+        (new_human_id, body.agent_pub_key.clone(), None)
     };
 
     // In dev mode without MongoDB, use simplified flow
-    if state.args.dev_mode && state.mongo.is_none() {
+    let Some(collection) = collection else {
         info!("Dev mode register (no MongoDB): {}", body.identifier);
         return generate_auth_response(
             &jwt,
@@ -777,62 +950,8 @@ async fn handle_register(
             false,
             false,
         );
-    }
-
-    // Production flow: use MongoDB
-    let mongo = match &state.mongo {
-        Some(m) => m,
-        None => {
-            return json_response(
-                StatusCode::SERVICE_UNAVAILABLE,
-                &ErrorResponse {
-                    error: "Database not available".into(),
-                    code: Some("DB_UNAVAILABLE".into()),
-                },
-            )
-        }
-    };
-
-    // Get users collection
-    let collection = match mongo.collection::<UserDoc>(USER_COLLECTION).await {
-        Ok(c) => c,
-        Err(e) => {
-            return json_response(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                &ErrorResponse {
-                    error: format!("Database error: {e}"),
-                    code: Some("DB_ERROR".into()),
-                },
-            )
-        }
     };
 
-    // Check if identifier already exists
-    match collection
-        .find_one(doc! { "identifier": &body.identifier })
-        .await
-    {
-        Ok(Some(_)) => {
-            return json_response(
-                StatusCode::CONFLICT,
-                &ErrorResponse {
-                    error: "An account with this identifier already exists".into(),
-                    code: Some("USER_EXISTS".into()),
-                },
-            )
-        }
-        Ok(None) => {} // Good, doesn't exist
-        Err(e) => {
-            return json_response(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                &ErrorResponse {
-                    error: format!("Database error: {e}"),
-                    code: Some("DB_ERROR".into()),
-                },
-            )
-        }
-    }
-
     // Hash password
     let password_hash = match hash_password(&body.password) {
         Ok(h) => h,
@@ -847,29 +966,6 @@ async fn handle_register(
         }
     };
 
-    // Generate custodial key material
-    let custodial_key_service = CustodialKeyService::new();
-    let custodial_key = match custodial_key_service.generate_key_material(&body.password) {
-        Ok(key) => key,
-        Err(e) => {
-            warn!("Failed to generate custodial key: {}", e);
-            return json_response(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                &ErrorResponse {
-                    error: "Failed to generate identity key".into(),
-                    code: Some("KEY_GEN_ERROR".into()),
-                },
-            );
-        }
-    };
-
-    // Use conductor-generated key if provisioned, otherwise use custodial key
-    let actual_agent_pub_key = if let Some(ref p) = provisioned {
-        p.agent_pub_key.clone()
-    } else {
-        custodial_key.public_key.clone()
-    };
-
     // Create user document with custodial key
     let mut user = UserDoc::new_with_custodial_key(
         body.identifier.clone(),
@@ -897,6 +993,9 @@ async fn handle_register(
         }
     }
 
+    // Link the new member to their inviter
+    user.invited_by = invited_by;
+
     // Capture permission level before user is moved into insert
     let user_permission_level = user.permission_level;
 
@@ -1087,11 +1186,41 @@ async fn handle_login(
         );
     }
 
+    // A linked account signs in as the account it was linked into. That
+    // account's custodial key is sealed with its own password, so it stays
+    // locked for this session.
+    let linked = user.linked_to.is_some();
+    let user = match resolve_linked_user(&collection, user).await {
+        Ok(Some(u)) => u,
+        Ok(None) => {
+            warn!(
+                "Login failed - linked account inactive: {}",
+                body.identifier
+            );
+            return json_response(
+                StatusCode::UNAUTHORIZED,
+                &ErrorResponse {
+                    error: "Invalid credentials".into(),
+                    code: Some("INVALID_CREDENTIALS".into()),
+                },
+            );
+        }
+        Err(e) => {
+            return json_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                &ErrorResponse {
+                    error: format!("Database error: {e}"),
+                    code: Some("DB_ERROR".into()),
+                },
+            )
+        }
+    };
+
     // Generate session ID for key cache lookup
     let session_id = uuid::Uuid::new_v4().to_string();
 
     // Activate custodial key if user has one
-    if user.has_custodial_key() {
+    if !linked && user.has_custodial_key() {
         let custodial_key_service = CustodialKeyService::new();
         match custodial_key_service.activate_key(&session_id, &user, &body.password) {
             Ok(_verifying_key) => {
@@ -1124,11 +1253,11 @@ async fn handle_login(
                 let provisioner = AgentProvisioner::new(Arc::clone(registry))
                     .with_app_id(state.args.installed_app_id.clone())
                     .with_bundle_path(state.args.happ_bundle_path.clone());
-                match provisioner.provision_agent(&body.identifier).await {
+                match provisioner.provision_agent(&user.identifier).await {
                     Ok(p) => {
                         info!(
                             "Auto-provisioned {} on {} (app: {})",
-                            body.identifier, p.conductor_id, p.installed_app_id
+                            user.identifier, p.conductor_id, p.installed_app_id
                         );
                         // Update UserDoc with conductor assignment
                         let update = doc! {
@@ -1138,7 +1267,7 @@ async fn handle_login(
                             }
                         };
                         if let Err(e) = collection
-                            .update_one(doc! { "identifier": &body.identifier }, update)
+                            .update_one(doc! { "identifier": &user.identifier }, update)
                             .await
                         {
                             warn!("Failed to update user doc after provisioning: {}", e);
@@ -1147,7 +1276,7 @@ async fn handle_login(
                         (p.agent_pub_key, Some(p.installed_app_id), Some(cid))
                     }
                     Err(e) => {
-                        warn!("Auto-provisioning failed for {}: {}", body.identifier, e);
+                        warn!("Auto-provisioning failed for {}: {}", user.identifier, e);
                         (user.agent_pub_key.clone(), None, None)
                     }
                 }
@@ -1170,7 +1299,7 @@ async fn handle_login(
 
     info!(
         "Login successful: {} (permission: {:?})",
-        body.identifier, user.permission_level
+        user.identifier, user.permission_level
     );
 
     generate_auth_response(
@@ -3186,7 +3315,7 @@ fn generate_oauth_token_response(
 // =============================================================================
 
 #[allow(clippy::result_large_err)]
-fn get_jwt_validator(state: &AppState) -> Result<JwtValidator, Response<BoxBody>> {
+pub(super) fn get_jwt_validator(state: &AppState) -> Result<JwtValidator, Response<BoxBody>> {
     if state.args.dev_mode {
         Ok(JwtValidator::new_dev())
     } else {
@@ -3214,7 +3343,7 @@ fn get_jwt_validator(state: &AppState) -> Result<JwtValidator, Response<BoxBody>
 
 /// Generate a successful auth response with JWT token
 #[allow(clippy::too_many_arguments)]
-fn generate_auth_response(
+pub(super) fn generate_auth_response(
     jwt: &JwtValidator,
     state: &AppState,
     human_id: &str,
@@ -3342,6 +3471,40 @@ pub async fn handle_auth_request(
             handle_elohim_verify_answer(req, state).await
         }
 
+        // Passkey (WebAuthn) endpoints
+        (&Method::GET, "/auth/passkeys") => passkeys::handle_list_passkeys(req, state).await,
+        (&Method::POST, "/auth/passkeys/register/options") => {
+            passkeys::handle_register_options(req, state).await
+        }
+        (&Method::POST, "/auth/passkeys/register") => {
+            passkeys::handle_register_passkey(req, state).await
+        }
+        (&Method::POST, "/auth/passkeys/login/options") => {
+            passkeys::handle_login_options(req, state).await
+        }
+        (&Method::POST, "/auth/passkeys/login") => passkeys::handle_passkey_login(req, state).await,
+        (&Method::DELETE, p) if passkeys::parse_passkey_path(p).is_some() => {
+            passkeys::handle_delete_passkey(req, state).await
+        }
+
+        // Account linking endpoints
+        (&Method::GET, "/auth/links") => identity_links::handle_list_links(req, state).await,
+        (&Method::POST, "/auth/links/password") => {
+            identity_links::handle_link_password(req, state).await
+        }
+        (&Method::POST, "/auth/links/passkey/options") => {
+            identity_links::handle_link_passkey_options(req, state).await
+        }
+        (&Method::POST, "/auth/links/passkey") => {
+            identity_links::handle_link_passkey(req, state).await
+        }
+        (&Method::POST, "/auth/links/api-key") => {
+            identity_links::handle_link_api_key(req, state).await
+        }
+        (&Method::DELETE, p) if identity_links::parse_link_path(p).is_some() => {
+            identity_links::handle_unlink(req, state).await
+        }
+
         // Method not allowed
         (_, "/auth/register")
         | (_, "/auth/login")
@@ -3359,7 +3522,17 @@ pub async fn handle_auth_request(
         | (_, "/auth/check-recovery-status")
         | (_, "/auth/activate-recovery")
         | (_, "/auth/elohim-verify/start")
-        | (_, "/auth/elohim-verify/answer") => json_response(
+        | (_, "/auth/elohim-verify/answer")
+        | (_, "/auth/passkeys")
+        | (_, "/auth/passkeys/register/options")
+        | (_, "/auth/passkeys/register")
+        | (_, "/auth/passkeys/login/options")
+        | (_, "/auth/passkeys/login")
+        | (_, "/auth/links")
+        | (_, "/auth/links/password")
+        | (_, "/auth/links/passkey/options")
+        | (_, "/auth/links/passkey")
+        | (_, "/auth/links/api-key") => json_response(
             StatusCode::METHOD_NOT_ALLOWED,
             &ErrorResponse {
                 error: "Method not allowed".into(),
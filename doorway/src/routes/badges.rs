@@ -0,0 +1,152 @@
+//! Open Badges Export API
+//!
+//! Lets the signed-in learner download their earned attestations as signed
+//! Open Badges 3.0 credentials from their profile, built in the background
+//! by the [`BadgeExporter`](crate::worker::badge_export::BadgeExporter).
+//!
+//! ## Routes
+//!
+//! - `POST /me/badges/export?format=ob3|clr` - Start an export (202)
+//! - `GET /me/badges/export` - Status of the latest export
+//! - `GET /me/badges/export/download` - The finished export file
+
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::{Method, Response, StatusCode};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use super::api::error_response;
+use super::auth_helpers::require_user;
+use crate::server::AppState;
+use crate::worker::badge_export::{BadgeExportJob, ExportFormat, ExportStatus};
+
+/// Whether a path is served by this module
+pub fn is_badge_export_route(path: &str) -> bool {
+    matches!(path, "/me/badges/export" | "/me/badges/export/download")
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ExportParams {
+    #[serde(default)]
+    format: ExportFormat,
+}
+
+fn private_response(
+    status: StatusCode,
+    content_type: &str,
+    body: Vec<u8>,
+) -> Response<Full<Bytes>> {
+    Response::builder()
+        .status(status)
+        .header("Content-Type", content_type)
+        .header("Cache-Control", "private, no-store")
+        .header("Vary", "Authorization")
+        .body(Full::new(Bytes::from(body)))
+        .unwrap()
+}
+
+fn job_response(status: StatusCode, job: &BadgeExportJob) -> Response<Full<Bytes>> {
+    private_response(
+        status,
+        "application/json",
+        serde_json::to_vec(job).unwrap_or_default(),
+    )
+}
+
+/// Download file name for an export
+fn file_name(job: &BadgeExportJob) -> String {
+    let format = match job.format {
+        ExportFormat::Ob3 => "open-badges",
+        ExportFormat::Clr => "clr",
+    };
+    format!("{format}-{}.json", job.requested_at.format("%Y%m%d"))
+}
+
+/// Handle /me/badges/export and /me/badges/export/download
+pub async fn handle_badge_export(
+    state: Arc<AppState>,
+    method: Method,
+    path: &str,
+    query: Option<&str>,
+    auth_header: Option<&str>,
+) -> Response<Full<Bytes>> {
+    let claims = match require_user(&state, auth_header) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+    let Some(ref exporter) = state.badge_exports else {
+        return error_response(
+            StatusCode::NOT_IMPLEMENTED,
+            "Open Badges export is not enabled (missing BADGE_ISSUER_KEY)",
+            "NOT_ENABLED",
+        );
+    };
+
+    match (method, path) {
+        (Method::POST, "/me/badges/export") => {
+            let params: ExportParams = match serde_urlencoded::from_str(query.unwrap_or("")) {
+                Ok(params) => params,
+                Err(e) => {
+                    return error_response(
+                        StatusCode::BAD_REQUEST,
+                        &format!("Invalid query parameters: {e}"),
+                        "BAD_REQUEST",
+                    )
+                }
+            };
+            let job = exporter.request(&claims.human_id, params.format);
+            job_response(StatusCode::ACCEPTED, &job)
+        }
+        (Method::GET, "/me/badges/export") => match exporter.job(&claims.human_id) {
+            Some(job) => job_response(StatusCode::OK, &job),
+            None => error_response(StatusCode::NOT_FOUND, "No export requested", "NOT_FOUND"),
+        },
+        (Method::GET, "/me/badges/export/download") => {
+            let Some(job) = exporter.job(&claims.human_id) else {
+                return error_response(StatusCode::NOT_FOUND, "No export requested", "NOT_FOUND");
+            };
+            match (job.status, job.file.clone()) {
+                (ExportStatus::Ready, Some(file)) => Response::builder()
+                    .status(StatusCode::OK)
+                    .header("Content-Type", "application/json")
+                    .header(
+                        "Content-Disposition",
+                        format!("attachment; filename=\"{}\"", file_name(&job)),
+                    )
+                    .header("Cache-Control", "private, no-store")
+                    .header("Vary", "Authorization")
+                    .body(Full::new(file))
+                    .unwrap(),
+                (ExportStatus::Failed, _) => job_response(StatusCode::BAD_GATEWAY, &job),
+                _ => job_response(StatusCode::CONFLICT, &job),
+            }
+        }
+        _ => error_response(
+            StatusCode::METHOD_NOT_ALLOWED,
+            "Method not allowed",
+            "METHOD_NOT_ALLOWED",
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_params_default_to_ob3() {
+        let params: ExportParams = serde_urlencoded::from_str("").unwrap();
+        assert_eq!(params.format, ExportFormat::Ob3);
+        let params: ExportParams = serde_urlencoded::from_str("format=clr").unwrap();
+        assert_eq!(params.format, ExportFormat::Clr);
+        assert!(serde_urlencoded::from_str::<ExportParams>("format=pdf").is_err());
+    }
+
+    #[test]
+    fn test_is_badge_export_route() {
+        assert!(is_badge_export_route("/me/badges/export"));
+        assert!(is_badge_export_route("/me/badges/export/download"));
+        assert!(!is_badge_export_route("/me/badges"));
+    }
+}
@@ -23,6 +23,19 @@
 //! 2. Fetch shards from elohim-storage endpoints
 //! 3. Reassemble and cache for future requests
 //!
+//! ## Torrent Metadata
+//!
+//! `GET /blobs/{address}/torrent` returns a `.torrent` for blobs above the
+//! configured size threshold, with `/store/{address}` as its web seed (see
+//! [`crate::worker::torrent`]). The first request starts generation and
+//! gets `202 Accepted` with `Retry-After`.
+//!
+//! ## Signed URLs
+//!
+//! With `MEDIA_URL_SECRET` set, blobs of gated or non-commons content are
+//! only served through signed, expiring URLs (see
+//! [`super::media_urls`]), and get no torrent.
+//!
 //! ## Example Usage
 //!
 //! ```bash
@@ -39,6 +52,7 @@
 use crate::cache::ContentCache;
 use crate::projection::ProjectionStore;
 use crate::services::{BlobResolution, ShardLocation, ShardManifest, ShardResolver};
+use crate::worker::torrent::{TorrentGenerator, TorrentLookup};
 use bytes::Bytes;
 use cid::Cid;
 use http_body_util::Full;
@@ -78,7 +92,7 @@ impl std::fmt::Display for BlobError {
 /// - Raw SHA256 hex (64 char hex string) - returns as-is
 ///
 /// Returns SHA256 hex string for cache lookups.
-fn parse_content_address(addr: &str) -> Result<String, BlobError> {
+pub fn parse_content_address(addr: &str) -> Result<String, BlobError> {
     // Try CID first (starts with common CID prefixes)
     if addr.starts_with("baf") || addr.starts_with("Qm") || addr.starts_with("z") {
         match Cid::from_str(addr) {
@@ -688,6 +702,55 @@ async fn get_shard_locations_from_projection(
     locations
 }
 
+// ============================================================================
+// Torrent Metadata
+// ============================================================================
+
+/// Seconds clients should wait before asking for a torrent again
+const TORRENT_RETRY_AFTER_SECS: u64 = 5;
+
+/// Extract the address from `/blobs/{address}/torrent`
+pub fn parse_torrent_path(path: &str) -> Option<&str> {
+    path.strip_prefix("/blobs/")?
+        .strip_suffix("/torrent")
+        .filter(|addr| !addr.is_empty() && !addr.contains('/'))
+}
+
+/// Handle GET /blobs/{address}/torrent
+pub fn handle_blob_torrent(
+    raw_address: &str,
+    torrents: Option<&Arc<TorrentGenerator>>,
+) -> Result<Response<Full<Bytes>>, BlobError> {
+    let hash = parse_content_address(raw_address)?;
+    let Some(torrents) = torrents else {
+        return Err(BlobError::NotFound);
+    };
+
+    match torrents.lookup(&hash) {
+        TorrentLookup::Ready(meta) => Ok(Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, "application/x-bittorrent")
+            .header(
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{hash}.torrent\""),
+            )
+            .header("X-Torrent-Info-Hash", meta.info_hash.as_str())
+            .header(header::CACHE_CONTROL, "public, max-age=86400")
+            .body(Full::new(meta.torrent.clone()))
+            .unwrap()),
+        TorrentLookup::Pending => Ok(Response::builder()
+            .status(StatusCode::ACCEPTED)
+            .header(header::RETRY_AFTER, TORRENT_RETRY_AFTER_SECS.to_string())
+            .header(header::CONTENT_TYPE, "text/plain")
+            .body(Full::new(Bytes::from("Torrent is being generated")))
+            .unwrap()),
+        TorrentLookup::Unavailable(reason) => {
+            debug!(hash = %hash, reason, "No torrent for blob");
+            Err(BlobError::NotFound)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -784,4 +847,15 @@ mod tests {
         // Wrong length
         assert!(parse_content_address("sha256-abc123").is_err());
     }
+
+    #[test]
+    fn test_parse_torrent_path() {
+        assert_eq!(
+            parse_torrent_path("/blobs/sha256-abc/torrent"),
+            Some("sha256-abc")
+        );
+        assert_eq!(parse_torrent_path("/blobs//torrent"), None);
+        assert_eq!(parse_torrent_path("/blobs/a/b/torrent"), None);
+        assert_eq!(parse_torrent_path("/blobs/sha256-abc"), None);
+    }
 }
@@ -0,0 +1,281 @@
+//! Bookmarks API
+//!
+//! The signed-in human's personal library from the content_store zome:
+//! content saved outside formal paths, sorted into folders of their own.
+//! The library is read on every page that shows a "saved" toggle, so doorway
+//! caches it per human (the zome rule is keyed by their id) and evicts it on
+//! their own writes; clients may keep it privately for a minute.
+//!
+//! ## Routes
+//!
+//! - `GET /me/bookmarks?folder=` - Library by folder, or one folder
+//! - `POST /me/bookmarks` - Save `{content_id, folder?}`; saving again moves it
+//! - `DELETE /me/bookmarks/{content_id}` - Take content out of the library
+
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::Incoming;
+use hyper::{Method, Request, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+use super::api::{error_response, private_json_response};
+use super::auth_helpers::require_user;
+use super::zome_helpers::{call_content_store_for, get_content_store_config};
+use crate::auth::Claims;
+use crate::cache::rules::CacheRuleExt;
+use crate::server::AppState;
+use crate::types::Result;
+
+/// Largest request body accepted
+const MAX_BODY_BYTES: usize = 16 * 1024;
+
+/// Zome function listing a library
+const LIBRARY_FN: &str = "get_my_bookmarks";
+
+/// Whether a path is served by this module
+pub fn is_bookmark_route(path: &str) -> bool {
+    path == "/me/bookmarks" || bookmark_content_id(path).is_some()
+}
+
+/// The content id of `/me/bookmarks/{content_id}`
+fn bookmark_content_id(path: &str) -> Option<&str> {
+    path.strip_prefix("/me/bookmarks/")
+        .filter(|id| !id.is_empty() && !id.contains('/'))
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct BookmarkParams {
+    folder: Option<String>,
+}
+
+/// Body of `POST /me/bookmarks`
+#[derive(Debug, Deserialize)]
+struct BookmarkBody {
+    content_id: String,
+    folder: Option<String>,
+}
+
+/// Must match BookmarkContentInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct BookmarkContentInput {
+    human_id: Option<String>,
+    content_id: String,
+    folder: Option<String>,
+}
+
+/// Must match RemoveBookmarkInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct RemoveBookmarkInput {
+    human_id: Option<String>,
+    content_id: String,
+}
+
+fn zome_error(fn_name: &str, error: impl std::fmt::Debug) -> Response<Full<Bytes>> {
+    warn!(fn_name, error = ?error, "Bookmark call failed");
+    error_response(
+        StatusCode::BAD_GATEWAY,
+        "Bookmark request failed",
+        "ZOME_ERROR",
+    )
+}
+
+/// A human's library, from the cache when doorway holds it
+async fn cached_library(
+    state: &AppState,
+    human_id: &Option<String>,
+    caller: &Claims,
+) -> Result<Value> {
+    let config = get_content_store_config(state)?;
+    let cache_key = state
+        .cache_rules
+        .cache_key(&config.dna_hash, &config.zome_name, LIBRARY_FN, human_id)
+        .to_storage_key();
+
+    if let Some(entry) = state.cache.get(&cache_key) {
+        debug!("Bookmark library cache hit");
+        if let Ok(library) = serde_json::from_slice(&entry.data) {
+            return Ok(library);
+        }
+    }
+
+    let library = call_content_store_for(state, LIBRARY_FN, human_id, Some(caller))
+        .await?
+        .filter(|data| !data.is_null())
+        .unwrap_or_else(|| Value::Array(vec![]));
+    let ttl = state
+        .cache_rules
+        .get_rule(&config.dna_hash, LIBRARY_FN)
+        .map(|rule| rule.ttl())
+        .unwrap_or(state.cache.config().user_ttl);
+    state.cache.set_with_refs(
+        &cache_key,
+        serde_json::to_vec(&library).unwrap_or_default(),
+        "application/json",
+        ttl,
+        state
+            .cache_rules
+            .cache_refs(&config.dna_hash, LIBRARY_FN, human_id),
+    );
+    Ok(library)
+}
+
+/// Drop a human's cached library after they change it
+fn evict_library(state: &AppState, human_id: &str) {
+    if let Ok(config) = get_content_store_config(state) {
+        state
+            .cache
+            .invalidate_entity(&config.dna_hash, LIBRARY_FN, human_id);
+    }
+}
+
+/// Keep only the named folder of a library
+fn only_folder(library: Value, folder: &str) -> Value {
+    match library {
+        Value::Array(folders) => Value::Array(
+            folders
+                .into_iter()
+                .filter(|f| f["folder"].as_str() == Some(folder))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+/// Handle /me/bookmarks and /me/bookmarks/{content_id}
+pub async fn handle_bookmarks(
+    req: Request<Incoming>,
+    state: Arc<AppState>,
+) -> Response<Full<Bytes>> {
+    let auth_header = req
+        .headers()
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string());
+    let claims = match require_user(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+    let human_id = Some(claims.human_id.clone());
+    let method = req.method().clone();
+    let path = req.uri().path().to_string();
+
+    match (method, bookmark_content_id(&path)) {
+        (Method::GET, None) => {
+            let params: BookmarkParams =
+                serde_urlencoded::from_str(req.uri().query().unwrap_or("")).unwrap_or_default();
+            match cached_library(&state, &human_id, &claims).await {
+                Ok(library) => {
+                    let library = match params.folder {
+                        Some(ref folder) => only_folder(library, folder),
+                        None => library,
+                    };
+                    private_json_response(&library, "private, max-age=60")
+                }
+                Err(e) => zome_error(LIBRARY_FN, e),
+            }
+        }
+        (Method::POST, None) => {
+            let body = match Limited::new(req.into_body(), MAX_BODY_BYTES)
+                .collect()
+                .await
+            {
+                Ok(collected) => collected.to_bytes(),
+                Err(_) => {
+                    return error_response(
+                        StatusCode::PAYLOAD_TOO_LARGE,
+                        &format!("Requests are limited to {MAX_BODY_BYTES} bytes"),
+                        "TOO_LARGE",
+                    )
+                }
+            };
+            let body: BookmarkBody = match serde_json::from_slice(&body) {
+                Ok(body) => body,
+                Err(e) => {
+                    return error_response(
+                        StatusCode::BAD_REQUEST,
+                        &format!("Invalid request: {e}"),
+                        "INVALID_JSON",
+                    )
+                }
+            };
+            if body.content_id.trim().is_empty() {
+                return error_response(
+                    StatusCode::BAD_REQUEST,
+                    "content_id is required",
+                    "BAD_REQUEST",
+                );
+            }
+            let input = BookmarkContentInput {
+                human_id,
+                content_id: body.content_id,
+                folder: body.folder,
+            };
+            let result =
+                call_content_store_for(&state, "bookmark_content", &input, Some(&claims)).await;
+            evict_library(&state, &claims.human_id);
+            match result {
+                Ok(data) => {
+                    private_json_response(&data.unwrap_or(Value::Null), "private, no-store")
+                }
+                Err(e) => zome_error("bookmark_content", e),
+            }
+        }
+        (Method::DELETE, Some(content_id)) => {
+            let input = RemoveBookmarkInput {
+                human_id,
+                content_id: content_id.to_string(),
+            };
+            let result =
+                call_content_store_for(&state, "remove_bookmark", &input, Some(&claims)).await;
+            evict_library(&state, &claims.human_id);
+            match result {
+                Ok(_) => Response::builder()
+                    .status(StatusCode::NO_CONTENT)
+                    .body(Full::new(Bytes::new()))
+                    .unwrap(),
+                Err(e) => zome_error("remove_bookmark", e),
+            }
+        }
+        _ => error_response(
+            StatusCode::METHOD_NOT_ALLOWED,
+            "Method not allowed",
+            "METHOD_NOT_ALLOWED",
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_is_bookmark_route() {
+        assert!(is_bookmark_route("/me/bookmarks"));
+        assert!(is_bookmark_route("/me/bookmarks/fractions-intro"));
+        assert_eq!(
+            bookmark_content_id("/me/bookmarks/fractions-intro"),
+            Some("fractions-intro")
+        );
+        assert!(!is_bookmark_route("/me/bookmarks/"));
+        assert!(!is_bookmark_route("/me/bookmarks/a/b"));
+        assert!(!is_bookmark_route("/me/reflections"));
+    }
+
+    #[test]
+    fn test_only_folder() {
+        let library = json!([
+            {"folder": "Saved", "bookmarks": []},
+            {"folder": "Week 3", "bookmarks": [{"bookmark": {"content_id": "fractions-intro"}}]}
+        ]);
+        let folder = only_folder(library, "Week 3");
+        assert_eq!(folder.as_array().unwrap().len(), 1);
+        assert_eq!(
+            folder[0]["bookmarks"][0]["bookmark"]["content_id"],
+            "fractions-intro"
+        );
+    }
+}
@@ -0,0 +1,80 @@
+//! Cache Snapshot Routes
+//!
+//! Admin endpoints for moving the response cache between instances during a
+//! blue-green rollout (see [`cache::snapshot`](crate::cache::snapshot)).
+//!
+//! ## Routes
+//!
+//! - `GET /admin/cache/dump` - The live cache as a snapshot (NDJSON)
+//! - `POST /admin/cache/load` - Load a snapshot body; answers with counts of
+//!   loaded, expired and already-cached entries
+
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::Incoming;
+use hyper::{Request, Response, StatusCode};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use super::api::{error_response, json_response};
+use super::auth_helpers::require_admin;
+use crate::cache::snapshot::{self, SNAPSHOT_CONTENT_TYPE};
+use crate::server::AppState;
+
+/// Largest snapshot accepted by `POST /admin/cache/load`
+const MAX_SNAPSHOT_BYTES: usize = 1024 * 1024 * 1024;
+
+fn auth_header(req: &Request<Incoming>) -> Option<String> {
+    req.headers()
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string())
+}
+
+/// Handle GET /admin/cache/dump
+pub fn handle_cache_dump(req: &Request<Incoming>, state: Arc<AppState>) -> Response<Full<Bytes>> {
+    if let Err(response) = require_admin(&state, auth_header(req).as_deref()) {
+        return response;
+    }
+
+    let body = snapshot::dump(&state.cache, chrono::Utc::now().timestamp());
+    info!(bytes = body.len(), "Cache snapshot dumped");
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", SNAPSHOT_CONTENT_TYPE)
+        .header("Cache-Control", "no-store")
+        .body(Full::new(Bytes::from(body)))
+        .unwrap()
+}
+
+/// Handle POST /admin/cache/load
+pub async fn handle_cache_load(
+    req: Request<Incoming>,
+    state: Arc<AppState>,
+) -> Response<Full<Bytes>> {
+    if let Err(response) = require_admin(&state, auth_header(&req).as_deref()) {
+        return response;
+    }
+
+    let body = match Limited::new(req.into_body(), MAX_SNAPSHOT_BYTES)
+        .collect()
+        .await
+    {
+        Ok(collected) => collected.to_bytes(),
+        Err(_) => {
+            return error_response(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                &format!("Snapshots are limited to {MAX_SNAPSHOT_BYTES} bytes"),
+                "TOO_LARGE",
+            )
+        }
+    };
+
+    match snapshot::load(&state.cache, &body, chrono::Utc::now().timestamp()) {
+        Ok(summary) => json_response(serde_json::to_vec(&summary).unwrap_or_default()),
+        Err(e) => {
+            warn!(error = %e, "Cache snapshot rejected");
+            error_response(StatusCode::BAD_REQUEST, &e.to_string(), "INVALID_SNAPSHOT")
+        }
+    }
+}
@@ -0,0 +1,263 @@
+//! Cache Rule Statistics Routes
+//!
+//! Admin view of how each cache rule performs: hits, misses, stale serves,
+//! invalidations, hit ratio and average payload size, next to the rule's
+//! TTL so deployers can tell which rules pay off and which TTLs to adjust.
+//!
+//! ## Routes
+//!
+//! - `GET /admin/cache/stats?hours=` - Counters since startup for every rule
+//!   seen or declared, plus persisted rollups summed over the last `hours`
+//!   (default 24) when MongoDB is available
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use http_body_util::Full;
+use hyper::{Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tracing::warn;
+
+use super::api::{error_response, json_response};
+use super::auth_helpers::require_user;
+use crate::auth::PermissionLevel;
+use crate::cache::{CacheRuleStore, RuleStatsSnapshot};
+use crate::db::schemas::{CacheRuleRollupDoc, CACHE_RULE_ROLLUP_COLLECTION};
+use crate::server::AppState;
+use crate::worker::cache_stats::summarize;
+
+/// Default and largest rollup span in hours
+const DEFAULT_HOURS: u32 = 24;
+const MAX_HOURS: u32 = 24 * 90;
+
+/// Query of `GET /admin/cache/stats`
+#[derive(Debug, Default, Deserialize)]
+struct StatsParams {
+    hours: Option<u32>,
+}
+
+/// One rule's numbers as served to admins
+#[derive(Debug, Serialize)]
+struct RuleReport {
+    dna_hash: String,
+    fn_name: String,
+    /// Declared by the DNA rather than a naming convention default
+    declared: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ttl_secs: Option<u64>,
+    hits: u64,
+    misses: u64,
+    stale_serves: u64,
+    invalidations: u64,
+    hit_ratio: f64,
+    avg_payload_bytes: u64,
+}
+
+impl RuleReport {
+    fn new(
+        stats: &RuleStatsSnapshot,
+        rules: &CacheRuleStore,
+        declared: &HashSet<(String, String)>,
+    ) -> Self {
+        Self {
+            dna_hash: stats.dna_hash.clone(),
+            fn_name: stats.fn_name.clone(),
+            declared: declared.contains(&(stats.dna_hash.clone(), stats.fn_name.clone())),
+            ttl_secs: rules
+                .get_rule(&stats.dna_hash, &stats.fn_name)
+                .map(|rule| rule.ttl_secs),
+            hits: stats.hits,
+            misses: stats.misses,
+            stale_serves: stats.stale_serves,
+            invalidations: stats.invalidations,
+            hit_ratio: stats.hit_ratio(),
+            avg_payload_bytes: stats.avg_payload_bytes(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct RollupReport {
+    hours: u32,
+    rules: Vec<RuleReport>,
+}
+
+#[derive(Debug, Serialize)]
+struct StatsResponse {
+    /// Start of the live counters (process start)
+    since: DateTime<Utc>,
+    rules: Vec<RuleReport>,
+    /// Absent when rollups are not stored
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rollups: Option<RollupReport>,
+}
+
+/// Live counters, with declared rules that saw no traffic as zero rows
+fn live_stats(
+    mut stats: Vec<RuleStatsSnapshot>,
+    declared: &HashSet<(String, String)>,
+) -> Vec<RuleStatsSnapshot> {
+    let seen: HashSet<(String, String)> = stats
+        .iter()
+        .map(|s| (s.dna_hash.clone(), s.fn_name.clone()))
+        .collect();
+    for (dna_hash, fn_name) in declared.difference(&seen) {
+        stats.push(RuleStatsSnapshot {
+            dna_hash: dna_hash.clone(),
+            fn_name: fn_name.clone(),
+            ..Default::default()
+        });
+    }
+    stats.sort_by(|a, b| {
+        (b.hits + b.misses)
+            .cmp(&(a.hits + a.misses))
+            .then_with(|| a.fn_name.cmp(&b.fn_name))
+    });
+    stats
+}
+
+/// Persisted rollups closed within the last `hours`
+async fn load_rollups(state: &AppState, hours: u32) -> Option<Vec<CacheRuleRollupDoc>> {
+    let mongo = state.mongo.as_ref()?;
+    let collection = match mongo
+        .collection::<CacheRuleRollupDoc>(CACHE_RULE_ROLLUP_COLLECTION)
+        .await
+    {
+        Ok(collection) => collection,
+        Err(e) => {
+            warn!(error = %e, "Cache rollup collection unavailable");
+            return None;
+        }
+    };
+
+    let cutoff = Utc::now() - chrono::Duration::hours(hours as i64);
+    let filter = bson::doc! {
+        "metadata.created_at": { "$gte": bson::DateTime::from_chrono(cutoff) }
+    };
+    match collection.find_many(filter).await {
+        Ok(rollups) => Some(rollups),
+        Err(e) => {
+            warn!(error = %e, "Failed to load cache rule rollups");
+            None
+        }
+    }
+}
+
+/// Handle GET /admin/cache/stats
+pub async fn handle_cache_stats(
+    state: Arc<AppState>,
+    query: Option<&str>,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    let claims = match require_user(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+    if claims.permission_level < PermissionLevel::Admin {
+        return error_response(
+            StatusCode::FORBIDDEN,
+            "Admin permission required",
+            "FORBIDDEN",
+        );
+    }
+
+    let params: StatsParams = match serde_urlencoded::from_str(query.unwrap_or("")) {
+        Ok(params) => params,
+        Err(e) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                &format!("Invalid query: {e}"),
+                "INVALID_QUERY",
+            )
+        }
+    };
+    let hours = params.hours.unwrap_or(DEFAULT_HOURS).clamp(1, MAX_HOURS);
+
+    let declared: HashSet<(String, String)> = state
+        .cache_rules
+        .declared_rules()
+        .into_iter()
+        .filter(|(_, rule)| rule.cacheable)
+        .map(|(dna_hash, rule)| (dna_hash, rule.fn_name))
+        .collect();
+    let report = |stats: &RuleStatsSnapshot| RuleReport::new(stats, &state.cache_rules, &declared);
+
+    let rule_stats = state.cache.rule_stats();
+    let rules = live_stats(rule_stats.snapshot(), &declared)
+        .iter()
+        .map(report)
+        .collect();
+    let rollups = load_rollups(&state, hours)
+        .await
+        .map(|rollups| RollupReport {
+            hours,
+            rules: summarize(&rollups).iter().map(report).collect(),
+        });
+
+    let response = StatsResponse {
+        since: rule_stats.since(),
+        rules,
+        rollups,
+    };
+    json_response(serde_json::to_vec(&response).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::rules::CacheRuleBuilder;
+
+    #[test]
+    fn test_live_stats_include_idle_declared_rules() {
+        let declared: HashSet<(String, String)> = [
+            ("dna".to_string(), "get_a".to_string()),
+            ("dna".to_string(), "get_idle".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        let stats = vec![RuleStatsSnapshot {
+            dna_hash: "dna".to_string(),
+            fn_name: "get_a".to_string(),
+            hits: 3,
+            misses: 1,
+            ..Default::default()
+        }];
+
+        let live = live_stats(stats, &declared);
+        assert_eq!(live.len(), 2);
+        assert_eq!(live[0].fn_name, "get_a");
+        assert_eq!(live[1].fn_name, "get_idle");
+        assert!(live[1].is_empty());
+    }
+
+    #[test]
+    fn test_rule_report_carries_ttl() {
+        let rules = CacheRuleStore::new();
+        rules.set_dna_rules("dna", vec![CacheRuleBuilder::new("get_a").ttl_1h().build()]);
+        let declared: HashSet<(String, String)> = [("dna".to_string(), "get_a".to_string())]
+            .into_iter()
+            .collect();
+
+        let stats = RuleStatsSnapshot {
+            dna_hash: "dna".to_string(),
+            fn_name: "get_a".to_string(),
+            hits: 1,
+            misses: 1,
+            ..Default::default()
+        };
+        let report = serde_json::to_value(RuleReport::new(&stats, &rules, &declared)).unwrap();
+        assert_eq!(report["ttl_secs"], 3600);
+        assert_eq!(report["declared"], true);
+        assert_eq!(report["hit_ratio"], 0.5);
+
+        // Convention default for an undeclared get_*
+        let other = RuleStatsSnapshot {
+            fn_name: "get_b".to_string(),
+            ..stats
+        };
+        let report = serde_json::to_value(RuleReport::new(&other, &rules, &declared)).unwrap();
+        assert_eq!(report["declared"], false);
+        assert_eq!(report["ttl_secs"], 300);
+    }
+}
@@ -0,0 +1,398 @@
+//! Caption Upload API
+//!
+//! Accepts WebVTT and SubRip caption files for a content node's media and
+//! registers them as caption tracks with `content_store::add_blob_caption`.
+//! The file is stored like any other blob (local cache, then elohim-storage)
+//! and served from `/store/{caption_hash}`.
+//!
+//! ## Routes
+//!
+//! - `POST /content/{content_id}/captions` - Upload a caption file (raw body)
+//!
+//! ## Query Parameters
+//!
+//! | Param | Description |
+//! |-------|-------------|
+//! | `language` | BCP 47 language tag (required) |
+//! | `label` | Track label shown in players |
+//! | `blob_hash` | Media blob the captions are for; may be omitted when the content has exactly one blob |
+//! | `format` | `vtt` or `srt`; detected from the file when omitted |
+//!
+//! Requires a token for the content's author, a steward or an admin.
+
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::Incoming;
+use hyper::{Request, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use super::api::error_response;
+use super::auth_helpers::require_user;
+use super::seed::forward_to_storage;
+use crate::auth::PermissionLevel;
+use crate::server::AppState;
+
+/// Role holding the content_store zome
+const CONTENT_ROLE: &str = "lamad";
+
+/// Zome owning content and caption tracks
+const CONTENT_ZOME: &str = "content_store";
+
+/// Largest caption file accepted
+const MAX_CAPTION_BYTES: usize = 2 * 1024 * 1024;
+
+/// How long uploaded captions stay in the local blob cache
+const CAPTION_CACHE_TTL: std::time::Duration = std::time::Duration::from_secs(3600);
+
+#[derive(Debug, Default, Deserialize)]
+struct CaptionUploadParams {
+    language: Option<String>,
+    label: Option<String>,
+    blob_hash: Option<String>,
+    format: Option<String>,
+}
+
+/// Extract the content id from `/content/{content_id}/captions`
+pub fn parse_captions_path(path: &str) -> Option<&str> {
+    path.strip_prefix("/content/")?
+        .strip_suffix("/captions")
+        .filter(|id| !id.is_empty() && !id.contains('/'))
+}
+
+/// Loose BCP 47 check: alphabetic primary subtag, alphanumeric subtags
+pub(crate) fn is_language_tag(tag: &str) -> bool {
+    let mut subtags = tag.split('-');
+    let primary_ok = subtags
+        .next()
+        .is_some_and(|p| (2..=8).contains(&p.len()) && p.chars().all(|c| c.is_ascii_alphabetic()));
+    primary_ok
+        && subtags
+            .all(|s| (1..=8).contains(&s.len()) && s.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+/// Recognize the caption format from the file contents
+fn detect_format(text: &str) -> Option<&'static str> {
+    let text = text.trim_start_matches('\u{feff}');
+    if text.starts_with("WEBVTT") {
+        return Some("vtt");
+    }
+
+    // SubRip: a cue number followed by a `start --> end` timing line
+    let mut lines = text.lines().map(str::trim).skip_while(|l| l.is_empty());
+    let index_ok = lines
+        .next()
+        .is_some_and(|l| !l.is_empty() && l.chars().all(|c| c.is_ascii_digit()));
+    let timing_ok = lines.next().is_some_and(|l| l.contains("-->"));
+    (index_ok && timing_ok).then_some("srt")
+}
+
+/// Must match AddBlobCaptionInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct AddBlobCaptionInput {
+    blob_hash: String,
+    content_id: String,
+    language: String,
+    label: Option<String>,
+    format: String,
+    caption_hash: String,
+    size_bytes: u64,
+    author_id: Option<String>,
+}
+
+/// Input for content_store::get_content_by_id
+#[derive(Debug, Serialize)]
+struct QueryByIdInput {
+    id: String,
+}
+
+/// Subset of ContentOutput needed to check authorship
+#[derive(Debug, Deserialize)]
+struct ContentOutput {
+    content: ContentAuthor,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContentAuthor {
+    author_id: Option<String>,
+}
+
+/// Input for content_store::get_blobs_by_content_id
+#[derive(Debug, Serialize)]
+struct QueryBlobsByContentIdInput {
+    content_id: String,
+}
+
+/// Subset of BlobMetadataOutput
+#[derive(Debug, Deserialize)]
+struct BlobMetadata {
+    hash: String,
+}
+
+#[derive(Debug, Serialize)]
+struct CaptionUploadResponse {
+    content_id: String,
+    blob_hash: String,
+    language: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+    format: String,
+    caption_hash: String,
+    url: String,
+    size_bytes: u64,
+    forwarded_to_storage: bool,
+}
+
+/// Handle POST /content/{content_id}/captions
+pub async fn handle_caption_upload(
+    req: Request<Incoming>,
+    state: Arc<AppState>,
+    content_id: String,
+) -> Response<Full<Bytes>> {
+    let auth_header = req
+        .headers()
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string());
+    let claims = match require_user(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let params: CaptionUploadParams =
+        match serde_urlencoded::from_str(req.uri().query().unwrap_or("")) {
+            Ok(params) => params,
+            Err(e) => {
+                return error_response(
+                    StatusCode::BAD_REQUEST,
+                    &format!("Invalid query parameters: {e}"),
+                    "INVALID_QUERY",
+                )
+            }
+        };
+    let Some(language) = params.language.filter(|l| is_language_tag(l)) else {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "A valid language tag is required (e.g. language=en or language=pt-BR)",
+            "INVALID_LANGUAGE",
+        );
+    };
+
+    let Some(zome_caller) = state.zome_caller.clone() else {
+        return error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Conductor not available",
+            "CONDUCTOR_UNAVAILABLE",
+        );
+    };
+
+    let body = match Limited::new(req.into_body(), MAX_CAPTION_BYTES)
+        .collect()
+        .await
+    {
+        Ok(collected) => collected.to_bytes(),
+        Err(_) => {
+            return error_response(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                &format!("Caption files are limited to {MAX_CAPTION_BYTES} bytes"),
+                "TOO_LARGE",
+            )
+        }
+    };
+    let Ok(text) = std::str::from_utf8(&body) else {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "Caption file must be UTF-8 text",
+            "INVALID_CAPTION",
+        );
+    };
+    let Some(format) = detect_format(text) else {
+        return error_response(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "Expected a WebVTT or SubRip (SRT) caption file",
+            "INVALID_CAPTION",
+        );
+    };
+    if let Some(ref declared) = params.format {
+        let declared = if declared == "webvtt" {
+            "vtt"
+        } else {
+            declared.as_str()
+        };
+        if declared != format {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                &format!("File is {format}, not {declared}"),
+                "FORMAT_MISMATCH",
+            );
+        }
+    }
+
+    // Only the author or a steward may attach captions
+    let content: Option<ContentOutput> = match zome_caller
+        .call(
+            CONTENT_ROLE,
+            CONTENT_ZOME,
+            "get_content_by_id",
+            &QueryByIdInput {
+                id: content_id.clone(),
+            },
+        )
+        .await
+    {
+        Ok(content) => content,
+        Err(e) => {
+            warn!(content_id = %content_id, error = %e, "Failed to load content for caption upload");
+            return error_response(
+                StatusCode::BAD_GATEWAY,
+                "Failed to load content",
+                "ZOME_ERROR",
+            );
+        }
+    };
+    let Some(content) = content else {
+        return error_response(StatusCode::NOT_FOUND, "Content not found", "NOT_FOUND");
+    };
+    let is_author = content.content.author_id.as_deref() == Some(claims.human_id.as_str());
+    if !is_author && !claims.is_steward && claims.permission_level < PermissionLevel::Admin {
+        return error_response(
+            StatusCode::FORBIDDEN,
+            "Only the content author or a steward can add captions",
+            "FORBIDDEN",
+        );
+    }
+
+    let blob_hash = match params.blob_hash.filter(|h| !h.is_empty()) {
+        Some(hash) => hash,
+        None => {
+            let blobs: Vec<BlobMetadata> = zome_caller
+                .call(
+                    CONTENT_ROLE,
+                    CONTENT_ZOME,
+                    "get_blobs_by_content_id",
+                    &QueryBlobsByContentIdInput {
+                        content_id: content_id.clone(),
+                    },
+                )
+                .await
+                .unwrap_or_default();
+            match blobs.as_slice() {
+                [blob] => blob.hash.clone(),
+                _ => {
+                    return error_response(
+                        StatusCode::BAD_REQUEST,
+                        "blob_hash is required when content does not have exactly one blob",
+                        "BLOB_REQUIRED",
+                    )
+                }
+            }
+        }
+    };
+
+    let caption_hash = format!("sha256-{}", hex::encode(Sha256::digest(&body)));
+    let content_type = if format == "vtt" {
+        "text/vtt"
+    } else {
+        "application/x-subrip"
+    };
+    state.cache.set(
+        &caption_hash,
+        body.to_vec(),
+        content_type,
+        CAPTION_CACHE_TTL,
+    );
+    let forwarded = forward_to_storage(&state, &caption_hash, Some(&body)).await;
+
+    let input = AddBlobCaptionInput {
+        blob_hash: blob_hash.clone(),
+        content_id: content_id.clone(),
+        language: language.clone(),
+        label: params.label.clone(),
+        format: format.to_string(),
+        caption_hash: caption_hash.clone(),
+        size_bytes: body.len() as u64,
+        author_id: Some(claims.human_id.clone()),
+    };
+    if let Err(e) = zome_caller
+        .call::<_, serde_json::Value>(CONTENT_ROLE, CONTENT_ZOME, "add_blob_caption", &input)
+        .await
+    {
+        warn!(content_id = %content_id, error = %e, "Failed to register caption track");
+        return error_response(
+            StatusCode::BAD_GATEWAY,
+            "Failed to register caption track",
+            "ZOME_ERROR",
+        );
+    }
+
+    info!(
+        content_id = %content_id,
+        blob_hash = %blob_hash,
+        language = %language,
+        format,
+        "Caption track added"
+    );
+    let response = CaptionUploadResponse {
+        content_id,
+        blob_hash,
+        language,
+        label: params.label,
+        format: format.to_string(),
+        url: format!("/store/{caption_hash}"),
+        caption_hash,
+        size_bytes: body.len() as u64,
+        forwarded_to_storage: forwarded,
+    };
+    Response::builder()
+        .status(StatusCode::CREATED)
+        .header("Content-Type", "application/json")
+        .header("Cache-Control", "no-cache")
+        .body(Full::new(Bytes::from(
+            serde_json::to_vec(&response).unwrap_or_default(),
+        )))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_captions_path() {
+        assert_eq!(
+            parse_captions_path("/content/intro-video/captions"),
+            Some("intro-video")
+        );
+        assert_eq!(parse_captions_path("/content//captions"), None);
+        assert_eq!(parse_captions_path("/content/a/b/captions"), None);
+        assert_eq!(parse_captions_path("/content/intro-video"), None);
+    }
+
+    #[test]
+    fn test_is_language_tag() {
+        assert!(is_language_tag("en"));
+        assert!(is_language_tag("pt-BR"));
+        assert!(is_language_tag("zh-Hant-TW"));
+        assert!(!is_language_tag(""));
+        assert!(!is_language_tag("e"));
+        assert!(!is_language_tag("en_US"));
+        assert!(!is_language_tag("en-"));
+    }
+
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(
+            detect_format("WEBVTT\n\n00:00.000 --> 00:01.000\nHello"),
+            Some("vtt")
+        );
+        assert_eq!(detect_format("\u{feff}WEBVTT - Title\n"), Some("vtt"));
+        assert_eq!(
+            detect_format("\n1\n00:00:00,000 --> 00:00:01,000\nHello\n"),
+            Some("srt")
+        );
+        assert_eq!(detect_format("Hello world"), None);
+        assert_eq!(detect_format("1\nHello"), None);
+    }
+}
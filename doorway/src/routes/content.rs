@@ -0,0 +1,312 @@
+//! Content Query API
+//!
+//! HTTP front for content_store query functions that combine several
+//! index filters in one call, so clients don't fetch by type and then
+//! filter the result set themselves.
+//!
+//! ## Routes
+//!
+//! - `GET /api/v1/content/query` - Combined filters (type AND tags AND reach ...)
+//!
+//! ## Query Parameters
+//!
+//! | Param | Description |
+//! |-------|-------------|
+//! | `type` | Content type |
+//! | `tags` | Comma-separated tags, all must match |
+//! | `any_tags` | Comma-separated tags, at least one must match |
+//! | `reach` | Exact reach level |
+//! | `author` | Author agent pubkey |
+//! | `created_after` | Microseconds since epoch |
+//! | `sort` | `created_at_desc`, `created_at_asc`, `title`, `estimated_minutes` |
+//! | `limit` | Page size (max 100, default 20) |
+//! | `cursor` | `next`/`prev` of a previous page |
+//! | `fields` | Comma-separated fields of each item to return (see [`fields`](super::fields)) |
+//!
+//! Results come in the [pagination](super::pagination) envelope.
+//!
+//! Responses are cached in the doorway [`ContentCache`](crate::cache::ContentCache)
+//! using the TTL declared by the DNA's `query_content` cache rule, and only
+//! when the rule's reach condition holds for every item: this route answers
+//! anyone, so a page holding non-commons content is never replayed.
+
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::{Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+use super::api::{error_response, overloaded_response};
+use super::fields::FieldSelection;
+use super::pagination::{page_response, Page, PageRequest};
+use super::zome_helpers::{call_content_store, get_content_store_config};
+use crate::cache::rules::{CacheRule, CacheRuleExt};
+use crate::server::AppState;
+use crate::types::DoorwayError;
+
+/// Zome function backing `/api/v1/content/query`
+const QUERY_CONTENT_FN: &str = "query_content";
+
+/// Default page size when `limit` is not given
+const DEFAULT_PAGE_SIZE: usize = 20;
+
+/// Largest page size accepted
+const MAX_PAGE_SIZE: usize = 100;
+
+/// Raw query string parameters
+#[derive(Debug, Default, Deserialize)]
+struct ContentQueryParams {
+    #[serde(rename = "type")]
+    content_type: Option<String>,
+    tags: Option<String>,
+    any_tags: Option<String>,
+    reach: Option<String>,
+    author: Option<String>,
+    created_after: Option<i64>,
+    sort: Option<String>,
+}
+
+/// Input for content_store::query_content
+/// Must match QueryContentInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryContentInput {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    pub tags_all: Vec<String>,
+    pub tags_any: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reach: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_after: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+    pub page_size: u32,
+    pub offset: u32,
+}
+
+impl ContentQueryParams {
+    fn into_input(self, page: PageRequest) -> QueryContentInput {
+        QueryContentInput {
+            content_type: self.content_type.filter(|s| !s.is_empty()),
+            tags_all: split_list(self.tags.as_deref()),
+            tags_any: split_list(self.any_tags.as_deref()),
+            reach: self.reach.filter(|s| !s.is_empty()),
+            author: self.author.filter(|s| !s.is_empty()),
+            created_after: self.created_after,
+            sort: self.sort.filter(|s| !s.is_empty()),
+            page_size: page.limit as u32,
+            offset: page.offset as u32,
+        }
+    }
+}
+
+/// Split a comma-separated list, dropping empty items
+fn split_list(value: Option<&str>) -> Vec<String> {
+    value
+        .unwrap_or("")
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Parse query string into zome input
+fn parse_content_query(query: Option<&str>) -> Result<QueryContentInput, String> {
+    let params: ContentQueryParams = serde_urlencoded::from_str(query.unwrap_or(""))
+        .map_err(|e| format!("Invalid query parameters: {e}"))?;
+    let page = PageRequest::from_query(query, DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE)?;
+    let input = params.into_input(page);
+
+    if input.content_type.is_none() && input.tags_all.is_empty() && input.tags_any.is_empty() {
+        return Err("At least one of type, tags or any_tags is required".to_string());
+    }
+
+    Ok(input)
+}
+
+/// Zome output (`PaginatedContentOutput`) in the pagination envelope
+fn content_page(output: &[u8], input: &QueryContentInput) -> Page<Value> {
+    let request = PageRequest {
+        limit: input.page_size as usize,
+        offset: input.offset as usize,
+    };
+    let output: Value = serde_json::from_slice(output).unwrap_or_default();
+    let items = match output.get("items") {
+        Some(Value::Array(items)) => items.clone(),
+        _ => vec![],
+    };
+    let has_more = output["has_more"].as_bool().unwrap_or(false);
+    let total = output["total_count"].as_u64().map(|total| total as usize);
+    Page::new(items, request, has_more, total)
+}
+
+/// Whether a query result may be cached and replayed to any caller
+fn is_cacheable_result(rule: Option<&CacheRule>, data: &Value) -> bool {
+    rule.is_some_and(|rule| rule.is_public_response(data))
+}
+
+/// Handle GET /api/v1/content/query
+pub async fn handle_content_query(state: Arc<AppState>, query: Option<&str>) -> Response<Full<Bytes>> {
+    let input = match parse_content_query(query) {
+        Ok(input) => input,
+        Err(msg) => return error_response(StatusCode::BAD_REQUEST, &msg, "INVALID_QUERY"),
+    };
+    let fields = match FieldSelection::from_query(query) {
+        Ok(fields) => fields,
+        Err(msg) => return error_response(StatusCode::BAD_REQUEST, &msg, "INVALID_QUERY"),
+    };
+    let respond = |page: Page<Value>| match fields {
+        Some(ref fields) => page_response(&page.map_ref(|item| fields.apply_to_content(item))),
+        None => page_response(&page),
+    };
+
+    let config = match get_content_store_config(&state) {
+        Ok(config) => config,
+        Err(e) => {
+            warn!(error = ?e, "Content zome not available");
+            return error_response(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Content zome not available",
+                "CONDUCTOR_UNAVAILABLE",
+            );
+        }
+    };
+
+    let cache_key = state
+        .cache_rules
+        .cache_key(
+            &config.dna_hash,
+            &config.zome_name,
+            QUERY_CONTENT_FN,
+            &input,
+        )
+        .to_storage_key();
+
+    let rule = state
+        .cache_rules
+        .get_rule(&config.dna_hash, QUERY_CONTENT_FN);
+    let replayable = |data: &[u8]| {
+        serde_json::from_slice(data).is_ok_and(|data| is_cacheable_result(rule.as_ref(), &data))
+    };
+
+    // Serve from cache when a previous identical query is still fresh
+    if let Some(entry) = state.cache.get(&cache_key).filter(|e| replayable(&e.data)) {
+        debug!("Content query cache hit");
+        return respond(content_page(&entry.data, &input));
+    }
+
+    match call_content_store(&state, QUERY_CONTENT_FN, &input).await {
+        Ok(Some(data)) => {
+            let body = serde_json::to_vec(&data).unwrap_or_default();
+            if is_cacheable_result(rule.as_ref(), &data) {
+                let ttl = rule
+                    .as_ref()
+                    .map(|rule| rule.ttl())
+                    .unwrap_or(state.cache.config().list_ttl);
+                let refs = state
+                    .cache_rules
+                    .cache_refs(&config.dna_hash, QUERY_CONTENT_FN, &input);
+                state
+                    .cache
+                    .set_with_refs(&cache_key, body.clone(), "application/json", ttl, refs);
+            } else {
+                debug!("Content query holds non-commons items, not cached");
+            }
+            respond(content_page(&body, &input))
+        }
+        Ok(None) => respond(content_page(b"null", &input)),
+        Err(e) => {
+            warn!(error = ?e, "Content query failed");
+            // The last result beats an error while the conductor is away
+            match state
+                .cache
+                .get_stale(&cache_key)
+                .filter(|e| replayable(&e.data))
+            {
+                Some(entry) => respond(content_page(&entry.data, &input)),
+                None => match e {
+                    DoorwayError::Overloaded(msg) => overloaded_response(&msg),
+                    _ => error_response(StatusCode::BAD_GATEWAY, "Query failed", "QUERY_FAILED"),
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::rules::CacheRuleBuilder;
+
+    #[test]
+    fn test_parse_content_query_combined() {
+        let input =
+            parse_content_query(Some("type=video&tags=a,b&any_tags=c&reach=commons&limit=500"))
+                .unwrap();
+        assert_eq!(input.content_type.as_deref(), Some("video"));
+        assert_eq!(input.tags_all, vec!["a", "b"]);
+        assert_eq!(input.tags_any, vec!["c"]);
+        assert_eq!(input.reach.as_deref(), Some("commons"));
+        assert_eq!(input.page_size, 100);
+        assert_eq!(input.offset, 0);
+    }
+
+    #[test]
+    fn test_parse_content_query_decodes_values() {
+        let input = parse_content_query(Some("tags=systems%20thinking,%20ethics")).unwrap();
+        assert_eq!(input.tags_all, vec!["systems thinking", "ethics"]);
+        assert_eq!(input.page_size, DEFAULT_PAGE_SIZE as u32);
+    }
+
+    #[test]
+    fn test_parse_content_query_requires_index_filter() {
+        assert!(parse_content_query(Some("reach=commons")).is_err());
+        assert!(parse_content_query(None).is_err());
+        assert!(parse_content_query(Some("type=")).is_err());
+    }
+
+    #[test]
+    fn test_content_page_from_zome_output() {
+        let input = parse_content_query(Some("type=video&limit=2&offset=2")).unwrap();
+        let output =
+            br#"{"items":[{"id":"c"},{"id":"d"}],"total_count":5,"offset":2,"has_more":true}"#;
+
+        let page = content_page(output, &input);
+        assert_eq!(page.items.len(), 2);
+        assert_eq!(page.total, Some(5));
+        assert!(page.next.is_some() && page.prev.is_some());
+
+        assert!(content_page(b"null", &input).items.is_empty());
+    }
+
+    #[test]
+    fn test_mixed_reach_result_not_cached() {
+        let rule = CacheRuleBuilder::new(QUERY_CONTENT_FN)
+            .ttl_15m()
+            .reach_based("items.content.reach", "commons")
+            .build();
+        let commons = serde_json::json!({"items": [
+            {"content": {"id": "a", "reach": "commons"}},
+            {"content": {"id": "b", "reach": "commons"}}
+        ], "total_count": 2, "has_more": false});
+        let mixed = serde_json::json!({"items": [
+            {"content": {"id": "a", "reach": "commons"}},
+            {"content": {"id": "b", "reach": "community"}}
+        ], "total_count": 2, "has_more": false});
+
+        assert!(is_cacheable_result(Some(&rule), &commons));
+        assert!(!is_cacheable_result(Some(&rule), &mixed));
+        // Without a rule there is no reach condition to check
+        assert!(!is_cacheable_result(None, &commons));
+    }
+
+    #[test]
+    fn test_parse_content_query_invalid_number() {
+        assert!(parse_content_query(Some("type=video&limit=abc")).is_err());
+    }
+}
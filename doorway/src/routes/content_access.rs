@@ -0,0 +1,293 @@
+//! Content Access Routes
+//!
+//! Steward dashboard for opt-in [content access logging](crate::services::content_access).
+//!
+//! ## Routes
+//!
+//! - `GET /steward/content-access?days=30` - Tracked content with views and referrer buckets over the span
+//! - `GET /steward/content-access/{id}?days=30` - Daily views of one content node
+//! - `PUT /steward/content-access/{id}` - Mark or unmark content: `{"track_access": true}`
+//!
+//! `days` counts back from today (UTC), at most 365. Views from the current
+//! flush interval aren't in the rollups yet.
+
+use bytes::Bytes;
+use chrono::Utc;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::Incoming;
+use hyper::{Request, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::info;
+
+use super::api::{error_response, json_response};
+use super::auth_helpers::require_steward;
+use crate::db::schemas::ReferrerCounts;
+use crate::server::AppState;
+use crate::services::content_access::{first_day, ContentAccessLog};
+
+/// Span of days when `days` is not given
+const DEFAULT_DAYS: u32 = 30;
+
+/// Longest span a dashboard may ask for
+const MAX_DAYS: u32 = 365;
+
+/// Largest mark body accepted
+const MAX_MARK_BYTES: usize = 1024;
+
+#[derive(Debug, Default, Deserialize)]
+struct AccessParams {
+    days: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MarkBody {
+    track_access: bool,
+}
+
+/// Views of one content node over the span
+#[derive(Debug, Default, Serialize)]
+struct AccessSummary {
+    content_id: String,
+    tracked: bool,
+    views: u64,
+    referrers: ReferrerCounts,
+}
+
+#[derive(Debug, Serialize)]
+struct AccessDay {
+    day: String,
+    views: u64,
+    referrers: ReferrerCounts,
+}
+
+/// Extract the content id from `/steward/content-access/{id}`
+pub fn parse_content_access_path(path: &str) -> Option<&str> {
+    path.strip_prefix("/steward/content-access/")
+        .filter(|id| !id.is_empty() && !id.contains('/'))
+}
+
+#[allow(clippy::result_large_err)]
+fn access_log(state: &AppState) -> Result<&Arc<ContentAccessLog>, Response<Full<Bytes>>> {
+    state.content_access.as_ref().ok_or_else(|| {
+        error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Content access logging is not enabled",
+            "ACCESS_LOG_DISABLED",
+        )
+    })
+}
+
+#[allow(clippy::result_large_err)]
+fn first_day_of(query: Option<&str>) -> Result<String, Response<Full<Bytes>>> {
+    let params: AccessParams = serde_urlencoded::from_str(query.unwrap_or("")).map_err(|e| {
+        error_response(
+            StatusCode::BAD_REQUEST,
+            &format!("Invalid query parameters: {e}"),
+            "INVALID_QUERY",
+        )
+    })?;
+    let days = params.days.unwrap_or(DEFAULT_DAYS).clamp(1, MAX_DAYS);
+    Ok(first_day(days, Utc::now()))
+}
+
+/// Handle GET /steward/content-access
+pub async fn handle_content_access(
+    state: Arc<AppState>,
+    query: Option<&str>,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    if let Err(response) = require_steward(&state, auth_header.as_deref()) {
+        return response;
+    }
+    let log = match access_log(&state) {
+        Ok(log) => log,
+        Err(response) => return response,
+    };
+    let since = match first_day_of(query) {
+        Ok(since) => since,
+        Err(response) => return response,
+    };
+
+    let marks = match log.tracked_marks().await {
+        Ok(marks) => marks,
+        Err(e) => return error_response(StatusCode::INTERNAL_SERVER_ERROR, &e, "DB_ERROR"),
+    };
+    let rollups = match log.rollups_since(None, &since).await {
+        Ok(rollups) => rollups,
+        Err(e) => return error_response(StatusCode::INTERNAL_SERVER_ERROR, &e, "DB_ERROR"),
+    };
+
+    // Content tracked earlier keeps its views after being unmarked
+    let mut summaries: HashMap<String, AccessSummary> = marks
+        .into_iter()
+        .map(|mark| {
+            let summary = AccessSummary {
+                content_id: mark.content_id.clone(),
+                tracked: true,
+                ..Default::default()
+            };
+            (mark.content_id, summary)
+        })
+        .collect();
+    for rollup in rollups {
+        let summary = summaries
+            .entry(rollup.content_id.clone())
+            .or_insert_with(|| AccessSummary {
+                content_id: rollup.content_id,
+                ..Default::default()
+            });
+        summary.views += rollup.views;
+        summary.referrers.merge(&rollup.referrers);
+    }
+    let mut summaries: Vec<AccessSummary> = summaries.into_values().collect();
+    summaries.sort_by(|a, b| {
+        b.views
+            .cmp(&a.views)
+            .then_with(|| a.content_id.cmp(&b.content_id))
+    });
+
+    json_response(
+        serde_json::to_vec(&serde_json::json!({
+            "since": since,
+            "content": summaries,
+        }))
+        .unwrap_or_default(),
+    )
+}
+
+/// Handle GET /steward/content-access/{id}
+pub async fn handle_content_access_detail(
+    state: Arc<AppState>,
+    content_id: &str,
+    query: Option<&str>,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    if let Err(response) = require_steward(&state, auth_header.as_deref()) {
+        return response;
+    }
+    let log = match access_log(&state) {
+        Ok(log) => log,
+        Err(response) => return response,
+    };
+    let since = match first_day_of(query) {
+        Ok(since) => since,
+        Err(response) => return response,
+    };
+
+    let rollups = match log.rollups_since(Some(content_id), &since).await {
+        Ok(rollups) => rollups,
+        Err(e) => return error_response(StatusCode::INTERNAL_SERVER_ERROR, &e, "DB_ERROR"),
+    };
+    let mut total = AccessSummary {
+        content_id: content_id.to_string(),
+        tracked: log.is_tracked(content_id),
+        ..Default::default()
+    };
+    let days: Vec<AccessDay> = rollups
+        .into_iter()
+        .map(|rollup| {
+            total.views += rollup.views;
+            total.referrers.merge(&rollup.referrers);
+            AccessDay {
+                day: rollup.day,
+                views: rollup.views,
+                referrers: rollup.referrers,
+            }
+        })
+        .collect();
+
+    json_response(
+        serde_json::to_vec(&serde_json::json!({
+            "since": since,
+            "total": total,
+            "days": days,
+        }))
+        .unwrap_or_default(),
+    )
+}
+
+/// Handle PUT /steward/content-access/{id}
+pub async fn handle_mark_content_access(
+    req: Request<Incoming>,
+    state: Arc<AppState>,
+    content_id: String,
+) -> Response<Full<Bytes>> {
+    let auth_header = req
+        .headers()
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string());
+    let claims = match require_steward(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+    let log = match access_log(&state) {
+        Ok(log) => log.clone(),
+        Err(response) => return response,
+    };
+
+    let body = match Limited::new(req.into_body(), MAX_MARK_BYTES)
+        .collect()
+        .await
+    {
+        Ok(collected) => collected.to_bytes(),
+        Err(_) => {
+            return error_response(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                &format!("Mark bodies are limited to {MAX_MARK_BYTES} bytes"),
+                "TOO_LARGE",
+            )
+        }
+    };
+    let mark: MarkBody = match serde_json::from_slice(&body) {
+        Ok(mark) => mark,
+        Err(e) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                &format!("Invalid JSON: {e}"),
+                "INVALID_JSON",
+            )
+        }
+    };
+
+    if let Err(e) = log
+        .set_tracking(&content_id, mark.track_access, &claims.human_id)
+        .await
+    {
+        return error_response(StatusCode::INTERNAL_SERVER_ERROR, &e, "DB_ERROR");
+    }
+    info!(
+        content_id = %content_id,
+        track_access = mark.track_access,
+        steward = %claims.human_id,
+        "Content access tracking changed"
+    );
+    json_response(
+        serde_json::to_vec(&serde_json::json!({
+            "content_id": content_id,
+            "track_access": mark.track_access,
+        }))
+        .unwrap_or_default(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_content_access_path() {
+        assert_eq!(
+            parse_content_access_path("/steward/content-access/intro"),
+            Some("intro")
+        );
+        assert_eq!(parse_content_access_path("/steward/content-access/"), None);
+        assert_eq!(
+            parse_content_access_path("/steward/content-access/a/b"),
+            None
+        );
+        assert_eq!(parse_content_access_path("/steward/content-access"), None);
+    }
+}
@@ -0,0 +1,246 @@
+//! Ranged Content Body Route
+//!
+//! Lets readers load a long markdown body piece by piece instead of in one
+//! multi-megabyte record. Apps fetch the record without its body (the
+//! `get_content_summary_by_id` zome function, or `?fields=` on the content
+//! routes, see [`fields`](super::fields)), render what they have, and page
+//! through the body as the reader scrolls.
+//!
+//! ## Routes
+//!
+//! - `GET /content/{id}/body?offset=&length=` - Up to `length` bytes of the body
+//!   from byte `offset`: `{id, content_format, offset, length, total_length, next_offset?, body}`
+//!
+//! Bodies come from the projection store, never the conductor. Offsets are
+//! UTF-8 byte offsets; a chunk never splits a character, so `length` may
+//! come back a few bytes short and `next_offset` is where to continue.
+//! `commons` and `public` content is served to anyone, other content only to
+//! its signed-in author (everyone else gets 404); [guests](super::guest)
+//! read like anonymous callers. Bodies kept in elohim-storage (`blob_cid`)
+//! answer 409 pointing at the blob, which serves byte ranges itself.
+
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::{Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use super::api::{error_response, json_response};
+use super::auth_helpers::require_user;
+use super::guest::guest_read;
+use crate::projection::ProjectedDocument;
+use crate::server::AppState;
+
+/// Reach levels anyone may read
+pub(crate) const PUBLIC_REACH: [&str; 2] = ["commons", "public"];
+
+/// Chunk size when `length` is not given
+const DEFAULT_CHUNK_BYTES: usize = 64 * 1024;
+
+/// Largest chunk served at once
+const MAX_CHUNK_BYTES: usize = 1024 * 1024;
+
+#[derive(Debug, Default, Deserialize)]
+struct BodyParams {
+    offset: Option<usize>,
+    length: Option<usize>,
+}
+
+/// Response of `GET /content/{id}/body`
+#[derive(Debug, Serialize)]
+struct BodyChunk<'a> {
+    id: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content_format: Option<&'a str>,
+    offset: usize,
+    length: usize,
+    total_length: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_offset: Option<usize>,
+    body: &'a str,
+}
+
+/// Extract the content id from `/content/{id}/body`
+pub fn parse_content_body_path(path: &str) -> Option<&str> {
+    path.strip_prefix("/content/")?
+        .strip_suffix("/body")
+        .filter(|id| !id.is_empty() && !id.contains('/'))
+}
+
+/// Largest char boundary at or below `index`
+fn floor_char_boundary(body: &str, index: usize) -> usize {
+    let mut index = index.min(body.len());
+    while !body.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+/// The chunk of `body` from `offset` of at most `length` bytes, as
+/// `(start, end)` on char boundaries
+///
+/// `None` when `offset` is past the end or inside a character.
+fn chunk_bounds(body: &str, offset: usize, length: usize) -> Option<(usize, usize)> {
+    if offset > body.len() || !body.is_char_boundary(offset) {
+        return None;
+    }
+    let end = floor_char_boundary(body, offset.saturating_add(length));
+    // Always make progress, even when a character is longer than `length`
+    let end = if end == offset && offset < body.len() {
+        body[offset..]
+            .chars()
+            .next()
+            .map_or(offset, |c| offset + c.len_utf8())
+    } else {
+        end
+    };
+    Some((offset, end))
+}
+
+fn text<'a>(doc: &'a ProjectedDocument, field: &str) -> Option<&'a str> {
+    doc.data.get(field).and_then(|v| v.as_str())
+}
+
+/// Whether anyone may read content at a reach level (`commons` or `public`)
+pub(crate) fn is_public_reach(reach: &str) -> bool {
+    PUBLIC_REACH.contains(&reach)
+}
+
+/// A document's reach: the projected field, else the record's own
+pub(crate) fn document_reach(doc: &ProjectedDocument) -> Option<&str> {
+    doc.reach.as_deref().or_else(|| text(doc, "reach"))
+}
+
+/// Whether anyone may read the document (reach `commons` or `public`)
+pub(crate) fn is_public(doc: &ProjectedDocument) -> bool {
+    is_public_reach(document_reach(doc).unwrap_or("private"))
+}
+
+/// Whether the requester may read the document's body
+#[allow(clippy::result_large_err)]
+fn readable(
+    state: &AppState,
+    doc: &ProjectedDocument,
+    auth_header: Option<&str>,
+) -> Result<bool, Response<Full<Bytes>>> {
+    if is_public(doc) {
+        return Ok(true);
+    }
+    if auth_header.is_none() {
+        return Ok(false);
+    }
+    let claims = require_user(state, auth_header)?;
+    Ok(doc.author == claims.agent_pub_key)
+}
+
+/// Handle GET /content/{id}/body
+pub async fn handle_content_body(
+    state: Arc<AppState>,
+    id: &str,
+    query: Option<&str>,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    let params: BodyParams = match serde_urlencoded::from_str(query.unwrap_or("")) {
+        Ok(params) => params,
+        Err(e) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                &format!("Invalid query parameters: {e}"),
+                "INVALID_QUERY",
+            )
+        }
+    };
+    // Guests read like anyone else who isn't signed in
+    let auth_header = match guest_read(&state, auth_header.as_deref()) {
+        Ok(true) => None,
+        Ok(false) => auth_header,
+        Err(response) => return response,
+    };
+    let Some(ref projection) = state.projection else {
+        return error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Projection store not available",
+            "PROJECTION_UNAVAILABLE",
+        );
+    };
+
+    let not_found = || {
+        error_response(
+            StatusCode::NOT_FOUND,
+            &format!("Not found: Content/{id}"),
+            "NOT_FOUND",
+        )
+    };
+    let Some(doc) = projection.get("Content", id).await else {
+        return not_found();
+    };
+    match readable(&state, &doc, auth_header.as_deref()) {
+        Ok(true) => {}
+        Ok(false) => return not_found(),
+        Err(response) => return response,
+    }
+
+    let body = text(&doc, "content").unwrap_or_default();
+    if let Some(cid) = text(&doc, "blob_cid").filter(|_| body.is_empty()) {
+        return error_response(
+            StatusCode::CONFLICT,
+            &format!("Body is stored as a blob; fetch /store/{cid} with a Range header"),
+            "BODY_IN_BLOB",
+        );
+    }
+
+    let offset = params.offset.unwrap_or(0);
+    let length = params
+        .length
+        .unwrap_or(DEFAULT_CHUNK_BYTES)
+        .clamp(1, MAX_CHUNK_BYTES);
+    let Some((start, end)) = chunk_bounds(body, offset, length) else {
+        return error_response(
+            StatusCode::RANGE_NOT_SATISFIABLE,
+            &format!(
+                "offset must be a character boundary within {} bytes",
+                body.len()
+            ),
+            "INVALID_OFFSET",
+        );
+    };
+
+    let chunk = BodyChunk {
+        id,
+        content_format: text(&doc, "content_format"),
+        offset: start,
+        length: end - start,
+        total_length: body.len(),
+        next_offset: (end < body.len()).then_some(end),
+        body: &body[start..end],
+    };
+    json_response(serde_json::to_vec(&chunk).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_content_body_path() {
+        assert_eq!(
+            parse_content_body_path("/content/intro/body"),
+            Some("intro")
+        );
+        assert_eq!(parse_content_body_path("/content//body"), None);
+        assert_eq!(parse_content_body_path("/content/a/b/body"), None);
+    }
+
+    #[test]
+    fn test_chunks_keep_characters_whole() {
+        let body = "ab€cd";
+        assert_eq!(chunk_bounds(body, 0, 3), Some((0, 2)));
+        assert_eq!(chunk_bounds(body, 2, 3), Some((2, 5)));
+        // A character longer than the chunk is still served whole
+        assert_eq!(chunk_bounds(body, 2, 1), Some((2, 5)));
+        assert_eq!(chunk_bounds(body, 5, 100), Some((5, 7)));
+        assert_eq!(chunk_bounds(body, 7, 100), Some((7, 7)));
+        assert_eq!(chunk_bounds(body, 3, 1), None);
+        assert_eq!(chunk_bounds(body, 8, 1), None);
+    }
+}
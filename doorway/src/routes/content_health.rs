@@ -0,0 +1,206 @@
+//! Content Health API
+//!
+//! Steward triage queue backed by the reports from the
+//! [content health sweep](crate::worker::content_health).
+//!
+//! ## Routes
+//!
+//! - `GET /steward/content-health` - Content with open maintenance issues
+//!
+//! ## Query Parameters
+//!
+//! | Param | Description |
+//! |-------|-------------|
+//! | `kind` | `stale`, `broken_fallback_url` or `archived_relationship` |
+//! | `content_type` | Only this content type |
+//! | `limit` | Page size (default 50, max 200) |
+//! | `cursor` | `next` or `prev` of a previous page |
+//!
+//! Reports are ordered by number of issues, most first, and served in the
+//! [page envelope](super::pagination). Requires a steward or admin token.
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use http_body_util::Full;
+use hyper::{Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::warn;
+
+use super::api::error_response;
+use super::auth_helpers::require_steward;
+use super::pagination::{page_response, Page, PageRequest};
+use crate::db::schemas::{
+    ContentHealthIssue, ContentHealthIssueKind, ContentHealthReportDoc, CONTENT_HEALTH_COLLECTION,
+};
+use crate::server::AppState;
+
+/// Default page size
+const DEFAULT_LIMIT: usize = 50;
+
+/// Largest `limit` accepted
+const MAX_LIMIT: usize = 200;
+
+#[derive(Debug, Default, Deserialize)]
+struct ContentHealthParams {
+    kind: Option<String>,
+    content_type: Option<String>,
+}
+
+/// Parsed filters
+#[derive(Debug, PartialEq)]
+struct ContentHealthQuery {
+    kind: Option<ContentHealthIssueKind>,
+    content_type: Option<String>,
+    page: PageRequest,
+}
+
+fn parse_query(query: Option<&str>) -> Result<ContentHealthQuery, String> {
+    let params: ContentHealthParams = serde_urlencoded::from_str(query.unwrap_or(""))
+        .map_err(|e| format!("Invalid query parameters: {e}"))?;
+
+    let kind = match params.kind.as_deref() {
+        None | Some("") => None,
+        Some(value) => Some(ContentHealthIssueKind::parse(value).ok_or_else(|| {
+            format!(
+                "Invalid kind '{value}', expected stale, broken_fallback_url or archived_relationship"
+            )
+        })?),
+    };
+
+    Ok(ContentHealthQuery {
+        kind,
+        content_type: params.content_type.filter(|t| !t.is_empty()),
+        page: PageRequest::from_query(query, DEFAULT_LIMIT, MAX_LIMIT)?,
+    })
+}
+
+/// Report as served to stewards
+#[derive(Debug, Serialize)]
+struct ContentHealthReportView<'a> {
+    content_id: &'a str,
+    title: &'a str,
+    content_type: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    author_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_updated_at: Option<DateTime<Utc>>,
+    issues: &'a [ContentHealthIssue],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    checked_at: Option<DateTime<Utc>>,
+}
+
+impl<'a> From<&'a ContentHealthReportDoc> for ContentHealthReportView<'a> {
+    fn from(report: &'a ContentHealthReportDoc) -> Self {
+        Self {
+            content_id: &report.content_id,
+            title: &report.title,
+            content_type: &report.content_type,
+            author_id: report.author_id.as_deref(),
+            last_updated_at: report.last_updated_at,
+            issues: &report.issues,
+            checked_at: report.checked_at,
+        }
+    }
+}
+
+/// Handle GET /steward/content-health
+pub async fn handle_content_health(
+    state: Arc<AppState>,
+    query: Option<&str>,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    if let Err(response) = require_steward(&state, auth_header.as_deref()) {
+        return response;
+    }
+
+    let query = match parse_query(query) {
+        Ok(query) => query,
+        Err(msg) => return error_response(StatusCode::BAD_REQUEST, &msg, "INVALID_QUERY"),
+    };
+
+    let Some(ref mongo) = state.mongo else {
+        return error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Content health storage not available",
+            "DATABASE_UNAVAILABLE",
+        );
+    };
+
+    let collection = match mongo
+        .collection::<ContentHealthReportDoc>(CONTENT_HEALTH_COLLECTION)
+        .await
+    {
+        Ok(collection) => collection,
+        Err(e) => {
+            warn!(error = %e, "Content health collection unavailable");
+            return error_response(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Content health storage not available",
+                "DATABASE_UNAVAILABLE",
+            );
+        }
+    };
+
+    let mut filter = bson::doc! {};
+    if let Some(kind) = query.kind {
+        filter.insert("issues.kind", bson::to_bson(&kind).unwrap_or_default());
+    }
+    if let Some(ref content_type) = query.content_type {
+        filter.insert("content_type", content_type.as_str());
+    }
+
+    let mut reports = match collection.find_many(filter).await {
+        Ok(reports) => reports,
+        Err(e) => {
+            warn!(error = %e, "Failed to load content health reports");
+            return error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to load content health reports",
+                "DATABASE_ERROR",
+            );
+        }
+    };
+    reports.sort_by(|a, b| b.issues.len().cmp(&a.issues.len()));
+
+    let page = Page::from_all(reports, query.page);
+    page_response(&page.map_ref(ContentHealthReportView::from))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_query_defaults() {
+        let query = parse_query(None).unwrap();
+        assert_eq!(query.kind, None);
+        assert_eq!(query.content_type, None);
+        assert_eq!(
+            query.page,
+            PageRequest {
+                limit: DEFAULT_LIMIT,
+                offset: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_query_filters() {
+        let query = parse_query(Some(
+            "kind=broken_fallback_url&content_type=lesson&limit=999&offset=20",
+        ))
+        .unwrap();
+        assert_eq!(query.kind, Some(ContentHealthIssueKind::BrokenFallbackUrl));
+        assert_eq!(query.content_type.as_deref(), Some("lesson"));
+        assert_eq!(
+            query.page,
+            PageRequest {
+                limit: MAX_LIMIT,
+                offset: 20
+            }
+        );
+
+        assert!(parse_query(Some("kind=outdated")).is_err());
+    }
+}
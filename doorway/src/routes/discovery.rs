@@ -0,0 +1,219 @@
+//! Discovery Route
+//!
+//! One machine-readable document of everything the doorway has learned from
+//! the zomes it fronts: `__doorway_cache_rules`, `__doorway_import_config`
+//! and the functions known per role and zome. SDKs and admin UIs read it to
+//! introspect capabilities instead of hardcoding them.
+//!
+//! ## Routes
+//!
+//! - `GET /discovery` - Discovered capabilities, one entry per DNA
+//!
+//! Functions are those the doorway knows about: cached functions, the writes
+//! that invalidate them, and import functions. Functions without a cache rule
+//! or import role may still exist in the zome.
+
+use bytes::Bytes;
+use dashmap::DashMap;
+use http_body_util::Full;
+use hyper::Response;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use super::api::json_response;
+use crate::cache::{CacheRule, CacheRuleStore};
+use crate::server::AppState;
+use crate::services::{ImportConfig, ImportConfigStore};
+use crate::worker::ZomeCallConfig;
+
+/// Body of `GET /discovery`
+#[derive(Debug, Serialize)]
+pub struct DiscoveryDocument {
+    /// Discovered DNAs, by role name then DNA hash
+    pub dnas: Vec<DnaCapabilities>,
+    /// Importable batch types and the DNAs accepting each
+    pub import_batch_types: BTreeMap<String, Vec<String>>,
+}
+
+/// What one DNA declared
+#[derive(Debug, Serialize)]
+pub struct DnaCapabilities {
+    pub dna_hash: String,
+    /// None when the DNA's cell hasn't been seen on the conductor yet
+    pub role_name: Option<String>,
+    pub app_id: Option<String>,
+    pub zomes: Vec<ZomeFunctions>,
+    /// Declared rules; undeclared `get_*`/`list_*` calls use convention defaults
+    pub cache_rules: Vec<CacheRule>,
+    /// Import configuration, when the DNA accepts imports
+    pub import_config: Option<ImportConfig>,
+}
+
+/// Functions known for a zome, sorted
+#[derive(Debug, Serialize)]
+pub struct ZomeFunctions {
+    pub zome_name: String,
+    pub functions: Vec<String>,
+}
+
+/// Merge the discovery stores into one document
+pub fn build_document(
+    zome_configs: &DashMap<String, ZomeCallConfig>,
+    cache_rules: &CacheRuleStore,
+    import_configs: Option<&ImportConfigStore>,
+) -> DiscoveryDocument {
+    let mut rules_by_dna: BTreeMap<String, Vec<CacheRule>> = BTreeMap::new();
+    for (dna_hash, rule) in cache_rules.declared_rules() {
+        rules_by_dna.entry(dna_hash).or_default().push(rule);
+    }
+    let imports_by_dna: BTreeMap<String, ImportConfig> = import_configs
+        .map(|store| {
+            store
+                .all_configs()
+                .into_iter()
+                .filter(|c| c.config.enabled)
+                .map(|c| (c.dna_hash, c.config))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let dna_hashes: BTreeSet<String> = zome_configs
+        .iter()
+        .map(|entry| entry.key().clone())
+        .chain(rules_by_dna.keys().cloned())
+        .chain(imports_by_dna.keys().cloned())
+        .collect();
+
+    let mut dnas: Vec<DnaCapabilities> = dna_hashes
+        .into_iter()
+        .map(|dna_hash| {
+            let config = zome_configs.get(&dna_hash).map(|c| c.clone());
+            let mut rules = rules_by_dna.remove(&dna_hash).unwrap_or_default();
+            rules.sort_by(|a, b| a.fn_name.cmp(&b.fn_name));
+            let import_config = imports_by_dna.get(&dna_hash).cloned();
+
+            let mut functions = BTreeSet::new();
+            for rule in &rules {
+                functions.insert(rule.fn_name.clone());
+                functions.extend(rule.invalidated_by.iter().cloned());
+            }
+            for batch in import_config.iter().flat_map(|c| &c.batch_types) {
+                functions.insert(batch.queue_fn.clone());
+                functions.insert(batch.process_fn.clone());
+                functions.insert(batch.status_fn.clone());
+            }
+            let zome_name = config
+                .as_ref()
+                .map(|c| c.zome_name.clone())
+                .unwrap_or_else(|| ZomeCallConfig::default().zome_name);
+
+            DnaCapabilities {
+                dna_hash,
+                role_name: config.as_ref().map(|c| c.role_name.clone()),
+                app_id: config.map(|c| c.app_id),
+                zomes: vec![ZomeFunctions {
+                    zome_name,
+                    functions: functions.into_iter().collect(),
+                }],
+                cache_rules: rules,
+                import_config,
+            }
+        })
+        .collect();
+    dnas.sort_by(|a, b| (&a.role_name, &a.dna_hash).cmp(&(&b.role_name, &b.dna_hash)));
+
+    let mut import_batch_types: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (dna_hash, config) in &imports_by_dna {
+        for batch in &config.batch_types {
+            import_batch_types
+                .entry(batch.batch_type.clone())
+                .or_default()
+                .push(dna_hash.clone());
+        }
+    }
+
+    DiscoveryDocument {
+        dnas,
+        import_batch_types,
+    }
+}
+
+/// Handle GET /discovery
+pub fn handle_discovery(state: Arc<AppState>) -> Response<Full<Bytes>> {
+    let document = build_document(
+        &state.zome_configs,
+        &state.cache_rules,
+        state.import_config_store.as_deref(),
+    );
+    json_response(serde_json::to_vec(&document).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use doorway_client::{CacheRuleBuilder, ImportBatchType};
+
+    #[test]
+    fn test_build_document_merges_stores() {
+        let zome_configs = DashMap::new();
+        zome_configs.insert(
+            "dna-lamad".to_string(),
+            ZomeCallConfig {
+                dna_hash: "dna-lamad".to_string(),
+                role_name: "lamad".to_string(),
+                ..Default::default()
+            },
+        );
+        let rules = CacheRuleStore::new();
+        rules.set_dna_rules(
+            "dna-lamad",
+            vec![CacheRuleBuilder::new("get_content")
+                .ttl(3600)
+                .invalidated_by(vec!["update_content"])
+                .build()],
+        );
+        let imports = ImportConfigStore::new();
+        imports.set_config(
+            "dna-lamad",
+            ImportConfig {
+                enabled: true,
+                base_route: "/import".to_string(),
+                batch_types: vec![ImportBatchType::new("content")],
+                require_auth: true,
+                allowed_agents: None,
+            },
+        );
+        rules.set_dna_rules(
+            "dna-unseen",
+            vec![CacheRuleBuilder::new("get_path").build()],
+        );
+
+        let document = build_document(&zome_configs, &rules, Some(&imports));
+
+        // Cells not yet seen on the conductor sort first
+        assert_eq!(document.dnas.len(), 2);
+        assert_eq!(document.dnas[0].dna_hash, "dna-unseen");
+        assert_eq!(document.dnas[0].role_name, None);
+
+        let lamad = &document.dnas[1];
+        assert_eq!(lamad.role_name.as_deref(), Some("lamad"));
+        assert_eq!(lamad.zomes[0].zome_name, "content_store");
+        assert_eq!(
+            lamad.zomes[0].functions,
+            vec![
+                "get_content",
+                "get_import_status",
+                "process_import_chunk",
+                "queue_import",
+                "update_content",
+            ]
+        );
+        assert_eq!(lamad.cache_rules[0].ttl_secs, 3600);
+        assert!(lamad.import_config.is_some());
+        assert_eq!(
+            document.import_batch_types.get("content"),
+            Some(&vec!["dna-lamad".to_string()])
+        );
+    }
+}
@@ -0,0 +1,362 @@
+//! Elohim Task Dispatch
+//!
+//! Forwards work to the elohim agent registered for a scope (see
+//! `register_elohim` in the imagodei zome). The doorway picks a live
+//! registration with the needed capability, records the task in the
+//! `elohim_tasks` collection and publishes it on the agent's NATS endpoint.
+//! The agent reports progress back, which the
+//! [task tracker](crate::worker::elohim_tasks) records.
+//!
+//! ## Routes
+//!
+//! - `POST /elohim/tasks` - Dispatch `{scope, kind, payload}`; answers `202` with the task id
+//! - `GET /elohim/tasks/{task_id}` - Task status and, once completed, its result
+//!
+//! `kind` is one of `moderation_review`, `translation` or `tutoring`.
+//! Dispatching needs a user token, or a steward token for moderation
+//! reviews. Tasks can be read by whoever dispatched them and by stewards.
+
+use bson::doc;
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::Incoming;
+use hyper::{Request, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use super::api::error_response;
+use super::auth_helpers::require_user;
+use super::zome_helpers::call_get_elohim_by_scope;
+use crate::auth::{Claims, PermissionLevel};
+use crate::db::schemas::{ElohimTaskDoc, ElohimTaskKind, ElohimTaskStatus, ELOHIM_TASK_COLLECTION};
+use crate::db::MongoCollection;
+use crate::nats::ElohimTaskMessage;
+use crate::server::AppState;
+
+/// Largest task body accepted
+const MAX_BODY_BYTES: usize = 256 * 1024;
+
+/// Body of `POST /elohim/tasks`
+#[derive(Debug, Deserialize)]
+struct DispatchBody {
+    scope: String,
+    kind: String,
+    #[serde(default)]
+    payload: Value,
+}
+
+#[derive(Debug, Serialize)]
+struct TaskView<'a> {
+    task_id: &'a str,
+    kind: ElohimTaskKind,
+    scope: &'a str,
+    elohim_id: &'a str,
+    status: ElohimTaskStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    created_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    completed_at: Option<String>,
+}
+
+impl<'a> From<&'a ElohimTaskDoc> for TaskView<'a> {
+    fn from(doc: &'a ElohimTaskDoc) -> Self {
+        Self {
+            task_id: &doc.task_id,
+            kind: doc.kind,
+            scope: &doc.scope,
+            elohim_id: &doc.elohim_id,
+            status: doc.status,
+            result: doc
+                .result_json
+                .as_deref()
+                .and_then(|r| serde_json::from_str(r).ok()),
+            error: doc.error.as_deref(),
+            created_at: doc
+                .metadata
+                .created_at
+                .and_then(|t| t.try_to_rfc3339_string().ok()),
+            completed_at: doc.completed_at.map(|t| t.to_rfc3339()),
+        }
+    }
+}
+
+/// Extract the task id from `/elohim/tasks/{task_id}`
+pub fn parse_task_path(path: &str) -> Option<&str> {
+    let id = path.strip_prefix("/elohim/tasks/")?;
+    (!id.is_empty() && !id.contains('/')).then_some(id)
+}
+
+fn is_steward(claims: &Claims) -> bool {
+    claims.is_steward || claims.permission_level >= PermissionLevel::Admin
+}
+
+/// JSON response that clients must not cache, since task status changes
+fn task_response(status: StatusCode, body: Vec<u8>) -> Response<Full<Bytes>> {
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .header("Cache-Control", "no-cache")
+        .header("Access-Control-Allow-Origin", "*")
+        .body(Full::new(Bytes::from(body)))
+        .unwrap()
+}
+
+async fn task_collection(
+    state: &AppState,
+) -> Result<MongoCollection<ElohimTaskDoc>, Response<Full<Bytes>>> {
+    let unavailable = || {
+        error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Elohim tasks not available",
+            "DATABASE_UNAVAILABLE",
+        )
+    };
+    let Some(ref mongo) = state.mongo else {
+        return Err(unavailable());
+    };
+    mongo
+        .collection::<ElohimTaskDoc>(ELOHIM_TASK_COLLECTION)
+        .await
+        .map_err(|e| {
+            warn!(error = %e, "Elohim task collection unavailable");
+            unavailable()
+        })
+}
+
+/// Handle POST /elohim/tasks
+pub async fn handle_dispatch_elohim_task(
+    req: Request<Incoming>,
+    state: Arc<AppState>,
+) -> Response<Full<Bytes>> {
+    let auth_header = req
+        .headers()
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string());
+    let claims = match require_user(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let Some(nats) = state.nats.clone() else {
+        return error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Elohim dispatch needs NATS",
+            "NATS_UNAVAILABLE",
+        );
+    };
+    let tasks = match task_collection(&state).await {
+        Ok(tasks) => tasks,
+        Err(response) => return response,
+    };
+
+    let body = match Limited::new(req.into_body(), MAX_BODY_BYTES)
+        .collect()
+        .await
+    {
+        Ok(collected) => collected.to_bytes(),
+        Err(_) => {
+            return error_response(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                &format!("Tasks are limited to {MAX_BODY_BYTES} bytes"),
+                "TOO_LARGE",
+            )
+        }
+    };
+    let dispatch: DispatchBody = match serde_json::from_slice(&body) {
+        Ok(dispatch) => dispatch,
+        Err(e) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                &format!("Invalid task: {e}"),
+                "INVALID_JSON",
+            )
+        }
+    };
+    let Some(kind) = ElohimTaskKind::parse(&dispatch.kind) else {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            &format!(
+                "Invalid kind '{}', expected one of: moderation_review, translation, tutoring",
+                dispatch.kind
+            ),
+            "INVALID_KIND",
+        );
+    };
+    if dispatch.scope.trim().is_empty() {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "scope is required",
+            "INVALID_SCOPE",
+        );
+    }
+    if kind == ElohimTaskKind::ModerationReview && !is_steward(&claims) {
+        return error_response(
+            StatusCode::FORBIDDEN,
+            "Steward permission required for moderation reviews",
+            "FORBIDDEN",
+        );
+    }
+
+    let registrations = match call_get_elohim_by_scope(&state, &dispatch.scope).await {
+        Ok(registrations) => registrations,
+        Err(e) => {
+            warn!(scope = %dispatch.scope, error = %e, "Failed to look up elohim registrations");
+            return error_response(StatusCode::BAD_GATEWAY, "Lookup failed", "ZOME_ERROR");
+        }
+    };
+    let Some(elohim) = registrations.into_iter().find(|r| {
+        r.alive
+            && r.registration
+                .capabilities
+                .iter()
+                .any(|c| c == kind.capability())
+    }) else {
+        return error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            &format!(
+                "No live elohim handles {} for scope '{}'",
+                kind.capability(),
+                dispatch.scope
+            ),
+            "NO_ELOHIM_AVAILABLE",
+        );
+    };
+
+    let message = ElohimTaskMessage::new(kind, dispatch.scope, dispatch.payload);
+    let task = ElohimTaskDoc {
+        task_id: message.task_id.clone(),
+        kind,
+        scope: message.scope.clone(),
+        elohim_id: elohim.registration.agent_id.clone(),
+        endpoint: elohim.registration.endpoint.clone(),
+        payload_json: message.payload.to_string(),
+        requested_by: claims.human_id.clone(),
+        status: ElohimTaskStatus::Dispatched,
+        ..Default::default()
+    };
+    if let Err(e) = tasks.insert_one(task).await {
+        warn!(error = %e, "Failed to record elohim task");
+        return error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to record task",
+            "DATABASE_ERROR",
+        );
+    }
+
+    let published = match serde_json::to_vec(&message) {
+        Ok(bytes) => nats
+            .publish(&elohim.registration.endpoint, Bytes::from(bytes))
+            .await
+            .map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+    if let Err(e) = published {
+        warn!(task_id = %message.task_id, error = %e, "Failed to publish elohim task");
+        let failed = bson::to_bson(&ElohimTaskStatus::Failed).unwrap_or_default();
+        if let Err(e) = tasks
+            .update_one(
+                doc! { "task_id": &message.task_id },
+                doc! { "$set": {
+                    "status": failed,
+                    "error": format!("Dispatch failed: {e}"),
+                    "metadata.updated_at": bson::DateTime::now(),
+                } },
+            )
+            .await
+        {
+            warn!(task_id = %message.task_id, error = %e, "Failed to mark elohim task failed");
+        }
+        return error_response(StatusCode::BAD_GATEWAY, "Dispatch failed", "NATS_ERROR");
+    }
+
+    info!(
+        task_id = %message.task_id,
+        kind = kind.capability(),
+        scope = %message.scope,
+        elohim_id = %elohim.registration.agent_id,
+        "Elohim task dispatched"
+    );
+    task_response(
+        StatusCode::ACCEPTED,
+        serde_json::to_vec(&serde_json::json!({
+            "task_id": message.task_id,
+            "status": ElohimTaskStatus::Dispatched,
+            "elohim_id": elohim.registration.agent_id,
+        }))
+        .unwrap_or_default(),
+    )
+}
+
+/// Handle GET /elohim/tasks/{task_id}
+pub async fn handle_elohim_task_status(
+    state: Arc<AppState>,
+    task_id: &str,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    let claims = match require_user(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+    let tasks = match task_collection(&state).await {
+        Ok(tasks) => tasks,
+        Err(response) => return response,
+    };
+
+    let task = match tasks.find_one(doc! { "task_id": task_id }).await {
+        Ok(Some(task)) => task,
+        Ok(None) => return error_response(StatusCode::NOT_FOUND, "Task not found", "NOT_FOUND"),
+        Err(e) => {
+            warn!(task_id = %task_id, error = %e, "Failed to load elohim task");
+            return error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to load task",
+                "DATABASE_ERROR",
+            );
+        }
+    };
+    // Don't reveal other people's tasks exist
+    if task.requested_by != claims.human_id && !is_steward(&claims) {
+        return error_response(StatusCode::NOT_FOUND, "Task not found", "NOT_FOUND");
+    }
+
+    task_response(
+        StatusCode::OK,
+        serde_json::to_vec(&TaskView::from(&task)).unwrap_or_default(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_task_path() {
+        assert_eq!(parse_task_path("/elohim/tasks/abc-123"), Some("abc-123"));
+        assert_eq!(parse_task_path("/elohim/tasks/"), None);
+        assert_eq!(parse_task_path("/elohim/tasks/abc/result"), None);
+        assert_eq!(parse_task_path("/elohim/tasks"), None);
+    }
+
+    #[test]
+    fn test_task_view_parses_result() {
+        let task = ElohimTaskDoc {
+            task_id: "t-1".to_string(),
+            kind: ElohimTaskKind::Translation,
+            status: ElohimTaskStatus::Completed,
+            result_json: Some(r#"{"title":"Hola"}"#.to_string()),
+            ..Default::default()
+        };
+        let view = serde_json::to_value(TaskView::from(&task)).unwrap();
+        assert_eq!(view["kind"], "translation");
+        assert_eq!(view["status"], "completed");
+        assert_eq!(view["result"]["title"], "Hola");
+        assert!(view.get("error").is_none());
+    }
+}
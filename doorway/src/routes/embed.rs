@@ -0,0 +1,295 @@
+//! Embed Routes
+//!
+//! Iframe player for [embedded learning paths](crate::services::embed).
+//!
+//! ## Routes
+//!
+//! - `GET /embed/{path_id}` - Player page; only partner origins in `EMBED_PARTNERS` may frame it
+//! - `GET /embed/{path_id}/path` - Path and ordered steps for the player; needs the partner's
+//!   token in `X-Embed-Token` and the partner origin in `X-Embed-Origin`
+//!
+//! The player has no access to the learner's doorway session: it shows the
+//! path outline and reads step bodies from `GET /content/{id}/body`, which
+//! only serves commons and public content to anonymous callers. Steps it
+//! can't show link to the app in a new tab.
+
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::{Response, StatusCode};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+use super::api::{error_response, json_response, not_enabled_response, overloaded_response};
+use super::zome_helpers::call_content_store;
+use crate::server::AppState;
+use crate::services::embed::{EmbedError, EmbedPolicy};
+use crate::services::site_export::{is_public_path, path_manifest};
+use crate::types::DoorwayError;
+
+/// Header carrying the partner's token
+pub const EMBED_TOKEN_HEADER: &str = "x-embed-token";
+
+/// Message when EMBED_PARTNERS is unset
+const NOT_ENABLED: &str = "Embedding is not enabled on this doorway";
+
+/// Header carrying the origin the token was posted from
+pub const EMBED_ORIGIN_HEADER: &str = "x-embed-origin";
+
+/// Player logic; reads its config from the `embed-config` element
+const PLAYER_SCRIPT: &str = r#"(function () {
+  "use strict";
+  var config = JSON.parse(document.getElementById("embed-config").textContent);
+  var player = document.getElementById("player");
+  var parentOrigin = null;
+
+  function post(message) {
+    if (parentOrigin) window.parent.postMessage(message, parentOrigin);
+  }
+
+  function el(tag, text) {
+    var node = document.createElement(tag);
+    if (text) node.textContent = text;
+    return node;
+  }
+
+  function appLink(id) {
+    var link = el("a", "Open in Elohim");
+    link.href = "/lamad/resource/" + encodeURIComponent(id);
+    link.target = "_blank";
+    link.rel = "noopener";
+    return link;
+  }
+
+  function openStep(step, item) {
+    if (item.querySelector(".body")) return;
+    var body = el("div");
+    body.className = "body";
+    item.appendChild(body);
+    fetch("/content/" + encodeURIComponent(step.resource_id) + "/body", { credentials: "omit" })
+      .then(function (r) { if (!r.ok) throw r.status; return r.json(); })
+      .then(function (chunk) {
+        body.appendChild(el("pre", chunk.body));
+        if (chunk.next_offset) body.appendChild(appLink(step.resource_id));
+      }, function () { body.appendChild(appLink(step.resource_id)); });
+  }
+
+  function render(path) {
+    player.textContent = "";
+    player.appendChild(el("h1", path.title || path.id));
+    if (path.description) player.appendChild(el("p", path.description));
+    var list = el("ol");
+    path.steps.forEach(function (step) {
+      var item = el("li");
+      var button = el("button", step.title || step.resource_id);
+      button.type = "button";
+      button.addEventListener("click", function () { openStep(step, item); });
+      item.appendChild(button);
+      if (step.estimated_minutes) item.appendChild(el("small", " " + step.estimated_minutes + " min"));
+      list.appendChild(item);
+    });
+    player.appendChild(list);
+    post({ type: "elohim-embed:loaded", path_id: path.id, step_count: path.steps.length });
+  }
+
+  function load(token) {
+    fetch(config.data_url, {
+      credentials: "omit",
+      headers: { "X-Embed-Token": token, "X-Embed-Origin": parentOrigin }
+    })
+      .then(function (r) { if (!r.ok) throw r.status; return r.json(); })
+      .then(render, function (status) {
+        player.textContent = "This learning path can't be shown here.";
+        post({ type: "elohim-embed:error", path_id: config.path_id, status: status });
+      });
+  }
+
+  window.addEventListener("message", function (event) {
+    if (event.source !== window.parent || config.origins.indexOf(event.origin) < 0) return;
+    var data = event.data || {};
+    if (data.type !== "elohim-embed:auth" || typeof data.token !== "string") return;
+    parentOrigin = event.origin;
+    load(data.token);
+  });
+
+  // Messages to an origin other than the parent's are dropped by the browser
+  config.origins.forEach(function (origin) {
+    window.parent.postMessage({ type: "elohim-embed:ready", path_id: config.path_id }, origin);
+  });
+})();
+"#;
+
+const PLAYER_STYLE: &str = "body{font:16px/1.5 system-ui,sans-serif;margin:0;padding:1rem;color:#222}\
+h1{font-size:1.25rem;margin:0 0 .5rem}ol{padding-left:1.25rem}\
+button{font:inherit;background:none;border:0;padding:0;color:#0b5cad;cursor:pointer;text-align:left}\
+pre{white-space:pre-wrap;font:inherit;background:#f6f6f6;padding:.75rem;border-radius:4px}";
+
+/// Extract the path id from `/embed/{path_id}`
+pub fn parse_embed_path(path: &str) -> Option<&str> {
+    path.strip_prefix("/embed/")
+        .filter(|id| !id.is_empty() && !id.contains('/'))
+}
+
+/// Extract the path id from `/embed/{path_id}/path`
+pub fn parse_embed_data_path(path: &str) -> Option<&str> {
+    path.strip_prefix("/embed/")?
+        .strip_suffix("/path")
+        .filter(|id| !id.is_empty() && !id.contains('/'))
+}
+
+fn embed_policy(state: &AppState) -> Option<&EmbedPolicy> {
+    state.embed.as_deref()
+}
+
+/// CSP of the player page
+fn content_security_policy(policy: &EmbedPolicy, nonce: &str) -> String {
+    let ancestors: Vec<&str> = policy.origins().collect();
+    format!(
+        "default-src 'none'; script-src 'nonce-{nonce}'; style-src 'nonce-{nonce}'; \
+         connect-src 'self'; img-src 'self' data: https:; base-uri 'none'; \
+         form-action 'none'; frame-ancestors {}",
+        ancestors.join(" ")
+    )
+}
+
+/// Render the player page
+fn render_player(policy: &EmbedPolicy, path_id: &str, nonce: &str) -> String {
+    let config = serde_json::json!({
+        "path_id": path_id,
+        "data_url": format!("/embed/{}/path", urlencoding::encode(path_id)),
+        "origins": policy.origins().collect::<Vec<_>>(),
+    });
+    // Nothing in the JSON may close the script element
+    let config = config.to_string().replace('<', "\\u003c");
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<meta name="referrer" content="no-referrer">
+<title>Learning path</title>
+<style nonce="{nonce}">{PLAYER_STYLE}</style>
+</head>
+<body>
+<main id="player">Loading…</main>
+<script type="application/json" id="embed-config">{config}</script>
+<script nonce="{nonce}">{PLAYER_SCRIPT}</script>
+</body>
+</html>
+"#
+    )
+}
+
+/// Handle GET /embed/{path_id}
+pub fn handle_embed_player(state: Arc<AppState>, path_id: &str) -> Response<Full<Bytes>> {
+    let Some(policy) = embed_policy(&state) else {
+        return not_enabled_response(StatusCode::NOT_FOUND, NOT_ENABLED);
+    };
+    let nonce = uuid::Uuid::new_v4().simple().to_string();
+    let html = render_player(policy, path_id, &nonce);
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", "text/html; charset=utf-8")
+        .header(
+            "Content-Security-Policy",
+            content_security_policy(policy, &nonce),
+        )
+        .header("Cache-Control", "no-store")
+        .header("X-Content-Type-Options", "nosniff")
+        .body(Full::new(Bytes::from(html)))
+        .unwrap()
+}
+
+/// Handle GET /embed/{path_id}/path
+pub async fn handle_embed_data(
+    state: Arc<AppState>,
+    path_id: &str,
+    token: Option<String>,
+    origin: Option<String>,
+) -> Response<Full<Bytes>> {
+    let Some(policy) = embed_policy(&state) else {
+        return not_enabled_response(StatusCode::NOT_FOUND, NOT_ENABLED);
+    };
+    let now = chrono::Utc::now().timestamp();
+    if let Err(e) = policy.verify(token.as_deref(), origin.as_deref(), path_id, now) {
+        debug!(path_id = %path_id, reason = %e, "Embed token refused");
+        let status = match e {
+            EmbedError::OriginNotAllowed(_) => StatusCode::FORBIDDEN,
+            _ => StatusCode::UNAUTHORIZED,
+        };
+        return error_response(status, &e.to_string(), "EMBED_REFUSED");
+    }
+
+    let not_found = || {
+        error_response(
+            StatusCode::NOT_FOUND,
+            &format!("Not found: LearningPath/{path_id}"),
+            "NOT_FOUND",
+        )
+    };
+    let output = match call_content_store(&state, "get_path_with_steps", &path_id).await {
+        Ok(Some(output)) => output,
+        Ok(None) => return not_found(),
+        Err(DoorwayError::Overloaded(msg)) => return overloaded_response(&msg),
+        Err(e) => {
+            warn!(path_id = %path_id, error = ?e, "Embed path lookup failed");
+            return error_response(StatusCode::BAD_GATEWAY, "Lookup failed", "ZOME_ERROR");
+        }
+    };
+    let Some(path) = output.get("path").filter(|path| is_public_path(path)) else {
+        return not_found();
+    };
+    let steps: Vec<serde_json::Value> = output
+        .get("steps")
+        .and_then(|steps| steps.as_array())
+        .map(|steps| {
+            steps
+                .iter()
+                .filter_map(|s| s.get("step").cloned())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let manifest = path_manifest(path, &steps, &BTreeSet::new());
+    let mut response = json_response(serde_json::to_vec(&manifest).unwrap_or_default());
+    response
+        .headers_mut()
+        .insert("Cache-Control", "private, no-store".parse().unwrap());
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_parse_embed_paths() {
+        assert_eq!(
+            parse_embed_path("/embed/governance-intro"),
+            Some("governance-intro")
+        );
+        assert_eq!(parse_embed_path("/embed/"), None);
+        assert_eq!(parse_embed_path("/embed/a/path"), None);
+        assert_eq!(parse_embed_data_path("/embed/a/path"), Some("a"));
+        assert_eq!(parse_embed_data_path("/embed//path"), None);
+    }
+
+    #[test]
+    fn test_player_page_is_locked_to_partners() {
+        let policy = EmbedPolicy::new(
+            &[format!("https://school.example={}", "s".repeat(32))],
+            Duration::from_secs(600),
+        )
+        .unwrap();
+        let csp = content_security_policy(&policy, "n1");
+        assert!(csp.contains("frame-ancestors https://school.example"));
+        assert!(csp.contains("script-src 'nonce-n1'"));
+
+        let html = render_player(&policy, "</script><script>alert(1)", "n1");
+        assert!(!html.contains("</script><script>alert(1)"));
+        assert!(html.contains(r#""origins":["https://school.example"]"#));
+        assert!(html.contains(r#"<script nonce="n1">"#));
+    }
+}
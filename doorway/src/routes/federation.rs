@@ -161,6 +161,17 @@ pub fn handle_doorway_keys(state: Arc<AppState>) -> Response<Full<Bytes>> {
         });
     }
 
+    // Key reciprocal peers pin to verify our calls
+    if let Some(ref reciprocal) = state.reciprocal {
+        keys.push(JwkKey {
+            kty: "OKP".to_string(),
+            crv: "Ed25519".to_string(),
+            key_use: "sig".to_string(),
+            kid: "reciprocal-1".to_string(),
+            x: base64_url_encode(&reciprocal.verifying_key().to_bytes()),
+        });
+    }
+
     let response = JwksResponse { keys };
 
     match serde_json::to_string_pretty(&response) {
@@ -0,0 +1,522 @@
+//! Atom Feeds
+//!
+//! Lets feed readers and podcast apps follow new commons content. Feeds are
+//! generated from the projection store and only ever include content with
+//! `reach == "commons"`.
+//!
+//! ## Routes
+//!
+//! | Route | Feed |
+//! |-------|------|
+//! | `GET /feeds/content.atom` | All commons content |
+//! | `GET /feeds/tags/{tag}.atom` | Commons content with a tag |
+//! | `GET /feeds/paths/{path_id}.atom` | Commons content that is a step of a public path |
+//!
+//! Entries are newest first, [`PAGE_SIZE`] per page. Older pages are reached
+//! with `?page=N` and linked with RFC 5005 `next`/`previous` links. Audio and
+//! video content carries an enclosure pointing at `/store/{blob_hash}`.
+
+use bytes::Bytes;
+use chrono::Utc;
+use http_body_util::Full;
+use hyper::{Response, StatusCode};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tracing::warn;
+
+use super::api::error_response;
+use super::preview::{content_url, escape_html};
+use crate::projection::document::rfc3339;
+use crate::projection::{ProjectedDocument, ProjectionQuery};
+use crate::server::AppState;
+
+/// Entries per feed page
+pub const PAGE_SIZE: usize = 50;
+
+/// Highest page served (deep history is for the API, not feed readers)
+const MAX_PAGE: usize = 100;
+
+/// Visibility of content included in feeds
+const FEED_REACH: &str = "commons";
+
+/// Which feed was requested
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FeedKind {
+    All,
+    Tag(String),
+    Path(String),
+}
+
+/// Parse `/feeds/...` into a feed kind
+pub fn parse_feed_path(path: &str) -> Option<FeedKind> {
+    let rest = path.strip_prefix("/feeds/")?;
+    if rest == "content.atom" {
+        return Some(FeedKind::All);
+    }
+    let decode = |s: &str| {
+        urlencoding::decode(s)
+            .ok()
+            .map(|s| s.into_owned())
+            .filter(|s| !s.is_empty() && !s.contains('/'))
+    };
+    if let Some(tag) = rest
+        .strip_prefix("tags/")
+        .and_then(|r| r.strip_suffix(".atom"))
+    {
+        return decode(tag).map(FeedKind::Tag);
+    }
+    if let Some(path_id) = rest
+        .strip_prefix("paths/")
+        .and_then(|r| r.strip_suffix(".atom"))
+    {
+        return decode(path_id).map(FeedKind::Path);
+    }
+    None
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct FeedParams {
+    page: Option<usize>,
+}
+
+/// 1-based page from the query string
+fn parse_page(query: Option<&str>) -> usize {
+    serde_urlencoded::from_str::<FeedParams>(query.unwrap_or(""))
+        .ok()
+        .and_then(|p| p.page)
+        .unwrap_or(1)
+        .clamp(1, MAX_PAGE)
+}
+
+/// Effective reach of a projected document
+fn document_reach(doc: &ProjectedDocument) -> &str {
+    doc.reach
+        .as_deref()
+        .or_else(|| doc.data.get("reach").and_then(|r| r.as_str()))
+        .unwrap_or("private")
+}
+
+/// Whether a Content document belongs in a feed
+fn is_feed_item(doc: &ProjectedDocument, tag: Option<&str>) -> bool {
+    if doc.doc_type != "Content" || doc.metadata.is_deleted || document_reach(doc) != FEED_REACH {
+        return false;
+    }
+    match tag {
+        Some(tag) => doc
+            .data
+            .get("tags")
+            .and_then(|t| t.as_array())
+            .is_some_and(|tags| tags.iter().any(|t| t.as_str() == Some(tag))),
+        None => true,
+    }
+}
+
+/// Content ids of a path's steps, in path order.
+///
+/// Accepts steps at the top level or grouped in chapters, each either a bare
+/// step or wrapped as `{"step": {..}}` (PathStepOutput).
+pub fn path_resource_ids(data: &serde_json::Value) -> Vec<String> {
+    let mut steps: Vec<&serde_json::Value> = Vec::new();
+    let mut collect = |list: Option<&serde_json::Value>| {
+        if let Some(list) = list.and_then(|l| l.as_array()) {
+            steps.extend(list.iter().map(|s| s.get("step").unwrap_or(s)));
+        }
+    };
+    collect(data.get("steps"));
+    if let Some(chapters) = data.get("chapters").and_then(|c| c.as_array()) {
+        for chapter in chapters {
+            collect(chapter.get("steps"));
+        }
+    }
+    collect(data.get("ungrouped_steps"));
+
+    let mut seen = HashSet::new();
+    steps
+        .into_iter()
+        .filter(|s| {
+            s.get("step_type")
+                .and_then(|t| t.as_str())
+                .unwrap_or("content")
+                == "content"
+        })
+        .filter_map(|s| s.get("resource_id").and_then(|r| r.as_str()))
+        .filter(|id| seen.insert(*id))
+        .map(String::from)
+        .collect()
+}
+
+/// Feed metadata
+#[derive(Debug, Clone)]
+pub struct FeedInfo {
+    pub title: String,
+    /// URL of this feed without the page parameter
+    pub self_url: String,
+    pub page: usize,
+    pub has_more: bool,
+}
+
+/// Render one `<entry>`
+fn render_entry(doc: &ProjectedDocument, base_url: &str) -> String {
+    let text = |key: &str| {
+        doc.data
+            .get(key)
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+    };
+    let url = escape_html(&content_url(base_url, &doc.doc_id));
+    let title = escape_html(text("title").unwrap_or(&doc.doc_id));
+    let (published, updated) = doc.entry_times();
+
+    let mut entry = String::from("  <entry>\n");
+    entry.push_str(&format!("    <id>{url}</id>\n"));
+    entry.push_str(&format!("    <title>{title}</title>\n"));
+    entry.push_str(&format!(
+        "    <link rel=\"alternate\" type=\"text/html\" href=\"{url}\"/>\n"
+    ));
+    entry.push_str(&format!(
+        "    <published>{}</published>\n",
+        rfc3339(published)
+    ));
+    entry.push_str(&format!("    <updated>{}</updated>\n", rfc3339(updated)));
+    if let Some(summary) = text("summary").or_else(|| text("description")) {
+        entry.push_str(&format!(
+            "    <summary>{}</summary>\n",
+            escape_html(summary)
+        ));
+    }
+    if let Some(author) = text("author_id") {
+        entry.push_str(&format!(
+            "    <author><name>{}</name></author>\n",
+            escape_html(author)
+        ));
+    }
+    if let Some(tags) = doc.data.get("tags").and_then(|t| t.as_array()) {
+        for tag in tags.iter().filter_map(|t| t.as_str()) {
+            entry.push_str(&format!("    <category term=\"{}\"/>\n", escape_html(tag)));
+        }
+    }
+
+    // Media enclosure for podcast apps
+    let format = text("content_format").unwrap_or("");
+    if let (Some(hash), true) = (&doc.blob_hash, matches!(format, "audio" | "video")) {
+        let mime = text("mime_type").unwrap_or(if format == "audio" {
+            "audio/mpeg"
+        } else {
+            "video/mp4"
+        });
+        let length = doc
+            .data
+            .get("content_size_bytes")
+            .and_then(|s| s.as_u64())
+            .map(|s| format!(" length=\"{s}\""))
+            .unwrap_or_default();
+        entry.push_str(&format!(
+            "    <link rel=\"enclosure\" type=\"{}\" href=\"{}\"{length}/>\n",
+            escape_html(mime),
+            escape_html(&format!("{}/store/{hash}", base_url.trim_end_matches('/')))
+        ));
+    }
+
+    entry.push_str("  </entry>\n");
+    entry
+}
+
+/// Render an Atom feed page
+pub fn render_atom(info: &FeedInfo, docs: &[ProjectedDocument], base_url: &str) -> String {
+    let page_url = |page: usize| {
+        if page == 1 {
+            info.self_url.clone()
+        } else {
+            format!("{}?page={page}", info.self_url)
+        }
+    };
+    let updated = docs
+        .iter()
+        .map(|d| d.entry_times().1)
+        .max()
+        .unwrap_or_else(Utc::now);
+
+    let mut feed = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    feed.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    feed.push_str(&format!("  <id>{}</id>\n", escape_html(&info.self_url)));
+    feed.push_str(&format!("  <title>{}</title>\n", escape_html(&info.title)));
+    feed.push_str(&format!("  <updated>{}</updated>\n", rfc3339(updated)));
+    feed.push_str("  <generator>elohim-doorway</generator>\n");
+    feed.push_str(&format!(
+        "  <link rel=\"self\" type=\"application/atom+xml\" href=\"{}\"/>\n",
+        escape_html(&page_url(info.page))
+    ));
+    feed.push_str(&format!(
+        "  <link rel=\"first\" href=\"{}\"/>\n",
+        escape_html(&page_url(1))
+    ));
+    if info.page > 1 {
+        feed.push_str(&format!(
+            "  <link rel=\"previous\" href=\"{}\"/>\n",
+            escape_html(&page_url(info.page - 1))
+        ));
+    }
+    if info.has_more && info.page < MAX_PAGE {
+        feed.push_str(&format!(
+            "  <link rel=\"next\" href=\"{}\"/>\n",
+            escape_html(&page_url(info.page + 1))
+        ));
+    }
+    feed.push_str(&format!(
+        "  <link rel=\"alternate\" type=\"text/html\" href=\"{}/lamad\"/>\n",
+        escape_html(base_url.trim_end_matches('/'))
+    ));
+    for doc in docs {
+        feed.push_str(&render_entry(doc, base_url));
+    }
+    feed.push_str("</feed>\n");
+    feed
+}
+
+/// Load one page of feed items, newest first.
+/// Returns the page and whether older items exist.
+#[allow(clippy::result_large_err)]
+async fn load_page(
+    state: &AppState,
+    kind: &FeedKind,
+    page: usize,
+) -> Result<(Vec<ProjectedDocument>, bool), Response<Full<Bytes>>> {
+    let Some(ref projection) = state.projection else {
+        return Err(error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Projection store not available",
+            "PROJECTION_UNAVAILABLE",
+        ));
+    };
+
+    let mut query = ProjectionQuery::by_type("Content");
+    let mut filter = bson::doc! {
+        "metadata.is_deleted": { "$ne": true },
+        "$or": [
+            { "reach": FEED_REACH },
+            { "reach": null, "data.reach": FEED_REACH },
+        ],
+    };
+    let tag = match kind {
+        FeedKind::All => None,
+        FeedKind::Tag(tag) => {
+            filter.insert("data.tags", tag.as_str());
+            Some(tag.as_str())
+        }
+        FeedKind::Path(path_id) => {
+            let path = projection.get("LearningPath", path_id).await;
+            let public = path.as_ref().is_some_and(|p| {
+                let visibility = p.data.get("visibility").and_then(|v| v.as_str());
+                matches!(visibility, Some("public" | "published"))
+                    || document_reach(p) == FEED_REACH
+            });
+            let Some(path) = path.filter(|_| public) else {
+                return Err(error_response(
+                    StatusCode::NOT_FOUND,
+                    "Path not found",
+                    "NOT_FOUND",
+                ));
+            };
+            query.doc_ids = Some(path_resource_ids(&path.data));
+            None
+        }
+    };
+    query.filter = Some(filter);
+    query.sort = Some(("created_at".to_string(), -1));
+
+    let offset = (page - 1) * PAGE_SIZE;
+    let paged_in_db = projection.has_mongodb();
+    if paged_in_db {
+        // One extra to learn whether there is a next page
+        query = query
+            .with_skip(offset as u64)
+            .with_limit(PAGE_SIZE as i64 + 1);
+    }
+
+    let mut docs = projection.query(query).await.map_err(|e| {
+        warn!(error = %e, "Feed query failed");
+        error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to load feed",
+            "QUERY_FAILED",
+        )
+    })?;
+
+    // The in-memory fallback ignores custom filters and paging
+    docs.retain(|d| is_feed_item(d, tag));
+    if !paged_in_db {
+        docs.sort_by_key(|d| std::cmp::Reverse(d.entry_times().0));
+        docs = docs.into_iter().skip(offset).collect();
+    }
+
+    let has_more = docs.len() > PAGE_SIZE;
+    docs.truncate(PAGE_SIZE);
+    Ok((docs, has_more))
+}
+
+/// Handle GET /feeds/...
+pub async fn handle_feed_request(
+    state: Arc<AppState>,
+    path: &str,
+    query: Option<&str>,
+    base_url: &str,
+) -> Response<Full<Bytes>> {
+    let Some(kind) = parse_feed_path(path) else {
+        return error_response(StatusCode::NOT_FOUND, "Unknown feed", "NOT_FOUND");
+    };
+    let page = parse_page(query);
+
+    let (docs, has_more) = match load_page(&state, &kind, page).await {
+        Ok(result) => result,
+        Err(response) => return response,
+    };
+
+    let base_url = state
+        .args
+        .doorway_url
+        .as_deref()
+        .unwrap_or(base_url)
+        .trim_end_matches('/');
+    let title = match kind {
+        FeedKind::All => "Elohim: new content".to_string(),
+        FeedKind::Tag(ref tag) => format!("Elohim: {tag}"),
+        FeedKind::Path(ref path_id) => {
+            let path_title = match state.projection {
+                Some(ref projection) => projection
+                    .get("LearningPath", path_id)
+                    .await
+                    .and_then(|p| p.data.get("title")?.as_str().map(String::from)),
+                None => None,
+            };
+            format!("Elohim: {}", path_title.as_deref().unwrap_or(path_id))
+        }
+    };
+    let info = FeedInfo {
+        title,
+        self_url: format!("{base_url}{path}"),
+        page,
+        has_more,
+    };
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", "application/atom+xml; charset=utf-8")
+        .header("Cache-Control", "public, max-age=300")
+        .body(Full::new(Bytes::from(render_atom(&info, &docs, base_url))))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn content(id: &str, data: serde_json::Value, reach: Option<&str>) -> ProjectedDocument {
+        ProjectedDocument {
+            doc_type: "Content".to_string(),
+            doc_id: id.to_string(),
+            reach: reach.map(String::from),
+            data,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_feed_path() {
+        assert_eq!(parse_feed_path("/feeds/content.atom"), Some(FeedKind::All));
+        assert_eq!(
+            parse_feed_path("/feeds/tags/civic%20life.atom"),
+            Some(FeedKind::Tag("civic life".to_string()))
+        );
+        assert_eq!(
+            parse_feed_path("/feeds/paths/governance-intro.atom"),
+            Some(FeedKind::Path("governance-intro".to_string()))
+        );
+        assert_eq!(parse_feed_path("/feeds/tags/.atom"), None);
+        assert_eq!(parse_feed_path("/feeds/content.rss"), None);
+    }
+
+    #[test]
+    fn test_parse_page() {
+        assert_eq!(parse_page(None), 1);
+        assert_eq!(parse_page(Some("page=3")), 3);
+        assert_eq!(parse_page(Some("page=0")), 1);
+        assert_eq!(parse_page(Some("page=abc")), 1);
+    }
+
+    #[test]
+    fn test_is_feed_item() {
+        let tagged = json!({ "tags": ["governance"] });
+        assert!(is_feed_item(
+            &content("a", tagged.clone(), Some("commons")),
+            None
+        ));
+        assert!(is_feed_item(
+            &content("a", tagged.clone(), Some("commons")),
+            Some("governance")
+        ));
+        assert!(!is_feed_item(
+            &content("a", tagged.clone(), Some("commons")),
+            Some("ecology")
+        ));
+        assert!(!is_feed_item(&content("a", tagged, Some("public")), None));
+        assert!(is_feed_item(
+            &content("a", json!({ "reach": "commons" }), None),
+            None
+        ));
+    }
+
+    #[test]
+    fn test_path_resource_ids() {
+        let data = json!({
+            "steps": [
+                { "step": { "resource_id": "c1", "step_type": "content" } },
+                { "resource_id": "https://example.org", "step_type": "external" }
+            ],
+            "chapters": [
+                { "steps": [{ "step": { "resource_id": "c2" } }, { "step": { "resource_id": "c1" } }] }
+            ]
+        });
+        assert_eq!(path_resource_ids(&data), vec!["c1", "c2"]);
+    }
+
+    #[test]
+    fn test_render_atom() {
+        let doc = content(
+            "intro",
+            json!({
+                "title": "Intro <1>",
+                "summary": "Start here",
+                "content_format": "audio",
+                "content_size_bytes": 1234,
+                "tags": ["governance"],
+                "created_at": "2025-03-01T12:00:00Z",
+                "updated_at": "2025-03-02T08:30:00Z"
+            }),
+            Some("commons"),
+        )
+        .with_blob_hash("sha256-abc");
+        let info = FeedInfo {
+            title: "Elohim: new content".to_string(),
+            self_url: "https://d.example/feeds/content.atom".to_string(),
+            page: 2,
+            has_more: true,
+        };
+        let atom = render_atom(&info, &[doc], "https://d.example");
+
+        assert!(atom.contains("<updated>2025-03-02T08:30:00Z</updated>"));
+        assert!(atom.contains("<published>2025-03-01T12:00:00Z</published>"));
+        assert!(atom.contains("<title>Intro &lt;1&gt;</title>"));
+        assert!(atom
+            .contains(r#"<link rel="next" href="https://d.example/feeds/content.atom?page=3"/>"#));
+        assert!(
+            atom.contains(r#"<link rel="previous" href="https://d.example/feeds/content.atom"/>"#)
+        );
+        assert!(atom.contains(
+            r#"<link rel="enclosure" type="audio/mpeg" href="https://d.example/store/sha256-abc" length="1234"/>"#
+        ));
+        assert!(atom.contains(r#"<category term="governance"/>"#));
+    }
+}
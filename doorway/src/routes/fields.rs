@@ -0,0 +1,164 @@
+//! Field selection for content routes
+//!
+//! Content with a long body (embedded base64 images, transcripts) makes a
+//! full record several megabytes. Content routes read `?fields=` to return
+//! only the listed fields:
+//!
+//! ```text
+//! GET /api/v1/cache/Content/{id}?fields=title,summary,estimated_minutes
+//! ```
+//!
+//! Nested fields are named with dots (`metadata.level`). `id` is always
+//! kept so the body can be fetched later from `GET /content/{id}/body`.
+//! Zome outputs wrapping the record (`{action_hash, entry_hash, content}`)
+//! keep their hashes and have the selection applied to `content`.
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+/// Most fields one request may select
+const MAX_FIELDS: usize = 50;
+
+/// Kept whatever the selection
+const ALWAYS_KEPT: &str = "id";
+
+#[derive(Debug, Default, Deserialize)]
+struct FieldParams {
+    fields: Option<String>,
+}
+
+/// Fields a client asked for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldSelection {
+    paths: Vec<Vec<String>>,
+}
+
+impl FieldSelection {
+    /// Read `fields` from a query string; `None` when absent or empty
+    pub fn from_query(query: Option<&str>) -> Result<Option<Self>, String> {
+        let params: FieldParams = serde_urlencoded::from_str(query.unwrap_or(""))
+            .map_err(|e| format!("Invalid query parameters: {e}"))?;
+        let Some(fields) = params.fields.filter(|f| !f.trim().is_empty()) else {
+            return Ok(None);
+        };
+        Self::parse(&fields).map(Some)
+    }
+
+    /// Parse a comma-separated list of (dotted) field names
+    pub fn parse(fields: &str) -> Result<Self, String> {
+        let mut paths: Vec<Vec<String>> = Vec::new();
+        for field in fields.split(',').map(str::trim).filter(|f| !f.is_empty()) {
+            let path: Vec<String> = field.split('.').map(str::to_string).collect();
+            if path.iter().any(|segment| segment.is_empty()) {
+                return Err(format!("Invalid field name: {field}"));
+            }
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+        if paths.len() > MAX_FIELDS {
+            return Err(format!("At most {MAX_FIELDS} fields may be selected"));
+        }
+        let id = vec![ALWAYS_KEPT.to_string()];
+        if !paths.contains(&id) {
+            paths.push(id);
+        }
+        Ok(Self { paths })
+    }
+
+    /// Keep only the selected fields of a record; arrays are selected item
+    /// by item, anything else is returned as is
+    pub fn apply(&self, value: &Value) -> Value {
+        match value {
+            Value::Object(object) => {
+                let mut selected = Map::new();
+                for path in &self.paths {
+                    copy_path(object, &mut selected, path);
+                }
+                Value::Object(selected)
+            }
+            Value::Array(items) => Value::Array(items.iter().map(|v| self.apply(v)).collect()),
+            other => other.clone(),
+        }
+    }
+
+    /// Like [`apply`](Self::apply), but a zome output wrapping the record
+    /// in `content` keeps its hashes and has `content` selected
+    pub fn apply_to_content(&self, value: &Value) -> Value {
+        match value {
+            Value::Object(object)
+                if object.contains_key("action_hash")
+                    && object.get("content").is_some_and(Value::is_object) =>
+            {
+                let mut wrapper = object.clone();
+                wrapper.insert("content".to_string(), self.apply(&object["content"]));
+                Value::Object(wrapper)
+            }
+            Value::Array(items) => {
+                Value::Array(items.iter().map(|v| self.apply_to_content(v)).collect())
+            }
+            other => self.apply(other),
+        }
+    }
+}
+
+/// Copy `path` from `source` into `target`, creating the objects on the way
+fn copy_path(source: &Map<String, Value>, target: &mut Map<String, Value>, path: &[String]) {
+    let Some((first, rest)) = path.split_first() else {
+        return;
+    };
+    let Some(value) = source.get(first) else {
+        return;
+    };
+    if rest.is_empty() {
+        target.insert(first.clone(), value.clone());
+        return;
+    }
+    let Value::Object(nested) = value else {
+        return;
+    };
+    let entry = target
+        .entry(first.clone())
+        .or_insert_with(|| Value::Object(Map::new()));
+    if let Value::Object(entry) = entry {
+        copy_path(nested, entry, rest);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_select_fields() {
+        let selection = FieldSelection::from_query(Some("fields=title,metadata.level&limit=5"))
+            .unwrap()
+            .unwrap();
+        let content = json!({
+            "id": "intro",
+            "title": "Intro",
+            "content": "a very long body",
+            "metadata": { "level": 2, "notes": "x" },
+        });
+        let expected = json!({ "id": "intro", "title": "Intro", "metadata": { "level": 2 } });
+        assert_eq!(selection.apply(&content), expected);
+
+        // Zome outputs keep their hashes
+        let output = json!({ "action_hash": "uhC", "entry_hash": "uhE", "content": content });
+        let selected = selection.apply_to_content(&json!([output]));
+        assert_eq!(selected[0]["action_hash"], "uhC");
+        assert_eq!(selected[0]["content"], expected);
+    }
+
+    #[test]
+    fn test_parse_fields() {
+        assert_eq!(FieldSelection::from_query(Some("fields=")).unwrap(), None);
+        assert_eq!(FieldSelection::from_query(None).unwrap(), None);
+        assert!(FieldSelection::parse("title,.level").is_err());
+        let many = (0..=MAX_FIELDS)
+            .map(|i| format!("f{i}"))
+            .collect::<Vec<_>>();
+        assert!(FieldSelection::parse(&many.join(",")).is_err());
+    }
+}
@@ -0,0 +1,26 @@
+//! Governance Routes
+//!
+//! Shows the runtime settings the [governance executor](crate::worker::governance)
+//! manages, so clients and operators can see which community decisions are
+//! in effect.
+//!
+//! ## Routes
+//!
+//! - `GET /governance/settings` - Every governed setting with its current value
+//!   and whether it comes from governance, doorway config or the default
+
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::Response;
+use std::sync::Arc;
+
+use super::api::json_response;
+use crate::server::AppState;
+
+/// Handle GET /governance/settings
+pub async fn handle_governance_settings(state: Arc<AppState>) -> Response<Full<Bytes>> {
+    let settings = state.governance.snapshot();
+    json_response(
+        serde_json::to_vec(&serde_json::json!({ "settings": settings })).unwrap_or_default(),
+    )
+}
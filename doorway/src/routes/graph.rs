@@ -0,0 +1,140 @@
+//! Content Graph API
+//!
+//! Serves relationship graphs from the projection layer so clients don't
+//! walk `get_related_content` hop by hop through the zome.
+//!
+//! ## Routes
+//!
+//! - `GET /graph/{content_id}` - Breadth-first neighbourhood of a content node
+//!
+//! ## Query Parameters
+//!
+//! | Param | Description |
+//! |-------|-------------|
+//! | `depth` | Hops from the root (default 1, max 4) |
+//! | `types` | Comma-separated relationship types to follow |
+//! | `direction` | `out`, `in` or `both` (default) |
+//!
+//! The response is `{ root, nodes: [{id, depth, title?, content_type?}],
+//! edges: [{id, source, target, relationship_type, confidence}], truncated }`,
+//! which D3 force layouts consume directly.
+
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::{Response, StatusCode};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tracing::warn;
+
+use super::api::{error_response, json_response};
+use crate::projection::graph::{GraphDirection, GraphOptions, MAX_GRAPH_DEPTH};
+use crate::server::AppState;
+
+/// Raw query string parameters
+#[derive(Debug, Default, Deserialize)]
+struct GraphParams {
+    depth: Option<u32>,
+    types: Option<String>,
+    direction: Option<String>,
+}
+
+/// Parse query string into traversal options
+fn parse_graph_query(query: Option<&str>) -> Result<GraphOptions, String> {
+    let params: GraphParams = serde_urlencoded::from_str(query.unwrap_or(""))
+        .map_err(|e| format!("Invalid query parameters: {e}"))?;
+
+    let direction = match params.direction.as_deref() {
+        None | Some("") => GraphDirection::default(),
+        Some(value) => GraphDirection::parse(value)
+            .ok_or_else(|| format!("Invalid direction '{value}', expected out, in or both"))?,
+    };
+
+    let types: HashSet<String> = params
+        .types
+        .as_deref()
+        .unwrap_or("")
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    Ok(GraphOptions {
+        depth: params.depth.unwrap_or(1).min(MAX_GRAPH_DEPTH),
+        types: (!types.is_empty()).then_some(types),
+        direction,
+    })
+}
+
+/// Handle GET /graph/{content_id}
+pub async fn handle_graph_request(
+    state: Arc<AppState>,
+    path: &str,
+    query: Option<&str>,
+) -> Response<Full<Bytes>> {
+    let content_id = path.trim_start_matches("/graph/").trim_end_matches('/');
+    if content_id.is_empty() || content_id.contains('/') {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "Expected /graph/{content_id}",
+            "INVALID_PATH",
+        );
+    }
+
+    let options = match parse_graph_query(query) {
+        Ok(options) => options,
+        Err(msg) => return error_response(StatusCode::BAD_REQUEST, &msg, "INVALID_QUERY"),
+    };
+
+    let Some(ref projection) = state.projection else {
+        return error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Projection store not available",
+            "PROJECTION_UNAVAILABLE",
+        );
+    };
+
+    match projection.content_graph(content_id, &options).await {
+        Ok(traversal) => json_response(serde_json::to_vec(&traversal).unwrap_or_default()),
+        Err(e) => {
+            warn!(content_id, error = %e, "Content graph traversal failed");
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Graph traversal failed",
+                "GRAPH_FAILED",
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_graph_query_defaults() {
+        let options = parse_graph_query(None).unwrap();
+        assert_eq!(options.depth, 1);
+        assert!(options.types.is_none());
+        assert_eq!(options.direction, GraphDirection::Both);
+    }
+
+    #[test]
+    fn test_parse_graph_query_full() {
+        let options =
+            parse_graph_query(Some("depth=9&types=DEPENDS_ON,%20RELATES_TO&direction=out"))
+                .unwrap();
+        assert_eq!(options.depth, MAX_GRAPH_DEPTH);
+        let types = options.types.unwrap();
+        assert!(types.contains("DEPENDS_ON"));
+        assert!(types.contains("RELATES_TO"));
+        assert_eq!(options.direction, GraphDirection::Outgoing);
+    }
+
+    #[test]
+    fn test_parse_graph_query_invalid() {
+        assert!(parse_graph_query(Some("direction=sideways")).is_err());
+        assert!(parse_graph_query(Some("depth=deep")).is_err());
+    }
+}
@@ -0,0 +1,100 @@
+//! Guest Session Routes
+//!
+//! Anonymous read-only sessions for landing pages (see
+//! [`guest_sessions`](crate::services::guest_sessions)).
+//!
+//! ## Routes
+//!
+//! - `POST /guest/session` - Start a guest session: `{token, token_type, expires_at, expires_in}`
+//!
+//! The token goes in `Authorization: Guest {token}` on `GET /api/v1/cache/...`
+//! and `GET /content/{id}/body`. Those routes call [`guest_read`] to tell
+//! guests apart and keep them to their rate limit.
+
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::header::{HeaderValue, RETRY_AFTER};
+use hyper::{Response, StatusCode};
+use serde::Serialize;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+use super::api::{error_response, json_response};
+use crate::server::AppState;
+use crate::services::guest_sessions::guest_token;
+
+/// Response of `POST /guest/session`
+#[derive(Debug, Serialize)]
+struct GuestSessionResponse {
+    token: String,
+    token_type: &'static str,
+    expires_at: i64,
+    expires_in: u64,
+}
+
+fn rate_limited(message: &str, retry_after: Duration) -> Response<Full<Bytes>> {
+    let mut response = error_response(StatusCode::TOO_MANY_REQUESTS, message, "RATE_LIMITED");
+    let seconds = retry_after.as_secs().max(1).to_string();
+    if let Ok(value) = HeaderValue::from_str(&seconds) {
+        response.headers_mut().insert(RETRY_AFTER, value);
+    }
+    response
+}
+
+/// Handle POST /guest/session
+pub fn handle_guest_session(state: Arc<AppState>, remote_ip: IpAddr) -> Response<Full<Bytes>> {
+    let Some(ref guests) = state.guest_sessions else {
+        return error_response(
+            StatusCode::NOT_FOUND,
+            "Guest sessions are not enabled on this doorway",
+            "GUEST_DISABLED",
+        );
+    };
+    if let Err(retry_after) = guests.allow_session(&remote_ip.to_string(), Instant::now()) {
+        return rate_limited("Too many guest sessions from this address", retry_after);
+    }
+
+    let issued = guests.issue(chrono::Utc::now().timestamp());
+    debug!(session = %issued.session_id, "Guest session started");
+    let body = GuestSessionResponse {
+        token: issued.token,
+        token_type: "Guest",
+        expires_at: issued.expires_at,
+        expires_in: guests.ttl().as_secs(),
+    };
+    let mut response = json_response(serde_json::to_vec(&body).unwrap_or_default());
+    response
+        .headers_mut()
+        .insert("Cache-Control", HeaderValue::from_static("no-store"));
+    response
+}
+
+/// Whether a read comes from a guest session
+///
+/// `Ok(false)` when the request carries no guest token. A guest read over
+/// its rate limit, or with a bad token, gets the error response instead.
+#[allow(clippy::result_large_err)]
+pub(crate) fn guest_read(
+    state: &AppState,
+    auth_header: Option<&str>,
+) -> Result<bool, Response<Full<Bytes>>> {
+    let Some(token) = guest_token(auth_header) else {
+        return Ok(false);
+    };
+    let Some(ref guests) = state.guest_sessions else {
+        return Err(error_response(
+            StatusCode::UNAUTHORIZED,
+            "Guest sessions are not enabled on this doorway",
+            "GUEST_DISABLED",
+        ));
+    };
+    let session_id = guests
+        .verify(token, chrono::Utc::now().timestamp())
+        .map_err(|e| error_response(StatusCode::UNAUTHORIZED, &e.to_string(), "GUEST_REFUSED"))?;
+    guests
+        .allow_read(&session_id, Instant::now())
+        .map_err(|retry_after| rate_limited("Guest read limit reached", retry_after))?;
+    Ok(true)
+}
@@ -88,7 +88,7 @@ pub struct Service {
 }
 
 /// Derive the doorway's DID from its configuration
-fn derive_doorway_did(state: &AppState) -> String {
+pub fn derive_doorway_did(state: &AppState) -> String {
     // If doorway_id is set, use it to construct did:web
     if let Some(ref doorway_id) = state.args.doorway_id {
         // doorway_id is like "alpha-elohim-host" or "doorway-a.elohim.host"
@@ -131,6 +131,13 @@ fn extract_domain(url: &str) -> Option<String> {
     }
 }
 
+/// Multibase z-prefix (base58btc) with Ed25519 multicodec prefix 0xed01
+fn ed25519_multibase(key: &ed25519_dalek::VerifyingKey) -> String {
+    let mut prefixed = vec![0xed, 0x01];
+    prefixed.extend_from_slice(&key.to_bytes());
+    format!("z{}", bs58::encode(&prefixed).into_string())
+}
+
 /// Build the DID Document for this doorway
 fn build_did_document(state: &AppState) -> DIDDocument {
     let did = derive_doorway_did(state);
@@ -191,6 +198,26 @@ fn build_did_document(state: &AppState) -> DIDDocument {
         capabilities.push("projection".to_string());
     }
 
+    let mut verification_method = vec![VerificationMethod {
+        id: format!("{did}#node-key"),
+        method_type: "Ed25519VerificationKey2020".to_string(),
+        controller: did.clone(),
+        public_key_multibase: state.node_verifying_key.as_ref().map(ed25519_multibase),
+    }];
+    let mut assertion_method = vec![format!("{}#node-key", did)];
+
+    // Key learners' Open Badges exports are signed with
+    if let Some(ref exports) = state.badge_exports {
+        let key_id = exports.issuer().key_id();
+        verification_method.push(VerificationMethod {
+            id: key_id.clone(),
+            method_type: "Ed25519VerificationKey2020".to_string(),
+            controller: did.clone(),
+            public_key_multibase: Some(ed25519_multibase(&exports.issuer().verifying_key())),
+        });
+        assertion_method.push(key_id);
+    }
+
     DIDDocument {
         context: vec![
             "https://www.w3.org/ns/did/v1".to_string(),
@@ -198,20 +225,9 @@ fn build_did_document(state: &AppState) -> DIDDocument {
             "https://elohim-protocol.org/ns/v1".to_string(),
         ],
         id: did.clone(),
-        verification_method: vec![VerificationMethod {
-            id: format!("{did}#node-key"),
-            method_type: "Ed25519VerificationKey2020".to_string(),
-            controller: did.clone(),
-            public_key_multibase: state.node_verifying_key.as_ref().map(|key| {
-                // Multibase z-prefix (base58btc) with Ed25519 multicodec prefix 0xed01
-                let pub_bytes = key.to_bytes();
-                let mut prefixed = vec![0xed, 0x01];
-                prefixed.extend_from_slice(&pub_bytes);
-                format!("z{}", bs58::encode(&prefixed).into_string())
-            }),
-        }],
+        verification_method,
         authentication: vec![format!("{}#node-key", did)],
-        assertion_method: vec![format!("{}#node-key", did)],
+        assertion_method,
         service: services,
         elohim_capabilities: capabilities,
         elohim_region: args.region.clone(),
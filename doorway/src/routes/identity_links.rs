@@ -0,0 +1,404 @@
+//! Identity Link Routes
+//!
+//! Lets a signed-in user [link another account](crate::services::identity_links)
+//! into theirs by proving they control it.
+//!
+//! ## Routes
+//!
+//! - `GET /auth/links` - The caller's identity and the accounts linked into it
+//! - `POST /auth/links/password` - Link an account by its password: `{"identifier": .., "password": ..}`
+//! - `POST /auth/links/passkey/options` - Request options for proving a passkey; `{"identifier": ..}` of the other account is optional
+//! - `POST /auth/links/passkey` - Link the account owning the passkey that answered the challenge
+//! - `POST /auth/links/api-key` - Link the account owning an API key: `{"apiKey": "ek_.."}`
+//! - `DELETE /auth/links/{identifier}` - Undo a link
+//!
+//! All routes need the JWT of the account being kept.
+
+use bson::doc;
+use hyper::{Request, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::warn;
+
+use super::auth_routes::{
+    get_auth_header, json_response, parse_json_body, BoxBody, SuccessResponse,
+};
+use super::passkeys::{
+    collection, error, invalid_credentials, mongo, relying_party, request_options, require_claims,
+    verify_passkey_assertion, LoginOptionsRequest, PasskeyLoginRequest,
+};
+use crate::auth::verify_password;
+use crate::db::schemas::{
+    ApiKeyDoc, IdentityLinkDoc, LinkProof, UserDoc, WebAuthnCeremony, API_KEY_COLLECTION,
+    USER_COLLECTION,
+};
+use crate::db::MongoClient;
+use crate::server::AppState;
+use crate::services::identity_links::{link_account, linked_accounts, unlink_account, LinkError};
+use crate::services::operator_onboarding::hash_api_key;
+
+// =============================================================================
+// Request/Response Types
+// =============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct LinkPasswordRequest {
+    pub identifier: String,
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkApiKeyRequest {
+    pub api_key: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkedAccountResponse {
+    pub identifier: String,
+    pub human_id: String,
+    pub agent_pub_key: String,
+    pub proof: LinkProof,
+    pub passkeys: usize,
+    pub api_keys: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub linked_at: Option<String>,
+}
+
+impl From<IdentityLinkDoc> for LinkedAccountResponse {
+    fn from(link: IdentityLinkDoc) -> Self {
+        Self {
+            identifier: link.identifier,
+            human_id: link.human_id,
+            agent_pub_key: link.agent_pub_key,
+            proof: link.proof,
+            passkeys: link.passkey_ids.len(),
+            api_keys: link.api_key_ids.len(),
+            linked_at: link.metadata.created_at.map(|t| t.to_chrono().to_rfc3339()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdentityResponse {
+    pub identifier: String,
+    pub human_id: String,
+    pub agent_pub_key: String,
+    pub linked: Vec<LinkedAccountResponse>,
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Extract the linked identifier from `/auth/links/{identifier}`
+pub fn parse_link_path(path: &str) -> Option<String> {
+    let identifier = path.strip_prefix("/auth/links/")?;
+    if identifier.is_empty() || identifier.contains('/') {
+        return None;
+    }
+    urlencoding::decode(identifier).ok().map(|i| i.into_owned())
+}
+
+fn link_error(e: LinkError) -> Response<BoxBody> {
+    let status = match e {
+        LinkError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        LinkError::NotLinked(_) => StatusCode::NOT_FOUND,
+        LinkError::SameAccount => StatusCode::BAD_REQUEST,
+        _ => StatusCode::CONFLICT,
+    };
+    error(status, e.to_string(), "LINK_REFUSED")
+}
+
+/// The signed-in account, which linked accounts are merged into
+async fn primary_user<'a>(
+    state: &'a AppState,
+    auth_header: Option<&str>,
+) -> Result<(&'a MongoClient, UserDoc), Response<BoxBody>> {
+    let claims = require_claims(state, auth_header)?;
+    let mongo = mongo(state)?;
+    let users = collection::<UserDoc>(mongo, USER_COLLECTION).await?;
+    match users
+        .find_one(doc! { "identifier": &claims.identifier, "is_active": true })
+        .await
+    {
+        Ok(Some(user)) => Ok((mongo, user)),
+        Ok(None) => Err(error(
+            StatusCode::NOT_FOUND,
+            "Account not found",
+            "NOT_FOUND",
+        )),
+        Err(e) => Err(error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            e.to_string(),
+            "DB_ERROR",
+        )),
+    }
+}
+
+/// Active account with a filter, or the generic credentials error
+async fn account(
+    mongo: &MongoClient,
+    filter: bson::Document,
+) -> Result<UserDoc, Response<BoxBody>> {
+    let users = collection::<UserDoc>(mongo, USER_COLLECTION).await?;
+    let mut filter = filter;
+    filter.insert("is_active", true);
+    match users.find_one(filter).await {
+        Ok(Some(user)) => Ok(user),
+        Ok(None) => Err(invalid_credentials()),
+        Err(e) => Err(error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            e.to_string(),
+            "DB_ERROR",
+        )),
+    }
+}
+
+async fn link(
+    mongo: &MongoClient,
+    primary: &UserDoc,
+    other: &UserDoc,
+    proof: LinkProof,
+) -> Response<BoxBody> {
+    match link_account(mongo, primary, other, proof).await {
+        Ok(link) => json_response(StatusCode::CREATED, &LinkedAccountResponse::from(link)),
+        Err(e) => link_error(e),
+    }
+}
+
+// =============================================================================
+// Route Handlers
+// =============================================================================
+
+/// GET /auth/links
+pub async fn handle_list_links(
+    req: Request<hyper::body::Incoming>,
+    state: Arc<AppState>,
+) -> Response<BoxBody> {
+    let (mongo, primary) = match primary_user(&state, get_auth_header(&req)).await {
+        Ok(p) => p,
+        Err(resp) => return resp,
+    };
+    match linked_accounts(mongo, &primary.identifier).await {
+        Ok(links) => json_response(
+            StatusCode::OK,
+            &IdentityResponse {
+                identifier: primary.identifier,
+                human_id: primary.human_id,
+                agent_pub_key: primary.agent_pub_key,
+                linked: links.into_iter().map(LinkedAccountResponse::from).collect(),
+            },
+        ),
+        Err(e) => link_error(e),
+    }
+}
+
+/// POST /auth/links/password
+pub async fn handle_link_password(
+    req: Request<hyper::body::Incoming>,
+    state: Arc<AppState>,
+) -> Response<BoxBody> {
+    let (mongo, primary) = match primary_user(&state, get_auth_header(&req)).await {
+        Ok(p) => p,
+        Err(resp) => return resp,
+    };
+    let body: LinkPasswordRequest = match parse_json_body(req).await {
+        Ok(b) => b,
+        Err(e) => {
+            return error(
+                StatusCode::BAD_REQUEST,
+                format!("Invalid JSON body: {e}"),
+                "INVALID_JSON",
+            )
+        }
+    };
+
+    let other = match account(mongo, doc! { "identifier": &body.identifier }).await {
+        Ok(u) => u,
+        Err(resp) => return resp,
+    };
+    match verify_password(&body.password, &other.password_hash) {
+        Ok(true) => link(mongo, &primary, &other, LinkProof::Password).await,
+        Ok(false) => {
+            warn!(
+                "Link failed - invalid password for {} (by {})",
+                body.identifier, primary.identifier
+            );
+            invalid_credentials()
+        }
+        Err(e) => {
+            warn!("Password verification error: {}", e);
+            error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Authentication error",
+                "AUTH_ERROR",
+            )
+        }
+    }
+}
+
+/// POST /auth/links/passkey/options
+pub async fn handle_link_passkey_options(
+    req: Request<hyper::body::Incoming>,
+    state: Arc<AppState>,
+) -> Response<BoxBody> {
+    let (mongo, primary) = match primary_user(&state, get_auth_header(&req)).await {
+        Ok(p) => p,
+        Err(resp) => return resp,
+    };
+    let rp = match relying_party(&state) {
+        Ok(rp) => rp,
+        Err(resp) => return resp,
+    };
+    let body: LoginOptionsRequest = match parse_json_body(req).await {
+        Ok(b) => b,
+        Err(e) => {
+            return error(
+                StatusCode::BAD_REQUEST,
+                format!("Invalid JSON body: {e}"),
+                "INVALID_JSON",
+            )
+        }
+    };
+    let other = body.identifier.filter(|i| !i.is_empty());
+    request_options(
+        &rp,
+        mongo,
+        other.as_deref(),
+        WebAuthnCeremony::Linking,
+        Some(primary.identifier),
+    )
+    .await
+}
+
+/// POST /auth/links/passkey
+pub async fn handle_link_passkey(
+    req: Request<hyper::body::Incoming>,
+    state: Arc<AppState>,
+) -> Response<BoxBody> {
+    let (mongo, primary) = match primary_user(&state, get_auth_header(&req)).await {
+        Ok(p) => p,
+        Err(resp) => return resp,
+    };
+    let rp = match relying_party(&state) {
+        Ok(rp) => rp,
+        Err(resp) => return resp,
+    };
+    let body: PasskeyLoginRequest = match parse_json_body(req).await {
+        Ok(b) => b,
+        Err(e) => {
+            return error(
+                StatusCode::BAD_REQUEST,
+                format!("Invalid JSON body: {e}"),
+                "INVALID_JSON",
+            )
+        }
+    };
+
+    let (passkey, pending) =
+        match verify_passkey_assertion(&rp, mongo, &body, WebAuthnCeremony::Linking).await {
+            Ok(verified) => verified,
+            Err(resp) => return resp,
+        };
+    // The challenge must have been issued to the account doing the linking
+    if pending.identifier.as_deref() != Some(primary.identifier.as_str()) {
+        warn!("Link failed - challenge issued to another user");
+        return invalid_credentials();
+    }
+    let other = match account(mongo, doc! { "identifier": &passkey.identifier }).await {
+        Ok(u) => u,
+        Err(resp) => return resp,
+    };
+    link(mongo, &primary, &other, LinkProof::Passkey).await
+}
+
+/// POST /auth/links/api-key
+pub async fn handle_link_api_key(
+    req: Request<hyper::body::Incoming>,
+    state: Arc<AppState>,
+) -> Response<BoxBody> {
+    let (mongo, primary) = match primary_user(&state, get_auth_header(&req)).await {
+        Ok(p) => p,
+        Err(resp) => return resp,
+    };
+    let body: LinkApiKeyRequest = match parse_json_body(req).await {
+        Ok(b) => b,
+        Err(e) => {
+            return error(
+                StatusCode::BAD_REQUEST,
+                format!("Invalid JSON body: {e}"),
+                "INVALID_JSON",
+            )
+        }
+    };
+
+    let keys = match collection::<ApiKeyDoc>(mongo, API_KEY_COLLECTION).await {
+        Ok(k) => k,
+        Err(resp) => return resp,
+    };
+    let key = match keys
+        .find_one(doc! { "key_hash": hash_api_key(&body.api_key), "is_active": true })
+        .await
+    {
+        Ok(Some(k)) => k,
+        Ok(None) => {
+            warn!("Link failed - unknown API key (by {})", primary.identifier);
+            return invalid_credentials();
+        }
+        Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string(), "DB_ERROR"),
+    };
+    if key
+        .expires_at
+        .is_some_and(|expires| expires < bson::DateTime::now())
+    {
+        return invalid_credentials();
+    }
+    let other = match account(mongo, doc! { "_id": key.owner_id }).await {
+        Ok(u) => u,
+        Err(resp) => return resp,
+    };
+    link(mongo, &primary, &other, LinkProof::ApiKey).await
+}
+
+/// DELETE /auth/links/{identifier}
+pub async fn handle_unlink(
+    req: Request<hyper::body::Incoming>,
+    state: Arc<AppState>,
+) -> Response<BoxBody> {
+    let Some(identifier) = parse_link_path(req.uri().path()) else {
+        return error(StatusCode::NOT_FOUND, "Link not found", "NOT_FOUND");
+    };
+    let (mongo, primary) = match primary_user(&state, get_auth_header(&req)).await {
+        Ok(p) => p,
+        Err(resp) => return resp,
+    };
+    match unlink_account(mongo, &primary.identifier, &identifier).await {
+        Ok(_) => json_response(
+            StatusCode::OK,
+            &SuccessResponse {
+                success: true,
+                message: format!("{identifier} unlinked"),
+            },
+        ),
+        Err(e) => link_error(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_link_path() {
+        assert_eq!(
+            parse_link_path("/auth/links/ana%40example.org"),
+            Some("ana@example.org".to_string())
+        );
+        assert_eq!(parse_link_path("/auth/links/"), None);
+        assert_eq!(parse_link_path("/auth/links/passkey/options"), None);
+        assert_eq!(parse_link_path("/auth/links"), None);
+    }
+}
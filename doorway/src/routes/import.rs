@@ -21,16 +21,72 @@
 //!
 //! - POST /import/queue → elohim-storage /import/queue
 //! - GET /import/status/{batch_id} → elohim-storage /import/status/{batch_id}
+//!
+//! ## Duplicate check
+//!
+//! Content batches are also checked for near-duplicates of projected
+//! content (see [`duplicate_detection`](crate::services::duplicate_detection)).
+//! The check runs alongside the import and its report is added to the
+//! status response as `duplicate_check`.
+//!
+//! ## Moderation
+//!
+//! When the [moderation](crate::services::moderation) filter is configured, content batch
+//! items are screened before the batch is queued. Flagged items are
+//! quarantined for steward review and the rest are queued as a new blob;
+//! the queue response reports them as `moderation.quarantined`.
+//!
+//! ## Provenance
+//!
+//! An upstream system may sign its manifest (see
+//! [`import_provenance`](crate::services::import_provenance)) and send the
+//! signature as `signature: {source, signature}`. The doorway verifies it
+//! against IMPORT_SIGNERS before anything else happens and forwards the
+//! verified `provenance` for the zome to store next to the batch. Signed
+//! imports that don't verify are refused; unsigned imports are unaffected.
+//!
+//! ## Validation rules
+//!
+//! Operator rules (see [`import_validation`](crate::services::import_validation))
+//! run over the items next. Items that break a rule are left out of the
+//! queued batch, and the errors are reported under `validation` in the queue
+//! and status responses.
+//!
+//! ## Abuse detection
+//!
+//! Before any of that, a queued batch is checked against its caller's
+//! baselines (see [`import_abuse`](crate::services::import_abuse)). The
+//! caller is the X-API-Key when one is sent, else the signed-in human, else
+//! the client IP. A caller whose batches come too fast or too large is
+//! throttled with `429 Too Many Requests` and `Retry-After`.
 
 use bytes::Bytes;
-use http_body_util::{BodyExt, Full};
+use http_body_util::{BodyExt, Full, Limited};
 use hyper::body::Incoming;
 use hyper::{Method, Request, Response, StatusCode};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Instant;
 use tracing::{debug, info, warn};
 
+use super::auth_helpers::require_user;
+use crate::server::limits::{payload_too_large, BodyClass, BodyLimits};
+use crate::server::AppState;
+use crate::services::duplicate_detection::DuplicateDetector;
+use crate::services::import_abuse::{ImportAbuseDetector, ImportVerdict};
+use crate::services::import_provenance::{ImportProvenance, ImportSigners, ManifestSignature};
+use crate::services::import_validation::{ImportValidator, ValidationReport};
+use crate::services::moderation::ModerationService;
 use crate::services::ImportConfigStore;
 
+/// Batch type whose items are checked for duplicates and moderated
+const CONTENT_BATCH_TYPE: &str = "content";
+
+/// Items screened by the moderation API at once
+const MODERATION_CONCURRENCY: usize = 8;
+
 // =============================================================================
 // Request/Response Types
 // =============================================================================
@@ -61,6 +117,9 @@ pub struct ImportQueueRequest {
     /// Higher delay = more conductor breathing room, slower overall
     #[serde(default)]
     pub chunk_delay_ms: Option<u64>,
+    /// Upstream signature over blob_hash + total_items (optional)
+    #[serde(default)]
+    pub signature: Option<ManifestSignature>,
 }
 
 fn default_schema_version() -> u32 {
@@ -144,6 +203,55 @@ pub fn match_import_route(
 // Route Handler
 // =============================================================================
 
+/// Optional checks run on imports, as configured
+#[derive(Clone, Default)]
+pub struct ImportChecks {
+    pub duplicates: Option<Arc<DuplicateDetector>>,
+    pub moderation: Option<Arc<ModerationService>>,
+    pub signers: Option<Arc<ImportSigners>>,
+    pub validator: Option<Arc<ImportValidator>>,
+    pub abuse: Option<Arc<ImportAbuseDetector>>,
+    /// Who is importing, when abuse detection is on
+    pub caller: Option<ImportCaller>,
+}
+
+/// Identity imports are tracked under for abuse detection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportCaller {
+    /// `api-key:{digest}`, `human:{human_id}` or `ip:{address}`
+    pub key: String,
+    /// Signed-in human, told when their imports are throttled
+    pub human: Option<String>,
+}
+
+impl ImportCaller {
+    /// Identify the caller of an import request
+    pub fn identify(req: &Request<Incoming>, state: &AppState, addr: SocketAddr) -> Self {
+        if let Some(api_key) = req.headers().get("x-api-key").and_then(|h| h.to_str().ok()) {
+            // Keys are only tracked by digest, never kept in memory as sent
+            let digest = hex::encode(Sha256::digest(api_key.as_bytes()));
+            return Self {
+                key: format!("api-key:{}", &digest[..16]),
+                human: None,
+            };
+        }
+        let auth_header = req
+            .headers()
+            .get("authorization")
+            .and_then(|h| h.to_str().ok());
+        match require_user(state, auth_header) {
+            Ok(claims) => Self {
+                key: format!("human:{}", claims.human_id),
+                human: Some(claims.human_id),
+            },
+            Err(_) => Self {
+                key: format!("ip:{}", addr.ip()),
+                human: None,
+            },
+        }
+    }
+}
+
 /// Handle import route request
 ///
 /// Doorway forwards all import requests to elohim-storage.
@@ -157,6 +265,8 @@ pub async fn handle_import_request(
     storage_url: Option<String>,
     batch_type: String,
     batch_id: Option<String>,
+    checks: ImportChecks,
+    body_limits: Arc<BodyLimits>,
 ) -> Response<Full<Bytes>> {
     let storage_url = match storage_url {
         Some(url) => url,
@@ -174,11 +284,11 @@ pub async fn handle_import_request(
     match method {
         Method::POST if batch_id.is_none() => {
             // POST /import/{batch_type} → forward to storage /import/queue
-            forward_queue_import(req, &storage_url, &batch_type).await
+            forward_queue_import(req, &storage_url, &batch_type, checks, &body_limits).await
         }
         Method::GET if batch_id.is_some() => {
             // GET /import/{batch_type}/{batch_id} → forward to storage /import/status/{batch_id}
-            forward_get_status(&storage_url, batch_id.as_ref().unwrap()).await
+            forward_get_status(&storage_url, batch_id.as_ref().unwrap(), &checks).await
         }
         _ => import_error_response(
             StatusCode::METHOD_NOT_ALLOWED,
@@ -192,10 +302,24 @@ async fn forward_queue_import(
     req: Request<Incoming>,
     storage_url: &str,
     batch_type: &str,
+    checks: ImportChecks,
+    body_limits: &BodyLimits,
 ) -> Response<Full<Bytes>> {
-    // Read request body
-    let body = match req.collect().await {
+    let duplicates = checks.duplicates.filter(|_| batch_type == CONTENT_BATCH_TYPE);
+    let moderation = checks
+        .moderation
+        .filter(|m| batch_type == CONTENT_BATCH_TYPE && m.screens_writes());
+    let validator = checks.validator.filter(|v| v.applies_to(batch_type));
+
+    // Read request body, up to the import limit
+    let limit = body_limits.limit(BodyClass::Import);
+    let body = match Limited::new(req.into_body(), limit).collect().await {
         Ok(collected) => collected.to_bytes(),
+        Err(e) if e.is::<http_body_util::LengthLimitError>() => {
+            body_limits.record_rejection(BodyClass::Import);
+            warn!(limit, "Import request body over limit");
+            return payload_too_large(limit);
+        }
         Err(e) => {
             warn!("Import request body error: {}", e);
             return import_error_response(StatusCode::BAD_REQUEST, "Failed to read request body");
@@ -203,7 +327,7 @@ async fn forward_queue_import(
     };
 
     // Parse to validate
-    let import_req: ImportQueueRequest = match serde_json::from_slice(&body) {
+    let mut import_req: ImportQueueRequest = match serde_json::from_slice(&body) {
         Ok(r) => r,
         Err(e) => {
             warn!("Import request JSON parse error: {}", e);
@@ -211,6 +335,109 @@ async fn forward_queue_import(
         }
     };
 
+    // Hold back callers whose imports depart from their baseline
+    if let (Some(abuse), Some(caller)) = (checks.abuse.as_ref(), checks.caller.as_ref()) {
+        if let Some(response) = check_abuse(abuse, caller, import_req.total_items).await {
+            return response;
+        }
+    }
+
+    // Check the upstream signature against the manifest as submitted
+    let provenance = match verify_provenance(checks.signers.as_deref(), &import_req) {
+        Ok(provenance) => provenance,
+        Err((status, message)) => {
+            warn!(blob_hash = %import_req.blob_hash, "{}", message);
+            return import_error_response(status, &message);
+        }
+    };
+
+    // Leave out items that break the operator's rules
+    let mut validation = None;
+    if let Some(ref validator) = validator {
+        match validate_import(validator, storage_url, batch_type, &mut import_req).await {
+            Ok(report) if report.rejected > 0 && report.rejected == report.checked => {
+                info!(
+                    blob_hash = %import_req.blob_hash,
+                    rejected = report.rejected,
+                    "Every import item failed validation"
+                );
+                let body = serde_json::json!({
+                    "error": "Every import item failed validation",
+                    "validation": report,
+                });
+                return Response::builder()
+                    .status(StatusCode::UNPROCESSABLE_ENTITY)
+                    .header("Content-Type", "application/json")
+                    .header("Access-Control-Allow-Origin", "*")
+                    .body(Full::new(Bytes::from(body.to_string())))
+                    .unwrap();
+            }
+            Ok(report) => {
+                if report.rejected > 0 {
+                    info!(
+                        blob_hash = %import_req.blob_hash,
+                        rejected = report.rejected,
+                        "Import items failed validation"
+                    );
+                }
+                validation = Some(report);
+            }
+            Err(e) => {
+                warn!(blob_hash = %import_req.blob_hash, error = %e, "Import validation failed");
+                return import_error_response(
+                    StatusCode::BAD_GATEWAY,
+                    &format!("Failed to validate import items: {e}"),
+                );
+            }
+        }
+    }
+
+    // Screen items before anything is queued
+    let mut quarantined = 0;
+    if let Some(moderation) = moderation {
+        match screen_import(&moderation, storage_url, &import_req).await {
+            Ok(None) => {}
+            Ok(Some(screened)) if screened.total_items == 0 => {
+                info!(
+                    blob_hash = %import_req.blob_hash,
+                    quarantined = screened.quarantined,
+                    "Every import item held for moderation review"
+                );
+                let body = serde_json::json!({
+                    "batch_id": import_req.batch_id,
+                    "queued_count": 0,
+                    "processing": false,
+                    "message": "All items are held for moderation review",
+                    "moderation": { "quarantined": screened.quarantined },
+                });
+                return Response::builder()
+                    .status(StatusCode::ACCEPTED)
+                    .header("Content-Type", "application/json")
+                    .header("Access-Control-Allow-Origin", "*")
+                    .body(Full::new(Bytes::from(body.to_string())))
+                    .unwrap();
+            }
+            Ok(Some(screened)) => {
+                info!(
+                    blob_hash = %import_req.blob_hash,
+                    screened_blob_hash = %screened.blob_hash,
+                    quarantined = screened.quarantined,
+                    "Import items held for moderation review"
+                );
+                import_req.blob_hash = screened.blob_hash;
+                import_req.total_items = screened.total_items;
+                quarantined = screened.quarantined;
+            }
+            Err(e) => {
+                warn!(blob_hash = %import_req.blob_hash, error = %e, "Import moderation failed");
+                return import_error_response(
+                    StatusCode::BAD_GATEWAY,
+                    &format!("Failed to screen import items: {e}"),
+                );
+            }
+        }
+    }
+
     info!(
         batch_type = batch_type,
         blob_hash = %import_req.blob_hash,
@@ -229,6 +456,7 @@ async fn forward_queue_import(
         "schema_version": import_req.schema_version,
         "chunk_size": import_req.chunk_size,
         "chunk_delay_ms": import_req.chunk_delay_ms,
+        "provenance": provenance,
     });
 
     // IMPORT_DEBUG: Log full request body
@@ -272,6 +500,17 @@ async fn forward_queue_import(
                     );
 
                     // IMPORT_DEBUG: Log response body
+                    if let Some(detector) = duplicates.filter(|_| status.is_success()) {
+                        start_duplicate_check(detector, storage_url, &body);
+                    }
+                    if let (Some(validator), Some(report), true) =
+                        (validator, validation.as_ref(), status.is_success())
+                    {
+                        if let Some(batch_id) = queued_batch_id(&body) {
+                            validator.set_report(&batch_id, report.clone());
+                        }
+                    }
+
                     if std::env::var("IMPORT_DEBUG").is_ok() {
                         debug!(
                             response_status = %status,
@@ -284,7 +523,17 @@ async fn forward_queue_import(
                         .status(StatusCode::from_u16(status.as_u16()).unwrap_or(StatusCode::OK))
                         .header("Content-Type", "application/json")
                         .header("Access-Control-Allow-Origin", "*")
-                        .body(Full::new(Bytes::from(body)))
+                        .body(Full::new(Bytes::from(with_field(
+                            with_field(
+                                body,
+                                "moderation",
+                                (quarantined > 0)
+                                    .then(|| serde_json::json!({ "quarantined": quarantined })),
+                            ),
+                            "validation",
+                            validation
+                                .map(|report| serde_json::to_value(report).unwrap_or_default()),
+                        ))))
                         .unwrap()
                 }
                 Err(e) => import_error_response(
@@ -303,8 +552,245 @@ async fn forward_queue_import(
     }
 }
 
+/// Verify a signed import's manifest signature
+///
+/// Returns the provenance to record, or `None` for unsigned imports.
+fn verify_provenance(
+    signers: Option<&ImportSigners>,
+    import_req: &ImportQueueRequest,
+) -> Result<Option<ImportProvenance>, (StatusCode, String)> {
+    let Some(ref signature) = import_req.signature else {
+        return Ok(None);
+    };
+    let Some(signers) = signers else {
+        return Err((
+            StatusCode::NOT_IMPLEMENTED,
+            "Signed imports are not enabled (missing IMPORT_SIGNERS)".to_string(),
+        ));
+    };
+    signers
+        .verify(signature, &import_req.blob_hash, import_req.total_items)
+        .map(Some)
+        .map_err(|e| (StatusCode::FORBIDDEN, e.to_string()))
+}
+
+/// Batch ID assigned in a storage queue response
+fn queued_batch_id(queue_response: &str) -> Option<String> {
+    let queued = serde_json::from_str::<serde_json::Value>(queue_response).ok()?;
+    queued.get("batch_id")?.as_str().map(str::to_string)
+}
+
+/// Check a batch's items against the operator's rules
+///
+/// Items that break a rule are taken out: the rest are uploaded to
+/// elohim-storage as a new blob and the request is pointed at it. When every
+/// item fails, the request is left as is.
+async fn validate_import(
+    validator: &ImportValidator,
+    storage_url: &str,
+    batch_type: &str,
+    import_req: &mut ImportQueueRequest,
+) -> Result<ValidationReport, String> {
+    let items = fetch_import_items(storage_url, &import_req.blob_hash).await?;
+    let report = validator.validate(batch_type, &items);
+    if report.rejected == 0 || report.rejected == report.checked {
+        return Ok(report);
+    }
+
+    let rejected = report.rejected_indexes();
+    let passed: Vec<serde_json::Value> = items
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| !rejected.contains(index))
+        .map(|(_, item)| item)
+        .collect();
+    import_req.blob_hash = upload_items(storage_url, &passed).await?;
+    import_req.total_items = passed.len() as u32;
+    Ok(report)
+}
+
+/// Fetch a queued batch's items from elohim-storage and check them for
+/// duplicates in the background
+fn start_duplicate_check(
+    detector: Arc<DuplicateDetector>,
+    storage_url: &str,
+    queue_response: &str,
+) {
+    let Ok(queued) = serde_json::from_str::<serde_json::Value>(queue_response) else {
+        return;
+    };
+    let (Some(batch_id), Some(blob_hash)) = (
+        queued.get("batch_id").and_then(|v| v.as_str()),
+        queued.get("blob_hash").and_then(|v| v.as_str()),
+    ) else {
+        return;
+    };
+    let batch_id = batch_id.to_string();
+    let storage_url = storage_url.to_string();
+    let blob_hash = blob_hash.to_string();
+
+    tokio::spawn(async move {
+        match fetch_import_items(&storage_url, &blob_hash).await {
+            Ok(items) => detector.check_batch(batch_id, items),
+            Err(e) => warn!(batch_id = %batch_id, error = %e, "Skipping import duplicate check"),
+        }
+    });
+}
+
+/// Fetch a batch's items blob from elohim-storage
+async fn fetch_import_items(
+    storage_url: &str,
+    blob_hash: &str,
+) -> Result<Vec<serde_json::Value>, String> {
+    let blob_url = format!("{}/blob/{}", storage_url.trim_end_matches('/'), blob_hash);
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(60))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
+    let resp = client
+        .get(&blob_url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch import blob: {e}"))?;
+    if !resp.status().is_success() {
+        return Err(format!("Import blob returned HTTP {}", resp.status()));
+    }
+    resp.json::<Vec<serde_json::Value>>()
+        .await
+        .map_err(|e| format!("Import blob is not a JSON array: {e}"))
+}
+
+/// A batch with its flagged items taken out
+struct ScreenedBatch {
+    /// Blob holding the items that passed
+    blob_hash: String,
+    total_items: u32,
+    quarantined: usize,
+}
+
+/// Moderate a content batch's items
+///
+/// Returns `None` when nothing was flagged. Otherwise flagged items are
+/// quarantined as `create_content` writes and the remaining items are
+/// uploaded to elohim-storage as a new blob.
+async fn screen_import(
+    moderation: &ModerationService,
+    storage_url: &str,
+    import_req: &ImportQueueRequest,
+) -> Result<Option<ScreenedBatch>, String> {
+    use futures::stream::{self, StreamExt};
+
+    let items = fetch_import_items(storage_url, &import_req.blob_hash).await?;
+    let verdicts: Vec<Vec<String>> = stream::iter(items.iter())
+        .map(|item| moderation.check(item))
+        .buffered(MODERATION_CONCURRENCY)
+        .collect()
+        .await;
+    if verdicts.iter().all(|reasons| reasons.is_empty()) {
+        return Ok(None);
+    }
+
+    let source = format!(
+        "import:{}",
+        import_req
+            .batch_id
+            .as_deref()
+            .unwrap_or(&import_req.blob_hash)
+    );
+    let mut passed = Vec::with_capacity(items.len());
+    let mut quarantined = 0;
+    for (item, reasons) in items.into_iter().zip(verdicts) {
+        if reasons.is_empty() {
+            passed.push(item);
+        } else {
+            moderation
+                .quarantine("create_content", &item, reasons, &source, None)
+                .await?;
+            quarantined += 1;
+        }
+    }
+
+    if passed.is_empty() {
+        return Ok(Some(ScreenedBatch {
+            blob_hash: import_req.blob_hash.clone(),
+            total_items: 0,
+            quarantined,
+        }));
+    }
+
+    Ok(Some(ScreenedBatch {
+        blob_hash: upload_items(storage_url, &passed).await?,
+        total_items: passed.len() as u32,
+        quarantined,
+    }))
+}
+
+/// Upload the items left in a batch to elohim-storage as a new blob
+async fn upload_items(storage_url: &str, items: &[serde_json::Value]) -> Result<String, String> {
+    let data = serde_json::to_vec(items).map_err(|e| e.to_string())?;
+    let blob_hash = blob_hash_of(&data);
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(60))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
+    let resp = client
+        .put(format!(
+            "{}/blob/{}",
+            storage_url.trim_end_matches('/'),
+            blob_hash
+        ))
+        .header("Content-Type", "application/octet-stream")
+        .body(data)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to upload screened items: {e}"))?;
+    if !resp.status().is_success() {
+        return Err(format!(
+            "Screened items upload returned HTTP {}",
+            resp.status()
+        ));
+    }
+    Ok(blob_hash)
+}
+
+/// Blob hash in the seeder's `sha256-{hex}` form
+fn blob_hash_of(data: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    format!("sha256-{:x}", Sha256::digest(data))
+}
+
+/// Add a field to a JSON object response body
+fn with_field(body: String, field: &str, value: Option<serde_json::Value>) -> String {
+    let Some(value) = value else {
+        return body;
+    };
+    match serde_json::from_str::<serde_json::Value>(&body) {
+        Ok(serde_json::Value::Object(mut object)) => {
+            object.insert(field.to_string(), value);
+            serde_json::Value::Object(object).to_string()
+        }
+        _ => body,
+    }
+}
+
+/// Add the batch's duplicate check report to a storage status response
+fn with_duplicate_check(
+    body: String,
+    batch_id: &str,
+    duplicates: Option<&DuplicateDetector>,
+) -> String {
+    let check = duplicates
+        .and_then(|d| d.report(batch_id))
+        .map(|check| serde_json::to_value(check).unwrap_or_default());
+    with_field(body, "duplicate_check", check)
+}
+
 /// Forward GET status request to elohim-storage
-async fn forward_get_status(storage_url: &str, batch_id: &str) -> Response<Full<Bytes>> {
+async fn forward_get_status(
+    storage_url: &str,
+    batch_id: &str,
+    checks: &ImportChecks,
+) -> Response<Full<Bytes>> {
     debug!(
         batch_id = batch_id,
         "Forwarding status request to elohim-storage"
@@ -337,7 +823,15 @@ async fn forward_get_status(storage_url: &str, batch_id: &str) -> Response<Full<
                     .status(StatusCode::from_u16(status.as_u16()).unwrap_or(StatusCode::OK))
                     .header("Content-Type", "application/json")
                     .header("Access-Control-Allow-Origin", "*")
-                    .body(Full::new(Bytes::from(body)))
+                    .body(Full::new(Bytes::from(with_field(
+                        with_duplicate_check(body, batch_id, checks.duplicates.as_deref()),
+                        "validation",
+                        checks
+                            .validator
+                            .as_ref()
+                            .and_then(|v| v.report(batch_id))
+                            .map(|report| serde_json::to_value(report).unwrap_or_default()),
+                    ))))
                     .unwrap(),
                 Err(e) => import_error_response(
                     StatusCode::BAD_GATEWAY,
@@ -356,6 +850,43 @@ async fn forward_get_status(storage_url: &str, batch_id: &str) -> Response<Full<
 // Helpers
 // =============================================================================
 
+/// Check a batch against its caller's baselines, answering 429 when the
+/// caller is throttled
+async fn check_abuse(
+    abuse: &ImportAbuseDetector,
+    caller: &ImportCaller,
+    total_items: u32,
+) -> Option<Response<Full<Bytes>>> {
+    let ImportVerdict::Throttle {
+        retry_after,
+        reason,
+        new,
+    } = abuse.check(&caller.key, total_items, Instant::now())
+    else {
+        return None;
+    };
+    if new {
+        warn!(caller = %caller.key, reason = %reason, "Throttling anomalous import caller");
+        abuse
+            .alert(&caller.key, caller.human.as_deref(), &reason)
+            .await;
+    }
+    let retry_after = retry_after.as_secs().max(1);
+    let body = serde_json::json!({
+        "error": format!("Import throttled: {reason}"),
+        "retry_after": retry_after,
+    });
+    Some(
+        Response::builder()
+            .status(StatusCode::TOO_MANY_REQUESTS)
+            .header("Content-Type", "application/json")
+            .header("Access-Control-Allow-Origin", "*")
+            .header("Retry-After", retry_after.to_string())
+            .body(Full::new(Bytes::from(body.to_string())))
+            .unwrap(),
+    )
+}
+
 /// Create error response
 fn import_error_response(status: StatusCode, message: &str) -> Response<Full<Bytes>> {
     let body = serde_json::json!({
@@ -452,4 +983,50 @@ mod tests {
         let result = match_import_route("/api/v1/cache/content/test", &store);
         assert!(result.is_none());
     }
+
+    #[test]
+    fn test_verify_provenance() {
+        let unsigned: ImportQueueRequest =
+            serde_json::from_str(r#"{"blob_hash":"sha256-abc","total_items":3}"#).unwrap();
+        assert_eq!(verify_provenance(None, &unsigned), Ok(None));
+
+        let signed: ImportQueueRequest = serde_json::from_str(
+            r#"{"blob_hash":"sha256-abc","total_items":3,
+                "signature":{"source":"seeder","signature":"AAAA"}}"#,
+        )
+        .unwrap();
+        let (status, _) = verify_provenance(None, &signed).unwrap_err();
+        assert_eq!(status, StatusCode::NOT_IMPLEMENTED);
+    }
+
+    #[test]
+    fn test_with_field() {
+        let body = r#"{"batch_id":"b1"}"#.to_string();
+        let merged = with_field(
+            body.clone(),
+            "moderation",
+            Some(serde_json::json!({ "quarantined": 2 })),
+        );
+        let merged: serde_json::Value = serde_json::from_str(&merged).unwrap();
+        assert_eq!(merged["batch_id"], "b1");
+        assert_eq!(merged["moderation"]["quarantined"], 2);
+
+        assert_eq!(with_field(body.clone(), "moderation", None), body);
+        assert_eq!(
+            with_field(
+                "not json".to_string(),
+                "moderation",
+                Some(serde_json::json!(1))
+            ),
+            "not json"
+        );
+    }
+
+    #[test]
+    fn test_blob_hash_of() {
+        assert_eq!(
+            blob_hash_of(b"[]"),
+            "sha256-4f53cda18c2baa0c0354bb5f9a3ecbe5ed12ab4d8e11ba873c2f11161202b945"
+        );
+    }
 }
@@ -0,0 +1,424 @@
+//! Insurance Claim Lifecycle API
+//!
+//! Moves Shefa insurance claims through
+//! `filed → submitted → under_review → approved/denied → paid`. The zome
+//! checks each transition; this module decides who may ask for it.
+//!
+//! ## Routes
+//!
+//! - `GET /shefa/claims?status=submitted` - Claims in a status (adjusters)
+//! - `GET /shefa/claims/{id}` - A claim (its member or an adjuster)
+//! - `POST /shefa/claims/{id}/evidence` - Attach `{observer_attestation_ids, member_document_ids}` (the member)
+//! - `POST /shefa/claims/{id}/assign` - Assign `{adjuster_id?}`, defaulting to the caller (adjusters)
+//! - `POST /shefa/claims/{id}/adjudicate` - Decide `{decision, reasoning, ...}` (the assigned adjuster)
+//! - `POST /shefa/claims/{id}/payment` - Record `{settlement_event_id}` for an approved claim (adjusters)
+//!
+//! Adjusters are the humans listed in `CLAIM_ADJUSTERS`, plus admins.
+
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::Incoming;
+use hyper::{Request, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use super::api::{error_response, json_response, private_json_response};
+use super::auth_helpers::require_user;
+use super::zome_helpers::call_content_store_for;
+use crate::auth::{Claims, PermissionLevel};
+use crate::server::AppState;
+
+/// Largest request body accepted
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// Step in a claim's lifecycle
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClaimAction {
+    Evidence,
+    Assign,
+    Adjudicate,
+    Payment,
+}
+
+impl ClaimAction {
+    fn zome_fn(self) -> &'static str {
+        match self {
+            Self::Evidence => "submit_claim_evidence",
+            Self::Assign => "assign_adjuster",
+            Self::Adjudicate => "adjudicate_claim",
+            Self::Payment => "record_claim_payment",
+        }
+    }
+
+    /// Whether only adjusters may take this step
+    fn needs_adjuster(self) -> bool {
+        !matches!(self, Self::Evidence)
+    }
+}
+
+/// Parse `/shefa/claims/{id}`
+pub fn parse_claim_path(path: &str) -> Option<&str> {
+    let id = path.strip_prefix("/shefa/claims/")?;
+    (!id.is_empty() && !id.contains('/')).then_some(id)
+}
+
+/// Parse `/shefa/claims/{id}/{evidence|assign|adjudicate|payment}`
+pub fn parse_claim_action_path(path: &str) -> Option<(&str, ClaimAction)> {
+    let rest = path.strip_prefix("/shefa/claims/")?;
+    let (id, action) = rest.rsplit_once('/')?;
+    let action = match action {
+        "evidence" => ClaimAction::Evidence,
+        "assign" => ClaimAction::Assign,
+        "adjudicate" => ClaimAction::Adjudicate,
+        "payment" => ClaimAction::Payment,
+        _ => return None,
+    };
+    (!id.is_empty() && !id.contains('/')).then_some((id, action))
+}
+
+/// Whether the caller may act as a claims adjuster
+fn is_adjuster(state: &AppState, claims: &Claims) -> bool {
+    claims.permission_level >= PermissionLevel::Admin
+        || state
+            .args
+            .claim_adjusters
+            .iter()
+            .any(|id| id == &claims.human_id)
+}
+
+fn decode_id(id: &str) -> String {
+    urlencoding::decode(id)
+        .map(|id| id.into_owned())
+        .unwrap_or_else(|_| id.to_string())
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct StatusParams {
+    status: Option<String>,
+}
+
+/// Body of `POST /shefa/claims/{id}/evidence`
+#[derive(Debug, Deserialize)]
+struct EvidenceBody {
+    #[serde(default)]
+    observer_attestation_ids: Vec<String>,
+    #[serde(default)]
+    member_document_ids: Vec<String>,
+}
+
+/// Body of `POST /shefa/claims/{id}/assign`
+#[derive(Debug, Default, Deserialize)]
+struct AssignBody {
+    adjuster_id: Option<String>,
+}
+
+/// Body of `POST /shefa/claims/{id}/adjudicate`
+#[derive(Debug, Deserialize)]
+struct AdjudicateBody {
+    decision: String,
+    reasoning: String,
+    approved_amount_value: Option<f64>,
+    approved_amount_unit: Option<String>,
+    interpretation_notes: Option<String>,
+    #[serde(default)]
+    applied_generosity_principle: bool,
+    #[serde(default)]
+    constitutional_basis_documents: Vec<String>,
+    #[serde(default)]
+    policy_citations: Vec<String>,
+    #[serde(default)]
+    flagged_for_governance: bool,
+    governance_review_reason: Option<String>,
+}
+
+/// Body of `POST /shefa/claims/{id}/payment`
+#[derive(Debug, Deserialize)]
+struct PaymentBody {
+    settlement_event_id: String,
+}
+
+/// Must match SubmitClaimEvidenceInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct SubmitClaimEvidenceInput {
+    claim_id: String,
+    submitted_by: String,
+    observer_attestation_ids: Vec<String>,
+    member_document_ids: Vec<String>,
+}
+
+/// Must match AssignAdjusterInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct AssignAdjusterInput {
+    claim_id: String,
+    adjuster_id: String,
+    assigned_by: String,
+}
+
+/// Must match AdjudicateClaimInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct AdjudicateClaimInput {
+    claim_id: String,
+    adjuster_id: String,
+    decision: String,
+    reasoning: String,
+    approved_amount_value: Option<f64>,
+    approved_amount_unit: Option<String>,
+    interpretation_notes: Option<String>,
+    applied_generosity_principle: bool,
+    constitutional_basis_documents: Vec<String>,
+    policy_citations: Vec<String>,
+    flagged_for_governance: bool,
+    governance_review_reason: Option<String>,
+}
+
+/// Must match RecordClaimPaymentInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct RecordClaimPaymentInput {
+    claim_id: String,
+    paid_by: String,
+    settlement_event_id: String,
+}
+
+/// Whether a claim (as returned by `get_insurance_claim`) belongs to the caller
+fn is_claim_member(claim: &Value, human_id: &str) -> bool {
+    ["member_id", "filed_by"]
+        .iter()
+        .any(|field| claim.get(field).and_then(Value::as_str) == Some(human_id))
+}
+
+/// Build the zome input for an action from its request body
+fn zome_input(
+    action: ClaimAction,
+    claim_id: String,
+    claims: &Claims,
+    body: &[u8],
+) -> Result<Value, serde_json::Error> {
+    let actor = claims.human_id.clone();
+    let input = match action {
+        ClaimAction::Evidence => {
+            let body: EvidenceBody = serde_json::from_slice(body)?;
+            serde_json::to_value(SubmitClaimEvidenceInput {
+                claim_id,
+                submitted_by: actor,
+                observer_attestation_ids: body.observer_attestation_ids,
+                member_document_ids: body.member_document_ids,
+            })?
+        }
+        ClaimAction::Assign => {
+            let body: AssignBody = if body.is_empty() {
+                AssignBody::default()
+            } else {
+                serde_json::from_slice(body)?
+            };
+            serde_json::to_value(AssignAdjusterInput {
+                claim_id,
+                adjuster_id: body.adjuster_id.unwrap_or_else(|| actor.clone()),
+                assigned_by: actor,
+            })?
+        }
+        ClaimAction::Adjudicate => {
+            let body: AdjudicateBody = serde_json::from_slice(body)?;
+            serde_json::to_value(AdjudicateClaimInput {
+                claim_id,
+                adjuster_id: actor,
+                decision: body.decision,
+                reasoning: body.reasoning,
+                approved_amount_value: body.approved_amount_value,
+                approved_amount_unit: body.approved_amount_unit,
+                interpretation_notes: body.interpretation_notes,
+                applied_generosity_principle: body.applied_generosity_principle,
+                constitutional_basis_documents: body.constitutional_basis_documents,
+                policy_citations: body.policy_citations,
+                flagged_for_governance: body.flagged_for_governance,
+                governance_review_reason: body.governance_review_reason,
+            })?
+        }
+        ClaimAction::Payment => {
+            let body: PaymentBody = serde_json::from_slice(body)?;
+            serde_json::to_value(RecordClaimPaymentInput {
+                claim_id,
+                paid_by: actor,
+                settlement_event_id: body.settlement_event_id,
+            })?
+        }
+    };
+    Ok(input)
+}
+
+/// Handle GET /shefa/claims?status=..
+pub async fn handle_claims_by_status(
+    state: Arc<AppState>,
+    query: Option<&str>,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    let claims = match require_user(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+    if !is_adjuster(&state, &claims) {
+        return error_response(
+            StatusCode::FORBIDDEN,
+            "Adjuster permission required",
+            "FORBIDDEN",
+        );
+    }
+
+    let params: StatusParams = serde_urlencoded::from_str(query.unwrap_or("")).unwrap_or_default();
+    let status = params.status.unwrap_or_else(|| "submitted".to_string());
+    match call_content_store_for(&state, "get_claims_by_status", &status, Some(&claims)).await {
+        Ok(data) => private_json_response(
+            &data.unwrap_or_else(|| serde_json::json!([])),
+            "private, no-store",
+        ),
+        Err(e) => {
+            warn!(status = %status, error = ?e, "Failed to list insurance claims");
+            error_response(StatusCode::BAD_GATEWAY, "Lookup failed", "ZOME_ERROR")
+        }
+    }
+}
+
+/// Handle GET /shefa/claims/{id}
+pub async fn handle_get_claim(
+    state: Arc<AppState>,
+    claim_id: &str,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    let claims = match require_user(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let claim_id = decode_id(claim_id);
+    let claim =
+        match call_content_store_for(&state, "get_insurance_claim", &claim_id, Some(&claims)).await
+        {
+            // Returned as (ActionHash, InsuranceClaim)
+            Ok(Some(Value::Array(mut found))) if found.len() == 2 => found.remove(1),
+            Ok(_) => return error_response(StatusCode::NOT_FOUND, "Claim not found", "NOT_FOUND"),
+            Err(e) => {
+                warn!(claim_id = %claim_id, error = ?e, "Failed to load insurance claim");
+                return error_response(StatusCode::BAD_GATEWAY, "Lookup failed", "ZOME_ERROR");
+            }
+        };
+    // Don't reveal other members' claims exist
+    if !is_claim_member(&claim, &claims.human_id) && !is_adjuster(&state, &claims) {
+        return error_response(StatusCode::NOT_FOUND, "Claim not found", "NOT_FOUND");
+    }
+
+    private_json_response(&claim, "private, no-store")
+}
+
+/// Handle POST /shefa/claims/{id}/{evidence|assign|adjudicate|payment}
+pub async fn handle_claim_action(
+    req: Request<Incoming>,
+    state: Arc<AppState>,
+    claim_id: &str,
+    action: ClaimAction,
+) -> Response<Full<Bytes>> {
+    let auth_header = req
+        .headers()
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string());
+    let claims = match require_user(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+    if action.needs_adjuster() && !is_adjuster(&state, &claims) {
+        return error_response(
+            StatusCode::FORBIDDEN,
+            "Adjuster permission required",
+            "FORBIDDEN",
+        );
+    }
+
+    let body = match Limited::new(req.into_body(), MAX_BODY_BYTES)
+        .collect()
+        .await
+    {
+        Ok(collected) => collected.to_bytes(),
+        Err(_) => {
+            return error_response(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                &format!("Requests are limited to {MAX_BODY_BYTES} bytes"),
+                "TOO_LARGE",
+            )
+        }
+    };
+    let claim_id = decode_id(claim_id);
+    let input = match zome_input(action, claim_id.clone(), &claims, &body) {
+        Ok(input) => input,
+        Err(e) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                &format!("Invalid request: {e}"),
+                "INVALID_JSON",
+            )
+        }
+    };
+
+    // The zome checks the transition, the member and the assigned adjuster
+    match call_content_store_for(&state, action.zome_fn(), &input, Some(&claims)).await {
+        Ok(data) => {
+            info!(
+                claim_id = %claim_id,
+                action = ?action,
+                actor = %claims.human_id,
+                "Insurance claim updated"
+            );
+            json_response(serde_json::to_vec(&data.unwrap_or(Value::Null)).unwrap_or_default())
+        }
+        Err(e) => {
+            warn!(claim_id = %claim_id, action = ?action, error = ?e, "Insurance claim update refused");
+            error_response(
+                StatusCode::CONFLICT,
+                &format!("Claim not updated: {e}"),
+                "CLAIM_UPDATE_FAILED",
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_claim_paths() {
+        assert_eq!(parse_claim_path("/shefa/claims/clm-1"), Some("clm-1"));
+        assert_eq!(parse_claim_path("/shefa/claims/clm-1/assign"), None);
+        assert_eq!(parse_claim_path("/shefa/claims/"), None);
+        assert_eq!(
+            parse_claim_action_path("/shefa/claims/clm-1/adjudicate"),
+            Some(("clm-1", ClaimAction::Adjudicate))
+        );
+        assert_eq!(
+            parse_claim_action_path("/shefa/claims/clm-1/payment"),
+            Some(("clm-1", ClaimAction::Payment))
+        );
+        assert_eq!(parse_claim_action_path("/shefa/claims/clm-1/settle"), None);
+        assert_eq!(parse_claim_action_path("/shefa/claims/a/b/assign"), None);
+    }
+
+    #[test]
+    fn test_only_evidence_is_open_to_members() {
+        assert!(!ClaimAction::Evidence.needs_adjuster());
+        for action in [
+            ClaimAction::Assign,
+            ClaimAction::Adjudicate,
+            ClaimAction::Payment,
+        ] {
+            assert!(action.needs_adjuster());
+        }
+    }
+
+    #[test]
+    fn test_is_claim_member() {
+        let claim = serde_json::json!({ "member_id": "human-a", "filed_by": "human-b" });
+        assert!(is_claim_member(&claim, "human-a"));
+        assert!(is_claim_member(&claim, "human-b"));
+        assert!(!is_claim_member(&claim, "human-c"));
+    }
+}
@@ -0,0 +1,294 @@
+//! Invitations
+//!
+//! Members grow the community by invitation. A member generates an invite
+//! code, optionally tied to a learning path or, for a gate's steward, to
+//! access through the gate; whoever registers with the code
+//! (`POST /auth/register` with `invite_code`) is linked to the inviter, who
+//! earns `invitation` points. With `INVITE_ONLY` set, registration needs a
+//! code. `INVITES_PER_MEMBER` caps the invitations a member may hold.
+//!
+//! ## Routes
+//!
+//! - `POST /invitations` - Create `{path_id?, gate_id?, grant_type?, valid_days?, note?}`;
+//!   answers with the invitation and its code
+//! - `GET /invitations` - The caller's invitations, paged, with their cap and
+//!   how many remain
+//! - `DELETE /invitations/{id}` - Revoke an unused invitation
+
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::Incoming;
+use hyper::{Request, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use super::api::{error_response, json_response};
+use super::auth_helpers::require_user;
+use super::pagination::{page_response_with, Page, PageRequest};
+use super::zome_helpers::{call_content_store, call_content_store_for};
+use crate::auth::{Claims, PermissionLevel};
+use crate::server::AppState;
+use crate::types::{DoorwayError, Result};
+
+/// Largest request body accepted
+const MAX_BODY_BYTES: usize = 8 * 1024;
+
+/// Invitations per page by default
+const DEFAULT_LIMIT: usize = 50;
+
+/// Most invitations per page
+const MAX_LIMIT: usize = 200;
+
+/// Parse `/invitations/{id}`
+pub fn parse_invitation_path(path: &str) -> Option<&str> {
+    let id = path.strip_prefix("/invitations/")?;
+    (!id.is_empty() && !id.contains('/')).then_some(id)
+}
+
+/// Body of `POST /invitations`
+#[derive(Debug, Default, Deserialize)]
+struct CreateBody {
+    #[serde(default)]
+    path_id: Option<String>,
+    #[serde(default)]
+    gate_id: Option<String>,
+    #[serde(default)]
+    grant_type: Option<String>,
+    #[serde(default)]
+    valid_days: Option<u32>,
+    #[serde(default)]
+    note: Option<String>,
+}
+
+/// Must match CreateInvitationInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct CreateInvitationInput<'a> {
+    inviter_id: &'a str,
+    inviter_agent_id: &'a str,
+    path_id: Option<String>,
+    gate_id: Option<String>,
+    grant_type: Option<String>,
+    valid_days: Option<u32>,
+    note: Option<String>,
+    max_invitations: Option<u32>,
+}
+
+/// Must match RevokeInvitationInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct RevokeInvitationInput<'a> {
+    invitation_id: &'a str,
+    inviter_id: &'a str,
+}
+
+/// Must match RedeemInvitationInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct RedeemInvitationInput<'a> {
+    code: &'a str,
+    invitee_id: &'a str,
+    invitee_agent_id: &'a str,
+}
+
+/// Fields of `GET /invitations` beside the page
+#[derive(Debug, Serialize)]
+struct InvitationAllowance {
+    /// `None` when the caller is not capped
+    cap: Option<u32>,
+    remaining: Option<u32>,
+}
+
+/// Invitations the caller may hold, `None` for no cap
+fn invitation_cap(state: &AppState, claims: &Claims) -> Option<u32> {
+    let cap = state.args.invites_per_member;
+    (cap > 0 && claims.permission_level < PermissionLevel::Admin).then_some(cap)
+}
+
+/// Whether an invite code can still be used
+pub(crate) async fn invite_code_usable(state: &AppState, code: &str) -> Result<bool> {
+    let invitation = call_content_store(state, "check_invitation", &code).await?;
+    Ok(invitation.is_some_and(|invitation| !invitation.is_null()))
+}
+
+/// Use an invite code for a member who is registering, returning the
+/// inviter's human id
+pub(crate) async fn redeem_invite_code(
+    state: &AppState,
+    code: &str,
+    invitee_id: &str,
+    invitee_agent_id: &str,
+) -> Result<String> {
+    let input = RedeemInvitationInput {
+        code,
+        invitee_id,
+        invitee_agent_id,
+    };
+    let redeemed = call_content_store(state, "redeem_invitation", &input).await?;
+    redeemed
+        .as_ref()
+        .and_then(|r| r["invitation"]["invitation"]["inviter_id"].as_str())
+        .map(str::to_string)
+        .ok_or_else(|| DoorwayError::Internal("Redeemed invitation has no inviter".into()))
+}
+
+/// Handle POST /invitations
+pub async fn handle_create_invitation(
+    req: Request<Incoming>,
+    state: Arc<AppState>,
+) -> Response<Full<Bytes>> {
+    let auth_header = req
+        .headers()
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string());
+    let claims = match require_user(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let body = match Limited::new(req.into_body(), MAX_BODY_BYTES)
+        .collect()
+        .await
+    {
+        Ok(collected) => collected.to_bytes(),
+        Err(_) => {
+            return error_response(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                &format!("Requests are limited to {MAX_BODY_BYTES} bytes"),
+                "TOO_LARGE",
+            )
+        }
+    };
+    let create: CreateBody = if body.is_empty() {
+        CreateBody::default()
+    } else {
+        match serde_json::from_slice(&body) {
+            Ok(create) => create,
+            Err(e) => {
+                return error_response(
+                    StatusCode::BAD_REQUEST,
+                    &format!("Invalid request: {e}"),
+                    "INVALID_JSON",
+                )
+            }
+        }
+    };
+
+    let input = CreateInvitationInput {
+        inviter_id: &claims.human_id,
+        inviter_agent_id: &claims.agent_pub_key,
+        path_id: create.path_id,
+        gate_id: create.gate_id,
+        grant_type: create.grant_type,
+        valid_days: create.valid_days,
+        note: create.note,
+        max_invitations: invitation_cap(&state, &claims),
+    };
+    match call_content_store_for(&state, "create_invitation", &input, Some(&claims)).await {
+        Ok(data) => {
+            info!(inviter = %claims.human_id, "Invitation created");
+            json_response(serde_json::to_vec(&data.unwrap_or(Value::Null)).unwrap_or_default())
+        }
+        Err(e) => {
+            warn!(inviter = %claims.human_id, error = ?e, "Failed to create invitation");
+            error_response(
+                StatusCode::FORBIDDEN,
+                "Invitation not created (invitation limit reached, unknown path, or not the gate's steward)",
+                "INVITE_FAILED",
+            )
+        }
+    }
+}
+
+/// Handle GET /invitations
+pub async fn handle_list_invitations(
+    state: Arc<AppState>,
+    query: Option<&str>,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    let claims = match require_user(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+    let page = match PageRequest::from_query(query, DEFAULT_LIMIT, MAX_LIMIT) {
+        Ok(page) => page,
+        Err(msg) => return error_response(StatusCode::BAD_REQUEST, &msg, "BAD_REQUEST"),
+    };
+
+    match call_content_store_for(
+        &state,
+        "get_invitations_by_inviter",
+        &claims.human_id,
+        Some(&claims),
+    )
+    .await
+    {
+        Ok(data) => {
+            let invitations = match data {
+                Some(Value::Array(invitations)) => invitations,
+                _ => Vec::new(),
+            };
+            let cap = invitation_cap(&state, &claims);
+            let held = invitations
+                .iter()
+                .filter(|i| i["invitation"]["status"] != "revoked")
+                .count() as u32;
+            let allowance = InvitationAllowance {
+                remaining: cap.map(|cap| cap.saturating_sub(held)),
+                cap,
+            };
+            page_response_with(&Page::from_all(invitations, page), &allowance)
+        }
+        Err(e) => {
+            warn!(error = ?e, "Failed to load invitations");
+            error_response(StatusCode::BAD_GATEWAY, "Lookup failed", "ZOME_ERROR")
+        }
+    }
+}
+
+/// Handle DELETE /invitations/{id}
+pub async fn handle_revoke_invitation(
+    state: Arc<AppState>,
+    invitation_id: &str,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    let claims = match require_user(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let input = RevokeInvitationInput {
+        invitation_id,
+        inviter_id: &claims.human_id,
+    };
+    match call_content_store_for(&state, "revoke_invitation", &input, Some(&claims)).await {
+        Ok(data) => {
+            info!(invitation_id = %invitation_id, "Invitation revoked");
+            json_response(serde_json::to_vec(&data.unwrap_or(Value::Null)).unwrap_or_default())
+        }
+        Err(e) => {
+            info!(invitation_id = %invitation_id, error = ?e, "Invitation not revoked");
+            error_response(
+                StatusCode::CONFLICT,
+                "Invitation not revoked (not yours, or already used)",
+                "REVOKE_FAILED",
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_invitation_path() {
+        assert_eq!(
+            parse_invitation_path("/invitations/invitation-uhCAk-1"),
+            Some("invitation-uhCAk-1")
+        );
+        assert_eq!(parse_invitation_path("/invitations/"), None);
+        assert_eq!(parse_invitation_path("/invitations/a/b"), None);
+        assert_eq!(parse_invitation_path("/invitations"), None);
+    }
+}
@@ -0,0 +1,218 @@
+//! Knowledge Map Layout API
+//!
+//! One-call rendering data for the knowledge map cluster/graph view. The
+//! content_store zome merges the map definition with content summaries and
+//! the viewer's mastery (bridged from imagodei); doorway resolves who the
+//! viewer is, enforces map visibility and caches the result per viewer.
+//!
+//! ## Routes
+//!
+//! - `GET /knowledge-maps/{id}/layout` - Map, nodes, edges, clusters and mastery overlay
+//!
+//! Anonymous requests get the layout without mastery and only for public maps.
+
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::{Response, StatusCode};
+use serde::Serialize;
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+use super::api::{error_response, json_response, overloaded_response};
+use super::auth_helpers::require_user;
+use super::zome_helpers::{call_content_store_for, get_content_store_config};
+use crate::cache::rules::CacheRuleExt;
+use crate::server::AppState;
+use crate::types::DoorwayError;
+
+/// Zome function backing the layout route
+const LAYOUT_FN: &str = "get_knowledge_map_layout";
+
+/// Input for content_store::get_knowledge_map_layout
+/// Must match KnowledgeMapLayoutInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Clone, Serialize)]
+pub struct KnowledgeMapLayoutInput {
+    pub map_id: String,
+    pub human_id: Option<String>,
+    pub skip_mastery: bool,
+}
+
+/// Extract the map id from `/knowledge-maps/{id}/layout`
+fn parse_layout_path(path: &str) -> Option<&str> {
+    let map_id = path
+        .strip_prefix("/knowledge-maps/")?
+        .strip_suffix("/layout")?;
+    (!map_id.is_empty() && !map_id.contains('/')).then_some(map_id)
+}
+
+/// Whether a viewer may see a map with the given definition
+///
+/// Public maps are open; otherwise only the owner and humans listed in
+/// `shared_with_json` may see it.
+fn can_view(knowledge_map: &serde_json::Value, viewer: Option<&str>) -> bool {
+    let visibility = knowledge_map
+        .get("visibility")
+        .and_then(|v| v.as_str())
+        .unwrap_or("private");
+    if visibility == "public" {
+        return true;
+    }
+    let Some(viewer) = viewer else {
+        return false;
+    };
+    if knowledge_map.get("owner_id").and_then(|v| v.as_str()) == Some(viewer) {
+        return true;
+    }
+    knowledge_map
+        .get("shared_with_json")
+        .and_then(|v| v.as_str())
+        .and_then(|json| serde_json::from_str::<Vec<String>>(json).ok())
+        .is_some_and(|shared| shared.iter().any(|h| h == viewer))
+}
+
+/// Return the layout if the viewer may see it
+fn layout_response(body: &[u8], viewer: Option<&str>) -> Response<Full<Bytes>> {
+    let layout: serde_json::Value = serde_json::from_slice(body).unwrap_or_default();
+    let allowed = layout
+        .get("knowledge_map")
+        .is_some_and(|map| can_view(map, viewer));
+    if !allowed {
+        // Same answer as a missing map so private maps can't be probed
+        return error_response(
+            StatusCode::NOT_FOUND,
+            "Knowledge map not found",
+            "NOT_FOUND",
+        );
+    }
+    json_response(body.to_vec())
+}
+
+/// Handle GET /knowledge-maps/{id}/layout
+pub async fn handle_knowledge_map_layout(
+    state: Arc<AppState>,
+    path: &str,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    let Some(map_id) = parse_layout_path(path) else {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "Expected /knowledge-maps/{id}/layout",
+            "INVALID_PATH",
+        );
+    };
+
+    let claims = require_user(&state, auth_header.as_deref()).ok();
+    let viewer = claims.as_ref().map(|claims| claims.human_id.clone());
+    let input = KnowledgeMapLayoutInput {
+        map_id: map_id.to_string(),
+        skip_mastery: viewer.is_none(),
+        human_id: viewer.clone(),
+    };
+
+    let config = match get_content_store_config(&state) {
+        Ok(config) => config,
+        Err(e) => {
+            warn!(error = ?e, "Content zome not available");
+            return error_response(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Content zome not available",
+                "CONDUCTOR_UNAVAILABLE",
+            );
+        }
+    };
+
+    // Keyed by viewer, since the mastery overlay is personal
+    let cache_key = state
+        .cache_rules
+        .cache_key(&config.dna_hash, &config.zome_name, LAYOUT_FN, &input)
+        .to_storage_key();
+
+    if let Some(entry) = state.cache.get(&cache_key) {
+        debug!(map_id, "Knowledge map layout cache hit");
+        return layout_response(&entry.data, viewer.as_deref());
+    }
+
+    match call_content_store_for(&state, LAYOUT_FN, &input, claims.as_ref()).await {
+        Ok(Some(data)) if !data.is_null() => {
+            let body = serde_json::to_vec(&data).unwrap_or_default();
+            let ttl = state
+                .cache_rules
+                .get_rule(&config.dna_hash, LAYOUT_FN)
+                .map(|rule| rule.ttl())
+                .unwrap_or(state.cache.config().user_ttl);
+            let refs = state
+                .cache_rules
+                .cache_refs(&config.dna_hash, LAYOUT_FN, &input);
+            state
+                .cache
+                .set_with_refs(&cache_key, body.clone(), "application/json", ttl, refs);
+            layout_response(&body, viewer.as_deref())
+        }
+        Ok(_) => error_response(
+            StatusCode::NOT_FOUND,
+            "Knowledge map not found",
+            "NOT_FOUND",
+        ),
+        Err(e) => {
+            warn!(map_id, error = ?e, "Knowledge map layout failed");
+            // The last layout beats an error while the conductor is away
+            match state.cache.get_stale(&cache_key) {
+                Some(entry) => layout_response(&entry.data, viewer.as_deref()),
+                None => match e {
+                    DoorwayError::Overloaded(msg) => overloaded_response(&msg),
+                    _ => error_response(StatusCode::BAD_GATEWAY, "Layout failed", "LAYOUT_FAILED"),
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_layout_path() {
+        assert_eq!(
+            parse_layout_path("/knowledge-maps/km-1/layout"),
+            Some("km-1")
+        );
+        assert_eq!(parse_layout_path("/knowledge-maps//layout"), None);
+        assert_eq!(parse_layout_path("/knowledge-maps/km-1"), None);
+        assert_eq!(parse_layout_path("/knowledge-maps/a/b/layout"), None);
+    }
+
+    #[test]
+    fn test_can_view_by_visibility() {
+        let public = json!({ "visibility": "public", "owner_id": "alice" });
+        assert!(can_view(&public, None));
+
+        let shared = json!({
+            "visibility": "shared",
+            "owner_id": "alice",
+            "shared_with_json": "[\"bob\"]"
+        });
+        assert!(can_view(&shared, Some("alice")));
+        assert!(can_view(&shared, Some("bob")));
+        assert!(!can_view(&shared, Some("carol")));
+        assert!(!can_view(&shared, None));
+    }
+
+    #[test]
+    fn test_layout_response_hides_private_maps() {
+        let body = serde_json::to_vec(&json!({
+            "knowledge_map": { "visibility": "private", "owner_id": "alice" },
+            "nodes": []
+        }))
+        .unwrap();
+        assert_eq!(
+            layout_response(&body, Some("bob")).status(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            layout_response(&body, Some("alice")).status(),
+            StatusCode::OK
+        );
+    }
+}
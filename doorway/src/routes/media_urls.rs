@@ -0,0 +1,328 @@
+//! Signed Media URL Routes
+//!
+//! Issues [signed media URLs](crate::services::media_urls) and enforces them
+//! on the blob routes. Without `MEDIA_URL_SECRET` every blob is served as
+//! before and the URL endpoint hands out plain `/store/{hash}` URLs, so
+//! clients can always ask for one.
+//!
+//! ## Routes
+//!
+//! - `GET /api/v1/media/{address}/url` - Signed URL for a blob, for a learner allowed to read
+//!   content referencing it: `{url, expires_at?}`
+//! - `GET|HEAD /store/{address}`, `/api/blob/{address}` - Protected blobs need the `exp`,
+//!   `agent` and `sig` of a signed URL; they are answered with `Cache-Control: private`
+//!
+//! A learner is issued a URL when they may read some content referencing the
+//! blob: it is theirs, or their reach covers it (as
+//! [`can_serve_at_reach`](crate::cache::can_serve_at_reach) decides) and,
+//! behind a premium gate, they hold a current access grant. Commons content
+//! whose license forbids redistribution is readable by any signed-in
+//! learner; its media is only kept from being linked openly.
+
+use bytes::Bytes;
+use chrono::{TimeZone, Utc};
+use http_body_util::Full;
+use hyper::body::Incoming;
+use hyper::header::{HeaderValue, CACHE_CONTROL};
+use hyper::{Request, Response, StatusCode};
+use serde::Serialize;
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+use super::api::{error_response, json_response};
+use super::auth_helpers::require_user;
+use super::blob::{self, parse_content_address};
+use super::content_body::{document_reach, is_public_reach};
+use super::zome_helpers::call_content_store_for;
+use crate::auth::Claims;
+use crate::cache::{can_serve_at_reach, RequesterContext};
+use crate::projection::{ProjectedDocument, ProjectionQuery};
+use crate::server::AppState;
+use crate::services::media_urls::{is_restricted, MediaUrlError};
+use crate::types::Result;
+
+/// Most referencing documents considered for one blob
+const MAX_REFERENCING_DOCS: i64 = 50;
+
+/// Response of `GET /api/v1/media/{address}/url`
+#[derive(Debug, Serialize)]
+struct MediaUrlResponse {
+    url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expires_at: Option<String>,
+}
+
+/// Extract the address from `/api/v1/media/{address}/url`
+pub fn parse_media_url_path(path: &str) -> Option<&str> {
+    path.strip_prefix("/api/v1/media/")?
+        .strip_suffix("/url")
+        .filter(|addr| !addr.is_empty() && !addr.contains('/'))
+}
+
+/// Projected documents referencing a blob
+///
+/// `None` when they can't be read; callers treat the blob as protected.
+async fn referencing_docs(state: &AppState, hash: &str) -> Option<Vec<ProjectedDocument>> {
+    let Some(ref projection) = state.projection else {
+        return Some(Vec::new());
+    };
+    let query = ProjectionQuery {
+        filter: Some(bson::doc! { "blob_hash": hash }),
+        limit: Some(MAX_REFERENCING_DOCS),
+        ..Default::default()
+    };
+    match projection.query(query).await {
+        Ok(docs) => Some(docs),
+        Err(e) => {
+            warn!(hash = %hash, error = %e, "Failed to look up content referencing blob");
+            None
+        }
+    }
+}
+
+/// Whether a blob is only served through signed URLs
+async fn is_protected(state: &AppState, hash: &str) -> bool {
+    let Some(ref signer) = state.media_urls else {
+        return false;
+    };
+    if let Some(protected) = signer.cached_protection(hash) {
+        return protected;
+    }
+    match referencing_docs(state, hash).await {
+        Some(docs) => {
+            let protected = docs.iter().any(is_restricted);
+            signer.remember_protection(hash, protected);
+            protected
+        }
+        None => true,
+    }
+}
+
+/// Must match ResourceAccessInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct ResourceAccessInput<'a> {
+    resource_id: &'a str,
+    learner_agent_id: &'a str,
+}
+
+/// Whether a signed-in learner's reach covers a referencing document, as
+/// the cache serves it: private content only to its author, `invited` and
+/// the geographic levels to any signed-in learner. A document without a
+/// reach counts as private.
+fn reach_allows(doc: &ProjectedDocument, agent: &str) -> bool {
+    match document_reach(doc).unwrap_or("private") {
+        reach if !is_public_reach(reach) => {
+            let requester = RequesterContext {
+                agent_id: agent.to_string(),
+                location: None,
+                authenticated: true,
+            };
+            can_serve_at_reach(reach, &requester, &doc.author)
+        }
+        _ => true,
+    }
+}
+
+/// Whether a signed-in learner may read a referencing document: its author
+/// always; anyone else when their reach covers it and, behind a premium
+/// gate, they hold a current grant
+async fn readable_by(state: &AppState, doc: &ProjectedDocument, reader: &Claims) -> Result<bool> {
+    let agent = reader.agent_pub_key.as_str();
+    if doc.author == agent {
+        return Ok(true);
+    }
+    if !reach_allows(doc, agent) {
+        return Ok(false);
+    }
+    let input = ResourceAccessInput {
+        resource_id: &doc.doc_id,
+        learner_agent_id: agent,
+    };
+    let access =
+        call_content_store_for(state, "check_resource_access", &input, Some(reader)).await?;
+    Ok(access.and_then(|access| access.as_bool()).unwrap_or(false))
+}
+
+/// Handle GET|HEAD /store/{address}, enforcing signed URLs on protected
+/// blobs
+///
+/// `/api/blob/{address}` is rewritten to this path (query included) before
+/// it gets here.
+pub async fn handle_guarded_blob(
+    req: Request<Incoming>,
+    state: Arc<AppState>,
+) -> Response<Full<Bytes>> {
+    let Some(signer) = state.media_urls.clone() else {
+        return serve_blob(req, &state).await;
+    };
+    let raw_address = req.uri().path().strip_prefix("/store/").unwrap_or("");
+    let Ok(hash) = parse_content_address(raw_address) else {
+        return serve_blob(req, &state).await;
+    };
+    if !is_protected(&state, &hash).await {
+        return serve_blob(req, &state).await;
+    }
+
+    let now = Utc::now().timestamp();
+    match signer.verify(&hash, req.uri().query(), now) {
+        Ok(verified) => {
+            debug!(hash = %hash, agent = %verified.agent, "Signed media download");
+            let expires_in = verified.expires_at - now;
+            let mut response = serve_blob(req, &state).await;
+            if let Ok(value) = HeaderValue::from_str(&format!("private, max-age={expires_in}")) {
+                response.headers_mut().insert(CACHE_CONTROL, value);
+            }
+            response
+        }
+        Err(e) => {
+            let code = match e {
+                MediaUrlError::Expired => "SIGNED_URL_EXPIRED",
+                MediaUrlError::InvalidSignature => "INVALID_SIGNATURE",
+                MediaUrlError::Missing | MediaUrlError::Config(_) => "SIGNED_URL_REQUIRED",
+            };
+            error_response(StatusCode::FORBIDDEN, &e.to_string(), code)
+        }
+    }
+}
+
+async fn serve_blob(req: Request<Incoming>, state: &AppState) -> Response<Full<Bytes>> {
+    match blob::handle_blob_request_with_storage_proxy(
+        req,
+        Arc::clone(&state.cache),
+        state.args.storage_url.clone(),
+    )
+    .await
+    {
+        Ok(response) => response,
+        Err(err) => blob::error_response(err),
+    }
+}
+
+/// Whether a torrent may be handed out for a blob: protected blobs have no
+/// public web seed
+pub async fn torrent_allowed(state: &AppState, raw_address: &str) -> bool {
+    match parse_content_address(raw_address) {
+        Ok(hash) => !is_protected(state, &hash).await,
+        Err(_) => true,
+    }
+}
+
+/// Handle GET /api/v1/media/{address}/url
+pub async fn handle_media_url(
+    state: Arc<AppState>,
+    raw_address: &str,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    let claims = match require_user(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+    let hash = match parse_content_address(raw_address) {
+        Ok(hash) => hash,
+        Err(e) => {
+            return error_response(StatusCode::BAD_REQUEST, &e.to_string(), "INVALID_ADDRESS")
+        }
+    };
+    let Some(ref signer) = state.media_urls else {
+        let response = MediaUrlResponse {
+            url: format!("/store/{hash}"),
+            expires_at: None,
+        };
+        return json_response(serde_json::to_vec(&response).unwrap_or_default());
+    };
+
+    let Some(docs) = referencing_docs(&state, &hash).await else {
+        return error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Content lookup unavailable",
+            "LOOKUP_FAILED",
+        );
+    };
+    let mut readable = docs.is_empty();
+    for doc in &docs {
+        match readable_by(&state, doc, &claims).await {
+            Ok(true) => {
+                readable = true;
+                break;
+            }
+            Ok(false) => {}
+            Err(e) => {
+                warn!(hash = %hash, content_id = %doc.doc_id, error = ?e, "Access check failed");
+                return error_response(
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    "Access check unavailable",
+                    "LOOKUP_FAILED",
+                );
+            }
+        }
+    }
+    if !readable {
+        return error_response(
+            StatusCode::FORBIDDEN,
+            "No readable content references this media",
+            "FORBIDDEN",
+        );
+    }
+
+    let signed = signer.sign(&hash, &claims.agent_pub_key, Utc::now().timestamp());
+    let response = MediaUrlResponse {
+        url: format!("/store/{hash}?{}", signed.query),
+        expires_at: Utc
+            .timestamp_opt(signed.expires_at, 0)
+            .single()
+            .map(|t| t.to_rfc3339()),
+    };
+    json_response(serde_json::to_vec(&response).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_media_url_path() {
+        assert_eq!(
+            parse_media_url_path("/api/v1/media/sha256-abc/url"),
+            Some("sha256-abc")
+        );
+        assert_eq!(parse_media_url_path("/api/v1/media//url"), None);
+        assert_eq!(parse_media_url_path("/api/v1/media/a/b/url"), None);
+        assert_eq!(parse_media_url_path("/api/v1/media/sha256-abc"), None);
+    }
+
+    #[test]
+    fn test_reach_allows() {
+        let doc = |reach: Option<&str>, data| ProjectedDocument {
+            author: "uhCAk-author".to_string(),
+            reach: reach.map(String::from),
+            data,
+            ..Default::default()
+        };
+        assert!(reach_allows(
+            &doc(Some("commons"), json!({})),
+            "uhCAk-learner"
+        ));
+        assert!(reach_allows(
+            &doc(Some("municipal"), json!({})),
+            "uhCAk-learner"
+        ));
+        assert!(!reach_allows(
+            &doc(Some("private"), json!({})),
+            "uhCAk-learner"
+        ));
+        assert!(reach_allows(
+            &doc(Some("private"), json!({})),
+            "uhCAk-author"
+        ));
+        // The record's own reach counts when the projection has none
+        assert!(!reach_allows(
+            &doc(None, json!({ "reach": "private" })),
+            "uhCAk-learner"
+        ));
+        assert!(!reach_allows(
+            &doc(Some("unheard-of"), json!({})),
+            "uhCAk-learner"
+        ));
+    }
+}
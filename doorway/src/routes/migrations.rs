@@ -0,0 +1,181 @@
+//! Schema Migration Status
+//!
+//! Admin view of how far the DHT has moved towards the next content schema
+//! version. The content_store zome sums the progress agents record during
+//! migration and healing passes per entry type, next to the planned field
+//! mappings (`providers::schema_v3_plans`).
+//!
+//! ## Routes
+//!
+//! - `GET /admin/migrations` - Per-entry-type healing/migration status
+
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::{Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::warn;
+
+use super::api::{error_response, json_response};
+use super::auth_helpers::require_user;
+use super::zome_helpers::call_content_store_for;
+use crate::auth::PermissionLevel;
+use crate::server::AppState;
+
+/// Status of one entry type, as returned by `get_migration_status`
+/// Must match EntryTypeMigrationStatus in holochain/dna/elohim/zomes/content_store/src/migration.rs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntryTypeMigrationStatus {
+    pub entry_type: String,
+    pub current_version: u32,
+    pub target_version: u32,
+    /// Planned field mappings (hc_rna MigrationSpec)
+    pub plan: Option<Value>,
+    pub scanned: u32,
+    pub at_target: u32,
+    pub migrated: u32,
+    pub degraded: u32,
+    pub failed: u32,
+    pub reporters: u32,
+    pub last_recorded_at: Option<String>,
+}
+
+impl EntryTypeMigrationStatus {
+    /// Scanned entries now at the target version
+    pub fn done(&self) -> u32 {
+        self.at_target.saturating_add(self.migrated)
+    }
+
+    /// Share of scanned entries at the target version, 0-100
+    pub fn percent_done(&self) -> f64 {
+        if self.scanned == 0 {
+            return 0.0;
+        }
+        (self.done() as f64 * 100.0 / self.scanned as f64).min(100.0)
+    }
+}
+
+/// Response of GET /admin/migrations
+#[derive(Debug, Serialize)]
+struct MigrationsResponse {
+    entry_types: Vec<EntryTypeView>,
+    /// Every entry type has been scanned and fully moved
+    complete: bool,
+    degraded: u32,
+    failed: u32,
+}
+
+#[derive(Debug, Serialize)]
+struct EntryTypeView {
+    #[serde(flatten)]
+    status: EntryTypeMigrationStatus,
+    percent_done: f64,
+}
+
+fn summarize(statuses: Vec<EntryTypeMigrationStatus>) -> MigrationsResponse {
+    let complete = !statuses.is_empty()
+        && statuses
+            .iter()
+            .all(|s| s.scanned > 0 && s.done() >= s.scanned);
+    let degraded = statuses.iter().map(|s| s.degraded).sum();
+    let failed = statuses.iter().map(|s| s.failed).sum();
+    let entry_types = statuses
+        .into_iter()
+        .map(|status| EntryTypeView {
+            percent_done: status.percent_done(),
+            status,
+        })
+        .collect();
+
+    MigrationsResponse {
+        entry_types,
+        complete,
+        degraded,
+        failed,
+    }
+}
+
+/// Handle GET /admin/migrations
+pub async fn handle_migration_status(
+    state: Arc<AppState>,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    let claims = match require_user(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+    if claims.permission_level < PermissionLevel::Admin {
+        return error_response(
+            StatusCode::FORBIDDEN,
+            "Admin permission required",
+            "FORBIDDEN",
+        );
+    }
+
+    let statuses: Vec<EntryTypeMigrationStatus> =
+        match call_content_store_for(&state, "get_migration_status", &(), Some(&claims)).await {
+            Ok(Some(value)) => serde_json::from_value(value).unwrap_or_else(|e| {
+                warn!(error = %e, "Unexpected migration status shape");
+                Vec::new()
+            }),
+            Ok(None) => Vec::new(),
+            Err(e) => {
+                warn!(error = ?e, "Failed to load migration status");
+                return error_response(StatusCode::BAD_GATEWAY, "Lookup failed", "ZOME_ERROR");
+            }
+        };
+
+    json_response(serde_json::to_vec(&summarize(statuses)).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(
+        entry_type: &str,
+        scanned: u32,
+        at_target: u32,
+        migrated: u32,
+    ) -> EntryTypeMigrationStatus {
+        EntryTypeMigrationStatus {
+            entry_type: entry_type.to_string(),
+            current_version: 2,
+            target_version: 3,
+            plan: None,
+            scanned,
+            at_target,
+            migrated,
+            degraded: 1,
+            failed: 0,
+            reporters: 1,
+            last_recorded_at: None,
+        }
+    }
+
+    #[test]
+    fn test_percent_done() {
+        assert_eq!(status("content", 0, 0, 0).percent_done(), 0.0);
+        assert_eq!(status("content", 200, 50, 100).percent_done(), 75.0);
+    }
+
+    #[test]
+    fn test_summarize() {
+        let summary = summarize(vec![
+            status("content", 10, 4, 6),
+            status("path_step", 5, 0, 2),
+        ]);
+        assert!(!summary.complete);
+        assert_eq!(summary.degraded, 2);
+
+        let summary = summarize(vec![status("content", 10, 4, 6)]);
+        assert!(summary.complete);
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["entry_types"][0]["entry_type"], "content");
+        assert_eq!(json["entry_types"][0]["percent_done"], 100.0);
+
+        assert!(!summarize(Vec::new()).complete);
+        assert!(!summarize(vec![status("content", 0, 0, 0)]).complete);
+    }
+}
@@ -3,23 +3,77 @@
 pub mod admin;
 pub mod admin_conductors;
 pub mod admin_users;
+pub mod analytics;
+pub mod annotations;
 pub mod api;
 pub mod apps;
+pub mod assessment_items;
+pub mod auth_helpers;
 pub mod auth_routes;
+pub mod badges;
 pub mod blob;
+pub mod bookmarks;
+pub mod cache_snapshot;
+pub mod cache_stats;
+pub mod captions;
+pub mod content;
+pub mod content_access;
+pub mod content_body;
+pub mod content_health;
 pub mod dashboard_ws;
 pub mod db;
 pub mod debug_stream;
+pub mod discovery;
+pub mod elohim;
+pub mod embed;
 pub mod federation;
+pub mod feeds;
+pub mod fields;
+pub mod governance;
+pub mod graph;
+pub mod guest;
 pub mod health;
 pub mod identity;
+pub mod identity_links;
 pub mod import;
 pub mod import_ws;
+pub mod insurance_claims;
+pub mod invitations;
+pub mod knowledge_maps;
+pub mod media_urls;
+pub mod migrations;
+pub mod moderation;
+pub mod notes_export;
+pub mod notifications;
+pub mod operators;
+pub mod pagination;
+pub mod passkeys;
+pub mod path_export;
+pub mod presence;
+pub mod preview;
+pub mod query_advisor;
+pub mod reciprocal;
+pub mod recommendations;
+pub mod recovery;
+pub mod reflections;
+pub mod region_hint;
+pub mod retention;
 pub mod seed;
+pub mod semantic;
+pub mod sessions;
+pub mod signal_journal;
+pub mod sitemap;
+pub mod solvency;
 pub mod status;
 pub mod stream;
 pub mod threshold;
+pub mod token_settlement;
+pub mod translations;
+pub mod tutor;
+pub mod verify;
+pub mod vouchers;
 pub mod zome_helpers;
+pub mod zome_policy;
 
 pub use admin::{
     handle_admin_pipeline, handle_capabilities, handle_cluster_metrics, handle_custodians,
@@ -43,26 +97,91 @@ pub use admin_users::{
     QuotaStatus,
     UsageTracker,
 };
+pub use analytics::handle_analytics_request;
+pub use annotations::{
+    handle_create_annotation, handle_delete_annotation, handle_get_annotation,
+    handle_list_annotations, handle_update_annotation,
+};
 pub use api::handle_api_request;
 pub use apps::handle_app_request;
+pub use assessment_items::{handle_pending_assessment_items, handle_review_assessment_item};
 pub use auth_routes::handle_auth_request;
+pub use badges::handle_badge_export;
 pub use blob::{
     error_response as blob_error_response, handle_blob_request, handle_blob_request_with_fallback,
     handle_blob_request_with_storage_proxy, BlobContext, BlobError,
 };
+pub use bookmarks::handle_bookmarks;
+pub use cache_snapshot::{handle_cache_dump, handle_cache_load};
+pub use cache_stats::handle_cache_stats;
+pub use captions::handle_caption_upload;
+pub use content::handle_content_query;
+pub use content_access::{
+    handle_content_access, handle_content_access_detail, handle_mark_content_access,
+};
+pub use content_body::handle_content_body;
+pub use content_health::handle_content_health;
 pub use dashboard_ws::handle_dashboard_ws;
 pub use db::handle_db_request;
 pub use debug_stream::{handle_debug_stream, DebugEvent, DebugHub};
+pub use discovery::handle_discovery;
+pub use elohim::{handle_dispatch_elohim_task, handle_elohim_task_status};
+pub use embed::{handle_embed_data, handle_embed_player};
 pub use federation::{
     handle_admin_add_federation_peer, handle_admin_federation_peers,
     handle_admin_refresh_federation_peers, handle_admin_remove_federation_peer,
     handle_doorway_keys, handle_federation_doorways, handle_federation_p2p_peers,
 };
+pub use feeds::handle_feed_request;
+pub use governance::handle_governance_settings;
+pub use graph::handle_graph_request;
+pub use guest::handle_guest_session;
 pub use health::{health_check, readiness_check, version_info};
 pub use identity::{handle_did_document, handle_did_endpoint};
 pub use import::{handle_import_request, match_import_route};
 pub use import_ws::handle_import_progress_ws;
+pub use insurance_claims::{handle_claim_action, handle_claims_by_status, handle_get_claim};
+pub use invitations::{
+    handle_create_invitation, handle_list_invitations, handle_revoke_invitation,
+};
+pub use knowledge_maps::handle_knowledge_map_layout;
+pub use media_urls::{handle_guarded_blob, handle_media_url};
+pub use migrations::handle_migration_status;
+pub use moderation::{
+    handle_moderated_write, handle_moderation_queue, handle_report, handle_review_moderation_item,
+};
+pub use notes_export::handle_notes_export;
+pub use notifications::handle_notifications;
+pub use operators::{handle_create_operator, handle_get_operator, handle_list_operators};
+pub use path_export::handle_path_export;
+pub use presence::{handle_presence, handle_presence_count};
+pub use preview::handle_content_preview;
+pub use query_advisor::handle_query_advisor;
+pub use reciprocal::{handle_inbound_call, handle_peer_call, handle_reciprocal_peers};
+pub use recommendations::handle_recommendations;
+pub use recovery::handle_recovery_request;
+pub use reflections::handle_reflections;
+pub use region_hint::handle_region_hint;
+pub use retention::{handle_retention_audit, handle_retention_policies, handle_retention_run};
 pub use seed::{handle_check_blob, handle_seed_blob, BlobUploadResponse};
+pub use semantic::{
+    handle_relationship_suggestions, handle_review_suggestion, handle_semantic_related,
+};
+pub use sessions::{
+    handle_calendar_feed, handle_cancel_session, handle_my_sessions, handle_schedule_session,
+};
+pub use signal_journal::{handle_signal_journal, handle_signal_replay};
+pub use sitemap::handle_sitemap;
+pub use solvency::handle_solvency;
 pub use status::status_check;
 pub use stream::handle_stream_request;
 pub use threshold::handle_threshold_request;
+pub use token_settlement::handle_token_settlement;
+pub use translations::{
+    handle_add_translation, handle_list_translations, handle_localized_content,
+    handle_pending_translations, handle_publish_translation,
+};
+pub use tutor::handle_tutor_chat;
+pub use verify::handle_verify_attestation;
+pub use vouchers::{handle_gate_voucher, handle_voucher_report};
+pub use zome_policy::{handle_get_zome_policy, handle_put_zome_policy};
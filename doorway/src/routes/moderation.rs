@@ -0,0 +1,929 @@
+//! Moderation API
+//!
+//! Content and discussion writes go through the
+//! [moderation filter](crate::services::moderation) before reaching the DHT.
+//! Writes it flags are quarantined for a steward instead of written. Users
+//! can report published content, and content whose attestation is revoked
+//! is queued as well.
+//!
+//! ## Routes
+//!
+//! - `POST /content` - Create content (`content_store::create_content` input)
+//! - `POST /discussions` - Start a discussion or comment thread (`create_discussion` input)
+//! - `POST /report` - Report published content (`{content_id, reason, details?}`)
+//! - `GET /steward/moderation-queue?status=&kind=&limit=&cursor=` - Queue items (default `pending`), oldest first
+//! - `POST /steward/moderation-queue/{id}/approve` - Make a quarantined write, or keep reported content
+//! - `POST /steward/moderation-queue/{id}/hide` - Drop a quarantined write, or move content to `private` reach
+//! - `POST /steward/moderation-queue/{id}/request-changes` - Send it back to the author
+//!
+//! Action bodies are optional: `{"note": "...", "steward_credential_id": "..."}`.
+//! The note is passed on to the author, who is notified of every decision
+//! except keeping reported content. Hiding published content calls
+//! `change_content_reach`, which needs the doorway agent to be the author or
+//! hold the given steward credential.
+//!
+//! Writes and reports need a user token; writes answer `201` once written or
+//! `202` with the queue item id when held for review. Reviewing needs a
+//! steward or admin token.
+
+use bson::oid::ObjectId;
+use bytes::Bytes;
+use chrono::Utc;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::Incoming;
+use hyper::{Request, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use super::api::{error_response, json_response};
+use super::auth_helpers::{require_steward, require_user};
+use super::notifications::notify;
+use super::pagination::{page_response, Page, PageRequest};
+use super::zome_helpers::call_content_store_for;
+use crate::db::schemas::{ModerationItemDoc, ModerationKind, ModerationStatus};
+use crate::db::MongoCollection;
+use crate::server::AppState;
+use crate::services::moderation::ContentFlag;
+
+/// Largest write body accepted
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// Largest report or review body accepted
+const MAX_NOTE_BYTES: usize = 16 * 1024;
+
+/// Longest reporter comment or steward note kept, in characters
+const MAX_NOTE_CHARS: usize = 2000;
+
+/// Reasons a user can report content for
+pub const REPORT_REASONS: &[&str] = &[
+    "spam",
+    "harassment",
+    "hate",
+    "violence",
+    "sexual",
+    "self_harm",
+    "misinformation",
+    "copyright",
+    "other",
+];
+
+/// Reach that hides content from everyone but its author
+const HIDDEN_REACH: &str = "private";
+
+/// Default queue page size
+const DEFAULT_LIMIT: usize = 50;
+
+/// Largest queue `limit` accepted
+const MAX_LIMIT: usize = 200;
+
+/// Moderated zome functions and the string fields their input needs
+const MODERATED_FNS: &[(&str, &[&str])] = &[
+    ("create_content", &["id", "content_type", "title"]),
+    ("create_discussion", &["entity_type", "entity_id", "title"]),
+];
+
+/// Zome function behind a write route
+pub fn submission_fn(path: &str) -> Option<&'static str> {
+    match path {
+        "/content" => Some("create_content"),
+        "/discussions" => Some("create_discussion"),
+        _ => None,
+    }
+}
+
+/// First required field missing from a write's input
+fn missing_field(zome_fn: &str, payload: &Value) -> Option<&'static str> {
+    let (_, fields) = MODERATED_FNS.iter().find(|(name, _)| *name == zome_fn)?;
+    fields
+        .iter()
+        .find(|field| {
+            !payload
+                .get(**field)
+                .and_then(|v| v.as_str())
+                .is_some_and(|v| !v.trim().is_empty())
+        })
+        .copied()
+}
+
+/// Steward decision on a queue item
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ModerationAction {
+    Approve,
+    Hide,
+    RequestChanges,
+}
+
+impl ModerationAction {
+    fn status(self) -> ModerationStatus {
+        match self {
+            Self::Approve => ModerationStatus::Approved,
+            Self::Hide => ModerationStatus::Hidden,
+            Self::RequestChanges => ModerationStatus::ChangesRequested,
+        }
+    }
+}
+
+/// Parse `/steward/moderation-queue/{id}/{approve|hide|request-changes}`
+pub fn parse_moderation_action_path(path: &str) -> Option<(&str, ModerationAction)> {
+    let rest = path.strip_prefix("/steward/moderation-queue/")?;
+    let (id, action) = rest.split_once('/')?;
+    let action = match action {
+        "approve" => ModerationAction::Approve,
+        "hide" => ModerationAction::Hide,
+        "request-changes" => ModerationAction::RequestChanges,
+        _ => return None,
+    };
+    (!id.is_empty()).then_some((id, action))
+}
+
+/// Optional body of a review action
+#[derive(Debug, Default, Deserialize)]
+struct ReviewBody {
+    note: Option<String>,
+    steward_credential_id: Option<String>,
+}
+
+/// Body of `POST /report`
+#[derive(Debug, Deserialize)]
+struct ReportBody {
+    content_id: String,
+    reason: String,
+    details: Option<String>,
+}
+
+/// Must match QueryByIdInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct QueryByIdInput<'a> {
+    id: &'a str,
+}
+
+/// Must match ChangeContentReachInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct ChangeContentReachInput {
+    content_id: String,
+    new_reach: String,
+    justification: String,
+    steward_credential_id: Option<String>,
+}
+
+/// Trim a free-text note to the kept length, dropping it when blank
+fn clean_note(note: Option<String>) -> Option<String> {
+    note.map(|n| n.trim().chars().take(MAX_NOTE_CHARS).collect::<String>())
+        .filter(|n| !n.is_empty())
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct QueueParams {
+    status: Option<String>,
+    kind: Option<String>,
+}
+
+/// Parsed queue filters
+#[derive(Debug, PartialEq)]
+struct QueueQuery {
+    status: ModerationStatus,
+    kind: Option<ModerationKind>,
+    page: PageRequest,
+}
+
+fn parse_query(query: Option<&str>) -> Result<QueueQuery, String> {
+    let params: QueueParams = serde_urlencoded::from_str(query.unwrap_or(""))
+        .map_err(|e| format!("Invalid query parameters: {e}"))?;
+
+    let status = match params.status.as_deref() {
+        None | Some("") => ModerationStatus::Pending,
+        Some(value) => ModerationStatus::parse(value).ok_or_else(|| {
+            format!(
+                "Invalid status '{value}', expected pending, approved, hidden or changes_requested"
+            )
+        })?,
+    };
+
+    let kind = match params.kind.as_deref() {
+        None | Some("") => None,
+        Some(value) => Some(
+            serde_json::from_value(Value::String(value.to_string())).map_err(|_| {
+                format!(
+                    "Invalid kind '{value}', expected quarantined_write, report or attestation_revoked"
+                )
+            })?,
+        ),
+    };
+
+    Ok(QueueQuery {
+        status,
+        kind,
+        page: PageRequest::from_query(query, DEFAULT_LIMIT, MAX_LIMIT)?,
+    })
+}
+
+/// Quarantined write as served to stewards
+#[derive(Debug, Serialize)]
+struct ModerationItemView<'a> {
+    id: String,
+    kind: ModerationKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    zome_fn: &'a str,
+    #[serde(skip_serializing_if = "Value::is_null")]
+    payload: Value,
+    reasons: &'a [String],
+    details: &'a [String],
+    source: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    submitted_by: Option<&'a str>,
+    report_count: usize,
+    status: ModerationStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reviewed_by: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    review_note: Option<&'a str>,
+}
+
+impl<'a> From<&'a ModerationItemDoc> for ModerationItemView<'a> {
+    fn from(doc: &'a ModerationItemDoc) -> Self {
+        Self {
+            id: doc.id.map(|id| id.to_hex()).unwrap_or_default(),
+            kind: doc.kind,
+            content_id: doc.content_id.as_deref(),
+            zome_fn: &doc.zome_fn,
+            payload: serde_json::from_str(&doc.payload_json).unwrap_or(Value::Null),
+            reasons: &doc.reasons,
+            details: &doc.details,
+            source: &doc.source,
+            submitted_by: doc.submitted_by.as_deref(),
+            report_count: doc.reporters.len(),
+            status: doc.status,
+            reviewed_by: doc.reviewed_by.as_deref(),
+            review_note: doc.review_note.as_deref(),
+        }
+    }
+}
+
+#[allow(clippy::result_large_err)]
+async fn moderation_queue(
+    state: &AppState,
+) -> Result<MongoCollection<ModerationItemDoc>, Response<Full<Bytes>>> {
+    let Some(ref moderation) = state.moderation else {
+        return Err(error_response(
+            StatusCode::NOT_FOUND,
+            "Moderation is not enabled",
+            "NOT_ENABLED",
+        ));
+    };
+    moderation.queue().await.map_err(|e| {
+        warn!(error = %e, "Moderation queue unavailable");
+        error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Moderation queue not available",
+            "DATABASE_UNAVAILABLE",
+        )
+    })
+}
+
+/// Handle POST /content and POST /discussions
+pub async fn handle_moderated_write(
+    req: Request<Incoming>,
+    state: Arc<AppState>,
+    zome_fn: &'static str,
+) -> Response<Full<Bytes>> {
+    let auth_header = req
+        .headers()
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string());
+    let claims = match require_user(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let body = match Limited::new(req.into_body(), MAX_BODY_BYTES)
+        .collect()
+        .await
+    {
+        Ok(collected) => collected.to_bytes(),
+        Err(_) => {
+            return error_response(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                &format!("Request bodies are limited to {MAX_BODY_BYTES} bytes"),
+                "TOO_LARGE",
+            )
+        }
+    };
+    let payload: Value = match serde_json::from_slice(&body) {
+        Ok(payload @ Value::Object(_)) => payload,
+        Ok(_) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                "Expected a JSON object",
+                "INVALID_JSON",
+            )
+        }
+        Err(e) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                &format!("Invalid JSON: {e}"),
+                "INVALID_JSON",
+            )
+        }
+    };
+    if let Some(field) = missing_field(zome_fn, &payload) {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            &format!("Missing required field '{field}'"),
+            "MISSING_FIELD",
+        );
+    }
+
+    if let Some(ref moderation) = state.moderation {
+        let reasons = moderation.check(&payload).await;
+        if !reasons.is_empty() {
+            return match moderation
+                .quarantine(
+                    zome_fn,
+                    &payload,
+                    reasons.clone(),
+                    "api",
+                    Some(claims.human_id.clone()),
+                )
+                .await
+            {
+                Ok(id) => {
+                    info!(
+                        moderation_id = %id,
+                        zome_fn,
+                        reasons = ?reasons,
+                        submitted_by = %claims.human_id,
+                        "Write quarantined for review"
+                    );
+                    Response::builder()
+                        .status(StatusCode::ACCEPTED)
+                        .header("Content-Type", "application/json")
+                        .body(Full::new(Bytes::from(
+                            serde_json::json!({
+                                "status": "pending_review",
+                                "moderation_id": id.to_hex(),
+                            })
+                            .to_string(),
+                        )))
+                        .unwrap()
+                }
+                Err(e) => {
+                    warn!(zome_fn, error = %e, "Failed to quarantine write");
+                    error_response(
+                        StatusCode::SERVICE_UNAVAILABLE,
+                        "Moderation queue not available",
+                        "DATABASE_UNAVAILABLE",
+                    )
+                }
+            };
+        }
+    }
+
+    // Goes through the pool so cached reads are invalidated
+    match call_content_store_for(&state, zome_fn, &payload, Some(&claims)).await {
+        Ok(data) => Response::builder()
+            .status(StatusCode::CREATED)
+            .header("Content-Type", "application/json")
+            .body(Full::new(Bytes::from(
+                serde_json::to_vec(&data.unwrap_or(Value::Null)).unwrap_or_default(),
+            )))
+            .unwrap(),
+        Err(e) => {
+            warn!(zome_fn, error = ?e, "Moderated write failed");
+            error_response(StatusCode::BAD_GATEWAY, "Write failed", "ZOME_ERROR")
+        }
+    }
+}
+
+/// Handle GET /steward/moderation-queue
+pub async fn handle_moderation_queue(
+    state: Arc<AppState>,
+    query: Option<&str>,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    if let Err(response) = require_steward(&state, auth_header.as_deref()) {
+        return response;
+    }
+
+    let query = match parse_query(query) {
+        Ok(query) => query,
+        Err(msg) => return error_response(StatusCode::BAD_REQUEST, &msg, "INVALID_QUERY"),
+    };
+
+    let collection = match moderation_queue(&state).await {
+        Ok(collection) => collection,
+        Err(response) => return response,
+    };
+
+    let mut filter = bson::doc! { "status": bson::to_bson(&query.status).unwrap_or_default() };
+    if let Some(kind) = query.kind {
+        filter.insert("kind", bson::to_bson(&kind).unwrap_or_default());
+    }
+    let mut items = match collection.find_many(filter).await {
+        Ok(items) => items,
+        Err(e) => {
+            warn!(error = %e, "Failed to load moderation queue");
+            return error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to load moderation queue",
+                "DATABASE_ERROR",
+            );
+        }
+    };
+    items.sort_by_key(|item| item.metadata.created_at);
+
+    let page = Page::from_all(items, query.page);
+    page_response(&page.map_ref(ModerationItemView::from))
+}
+
+/// Author's agent key from `get_content_by_id`, `Ok(None)` when the
+/// content doesn't exist
+async fn content_author(
+    state: &AppState,
+    content_id: &str,
+    caller: &Claims,
+) -> Result<Option<String>, String> {
+    let content = call_content_store_for(
+        state,
+        "get_content_by_id",
+        &QueryByIdInput { id: content_id },
+        Some(caller),
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(content.filter(|c| !c.is_null()).map(|c| {
+        c.get("content")
+            .and_then(|content| content.get("author_id"))
+            .and_then(|a| a.as_str())
+            .unwrap_or_default()
+            .to_string()
+    }))
+}
+
+/// Handle POST /report
+pub async fn handle_report(req: Request<Incoming>, state: Arc<AppState>) -> Response<Full<Bytes>> {
+    let auth_header = req
+        .headers()
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string());
+    let claims = match require_user(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let Some(moderation) = state.moderation.clone() else {
+        return error_response(
+            StatusCode::NOT_FOUND,
+            "Moderation is not enabled",
+            "NOT_ENABLED",
+        );
+    };
+
+    let body = match Limited::new(req.into_body(), MAX_NOTE_BYTES)
+        .collect()
+        .await
+    {
+        Ok(collected) => collected.to_bytes(),
+        Err(_) => {
+            return error_response(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                &format!("Reports are limited to {MAX_NOTE_BYTES} bytes"),
+                "TOO_LARGE",
+            )
+        }
+    };
+    let report: ReportBody = match serde_json::from_slice(&body) {
+        Ok(report) => report,
+        Err(e) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                &format!("Invalid report: {e}"),
+                "INVALID_JSON",
+            )
+        }
+    };
+    if !REPORT_REASONS.contains(&report.reason.as_str()) {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            &format!(
+                "Invalid reason '{}', expected one of: {}",
+                report.reason,
+                REPORT_REASONS.join(", ")
+            ),
+            "INVALID_REASON",
+        );
+    }
+
+    let author = match content_author(&state, &report.content_id, &claims).await {
+        Ok(Some(author)) => Some(author).filter(|a| !a.is_empty()),
+        Ok(None) => return error_response(StatusCode::NOT_FOUND, "Content not found", "NOT_FOUND"),
+        Err(e) => {
+            warn!(content_id = %report.content_id, error = %e, "Failed to look up reported content");
+            return error_response(StatusCode::BAD_GATEWAY, "Lookup failed", "ZOME_ERROR");
+        }
+    };
+
+    let flag = ContentFlag {
+        kind: ModerationKind::Report,
+        content_id: report.content_id.clone(),
+        reason: format!("report:{}", report.reason),
+        details: clean_note(report.details),
+        source: "report",
+        reporter: Some(claims.human_id.clone()),
+        author,
+    };
+    match moderation.flag_content(flag).await {
+        Ok(id) => {
+            info!(
+                moderation_id = %id,
+                content_id = %report.content_id,
+                reason = %report.reason,
+                reporter = %claims.human_id,
+                "Content reported"
+            );
+            Response::builder()
+                .status(StatusCode::ACCEPTED)
+                .header("Content-Type", "application/json")
+                .body(Full::new(Bytes::from(
+                    serde_json::json!({ "status": "received" }).to_string(),
+                )))
+                .unwrap()
+        }
+        Err(e) => {
+            warn!(content_id = %report.content_id, error = %e, "Failed to queue report");
+            error_response(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Moderation queue not available",
+                "DATABASE_UNAVAILABLE",
+            )
+        }
+    }
+}
+
+/// What the author is told about a decision, `None` when nothing changes
+/// for them
+fn author_notice(
+    kind: ModerationKind,
+    action: ModerationAction,
+    note: Option<&str>,
+) -> Option<(&'static str, String)> {
+    let (title, message) = match (kind.is_published(), action) {
+        (false, ModerationAction::Approve) => (
+            "Your submission was published",
+            "A steward reviewed your submission and published it.",
+        ),
+        (false, ModerationAction::Hide) => (
+            "Your submission was not published",
+            "A steward reviewed your submission and decided not to publish it.",
+        ),
+        (false, ModerationAction::RequestChanges) => (
+            "Changes requested",
+            "A steward asked for changes before your submission can be published. \
+Please revise it and submit it again.",
+        ),
+        (true, ModerationAction::Approve) => return None,
+        (true, ModerationAction::Hide) => (
+            "Your content was hidden",
+            "After review, a steward moved your content to private reach. Only you can see it now.",
+        ),
+        (true, ModerationAction::RequestChanges) => (
+            "Changes requested",
+            "A steward reviewed your content and asked you to update it.",
+        ),
+    };
+    let message = match note {
+        Some(note) => format!("{message}\n\nSteward note: {note}"),
+        None => message.to_string(),
+    };
+    Some((title, message))
+}
+
+/// Handle POST /steward/moderation-queue/{id}/{approve|hide|request-changes}
+pub async fn handle_review_moderation_item(
+    req: Request<Incoming>,
+    state: Arc<AppState>,
+    item_id: String,
+    action: ModerationAction,
+) -> Response<Full<Bytes>> {
+    let auth_header = req
+        .headers()
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string());
+    let claims = match require_steward(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let Ok(object_id) = ObjectId::parse_str(&item_id) else {
+        return error_response(StatusCode::BAD_REQUEST, "Invalid item id", "INVALID_ID");
+    };
+
+    let body = match Limited::new(req.into_body(), MAX_NOTE_BYTES)
+        .collect()
+        .await
+    {
+        Ok(collected) => collected.to_bytes(),
+        Err(_) => {
+            return error_response(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                &format!("Review bodies are limited to {MAX_NOTE_BYTES} bytes"),
+                "TOO_LARGE",
+            )
+        }
+    };
+    let review: ReviewBody = if body.is_empty() {
+        ReviewBody::default()
+    } else {
+        match serde_json::from_slice(&body) {
+            Ok(review) => review,
+            Err(e) => {
+                return error_response(
+                    StatusCode::BAD_REQUEST,
+                    &format!("Invalid JSON: {e}"),
+                    "INVALID_JSON",
+                )
+            }
+        }
+    };
+    let note = clean_note(review.note);
+
+    let collection = match moderation_queue(&state).await {
+        Ok(collection) => collection,
+        Err(response) => return response,
+    };
+
+    let item = match collection.find_one(bson::doc! { "_id": object_id }).await {
+        Ok(Some(item)) => item,
+        Ok(None) => return error_response(StatusCode::NOT_FOUND, "Item not found", "NOT_FOUND"),
+        Err(e) => {
+            warn!(item_id = %item_id, error = %e, "Failed to load moderation item");
+            return error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to load item",
+                "DATABASE_ERROR",
+            );
+        }
+    };
+    if item.status != ModerationStatus::Pending {
+        return error_response(
+            StatusCode::CONFLICT,
+            "Item has already been reviewed",
+            "ALREADY_REVIEWED",
+        );
+    }
+
+    let mut result = Value::Null;
+    match (item.kind.is_published(), action) {
+        (false, ModerationAction::Approve) => {
+            // Only replay the writes this queue is for
+            if !MODERATED_FNS.iter().any(|(name, _)| *name == item.zome_fn) {
+                return error_response(
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    &format!("'{}' writes can't be approved here", item.zome_fn),
+                    "UNSUPPORTED_WRITE",
+                );
+            }
+            let payload: Value = match serde_json::from_str(&item.payload_json) {
+                Ok(payload) => payload,
+                Err(e) => {
+                    warn!(item_id = %item_id, error = %e, "Quarantined payload is not JSON");
+                    return error_response(
+                        StatusCode::UNPROCESSABLE_ENTITY,
+                        "Quarantined payload is unreadable",
+                        "INVALID_PAYLOAD",
+                    );
+                }
+            };
+            match call_content_store_for(&state, &item.zome_fn, &payload, Some(&claims)).await {
+                Ok(data) => result = data.unwrap_or(Value::Null),
+                Err(e) => {
+                    warn!(item_id = %item_id, zome_fn = %item.zome_fn, error = ?e, "Approved write failed");
+                    return error_response(
+                        StatusCode::BAD_GATEWAY,
+                        "Failed to make the write",
+                        "ZOME_ERROR",
+                    );
+                }
+            }
+        }
+        (true, ModerationAction::Hide) => {
+            let Some(ref content_id) = item.content_id else {
+                return error_response(
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    "Item has no content id",
+                    "INVALID_ITEM",
+                );
+            };
+            let input = ChangeContentReachInput {
+                content_id: content_id.clone(),
+                new_reach: HIDDEN_REACH.to_string(),
+                justification: note.clone().unwrap_or_else(|| {
+                    format!(
+                        "Hidden after moderation review ({})",
+                        item.reasons.join(", ")
+                    )
+                }),
+                steward_credential_id: review.steward_credential_id.clone(),
+            };
+            // Goes through the pool so cached content reads are invalidated
+            match call_content_store_for(&state, "change_content_reach", &input, Some(&claims))
+                .await
+            {
+                Ok(data) => result = data.unwrap_or(Value::Null),
+                Err(e) => {
+                    warn!(item_id = %item_id, content_id = %content_id, error = ?e, "Failed to hide content");
+                    return error_response(
+                        StatusCode::BAD_GATEWAY,
+                        "Failed to hide content (does the steward credential cover it?)",
+                        "ZOME_ERROR",
+                    );
+                }
+            }
+        }
+        // Nothing is written: the quarantined write is dropped, or the
+        // published content stays as it is
+        _ => {}
+    }
+
+    let status = action.status();
+    let mut set = bson::doc! {
+        "status": bson::to_bson(&status).unwrap_or_default(),
+        "reviewed_by": &claims.human_id,
+        "reviewed_at": bson::to_bson(&Utc::now()).unwrap_or_default(),
+        "metadata.updated_at": bson::DateTime::now(),
+    };
+    if let Some(ref note) = note {
+        set.insert("review_note", note);
+    }
+    if let Err(e) = collection
+        .update_one(bson::doc! { "_id": object_id }, bson::doc! { "$set": set })
+        .await
+    {
+        warn!(item_id = %item_id, error = %e, "Failed to record moderation review");
+        return error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to record review",
+            "DATABASE_ERROR",
+        );
+    }
+
+    // Revocation items don't know their author until someone looks
+    let mut author = item.author.clone().filter(|a| !a.is_empty());
+    if author.is_none() && item.kind.is_published() {
+        if let Some(ref content_id) = item.content_id {
+            author = content_author(&state, content_id, &claims)
+                .await
+                .ok()
+                .flatten()
+                .filter(|a| !a.is_empty());
+        }
+    }
+    let mut notified = false;
+    if let (Some(author), Some((title, message))) =
+        (author, author_notice(item.kind, action, note.as_deref()))
+    {
+        match notify(&state, &author, title, &message, item.content_id.as_deref()).await {
+            Ok(()) => notified = true,
+            Err(e) => warn!(item_id = %item_id, error = %e, "Failed to notify author"),
+        }
+    }
+
+    info!(
+        item_id = %item_id,
+        kind = ?item.kind,
+        status = ?status,
+        notified,
+        reviewer = %claims.human_id,
+        "Moderation item reviewed"
+    );
+    json_response(
+        serde_json::to_vec(&serde_json::json!({
+            "id": item_id,
+            "status": status,
+            "author_notified": notified,
+            "result": result,
+        }))
+        .unwrap_or_default(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_submission_fn() {
+        assert_eq!(submission_fn("/content"), Some("create_content"));
+        assert_eq!(submission_fn("/discussions"), Some("create_discussion"));
+        assert_eq!(submission_fn("/content/intro"), None);
+    }
+
+    #[test]
+    fn test_missing_field() {
+        let payload = serde_json::json!({
+            "id": "intro",
+            "content_type": "concept",
+            "title": "  ",
+        });
+        assert_eq!(missing_field("create_content", &payload), Some("title"));
+        assert_eq!(
+            missing_field("create_discussion", &payload),
+            Some("entity_type")
+        );
+
+        let payload = serde_json::json!({
+            "id": "intro",
+            "content_type": "concept",
+            "title": "Intro",
+        });
+        assert_eq!(missing_field("create_content", &payload), None);
+    }
+
+    #[test]
+    fn test_parse_moderation_action_path() {
+        assert_eq!(
+            parse_moderation_action_path("/steward/moderation-queue/abc/approve"),
+            Some(("abc", ModerationAction::Approve))
+        );
+        assert_eq!(
+            parse_moderation_action_path("/steward/moderation-queue/abc/hide"),
+            Some(("abc", ModerationAction::Hide))
+        );
+        assert_eq!(
+            parse_moderation_action_path("/steward/moderation-queue/abc/request-changes"),
+            Some(("abc", ModerationAction::RequestChanges))
+        );
+        assert_eq!(
+            parse_moderation_action_path("/steward/moderation-queue/abc/reject"),
+            None
+        );
+        assert_eq!(
+            parse_moderation_action_path("/steward/moderation-queue"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_query() {
+        let defaults = parse_query(None).unwrap();
+        assert_eq!(defaults.status, ModerationStatus::Pending);
+        assert_eq!(defaults.kind, None);
+        assert_eq!(defaults.page.limit, DEFAULT_LIMIT);
+
+        let query = parse_query(Some(
+            "status=changes_requested&kind=report&limit=999&offset=5",
+        ))
+        .unwrap();
+        assert_eq!(query.status, ModerationStatus::ChangesRequested);
+        assert_eq!(query.kind, Some(ModerationKind::Report));
+        assert_eq!(query.page.limit, MAX_LIMIT);
+        assert_eq!(query.page.offset, 5);
+
+        assert!(parse_query(Some("status=flagged")).is_err());
+        assert!(parse_query(Some("kind=rumour")).is_err());
+    }
+
+    #[test]
+    fn test_clean_note() {
+        assert_eq!(
+            clean_note(Some("  fix the title ".into())),
+            Some("fix the title".into())
+        );
+        assert_eq!(clean_note(Some("   ".into())), None);
+        assert_eq!(clean_note(None), None);
+        let long = "x".repeat(MAX_NOTE_CHARS + 10);
+        assert_eq!(
+            clean_note(Some(long)).map(|n| n.len()),
+            Some(MAX_NOTE_CHARS)
+        );
+    }
+
+    #[test]
+    fn test_author_notice() {
+        // Keeping reported content isn't news to its author
+        assert!(author_notice(ModerationKind::Report, ModerationAction::Approve, None).is_none());
+
+        let (title, message) = author_notice(
+            ModerationKind::QuarantinedWrite,
+            ModerationAction::RequestChanges,
+            Some("Please cite your sources"),
+        )
+        .unwrap();
+        assert_eq!(title, "Changes requested");
+        assert!(message.ends_with("Steward note: Please cite your sources"));
+
+        let (title, _) = author_notice(
+            ModerationKind::AttestationRevoked,
+            ModerationAction::Hide,
+            None,
+        )
+        .unwrap();
+        assert_eq!(title, "Your content was hidden");
+    }
+}
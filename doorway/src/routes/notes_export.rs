@@ -0,0 +1,105 @@
+//! Notes Export API
+//!
+//! Lets the signed-in learner download their step notes, reflections,
+//! bookmarks and annotations as a zipped Markdown vault for Obsidian and
+//! other PKM tools (see [`notes_vault`](crate::services::notes_vault)).
+//!
+//! ## Routes
+//!
+//! - `GET /me/export/notes.zip` - The learner's notes vault
+
+use bytes::Bytes;
+use chrono::Utc;
+use http_body_util::Full;
+use hyper::{Response, StatusCode};
+use serde::Serialize;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use super::api::error_response;
+use super::auth_helpers::require_user;
+use super::zome_helpers::call_content_store_for;
+use crate::server::AppState;
+use crate::services::notes_vault::{vault_zip, NotesExport};
+
+/// Must match NotesExportInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct NotesExportInput {
+    agent_id: String,
+    human_id: String,
+}
+
+/// Handle GET /me/export/notes.zip
+pub async fn handle_notes_export(
+    state: Arc<AppState>,
+    auth_header: Option<&str>,
+) -> Response<Full<Bytes>> {
+    let claims = match require_user(&state, auth_header) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let input = NotesExportInput {
+        agent_id: claims.agent_pub_key.clone(),
+        human_id: claims.human_id.clone(),
+    };
+    let export: NotesExport =
+        match call_content_store_for(&state, "get_notes_export", &input, Some(&claims)).await {
+            Ok(Some(data)) => match serde_json::from_value(data) {
+                Ok(export) => export,
+                Err(e) => {
+                    warn!(error = %e, "Notes export did not match the expected shape");
+                    return error_response(
+                        StatusCode::BAD_GATEWAY,
+                        "Notes export failed",
+                        "ZOME_ERROR",
+                    );
+                }
+            },
+            Ok(None) => {
+                return error_response(StatusCode::NOT_FOUND, "Nothing to export", "NOT_FOUND")
+            }
+            Err(e) => {
+                warn!(error = ?e, "Notes export call failed");
+                return error_response(
+                    StatusCode::BAD_GATEWAY,
+                    "Notes export failed",
+                    "ZOME_ERROR",
+                );
+            }
+        };
+
+    let base = state.args.doorway_url.as_deref().unwrap_or("");
+    match vault_zip(&export, base) {
+        Ok(zip) => {
+            info!(
+                human_id = %claims.human_id,
+                paths = export.paths.len(),
+                items = export.items.len(),
+                "Notes vault exported"
+            );
+            Response::builder()
+                .status(StatusCode::OK)
+                .header("Content-Type", "application/zip")
+                .header(
+                    "Content-Disposition",
+                    format!(
+                        "attachment; filename=\"elohim-notes-{}.zip\"",
+                        Utc::now().format("%Y%m%d")
+                    ),
+                )
+                .header("Cache-Control", "private, no-store")
+                .header("Vary", "Authorization")
+                .body(Full::new(Bytes::from(zip)))
+                .unwrap()
+        }
+        Err(e) => {
+            warn!(error = %e, "Failed to write notes vault");
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Notes export failed",
+                "EXPORT_FAILED",
+            )
+        }
+    }
+}
@@ -0,0 +1,168 @@
+//! Notifications API
+//!
+//! Messages the doorway leaves for humans, such as moderation outcomes for
+//! their content.
+//!
+//! ## Routes
+//!
+//! - `GET /notifications?limit=` - Newest first, for the token's human id and agent key
+//!
+//! Listing marks the returned notifications read.
+
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::{Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::warn;
+
+use super::api::{error_response, json_response};
+use super::auth_helpers::require_user;
+use crate::db::schemas::{NotificationDoc, NOTIFICATION_COLLECTION};
+use crate::server::AppState;
+
+/// Default page size
+const DEFAULT_LIMIT: usize = 20;
+
+/// Largest `limit` accepted
+const MAX_LIMIT: usize = 100;
+
+/// Leave a notification for a human id or agent key
+pub(crate) async fn notify(
+    state: &AppState,
+    recipient: &str,
+    title: &str,
+    message: &str,
+    content_id: Option<&str>,
+) -> Result<(), String> {
+    let Some(ref mongo) = state.mongo else {
+        return Err("Notifications need MongoDB".to_string());
+    };
+    let notification = NotificationDoc {
+        recipient: recipient.to_string(),
+        kind: "moderation".to_string(),
+        title: title.to_string(),
+        message: message.to_string(),
+        content_id: content_id.map(str::to_string),
+        ..Default::default()
+    };
+    mongo
+        .collection::<NotificationDoc>(NOTIFICATION_COLLECTION)
+        .await
+        .map_err(|e| e.to_string())?
+        .insert_one(notification)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct LimitParams {
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct NotificationView<'a> {
+    id: String,
+    kind: &'a str,
+    title: &'a str,
+    message: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content_id: Option<&'a str>,
+    read: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    created_at: Option<String>,
+}
+
+impl<'a> From<&'a NotificationDoc> for NotificationView<'a> {
+    fn from(doc: &'a NotificationDoc) -> Self {
+        Self {
+            id: doc.id.map(|id| id.to_hex()).unwrap_or_default(),
+            kind: &doc.kind,
+            title: &doc.title,
+            message: &doc.message,
+            content_id: doc.content_id.as_deref(),
+            read: doc.read,
+            created_at: doc
+                .metadata
+                .created_at
+                .and_then(|t| t.try_to_rfc3339_string().ok()),
+        }
+    }
+}
+
+/// Handle GET /notifications
+pub async fn handle_notifications(
+    state: Arc<AppState>,
+    query: Option<&str>,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    let claims = match require_user(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let Some(ref mongo) = state.mongo else {
+        return error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Notifications not available",
+            "DATABASE_UNAVAILABLE",
+        );
+    };
+    let collection = match mongo
+        .collection::<NotificationDoc>(NOTIFICATION_COLLECTION)
+        .await
+    {
+        Ok(collection) => collection,
+        Err(e) => {
+            warn!(error = %e, "Notification collection unavailable");
+            return error_response(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Notifications not available",
+                "DATABASE_UNAVAILABLE",
+            );
+        }
+    };
+
+    let params: LimitParams = serde_urlencoded::from_str(query.unwrap_or("")).unwrap_or_default();
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let recipients = vec![claims.human_id.clone(), claims.agent_pub_key.clone()];
+    let mut notifications = match collection
+        .find_many(bson::doc! { "recipient": { "$in": recipients.clone() } })
+        .await
+    {
+        Ok(notifications) => notifications,
+        Err(e) => {
+            warn!(error = %e, "Failed to load notifications");
+            return error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to load notifications",
+                "DATABASE_ERROR",
+            );
+        }
+    };
+    notifications.sort_by(|a, b| b.metadata.created_at.cmp(&a.metadata.created_at));
+    notifications.truncate(limit);
+
+    let unread: Vec<_> = notifications
+        .iter()
+        .filter(|n| !n.read)
+        .filter_map(|n| n.id)
+        .collect();
+    if !unread.is_empty() {
+        if let Err(e) = collection
+            .inner()
+            .update_many(
+                bson::doc! { "_id": { "$in": unread }, "recipient": { "$in": recipients } },
+                bson::doc! { "$set": { "read": true, "metadata.updated_at": bson::DateTime::now() } },
+            )
+            .await
+        {
+            warn!(error = %e, "Failed to mark notifications read");
+        }
+    }
+
+    let body: Vec<NotificationView> = notifications.iter().map(NotificationView::from).collect();
+    json_response(serde_json::to_vec(&body).unwrap_or_default())
+}
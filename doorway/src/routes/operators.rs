@@ -0,0 +1,234 @@
+//! Operator Onboarding Routes
+//!
+//! Admin endpoints provisioning operators (tenants) through
+//! [operator onboarding](crate::services::operator_onboarding).
+//!
+//! ## Routes
+//!
+//! - `POST /admin/operators` - Provision `{operator_id, name, contact_email?, install_happ?,
+//!   bundle_path?}`; returns the operator with its API keys, which are shown only here.
+//!   Posting a partly provisioned operator again retries its failed steps
+//! - `GET /admin/operators?limit=&cursor=` - Operators in the
+//!   [pagination](super::pagination) envelope
+//! - `GET /admin/operators/{operator_id}` - One operator with its step outcomes
+
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::Incoming;
+use hyper::{Request, Response, StatusCode};
+use serde::Serialize;
+use std::sync::Arc;
+use tracing::warn;
+
+use super::api::{error_response, json_response, not_enabled_response};
+use super::auth_helpers::require_admin;
+use super::pagination::{page_response, Page, PageRequest};
+use crate::db::schemas::{OperatorDoc, OperatorStatus, OperatorStep, OPERATOR_COLLECTION};
+use crate::server::AppState;
+use crate::services::operator_onboarding::{
+    provision, IssuedApiKey, OnboardOperatorRequest, OnboardingError,
+};
+
+/// Largest request body accepted
+const MAX_BODY_BYTES: usize = 8 * 1024;
+
+/// Message when MongoDB is not configured
+const NOT_ENABLED: &str = "Operator onboarding requires MongoDB";
+
+/// Default and largest page of operators
+const DEFAULT_LIMIT: usize = 50;
+const MAX_LIMIT: usize = 200;
+
+/// An operator as served to admins
+#[derive(Debug, Serialize)]
+struct OperatorView<'a> {
+    operator_id: &'a str,
+    name: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    contact_email: Option<&'a str>,
+    status: OperatorStatus,
+    cache_namespace: &'a str,
+    nats_stream: &'a str,
+    nats_subjects: &'a [String],
+    collections: &'a [String],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    installed_app_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    conductor_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    agent_pub_key: Option<&'a str>,
+    steps: &'a [OperatorStep],
+    created_at: Option<String>,
+}
+
+impl<'a> From<&'a OperatorDoc> for OperatorView<'a> {
+    fn from(doc: &'a OperatorDoc) -> Self {
+        Self {
+            operator_id: &doc.operator_id,
+            name: &doc.name,
+            contact_email: doc.contact_email.as_deref(),
+            status: doc.status,
+            cache_namespace: &doc.cache_namespace,
+            nats_stream: &doc.nats_stream,
+            nats_subjects: &doc.nats_subjects,
+            collections: &doc.collections,
+            installed_app_id: doc.installed_app_id.as_deref(),
+            conductor_id: doc.conductor_id.as_deref(),
+            agent_pub_key: doc.agent_pub_key.as_deref(),
+            steps: &doc.steps,
+            created_at: doc
+                .metadata
+                .created_at
+                .and_then(|t| t.try_to_rfc3339_string().ok()),
+        }
+    }
+}
+
+/// Response of `POST /admin/operators`
+#[derive(Debug, Serialize)]
+struct OnboardResponse<'a> {
+    #[serde(flatten)]
+    operator: OperatorView<'a>,
+    api_keys: &'a [IssuedApiKey],
+}
+
+fn database_error(e: impl std::fmt::Display) -> Response<Full<Bytes>> {
+    warn!(error = %e, "Failed to read operators");
+    error_response(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Failed to read operators",
+        "DATABASE_ERROR",
+    )
+}
+
+/// Handle POST /admin/operators
+pub async fn handle_create_operator(
+    req: Request<Incoming>,
+    state: Arc<AppState>,
+) -> Response<Full<Bytes>> {
+    let auth_header = req
+        .headers()
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string());
+    if let Err(response) = require_admin(&state, auth_header.as_deref()) {
+        return response;
+    }
+
+    let body = match Limited::new(req.into_body(), MAX_BODY_BYTES)
+        .collect()
+        .await
+    {
+        Ok(collected) => collected.to_bytes(),
+        Err(_) => {
+            return error_response(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                &format!("Requests are limited to {MAX_BODY_BYTES} bytes"),
+                "TOO_LARGE",
+            )
+        }
+    };
+    let request: OnboardOperatorRequest = match serde_json::from_slice(&body) {
+        Ok(request) => request,
+        Err(e) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                &format!("Invalid request: {e}"),
+                "BAD_REQUEST",
+            )
+        }
+    };
+
+    match provision(&state, request).await {
+        Ok(outcome) => {
+            let response = OnboardResponse {
+                operator: OperatorView::from(&outcome.operator),
+                api_keys: &outcome.api_keys,
+            };
+            let mut response = json_response(serde_json::to_vec(&response).unwrap_or_default());
+            *response.status_mut() = StatusCode::CREATED;
+            response
+        }
+        Err(e) => {
+            let (status, code) = match e {
+                OnboardingError::Invalid(_) => (StatusCode::BAD_REQUEST, "BAD_REQUEST"),
+                OnboardingError::Exists(_) => (StatusCode::CONFLICT, "ALREADY_EXISTS"),
+                OnboardingError::Unavailable(_) => (StatusCode::SERVICE_UNAVAILABLE, "NOT_ENABLED"),
+                OnboardingError::Database(_) => {
+                    warn!(error = %e, "Operator provisioning failed");
+                    (StatusCode::INTERNAL_SERVER_ERROR, "DATABASE_ERROR")
+                }
+            };
+            error_response(status, &e.to_string(), code)
+        }
+    }
+}
+
+/// Handle GET /admin/operators
+pub async fn handle_list_operators(
+    state: Arc<AppState>,
+    query: Option<&str>,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    if let Err(response) = require_admin(&state, auth_header.as_deref()) {
+        return response;
+    }
+    let Some(ref mongo) = state.mongo else {
+        return not_enabled_response(StatusCode::SERVICE_UNAVAILABLE, NOT_ENABLED);
+    };
+    let page = match PageRequest::from_query(query, DEFAULT_LIMIT, MAX_LIMIT) {
+        Ok(page) => page,
+        Err(msg) => return error_response(StatusCode::BAD_REQUEST, &msg, "BAD_REQUEST"),
+    };
+
+    let operators = match mongo.collection::<OperatorDoc>(OPERATOR_COLLECTION).await {
+        Ok(collection) => {
+            collection
+                .find_many(bson::doc! { "metadata.is_deleted": false })
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    match operators {
+        Ok(mut operators) => {
+            operators.sort_by(|a, b| a.operator_id.cmp(&b.operator_id));
+            let page = Page::from_all(operators, page);
+            page_response(&page.map_ref(OperatorView::from))
+        }
+        Err(e) => database_error(e),
+    }
+}
+
+/// Handle GET /admin/operators/{operator_id}
+pub async fn handle_get_operator(
+    state: Arc<AppState>,
+    operator_id: &str,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    if let Err(response) = require_admin(&state, auth_header.as_deref()) {
+        return response;
+    }
+    let Some(ref mongo) = state.mongo else {
+        return not_enabled_response(StatusCode::SERVICE_UNAVAILABLE, NOT_ENABLED);
+    };
+
+    let operator = match mongo.collection::<OperatorDoc>(OPERATOR_COLLECTION).await {
+        Ok(collection) => {
+            collection
+                .find_one(bson::doc! { "operator_id": operator_id, "metadata.is_deleted": false })
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    match operator {
+        Ok(Some(operator)) => {
+            json_response(serde_json::to_vec(&OperatorView::from(&operator)).unwrap_or_default())
+        }
+        Ok(None) => error_response(
+            StatusCode::NOT_FOUND,
+            &format!("No operator '{operator_id}'"),
+            "NOT_FOUND",
+        ),
+        Err(e) => database_error(e),
+    }
+}
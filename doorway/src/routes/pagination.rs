@@ -0,0 +1,362 @@
+//! Pagination for listing routes
+//!
+//! Listings page through `?limit=&cursor=` and answer with one envelope:
+//!
+//! ```json
+//! { "items": [...], "next": "<cursor>", "prev": "<cursor>", "total": 120 }
+//! ```
+//!
+//! Cursors are opaque; clients pass back the `next` or `prev` value they were
+//! given. `offset` is still read for clients written before cursors. `next`
+//! is absent on the last page, `prev` on the first, `total` when the source
+//! can't count cheaply.
+//!
+//! [`page_response`] also sets `X-Next-Cursor` / `X-Prev-Cursor`. The HTTP
+//! server runs every response through [`add_link_header`], which turns those
+//! into an RFC 5988 `Link` header relative to the request URL, so a listing
+//! only has to build a [`Page`] to get both.
+
+use base64::prelude::*;
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::header::{HeaderValue, ACCESS_CONTROL_EXPOSE_HEADERS, LINK};
+use hyper::{Response, Uri};
+use serde::{Deserialize, Serialize};
+
+use super::api::json_response;
+
+/// Cursor of the following page
+pub const NEXT_CURSOR_HEADER: &str = "x-next-cursor";
+
+/// Cursor of the preceding page
+pub const PREV_CURSOR_HEADER: &str = "x-prev-cursor";
+
+/// Paging parameters every listing reads
+#[derive(Debug, Default, Deserialize)]
+struct PageParams {
+    limit: Option<usize>,
+    cursor: Option<String>,
+    offset: Option<usize>,
+}
+
+/// Which slice of a listing was asked for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageRequest {
+    pub limit: usize,
+    pub offset: usize,
+}
+
+impl PageRequest {
+    /// Read `limit` and `cursor` (or `offset`) from a query string; other
+    /// parameters are left to the route
+    pub fn from_query(
+        query: Option<&str>,
+        default_limit: usize,
+        max_limit: usize,
+    ) -> Result<Self, String> {
+        let params: PageParams = serde_urlencoded::from_str(query.unwrap_or(""))
+            .map_err(|e| format!("Invalid paging parameters: {e}"))?;
+        let offset = match params.cursor.as_deref().filter(|c| !c.is_empty()) {
+            Some(cursor) => decode_cursor(cursor).ok_or("Invalid cursor")?,
+            None => params.offset.unwrap_or(0),
+        };
+        Ok(Self {
+            limit: params.limit.unwrap_or(default_limit).clamp(1, max_limit),
+            offset,
+        })
+    }
+
+    /// Items to fetch from a source that can't count: one extra tells
+    /// whether another page follows
+    pub fn probe_limit(&self) -> usize {
+        self.limit + 1
+    }
+
+    fn next_cursor(&self) -> String {
+        encode_cursor(self.offset + self.limit)
+    }
+
+    fn prev_cursor(&self) -> Option<String> {
+        (self.offset > 0).then(|| encode_cursor(self.offset.saturating_sub(self.limit)))
+    }
+}
+
+fn encode_cursor(offset: usize) -> String {
+    BASE64_URL_SAFE_NO_PAD.encode(format!("o:{offset}"))
+}
+
+fn decode_cursor(cursor: &str) -> Option<usize> {
+    let decoded = BASE64_URL_SAFE_NO_PAD.decode(cursor).ok()?;
+    std::str::from_utf8(&decoded)
+        .ok()?
+        .strip_prefix("o:")?
+        .parse()
+        .ok()
+}
+
+/// One page of a listing
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prev: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<usize>,
+}
+
+impl<T> Page<T> {
+    /// Page out of items fetched with [`PageRequest::probe_limit`]
+    pub fn from_probe(mut items: Vec<T>, request: PageRequest) -> Self {
+        let has_more = items.len() > request.limit;
+        items.truncate(request.limit);
+        Self::new(items, request, has_more, None)
+    }
+
+    /// Page sliced out of the complete listing
+    pub fn from_all(items: Vec<T>, request: PageRequest) -> Self {
+        let total = items.len();
+        let items: Vec<T> = items
+            .into_iter()
+            .skip(request.offset)
+            .take(request.limit)
+            .collect();
+        let has_more = request.offset + items.len() < total;
+        Self::new(items, request, has_more, Some(total))
+    }
+
+    /// Page of a source that paged itself
+    pub fn new(items: Vec<T>, request: PageRequest, has_more: bool, total: Option<usize>) -> Self {
+        Self {
+            items,
+            next: has_more.then(|| request.next_cursor()),
+            prev: request.prev_cursor(),
+            total,
+        }
+    }
+
+    /// Same page with each item borrowed into a view
+    pub fn map_ref<'a, U>(&'a self, f: impl FnMut(&'a T) -> U) -> Page<U> {
+        Page {
+            items: self.items.iter().map(f).collect(),
+            next: self.next.clone(),
+            prev: self.prev.clone(),
+            total: self.total,
+        }
+    }
+}
+
+/// JSON envelope of a page, with its cursors as headers
+pub fn page_response<T: Serialize>(page: &Page<T>) -> Response<Full<Bytes>> {
+    let mut response = json_response(serde_json::to_vec(page).unwrap_or_default());
+    set_cursor_headers(&mut response, page);
+    response
+}
+
+/// JSON envelope of a page with route-specific fields beside `items`, with
+/// its cursors as headers
+pub fn page_response_with<T: Serialize, E: Serialize>(
+    page: &Page<T>,
+    extra: &E,
+) -> Response<Full<Bytes>> {
+    #[derive(Serialize)]
+    struct Envelope<'a, T, E> {
+        #[serde(flatten)]
+        page: &'a Page<T>,
+        #[serde(flatten)]
+        extra: &'a E,
+    }
+
+    let body = serde_json::to_vec(&Envelope { page, extra }).unwrap_or_default();
+    let mut response = json_response(body);
+    set_cursor_headers(&mut response, page);
+    response
+}
+
+/// Items of a page as a bare JSON array, linked through headers only
+///
+/// For listings whose clients predate the envelope.
+pub fn array_page_response<T: Serialize>(page: &Page<T>) -> Response<Full<Bytes>> {
+    let mut response = json_response(serde_json::to_vec(&page.items).unwrap_or_default());
+    set_cursor_headers(&mut response, page);
+    response
+}
+
+fn set_cursor_headers<B, T>(response: &mut Response<B>, page: &Page<T>) {
+    let headers = response.headers_mut();
+    for (name, cursor) in [
+        (NEXT_CURSOR_HEADER, &page.next),
+        (PREV_CURSOR_HEADER, &page.prev),
+    ] {
+        if let Some(value) = cursor
+            .as_deref()
+            .and_then(|c| HeaderValue::from_str(c).ok())
+        {
+            headers.insert(name, value);
+        }
+    }
+}
+
+/// `Link` header value for a listing's neighbours (RFC 5988)
+///
+/// URLs are relative to the request: its path and query with `cursor`
+/// replaced (and any `offset` dropped).
+pub fn link_header(
+    path: &str,
+    query: Option<&str>,
+    next: Option<&str>,
+    prev: Option<&str>,
+) -> Option<String> {
+    let params: Vec<(String, String)> =
+        serde_urlencoded::from_str(query.unwrap_or("")).unwrap_or_default();
+    let url = |cursor: &str| {
+        let mut params: Vec<(&str, &str)> = params
+            .iter()
+            .filter(|(key, _)| key != "cursor" && key != "offset")
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect();
+        params.push(("cursor", cursor));
+        let query = serde_urlencoded::to_string(&params).unwrap_or_default();
+        format!("{path}?{query}")
+    };
+
+    let links: Vec<String> = [(next, "next"), (prev, "prev")]
+        .into_iter()
+        .filter_map(|(cursor, rel)| Some(format!("<{}>; rel=\"{rel}\"", url(cursor?))))
+        .collect();
+    (!links.is_empty()).then(|| links.join(", "))
+}
+
+/// Add a `Link` header to a paged response, from its cursor headers, and
+/// let browsers read them cross-origin
+pub fn add_link_header<B>(response: &mut Response<B>, uri: &Uri) {
+    let headers = response.headers();
+    let cursor = |name| headers.get(name).and_then(|v| v.to_str().ok());
+    let Some(link) = link_header(
+        uri.path(),
+        uri.query(),
+        cursor(NEXT_CURSOR_HEADER),
+        cursor(PREV_CURSOR_HEADER),
+    ) else {
+        return;
+    };
+    if let Ok(value) = HeaderValue::from_str(&link) {
+        let headers = response.headers_mut();
+        headers.insert(LINK, value);
+        headers.insert(
+            ACCESS_CONTROL_EXPOSE_HEADERS,
+            HeaderValue::from_static("Link, X-Next-Cursor, X-Prev-Cursor"),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_request_cursor_and_offset() {
+        let request = PageRequest::from_query(Some("limit=999&type=video"), 20, 100).unwrap();
+        assert_eq!(
+            request,
+            PageRequest {
+                limit: 100,
+                offset: 0
+            }
+        );
+
+        let request = PageRequest::from_query(Some("offset=40"), 20, 100).unwrap();
+        assert_eq!(request.offset, 40);
+
+        let cursor = encode_cursor(60);
+        let query = format!("cursor={cursor}&offset=5");
+        let request = PageRequest::from_query(Some(&query), 20, 100).unwrap();
+        assert_eq!(
+            request,
+            PageRequest {
+                limit: 20,
+                offset: 60
+            }
+        );
+
+        assert!(PageRequest::from_query(Some("cursor=bogus"), 20, 100).is_err());
+        assert!(PageRequest::from_query(Some("limit=many"), 20, 100).is_err());
+    }
+
+    #[test]
+    fn test_page_cursors() {
+        let first = PageRequest {
+            limit: 2,
+            offset: 0,
+        };
+        let page = Page::from_all(vec![1, 2, 3, 4, 5], first);
+        assert_eq!(page.items, vec![1, 2]);
+        assert_eq!(page.total, Some(5));
+        assert_eq!(page.prev, None);
+
+        let next = decode_cursor(page.next.as_deref().unwrap()).unwrap();
+        let last = PageRequest {
+            limit: 2,
+            offset: next + 2,
+        };
+        let page = Page::from_all(vec![1, 2, 3, 4, 5], last);
+        assert_eq!(page.items, vec![5]);
+        assert_eq!(page.next, None);
+        assert_eq!(decode_cursor(page.prev.as_deref().unwrap()), Some(2));
+
+        let page = Page::from_probe(vec![1, 2, 3], first);
+        assert_eq!((page.items.len(), page.next.is_some()), (2, true));
+        let page = Page::from_probe(vec![1, 2], first);
+        assert!(page.next.is_none());
+    }
+
+    #[test]
+    fn test_link_header() {
+        let link = link_header(
+            "/steward/moderation-queue",
+            Some("status=pending&offset=10&limit=10"),
+            Some("bmV4dA"),
+            Some("cHJldg"),
+        )
+        .unwrap();
+        assert_eq!(
+            link,
+            "</steward/moderation-queue?status=pending&limit=10&cursor=bmV4dA>; rel=\"next\", \
+             </steward/moderation-queue?status=pending&limit=10&cursor=cHJldg>; rel=\"prev\""
+        );
+
+        assert_eq!(link_header("/x", None, None, None), None);
+
+        let mut response = page_response(&Page::from_all(
+            vec![1, 2, 3],
+            PageRequest {
+                limit: 1,
+                offset: 1,
+            },
+        ));
+        add_link_header(&mut response, &"/x?limit=1&offset=1".parse().unwrap());
+        let link = response.headers().get(LINK).unwrap().to_str().unwrap();
+        assert!(link.contains("rel=\"next\"") && link.contains("rel=\"prev\""));
+    }
+    #[tokio::test]
+    async fn test_page_response_with_extra_fields() {
+        use http_body_util::BodyExt;
+
+        let page = Page::from_all(
+            vec![1, 2, 3],
+            PageRequest {
+                limit: 2,
+                offset: 0,
+            },
+        );
+        let response = page_response_with(&page, &serde_json::json!({ "remaining": 4 }));
+        assert!(response.headers().contains_key(NEXT_CURSOR_HEADER));
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["items"], serde_json::json!([1, 2]));
+        assert_eq!(body["total"], 3);
+        assert_eq!(body["remaining"], 4);
+    }
+}
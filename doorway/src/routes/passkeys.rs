@@ -0,0 +1,838 @@
+//! Passkey Routes
+//!
+//! Passwordless login with [WebAuthn passkeys](crate::auth::webauthn). A
+//! signed-in user registers one passkey per device; afterwards the passkey
+//! alone signs them in as the same doorway identity and agent key.
+//!
+//! ## Routes
+//!
+//! - `POST /auth/passkeys/register/options` - Creation options for `navigator.credentials.create` (JWT required)
+//! - `POST /auth/passkeys/register` - Store the new credential: `{...credential, "deviceName": "Laptop"}` (JWT required)
+//! - `POST /auth/passkeys/login/options` - Request options for `navigator.credentials.get`; `{"identifier": ..}` is optional
+//! - `POST /auth/passkeys/login` - Verify the assertion and issue a JWT like `/auth/login`
+//! - `GET /auth/passkeys` - The caller's passkeys (JWT required)
+//! - `DELETE /auth/passkeys/{credential_id}` - Remove one of the caller's passkeys (JWT required)
+//!
+//! Credentials are posted in the browser's `PublicKeyCredential.toJSON()`
+//! shape (base64url fields). Challenges are single use and expire after five
+//! minutes.
+//!
+//! A passkey login doesn't unlock the custodial signing key, which is
+//! encrypted with the password: sessions started this way have no signing
+//! key cached until the user also logs in with their password.
+
+use base64::prelude::*;
+use bson::doc;
+use hyper::{Request, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use super::auth_routes::{
+    generate_auth_response, get_auth_header, get_jwt_validator, json_response, parse_json_body,
+    BoxBody, ErrorResponse, SuccessResponse,
+};
+use crate::auth::webauthn::{
+    client_challenge, decode_b64url, new_challenge, COSE_EDDSA, COSE_ES256,
+};
+use crate::auth::{extract_token_from_header, Claims, RelyingParty};
+use crate::db::mongo::{IntoIndexes, MutMetadata};
+use crate::db::schemas::{
+    PasskeyDoc, UserDoc, WebAuthnCeremony, WebAuthnChallengeDoc, PASSKEY_COLLECTION,
+    USER_COLLECTION, WEBAUTHN_CHALLENGE_COLLECTION,
+};
+use crate::db::{MongoClient, MongoCollection};
+use crate::server::AppState;
+use crate::services::identity_links::resolve_linked_user;
+
+/// How long a ceremony may take
+const CHALLENGE_TTL: Duration = Duration::from_secs(300);
+
+/// Longest device name kept
+const MAX_DEVICE_NAME_CHARS: usize = 64;
+
+// =============================================================================
+// Request/Response Types
+// =============================================================================
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttestationResponse {
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    pub attestation_object: String,
+    #[serde(default)]
+    pub transports: Vec<String>,
+}
+
+/// New credential from `navigator.credentials.create`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterPasskeyRequest {
+    pub id: String,
+    pub response: AttestationResponse,
+    #[serde(default)]
+    pub device_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct LoginOptionsRequest {
+    #[serde(default)]
+    pub identifier: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssertionResponse {
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    pub authenticator_data: String,
+    pub signature: String,
+}
+
+/// Assertion from `navigator.credentials.get`
+#[derive(Debug, Deserialize)]
+pub struct PasskeyLoginRequest {
+    pub id: String,
+    pub response: AssertionResponse,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyResponse {
+    pub credential_id: String,
+    pub device_name: String,
+    pub aaguid: String,
+    pub transports: Vec<String>,
+    pub backup_eligible: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_used_at: Option<String>,
+}
+
+impl From<PasskeyDoc> for PasskeyResponse {
+    fn from(passkey: PasskeyDoc) -> Self {
+        Self {
+            credential_id: passkey.credential_id,
+            device_name: passkey.device_name,
+            aaguid: passkey.aaguid,
+            transports: passkey.transports,
+            backup_eligible: passkey.backup_eligible,
+            created_at: passkey
+                .metadata
+                .created_at
+                .map(|t| t.to_chrono().to_rfc3339()),
+            last_used_at: passkey.last_used_at.map(|t| t.to_chrono().to_rfc3339()),
+        }
+    }
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Extract the credential id from `/auth/passkeys/{credential_id}`
+pub fn parse_passkey_path(path: &str) -> Option<&str> {
+    path.strip_prefix("/auth/passkeys/")
+        .filter(|id| !id.is_empty() && !id.contains('/'))
+        .filter(|id| !matches!(*id, "register" | "login"))
+}
+
+pub(super) fn error(
+    status: StatusCode,
+    message: impl Into<String>,
+    code: &str,
+) -> Response<BoxBody> {
+    json_response(
+        status,
+        &ErrorResponse {
+            error: message.into(),
+            code: Some(code.into()),
+        },
+    )
+}
+
+#[allow(clippy::result_large_err)]
+pub(super) fn relying_party(state: &AppState) -> Result<RelyingParty, Response<BoxBody>> {
+    match RelyingParty::from_args(&state.args) {
+        Ok(Some(rp)) => Ok(rp),
+        Ok(None) => Err(error(
+            StatusCode::NOT_IMPLEMENTED,
+            "Passkeys not enabled (missing WEBAUTHN_RP_ID)",
+            "NOT_ENABLED",
+        )),
+        Err(e) => Err(error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            e.to_string(),
+            "CONFIG_ERROR",
+        )),
+    }
+}
+
+#[allow(clippy::result_large_err)]
+pub(super) fn mongo(state: &AppState) -> Result<&MongoClient, Response<BoxBody>> {
+    state.mongo.as_ref().ok_or_else(|| {
+        error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Database not available",
+            "DB_UNAVAILABLE",
+        )
+    })
+}
+
+pub(super) async fn collection<T>(
+    mongo: &MongoClient,
+    name: &str,
+) -> Result<MongoCollection<T>, Response<BoxBody>>
+where
+    T: Serialize + DeserializeOwned + Unpin + Send + Sync + Default + IntoIndexes + MutMetadata,
+{
+    mongo.collection::<T>(name).await.map_err(|e| {
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Database error: {e}"),
+            "DB_ERROR",
+        )
+    })
+}
+
+/// Claims of the signed-in caller
+#[allow(clippy::result_large_err)]
+pub(super) fn require_claims(
+    state: &AppState,
+    auth_header: Option<&str>,
+) -> Result<Claims, Response<BoxBody>> {
+    let token = extract_token_from_header(auth_header).ok_or_else(|| {
+        error(
+            StatusCode::UNAUTHORIZED,
+            "No token provided",
+            "UNAUTHORIZED",
+        )
+    })?;
+    let jwt = get_jwt_validator(state)?;
+    let result = jwt.verify_token(token);
+    match result.claims {
+        Some(claims) if result.valid => Ok(claims),
+        _ => Err(error(
+            StatusCode::UNAUTHORIZED,
+            result
+                .error
+                .unwrap_or_else(|| "Invalid or expired token".into()),
+            "UNAUTHORIZED",
+        )),
+    }
+}
+
+/// Remember a challenge until its ceremony completes
+pub(super) async fn issue_challenge(
+    mongo: &MongoClient,
+    ceremony: WebAuthnCeremony,
+    identifier: Option<String>,
+) -> Result<String, Response<BoxBody>> {
+    let challenges =
+        collection::<WebAuthnChallengeDoc>(mongo, WEBAUTHN_CHALLENGE_COLLECTION).await?;
+    let challenge = new_challenge();
+    challenges
+        .insert_one(WebAuthnChallengeDoc::new(
+            challenge.clone(),
+            ceremony,
+            identifier,
+            CHALLENGE_TTL,
+        ))
+        .await
+        .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string(), "DB_ERROR"))?;
+    Ok(challenge)
+}
+
+/// Take the pending challenge echoed in `client_data_json`
+///
+/// Deleting it makes every challenge single use.
+async fn consume_challenge(
+    mongo: &MongoClient,
+    ceremony: WebAuthnCeremony,
+    client_data_json: &[u8],
+) -> Result<Option<WebAuthnChallengeDoc>, Response<BoxBody>> {
+    let Ok(challenge) = client_challenge(client_data_json) else {
+        return Ok(None);
+    };
+    let challenges =
+        collection::<WebAuthnChallengeDoc>(mongo, WEBAUTHN_CHALLENGE_COLLECTION).await?;
+    let pending = challenges
+        .inner()
+        .find_one_and_delete(doc! {
+            "challenge": challenge.trim_end_matches('='),
+            "ceremony": ceremony.as_str(),
+        })
+        .await
+        .map_err(|e| {
+            error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Database error: {e}"),
+                "DB_ERROR",
+            )
+        })?;
+    Ok(pending.filter(WebAuthnChallengeDoc::is_valid))
+}
+
+pub(super) fn credential_descriptors(passkeys: &[PasskeyDoc]) -> Vec<serde_json::Value> {
+    passkeys
+        .iter()
+        .map(|p| {
+            serde_json::json!({
+                "type": "public-key",
+                "id": p.credential_id,
+                "transports": p.transports,
+            })
+        })
+        .collect()
+}
+
+/// Options for `navigator.credentials.get`
+///
+/// `allow_for` limits the passkeys offered to one account's; without it the
+/// authenticator offers its discoverable passkeys. The challenge is issued
+/// to `issued_to`.
+pub(super) async fn request_options(
+    rp: &RelyingParty,
+    mongo: &MongoClient,
+    allow_for: Option<&str>,
+    ceremony: WebAuthnCeremony,
+    issued_to: Option<String>,
+) -> Response<BoxBody> {
+    let allowed = match allow_for {
+        Some(identifier) => match collection::<PasskeyDoc>(mongo, PASSKEY_COLLECTION).await {
+            Ok(passkeys) => passkeys
+                .find_many(doc! { "identifier": identifier })
+                .await
+                .unwrap_or_default(),
+            Err(resp) => return resp,
+        },
+        None => Vec::new(),
+    };
+    let challenge = match issue_challenge(mongo, ceremony, issued_to).await {
+        Ok(c) => c,
+        Err(resp) => return resp,
+    };
+
+    json_response(
+        StatusCode::OK,
+        &serde_json::json!({
+            "publicKey": {
+                "challenge": challenge,
+                "rpId": rp.id(),
+                "timeout": CHALLENGE_TTL.as_millis() as u64,
+                "userVerification": rp.user_verification(),
+                "allowCredentials": credential_descriptors(&allowed),
+            }
+        }),
+    )
+}
+
+/// Use a generic error to prevent user enumeration
+pub(super) fn invalid_credentials() -> Response<BoxBody> {
+    error(
+        StatusCode::UNAUTHORIZED,
+        "Invalid credentials",
+        "INVALID_CREDENTIALS",
+    )
+}
+
+/// Verify a passkey assertion answering a `ceremony` challenge
+///
+/// Records the passkey's new signature counter. Returns the passkey and the
+/// challenge it answered; callers check who the challenge was issued to.
+pub(super) async fn verify_passkey_assertion(
+    rp: &RelyingParty,
+    mongo: &MongoClient,
+    body: &PasskeyLoginRequest,
+    ceremony: WebAuthnCeremony,
+) -> Result<(PasskeyDoc, WebAuthnChallengeDoc), Response<BoxBody>> {
+    let decoded = (
+        decode_b64url(&body.response.client_data_json, "client data"),
+        decode_b64url(&body.response.authenticator_data, "authenticator data"),
+        decode_b64url(&body.response.signature, "signature"),
+    );
+    let (Ok(client_data), Ok(authenticator_data), Ok(signature)) = decoded else {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "Malformed passkey assertion",
+            "INVALID_PASSKEY",
+        ));
+    };
+
+    let passkeys = collection::<PasskeyDoc>(mongo, PASSKEY_COLLECTION).await?;
+    let credential_id = body.id.trim_end_matches('=');
+    let passkey = match passkeys
+        .find_one(doc! { "credential_id": credential_id })
+        .await
+    {
+        Ok(Some(p)) => p,
+        Ok(None) => {
+            warn!("Passkey assertion failed - unknown credential");
+            return Err(invalid_credentials());
+        }
+        Err(e) => {
+            return Err(error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                e.to_string(),
+                "DB_ERROR",
+            ))
+        }
+    };
+
+    let pending = match consume_challenge(mongo, ceremony, &client_data).await? {
+        Some(p) => p,
+        None => {
+            return Err(error(
+                StatusCode::BAD_REQUEST,
+                "Unknown or expired challenge",
+                "INVALID_CHALLENGE",
+            ))
+        }
+    };
+
+    let public_key = match decode_b64url(&passkey.public_key, "stored public key") {
+        Ok(k) => k,
+        Err(e) => {
+            return Err(error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                e.to_string(),
+                "AUTH_ERROR",
+            ))
+        }
+    };
+    let sign_count = match rp.verify_assertion(
+        &pending.challenge,
+        &public_key,
+        passkey.sign_count,
+        &client_data,
+        &authenticator_data,
+        &signature,
+    ) {
+        Ok(count) => count,
+        Err(e) => {
+            warn!("Passkey assertion failed for {}: {}", passkey.identifier, e);
+            return Err(invalid_credentials());
+        }
+    };
+    if let Err(e) = passkeys
+        .update_one(
+            doc! { "credential_id": credential_id },
+            doc! { "$set": {
+                "sign_count": sign_count as i64,
+                "last_used_at": bson::DateTime::now(),
+                "metadata.updated_at": bson::DateTime::now(),
+            } },
+        )
+        .await
+    {
+        warn!(
+            "Failed to record passkey use for {}: {}",
+            passkey.identifier, e
+        );
+    }
+
+    Ok((passkey, pending))
+}
+
+// =============================================================================
+// Route Handlers
+// =============================================================================
+
+/// POST /auth/passkeys/register/options
+pub async fn handle_register_options(
+    req: Request<hyper::body::Incoming>,
+    state: Arc<AppState>,
+) -> Response<BoxBody> {
+    let claims = match require_claims(&state, get_auth_header(&req)) {
+        Ok(c) => c,
+        Err(resp) => return resp,
+    };
+    let rp = match relying_party(&state) {
+        Ok(rp) => rp,
+        Err(resp) => return resp,
+    };
+    let mongo = match mongo(&state) {
+        Ok(m) => m,
+        Err(resp) => return resp,
+    };
+
+    // Don't register a second passkey on a device that already has one
+    let existing = match collection::<PasskeyDoc>(mongo, PASSKEY_COLLECTION).await {
+        Ok(passkeys) => passkeys
+            .find_many(doc! { "identifier": &claims.identifier })
+            .await
+            .unwrap_or_default(),
+        Err(resp) => return resp,
+    };
+    let challenge = match issue_challenge(
+        mongo,
+        WebAuthnCeremony::Registration,
+        Some(claims.identifier.clone()),
+    )
+    .await
+    {
+        Ok(c) => c,
+        Err(resp) => return resp,
+    };
+
+    json_response(
+        StatusCode::OK,
+        &serde_json::json!({
+            "publicKey": {
+                "challenge": challenge,
+                "rp": { "id": rp.id(), "name": rp.name() },
+                "user": {
+                    "id": BASE64_URL_SAFE_NO_PAD
+                        .encode(claims.human_id.as_bytes()),
+                    "name": claims.identifier,
+                    "displayName": claims.identifier,
+                },
+                "pubKeyCredParams": [
+                    { "type": "public-key", "alg": COSE_ES256 },
+                    { "type": "public-key", "alg": COSE_EDDSA },
+                ],
+                "timeout": CHALLENGE_TTL.as_millis() as u64,
+                "attestation": "none",
+                "authenticatorSelection": {
+                    "residentKey": "preferred",
+                    "userVerification": rp.user_verification(),
+                },
+                "excludeCredentials": credential_descriptors(&existing),
+            }
+        }),
+    )
+}
+
+/// POST /auth/passkeys/register
+pub async fn handle_register_passkey(
+    req: Request<hyper::body::Incoming>,
+    state: Arc<AppState>,
+) -> Response<BoxBody> {
+    let claims = match require_claims(&state, get_auth_header(&req)) {
+        Ok(c) => c,
+        Err(resp) => return resp,
+    };
+    let rp = match relying_party(&state) {
+        Ok(rp) => rp,
+        Err(resp) => return resp,
+    };
+    let mongo = match mongo(&state) {
+        Ok(m) => m,
+        Err(resp) => return resp,
+    };
+    let body: RegisterPasskeyRequest = match parse_json_body(req).await {
+        Ok(b) => b,
+        Err(e) => {
+            return error(
+                StatusCode::BAD_REQUEST,
+                format!("Invalid JSON body: {e}"),
+                "INVALID_JSON",
+            )
+        }
+    };
+
+    let decoded =
+        decode_b64url(&body.response.client_data_json, "client data").and_then(|client_data| {
+            let attestation =
+                decode_b64url(&body.response.attestation_object, "attestation object")?;
+            Ok((client_data, attestation))
+        });
+    let (client_data, attestation) = match decoded {
+        Ok(d) => d,
+        Err(e) => return error(StatusCode::BAD_REQUEST, e.to_string(), "INVALID_PASSKEY"),
+    };
+
+    let pending = match consume_challenge(mongo, WebAuthnCeremony::Registration, &client_data).await
+    {
+        Ok(Some(p)) if p.identifier.as_deref() == Some(claims.identifier.as_str()) => p,
+        Ok(_) => {
+            return error(
+                StatusCode::BAD_REQUEST,
+                "Unknown or expired challenge",
+                "INVALID_CHALLENGE",
+            )
+        }
+        Err(resp) => return resp,
+    };
+    let credential = match rp.verify_registration(&pending.challenge, &client_data, &attestation) {
+        Ok(c) => c,
+        Err(e) => {
+            warn!(
+                "Passkey registration rejected for {}: {}",
+                claims.identifier, e
+            );
+            return error(StatusCode::BAD_REQUEST, e.to_string(), "INVALID_PASSKEY");
+        }
+    };
+
+    let credential_id = BASE64_URL_SAFE_NO_PAD.encode(&credential.credential_id);
+    let device_name = body
+        .device_name
+        .map(|name| name.trim().chars().take(MAX_DEVICE_NAME_CHARS).collect())
+        .filter(|name: &String| !name.is_empty())
+        .unwrap_or_else(|| "Passkey".to_string());
+    let passkey = PasskeyDoc {
+        credential_id: credential_id.clone(),
+        identifier: claims.identifier.clone(),
+        human_id: claims.human_id.clone(),
+        public_key: BASE64_URL_SAFE_NO_PAD.encode(&credential.public_key),
+        sign_count: credential.sign_count,
+        aaguid: hex::encode(credential.aaguid),
+        device_name,
+        transports: body.response.transports,
+        backup_eligible: credential.backup_eligible,
+        ..Default::default()
+    };
+
+    let passkeys = match collection::<PasskeyDoc>(mongo, PASSKEY_COLLECTION).await {
+        Ok(p) => p,
+        Err(resp) => return resp,
+    };
+    // The unique index refuses a credential registered before
+    if let Err(e) = passkeys.insert_one(passkey.clone()).await {
+        warn!("Failed to store passkey for {}: {}", claims.identifier, e);
+        return error(
+            StatusCode::CONFLICT,
+            "Passkey could not be stored (already registered?)",
+            "PASSKEY_EXISTS",
+        );
+    }
+
+    info!(
+        "Passkey registered for {} ({})",
+        claims.identifier, passkey.device_name
+    );
+    json_response(StatusCode::CREATED, &PasskeyResponse::from(passkey))
+}
+
+/// POST /auth/passkeys/login/options
+pub async fn handle_login_options(
+    req: Request<hyper::body::Incoming>,
+    state: Arc<AppState>,
+) -> Response<BoxBody> {
+    let rp = match relying_party(&state) {
+        Ok(rp) => rp,
+        Err(resp) => return resp,
+    };
+    let mongo = match mongo(&state) {
+        Ok(m) => m,
+        Err(resp) => return resp,
+    };
+    let body: LoginOptionsRequest = match parse_json_body(req).await {
+        Ok(b) => b,
+        Err(e) => {
+            return error(
+                StatusCode::BAD_REQUEST,
+                format!("Invalid JSON body: {e}"),
+                "INVALID_JSON",
+            )
+        }
+    };
+    let identifier = body.identifier.filter(|i| !i.is_empty());
+    request_options(
+        &rp,
+        mongo,
+        identifier.as_deref(),
+        WebAuthnCeremony::Authentication,
+        identifier.clone(),
+    )
+    .await
+}
+
+/// POST /auth/passkeys/login
+///
+/// Flow:
+/// 1. Find the passkey by credential ID and take the pending challenge
+/// 2. Verify the assertion signature and counter
+/// 3. Record the new counter and issue a JWT for the passkey's user
+pub async fn handle_passkey_login(
+    req: Request<hyper::body::Incoming>,
+    state: Arc<AppState>,
+) -> Response<BoxBody> {
+    let rp = match relying_party(&state) {
+        Ok(rp) => rp,
+        Err(resp) => return resp,
+    };
+    let jwt = match get_jwt_validator(&state) {
+        Ok(j) => j,
+        Err(resp) => return resp,
+    };
+    let mongo = match mongo(&state) {
+        Ok(m) => m,
+        Err(resp) => return resp,
+    };
+    let body: PasskeyLoginRequest = match parse_json_body(req).await {
+        Ok(b) => b,
+        Err(e) => {
+            return error(
+                StatusCode::BAD_REQUEST,
+                format!("Invalid JSON body: {e}"),
+                "INVALID_JSON",
+            )
+        }
+    };
+    let (passkey, pending) =
+        match verify_passkey_assertion(&rp, mongo, &body, WebAuthnCeremony::Authentication).await {
+            Ok(verified) => verified,
+            Err(resp) => return resp,
+        };
+    // A challenge issued for one account can't sign in another
+    if pending
+        .identifier
+        .as_ref()
+        .is_some_and(|i| *i != passkey.identifier)
+    {
+        warn!("Passkey login failed - challenge issued for another user");
+        return invalid_credentials();
+    }
+
+    let users = match collection::<UserDoc>(mongo, USER_COLLECTION).await {
+        Ok(u) => u,
+        Err(resp) => return resp,
+    };
+    let user = match users
+        .find_one(doc! { "identifier": &passkey.identifier, "is_active": true })
+        .await
+    {
+        // Passkeys move with a link, but one registered afterwards may not have
+        Ok(Some(u)) => resolve_linked_user(&users, u).await,
+        other => other,
+    };
+    let user = match user {
+        Ok(Some(u)) => u,
+        Ok(None) => {
+            warn!(
+                "Passkey login failed - inactive user: {}",
+                passkey.identifier
+            );
+            return invalid_credentials();
+        }
+        Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string(), "DB_ERROR"),
+    };
+
+    let (installed_app_id, conductor_id) = state
+        .conductor_registry
+        .as_ref()
+        .and_then(|r| r.get_conductor_for_agent(&user.agent_pub_key))
+        .map(|e| (Some(e.app_id), Some(e.conductor_id)))
+        .unwrap_or((None, user.conductor_id.clone()));
+
+    info!(
+        "Passkey login successful: {} (permission: {:?})",
+        user.identifier, user.permission_level
+    );
+
+    generate_auth_response(
+        &jwt,
+        &state,
+        &user.human_id,
+        &user.agent_pub_key,
+        &user.identifier,
+        Some(uuid::Uuid::new_v4().to_string()),
+        StatusCode::OK,
+        None,
+        user.permission_level,
+        installed_app_id,
+        conductor_id,
+        user.is_steward,
+        user.conductor_id.is_some(),
+    )
+}
+
+/// GET /auth/passkeys
+pub async fn handle_list_passkeys(
+    req: Request<hyper::body::Incoming>,
+    state: Arc<AppState>,
+) -> Response<BoxBody> {
+    let claims = match require_claims(&state, get_auth_header(&req)) {
+        Ok(c) => c,
+        Err(resp) => return resp,
+    };
+    let mongo = match mongo(&state) {
+        Ok(m) => m,
+        Err(resp) => return resp,
+    };
+    let passkeys = match collection::<PasskeyDoc>(mongo, PASSKEY_COLLECTION).await {
+        Ok(p) => p,
+        Err(resp) => return resp,
+    };
+    match passkeys
+        .find_many(doc! { "identifier": &claims.identifier })
+        .await
+    {
+        Ok(found) => json_response(
+            StatusCode::OK,
+            &serde_json::json!({
+                "passkeys": found.into_iter().map(PasskeyResponse::from).collect::<Vec<_>>(),
+            }),
+        ),
+        Err(e) => error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string(), "DB_ERROR"),
+    }
+}
+
+/// DELETE /auth/passkeys/{credential_id}
+pub async fn handle_delete_passkey(
+    req: Request<hyper::body::Incoming>,
+    state: Arc<AppState>,
+) -> Response<BoxBody> {
+    let Some(credential_id) = parse_passkey_path(req.uri().path()).map(str::to_string) else {
+        return error(StatusCode::NOT_FOUND, "Passkey not found", "NOT_FOUND");
+    };
+    let claims = match require_claims(&state, get_auth_header(&req)) {
+        Ok(c) => c,
+        Err(resp) => return resp,
+    };
+    let mongo = match mongo(&state) {
+        Ok(m) => m,
+        Err(resp) => return resp,
+    };
+    let passkeys = match collection::<PasskeyDoc>(mongo, PASSKEY_COLLECTION).await {
+        Ok(p) => p,
+        Err(resp) => return resp,
+    };
+
+    // Removed outright so the device can register it again
+    match passkeys
+        .inner()
+        .delete_one(doc! {
+            "credential_id": &credential_id,
+            "identifier": &claims.identifier,
+        })
+        .await
+    {
+        Ok(result) if result.deleted_count > 0 => {
+            info!("Passkey removed for {}", claims.identifier);
+            json_response(
+                StatusCode::OK,
+                &SuccessResponse {
+                    success: true,
+                    message: "Passkey removed".into(),
+                },
+            )
+        }
+        Ok(_) => error(StatusCode::NOT_FOUND, "Passkey not found", "NOT_FOUND"),
+        Err(e) => error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Database error: {e}"),
+            "DB_ERROR",
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_passkey_path() {
+        assert_eq!(parse_passkey_path("/auth/passkeys/AbC_-1"), Some("AbC_-1"));
+        assert_eq!(parse_passkey_path("/auth/passkeys/"), None);
+        assert_eq!(parse_passkey_path("/auth/passkeys/register"), None);
+        assert_eq!(parse_passkey_path("/auth/passkeys/login/options"), None);
+        assert_eq!(parse_passkey_path("/auth/passkeys"), None);
+    }
+}
@@ -0,0 +1,95 @@
+//! Path Export Routes
+//!
+//! Printable PDFs of public paths from the
+//! [path renderer](crate::worker::path_render), for offline and classroom use.
+//!
+//! ## Routes
+//!
+//! - `GET /paths/{id}/export.pdf` - The path as a PDF; `202` with `Retry-After`
+//!   while a render for its current version is under way
+//!
+//! Needs `PATH_RENDERER_URL`.
+
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::{Response, StatusCode};
+use serde_json::json;
+use std::sync::Arc;
+use tracing::warn;
+
+use super::api::error_response;
+use crate::server::AppState;
+use crate::services::site_export::file_stem;
+use crate::worker::path_render::RenderOutcome;
+
+/// Seconds a client is asked to wait for a render
+const RETRY_AFTER_SECS: u64 = 5;
+
+/// Parse `/paths/{id}/export.pdf`
+pub fn parse_path_export_path(path: &str) -> Option<&str> {
+    let id = path.strip_prefix("/paths/")?.strip_suffix("/export.pdf")?;
+    (!id.is_empty() && !id.contains('/')).then_some(id)
+}
+
+/// Handle GET /paths/{id}/export.pdf
+pub async fn handle_path_export(state: Arc<AppState>, path_id: &str) -> Response<Full<Bytes>> {
+    let Some(ref renderer) = state.path_renderer else {
+        return error_response(
+            StatusCode::NOT_FOUND,
+            "Path export is not enabled",
+            "NOT_ENABLED",
+        );
+    };
+
+    match renderer.pdf(path_id).await {
+        Ok(RenderOutcome::Ready(pdf)) => Response::builder()
+            .status(StatusCode::OK)
+            .header("Content-Type", "application/pdf")
+            .header(
+                "Content-Disposition",
+                format!("attachment; filename=\"{}.pdf\"", file_stem(path_id)),
+            )
+            .header("Cache-Control", "public, max-age=300")
+            .body(Full::new(pdf))
+            .unwrap(),
+        Ok(RenderOutcome::Pending) => Response::builder()
+            .status(StatusCode::ACCEPTED)
+            .header("Content-Type", "application/json")
+            .header("Retry-After", RETRY_AFTER_SECS.to_string())
+            .body(Full::new(Bytes::from(
+                json!({"status": "rendering", "retry_after": RETRY_AFTER_SECS}).to_string(),
+            )))
+            .unwrap(),
+        Ok(RenderOutcome::NotFound) => {
+            error_response(StatusCode::NOT_FOUND, "Path not found", "NOT_FOUND")
+        }
+        Ok(RenderOutcome::Failed(e)) => {
+            warn!(path_id = %path_id, error = %e, "Path PDF unavailable");
+            error_response(
+                StatusCode::BAD_GATEWAY,
+                "Path could not be rendered",
+                "RENDER_FAILED",
+            )
+        }
+        Err(e) => {
+            warn!(path_id = %path_id, error = %e, "Failed to load path for export");
+            error_response(StatusCode::BAD_GATEWAY, "Lookup failed", "ZOME_ERROR")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_path_export_path() {
+        assert_eq!(
+            parse_path_export_path("/paths/fractions-101/export.pdf"),
+            Some("fractions-101")
+        );
+        assert_eq!(parse_path_export_path("/paths//export.pdf"), None);
+        assert_eq!(parse_path_export_path("/paths/a/b/export.pdf"), None);
+        assert_eq!(parse_path_export_path("/paths/fractions-101"), None);
+    }
+}
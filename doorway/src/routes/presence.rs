@@ -0,0 +1,91 @@
+//! Presence Routes
+//!
+//! Who else is studying a path or step right now (see
+//! [`presence`](crate::proxy::presence)).
+//!
+//! ## Routes
+//!
+//! - `GET /presence` - WebSocket; join a room and hear its members come, go and move
+//! - `GET /presence/count?path_id=&step=` - Members in a room, for "3 others are
+//!   studying this chapter" without holding a socket open
+
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::body::Incoming;
+use hyper::{Request, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::error;
+
+use super::api::{error_response, json_response};
+use crate::proxy::presence::{run_presence, RoomKey};
+use crate::server::AppState;
+
+#[derive(Debug, Default, Deserialize)]
+struct CountParams {
+    path_id: Option<String>,
+    step: Option<String>,
+}
+
+/// Response of `GET /presence/count`
+#[derive(Debug, Serialize)]
+struct CountResponse {
+    room: RoomKey,
+    members: usize,
+}
+
+fn presence_disabled() -> Response<Full<Bytes>> {
+    error_response(
+        StatusCode::NOT_FOUND,
+        "Presence is not enabled on this doorway",
+        "PRESENCE_DISABLED",
+    )
+}
+
+/// Handle GET /presence (WebSocket upgrade)
+pub fn handle_presence(req: Request<Incoming>, state: Arc<AppState>) -> Response<Full<Bytes>> {
+    if !state.presence.is_enabled() {
+        return presence_disabled();
+    }
+
+    match hyper_tungstenite::upgrade(req, Some(state.body_limits.websocket_config())) {
+        Ok((response, websocket)) => {
+            let hub = Arc::clone(&state.presence);
+            tokio::spawn(async move {
+                match websocket.await {
+                    Ok(ws) => run_presence(ws, hub).await,
+                    Err(e) => error!("Presence WebSocket upgrade failed: {:?}", e),
+                }
+            });
+
+            let (parts, _) = response.into_parts();
+            Response::from_parts(parts, Full::new(Bytes::new()))
+        }
+        Err(e) => error_response(
+            StatusCode::BAD_REQUEST,
+            &format!("WebSocket upgrade failed: {e}"),
+            "UPGRADE_FAILED",
+        ),
+    }
+}
+
+/// Handle GET /presence/count
+pub fn handle_presence_count(state: Arc<AppState>, query: Option<&str>) -> Response<Full<Bytes>> {
+    if !state.presence.is_enabled() {
+        return presence_disabled();
+    }
+
+    let params: CountParams = serde_urlencoded::from_str(query.unwrap_or("")).unwrap_or_default();
+    let room = match RoomKey::new(
+        params.path_id.as_deref().unwrap_or(""),
+        params.step.as_deref(),
+    ) {
+        Ok(room) => room,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, &e.to_string(), "INVALID_ROOM"),
+    };
+    let body = CountResponse {
+        members: state.presence.count(&room),
+        room,
+    };
+    json_response(serde_json::to_vec(&body).unwrap_or_default())
+}
@@ -0,0 +1,346 @@
+//! Content Preview API
+//!
+//! Link unfurling for chat apps and social platforms. Crawlers fetching a
+//! shared link get server-rendered Open Graph tags; people following it are
+//! sent on to the content in the app.
+//!
+//! ## Routes
+//!
+//! - `GET /preview/{content_id}` - HTML page with Open Graph / Twitter card tags
+//! - `GET /preview/{content_id}?format=oembed` - oEmbed JSON (also chosen by
+//!   `Accept: application/json+oembed`)
+//!
+//! Previews are built from the projection store, so they never touch the
+//! conductor. Only `commons` and `public` content has a preview; anything
+//! narrower answers 404 so a link doesn't reveal that the content exists.
+
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::{Response, StatusCode};
+use serde::Serialize;
+use std::sync::Arc;
+
+use super::api::error_response;
+use crate::projection::ProjectedDocument;
+use crate::server::AppState;
+
+/// Reach levels anyone may preview
+const PREVIEWABLE_REACH: [&str; 2] = ["commons", "public"];
+
+/// Longest description put in a card
+const MAX_DESCRIPTION_CHARS: usize = 200;
+
+/// Name reported as site / oEmbed provider
+const PROVIDER_NAME: &str = "Elohim";
+
+/// Extract the content id from `/preview/{content_id}`
+pub fn parse_preview_path(path: &str) -> Option<&str> {
+    path.strip_prefix("/preview/")
+        .filter(|id| !id.is_empty() && !id.contains('/'))
+}
+
+/// Card data for one piece of content
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContentPreview {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub content_type: Option<String>,
+    pub thumbnail_url: Option<String>,
+    pub estimated_minutes: Option<u32>,
+}
+
+impl ContentPreview {
+    /// Build a preview from a projected Content document, if it is public
+    pub fn from_document(doc: &ProjectedDocument) -> Option<Self> {
+        let data = &doc.data;
+        let text = |key: &str| {
+            data.get(key)
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(String::from)
+        };
+
+        let reach = doc
+            .reach
+            .clone()
+            .or_else(|| text("reach"))
+            .unwrap_or_else(|| "commons".to_string());
+        if !PREVIEWABLE_REACH.contains(&reach.as_str()) {
+            return None;
+        }
+
+        let description = text("summary").or_else(|| text("description"));
+        Some(Self {
+            id: doc.doc_id.clone(),
+            title: text("title").unwrap_or_else(|| doc.doc_id.clone()),
+            description: truncate(description.as_deref().unwrap_or(""), MAX_DESCRIPTION_CHARS),
+            content_type: text("content_type"),
+            thumbnail_url: text("thumbnail_url"),
+            estimated_minutes: data
+                .get("estimated_minutes")
+                .and_then(|v| v.as_u64())
+                .map(|m| m as u32),
+        })
+    }
+}
+
+/// Shorten to at most `max` characters, ending in an ellipsis when cut
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(max.saturating_sub(1)).collect();
+    format!("{}…", cut.trim_end())
+}
+
+/// Escape text for use in HTML content and attribute values
+pub(crate) fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Where the content is viewed in the app
+pub(crate) fn content_url(base_url: &str, id: &str) -> String {
+    format!(
+        "{}/lamad/resource/{}",
+        base_url.trim_end_matches('/'),
+        urlencoding::encode(id)
+    )
+}
+
+/// Render the Open Graph page
+pub fn render_og_html(preview: &ContentPreview, base_url: &str) -> String {
+    let url = escape_html(&content_url(base_url, &preview.id));
+    let title = escape_html(&preview.title);
+    let description = escape_html(&preview.description);
+    let oembed_url = escape_html(&format!(
+        "{}/preview/{}?format=oembed",
+        base_url.trim_end_matches('/'),
+        urlencoding::encode(&preview.id)
+    ));
+
+    let mut meta = vec![
+        format!(r#"<meta property="og:site_name" content="{PROVIDER_NAME}">"#),
+        r#"<meta property="og:type" content="article">"#.to_string(),
+        format!(r#"<meta property="og:title" content="{title}">"#),
+        format!(r#"<meta property="og:description" content="{description}">"#),
+        format!(r#"<meta property="og:url" content="{url}">"#),
+        format!(r#"<meta name="description" content="{description}">"#),
+    ];
+    match preview.thumbnail_url {
+        Some(ref thumbnail) => {
+            let thumbnail = escape_html(thumbnail);
+            meta.push(format!(
+                r#"<meta property="og:image" content="{thumbnail}">"#
+            ));
+            meta.push(r#"<meta name="twitter:card" content="summary_large_image">"#.to_string());
+        }
+        None => meta.push(r#"<meta name="twitter:card" content="summary">"#.to_string()),
+    }
+    if let Some(minutes) = preview.estimated_minutes {
+        // Slack and others show label/data pairs under the card
+        meta.push(r#"<meta name="twitter:label1" content="Time">"#.to_string());
+        meta.push(format!(
+            r#"<meta name="twitter:data1" content="{minutes} min">"#
+        ));
+    }
+    if let Some(ref content_type) = preview.content_type {
+        let content_type = escape_html(content_type);
+        meta.push(format!(
+            r#"<meta property="article:section" content="{content_type}">"#
+        ));
+    }
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+{meta}
+<link rel="canonical" href="{url}">
+<link rel="alternate" type="application/json+oembed" href="{oembed_url}" title="{title}">
+<meta http-equiv="refresh" content="0; url={url}">
+</head>
+<body>
+<p><a href="{url}">{title}</a></p>
+</body>
+</html>
+"#,
+        meta = meta.join("\n")
+    )
+}
+
+/// oEmbed response (`link` type)
+#[derive(Debug, Serialize)]
+pub struct OEmbed {
+    pub version: &'static str,
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub title: String,
+    pub provider_name: &'static str,
+    pub provider_url: String,
+    /// Canonical content URL (extension; most consumers ignore it)
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_minutes: Option<u32>,
+    pub cache_age: u32,
+}
+
+/// Build the oEmbed response
+pub fn oembed(preview: &ContentPreview, base_url: &str) -> OEmbed {
+    OEmbed {
+        version: "1.0",
+        kind: "link",
+        title: preview.title.clone(),
+        provider_name: PROVIDER_NAME,
+        provider_url: base_url.trim_end_matches('/').to_string(),
+        url: content_url(base_url, &preview.id),
+        description: Some(preview.description.clone()).filter(|d| !d.is_empty()),
+        thumbnail_url: preview.thumbnail_url.clone(),
+        estimated_minutes: preview.estimated_minutes,
+        cache_age: 3600,
+    }
+}
+
+/// Whether the caller asked for oEmbed JSON rather than HTML
+fn wants_oembed(query: Option<&str>, accept: Option<&str>) -> bool {
+    let by_query = query.is_some_and(|q| {
+        q.split('&')
+            .any(|pair| pair == "format=oembed" || pair == "format=json")
+    });
+    by_query || accept.is_some_and(|a| a.contains("application/json+oembed"))
+}
+
+/// Handle GET /preview/{content_id}
+pub async fn handle_content_preview(
+    state: Arc<AppState>,
+    content_id: &str,
+    query: Option<&str>,
+    accept: Option<&str>,
+    base_url: &str,
+) -> Response<Full<Bytes>> {
+    let Some(ref projection) = state.projection else {
+        return error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Projection store not available",
+            "PROJECTION_UNAVAILABLE",
+        );
+    };
+
+    let preview = match projection.get("Content", content_id).await {
+        Some(doc) => ContentPreview::from_document(&doc),
+        None => None,
+    };
+    let Some(preview) = preview else {
+        return error_response(StatusCode::NOT_FOUND, "Content not found", "NOT_FOUND");
+    };
+
+    let base_url = state.args.doorway_url.as_deref().unwrap_or(base_url);
+    let (content_type, body) = if wants_oembed(query, accept) {
+        (
+            "application/json+oembed",
+            serde_json::to_vec(&oembed(&preview, base_url)).unwrap_or_default(),
+        )
+    } else {
+        (
+            "text/html; charset=utf-8",
+            render_og_html(&preview, base_url).into_bytes(),
+        )
+    };
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", content_type)
+        .header("Cache-Control", "public, max-age=300")
+        .header("Vary", "Accept")
+        .body(Full::new(Bytes::from(body)))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn doc(data: serde_json::Value, reach: Option<&str>) -> ProjectedDocument {
+        ProjectedDocument {
+            doc_type: "Content".to_string(),
+            doc_id: "intro-to-governance".to_string(),
+            reach: reach.map(String::from),
+            data,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_preview_path() {
+        assert_eq!(parse_preview_path("/preview/abc"), Some("abc"));
+        assert_eq!(parse_preview_path("/preview/"), None);
+        assert_eq!(parse_preview_path("/preview/a/b"), None);
+    }
+
+    #[test]
+    fn test_from_document_prefers_summary_and_hides_private() {
+        let data = json!({
+            "title": "Intro to Governance",
+            "description": "Long description",
+            "summary": "Short summary",
+            "thumbnail_url": "https://cdn.example/thumb.png",
+            "estimated_minutes": 12,
+            "content_type": "lesson"
+        });
+        let preview = ContentPreview::from_document(&doc(data.clone(), Some("public"))).unwrap();
+        assert_eq!(preview.description, "Short summary");
+        assert_eq!(preview.estimated_minutes, Some(12));
+
+        assert!(ContentPreview::from_document(&doc(data, Some("community"))).is_none());
+        assert!(ContentPreview::from_document(&doc(json!({ "reach": "private" }), None)).is_none());
+    }
+
+    #[test]
+    fn test_render_og_html_escapes() {
+        let preview = ContentPreview {
+            id: "c1".to_string(),
+            title: "Tom & \"Jerry\" <script>".to_string(),
+            description: truncate(&"x".repeat(300), MAX_DESCRIPTION_CHARS),
+            content_type: None,
+            thumbnail_url: None,
+            estimated_minutes: Some(5),
+        };
+        let html = render_og_html(&preview, "https://doorway.example/");
+
+        assert!(html.contains("Tom &amp; &quot;Jerry&quot; &lt;script&gt;"));
+        assert!(!html.contains("<script>"));
+        assert!(html.contains(
+            r#"<meta property="og:url" content="https://doorway.example/lamad/resource/c1">"#
+        ));
+        assert!(html.contains("https://doorway.example/preview/c1?format=oembed"));
+        assert!(html.contains(r#"content="5 min""#));
+        assert_eq!(preview.description.chars().count(), MAX_DESCRIPTION_CHARS);
+    }
+
+    #[test]
+    fn test_wants_oembed() {
+        assert!(wants_oembed(Some("format=oembed"), None));
+        assert!(wants_oembed(None, Some("application/json+oembed")));
+        assert!(!wants_oembed(Some("format=html"), Some("text/html")));
+        assert!(!wants_oembed(None, None));
+    }
+}
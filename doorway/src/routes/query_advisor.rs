@@ -0,0 +1,47 @@
+//! Query Advisor Routes
+//!
+//! Admin view of the [query advisor](crate::worker::query_advisor): the
+//! slowest recent zome calls, per-function timings and suggested cache-rule
+//! changes.
+//!
+//! ## Routes
+//!
+//! - `GET /admin/query-advisor` - Latest advisor report
+
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::{Response, StatusCode};
+use std::sync::Arc;
+
+use super::api::{error_response, json_response};
+use super::auth_helpers::require_user;
+use crate::auth::PermissionLevel;
+use crate::server::AppState;
+
+/// Handle GET /admin/query-advisor
+pub fn handle_query_advisor(
+    state: Arc<AppState>,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    let claims = match require_user(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+    if claims.permission_level < PermissionLevel::Admin {
+        return error_response(
+            StatusCode::FORBIDDEN,
+            "Admin permission required",
+            "FORBIDDEN",
+        );
+    }
+    let Some(ref advisor) = state.query_advisor else {
+        return error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Query advisor not enabled (SLOW_QUERY_THRESHOLD_MS=0)",
+            "NOT_ENABLED",
+        );
+    };
+
+    let report = advisor.report(&state.cache_rules);
+    json_response(serde_json::to_vec(&report).unwrap_or_default())
+}
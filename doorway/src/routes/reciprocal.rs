@@ -0,0 +1,246 @@
+//! Reciprocal Federation Routes
+//!
+//! Gateway-to-gateway calls between doorways of different communities (see
+//! [`services::reciprocal_federation`](crate::services::reciprocal_federation)).
+//!
+//! ## Routes
+//!
+//! - `GET /federation/reciprocal` - Reciprocal peers and the functions shared with them
+//! - `POST /federation/peers/{doorway_id}/call/{fn}` - Call a shared function on a
+//!   peer's network with a JSON input; answers with its commons results
+//! - `POST /federation/v1/call/{fn}` - Signed call from a peer doorway, answered from
+//!   this network's DHT
+//!
+//! Learners need no account to browse a peer's commons content. Disabled
+//! unless `RECIPROCAL_PEERS` is set.
+
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::Incoming;
+use hyper::{Request, Response, StatusCode};
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+use super::api::{error_response, json_response, not_enabled_response, overloaded_response};
+use super::zome_helpers::call_content_store;
+use crate::server::AppState;
+use crate::services::reciprocal_federation::{
+    commons_only, ReciprocalError, SignedCall, DOORWAY_ID_HEADER, SIGNATURE_HEADER,
+    TIMESTAMP_HEADER,
+};
+use crate::types::DoorwayError;
+
+/// Largest call input accepted
+const MAX_BODY_BYTES: usize = 16 * 1024;
+
+/// Message when RECIPROCAL_PEERS is unset
+const NOT_ENABLED: &str = "Reciprocal federation not enabled (missing RECIPROCAL_PEERS)";
+
+fn is_fn_name(name: &str) -> bool {
+    !name.is_empty() && name.bytes().all(|b| b.is_ascii_lowercase() || b == b'_')
+}
+
+/// Parse `/federation/v1/call/{fn}`
+pub fn parse_inbound_call_path(path: &str) -> Option<&str> {
+    let fn_name = path.strip_prefix("/federation/v1/call/")?;
+    is_fn_name(fn_name).then_some(fn_name)
+}
+
+/// Parse `/federation/peers/{doorway_id}/call/{fn}`
+pub fn parse_peer_call_path(path: &str) -> Option<(&str, &str)> {
+    let rest = path.strip_prefix("/federation/peers/")?;
+    let (peer_id, fn_name) = rest.split_once("/call/")?;
+    (!peer_id.is_empty() && !peer_id.contains('/') && is_fn_name(fn_name))
+        .then_some((peer_id, fn_name))
+}
+
+/// Call input; an empty body is `null`
+async fn read_input(req: Request<Incoming>) -> Result<Bytes, Response<Full<Bytes>>> {
+    match Limited::new(req.into_body(), MAX_BODY_BYTES)
+        .collect()
+        .await
+    {
+        Ok(collected) => Ok(collected.to_bytes()),
+        Err(_) => Err(error_response(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            &format!("Call input is limited to {MAX_BODY_BYTES} bytes"),
+            "TOO_LARGE",
+        )),
+    }
+}
+
+fn parse_input(body: &[u8]) -> Result<Value, Response<Full<Bytes>>> {
+    if body.is_empty() {
+        return Ok(Value::Null);
+    }
+    serde_json::from_slice(body).map_err(|e| {
+        error_response(
+            StatusCode::BAD_REQUEST,
+            &format!("Invalid input: {e}"),
+            "INVALID_JSON",
+        )
+    })
+}
+
+/// Handle GET /federation/reciprocal
+pub fn handle_reciprocal_peers(state: Arc<AppState>) -> Response<Full<Bytes>> {
+    let Some(ref reciprocal) = state.reciprocal else {
+        return not_enabled_response(StatusCode::NOT_IMPLEMENTED, NOT_ENABLED);
+    };
+    let mut peers: Vec<Value> = reciprocal
+        .peers()
+        .map(|peer| serde_json::json!({ "doorway_id": peer.doorway_id, "url": peer.url }))
+        .collect();
+    peers.sort_by(|a, b| a["doorway_id"].as_str().cmp(&b["doorway_id"].as_str()));
+
+    json_response(
+        serde_json::to_vec(&serde_json::json!({
+            "peers": peers,
+            "shared_functions": reciprocal.exported_fns(),
+        }))
+        .unwrap_or_default(),
+    )
+}
+
+/// Handle POST /federation/peers/{doorway_id}/call/{fn}
+pub async fn handle_peer_call(
+    req: Request<Incoming>,
+    state: Arc<AppState>,
+    peer_id: &str,
+    fn_name: &str,
+) -> Response<Full<Bytes>> {
+    let Some(reciprocal) = state.reciprocal.clone() else {
+        return not_enabled_response(StatusCode::NOT_IMPLEMENTED, NOT_ENABLED);
+    };
+    // Don't relay a peer's call on to a third network
+    if req.headers().contains_key("X-Federation-Hop") {
+        return error_response(
+            StatusCode::LOOP_DETECTED,
+            "Federated calls are not relayed",
+            "LOOP_DETECTED",
+        );
+    }
+    let input = match read_input(req).await.and_then(|body| parse_input(&body)) {
+        Ok(input) => input,
+        Err(response) => return response,
+    };
+
+    match reciprocal.call_peer(peer_id, fn_name, &input).await {
+        Ok(data) => {
+            debug!(peer = %peer_id, fn_name = %fn_name, "Reciprocal call answered");
+            json_response(serde_json::to_vec(&commons_only(data)).unwrap_or_default())
+        }
+        Err(ReciprocalError::UnknownPeer(_)) => error_response(
+            StatusCode::NOT_FOUND,
+            "Unknown peer doorway",
+            "UNKNOWN_PEER",
+        ),
+        Err(e) => {
+            warn!(peer = %peer_id, fn_name = %fn_name, error = %e, "Reciprocal call failed");
+            error_response(StatusCode::BAD_GATEWAY, "Peer call failed", "PEER_ERROR")
+        }
+    }
+}
+
+/// Handle POST /federation/v1/call/{fn}
+pub async fn handle_inbound_call(
+    req: Request<Incoming>,
+    state: Arc<AppState>,
+    fn_name: &str,
+) -> Response<Full<Bytes>> {
+    let Some(reciprocal) = state.reciprocal.clone() else {
+        return not_enabled_response(StatusCode::NOT_IMPLEMENTED, NOT_ENABLED);
+    };
+    let call = match signed_call(&req) {
+        Some(call) => call,
+        None => {
+            return error_response(
+                StatusCode::UNAUTHORIZED,
+                "Missing doorway signature headers",
+                "UNAUTHORIZED",
+            )
+        }
+    };
+    let body = match read_input(req).await {
+        Ok(body) => body,
+        Err(response) => return response,
+    };
+
+    let now = chrono::Utc::now().timestamp();
+    if let Err(e) = reciprocal.verify_call(&call, fn_name, &body, now) {
+        info!(peer = %call.doorway_id, fn_name = %fn_name, reason = %e, "Reciprocal call refused");
+        let status = match e {
+            ReciprocalError::NotExported(_) => StatusCode::FORBIDDEN,
+            _ => StatusCode::UNAUTHORIZED,
+        };
+        return error_response(status, &e.to_string(), "FEDERATION_REFUSED");
+    }
+    let input = match parse_input(&body) {
+        Ok(input) => input,
+        Err(response) => return response,
+    };
+
+    match call_content_store(&state, fn_name, &input).await {
+        Ok(data) => {
+            debug!(peer = %call.doorway_id, fn_name = %fn_name, "Reciprocal call served");
+            let data = commons_only(data.unwrap_or(Value::Null));
+            json_response(serde_json::to_vec(&data).unwrap_or_default())
+        }
+        Err(DoorwayError::Overloaded(msg)) => overloaded_response(&msg),
+        Err(e) => {
+            warn!(peer = %call.doorway_id, fn_name = %fn_name, error = ?e, "Reciprocal call failed");
+            error_response(StatusCode::BAD_GATEWAY, "Lookup failed", "ZOME_ERROR")
+        }
+    }
+}
+
+/// Signature headers of an incoming call
+fn signed_call(req: &Request<Incoming>) -> Option<SignedCall> {
+    let header = |name: &str| {
+        req.headers()
+            .get(name)
+            .and_then(|h| h.to_str().ok())
+            .map(|s| s.to_string())
+    };
+    Some(SignedCall {
+        doorway_id: header(DOORWAY_ID_HEADER)?,
+        timestamp: header(TIMESTAMP_HEADER)?.parse().ok()?,
+        signature: header(SIGNATURE_HEADER)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_inbound_call_path() {
+        assert_eq!(
+            parse_inbound_call_path("/federation/v1/call/get_content_by_id"),
+            Some("get_content_by_id")
+        );
+        assert_eq!(parse_inbound_call_path("/federation/v1/call/"), None);
+        assert_eq!(
+            parse_inbound_call_path("/federation/v1/call/../admin"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_peer_call_path() {
+        assert_eq!(
+            parse_peer_call_path("/federation/peers/beta/call/get_all_paths"),
+            Some(("beta", "get_all_paths"))
+        );
+        assert_eq!(
+            parse_peer_call_path("/federation/peers//call/get_all_paths"),
+            None
+        );
+        assert_eq!(parse_peer_call_path("/federation/peers/beta/call/"), None);
+        assert_eq!(
+            parse_peer_call_path("/federation/peers/a/b/call/get_all_paths"),
+            None
+        );
+    }
+}
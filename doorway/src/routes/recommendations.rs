@@ -0,0 +1,107 @@
+//! Recommendations API
+//!
+//! Serves the signed-in learner's ranked "what to learn next" list from the
+//! [`RecommendationEngine`](crate::worker::recommendations::RecommendationEngine).
+//!
+//! ## Routes
+//!
+//! - `GET /me/recommendations?limit=` - Ranked content suggestions (default 10, max 50)
+
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::{Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::warn;
+
+use super::api::{error_response, json_response};
+use super::auth_helpers::require_user;
+use crate::server::AppState;
+use crate::worker::recommendations::Recommendation;
+
+/// Default number of suggestions
+const DEFAULT_LIMIT: usize = 10;
+
+/// Largest `limit` accepted
+const MAX_LIMIT: usize = 50;
+
+#[derive(Debug, Default, Deserialize)]
+struct RecommendationParams {
+    limit: Option<usize>,
+}
+
+/// Response body
+#[derive(Debug, Serialize)]
+struct RecommendationsResponse {
+    agent_id: String,
+    strategy: String,
+    recommendations: Vec<Recommendation>,
+}
+
+/// Parse `limit`, clamped to [`MAX_LIMIT`]
+fn parse_limit(query: Option<&str>) -> Result<usize, String> {
+    let params: RecommendationParams = serde_urlencoded::from_str(query.unwrap_or(""))
+        .map_err(|e| format!("Invalid query parameters: {e}"))?;
+    Ok(params.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT))
+}
+
+/// Handle GET /me/recommendations
+pub async fn handle_recommendations(
+    state: Arc<AppState>,
+    query: Option<&str>,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    let claims = match require_user(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let limit = match parse_limit(query) {
+        Ok(limit) => limit,
+        Err(msg) => return error_response(StatusCode::BAD_REQUEST, &msg, "INVALID_QUERY"),
+    };
+
+    let Some(ref engine) = state.recommendations else {
+        return error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Recommendations not available",
+            "RECOMMENDATIONS_UNAVAILABLE",
+        );
+    };
+
+    match engine
+        .recommendations_for(&claims.agent_pub_key, &claims.human_id, limit)
+        .await
+    {
+        Ok(recommendations) => {
+            let body = RecommendationsResponse {
+                agent_id: claims.agent_pub_key,
+                strategy: engine.strategy_name().to_string(),
+                recommendations,
+            };
+            json_response(serde_json::to_vec(&body).unwrap_or_default())
+        }
+        Err(e) => {
+            warn!(human_id = %claims.human_id, error = %e, "Recommendations failed");
+            error_response(
+                StatusCode::BAD_GATEWAY,
+                "Recommendations failed",
+                "RECOMMENDATIONS_FAILED",
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_limit() {
+        assert_eq!(parse_limit(None).unwrap(), DEFAULT_LIMIT);
+        assert_eq!(parse_limit(Some("limit=5")).unwrap(), 5);
+        assert_eq!(parse_limit(Some("limit=500")).unwrap(), MAX_LIMIT);
+        assert_eq!(parse_limit(Some("limit=0")).unwrap(), 1);
+        assert!(parse_limit(Some("limit=many")).is_err());
+    }
+}
@@ -0,0 +1,672 @@
+//! Emergency Recovery API
+//!
+//! Guided recovery of a custodian commitment. Recovering someone's content
+//! takes several zome calls in a fixed order - fire an emergency trigger,
+//! wait for custodians to approve, wait for their shards, reconstruct - and
+//! the person asking is often a grieving family member. The doorway runs
+//! that sequence as a saga whose state lives in MongoDB, so a recovery that
+//! is waiting on custodians (or was interrupted) resumes on the next call.
+//!
+//! ## Routes
+//!
+//! - `POST /recovery/{commitment_id}/activate` - Start or resume recovery
+//! - `GET /recovery/{commitment_id}` - Current saga state
+//!
+//! ## Steps
+//!
+//! | Step | Zome calls | Advances when |
+//! |------|------------|---------------|
+//! | `validating_trigger` | `get_custodian_commitment`, `activate_emergency_manual` or `trigger_dead_mans_switch` | A trigger fires (or the commitment is already activated) |
+//! | `awaiting_consensus` | `check_consensus_status` | M custodians approve |
+//! | `gathering_shards` | `get_recovery_shard_status` | Every content item has enough shards |
+//! | `reconstructing` | `reconstruct_content_from_shards` | Every content item verifies |
+//!
+//! Zome call failures leave the saga at its current step with `last_error`
+//! set; calling `activate` again retries from there.
+
+use bson::doc;
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full};
+use hyper::body::Incoming;
+use hyper::{Method, Request, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use super::auth_helpers::require_user;
+use super::zome_helpers::call_content_store_for;
+use crate::auth::Claims;
+use crate::db::schemas::{
+    RecoveryContentProgress, RecoverySagaDoc, RecoveryStep, RECOVERY_SAGA_COLLECTION,
+};
+use crate::db::MongoCollection;
+use crate::server::AppState;
+use crate::types::DoorwayError;
+
+type FullBody = Full<Bytes>;
+
+/// Upper bound on steps driven per request (one pass through the protocol)
+const MAX_STEPS_PER_REQUEST: usize = 6;
+
+// =============================================================================
+// Request / Response Types
+// =============================================================================
+
+/// Body of `POST /recovery/{commitment_id}/activate`
+#[derive(Debug, Default, Deserialize)]
+pub struct ActivateRecoveryRequest {
+    /// Content to reconstruct once the commitment is activated
+    #[serde(default)]
+    pub content_ids: Vec<String>,
+    /// Narrative reason for the emergency
+    #[serde(default)]
+    pub reason: String,
+    /// Passphrase for a `manual_signal` trigger, if the commitment has one
+    #[serde(default)]
+    pub passphrase: Option<String>,
+}
+
+/// Reconstructed content returned when the saga completes
+#[derive(Debug, Clone, Serialize)]
+pub struct RecoveredContent {
+    pub content_id: String,
+    pub content: String,
+    pub verification_status: String,
+}
+
+/// Saga state returned by both routes
+#[derive(Debug, Serialize)]
+pub struct RecoveryResponse {
+    pub commitment_id: String,
+    pub step: RecoveryStep,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trigger_type: Option<String>,
+    pub content: Vec<RecoveryContentProgress>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub consensus: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    pub attempts: u32,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub recovered: Vec<RecoveredContent>,
+}
+
+impl RecoveryResponse {
+    fn from_saga(saga: &RecoverySagaDoc, recovered: Vec<RecoveredContent>) -> Self {
+        Self {
+            commitment_id: saga.commitment_id.clone(),
+            step: saga.step,
+            trigger_type: saga.trigger_type.clone(),
+            content: saga.content.clone(),
+            consensus: saga
+                .consensus_json
+                .as_deref()
+                .and_then(|json| serde_json::from_str(json).ok()),
+            last_error: saga.last_error.clone(),
+            attempts: saga.attempts,
+            recovered,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorResponse {
+    error: String,
+    code: &'static str,
+}
+
+/// Input for content_store::activate_emergency_manual
+#[derive(Debug, Serialize)]
+struct ActivateEmergencyManualInput<'a> {
+    commitment_id: &'a str,
+    beneficiary_id: &'a str,
+    passphrase: &'a str,
+    reason: &'a str,
+}
+
+/// Input for content_store::check_consensus_status
+#[derive(Debug, Serialize)]
+struct CheckConsensusStatusInput<'a> {
+    commitment_id: &'a str,
+}
+
+/// Input for get_recovery_shard_status and reconstruct_content_from_shards
+#[derive(Debug, Serialize)]
+struct ReconstructContentInput<'a> {
+    commitment_id: &'a str,
+    content_id: &'a str,
+}
+
+// =============================================================================
+// Route Parsing
+// =============================================================================
+
+#[derive(Debug, PartialEq, Eq)]
+enum RecoveryRoute<'a> {
+    Activate(&'a str),
+    Status(&'a str),
+}
+
+/// Parse `/recovery/{commitment_id}[/activate]`
+fn parse_recovery_path(method: &Method, path: &str) -> Option<RecoveryRoute<'_>> {
+    let rest = path.strip_prefix("/recovery/")?.trim_end_matches('/');
+    match (method, rest.split_once('/')) {
+        (&Method::POST, Some((id, "activate"))) if !id.is_empty() => Some(RecoveryRoute::Activate(id)),
+        (&Method::GET, None) if !rest.is_empty() => Some(RecoveryRoute::Status(rest)),
+        _ => None,
+    }
+}
+
+// =============================================================================
+// Step Decisions
+// =============================================================================
+
+/// What the trigger validation step should do with a commitment
+#[derive(Debug, PartialEq, Eq)]
+enum TriggerDecision {
+    /// Already activated - go straight to shards
+    Activated,
+    /// A consensus vote is open or the commitment uses M-of-N
+    AwaitConsensus,
+    /// Fire the manual_signal trigger with the supplied passphrase
+    ManualSignal,
+    /// Fire the dead man's switch (the zome re-checks the window)
+    DeadMansSwitch,
+    /// Recovery cannot start
+    Reject(String),
+}
+
+fn enabled_trigger(commitment: &serde_json::Value, trigger_type: &str) -> bool {
+    commitment["emergency_triggers_json"]
+        .as_str()
+        .and_then(|json| serde_json::from_str::<Vec<serde_json::Value>>(json).ok())
+        .unwrap_or_default()
+        .iter()
+        .any(|t| t["trigger_type"] == trigger_type && t["enabled"] == true)
+}
+
+/// Decide how to activate a commitment (the `commitment` field of CustodianCommitmentOutput)
+fn trigger_decision(commitment: &serde_json::Value, has_passphrase: bool) -> TriggerDecision {
+    match commitment["state"].as_str().unwrap_or("") {
+        "activated" => return TriggerDecision::Activated,
+        "consensus_pending" => return TriggerDecision::AwaitConsensus,
+        "accepted" | "in-progress" => {}
+        other => {
+            return TriggerDecision::Reject(format!(
+                "Commitment is {other}; only accepted commitments can be recovered"
+            ))
+        }
+    }
+
+    if has_passphrase && enabled_trigger(commitment, "manual_signal") {
+        TriggerDecision::ManualSignal
+    } else if enabled_trigger(commitment, "m_of_n_consensus") {
+        TriggerDecision::AwaitConsensus
+    } else if enabled_trigger(commitment, "dead_mans_switch") {
+        TriggerDecision::DeadMansSwitch
+    } else if enabled_trigger(commitment, "manual_signal") {
+        TriggerDecision::Reject("A passphrase is required to activate this commitment".to_string())
+    } else {
+        TriggerDecision::Reject("Commitment has no emergency trigger the doorway can fire".to_string())
+    }
+}
+
+/// Next step after a consensus tally, or None while votes are pending
+fn consensus_outcome(status: &serde_json::Value) -> Option<Result<RecoveryStep, String>> {
+    match status["activation_status"].as_str() {
+        Some("approved") => Some(Ok(RecoveryStep::GatheringShards)),
+        Some("rejected") => Some(Err("Custodians rejected the recovery".to_string())),
+        _ => None,
+    }
+}
+
+/// Step to enter once the commitment reports a state after a trigger fired
+fn step_for_commitment_state(state: &str) -> Option<RecoveryStep> {
+    match state {
+        "activated" => Some(RecoveryStep::GatheringShards),
+        "consensus_pending" => Some(RecoveryStep::AwaitingConsensus),
+        _ => None,
+    }
+}
+
+// =============================================================================
+// Saga Driver
+// =============================================================================
+
+/// Result of running one step
+enum StepOutcome {
+    /// Moved to another step; keep going
+    Advanced,
+    /// Waiting on custodians or a retry; stop for now
+    Blocked,
+}
+
+async fn run_step(
+    state: &AppState,
+    saga: &mut RecoverySagaDoc,
+    request: &ActivateRecoveryRequest,
+    recovered: &mut Vec<RecoveredContent>,
+    caller: &Claims,
+) -> Result<StepOutcome, DoorwayError> {
+    let commitment_id = saga.commitment_id.clone();
+
+    match saga.step {
+        RecoveryStep::ValidatingTrigger => {
+            let output = call_content_store_for(state, "get_custodian_commitment", &commitment_id, Some(caller)).await?;
+            let commitment = match output {
+                Some(output) if !output.is_null() => output["commitment"].clone(),
+                _ => {
+                    saga.fail("Commitment not found");
+                    return Ok(StepOutcome::Advanced);
+                }
+            };
+
+            match trigger_decision(&commitment, request.passphrase.is_some()) {
+                TriggerDecision::Activated => saga.advance(RecoveryStep::GatheringShards),
+                TriggerDecision::AwaitConsensus => {
+                    saga.trigger_type = Some("m_of_n_consensus".to_string());
+                    saga.advance(RecoveryStep::AwaitingConsensus);
+                }
+                TriggerDecision::ManualSignal => {
+                    let input = ActivateEmergencyManualInput {
+                        commitment_id: &commitment_id,
+                        beneficiary_id: commitment["beneficiary_agent_id"].as_str().unwrap_or(""),
+                        passphrase: request.passphrase.as_deref().unwrap_or(""),
+                        reason: &saga.reason,
+                    };
+                    call_content_store_for(state, "activate_emergency_manual", &input, Some(caller)).await?;
+                    saga.trigger_type = Some("manual_signal".to_string());
+                    saga.advance(RecoveryStep::GatheringShards);
+                }
+                TriggerDecision::DeadMansSwitch => {
+                    let status =
+                        call_content_store_for(state, "trigger_dead_mans_switch", &commitment_id, Some(caller)).await?.unwrap_or_default();
+                    saga.trigger_type = Some("dead_mans_switch".to_string());
+                    match step_for_commitment_state(status["commitment_state"].as_str().unwrap_or("")) {
+                        Some(step) => saga.advance(step),
+                        None => saga.fail(format!(
+                            "The beneficiary checked in within the last {} days; recovery is not available yet",
+                            status["inactivity_days"]
+                        )),
+                    }
+                }
+                TriggerDecision::Reject(reason) => saga.fail(reason),
+            }
+            Ok(StepOutcome::Advanced)
+        }
+
+        RecoveryStep::AwaitingConsensus => {
+            let input = CheckConsensusStatusInput { commitment_id: &commitment_id };
+            let status = call_content_store_for(state, "check_consensus_status", &input, Some(caller)).await?.unwrap_or_default();
+            saga.consensus_json = Some(status.to_string());
+
+            match consensus_outcome(&status) {
+                Some(Ok(step)) => saga.advance(step),
+                Some(Err(reason)) => saga.fail(reason),
+                None => return Ok(StepOutcome::Blocked),
+            }
+            Ok(StepOutcome::Advanced)
+        }
+
+        RecoveryStep::GatheringShards => {
+            if saga.content.is_empty() {
+                saga.fail("No content_ids given to recover");
+                return Ok(StepOutcome::Advanced);
+            }
+
+            let mut all_ready = true;
+            for progress in saga.content.iter_mut() {
+                let input = ReconstructContentInput {
+                    commitment_id: &commitment_id,
+                    content_id: &progress.content_id,
+                };
+                let status =
+                    call_content_store_for(state, "get_recovery_shard_status", &input, Some(caller)).await?.unwrap_or_default();
+                progress.shards_available = status["shards_available"].as_u64().unwrap_or(0) as u32;
+                progress.shards_required = status["shards_required"].as_u64().unwrap_or(0) as u32;
+                progress.custodian_agent_ids = status["custodian_agent_ids"]
+                    .as_array()
+                    .map(|ids| ids.iter().filter_map(|id| id.as_str().map(str::to_string)).collect())
+                    .unwrap_or_default();
+                all_ready &= status["ready"].as_bool().unwrap_or(false);
+            }
+
+            if !all_ready {
+                return Ok(StepOutcome::Blocked);
+            }
+            saga.advance(RecoveryStep::Reconstructing);
+            Ok(StepOutcome::Advanced)
+        }
+
+        RecoveryStep::Reconstructing => {
+            let mut failures = Vec::new();
+            for progress in saga.content.iter_mut() {
+                let input = ReconstructContentInput {
+                    commitment_id: &commitment_id,
+                    content_id: &progress.content_id,
+                };
+                let output = call_content_store_for(state, "reconstruct_content_from_shards", &input, Some(caller))
+                    .await?
+                    .unwrap_or_default();
+                let verification = output["verification_status"].as_str().unwrap_or("unverified").to_string();
+                progress.error = output["error_message"].as_str().map(str::to_string);
+                progress.verification_status = Some(verification.clone());
+
+                if verification == "verified" {
+                    recovered.push(RecoveredContent {
+                        content_id: progress.content_id.clone(),
+                        content: output["content"].as_str().unwrap_or("").to_string(),
+                        verification_status: verification,
+                    });
+                } else {
+                    failures.push(progress.content_id.clone());
+                }
+            }
+
+            if failures.is_empty() {
+                saga.advance(RecoveryStep::Completed);
+                Ok(StepOutcome::Advanced)
+            } else {
+                // Shards may still be arriving; stay here so the next call retries
+                recovered.clear();
+                saga.last_error = Some(format!("Could not verify reconstruction of: {}", failures.join(", ")));
+                Ok(StepOutcome::Blocked)
+            }
+        }
+
+        RecoveryStep::Completed | RecoveryStep::Failed => Ok(StepOutcome::Blocked),
+    }
+}
+
+/// Drive the saga forward until it blocks or finishes, persisting after every step
+async fn drive_saga(
+    state: &AppState,
+    collection: &MongoCollection<RecoverySagaDoc>,
+    saga: &mut RecoverySagaDoc,
+    request: &ActivateRecoveryRequest,
+    caller: &Claims,
+) -> Result<Vec<RecoveredContent>, DoorwayError> {
+    let mut recovered = Vec::new();
+    saga.attempts += 1;
+
+    // A completed saga re-runs reconstruction so the caller gets the content again
+    if saga.step == RecoveryStep::Completed {
+        saga.step = RecoveryStep::Reconstructing;
+    }
+
+    for _ in 0..MAX_STEPS_PER_REQUEST {
+        let from = saga.step;
+        let outcome = match run_step(state, saga, request, &mut recovered, caller).await {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                warn!(commitment_id = %saga.commitment_id, step = ?from, error = %e, "Recovery step failed");
+                saga.last_error = Some(e.to_string());
+                StepOutcome::Blocked
+            }
+        };
+        save_saga(collection, saga).await?;
+
+        if from != saga.step {
+            info!(commitment_id = %saga.commitment_id, from = ?from, to = ?saga.step, "Recovery saga advanced");
+        }
+        if matches!(outcome, StepOutcome::Blocked) || saga.step.is_terminal() {
+            break;
+        }
+    }
+
+    Ok(recovered)
+}
+
+async fn save_saga(
+    collection: &MongoCollection<RecoverySagaDoc>,
+    saga: &mut RecoverySagaDoc,
+) -> Result<(), DoorwayError> {
+    saga.metadata.updated_at = Some(bson::DateTime::now());
+    collection
+        .inner()
+        .replace_one(doc! { "commitment_id": &saga.commitment_id }, &*saga)
+        .upsert(true)
+        .await
+        .map_err(|e| DoorwayError::Database(format!("Failed to save recovery saga: {e}")))?;
+    Ok(())
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn json_response<T: Serialize>(status: StatusCode, body: &T) -> Response<FullBody> {
+    let json = serde_json::to_vec(body).unwrap_or_default();
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .header("Cache-Control", "no-store")
+        .header("Access-Control-Allow-Origin", "*")
+        .body(Full::new(Bytes::from(json)))
+        .unwrap()
+}
+
+fn error_response(status: StatusCode, error: &str, code: &'static str) -> Response<FullBody> {
+    json_response(
+        status,
+        &ErrorResponse {
+            error: error.to_string(),
+            code,
+        },
+    )
+}
+
+// =============================================================================
+// Route Handler
+// =============================================================================
+
+/// Handle /recovery/* routes
+pub async fn handle_recovery_request(
+    req: Request<Incoming>,
+    state: Arc<AppState>,
+    path: &str,
+) -> Response<FullBody> {
+    let route = match parse_recovery_path(req.method(), path) {
+        Some(route) => route,
+        None => return error_response(StatusCode::NOT_FOUND, "Unknown recovery route", "NOT_FOUND"),
+    };
+
+    let auth_header = req
+        .headers()
+        .get(hyper::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok());
+    let claims = match require_user(&state, auth_header) {
+        Ok(claims) => claims,
+        Err(resp) => return resp,
+    };
+
+    let mongo = match &state.mongo {
+        Some(mongo) => mongo,
+        None => {
+            return error_response(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Database not available",
+                "DB_UNAVAILABLE",
+            )
+        }
+    };
+    let collection = match mongo.collection::<RecoverySagaDoc>(RECOVERY_SAGA_COLLECTION).await {
+        Ok(collection) => collection,
+        Err(e) => return error_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string(), "DB_ERROR"),
+    };
+
+    let commitment_id = match route {
+        RecoveryRoute::Activate(id) | RecoveryRoute::Status(id) => id.to_string(),
+    };
+    let existing = match collection.find_one(doc! { "commitment_id": &commitment_id }).await {
+        Ok(existing) => existing,
+        Err(e) => return error_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string(), "DB_ERROR"),
+    };
+
+    // A recovery belongs to whoever started it
+    if let Some(saga) = &existing {
+        if saga.requested_by != claims.human_id && !saga.step.is_terminal() {
+            return error_response(
+                StatusCode::CONFLICT,
+                "Another recovery for this commitment is in progress",
+                "RECOVERY_IN_PROGRESS",
+            );
+        }
+    }
+
+    match route {
+        RecoveryRoute::Status(_) => match existing {
+            Some(saga) if saga.requested_by == claims.human_id => {
+                json_response(StatusCode::OK, &RecoveryResponse::from_saga(&saga, Vec::new()))
+            }
+            _ => error_response(StatusCode::NOT_FOUND, "No recovery for this commitment", "NOT_FOUND"),
+        },
+        RecoveryRoute::Activate(_) => {
+            let body = match req.collect().await {
+                Ok(collected) => collected.to_bytes(),
+                Err(_) => return error_response(StatusCode::BAD_REQUEST, "Failed to read request body", "BAD_REQUEST"),
+            };
+            let request: ActivateRecoveryRequest = if body.is_empty() {
+                ActivateRecoveryRequest::default()
+            } else {
+                match serde_json::from_slice(&body) {
+                    Ok(request) => request,
+                    Err(e) => return error_response(StatusCode::BAD_REQUEST, &format!("Invalid JSON: {e}"), "INVALID_JSON"),
+                }
+            };
+
+            let mut saga = match existing {
+                // Resume an unfinished (or completed) recovery, picking up any new content ids
+                Some(mut saga) if saga.step != RecoveryStep::Failed && saga.requested_by == claims.human_id => {
+                    for content_id in &request.content_ids {
+                        if !saga.content.iter().any(|c| c.content_id == *content_id) {
+                            saga.content.push(RecoveryContentProgress {
+                                content_id: content_id.clone(),
+                                ..Default::default()
+                            });
+                        }
+                    }
+                    saga
+                }
+                // Start over after a failure, or begin a new recovery
+                previous => {
+                    let mut saga = RecoverySagaDoc::new(
+                        commitment_id.clone(),
+                        claims.human_id.clone(),
+                        request.reason.clone(),
+                        request.content_ids.clone(),
+                    );
+                    saga.id = previous.and_then(|p| p.id);
+                    saga
+                }
+            };
+
+            match drive_saga(&state, &collection, &mut saga, &request, &claims).await {
+                Ok(recovered) => json_response(StatusCode::OK, &RecoveryResponse::from_saga(&saga, recovered)),
+                Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string(), "DB_ERROR"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn commitment(state: &str, triggers: serde_json::Value) -> serde_json::Value {
+        json!({
+            "state": state,
+            "beneficiary_agent_id": "uhCAk-beneficiary",
+            "emergency_triggers_json": triggers.to_string(),
+        })
+    }
+
+    #[test]
+    fn test_parse_recovery_path() {
+        assert_eq!(
+            parse_recovery_path(&Method::POST, "/recovery/c-1/activate"),
+            Some(RecoveryRoute::Activate("c-1"))
+        );
+        assert_eq!(
+            parse_recovery_path(&Method::GET, "/recovery/c-1"),
+            Some(RecoveryRoute::Status("c-1"))
+        );
+        assert_eq!(parse_recovery_path(&Method::GET, "/recovery/c-1/activate"), None);
+        assert_eq!(parse_recovery_path(&Method::POST, "/recovery//activate"), None);
+        assert_eq!(parse_recovery_path(&Method::GET, "/recovery/"), None);
+    }
+
+    #[test]
+    fn test_trigger_decision_by_state() {
+        assert_eq!(
+            trigger_decision(&commitment("activated", json!([])), false),
+            TriggerDecision::Activated
+        );
+        assert_eq!(
+            trigger_decision(&commitment("consensus_pending", json!([])), false),
+            TriggerDecision::AwaitConsensus
+        );
+        assert!(matches!(
+            trigger_decision(&commitment("proposed", json!([])), false),
+            TriggerDecision::Reject(_)
+        ));
+    }
+
+    #[test]
+    fn test_trigger_decision_prefers_passphrase() {
+        let triggers = json!([
+            {"trigger_type": "manual_signal", "enabled": true},
+            {"trigger_type": "m_of_n_consensus", "enabled": true},
+        ]);
+        assert_eq!(
+            trigger_decision(&commitment("accepted", triggers.clone()), true),
+            TriggerDecision::ManualSignal
+        );
+        assert_eq!(
+            trigger_decision(&commitment("accepted", triggers), false),
+            TriggerDecision::AwaitConsensus
+        );
+    }
+
+    #[test]
+    fn test_trigger_decision_dead_mans_switch_and_disabled() {
+        let triggers = json!([
+            {"trigger_type": "m_of_n_consensus", "enabled": false},
+            {"trigger_type": "dead_mans_switch", "enabled": true},
+        ]);
+        assert_eq!(
+            trigger_decision(&commitment("accepted", triggers), false),
+            TriggerDecision::DeadMansSwitch
+        );
+
+        let manual_only = json!([{"trigger_type": "manual_signal", "enabled": true}]);
+        assert!(matches!(
+            trigger_decision(&commitment("accepted", manual_only), false),
+            TriggerDecision::Reject(_)
+        ));
+    }
+
+    #[test]
+    fn test_consensus_outcome() {
+        assert_eq!(
+            consensus_outcome(&json!({"activation_status": "approved"})),
+            Some(Ok(RecoveryStep::GatheringShards))
+        );
+        assert!(matches!(
+            consensus_outcome(&json!({"activation_status": "rejected"})),
+            Some(Err(_))
+        ));
+        assert_eq!(consensus_outcome(&json!({"activation_status": "pending"})), None);
+    }
+
+    #[test]
+    fn test_step_for_commitment_state() {
+        assert_eq!(step_for_commitment_state("activated"), Some(RecoveryStep::GatheringShards));
+        assert_eq!(
+            step_for_commitment_state("consensus_pending"),
+            Some(RecoveryStep::AwaitingConsensus)
+        );
+        assert_eq!(step_for_commitment_state("accepted"), None);
+    }
+}
@@ -0,0 +1,310 @@
+//! Reflection Journal API
+//!
+//! The signed-in learner's reflection journal from the content_store zome:
+//! their own entries, the facilitators they have explicitly shared a path's
+//! reflections with, and the learner data export. Reflections are personal,
+//! so nothing here is cached by doorway or by clients (`private, no-store`);
+//! the zome declares these reads non-cacheable as well.
+//!
+//! ## Routes
+//!
+//! - `GET /me/reflections?path_id=` - Own reflections, on one path or all
+//! - `POST /me/reflections` - Journal `{path_id, step_index, prompt?, response}`
+//! - `GET /me/reflections/grants` - Facilitator grants, active and revoked
+//! - `POST /me/reflections/grants` - Share a path's reflections `{facilitator_id, path_id}`
+//! - `DELETE /me/reflections/grants` - Stop sharing `{facilitator_id, path_id}`
+//! - `GET /me/reflections/shared?learner_id=&path_id=` - A learner's reflections
+//!   shared with the caller as facilitator
+//! - `GET /me/export` - Learner data export (includes reflections and grants);
+//!   the Markdown notes vault is served by [`notes_export`](super::notes_export)
+
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::Incoming;
+use hyper::{Method, Request, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use super::api::{error_response, private_json_response};
+use super::auth_helpers::require_user;
+use super::zome_helpers::call_content_store_for;
+use crate::auth::Claims;
+use crate::server::AppState;
+
+/// Largest request body accepted
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// Whether a path is served by this module
+pub fn is_reflection_route(path: &str) -> bool {
+    matches!(
+        path,
+        "/me/reflections" | "/me/reflections/grants" | "/me/reflections/shared" | "/me/export"
+    )
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ReflectionParams {
+    path_id: Option<String>,
+    learner_id: Option<String>,
+}
+
+/// Body of `POST /me/reflections`
+#[derive(Debug, Deserialize)]
+struct ReflectionBody {
+    path_id: String,
+    step_index: u32,
+    prompt: Option<String>,
+    response: String,
+}
+
+/// Body of `POST|DELETE /me/reflections/grants`
+#[derive(Debug, Deserialize)]
+struct GrantBody {
+    facilitator_id: String,
+    path_id: String,
+}
+
+/// Must match CreateReflectionInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct CreateReflectionInput {
+    human_id: Option<String>,
+    path_id: String,
+    step_index: u32,
+    prompt: Option<String>,
+    response: String,
+}
+
+/// Must match LearnerReflectionsInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct LearnerReflectionsInput {
+    human_id: Option<String>,
+    path_id: Option<String>,
+}
+
+/// Must match ReflectionGrantInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct ReflectionGrantInput {
+    human_id: Option<String>,
+    facilitator_id: String,
+    path_id: String,
+}
+
+/// Must match SharedReflectionsInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct SharedReflectionsInput {
+    facilitator_id: Option<String>,
+    learner_id: String,
+    path_id: String,
+}
+
+async fn read_json<T: DeserializeOwned>(
+    req: Request<Incoming>,
+) -> Result<T, Response<Full<Bytes>>> {
+    let body = Limited::new(req.into_body(), MAX_BODY_BYTES)
+        .collect()
+        .await
+        .map_err(|_| {
+            error_response(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                &format!("Requests are limited to {MAX_BODY_BYTES} bytes"),
+                "TOO_LARGE",
+            )
+        })?
+        .to_bytes();
+    serde_json::from_slice(&body).map_err(|e| {
+        error_response(
+            StatusCode::BAD_REQUEST,
+            &format!("Invalid request: {e}"),
+            "INVALID_JSON",
+        )
+    })
+}
+
+/// Call the zome and answer privately
+async fn zome_response<I: Serialize>(
+    state: &AppState,
+    fn_name: &str,
+    input: &I,
+    empty: Value,
+    caller: &Claims,
+) -> Response<Full<Bytes>> {
+    match call_content_store_for(state, fn_name, input, Some(caller)).await {
+        Ok(data) => private_json_response(
+            &data.filter(|d| !d.is_null()).unwrap_or(empty),
+            "private, no-store",
+        ),
+        Err(e) => {
+            warn!(fn_name, error = ?e, "Reflection call failed");
+            error_response(
+                StatusCode::BAD_GATEWAY,
+                "Reflection request failed",
+                "ZOME_ERROR",
+            )
+        }
+    }
+}
+
+/// Handle /me/reflections, /me/reflections/grants, /me/reflections/shared and /me/export
+pub async fn handle_reflections(
+    req: Request<Incoming>,
+    state: Arc<AppState>,
+) -> Response<Full<Bytes>> {
+    let auth_header = req
+        .headers()
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string());
+    let claims = match require_user(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+    let human_id = Some(claims.human_id.clone());
+    let params: ReflectionParams =
+        serde_urlencoded::from_str(req.uri().query().unwrap_or("")).unwrap_or_default();
+    let method = req.method().clone();
+    let path = req.uri().path().to_string();
+
+    match (method, path.as_str()) {
+        (Method::GET, "/me/reflections") => {
+            let input = LearnerReflectionsInput {
+                human_id,
+                path_id: params.path_id,
+            };
+            zome_response(
+                &state,
+                "get_learner_reflections",
+                &input,
+                Value::Array(vec![]),
+                &claims,
+            )
+            .await
+        }
+        (Method::POST, "/me/reflections") => {
+            let body: ReflectionBody = match read_json(req).await {
+                Ok(body) => body,
+                Err(response) => return response,
+            };
+            if body.response.trim().is_empty() {
+                return error_response(
+                    StatusCode::BAD_REQUEST,
+                    "response cannot be empty",
+                    "BAD_REQUEST",
+                );
+            }
+            let input = CreateReflectionInput {
+                human_id,
+                path_id: body.path_id,
+                step_index: body.step_index,
+                prompt: body.prompt,
+                response: body.response,
+            };
+            zome_response(&state, "create_reflection", &input, Value::Null, &claims).await
+        }
+        (Method::GET, "/me/reflections/grants") => {
+            zome_response(
+                &state,
+                "get_reflection_grants",
+                &human_id,
+                Value::Array(vec![]),
+                &claims,
+            )
+            .await
+        }
+        (Method::POST, "/me/reflections/grants") | (Method::DELETE, "/me/reflections/grants") => {
+            let revoke = req.method() == Method::DELETE;
+            let body: GrantBody = match read_json(req).await {
+                Ok(body) => body,
+                Err(response) => return response,
+            };
+            if body.facilitator_id == claims.human_id {
+                return error_response(
+                    StatusCode::BAD_REQUEST,
+                    "Reflections can only be shared with someone else",
+                    "BAD_REQUEST",
+                );
+            }
+            info!(
+                learner = %claims.human_id,
+                facilitator = %body.facilitator_id,
+                path_id = %body.path_id,
+                revoke,
+                "Reflection sharing changed"
+            );
+            let input = ReflectionGrantInput {
+                human_id,
+                facilitator_id: body.facilitator_id,
+                path_id: body.path_id,
+            };
+            let fn_name = if revoke {
+                "revoke_reflection_access"
+            } else {
+                "grant_reflection_access"
+            };
+            zome_response(&state, fn_name, &input, Value::Null, &claims).await
+        }
+        (Method::GET, "/me/reflections/shared") => {
+            let (Some(learner_id), Some(path_id)) = (params.learner_id, params.path_id) else {
+                return error_response(
+                    StatusCode::BAD_REQUEST,
+                    "learner_id and path_id are required",
+                    "BAD_REQUEST",
+                );
+            };
+            let input = SharedReflectionsInput {
+                facilitator_id: human_id,
+                learner_id,
+                path_id,
+            };
+            match call_content_store_for(&state, "get_shared_reflections", &input, Some(&claims))
+                .await
+            {
+                Ok(data) => private_json_response(
+                    &data.unwrap_or_else(|| Value::Array(vec![])),
+                    "private, no-store",
+                ),
+                // The zome refuses without an active grant; don't reveal whether reflections exist
+                Err(e) => {
+                    warn!(facilitator = %claims.human_id, error = ?e, "Shared reflections refused");
+                    error_response(
+                        StatusCode::FORBIDDEN,
+                        "These reflections have not been shared with you",
+                        "FORBIDDEN",
+                    )
+                }
+            }
+        }
+        (Method::GET, "/me/export") => {
+            zome_response(
+                &state,
+                "export_learner_data",
+                &human_id,
+                Value::Null,
+                &claims,
+            )
+            .await
+        }
+        _ => error_response(
+            StatusCode::METHOD_NOT_ALLOWED,
+            "Method not allowed",
+            "METHOD_NOT_ALLOWED",
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_reflection_route() {
+        assert!(is_reflection_route("/me/reflections"));
+        assert!(is_reflection_route("/me/reflections/grants"));
+        assert!(is_reflection_route("/me/reflections/shared"));
+        assert!(is_reflection_route("/me/export"));
+        assert!(!is_reflection_route("/me/reflections/other"));
+        assert!(!is_reflection_route("/me/recommendations"));
+    }
+}
@@ -0,0 +1,182 @@
+//! Region Hint Route
+//!
+//! Tells apps which [doorway](crate::services::regions) to connect to. The
+//! client names its region (`region`, or the `X-Client-Region` header set by
+//! a geo-aware load balancer) and/or its location (`lat`, `lon`); the
+//! healthy doorways heard over NATS are ranked for it, this one included.
+//!
+//! Apps that can't read the hint pass `redirect` instead and are sent to the
+//! same path on the nearest doorway. When that is this doorway, or the
+//! nearest doorway has no public URL, the redirect stays here.
+//!
+//! ## Routes
+//!
+//! - `GET /region-hint?region=&lat=&lon=` - Ranked doorways: `{region, node_id, nearest, doorways}`
+//! - `GET /region-hint?redirect=/path` - `307` to `/path` on the nearest doorway
+
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::header::{CACHE_CONTROL, LOCATION};
+use hyper::{Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Instant;
+
+use super::api::{error_response, json_response};
+use crate::proxy::admission::Pressure;
+use crate::server::AppState;
+use crate::services::regions::{GeoPoint, RankedDoorway, RegionHint};
+
+/// Header a geo-aware load balancer may set to the client's region
+pub const CLIENT_REGION_HEADER: &str = "x-client-region";
+
+#[derive(Debug, Default, Deserialize)]
+struct RegionHintParams {
+    region: Option<String>,
+    lat: Option<f64>,
+    lon: Option<f64>,
+    redirect: Option<String>,
+}
+
+/// Response of `GET /region-hint`
+#[derive(Debug, Serialize)]
+struct RegionHintResponse {
+    /// Region of the doorway answering
+    region: String,
+    node_id: String,
+    nearest: RankedDoorway,
+    doorways: Vec<RankedDoorway>,
+}
+
+/// Whether this doorway can take clients, and its conductor calls in flight
+///
+/// Healthy means the conductor is connected and not under high pressure.
+pub fn doorway_health(state: &AppState) -> (bool, usize) {
+    let Some(ref pool) = state.pool else {
+        return (false, 0);
+    };
+    let load = pool.load();
+    let pressured = state
+        .admission
+        .as_ref()
+        .is_some_and(|admission| admission.pressure(&load) == Pressure::High);
+    (pool.is_healthy() && !pressured, load.in_flight)
+}
+
+/// Only same-origin paths may be redirected to
+fn redirect_path(path: &str) -> Option<&str> {
+    (path.starts_with('/') && !path.starts_with("//") && !path.contains('\\')).then_some(path)
+}
+
+fn redirect(location: &str) -> Response<Full<Bytes>> {
+    Response::builder()
+        .status(StatusCode::TEMPORARY_REDIRECT)
+        .header(LOCATION, location)
+        .header(CACHE_CONTROL, "no-store")
+        .body(Full::new(Bytes::new()))
+        .unwrap()
+}
+
+/// Handle GET /region-hint
+pub fn handle_region_hint(
+    state: Arc<AppState>,
+    query: Option<&str>,
+    client_region: Option<String>,
+) -> Response<Full<Bytes>> {
+    let Some(ref directory) = state.regions else {
+        return error_response(
+            StatusCode::NOT_FOUND,
+            "Region awareness is not configured on this doorway",
+            "REGIONS_DISABLED",
+        );
+    };
+    let params: RegionHintParams = match serde_urlencoded::from_str(query.unwrap_or("")) {
+        Ok(params) => params,
+        Err(e) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                &format!("Invalid query parameters: {e}"),
+                "INVALID_QUERY",
+            )
+        }
+    };
+    let location = match (params.lat, params.lon) {
+        (None, None) => None,
+        (Some(lat), Some(lon)) => match GeoPoint::new(lat, lon) {
+            Some(point) => Some(point),
+            None => {
+                return error_response(
+                    StatusCode::BAD_REQUEST,
+                    "lat must be within ±90 and lon within ±180",
+                    "INVALID_LOCATION",
+                )
+            }
+        },
+        _ => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                "lat and lon must be given together",
+                "INVALID_LOCATION",
+            )
+        }
+    };
+    let hint = RegionHint {
+        region: params
+            .region
+            .or(client_region)
+            .map(|r| r.trim().to_string())
+            .filter(|r| !r.is_empty()),
+        location,
+    };
+
+    let (healthy, in_flight) = doorway_health(&state);
+    let doorways = directory.rank(
+        directory.presence(healthy, in_flight),
+        &hint,
+        Instant::now(),
+    );
+    let Some(nearest) = doorways.first().cloned() else {
+        return error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "No doorway available",
+            "NO_DOORWAY",
+        );
+    };
+
+    if let Some(path) = params.redirect {
+        let Some(path) = redirect_path(&path) else {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                "redirect must be a path on this site",
+                "INVALID_REDIRECT",
+            );
+        };
+        return match nearest.url {
+            Some(ref url) if !nearest.current => {
+                redirect(&format!("{}{path}", url.trim_end_matches('/')))
+            }
+            _ => redirect(path),
+        };
+    }
+
+    let response = RegionHintResponse {
+        region: directory.region().to_string(),
+        node_id: directory.node_id().to_string(),
+        nearest,
+        doorways,
+    };
+    json_response(serde_json::to_vec(&response).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redirect_path() {
+        assert_eq!(redirect_path("/lamad/path/1"), Some("/lamad/path/1"));
+        assert_eq!(redirect_path("//evil.example"), None);
+        assert_eq!(redirect_path("/\\evil.example"), None);
+        assert_eq!(redirect_path("https://evil.example"), None);
+    }
+}
@@ -0,0 +1,231 @@
+//! Retention Routes
+//!
+//! Admin access to the [retention policies](crate::worker::retention): list
+//! what's configured, preview or run a policy, and read the audit trail.
+//!
+//! ## Routes
+//!
+//! - `GET /admin/retention/policies` - Configured policies
+//! - `POST /admin/retention/run` - Run `{policy_id, dry_run?}`; dry runs (the default) only
+//!   count matches and list a sample of them
+//! - `GET /admin/retention/audit?policy_id=&limit=&cursor=` - Policy runs, newest first,
+//!   in the [pagination](super::pagination) envelope
+
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::Incoming;
+use hyper::{Request, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use super::api::{error_response, json_response, not_enabled_response};
+use super::auth_helpers::require_admin;
+use super::pagination::{page_response, Page, PageRequest};
+use crate::server::AppState;
+
+/// Largest request body accepted
+const MAX_BODY_BYTES: usize = 4 * 1024;
+
+/// Message when RETENTION_POLICIES or MongoDB is missing
+const NOT_ENABLED: &str = "Retention not enabled (requires RETENTION_POLICIES and MongoDB)";
+
+/// Default and largest page of audit records
+const DEFAULT_LIMIT: usize = 50;
+const MAX_LIMIT: usize = 500;
+
+/// Body of `POST /admin/retention/run`
+#[derive(Debug, Deserialize)]
+struct RunBody {
+    policy_id: String,
+    #[serde(default = "default_dry_run")]
+    dry_run: bool,
+}
+
+fn default_dry_run() -> bool {
+    true
+}
+
+/// Query of `GET /admin/retention/audit`
+#[derive(Debug, Default, Deserialize)]
+struct AuditParams {
+    policy_id: Option<String>,
+}
+
+/// An audit record as listed
+#[derive(Debug, Serialize)]
+struct AuditView {
+    policy_id: String,
+    action: String,
+    dry_run: bool,
+    started_at: Option<String>,
+    finished_at: Option<String>,
+    cutoff: Option<String>,
+    matched: u64,
+    affected: u64,
+    sample_ids: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+fn bad_request(message: &str) -> Response<Full<Bytes>> {
+    error_response(StatusCode::BAD_REQUEST, message, "BAD_REQUEST")
+}
+
+/// Handle GET /admin/retention/policies
+pub fn handle_retention_policies(
+    state: Arc<AppState>,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    if let Err(response) = require_admin(&state, auth_header.as_deref()) {
+        return response;
+    }
+    let Some(ref engine) = state.retention else {
+        return not_enabled_response(StatusCode::SERVICE_UNAVAILABLE, NOT_ENABLED);
+    };
+
+    let policies: Vec<serde_json::Value> = engine
+        .policies()
+        .iter()
+        .map(|policy| {
+            let mut view = serde_json::to_value(policy).unwrap_or_default();
+            view["doc_types"] = serde_json::json!(policy.doc_types());
+            view
+        })
+        .collect();
+    json_response(
+        serde_json::to_vec(&serde_json::json!({ "policies": policies })).unwrap_or_default(),
+    )
+}
+
+/// Handle POST /admin/retention/run
+pub async fn handle_retention_run(
+    req: Request<Incoming>,
+    state: Arc<AppState>,
+) -> Response<Full<Bytes>> {
+    let auth_header = req
+        .headers()
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string());
+    if let Err(response) = require_admin(&state, auth_header.as_deref()) {
+        return response;
+    }
+    let Some(engine) = state.retention.clone() else {
+        return not_enabled_response(StatusCode::SERVICE_UNAVAILABLE, NOT_ENABLED);
+    };
+
+    let body = match Limited::new(req.into_body(), MAX_BODY_BYTES)
+        .collect()
+        .await
+    {
+        Ok(collected) => collected.to_bytes(),
+        Err(_) => {
+            return error_response(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                &format!("Requests are limited to {MAX_BODY_BYTES} bytes"),
+                "TOO_LARGE",
+            )
+        }
+    };
+    let run: RunBody = match serde_json::from_slice(&body) {
+        Ok(run) => run,
+        Err(e) => return bad_request(&format!("Invalid request: {e}")),
+    };
+    let Some(policy) = engine.policy(&run.policy_id) else {
+        return error_response(
+            StatusCode::NOT_FOUND,
+            &format!("No retention policy '{}'", run.policy_id),
+            "NOT_FOUND",
+        );
+    };
+
+    let report = engine.run(policy, run.dry_run).await;
+    info!(
+        policy_id = %report.policy_id,
+        dry_run = report.dry_run,
+        matched = report.matched,
+        affected = report.affected,
+        "Retention policy run by admin"
+    );
+    if report.error.is_some() {
+        let mut response = json_response(serde_json::to_vec(&report).unwrap_or_default());
+        *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+        return response;
+    }
+    json_response(serde_json::to_vec(&report).unwrap_or_default())
+}
+
+/// Handle GET /admin/retention/audit
+pub async fn handle_retention_audit(
+    state: Arc<AppState>,
+    query: Option<&str>,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    if let Err(response) = require_admin(&state, auth_header.as_deref()) {
+        return response;
+    }
+    let Some(ref engine) = state.retention else {
+        return not_enabled_response(StatusCode::SERVICE_UNAVAILABLE, NOT_ENABLED);
+    };
+
+    let params: AuditParams = match serde_urlencoded::from_str(query.unwrap_or("")) {
+        Ok(params) => params,
+        Err(e) => return bad_request(&format!("Invalid query: {e}")),
+    };
+    let page = match PageRequest::from_query(query, DEFAULT_LIMIT, MAX_LIMIT) {
+        Ok(page) => page,
+        Err(msg) => return bad_request(&msg),
+    };
+    let policy_id = params.policy_id.as_deref().filter(|id| !id.is_empty());
+
+    match engine
+        .audit_log(policy_id, page.offset as u64, page.probe_limit() as i64)
+        .await
+    {
+        Ok(records) => {
+            let runs: Vec<AuditView> = records
+                .into_iter()
+                .map(|record| AuditView {
+                    policy_id: record.policy_id,
+                    action: record.action,
+                    dry_run: record.dry_run,
+                    started_at: record
+                        .metadata
+                        .created_at
+                        .and_then(|t| t.try_to_rfc3339_string().ok()),
+                    finished_at: record.finished_at.map(|t| t.to_rfc3339()),
+                    cutoff: record.cutoff.map(|t| t.to_rfc3339()),
+                    matched: record.matched,
+                    affected: record.affected,
+                    sample_ids: record.sample_ids,
+                    error: record.error,
+                })
+                .collect();
+            page_response(&Page::from_probe(runs, page))
+        }
+        Err(e) => {
+            warn!(error = %e, "Failed to read retention audit");
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to read retention audit",
+                "DATABASE_ERROR",
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_body_defaults_to_dry_run() {
+        let run: RunBody = serde_json::from_str(r#"{"policy_id": "erased-users"}"#).unwrap();
+        assert!(run.dry_run);
+        let run: RunBody =
+            serde_json::from_str(r#"{"policy_id": "erased-users", "dry_run": false}"#).unwrap();
+        assert!(!run.dry_run);
+        assert!(serde_json::from_str::<RunBody>("{}").is_err());
+    }
+}
@@ -20,13 +20,14 @@
 //! elohim-storage is the authoritative blob store.
 
 use bytes::Bytes;
-use http_body_util::{BodyExt, Full};
+use http_body_util::{BodyExt, Full, Limited};
 use hyper::body::Incoming;
 use hyper::{Request, Response, StatusCode};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tracing::{debug, error, info, warn};
 
+use crate::server::limits::{payload_too_large, BodyClass};
 use crate::server::AppState;
 
 // =============================================================================
@@ -124,9 +125,15 @@ pub async fn handle_seed_blob(
         );
     }
 
-    // Read request body
-    let body = match req.collect().await {
+    // Read request body, up to the import limit
+    let limit = state.body_limits.limit(BodyClass::Import);
+    let body = match Limited::new(req.into_body(), limit).collect().await {
         Ok(collected) => collected.to_bytes(),
+        Err(e) if e.is::<http_body_util::LengthLimitError>() => {
+            state.body_limits.record_rejection(BodyClass::Import);
+            warn!(hash = %expected_hash, limit, "Blob body over limit");
+            return payload_too_large(limit);
+        }
         Err(e) => {
             warn!(error = %e, "Failed to read blob body");
             return error_response(StatusCode::BAD_REQUEST, "Failed to read request body");
@@ -190,7 +197,7 @@ pub async fn handle_seed_blob(
 /// Forward a blob to elohim-storage
 ///
 /// If body is None, reads from local cache.
-async fn forward_to_storage(state: &AppState, hash: &str, body: Option<&Bytes>) -> bool {
+pub(crate) async fn forward_to_storage(state: &AppState, hash: &str, body: Option<&Bytes>) -> bool {
     let storage_url = match &state.args.storage_url {
         Some(url) => url.clone(),
         None => {
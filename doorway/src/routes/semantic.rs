@@ -0,0 +1,434 @@
+//! Semantic Related-Content API
+//!
+//! Serves similarity lookups over the vectors written by the
+//! [embedding worker](crate::worker::embeddings), and the steward review
+//! queue for the relationships it suggests.
+//!
+//! ## Routes
+//!
+//! - `GET /content/{id}/semantic-related?limit=` - Content that reads alike
+//! - `GET /steward/relationship-suggestions?status=&limit=&cursor=` - Suggestions (default `pending`)
+//! - `POST /steward/relationship-suggestions/{id}/approve` - Create the relationship
+//! - `POST /steward/relationship-suggestions/{id}/reject` - Dismiss the suggestion
+//!
+//! Only commons content is embedded, so lookups are public. Approving a
+//! suggestion calls `content_store::create_relationship` with
+//! `relationship_type: "RELATES_TO"`, `inference_source: "semantic"` and
+//! the similarity as confidence. Reviewing needs a steward or admin token.
+
+use bson::oid::ObjectId;
+use bytes::Bytes;
+use chrono::Utc;
+use http_body_util::Full;
+use hyper::{Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use super::api::{error_response, json_response};
+use super::auth_helpers::require_steward;
+use super::pagination::{page_response, Page, PageRequest};
+use super::zome_helpers::call_content_store_for;
+use crate::db::schemas::{
+    RelationshipSuggestionDoc, SuggestionStatus, RELATIONSHIP_SUGGESTION_COLLECTION,
+};
+use crate::db::MongoCollection;
+use crate::server::AppState;
+
+/// Default number of related items
+const DEFAULT_RELATED_LIMIT: usize = 10;
+
+/// Largest related `limit` accepted
+const MAX_RELATED_LIMIT: usize = 50;
+
+/// Default suggestion page size
+const DEFAULT_LIMIT: usize = 50;
+
+/// Largest suggestion `limit` accepted
+const MAX_LIMIT: usize = 200;
+
+/// Parse `/content/{id}/semantic-related`
+pub fn parse_semantic_related_path(path: &str) -> Option<&str> {
+    path.strip_prefix("/content/")?
+        .strip_suffix("/semantic-related")
+        .filter(|id| !id.is_empty() && !id.contains('/'))
+}
+
+/// Review decision on a suggestion
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SuggestionAction {
+    Approve,
+    Reject,
+}
+
+/// Parse `/steward/relationship-suggestions/{id}/{approve|reject}`
+pub fn parse_suggestion_action_path(path: &str) -> Option<(&str, SuggestionAction)> {
+    let rest = path.strip_prefix("/steward/relationship-suggestions/")?;
+    let (id, action) = rest.split_once('/')?;
+    let action = match action {
+        "approve" => SuggestionAction::Approve,
+        "reject" => SuggestionAction::Reject,
+        _ => return None,
+    };
+    (!id.is_empty()).then_some((id, action))
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct LimitParams {
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct RelatedResponse<'a> {
+    content_id: &'a str,
+    related: Vec<crate::worker::embeddings::SemanticMatch>,
+}
+
+/// Handle GET /content/{id}/semantic-related
+pub async fn handle_semantic_related(
+    state: Arc<AppState>,
+    content_id: &str,
+    query: Option<&str>,
+) -> Response<Full<Bytes>> {
+    let Some(ref index) = state.semantic else {
+        return error_response(
+            StatusCode::NOT_FOUND,
+            "Semantic search is not enabled",
+            "NOT_ENABLED",
+        );
+    };
+
+    let params: LimitParams = serde_urlencoded::from_str(query.unwrap_or("")).unwrap_or_default();
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_RELATED_LIMIT)
+        .clamp(1, MAX_RELATED_LIMIT);
+
+    match index.related(content_id, limit).await {
+        Ok(Some(related)) => {
+            let body = RelatedResponse {
+                content_id,
+                related,
+            };
+            Response::builder()
+                .status(StatusCode::OK)
+                .header("Content-Type", "application/json")
+                .header("Cache-Control", "public, max-age=300")
+                .body(Full::new(Bytes::from(
+                    serde_json::to_vec(&body).unwrap_or_default(),
+                )))
+                .unwrap()
+        }
+        Ok(None) => error_response(
+            StatusCode::NOT_FOUND,
+            "Content has not been embedded (only commons content is)",
+            "NOT_FOUND",
+        ),
+        Err(e) => {
+            warn!(content_id, error = %e, "Semantic lookup failed");
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Semantic lookup failed",
+                "DATABASE_ERROR",
+            )
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SuggestionParams {
+    status: Option<String>,
+}
+
+/// Parsed suggestion filters
+#[derive(Debug, PartialEq)]
+struct SuggestionQuery {
+    status: SuggestionStatus,
+    page: PageRequest,
+}
+
+fn parse_query(query: Option<&str>) -> Result<SuggestionQuery, String> {
+    let params: SuggestionParams = serde_urlencoded::from_str(query.unwrap_or(""))
+        .map_err(|e| format!("Invalid query parameters: {e}"))?;
+
+    let status = match params.status.as_deref() {
+        None | Some("") => SuggestionStatus::Pending,
+        Some(value) => SuggestionStatus::parse(value).ok_or_else(|| {
+            format!("Invalid status '{value}', expected pending, approved or rejected")
+        })?,
+    };
+
+    Ok(SuggestionQuery {
+        status,
+        page: PageRequest::from_query(query, DEFAULT_LIMIT, MAX_LIMIT)?,
+    })
+}
+
+/// Suggestion as served to stewards
+#[derive(Debug, Serialize)]
+struct SuggestionView<'a> {
+    id: String,
+    source_id: &'a str,
+    source_title: &'a str,
+    target_id: &'a str,
+    target_title: &'a str,
+    similarity: f64,
+    model: &'a str,
+    status: SuggestionStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reviewed_by: Option<&'a str>,
+}
+
+impl<'a> From<&'a RelationshipSuggestionDoc> for SuggestionView<'a> {
+    fn from(doc: &'a RelationshipSuggestionDoc) -> Self {
+        Self {
+            id: doc.id.map(|id| id.to_hex()).unwrap_or_default(),
+            source_id: &doc.source_id,
+            source_title: &doc.source_title,
+            target_id: &doc.target_id,
+            target_title: &doc.target_title,
+            similarity: doc.similarity,
+            model: &doc.model,
+            status: doc.status,
+            reviewed_by: doc.reviewed_by.as_deref(),
+        }
+    }
+}
+
+/// Must match CreateRelationshipInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct CreateRelationshipInput {
+    source_id: String,
+    target_id: String,
+    relationship_type: String,
+    confidence: f64,
+    inference_source: String,
+    metadata_json: Option<String>,
+}
+
+#[allow(clippy::result_large_err)]
+async fn suggestion_collection(
+    state: &AppState,
+) -> Result<MongoCollection<RelationshipSuggestionDoc>, Response<Full<Bytes>>> {
+    let unavailable = || {
+        error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Suggestion storage not available",
+            "DATABASE_UNAVAILABLE",
+        )
+    };
+    let Some(ref mongo) = state.mongo else {
+        return Err(unavailable());
+    };
+    mongo
+        .collection::<RelationshipSuggestionDoc>(RELATIONSHIP_SUGGESTION_COLLECTION)
+        .await
+        .map_err(|e| {
+            warn!(error = %e, "Suggestion collection unavailable");
+            unavailable()
+        })
+}
+
+/// Handle GET /steward/relationship-suggestions
+pub async fn handle_relationship_suggestions(
+    state: Arc<AppState>,
+    query: Option<&str>,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    if let Err(response) = require_steward(&state, auth_header.as_deref()) {
+        return response;
+    }
+
+    let query = match parse_query(query) {
+        Ok(query) => query,
+        Err(msg) => return error_response(StatusCode::BAD_REQUEST, &msg, "INVALID_QUERY"),
+    };
+
+    let collection = match suggestion_collection(&state).await {
+        Ok(collection) => collection,
+        Err(response) => return response,
+    };
+
+    let filter = bson::doc! { "status": bson::to_bson(&query.status).unwrap_or_default() };
+    let mut suggestions = match collection.find_many(filter).await {
+        Ok(suggestions) => suggestions,
+        Err(e) => {
+            warn!(error = %e, "Failed to load relationship suggestions");
+            return error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to load relationship suggestions",
+                "DATABASE_ERROR",
+            );
+        }
+    };
+    suggestions.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+
+    let page = Page::from_all(suggestions, query.page);
+    page_response(&page.map_ref(SuggestionView::from))
+}
+
+/// Handle POST /steward/relationship-suggestions/{id}/{approve|reject}
+pub async fn handle_review_suggestion(
+    state: Arc<AppState>,
+    suggestion_id: &str,
+    action: SuggestionAction,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    let claims = match require_steward(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let Ok(object_id) = ObjectId::parse_str(suggestion_id) else {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "Invalid suggestion id",
+            "INVALID_ID",
+        );
+    };
+
+    let collection = match suggestion_collection(&state).await {
+        Ok(collection) => collection,
+        Err(response) => return response,
+    };
+
+    let suggestion = match collection.find_one(bson::doc! { "_id": object_id }).await {
+        Ok(Some(suggestion)) => suggestion,
+        Ok(None) => {
+            return error_response(StatusCode::NOT_FOUND, "Suggestion not found", "NOT_FOUND")
+        }
+        Err(e) => {
+            warn!(suggestion_id, error = %e, "Failed to load suggestion");
+            return error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to load suggestion",
+                "DATABASE_ERROR",
+            );
+        }
+    };
+    if suggestion.status != SuggestionStatus::Pending {
+        return error_response(
+            StatusCode::CONFLICT,
+            "Suggestion has already been reviewed",
+            "ALREADY_REVIEWED",
+        );
+    }
+
+    let status = match action {
+        SuggestionAction::Reject => SuggestionStatus::Rejected,
+        SuggestionAction::Approve => {
+            let input = CreateRelationshipInput {
+                source_id: suggestion.source_id.clone(),
+                target_id: suggestion.target_id.clone(),
+                relationship_type: "RELATES_TO".to_string(),
+                confidence: suggestion.similarity.clamp(0.0, 1.0),
+                inference_source: "semantic".to_string(),
+                metadata_json: serde_json::to_string(&serde_json::json!({
+                    "model": suggestion.model,
+                    "approved_by": claims.human_id,
+                }))
+                .ok(),
+            };
+            // Goes through the pool so cached relationship reads are invalidated
+            if let Err(e) =
+                call_content_store_for(&state, "create_relationship", &input, Some(&claims)).await
+            {
+                warn!(suggestion_id, error = ?e, "Failed to create relationship");
+                return error_response(
+                    StatusCode::BAD_GATEWAY,
+                    "Failed to create relationship",
+                    "ZOME_ERROR",
+                );
+            }
+            SuggestionStatus::Approved
+        }
+    };
+
+    let update = bson::doc! {
+        "$set": {
+            "status": bson::to_bson(&status).unwrap_or_default(),
+            "reviewed_by": &claims.human_id,
+            "reviewed_at": bson::to_bson(&Utc::now()).unwrap_or_default(),
+            "metadata.updated_at": bson::DateTime::now(),
+        }
+    };
+    if let Err(e) = collection
+        .update_one(bson::doc! { "_id": object_id }, update)
+        .await
+    {
+        warn!(suggestion_id, error = %e, "Failed to record suggestion review");
+        return error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to record review",
+            "DATABASE_ERROR",
+        );
+    }
+
+    info!(
+        suggestion_id,
+        source_id = %suggestion.source_id,
+        target_id = %suggestion.target_id,
+        status = ?status,
+        reviewer = %claims.human_id,
+        "Relationship suggestion reviewed"
+    );
+    json_response(
+        serde_json::to_vec(&serde_json::json!({
+            "id": suggestion_id,
+            "status": status,
+        }))
+        .unwrap_or_default(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_semantic_related_path() {
+        assert_eq!(
+            parse_semantic_related_path("/content/intro/semantic-related"),
+            Some("intro")
+        );
+        assert_eq!(
+            parse_semantic_related_path("/content//semantic-related"),
+            None
+        );
+        assert_eq!(parse_semantic_related_path("/content/intro/captions"), None);
+    }
+
+    #[test]
+    fn test_parse_suggestion_action_path() {
+        assert_eq!(
+            parse_suggestion_action_path("/steward/relationship-suggestions/abc/approve"),
+            Some(("abc", SuggestionAction::Approve))
+        );
+        assert_eq!(
+            parse_suggestion_action_path("/steward/relationship-suggestions/abc/reject"),
+            Some(("abc", SuggestionAction::Reject))
+        );
+        assert_eq!(
+            parse_suggestion_action_path("/steward/relationship-suggestions/abc/delete"),
+            None
+        );
+        assert_eq!(
+            parse_suggestion_action_path("/steward/relationship-suggestions"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_query() {
+        let defaults = parse_query(None).unwrap();
+        assert_eq!(defaults.status, SuggestionStatus::Pending);
+        assert_eq!(defaults.page.limit, DEFAULT_LIMIT);
+
+        let query = parse_query(Some("status=rejected&limit=999&offset=5")).unwrap();
+        assert_eq!(query.status, SuggestionStatus::Rejected);
+        assert_eq!(query.page.limit, MAX_LIMIT);
+        assert_eq!(query.page.offset, 5);
+
+        assert!(parse_query(Some("status=maybe")).is_err());
+    }
+}
@@ -0,0 +1,316 @@
+//! Learning Sessions and Calendar Feeds
+//!
+//! Learners block study sessions for a path; facilitators publish cohort
+//! sessions that show up for the learners sharing the path with them (see
+//! [`calendar`](crate::services::calendar)). Attendees get a
+//! `session_reminder` notification shortly before a session starts.
+//!
+//! ## Routes
+//!
+//! - `POST /sessions` - Schedule `{path_id, title, starts_at, kind?, duration_minutes?, description?, location?}`;
+//!   `kind` is `study` (default) or `cohort`, `starts_at` is RFC 3339
+//! - `DELETE /sessions/{id}` - Cancel a session you organized
+//! - `GET /me/sessions` - The caller's calendar, with a `feed_url` when feeds are enabled
+//! - `GET /me/calendar.ics?token=` - The caller's calendar as iCalendar; calendar
+//!   apps pass the feed token, everyone else a bearer token
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::Incoming;
+use hyper::{Request, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use super::api::{error_response, json_response, overloaded_response};
+use super::auth_helpers::require_user;
+use super::zome_helpers::call_content_store_for;
+use crate::auth::Claims;
+use crate::server::AppState;
+use crate::services::calendar::{render_calendar, LearningSession, LearningSessionOutput};
+use crate::types::{DoorwayError, Result};
+
+/// Largest request body accepted
+const MAX_BODY_BYTES: usize = 8 * 1024;
+
+/// Session length when none is given, in minutes
+const DEFAULT_DURATION_MINUTES: u32 = 60;
+
+/// Parse `/sessions/{id}`
+pub fn parse_session_path(path: &str) -> Option<&str> {
+    let id = path.strip_prefix("/sessions/")?;
+    (!id.is_empty() && !id.contains('/')).then_some(id)
+}
+
+fn default_kind() -> String {
+    "study".to_string()
+}
+
+/// Body of `POST /sessions`
+#[derive(Debug, Deserialize)]
+struct ScheduleBody {
+    path_id: String,
+    title: String,
+    starts_at: DateTime<Utc>,
+    #[serde(default = "default_kind")]
+    kind: String,
+    #[serde(default)]
+    duration_minutes: Option<u32>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    location: Option<String>,
+}
+
+/// Must match ScheduleLearningSessionInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct ScheduleLearningSessionInput<'a> {
+    organizer_id: &'a str,
+    kind: String,
+    path_id: String,
+    title: String,
+    description: Option<String>,
+    location: Option<String>,
+    starts_at_micros: i64,
+    duration_minutes: u32,
+}
+
+/// Must match CancelLearningSessionInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct CancelLearningSessionInput<'a> {
+    session_id: &'a str,
+    organizer_id: &'a str,
+}
+
+/// Response of `GET /me/sessions`
+#[derive(Debug, Serialize)]
+struct CalendarResponse {
+    sessions: Vec<LearningSession>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    feed_url: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct FeedParams {
+    token: Option<String>,
+}
+
+/// A learner's sessions, as the zome returns them
+async fn load_calendar(
+    state: &AppState,
+    learner: &str,
+    caller: Option<&Claims>,
+) -> Result<Vec<LearningSession>> {
+    let data = call_content_store_for(state, "get_learner_calendar", &learner, caller).await?;
+    let outputs: Vec<LearningSessionOutput> = match data {
+        Some(value) => serde_json::from_value(value)?,
+        None => Vec::new(),
+    };
+    Ok(outputs.into_iter().map(|o| o.session).collect())
+}
+
+/// Handle POST /sessions
+pub async fn handle_schedule_session(
+    req: Request<Incoming>,
+    state: Arc<AppState>,
+) -> Response<Full<Bytes>> {
+    let auth_header = req
+        .headers()
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string());
+    let claims = match require_user(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let body = match Limited::new(req.into_body(), MAX_BODY_BYTES)
+        .collect()
+        .await
+    {
+        Ok(collected) => collected.to_bytes(),
+        Err(_) => {
+            return error_response(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                &format!("Requests are limited to {MAX_BODY_BYTES} bytes"),
+                "TOO_LARGE",
+            )
+        }
+    };
+    let schedule: ScheduleBody = match serde_json::from_slice(&body) {
+        Ok(schedule) => schedule,
+        Err(e) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                &format!("Invalid request: {e}"),
+                "INVALID_JSON",
+            )
+        }
+    };
+
+    let input = ScheduleLearningSessionInput {
+        organizer_id: &claims.human_id,
+        kind: schedule.kind,
+        path_id: schedule.path_id,
+        title: schedule.title,
+        description: schedule.description,
+        location: schedule.location,
+        starts_at_micros: schedule.starts_at.timestamp_micros(),
+        duration_minutes: schedule
+            .duration_minutes
+            .unwrap_or(DEFAULT_DURATION_MINUTES),
+    };
+    match call_content_store_for(&state, "schedule_learning_session", &input, Some(&claims)).await {
+        Ok(data) => {
+            info!(organizer = %claims.human_id, kind = %input.kind, "Learning session scheduled");
+            json_response(serde_json::to_vec(&data.unwrap_or(Value::Null)).unwrap_or_default())
+        }
+        Err(e) => {
+            warn!(organizer = %claims.human_id, error = ?e, "Failed to schedule session");
+            error_response(
+                StatusCode::BAD_REQUEST,
+                "Session not scheduled (unknown path, start in the past, or invalid kind or duration)",
+                "SCHEDULE_FAILED",
+            )
+        }
+    }
+}
+
+/// Handle DELETE /sessions/{id}
+pub async fn handle_cancel_session(
+    state: Arc<AppState>,
+    session_id: &str,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    let claims = match require_user(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let input = CancelLearningSessionInput {
+        session_id,
+        organizer_id: &claims.human_id,
+    };
+    match call_content_store_for(&state, "cancel_learning_session", &input, Some(&claims)).await {
+        Ok(data) => {
+            info!(session_id = %session_id, "Learning session cancelled");
+            json_response(serde_json::to_vec(&data.unwrap_or(Value::Null)).unwrap_or_default())
+        }
+        Err(e) => {
+            info!(session_id = %session_id, error = ?e, "Session not cancelled");
+            error_response(
+                StatusCode::CONFLICT,
+                "Session not cancelled (not found, or not yours)",
+                "CANCEL_FAILED",
+            )
+        }
+    }
+}
+
+/// Handle GET /me/sessions
+pub async fn handle_my_sessions(
+    state: Arc<AppState>,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    let claims = match require_user(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    match load_calendar(&state, &claims.human_id, Some(&claims)).await {
+        Ok(sessions) => {
+            let feed_url = state.calendar_feeds.as_ref().map(|feeds| {
+                let base = state.args.doorway_url.as_deref().unwrap_or("");
+                format!(
+                    "{}/me/calendar.ics?token={}",
+                    base.trim_end_matches('/'),
+                    urlencoding::encode(&feeds.feed_token(&claims.human_id))
+                )
+            });
+            let body = CalendarResponse { sessions, feed_url };
+            json_response(serde_json::to_vec(&body).unwrap_or_default())
+        }
+        Err(e) => {
+            warn!(error = %e, "Failed to load calendar");
+            error_response(StatusCode::BAD_GATEWAY, "Lookup failed", "ZOME_ERROR")
+        }
+    }
+}
+
+/// Handle GET /me/calendar.ics
+pub async fn handle_calendar_feed(
+    state: Arc<AppState>,
+    query: Option<&str>,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    let params: FeedParams = serde_urlencoded::from_str(query.unwrap_or("")).unwrap_or_default();
+    // Calendar apps polling with a feed token read as anonymous callers
+    let (learner, claims) = match (params.token, &state.calendar_feeds) {
+        (Some(token), Some(feeds)) => match feeds.verify(&token) {
+            Some(learner) => (learner, None),
+            None => {
+                return error_response(
+                    StatusCode::UNAUTHORIZED,
+                    "Invalid calendar feed token",
+                    "INVALID_FEED_TOKEN",
+                )
+            }
+        },
+        (Some(_), None) => {
+            return error_response(
+                StatusCode::NOT_FOUND,
+                "Calendar feeds are not enabled on this doorway",
+                "FEEDS_DISABLED",
+            )
+        }
+        (None, _) => match require_user(&state, auth_header.as_deref()) {
+            Ok(claims) => (claims.human_id.clone(), Some(claims)),
+            Err(response) => return response,
+        },
+    };
+
+    match load_calendar(&state, &learner, claims.as_ref()).await {
+        Ok(sessions) => {
+            let ics = render_calendar("Learning sessions", &sessions, Utc::now());
+            Response::builder()
+                .status(StatusCode::OK)
+                .header("Content-Type", "text/calendar; charset=utf-8")
+                .header("Cache-Control", "private, max-age=300")
+                .body(Full::new(Bytes::from(ics)))
+                .unwrap()
+        }
+        Err(DoorwayError::Overloaded(msg)) => overloaded_response(&msg),
+        Err(e) => {
+            warn!(error = %e, "Failed to load calendar feed");
+            error_response(StatusCode::BAD_GATEWAY, "Lookup failed", "ZOME_ERROR")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_session_path() {
+        assert_eq!(
+            parse_session_path("/sessions/session-uhCAk-1"),
+            Some("session-uhCAk-1")
+        );
+        assert_eq!(parse_session_path("/sessions/"), None);
+        assert_eq!(parse_session_path("/sessions/a/b"), None);
+    }
+
+    #[test]
+    fn test_schedule_body_defaults() {
+        let body: ScheduleBody = serde_json::from_str(
+            r#"{"path_id":"path-1","title":"Review","starts_at":"2026-10-20T19:00:00+02:00"}"#,
+        )
+        .unwrap();
+        assert_eq!(body.kind, "study");
+        assert_eq!(body.duration_minutes, None);
+        assert_eq!(body.starts_at.timestamp_micros(), 1_792_515_600_000_000);
+    }
+}
@@ -0,0 +1,314 @@
+//! Signal Journal Routes
+//!
+//! Admin access to the conductor [signal journal](crate::worker::signal_journal).
+//! After a projection bug is fixed, replaying the affected time range rebuilds
+//! the projected documents and evicts stale cache entries without re-crawling
+//! the DHT.
+//!
+//! ## Routes
+//!
+//! - `GET /admin/signals/journal?from=&to=&after_offset=&kinds=&limit=` - Journaled signals,
+//!   oldest first, with their offsets (page with `after_offset`)
+//! - `POST /admin/signals/replay` - Replay `{from?, to?, after_offset?, kinds?}`; answers with
+//!   counts of replayed signals and the offsets covered
+//!
+//! `from`/`to` are RFC 3339 times; `kinds` are `projection` and/or
+//! `cache_invalidation`.
+
+use bson::DateTime;
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::Incoming;
+use hyper::{Request, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use super::api::{error_response, json_response, not_enabled_response};
+use super::auth_helpers::require_admin;
+use crate::db::schemas::JournaledSignalKind;
+use crate::projection::{EngineConfig, ProjectionEngine};
+use crate::server::AppState;
+use crate::worker::signal_journal::JournalRange;
+
+/// Largest request body accepted
+const MAX_BODY_BYTES: usize = 4 * 1024;
+
+/// Message when MongoDB is not configured
+const NOT_ENABLED: &str = "Signal journal not enabled (requires MongoDB)";
+
+/// Default and largest page of journal entries
+const DEFAULT_LIMIT: i64 = 100;
+const MAX_LIMIT: i64 = 1000;
+
+/// Query of `GET /admin/signals/journal`
+#[derive(Debug, Default, Deserialize)]
+struct JournalParams {
+    from: Option<String>,
+    to: Option<String>,
+    after_offset: Option<i64>,
+    /// Comma-separated kinds
+    kinds: Option<String>,
+    limit: Option<i64>,
+}
+
+/// Body of `POST /admin/signals/replay`
+#[derive(Debug, Default, Deserialize)]
+struct ReplayBody {
+    #[serde(default)]
+    from: Option<String>,
+    #[serde(default)]
+    to: Option<String>,
+    #[serde(default)]
+    after_offset: Option<i64>,
+    #[serde(default)]
+    kinds: Vec<String>,
+}
+
+/// A journal entry as listed
+#[derive(Debug, Serialize)]
+struct JournalEntryView {
+    offset: i64,
+    received_at: Option<String>,
+    kind: JournaledSignalKind,
+    doc_type: String,
+    doc_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_fn: Option<String>,
+}
+
+fn parse_time(field: &str, value: Option<&str>) -> Result<Option<DateTime>, String> {
+    match value.filter(|v| !v.is_empty()) {
+        None => Ok(None),
+        Some(text) => chrono::DateTime::parse_from_rfc3339(text)
+            .map(|t| Some(DateTime::from_millis(t.timestamp_millis())))
+            .map_err(|_| format!("`{field}` must be an RFC 3339 time")),
+    }
+}
+
+fn parse_kinds<'a>(
+    names: impl IntoIterator<Item = &'a str>,
+) -> Result<Vec<JournaledSignalKind>, String> {
+    names
+        .into_iter()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            JournaledSignalKind::parse(name).ok_or_else(|| format!("Unknown signal kind `{name}`"))
+        })
+        .collect()
+}
+
+fn journal_range(
+    from: Option<&str>,
+    to: Option<&str>,
+    after_offset: Option<i64>,
+    kinds: Vec<JournaledSignalKind>,
+) -> Result<JournalRange, String> {
+    let range = JournalRange {
+        from: parse_time("from", from)?,
+        to: parse_time("to", to)?,
+        after_offset,
+        kinds,
+    };
+    if let (Some(from), Some(to)) = (range.from, range.to) {
+        if from >= to {
+            return Err("`from` must be before `to`".to_string());
+        }
+    }
+    Ok(range)
+}
+
+fn bad_request(message: &str) -> Response<Full<Bytes>> {
+    error_response(StatusCode::BAD_REQUEST, message, "BAD_REQUEST")
+}
+
+/// Handle GET /admin/signals/journal
+pub async fn handle_signal_journal(
+    state: Arc<AppState>,
+    query: Option<&str>,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    if let Err(response) = require_admin(&state, auth_header.as_deref()) {
+        return response;
+    }
+    let Some(ref journal) = state.signal_journal else {
+        return not_enabled_response(StatusCode::SERVICE_UNAVAILABLE, NOT_ENABLED);
+    };
+
+    let params: JournalParams = match serde_urlencoded::from_str(query.unwrap_or("")) {
+        Ok(params) => params,
+        Err(e) => return bad_request(&format!("Invalid query: {e}")),
+    };
+    let range =
+        match parse_kinds(params.kinds.as_deref().unwrap_or("").split(',')).and_then(|kinds| {
+            journal_range(
+                params.from.as_deref(),
+                params.to.as_deref(),
+                params.after_offset,
+                kinds,
+            )
+        }) {
+            Ok(range) => range,
+            Err(message) => return bad_request(&message),
+        };
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    match journal.read(&range, limit).await {
+        Ok(entries) => {
+            let entries: Vec<JournalEntryView> = entries
+                .into_iter()
+                .map(|entry| JournalEntryView {
+                    offset: entry.offset,
+                    received_at: entry
+                        .metadata
+                        .created_at
+                        .and_then(|t| t.try_to_rfc3339_string().ok()),
+                    kind: entry.kind,
+                    doc_type: entry.doc_type,
+                    doc_id: entry.doc_id,
+                    source_fn: entry.source_fn,
+                })
+                .collect();
+            let next_offset = entries.last().map(|entry| entry.offset);
+            json_response(
+                serde_json::to_vec(&serde_json::json!({
+                    "entries": entries,
+                    "next_offset": next_offset,
+                }))
+                .unwrap_or_default(),
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "Failed to read signal journal");
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to read signal journal",
+                "DATABASE_ERROR",
+            )
+        }
+    }
+}
+
+/// Handle POST /admin/signals/replay
+pub async fn handle_signal_replay(
+    req: Request<Incoming>,
+    state: Arc<AppState>,
+) -> Response<Full<Bytes>> {
+    let auth_header = req
+        .headers()
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string());
+    if let Err(response) = require_admin(&state, auth_header.as_deref()) {
+        return response;
+    }
+    let Some(journal) = state.signal_journal.clone() else {
+        return not_enabled_response(StatusCode::SERVICE_UNAVAILABLE, NOT_ENABLED);
+    };
+    let Some(store) = state.projection.clone() else {
+        return error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Projection store not available",
+            "NOT_ENABLED",
+        );
+    };
+
+    let body = match Limited::new(req.into_body(), MAX_BODY_BYTES)
+        .collect()
+        .await
+    {
+        Ok(collected) => collected.to_bytes(),
+        Err(_) => {
+            return error_response(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                &format!("Requests are limited to {MAX_BODY_BYTES} bytes"),
+                "TOO_LARGE",
+            )
+        }
+    };
+    let replay: ReplayBody = if body.is_empty() {
+        ReplayBody::default()
+    } else {
+        match serde_json::from_slice(&body) {
+            Ok(replay) => replay,
+            Err(e) => return bad_request(&format!("Invalid request: {e}")),
+        }
+    };
+    let range = match parse_kinds(replay.kinds.iter().map(String::as_str)).and_then(|kinds| {
+        journal_range(
+            replay.from.as_deref(),
+            replay.to.as_deref(),
+            replay.after_offset,
+            kinds,
+        )
+    }) {
+        Ok(range) => range,
+        Err(message) => return bad_request(&message),
+    };
+    if range.from.is_none() && range.after_offset.is_none() {
+        return bad_request("Give `from` or `after_offset` to choose where the replay starts");
+    }
+
+    let engine = ProjectionEngine::new(store, EngineConfig::default());
+    match journal
+        .replay(&range, &engine, &state.cache, &state.cache_rules)
+        .await
+    {
+        Ok(summary) => {
+            info!(
+                projection_signals = summary.projection_signals,
+                cache_invalidations = summary.cache_invalidations,
+                failed = summary.failed,
+                "Signal journal replayed"
+            );
+            json_response(serde_json::to_vec(&summary).unwrap_or_default())
+        }
+        Err(e) => {
+            warn!(error = %e, "Signal replay failed");
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Signal replay failed",
+                "DATABASE_ERROR",
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_kinds() {
+        assert_eq!(
+            parse_kinds("projection, cache_invalidation".split(',')),
+            Ok(vec![
+                JournaledSignalKind::Projection,
+                JournaledSignalKind::CacheInvalidation
+            ])
+        );
+        assert_eq!(parse_kinds("".split(',')), Ok(vec![]));
+        assert!(parse_kinds(["webhook"]).is_err());
+    }
+
+    #[test]
+    fn test_journal_range() {
+        let range = journal_range(
+            Some("2026-03-01T00:00:00Z"),
+            Some("2026-03-02T00:00:00Z"),
+            None,
+            vec![],
+        )
+        .unwrap();
+        assert_eq!(range.from, Some(DateTime::from_millis(1_772_323_200_000)));
+        assert!(journal_range(Some("yesterday"), None, None, vec![]).is_err());
+        assert!(journal_range(
+            Some("2026-03-02T00:00:00Z"),
+            Some("2026-03-01T00:00:00Z"),
+            None,
+            vec![]
+        )
+        .is_err());
+    }
+}
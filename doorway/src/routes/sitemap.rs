@@ -0,0 +1,70 @@
+//! Sitemap Routes
+//!
+//! Serves the sitemaps built by the [sitemap worker](crate::worker::sitemap).
+//!
+//! ## Routes
+//!
+//! - `GET /sitemap.xml` - Sitemap index
+//! - `GET /sitemaps/{name}.xml` - One sitemap (`content-{type}.xml`, `paths.xml`)
+//!
+//! Needs `DOORWAY_URL` (sitemaps hold absolute URLs) and the projection store.
+
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::{Response, StatusCode};
+use std::sync::Arc;
+
+use super::api::error_response;
+use crate::server::AppState;
+use crate::worker::sitemap::INDEX_NAME;
+
+/// Map a request path to a sitemap file name
+pub fn parse_sitemap_path(path: &str) -> Option<&str> {
+    if path == "/sitemap.xml" {
+        return Some(INDEX_NAME);
+    }
+    path.strip_prefix("/sitemaps/")
+        .filter(|name| name.ends_with(".xml") && !name.contains('/'))
+}
+
+/// Handle GET /sitemap.xml and /sitemaps/{name}.xml
+pub async fn handle_sitemap(state: Arc<AppState>, name: &str) -> Response<Full<Bytes>> {
+    let Some(ref generator) = state.sitemaps else {
+        return error_response(
+            StatusCode::NOT_FOUND,
+            "Sitemaps are not enabled",
+            "NOT_ENABLED",
+        );
+    };
+
+    match generator.get(name) {
+        Some(xml) => Response::builder()
+            .status(StatusCode::OK)
+            .header("Content-Type", "application/xml; charset=utf-8")
+            .header("Cache-Control", "public, max-age=3600")
+            .body(Full::new(xml))
+            .unwrap(),
+        None if generator.get(INDEX_NAME).is_none() => error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Sitemaps are still being generated",
+            "NOT_READY",
+        ),
+        None => error_response(StatusCode::NOT_FOUND, "Sitemap not found", "NOT_FOUND"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sitemap_path() {
+        assert_eq!(parse_sitemap_path("/sitemap.xml"), Some(INDEX_NAME));
+        assert_eq!(
+            parse_sitemap_path("/sitemaps/content-lesson.xml"),
+            Some("content-lesson.xml")
+        );
+        assert_eq!(parse_sitemap_path("/sitemaps/paths"), None);
+        assert_eq!(parse_sitemap_path("/sitemaps/a/b.xml"), None);
+    }
+}
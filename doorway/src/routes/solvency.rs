@@ -0,0 +1,77 @@
+//! Mutual Pool Solvency
+//!
+//! Lets members see the health of the Shefa insurance mutual: pooled
+//! contributions against claims paid and still owed. Snapshots are recorded
+//! by the [solvency worker](crate::worker::solvency).
+//!
+//! ## Routes
+//!
+//! - `GET /shefa/solvency?limit=..` - The latest snapshot and the most recent
+//!   ones before it, newest first
+//!
+//! Requires a user token.
+
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::{Response, StatusCode};
+use serde::Deserialize;
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::warn;
+
+use super::api::{error_response, json_response};
+use super::auth_helpers::require_user;
+use super::zome_helpers::call_content_store_for;
+use crate::server::AppState;
+
+/// Snapshots returned by default
+const DEFAULT_LIMIT: u32 = 30;
+
+/// Most snapshots returned
+const MAX_LIMIT: u32 = 365;
+
+#[derive(Debug, Default, Deserialize)]
+struct LimitParams {
+    limit: Option<u32>,
+}
+
+/// Handle GET /shefa/solvency
+pub async fn handle_solvency(
+    state: Arc<AppState>,
+    query: Option<&str>,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    let claims = match require_user(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let params: LimitParams = serde_urlencoded::from_str(query.unwrap_or("")).unwrap_or_default();
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let snapshots =
+        match call_content_store_for(&state, "get_solvency_snapshots", &limit, Some(&claims)).await
+        {
+            Ok(Some(Value::Array(snapshots))) => snapshots,
+            Ok(_) => Vec::new(),
+            Err(e) => {
+                warn!(error = ?e, "Failed to load solvency snapshots");
+                return error_response(StatusCode::BAD_GATEWAY, "Lookup failed", "ZOME_ERROR");
+            }
+        };
+    if snapshots.is_empty() {
+        return error_response(
+            StatusCode::NOT_FOUND,
+            "No solvency snapshot recorded yet",
+            "NOT_FOUND",
+        );
+    }
+
+    json_response(
+        serde_json::to_vec(&serde_json::json!({
+            "latest": snapshots[0],
+            "history": snapshots,
+        }))
+        .unwrap_or_default(),
+    )
+}
@@ -9,7 +9,15 @@ use hyper::{Response, StatusCode};
 use serde::Serialize;
 use std::sync::Arc;
 
+use crate::cache::SingleFlightStats;
+use crate::db::L1Stats;
 use crate::orchestrator::NodeHealthStatus;
+use crate::proxy::admission::AdmissionStats;
+use crate::proxy::heartbeat::HeartbeatStats;
+use crate::proxy::outbound::OutboundStats;
+use crate::proxy::presence::PresenceStats;
+use crate::proxy::reconnect::ReconnectStats;
+use crate::server::limits::BodyLimitStats;
 use crate::server::AppState;
 
 /// Bootstrap service stats
@@ -150,6 +158,22 @@ pub struct StatusResponse {
     pub bootstrap: BootstrapStats,
     /// Cache service stats
     pub cache: CacheStats,
+    /// Request size limits and oversized requests turned away
+    pub body_limits: BodyLimitStats,
+    /// App WebSocket outbound queues: dropped signals and slow clients
+    pub ws_outbound: OutboundStats,
+    /// App WebSocket heartbeat and dead connections reaped
+    pub ws_heartbeat: HeartbeatStats,
+    /// App WebSocket conductor reconnects and calls held meanwhile
+    pub ws_reconnect: ReconnectStats,
+    /// Presence rooms and their members
+    pub presence: PresenceStats,
+    /// Identical cacheable zome calls answered by one conductor call
+    pub zome_coalescing: SingleFlightStats,
+    /// Conductor pressure and cacheable reads delayed or shed, when enabled
+    pub admission: Option<AdmissionStats>,
+    /// L1 query cache size and L1/L2 (MongoDB) hit ratio, when enabled
+    pub l1_cache: Option<L1Stats>,
     /// Orchestrator cluster stats
     pub orchestrator: OrchestratorStats,
     /// Diagnostic information and recommendations
@@ -365,6 +389,16 @@ pub async fn status_check(state: Arc<AppState>) -> Response<Full<Bytes>> {
         storage,
         bootstrap,
         cache,
+        body_limits: state.body_limits.stats(),
+        ws_outbound: state.ws_outbound.stats(),
+        ws_heartbeat: state.ws_heartbeat.stats(),
+        ws_reconnect: state.ws_reconnect.stats(),
+        presence: state.presence.stats(),
+        zome_coalescing: state.zome_flights.stats(),
+        admission: state.admission.as_ref().map(|admission| {
+            admission.stats(state.pool.as_ref().map(|p| p.load()).unwrap_or_default())
+        }),
+        l1_cache: state.projection.as_ref().and_then(|p| p.l1_stats()),
         orchestrator,
         diagnostics,
     };
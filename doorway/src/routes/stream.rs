@@ -12,6 +12,15 @@
 //! | `GET /api/stream/hls/{content_id}/{variant}` | HLS variant playlist |
 //! | `GET /api/stream/dash/{content_id}` | DASH MPD manifest |
 //! | `GET /api/stream/chunk/{hash}/{index}` | Individual chunk |
+//! | `GET /api/stream/media/{hash}?max_bitrate=` | Redirect to the best registered variant |
+//!
+//! ## Variant Selection
+//!
+//! `media` looks up the renditions registered for a blob and picks the
+//! highest bitrate at or under the player's `max_bitrate` hint (Mbps). If
+//! every variant is above the hint, the lowest one is used; with no hint the
+//! original blob is served. The response is a redirect to `/store/{hash}`
+//! with the chosen hash in `X-Selected-Variant`.
 //!
 //! ## Integration
 //!
@@ -23,7 +32,9 @@
 use bytes::Bytes;
 use http_body_util::Full;
 use hyper::{Response, StatusCode};
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use tracing::debug;
 
 use crate::cache::BlobMetadata;
 use crate::server::AppState;
@@ -236,6 +247,104 @@ pub async fn handle_blob_range(
     error_response(StatusCode::NOT_FOUND, "Blob range not available")
 }
 
+/// Role holding the content_store zome
+const CONTENT_ROLE: &str = "lamad";
+
+/// Zome owning blob variants
+const CONTENT_ZOME: &str = "content_store";
+
+/// Input for content_store::get_blob_variants
+#[derive(Debug, Serialize)]
+struct QueryBlobVariantsInput {
+    blob_hash: String,
+}
+
+/// Subset of BlobVariantOutput
+#[derive(Debug, Deserialize)]
+struct BlobVariantOutput {
+    variant: BlobVariantSummary,
+}
+
+/// Subset of the BlobVariant entry used for selection
+#[derive(Debug, Clone, Deserialize)]
+pub struct BlobVariantSummary {
+    pub hash: String,
+    pub resolution: Option<String>,
+    pub bitrate_mbps: f32,
+    pub codec: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct MediaParams {
+    max_bitrate: Option<f32>,
+}
+
+/// Pick a variant for a player's bitrate ceiling (Mbps).
+///
+/// Returns the highest bitrate at or under `max_bitrate`, falling back to the
+/// lowest bitrate when none fit. Returns None when there is no hint, meaning
+/// the original should be served.
+pub fn select_variant(
+    variants: &[BlobVariantSummary],
+    max_bitrate: Option<f32>,
+) -> Option<&BlobVariantSummary> {
+    let max_bitrate = max_bitrate.filter(|m| m.is_finite() && *m > 0.0)?;
+    variants
+        .iter()
+        .filter(|v| v.bitrate_mbps <= max_bitrate)
+        .max_by(|a, b| a.bitrate_mbps.total_cmp(&b.bitrate_mbps))
+        .or_else(|| {
+            variants
+                .iter()
+                .min_by(|a, b| a.bitrate_mbps.total_cmp(&b.bitrate_mbps))
+        })
+}
+
+/// Handle media request with an optional `max_bitrate` hint
+///
+/// Redirects to the selected variant (or the original blob) in `/store`.
+pub async fn handle_media(
+    state: Arc<AppState>,
+    blob_hash: &str,
+    query: Option<&str>,
+) -> Response<Full<Bytes>> {
+    let params: MediaParams = match serde_urlencoded::from_str(query.unwrap_or("")) {
+        Ok(params) => params,
+        Err(_) => return error_response(StatusCode::BAD_REQUEST, "Invalid max_bitrate"),
+    };
+
+    let variants: Vec<BlobVariantSummary> = match (params.max_bitrate, &state.zome_caller) {
+        (Some(_), Some(zome_caller)) => zome_caller
+            .call::<_, Vec<BlobVariantOutput>>(
+                CONTENT_ROLE,
+                CONTENT_ZOME,
+                "get_blob_variants",
+                &QueryBlobVariantsInput {
+                    blob_hash: blob_hash.to_string(),
+                },
+            )
+            .await
+            .map(|outputs| outputs.into_iter().map(|o| o.variant).collect())
+            .unwrap_or_else(|e| {
+                debug!(blob_hash, error = %e, "Variant lookup failed, serving original");
+                Vec::new()
+            }),
+        _ => Vec::new(),
+    };
+
+    let selected = select_variant(&variants, params.max_bitrate)
+        .map(|v| v.hash.as_str())
+        .unwrap_or(blob_hash);
+
+    Response::builder()
+        .status(StatusCode::TEMPORARY_REDIRECT)
+        .header("Location", format!("/store/{selected}"))
+        .header("X-Selected-Variant", selected)
+        .header("Cache-Control", "max-age=60")
+        .body(Full::new(Bytes::new()))
+        .unwrap()
+}
+
 // ============================================================================
 // Playlist Generation
 // ============================================================================
@@ -465,6 +574,7 @@ fn error_response(status: StatusCode, message: &str) -> Response<Full<Bytes>> {
 pub async fn handle_stream_request(
     state: Arc<AppState>,
     path: &str,
+    query: Option<&str>,
     base_url: &str,
 ) -> Response<Full<Bytes>> {
     // Parse the path: /api/stream/{type}/{content_id}/{optional_variant}
@@ -493,6 +603,9 @@ pub async fn handle_stream_request(
             Err(_) => error_response(StatusCode::BAD_REQUEST, "Invalid chunk index"),
         },
 
+        // Adaptive media: /api/stream/media/{hash}?max_bitrate={mbps}
+        ["media", hash] => handle_media(state, hash, query).await,
+
         _ => error_response(StatusCode::NOT_FOUND, "Unknown streaming endpoint"),
     }
 }
@@ -586,6 +699,42 @@ mod tests {
         assert!(mpd.contains("/api/stream/chunk/hash_1080p/"));
     }
 
+    fn variant(hash: &str, bitrate_mbps: f32) -> BlobVariantSummary {
+        BlobVariantSummary {
+            hash: hash.to_string(),
+            resolution: None,
+            bitrate_mbps,
+            codec: "h264".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_select_variant() {
+        let variants = vec![
+            variant("sha256-480", 1.5),
+            variant("sha256-720", 3.0),
+            variant("sha256-1080", 6.0),
+        ];
+
+        assert_eq!(
+            select_variant(&variants, Some(4.0)).unwrap().hash,
+            "sha256-720"
+        );
+        assert_eq!(
+            select_variant(&variants, Some(6.0)).unwrap().hash,
+            "sha256-1080"
+        );
+        // Nothing fits: lowest bitrate
+        assert_eq!(
+            select_variant(&variants, Some(0.5)).unwrap().hash,
+            "sha256-480"
+        );
+        // No (usable) hint: serve the original
+        assert!(select_variant(&variants, None).is_none());
+        assert!(select_variant(&variants, Some(f32::NAN)).is_none());
+        assert!(select_variant(&[], Some(4.0)).is_none());
+    }
+
     #[test]
     fn test_format_iso_duration() {
         assert_eq!(format_iso_duration(0), "PT0S");
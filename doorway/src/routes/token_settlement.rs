@@ -0,0 +1,179 @@
+//! Token Settlement Route
+//!
+//! Redeems a payment made on an hREA/ValueFlows network or token ledger for
+//! access through a premium gate (see
+//! [`services::token_settlement`](crate::services::token_settlement)).
+//!
+//! ## Routes
+//!
+//! - `POST /shefa/settlements/token` - Redeem a signed [`PaymentProof`];
+//!   answers with the AccessGrant
+//!
+//! Requires a user token; the proof must name the caller's agent as the
+//! learner. Disabled unless `TOKEN_SETTLEMENT_SIGNERS` is set, and then
+//! `TOKEN_SETTLEMENT_RECEIVER` must name the account payments go to.
+
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::Incoming;
+use hyper::{Request, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use super::api::{error_response, json_response};
+use super::auth_helpers::require_user;
+use super::zome_helpers::call_content_store_for;
+use crate::server::AppState;
+use crate::services::token_settlement::{GateTerms, PaymentProof, TokenSettlement};
+
+/// Largest proof body accepted
+const MAX_BODY_BYTES: usize = 16 * 1024;
+
+/// Must match PremiumGateOutput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Deserialize)]
+struct PremiumGateOutput {
+    gate: GateTerms,
+}
+
+/// Must match GrantPaidAccessInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct GrantPaidAccessInput<'a> {
+    gate_id: &'a str,
+    learner_agent_id: &'a str,
+    settlement_network: &'a str,
+    payment_event_id: &'a str,
+    payment_amount: f64,
+    payment_unit: &'a str,
+}
+
+/// Handle POST /shefa/settlements/token
+pub async fn handle_token_settlement(
+    req: Request<Incoming>,
+    state: Arc<AppState>,
+) -> Response<Full<Bytes>> {
+    let auth_header = req
+        .headers()
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string());
+    let claims = match require_user(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let settlement = match TokenSettlement::from_args(&state.args) {
+        Ok(Some(settlement)) => settlement,
+        Ok(None) => {
+            return error_response(
+                StatusCode::NOT_IMPLEMENTED,
+                "Token settlement not enabled (missing TOKEN_SETTLEMENT_SIGNERS)",
+                "NOT_ENABLED",
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "Token settlement misconfigured");
+            return error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Token settlement misconfigured",
+                "CONFIG_ERROR",
+            );
+        }
+    };
+
+    let body = match Limited::new(req.into_body(), MAX_BODY_BYTES)
+        .collect()
+        .await
+    {
+        Ok(collected) => collected.to_bytes(),
+        Err(_) => {
+            return error_response(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                &format!("Proofs are limited to {MAX_BODY_BYTES} bytes"),
+                "TOO_LARGE",
+            )
+        }
+    };
+    let proof: PaymentProof = match serde_json::from_slice(&body) {
+        Ok(proof) => proof,
+        Err(e) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                &format!("Invalid payment proof: {e}"),
+                "INVALID_JSON",
+            )
+        }
+    };
+    if proof.learner_agent_id != claims.agent_pub_key {
+        return error_response(
+            StatusCode::FORBIDDEN,
+            "Payment proof is for another learner",
+            "FORBIDDEN",
+        );
+    }
+
+    let gate =
+        match call_content_store_for(&state, "get_premium_gate", &proof.gate_id, Some(&claims))
+            .await
+        {
+            Ok(Some(value)) => match serde_json::from_value::<PremiumGateOutput>(value) {
+                Ok(output) => output.gate,
+                Err(e) => {
+                    warn!(gate_id = %proof.gate_id, error = %e, "Unreadable premium gate");
+                    return error_response(StatusCode::BAD_GATEWAY, "Lookup failed", "ZOME_ERROR");
+                }
+            },
+            Ok(None) => {
+                return error_response(StatusCode::NOT_FOUND, "Gate not found", "NOT_FOUND")
+            }
+            Err(e) => {
+                warn!(gate_id = %proof.gate_id, error = ?e, "Failed to load premium gate");
+                return error_response(StatusCode::BAD_GATEWAY, "Lookup failed", "ZOME_ERROR");
+            }
+        };
+
+    if let Err(e) = settlement.verify(&proof, &gate, chrono::Utc::now().timestamp()) {
+        info!(
+            network = %proof.network,
+            event_id = %proof.event_id,
+            gate_id = %proof.gate_id,
+            reason = %e,
+            "Token payment refused"
+        );
+        return error_response(
+            StatusCode::PAYMENT_REQUIRED,
+            &e.to_string(),
+            "INVALID_PAYMENT_PROOF",
+        );
+    }
+
+    let input = GrantPaidAccessInput {
+        gate_id: &proof.gate_id,
+        learner_agent_id: &proof.learner_agent_id,
+        settlement_network: &proof.network,
+        payment_event_id: &proof.event_id,
+        payment_amount: proof.amount,
+        payment_unit: &proof.unit,
+    };
+    match call_content_store_for(&state, "grant_paid_access", &input, Some(&claims)).await {
+        Ok(data) => {
+            info!(
+                network = %proof.network,
+                event_id = %proof.event_id,
+                gate_id = %proof.gate_id,
+                learner = %proof.learner_agent_id,
+                "Token payment settled"
+            );
+            json_response(serde_json::to_vec(&data.unwrap_or(Value::Null)).unwrap_or_default())
+        }
+        Err(e) => {
+            warn!(event_id = %proof.event_id, error = ?e, "Failed to grant access for token payment");
+            error_response(
+                StatusCode::CONFLICT,
+                "Access not granted (has this payment already been redeemed?)",
+                "GRANT_FAILED",
+            )
+        }
+    }
+}
@@ -0,0 +1,637 @@
+//! Content Translation API
+//!
+//! Serves content in the reader's language from `ContentTranslation`
+//! entries, so one content node covers every language a community speaks.
+//!
+//! ## Routes
+//!
+//! - `GET /api/v1/content/{id}/localized` - Content with translated fields applied
+//! - `GET /api/v1/content/{id}/translations` - All translations of a content node
+//! - `POST /api/v1/content/{id}/translations` - Add or replace a translation (JSON body)
+//! - `POST /api/v1/content/{id}/translations/{locale}/publish` - Publish a reviewed draft
+//! - `GET /api/v1/translations/pending` - Drafts awaiting steward review
+//!
+//! The locale for `localized` comes from `?locale=` (comma-separated list
+//! allowed) or else `Accept-Language`; the zome falls back through the list
+//! and finally to the original. Responses carry `Content-Language` and
+//! `Vary: Accept-Language`, and are cached per negotiated locale list (see
+//! [`CacheKey::with_locale`]).
+//!
+//! When the preferred locale has no translation and
+//! [machine translation](crate::worker::machine_translation) is enabled, a
+//! draft is queued for it. Drafts are never served; they wait for a steward.
+//!
+//! Reads only serve `commons`/`public` content. Adding a translation needs a
+//! token for the content's author, a steward or an admin; reviewing drafts
+//! needs a steward or an admin.
+
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::Incoming;
+use hyper::{Request, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+use super::api::{error_response, json_response, overloaded_response};
+use super::auth_helpers::require_user;
+use super::captions::is_language_tag;
+use super::content_body::is_public_reach;
+use super::zome_helpers::{call_content_store, call_content_store_for, get_content_store_config};
+use crate::auth::{Claims, PermissionLevel};
+use crate::cache::rules::CacheRuleExt;
+use crate::cache::CacheKey;
+use crate::server::AppState;
+use crate::types::DoorwayError;
+
+/// Zome function backing `/localized`
+const LOCALIZED_FN: &str = "get_content_localized";
+
+/// Most locales taken from a request (keeps cache keys bounded)
+const MAX_LOCALES: usize = 6;
+
+/// Largest translation body accepted
+const MAX_TRANSLATION_BYTES: usize = 1024 * 1024;
+
+/// Route a `/api/v1/content/{id}/...` path
+#[derive(Debug, Clone, PartialEq)]
+pub enum TranslationRoute<'a> {
+    Localized(&'a str),
+    Translations(&'a str),
+    /// Content id and locale
+    Publish(&'a str, &'a str),
+}
+
+/// Parse `/api/v1/content/{id}/localized`, `/api/v1/content/{id}/translations`
+/// and `/api/v1/content/{id}/translations/{locale}/publish`
+pub fn parse_translation_path(path: &str) -> Option<TranslationRoute<'_>> {
+    let rest = path.strip_prefix("/api/v1/content/")?;
+    let (id, action) = rest.split_once('/')?;
+    if id.is_empty() {
+        return None;
+    }
+    match action {
+        "localized" => Some(TranslationRoute::Localized(id)),
+        "translations" => Some(TranslationRoute::Translations(id)),
+        _ => {
+            let locale = action
+                .strip_prefix("translations/")?
+                .strip_suffix("/publish")?;
+            (!locale.is_empty() && !locale.contains('/'))
+                .then_some(TranslationRoute::Publish(id, locale))
+        }
+    }
+}
+
+/// Canonical casing for a locale tag ("PT-br" -> "pt-BR", "zh-hant" -> "zh-Hant")
+pub fn normalize_locale(tag: &str) -> String {
+    tag.trim()
+        .split(['-', '_'])
+        .enumerate()
+        .map(|(i, subtag)| match (i, subtag.len()) {
+            (0, _) => subtag.to_lowercase(),
+            (_, 2) => subtag.to_uppercase(),
+            (_, 4) => {
+                let lower = subtag.to_lowercase();
+                let mut chars = lower.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_uppercase().chain(chars).collect())
+                    .unwrap_or_default()
+            }
+            _ => subtag.to_lowercase(),
+        })
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Locales from an `Accept-Language` header, most preferred first.
+/// Drops `*`, `q=0` and malformed tags.
+pub fn parse_accept_language(header: &str) -> Vec<String> {
+    let mut weighted: Vec<(String, f32)> = header
+        .split(',')
+        .filter_map(|item| {
+            let mut parts = item.split(';').map(str::trim);
+            let tag = normalize_locale(parts.next()?);
+            let q = parts
+                .find_map(|p| p.strip_prefix("q="))
+                .map(|q| q.parse::<f32>().unwrap_or(0.0))
+                .unwrap_or(1.0);
+            (q > 0.0 && is_language_tag(&tag)).then_some((tag, q))
+        })
+        .collect();
+    // Stable: equal weights keep header order
+    weighted.sort_by(|a, b| b.1.total_cmp(&a.1));
+    dedupe(weighted.into_iter().map(|(tag, _)| tag))
+}
+
+fn dedupe(locales: impl Iterator<Item = String>) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for locale in locales {
+        if !out.contains(&locale) {
+            out.push(locale);
+        }
+        if out.len() == MAX_LOCALES {
+            break;
+        }
+    }
+    out
+}
+
+/// Negotiated locale list: `?locale=` wins over `Accept-Language`
+pub fn negotiate_locales(query: Option<&str>, accept_language: Option<&str>) -> Vec<String> {
+    let from_query = query
+        .unwrap_or("")
+        .split('&')
+        .filter_map(|pair| pair.strip_prefix("locale="))
+        .flat_map(|value| {
+            urlencoding::decode(value)
+                .map(|v| v.into_owned())
+                .unwrap_or_default()
+                .split(',')
+                .map(normalize_locale)
+                .filter(|tag| is_language_tag(tag))
+                .collect::<Vec<_>>()
+        });
+    let locales = dedupe(from_query);
+    if !locales.is_empty() {
+        return locales;
+    }
+    accept_language
+        .map(parse_accept_language)
+        .unwrap_or_default()
+}
+
+/// Must match GetContentLocalizedInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct GetContentLocalizedInput {
+    id: String,
+    locale: String,
+    fallback_chain: Vec<String>,
+}
+
+/// Must match AddTranslationInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct AddTranslationInput {
+    content_id: String,
+    locale: String,
+    title: String,
+    description: String,
+    body: Option<String>,
+    translator_id: Option<String>,
+}
+
+/// Input for content_store::get_content_by_id
+#[derive(Debug, Serialize)]
+struct QueryByIdInput {
+    id: String,
+}
+
+/// POST body for `/translations`
+#[derive(Debug, Deserialize)]
+struct TranslationRequest {
+    locale: String,
+    title: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    body: Option<String>,
+}
+
+fn is_draft(translation: &serde_json::Value) -> bool {
+    translation
+        .get("translation")
+        .and_then(|t| t.get("draft"))
+        .and_then(|d| d.as_bool())
+        .unwrap_or(false)
+}
+
+/// Whether the served translation is in the preferred language
+fn serves_language(served: Option<&str>, preferred: &str) -> bool {
+    let primary = |tag: &str| tag.split('-').next().unwrap_or(tag).to_lowercase();
+    served.is_some_and(|served| primary(served) == primary(preferred))
+}
+
+fn content_reach(data: &serde_json::Value) -> Option<&str> {
+    data.get("content")?.get("reach")?.as_str()
+}
+
+fn localized_response(body: Vec<u8>, content_language: Option<&str>) -> Response<Full<Bytes>> {
+    let mut builder = Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", "application/json")
+        .header("Cache-Control", "public, max-age=60")
+        .header("Vary", "Accept-Language")
+        .header("Access-Control-Allow-Origin", "*")
+        .header("Cross-Origin-Resource-Policy", "cross-origin");
+    if let Some(language) = content_language {
+        builder = builder.header("Content-Language", language);
+    }
+    builder.body(Full::new(Bytes::from(body))).unwrap()
+}
+
+/// Handle GET /api/v1/content/{id}/localized
+pub async fn handle_localized_content(
+    state: Arc<AppState>,
+    content_id: &str,
+    query: Option<&str>,
+    accept_language: Option<&str>,
+) -> Response<Full<Bytes>> {
+    let locales = negotiate_locales(query, accept_language);
+
+    let config = match get_content_store_config(&state) {
+        Ok(config) => config,
+        Err(e) => {
+            warn!(error = ?e, "Content zome not available");
+            return error_response(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Content zome not available",
+                "CONDUCTOR_UNAVAILABLE",
+            );
+        }
+    };
+
+    let chain = locales.join(",");
+    let cache_key = CacheKey::with_locale(
+        &config.dna_hash,
+        &config.zome_name,
+        LOCALIZED_FN,
+        content_id,
+        &chain,
+    )
+    .to_storage_key();
+    if let Some(entry) = state.cache.get(&cache_key) {
+        debug!(content_id, locales = %chain, "Localized content cache hit");
+        let language = serde_json::from_slice::<serde_json::Value>(&entry.data)
+            .ok()
+            .and_then(|d| d.get("locale").and_then(|l| l.as_str()).map(String::from));
+        return localized_response(entry.data, language.as_deref());
+    }
+
+    let preferred = locales.first().cloned();
+    let mut locales = locales.into_iter();
+    let input = GetContentLocalizedInput {
+        id: content_id.to_string(),
+        locale: locales.next().unwrap_or_default(),
+        fallback_chain: locales.collect(),
+    };
+    let data = match call_content_store(&state, LOCALIZED_FN, &input).await {
+        Ok(Some(data)) if !data.is_null() => data,
+        Ok(_) => return error_response(StatusCode::NOT_FOUND, "Content not found", "NOT_FOUND"),
+        Err(DoorwayError::Overloaded(msg)) => return overloaded_response(&msg),
+        Err(e) => {
+            warn!(content_id, error = ?e, "Localized content lookup failed");
+            return error_response(StatusCode::BAD_GATEWAY, "Lookup failed", "ZOME_ERROR");
+        }
+    };
+    // Don't reveal that narrower content exists
+    if !content_reach(&data).is_some_and(is_public_reach) {
+        return error_response(StatusCode::NOT_FOUND, "Content not found", "NOT_FOUND");
+    }
+
+    let language = data
+        .get("locale")
+        .and_then(|l| l.as_str())
+        .map(String::from);
+    if let (Some(translator), Some(preferred)) = (&state.machine_translation, preferred) {
+        if !serves_language(language.as_deref(), &preferred) {
+            translator.enqueue(content_id, &preferred);
+        }
+    }
+    let body = serde_json::to_vec(&data).unwrap_or_default();
+    let ttl = state
+        .cache_rules
+        .get_rule(&config.dna_hash, LOCALIZED_FN)
+        .map(|rule| rule.ttl())
+        .unwrap_or(state.cache.config().content_ttl);
+    state
+        .cache
+        .set(&cache_key, body.clone(), "application/json", ttl);
+    localized_response(body, language.as_deref())
+}
+
+/// Handle GET /api/v1/content/{id}/translations
+pub async fn handle_list_translations(
+    state: Arc<AppState>,
+    content_id: &str,
+) -> Response<Full<Bytes>> {
+    // Translations of narrower content stay private too
+    match call_content_store(
+        &state,
+        "get_content_by_id",
+        &QueryByIdInput {
+            id: content_id.to_string(),
+        },
+    )
+    .await
+    {
+        Ok(Some(data)) if content_reach(&data).is_some_and(is_public_reach) => {}
+        Ok(_) => return error_response(StatusCode::NOT_FOUND, "Content not found", "NOT_FOUND"),
+        Err(DoorwayError::Overloaded(msg)) => return overloaded_response(&msg),
+        Err(e) => {
+            warn!(content_id, error = ?e, "Failed to load content");
+            return error_response(StatusCode::BAD_GATEWAY, "Lookup failed", "ZOME_ERROR");
+        }
+    }
+
+    match call_content_store(&state, "get_content_translations", &content_id).await {
+        Ok(data) => {
+            // Drafts are only for stewards (see /api/v1/translations/pending)
+            let published: Vec<serde_json::Value> = data
+                .and_then(|d| d.as_array().cloned())
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|t| !is_draft(t))
+                .collect();
+            json_response(serde_json::to_vec(&published).unwrap_or_default())
+        }
+        Err(DoorwayError::Overloaded(msg)) => overloaded_response(&msg),
+        Err(e) => {
+            warn!(content_id, error = ?e, "Failed to list translations");
+            error_response(StatusCode::BAD_GATEWAY, "Lookup failed", "ZOME_ERROR")
+        }
+    }
+}
+
+/// Handle POST /api/v1/content/{id}/translations
+pub async fn handle_add_translation(
+    req: Request<Incoming>,
+    state: Arc<AppState>,
+    content_id: String,
+) -> Response<Full<Bytes>> {
+    let auth_header = req
+        .headers()
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string());
+    let claims = match require_user(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let body = match Limited::new(req.into_body(), MAX_TRANSLATION_BYTES)
+        .collect()
+        .await
+    {
+        Ok(collected) => collected.to_bytes(),
+        Err(_) => {
+            return error_response(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                &format!("Translations are limited to {MAX_TRANSLATION_BYTES} bytes"),
+                "TOO_LARGE",
+            )
+        }
+    };
+    let request: TranslationRequest = match serde_json::from_slice(&body) {
+        Ok(request) => request,
+        Err(e) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                &format!("Invalid translation: {e}"),
+                "INVALID_REQUEST",
+            )
+        }
+    };
+    let locale = normalize_locale(&request.locale);
+    if !is_language_tag(&locale) {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "A valid locale is required (e.g. es or pt-BR)",
+            "INVALID_LOCALE",
+        );
+    }
+    if request.title.trim().is_empty() {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "Title is required",
+            "INVALID_REQUEST",
+        );
+    }
+
+    // Only the author or a steward may translate
+    let content = match call_content_store_for(
+        &state,
+        "get_content_by_id",
+        &QueryByIdInput {
+            id: content_id.clone(),
+        },
+        Some(&claims),
+    )
+    .await
+    {
+        Ok(Some(content)) if !content.is_null() => content,
+        Ok(_) => return error_response(StatusCode::NOT_FOUND, "Content not found", "NOT_FOUND"),
+        Err(e) => {
+            warn!(content_id = %content_id, error = ?e, "Failed to load content for translation");
+            return error_response(
+                StatusCode::BAD_GATEWAY,
+                "Failed to load content",
+                "ZOME_ERROR",
+            );
+        }
+    };
+    let author = content
+        .get("content")
+        .and_then(|c| c.get("author_id"))
+        .and_then(|a| a.as_str());
+    let is_author = author == Some(claims.human_id.as_str());
+    if !is_author && !claims.is_steward && claims.permission_level < PermissionLevel::Admin {
+        return error_response(
+            StatusCode::FORBIDDEN,
+            "Only the content author or a steward can add translations",
+            "FORBIDDEN",
+        );
+    }
+
+    let input = AddTranslationInput {
+        content_id: content_id.clone(),
+        locale: locale.clone(),
+        title: request.title,
+        description: request.description,
+        body: request.body,
+        translator_id: Some(claims.human_id.clone()),
+    };
+    // Goes through the pool so cached localized reads are invalidated
+    match call_content_store_for(&state, "add_translation", &input, Some(&claims)).await {
+        Ok(data) => {
+            info!(content_id = %content_id, locale = %locale, "Translation added");
+            let body = serde_json::to_vec(&data.unwrap_or_default()).unwrap_or_default();
+            Response::builder()
+                .status(StatusCode::CREATED)
+                .header("Content-Type", "application/json")
+                .body(Full::new(Bytes::from(body)))
+                .unwrap()
+        }
+        Err(e) => {
+            warn!(content_id = %content_id, error = ?e, "Failed to add translation");
+            error_response(
+                StatusCode::BAD_GATEWAY,
+                "Failed to add translation",
+                "ZOME_ERROR",
+            )
+        }
+    }
+}
+
+/// Must match PublishTranslationInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct PublishTranslationInput {
+    content_id: String,
+    locale: String,
+}
+
+/// Validate the token and require a steward or an admin
+#[allow(clippy::result_large_err)]
+fn require_reviewer(
+    state: &AppState,
+    auth_header: Option<&str>,
+) -> Result<Claims, Response<Full<Bytes>>> {
+    let claims = require_user(state, auth_header)?;
+    if !claims.is_steward && claims.permission_level < PermissionLevel::Admin {
+        return Err(error_response(
+            StatusCode::FORBIDDEN,
+            "Only stewards can review translations",
+            "FORBIDDEN",
+        ));
+    }
+    Ok(claims)
+}
+
+/// Handle GET /api/v1/translations/pending
+pub async fn handle_pending_translations(
+    state: Arc<AppState>,
+    auth_header: Option<&str>,
+) -> Response<Full<Bytes>> {
+    let claims = match require_reviewer(&state, auth_header) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    match call_content_store_for(&state, "get_pending_translations", &(), Some(&claims)).await {
+        Ok(data) => {
+            let data = data.unwrap_or_else(|| serde_json::json!([]));
+            Response::builder()
+                .status(StatusCode::OK)
+                .header("Content-Type", "application/json")
+                .header("Cache-Control", "private, no-store")
+                .body(Full::new(Bytes::from(
+                    serde_json::to_vec(&data).unwrap_or_default(),
+                )))
+                .unwrap()
+        }
+        Err(e) => {
+            warn!(error = ?e, "Failed to list pending translations");
+            error_response(StatusCode::BAD_GATEWAY, "Lookup failed", "ZOME_ERROR")
+        }
+    }
+}
+
+/// Handle POST /api/v1/content/{id}/translations/{locale}/publish
+pub async fn handle_publish_translation(
+    state: Arc<AppState>,
+    content_id: &str,
+    locale: &str,
+    auth_header: Option<&str>,
+) -> Response<Full<Bytes>> {
+    let claims = match require_reviewer(&state, auth_header) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+    let locale = normalize_locale(&urlencoding::decode(locale).unwrap_or_default());
+    if !is_language_tag(&locale) {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "A valid locale is required (e.g. es or pt-BR)",
+            "INVALID_LOCALE",
+        );
+    }
+
+    let input = PublishTranslationInput {
+        content_id: content_id.to_string(),
+        locale: locale.clone(),
+    };
+    // Goes through the pool so cached localized reads are invalidated
+    match call_content_store_for(&state, "publish_translation", &input, Some(&claims)).await {
+        Ok(data) => {
+            info!(
+                content_id,
+                locale = %locale,
+                reviewer = %claims.human_id,
+                "Translation published"
+            );
+            json_response(serde_json::to_vec(&data.unwrap_or_default()).unwrap_or_default())
+        }
+        Err(e) => {
+            warn!(content_id, locale = %locale, error = ?e, "Failed to publish translation");
+            error_response(
+                StatusCode::BAD_GATEWAY,
+                "Failed to publish translation (is there a draft for this locale?)",
+                "ZOME_ERROR",
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_translation_path() {
+        assert_eq!(
+            parse_translation_path("/api/v1/content/intro/localized"),
+            Some(TranslationRoute::Localized("intro"))
+        );
+        assert_eq!(
+            parse_translation_path("/api/v1/content/intro/translations"),
+            Some(TranslationRoute::Translations("intro"))
+        );
+        assert_eq!(
+            parse_translation_path("/api/v1/content/intro/translations/pt-BR/publish"),
+            Some(TranslationRoute::Publish("intro", "pt-BR"))
+        );
+        assert_eq!(
+            parse_translation_path("/api/v1/content/intro/translations//publish"),
+            None
+        );
+        assert_eq!(parse_translation_path("/api/v1/content/query"), None);
+        assert_eq!(parse_translation_path("/api/v1/content//localized"), None);
+    }
+
+    #[test]
+    fn test_normalize_locale() {
+        assert_eq!(normalize_locale("PT-br"), "pt-BR");
+        assert_eq!(normalize_locale("zh-hant-tw"), "zh-Hant-TW");
+        assert_eq!(normalize_locale("en_US"), "en-US");
+    }
+
+    #[test]
+    fn test_parse_accept_language() {
+        assert_eq!(
+            parse_accept_language("fr-CH, fr;q=0.9, en;q=0.8, de;q=0.7, *;q=0.5"),
+            vec!["fr-CH", "fr", "en", "de"]
+        );
+        assert_eq!(
+            parse_accept_language("en;q=0.5, es, pt-br;q=0"),
+            vec!["es", "en"]
+        );
+        assert!(parse_accept_language("").is_empty());
+    }
+
+    #[test]
+    fn test_serves_language() {
+        assert!(serves_language(Some("pt"), "pt-BR"));
+        assert!(!serves_language(Some("es"), "pt-BR"));
+        assert!(!serves_language(None, "pt-BR"));
+    }
+
+    #[test]
+    fn test_negotiate_locales_query_wins() {
+        assert_eq!(
+            negotiate_locales(Some("locale=pt-br%2Cen"), Some("de")),
+            vec!["pt-BR", "en"]
+        );
+        assert_eq!(negotiate_locales(Some("locale="), Some("de")), vec!["de"]);
+        assert!(negotiate_locales(None, None).is_empty());
+    }
+}
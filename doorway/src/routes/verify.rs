@@ -0,0 +1,374 @@
+//! Attestation Verification
+//!
+//! Lets a third party check a learner's claimed badge or completion without
+//! Holochain tooling. The attestation, its holder and any revocation come
+//! from `verify_attestation` in the imagodei zome; doorway works out whether
+//! it is still valid and renders it as a page or as JSON carrying an
+//! Open Badges 3.0 credential.
+//!
+//! ## Routes
+//!
+//! - `GET /verify/attestation/{id}` - Verification page; JSON for
+//!   `?format=json` or `Accept: application/json`
+//!
+//! Responses are public and cached for five minutes, so a revocation is
+//! reflected within that window. Holders are only named when their profile
+//! is public.
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use http_body_util::Full;
+use hyper::{Response, StatusCode};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tracing::warn;
+
+use super::api::error_response;
+use super::preview::escape_html;
+use super::zome_helpers::{call_verify_attestation, AttestationVerification};
+use crate::projection::document::{entry_timestamp_rfc3339, rfc3339};
+use crate::server::AppState;
+
+/// Name reported for the issuing network
+pub const ISSUER_NAME: &str = "Elohim";
+
+/// JSON-LD contexts of an Open Badges 3.0 credential
+pub const OPEN_BADGE_CONTEXT: [&str; 2] = [
+    "https://www.w3.org/ns/credentials/v2",
+    "https://purl.imsglobal.org/spec/ob/v3p0/context-3.0.3.json",
+];
+
+/// Extract the attestation id from `/verify/attestation/{id}`
+pub fn parse_verify_attestation_path(path: &str) -> Option<&str> {
+    path.strip_prefix("/verify/attestation/")
+        .filter(|id| !id.is_empty() && !id.contains('/'))
+}
+
+/// Parse a zome timestamp, either RFC 3339 or the `Timestamp(...)` debug
+/// form entries are stamped with
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    let value = value
+        .strip_prefix("Timestamp(")
+        .and_then(|v| v.strip_suffix(')'))
+        .unwrap_or(value);
+    DateTime::parse_from_rfc3339(value.trim())
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// `revoked`, `expired` or `valid`
+pub fn attestation_status(
+    verification: &AttestationVerification,
+    now: DateTime<Utc>,
+) -> &'static str {
+    if verification.revocation.is_some() {
+        return "revoked";
+    }
+    let expired = verification
+        .attestation
+        .expires_at
+        .as_deref()
+        .and_then(parse_timestamp)
+        .is_some_and(|expires| expires <= now);
+    if expired {
+        "expired"
+    } else {
+        "valid"
+    }
+}
+
+fn verify_url(base_url: &str, id: &str) -> String {
+    format!(
+        "{}/verify/attestation/{}",
+        base_url.trim_end_matches('/'),
+        urlencoding::encode(id)
+    )
+}
+
+/// The attestation as an Open Badges 3.0 `OpenBadgeCredential`
+///
+/// The credential is unsigned; this endpoint is how it is verified.
+pub fn open_badge_credential(verification: &AttestationVerification, base_url: &str) -> Value {
+    let a = &verification.attestation;
+    let mut achievement = json!({
+        "id": format!("urn:elohim:achievement:{}:{}", a.category, a.attestation_type),
+        "type": ["Achievement"],
+        "name": a.display_name,
+        "description": a.description,
+        "criteria": { "narrative": a.description },
+    });
+    if let Some(ref icon_url) = a.icon_url {
+        achievement["image"] = json!({ "id": icon_url, "type": "Image" });
+    }
+
+    let mut credential = json!({
+        "@context": OPEN_BADGE_CONTEXT,
+        "id": verify_url(base_url, &a.id),
+        "type": ["VerifiableCredential", "OpenBadgeCredential"],
+        "name": a.display_name,
+        "issuer": {
+            "id": format!("urn:elohim:agent:{}", a.issued_by),
+            "type": ["Profile"],
+            "name": ISSUER_NAME,
+            "url": base_url.trim_end_matches('/'),
+        },
+        "validFrom": entry_timestamp_rfc3339(&a.issued_at),
+        "credentialSubject": {
+            "id": format!("urn:elohim:human:{}", a.agent_id),
+            "type": ["AchievementSubject"],
+            "achievement": achievement,
+        },
+    });
+    if let Some(ref expires_at) = a.expires_at {
+        credential["validUntil"] = json!(entry_timestamp_rfc3339(expires_at));
+    }
+    credential
+}
+
+/// Verification result returned as JSON
+pub fn verification_json(
+    verification: &AttestationVerification,
+    base_url: &str,
+    now: DateTime<Utc>,
+) -> Value {
+    let a = &verification.attestation;
+    let status = attestation_status(verification, now);
+    let earned_via = serde_json::from_str::<Value>(&a.earned_via_json)
+        .unwrap_or_else(|_| Value::String(a.earned_via_json.clone()));
+    json!({
+        "id": a.id,
+        "status": status,
+        "valid": status == "valid",
+        "checked_at": rfc3339(now),
+        "issuer": a.issued_by,
+        "issued_at": entry_timestamp_rfc3339(&a.issued_at),
+        "holder": verification.holder.as_ref().map(|h| json!({
+            "human_id": h.human_id,
+            "display_name": h.display_name,
+        })),
+        "category": a.category,
+        "attestation_type": a.attestation_type,
+        "display_name": a.display_name,
+        "tier": a.tier,
+        "earned_via": earned_via,
+        "expires_at": a.expires_at.as_deref().map(entry_timestamp_rfc3339),
+        "revocation": verification.revocation.as_ref().map(|r| json!({
+            "revoked_by": r.revoked_by,
+            "reason": r.reason,
+            "revoked_at": entry_timestamp_rfc3339(&r.revoked_at),
+        })),
+        "open_badge": open_badge_credential(verification, base_url),
+    })
+}
+
+/// Render the verification page
+pub fn render_verification_html(
+    verification: &AttestationVerification,
+    base_url: &str,
+    now: DateTime<Utc>,
+) -> String {
+    let a = &verification.attestation;
+    let status = attestation_status(verification, now);
+    let headline = match status {
+        "revoked" => "This attestation has been revoked",
+        "expired" => "This attestation has expired",
+        _ => "This attestation is valid",
+    };
+    let holder = verification
+        .holder
+        .as_ref()
+        .and_then(|h| h.display_name.clone())
+        .unwrap_or_else(|| "A learner".to_string());
+    let json_url = escape_html(&format!("{}?format=json", verify_url(base_url, &a.id)));
+
+    let mut rows = vec![
+        ("Holder", holder),
+        ("Issued by", a.issued_by.clone()),
+        ("Issued", entry_timestamp_rfc3339(&a.issued_at)),
+    ];
+    if let Some(ref tier) = a.tier {
+        rows.push(("Tier", tier.clone()));
+    }
+    if let Some(ref expires_at) = a.expires_at {
+        rows.push(("Expires", entry_timestamp_rfc3339(expires_at)));
+    }
+    if let Some(ref revocation) = verification.revocation {
+        rows.push(("Revoked", entry_timestamp_rfc3339(&revocation.revoked_at)));
+        rows.push(("Reason", revocation.reason.clone()));
+    }
+    let rows: Vec<String> = rows
+        .into_iter()
+        .map(|(label, value)| format!("<dt>{label}</dt><dd>{}</dd>", escape_html(&value)))
+        .collect();
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title} - {ISSUER_NAME} verification</title>
+<meta name="robots" content="noindex">
+<link rel="alternate" type="application/json" href="{json_url}">
+</head>
+<body>
+<h1 class="{status}">{headline}</h1>
+<h2>{title}</h2>
+<p>{description}</p>
+<dl>
+{rows}
+</dl>
+<p><a href="{json_url}">Open Badges 3.0 credential (JSON)</a></p>
+</body>
+</html>
+"#,
+        title = escape_html(&a.display_name),
+        description = escape_html(&a.description),
+        rows = rows.join("\n"),
+    )
+}
+
+/// Whether the caller asked for JSON rather than the page
+fn wants_json(query: Option<&str>, accept: Option<&str>) -> bool {
+    let by_query = query.is_some_and(|q| q.split('&').any(|pair| pair == "format=json"));
+    by_query || accept.is_some_and(|a| a.contains("application/json") || a.contains("ld+json"))
+}
+
+/// Handle GET /verify/attestation/{id}
+pub async fn handle_verify_attestation(
+    state: Arc<AppState>,
+    attestation_id: &str,
+    query: Option<&str>,
+    accept: Option<&str>,
+    base_url: &str,
+) -> Response<Full<Bytes>> {
+    let verification = match call_verify_attestation(&state, attestation_id).await {
+        Ok(Some(verification)) => verification,
+        Ok(None) => {
+            return error_response(StatusCode::NOT_FOUND, "Attestation not found", "NOT_FOUND")
+        }
+        Err(e) => {
+            warn!(attestation_id, error = ?e, "Attestation verification failed");
+            return error_response(
+                StatusCode::BAD_GATEWAY,
+                "Attestation could not be verified right now",
+                "ZOME_ERROR",
+            );
+        }
+    };
+
+    let base_url = state.args.doorway_url.as_deref().unwrap_or(base_url);
+    let now = Utc::now();
+    let (content_type, body) = if wants_json(query, accept) {
+        (
+            "application/json",
+            serde_json::to_vec(&verification_json(&verification, base_url, now))
+                .unwrap_or_default(),
+        )
+    } else {
+        (
+            "text/html; charset=utf-8",
+            render_verification_html(&verification, base_url, now).into_bytes(),
+        )
+    };
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", content_type)
+        .header("Cache-Control", "public, max-age=300")
+        .header("Vary", "Accept")
+        .header("Access-Control-Allow-Origin", "*")
+        .body(Full::new(Bytes::from(body)))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routes::zome_helpers::{Attestation, AttestationHolder, AttestationRevocation};
+
+    fn verification(expires_at: Option<&str>, revoked: bool) -> AttestationVerification {
+        AttestationVerification {
+            attestation: Attestation {
+                id: "att-1".to_string(),
+                agent_id: "human-1".to_string(),
+                category: "path_completion".to_string(),
+                attestation_type: "intro-path".to_string(),
+                display_name: "Intro <Path>".to_string(),
+                description: "Completed the intro path".to_string(),
+                icon_url: None,
+                tier: Some("gold".to_string()),
+                earned_via_json: r#"{"path_id":"intro-path"}"#.to_string(),
+                issued_at: "Timestamp(2026-01-02T03:04:05.000000Z)".to_string(),
+                issued_by: "uhCAkIssuer".to_string(),
+                expires_at: expires_at.map(String::from),
+                proof: None,
+            },
+            holder: Some(AttestationHolder {
+                human_id: "human-1".to_string(),
+                display_name: None,
+            }),
+            revocation: revoked.then(|| AttestationRevocation {
+                attestation_id: "att-1".to_string(),
+                revoked_by: "uhCAkIssuer".to_string(),
+                reason: "Issued in error".to_string(),
+                revoked_at: "Timestamp(2026-02-01T00:00:00Z)".to_string(),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_parse_verify_attestation_path() {
+        assert_eq!(
+            parse_verify_attestation_path("/verify/attestation/att-1"),
+            Some("att-1")
+        );
+        assert_eq!(parse_verify_attestation_path("/verify/attestation/"), None);
+        assert_eq!(
+            parse_verify_attestation_path("/verify/attestation/a/b"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_attestation_status() {
+        let now = parse_timestamp("2026-06-01T00:00:00Z").unwrap();
+        assert_eq!(attestation_status(&verification(None, false), now), "valid");
+        assert_eq!(
+            attestation_status(&verification(Some("2026-03-01T00:00:00Z"), false), now),
+            "expired"
+        );
+        assert_eq!(
+            attestation_status(&verification(Some("2027-01-01T00:00:00Z"), false), now),
+            "valid"
+        );
+        // Revocation wins over expiry
+        assert_eq!(
+            attestation_status(&verification(Some("2026-03-01T00:00:00Z"), true), now),
+            "revoked"
+        );
+    }
+
+    #[test]
+    fn test_open_badge_credential() {
+        let badge = open_badge_credential(&verification(None, false), "https://doorway.example/");
+        assert_eq!(badge["type"][1], "OpenBadgeCredential");
+        assert_eq!(
+            badge["id"],
+            "https://doorway.example/verify/attestation/att-1"
+        );
+        assert_eq!(badge["validFrom"], "2026-01-02T03:04:05Z");
+        assert_eq!(badge["credentialSubject"]["id"], "urn:elohim:human:human-1");
+        assert!(badge.get("validUntil").is_none());
+    }
+
+    #[test]
+    fn test_render_verification_html_escapes() {
+        let now = parse_timestamp("2026-06-01T00:00:00Z").unwrap();
+        let html =
+            render_verification_html(&verification(None, true), "https://doorway.example", now);
+        assert!(html.contains("This attestation has been revoked"));
+        assert!(html.contains("Intro &lt;Path&gt;"));
+        assert!(!html.contains("<Path>"));
+        assert!(html.contains("Issued in error"));
+    }
+}
@@ -0,0 +1,275 @@
+//! Premium Gate Vouchers
+//!
+//! Gift/voucher codes for gated content. A gate's steward mints a batch of
+//! single-use or limited-count codes and hands them out; a learner redeems
+//! one for an AccessGrant with `granted_via: "voucher"`. The DHT only holds
+//! hashes of the codes, so a batch's codes are shown once, in the mint
+//! response.
+//!
+//! ## Routes
+//!
+//! - `POST /gates/{id}/vouchers` - Mint `{count, max_redemptions?, grant_type?, valid_days?, note?}`
+//!   (the gate's steward); answers with the batch and its codes
+//! - `POST /gates/{id}/redeem` - Redeem `{code}` for the caller; answers with the AccessGrant
+//! - `GET /admin/vouchers?gate_id=..` - Redemption rates per batch, of one gate or all (admins)
+
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::Incoming;
+use hyper::{Request, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use super::api::{error_response, json_response};
+use super::auth_helpers::require_user;
+use super::zome_helpers::call_content_store_for;
+use crate::auth::PermissionLevel;
+use crate::server::AppState;
+
+/// Largest request body accepted
+const MAX_BODY_BYTES: usize = 8 * 1024;
+
+/// Voucher action on a gate
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GateVoucherAction {
+    Mint,
+    Redeem,
+}
+
+/// Parse `/gates/{id}/{vouchers|redeem}`
+pub fn parse_gate_voucher_path(path: &str) -> Option<(&str, GateVoucherAction)> {
+    let rest = path.strip_prefix("/gates/")?;
+    let (id, action) = rest.rsplit_once('/')?;
+    let action = match action {
+        "vouchers" => GateVoucherAction::Mint,
+        "redeem" => GateVoucherAction::Redeem,
+        _ => return None,
+    };
+    (!id.is_empty() && !id.contains('/')).then_some((id, action))
+}
+
+/// Body of `POST /gates/{id}/vouchers`
+#[derive(Debug, Deserialize)]
+struct MintBody {
+    count: u32,
+    #[serde(default)]
+    max_redemptions: Option<u32>,
+    #[serde(default)]
+    grant_type: Option<String>,
+    #[serde(default)]
+    valid_days: Option<u32>,
+    #[serde(default)]
+    note: Option<String>,
+}
+
+/// Body of `POST /gates/{id}/redeem`
+#[derive(Debug, Deserialize)]
+struct RedeemBody {
+    code: String,
+}
+
+/// Must match CreateVoucherBatchInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct CreateVoucherBatchInput<'a> {
+    gate_id: &'a str,
+    minted_by: &'a str,
+    count: u32,
+    max_redemptions: Option<u32>,
+    grant_type: Option<String>,
+    valid_days: Option<u32>,
+    note: Option<String>,
+}
+
+/// Must match RedeemVoucherInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct RedeemVoucherInput<'a> {
+    gate_id: &'a str,
+    code: &'a str,
+    learner_agent_id: &'a str,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ReportParams {
+    gate_id: Option<String>,
+}
+
+/// Handle POST /gates/{id}/vouchers and POST /gates/{id}/redeem
+pub async fn handle_gate_voucher(
+    req: Request<Incoming>,
+    state: Arc<AppState>,
+    gate_id: &str,
+    action: GateVoucherAction,
+) -> Response<Full<Bytes>> {
+    let auth_header = req
+        .headers()
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string());
+    let claims = match require_user(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+
+    let body = match Limited::new(req.into_body(), MAX_BODY_BYTES)
+        .collect()
+        .await
+    {
+        Ok(collected) => collected.to_bytes(),
+        Err(_) => {
+            return error_response(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                &format!("Requests are limited to {MAX_BODY_BYTES} bytes"),
+                "TOO_LARGE",
+            )
+        }
+    };
+
+    match action {
+        GateVoucherAction::Mint => {
+            let mint: MintBody = match serde_json::from_slice(&body) {
+                Ok(mint) => mint,
+                Err(e) => {
+                    return error_response(
+                        StatusCode::BAD_REQUEST,
+                        &format!("Invalid request: {e}"),
+                        "INVALID_JSON",
+                    )
+                }
+            };
+            let input = CreateVoucherBatchInput {
+                gate_id,
+                minted_by: &claims.agent_pub_key,
+                count: mint.count,
+                max_redemptions: mint.max_redemptions,
+                grant_type: mint.grant_type,
+                valid_days: mint.valid_days,
+                note: mint.note,
+            };
+            match call_content_store_for(&state, "create_voucher_batch", &input, Some(&claims))
+                .await
+            {
+                Ok(data) => {
+                    info!(gate_id = %gate_id, count = mint.count, "Voucher batch minted");
+                    json_response(
+                        serde_json::to_vec(&data.unwrap_or(Value::Null)).unwrap_or_default(),
+                    )
+                }
+                Err(e) => {
+                    warn!(gate_id = %gate_id, error = ?e, "Failed to mint vouchers");
+                    error_response(
+                        StatusCode::FORBIDDEN,
+                        "Vouchers not minted (only the gate's steward can mint them)",
+                        "MINT_FAILED",
+                    )
+                }
+            }
+        }
+        GateVoucherAction::Redeem => {
+            let redeem: RedeemBody = match serde_json::from_slice(&body) {
+                Ok(redeem) => redeem,
+                Err(e) => {
+                    return error_response(
+                        StatusCode::BAD_REQUEST,
+                        &format!("Invalid request: {e}"),
+                        "INVALID_JSON",
+                    )
+                }
+            };
+            let input = RedeemVoucherInput {
+                gate_id,
+                code: &redeem.code,
+                learner_agent_id: &claims.agent_pub_key,
+            };
+            match call_content_store_for(&state, "redeem_voucher", &input, Some(&claims)).await {
+                Ok(data) => {
+                    info!(gate_id = %gate_id, learner = %claims.agent_pub_key, "Voucher redeemed");
+                    json_response(
+                        serde_json::to_vec(&data.unwrap_or(Value::Null)).unwrap_or_default(),
+                    )
+                }
+                Err(e) => {
+                    info!(gate_id = %gate_id, error = ?e, "Voucher refused");
+                    error_response(
+                        StatusCode::CONFLICT,
+                        "Voucher not redeemed (invalid, expired or already used)",
+                        "REDEEM_FAILED",
+                    )
+                }
+            }
+        }
+    }
+}
+
+/// Handle GET /admin/vouchers
+pub async fn handle_voucher_report(
+    state: Arc<AppState>,
+    query: Option<&str>,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    let claims = match require_user(&state, auth_header.as_deref()) {
+        Ok(claims) => claims,
+        Err(response) => return response,
+    };
+    if claims.permission_level < PermissionLevel::Admin {
+        return error_response(
+            StatusCode::FORBIDDEN,
+            "Admin permission required",
+            "FORBIDDEN",
+        );
+    }
+
+    let params: ReportParams = serde_urlencoded::from_str(query.unwrap_or("")).unwrap_or_default();
+    let gate_id = params.gate_id.filter(|id| !id.is_empty());
+
+    match call_content_store_for(
+        &state,
+        "get_voucher_redemption_report",
+        &gate_id,
+        Some(&claims),
+    )
+    .await
+    {
+        Ok(data) => json_response(
+            serde_json::to_vec(&data.unwrap_or(Value::Array(Vec::new()))).unwrap_or_default(),
+        ),
+        Err(e) => {
+            warn!(error = ?e, "Failed to load voucher report");
+            error_response(StatusCode::BAD_GATEWAY, "Lookup failed", "ZOME_ERROR")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_gate_voucher_path() {
+        assert_eq!(
+            parse_gate_voucher_path("/gates/gate-1/redeem"),
+            Some(("gate-1", GateVoucherAction::Redeem))
+        );
+        assert_eq!(
+            parse_gate_voucher_path("/gates/gate-1/vouchers"),
+            Some(("gate-1", GateVoucherAction::Mint))
+        );
+        assert_eq!(parse_gate_voucher_path("/gates//redeem"), None);
+        assert_eq!(parse_gate_voucher_path("/gates/a/b/redeem"), None);
+        assert_eq!(parse_gate_voucher_path("/gates/gate-1/refund"), None);
+        assert_eq!(parse_gate_voucher_path("/gates/gate-1"), None);
+    }
+
+    #[test]
+    fn test_redeem_input_shape() {
+        let input = RedeemVoucherInput {
+            gate_id: "gate-1",
+            code: "K7QH-M2XP-9TWD",
+            learner_agent_id: "uhCAk-learner",
+        };
+        let value = serde_json::to_value(&input).unwrap();
+        assert_eq!(value["code"], "K7QH-M2XP-9TWD");
+        assert_eq!(value["learner_agent_id"], "uhCAk-learner");
+    }
+}
@@ -4,12 +4,25 @@
 //! doorway's HTTP handlers, particularly for identity management operations.
 
 use serde::{Deserialize, Serialize};
+use std::time::Instant;
 use tracing::{debug, warn};
 
-use crate::server::AppState;
+use crate::auth::Claims;
+use crate::cache::{apply_write_invalidation, SingleFlight};
+use crate::proxy::admission::CallPriority;
+use crate::server::{staging, AppState};
 use crate::types::{DoorwayError, Result};
 use crate::worker::{ZomeCallBuilder, ZomeCallConfig};
 
+/// Cacheable content_store calls in flight, by cache key
+pub type ContentStoreFlights = SingleFlight<Result<Option<serde_json::Value>>>;
+
+/// Role name of the content DNA in the hApp manifest
+pub const CONTENT_ROLE: &str = "lamad";
+
+/// Zome serving content, paths and related data in the content DNA
+pub const CONTENT_ZOME: &str = "content_store";
+
 // =============================================================================
 // Imagodei Zome Types
 // =============================================================================
@@ -49,6 +62,74 @@ pub struct Human {
     pub updated_at: String,
 }
 
+/// Elohim registration from imagodei::get_elohim_by_scope
+/// Must match ElohimRegistration in holochain/dna/imagodei/zomes/imagodei_integrity/src/lib.rs
+#[derive(Debug, Clone, Deserialize)]
+pub struct ElohimRegistration {
+    pub id: String,
+    pub agent_id: String,
+    pub scope: String,
+    pub capabilities: Vec<String>,
+    pub endpoint: String,
+    pub holochain_agent_key: String,
+    pub active: bool,
+    pub registered_at: String,
+    pub updated_at: String,
+}
+
+/// Registration with liveness, as returned by imagodei::get_elohim_by_scope
+/// Must match ElohimRegistrationOutput in holochain/dna/imagodei/zomes/imagodei/src/lib.rs
+#[derive(Debug, Clone, Deserialize)]
+pub struct ElohimRegistrationOutput {
+    pub registration: ElohimRegistration,
+    pub last_heartbeat_micros: Option<i64>,
+    pub alive: bool,
+}
+
+/// Attestation entry from the zome
+/// Must match Attestation in holochain/dna/imagodei/zomes/imagodei_integrity/src/lib.rs
+#[derive(Debug, Clone, Deserialize)]
+pub struct Attestation {
+    pub id: String,
+    pub agent_id: String,
+    pub category: String,
+    pub attestation_type: String,
+    pub display_name: String,
+    pub description: String,
+    pub icon_url: Option<String>,
+    pub tier: Option<String>,
+    pub earned_via_json: String,
+    pub issued_at: String,
+    pub issued_by: String,
+    pub expires_at: Option<String>,
+    pub proof: Option<String>,
+}
+
+/// Must match AttestationRevocation in holochain/dna/imagodei/zomes/imagodei_integrity/src/lib.rs
+#[derive(Debug, Clone, Deserialize)]
+pub struct AttestationRevocation {
+    pub attestation_id: String,
+    pub revoked_by: String,
+    pub reason: String,
+    pub revoked_at: String,
+}
+
+/// Must match AttestationHolder in holochain/dna/imagodei/zomes/imagodei/src/lib.rs
+#[derive(Debug, Clone, Deserialize)]
+pub struct AttestationHolder {
+    pub human_id: String,
+    pub display_name: Option<String>,
+}
+
+/// Output from imagodei::verify_attestation
+/// Must match AttestationVerification in holochain/dna/imagodei/zomes/imagodei/src/lib.rs
+#[derive(Debug, Clone, Deserialize)]
+pub struct AttestationVerification {
+    pub attestation: Attestation,
+    pub holder: Option<AttestationHolder>,
+    pub revocation: Option<AttestationRevocation>,
+}
+
 // =============================================================================
 // Zome Call Functions
 // =============================================================================
@@ -81,7 +162,7 @@ pub async fn call_create_human(state: &AppState, input: CreateHumanInput) -> Res
     );
 
     // Build the zome call
-    let builder = ZomeCallBuilder::new(zome_config);
+    let builder = ZomeCallBuilder::new(zome_config.clone());
     let payload = builder.build_zome_call("create_human", &input)?;
 
     // Send via worker pool
@@ -95,6 +176,13 @@ pub async fn call_create_human(state: &AppState, input: CreateHumanInput) -> Res
         .parse_response(&response)?
         .ok_or_else(|| DoorwayError::Holochain("Empty response from create_human".into()))?;
 
+    invalidate_after_call(
+        state,
+        &zome_config,
+        "create_human",
+        &serde_json::to_value(&input).unwrap_or_default(),
+    );
+
     debug!(
         human_id = %result.human.id,
         "Successfully created human in imagodei zome"
@@ -103,6 +191,195 @@ pub async fn call_create_human(state: &AppState, input: CreateHumanInput) -> Res
     Ok(result)
 }
 
+/// Call imagodei::get_elohim_by_scope via the worker pool
+///
+/// Returns active registrations for the scope, live ones first.
+pub async fn call_get_elohim_by_scope(
+    state: &AppState,
+    scope: &str,
+) -> Result<Vec<ElohimRegistrationOutput>> {
+    let pool = state.pool.as_ref().ok_or_else(|| {
+        DoorwayError::Internal("Worker pool not available - conductor not connected?".into())
+    })?;
+
+    let zome_config = get_zome_config_by_role(state, "imagodei")?;
+
+    debug!(scope = %scope, "Calling get_elohim_by_scope on imagodei zome");
+
+    let builder = ZomeCallBuilder::new(zome_config);
+    let payload = builder.build_zome_call("get_elohim_by_scope", &scope)?;
+
+    let response = pool
+        .request(payload)
+        .await
+        .map_err(|e| DoorwayError::Holochain(format!("Zome call failed: {e}")))?;
+
+    Ok(builder.parse_response(&response)?.unwrap_or_default())
+}
+
+/// Call imagodei::verify_attestation via the worker pool
+///
+/// Returns None when no attestation has the id.
+pub async fn call_verify_attestation(
+    state: &AppState,
+    attestation_id: &str,
+) -> Result<Option<AttestationVerification>> {
+    let pool = state.pool.as_ref().ok_or_else(|| {
+        DoorwayError::Internal("Worker pool not available - conductor not connected?".into())
+    })?;
+
+    let zome_config = get_zome_config_by_role(state, "imagodei")?;
+
+    debug!(attestation_id = %attestation_id, "Calling verify_attestation on imagodei zome");
+
+    let builder = ZomeCallBuilder::new(zome_config);
+    let payload = builder.build_zome_call("verify_attestation", &attestation_id)?;
+
+    let response = pool
+        .request(payload)
+        .await
+        .map_err(|e| DoorwayError::Holochain(format!("Zome call failed: {e}")))?;
+
+    Ok(builder
+        .parse_response::<Option<AttestationVerification>>(&response)?
+        .flatten())
+}
+
+/// Call a content_store zome function via the worker pool
+///
+/// Returns the raw zome output as JSON; doorway does not interpret it.
+/// The call is made for no one in particular: cacheable reads are low
+/// priority and can fail with [`DoorwayError::Overloaded`] (see
+/// [`call_content_store_for`]).
+pub async fn call_content_store<I: Serialize>(
+    state: &AppState,
+    fn_name: &str,
+    input: &I,
+) -> Result<Option<serde_json::Value>> {
+    call_content_store_for(state, fn_name, input, None).await
+}
+
+/// Call a content_store zome function for a caller
+///
+/// `caller` is the signed-in human the call is made for, if any. Cacheable
+/// reads made anonymously are low priority and give way while the conductor
+/// is under pressure (see [`admission`](crate::proxy::admission)); those
+/// can fail with [`DoorwayError::Overloaded`].
+pub async fn call_content_store_for<I: Serialize>(
+    state: &AppState,
+    fn_name: &str,
+    input: &I,
+    caller: Option<&Claims>,
+) -> Result<Option<serde_json::Value>> {
+    let pool = state.pool.as_ref().ok_or_else(|| {
+        DoorwayError::Internal("Worker pool not available - conductor not connected?".into())
+    })?;
+
+    let zome_config = get_content_store_config(state)?;
+
+    debug!(fn_name = %fn_name, "Calling content_store zome");
+
+    let builder = ZomeCallBuilder::new(zome_config.clone());
+    let payload = builder.build_zome_call(fn_name, input)?;
+    let cacheable = state
+        .cache_rules
+        .get_rule(&zome_config.dna_hash, fn_name)
+        .is_some_and(|rule| rule.cacheable);
+    // Anonymous reads give way while the conductor is under pressure
+    let priority = if cacheable && caller.is_none() {
+        CallPriority::Low
+    } else {
+        CallPriority::Normal
+    };
+
+    let (builder, zome_config) = (&builder, &zome_config);
+    let call = move || async move {
+        let started = Instant::now();
+        let response = pool
+            .request_with_priority(payload, priority)
+            .await
+            .map_err(|e| match e {
+                DoorwayError::Overloaded(_) => e,
+                e => DoorwayError::Holochain(format!("Zome call failed: {e}")),
+            })?;
+
+        let args = serde_json::to_value(input).unwrap_or_default();
+        if let Some(ref advisor) = state.query_advisor {
+            advisor.record_call(
+                &zome_config.dna_hash,
+                fn_name,
+                started.elapsed(),
+                cacheable,
+                &args,
+            );
+        }
+
+        let output = builder.parse_response::<serde_json::Value>(&response)?;
+        invalidate_after_call(state, zome_config, fn_name, &args);
+
+        Ok(output)
+    };
+
+    if !cacheable {
+        return call().await;
+    }
+
+    // Identical cacheable calls share one conductor call (cold cache bursts);
+    // anonymous calls fly apart so a shed one doesn't fail signed-in callers
+    let mut key = state
+        .cache_rules
+        .cache_key(
+            &zome_config.dna_hash,
+            &zome_config.zome_name,
+            fn_name,
+            input,
+        )
+        .to_storage_key();
+    if priority == CallPriority::Low {
+        key.push_str(":low");
+    }
+    state.zome_flights.run(&key, call).await
+}
+
+/// Drop cached results made stale by a successful zome call
+///
+/// Covers the DNA's own `invalidated_by` rules and, separately, rules in
+/// other DNAs that depend on this role through bridge calls (e.g. content
+/// step gating that reads mastery from imagodei). Rules naming a field of
+/// the call's `input` only lose the entries for the IDs it holds.
+pub fn invalidate_after_call(
+    state: &AppState,
+    config: &ZomeCallConfig,
+    fn_name: &str,
+    input: &serde_json::Value,
+) -> usize {
+    let targets =
+        state
+            .cache_rules
+            .write_invalidations(&config.role_name, &config.dna_hash, fn_name);
+
+    if let Some(ref advisor) = state.query_advisor {
+        for target in &targets {
+            advisor.record_invalidation(&target.dna_hash, &target.fn_name);
+        }
+    }
+
+    let removed: usize = targets
+        .iter()
+        .map(|target| apply_write_invalidation(&state.cache, target, input))
+        .sum();
+
+    if removed > 0 {
+        debug!(
+            role = %config.role_name,
+            fn_name = %fn_name,
+            removed,
+            "Invalidated cache entries after zome call"
+        );
+    }
+    removed
+}
+
 /// Get agent public key from the imagodei zome config
 ///
 /// Returns the agent public key that the conductor uses for this app.
@@ -132,13 +409,27 @@ pub fn get_agent_pub_key(state: &AppState) -> Result<String> {
 /// Searches through discovered zome configs to find the one with matching role_name.
 /// Role names are defined in the hApp manifest (e.g., "lamad", "imagodei", "infrastructure").
 fn get_zome_config_by_role(state: &AppState, role_name: &str) -> Result<ZomeCallConfig> {
+    // For imagodei role, the zome is also named "imagodei"
+    get_zome_config(state, role_name, role_name)
+}
+
+/// Get ZomeCallConfig for the content_store zome in the lamad DNA
+pub fn get_content_store_config(state: &AppState) -> Result<ZomeCallConfig> {
+    get_zome_config(state, CONTENT_ROLE, CONTENT_ZOME)
+}
+
+/// Get ZomeCallConfig for a role, targeting the given zome
+///
+/// Staging requests get the role's parallel staging cell and never fall back
+/// to production.
+fn get_zome_config(state: &AppState, role_name: &str, zome_name: &str) -> Result<ZomeCallConfig> {
+    let role_name = &staging::role_name(role_name);
     for entry in state.zome_configs.iter() {
         let config = entry.value();
         if config.role_name == role_name {
             // Clone the config and set the correct zome name
             let mut result = config.clone();
-            // For imagodei role, the zome is also named "imagodei"
-            result.zome_name = role_name.to_string();
+            result.zome_name = zome_name.to_string();
             return Ok(result);
         }
     }
@@ -0,0 +1,102 @@
+//! Zome Policy Routes
+//!
+//! Admin access to the [zome call policy](crate::auth::policy) the app proxy
+//! enforces, so operators can change who may call what without a redeploy.
+//!
+//! ## Routes
+//!
+//! - `GET /admin/zome-policy` - Current rules and how many calls they denied
+//! - `PUT /admin/zome-policy` - Replace the rules with `{rules: [...]}`; saved
+//!   to `ZOME_POLICY_FILE` when configured
+
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::Incoming;
+use hyper::{Request, Response, StatusCode};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use super::api::{error_response, json_response};
+use super::auth_helpers::require_admin;
+use crate::auth::policy::PolicySet;
+use crate::server::AppState;
+
+/// Largest policy document accepted
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+fn policy_view(state: &AppState) -> Response<Full<Bytes>> {
+    let policy = state.zome_policy.current();
+    json_response(
+        serde_json::to_vec(&serde_json::json!({
+            "rules": policy.rules,
+            "denied_calls": state.zome_policy.denied_count(),
+            "persisted": state.args.zome_policy_file.is_some(),
+        }))
+        .unwrap_or_default(),
+    )
+}
+
+/// Handle GET /admin/zome-policy
+pub fn handle_get_zome_policy(
+    state: Arc<AppState>,
+    auth_header: Option<String>,
+) -> Response<Full<Bytes>> {
+    if let Err(response) = require_admin(&state, auth_header.as_deref()) {
+        return response;
+    }
+    policy_view(&state)
+}
+
+/// Handle PUT /admin/zome-policy
+pub async fn handle_put_zome_policy(
+    req: Request<Incoming>,
+    state: Arc<AppState>,
+) -> Response<Full<Bytes>> {
+    let auth_header = req
+        .headers()
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string());
+    if let Err(response) = require_admin(&state, auth_header.as_deref()) {
+        return response;
+    }
+
+    let body = match Limited::new(req.into_body(), MAX_BODY_BYTES)
+        .collect()
+        .await
+    {
+        Ok(collected) => collected.to_bytes(),
+        Err(_) => {
+            return error_response(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                &format!("Policies are limited to {MAX_BODY_BYTES} bytes"),
+                "TOO_LARGE",
+            )
+        }
+    };
+    let policy = match std::str::from_utf8(&body)
+        .map_err(|e| e.to_string())
+        .and_then(PolicySet::parse)
+    {
+        Ok(policy) => policy,
+        Err(e) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                &format!("Invalid policy: {e}"),
+                "BAD_REQUEST",
+            )
+        }
+    };
+
+    let rules = policy.rules.len();
+    if let Err(e) = state.zome_policy.replace(policy) {
+        warn!(error = %e, "Failed to save zome policy");
+        return error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to save zome policy",
+            "INTERNAL_ERROR",
+        );
+    }
+    info!(rules, "Zome policy replaced by admin");
+    policy_view(&state)
+}
@@ -27,6 +27,8 @@ use crate::nats::{HostRouter, NatsClient};
 use crate::orchestrator::OrchestratorState;
 use crate::projection::{ProjectionConfig, ProjectionStore};
 use crate::routes;
+use crate::server::limits::BodyClass;
+use crate::server::staging;
 use crate::server::websocket;
 use crate::services::{
     spawn_health_probe_task, CustodianService, CustodianServiceConfig, VerificationService,
@@ -101,6 +103,70 @@ pub struct AppState {
     pub peer_url_list: crate::services::federation::PeerUrlList,
     /// Cached P2P health from elohim-storage sidecar (polled every 30s)
     pub p2p_health: Arc<tokio::sync::RwLock<Option<crate::routes::health::P2PHealth>>>,
+    /// Per-agent "what to learn next" ranking (requires zome_caller)
+    pub recommendations: Option<Arc<crate::worker::recommendations::RecommendationEngine>>,
+    /// Torrent metadata for large blobs (requires storage and public doorway URLs)
+    pub torrents: Option<Arc<crate::worker::torrent::TorrentGenerator>>,
+    /// Signed URLs for protected media (requires MEDIA_URL_SECRET)
+    pub media_urls: Option<Arc<crate::services::media_urls::MediaUrlSigner>>,
+    /// Partner sites allowed to embed learning paths (EMBED_PARTNERS set)
+    pub embed: Option<Arc<crate::services::embed::EmbedPolicy>>,
+    /// Anonymous read-only guest sessions (requires GUEST_TOKEN_SECRET)
+    pub guest_sessions: Option<Arc<crate::services::guest_sessions::GuestSessions>>,
+    /// Signed calendar feed URLs (requires CALENDAR_FEED_SECRET)
+    pub calendar_feeds: Option<Arc<crate::services::calendar::CalendarFeeds>>,
+    /// Conductor pressure thresholds for low-priority calls (None when disabled)
+    pub admission: Option<Arc<crate::proxy::admission::AdmissionPolicy>>,
+    /// Doorways in other regions, for nearest-doorway hints (REGION set)
+    pub regions: Option<Arc<crate::services::regions::RegionDirectory>>,
+    /// Views of steward-marked content, counted into daily rollups (needs MongoDB)
+    pub content_access: Option<Arc<crate::services::content_access::ContentAccessLog>>,
+    /// Per-caller import baselines and throttles (None when disabled)
+    pub import_abuse: Option<Arc<crate::services::import_abuse::ImportAbuseDetector>>,
+    /// Generated sitemaps (requires projection and public doorway URL)
+    pub sitemaps: Option<Arc<crate::worker::sitemap::SitemapGenerator>>,
+    /// Signed Open Badges exports of learners' attestations (requires an issuer key)
+    pub badge_exports: Option<Arc<crate::worker::badge_export::BadgeExporter>>,
+    /// Printable PDFs of public paths (requires a renderer)
+    pub path_renderer: Option<Arc<crate::worker::path_render::PathRenderer>>,
+    /// Drafts missing translations for steward review (requires a provider)
+    pub machine_translation: Option<Arc<crate::worker::machine_translation::MachineTranslator>>,
+    /// Content embeddings for semantic related-content (requires MongoDB and a provider)
+    pub semantic: Option<Arc<crate::worker::embeddings::SemanticIndex>>,
+    /// Near-duplicate check for content imports (requires projection)
+    pub duplicate_detector: Option<Arc<crate::services::duplicate_detection::DuplicateDetector>>,
+    /// Content-grounded tutor chat proxy (requires a provider)
+    pub tutor: Option<Arc<crate::services::tutor::TutorService>>,
+    /// Moderation filter and quarantine queue for user writes (requires MongoDB)
+    pub moderation: Option<Arc<crate::services::moderation::ModerationService>>,
+    /// Signed calls to and from doorways of other communities (requires reciprocal peers)
+    pub reciprocal: Option<Arc<crate::services::reciprocal_federation::ReciprocalFederation>>,
+    /// Trusted signers of import manifests (requires IMPORT_SIGNERS)
+    pub import_signers: Option<Arc<crate::services::import_provenance::ImportSigners>>,
+    /// Operator-defined import validation rules (requires IMPORT_RULES or plugins)
+    pub import_validator: Option<Arc<crate::services::import_validation::ImportValidator>>,
+    /// Durable journal of conductor signals for replay (requires MongoDB)
+    pub signal_journal: Option<Arc<crate::worker::signal_journal::SignalJournal>>,
+    /// Slow query log and cache-rule tuning advisor (None when disabled)
+    pub query_advisor: Option<Arc<crate::worker::query_advisor::QueryAdvisor>>,
+    /// Retention policies over doorway-held data (requires RETENTION_POLICIES and MongoDB)
+    pub retention: Option<Arc<crate::worker::retention::RetentionEngine>>,
+    /// Runtime settings the community can change through governance
+    pub governance: Arc<crate::worker::governance::GovernedSettings>,
+    /// Request body and WebSocket frame limits per route class
+    pub body_limits: Arc<crate::server::limits::BodyLimits>,
+    /// Outbound queue sizes and slow-client policy for app WebSockets
+    pub ws_outbound: Arc<crate::proxy::outbound::OutboundPolicy>,
+    /// Heartbeat interval, liveness timeout and reaped app connections
+    pub ws_heartbeat: Arc<crate::proxy::heartbeat::HeartbeatPolicy>,
+    /// Conductor reconnect hold window and upstream drops for app WebSockets
+    pub ws_reconnect: Arc<crate::proxy::reconnect::ReconnectPolicy>,
+    /// Who is studying which path or step, for presence rooms
+    pub presence: Arc<crate::proxy::presence::PresenceHub>,
+    /// Operator's allow/deny rules for zome calls
+    pub zome_policy: Arc<crate::auth::policy::PolicyStore>,
+    /// Cacheable content_store calls in flight, shared by identical callers
+    pub zome_flights: Arc<crate::routes::zome_helpers::ContentStoreFlights>,
 }
 
 impl AppState {
@@ -137,6 +203,14 @@ impl AppState {
         let peer_url_list =
             crate::services::federation::new_peer_url_list(args.federation_peers.clone());
 
+        let body_limits = Arc::new(crate::server::limits::BodyLimits::from_args(&args));
+        let ws_outbound = Arc::new(crate::proxy::outbound::OutboundPolicy::from_args(&args));
+        let ws_heartbeat = Arc::new(crate::proxy::heartbeat::HeartbeatPolicy::from_args(&args));
+        let ws_reconnect = Arc::new(crate::proxy::reconnect::ReconnectPolicy::from_args(&args));
+        let presence = Arc::new(crate::proxy::presence::PresenceHub::from_args(&args));
+        let zome_policy = Arc::new(crate::auth::policy::PolicyStore::from_args(&args));
+        let zome_flights = Arc::new(crate::cache::SingleFlight::new());
+
         Self {
             args,
             mongo: None,
@@ -167,6 +241,38 @@ impl AppState {
             peer_cache: crate::services::federation::new_peer_cache(),
             peer_url_list,
             p2p_health: Arc::new(tokio::sync::RwLock::new(None)),
+            recommendations: None,
+            torrents: None,
+            media_urls: None,
+            embed: None,
+            guest_sessions: None,
+            calendar_feeds: None,
+            import_abuse: None,
+            admission: None,
+            regions: None,
+            content_access: None,
+            sitemaps: None,
+            badge_exports: None,
+            path_renderer: None,
+            machine_translation: None,
+            semantic: None,
+            duplicate_detector: None,
+            tutor: None,
+            moderation: None,
+            reciprocal: None,
+            import_signers: None,
+            import_validator: None,
+            signal_journal: None,
+            query_advisor: None,
+            retention: None,
+            governance: Arc::new(crate::worker::governance::GovernedSettings::new()),
+            body_limits,
+            ws_outbound,
+            ws_heartbeat,
+            ws_reconnect,
+            presence,
+            zome_policy,
+            zome_flights,
         }
     }
 
@@ -207,6 +313,14 @@ impl AppState {
         let peer_url_list =
             crate::services::federation::new_peer_url_list(args.federation_peers.clone());
 
+        let body_limits = Arc::new(crate::server::limits::BodyLimits::from_args(&args));
+        let ws_outbound = Arc::new(crate::proxy::outbound::OutboundPolicy::from_args(&args));
+        let ws_heartbeat = Arc::new(crate::proxy::heartbeat::HeartbeatPolicy::from_args(&args));
+        let ws_reconnect = Arc::new(crate::proxy::reconnect::ReconnectPolicy::from_args(&args));
+        let presence = Arc::new(crate::proxy::presence::PresenceHub::from_args(&args));
+        let zome_policy = Arc::new(crate::auth::policy::PolicyStore::from_args(&args));
+        let zome_flights = Arc::new(crate::cache::SingleFlight::new());
+
         Self {
             args,
             mongo,
@@ -237,6 +351,38 @@ impl AppState {
             peer_cache: crate::services::federation::new_peer_cache(),
             peer_url_list,
             p2p_health: Arc::new(tokio::sync::RwLock::new(None)),
+            recommendations: None,
+            torrents: None,
+            media_urls: None,
+            embed: None,
+            guest_sessions: None,
+            calendar_feeds: None,
+            import_abuse: None,
+            admission: None,
+            regions: None,
+            content_access: None,
+            sitemaps: None,
+            badge_exports: None,
+            path_renderer: None,
+            machine_translation: None,
+            semantic: None,
+            duplicate_detector: None,
+            tutor: None,
+            moderation: None,
+            reciprocal: None,
+            import_signers: None,
+            import_validator: None,
+            signal_journal: None,
+            query_advisor: None,
+            retention: None,
+            governance: Arc::new(crate::worker::governance::GovernedSettings::new()),
+            body_limits,
+            ws_outbound,
+            ws_heartbeat,
+            ws_reconnect,
+            presence,
+            zome_policy,
+            zome_flights,
         }
     }
 
@@ -292,6 +438,14 @@ impl AppState {
         let peer_url_list =
             crate::services::federation::new_peer_url_list(args.federation_peers.clone());
 
+        let body_limits = Arc::new(crate::server::limits::BodyLimits::from_args(&args));
+        let ws_outbound = Arc::new(crate::proxy::outbound::OutboundPolicy::from_args(&args));
+        let ws_heartbeat = Arc::new(crate::proxy::heartbeat::HeartbeatPolicy::from_args(&args));
+        let ws_reconnect = Arc::new(crate::proxy::reconnect::ReconnectPolicy::from_args(&args));
+        let presence = Arc::new(crate::proxy::presence::PresenceHub::from_args(&args));
+        let zome_policy = Arc::new(crate::auth::policy::PolicyStore::from_args(&args));
+        let zome_flights = Arc::new(crate::cache::SingleFlight::new());
+
         Self {
             args,
             mongo,
@@ -322,6 +476,38 @@ impl AppState {
             peer_cache: crate::services::federation::new_peer_cache(),
             peer_url_list,
             p2p_health: Arc::new(tokio::sync::RwLock::new(None)),
+            recommendations: None,
+            torrents: None,
+            media_urls: None,
+            embed: None,
+            guest_sessions: None,
+            calendar_feeds: None,
+            import_abuse: None,
+            admission: None,
+            regions: None,
+            content_access: None,
+            sitemaps: None,
+            badge_exports: None,
+            path_renderer: None,
+            machine_translation: None,
+            semantic: None,
+            duplicate_detector: None,
+            tutor: None,
+            moderation: None,
+            reciprocal: None,
+            import_signers: None,
+            import_validator: None,
+            signal_journal: None,
+            query_advisor: None,
+            retention: None,
+            governance: Arc::new(crate::worker::governance::GovernedSettings::new()),
+            body_limits,
+            ws_outbound,
+            ws_heartbeat,
+            ws_reconnect,
+            presence,
+            zome_policy,
+            zome_flights,
         }
     }
 
@@ -351,8 +537,21 @@ impl AppState {
         let cache_rules = Arc::new(CacheRuleStore::new());
 
         // Initialize projection store with MongoDB
-        let projection_store =
+        let mut projection_store =
             ProjectionStore::new(mongo.clone(), ProjectionConfig::default()).await?;
+        let l1_rules =
+            crate::db::l1::parse_l1_rules(&args.l1_cache_rules).map_err(DoorwayError::Config)?;
+        if !l1_rules.is_empty() {
+            info!(
+                "L1 cache enabled for {:?} ({} entries)",
+                l1_rules.iter().map(|rule| &rule.doc_type).collect::<Vec<_>>(),
+                args.l1_cache_capacity
+            );
+            projection_store = projection_store.with_l1(Arc::new(crate::db::L1Cache::new(
+                args.l1_cache_capacity,
+                l1_rules,
+            )));
+        }
         let projection = Some(Arc::new(projection_store));
 
         let signing = Arc::new(SigningService::new(SigningConfig::default()));
@@ -371,6 +570,14 @@ impl AppState {
         let peer_url_list =
             crate::services::federation::new_peer_url_list(args.federation_peers.clone());
 
+        let body_limits = Arc::new(crate::server::limits::BodyLimits::from_args(&args));
+        let ws_outbound = Arc::new(crate::proxy::outbound::OutboundPolicy::from_args(&args));
+        let ws_heartbeat = Arc::new(crate::proxy::heartbeat::HeartbeatPolicy::from_args(&args));
+        let ws_reconnect = Arc::new(crate::proxy::reconnect::ReconnectPolicy::from_args(&args));
+        let presence = Arc::new(crate::proxy::presence::PresenceHub::from_args(&args));
+        let zome_policy = Arc::new(crate::auth::policy::PolicyStore::from_args(&args));
+        let zome_flights = Arc::new(crate::cache::SingleFlight::new());
+
         Ok(Self {
             args,
             mongo: Some(mongo),
@@ -401,6 +608,38 @@ impl AppState {
             peer_cache: crate::services::federation::new_peer_cache(),
             peer_url_list,
             p2p_health: Arc::new(tokio::sync::RwLock::new(None)),
+            recommendations: None,
+            torrents: None,
+            media_urls: None,
+            embed: None,
+            guest_sessions: None,
+            calendar_feeds: None,
+            import_abuse: None,
+            admission: None,
+            regions: None,
+            content_access: None,
+            sitemaps: None,
+            badge_exports: None,
+            path_renderer: None,
+            machine_translation: None,
+            semantic: None,
+            duplicate_detector: None,
+            tutor: None,
+            moderation: None,
+            reciprocal: None,
+            import_signers: None,
+            import_validator: None,
+            signal_journal: None,
+            query_advisor: None,
+            retention: None,
+            governance: Arc::new(crate::worker::governance::GovernedSettings::new()),
+            body_limits,
+            ws_outbound,
+            ws_heartbeat,
+            ws_reconnect,
+            presence,
+            zome_policy,
+            zome_flights,
         })
     }
 
@@ -531,11 +770,37 @@ pub async fn run(state: Arc<AppState>) -> Result<(), DoorwayError> {
     }
 }
 
-/// Route incoming HTTP requests
+/// Handle an incoming HTTP request, in staging scope for staging API keys
 async fn handle_request(
     state: Arc<AppState>,
     addr: SocketAddr,
     req: Request<Incoming>,
+) -> Result<Response<BoxBody>, hyper::Error> {
+    // Paged listings are linked relative to the URL they were asked for
+    let uri = req.uri().clone();
+
+    if !staging::is_staging_request(&state.args, req.headers()) {
+        let mut response = route_request(state, addr, req).await?;
+        routes::pagination::add_link_header(&mut response, &uri);
+        return Ok(response);
+    }
+
+    info!("[{}] Serving {} from staging cells", addr, req.uri().path());
+    let role_suffix = state.args.staging_role_suffix.clone();
+    let mut response = staging::scope(role_suffix, route_request(state, addr, req)).await?;
+    response.headers_mut().insert(
+        staging::ENVIRONMENT_HEADER,
+        hyper::header::HeaderValue::from_static("staging"),
+    );
+    routes::pagination::add_link_header(&mut response, &uri);
+    Ok(response)
+}
+
+/// Route incoming HTTP requests
+async fn route_request(
+    state: Arc<AppState>,
+    addr: SocketAddr,
+    req: Request<Incoming>,
 ) -> Result<Response<BoxBody>, hyper::Error> {
     let method = req.method().clone();
     let path = req.uri().path().to_string();
@@ -565,6 +830,13 @@ async fn handle_request(
         }
     }
 
+    // Turn away oversized bodies before any handler buffers them
+    if let Some(class) = BodyClass::for_route(&method, &path) {
+        if let Err(response) = state.body_limits.check_declared(class, req.headers()) {
+            return Ok(to_boxed(response));
+        }
+    }
+
     // Handle auth routes (/auth/*) - these consume the request
     if path.starts_with("/auth") {
         if let Some(response) = routes::handle_auth_request(req, Arc::clone(&state)).await {
@@ -593,6 +865,32 @@ async fn handle_request(
         // Comprehensive status (runtime stats, cluster health, storage diagnostics)
         (Method::GET, "/status") => to_boxed(routes::status_check(Arc::clone(&state)).await),
 
+        // Nearest healthy doorway for a client, or a redirect to it
+        (Method::GET, "/region-hint") => {
+            let client_region = req
+                .headers()
+                .get(routes::region_hint::CLIENT_REGION_HEADER)
+                .and_then(|v| v.to_str().ok())
+                .map(|s| s.to_string());
+            to_boxed(routes::handle_region_hint(
+                Arc::clone(&state),
+                req.uri().query(),
+                client_region,
+            ))
+        }
+
+        // Zome-declared capabilities (cache rules, import config, functions)
+        (Method::GET, "/discovery") => to_boxed(routes::handle_discovery(Arc::clone(&state))),
+
+        // Presence rooms: who else is studying a path or step
+        (Method::GET, "/presence") if hyper_tungstenite::is_upgrade_request(&req) => {
+            return Ok(to_boxed(routes::handle_presence(req, Arc::clone(&state))));
+        }
+        (Method::GET, "/presence/count") => to_boxed(routes::handle_presence_count(
+            Arc::clone(&state),
+            req.uri().query(),
+        )),
+
         // Debug stream WebSocket for real-time debugging
         (Method::GET, "/debug/stream") if hyper_tungstenite::is_upgrade_request(&req) => {
             return Ok(to_boxed(
@@ -630,6 +928,37 @@ async fn handle_request(
             to_boxed(routes::handle_federation_p2p_peers(Arc::clone(&state)).await)
         }
 
+        // Reciprocal federation with other communities' doorways
+        (Method::GET, "/federation/reciprocal") => {
+            to_boxed(routes::handle_reciprocal_peers(Arc::clone(&state)))
+        }
+
+        // POST /federation/peers/{doorway_id}/call/{fn}
+        (Method::POST, p) if routes::reciprocal::parse_peer_call_path(p).is_some() => {
+            match routes::reciprocal::parse_peer_call_path(p) {
+                Some((peer_id, fn_name)) => {
+                    to_boxed(routes::handle_peer_call(req, state, peer_id, fn_name).await)
+                }
+                None => to_boxed(routes::api::error_response(
+                    StatusCode::NOT_FOUND,
+                    "Not found",
+                    "NOT_FOUND",
+                )),
+            }
+        }
+
+        // POST /federation/v1/call/{fn} (signed, from a peer doorway)
+        (Method::POST, p) if routes::reciprocal::parse_inbound_call_path(p).is_some() => {
+            match routes::reciprocal::parse_inbound_call_path(p) {
+                Some(fn_name) => to_boxed(routes::handle_inbound_call(req, state, fn_name).await),
+                None => to_boxed(routes::api::error_response(
+                    StatusCode::NOT_FOUND,
+                    "Not found",
+                    "NOT_FOUND",
+                )),
+            }
+        }
+
         // CORS preflight
         (Method::OPTIONS, _) => to_boxed(preflight_response()),
 
@@ -886,6 +1215,24 @@ async fn handle_request(
             }
         }
 
+        // Cache snapshots for blue-green deploys
+        (Method::GET, "/admin/cache/dump") => {
+            to_boxed(routes::handle_cache_dump(&req, Arc::clone(&state)))
+        }
+        (Method::POST, "/admin/cache/load") => {
+            to_boxed(routes::handle_cache_load(req, Arc::clone(&state)).await)
+        }
+
+        // Per-rule cache hit ratios and rollups: GET /admin/cache/stats?hours=
+        (Method::GET, "/admin/cache/stats") => {
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            to_boxed(routes::handle_cache_stats(state, req.uri().query(), auth_header).await)
+        }
+
         // Admin seed routes for bulk upload
         // PUT /admin/seed/blob - Upload blob to projection cache
         (Method::PUT, "/admin/seed/blob") => {
@@ -945,7 +1292,8 @@ async fn handle_request(
                 "https"
             };
             let base_url = format!("{scheme}://{host}");
-            to_boxed(routes::handle_stream_request(state, p, &base_url).await)
+            let query = req.uri().query().map(String::from);
+            to_boxed(routes::handle_stream_request(state, p, query.as_deref(), &base_url).await)
         }
 
         // Blob verification endpoint
@@ -954,72 +1302,61 @@ async fn handle_request(
         // Content store streaming with Range support (HTTP 206)
         // GET /store/{hash} - Stream entire content or byte range
         // HEAD /store/{hash} - Get content metadata
-        // Falls back to elohim-storage proxy on cache miss
-        (Method::GET, p) if p.starts_with("/store/") => {
-            match routes::blob::handle_blob_request_with_storage_proxy(
-                req,
-                Arc::clone(&state.cache),
-                state.args.storage_url.clone(),
-            )
-            .await
-            {
-                Ok(resp) => to_boxed(resp),
-                Err(err) => to_boxed(routes::blob::error_response(err)),
-            }
-        }
-        (Method::HEAD, p) if p.starts_with("/store/") => {
-            match routes::blob::handle_blob_request_with_storage_proxy(
-                req,
-                Arc::clone(&state.cache),
-                state.args.storage_url.clone(),
-            )
-            .await
-            {
-                Ok(resp) => to_boxed(resp),
-                Err(err) => to_boxed(routes::blob::error_response(err)),
-            }
+        // Falls back to elohim-storage proxy on cache miss; protected media
+        // needs a signed URL
+        (Method::GET | Method::HEAD, p) if p.starts_with("/store/") => {
+            to_boxed(routes::handle_guarded_blob(req, state).await)
         }
 
         // Blob API alias for /store/* (used by Angular app in doorway mode)
         // GET /api/blob/{hash} - Stream entire content or byte range
         // HEAD /api/blob/{hash} - Get content metadata
-        // Falls back to elohim-storage proxy on cache miss
-        (Method::GET, p) if p.starts_with("/api/blob/") => {
-            // Rewrite path from /api/blob/{hash} to /store/{hash} for blob handler
+        (Method::GET | Method::HEAD, p) if p.starts_with("/api/blob/") => {
+            // Rewrite path from /api/blob/{hash} to /store/{hash}, keeping
+            // any URL signature
             let hash = p.strip_prefix("/api/blob/").unwrap_or("");
-            let new_uri = format!("/store/{hash}");
+            let new_uri = match req.uri().query() {
+                Some(query) => format!("/store/{hash}?{query}"),
+                None => format!("/store/{hash}"),
+            };
             let (mut parts, body) = req.into_parts();
             parts.uri = new_uri.parse().unwrap_or(parts.uri);
             let req = Request::from_parts(parts, body);
-            match routes::blob::handle_blob_request_with_storage_proxy(
-                req,
-                Arc::clone(&state.cache),
-                state.args.storage_url.clone(),
-            )
-            .await
-            {
-                Ok(resp) => to_boxed(resp),
-                Err(err) => to_boxed(routes::blob::error_response(err)),
-            }
+            to_boxed(routes::handle_guarded_blob(req, state).await)
         }
-        (Method::HEAD, p) if p.starts_with("/api/blob/") => {
-            let hash = p.strip_prefix("/api/blob/").unwrap_or("");
-            let new_uri = format!("/store/{hash}");
-            let (mut parts, body) = req.into_parts();
-            parts.uri = new_uri.parse().unwrap_or(parts.uri);
-            let req = Request::from_parts(parts, body);
-            match routes::blob::handle_blob_request_with_storage_proxy(
-                req,
-                Arc::clone(&state.cache),
-                state.args.storage_url.clone(),
-            )
-            .await
-            {
+
+        // Signed URL for protected media
+        // GET /api/v1/media/{hash}/url
+        (Method::GET, p) if routes::media_urls::parse_media_url_path(p).is_some() => {
+            let address = routes::media_urls::parse_media_url_path(p).unwrap_or("");
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            to_boxed(routes::handle_media_url(state, address, auth_header).await)
+        }
+
+        // Torrent metadata for large blobs, web-seeded from /store/{hash}
+        // GET /blobs/{hash}/torrent
+        (Method::GET, p) if routes::blob::parse_torrent_path(p).is_some() => {
+            let address = routes::blob::parse_torrent_path(p).unwrap_or("");
+            let torrent = if routes::media_urls::torrent_allowed(&state, address).await {
+                routes::blob::handle_blob_torrent(address, state.torrents.as_ref())
+            } else {
+                Err(routes::BlobError::NotFound)
+            };
+            match torrent {
                 Ok(resp) => to_boxed(resp),
                 Err(err) => to_boxed(routes::blob::error_response(err)),
             }
         }
 
+        // Anonymous read-only session for landing pages: POST /guest/session
+        (Method::POST, "/guest/session") => {
+            to_boxed(routes::handle_guest_session(state, addr.ip()))
+        }
+
         // Cache API routes: GET /api/v1/cache/{type}/{id?}
         (Method::GET, p) if p.starts_with("/api/v1/cache/") => {
             let query = req.uri().query();
@@ -1030,11 +1367,734 @@ async fn handle_request(
                 .and_then(|h| h.to_str().ok())
                 .map(|s| s.to_string());
             let remote_ip = addr.ip();
+            // Referrer only picks a bucket in content access rollups
+            let referer = req
+                .headers()
+                .get("referer")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            to_boxed(
+                routes::handle_api_request(state, p, query, Some(remote_ip), auth_header, referer)
+                    .await,
+            )
+        }
+
+        // Content query API: GET /api/v1/content/query?type=..&tags=..&reach=..
+        (Method::GET, "/api/v1/content/query") => {
+            to_boxed(routes::handle_content_query(state, req.uri().query()).await)
+        }
+
+        // Content graph: GET /graph/{content_id}?depth=..&types=..&direction=..
+        (Method::GET, p) if p.starts_with("/graph/") => {
+            to_boxed(routes::handle_graph_request(state, p, req.uri().query()).await)
+        }
+
+        // Knowledge map rendering data: GET /knowledge-maps/{id}/layout
+        (Method::GET, p) if p.starts_with("/knowledge-maps/") => {
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            to_boxed(routes::handle_knowledge_map_layout(state, p, auth_header).await)
+        }
+
+        // Content-grounded tutor chat (streamed): POST /tutor/chat
+        (Method::POST, "/tutor/chat") => routes::handle_tutor_chat(req, state).await,
+
+        // Learner notes as a zipped Markdown vault: GET /me/export/notes.zip
+        (Method::GET, "/me/export/notes.zip") => {
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            to_boxed(routes::handle_notes_export(state, auth_header.as_deref()).await)
+        }
+
+        // Reflection journal, facilitator sharing and learner data export
+        (_, p) if routes::reflections::is_reflection_route(p) => {
+            to_boxed(routes::handle_reflections(req, state).await)
+        }
+
+        // Personal library of bookmarked content
+        (_, p) if routes::bookmarks::is_bookmark_route(p) => {
+            to_boxed(routes::handle_bookmarks(req, state).await)
+        }
+
+        // Open Badges export of earned attestations
+        (_, p) if routes::badges::is_badge_export_route(p) => {
+            let method = req.method().clone();
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok());
+            to_boxed(
+                routes::handle_badge_export(state, method, p, req.uri().query(), auth_header)
+                    .await,
+            )
+        }
+
+        // Learner recommendations: GET /me/recommendations?limit=..
+        (Method::GET, "/me/recommendations") => {
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            to_boxed(routes::handle_recommendations(state, req.uri().query(), auth_header).await)
+        }
+
+        // Operator analytics: GET /analytics/paths, GET /analytics/paths/{path_id}
+        (Method::GET, p) if p.starts_with("/analytics/") => {
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            to_boxed(routes::handle_analytics_request(state, p, auth_header).await)
+        }
+
+        // Steward maintenance queue: GET /steward/content-health?kind=..
+        (Method::GET, "/steward/content-health") => {
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            to_boxed(routes::handle_content_health(state, req.uri().query(), auth_header).await)
+        }
+
+        // Question bank review queue: GET /steward/assessment-items
+        (Method::GET, "/steward/assessment-items") => {
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            to_boxed(routes::handle_pending_assessment_items(state, auth_header).await)
+        }
+
+        // POST /steward/assessment-items/{id}/approve|reject
+        (Method::POST, p) if routes::assessment_items::parse_review_path(p).is_some() => {
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            match routes::assessment_items::parse_review_path(p) {
+                Some((id, action)) => to_boxed(
+                    routes::handle_review_assessment_item(state, id, action, auth_header).await,
+                ),
+                None => to_boxed(routes::api::error_response(
+                    StatusCode::NOT_FOUND,
+                    "Not found",
+                    "NOT_FOUND",
+                )),
+            }
+        }
+
+        // Relationship suggestions from embeddings: GET /steward/relationship-suggestions?status=..
+        (Method::GET, "/steward/relationship-suggestions") => {
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            to_boxed(
+                routes::handle_relationship_suggestions(state, req.uri().query(), auth_header)
+                    .await,
+            )
+        }
+
+        // POST /steward/relationship-suggestions/{id}/approve|reject
+        (Method::POST, p) if routes::semantic::parse_suggestion_action_path(p).is_some() => {
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            match routes::semantic::parse_suggestion_action_path(p) {
+                Some((id, action)) => to_boxed(
+                    routes::handle_review_suggestion(state, id, action, auth_header).await,
+                ),
+                None => to_boxed(routes::api::error_response(
+                    StatusCode::NOT_FOUND,
+                    "Not found",
+                    "NOT_FOUND",
+                )),
+            }
+        }
+
+        // Content access rollups: GET /steward/content-access?days=..
+        (Method::GET, "/steward/content-access") => {
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            to_boxed(routes::handle_content_access(state, req.uri().query(), auth_header).await)
+        }
+
+        // GET /steward/content-access/{id}?days=..
+        (Method::GET, p) if routes::content_access::parse_content_access_path(p).is_some() => {
+            let id = routes::content_access::parse_content_access_path(p).unwrap_or_default();
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            to_boxed(
+                routes::handle_content_access_detail(state, id, req.uri().query(), auth_header)
+                    .await,
+            )
+        }
+
+        // Mark content for access logging: PUT /steward/content-access/{id}
+        (Method::PUT, p) if routes::content_access::parse_content_access_path(p).is_some() => {
+            let id = routes::content_access::parse_content_access_path(p)
+                .unwrap_or_default()
+                .to_string();
+            to_boxed(routes::handle_mark_content_access(req, state, id).await)
+        }
+
+        // Moderated writes: POST /content, POST /discussions
+        (Method::POST, p) if routes::moderation::submission_fn(p).is_some() => {
+            match routes::moderation::submission_fn(p) {
+                Some(zome_fn) => to_boxed(routes::handle_moderated_write(req, state, zome_fn).await),
+                None => to_boxed(routes::api::error_response(
+                    StatusCode::NOT_FOUND,
+                    "Not found",
+                    "NOT_FOUND",
+                )),
+            }
+        }
+
+        // Content reports: POST /report
+        (Method::POST, "/report") => to_boxed(routes::handle_report(req, state).await),
+
+        // Moderation queue: GET /steward/moderation-queue?status=..&kind=..
+        (Method::GET, "/steward/moderation-queue") => {
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            to_boxed(routes::handle_moderation_queue(state, req.uri().query(), auth_header).await)
+        }
+
+        // POST /steward/moderation-queue/{id}/approve|hide|request-changes
+        (Method::POST, p) if routes::moderation::parse_moderation_action_path(p).is_some() => {
+            match routes::moderation::parse_moderation_action_path(p) {
+                Some((id, action)) => {
+                    let id = id.to_string();
+                    to_boxed(routes::handle_review_moderation_item(req, state, id, action).await)
+                }
+                None => to_boxed(routes::api::error_response(
+                    StatusCode::NOT_FOUND,
+                    "Not found",
+                    "NOT_FOUND",
+                )),
+            }
+        }
+
+        // Governed runtime settings: GET /governance/settings
+        (Method::GET, "/governance/settings") => {
+            to_boxed(routes::handle_governance_settings(state).await)
+        }
+
+        // Elohim dispatch: POST /elohim/tasks
+        (Method::POST, "/elohim/tasks") => {
+            to_boxed(routes::handle_dispatch_elohim_task(req, state).await)
+        }
+
+        // GET /elohim/tasks/{task_id}
+        (Method::GET, p) if routes::elohim::parse_task_path(p).is_some() => {
+            let task_id = routes::elohim::parse_task_path(p).unwrap_or_default().to_string();
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            to_boxed(routes::handle_elohim_task_status(state, &task_id, auth_header).await)
+        }
+
+        // Token settlement: POST /shefa/settlements/token
+        (Method::POST, "/shefa/settlements/token") => {
+            to_boxed(routes::handle_token_settlement(req, state).await)
+        }
+
+        // Mutual pool health: GET /shefa/solvency?limit=..
+        (Method::GET, "/shefa/solvency") => {
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            to_boxed(routes::handle_solvency(state, req.uri().query(), auth_header).await)
+        }
+
+        // Insurance claims: GET /shefa/claims?status=..
+        (Method::GET, "/shefa/claims") => {
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            to_boxed(routes::handle_claims_by_status(state, req.uri().query(), auth_header).await)
+        }
+
+        // GET /shefa/claims/{id}
+        (Method::GET, p) if routes::insurance_claims::parse_claim_path(p).is_some() => {
+            let claim_id = routes::insurance_claims::parse_claim_path(p)
+                .unwrap_or_default()
+                .to_string();
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            to_boxed(routes::handle_get_claim(state, &claim_id, auth_header).await)
+        }
+
+        // POST /shefa/claims/{id}/{evidence|assign|adjudicate|payment}
+        (Method::POST, p) if routes::insurance_claims::parse_claim_action_path(p).is_some() => {
+            match routes::insurance_claims::parse_claim_action_path(p) {
+                Some((id, action)) => {
+                    to_boxed(routes::handle_claim_action(req, state, id, action).await)
+                }
+                None => to_boxed(routes::api::error_response(
+                    StatusCode::NOT_FOUND,
+                    "Not found",
+                    "NOT_FOUND",
+                )),
+            }
+        }
+
+        // Gate vouchers: POST /gates/{id}/vouchers|redeem
+        (Method::POST, p) if routes::vouchers::parse_gate_voucher_path(p).is_some() => {
+            match routes::vouchers::parse_gate_voucher_path(p) {
+                Some((id, action)) => {
+                    to_boxed(routes::handle_gate_voucher(req, state, id, action).await)
+                }
+                None => to_boxed(routes::api::error_response(
+                    StatusCode::NOT_FOUND,
+                    "Not found",
+                    "NOT_FOUND",
+                )),
+            }
+        }
+
+        // Conductor signal journal: GET /admin/signals/journal?from=&to=&after_offset=
+        (Method::GET, "/admin/signals/journal") => {
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            to_boxed(routes::handle_signal_journal(state, req.uri().query(), auth_header).await)
+        }
+
+        // Replay journaled signals into projections and the cache
+        (Method::POST, "/admin/signals/replay") => {
+            to_boxed(routes::handle_signal_replay(req, state).await)
+        }
+
+        // Slow zome calls and cache-rule suggestions
+        (Method::GET, "/admin/query-advisor") => {
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            to_boxed(routes::handle_query_advisor(state, auth_header))
+        }
+
+        // Per-entry-type schema healing/migration status across the DHT
+        (Method::GET, "/admin/migrations") => {
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            to_boxed(routes::handle_migration_status(state, auth_header).await)
+        }
+
+        // Retention policies: list, preview or run, and their audit trail
+        (Method::GET, "/admin/retention/policies") => {
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            to_boxed(routes::handle_retention_policies(state, auth_header))
+        }
+
+        (Method::POST, "/admin/retention/run") => {
+            to_boxed(routes::handle_retention_run(req, state).await)
+        }
+
+        (Method::GET, "/admin/retention/audit") => {
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            to_boxed(routes::handle_retention_audit(state, req.uri().query(), auth_header).await)
+        }
+
+        (Method::POST, "/admin/operators") => {
+            to_boxed(routes::handle_create_operator(req, state).await)
+        }
+
+        (Method::GET, "/admin/operators") => {
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            to_boxed(routes::handle_list_operators(state, req.uri().query(), auth_header).await)
+        }
+
+        (Method::GET, p) if p.starts_with("/admin/operators/") => {
+            let operator_id = p.trim_start_matches("/admin/operators/").to_string();
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            to_boxed(routes::handle_get_operator(state, &operator_id, auth_header).await)
+        }
+
+        // Zome call policy enforced by the app proxy
+        (Method::GET, "/admin/zome-policy") => {
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            to_boxed(routes::handle_get_zome_policy(state, auth_header))
+        }
+
+        (Method::PUT, "/admin/zome-policy") => {
+            to_boxed(routes::handle_put_zome_policy(req, state).await)
+        }
+
+        // Learning sessions: POST /sessions, DELETE /sessions/{id}
+        (Method::POST, "/sessions") => to_boxed(routes::handle_schedule_session(req, state).await),
+
+        (Method::DELETE, p) if routes::sessions::parse_session_path(p).is_some() => {
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            let id = routes::sessions::parse_session_path(p).unwrap_or_default();
+            to_boxed(routes::handle_cancel_session(state, id, auth_header).await)
+        }
+
+        // Calendars: GET /me/sessions, GET /me/calendar.ics?token=..
+        (Method::GET, "/me/sessions") => {
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            to_boxed(routes::handle_my_sessions(state, auth_header).await)
+        }
+
+        (Method::GET, "/me/calendar.ics") => {
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            to_boxed(routes::handle_calendar_feed(state, req.uri().query(), auth_header).await)
+        }
+
+        // Annotations (W3C Web Annotation): POST|GET /annotations,
+        // GET|PUT|DELETE /annotations/{id}
+        (Method::POST, "/annotations") => {
+            to_boxed(routes::handle_create_annotation(req, state).await)
+        }
+
+        (Method::GET, "/annotations") => {
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            to_boxed(routes::handle_list_annotations(state, req.uri().query(), auth_header).await)
+        }
+
+        (Method::GET, p) if routes::annotations::parse_annotation_path(p).is_some() => {
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            let id = routes::annotations::parse_annotation_path(p).unwrap_or_default();
+            to_boxed(routes::handle_get_annotation(state, id, auth_header).await)
+        }
+
+        (Method::PUT, p) if routes::annotations::parse_annotation_path(p).is_some() => {
+            let id = routes::annotations::parse_annotation_path(p)
+                .unwrap_or_default()
+                .to_string();
+            to_boxed(routes::handle_update_annotation(req, state, id).await)
+        }
+
+        (Method::DELETE, p) if routes::annotations::parse_annotation_path(p).is_some() => {
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            let id = routes::annotations::parse_annotation_path(p).unwrap_or_default();
+            to_boxed(routes::handle_delete_annotation(state, id, auth_header).await)
+        }
+
+        // Invitations: POST|GET /invitations, DELETE /invitations/{id}
+        (Method::POST, "/invitations") => {
+            to_boxed(routes::handle_create_invitation(req, state).await)
+        }
+
+        (Method::GET, "/invitations") => {
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            to_boxed(routes::handle_list_invitations(state, req.uri().query(), auth_header).await)
+        }
+
+        (Method::DELETE, p) if routes::invitations::parse_invitation_path(p).is_some() => {
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            let id = routes::invitations::parse_invitation_path(p).unwrap_or_default();
+            to_boxed(routes::handle_revoke_invitation(state, id, auth_header).await)
+        }
+
+        // Voucher redemption report: GET /admin/vouchers?gate_id=..
+        (Method::GET, "/admin/vouchers") => {
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            to_boxed(routes::handle_voucher_report(state, req.uri().query(), auth_header).await)
+        }
+
+        // Notifications: GET /notifications?limit=..
+        (Method::GET, "/notifications") => {
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            to_boxed(routes::handle_notifications(state, req.uri().query(), auth_header).await)
+        }
+
+        // Ranged content body: GET /content/{id}/body?offset=..&length=..
+        (Method::GET, p) if routes::content_body::parse_content_body_path(p).is_some() => {
+            let id = routes::content_body::parse_content_body_path(p).unwrap_or_default();
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            to_boxed(routes::handle_content_body(state, id, req.uri().query(), auth_header).await)
+        }
+
+        // Semantic related content: GET /content/{id}/semantic-related?limit=..
+        (Method::GET, p) if routes::semantic::parse_semantic_related_path(p).is_some() => {
+            let id = routes::semantic::parse_semantic_related_path(p).unwrap_or_default();
+            to_boxed(routes::handle_semantic_related(state, id, req.uri().query()).await)
+        }
+
+        // Caption upload: POST /content/{content_id}/captions?language=..
+        (Method::POST, p) if routes::captions::parse_captions_path(p).is_some() => {
+            let content_id = routes::captions::parse_captions_path(p)
+                .unwrap_or_default()
+                .to_string();
+            to_boxed(routes::handle_caption_upload(req, state, content_id).await)
+        }
+
+        // Sitemaps: GET /sitemap.xml, /sitemaps/{name}.xml
+        (Method::GET, p) if routes::sitemap::parse_sitemap_path(p).is_some() => {
+            let name = routes::sitemap::parse_sitemap_path(p).unwrap_or_default();
+            to_boxed(routes::handle_sitemap(state, name).await)
+        }
+
+        // Printable paths: GET /paths/{id}/export.pdf
+        (Method::GET, p) if routes::path_export::parse_path_export_path(p).is_some() => {
+            let path_id = routes::path_export::parse_path_export_path(p).unwrap_or_default();
+            to_boxed(routes::handle_path_export(state, path_id).await)
+        }
+
+        // Atom feeds: GET /feeds/content.atom, /feeds/tags/{tag}.atom, /feeds/paths/{id}.atom
+        (Method::GET, p) if p.starts_with("/feeds/") => {
+            let query = req.uri().query().map(|s| s.to_string());
+            let host = req
+                .headers()
+                .get("host")
+                .and_then(|h| h.to_str().ok())
+                .unwrap_or("localhost");
+            let scheme = if host.contains("localhost") || host.starts_with("127.") {
+                "http"
+            } else {
+                "https"
+            };
+            let base_url = format!("{scheme}://{host}");
+            to_boxed(routes::handle_feed_request(state, p, query.as_deref(), &base_url).await)
+        }
+
+        // Link unfurling: GET /preview/{content_id}[?format=oembed]
+        (Method::GET, p) if routes::preview::parse_preview_path(p).is_some() => {
+            let content_id = routes::preview::parse_preview_path(p).unwrap_or_default();
+            let headers = req.headers();
+            let accept = headers
+                .get("accept")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            let query = req.uri().query().map(|s| s.to_string());
+            let host = headers
+                .get("host")
+                .and_then(|h| h.to_str().ok())
+                .unwrap_or("localhost");
+            let scheme = if host.contains("localhost") || host.starts_with("127.") {
+                "http"
+            } else {
+                "https"
+            };
+            let base_url = format!("{scheme}://{host}");
+            to_boxed(
+                routes::handle_content_preview(
+                    state,
+                    content_id,
+                    query.as_deref(),
+                    accept.as_deref(),
+                    &base_url,
+                )
+                .await,
+            )
+        }
+
+        // Embedded path data for the player: GET /embed/{path_id}/path
+        (Method::GET, p) if routes::embed::parse_embed_data_path(p).is_some() => {
+            let path_id = routes::embed::parse_embed_data_path(p).unwrap_or_default();
+            let header = |name: &str| {
+                req.headers()
+                    .get(name)
+                    .and_then(|h| h.to_str().ok())
+                    .map(|s| s.to_string())
+            };
+            let token = header(routes::embed::EMBED_TOKEN_HEADER);
+            let origin = header(routes::embed::EMBED_ORIGIN_HEADER);
+            to_boxed(routes::handle_embed_data(state, path_id, token, origin).await)
+        }
+
+        // Iframe player for partner sites: GET /embed/{path_id}
+        (Method::GET, p) if routes::embed::parse_embed_path(p).is_some() => {
+            let path_id = routes::embed::parse_embed_path(p).unwrap_or_default();
+            to_boxed(routes::handle_embed_player(state, path_id))
+        }
+
+        // Public attestation verification: GET /verify/attestation/{id}[?format=json]
+        (Method::GET, p) if routes::verify::parse_verify_attestation_path(p).is_some() => {
+            let attestation_id =
+                routes::verify::parse_verify_attestation_path(p).unwrap_or_default();
+            let headers = req.headers();
+            let accept = headers
+                .get("accept")
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            let query = req.uri().query().map(|s| s.to_string());
+            let host = headers
+                .get("host")
+                .and_then(|h| h.to_str().ok())
+                .unwrap_or("localhost");
+            let scheme = if host.contains("localhost") || host.starts_with("127.") {
+                "http"
+            } else {
+                "https"
+            };
+            let base_url = format!("{scheme}://{host}");
             to_boxed(
-                routes::handle_api_request(state, p, query, Some(remote_ip), auth_header).await,
+                routes::handle_verify_attestation(
+                    state,
+                    attestation_id,
+                    query.as_deref(),
+                    accept.as_deref(),
+                    &base_url,
+                )
+                .await,
             )
         }
 
+        // Translation drafts awaiting steward review
+        (Method::GET, "/api/v1/translations/pending") => {
+            let auth_header = req
+                .headers()
+                .get("authorization")
+                .and_then(|h| h.to_str().ok());
+            to_boxed(routes::handle_pending_translations(state, auth_header).await)
+        }
+
+        // Translations: GET /api/v1/content/{id}/localized (Accept-Language),
+        // GET|POST /api/v1/content/{id}/translations,
+        // POST /api/v1/content/{id}/translations/{locale}/publish
+        (method, p) if routes::translations::parse_translation_path(p).is_some() => {
+            match (method, routes::translations::parse_translation_path(p)) {
+                (Method::GET, Some(routes::translations::TranslationRoute::Localized(id))) => {
+                    let query = req.uri().query().map(|s| s.to_string());
+                    let accept_language = req
+                        .headers()
+                        .get("accept-language")
+                        .and_then(|h| h.to_str().ok())
+                        .map(|s| s.to_string());
+                    to_boxed(
+                        routes::handle_localized_content(
+                            state,
+                            id,
+                            query.as_deref(),
+                            accept_language.as_deref(),
+                        )
+                        .await,
+                    )
+                }
+                (Method::GET, Some(routes::translations::TranslationRoute::Translations(id))) => {
+                    to_boxed(routes::handle_list_translations(state, id).await)
+                }
+                (Method::POST, Some(routes::translations::TranslationRoute::Translations(id))) => {
+                    let content_id = id.to_string();
+                    to_boxed(routes::handle_add_translation(req, state, content_id).await)
+                }
+                (Method::POST, Some(routes::translations::TranslationRoute::Publish(id, locale))) => {
+                    let auth_header = req
+                        .headers()
+                        .get("authorization")
+                        .and_then(|h| h.to_str().ok());
+                    to_boxed(
+                        routes::handle_publish_translation(state, id, locale, auth_header).await,
+                    )
+                }
+                _ => to_boxed(routes::api::error_response(
+                    StatusCode::METHOD_NOT_ALLOWED,
+                    "Method not allowed",
+                    "METHOD_NOT_ALLOWED",
+                )),
+            }
+        }
+
+        // Emergency recovery saga: POST /recovery/{commitment_id}/activate, GET /recovery/{commitment_id}
+        (_, p) if p.starts_with("/recovery/") => {
+            to_boxed(routes::handle_recovery_request(req, Arc::clone(&state), p).await)
+        }
+
         // WebSocket import progress (proxy to elohim-storage)
         // GET /import/progress - WebSocket upgrade for real-time progress
         (Method::GET, "/import/progress") if hyper_tungstenite::is_upgrade_request(&req) => {
@@ -1071,16 +2131,29 @@ async fn handle_request(
             info!(
                 batch_type = %batch_type,
                 batch_id = ?batch_id,
-                storage_url = ?state.args.storage_url,
+                storage_url = ?staging::storage_url(&state.args),
                 "Forwarding import request to elohim-storage"
             );
 
+            let caller = state
+                .import_abuse
+                .is_some()
+                .then(|| routes::import::ImportCaller::identify(&req, &state, addr));
             return Ok(to_boxed(
                 routes::handle_import_request(
                     req,
-                    state.args.storage_url.clone(),
+                    staging::storage_url(&state.args),
                     batch_type,
                     batch_id,
+                    routes::import::ImportChecks {
+                        duplicates: state.duplicate_detector.clone(),
+                        moderation: state.moderation.clone(),
+                        signers: state.import_signers.clone(),
+                        validator: state.import_validator.clone(),
+                        abuse: state.import_abuse.clone(),
+                        caller,
+                    },
+                    Arc::clone(&state.body_limits),
                 )
                 .await,
             ));
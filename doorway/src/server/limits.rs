@@ -0,0 +1,209 @@
+//! Per-route request size limits
+//!
+//! Request bodies are capped by route class before they reach a handler, so
+//! an accidental 200MB import POST is turned away at the door instead of
+//! being buffered and forwarded to the conductor:
+//!
+//! - **JSON**: API writes, zome calls and everything else with a body
+//!   (`MAX_JSON_BODY_BYTES`)
+//! - **Import**: bulk imports and seed blobs (`MAX_IMPORT_BODY_BYTES`)
+//! - **WebSocket frames** proxied to the conductor (`MAX_WS_FRAME_BYTES`)
+//!
+//! Routes may still apply a tighter limit of their own. Oversized requests
+//! get `413 Payload Too Large` and are counted in `GET /status`.
+
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::header::CONTENT_LENGTH;
+use hyper::{HeaderMap, Method, Response, StatusCode};
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
+use tracing::info;
+
+use crate::config::Args;
+
+/// Which limit applies to a request body
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyClass {
+    Json,
+    Import,
+}
+
+impl BodyClass {
+    /// Class of a route, or None when no class limit applies
+    ///
+    /// Methods without a body have none, and cache snapshots never reach the
+    /// conductor, so their handler bounds them itself.
+    pub fn for_route(method: &Method, path: &str) -> Option<Self> {
+        if !matches!(*method, Method::POST | Method::PUT | Method::PATCH)
+            || path == "/admin/cache/load"
+        {
+            return None;
+        }
+        let bulk = path.starts_with("/import/") || path.starts_with("/admin/seed/");
+        Some(if bulk { Self::Import } else { Self::Json })
+    }
+}
+
+/// Configured limits and rejection counters
+#[derive(Debug)]
+pub struct BodyLimits {
+    json_bytes: usize,
+    import_bytes: usize,
+    ws_frame_bytes: usize,
+    rejected_json: AtomicU64,
+    rejected_import: AtomicU64,
+}
+
+/// Limits and rejections as reported by `GET /status`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct BodyLimitStats {
+    pub max_json_body_bytes: usize,
+    pub max_import_body_bytes: usize,
+    pub max_ws_frame_bytes: usize,
+    pub rejected_json: u64,
+    pub rejected_import: u64,
+}
+
+impl BodyLimits {
+    pub fn new(json_bytes: usize, import_bytes: usize, ws_frame_bytes: usize) -> Self {
+        Self {
+            json_bytes,
+            import_bytes,
+            ws_frame_bytes,
+            rejected_json: AtomicU64::new(0),
+            rejected_import: AtomicU64::new(0),
+        }
+    }
+
+    pub fn from_args(args: &Args) -> Self {
+        Self::new(
+            args.max_json_body_bytes,
+            args.max_import_body_bytes,
+            args.max_ws_frame_bytes,
+        )
+    }
+
+    /// Largest body accepted for a class
+    pub fn limit(&self, class: BodyClass) -> usize {
+        match class {
+            BodyClass::Json => self.json_bytes,
+            BodyClass::Import => self.import_bytes,
+        }
+    }
+
+    /// Count a rejected request
+    pub fn record_rejection(&self, class: BodyClass) {
+        let counter = match class {
+            BodyClass::Json => &self.rejected_json,
+            BodyClass::Import => &self.rejected_import,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Reject a request whose declared Content-Length is over its limit
+    ///
+    /// Bodies sent without a Content-Length are bounded by the handler while
+    /// reading.
+    pub fn check_declared(
+        &self,
+        class: BodyClass,
+        headers: &HeaderMap,
+    ) -> Result<(), Response<Full<Bytes>>> {
+        let declared = headers
+            .get(CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u64>().ok());
+        let limit = self.limit(class);
+        match declared {
+            Some(length) if length > limit as u64 => {
+                self.record_rejection(class);
+                info!(length, limit, ?class, "Request body over limit");
+                Err(payload_too_large(limit))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// WebSocket settings for connections proxied to the conductor
+    pub fn websocket_config(&self) -> WebSocketConfig {
+        WebSocketConfig {
+            max_frame_size: Some(self.ws_frame_bytes),
+            max_message_size: Some(self.ws_frame_bytes),
+            ..Default::default()
+        }
+    }
+
+    pub fn stats(&self) -> BodyLimitStats {
+        BodyLimitStats {
+            max_json_body_bytes: self.json_bytes,
+            max_import_body_bytes: self.import_bytes,
+            max_ws_frame_bytes: self.ws_frame_bytes,
+            rejected_json: self.rejected_json.load(Ordering::Relaxed),
+            rejected_import: self.rejected_import.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// 413 response naming the limit
+pub fn payload_too_large(limit: usize) -> Response<Full<Bytes>> {
+    let body = serde_json::json!({
+        "error": format!("Request body is limited to {limit} bytes"),
+        "code": "PAYLOAD_TOO_LARGE",
+        "limit": limit,
+    });
+    Response::builder()
+        .status(StatusCode::PAYLOAD_TOO_LARGE)
+        .header("Content-Type", "application/json")
+        .header("Access-Control-Allow-Origin", "*")
+        .body(Full::new(Bytes::from(body.to_string())))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::header::HeaderValue;
+
+    #[test]
+    fn test_body_class() {
+        assert_eq!(
+            BodyClass::for_route(&Method::POST, "/import/content"),
+            Some(BodyClass::Import)
+        );
+        assert_eq!(
+            BodyClass::for_route(&Method::PUT, "/admin/seed/blob"),
+            Some(BodyClass::Import)
+        );
+        assert_eq!(
+            BodyClass::for_route(&Method::POST, "/api/v1/zome/content_store/create_content"),
+            Some(BodyClass::Json)
+        );
+        assert_eq!(BodyClass::for_route(&Method::GET, "/import/content"), None);
+        assert_eq!(
+            BodyClass::for_route(&Method::POST, "/admin/cache/load"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_check_declared() {
+        let limits = BodyLimits::new(1024, 4096, 1024);
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_LENGTH, HeaderValue::from_static("2048"));
+
+        let rejected = limits
+            .check_declared(BodyClass::Json, &headers)
+            .unwrap_err();
+        assert_eq!(rejected.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert!(limits.check_declared(BodyClass::Import, &headers).is_ok());
+        assert!(limits
+            .check_declared(BodyClass::Json, &HeaderMap::new())
+            .is_ok());
+
+        let stats = limits.stats();
+        assert_eq!(stats.rejected_json, 1);
+        assert_eq!(stats.rejected_import, 0);
+    }
+}
@@ -1,6 +1,8 @@
 //! Server components for Doorway
 
 pub mod http;
+pub mod limits;
+pub mod staging;
 pub mod websocket;
 
 pub use http::{run, AppState};
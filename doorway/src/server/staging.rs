@@ -0,0 +1,127 @@
+//! Staging dry runs for operators
+//!
+//! Requests carrying one of the `STAGING_API_KEYS` in `X-API-Key` are served
+//! from a parallel set of staging cells instead of the production DHT, so an
+//! operator can rehearse imports, path edits and gate configuration through
+//! the same doorway before touching production.
+//!
+//! Staging cells are installed in the same hApp under parallel role names
+//! (`lamad-staging` next to `lamad`, with their own network seed), and are
+//! picked up by discovery like any other cell. While a request is in staging
+//! scope, zome calls resolve `{role}` to `{role}{STAGING_ROLE_SUFFIX}` and
+//! fail rather than fall back to production when the staging role is missing.
+//! Imports are forwarded to `STAGING_STORAGE_URL`, an elohim-storage bound to
+//! the staging cells.
+//!
+//! The projection and its cache API (`/api/v1/cache`) only mirror production
+//! and are not available to staging requests. Responses are marked with
+//! `X-Doorway-Environment: staging`.
+
+use hyper::HeaderMap;
+use std::future::Future;
+
+use crate::auth::api_key::constant_time_compare;
+use crate::config::Args;
+
+/// Response header naming the environment that served a staging request
+pub const ENVIRONMENT_HEADER: &str = "x-doorway-environment";
+
+tokio::task_local! {
+    /// Role suffix for the request being handled, set for staging requests
+    static STAGING_SUFFIX: String;
+}
+
+/// Whether the request's API key is routed to staging
+pub fn is_staging_request(args: &Args, headers: &HeaderMap) -> bool {
+    let Some(key) = headers
+        .get("x-api-key")
+        .and_then(|v| v.to_str().ok())
+        .filter(|k| !k.is_empty())
+    else {
+        return false;
+    };
+    args.staging_api_keys
+        .iter()
+        .filter(|k| !k.is_empty())
+        .any(|staging_key| constant_time_compare(key, staging_key))
+}
+
+/// Run a request handler in staging scope, calling roles with `role_suffix`
+pub async fn scope<F: Future>(role_suffix: String, handler: F) -> F::Output {
+    STAGING_SUFFIX.scope(role_suffix, handler).await
+}
+
+/// Whether the current request is being served from staging
+pub fn is_active() -> bool {
+    STAGING_SUFFIX.try_with(|_| ()).is_ok()
+}
+
+/// Role name to call for `role_name` in the current request
+pub fn role_name(role_name: &str) -> String {
+    STAGING_SUFFIX
+        .try_with(|suffix| format!("{role_name}{suffix}"))
+        .unwrap_or_else(|_| role_name.to_string())
+}
+
+/// elohim-storage to forward imports to for the current request
+pub fn storage_url(args: &Args) -> Option<String> {
+    if is_active() {
+        args.staging_storage_url.clone()
+    } else {
+        args.storage_url.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    fn args() -> Args {
+        Args::parse_from([
+            "doorway",
+            "--staging-api-keys",
+            "rehearse-1,rehearse-2",
+            "--storage-url",
+            "http://storage:8090",
+            "--staging-storage-url",
+            "http://storage-staging:8090",
+        ])
+    }
+
+    fn headers(key: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-api-key", key.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_is_staging_request() {
+        let args = args();
+        assert!(is_staging_request(&args, &headers("rehearse-2")));
+        assert!(!is_staging_request(&args, &headers("production-key")));
+        assert!(!is_staging_request(&args, &HeaderMap::new()));
+        assert!(!is_staging_request(
+            &Args::parse_from(["doorway"]),
+            &headers("rehearse-1")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_roles_and_storage_follow_scope() {
+        let args = args();
+        assert!(!is_active());
+        assert_eq!(role_name("lamad"), "lamad");
+        assert_eq!(storage_url(&args).as_deref(), Some("http://storage:8090"));
+
+        scope(args.staging_role_suffix.clone(), async {
+            assert!(is_active());
+            assert_eq!(role_name("lamad"), "lamad-staging");
+            assert_eq!(
+                storage_url(&args).as_deref(),
+                Some("http://storage-staging:8090")
+            );
+        })
+        .await;
+    }
+}
@@ -16,6 +16,7 @@ use hyper::{Request, Response, StatusCode};
 use std::sync::Arc;
 use tracing::{error, info, warn};
 
+use crate::auth::policy::Caller;
 use crate::auth::{
     extract_token_from_header, ApiKeyValidator, Claims, JwtValidator, PermissionLevel,
 };
@@ -48,7 +49,7 @@ pub async fn handle_admin_upgrade(
                 assigned_admin_url.as_deref().unwrap_or("default pool")
             );
 
-            match hyper_tungstenite::upgrade(req, None) {
+            match hyper_tungstenite::upgrade(req, Some(state.body_limits.websocket_config())) {
                 Ok((response, websocket)) => {
                     let conductor_url = state.args.conductor_url.clone();
                     let dev_mode = state.args.dev_mode;
@@ -152,14 +153,23 @@ pub async fn handle_app_upgrade(
 
     // Route to the agent's assigned conductor if JWT present, else use default
     let (conductor_host, conductor_port) = resolve_conductor_for_app(&state, &req, port);
+    // Zome policy rules can depend on who is calling
+    let claims = extract_claims(&state, &req);
 
     info!(
         "App WebSocket upgrade request for port {} (origin: {:?}, conductor: {}:{})",
         port, origin, conductor_host, conductor_port
     );
 
-    match hyper_tungstenite::upgrade(req, None) {
+    match hyper_tungstenite::upgrade(req, Some(state.body_limits.websocket_config())) {
         Ok((response, websocket)) => {
+            let outbound = Arc::clone(&state.ws_outbound);
+            let heartbeat = Arc::clone(&state.ws_heartbeat);
+            let reconnect = Arc::clone(&state.ws_reconnect);
+            let guard = proxy::call_guard::CallGuard::new(
+                Arc::clone(&state.zome_policy),
+                Caller::from_claims(claims.as_ref()),
+            );
             // App connections use direct proxy to the conductor hosting this agent
             tokio::spawn(async move {
                 match websocket.await {
@@ -170,6 +180,10 @@ pub async fn handle_app_upgrade(
                             origin,
                             query,
                             &conductor_host,
+                            outbound,
+                            heartbeat,
+                            reconnect,
+                            guard,
                         )
                         .await
                         {
@@ -0,0 +1,276 @@
+//! Learning Session Calendars
+//!
+//! Learners block study sessions for a path, and facilitators publish cohort
+//! sessions for the learners sharing a path with them (LearningSession
+//! entries in the content DNA). This module turns a learner's sessions into
+//! an iCalendar feed (RFC 5545) for `GET /me/calendar.ics`.
+//!
+//! ## Feed tokens
+//!
+//! Calendar apps subscribe to a URL and can't send a doorway JWT, so the feed
+//! also accepts `?token=`. A feed token is `{learner}.{sig}`: the learner's
+//! human id and hex HMAC-SHA256, keyed with `CALENDAR_FEED_SECRET`, over the
+//! lines
+//!
+//! ```text
+//! elohim-calendar:v1
+//! {learner}
+//! ```
+//!
+//! Feed tokens don't expire; rotating the secret revokes all of them. They
+//! only ever unlock the calendar feed.
+
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use thiserror::Error;
+
+use crate::config::Args;
+
+/// Version prefix of the signed payload
+const TOKEN_DOMAIN: &str = "elohim-calendar:v1";
+
+/// Shortest secret accepted
+const MIN_SECRET_LEN: usize = 32;
+
+/// Longest content line before folding, in octets (RFC 5545 §3.1)
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Calendar feed errors
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum CalendarError {
+    #[error("Invalid calendar feed config: {0}")]
+    Config(String),
+}
+
+/// Fields of a LearningSession the calendar needs
+/// Must match LearningSession in holochain/dna/elohim/zomes/content_store_integrity/src/lib.rs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LearningSession {
+    pub id: String,
+    pub kind: String,
+    pub organizer_id: String,
+    pub path_id: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub location: Option<String>,
+    pub starts_at_micros: i64,
+    pub duration_minutes: u32,
+    pub status: String,
+}
+
+/// Must match LearningSessionOutput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Clone, Deserialize)]
+pub struct LearningSessionOutput {
+    pub session: LearningSession,
+}
+
+/// Signs and checks calendar feed tokens
+#[derive(Debug)]
+pub struct CalendarFeeds {
+    key: Vec<u8>,
+}
+
+impl CalendarFeeds {
+    /// Feed tokens as configured, `None` when `CALENDAR_FEED_SECRET` is unset
+    pub fn from_args(args: &Args) -> Result<Option<Self>, CalendarError> {
+        args.calendar_feed_secret
+            .as_deref()
+            .map(Self::new)
+            .transpose()
+    }
+
+    pub fn new(secret: &str) -> Result<Self, CalendarError> {
+        if secret.len() < MIN_SECRET_LEN {
+            return Err(CalendarError::Config(format!(
+                "CALENDAR_FEED_SECRET must be at least {MIN_SECRET_LEN} characters"
+            )));
+        }
+        Ok(Self {
+            key: secret.as_bytes().to_vec(),
+        })
+    }
+
+    fn mac(&self, learner: &str) -> Hmac<Sha256> {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC accepts any key length");
+        mac.update(format!("{TOKEN_DOMAIN}\n{learner}").as_bytes());
+        mac
+    }
+
+    /// The feed token of a learner's calendar
+    pub fn feed_token(&self, learner: &str) -> String {
+        let sig = hex::encode(self.mac(learner).finalize().into_bytes());
+        format!("{learner}.{sig}")
+    }
+
+    /// The learner a feed token is for, if it is genuine
+    pub fn verify(&self, token: &str) -> Option<String> {
+        let (learner, sig) = token.trim().rsplit_once('.')?;
+        let sig = hex::decode(sig).ok()?;
+        self.mac(learner).verify_slice(&sig).ok()?;
+        (!learner.is_empty()).then(|| learner.to_string())
+    }
+}
+
+/// RFC 5545 UTC date-time
+fn ics_time(at: DateTime<Utc>) -> String {
+    at.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escape a TEXT value
+fn escape_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Append a content line, folded at 75 octets without splitting a character
+fn push_line(out: &mut String, line: &str) {
+    let mut rest = line;
+    let mut limit = MAX_LINE_OCTETS;
+    while rest.len() > limit {
+        let mut cut = limit;
+        while !rest.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        out.push_str(&rest[..cut]);
+        out.push_str("\r\n ");
+        rest = &rest[cut..];
+        // Continuation lines start with the folding space
+        limit = MAX_LINE_OCTETS - 1;
+    }
+    out.push_str(rest);
+    out.push_str("\r\n");
+}
+
+/// Render sessions as an iCalendar feed
+///
+/// Cancelled sessions stay in the feed with `STATUS:CANCELLED`, so
+/// subscribed calendars remove them.
+pub fn render_calendar(name: &str, sessions: &[LearningSession], now: DateTime<Utc>) -> String {
+    let mut out = String::new();
+    push_line(&mut out, "BEGIN:VCALENDAR");
+    push_line(&mut out, "VERSION:2.0");
+    push_line(&mut out, "PRODID:-//Elohim//Doorway Learning Sessions//EN");
+    push_line(&mut out, "CALSCALE:GREGORIAN");
+    push_line(&mut out, "METHOD:PUBLISH");
+    push_line(&mut out, &format!("X-WR-CALNAME:{}", escape_text(name)));
+
+    let stamp = ics_time(now);
+    for session in sessions {
+        let Some(start) = DateTime::from_timestamp_micros(session.starts_at_micros) else {
+            continue;
+        };
+        let end = start + Duration::minutes(session.duration_minutes as i64);
+        push_line(&mut out, "BEGIN:VEVENT");
+        push_line(
+            &mut out,
+            &format!("UID:{}@elohim", escape_text(&session.id)),
+        );
+        push_line(&mut out, &format!("DTSTAMP:{stamp}"));
+        push_line(&mut out, &format!("DTSTART:{}", ics_time(start)));
+        push_line(&mut out, &format!("DTEND:{}", ics_time(end)));
+        push_line(
+            &mut out,
+            &format!("SUMMARY:{}", escape_text(&session.title)),
+        );
+        if let Some(ref description) = session.description {
+            push_line(
+                &mut out,
+                &format!("DESCRIPTION:{}", escape_text(description)),
+            );
+        }
+        if let Some(ref location) = session.location {
+            push_line(&mut out, &format!("LOCATION:{}", escape_text(location)));
+        }
+        push_line(
+            &mut out,
+            &format!("CATEGORIES:{}", escape_text(&session.kind.to_uppercase())),
+        );
+        let status = if session.status == "cancelled" {
+            "CANCELLED"
+        } else {
+            "CONFIRMED"
+        };
+        push_line(&mut out, &format!("STATUS:{status}"));
+        push_line(&mut out, "END:VEVENT");
+    }
+
+    push_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(status: &str) -> LearningSession {
+        LearningSession {
+            id: "session-uhCAk-1".to_string(),
+            kind: "cohort".to_string(),
+            organizer_id: "uhCAk-facilitator".to_string(),
+            path_id: "path-1".to_string(),
+            title: "Week 2; roots, stems".to_string(),
+            description: Some("Bring notes\nand questions".to_string()),
+            location: None,
+            // 2026-10-20T17:00:00Z
+            starts_at_micros: 1_792_515_600_000_000,
+            duration_minutes: 90,
+            status: status.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_render_calendar() {
+        let now = DateTime::from_timestamp(1_792_000_000, 0).unwrap();
+        let ics = render_calendar(
+            "Learning sessions",
+            &[session("scheduled"), session("cancelled")],
+            now,
+        );
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert!(ics.contains("DTSTART:20261020T170000Z\r\n"));
+        assert!(ics.contains("DTEND:20261020T183000Z\r\n"));
+        assert!(ics.contains("SUMMARY:Week 2\\; roots\\, stems\r\n"));
+        assert!(ics.contains("DESCRIPTION:Bring notes\\nand questions\r\n"));
+        assert!(ics.contains("CATEGORIES:COHORT\r\n"));
+        assert!(ics.contains("STATUS:CONFIRMED\r\n"));
+        assert!(ics.contains("STATUS:CANCELLED\r\n"));
+
+        let mut folded = String::new();
+        push_line(&mut folded, &format!("SUMMARY:{}", "é".repeat(60)));
+        for line in folded.split("\r\n").filter(|l| !l.is_empty()) {
+            assert!(line.len() <= MAX_LINE_OCTETS);
+        }
+        assert_eq!(
+            folded.replace("\r\n ", ""),
+            format!("SUMMARY:{}\r\n", "é".repeat(60))
+        );
+    }
+
+    #[test]
+    fn test_feed_tokens() {
+        let feeds = CalendarFeeds::new(&"c".repeat(32)).unwrap();
+        let token = feeds.feed_token("uhCAk-learner");
+        assert_eq!(feeds.verify(&token), Some("uhCAk-learner".to_string()));
+
+        let forged = token.replacen("uhCAk-learner", "uhCAk-other", 1);
+        assert_eq!(feeds.verify(&forged), None);
+        assert_eq!(feeds.verify("no-signature"), None);
+        assert!(CalendarFeeds::new("short").is_err());
+    }
+}
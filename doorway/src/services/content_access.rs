@@ -0,0 +1,355 @@
+//! Content Access Logging
+//!
+//! Gives contributors reach metrics for content a steward opts in, without
+//! general-purpose analytics. Stewards mark content `track_access: true`
+//! (`PUT /steward/content-access/{id}`); views of marked content through the
+//! cache API are then counted in memory and added every
+//! `CONTENT_ACCESS_FLUSH_SECS` to a rollup per content node and UTC day.
+//!
+//! Views are anonymous: no address, agent or full referrer is kept. The
+//! `Referer` header only picks one of a few [`ReferrerBucket`]s (direct,
+//! internal, search, social, other). Unmarked content isn't counted at all.
+//!
+//! Marks are reloaded on every flush, so a mark set through another doorway
+//! sharing the database takes effect within one interval.
+
+use bson::doc;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use dashmap::DashMap;
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::db::schemas::{
+    ContentAccessRollupDoc, ContentAccessTrackingDoc, Metadata, ReferrerBucket, ReferrerCounts,
+    CONTENT_ACCESS_ROLLUP_COLLECTION, CONTENT_ACCESS_TRACKING_COLLECTION,
+};
+use crate::db::{MongoClient, MongoCollection};
+
+/// Web search engines, by registrable domain label
+const SEARCH_ENGINES: [&str; 8] = [
+    "google",
+    "bing",
+    "duckduckgo",
+    "yahoo",
+    "baidu",
+    "yandex",
+    "ecosia",
+    "startpage",
+];
+
+/// Social networks and forums, by host suffix
+const SOCIAL_HOSTS: [&str; 14] = [
+    "facebook.com",
+    "instagram.com",
+    "twitter.com",
+    "x.com",
+    "t.co",
+    "linkedin.com",
+    "lnkd.in",
+    "reddit.com",
+    "news.ycombinator.com",
+    "youtube.com",
+    "tiktok.com",
+    "bsky.app",
+    "threads.net",
+    "discord.com",
+];
+
+/// Put a `Referer` header in its bucket
+///
+/// `own_host` is this doorway's public host; referrers from it or its
+/// subdomains are internal.
+pub fn classify_referrer(referer: Option<&str>, own_host: Option<&str>) -> ReferrerBucket {
+    let Some(referer) = referer.map(str::trim).filter(|r| !r.is_empty()) else {
+        return ReferrerBucket::Direct;
+    };
+    let Some(host) = reqwest::Url::parse(referer).ok().and_then(|url| {
+        url.host_str()
+            .map(|h| h.trim_start_matches("www.").to_lowercase())
+    }) else {
+        return ReferrerBucket::Other;
+    };
+    let under = |domain: &str| host == domain || host.ends_with(&format!(".{domain}"));
+
+    if own_host.is_some_and(|own| under(own.trim_start_matches("www."))) {
+        ReferrerBucket::Internal
+    } else if SOCIAL_HOSTS.iter().any(|social| under(social))
+        // Mastodon and other fediverse instances share no domain
+        || host.starts_with("mastodon.")
+    {
+        ReferrerBucket::Social
+    } else if host.split('.').any(|label| SEARCH_ENGINES.contains(&label)) {
+        ReferrerBucket::Search
+    } else {
+        ReferrerBucket::Other
+    }
+}
+
+/// UTC day a view is counted on
+pub fn day_of(at: DateTime<Utc>) -> String {
+    at.format("%Y-%m-%d").to_string()
+}
+
+/// First day of a span of `days` ending today
+pub fn first_day(days: u32, now: DateTime<Utc>) -> String {
+    day_of(now - ChronoDuration::days(i64::from(days.max(1)) - 1))
+}
+
+/// Tracked content and the views not yet written
+pub struct ContentAccessLog {
+    mongo: MongoClient,
+    own_host: Option<String>,
+    tracked: RwLock<HashSet<String>>,
+    pending: DashMap<(String, String), ReferrerCounts>,
+}
+
+impl ContentAccessLog {
+    pub fn new(mongo: MongoClient, doorway_url: Option<&str>) -> Self {
+        let own_host = doorway_url
+            .and_then(|url| reqwest::Url::parse(url).ok())
+            .and_then(|url| url.host_str().map(str::to_lowercase));
+        Self {
+            mongo,
+            own_host,
+            tracked: RwLock::new(HashSet::new()),
+            pending: DashMap::new(),
+        }
+    }
+
+    async fn marks(&self) -> Result<MongoCollection<ContentAccessTrackingDoc>, String> {
+        self.mongo
+            .collection(CONTENT_ACCESS_TRACKING_COLLECTION)
+            .await
+            .map_err(|e| format!("Access tracking collection unavailable: {e}"))
+    }
+
+    async fn rollups(&self) -> Result<MongoCollection<ContentAccessRollupDoc>, String> {
+        self.mongo
+            .collection(CONTENT_ACCESS_ROLLUP_COLLECTION)
+            .await
+            .map_err(|e| format!("Access rollup collection unavailable: {e}"))
+    }
+
+    pub fn is_tracked(&self, content_id: &str) -> bool {
+        self.tracked
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(content_id)
+    }
+
+    /// Count a view of `content_id` if it is tracked
+    pub fn record(&self, content_id: &str, referer: Option<&str>, at: DateTime<Utc>) {
+        if !self.is_tracked(content_id) {
+            return;
+        }
+        let bucket = classify_referrer(referer, self.own_host.as_deref());
+        self.pending
+            .entry((content_id.to_string(), day_of(at)))
+            .or_default()
+            .add(bucket, 1);
+    }
+
+    /// Marks currently enabling tracking
+    pub async fn tracked_marks(&self) -> Result<Vec<ContentAccessTrackingDoc>, String> {
+        self.marks()
+            .await?
+            .find_many(doc! { "track_access": true })
+            .await
+            .map_err(|e| format!("Failed to read access tracking marks: {e}"))
+    }
+
+    /// Reload the tracked content from the database. Returns how many.
+    pub async fn reload(&self) -> Result<usize, String> {
+        let tracked: HashSet<String> = self
+            .tracked_marks()
+            .await?
+            .into_iter()
+            .map(|mark| mark.content_id)
+            .collect();
+        let count = tracked.len();
+        *self.tracked.write().unwrap_or_else(|e| e.into_inner()) = tracked;
+        Ok(count)
+    }
+
+    /// Turn tracking of `content_id` on or off
+    ///
+    /// Turning it off stops counting; the rollups already written stay.
+    pub async fn set_tracking(
+        &self,
+        content_id: &str,
+        track_access: bool,
+        steward: &str,
+    ) -> Result<(), String> {
+        let metadata = bson::to_bson(&Metadata::new()).unwrap_or(bson::Bson::Null);
+        self.marks()
+            .await?
+            .inner()
+            .update_one(
+                doc! { "content_id": content_id },
+                doc! {
+                    "$set": {
+                        "track_access": track_access,
+                        "marked_by": steward,
+                    },
+                    "$setOnInsert": { "metadata": metadata },
+                },
+            )
+            .upsert(true)
+            .await
+            .map_err(|e| format!("Failed to store access tracking mark: {e}"))?;
+
+        let mut tracked = self.tracked.write().unwrap_or_else(|e| e.into_inner());
+        if track_access {
+            tracked.insert(content_id.to_string());
+        } else {
+            tracked.remove(content_id);
+        }
+        Ok(())
+    }
+
+    /// Add the views counted since the last flush to their daily rollups.
+    /// Returns rollups written.
+    ///
+    /// Views that fail to store are kept for the next flush.
+    pub async fn flush(&self) -> Result<usize, String> {
+        let keys: Vec<(String, String)> = self.pending.iter().map(|e| e.key().clone()).collect();
+        if keys.is_empty() {
+            return Ok(0);
+        }
+        let collection = self.rollups().await?;
+        let metadata = bson::to_bson(&Metadata::new()).unwrap_or(bson::Bson::Null);
+
+        let mut written = 0;
+        for key in keys {
+            let Some(((content_id, day), counts)) = self.pending.remove(&key) else {
+                continue;
+            };
+            let mut inc = doc! { "views": counts.total() as i64 };
+            for bucket in ReferrerBucket::ALL {
+                let views = counts.get(bucket);
+                if views > 0 {
+                    inc.insert(format!("referrers.{}", bucket.as_str()), views as i64);
+                }
+            }
+            let result = collection
+                .inner()
+                .update_one(
+                    doc! { "content_id": &content_id, "day": &day },
+                    doc! {
+                        "$inc": inc,
+                        "$setOnInsert": { "metadata": metadata.clone() },
+                    },
+                )
+                .upsert(true)
+                .await;
+            match result {
+                Ok(_) => written += 1,
+                Err(e) => {
+                    warn!(content_id = %content_id, error = %e, "Failed to store content access rollup");
+                    self.pending
+                        .entry((content_id, day))
+                        .or_default()
+                        .merge(&counts);
+                }
+            }
+        }
+        Ok(written)
+    }
+
+    /// Rollups from `first_day` on, for one content node or every one
+    pub async fn rollups_since(
+        &self,
+        content_id: Option<&str>,
+        first_day: &str,
+    ) -> Result<Vec<ContentAccessRollupDoc>, String> {
+        let mut filter = doc! { "day": { "$gte": first_day } };
+        if let Some(content_id) = content_id {
+            filter.insert("content_id", content_id);
+        }
+        let mut rollups = self
+            .rollups()
+            .await?
+            .find_many(filter)
+            .await
+            .map_err(|e| format!("Failed to read content access rollups: {e}"))?;
+        rollups.sort_by(|a, b| a.day.cmp(&b.day));
+        Ok(rollups)
+    }
+}
+
+/// Spawn the task writing counted views and reloading marks every `interval`
+pub fn spawn_content_access_task(log: Arc<ContentAccessLog>, interval: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!(
+            interval_secs = interval.as_secs(),
+            "Content access logging started"
+        );
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match log.reload().await {
+                Ok(count) => debug!(tracked = count, "Reloaded access tracking marks"),
+                Err(e) => warn!(error = %e, "Failed to reload access tracking marks"),
+            }
+            match log.flush().await {
+                Ok(0) => {}
+                Ok(written) => debug!(rollups = written, "Content access views written"),
+                Err(e) => {
+                    warn!(error = %e, "Content access flush failed (will retry next interval)")
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_classify_referrer() {
+        let own = Some("doorway.elohim.host");
+        let bucket = |referer| classify_referrer(referer, own);
+        assert_eq!(bucket(None), ReferrerBucket::Direct);
+        assert_eq!(bucket(Some(" ")), ReferrerBucket::Direct);
+        assert_eq!(
+            bucket(Some("https://doorway.elohim.host/lamad/path")),
+            ReferrerBucket::Internal
+        );
+        assert_eq!(
+            bucket(Some("https://app.doorway.elohim.host/")),
+            ReferrerBucket::Internal
+        );
+        assert_eq!(
+            bucket(Some("https://www.google.co.uk/search?q=x")),
+            ReferrerBucket::Search
+        );
+        assert_eq!(bucket(Some("https://t.co/abc")), ReferrerBucket::Social);
+        assert_eq!(
+            bucket(Some("https://old.reddit.com/r/x")),
+            ReferrerBucket::Social
+        );
+        assert_eq!(
+            bucket(Some("https://mastodon.social/@x")),
+            ReferrerBucket::Social
+        );
+        assert_eq!(
+            bucket(Some("https://blog.example.org/post")),
+            ReferrerBucket::Other
+        );
+        assert_eq!(bucket(Some("not a url")), ReferrerBucket::Other);
+    }
+
+    #[test]
+    fn test_days() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 23, 59, 0).unwrap();
+        assert_eq!(day_of(now), "2026-03-01");
+        assert_eq!(first_day(1, now), "2026-03-01");
+        assert_eq!(first_day(2, now), "2026-02-28");
+        assert_eq!(first_day(0, now), "2026-03-01");
+    }
+}
@@ -0,0 +1,154 @@
+//! Content licenses
+//!
+//! Content records its license in metadata as an SPDX license expression
+//! (`{"license": "CC-BY-SA-4.0"}`), validated by the content_store zome on
+//! write and indexed for `get_content_by_license`. This module decides what
+//! the doorway may redistribute: the static site export and reciprocal
+//! federation only pass on commons reach content whose license allows it.
+//!
+//! An expression is redistributable when every `AND` term, or any `OR`
+//! alternative, is an open license below. `WITH` exceptions don't change the
+//! outcome. `LicenseRef-*` and any identifier not listed are custom terms
+//! and are held back. Content without a license keeps the old behaviour and
+//! is governed by reach alone.
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// Open licenses (lowercase identifiers, or prefixes ending in `-`) whose
+/// terms allow copying and adapting the work for any purpose
+const OPEN_LICENSES: [&str; 22] = [
+    "cc0-1.0",
+    "cc-by-1.0",
+    "cc-by-2.0",
+    "cc-by-2.5",
+    "cc-by-3.0",
+    "cc-by-4.0",
+    "cc-by-sa-",
+    "cc-pdm-1.0",
+    "pddl-1.0",
+    "odc-by-1.0",
+    "odbl-1.0",
+    "unlicense",
+    "mit",
+    "apache-2.0",
+    "bsd-2-clause",
+    "bsd-3-clause",
+    "isc",
+    "mpl-2.0",
+    "gpl-",
+    "lgpl-",
+    "agpl-",
+    "gfdl-",
+];
+
+/// How a content's license bears on redistribution
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LicenseStatus {
+    /// Open license: may leave the network
+    Commons,
+    /// License with terms we can't honour downstream (NC, ND, custom)
+    Restricted,
+    /// No license recorded; reach decides
+    Unspecified,
+}
+
+/// License expression of a content record
+///
+/// Reads `metadata_json` (a JSON string, as stored by the zome) or an
+/// already-decoded `metadata` object.
+pub fn license_of(content: &Value) -> Option<String> {
+    let decoded;
+    let metadata = match content.get("metadata_json").and_then(Value::as_str) {
+        Some(json) => {
+            decoded = serde_json::from_str::<Value>(json).ok()?;
+            &decoded
+        }
+        None => content.get("metadata")?,
+    };
+    metadata
+        .get("license")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|license| !license.is_empty())
+        .map(String::from)
+}
+
+fn is_open_id(id: &str) -> bool {
+    let id = id.trim_end_matches('+').to_ascii_lowercase();
+    OPEN_LICENSES
+        .iter()
+        .any(|open| match open.strip_suffix('-') {
+            Some(prefix) => id.starts_with(prefix) && id.len() > open.len(),
+            None => id == *open,
+        })
+}
+
+/// Whether an SPDX expression lets us redistribute the work
+///
+/// Parentheses are ignored and `AND` binds tighter than `OR`, which covers
+/// the expressions content authors write in practice.
+pub fn is_redistributable(expression: &str) -> bool {
+    let spaced = expression.replace(['(', ')'], " ");
+    let tokens: Vec<&str> = spaced.split_whitespace().collect();
+    tokens.split(|t| *t == "OR").any(|alternative| {
+        let mut ids = Vec::new();
+        let mut after_with = false;
+        for token in alternative {
+            match *token {
+                "AND" => after_with = false,
+                "WITH" => after_with = true,
+                _ if after_with => after_with = false,
+                id => ids.push(id),
+            }
+        }
+        !ids.is_empty() && ids.into_iter().all(is_open_id)
+    })
+}
+
+/// Redistribution status of a content record
+pub fn license_status(content: &Value) -> LicenseStatus {
+    match license_of(content) {
+        None => LicenseStatus::Unspecified,
+        Some(license) if is_redistributable(&license) => LicenseStatus::Commons,
+        Some(_) => LicenseStatus::Restricted,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_is_redistributable() {
+        assert!(is_redistributable("CC-BY-SA-4.0"));
+        assert!(is_redistributable("MIT OR Apache-2.0"));
+        assert!(is_redistributable("GPL-2.0+ WITH Classpath-exception-2.0"));
+        assert!(is_redistributable("CC-BY-NC-4.0 OR CC-BY-4.0"));
+        assert!(!is_redistributable("CC-BY-NC-SA-4.0"));
+        assert!(!is_redistributable("CC-BY-ND-4.0"));
+        assert!(!is_redistributable("MIT AND LicenseRef-acme"));
+        assert!(!is_redistributable("cc-by-sa-"));
+        assert!(!is_redistributable(""));
+    }
+
+    #[test]
+    fn test_license_status() {
+        let open = json!({"metadata_json": r#"{"license":"CC0-1.0"}"#});
+        let closed = json!({"metadata_json": r#"{"license":"CC-BY-NC-4.0"}"#});
+        let decoded = json!({"metadata": {"license": "CC-BY-4.0"}});
+        assert_eq!(license_status(&open), LicenseStatus::Commons);
+        assert_eq!(license_status(&closed), LicenseStatus::Restricted);
+        assert_eq!(license_status(&decoded), LicenseStatus::Commons);
+        assert_eq!(
+            license_status(&json!({"metadata_json": "{}"})),
+            LicenseStatus::Unspecified
+        );
+        assert_eq!(
+            license_status(&json!({"metadata_json": "not json"})),
+            LicenseStatus::Unspecified
+        );
+    }
+}
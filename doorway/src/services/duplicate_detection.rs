@@ -0,0 +1,511 @@
+//! Near-duplicate detection for content imports
+//!
+//! Every content import batch gets a duplicate check against the projection
+//! store, so curators see "this article is already in the graph" before ten
+//! copies of it fragment the relationships around it.
+//!
+//! Bodies are reduced to word shingles and compared with MinHash; banded
+//! locality-sensitive hashing keeps the lookup to a handful of candidates per
+//! item. Items are also compared with earlier items in the same batch. Items
+//! without a body (manifest-mode imports) are matched on `content_hash` only.
+//!
+//! The check is advisory: items are imported either way, and the report is
+//! added to the batch's import status under `duplicate_check`.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, RwLock};
+use tracing::{info, warn};
+
+use crate::projection::{ProjectedDocument, ProjectionQuery, ProjectionStore};
+
+/// Words per shingle
+const SHINGLE_WORDS: usize = 5;
+
+/// MinHash signature length
+const NUM_HASHES: usize = 128;
+
+/// LSH bands; rows per band = NUM_HASHES / BANDS. 32 bands of 4 rows
+/// surface pairs from roughly 0.4 similarity upward as candidates.
+const BANDS: usize = 32;
+
+/// Bodies shorter than this (in words) are too short to judge
+const MIN_WORDS: usize = 20;
+
+/// Matches reported per item, best first
+const MAX_MATCHES_PER_ITEM: usize = 3;
+
+/// Batch reports kept in memory; oldest are dropped first
+const MAX_REPORTS: usize = 200;
+
+/// Lowercased words of a body, with HTML tags skipped
+fn words(text: &str) -> Vec<String> {
+    let mut plain = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                plain.push(' ');
+            }
+            _ if !in_tag => plain.push(c),
+            _ => {}
+        }
+    }
+    plain
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ u64::from(*b)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+/// MinHash signature of a body's word shingles
+#[derive(Debug, Clone, PartialEq)]
+pub struct MinHash(Vec<u64>);
+
+impl MinHash {
+    /// Signature for a body; `None` when it is too short to judge
+    pub fn from_text(text: &str) -> Option<Self> {
+        let words = words(text);
+        if words.len() < MIN_WORDS {
+            return None;
+        }
+        let mut mins = vec![u64::MAX; NUM_HASHES];
+        for shingle in words.windows(SHINGLE_WORDS) {
+            let base = fnv1a(shingle.join(" ").as_bytes());
+            for (i, min) in mins.iter_mut().enumerate() {
+                let value = splitmix64(base ^ splitmix64(i as u64));
+                if value < *min {
+                    *min = value;
+                }
+            }
+        }
+        Some(Self(mins))
+    }
+
+    /// Estimated Jaccard similarity of the two shingle sets
+    pub fn similarity(&self, other: &Self) -> f64 {
+        let equal = self.0.iter().zip(&other.0).filter(|(a, b)| a == b).count();
+        equal as f64 / NUM_HASHES as f64
+    }
+
+    fn band_keys(&self) -> impl Iterator<Item = (usize, u64)> + '_ {
+        self.0
+            .chunks(NUM_HASHES / BANDS)
+            .enumerate()
+            .map(|(band, rows)| {
+                let bytes: Vec<u8> = rows.iter().flat_map(|r| r.to_le_bytes()).collect();
+                (band, fnv1a(&bytes))
+            })
+    }
+}
+
+/// Content an import item may duplicate
+#[derive(Debug, Clone)]
+struct IndexedContent {
+    id: String,
+    title: String,
+    signature: Option<MinHash>,
+}
+
+/// Signatures of known content, banded for candidate lookup
+#[derive(Debug, Default)]
+pub struct MinHashIndex {
+    entries: Vec<IndexedContent>,
+    bands: HashMap<(usize, u64), Vec<usize>>,
+    by_content_hash: HashMap<String, usize>,
+}
+
+impl MinHashIndex {
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn insert(&mut self, content: &ImportedContent) {
+        let index = self.entries.len();
+        if let Some(ref signature) = content.signature {
+            for key in signature.band_keys() {
+                self.bands.entry(key).or_default().push(index);
+            }
+        }
+        if let Some(ref hash) = content.content_hash {
+            self.by_content_hash.entry(hash.clone()).or_insert(index);
+        }
+        self.entries.push(IndexedContent {
+            id: content.id.clone(),
+            title: content.title.clone(),
+            signature: content.signature.clone(),
+        });
+    }
+
+    /// Entries at or above `threshold`, best first, skipping the item itself
+    fn matches(&self, content: &ImportedContent, threshold: f64) -> Vec<(&IndexedContent, f64)> {
+        let mut found: Vec<(&IndexedContent, f64)> = Vec::new();
+        if let Some(&index) = content
+            .content_hash
+            .as_ref()
+            .and_then(|hash| self.by_content_hash.get(hash))
+        {
+            found.push((&self.entries[index], 1.0));
+        }
+        if let Some(ref signature) = content.signature {
+            let candidates: HashSet<usize> = signature
+                .band_keys()
+                .filter_map(|key| self.bands.get(&key))
+                .flatten()
+                .copied()
+                .collect();
+            for index in candidates {
+                let entry = &self.entries[index];
+                if let Some(ref other) = entry.signature {
+                    let similarity = signature.similarity(other);
+                    if similarity >= threshold {
+                        found.push((entry, similarity));
+                    }
+                }
+            }
+        }
+
+        found.retain(|(entry, _)| entry.id != content.id);
+        found.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.id.cmp(&b.0.id)));
+        let mut seen = HashSet::new();
+        found.retain(|(entry, _)| seen.insert(entry.id.clone()));
+        found.truncate(MAX_MATCHES_PER_ITEM);
+        found
+    }
+}
+
+/// The parts of a content item the check reads
+#[derive(Debug, Clone, PartialEq)]
+struct ImportedContent {
+    id: String,
+    title: String,
+    signature: Option<MinHash>,
+    content_hash: Option<String>,
+}
+
+impl ImportedContent {
+    /// From an import item (CreateContentInput) or projected content data
+    fn from_json(value: &Value) -> Option<Self> {
+        let text = |key: &str| {
+            value
+                .get(key)
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        };
+        Some(Self {
+            id: text("id")?,
+            title: text("title").unwrap_or_default(),
+            signature: text("content").as_deref().and_then(MinHash::from_text),
+            content_hash: text("content_hash"),
+        })
+    }
+}
+
+/// Where a suspected duplicate was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateSource {
+    /// Content already in the graph
+    Existing,
+    /// An earlier item in the same batch
+    Batch,
+}
+
+/// One item that reads like content already known
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SuspectedDuplicate {
+    pub item_id: String,
+    pub item_title: String,
+    pub duplicate_of: String,
+    pub duplicate_title: String,
+    /// Estimated Jaccard similarity of the bodies (1.0 for equal content hashes)
+    pub similarity: f64,
+    pub source: DuplicateSource,
+}
+
+/// Result of checking one batch
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DuplicateReport {
+    pub threshold: f64,
+    /// Items compared
+    pub checked: usize,
+    /// Items without an id, or too short to compare
+    pub skipped: usize,
+    /// Content in the projection store it was compared against
+    pub existing_content: usize,
+    pub suspected_duplicates: Vec<SuspectedDuplicate>,
+}
+
+/// Compare import items with existing content and with each other
+pub fn find_duplicates(
+    existing: &MinHashIndex,
+    items: &[Value],
+    threshold: f64,
+) -> DuplicateReport {
+    let mut report = DuplicateReport {
+        threshold,
+        checked: 0,
+        skipped: 0,
+        existing_content: existing.len(),
+        suspected_duplicates: Vec::new(),
+    };
+    let mut batch = MinHashIndex::default();
+
+    for item in items {
+        let Some(content) = ImportedContent::from_json(item) else {
+            report.skipped += 1;
+            continue;
+        };
+        if content.signature.is_none() && content.content_hash.is_none() {
+            report.skipped += 1;
+            continue;
+        }
+        report.checked += 1;
+
+        let found = existing
+            .matches(&content, threshold)
+            .into_iter()
+            .map(|m| (m, DuplicateSource::Existing))
+            .chain(
+                batch
+                    .matches(&content, threshold)
+                    .into_iter()
+                    .map(|m| (m, DuplicateSource::Batch)),
+            );
+        for ((entry, similarity), source) in found.take(MAX_MATCHES_PER_ITEM) {
+            report.suspected_duplicates.push(SuspectedDuplicate {
+                item_id: content.id.clone(),
+                item_title: content.title.clone(),
+                duplicate_of: entry.id.clone(),
+                duplicate_title: entry.title.clone(),
+                similarity: (similarity * 1000.0).round() / 1000.0,
+                source,
+            });
+        }
+        batch.insert(&content);
+    }
+    report
+}
+
+/// State of a batch's duplicate check, as shown in its import status
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum DuplicateCheck {
+    Running,
+    Completed(DuplicateReport),
+    Failed { error: String },
+}
+
+/// Runs duplicate checks for import batches and keeps their reports
+pub struct DuplicateDetector {
+    projection: Arc<ProjectionStore>,
+    threshold: f64,
+    reports: RwLock<(HashMap<String, DuplicateCheck>, VecDeque<String>)>,
+}
+
+impl DuplicateDetector {
+    pub fn new(projection: Arc<ProjectionStore>, threshold: f64) -> Self {
+        Self {
+            projection,
+            threshold,
+            reports: RwLock::new((HashMap::new(), VecDeque::new())),
+        }
+    }
+
+    /// Report for a batch, if it was checked by this doorway
+    pub fn report(&self, batch_id: &str) -> Option<DuplicateCheck> {
+        self.reports.read().ok()?.0.get(batch_id).cloned()
+    }
+
+    fn set_report(&self, batch_id: &str, check: DuplicateCheck) {
+        let Ok(mut reports) = self.reports.write() else {
+            return;
+        };
+        let (checks, order) = &mut *reports;
+        if checks.insert(batch_id.to_string(), check).is_none() {
+            order.push_back(batch_id.to_string());
+        }
+        while order.len() > MAX_REPORTS {
+            if let Some(oldest) = order.pop_front() {
+                checks.remove(&oldest);
+            }
+        }
+    }
+
+    /// Signatures of the content in the projection store
+    async fn existing_index(&self) -> Result<MinHashIndex, String> {
+        let mut query = ProjectionQuery::by_type("Content");
+        query.filter = Some(bson::doc! { "metadata.is_deleted": { "$ne": true } });
+        let docs: Vec<ProjectedDocument> = self
+            .projection
+            .query(query)
+            .await
+            .map_err(|e| format!("Content query failed: {e}"))?;
+
+        let mut index = MinHashIndex::default();
+        for doc in &docs {
+            if let Some(content) = ImportedContent::from_json(&doc.data) {
+                index.insert(&content);
+            }
+        }
+        Ok(index)
+    }
+
+    /// Check a batch in the background; the report appears once done
+    pub fn check_batch(self: &Arc<Self>, batch_id: String, items: Vec<Value>) {
+        self.set_report(&batch_id, DuplicateCheck::Running);
+        let detector = Arc::clone(self);
+        tokio::spawn(async move {
+            let check = match detector.existing_index().await {
+                Ok(existing) => {
+                    let report = find_duplicates(&existing, &items, detector.threshold);
+                    info!(
+                        batch_id = %batch_id,
+                        checked = report.checked,
+                        suspected = report.suspected_duplicates.len(),
+                        "Import duplicate check finished"
+                    );
+                    DuplicateCheck::Completed(report)
+                }
+                Err(e) => {
+                    warn!(batch_id = %batch_id, error = %e, "Import duplicate check failed");
+                    DuplicateCheck::Failed { error: e }
+                }
+            };
+            detector.set_report(&batch_id, check);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const ARTICLE: &str = "The commons are resources held in common by a community, \
+        not owned privately. Communities develop rules over time for who may use them, \
+        how much each member may take, and how disputes are settled when they arise. \
+        Elinor Ostrom showed that such rules can sustain forests, fisheries and water \
+        systems for centuries without either privatisation or central control.";
+
+    fn item(id: &str, body: &str) -> Value {
+        json!({ "id": id, "title": format!("Title {id}"), "content": body })
+    }
+
+    fn index_of(items: &[Value]) -> MinHashIndex {
+        let mut index = MinHashIndex::default();
+        for item in items {
+            index.insert(&ImportedContent::from_json(item).unwrap());
+        }
+        index
+    }
+
+    #[test]
+    fn test_words_skip_markup() {
+        assert_eq!(
+            words("<p>Hello, <b>World</b>!</p>"),
+            vec!["hello".to_string(), "world".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_minhash_similarity() {
+        let a = MinHash::from_text(ARTICLE).unwrap();
+        let edited = ARTICLE.replace("centuries", "generations");
+        let b = MinHash::from_text(&edited).unwrap();
+        let other = MinHash::from_text(
+            "Photosynthesis converts light energy into chemical energy stored in glucose. \
+             Chlorophyll in the chloroplasts absorbs mostly blue and red light, while \
+             the light reactions split water and release oxygen as a by-product of the process.",
+        )
+        .unwrap();
+
+        assert_eq!(a.similarity(&a), 1.0);
+        assert!(a.similarity(&b) > 0.6, "{}", a.similarity(&b));
+        assert!(a.similarity(&other) < 0.1, "{}", a.similarity(&other));
+        assert_eq!(MinHash::from_text("too short to judge"), None);
+    }
+
+    #[test]
+    fn test_find_duplicates_against_existing_and_batch() {
+        let existing = index_of(&[item("commons-intro", ARTICLE)]);
+        let items = vec![
+            item("commons-copy", &format!("{ARTICLE} ")),
+            item("commons-copy-2", ARTICLE),
+            item("short", "Just a few words"),
+            json!({ "title": "No id", "content": ARTICLE }),
+        ];
+        let report = find_duplicates(&existing, &items, 0.8);
+
+        assert_eq!(report.checked, 2);
+        assert_eq!(report.skipped, 2);
+        assert_eq!(report.existing_content, 1);
+
+        let pairs: Vec<(&str, &str, DuplicateSource)> = report
+            .suspected_duplicates
+            .iter()
+            .map(|d| (d.item_id.as_str(), d.duplicate_of.as_str(), d.source))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("commons-copy", "commons-intro", DuplicateSource::Existing),
+                ("commons-copy-2", "commons-intro", DuplicateSource::Existing),
+                ("commons-copy-2", "commons-copy", DuplicateSource::Batch),
+            ]
+        );
+        assert!(report
+            .suspected_duplicates
+            .iter()
+            .all(|d| d.similarity == 1.0));
+    }
+
+    #[test]
+    fn test_reimport_of_same_id_is_not_a_duplicate() {
+        let existing = index_of(&[item("commons-intro", ARTICLE)]);
+        let report = find_duplicates(&existing, &[item("commons-intro", ARTICLE)], 0.8);
+        assert!(report.suspected_duplicates.is_empty());
+    }
+
+    #[test]
+    fn test_manifest_items_match_on_content_hash() {
+        let existing = index_of(&[json!({
+            "id": "video-1",
+            "title": "Lecture",
+            "content": "",
+            "content_hash": "sha256-abc"
+        })]);
+        let items = vec![json!({
+            "id": "video-2",
+            "title": "Lecture (copy)",
+            "content": "",
+            "content_hash": "sha256-abc"
+        })];
+        let report = find_duplicates(&existing, &items, 0.8);
+        assert_eq!(report.suspected_duplicates.len(), 1);
+        assert_eq!(report.suspected_duplicates[0].duplicate_of, "video-1");
+        assert_eq!(report.suspected_duplicates[0].similarity, 1.0);
+    }
+}
@@ -0,0 +1,267 @@
+//! Embeddable learning paths
+//!
+//! Lets partner sites show a learning path in an iframe without a frontend
+//! integration. The operator lists the partners allowed to embed in
+//! `EMBED_PARTNERS`, each with a shared secret; `/embed/{path_id}` may only
+//! be framed by those origins (CSP `frame-ancestors`).
+//!
+//! ## Handshake
+//!
+//! 1. The partner page frames `/embed/{path_id}`. The player posts
+//!    `{type: "elohim-embed:ready", path_id}` to the allowed origins.
+//! 2. The partner page answers with a scoped token,
+//!    `{type: "elohim-embed:auth", token}`. The player only accepts it from
+//!    its parent window and an allowed origin.
+//! 3. The player loads the path from `/embed/{path_id}/path`, passing the
+//!    token and the origin it came from, and reports
+//!    `elohim-embed:loaded` or `elohim-embed:error` back to the parent.
+//!
+//! ## Tokens
+//!
+//! Partners mint tokens on their own server, so the secret never reaches a
+//! browser. A token is `{exp}.{sig}` where `exp` is a unix time at most
+//! `EMBED_TOKEN_MAX_TTL_SECS` ahead and `sig` is hex HMAC-SHA256, keyed with
+//! the partner's secret, over the lines
+//!
+//! ```text
+//! elohim-embed:v1
+//! {origin}
+//! {path_id}
+//! {exp}
+//! ```
+//!
+//! A token is only good for the path and the partner it was minted for.
+//! Embeds only ever show public paths and commons/public content.
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::time::Duration;
+use thiserror::Error;
+
+use crate::config::Args;
+
+/// Version prefix of the signed payload
+const TOKEN_DOMAIN: &str = "elohim-embed:v1";
+
+/// Shortest partner secret accepted
+const MIN_SECRET_LEN: usize = 32;
+
+/// Embed token errors
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum EmbedError {
+    #[error("Embed token required")]
+    Missing,
+
+    #[error("Origin {0} may not embed paths")]
+    OriginNotAllowed(String),
+
+    #[error("Malformed embed token")]
+    Malformed,
+
+    #[error("Invalid embed token signature")]
+    InvalidSignature,
+
+    #[error("Embed token expired")]
+    Expired,
+
+    #[error("Embed token lives longer than {0}s")]
+    TooLong(u64),
+
+    #[error("Invalid embed config: {0}")]
+    Config(String),
+}
+
+/// A partner site allowed to embed
+#[derive(Debug, Clone)]
+struct EmbedPartner {
+    origin: String,
+    secret: Vec<u8>,
+}
+
+/// Normalize an origin to `scheme://host[:port]`
+///
+/// `None` when it isn't a bare http(s) origin.
+pub fn normalize_origin(origin: &str) -> Option<String> {
+    let url = reqwest::Url::parse(origin.trim()).ok()?;
+    let bare = matches!(url.path(), "" | "/") && url.query().is_none() && url.fragment().is_none();
+    if !matches!(url.scheme(), "http" | "https") || !bare || url.host_str().is_none() {
+        return None;
+    }
+    Some(url.origin().ascii_serialization())
+}
+
+/// Partners allowed to embed and the tokens they may present
+#[derive(Debug)]
+pub struct EmbedPolicy {
+    partners: Vec<EmbedPartner>,
+    max_ttl: Duration,
+}
+
+impl EmbedPolicy {
+    /// Policy as configured, `None` when `EMBED_PARTNERS` is unset
+    pub fn from_args(args: &Args) -> Result<Option<Self>, EmbedError> {
+        if args.embed_partners.is_empty() {
+            return Ok(None);
+        }
+        Self::new(
+            &args.embed_partners,
+            Duration::from_secs(args.embed_token_max_ttl_secs),
+        )
+        .map(Some)
+    }
+
+    /// Policy from `origin=secret` entries
+    pub fn new(entries: &[String], max_ttl: Duration) -> Result<Self, EmbedError> {
+        if max_ttl.is_zero() {
+            return Err(EmbedError::Config(
+                "EMBED_TOKEN_MAX_TTL_SECS must be positive".to_string(),
+            ));
+        }
+        let mut partners: Vec<EmbedPartner> = Vec::new();
+        for entry in entries.iter().map(|e| e.trim()).filter(|e| !e.is_empty()) {
+            let (origin, secret) = entry
+                .split_once('=')
+                .ok_or_else(|| EmbedError::Config(format!("Expected origin=secret: {entry}")))?;
+            let origin = normalize_origin(origin)
+                .ok_or_else(|| EmbedError::Config(format!("Not an origin: {origin}")))?;
+            if secret.len() < MIN_SECRET_LEN {
+                return Err(EmbedError::Config(format!(
+                    "Secret for {origin} must be at least {MIN_SECRET_LEN} characters"
+                )));
+            }
+            if partners.iter().any(|p| p.origin == origin) {
+                return Err(EmbedError::Config(format!("Duplicate partner: {origin}")));
+            }
+            partners.push(EmbedPartner {
+                origin,
+                secret: secret.as_bytes().to_vec(),
+            });
+        }
+        if partners.is_empty() {
+            return Err(EmbedError::Config("No embed partners".to_string()));
+        }
+        Ok(Self { partners, max_ttl })
+    }
+
+    /// Origins allowed to frame embeds
+    pub fn origins(&self) -> impl Iterator<Item = &str> {
+        self.partners.iter().map(|p| p.origin.as_str())
+    }
+
+    fn partner(&self, origin: &str) -> Option<&EmbedPartner> {
+        let origin = normalize_origin(origin)?;
+        self.partners.iter().find(|p| p.origin == origin)
+    }
+
+    fn mac(partner: &EmbedPartner, path_id: &str, exp: i64) -> Hmac<Sha256> {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&partner.secret).expect("HMAC accepts any key length");
+        mac.update(format!("{TOKEN_DOMAIN}\n{}\n{path_id}\n{exp}", partner.origin).as_bytes());
+        mac
+    }
+
+    /// Mint a token as a partner would, for tests and tooling
+    pub fn sign(&self, origin: &str, path_id: &str, exp: i64) -> Result<String, EmbedError> {
+        let partner = self
+            .partner(origin)
+            .ok_or_else(|| EmbedError::OriginNotAllowed(origin.to_string()))?;
+        let sig = hex::encode(Self::mac(partner, path_id, exp).finalize().into_bytes());
+        Ok(format!("{exp}.{sig}"))
+    }
+
+    /// Check a token presented for `path_id` by the page at `origin`
+    pub fn verify(
+        &self,
+        token: Option<&str>,
+        origin: Option<&str>,
+        path_id: &str,
+        now: i64,
+    ) -> Result<(), EmbedError> {
+        let (Some(token), Some(origin)) = (token, origin) else {
+            return Err(EmbedError::Missing);
+        };
+        let partner = self
+            .partner(origin)
+            .ok_or_else(|| EmbedError::OriginNotAllowed(origin.to_string()))?;
+        let (exp, sig) = token.trim().split_once('.').ok_or(EmbedError::Malformed)?;
+        let exp: i64 = exp.parse().map_err(|_| EmbedError::Malformed)?;
+        let sig = hex::decode(sig).map_err(|_| EmbedError::Malformed)?;
+        Self::mac(partner, path_id, exp)
+            .verify_slice(&sig)
+            .map_err(|_| EmbedError::InvalidSignature)?;
+        if exp < now {
+            return Err(EmbedError::Expired);
+        }
+        if exp - now > self.max_ttl.as_secs() as i64 {
+            return Err(EmbedError::TooLong(self.max_ttl.as_secs()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARTNER: &str = "https://school.example";
+
+    fn policy() -> EmbedPolicy {
+        EmbedPolicy::new(
+            &[format!("{PARTNER}/={}", "s".repeat(32))],
+            Duration::from_secs(600),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_token_round_trip() {
+        let policy = policy();
+        let token = policy.sign(PARTNER, "governance-intro", 1_500).unwrap();
+        let verify = |token: &str, origin: &str, path: &str, now: i64| {
+            policy.verify(Some(token), Some(origin), path, now)
+        };
+        assert_eq!(verify(&token, PARTNER, "governance-intro", 1_000), Ok(()));
+
+        assert_eq!(
+            verify(&token, PARTNER, "other-path", 1_000),
+            Err(EmbedError::InvalidSignature)
+        );
+        assert_eq!(
+            verify(&token, "https://evil.example", "governance-intro", 1_000),
+            Err(EmbedError::OriginNotAllowed(
+                "https://evil.example".to_string()
+            ))
+        );
+        assert_eq!(
+            verify(&token, PARTNER, "governance-intro", 1_501),
+            Err(EmbedError::Expired)
+        );
+        assert_eq!(
+            verify(&token, PARTNER, "governance-intro", 800),
+            Err(EmbedError::TooLong(600))
+        );
+        assert_eq!(
+            verify("1500.zz", PARTNER, "governance-intro", 1_000),
+            Err(EmbedError::Malformed)
+        );
+        assert_eq!(
+            policy.verify(None, Some(PARTNER), "governance-intro", 1_000),
+            Err(EmbedError::Missing)
+        );
+    }
+
+    #[test]
+    fn test_partner_config() {
+        assert_eq!(
+            normalize_origin("HTTPS://School.Example:443/"),
+            Some(PARTNER.to_string())
+        );
+        assert_eq!(normalize_origin("https://school.example/courses"), None);
+        assert_eq!(normalize_origin("javascript:alert(1)"), None);
+
+        let ttl = Duration::from_secs(600);
+        assert!(EmbedPolicy::new(&[format!("{PARTNER}=short")], ttl).is_err());
+        assert!(EmbedPolicy::new(&[PARTNER.to_string()], ttl).is_err());
+        assert_eq!(policy().origins().collect::<Vec<_>>(), vec![PARTNER]);
+    }
+}
@@ -0,0 +1,292 @@
+//! Guest Sessions
+//!
+//! Lets landing pages show real content before a visitor signs up, without
+//! every visitor becoming an anonymous call to the conductor. A page asks
+//! for a guest token (`POST /guest/session`) and sends it as
+//! `Authorization: Guest {token}` on its reads. Guest reads:
+//!
+//! - only work on the cache API (`GET /api/v1/cache/...`) and ranged bodies
+//!   (`GET /content/{id}/body`),
+//! - are served from the projection only, never the conductor, and only
+//!   return `commons` and `public` documents,
+//! - are limited to `GUEST_READS_PER_MINUTE` per token; a client IP may
+//!   start `GUEST_SESSIONS_PER_MINUTE` sessions.
+//!
+//! ## Tokens
+//!
+//! A token is `{id}.{exp}.{sig}`: a random session id, a unix time
+//! `GUEST_TOKEN_TTL_SECS` ahead, and hex HMAC-SHA256, keyed with
+//! `GUEST_TOKEN_SECRET`, over the lines
+//!
+//! ```text
+//! elohim-guest:v1
+//! {id}
+//! {exp}
+//! ```
+//!
+//! Guest tokens name nobody and are refused wherever a doorway JWT is
+//! expected.
+
+use dashmap::DashMap;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+use crate::config::Args;
+
+/// Version prefix of the signed payload
+const TOKEN_DOMAIN: &str = "elohim-guest:v1";
+
+/// Shortest secret accepted
+const MIN_SECRET_LEN: usize = 32;
+
+/// Scheme of the `Authorization` header carrying a guest token
+const AUTH_SCHEME: &str = "Guest ";
+
+/// Window the rate limits are counted over
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Checks between sweeps of idle keys
+const SWEEP_EVERY: u64 = 1000;
+
+/// Guest session errors
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum GuestError {
+    #[error("Malformed guest token")]
+    Malformed,
+
+    #[error("Invalid guest token signature")]
+    InvalidSignature,
+
+    #[error("Guest token expired")]
+    Expired,
+
+    #[error("Invalid guest session config: {0}")]
+    Config(String),
+}
+
+/// A guest token as handed to a landing page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GuestToken {
+    pub token: String,
+    pub session_id: String,
+    pub expires_at: i64,
+}
+
+/// The guest token in an `Authorization` header, if it carries one
+pub fn guest_token(auth_header: Option<&str>) -> Option<&str> {
+    auth_header?.strip_prefix(AUTH_SCHEME).map(str::trim)
+}
+
+/// Sliding one-minute request counts per key
+#[derive(Debug)]
+struct WindowLimiter {
+    max_per_window: usize,
+    hits: DashMap<String, VecDeque<Instant>>,
+    checks: AtomicU64,
+}
+
+impl WindowLimiter {
+    fn new(max_per_window: usize) -> Self {
+        Self {
+            max_per_window,
+            hits: DashMap::new(),
+            checks: AtomicU64::new(0),
+        }
+    }
+
+    /// Count a request by `key`, or say how long until it may retry
+    fn check(&self, key: &str, now: Instant) -> Result<(), Duration> {
+        if self.checks.fetch_add(1, Ordering::Relaxed) % SWEEP_EVERY == SWEEP_EVERY - 1 {
+            self.hits.retain(|_, hits| {
+                hits.back()
+                    .is_some_and(|last| now.duration_since(*last) < RATE_WINDOW)
+            });
+        }
+        let mut hits = self.hits.entry(key.to_string()).or_default();
+        while hits
+            .front()
+            .is_some_and(|first| now.duration_since(*first) >= RATE_WINDOW)
+        {
+            hits.pop_front();
+        }
+        if hits.len() >= self.max_per_window {
+            let oldest = hits.front().copied().unwrap_or(now);
+            return Err(RATE_WINDOW.saturating_sub(now.duration_since(oldest)));
+        }
+        hits.push_back(now);
+        Ok(())
+    }
+}
+
+/// Issues and checks guest tokens, and keeps guests to their rate limits
+#[derive(Debug)]
+pub struct GuestSessions {
+    key: Vec<u8>,
+    ttl: Duration,
+    sessions: WindowLimiter,
+    reads: WindowLimiter,
+}
+
+impl GuestSessions {
+    /// Guest sessions as configured, `None` when `GUEST_TOKEN_SECRET` is unset
+    pub fn from_args(args: &Args) -> Result<Option<Self>, GuestError> {
+        let Some(secret) = args.guest_token_secret.as_deref() else {
+            return Ok(None);
+        };
+        Self::new(
+            secret,
+            Duration::from_secs(args.guest_token_ttl_secs),
+            args.guest_sessions_per_minute,
+            args.guest_reads_per_minute,
+        )
+        .map(Some)
+    }
+
+    pub fn new(
+        secret: &str,
+        ttl: Duration,
+        sessions_per_minute: usize,
+        reads_per_minute: usize,
+    ) -> Result<Self, GuestError> {
+        if secret.len() < MIN_SECRET_LEN {
+            return Err(GuestError::Config(format!(
+                "GUEST_TOKEN_SECRET must be at least {MIN_SECRET_LEN} characters"
+            )));
+        }
+        if ttl.is_zero() {
+            return Err(GuestError::Config(
+                "GUEST_TOKEN_TTL_SECS must be positive".to_string(),
+            ));
+        }
+        if sessions_per_minute == 0 || reads_per_minute == 0 {
+            return Err(GuestError::Config(
+                "Guest rate limits must be positive".to_string(),
+            ));
+        }
+        Ok(Self {
+            key: secret.as_bytes().to_vec(),
+            ttl,
+            sessions: WindowLimiter::new(sessions_per_minute),
+            reads: WindowLimiter::new(reads_per_minute),
+        })
+    }
+
+    /// Lifetime of issued tokens
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    fn mac(&self, session_id: &str, exp: i64) -> Hmac<Sha256> {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC accepts any key length");
+        mac.update(format!("{TOKEN_DOMAIN}\n{session_id}\n{exp}").as_bytes());
+        mac
+    }
+
+    /// Count a new session from `client`, or say how long until it may retry
+    pub fn allow_session(&self, client: &str, now: Instant) -> Result<(), Duration> {
+        self.sessions.check(client, now)
+    }
+
+    /// Count a read in a guest session, or say how long until it may retry
+    pub fn allow_read(&self, session_id: &str, now: Instant) -> Result<(), Duration> {
+        self.reads.check(session_id, now)
+    }
+
+    /// Sign a token for a new session, valid for the configured TTL
+    pub fn issue(&self, now: i64) -> GuestToken {
+        let session_id = uuid::Uuid::new_v4().simple().to_string();
+        let expires_at = now + self.ttl.as_secs() as i64;
+        let sig = hex::encode(self.mac(&session_id, expires_at).finalize().into_bytes());
+        GuestToken {
+            token: format!("{session_id}.{expires_at}.{sig}"),
+            session_id,
+            expires_at,
+        }
+    }
+
+    /// Check a token, returning its session id
+    pub fn verify(&self, token: &str, now: i64) -> Result<String, GuestError> {
+        let mut parts = token.trim().splitn(3, '.');
+        let (Some(session_id), Some(exp), Some(sig)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(GuestError::Malformed);
+        };
+        let exp: i64 = exp.parse().map_err(|_| GuestError::Malformed)?;
+        let sig = hex::decode(sig).map_err(|_| GuestError::Malformed)?;
+        self.mac(session_id, exp)
+            .verify_slice(&sig)
+            .map_err(|_| GuestError::InvalidSignature)?;
+        if exp < now {
+            return Err(GuestError::Expired);
+        }
+        Ok(session_id.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sessions() -> GuestSessions {
+        GuestSessions::new(&"s".repeat(32), Duration::from_secs(900), 2, 3).unwrap()
+    }
+
+    #[test]
+    fn test_guest_token_round_trip() {
+        let sessions = sessions();
+        let issued = sessions.issue(1_000);
+        assert_eq!(issued.expires_at, 1_900);
+        assert_eq!(
+            sessions.verify(&issued.token, 1_500),
+            Ok(issued.session_id.clone())
+        );
+
+        assert_eq!(
+            sessions.verify(&issued.token, 1_901),
+            Err(GuestError::Expired)
+        );
+        let extended = issued.token.replace(".1900.", ".9900.");
+        assert_eq!(
+            sessions.verify(&extended, 1_500),
+            Err(GuestError::InvalidSignature)
+        );
+        assert_eq!(
+            sessions.verify("not-a-token", 1_500),
+            Err(GuestError::Malformed)
+        );
+        assert!(GuestSessions::new("short", Duration::from_secs(900), 2, 3).is_err());
+
+        assert_eq!(guest_token(Some("Guest abc.1.ff")), Some("abc.1.ff"));
+        assert_eq!(guest_token(Some("Bearer abc")), None);
+        assert_eq!(guest_token(None), None);
+    }
+
+    #[test]
+    fn test_guest_rate_limits() {
+        let sessions = sessions();
+        let start = Instant::now();
+        assert_eq!(sessions.allow_session("203.0.113.9", start), Ok(()));
+        assert_eq!(sessions.allow_session("203.0.113.9", start), Ok(()));
+        let later = start + Duration::from_secs(20);
+        assert_eq!(
+            sessions.allow_session("203.0.113.9", later),
+            Err(Duration::from_secs(40))
+        );
+        assert_eq!(sessions.allow_session("198.51.100.4", later), Ok(()));
+        assert_eq!(
+            sessions.allow_session("203.0.113.9", start + RATE_WINDOW),
+            Ok(())
+        );
+
+        for _ in 0..3 {
+            assert_eq!(sessions.allow_read("g1", start), Ok(()));
+        }
+        assert!(sessions.allow_read("g1", start).is_err());
+        assert_eq!(sessions.allow_read("g2", start), Ok(()));
+    }
+}
@@ -0,0 +1,361 @@
+//! Identity Links
+//!
+//! Merges a second account into the account a person keeps, so one human
+//! ends up with one agent key. The person signs in to the account they keep
+//! and proves control of the other one with its password, a passkey or an
+//! API key. Linking then:
+//!
+//! 1. records an [`IdentityLinkDoc`] listing what is moved,
+//! 2. moves the other account's passkeys and API keys to the kept account,
+//! 3. marks the other account `linked_to` the kept one, so its password
+//!    login signs in as the kept identity and agent key.
+//!
+//! If a step fails, the steps before it are undone. Unlinking undoes a link
+//! the same way and keeps the record, marked unlinked.
+//!
+//! The linked account's agent stays provisioned on its conductor; tokens
+//! just stop naming it. Accounts can't be chained: an account that others
+//! are linked into can't itself be linked, and linking into an account
+//! that is linked elsewhere is refused.
+
+use bson::{doc, DateTime};
+use thiserror::Error;
+use tracing::{info, warn};
+
+use crate::db::schemas::{
+    ApiKeyDoc, IdentityLinkDoc, LinkProof, PasskeyDoc, UserDoc, API_KEY_COLLECTION,
+    IDENTITY_LINK_COLLECTION, PASSKEY_COLLECTION, USER_COLLECTION,
+};
+use crate::db::{MongoClient, MongoCollection};
+use crate::types::DoorwayError;
+
+/// Identity link errors
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum LinkError {
+    #[error("An account can't be linked into itself")]
+    SameAccount,
+
+    #[error("{0} is linked into another account; sign in there to link accounts")]
+    PrimaryLinked(String),
+
+    #[error("{0} is already linked into another account")]
+    AlreadyLinked(String),
+
+    #[error("Other accounts are linked into {0}; unlink them first")]
+    HasLinkedAccounts(String),
+
+    #[error("{0} is not linked into this account")]
+    NotLinked(String),
+
+    #[error("Database error: {0}")]
+    Database(String),
+}
+
+impl From<DoorwayError> for LinkError {
+    fn from(e: DoorwayError) -> Self {
+        LinkError::Database(e.to_string())
+    }
+}
+
+/// Check that `other` may be linked into `primary`
+///
+/// `other_has_links` tells whether accounts are linked into `other`.
+pub fn check_linkable(
+    primary: &UserDoc,
+    other: &UserDoc,
+    other_has_links: bool,
+) -> Result<(), LinkError> {
+    if primary.identifier == other.identifier || primary.agent_pub_key == other.agent_pub_key {
+        return Err(LinkError::SameAccount);
+    }
+    if primary.linked_to.is_some() {
+        return Err(LinkError::PrimaryLinked(primary.identifier.clone()));
+    }
+    if other.linked_to.is_some() {
+        return Err(LinkError::AlreadyLinked(other.identifier.clone()));
+    }
+    if other_has_links {
+        return Err(LinkError::HasLinkedAccounts(other.identifier.clone()));
+    }
+    Ok(())
+}
+
+/// The account a user signs in as: itself, or the account it is linked into
+///
+/// `None` when the account it is linked into is gone or inactive.
+pub async fn resolve_linked_user(
+    users: &MongoCollection<UserDoc>,
+    user: UserDoc,
+) -> Result<Option<UserDoc>, DoorwayError> {
+    let Some(primary) = &user.linked_to else {
+        return Ok(Some(user));
+    };
+    users
+        .find_one(doc! { "identifier": primary, "is_active": true })
+        .await
+}
+
+/// Accounts linked into `primary_identifier`
+pub async fn linked_accounts(
+    mongo: &MongoClient,
+    primary_identifier: &str,
+) -> Result<Vec<IdentityLinkDoc>, LinkError> {
+    let links = mongo
+        .collection::<IdentityLinkDoc>(IDENTITY_LINK_COLLECTION)
+        .await?;
+    Ok(links
+        .find_many(doc! { "primary_identifier": primary_identifier })
+        .await?)
+}
+
+/// Link `other` into `primary`, proven by `proof`
+pub async fn link_account(
+    mongo: &MongoClient,
+    primary: &UserDoc,
+    other: &UserDoc,
+    proof: LinkProof,
+) -> Result<IdentityLinkDoc, LinkError> {
+    let links = mongo
+        .collection::<IdentityLinkDoc>(IDENTITY_LINK_COLLECTION)
+        .await?;
+    let has_links = links
+        .find_one(doc! { "primary_identifier": &other.identifier })
+        .await?
+        .is_some();
+    check_linkable(primary, other, has_links)?;
+
+    let passkeys = mongo.collection::<PasskeyDoc>(PASSKEY_COLLECTION).await?;
+    let passkey_ids = passkeys
+        .find_many(doc! { "identifier": &other.identifier })
+        .await?
+        .into_iter()
+        .map(|p| p.credential_id)
+        .collect();
+    let api_key_ids = match other._id {
+        Some(owner_id) => mongo
+            .collection::<ApiKeyDoc>(API_KEY_COLLECTION)
+            .await?
+            .find_many(doc! { "owner_id": owner_id })
+            .await?
+            .into_iter()
+            .filter_map(|k| k._id)
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let mut link = IdentityLinkDoc {
+        primary_user_id: primary._id,
+        primary_identifier: primary.identifier.clone(),
+        primary_human_id: primary.human_id.clone(),
+        primary_agent_pub_key: primary.agent_pub_key.clone(),
+        user_id: other._id,
+        identifier: other.identifier.clone(),
+        human_id: other.human_id.clone(),
+        agent_pub_key: other.agent_pub_key.clone(),
+        conductor_id: other.conductor_id.clone(),
+        proof,
+        passkey_ids,
+        api_key_ids,
+        ..Default::default()
+    };
+    // The unique index refuses a second active link of the same account
+    link.id = Some(
+        links
+            .insert_one(link.clone())
+            .await
+            .map_err(|_| LinkError::AlreadyLinked(other.identifier.clone()))?,
+    );
+
+    if let Err(e) = move_credentials(mongo, &link).await {
+        warn!(
+            "Linking {} into {} failed, rolling back: {}",
+            link.identifier, link.primary_identifier, e
+        );
+        if let Err(e) = restore(mongo, &link).await {
+            warn!("Rollback of link {} failed: {}", link.identifier, e);
+        }
+        return Err(e);
+    }
+
+    info!(
+        "Linked {} into {} ({:?}, {} passkeys, {} API keys)",
+        link.identifier,
+        link.primary_identifier,
+        link.proof,
+        link.passkey_ids.len(),
+        link.api_key_ids.len()
+    );
+    Ok(link)
+}
+
+/// Undo the link of `identifier` into `primary_identifier`
+pub async fn unlink_account(
+    mongo: &MongoClient,
+    primary_identifier: &str,
+    identifier: &str,
+) -> Result<IdentityLinkDoc, LinkError> {
+    let links = mongo
+        .collection::<IdentityLinkDoc>(IDENTITY_LINK_COLLECTION)
+        .await?;
+    let link = links
+        .find_one(doc! {
+            "primary_identifier": primary_identifier,
+            "identifier": identifier,
+        })
+        .await?
+        .ok_or_else(|| LinkError::NotLinked(identifier.to_string()))?;
+    restore(mongo, &link).await?;
+    info!("Unlinked {} from {}", identifier, primary_identifier);
+    Ok(link)
+}
+
+/// Hand the linked account's credentials to the primary account
+async fn move_credentials(mongo: &MongoClient, link: &IdentityLinkDoc) -> Result<(), LinkError> {
+    let now = DateTime::now();
+    if !link.passkey_ids.is_empty() {
+        let passkeys = mongo.collection::<PasskeyDoc>(PASSKEY_COLLECTION).await?;
+        passkeys
+            .inner()
+            .update_many(
+                doc! { "credential_id": { "$in": &link.passkey_ids } },
+                doc! { "$set": {
+                    "identifier": &link.primary_identifier,
+                    "human_id": &link.primary_human_id,
+                    "metadata.updated_at": now,
+                } },
+            )
+            .await
+            .map_err(|e| LinkError::Database(format!("Failed to move passkeys: {e}")))?;
+    }
+    if let (false, Some(primary_user_id)) = (link.api_key_ids.is_empty(), link.primary_user_id) {
+        let api_keys = mongo.collection::<ApiKeyDoc>(API_KEY_COLLECTION).await?;
+        api_keys
+            .inner()
+            .update_many(
+                doc! { "_id": { "$in": &link.api_key_ids } },
+                doc! { "$set": { "owner_id": primary_user_id, "metadata.updated_at": now } },
+            )
+            .await
+            .map_err(|e| LinkError::Database(format!("Failed to move API keys: {e}")))?;
+    }
+    let users = mongo.collection::<UserDoc>(USER_COLLECTION).await?;
+    users
+        .update_one(
+            doc! { "identifier": &link.identifier },
+            doc! {
+                "$set": { "linked_to": &link.primary_identifier, "metadata.updated_at": now },
+                "$inc": { "token_version": 1 },
+            },
+        )
+        .await?;
+    Ok(())
+}
+
+/// Give the linked account its credentials back and close the link
+async fn restore(mongo: &MongoClient, link: &IdentityLinkDoc) -> Result<(), LinkError> {
+    let now = DateTime::now();
+    let users = mongo.collection::<UserDoc>(USER_COLLECTION).await?;
+    users
+        .update_one(
+            doc! { "identifier": &link.identifier },
+            doc! {
+                "$unset": { "linked_to": "" },
+                "$set": { "metadata.updated_at": now },
+            },
+        )
+        .await?;
+    if !link.passkey_ids.is_empty() {
+        let passkeys = mongo.collection::<PasskeyDoc>(PASSKEY_COLLECTION).await?;
+        passkeys
+            .inner()
+            .update_many(
+                doc! {
+                    "credential_id": { "$in": &link.passkey_ids },
+                    "identifier": &link.primary_identifier,
+                },
+                doc! { "$set": {
+                    "identifier": &link.identifier,
+                    "human_id": &link.human_id,
+                    "metadata.updated_at": now,
+                } },
+            )
+            .await
+            .map_err(|e| LinkError::Database(format!("Failed to restore passkeys: {e}")))?;
+    }
+    if let (false, Some(user_id)) = (link.api_key_ids.is_empty(), link.user_id) {
+        let api_keys = mongo.collection::<ApiKeyDoc>(API_KEY_COLLECTION).await?;
+        api_keys
+            .inner()
+            .update_many(
+                doc! { "_id": { "$in": &link.api_key_ids } },
+                doc! { "$set": { "owner_id": user_id, "metadata.updated_at": now } },
+            )
+            .await
+            .map_err(|e| LinkError::Database(format!("Failed to restore API keys: {e}")))?;
+    }
+
+    let links = mongo
+        .collection::<IdentityLinkDoc>(IDENTITY_LINK_COLLECTION)
+        .await?;
+    links
+        .update_one(
+            doc! { "_id": link.id },
+            doc! { "$set": {
+                "unlinked_at": now,
+                "metadata.is_deleted": true,
+                "metadata.deleted_at": now,
+                "metadata.updated_at": now,
+            } },
+        )
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(identifier: &str, agent: &str) -> UserDoc {
+        UserDoc::new(
+            identifier.to_string(),
+            "email".to_string(),
+            String::new(),
+            format!("human-{identifier}"),
+            agent.to_string(),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_check_linkable() {
+        let primary = user("ana@example.org", "uhCAkAna");
+        let other = user("ana.b@example.org", "uhCAkAnaB");
+        assert_eq!(check_linkable(&primary, &other, false), Ok(()));
+
+        assert_eq!(
+            check_linkable(&primary, &primary, false),
+            Err(LinkError::SameAccount)
+        );
+        assert_eq!(
+            check_linkable(&primary, &user("ana.c@example.org", "uhCAkAna"), false),
+            Err(LinkError::SameAccount)
+        );
+        assert_eq!(
+            check_linkable(&primary, &other, true),
+            Err(LinkError::HasLinkedAccounts(
+                "ana.b@example.org".to_string()
+            ))
+        );
+
+        let mut linked = other.clone();
+        linked.linked_to = Some("someone@example.org".to_string());
+        assert_eq!(
+            check_linkable(&primary, &linked, false),
+            Err(LinkError::AlreadyLinked("ana.b@example.org".to_string()))
+        );
+        assert_eq!(
+            check_linkable(&linked, &primary, false),
+            Err(LinkError::PrimaryLinked("ana.b@example.org".to_string()))
+        );
+    }
+}
@@ -0,0 +1,355 @@
+//! Import Abuse Detection
+//!
+//! Keeps runaway or malicious import scripts off the conductor. Every
+//! caller queueing imports (an API key, a signed-in human, or else a client
+//! IP) gets rolling baselines of how many batches it queues per minute and
+//! how many items a batch holds. A batch is anomalous when the caller goes
+//! over `IMPORT_ABUSE_MAX_PER_MINUTE`, or, once a baseline has formed, when
+//! its rate or batch size is `IMPORT_ABUSE_DEVIATION` times the baseline.
+//!
+//! An anomalous caller is throttled for `IMPORT_ABUSE_THROTTLE_SECS`: its
+//! imports get `429 Too Many Requests` with `Retry-After`. The throttle is
+//! reported as a notification to `IMPORT_ABUSE_ALERT_RECIPIENTS` and to the
+//! caller itself when it's a signed-in human. Anomalous batches don't feed
+//! the baselines.
+
+use dashmap::DashMap;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use crate::config::Args;
+use crate::db::schemas::{NotificationDoc, NOTIFICATION_COLLECTION};
+use crate::db::MongoClient;
+
+/// Window the batch rate is counted over
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Weight of the newest sample in a baseline
+const BASELINE_WEIGHT: f64 = 0.1;
+
+/// Batches seen before baselines are trusted
+const MIN_SAMPLES: u64 = 10;
+
+/// Batch sizes below this are never anomalous, whatever the baseline
+const MIN_ANOMALOUS_ITEMS: u32 = 100;
+
+/// Callers idle this long are forgotten
+const IDLE_EXPIRY: Duration = Duration::from_secs(6 * 3600);
+
+/// Checks between sweeps of idle callers
+const SWEEP_EVERY: u64 = 1000;
+
+/// Detection settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportAbuseConfig {
+    /// Batches per minute no caller may exceed
+    pub max_per_minute: usize,
+    /// How far over its baseline a caller may go
+    pub deviation: f64,
+    /// How long an anomalous caller is refused
+    pub throttle: Duration,
+    /// Human ids or agent keys alerted on throttling
+    pub alert_recipients: Vec<String>,
+}
+
+impl ImportAbuseConfig {
+    /// Settings as configured, `None` when detection is off
+    pub fn from_args(args: &Args) -> Option<Self> {
+        (args.import_abuse_max_per_minute > 0).then(|| Self {
+            max_per_minute: args.import_abuse_max_per_minute,
+            deviation: args.import_abuse_deviation.max(1.0),
+            throttle: Duration::from_secs(args.import_abuse_throttle_secs),
+            alert_recipients: args
+                .import_abuse_alert_recipients
+                .iter()
+                .map(|r| r.trim().to_string())
+                .filter(|r| !r.is_empty())
+                .collect(),
+        })
+    }
+}
+
+/// Outcome of checking a batch
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportVerdict {
+    Allow,
+    Throttle {
+        retry_after: Duration,
+        reason: String,
+        /// The batch that started the throttle, as opposed to one refused
+        /// during it
+        new: bool,
+    },
+}
+
+/// One caller's history
+#[derive(Debug)]
+struct CallerStats {
+    recent: VecDeque<Instant>,
+    rate_baseline: f64,
+    size_baseline: f64,
+    samples: u64,
+    throttled_until: Option<Instant>,
+    last_seen: Instant,
+}
+
+impl CallerStats {
+    fn new(now: Instant) -> Self {
+        Self {
+            recent: VecDeque::new(),
+            rate_baseline: 0.0,
+            size_baseline: 0.0,
+            samples: 0,
+            throttled_until: None,
+            last_seen: now,
+        }
+    }
+
+    fn learn(&mut self, rate: f64, size: f64) {
+        if self.samples == 0 {
+            self.rate_baseline = rate;
+            self.size_baseline = size;
+        } else {
+            self.rate_baseline += BASELINE_WEIGHT * (rate - self.rate_baseline);
+            self.size_baseline += BASELINE_WEIGHT * (size - self.size_baseline);
+        }
+        self.samples += 1;
+    }
+}
+
+/// A caller's baselines as reported
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallerBaseline {
+    pub batches_per_minute: f64,
+    pub items_per_batch: f64,
+    pub samples: u64,
+    pub throttled: bool,
+}
+
+/// Per-caller baselines and throttles for the import endpoints
+pub struct ImportAbuseDetector {
+    config: ImportAbuseConfig,
+    callers: DashMap<String, CallerStats>,
+    checks: AtomicU64,
+    mongo: Option<MongoClient>,
+}
+
+impl ImportAbuseDetector {
+    pub fn new(config: ImportAbuseConfig, mongo: Option<MongoClient>) -> Self {
+        Self {
+            config,
+            callers: DashMap::new(),
+            checks: AtomicU64::new(0),
+            mongo,
+        }
+    }
+
+    pub fn config(&self) -> &ImportAbuseConfig {
+        &self.config
+    }
+
+    /// Record a batch of `items` queued by `caller` and decide whether it
+    /// may go through
+    pub fn check(&self, caller: &str, items: u32, now: Instant) -> ImportVerdict {
+        if self.checks.fetch_add(1, Ordering::Relaxed) % SWEEP_EVERY == SWEEP_EVERY - 1 {
+            self.callers
+                .retain(|_, stats| now.duration_since(stats.last_seen) < IDLE_EXPIRY);
+        }
+
+        let mut stats = self
+            .callers
+            .entry(caller.to_string())
+            .or_insert_with(|| CallerStats::new(now));
+        stats.last_seen = now;
+        if let Some(until) = stats.throttled_until {
+            if until > now {
+                return ImportVerdict::Throttle {
+                    retry_after: until - now,
+                    reason: "earlier batches were anomalous".to_string(),
+                    new: false,
+                };
+            }
+            stats.throttled_until = None;
+        }
+
+        while stats
+            .recent
+            .front()
+            .is_some_and(|at| now.duration_since(*at) >= RATE_WINDOW)
+        {
+            stats.recent.pop_front();
+        }
+        stats.recent.push_back(now);
+        let rate = stats.recent.len();
+
+        match self.anomaly(&stats, rate, items) {
+            Some(reason) => {
+                stats.throttled_until = Some(now + self.config.throttle);
+                ImportVerdict::Throttle {
+                    retry_after: self.config.throttle,
+                    reason,
+                    new: true,
+                }
+            }
+            None => {
+                stats.learn(rate as f64, items as f64);
+                ImportVerdict::Allow
+            }
+        }
+    }
+
+    fn anomaly(&self, stats: &CallerStats, rate: usize, items: u32) -> Option<String> {
+        let deviation = self.config.deviation;
+        if rate > self.config.max_per_minute {
+            return Some(format!(
+                "{rate} batches in the last minute (limit {})",
+                self.config.max_per_minute
+            ));
+        }
+        if stats.samples < MIN_SAMPLES {
+            return None;
+        }
+        if rate as f64 > deviation * stats.rate_baseline.max(1.0) {
+            return Some(format!(
+                "{rate} batches in the last minute against a baseline of {:.1}",
+                stats.rate_baseline
+            ));
+        }
+        if items >= MIN_ANOMALOUS_ITEMS && items as f64 > deviation * stats.size_baseline.max(1.0) {
+            return Some(format!(
+                "{items} items in one batch against a baseline of {:.0}",
+                stats.size_baseline
+            ));
+        }
+        None
+    }
+
+    /// Baselines of a caller, if it has queued anything recently
+    pub fn baseline(&self, caller: &str) -> Option<CallerBaseline> {
+        let now = Instant::now();
+        self.callers.get(caller).map(|stats| CallerBaseline {
+            batches_per_minute: stats.rate_baseline,
+            items_per_batch: stats.size_baseline,
+            samples: stats.samples,
+            throttled: stats.throttled_until.is_some_and(|until| until > now),
+        })
+    }
+
+    /// Notify the configured recipients, and the caller when it is a human,
+    /// that `caller` was throttled
+    pub async fn alert(&self, caller: &str, human: Option<&str>, reason: &str) {
+        let Some(ref mongo) = self.mongo else {
+            return;
+        };
+        let collection = match mongo
+            .collection::<NotificationDoc>(NOTIFICATION_COLLECTION)
+            .await
+        {
+            Ok(collection) => collection,
+            Err(e) => {
+                warn!(error = %e, "Failed to open notifications for import alert");
+                return;
+            }
+        };
+
+        let minutes = self.config.throttle.as_secs().div_ceil(60);
+        let recipients = self
+            .config
+            .alert_recipients
+            .iter()
+            .map(|recipient| {
+                (
+                    recipient.as_str(),
+                    format!("Imports from {caller} throttled for {minutes} min: {reason}"),
+                )
+            })
+            .chain(human.map(|human| {
+                (
+                    human,
+                    format!(
+                        "Your imports are paused for {minutes} min: {reason}. \
+                         Slow your import script down before retrying."
+                    ),
+                )
+            }));
+        for (recipient, message) in recipients {
+            let notification = NotificationDoc {
+                recipient: recipient.to_string(),
+                kind: "import_abuse".to_string(),
+                title: "Import throttled".to_string(),
+                message,
+                ..Default::default()
+            };
+            if let Err(e) = collection.insert_one(notification).await {
+                warn!(recipient, error = %e, "Failed to leave import alert");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detector(max_per_minute: usize) -> ImportAbuseDetector {
+        ImportAbuseDetector::new(
+            ImportAbuseConfig {
+                max_per_minute,
+                deviation: 5.0,
+                throttle: Duration::from_secs(300),
+                alert_recipients: Vec::new(),
+            },
+            None,
+        )
+    }
+
+    #[test]
+    fn test_burst_over_limit_is_throttled() {
+        let detector = detector(50);
+        let start = Instant::now();
+        for i in 0..50 {
+            let at = start + Duration::from_millis(i * 100);
+            assert_eq!(detector.check("key:a", 10, at), ImportVerdict::Allow);
+        }
+        let at = start + Duration::from_secs(5);
+        let ImportVerdict::Throttle { new, .. } = detector.check("key:a", 10, at) else {
+            panic!("51st batch in a minute allowed");
+        };
+        assert!(new);
+
+        // Refused until the throttle lapses, other callers unaffected
+        let later = at + Duration::from_secs(60);
+        assert!(matches!(
+            detector.check("key:a", 10, later),
+            ImportVerdict::Throttle { new: false, .. }
+        ));
+        assert_eq!(detector.check("key:b", 10, later), ImportVerdict::Allow);
+        let lapsed = at + Duration::from_secs(301);
+        assert_eq!(detector.check("key:a", 10, lapsed), ImportVerdict::Allow);
+    }
+
+    #[test]
+    fn test_deviation_from_baseline() {
+        let detector = detector(1000);
+        let start = Instant::now();
+        // One 200-item batch every 10 minutes
+        for i in 0..MIN_SAMPLES {
+            let at = start + Duration::from_secs(i * 600);
+            assert_eq!(detector.check("human:h", 200, at), ImportVerdict::Allow);
+        }
+        let baseline = detector.baseline("human:h").unwrap();
+        assert_eq!(baseline.items_per_batch, 200.0);
+        assert_eq!(baseline.batches_per_minute, 1.0);
+
+        let next = start + Duration::from_secs(MIN_SAMPLES * 600);
+        assert!(matches!(
+            detector.check("human:h", 5_000, next),
+            ImportVerdict::Throttle { new: true, .. }
+        ));
+        assert!(detector.baseline("human:h").unwrap().throttled);
+        // The anomalous batch didn't move the baseline
+        assert_eq!(detector.baseline("human:h").unwrap().items_per_batch, 200.0);
+    }
+}
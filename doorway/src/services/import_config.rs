@@ -161,6 +161,11 @@ impl ImportConfigStore {
             .and_then(|c| c.get_batch_type(batch_type).cloned())
     }
 
+    /// Every DNA's config, including those discovered without one
+    pub fn all_configs(&self) -> Vec<DnaImportConfig> {
+        self.configs.iter().map(|c| c.clone()).collect()
+    }
+
     /// Get all DNAs with import enabled
     pub fn get_import_enabled_dnas(&self) -> Vec<String> {
         self.configs
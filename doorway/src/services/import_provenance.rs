@@ -0,0 +1,237 @@
+//! Import Provenance
+//!
+//! Lets an upstream system (a seeder, a partner's content pipeline) sign the
+//! manifest of an import it submits. The doorway checks the signature against
+//! the keys it trusts before queuing the batch, and the verified signature is
+//! passed through elohim-storage to `queue_import`, which stores it as an
+//! `ImportProvenance` entry next to the `ImportBatch`. Audits can later
+//! re-check the signature to prove which system introduced which content.
+//!
+//! Signing is optional: unsigned imports are queued as before. A signed
+//! import from an unknown source or with a bad signature is refused.
+//!
+//! ## Signing
+//!
+//! The upstream system signs, with Ed25519, the UTF-8 lines
+//!
+//! ```text
+//! elohim-import-manifest:v1
+//! {source}
+//! {blob_hash}
+//! {total_items}
+//! ```
+//!
+//! joined with `\n`.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::config::Args;
+
+/// Version prefix of the signed payload
+const MANIFEST_DOMAIN: &str = "elohim-import-manifest:v1";
+
+/// Signature sent with an import request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestSignature {
+    /// Upstream system, matching a configured signer
+    pub source: String,
+    /// Base64 Ed25519 signature over the manifest
+    pub signature: String,
+}
+
+/// A verified manifest signature, as recorded by the zome
+/// Must match ImportProvenanceInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportProvenance {
+    pub source: String,
+    /// Base64 key the signature was checked against
+    pub public_key: String,
+    pub signature: String,
+    pub blob_hash: String,
+    pub total_items: u32,
+    /// Doorway that checked the signature
+    pub verified_by: Option<String>,
+}
+
+/// Why a manifest signature was refused
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ProvenanceError {
+    #[error("No trusted import signer '{0}'")]
+    UnknownSource(String),
+
+    #[error("Invalid manifest signature")]
+    InvalidSignature,
+
+    #[error("Invalid import signer config: {0}")]
+    Config(String),
+}
+
+/// Bytes an upstream system signs
+pub fn signing_payload(source: &str, blob_hash: &str, total_items: u32) -> Vec<u8> {
+    [
+        MANIFEST_DOMAIN.to_string(),
+        source.to_string(),
+        blob_hash.to_string(),
+        total_items.to_string(),
+    ]
+    .join("\n")
+    .into_bytes()
+}
+
+/// Verifies import manifests against the configured upstream systems
+#[derive(Debug, Clone)]
+pub struct ImportSigners {
+    signers: HashMap<String, VerifyingKey>,
+    doorway_id: Option<String>,
+}
+
+impl ImportSigners {
+    /// Signers as configured, `None` when no signer is set up
+    pub fn from_args(args: &Args) -> Result<Option<Self>, ProvenanceError> {
+        if args.import_signers.is_empty() {
+            return Ok(None);
+        }
+        let mut signers = HashMap::new();
+        for entry in &args.import_signers {
+            let (source, key) = parse_signer(entry)?;
+            signers.insert(source, key);
+        }
+        Ok(Some(Self {
+            signers,
+            doorway_id: args.doorway_id.clone(),
+        }))
+    }
+
+    /// Number of trusted upstream systems
+    pub fn len(&self) -> usize {
+        self.signers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.signers.is_empty()
+    }
+
+    /// Check a manifest signature, returning the provenance to record
+    pub fn verify(
+        &self,
+        signed: &ManifestSignature,
+        blob_hash: &str,
+        total_items: u32,
+    ) -> Result<ImportProvenance, ProvenanceError> {
+        let key = self
+            .signers
+            .get(&signed.source)
+            .ok_or_else(|| ProvenanceError::UnknownSource(signed.source.clone()))?;
+        let signature = BASE64
+            .decode(&signed.signature)
+            .ok()
+            .and_then(|bytes| Signature::from_slice(&bytes).ok())
+            .ok_or(ProvenanceError::InvalidSignature)?;
+        key.verify_strict(
+            &signing_payload(&signed.source, blob_hash, total_items),
+            &signature,
+        )
+        .map_err(|_| ProvenanceError::InvalidSignature)?;
+
+        Ok(ImportProvenance {
+            source: signed.source.clone(),
+            public_key: BASE64.encode(key.to_bytes()),
+            signature: signed.signature.clone(),
+            blob_hash: blob_hash.to_string(),
+            total_items,
+            verified_by: self.doorway_id.clone(),
+        })
+    }
+}
+
+/// Parse a `source=base64key` signer entry
+fn parse_signer(entry: &str) -> Result<(String, VerifyingKey), ProvenanceError> {
+    let (source, key) = entry
+        .split_once('=')
+        .ok_or_else(|| ProvenanceError::Config(format!("expected source=key, got '{entry}'")))?;
+    let bytes: [u8; 32] = BASE64
+        .decode(key.trim())
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| ProvenanceError::Config(format!("invalid key for '{source}'")))?;
+    let key = VerifyingKey::from_bytes(&bytes)
+        .map_err(|_| ProvenanceError::Config(format!("invalid key for '{source}'")))?;
+    Ok((source.trim().to_string(), key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    fn signing_key() -> SigningKey {
+        SigningKey::from_bytes(&[3u8; 32])
+    }
+
+    fn signers() -> ImportSigners {
+        let key = BASE64.encode(signing_key().verifying_key().to_bytes());
+        let args = Args::parse_from([
+            "doorway",
+            "--import-signers",
+            &format!("seeder={key}"),
+            "--doorway-id",
+            "doorway-a",
+        ]);
+        ImportSigners::from_args(&args).unwrap().unwrap()
+    }
+
+    fn signed(source: &str, blob_hash: &str, total_items: u32) -> ManifestSignature {
+        let payload = signing_payload(source, blob_hash, total_items);
+        ManifestSignature {
+            source: source.to_string(),
+            signature: BASE64.encode(signing_key().sign(&payload).to_bytes()),
+        }
+    }
+
+    #[test]
+    fn test_valid_signature_becomes_provenance() {
+        let provenance = signers()
+            .verify(&signed("seeder", "sha256-abc", 12), "sha256-abc", 12)
+            .unwrap();
+        assert_eq!(provenance.source, "seeder");
+        assert_eq!(provenance.blob_hash, "sha256-abc");
+        assert_eq!(provenance.total_items, 12);
+        assert_eq!(provenance.verified_by.as_deref(), Some("doorway-a"));
+        assert_eq!(
+            provenance.public_key,
+            BASE64.encode(signing_key().verifying_key().to_bytes())
+        );
+    }
+
+    #[test]
+    fn test_tampered_manifest_rejected() {
+        let signers = signers();
+        let signature = signed("seeder", "sha256-abc", 12);
+        assert_eq!(
+            signers.verify(&signature, "sha256-abc", 13),
+            Err(ProvenanceError::InvalidSignature)
+        );
+        assert_eq!(
+            signers.verify(&signature, "sha256-other", 12),
+            Err(ProvenanceError::InvalidSignature)
+        );
+
+        let unknown = signed("other-system", "sha256-abc", 12);
+        assert_eq!(
+            signers.verify(&unknown, "sha256-abc", 12),
+            Err(ProvenanceError::UnknownSource("other-system".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_config() {
+        let args = Args::parse_from(["doorway"]);
+        assert!(ImportSigners::from_args(&args).unwrap().is_none());
+        let args = Args::parse_from(["doorway", "--import-signers", "seeder"]);
+        assert!(ImportSigners::from_args(&args).is_err());
+    }
+}
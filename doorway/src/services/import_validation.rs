@@ -0,0 +1,397 @@
+//! Custom validation rules for imports
+//!
+//! Operators can hold import batches to their own standards ("all course
+//! content must include learning_objectives", "videos need a thumbnail")
+//! without changing the DNA. Rules implement [`ImportRule`] and come from
+//! two places:
+//!
+//! - **Rule file** (`IMPORT_RULES`): a JSON array of declarative field rules,
+//!   e.g. `{"name": "video-thumbnail", "when": {"content_type": "video"},
+//!   "require": ["thumbnail_url"]}`. `batch_types` limits a rule to some
+//!   batch types; `message` overrides the default error.
+//! - **Plugins** (`IMPORT_RULE_PLUGINS`): dynamic libraries built in Rust (or
+//!   anything with a C ABI) exporting
+//!
+//!   ```text
+//!   elohim_rule_name() -> *const c_char
+//!   elohim_rule_check(item_json: *const c_char) -> *mut c_char
+//!   elohim_rule_free(ptr: *mut c_char)
+//!   ```
+//!
+//!   `elohim_rule_check` returns null when the item passes, otherwise a JSON
+//!   array of `{"field": .., "message": ..}` violations, which the doorway
+//!   hands back to `elohim_rule_free`.
+//!
+//! Items breaking a rule are left out of the queued batch. The structured
+//! errors are returned with the queue response and added to the batch's
+//! import status under `validation`.
+
+use libloading::Library;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, VecDeque};
+use std::ffi::{c_char, CStr, CString};
+use std::sync::RwLock;
+
+use crate::config::Args;
+
+/// Batch reports kept in memory; oldest are dropped first
+const MAX_REPORTS: usize = 200;
+
+/// A custom check applied to each item of an import batch
+pub trait ImportRule: Send + Sync {
+    /// Rule name shown in errors
+    fn name(&self) -> &str;
+
+    /// Whether the rule checks items of this batch type
+    fn applies_to(&self, _batch_type: &str) -> bool {
+        true
+    }
+
+    /// Violations of the rule by one item (empty if it passes)
+    fn check(&self, item: &Value) -> Vec<RuleViolation>;
+}
+
+/// One way an item breaks a rule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleViolation {
+    /// Offending field, if the rule is about one
+    #[serde(default)]
+    pub field: Option<String>,
+    pub message: String,
+}
+
+/// A rule violation by an item of a batch
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ItemError {
+    /// Position of the item in the submitted batch
+    pub index: usize,
+    pub item_id: Option<String>,
+    pub rule: String,
+    pub field: Option<String>,
+    pub message: String,
+}
+
+/// Result of validating one batch
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ValidationReport {
+    /// Items checked
+    pub checked: usize,
+    /// Items left out of the batch
+    pub rejected: usize,
+    pub errors: Vec<ItemError>,
+}
+
+impl ValidationReport {
+    /// Indexes of the items that broke a rule
+    pub fn rejected_indexes(&self) -> Vec<usize> {
+        let mut indexes: Vec<usize> = self.errors.iter().map(|e| e.index).collect();
+        indexes.dedup();
+        indexes
+    }
+}
+
+/// Declarative rule: items matching `when` must have every `require` field
+#[derive(Debug, Clone, Deserialize)]
+pub struct FieldRule {
+    pub name: String,
+    /// Batch types checked (all if empty)
+    #[serde(default)]
+    pub batch_types: Vec<String>,
+    /// Field values an item must have for the rule to apply
+    #[serde(default)]
+    pub when: Map<String, Value>,
+    /// Fields that must be present and non-empty (dotted paths allowed)
+    pub require: Vec<String>,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+impl ImportRule for FieldRule {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn applies_to(&self, batch_type: &str) -> bool {
+        self.batch_types.is_empty() || self.batch_types.iter().any(|t| t == batch_type)
+    }
+
+    fn check(&self, item: &Value) -> Vec<RuleViolation> {
+        let matches = self
+            .when
+            .iter()
+            .all(|(field, expected)| field_value(item, field) == Some(expected));
+        if !matches {
+            return Vec::new();
+        }
+        self.require
+            .iter()
+            .filter(|field| field_value(item, field).map_or(true, is_empty))
+            .map(|field| RuleViolation {
+                field: Some(field.clone()),
+                message: self
+                    .message
+                    .clone()
+                    .unwrap_or_else(|| format!("'{field}' is required")),
+            })
+            .collect()
+    }
+}
+
+/// Value at a dotted field path
+fn field_value<'a>(item: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(item, |value, key| value.get(key))
+}
+
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(s) => s.trim().is_empty(),
+        Value::Array(items) => items.is_empty(),
+        Value::Object(fields) => fields.is_empty(),
+        _ => false,
+    }
+}
+
+/// Parse a JSON array of field rules
+pub fn parse_rules(json: &str) -> Result<Vec<FieldRule>, String> {
+    let rules: Vec<FieldRule> =
+        serde_json::from_str(json).map_err(|e| format!("Invalid import rules: {e}"))?;
+    if let Some(rule) = rules.iter().find(|r| r.require.is_empty()) {
+        return Err(format!("Import rule '{}' requires no fields", rule.name));
+    }
+    Ok(rules)
+}
+
+type NameFn = unsafe extern "C" fn() -> *const c_char;
+type CheckFn = unsafe extern "C" fn(*const c_char) -> *mut c_char;
+type FreeFn = unsafe extern "C" fn(*mut c_char);
+
+/// A rule loaded from a dynamic library
+pub struct PluginRule {
+    name: String,
+    check: CheckFn,
+    free: FreeFn,
+    /// Keeps the function pointers above valid
+    _library: Library,
+}
+
+impl PluginRule {
+    /// Load a rule plugin
+    pub fn load(path: &str) -> Result<Self, String> {
+        let error = |e: libloading::Error| format!("Failed to load rule plugin {path}: {e}");
+        // SAFETY: plugins are libraries the operator installed for this
+        // purpose; the symbols are declared with the ABI documented above.
+        unsafe {
+            let library = Library::new(path).map_err(error)?;
+            let name: NameFn = *library
+                .get::<NameFn>(b"elohim_rule_name\0")
+                .map_err(error)?;
+            let check: CheckFn = *library
+                .get::<CheckFn>(b"elohim_rule_check\0")
+                .map_err(error)?;
+            let free: FreeFn = *library
+                .get::<FreeFn>(b"elohim_rule_free\0")
+                .map_err(error)?;
+            let name_ptr = name();
+            if name_ptr.is_null() {
+                return Err(format!("Rule plugin {path} has no name"));
+            }
+            Ok(Self {
+                name: CStr::from_ptr(name_ptr).to_string_lossy().into_owned(),
+                check,
+                free,
+                _library: library,
+            })
+        }
+    }
+}
+
+impl ImportRule for PluginRule {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn check(&self, item: &Value) -> Vec<RuleViolation> {
+        let Ok(input) = CString::new(item.to_string()) else {
+            return vec![RuleViolation {
+                field: None,
+                message: "Item contains a NUL byte".to_string(),
+            }];
+        };
+        // SAFETY: the plugin returns null or a NUL-terminated string it
+        // allocated, which is handed back to it once copied.
+        let output = unsafe {
+            let ptr = (self.check)(input.as_ptr());
+            if ptr.is_null() {
+                return Vec::new();
+            }
+            let output = CStr::from_ptr(ptr).to_string_lossy().into_owned();
+            (self.free)(ptr);
+            output
+        };
+        serde_json::from_str(&output).unwrap_or_else(|e| {
+            vec![RuleViolation {
+                field: None,
+                message: format!("Rule plugin returned invalid JSON: {e}"),
+            }]
+        })
+    }
+}
+
+/// Runs the configured rules over import batches and keeps their reports
+pub struct ImportValidator {
+    rules: Vec<Box<dyn ImportRule>>,
+    reports: RwLock<(HashMap<String, ValidationReport>, VecDeque<String>)>,
+}
+
+impl ImportValidator {
+    pub fn new(rules: Vec<Box<dyn ImportRule>>) -> Self {
+        Self {
+            rules,
+            reports: RwLock::new((HashMap::new(), VecDeque::new())),
+        }
+    }
+
+    /// Validator with the rule file and plugins as configured, `None` when
+    /// neither is set up
+    pub fn from_args(args: &Args) -> Result<Option<Self>, String> {
+        let mut rules: Vec<Box<dyn ImportRule>> = Vec::new();
+        if let Some(ref path) = args.import_rules {
+            let json = std::fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read import rules {path}: {e}"))?;
+            for rule in parse_rules(&json)? {
+                rules.push(Box::new(rule));
+            }
+        }
+        for path in args.import_rule_plugins.iter().filter(|p| !p.is_empty()) {
+            rules.push(Box::new(PluginRule::load(path)?));
+        }
+        Ok((!rules.is_empty()).then(|| Self::new(rules)))
+    }
+
+    /// Names of the loaded rules
+    pub fn rule_names(&self) -> Vec<&str> {
+        self.rules.iter().map(|r| r.name()).collect()
+    }
+
+    /// Whether any rule checks this batch type
+    pub fn applies_to(&self, batch_type: &str) -> bool {
+        self.rules.iter().any(|r| r.applies_to(batch_type))
+    }
+
+    /// Check every item of a batch
+    pub fn validate(&self, batch_type: &str, items: &[Value]) -> ValidationReport {
+        let rules: Vec<&dyn ImportRule> = self
+            .rules
+            .iter()
+            .filter(|r| r.applies_to(batch_type))
+            .map(|r| r.as_ref())
+            .collect();
+
+        let mut report = ValidationReport {
+            checked: items.len(),
+            ..Default::default()
+        };
+        for (index, item) in items.iter().enumerate() {
+            let item_id = item.get("id").and_then(|v| v.as_str()).map(str::to_string);
+            let before = report.errors.len();
+            for rule in &rules {
+                report
+                    .errors
+                    .extend(rule.check(item).into_iter().map(|v| ItemError {
+                        index,
+                        item_id: item_id.clone(),
+                        rule: rule.name().to_string(),
+                        field: v.field,
+                        message: v.message,
+                    }));
+            }
+            if report.errors.len() > before {
+                report.rejected += 1;
+            }
+        }
+        report
+    }
+
+    /// Report for a batch, if it was validated by this doorway
+    pub fn report(&self, batch_id: &str) -> Option<ValidationReport> {
+        self.reports.read().ok()?.0.get(batch_id).cloned()
+    }
+
+    /// Keep a batch's report for its import status
+    pub fn set_report(&self, batch_id: &str, report: ValidationReport) {
+        let Ok(mut reports) = self.reports.write() else {
+            return;
+        };
+        let (by_batch, order) = &mut *reports;
+        if by_batch.insert(batch_id.to_string(), report).is_none() {
+            order.push_back(batch_id.to_string());
+        }
+        while order.len() > MAX_REPORTS {
+            if let Some(oldest) = order.pop_front() {
+                by_batch.remove(&oldest);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn validator() -> ImportValidator {
+        let rules = parse_rules(
+            r#"[
+                {"name": "course-objectives", "batch_types": ["content"],
+                 "when": {"content_type": "course"}, "require": ["learning_objectives"]},
+                {"name": "video-thumbnail", "when": {"content_type": "video"},
+                 "require": ["metadata.thumbnail_url"], "message": "Videos need a thumbnail"}
+            ]"#,
+        )
+        .unwrap();
+        ImportValidator::new(
+            rules
+                .into_iter()
+                .map(|r| Box::new(r) as Box<dyn ImportRule>)
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_field_rules() {
+        let items = vec![
+            json!({"id": "c1", "content_type": "course", "learning_objectives": ["a"]}),
+            json!({"id": "c2", "content_type": "course", "learning_objectives": []}),
+            json!({"id": "v1", "content_type": "video", "metadata": {"thumbnail_url": "t.png"}}),
+            json!({"id": "v2", "content_type": "video"}),
+            json!({"id": "a1", "content_type": "article"}),
+        ];
+        let report = validator().validate("content", &items);
+        assert_eq!(report.checked, 5);
+        assert_eq!(report.rejected, 2);
+        assert_eq!(report.rejected_indexes(), vec![1, 3]);
+        assert_eq!(report.errors[0].rule, "course-objectives");
+        assert_eq!(
+            report.errors[0].field.as_deref(),
+            Some("learning_objectives")
+        );
+        assert_eq!(report.errors[1].item_id.as_deref(), Some("v2"));
+        assert_eq!(report.errors[1].message, "Videos need a thumbnail");
+    }
+
+    #[test]
+    fn test_rules_limited_to_batch_types() {
+        let validator = validator();
+        let items = vec![json!({"id": "c2", "content_type": "course"})];
+        assert_eq!(validator.validate("paths", &items).rejected, 0);
+        assert_eq!(validator.validate("content", &items).rejected, 1);
+    }
+
+    #[test]
+    fn test_parse_rules_rejects_empty_requirements() {
+        assert!(parse_rules(r#"[{"name": "noop", "require": []}]"#).is_err());
+        assert!(parse_rules("{}").is_err());
+    }
+}
@@ -0,0 +1,254 @@
+//! Signed media URLs
+//!
+//! Keeps gated and non-commons media from being hotlinked. A blob is
+//! protected when any content referencing it has a reach other than
+//! `commons`/`public`, or a license the doorway may not redistribute (see
+//! [`content_license`](super::content_license)). `/store/{hash}` and
+//! `/api/blob/{hash}` only serve protected blobs to URLs carrying a valid,
+//! unexpired signature; a learner asks for one through
+//! `GET /api/v1/media/{hash}/url`.
+//!
+//! ## Signing
+//!
+//! A signed URL adds `?exp={unix_seconds}&agent={agent}&sig={hex}` where
+//! `sig` is HMAC-SHA256, keyed with `MEDIA_URL_SECRET`, over the lines
+//!
+//! ```text
+//! elohim-media-url:v1
+//! {sha256-hash}
+//! {exp}
+//! {agent}
+//! ```
+//!
+//! The agent binding doesn't stop the URL being shared before it expires,
+//! but every download through it is attributable to the learner it was
+//! issued to.
+
+use dashmap::DashMap;
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+use super::content_license::{license_status, LicenseStatus};
+use crate::config::Args;
+use crate::projection::ProjectedDocument;
+use crate::routes::content_body::is_public;
+
+/// Version prefix of the signed payload
+const URL_DOMAIN: &str = "elohim-media-url:v1";
+
+/// Shortest secret accepted
+const MIN_SECRET_LEN: usize = 32;
+
+/// How long a blob's protection is remembered before the projection is
+/// consulted again
+const PROTECTION_TTL: Duration = Duration::from_secs(60);
+
+/// Signed URL errors
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum MediaUrlError {
+    #[error("Signed URL required")]
+    Missing,
+
+    #[error("Signed URL expired")]
+    Expired,
+
+    #[error("Invalid URL signature")]
+    InvalidSignature,
+
+    #[error("Invalid media URL config: {0}")]
+    Config(String),
+}
+
+/// Signature parameters of a media URL
+#[derive(Debug, Default, Deserialize)]
+struct SignatureParams {
+    exp: Option<i64>,
+    agent: Option<String>,
+    sig: Option<String>,
+}
+
+/// A signed URL as handed to a learner
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedMediaUrl {
+    /// Query string to append to the blob path
+    pub query: String,
+    pub expires_at: i64,
+}
+
+/// A signature that checked out
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifiedMediaUrl {
+    /// Agent the URL was issued to
+    pub agent: String,
+    pub expires_at: i64,
+}
+
+/// Signs and checks media URLs, and remembers which blobs need them
+#[derive(Debug)]
+pub struct MediaUrlSigner {
+    key: Vec<u8>,
+    ttl: Duration,
+    protection: DashMap<String, (bool, Instant)>,
+}
+
+impl MediaUrlSigner {
+    /// Signer as configured, `None` when `MEDIA_URL_SECRET` is unset
+    pub fn from_args(args: &Args) -> Result<Option<Self>, MediaUrlError> {
+        let Some(secret) = args.media_url_secret.as_deref() else {
+            return Ok(None);
+        };
+        Self::new(secret, Duration::from_secs(args.media_url_ttl_secs)).map(Some)
+    }
+
+    pub fn new(secret: &str, ttl: Duration) -> Result<Self, MediaUrlError> {
+        if secret.len() < MIN_SECRET_LEN {
+            return Err(MediaUrlError::Config(format!(
+                "MEDIA_URL_SECRET must be at least {MIN_SECRET_LEN} characters"
+            )));
+        }
+        if ttl.is_zero() {
+            return Err(MediaUrlError::Config(
+                "MEDIA_URL_TTL_SECS must be positive".to_string(),
+            ));
+        }
+        Ok(Self {
+            key: secret.as_bytes().to_vec(),
+            ttl,
+            protection: DashMap::new(),
+        })
+    }
+
+    /// Lifetime of issued URLs
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    fn mac(&self, hash: &str, exp: i64, agent: &str) -> Hmac<Sha256> {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC accepts any key length");
+        mac.update(format!("{URL_DOMAIN}\n{hash}\n{exp}\n{agent}").as_bytes());
+        mac
+    }
+
+    /// Sign a normalized blob hash for an agent, valid for the configured TTL
+    pub fn sign(&self, hash: &str, agent: &str, now: i64) -> SignedMediaUrl {
+        let expires_at = now + self.ttl.as_secs() as i64;
+        let sig = hex::encode(self.mac(hash, expires_at, agent).finalize().into_bytes());
+        let query = serde_urlencoded::to_string([
+            ("exp", expires_at.to_string()),
+            ("agent", agent.to_string()),
+            ("sig", sig),
+        ])
+        .unwrap_or_default();
+        SignedMediaUrl { query, expires_at }
+    }
+
+    /// Check the signature in a request's query
+    pub fn verify(
+        &self,
+        hash: &str,
+        query: Option<&str>,
+        now: i64,
+    ) -> Result<VerifiedMediaUrl, MediaUrlError> {
+        let params: SignatureParams =
+            serde_urlencoded::from_str(query.unwrap_or("")).unwrap_or_default();
+        let (Some(exp), Some(agent), Some(sig)) = (params.exp, params.agent, params.sig) else {
+            return Err(MediaUrlError::Missing);
+        };
+        let sig = hex::decode(sig).map_err(|_| MediaUrlError::InvalidSignature)?;
+        self.mac(hash, exp, &agent)
+            .verify_slice(&sig)
+            .map_err(|_| MediaUrlError::InvalidSignature)?;
+        if exp < now {
+            return Err(MediaUrlError::Expired);
+        }
+        Ok(VerifiedMediaUrl {
+            agent,
+            expires_at: exp,
+        })
+    }
+
+    /// Remembered protection of a blob, if still fresh
+    pub fn cached_protection(&self, hash: &str) -> Option<bool> {
+        let entry = self.protection.get(hash)?;
+        let (protected, at) = *entry;
+        (at.elapsed() < PROTECTION_TTL).then_some(protected)
+    }
+
+    pub fn remember_protection(&self, hash: &str, protected: bool) {
+        self.protection
+            .insert(hash.to_string(), (protected, Instant::now()));
+    }
+}
+
+/// Whether a projected document keeps its media from being served openly;
+/// a document without a reach counts as private
+pub fn is_restricted(doc: &ProjectedDocument) -> bool {
+    !is_public(doc) || license_status(&doc.data) == LicenseStatus::Restricted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const HASH: &str = "sha256-a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a";
+
+    fn signer() -> MediaUrlSigner {
+        MediaUrlSigner::new(&"s".repeat(32), Duration::from_secs(600)).unwrap()
+    }
+
+    #[test]
+    fn test_signed_url_round_trip() {
+        let signer = signer();
+        let url = signer.sign(HASH, "uhCAk-learner", 1_000);
+        assert_eq!(url.expires_at, 1_600);
+        assert_eq!(
+            signer.verify(HASH, Some(&url.query), 1_500),
+            Ok(VerifiedMediaUrl {
+                agent: "uhCAk-learner".to_string(),
+                expires_at: 1_600
+            })
+        );
+
+        assert_eq!(
+            signer.verify(HASH, Some(&url.query), 1_601),
+            Err(MediaUrlError::Expired)
+        );
+        let other = HASH.replace("a7", "b7");
+        assert_eq!(
+            signer.verify(&other, Some(&url.query), 1_500),
+            Err(MediaUrlError::InvalidSignature)
+        );
+        let extended = url.query.replace("exp=1600", "exp=9999");
+        assert_eq!(
+            signer.verify(HASH, Some(&extended), 1_500),
+            Err(MediaUrlError::InvalidSignature)
+        );
+        assert_eq!(
+            signer.verify(HASH, None, 1_500),
+            Err(MediaUrlError::Missing)
+        );
+        assert!(MediaUrlSigner::new("short", Duration::from_secs(600)).is_err());
+    }
+
+    #[test]
+    fn test_restricted_documents() {
+        let doc = |reach: Option<&str>, data| ProjectedDocument {
+            reach: reach.map(String::from),
+            data,
+            ..Default::default()
+        };
+        assert!(!is_restricted(&doc(Some("commons"), json!({}))));
+        assert!(!is_restricted(&doc(None, json!({ "reach": "public" }))));
+        assert!(is_restricted(&doc(Some("community"), json!({}))));
+        assert!(is_restricted(&doc(None, json!({}))));
+        assert!(is_restricted(&doc(
+            Some("commons"),
+            json!({ "metadata": { "license": "CC-BY-NC-4.0" } })
+        )));
+    }
+}
@@ -11,24 +11,64 @@
 //! - **ShardResolver**: Native Holochain blob resolution via elohim-storage
 //! - **ImportOrchestrator**: Batch import processing (elohim-store → zome)
 //! - **ImportConfig**: Zome-declared import capability discovery
+//! - **ImportProvenance**: Upstream signatures over import manifests, kept for audits
+//! - **ImportValidation**: Operator rule files and plugins checking import items
+//! - **ImportAbuse**: Per-caller import baselines, throttling and alerts on runaway imports
+//! - **DuplicateDetection**: MinHash near-duplicate check for content imports
+//! - **Moderation**: Word list / moderation API screening, user reports and revocations in a steward queue
 //! - **Discovery**: Runtime discovery of zome capabilities from conductor
 //! - **RouteRegistry**: Dynamic route management from DNAs and external agents
 //! - **DIDResolver**: W3C DID resolution for doorway federation
 //! - **ElohimVerifier**: AI-assisted identity verification for disaster recovery
+//! - **SiteExport**: Static JSON/HTML bundle of public content (`doorway export-site`)
+//! - **Tutor**: Content-grounded chat proxy with per-operator token budgets
+//! - **TokenSettlement**: Signed hREA/token ledger payment proofs for premium gates
+//! - **ReciprocalFederation**: Signed gateway-to-gateway calls for commons content on peer networks
+//! - **Regions**: Doorway presence across regions and nearest-doorway hints for clients
+//! - **ContentAccess**: Opt-in, anonymized daily view counts for steward-marked content
+//! - **ContentLicense**: SPDX license checks deciding which content may be redistributed
+//! - **Embed**: Partner-framed learning path player with scoped, signed embed tokens
+//! - **MediaUrls**: Signed, expiring URLs for media of gated or non-commons content
+//! - **GuestSessions**: Short-lived anonymous tokens for rate-limited, projection-only public reads
+//! - **Calendar**: iCalendar feeds of learners' study and cohort sessions, with signed feed tokens
+//! - **WebAnnotation**: W3C Web Annotation JSON for learners' highlights, comments and cohort annotation layers
+//! - **NotesVault**: Zipped Markdown (Obsidian) vault of a learner's notes, reflections, bookmarks and annotations
+//! - **IdentityLinks**: Merging a second account (and its passkeys, API keys and agent key) into the one a person keeps
+//! - **OperatorOnboarding**: One-call tenant provisioning (keys, cache namespace, NATS, collections, hApp)
 
+pub mod calendar;
+pub mod content_access;
+pub mod content_license;
 pub mod custodian;
 pub mod did_resolver;
 pub mod discovery;
+pub mod duplicate_detection;
 pub mod elohim_verifier;
+pub mod embed;
 pub mod federation;
+pub mod guest_sessions;
+pub mod identity_links;
+pub mod import_abuse;
 pub mod import_client;
 pub mod import_config;
 pub mod import_orchestrator;
+pub mod import_provenance;
+pub mod import_validation;
+pub mod media_urls;
+pub mod moderation;
+pub mod notes_vault;
+pub mod operator_onboarding;
+pub mod reciprocal_federation;
 pub mod recording;
+pub mod regions;
 pub mod route_registry;
 pub mod shard_resolver;
+pub mod site_export;
 pub mod storage_registration;
+pub mod token_settlement;
+pub mod tutor;
 pub mod verification;
+pub mod web_annotation;
 pub mod zome_caller;
 
 pub use custodian::{
@@ -0,0 +1,472 @@
+//! Content moderation
+//!
+//! Screens user-submitted writes (new content, discussions, content imports)
+//! before they reach the DHT. Two checks can be configured:
+//!
+//! - a word list, matched against whole words so "class" doesn't trip "ass"
+//! - an OpenAI-compatible moderation API (`POST {url}/v1/moderations`)
+//!
+//! API results count when the provider flags them, or when a category score
+//! reaches the `moderation.score_threshold` setting (configured, or set by
+//! [governance](crate::worker::governance)).
+//!
+//! Flagged writes are not rejected. They are quarantined in the
+//! `moderation_queue` collection for a steward to approve (which makes the
+//! original zome call) or hide. If the moderation API can't be reached the
+//! write is quarantined too, so an outage doesn't let everything through.
+//!
+//! The same queue collects user reports about published content and content
+//! whose attestation was revoked (`revoke_content_attestation` write
+//! signals). Repeat reports are folded into the open item.
+
+use bson::{doc, oid::ObjectId};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::cache::CacheInvalidation;
+use crate::db::schemas::{
+    ModerationItemDoc, ModerationKind, ModerationStatus, MODERATION_QUEUE_COLLECTION,
+};
+use crate::db::{MongoClient, MongoCollection};
+use crate::worker::governance::{GovernedSettings, MODERATION_SCORE_THRESHOLD};
+
+/// Timeout for one moderation API call
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Write signal that queues content for review
+const REVOCATION_FN: &str = "revoke_content_attestation";
+
+/// Payload fields holding user-written text
+const TEXT_FIELDS: &[&str] = &[
+    "title",
+    "description",
+    "summary",
+    "content",
+    "body",
+    "text",
+    "messages_json",
+];
+
+/// Moderation settings
+#[derive(Debug, Clone, Default)]
+pub struct ModerationConfig {
+    /// Blocked words and phrases
+    pub word_list: Vec<String>,
+    /// Moderation API base URL
+    pub api_url: Option<String>,
+    pub api_key: Option<String>,
+}
+
+impl ModerationConfig {
+    /// Whether any check is configured
+    pub fn is_enabled(&self) -> bool {
+        !self.word_list.is_empty() || self.api_url.is_some()
+    }
+}
+
+/// Parse a word list file: one word or phrase per line, `#` starts a comment
+pub fn parse_word_list(text: &str) -> Vec<String> {
+    text.lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| line.to_lowercase())
+        .collect()
+}
+
+/// Lowercased words of a text
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+/// Whole-word matcher for the word list
+#[derive(Debug, Clone, Default)]
+pub struct WordFilter {
+    /// Each term split into words, so phrases match across whitespace
+    terms: Vec<(String, Vec<String>)>,
+}
+
+impl WordFilter {
+    pub fn new(terms: &[String]) -> Self {
+        Self {
+            terms: terms
+                .iter()
+                .map(|term| (term.clone(), words(term)))
+                .filter(|(_, parts)| !parts.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Terms found in the text
+    pub fn matches(&self, text: &str) -> Vec<String> {
+        if self.terms.is_empty() {
+            return Vec::new();
+        }
+        let text = words(text);
+        self.terms
+            .iter()
+            .filter(|(_, parts)| {
+                text.windows(parts.len())
+                    .any(|window| window == parts.as_slice())
+            })
+            .map(|(term, _)| term.clone())
+            .collect()
+    }
+}
+
+/// The user-written text of a zome call payload
+pub fn moderated_text(payload: &Value) -> String {
+    let mut parts: Vec<&str> = TEXT_FIELDS
+        .iter()
+        .filter_map(|field| payload.get(*field).and_then(|v| v.as_str()))
+        .collect();
+    if let Some(tags) = payload.get("tags").and_then(|v| v.as_array()) {
+        parts.extend(tags.iter().filter_map(|t| t.as_str()));
+    }
+    parts.join("\n")
+}
+
+#[derive(Debug, Deserialize)]
+struct ModerationApiResponse {
+    #[serde(default)]
+    results: Vec<ModerationApiResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModerationApiResult {
+    #[serde(default)]
+    flagged: bool,
+    #[serde(default)]
+    categories: BTreeMap<String, bool>,
+    #[serde(default)]
+    category_scores: BTreeMap<String, f64>,
+}
+
+impl ModerationApiResult {
+    /// Reasons for a flagged result, or for categories scoring at least
+    /// `threshold`
+    fn reasons(&self, threshold: Option<f64>) -> Vec<String> {
+        let over_threshold = |category: &String| {
+            threshold.is_some_and(|t| self.category_scores.get(category).is_some_and(|s| *s >= t))
+        };
+        let categories: std::collections::BTreeSet<&String> = self
+            .categories
+            .iter()
+            .filter(|(category, hit)| (self.flagged && **hit) || over_threshold(category))
+            .map(|(category, _)| category)
+            .chain(self.category_scores.keys().filter(|c| over_threshold(c)))
+            .collect();
+        if !self.flagged && categories.is_empty() {
+            return Vec::new();
+        }
+        let reasons: Vec<String> = categories
+            .into_iter()
+            .map(|category| format!("api:{category}"))
+            .collect();
+        if reasons.is_empty() {
+            vec!["api:flagged".to_string()]
+        } else {
+            reasons
+        }
+    }
+}
+
+/// Moderation filter and review queue
+pub struct ModerationService {
+    config: ModerationConfig,
+    filter: WordFilter,
+    client: reqwest::Client,
+    mongo: MongoClient,
+    settings: Arc<GovernedSettings>,
+}
+
+impl ModerationService {
+    pub fn new(
+        config: ModerationConfig,
+        mongo: MongoClient,
+        settings: Arc<GovernedSettings>,
+    ) -> Self {
+        let filter = WordFilter::new(&config.word_list);
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        Self {
+            config,
+            filter,
+            client,
+            mongo,
+            settings,
+        }
+    }
+
+    /// Whether writes are screened (a word list or API is configured)
+    pub fn screens_writes(&self) -> bool {
+        self.config.is_enabled()
+    }
+
+    /// Reasons to hold a zome call payload back (empty when it's fine)
+    pub async fn check(&self, payload: &Value) -> Vec<String> {
+        if !self.screens_writes() {
+            return Vec::new();
+        }
+        let text = moderated_text(payload);
+        if text.trim().is_empty() {
+            return Vec::new();
+        }
+
+        let mut reasons: Vec<String> = self
+            .filter
+            .matches(&text)
+            .into_iter()
+            .map(|term| format!("word:{term}"))
+            .collect();
+
+        if self.config.api_url.is_some() {
+            match self.check_api(&text).await {
+                Ok(api_reasons) => reasons.extend(api_reasons),
+                Err(e) => {
+                    warn!(error = %e, "Moderation API unavailable, holding write for review");
+                    reasons.push("api:unavailable".to_string());
+                }
+            }
+        }
+        reasons
+    }
+
+    async fn check_api(&self, text: &str) -> Result<Vec<String>, String> {
+        let Some(ref url) = self.config.api_url else {
+            return Ok(Vec::new());
+        };
+        let mut request = self
+            .client
+            .post(format!("{}/v1/moderations", url.trim_end_matches('/')))
+            .json(&serde_json::json!({ "input": text }));
+        if let Some(ref key) = self.config.api_key {
+            request = request.bearer_auth(key);
+        }
+        let response = request.send().await.map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("HTTP {}", response.status()));
+        }
+        let body: ModerationApiResponse = response.json().await.map_err(|e| e.to_string())?;
+        let threshold = self.settings.get(MODERATION_SCORE_THRESHOLD);
+        Ok(body
+            .results
+            .iter()
+            .flat_map(|result| result.reasons(threshold))
+            .collect())
+    }
+
+    /// The review queue collection
+    pub async fn queue(&self) -> Result<MongoCollection<ModerationItemDoc>, String> {
+        self.mongo
+            .collection(MODERATION_QUEUE_COLLECTION)
+            .await
+            .map_err(|e| format!("Moderation queue unavailable: {e}"))
+    }
+
+    /// Hold a write for steward review
+    pub async fn quarantine(
+        &self,
+        zome_fn: &str,
+        payload: &Value,
+        reasons: Vec<String>,
+        source: &str,
+        submitted_by: Option<String>,
+    ) -> Result<ObjectId, String> {
+        let item = ModerationItemDoc {
+            kind: ModerationKind::QuarantinedWrite,
+            zome_fn: zome_fn.to_string(),
+            payload_json: payload.to_string(),
+            reasons,
+            source: source.to_string(),
+            author: submitted_by.clone(),
+            submitted_by,
+            status: ModerationStatus::Pending,
+            ..Default::default()
+        };
+        self.queue()
+            .await?
+            .insert_one(item)
+            .await
+            .map_err(|e| format!("Failed to quarantine write: {e}"))
+    }
+
+    /// Queue published content for review, folding into its open item of
+    /// the same kind
+    pub async fn flag_content(&self, flag: ContentFlag) -> Result<ObjectId, String> {
+        let queue = self.queue().await?;
+        let kind = bson::to_bson(&flag.kind).unwrap_or_default();
+        let pending = bson::to_bson(&ModerationStatus::Pending).unwrap_or_default();
+        let open = queue
+            .find_one(doc! { "content_id": &flag.content_id, "kind": &kind, "status": &pending })
+            .await
+            .map_err(|e| format!("Failed to read moderation queue: {e}"))?;
+
+        if let Some(id) = open.and_then(|item| item.id) {
+            let reporters: Vec<String> = flag.reporter.into_iter().collect();
+            let mut update = doc! {
+                "$addToSet": {
+                    "reasons": &flag.reason,
+                    "reporters": { "$each": reporters },
+                },
+                "$set": { "metadata.updated_at": bson::DateTime::now() },
+            };
+            if let Some(ref details) = flag.details {
+                update.insert("$push", doc! { "details": details });
+            }
+            queue
+                .update_one(doc! { "_id": id }, update)
+                .await
+                .map_err(|e| format!("Failed to update moderation item: {e}"))?;
+            return Ok(id);
+        }
+
+        let item = ModerationItemDoc {
+            kind: flag.kind,
+            content_id: Some(flag.content_id),
+            reasons: vec![flag.reason],
+            details: flag.details.into_iter().collect(),
+            source: flag.source.to_string(),
+            reporters: flag.reporter.into_iter().collect(),
+            author: flag.author,
+            status: ModerationStatus::Pending,
+            ..Default::default()
+        };
+        queue
+            .insert_one(item)
+            .await
+            .map_err(|e| format!("Failed to queue content for review: {e}"))
+    }
+}
+
+/// Published content to put in front of a steward
+#[derive(Debug, Clone)]
+pub struct ContentFlag {
+    pub kind: ModerationKind,
+    pub content_id: String,
+    /// Queue reason, e.g. `report:spam`
+    pub reason: String,
+    pub details: Option<String>,
+    /// `report` or `attestation`
+    pub source: &'static str,
+    pub reporter: Option<String>,
+    /// Author's agent key, when known
+    pub author: Option<String>,
+}
+
+/// Queue content whose attestation was revoked, from zome write signals
+pub fn spawn_revocation_task(
+    mut rx: broadcast::Receiver<CacheInvalidation>,
+    moderation: Arc<ModerationService>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!("Attestation revocation review started");
+
+        loop {
+            match rx.recv().await {
+                Ok(invalidation) if invalidation.source_fn == REVOCATION_FN => {
+                    let flag = ContentFlag {
+                        kind: ModerationKind::AttestationRevoked,
+                        content_id: invalidation.doc_id.clone(),
+                        reason: "attestation:revoked".to_string(),
+                        details: None,
+                        source: "attestation",
+                        reporter: None,
+                        author: None,
+                    };
+                    match moderation.flag_content(flag).await {
+                        Ok(id) => info!(
+                            moderation_id = %id,
+                            content_id = %invalidation.doc_id,
+                            "Content with a revoked attestation queued for review"
+                        ),
+                        Err(e) => warn!(
+                            content_id = %invalidation.doc_id,
+                            error = %e,
+                            "Failed to queue revoked attestation for review"
+                        ),
+                    }
+                }
+                Ok(_) => {}
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    warn!(missed = n, "Attestation revocation review lagged");
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_word_list() {
+        let list = parse_word_list("# blocked\nBadWord\n\n  two words  # phrase\n");
+        assert_eq!(list, vec!["badword".to_string(), "two words".to_string()]);
+    }
+
+    #[test]
+    fn test_word_filter_matches_whole_words() {
+        let filter = WordFilter::new(&["ass".to_string(), "buy now".to_string()]);
+        assert!(filter.matches("A class on grass").is_empty());
+        assert_eq!(filter.matches("What an ASS."), vec!["ass".to_string()]);
+        assert_eq!(filter.matches("Buy\n now!"), vec!["buy now".to_string()]);
+        assert!(filter.matches("buy it now").is_empty());
+    }
+
+    #[test]
+    fn test_moderated_text() {
+        let payload = serde_json::json!({
+            "id": "badword-1",
+            "title": "Title",
+            "content": "Body",
+            "tags": ["tag"],
+            "reach": "commons",
+        });
+        let text = moderated_text(&payload);
+        assert!(text.contains("Title") && text.contains("Body") && text.contains("tag"));
+        assert!(!text.contains("badword") && !text.contains("commons"));
+    }
+
+    #[test]
+    fn test_api_result_reasons() {
+        let result: ModerationApiResult = serde_json::from_value(serde_json::json!({
+            "flagged": true,
+            "categories": { "harassment": true, "violence": false },
+        }))
+        .unwrap();
+        assert_eq!(result.reasons(None), vec!["api:harassment".to_string()]);
+
+        let clean: ModerationApiResult =
+            serde_json::from_value(serde_json::json!({ "flagged": false })).unwrap();
+        assert!(clean.reasons(None).is_empty());
+    }
+
+    #[test]
+    fn test_api_result_score_threshold() {
+        let result: ModerationApiResult = serde_json::from_value(serde_json::json!({
+            "flagged": false,
+            "categories": { "harassment": false, "violence": false },
+            "category_scores": { "harassment": 0.62, "violence": 0.1 },
+        }))
+        .unwrap();
+        assert!(result.reasons(None).is_empty());
+        assert!(result.reasons(Some(0.7)).is_empty());
+        assert_eq!(
+            result.reasons(Some(0.6)),
+            vec!["api:harassment".to_string()]
+        );
+    }
+}
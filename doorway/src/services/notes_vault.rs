@@ -0,0 +1,494 @@
+//! Notes vault export
+//!
+//! Compiles a learner's step notes, reflections, bookmarks and annotations
+//! (gathered by the content DNA's `get_notes_export`) into a Markdown vault
+//! that opens as-is in Obsidian, Logseq and other PKM tools, zipped for
+//! download.
+//!
+//! ## Layout
+//!
+//! ```text
+//! README.md           Index: paths and bookmark folders
+//! Paths/{title}.md    Step notes and reflections, in step order
+//! Content/{title}.md  One note per content item: its bookmark folder,
+//!                     annotations, notes and reflections, and relationships
+//! ```
+//!
+//! Notes link with `[[wikilinks]]`. A content item's relationships to other
+//! items in the vault become links under "Related", and each item lists the
+//! items relating to it under "Linked from", so the vault's backlinks mirror
+//! the content graph. File names come from titles; a title shared by two
+//! items gets the content id appended.
+
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Cursor, Write};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use super::web_annotation::{content_iri, Annotation};
+use crate::projection::document::parse_entry_timestamp;
+
+/// Must match ReflectionEntry in holochain/dna/elohim/zomes/content_store_integrity/src/lib.rs
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReflectionEntry {
+    pub path_id: String,
+    pub step_index: u32,
+    #[serde(default)]
+    pub prompt: Option<String>,
+    pub response: String,
+    pub created_at: String,
+}
+
+/// Must match NotesExportStep in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Clone, Deserialize)]
+pub struct NotesExportStep {
+    pub step_index: u32,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub content_id: Option<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+    #[serde(default)]
+    pub reflections: Vec<ReflectionEntry>,
+}
+
+/// Must match NotesExportPath in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Clone, Deserialize)]
+pub struct NotesExportPath {
+    pub path_id: String,
+    pub title: String,
+    pub steps: Vec<NotesExportStep>,
+}
+
+/// Must match NotesExportLink in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Clone, Deserialize)]
+pub struct NotesExportLink {
+    pub content_id: String,
+    pub relationship_type: String,
+}
+
+/// Must match NotesExportItem in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Clone, Deserialize)]
+pub struct NotesExportItem {
+    pub content_id: String,
+    pub title: String,
+    #[serde(default)]
+    pub bookmark_folder: Option<String>,
+    #[serde(default)]
+    pub annotations: Vec<Annotation>,
+    #[serde(default)]
+    pub related: Vec<NotesExportLink>,
+}
+
+/// Must match NotesExport in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Clone, Deserialize)]
+pub struct NotesExport {
+    pub learner_id: String,
+    pub exported_at: String,
+    pub paths: Vec<NotesExportPath>,
+    pub items: Vec<NotesExportItem>,
+}
+
+/// Note name for a title: characters vaults and file systems reject removed
+pub fn note_name(title: &str) -> String {
+    let name: String = title
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | '#' | '^' | '[' | ']' => ' ',
+            c if c.is_control() => ' ',
+            c => c,
+        })
+        .collect();
+    let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+    let name = name.trim_start_matches('.');
+    if name.is_empty() {
+        "Untitled".to_string()
+    } else {
+        name.chars().take(120).collect()
+    }
+}
+
+/// Unique note names for ids, from their titles
+fn unique_names<'a>(entries: impl Iterator<Item = (&'a str, &'a str)>) -> HashMap<String, String> {
+    let entries: Vec<(&str, String)> = entries.map(|(id, t)| (id, note_name(t))).collect();
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for (_, name) in entries.iter() {
+        *counts.entry(name.as_str()).or_default() += 1;
+    }
+    entries
+        .iter()
+        .map(|(id, name)| {
+            let unique = if counts[name.as_str()] > 1 {
+                format!("{} ({})", name, note_name(id))
+            } else {
+                name.clone()
+            };
+            (id.to_string(), unique)
+        })
+        .collect()
+}
+
+/// Day of a zome timestamp, or the original when it doesn't parse
+fn day(value: &str) -> String {
+    parse_entry_timestamp(&Value::String(value.to_string()))
+        .map(|t| t.format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|| value.to_string())
+}
+
+/// YAML double-quoted scalar
+fn yaml_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+/// Text quoted as a Markdown block quote
+fn quote(text: &str) -> String {
+    text.lines()
+        .map(|line| format!("> {line}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Quoted text of a TextQuoteSelector, when the range was given with one
+fn quoted_range(selector_json: &str) -> Option<String> {
+    let selector: Value = serde_json::from_str(selector_json).ok()?;
+    let selectors = match selector {
+        Value::Array(selectors) => selectors,
+        selector => vec![selector],
+    };
+    selectors
+        .iter()
+        .find(|s| s["type"] == "TextQuoteSelector")
+        .and_then(|s| s["exact"].as_str())
+        .map(str::to_string)
+}
+
+fn render_reflections(out: &mut String, reflections: &[ReflectionEntry]) {
+    for reflection in reflections {
+        match reflection.prompt {
+            Some(ref prompt) => out.push_str(&format!(
+                "**Reflection** ({}) - *{}*\n\n",
+                day(&reflection.created_at),
+                prompt
+            )),
+            None => out.push_str(&format!(
+                "**Reflection** ({})\n\n",
+                day(&reflection.created_at)
+            )),
+        }
+        out.push_str(&format!("{}\n\n", reflection.response.trim()));
+    }
+}
+
+/// A vault being rendered
+struct Vault<'a> {
+    export: &'a NotesExport,
+    base: &'a str,
+    paths: HashMap<String, String>,
+    items: HashMap<String, String>,
+}
+
+impl Vault<'_> {
+    fn link(&self, names: &HashMap<String, String>, id: &str) -> String {
+        match names.get(id) {
+            Some(name) => format!("[[{name}]]"),
+            None => id.to_string(),
+        }
+    }
+
+    fn render_path(&self, path: &NotesExportPath) -> String {
+        let mut out = format!(
+            "---\npath_id: {}\ntags: [elohim/path]\n---\n\n# {}\n\n",
+            yaml_string(&path.path_id),
+            path.title
+        );
+        for step in path.steps.iter() {
+            let title = step.title.as_deref().unwrap_or("(removed step)");
+            out.push_str(&format!("## Step {}: {}\n\n", step.step_index + 1, title));
+            if let Some(ref content_id) = step.content_id {
+                out.push_str(&format!(
+                    "Content: {}\n\n",
+                    self.link(&self.items, content_id)
+                ));
+            }
+            if let Some(ref note) = step.note {
+                out.push_str(&format!("{}\n\n", note.trim()));
+            }
+            render_reflections(&mut out, &step.reflections);
+        }
+        out
+    }
+
+    fn render_item(&self, item: &NotesExportItem, linked_from: &[(&str, &str)]) -> String {
+        let mut out = format!(
+            "---\ncontent_id: {}\nsource: {}\n",
+            yaml_string(&item.content_id),
+            yaml_string(&content_iri(self.base, &item.content_id))
+        );
+        match item.bookmark_folder {
+            Some(ref folder) => out.push_str(&format!(
+                "bookmark_folder: {}\ntags: [elohim/content, elohim/bookmark]\n",
+                yaml_string(folder)
+            )),
+            None => out.push_str("tags: [elohim/content]\n"),
+        }
+        out.push_str(&format!("---\n\n# {}\n\n", item.title));
+
+        if !item.annotations.is_empty() {
+            out.push_str("## Annotations\n\n");
+            for annotation in item.annotations.iter() {
+                if let Some(range) = quoted_range(&annotation.selector_json) {
+                    out.push_str(&format!("{}\n\n", quote(&range)));
+                }
+                let body = annotation.body.trim();
+                if !body.is_empty() {
+                    out.push_str(&format!("{body}\n\n"));
+                }
+                out.push_str(&format!(
+                    "*{}, {}, {}*\n\n",
+                    annotation.motivation,
+                    annotation.visibility,
+                    day(&annotation.created_at)
+                ));
+            }
+        }
+
+        let mut wrote_notes = false;
+        for path in self.export.paths.iter() {
+            for step in path.steps.iter() {
+                if step.content_id.as_deref() != Some(item.content_id.as_str()) {
+                    continue;
+                }
+                if !wrote_notes {
+                    out.push_str("## Notes\n\n");
+                    wrote_notes = true;
+                }
+                out.push_str(&format!(
+                    "### {} - step {}\n\n",
+                    self.link(&self.paths, &path.path_id),
+                    step.step_index + 1
+                ));
+                if let Some(ref note) = step.note {
+                    out.push_str(&format!("{}\n\n", note.trim()));
+                }
+                render_reflections(&mut out, &step.reflections);
+            }
+        }
+
+        if !item.related.is_empty() {
+            out.push_str("## Related\n\n");
+            for related in item.related.iter() {
+                out.push_str(&format!(
+                    "- {}: {}\n",
+                    related.relationship_type,
+                    self.link(&self.items, &related.content_id)
+                ));
+            }
+            out.push('\n');
+        }
+        if !linked_from.is_empty() {
+            out.push_str("## Linked from\n\n");
+            for (source_id, relationship_type) in linked_from {
+                out.push_str(&format!(
+                    "- {}: {}\n",
+                    relationship_type,
+                    self.link(&self.items, source_id)
+                ));
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    fn render_index(&self) -> String {
+        let mut out = format!(
+            "# Learning notes\n\nExported from Elohim on {}.\n\n",
+            day(&self.export.exported_at)
+        );
+        if !self.export.paths.is_empty() {
+            out.push_str("## Paths\n\n");
+            for path in self.export.paths.iter() {
+                out.push_str(&format!("- {}\n", self.link(&self.paths, &path.path_id)));
+            }
+            out.push('\n');
+        }
+
+        let mut folders: BTreeMap<&str, Vec<&NotesExportItem>> = BTreeMap::new();
+        for item in self.export.items.iter() {
+            if let Some(ref folder) = item.bookmark_folder {
+                folders.entry(folder).or_default().push(item);
+            }
+        }
+        if !folders.is_empty() {
+            out.push_str("## Bookmarks\n\n");
+            for (folder, items) in folders {
+                out.push_str(&format!("### {folder}\n\n"));
+                for item in items {
+                    out.push_str(&format!("- {}\n", self.link(&self.items, &item.content_id)));
+                }
+                out.push('\n');
+            }
+        }
+        out
+    }
+}
+
+/// Markdown files of the vault, by path within it
+pub fn render_vault(export: &NotesExport, base: &str) -> Vec<(String, String)> {
+    let mut titles: Vec<(&str, &str)> = export
+        .paths
+        .iter()
+        .map(|p| (p.path_id.as_str(), p.title.as_str()))
+        .collect();
+    let path_count = titles.len();
+    titles.extend(
+        export
+            .items
+            .iter()
+            .map(|i| (i.content_id.as_str(), i.title.as_str())),
+    );
+    // Paths and content share one namespace so wikilinks stay unambiguous
+    let names = unique_names(titles.iter().copied());
+    let vault = Vault {
+        export,
+        base,
+        paths: titles[..path_count]
+            .iter()
+            .map(|(id, _)| (id.to_string(), names[*id].clone()))
+            .collect(),
+        items: titles[path_count..]
+            .iter()
+            .map(|(id, _)| (id.to_string(), names[*id].clone()))
+            .collect(),
+    };
+
+    let mut linked_from: HashMap<&str, Vec<(&str, &str)>> = HashMap::new();
+    for item in export.items.iter() {
+        for related in item.related.iter() {
+            linked_from
+                .entry(related.content_id.as_str())
+                .or_default()
+                .push((item.content_id.as_str(), related.relationship_type.as_str()));
+        }
+    }
+
+    let mut files = vec![("README.md".to_string(), vault.render_index())];
+    for path in export.paths.iter() {
+        files.push((
+            format!("Paths/{}.md", vault.paths[&path.path_id]),
+            vault.render_path(path),
+        ));
+    }
+    for item in export.items.iter() {
+        let sources = linked_from
+            .get(item.content_id.as_str())
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        files.push((
+            format!("Content/{}.md", vault.items[&item.content_id]),
+            vault.render_item(item, sources),
+        ));
+    }
+    files
+}
+
+/// The vault as a zip archive
+pub fn vault_zip(export: &NotesExport, base: &str) -> Result<Vec<u8>, String> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (name, markdown) in render_vault(export, base) {
+        zip.start_file(name, options).map_err(|e| e.to_string())?;
+        zip.write_all(markdown.as_bytes())
+            .map_err(|e| e.to_string())?;
+    }
+    zip.finish()
+        .map(|cursor| cursor.into_inner())
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn export() -> NotesExport {
+        serde_json::from_value(json!({
+            "learner_id": "uhCAk-learner",
+            "exported_at": "Timestamp(2026-10-16T09:30:00.000000Z)",
+            "paths": [{
+                "path_id": "path-1",
+                "title": "Fractions",
+                "steps": [{
+                    "step_index": 0,
+                    "title": "Tenths",
+                    "content_id": "fractions-intro",
+                    "note": "Ten parts of a whole",
+                    "reflections": [{
+                        "path_id": "path-1",
+                        "step_index": 0,
+                        "prompt": "Where do you see tenths?",
+                        "response": "Money",
+                        "created_at": "Timestamp(2026-10-15T09:30:00.000000Z)"
+                    }]
+                }]
+            }],
+            "items": [
+                {
+                    "content_id": "fractions-intro",
+                    "title": "Fractions: an introduction",
+                    "bookmark_folder": "Week 3",
+                    "annotations": [{
+                        "id": "annotation-uhCAk-1",
+                        "author_id": "uhCAk-learner",
+                        "content_id": "fractions-intro",
+                        "selector_json": "{\"type\":\"TextQuoteSelector\",\"exact\":\"tenths\"}",
+                        "motivation": "commenting",
+                        "body": "Why base 10?",
+                        "visibility": "private",
+                        "created_at": "Timestamp(2026-10-16T09:30:00.000000Z)",
+                        "updated_at": "Timestamp(2026-10-16T09:30:00.000000Z)"
+                    }],
+                    "related": [{"content_id": "decimals", "relationship_type": "RELATES_TO"}]
+                },
+                {"content_id": "decimals", "title": "Decimals"}
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_render_vault() {
+        let files: HashMap<String, String> = render_vault(&export(), "https://doorway.example")
+            .into_iter()
+            .collect();
+        assert_eq!(files.len(), 4);
+
+        let intro = &files["Content/Fractions an introduction.md"];
+        assert!(intro.contains("bookmark_folder: \"Week 3\""));
+        assert!(intro.contains("source: \"https://doorway.example/content/fractions-intro\""));
+        assert!(intro.contains("> tenths\n\nWhy base 10?"));
+        assert!(intro.contains("### [[Fractions]] - step 1\n\nTen parts of a whole"));
+        assert!(intro.contains("- RELATES_TO: [[Decimals]]"));
+
+        // The relationship shows on its target too
+        assert!(files["Content/Decimals.md"]
+            .contains("## Linked from\n\n- RELATES_TO: [[Fractions an introduction]]"));
+        assert!(files["Paths/Fractions.md"].contains("Content: [[Fractions an introduction]]"));
+        assert!(files["Paths/Fractions.md"].contains("*Where do you see tenths?*\n\nMoney"));
+        assert!(files["README.md"].contains("### Week 3\n\n- [[Fractions an introduction]]"));
+
+        let bytes = vault_zip(&export(), "https://doorway.example").unwrap();
+        assert_eq!(&bytes[..2], b"PK");
+    }
+
+    #[test]
+    fn test_unique_names() {
+        let names = unique_names([("a", "Intro"), ("b", "Intro"), ("c", "What/why?")].into_iter());
+        assert_eq!(names["a"], "Intro (a)");
+        assert_eq!(names["b"], "Intro (b)");
+        assert_eq!(names["c"], "What why");
+        assert_eq!(note_name("..."), "Untitled");
+    }
+}
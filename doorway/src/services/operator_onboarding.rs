@@ -0,0 +1,456 @@
+//! Operator Onboarding
+//!
+//! Provisions a new operator (tenant) in one call: the operator record, an
+//! admin and a standard API key, a response cache namespace, a JetStream
+//! stream for the operator's NATS subjects, operator-scoped MongoDB
+//! collections with their indexes and, on request, a hApp installed through
+//! the conductor admin API.
+//!
+//! Each step's outcome is kept on the [`OperatorDoc`]. Steps are idempotent,
+//! so provisioning a partly provisioned operator again retries what failed;
+//! provisioning a ready one is a conflict. API keys are only created (and
+//! returned in plaintext) the first time.
+
+use base64::prelude::*;
+use bson::{doc, oid::ObjectId};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use tracing::{info, warn};
+
+use crate::auth::PermissionLevel;
+use crate::conductor::AgentProvisioner;
+use crate::db::mongo::{IntoIndexes, MutMetadata};
+use crate::db::schemas::{
+    ApiKeyDoc, Metadata, ModerationItemDoc, NotificationDoc, OperatorDoc, OperatorStatus,
+    OperatorStep, StepStatus, UserDoc, API_KEY_COLLECTION, OPERATOR_COLLECTION,
+};
+use crate::db::MongoClient;
+use crate::server::AppState;
+
+/// Operator ID length bounds
+const MIN_ID_LEN: usize = 3;
+const MAX_ID_LEN: usize = 32;
+
+/// How long an operator stream keeps messages
+const STREAM_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 3600);
+
+/// Operator onboarding errors
+#[derive(Debug, Error)]
+pub enum OnboardingError {
+    #[error("Invalid request: {0}")]
+    Invalid(String),
+
+    #[error("Operator already provisioned: {0}")]
+    Exists(String),
+
+    #[error("Operator onboarding unavailable: {0}")]
+    Unavailable(String),
+
+    #[error("Database error: {0}")]
+    Database(String),
+}
+
+/// Body of `POST /admin/operators`
+#[derive(Debug, Clone, Deserialize)]
+pub struct OnboardOperatorRequest {
+    pub operator_id: String,
+    pub name: String,
+    #[serde(default)]
+    pub contact_email: Option<String>,
+    /// Install the hApp for the operator on the least loaded conductor
+    #[serde(default)]
+    pub install_happ: bool,
+    /// hApp bundle to install instead of the configured one
+    #[serde(default)]
+    pub bundle_path: Option<String>,
+}
+
+/// An API key created for an operator, shown once
+#[derive(Debug, Clone, Serialize)]
+pub struct IssuedApiKey {
+    pub name: String,
+    pub permission_level: PermissionLevel,
+    pub key: String,
+}
+
+/// Result of a provisioning run
+#[derive(Debug, Clone)]
+pub struct OnboardingOutcome {
+    pub operator: OperatorDoc,
+    /// Empty when the keys were issued by an earlier run
+    pub api_keys: Vec<IssuedApiKey>,
+}
+
+/// Check an operator ID: lowercase letters, digits and dashes, 3-32 long,
+/// not starting or ending with a dash
+pub fn validate_operator_id(id: &str) -> Result<(), OnboardingError> {
+    let valid_chars = id
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if !(MIN_ID_LEN..=MAX_ID_LEN).contains(&id.len())
+        || !valid_chars
+        || id.starts_with('-')
+        || id.ends_with('-')
+    {
+        return Err(OnboardingError::Invalid(format!(
+            "operator_id must be {MIN_ID_LEN}-{MAX_ID_LEN} lowercase letters, digits or dashes"
+        )));
+    }
+    Ok(())
+}
+
+/// Names reserved for an operator
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperatorPlan {
+    pub cache_namespace: String,
+    pub nats_stream: String,
+    pub nats_subjects: Vec<String>,
+    pub users_collection: String,
+    pub notifications_collection: String,
+    pub moderation_collection: String,
+}
+
+impl OperatorPlan {
+    /// Plan for a validated operator ID
+    pub fn new(operator_id: &str) -> Self {
+        let collection = |name: &str| format!("op_{}_{name}", operator_id.replace('-', "_"));
+        Self {
+            cache_namespace: format!("op:{operator_id}:"),
+            nats_stream: format!("OPERATOR_{}", operator_id.to_uppercase().replace('-', "_")),
+            nats_subjects: vec![format!("OPERATOR.{operator_id}.>")],
+            users_collection: collection("users"),
+            notifications_collection: collection("notifications"),
+            moderation_collection: collection("moderation_queue"),
+        }
+    }
+
+    pub fn collections(&self) -> Vec<String> {
+        vec![
+            self.users_collection.clone(),
+            self.notifications_collection.clone(),
+            self.moderation_collection.clone(),
+        ]
+    }
+}
+
+/// New API key and the SHA-256 hex digest stored for it
+pub fn generate_api_key() -> (String, String) {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let key = format!("ek_{}", BASE64_URL_SAFE_NO_PAD.encode(bytes));
+    let hash = hash_api_key(&key);
+    (key, hash)
+}
+
+/// Stored form of an API key
+pub fn hash_api_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
+
+fn step(name: &str, status: StepStatus, detail: impl Into<Option<String>>) -> OperatorStep {
+    OperatorStep {
+        step: name.to_string(),
+        status,
+        detail: detail.into(),
+    }
+}
+
+fn failed(name: &str, error: impl std::fmt::Display) -> OperatorStep {
+    warn!(step = name, error = %error, "Operator provisioning step failed");
+    step(name, StepStatus::Failed, error.to_string())
+}
+
+/// Provision an operator, or finish provisioning a partial one
+pub async fn provision(
+    state: &AppState,
+    request: OnboardOperatorRequest,
+) -> Result<OnboardingOutcome, OnboardingError> {
+    validate_operator_id(&request.operator_id)?;
+    if request.name.trim().is_empty() {
+        return Err(OnboardingError::Invalid("name is required".to_string()));
+    }
+    let mongo = state
+        .mongo
+        .as_ref()
+        .ok_or_else(|| OnboardingError::Unavailable("MongoDB not configured".to_string()))?;
+    let operators = mongo
+        .collection::<OperatorDoc>(OPERATOR_COLLECTION)
+        .await
+        .map_err(|e| OnboardingError::Database(e.to_string()))?;
+
+    let plan = OperatorPlan::new(&request.operator_id);
+    let filter = doc! { "operator_id": &request.operator_id, "metadata.is_deleted": false };
+    let existing = operators
+        .find_one(filter)
+        .await
+        .map_err(|e| OnboardingError::Database(e.to_string()))?;
+
+    // 1. Operator record
+    let mut operator = match existing {
+        Some(operator) if operator.status == OperatorStatus::Ready => {
+            return Err(OnboardingError::Exists(request.operator_id));
+        }
+        Some(operator) => operator,
+        None => {
+            let mut operator = OperatorDoc {
+                metadata: Metadata::new(),
+                operator_id: request.operator_id.clone(),
+                name: request.name.clone(),
+                contact_email: request.contact_email.clone(),
+                cache_namespace: plan.cache_namespace.clone(),
+                nats_stream: plan.nats_stream.clone(),
+                nats_subjects: plan.nats_subjects.clone(),
+                collections: plan.collections(),
+                ..Default::default()
+            };
+            let id = operators
+                .insert_one(operator.clone())
+                .await
+                .map_err(|e| OnboardingError::Database(e.to_string()))?;
+            operator.id = Some(id);
+            operator
+        }
+    };
+    let owner_id = operator
+        .id
+        .ok_or_else(|| OnboardingError::Database("Operator record has no _id".to_string()))?;
+    let mut steps = vec![step("record", StepStatus::Done, None)];
+
+    // 2. API keys
+    let (api_keys_step, api_keys) = issue_api_keys(mongo, owner_id, &request.operator_id).await;
+    steps.push(api_keys_step);
+
+    // 3. Cache namespace: start empty
+    let evicted = state.cache.invalidate_pattern(&plan.cache_namespace);
+    steps.push(step(
+        "cache_namespace",
+        StepStatus::Done,
+        format!("{} (evicted {evicted})", plan.cache_namespace),
+    ));
+
+    // 4. NATS subjects
+    steps.push(create_stream(state, &plan).await);
+
+    // 5. Collections and indexes
+    steps.push(create_collections(mongo, &plan).await);
+
+    // 6. hApp installation
+    steps.push(install_happ(state, &request, &mut operator).await);
+
+    operator.steps = steps;
+    operator.status = if operator
+        .steps
+        .iter()
+        .any(|s| s.status == StepStatus::Failed)
+    {
+        OperatorStatus::Partial
+    } else {
+        OperatorStatus::Ready
+    };
+    let steps =
+        bson::to_bson(&operator.steps).map_err(|e| OnboardingError::Database(e.to_string()))?;
+    let status =
+        bson::to_bson(&operator.status).map_err(|e| OnboardingError::Database(e.to_string()))?;
+    let update = doc! {
+        "$set": {
+            "steps": steps,
+            "status": status,
+            "installed_app_id": operator.installed_app_id.clone(),
+            "conductor_id": operator.conductor_id.clone(),
+            "agent_pub_key": operator.agent_pub_key.clone(),
+            "metadata.updated_at": bson::DateTime::now(),
+        }
+    };
+    operators
+        .update_one(doc! { "_id": owner_id }, update)
+        .await
+        .map_err(|e| OnboardingError::Database(e.to_string()))?;
+
+    info!(
+        operator = %operator.operator_id,
+        status = ?operator.status,
+        "Operator provisioned"
+    );
+    Ok(OnboardingOutcome { operator, api_keys })
+}
+
+/// Create the admin and standard keys unless the operator has keys already
+async fn issue_api_keys(
+    mongo: &MongoClient,
+    owner_id: ObjectId,
+    operator_id: &str,
+) -> (OperatorStep, Vec<IssuedApiKey>) {
+    const NAME: &str = "api_keys";
+    let keys = match mongo.collection::<ApiKeyDoc>(API_KEY_COLLECTION).await {
+        Ok(keys) => keys,
+        Err(e) => return (failed(NAME, e), Vec::new()),
+    };
+    match keys
+        .find_one(doc! { "owner_id": owner_id, "metadata.is_deleted": false })
+        .await
+    {
+        Ok(Some(_)) => {
+            let detail = "issued by an earlier run".to_string();
+            return (step(NAME, StepStatus::Done, detail), Vec::new());
+        }
+        Ok(None) => {}
+        Err(e) => return (failed(NAME, e), Vec::new()),
+    }
+
+    let mut issued = Vec::new();
+    for (suffix, level) in [
+        ("admin", PermissionLevel::Admin),
+        ("default", PermissionLevel::Authenticated),
+    ] {
+        let (key, key_hash) = generate_api_key();
+        let name = format!("{operator_id}-{suffix}");
+        let doc = ApiKeyDoc::new(key_hash, name.clone(), owner_id, level);
+        if let Err(e) = keys.insert_one(doc).await {
+            return (failed(NAME, e), Vec::new());
+        }
+        issued.push(IssuedApiKey {
+            name,
+            permission_level: level,
+            key,
+        });
+    }
+    let detail = format!("{} keys", issued.len());
+    (step(NAME, StepStatus::Done, detail), issued)
+}
+
+async fn create_stream(state: &AppState, plan: &OperatorPlan) -> OperatorStep {
+    const NAME: &str = "nats";
+    let Some(ref nats) = state.nats else {
+        return step(NAME, StepStatus::Skipped, "NATS not configured".to_string());
+    };
+    let jetstream = async_nats::jetstream::new(nats.inner().clone());
+    let config = async_nats::jetstream::stream::Config {
+        name: plan.nats_stream.clone(),
+        subjects: plan.nats_subjects.clone(),
+        max_age: STREAM_MAX_AGE,
+        storage: async_nats::jetstream::stream::StorageType::File,
+        ..Default::default()
+    };
+    match jetstream.get_or_create_stream(config).await {
+        Ok(_) => step(NAME, StepStatus::Done, plan.nats_stream.clone()),
+        Err(e) => failed(NAME, e),
+    }
+}
+
+async fn create_collections(mongo: &MongoClient, plan: &OperatorPlan) -> OperatorStep {
+    const NAME: &str = "collections";
+    async fn ensure<T>(mongo: &MongoClient, name: &str) -> Result<(), String>
+    where
+        T: Serialize
+            + serde::de::DeserializeOwned
+            + Unpin
+            + Send
+            + Sync
+            + Default
+            + IntoIndexes
+            + MutMetadata,
+    {
+        mongo
+            .collection::<T>(name)
+            .await
+            .map(|_| ())
+            .map_err(|e| format!("{name}: {e}"))
+    }
+
+    let results = [
+        ensure::<UserDoc>(mongo, &plan.users_collection).await,
+        ensure::<NotificationDoc>(mongo, &plan.notifications_collection).await,
+        ensure::<ModerationItemDoc>(mongo, &plan.moderation_collection).await,
+    ];
+    let errors: Vec<String> = results.into_iter().filter_map(Result::err).collect();
+    if errors.is_empty() {
+        step(NAME, StepStatus::Done, plan.collections().join(", "))
+    } else {
+        failed(NAME, errors.join("; "))
+    }
+}
+
+async fn install_happ(
+    state: &AppState,
+    request: &OnboardOperatorRequest,
+    operator: &mut OperatorDoc,
+) -> OperatorStep {
+    const NAME: &str = "happ";
+    if !request.install_happ {
+        return step(NAME, StepStatus::Skipped, "not requested".to_string());
+    }
+    let Some(ref registry) = state.conductor_registry else {
+        return step(
+            NAME,
+            StepStatus::Skipped,
+            "no conductors registered".to_string(),
+        );
+    };
+
+    let bundle_path = request
+        .bundle_path
+        .clone()
+        .unwrap_or_else(|| state.args.happ_bundle_path.clone());
+    let provisioner = AgentProvisioner::new(Arc::clone(registry))
+        .with_app_id(state.args.installed_app_id.clone())
+        .with_bundle_path(bundle_path);
+    match provisioner
+        .provision_agent(&format!("operator:{}", operator.operator_id))
+        .await
+    {
+        Ok(provisioned) => {
+            let detail = format!(
+                "{} on {}",
+                provisioned.installed_app_id, provisioned.conductor_id
+            );
+            operator.installed_app_id = Some(provisioned.installed_app_id);
+            operator.conductor_id = Some(provisioned.conductor_id);
+            operator.agent_pub_key = Some(provisioned.agent_pub_key);
+            step(NAME, StepStatus::Done, detail)
+        }
+        Err(e) => failed(NAME, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_operator_id() {
+        assert!(validate_operator_id("acme-learning").is_ok());
+        assert!(validate_operator_id("op1").is_ok());
+        for bad in [
+            "ab",
+            "Acme",
+            "acme_learning",
+            "-acme",
+            "acme-",
+            &"a".repeat(33),
+        ] {
+            assert!(validate_operator_id(bad).is_err(), "{bad} accepted");
+        }
+    }
+
+    #[test]
+    fn test_operator_plan_names() {
+        let plan = OperatorPlan::new("acme-learning");
+        assert_eq!(plan.cache_namespace, "op:acme-learning:");
+        assert_eq!(plan.nats_stream, "OPERATOR_ACME_LEARNING");
+        assert_eq!(plan.nats_subjects, vec!["OPERATOR.acme-learning.>"]);
+        assert_eq!(plan.users_collection, "op_acme_learning_users");
+        assert_eq!(plan.collections().len(), 3);
+    }
+
+    #[test]
+    fn test_generated_api_key_matches_hash() {
+        let (key, hash) = generate_api_key();
+        assert!(key.starts_with("ek_"));
+        assert_eq!(hash, hash_api_key(&key));
+        assert_ne!(generate_api_key().0, key);
+    }
+}
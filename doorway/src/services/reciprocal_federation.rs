@@ -0,0 +1,450 @@
+//! Reciprocal Federation
+//!
+//! Lets two doorways run by different communities serve each other's
+//! learners. Each side names the other as a reciprocal peer and pins its
+//! Ed25519 key; a doorway then forwards calls to a short list of public,
+//! read-only content_store functions to the peer, which answers from its own
+//! DHT. Learners browse commons content hosted on the other network without
+//! joining its DHT.
+//!
+//! Calls go over HTTPS to the peer's `POST /federation/v1/call/{fn}`. They
+//! are authenticated by a signature from the calling doorway's key, and
+//! optionally by a client certificate for peers whose ingress requires mTLS.
+//! Answers are limited to commons content: anything carrying a narrower
+//! `reach`, or a license that doesn't allow redistribution, is withheld.
+//!
+//! ## Signing
+//!
+//! The calling doorway signs, with Ed25519, the UTF-8 lines
+//!
+//! ```text
+//! elohim-federation-call:v1
+//! {from_doorway_id}
+//! {to_doorway_id}
+//! {timestamp}
+//! {fn_name}
+//! {hex sha256 of the JSON body}
+//! ```
+//!
+//! joined with `\n`, and sends them in the `X-Doorway-Id`,
+//! `X-Doorway-Timestamp` (Unix seconds) and `X-Doorway-Signature` (base64)
+//! headers.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use super::content_license::{license_status, LicenseStatus};
+use crate::config::Args;
+use crate::routes::content_body::is_public_reach;
+
+/// Version prefix of the signed payload
+const CALL_DOMAIN: &str = "elohim-federation-call:v1";
+
+/// How far a call's timestamp may be from our clock
+const MAX_CLOCK_SKEW_SECS: i64 = 300;
+
+/// Timeout for calls to a peer
+const PEER_TIMEOUT: Duration = Duration::from_secs(15);
+
+pub const DOORWAY_ID_HEADER: &str = "X-Doorway-Id";
+pub const TIMESTAMP_HEADER: &str = "X-Doorway-Timestamp";
+pub const SIGNATURE_HEADER: &str = "X-Doorway-Signature";
+
+/// Why a reciprocal call failed
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ReciprocalError {
+    #[error("Unknown peer doorway '{0}'")]
+    UnknownPeer(String),
+
+    #[error("Function '{0}' is not shared with peers")]
+    NotExported(String),
+
+    #[error("Invalid signature")]
+    InvalidSignature,
+
+    #[error("Call timestamp outside the allowed window")]
+    Stale,
+
+    #[error("Peer call failed: {0}")]
+    Peer(String),
+
+    #[error("Invalid reciprocal federation config: {0}")]
+    Config(String),
+}
+
+/// A doorway we federate with in both directions
+#[derive(Debug, Clone)]
+pub struct ReciprocalPeer {
+    pub doorway_id: String,
+    pub url: String,
+    pub verifying_key: VerifyingKey,
+}
+
+/// Headers of a signed call
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignedCall {
+    pub doorway_id: String,
+    pub timestamp: i64,
+    pub signature: String,
+}
+
+/// Signs outgoing and verifies incoming reciprocal calls
+pub struct ReciprocalFederation {
+    doorway_id: String,
+    signing_key: SigningKey,
+    peers: HashMap<String, ReciprocalPeer>,
+    exported_fns: HashSet<String>,
+    client: reqwest::Client,
+}
+
+impl ReciprocalFederation {
+    /// Reciprocal federation as configured, `None` when no peer is set up
+    pub fn from_args(args: &Args) -> Result<Option<Self>, ReciprocalError> {
+        if args.reciprocal_peers.is_empty() {
+            return Ok(None);
+        }
+        let doorway_id = args
+            .doorway_id
+            .clone()
+            .ok_or_else(|| ReciprocalError::Config("DOORWAY_ID is required".to_string()))?;
+        let seed: [u8; 32] = args
+            .reciprocal_signing_key
+            .as_deref()
+            .and_then(|key| BASE64.decode(key.trim()).ok())
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| {
+                ReciprocalError::Config(
+                    "RECIPROCAL_SIGNING_KEY must be a base64 32-byte Ed25519 seed".to_string(),
+                )
+            })?;
+
+        let mut peers = HashMap::new();
+        for entry in &args.reciprocal_peers {
+            let peer = parse_peer(entry)?;
+            peers.insert(peer.doorway_id.clone(), peer);
+        }
+
+        let mut client = reqwest::Client::builder().timeout(PEER_TIMEOUT);
+        if let Some(ref path) = args.reciprocal_client_cert {
+            let pem = std::fs::read(path).map_err(|e| {
+                ReciprocalError::Config(format!("can't read client certificate {path}: {e}"))
+            })?;
+            let identity = reqwest::Identity::from_pem(&pem).map_err(|e| {
+                ReciprocalError::Config(format!("invalid client certificate {path}: {e}"))
+            })?;
+            client = client.identity(identity);
+        }
+        let client = client
+            .build()
+            .map_err(|e| ReciprocalError::Config(e.to_string()))?;
+
+        Ok(Some(Self {
+            doorway_id,
+            signing_key: SigningKey::from_bytes(&seed),
+            peers,
+            exported_fns: args.reciprocal_exported_fns.iter().cloned().collect(),
+            client,
+        }))
+    }
+
+    /// Our public key, for peers to pin
+    pub fn verifying_key(&self) -> VerifyingKey {
+        self.signing_key.verifying_key()
+    }
+
+    pub fn peers(&self) -> impl Iterator<Item = &ReciprocalPeer> {
+        self.peers.values()
+    }
+
+    pub fn is_exported(&self, fn_name: &str) -> bool {
+        self.exported_fns.contains(fn_name)
+    }
+
+    /// Exported functions, sorted
+    pub fn exported_fns(&self) -> Vec<&str> {
+        let mut fns: Vec<&str> = self.exported_fns.iter().map(String::as_str).collect();
+        fns.sort_unstable();
+        fns
+    }
+
+    /// Sign a call to `to_doorway_id`. `now` is in Unix seconds.
+    pub fn sign_call(
+        &self,
+        to_doorway_id: &str,
+        fn_name: &str,
+        body: &[u8],
+        now: i64,
+    ) -> SignedCall {
+        let payload = signing_payload(&self.doorway_id, to_doorway_id, now, fn_name, body);
+        SignedCall {
+            doorway_id: self.doorway_id.clone(),
+            timestamp: now,
+            signature: BASE64.encode(self.signing_key.sign(&payload).to_bytes()),
+        }
+    }
+
+    /// Check an incoming call was signed by a peer for us, for a function
+    /// we share. `now` is in Unix seconds.
+    pub fn verify_call(
+        &self,
+        call: &SignedCall,
+        fn_name: &str,
+        body: &[u8],
+        now: i64,
+    ) -> Result<&ReciprocalPeer, ReciprocalError> {
+        let peer = self
+            .peers
+            .get(&call.doorway_id)
+            .ok_or_else(|| ReciprocalError::UnknownPeer(call.doorway_id.clone()))?;
+        let signature = BASE64
+            .decode(&call.signature)
+            .ok()
+            .and_then(|bytes| Signature::from_slice(&bytes).ok())
+            .ok_or(ReciprocalError::InvalidSignature)?;
+        let payload = signing_payload(
+            &call.doorway_id,
+            &self.doorway_id,
+            call.timestamp,
+            fn_name,
+            body,
+        );
+        peer.verifying_key
+            .verify_strict(&payload, &signature)
+            .map_err(|_| ReciprocalError::InvalidSignature)?;
+        if (now - call.timestamp).abs() > MAX_CLOCK_SKEW_SECS {
+            return Err(ReciprocalError::Stale);
+        }
+        if !self.is_exported(fn_name) {
+            return Err(ReciprocalError::NotExported(fn_name.to_string()));
+        }
+        Ok(peer)
+    }
+
+    /// Call a shared function on a peer doorway
+    pub async fn call_peer(
+        &self,
+        peer_id: &str,
+        fn_name: &str,
+        input: &Value,
+    ) -> Result<Value, ReciprocalError> {
+        let peer = self
+            .peers
+            .get(peer_id)
+            .ok_or_else(|| ReciprocalError::UnknownPeer(peer_id.to_string()))?;
+        let body = serde_json::to_vec(input).map_err(|e| ReciprocalError::Peer(e.to_string()))?;
+        let call = self.sign_call(peer_id, fn_name, &body, chrono::Utc::now().timestamp());
+
+        let url = format!(
+            "{}/federation/v1/call/{}",
+            peer.url.trim_end_matches('/'),
+            fn_name
+        );
+        let response = self
+            .client
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .header("X-Federation-Hop", "1")
+            .header(DOORWAY_ID_HEADER, &call.doorway_id)
+            .header(TIMESTAMP_HEADER, call.timestamp.to_string())
+            .header(SIGNATURE_HEADER, &call.signature)
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| ReciprocalError::Peer(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(ReciprocalError::Peer(format!(
+                "{} answered {}",
+                peer_id,
+                response.status()
+            )));
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| ReciprocalError::Peer(e.to_string()))
+    }
+}
+
+/// Bytes a calling doorway signs
+pub fn signing_payload(
+    from: &str,
+    to: &str,
+    timestamp: i64,
+    fn_name: &str,
+    body: &[u8],
+) -> Vec<u8> {
+    [
+        CALL_DOMAIN.to_string(),
+        from.to_string(),
+        to.to_string(),
+        timestamp.to_string(),
+        fn_name.to_string(),
+        hex::encode(Sha256::digest(body)),
+    ]
+    .join("\n")
+    .into_bytes()
+}
+
+/// Drop whatever isn't commons content from a zome answer before it leaves
+/// the network. Records carry `reach` and license metadata themselves or one
+/// level down (e.g. `{action_hash, content: {reach, ..}}`); withheld records
+/// become `null` on their own and are removed from lists.
+pub fn commons_only(value: Value) -> Value {
+    match value {
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .filter(|item| !is_restricted(item))
+                .collect(),
+        ),
+        item if is_restricted(&item) => Value::Null,
+        item => item,
+    }
+}
+
+fn is_restricted(value: &Value) -> bool {
+    let Value::Object(map) = value else {
+        return false;
+    };
+    let restricted_reach =
+        |reach: &Value| reach.as_str().is_some_and(|reach| !is_public_reach(reach));
+    let restricted_license = |record: &Value| license_status(record) == LicenseStatus::Restricted;
+    map.get("reach").is_some_and(restricted_reach)
+        || restricted_license(value)
+        || map.values().any(|field| {
+            field.get("reach").is_some_and(restricted_reach) || restricted_license(field)
+        })
+}
+
+/// Parse a `doorway_id|url|base64key` peer entry
+fn parse_peer(entry: &str) -> Result<ReciprocalPeer, ReciprocalError> {
+    let mut parts = entry.split('|').map(str::trim);
+    let (Some(doorway_id), Some(url), Some(key), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(ReciprocalError::Config(format!(
+            "expected doorway_id|url|key, got '{entry}'"
+        )));
+    };
+    let bytes: [u8; 32] = BASE64
+        .decode(key)
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| ReciprocalError::Config(format!("invalid key for '{doorway_id}'")))?;
+    let verifying_key = VerifyingKey::from_bytes(&bytes)
+        .map_err(|_| ReciprocalError::Config(format!("invalid key for '{doorway_id}'")))?;
+    Ok(ReciprocalPeer {
+        doorway_id: doorway_id.to_string(),
+        url: url.to_string(),
+        verifying_key,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use serde_json::json;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn key_b64(seed: u8) -> String {
+        BASE64.encode(
+            SigningKey::from_bytes(&[seed; 32])
+                .verifying_key()
+                .to_bytes(),
+        )
+    }
+
+    /// Doorway `id` with seed `seed`, peered with `peer_id` (seed `peer_seed`)
+    fn doorway(id: &str, seed: u8, peer_id: &str, peer_seed: u8) -> ReciprocalFederation {
+        let args = Args::parse_from([
+            "doorway",
+            "--doorway-id",
+            id,
+            "--reciprocal-signing-key",
+            &BASE64.encode([seed; 32]),
+            "--reciprocal-peers",
+            &format!("{peer_id}|https://{peer_id}.example|{}", key_b64(peer_seed)),
+        ]);
+        ReciprocalFederation::from_args(&args).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_call_between_peers() {
+        let alpha = doorway("alpha", 1, "beta", 2);
+        let beta = doorway("beta", 2, "alpha", 1);
+        let body = br#"{"id":"intro"}"#;
+
+        let call = alpha.sign_call("beta", "get_content_by_id", body, NOW);
+        let peer = beta
+            .verify_call(&call, "get_content_by_id", body, NOW + 10)
+            .unwrap();
+        assert_eq!(peer.doorway_id, "alpha");
+    }
+
+    #[test]
+    fn test_call_rejected() {
+        let alpha = doorway("alpha", 1, "beta", 2);
+        let beta = doorway("beta", 2, "alpha", 1);
+        let body = br#"{"id":"intro"}"#;
+        let call = alpha.sign_call("beta", "get_content_by_id", body, NOW);
+
+        assert!(matches!(
+            beta.verify_call(&call, "get_content_by_id", br#"{"id":"other"}"#, NOW),
+            Err(ReciprocalError::InvalidSignature)
+        ));
+        assert!(matches!(
+            alpha.verify_call(&call, "get_content_by_id", body, NOW),
+            Err(ReciprocalError::UnknownPeer(_))
+        ));
+        assert!(matches!(
+            beta.verify_call(&call, "get_content_by_id", body, NOW + 3600),
+            Err(ReciprocalError::Stale)
+        ));
+
+        let write = alpha.sign_call("beta", "create_content", body, NOW);
+        assert!(matches!(
+            beta.verify_call(&write, "create_content", body, NOW),
+            Err(ReciprocalError::NotExported(_))
+        ));
+    }
+
+    #[test]
+    fn test_commons_only() {
+        let list = json!([
+            {"action_hash": "a", "content": {"id": "open", "reach": "commons"}},
+            {"action_hash": "b", "content": {"id": "mine", "reach": "private"}},
+            {"id": "path-1", "reach": "community"},
+            {"id": "no-reach"},
+            {"action_hash": "c", "content": {
+                "id": "nc",
+                "reach": "commons",
+                "metadata_json": "{\"license\":\"CC-BY-NC-4.0\"}"
+            }}
+        ]);
+        let shared = commons_only(list);
+        let ids: Vec<&Value> = shared.as_array().unwrap().iter().collect();
+        assert_eq!(ids.len(), 2);
+        assert_eq!(ids[0]["content"]["id"], "open");
+        assert_eq!(ids[1]["id"], "no-reach");
+
+        assert_eq!(
+            commons_only(json!({"content": {"reach": "private"}})),
+            Value::Null
+        );
+    }
+
+    #[test]
+    fn test_not_configured_by_default() {
+        let args = Args::parse_from(["doorway"]);
+        assert!(ReciprocalFederation::from_args(&args).unwrap().is_none());
+
+        let args = Args::parse_from(["doorway", "--reciprocal-peers", "beta|https://beta"]);
+        assert!(ReciprocalFederation::from_args(&args).is_err());
+    }
+}
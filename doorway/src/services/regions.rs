@@ -0,0 +1,298 @@
+//! Region awareness
+//!
+//! Lets apps reach the nearest healthy doorway when doorways run in several
+//! regions. A doorway with `REGION` set (and NATS) announces a
+//! [`DoorwayPresence`] on `DOORWAY.PRESENCE.{region}` every
+//! `REGION_PRESENCE_INTERVAL_SECS`, and keeps a directory of the doorways it
+//! hears from. Doorways silent for three intervals are forgotten.
+//!
+//! `GET /region-hint` (see [`routes::region_hint`](crate::routes::region_hint))
+//! ranks the healthy doorways for a client:
+//!
+//! 1. Nearest first, when both the client (`lat`/`lon`) and the doorway
+//!    (`REGION_LOCATION`) have a location
+//! 2. Then doorways in the client's region (`region` or `X-Client-Region`),
+//!    or in this doorway's region when the client names none
+//! 3. Then this doorway, so clients aren't moved without a reason
+//! 4. Then the fewest conductor calls in flight
+//!
+//! The same doorways share cache invalidations over NATS, so a write seen in
+//! one region evicts stale reads everywhere (see
+//! [`worker::regions`](crate::worker::regions)).
+
+use dashmap::DashMap;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::time::{Duration, Instant};
+
+use crate::config::Args;
+pub use crate::nats::messages::{DoorwayPresence, GeoPoint};
+
+/// Presence intervals a doorway may miss before it is forgotten
+const MISSED_INTERVALS: u32 = 3;
+
+/// Mean Earth radius in kilometres
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+impl GeoPoint {
+    /// Parse "latitude,longitude"
+    pub fn parse(value: &str) -> Option<Self> {
+        let (lat, lon) = value.split_once(',')?;
+        Self::new(lat.trim().parse().ok()?, lon.trim().parse().ok()?)
+    }
+
+    pub fn new(lat: f64, lon: f64) -> Option<Self> {
+        ((-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon))
+            .then_some(Self { lat, lon })
+    }
+
+    /// Great-circle distance
+    pub fn distance_km(&self, other: &GeoPoint) -> f64 {
+        let (lat1, lat2) = (self.lat.to_radians(), other.lat.to_radians());
+        let dlat = lat2 - lat1;
+        let dlon = (other.lon - self.lon).to_radians();
+        let a = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+    }
+}
+
+/// What a client tells us about where it is
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RegionHint {
+    pub region: Option<String>,
+    pub location: Option<GeoPoint>,
+}
+
+/// A doorway as suggested to a client
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RankedDoorway {
+    pub node_id: String,
+    pub region: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distance_km: Option<f64>,
+    /// The doorway answering
+    pub current: bool,
+}
+
+/// This doorway's identity and the doorways heard from
+pub struct RegionDirectory {
+    node_id: String,
+    region: String,
+    url: Option<String>,
+    location: Option<GeoPoint>,
+    interval: Duration,
+    peers: DashMap<String, (DoorwayPresence, Instant)>,
+}
+
+impl RegionDirectory {
+    /// Directory as configured, `None` when `REGION` is unset
+    pub fn from_args(args: &Args) -> Result<Option<Self>, String> {
+        let Some(region) = args.region.clone().filter(|r| !r.is_empty()) else {
+            return Ok(None);
+        };
+        let location = match args.region_location.as_deref() {
+            Some(value) => Some(GeoPoint::parse(value).ok_or_else(|| {
+                format!("REGION_LOCATION must be \"latitude,longitude\", got {value:?}")
+            })?),
+            None => None,
+        };
+        Ok(Some(Self::new(
+            args.node_id.to_string(),
+            region,
+            args.doorway_url.clone(),
+            location,
+            Duration::from_secs(args.region_presence_interval_secs.max(1)),
+        )))
+    }
+
+    pub fn new(
+        node_id: String,
+        region: String,
+        url: Option<String>,
+        location: Option<GeoPoint>,
+        interval: Duration,
+    ) -> Self {
+        Self {
+            node_id,
+            region,
+            url,
+            location,
+            interval,
+            peers: DashMap::new(),
+        }
+    }
+
+    pub fn node_id(&self) -> &str {
+        &self.node_id
+    }
+
+    pub fn region(&self) -> &str {
+        &self.region
+    }
+
+    /// Interval between presence announcements
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// This doorway's announcement
+    pub fn presence(&self, healthy: bool, in_flight: usize) -> DoorwayPresence {
+        DoorwayPresence {
+            node_id: self.node_id.clone(),
+            region: self.region.clone(),
+            url: self.url.clone(),
+            location: self.location,
+            healthy,
+            in_flight,
+            version: Some(env!("CARGO_PKG_VERSION").to_string()),
+        }
+    }
+
+    /// Record another doorway's announcement
+    pub fn observe(&self, presence: DoorwayPresence, now: Instant) {
+        if presence.node_id != self.node_id {
+            self.peers.insert(presence.node_id.clone(), (presence, now));
+        }
+    }
+
+    /// Doorways heard from recently, forgetting the rest
+    pub fn peers(&self, now: Instant) -> Vec<DoorwayPresence> {
+        let expiry = self.interval * MISSED_INTERVALS;
+        self.peers
+            .retain(|_, (_, seen)| now.duration_since(*seen) < expiry);
+        self.peers.iter().map(|entry| entry.0.clone()).collect()
+    }
+
+    /// Healthy doorways ranked for a client, best first
+    ///
+    /// `own` is this doorway's current presence. It is suggested even when
+    /// unhealthy if no other doorway is healthy.
+    pub fn rank(
+        &self,
+        own: DoorwayPresence,
+        hint: &RegionHint,
+        now: Instant,
+    ) -> Vec<RankedDoorway> {
+        let own_fallback = own.clone();
+        let mut doorways: Vec<DoorwayPresence> = std::iter::once(own)
+            .chain(self.peers(now))
+            .filter(|doorway| doorway.healthy)
+            .collect();
+        if doorways.is_empty() {
+            doorways.push(own_fallback);
+        }
+
+        let region = hint.region.as_deref().unwrap_or(&self.region);
+        let distance = |doorway: &DoorwayPresence| {
+            Some(hint.location?.distance_km(doorway.location.as_ref()?))
+        };
+        let key = |doorway: &DoorwayPresence| {
+            (
+                distance(doorway).unwrap_or(f64::INFINITY),
+                doorway.region != region,
+                doorway.node_id != self.node_id,
+                doorway.in_flight,
+            )
+        };
+        doorways.sort_by(|a, b| {
+            let (a, b) = (key(a), key(b));
+            a.0.partial_cmp(&b.0)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| (a.1, a.2, a.3).cmp(&(b.1, b.2, b.3)))
+        });
+
+        doorways
+            .into_iter()
+            .map(|doorway| RankedDoorway {
+                distance_km: distance(&doorway).map(|km| km.round()),
+                current: doorway.node_id == self.node_id,
+                node_id: doorway.node_id,
+                region: doorway.region,
+                url: doorway.url,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BERLIN: GeoPoint = GeoPoint {
+        lat: 52.52,
+        lon: 13.40,
+    };
+    const OREGON: GeoPoint = GeoPoint {
+        lat: 45.52,
+        lon: -122.68,
+    };
+
+    fn peer(node_id: &str, region: &str, location: Option<GeoPoint>) -> DoorwayPresence {
+        DoorwayPresence {
+            node_id: node_id.to_string(),
+            region: region.to_string(),
+            url: Some(format!("https://{node_id}.example")),
+            location,
+            healthy: true,
+            in_flight: 0,
+            version: None,
+        }
+    }
+
+    fn directory() -> RegionDirectory {
+        RegionDirectory::new(
+            "here".to_string(),
+            "us-west".to_string(),
+            None,
+            Some(OREGON),
+            Duration::from_secs(15),
+        )
+    }
+
+    fn order(ranked: &[RankedDoorway]) -> Vec<&str> {
+        ranked.iter().map(|d| d.node_id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_geo_point() {
+        assert_eq!(GeoPoint::parse("52.52, 13.40"), Some(BERLIN));
+        assert_eq!(GeoPoint::parse("91,0"), None);
+        assert_eq!(GeoPoint::parse("berlin"), None);
+        let km = BERLIN.distance_km(&OREGON);
+        assert!((8_300.0..8_500.0).contains(&km), "{km}");
+    }
+
+    #[test]
+    fn test_rank_nearest_healthy() {
+        let directory = directory();
+        let now = Instant::now();
+        let own = directory.presence(true, 5);
+        directory.observe(peer("eu", "eu-central", Some(BERLIN)), now);
+        directory.observe(peer("west-2", "us-west", None), now);
+
+        // No hint: stay in region, this doorway first
+        let ranked = directory.rank(own.clone(), &RegionHint::default(), now);
+        assert_eq!(order(&ranked), ["here", "west-2", "eu"]);
+        assert!(ranked[0].current);
+
+        // A client in Berlin goes to the doorway there
+        let berlin = RegionHint {
+            location: Some(BERLIN),
+            ..Default::default()
+        };
+        let ranked = directory.rank(own.clone(), &berlin, now);
+        assert_eq!(ranked[0].node_id, "eu");
+        assert_eq!(ranked[0].distance_km, Some(0.0));
+
+        // Unhealthy and silent doorways are left out
+        let mut sick = peer("eu", "eu-central", Some(BERLIN));
+        sick.healthy = false;
+        directory.observe(sick, now);
+        let later = now + Duration::from_secs(50);
+        directory.observe(peer("west-3", "us-west", None), later);
+        let ranked = directory.rank(own, &berlin, later);
+        assert_eq!(order(&ranked), ["here", "west-3"]);
+    }
+}
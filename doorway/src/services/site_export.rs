@@ -0,0 +1,840 @@
+//! Static site export
+//!
+//! `doorway export-site` walks public learning paths and commons content and
+//! writes a self-contained bundle that can be served from a CDN or copied to
+//! a USB stick for low-connectivity deployments. Nothing in it needs a
+//! running doorway or conductor.
+//!
+//! ## Layout
+//!
+//! ```text
+//! index.json            Site manifest (paths, counts, generation time)
+//! index.html            Path listing
+//! paths/{id}.json       Path manifest: metadata + ordered steps
+//! paths/{id}.html
+//! content/{id}.json     Content payload (entry as stored, plus its blobs)
+//! content/{id}.html
+//! media-manifest.json   Every referenced blob with size, mime type and URLs
+//! media/{hash}          Blob bytes (only with --include-media)
+//! ```
+//!
+//! Only paths with `public`/`published` visibility and content with
+//! `commons` reach are exported. Content whose license doesn't allow
+//! redistribution (see [`content_license`](super::content_license)) is left
+//! out too; exported content carries its `license`. A public path step
+//! pointing at content that wasn't exported stays in the manifest with
+//! `content: null`. HTML links are relative so the bundle works from
+//! `file://`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{info, warn};
+
+use super::content_license::{license_of, license_status, LicenseStatus};
+use super::verification::compute_sha256;
+use super::ZomeCaller;
+use crate::routes::content::QueryContentInput;
+use crate::routes::preview::escape_html;
+
+const CONTENT_ROLE: &str = "lamad";
+const CONTENT_ZOME: &str = "content_store";
+
+/// Reach of exported content
+const EXPORT_REACH: &str = "commons";
+
+/// Path visibilities that are exported
+const PUBLIC_VISIBILITY: [&str; 2] = ["public", "published"];
+
+/// Page size for query_content (zome maximum)
+const PAGE_SIZE: u32 = 100;
+
+/// Largest blob copied into the bundle
+const MAX_MEDIA_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+/// Timeout for one blob download
+const MEDIA_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Options for one export run
+#[derive(Debug, Clone)]
+pub struct SiteExportConfig {
+    /// Output directory (created if missing)
+    pub out_dir: PathBuf,
+    /// Download blob bytes into `media/`
+    pub include_media: bool,
+    /// elohim-storage base URL, tried before a blob's fallback URLs
+    pub storage_url: Option<String>,
+}
+
+/// What an export wrote
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct ExportSummary {
+    pub paths: usize,
+    pub content: usize,
+    pub media: usize,
+    pub media_downloaded: usize,
+    pub media_failed: usize,
+    /// Commons content left out because its license forbids redistribution
+    pub withheld_by_license: usize,
+}
+
+// =============================================================================
+// Zome types (subsets)
+// =============================================================================
+
+/// Subset of PathIndex in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Clone, Deserialize)]
+struct PathIndex {
+    paths: Vec<PathIndexEntry>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct PathIndexEntry {
+    id: String,
+}
+
+/// PathReadInput::WithOptions in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Clone, Serialize)]
+struct PathReadInput<'a> {
+    path_id: &'a str,
+    include_content: bool,
+}
+
+/// Subset of PathWithSteps; entries are kept as JSON
+#[derive(Debug, Clone, Deserialize)]
+struct PathWithSteps {
+    path: Value,
+    steps: Vec<PathStepOutput>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct PathStepOutput {
+    step: Value,
+    #[serde(default)]
+    content: Option<ContentOutput>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ContentOutput {
+    content: Value,
+}
+
+/// Subset of ContentStats
+#[derive(Debug, Clone, Deserialize)]
+struct ContentStats {
+    by_type: BTreeMap<String, u32>,
+}
+
+/// Subset of PaginatedContentOutput
+#[derive(Debug, Clone, Deserialize)]
+struct PaginatedContentOutput {
+    items: Vec<ContentOutput>,
+    has_more: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct QueryBlobsByContentIdInput<'a> {
+    content_id: &'a str,
+}
+
+/// Subset of BlobMetadataOutput
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportBlob {
+    pub hash: String,
+    pub size_bytes: u64,
+    pub mime_type: String,
+    pub fallback_urls: Vec<String>,
+    pub reach: String,
+}
+
+// =============================================================================
+// Bundle model
+// =============================================================================
+
+/// One step in a path manifest
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StepManifest {
+    pub order_index: u64,
+    pub step_type: String,
+    pub resource_id: String,
+    pub title: Option<String>,
+    pub is_optional: bool,
+    pub estimated_minutes: Option<u64>,
+    /// Relative path of the exported content payload
+    pub content: Option<String>,
+}
+
+/// `paths/{id}.json`
+#[derive(Debug, Clone, Serialize)]
+pub struct PathManifest {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub difficulty: Option<String>,
+    pub estimated_duration: Option<String>,
+    pub tags: Vec<String>,
+    pub steps: Vec<StepManifest>,
+}
+
+/// One entry in `index.json`
+#[derive(Debug, Clone, Serialize)]
+pub struct PathListing {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub step_count: usize,
+    pub manifest: String,
+}
+
+/// `index.json`
+#[derive(Debug, Clone, Serialize)]
+pub struct SiteIndex {
+    pub generated_at: String,
+    pub paths: Vec<PathListing>,
+    pub content_count: usize,
+    pub media_count: usize,
+}
+
+/// One entry in `media-manifest.json`
+#[derive(Debug, Clone, Serialize)]
+pub struct MediaEntry {
+    pub hash: String,
+    pub size_bytes: u64,
+    pub mime_type: String,
+    pub content_ids: BTreeSet<String>,
+    pub urls: Vec<String>,
+    /// Relative path of the bundled copy (only with --include-media)
+    pub file: Option<String>,
+}
+
+pub(crate) fn str_field(value: &Value, key: &str) -> Option<String> {
+    value
+        .get(key)
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+}
+
+/// Whether a LearningPath entry is exported
+pub fn is_public_path(path: &Value) -> bool {
+    str_field(path, "visibility").is_some_and(|v| PUBLIC_VISIBILITY.contains(&v.as_str()))
+}
+
+/// Whether a Content entry has commons reach
+pub fn is_commons_content(content: &Value) -> bool {
+    str_field(content, "reach").as_deref() == Some(EXPORT_REACH)
+}
+
+/// Whether a Content entry is exported: commons reach, and a license (if
+/// any) that allows redistribution
+pub fn is_exportable_content(content: &Value) -> bool {
+    is_commons_content(content) && license_status(content) != LicenseStatus::Restricted
+}
+
+/// File-system safe name for an id (ids may contain `/` or `:`)
+pub fn file_stem(id: &str) -> String {
+    id.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>()
+        .trim_start_matches('.')
+        .to_string()
+}
+
+fn content_file(id: &str) -> String {
+    format!("content/{}.json", file_stem(id))
+}
+
+fn media_file(hash: &str) -> String {
+    format!("media/{}", file_stem(hash))
+}
+
+/// Build a path manifest; `exported` holds the ids of exported content
+pub fn path_manifest(path: &Value, steps: &[Value], exported: &BTreeSet<String>) -> PathManifest {
+    let mut steps: Vec<StepManifest> = steps
+        .iter()
+        .map(|step| {
+            let resource_id = str_field(step, "resource_id").unwrap_or_default();
+            StepManifest {
+                order_index: step
+                    .get("order_index")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(0),
+                step_type: str_field(step, "step_type").unwrap_or_else(|| "content".to_string()),
+                title: str_field(step, "step_title"),
+                is_optional: step
+                    .get("is_optional")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false),
+                estimated_minutes: step.get("estimated_minutes").and_then(|v| v.as_u64()),
+                content: exported
+                    .contains(&resource_id)
+                    .then(|| content_file(&resource_id)),
+                resource_id,
+            }
+        })
+        .collect();
+    steps.sort_by_key(|s| s.order_index);
+
+    PathManifest {
+        id: str_field(path, "id").unwrap_or_default(),
+        title: str_field(path, "title").unwrap_or_default(),
+        description: str_field(path, "description").unwrap_or_default(),
+        difficulty: str_field(path, "difficulty"),
+        estimated_duration: str_field(path, "estimated_duration"),
+        tags: path
+            .get("tags")
+            .and_then(|t| t.as_array())
+            .map(|tags| {
+                tags.iter()
+                    .filter_map(|t| t.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        steps,
+    }
+}
+
+// =============================================================================
+// HTML
+// =============================================================================
+
+fn html_page(title: &str, body: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title}</title>
+<style>body{{font-family:sans-serif;max-width:48rem;margin:2rem auto;padding:0 1rem;line-height:1.5}}.body{{white-space:pre-wrap}}</style>
+</head>
+<body>
+{body}
+</body>
+</html>
+"#,
+        title = escape_html(title)
+    )
+}
+
+/// Render `index.html`
+pub fn render_index_html(index: &SiteIndex) -> String {
+    let mut body = String::from("<h1>Learning paths</h1>\n<ul>\n");
+    for path in &index.paths {
+        body.push_str(&format!(
+            "<li><a href=\"paths/{}.html\">{}</a> <small>({} steps)</small><br>{}</li>\n",
+            escape_html(&file_stem(&path.id)),
+            escape_html(&path.title),
+            path.step_count,
+            escape_html(&path.description)
+        ));
+    }
+    body.push_str("</ul>\n");
+    html_page("Learning paths", &body)
+}
+
+/// Render `paths/{id}.html`
+pub fn render_path_html(manifest: &PathManifest, titles: &BTreeMap<String, String>) -> String {
+    let mut body = format!(
+        "<p><a href=\"../index.html\">All paths</a></p>\n<h1>{}</h1>\n<p>{}</p>\n<ol>\n",
+        escape_html(&manifest.title),
+        escape_html(&manifest.description)
+    );
+    for step in &manifest.steps {
+        let title = step
+            .title
+            .clone()
+            .or_else(|| titles.get(&step.resource_id).cloned())
+            .unwrap_or_else(|| step.resource_id.clone());
+        match step.content {
+            Some(_) => body.push_str(&format!(
+                "<li><a href=\"../content/{}.html\">{}</a></li>\n",
+                escape_html(&file_stem(&step.resource_id)),
+                escape_html(&title)
+            )),
+            None => body.push_str(&format!("<li>{}</li>\n", escape_html(&title))),
+        }
+    }
+    body.push_str("</ol>\n");
+    html_page(&manifest.title, &body)
+}
+
+/// Render `content/{id}.html`; the body is shown as text, never as markup
+pub fn render_content_html(content: &Value, media: &[MediaEntry]) -> String {
+    let title = str_field(content, "title").unwrap_or_default();
+    let mut body = format!(
+        "<p><a href=\"../index.html\">All paths</a></p>\n<h1>{}</h1>\n",
+        escape_html(&title)
+    );
+    if let Some(description) = str_field(content, "description") {
+        body.push_str(&format!("<p>{}</p>\n", escape_html(&description)));
+    }
+    if let Some(license) = license_of(content) {
+        body.push_str(&format!(
+            "<p><small>License: {}</small></p>\n",
+            escape_html(&license)
+        ));
+    }
+    for entry in media {
+        let src = entry
+            .file
+            .as_ref()
+            .map(|f| format!("../{f}"))
+            .or_else(|| entry.urls.first().cloned());
+        let Some(src) = src else { continue };
+        let src = escape_html(&src);
+        let tag = if entry.mime_type.starts_with("video/") {
+            format!("<video controls src=\"{src}\"></video>")
+        } else if entry.mime_type.starts_with("audio/") {
+            format!("<audio controls src=\"{src}\"></audio>")
+        } else if entry.mime_type.starts_with("image/") {
+            format!("<img alt=\"\" src=\"{src}\">")
+        } else {
+            format!("<a href=\"{src}\">{}</a>", escape_html(&entry.hash))
+        };
+        body.push_str(&format!("<p>{tag}</p>\n"));
+    }
+    if let Some(text) = str_field(content, "content") {
+        body.push_str(&format!(
+            "<div class=\"body\">{}</div>\n",
+            escape_html(&text)
+        ));
+    }
+    html_page(&title, &body)
+}
+
+// =============================================================================
+// Export
+// =============================================================================
+
+fn write_file(root: &Path, relative: &str, data: &[u8]) -> Result<(), String> {
+    let target = root.join(relative);
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+    std::fs::write(&target, data).map_err(|e| format!("Failed to write {}: {e}", target.display()))
+}
+
+fn write_json<T: Serialize>(root: &Path, relative: &str, value: &T) -> Result<(), String> {
+    let data = serde_json::to_vec_pretty(value)
+        .map_err(|e| format!("Failed to serialize {relative}: {e}"))?;
+    write_file(root, relative, &data)
+}
+
+/// Walks the DHT and writes the bundle
+pub struct SiteExporter<'a> {
+    zome_caller: &'a ZomeCaller,
+    config: SiteExportConfig,
+    client: reqwest::Client,
+}
+
+impl<'a> SiteExporter<'a> {
+    pub fn new(zome_caller: &'a ZomeCaller, config: SiteExportConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(MEDIA_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        Self {
+            zome_caller,
+            config,
+            client,
+        }
+    }
+
+    /// Public paths with their steps (content included)
+    async fn load_paths(&self) -> Result<Vec<PathWithSteps>, String> {
+        let index: PathIndex = self
+            .zome_caller
+            .call(CONTENT_ROLE, CONTENT_ZOME, "get_all_paths", &())
+            .await?;
+
+        let mut paths = Vec::new();
+        for entry in index.paths {
+            let input = PathReadInput {
+                path_id: &entry.id,
+                include_content: true,
+            };
+            match self
+                .zome_caller
+                .call::<_, Option<PathWithSteps>>(
+                    CONTENT_ROLE,
+                    CONTENT_ZOME,
+                    "get_path_with_steps",
+                    &input,
+                )
+                .await
+            {
+                Ok(Some(path)) if is_public_path(&path.path) => paths.push(path),
+                Ok(_) => {}
+                Err(e) => warn!(path_id = %entry.id, error = %e, "Skipping path"),
+            }
+        }
+        Ok(paths)
+    }
+
+    /// All commons content, one content type at a time
+    async fn load_commons_content(&self) -> Result<Vec<Value>, String> {
+        let stats: ContentStats = self
+            .zome_caller
+            .call(CONTENT_ROLE, CONTENT_ZOME, "get_content_stats", &None::<()>)
+            .await?;
+
+        let mut content = Vec::new();
+        for content_type in stats.by_type.into_keys() {
+            let mut offset = 0;
+            loop {
+                let input = QueryContentInput {
+                    content_type: Some(content_type.clone()),
+                    tags_all: Vec::new(),
+                    tags_any: Vec::new(),
+                    reach: Some(EXPORT_REACH.to_string()),
+                    author: None,
+                    created_after: None,
+                    sort: None,
+                    page_size: PAGE_SIZE,
+                    offset,
+                };
+                let page: PaginatedContentOutput = self
+                    .zome_caller
+                    .call(CONTENT_ROLE, CONTENT_ZOME, "query_content", &input)
+                    .await?;
+                offset += page.items.len() as u32;
+                content.extend(page.items.into_iter().map(|item| item.content));
+                if !page.has_more || offset == 0 {
+                    break;
+                }
+            }
+        }
+        Ok(content)
+    }
+
+    async fn load_blobs(&self, content_id: &str) -> Vec<ExportBlob> {
+        match self
+            .zome_caller
+            .call::<_, Vec<ExportBlob>>(
+                CONTENT_ROLE,
+                CONTENT_ZOME,
+                "get_blobs_by_content_id",
+                &QueryBlobsByContentIdInput { content_id },
+            )
+            .await
+        {
+            Ok(blobs) => blobs
+                .into_iter()
+                .filter(|b| b.reach == EXPORT_REACH)
+                .collect(),
+            Err(e) => {
+                warn!(content_id, error = %e, "Failed to load blobs");
+                Vec::new()
+            }
+        }
+    }
+
+    /// Candidate download URLs for a blob
+    fn media_urls(&self, blob: &ExportBlob) -> Vec<String> {
+        let mut urls = Vec::new();
+        if let Some(ref storage) = self.config.storage_url {
+            urls.push(format!(
+                "{}/blob/{}",
+                storage.trim_end_matches('/'),
+                blob.hash
+            ));
+        }
+        urls.extend(blob.fallback_urls.iter().cloned());
+        urls
+    }
+
+    /// Download a blob and check it against its hash
+    async fn download(&self, entry: &MediaEntry, urls: &[String]) -> Result<Vec<u8>, String> {
+        if entry.size_bytes > MAX_MEDIA_BYTES {
+            return Err(format!("Blob exceeds {MAX_MEDIA_BYTES} bytes"));
+        }
+        let digest = entry.hash.strip_prefix("sha256-").unwrap_or(&entry.hash);
+
+        let mut last_error = "No URLs".to_string();
+        for url in urls {
+            let data = match self.client.get(url).send().await {
+                Ok(response) if response.status().is_success() => match response.bytes().await {
+                    Ok(data) => data,
+                    Err(e) => {
+                        last_error = format!("{url}: {e}");
+                        continue;
+                    }
+                },
+                Ok(response) => {
+                    last_error = format!("{url}: HTTP {}", response.status());
+                    continue;
+                }
+                Err(e) => {
+                    last_error = format!("{url}: {e}");
+                    continue;
+                }
+            };
+            if compute_sha256(&data).eq_ignore_ascii_case(digest) {
+                return Ok(data.to_vec());
+            }
+            last_error = format!("{url}: hash mismatch");
+        }
+        Err(last_error)
+    }
+
+    /// Run the export
+    pub async fn export(&self) -> Result<ExportSummary, String> {
+        let root = self.config.out_dir.as_path();
+        std::fs::create_dir_all(root)
+            .map_err(|e| format!("Failed to create {}: {e}", root.display()))?;
+
+        let paths = self.load_paths().await?;
+        info!(paths = paths.len(), "Loaded public paths");
+
+        // Commons content: everything queryable plus whatever public paths reach
+        let mut content: BTreeMap<String, Value> = BTreeMap::new();
+        for item in self.load_commons_content().await? {
+            if let Some(id) = str_field(&item, "id") {
+                content.insert(id, item);
+            }
+        }
+        for step in paths.iter().flat_map(|p| &p.steps) {
+            if let Some(ref output) = step.content {
+                if let Some(id) = str_field(&output.content, "id") {
+                    content.entry(id).or_insert_with(|| output.content.clone());
+                }
+            }
+        }
+        content.retain(|_, c| is_commons_content(c));
+        let commons = content.len();
+        content.retain(|_, c| is_exportable_content(c));
+        let withheld_by_license = commons - content.len();
+        info!(
+            content = content.len(),
+            withheld_by_license, "Loaded commons content"
+        );
+
+        let mut summary = ExportSummary {
+            withheld_by_license,
+            ..ExportSummary::default()
+        };
+
+        // Media manifest
+        let mut media: BTreeMap<String, MediaEntry> = BTreeMap::new();
+        let mut media_urls: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for id in content.keys() {
+            for blob in self.load_blobs(id).await {
+                let urls = self.media_urls(&blob);
+                let entry = media
+                    .entry(blob.hash.clone())
+                    .or_insert_with(|| MediaEntry {
+                        hash: blob.hash.clone(),
+                        size_bytes: blob.size_bytes,
+                        mime_type: blob.mime_type.clone(),
+                        content_ids: BTreeSet::new(),
+                        urls: blob.fallback_urls.clone(),
+                        file: None,
+                    });
+                entry.content_ids.insert(id.clone());
+                media_urls.entry(blob.hash.clone()).or_insert(urls);
+            }
+        }
+        if self.config.include_media {
+            for entry in media.values_mut() {
+                let file = media_file(&entry.hash);
+                if root.join(&file).exists() {
+                    entry.file = Some(file);
+                    summary.media_downloaded += 1;
+                    continue;
+                }
+                let urls = media_urls.get(&entry.hash).cloned().unwrap_or_default();
+                match self.download(entry, &urls).await {
+                    Ok(data) => {
+                        write_file(root, &file, &data)?;
+                        entry.file = Some(file);
+                        summary.media_downloaded += 1;
+                    }
+                    Err(e) => {
+                        warn!(hash = %entry.hash, error = %e, "Failed to bundle blob");
+                        summary.media_failed += 1;
+                    }
+                }
+            }
+        }
+        summary.media = media.len();
+        write_json(
+            root,
+            "media-manifest.json",
+            &media.values().collect::<Vec<_>>(),
+        )?;
+
+        // Content payloads
+        let mut titles = BTreeMap::new();
+        for (id, item) in &content {
+            let blobs: Vec<MediaEntry> = media
+                .values()
+                .filter(|m| m.content_ids.contains(id))
+                .cloned()
+                .collect();
+            let mut payload = item.clone();
+            if let Some(object) = payload.as_object_mut() {
+                object.insert(
+                    "media".to_string(),
+                    serde_json::to_value(&blobs).unwrap_or_default(),
+                );
+                object.insert(
+                    "license".to_string(),
+                    license_of(item).map_or(Value::Null, Value::String),
+                );
+            }
+            let stem = file_stem(id);
+            write_json(root, &content_file(id), &payload)?;
+            write_file(
+                root,
+                &format!("content/{stem}.html"),
+                render_content_html(item, &blobs).as_bytes(),
+            )?;
+            if let Some(title) = str_field(item, "title") {
+                titles.insert(id.clone(), title);
+            }
+        }
+        summary.content = content.len();
+
+        // Path manifests
+        let exported: BTreeSet<String> = content.keys().cloned().collect();
+        let mut listings = Vec::new();
+        for path in &paths {
+            let steps: Vec<Value> = path.steps.iter().map(|s| s.step.clone()).collect();
+            let manifest = path_manifest(&path.path, &steps, &exported);
+            if manifest.id.is_empty() {
+                continue;
+            }
+            let stem = file_stem(&manifest.id);
+            let manifest_file = format!("paths/{stem}.json");
+            write_json(root, &manifest_file, &manifest)?;
+            write_file(
+                root,
+                &format!("paths/{stem}.html"),
+                render_path_html(&manifest, &titles).as_bytes(),
+            )?;
+            listings.push(PathListing {
+                id: manifest.id.clone(),
+                title: manifest.title.clone(),
+                description: manifest.description.clone(),
+                step_count: manifest.steps.len(),
+                manifest: manifest_file,
+            });
+        }
+        listings.sort_by(|a, b| a.title.cmp(&b.title));
+        summary.paths = listings.len();
+
+        let index = SiteIndex {
+            generated_at: chrono::Utc::now().to_rfc3339(),
+            paths: listings,
+            content_count: summary.content,
+            media_count: summary.media,
+        };
+        write_json(root, "index.json", &index)?;
+        write_file(root, "index.html", render_index_html(&index).as_bytes())?;
+
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_visibility_and_reach_gates() {
+        assert!(is_public_path(&json!({ "visibility": "public" })));
+        assert!(is_public_path(&json!({ "visibility": "published" })));
+        assert!(!is_public_path(&json!({ "visibility": "private" })));
+        assert!(is_commons_content(&json!({ "reach": "commons" })));
+        assert!(!is_commons_content(&json!({ "reach": "community" })));
+        assert!(!is_commons_content(&json!({})));
+    }
+
+    #[test]
+    fn test_license_gate() {
+        let licensed = |license: &str| {
+            json!({
+                "reach": "commons",
+                "metadata_json": json!({ "license": license }).to_string()
+            })
+        };
+        assert!(is_exportable_content(&licensed("CC-BY-SA-4.0")));
+        assert!(!is_exportable_content(&licensed("CC-BY-NC-ND-4.0")));
+        assert!(is_exportable_content(
+            &json!({ "reach": "commons", "metadata_json": "{}" })
+        ));
+
+        let html = render_content_html(&licensed("CC-BY-SA-4.0"), &[]);
+        assert!(html.contains("License: CC-BY-SA-4.0"));
+    }
+
+    #[test]
+    fn test_file_stem() {
+        assert_eq!(file_stem("intro-to-governance"), "intro-to-governance");
+        assert_eq!(file_stem("a/b:c"), "a_b_c");
+        assert_eq!(file_stem("../etc"), "_etc");
+    }
+
+    #[test]
+    fn test_path_manifest_orders_steps_and_links_exported_content() {
+        let path = json!({
+            "id": "governance",
+            "title": "Governance",
+            "description": "How we decide",
+            "difficulty": "beginner",
+            "tags": ["civics"],
+            "visibility": "public"
+        });
+        let steps = vec![
+            json!({ "order_index": 1, "step_type": "content", "resource_id": "private-notes", "is_optional": true }),
+            json!({ "order_index": 0, "step_type": "content", "resource_id": "intro", "step_title": "Start here" }),
+        ];
+        let exported = BTreeSet::from(["intro".to_string()]);
+
+        let manifest = path_manifest(&path, &steps, &exported);
+        assert_eq!(manifest.tags, vec!["civics"]);
+        assert_eq!(manifest.steps[0].resource_id, "intro");
+        assert_eq!(
+            manifest.steps[0].content.as_deref(),
+            Some("content/intro.json")
+        );
+        assert_eq!(manifest.steps[1].content, None);
+        assert!(manifest.steps[1].is_optional);
+
+        let html = render_path_html(&manifest, &BTreeMap::new());
+        assert!(html.contains("<a href=\"../content/intro.html\">Start here</a>"));
+        assert!(html.contains("<li>private-notes</li>"));
+    }
+
+    #[test]
+    fn test_render_content_html_escapes_body_and_embeds_media() {
+        let content = json!({
+            "title": "Clip",
+            "content": "<script>alert(1)</script>"
+        });
+        let media = vec![MediaEntry {
+            hash: "sha256-abc".to_string(),
+            size_bytes: 10,
+            mime_type: "video/mp4".to_string(),
+            content_ids: BTreeSet::new(),
+            urls: vec!["https://cdn.example/abc".to_string()],
+            file: Some("media/sha256-abc".to_string()),
+        }];
+
+        let html = render_content_html(&content, &media);
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("<video controls src=\"../media/sha256-abc\"></video>"));
+    }
+}
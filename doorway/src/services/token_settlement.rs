@@ -0,0 +1,432 @@
+//! Token Settlement Adapter
+//!
+//! Lets premium gates be paid for outside card processors. A payment made on
+//! an external hREA/ValueFlows network or a token ledger comes back as a
+//! [`PaymentProof`] signed by that network's settlement key. The doorway
+//! checks the signature against the keys it trusts, checks the payment
+//! against the gate's price, and only then asks the DNA to grant access
+//! (`grant_paid_access`, which also refuses to redeem a payment twice).
+//!
+//! ## Signing
+//!
+//! The network signs, with Ed25519, the UTF-8 lines
+//!
+//! ```text
+//! elohim-payment-proof:v1
+//! {network}
+//! {event_id}
+//! {provider}
+//! {receiver}
+//! {amount}
+//! {unit}
+//! {gate_id}
+//! {learner_agent_id}
+//! {settled_at}
+//! ```
+//!
+//! joined with `\n`, where `amount` is written in its shortest decimal form
+//! (`12.5`, `10`) and `settled_at` is in Unix seconds.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::config::Args;
+
+/// Version prefix of the signed payload
+const PROOF_DOMAIN: &str = "elohim-payment-proof:v1";
+
+/// Clock skew allowed for proofs settled "in the future"
+const MAX_CLOCK_SKEW_SECS: i64 = 300;
+
+/// Pricing models that don't take payments
+const FREE_PRICING_MODELS: [&str; 2] = ["free_with_attribution", "commons_sponsored"];
+
+/// A payment settled on an external network
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentProof {
+    /// Settlement network, matching a configured signer (e.g. `hrea`)
+    pub network: String,
+    /// Payment event ID on that network
+    pub event_id: String,
+    /// Paying account
+    pub provider: String,
+    /// Receiving account
+    pub receiver: String,
+    pub amount: f64,
+    pub unit: String,
+    /// Gate being paid for
+    pub gate_id: String,
+    /// Learner the access is for
+    pub learner_agent_id: String,
+    /// When the payment settled (Unix seconds)
+    pub settled_at: i64,
+    /// Base64 Ed25519 signature by the network's settlement key
+    pub signature: String,
+}
+
+impl PaymentProof {
+    /// Bytes the network signs
+    pub fn signing_payload(&self) -> Vec<u8> {
+        [
+            PROOF_DOMAIN.to_string(),
+            self.network.clone(),
+            self.event_id.clone(),
+            self.provider.clone(),
+            self.receiver.clone(),
+            self.amount.to_string(),
+            self.unit.clone(),
+            self.gate_id.clone(),
+            self.learner_agent_id.clone(),
+            self.settled_at.to_string(),
+        ]
+        .join("\n")
+        .into_bytes()
+    }
+}
+
+/// Price terms of a premium gate
+/// Must match PremiumGate in holochain/dna/elohim/zomes/content_store_integrity/src/lib.rs
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct GateTerms {
+    pub id: String,
+    pub pricing_model: String,
+    pub price_amount: Option<f64>,
+    pub price_unit: Option<String>,
+    pub min_amount: Option<f64>,
+    pub is_active: bool,
+}
+
+impl GateTerms {
+    /// Least a payment must be to open the gate
+    fn required_amount(&self) -> f64 {
+        match self.pricing_model.as_str() {
+            "pay_what_you_can" => self.min_amount.or(self.price_amount),
+            _ => self.price_amount,
+        }
+        .unwrap_or(0.0)
+    }
+}
+
+/// Why a payment proof was refused
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum SettlementError {
+    #[error("No trusted signer for network '{0}'")]
+    UnknownNetwork(String),
+
+    #[error("Invalid signature")]
+    InvalidSignature,
+
+    #[error("Proof is for gate '{0}'")]
+    WrongGate(String),
+
+    #[error("Gate is not active")]
+    GateInactive,
+
+    #[error("Gate doesn't take payments")]
+    NotPayable,
+
+    #[error("Payment went to '{0}', not the doorway's receiving account")]
+    WrongReceiver(String),
+
+    #[error("Payment was made from the receiving account to itself")]
+    SelfPayment,
+
+    #[error("Paid in {paid}, gate is priced in {expected}")]
+    WrongUnit { paid: String, expected: String },
+
+    #[error("Paid {paid}, gate requires {required}")]
+    Underpaid { paid: f64, required: f64 },
+
+    #[error("Payment settled too long ago or in the future")]
+    Stale,
+
+    #[error("Invalid settlement config: {0}")]
+    Config(String),
+}
+
+/// Verifies payment proofs against the configured settlement networks
+#[derive(Debug, Clone)]
+pub struct TokenSettlement {
+    signers: HashMap<String, VerifyingKey>,
+    receiver: String,
+    max_age: Duration,
+}
+
+impl TokenSettlement {
+    /// Settlement as configured, `None` when no signer is set up. A
+    /// receiving account is required once signers are.
+    pub fn from_args(args: &Args) -> Result<Option<Self>, SettlementError> {
+        if args.token_settlement_signers.is_empty() {
+            return Ok(None);
+        }
+        let mut signers = HashMap::new();
+        for entry in &args.token_settlement_signers {
+            let (network, key) = parse_signer(entry)?;
+            signers.insert(network, key);
+        }
+        let receiver = args
+            .token_settlement_receiver
+            .clone()
+            .filter(|r| !r.is_empty())
+            .ok_or_else(|| {
+                SettlementError::Config(
+                    "TOKEN_SETTLEMENT_RECEIVER is required when TOKEN_SETTLEMENT_SIGNERS is set"
+                        .to_string(),
+                )
+            })?;
+        Ok(Some(Self {
+            signers,
+            receiver,
+            max_age: Duration::from_secs(args.token_settlement_max_age_secs),
+        }))
+    }
+
+    /// Check a proof's signature and that it pays for `gate`. `now` is in
+    /// Unix seconds.
+    pub fn verify(
+        &self,
+        proof: &PaymentProof,
+        gate: &GateTerms,
+        now: i64,
+    ) -> Result<(), SettlementError> {
+        let key = self
+            .signers
+            .get(&proof.network)
+            .ok_or_else(|| SettlementError::UnknownNetwork(proof.network.clone()))?;
+        let signature = BASE64
+            .decode(&proof.signature)
+            .ok()
+            .and_then(|bytes| Signature::from_slice(&bytes).ok())
+            .ok_or(SettlementError::InvalidSignature)?;
+        key.verify_strict(&proof.signing_payload(), &signature)
+            .map_err(|_| SettlementError::InvalidSignature)?;
+
+        if proof.settled_at > now + MAX_CLOCK_SKEW_SECS
+            || now - proof.settled_at > self.max_age.as_secs() as i64
+        {
+            return Err(SettlementError::Stale);
+        }
+        if proof.gate_id != gate.id {
+            return Err(SettlementError::WrongGate(proof.gate_id.clone()));
+        }
+        if !gate.is_active {
+            return Err(SettlementError::GateInactive);
+        }
+        if FREE_PRICING_MODELS.contains(&gate.pricing_model.as_str()) {
+            return Err(SettlementError::NotPayable);
+        }
+        if proof.receiver != self.receiver {
+            return Err(SettlementError::WrongReceiver(proof.receiver.clone()));
+        }
+        if proof.provider == proof.receiver {
+            return Err(SettlementError::SelfPayment);
+        }
+        if let Some(ref unit) = gate.price_unit {
+            if !unit.eq_ignore_ascii_case(&proof.unit) {
+                return Err(SettlementError::WrongUnit {
+                    paid: proof.unit.clone(),
+                    expected: unit.clone(),
+                });
+            }
+        }
+        let required = gate.required_amount();
+        if !proof.amount.is_finite() || proof.amount < required || proof.amount <= 0.0 {
+            return Err(SettlementError::Underpaid {
+                paid: proof.amount,
+                required,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Parse a `network=base64key` signer entry
+fn parse_signer(entry: &str) -> Result<(String, VerifyingKey), SettlementError> {
+    let (network, key) = entry
+        .split_once('=')
+        .ok_or_else(|| SettlementError::Config(format!("expected network=key, got '{entry}'")))?;
+    let bytes: [u8; 32] = BASE64
+        .decode(key.trim())
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| SettlementError::Config(format!("invalid key for '{network}'")))?;
+    let key = VerifyingKey::from_bytes(&bytes)
+        .map_err(|_| SettlementError::Config(format!("invalid key for '{network}'")))?;
+    Ok((network.trim().to_string(), key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn signing_key() -> SigningKey {
+        SigningKey::from_bytes(&[7u8; 32])
+    }
+
+    fn settlement() -> TokenSettlement {
+        let key = BASE64.encode(signing_key().verifying_key().to_bytes());
+        let args = Args::parse_from([
+            "doorway",
+            "--token-settlement-signers",
+            &format!("hrea={key}"),
+            "--token-settlement-receiver",
+            "commons-account",
+        ]);
+        TokenSettlement::from_args(&args).unwrap().unwrap()
+    }
+
+    fn gate() -> GateTerms {
+        GateTerms {
+            id: "gate-1".to_string(),
+            pricing_model: "one_time".to_string(),
+            price_amount: Some(12.5),
+            price_unit: Some("elohim-credit".to_string()),
+            is_active: true,
+            ..Default::default()
+        }
+    }
+
+    fn signed(mut proof: PaymentProof) -> PaymentProof {
+        proof.signature = BASE64.encode(signing_key().sign(&proof.signing_payload()).to_bytes());
+        proof
+    }
+
+    fn proof() -> PaymentProof {
+        signed(PaymentProof {
+            network: "hrea".to_string(),
+            event_id: "evt-1".to_string(),
+            provider: "learner-account".to_string(),
+            receiver: "commons-account".to_string(),
+            amount: 12.5,
+            unit: "elohim-credit".to_string(),
+            gate_id: "gate-1".to_string(),
+            learner_agent_id: "uhCAk-learner".to_string(),
+            settled_at: NOW - 60,
+            signature: String::new(),
+        })
+    }
+
+    #[test]
+    fn test_valid_proof() {
+        assert_eq!(settlement().verify(&proof(), &gate(), NOW), Ok(()));
+    }
+
+    #[test]
+    fn test_tampered_proof_rejected() {
+        let mut tampered = proof();
+        tampered.amount = 1000.0;
+        assert_eq!(
+            settlement().verify(&tampered, &gate(), NOW),
+            Err(SettlementError::InvalidSignature)
+        );
+
+        let mut unknown = proof();
+        unknown.network = "other-ledger".to_string();
+        assert!(matches!(
+            settlement().verify(&unknown, &gate(), NOW),
+            Err(SettlementError::UnknownNetwork(_))
+        ));
+    }
+
+    #[test]
+    fn test_payment_checked_against_gate() {
+        let settlement = settlement();
+        let underpaid = signed(PaymentProof {
+            amount: 5.0,
+            ..proof()
+        });
+        assert!(matches!(
+            settlement.verify(&underpaid, &gate(), NOW),
+            Err(SettlementError::Underpaid { .. })
+        ));
+
+        let wrong_unit = signed(PaymentProof {
+            unit: "usd".to_string(),
+            ..proof()
+        });
+        assert!(matches!(
+            settlement.verify(&wrong_unit, &gate(), NOW),
+            Err(SettlementError::WrongUnit { .. })
+        ));
+
+        let elsewhere = signed(PaymentProof {
+            receiver: "someone-else".to_string(),
+            ..proof()
+        });
+        assert!(matches!(
+            settlement.verify(&elsewhere, &gate(), NOW),
+            Err(SettlementError::WrongReceiver(_))
+        ));
+
+        let free = GateTerms {
+            pricing_model: "commons_sponsored".to_string(),
+            ..gate()
+        };
+        assert_eq!(
+            settlement.verify(&proof(), &free, NOW),
+            Err(SettlementError::NotPayable)
+        );
+
+        let old = NOW + 2 * 86400;
+        assert_eq!(
+            settlement.verify(&proof(), &gate(), old),
+            Err(SettlementError::Stale)
+        );
+    }
+
+    #[test]
+    fn test_pay_what_you_can_minimum() {
+        let gate = GateTerms {
+            pricing_model: "pay_what_you_can".to_string(),
+            min_amount: Some(2.0),
+            ..gate()
+        };
+        let proof = signed(PaymentProof {
+            amount: 3.0,
+            ..proof()
+        });
+        assert_eq!(settlement().verify(&proof, &gate, NOW), Ok(()));
+    }
+
+    #[test]
+    fn test_not_configured_by_default() {
+        let args = Args::parse_from(["doorway"]);
+        assert!(TokenSettlement::from_args(&args).unwrap().is_none());
+
+        let args = Args::parse_from(["doorway", "--token-settlement-signers", "hrea"]);
+        assert!(TokenSettlement::from_args(&args).is_err());
+    }
+
+    #[test]
+    fn test_receiver_required_with_signers() {
+        let key = BASE64.encode(signing_key().verifying_key().to_bytes());
+        let args = Args::parse_from([
+            "doorway",
+            "--token-settlement-signers",
+            &format!("hrea={key}"),
+        ]);
+        assert!(matches!(
+            TokenSettlement::from_args(&args),
+            Err(SettlementError::Config(_))
+        ));
+    }
+
+    #[test]
+    fn test_self_payment_rejected() {
+        let to_itself = signed(PaymentProof {
+            provider: "commons-account".to_string(),
+            ..proof()
+        });
+        assert_eq!(
+            settlement().verify(&to_itself, &gate(), NOW),
+            Err(SettlementError::SelfPayment)
+        );
+    }
+}
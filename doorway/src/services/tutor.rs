@@ -0,0 +1,417 @@
+//! Conversational tutor
+//!
+//! Proxies learner chats to any OpenAI-compatible chat completions API
+//! (`POST {url}/v1/chat/completions`), hosted or local, with a system
+//! prompt grounded in the learner's current path step, the content being
+//! studied and their mastery of it. Replies are streamed back as the
+//! provider's server-sent events.
+//!
+//! ## Budget
+//!
+//! Spend is capped by a monthly token budget per operator API key. Usage is
+//! read from the provider's final `usage` event (estimated from text length
+//! when the provider doesn't send one) and kept in the `tutor_usage`
+//! collection, or in memory when MongoDB isn't configured. Chats already in
+//! flight when the budget runs out are allowed to finish. An approved
+//! `rate_limit.tutor_monthly_tokens` [governance](crate::worker::governance)
+//! setting replaces the configured budget.
+
+use bson::doc;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::warn;
+
+use crate::db::schemas::{Metadata, TutorUsageDoc, TUTOR_USAGE_COLLECTION};
+use crate::db::{MongoClient, MongoCollection};
+use crate::worker::governance::{GovernedSettings, TUTOR_MONTHLY_TOKENS};
+
+/// Timeout for a whole streamed reply
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(180);
+
+/// Longest content body put in the prompt, in characters
+const MAX_BODY_CHARS: usize = 12_000;
+
+/// Rough characters per token, for estimating usage
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Tutor settings
+#[derive(Debug, Clone)]
+pub struct TutorConfig {
+    /// Chat completions API base URL
+    pub url: String,
+    pub api_key: Option<String>,
+    pub model: String,
+    /// Tokens per operator key per calendar month (0 = unlimited)
+    pub monthly_token_budget: u64,
+}
+
+/// One turn of the conversation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+/// What the tutor knows about the learner and the material
+///
+/// Built from `content_store::get_tutor_context` and the content itself.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TutorGrounding {
+    pub path_title: Option<String>,
+    pub step_title: Option<String>,
+    pub step_narrative: Option<String>,
+    pub content_title: Option<String>,
+    pub content_body: Option<String>,
+    pub mastery_level: Option<String>,
+}
+
+impl TutorGrounding {
+    /// System prompt for the chat
+    pub fn system_prompt(&self) -> String {
+        let mut prompt = String::from(
+            "You are a patient tutor helping a learner understand a learning resource. \
+Explain in plain language, check understanding with short questions and prefer hints \
+over handing out answers. Stay with the material below; if the learner asks about \
+something it doesn't cover, say so.",
+        );
+        if let Some(ref path) = self.path_title {
+            prompt.push_str(&format!("\n\nLearning path: {path}"));
+        }
+        if let Some(ref step) = self.step_title {
+            prompt.push_str(&format!("\nCurrent step: {step}"));
+        }
+        if let Some(ref narrative) = self.step_narrative {
+            prompt.push_str(&format!("\nStep notes: {narrative}"));
+        }
+        if let Some(ref level) = self.mastery_level {
+            prompt.push_str(&format!(
+                "\nLearner's mastery of this content (Bloom's taxonomy): {level}. \
+Pitch explanations at that level and nudge them one level higher."
+            ));
+        }
+        if let Some(ref title) = self.content_title {
+            prompt.push_str(&format!("\n\nResource: {title}"));
+        }
+        if let Some(ref body) = self.content_body {
+            let body: String = body.chars().take(MAX_BODY_CHARS).collect();
+            prompt.push_str(&format!("\n\n{body}"));
+        }
+        prompt
+    }
+}
+
+/// Tokens spent on one chat
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub struct TokenUsage {
+    #[serde(default)]
+    pub prompt_tokens: u64,
+    #[serde(default)]
+    pub completion_tokens: u64,
+}
+
+/// Estimate tokens for text when the provider doesn't report usage
+pub fn estimate_tokens(chars: usize) -> u64 {
+    chars.div_ceil(CHARS_PER_TOKEN) as u64
+}
+
+/// Reads the provider's event stream as it is forwarded, to count usage
+#[derive(Debug)]
+pub struct UsageMeter {
+    /// Incomplete line carried over from the previous chunk
+    partial: String,
+    prompt_chars: usize,
+    completion_chars: usize,
+    reported: Option<TokenUsage>,
+}
+
+impl UsageMeter {
+    pub fn new(prompt_chars: usize) -> Self {
+        Self {
+            partial: String::new(),
+            prompt_chars,
+            completion_chars: 0,
+            reported: None,
+        }
+    }
+
+    /// Feed a chunk of the event stream
+    pub fn feed(&mut self, chunk: &[u8]) {
+        self.partial.push_str(&String::from_utf8_lossy(chunk));
+        while let Some(end) = self.partial.find('\n') {
+            let line: String = self.partial.drain(..=end).collect();
+            self.line(line.trim());
+        }
+    }
+
+    fn line(&mut self, line: &str) {
+        let Some(data) = line.strip_prefix("data:").map(str::trim) else {
+            return;
+        };
+        let Ok(event) = serde_json::from_str::<Value>(data) else {
+            return;
+        };
+        if let Some(usage) = event
+            .get("usage")
+            .filter(|u| !u.is_null())
+            .and_then(|u| serde_json::from_value::<TokenUsage>(u.clone()).ok())
+        {
+            self.reported = Some(usage);
+        }
+        let deltas = event
+            .get("choices")
+            .and_then(|c| c.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|choice| choice.pointer("/delta/content").and_then(|c| c.as_str()));
+        for delta in deltas {
+            self.completion_chars += delta.chars().count();
+        }
+    }
+
+    /// Usage for the chat, as reported or estimated
+    pub fn finish(mut self) -> TokenUsage {
+        let rest = std::mem::take(&mut self.partial);
+        self.line(rest.trim());
+        self.reported.unwrap_or(TokenUsage {
+            prompt_tokens: estimate_tokens(self.prompt_chars),
+            completion_tokens: estimate_tokens(self.completion_chars),
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: &'a [ChatMessage],
+    stream: bool,
+    stream_options: Value,
+}
+
+/// Chat proxy with per-operator budgeting
+pub struct TutorService {
+    config: TutorConfig,
+    client: reqwest::Client,
+    mongo: Option<MongoClient>,
+    settings: Arc<GovernedSettings>,
+    operator_key: String,
+    /// Usage when MongoDB isn't configured: (period, tokens)
+    local_usage: Mutex<(String, u64)>,
+}
+
+impl TutorService {
+    pub fn new(
+        config: TutorConfig,
+        mongo: Option<MongoClient>,
+        settings: Arc<GovernedSettings>,
+    ) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        let operator_key = operator_key(config.api_key.as_deref());
+        Self {
+            config,
+            client,
+            mongo,
+            settings,
+            operator_key,
+            local_usage: Mutex::new((String::new(), 0)),
+        }
+    }
+
+    pub fn model(&self) -> &str {
+        &self.config.model
+    }
+
+    async fn usage_collection(&self) -> Option<MongoCollection<TutorUsageDoc>> {
+        let mongo = self.mongo.as_ref()?;
+        match mongo.collection(TUTOR_USAGE_COLLECTION).await {
+            Ok(collection) => Some(collection),
+            Err(e) => {
+                warn!(error = %e, "Tutor usage collection unavailable");
+                None
+            }
+        }
+    }
+
+    /// Tokens spent with the operator key this month
+    pub async fn tokens_used(&self) -> Result<u64, String> {
+        let period = current_period();
+        match self.usage_collection().await {
+            Some(collection) => Ok(collection
+                .find_one(doc! { "operator_key": &self.operator_key, "period": &period })
+                .await
+                .map_err(|e| format!("Failed to read tutor usage: {e}"))?
+                .map(|usage| usage.total_tokens())
+                .unwrap_or(0)),
+            None => {
+                let local = self.local_usage.lock().unwrap_or_else(|e| e.into_inner());
+                Ok(if local.0 == period { local.1 } else { 0 })
+            }
+        }
+    }
+
+    /// Monthly budget in effect, governed or configured (0 = unlimited)
+    pub fn monthly_budget(&self) -> u64 {
+        self.settings
+            .get_u64(TUTOR_MONTHLY_TOKENS)
+            .unwrap_or(self.config.monthly_token_budget)
+    }
+
+    /// Tokens left this month, `None` when unlimited
+    pub async fn remaining_budget(&self) -> Result<Option<u64>, String> {
+        let budget = self.monthly_budget();
+        if budget == 0 {
+            return Ok(None);
+        }
+        let used = self.tokens_used().await?;
+        Ok(Some(budget.saturating_sub(used)))
+    }
+
+    /// Count a finished chat against the operator key
+    pub async fn record_usage(&self, usage: TokenUsage) {
+        let period = current_period();
+        match self.usage_collection().await {
+            Some(collection) => {
+                let metadata = bson::to_bson(&Metadata::new()).unwrap_or(bson::Bson::Null);
+                if let Err(e) = collection
+                    .inner()
+                    .update_one(
+                        doc! { "operator_key": &self.operator_key, "period": &period },
+                        doc! {
+                            "$inc": {
+                                "prompt_tokens": usage.prompt_tokens as i64,
+                                "completion_tokens": usage.completion_tokens as i64,
+                                "requests": 1_i64,
+                            },
+                            "$setOnInsert": { "metadata": metadata },
+                        },
+                    )
+                    .upsert(true)
+                    .await
+                {
+                    warn!(error = %e, "Failed to record tutor usage");
+                }
+            }
+            None => {
+                let mut local = self.local_usage.lock().unwrap_or_else(|e| e.into_inner());
+                if local.0 != period {
+                    *local = (period, 0);
+                }
+                local.1 = local
+                    .1
+                    .saturating_add(usage.prompt_tokens + usage.completion_tokens);
+            }
+        }
+    }
+
+    /// Start a streamed chat; the caller forwards and meters the body
+    pub async fn start_chat(&self, messages: &[ChatMessage]) -> Result<reqwest::Response, String> {
+        let request = ChatRequest {
+            model: &self.config.model,
+            messages,
+            stream: true,
+            stream_options: serde_json::json!({ "include_usage": true }),
+        };
+        let mut builder = self
+            .client
+            .post(format!(
+                "{}/v1/chat/completions",
+                self.config.url.trim_end_matches('/')
+            ))
+            .json(&request);
+        if let Some(ref key) = self.config.api_key {
+            builder = builder.bearer_auth(key);
+        }
+        let response = builder
+            .send()
+            .await
+            .map_err(|e| format!("Tutor request failed: {e}"))?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "Tutor provider returned HTTP {}",
+                response.status()
+            ));
+        }
+        Ok(response)
+    }
+}
+
+/// Current budget period, as `YYYY-MM` (UTC)
+fn current_period() -> String {
+    Utc::now().format("%Y-%m").to_string()
+}
+
+/// Fingerprint identifying an operator API key without storing it
+pub fn operator_key(api_key: Option<&str>) -> String {
+    match api_key {
+        Some(key) => hex::encode(&Sha256::digest(key.as_bytes())[..8]),
+        None => "keyless".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_system_prompt_includes_grounding() {
+        let grounding = TutorGrounding {
+            path_title: Some("Foundations".into()),
+            step_title: Some("Why commons matter".into()),
+            step_narrative: None,
+            content_title: Some("The Commons".into()),
+            content_body: Some("Commons are shared resources.".into()),
+            mastery_level: Some("remember".into()),
+        };
+        let prompt = grounding.system_prompt();
+        assert!(prompt.contains("Learning path: Foundations"));
+        assert!(prompt.contains("Current step: Why commons matter"));
+        assert!(prompt.contains("mastery of this content (Bloom's taxonomy): remember"));
+        assert!(prompt.ends_with("Commons are shared resources."));
+        assert!(!prompt.contains("Step notes"));
+    }
+
+    #[test]
+    fn test_usage_meter_prefers_reported_usage() {
+        let mut meter = UsageMeter::new(400);
+        meter.feed(b"data: {\"choices\":[{\"delta\":{\"content\":\"Hel");
+        meter.feed(b"lo\"}}]}\n\ndata: {\"choices\":[],\"usage\":{\"prompt_tokens\":120,");
+        meter.feed(b"\"completion_tokens\":7}}\n\ndata: [DONE]\n\n");
+        assert_eq!(
+            meter.finish(),
+            TokenUsage {
+                prompt_tokens: 120,
+                completion_tokens: 7
+            }
+        );
+    }
+
+    #[test]
+    fn test_usage_meter_estimates_without_usage() {
+        let mut meter = UsageMeter::new(400);
+        meter.feed(b"data: {\"choices\":[{\"delta\":{\"content\":\"abcdefgh\"}}]}\n\n");
+        meter.feed(b"data: {\"choices\":[{\"delta\":{\"content\":\"ij\"}}],\"usage\":null}");
+        assert_eq!(
+            meter.finish(),
+            TokenUsage {
+                prompt_tokens: 100,
+                completion_tokens: 3
+            }
+        );
+    }
+
+    #[test]
+    fn test_operator_key_fingerprint() {
+        let key = operator_key(Some("sk-test"));
+        assert_eq!(key.len(), 16);
+        assert!(!key.contains("sk-test"));
+        assert_eq!(key, operator_key(Some("sk-test")));
+        assert_ne!(key, operator_key(Some("sk-other")));
+        assert_eq!(operator_key(None), "keyless");
+    }
+}
@@ -0,0 +1,319 @@
+//! Web Annotations
+//!
+//! Learners highlight and comment on ranges of content (Annotation entries in
+//! the content DNA). The doorway speaks the W3C Web Annotation Data Model
+//! (<https://www.w3.org/TR/annotation-model/>) on `/annotations`, so
+//! annotation clients such as Hypothesis-style sidebars can use it; this
+//! module converts between that JSON and the zome's entries.
+//!
+//! ## Mapping
+//!
+//! - `target.source` is the content's IRI (`{doorway}/content/{id}`) or a bare
+//!   content id; `target.selector` is the range and is stored as given
+//! - A target naming an annotation (`{doorway}/annotations/{id}`) makes a reply
+//! - `body` is a `TextualBody` (or `bodyValue`); a highlight may have none
+//! - `visibility` (`private`, `cohort`, `public`) and `path_id` are Elohim
+//!   extensions; cohort annotations form the path's shared layer
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use crate::projection::document::entry_timestamp_rfc3339;
+
+/// JSON-LD context of Web Annotations
+pub const ANNO_CONTEXT: &str = "http://www.w3.org/ns/anno.jsonld";
+
+/// Media type of Web Annotation responses
+pub const ANNO_CONTENT_TYPE: &str =
+    "application/ld+json; profile=\"http://www.w3.org/ns/anno.jsonld\"";
+
+/// Selectors a range may be given with
+const SELECTOR_TYPES: [&str; 8] = [
+    "TextQuoteSelector",
+    "TextPositionSelector",
+    "FragmentSelector",
+    "CssSelector",
+    "XPathSelector",
+    "RangeSelector",
+    "DataPositionSelector",
+    "SvgSelector",
+];
+
+/// Web Annotation errors
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum AnnotationError {
+    #[error("Invalid annotation: {0}")]
+    Invalid(String),
+}
+
+/// Must match Annotation in holochain/dna/elohim/zomes/content_store_integrity/src/lib.rs
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Annotation {
+    pub id: String,
+    pub author_id: String,
+    pub content_id: String,
+    #[serde(default)]
+    pub path_id: Option<String>,
+    pub selector_json: String,
+    pub motivation: String,
+    pub body: String,
+    #[serde(default)]
+    pub reply_to: Option<String>,
+    pub visibility: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Must match AnnotationOutput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Clone, Deserialize)]
+pub struct AnnotationOutput {
+    pub annotation: Annotation,
+}
+
+/// An incoming Web Annotation, in the zome's terms
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ParsedAnnotation {
+    /// Empty for replies
+    pub content_id: String,
+    pub reply_to: Option<String>,
+    /// Empty for replies
+    pub selector_json: String,
+    pub motivation: String,
+    pub body: String,
+    pub visibility: Option<String>,
+    pub path_id: Option<String>,
+}
+
+/// IRI of an annotation
+pub fn annotation_iri(base: &str, id: &str) -> String {
+    format!("{}/annotations/{}", base.trim_end_matches('/'), id)
+}
+
+/// IRI of a content node
+pub fn content_iri(base: &str, id: &str) -> String {
+    format!("{}/content/{}", base.trim_end_matches('/'), id)
+}
+
+/// The id after `/{kind}/` in an IRI, if it names one
+fn iri_id<'a>(iri: &'a str, kind: &str) -> Option<&'a str> {
+    let (_, id) = iri.rsplit_once(&format!("/{kind}/"))?;
+    (!id.is_empty() && !id.contains('/')).then_some(id)
+}
+
+fn invalid(message: &str) -> AnnotationError {
+    AnnotationError::Invalid(message.to_string())
+}
+
+/// Check a selector, or list of selectors, names known selector types
+fn check_selector(selector: &Value) -> Result<(), AnnotationError> {
+    let selectors = match selector {
+        Value::Array(selectors) if !selectors.is_empty() => selectors.iter().collect(),
+        Value::Object(_) => vec![selector],
+        _ => {
+            return Err(invalid(
+                "target.selector must be a selector or a list of them",
+            ))
+        }
+    };
+    for selector in selectors {
+        let kind = selector["type"].as_str().unwrap_or_default();
+        if !SELECTOR_TYPES.contains(&kind) {
+            return Err(AnnotationError::Invalid(format!(
+                "Unsupported selector type '{kind}'. Must be one of: {SELECTOR_TYPES:?}"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// The comment text of a body: a TextualBody, a list holding one, or `bodyValue`
+fn body_text(annotation: &Value) -> Result<String, AnnotationError> {
+    if let Some(value) = annotation["bodyValue"].as_str() {
+        return Ok(value.trim().to_string());
+    }
+    let textual = match &annotation["body"] {
+        Value::Null => return Ok(String::new()),
+        Value::Array(bodies) => bodies.iter().find(|b| b["value"].is_string()),
+        body => Some(body),
+    };
+    textual
+        .and_then(|b| b["value"].as_str())
+        .map(|v| v.trim().to_string())
+        .ok_or_else(|| invalid("body must be a TextualBody with a value"))
+}
+
+/// Read a Web Annotation sent by a client
+pub fn parse_web_annotation(annotation: &Value) -> Result<ParsedAnnotation, AnnotationError> {
+    let target = match &annotation["target"] {
+        Value::Array(targets) if targets.len() == 1 => &targets[0],
+        Value::Array(_) => return Err(invalid("annotations have exactly one target")),
+        target => target,
+    };
+    let (source, selector) = match target {
+        Value::String(source) => (source.as_str(), None),
+        Value::Object(_) => (
+            target["source"].as_str().unwrap_or_default(),
+            target.get("selector"),
+        ),
+        _ => return Err(invalid("target is required")),
+    };
+    if source.is_empty() {
+        return Err(invalid("target.source is required"));
+    }
+
+    let body = body_text(annotation)?;
+    let mut parsed = ParsedAnnotation {
+        body,
+        visibility: annotation["visibility"].as_str().map(str::to_string),
+        path_id: annotation["path_id"].as_str().map(str::to_string),
+        ..Default::default()
+    };
+    if let Some(parent) = iri_id(source, "annotations") {
+        parsed.reply_to = Some(parent.to_string());
+    } else {
+        let selector = selector.ok_or_else(|| invalid("target.selector is required"))?;
+        check_selector(selector)?;
+        parsed.content_id = iri_id(source, "content").unwrap_or(source).to_string();
+        parsed.selector_json = selector.to_string();
+    }
+
+    parsed.motivation = match annotation["motivation"].as_str() {
+        Some(motivation) => motivation.to_string(),
+        None if parsed.reply_to.is_some() => "replying".to_string(),
+        None if parsed.body.is_empty() => "highlighting".to_string(),
+        None => "commenting".to_string(),
+    };
+    Ok(parsed)
+}
+
+/// An annotation as a Web Annotation
+pub fn to_web_annotation(annotation: &Annotation, base: &str) -> Value {
+    let target = match annotation.reply_to {
+        Some(ref parent) => json!(annotation_iri(base, parent)),
+        None => json!({
+            "source": content_iri(base, &annotation.content_id),
+            "selector": serde_json::from_str::<Value>(&annotation.selector_json)
+                .unwrap_or(Value::Null),
+        }),
+    };
+    let mut web = json!({
+        "@context": ANNO_CONTEXT,
+        "id": annotation_iri(base, &annotation.id),
+        "type": "Annotation",
+        "motivation": annotation.motivation,
+        "creator": annotation.author_id,
+        "created": entry_timestamp_rfc3339(&annotation.created_at),
+        "modified": entry_timestamp_rfc3339(&annotation.updated_at),
+        "target": target,
+        "visibility": annotation.visibility,
+    });
+    if !annotation.body.is_empty() {
+        web["body"] = json!({
+            "type": "TextualBody",
+            "value": annotation.body,
+            "format": "text/plain",
+        });
+    }
+    if let Some(ref path_id) = annotation.path_id {
+        web["path_id"] = json!(path_id);
+    }
+    web
+}
+
+/// Annotations as a Web Annotation page
+pub fn annotation_page(annotations: &[Annotation], base: &str) -> Value {
+    let items: Vec<Value> = annotations
+        .iter()
+        .map(|annotation| to_web_annotation(annotation, base))
+        .collect();
+    json!({
+        "@context": ANNO_CONTEXT,
+        "type": "AnnotationPage",
+        "startIndex": 0,
+        "items": items,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_web_annotation() {
+        let parsed = parse_web_annotation(&json!({
+            "@context": ANNO_CONTEXT,
+            "type": "Annotation",
+            "body": {"type": "TextualBody", "value": " Why base 10? "},
+            "target": {
+                "source": "https://doorway.example/content/fractions-intro",
+                "selector": {"type": "TextQuoteSelector", "exact": "tenths"}
+            },
+            "visibility": "cohort",
+            "path_id": "path-1"
+        }))
+        .unwrap();
+        assert_eq!(parsed.content_id, "fractions-intro");
+        assert_eq!(parsed.motivation, "commenting");
+        assert_eq!(parsed.body, "Why base 10?");
+        assert_eq!(parsed.visibility.as_deref(), Some("cohort"));
+        let selector: Value = serde_json::from_str(&parsed.selector_json).unwrap();
+        assert_eq!(selector["exact"], "tenths");
+
+        let reply = parse_web_annotation(&json!({
+            "bodyValue": "Because of our ten fingers",
+            "target": "https://doorway.example/annotations/annotation-uhCAk-1"
+        }))
+        .unwrap();
+        assert_eq!(reply.reply_to.as_deref(), Some("annotation-uhCAk-1"));
+        assert_eq!(reply.motivation, "replying");
+
+        let highlight = parse_web_annotation(&json!({
+            "target": {"source": "fractions-intro", "selector": {"type": "TextPositionSelector", "start": 4, "end": 9}}
+        }))
+        .unwrap();
+        assert_eq!(highlight.motivation, "highlighting");
+        assert_eq!(highlight.content_id, "fractions-intro");
+
+        assert!(parse_web_annotation(&json!({"target": {"source": "fractions-intro"}})).is_err());
+        assert!(parse_web_annotation(&json!({
+            "target": {"source": "fractions-intro", "selector": {"type": "Guess"}}
+        }))
+        .is_err());
+    }
+
+    #[test]
+    fn test_to_web_annotation() {
+        let annotation = Annotation {
+            id: "annotation-uhCAk-1".to_string(),
+            author_id: "uhCAk-learner".to_string(),
+            content_id: "fractions-intro".to_string(),
+            path_id: Some("path-1".to_string()),
+            selector_json: r#"{"type":"TextQuoteSelector","exact":"tenths"}"#.to_string(),
+            motivation: "commenting".to_string(),
+            body: "Why base 10?".to_string(),
+            reply_to: None,
+            visibility: "cohort".to_string(),
+            created_at: "Timestamp(2026-10-16T09:30:00.000000Z)".to_string(),
+            updated_at: "Timestamp(2026-10-16T09:30:00.000000Z)".to_string(),
+        };
+        let web = to_web_annotation(&annotation, "https://doorway.example/");
+        assert_eq!(
+            web["id"],
+            "https://doorway.example/annotations/annotation-uhCAk-1"
+        );
+        assert_eq!(
+            web["target"]["source"],
+            "https://doorway.example/content/fractions-intro"
+        );
+        assert_eq!(web["target"]["selector"]["exact"], "tenths");
+        assert_eq!(web["body"]["value"], "Why base 10?");
+        assert_eq!(web["created"], "2026-10-16T09:30:00Z");
+
+        // What goes out comes back in unchanged
+        let parsed = parse_web_annotation(&web).unwrap();
+        assert_eq!(parsed.content_id, annotation.content_id);
+        assert_eq!(parsed.body, annotation.body);
+        assert_eq!(parsed.path_id, annotation.path_id);
+    }
+}
@@ -171,6 +171,77 @@ impl ZomeCaller {
     }
 }
 
+/// Trait for raw zome calls (allows background workers to be tested against
+/// canned responses)
+#[async_trait::async_trait]
+pub trait ZomeCall: Send + Sync {
+    /// Call a zome function with raw bytes payload, return raw bytes
+    async fn call_zome(
+        &self,
+        role_name: &str,
+        zome_name: &str,
+        fn_name: &str,
+        payload: Vec<u8>,
+    ) -> Result<Vec<u8>, String>;
+
+    /// Typed wrapper: serialize input with MessagePack, deserialize output
+    async fn call<I: Serialize + Sync, O: DeserializeOwned>(
+        &self,
+        role_name: &str,
+        zome_name: &str,
+        fn_name: &str,
+        input: &I,
+    ) -> Result<O, String>
+    where
+        Self: Sized,
+    {
+        let payload =
+            rmp_serde::to_vec(input).map_err(|e| format!("Failed to serialize input: {e}"))?;
+
+        let response_bytes = self
+            .call_zome(role_name, zome_name, fn_name, payload)
+            .await?;
+
+        rmp_serde::from_slice(&response_bytes)
+            .map_err(|e| format!("Failed to deserialize response: {e}"))
+    }
+}
+
+#[async_trait::async_trait]
+impl ZomeCall for ZomeCaller {
+    async fn call_zome(
+        &self,
+        role_name: &str,
+        zome_name: &str,
+        fn_name: &str,
+        payload: Vec<u8>,
+    ) -> Result<Vec<u8>, String> {
+        ZomeCaller::call_zome(self, role_name, zome_name, fn_name, payload).await
+    }
+}
+
+/// Zome caller for worker tests: answers each call from a closure over the
+/// function name and MessagePack input
+#[cfg(test)]
+pub(crate) struct StubZomeCaller<F>(pub F);
+
+#[cfg(test)]
+#[async_trait::async_trait]
+impl<F> ZomeCall for StubZomeCaller<F>
+where
+    F: Fn(&str, &[u8]) -> Result<Vec<u8>, String> + Send + Sync,
+{
+    async fn call_zome(
+        &self,
+        _role_name: &str,
+        _zome_name: &str,
+        fn_name: &str,
+        payload: Vec<u8>,
+    ) -> Result<Vec<u8>, String> {
+        (self.0)(fn_name, &payload)
+    }
+}
+
 /// Build a CallZome inner request (MessagePack)
 fn build_call_zome_request(
     role_name: &str,
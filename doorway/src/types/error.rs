@@ -5,7 +5,7 @@
 use hyper::StatusCode;
 
 /// Main error type for Doorway operations
-#[derive(Debug, thiserror::Error)]
+#[derive(Debug, Clone, thiserror::Error)]
 pub enum DoorwayError {
     #[error("Bad request: {0}")]
     BadRequest(String),
@@ -45,6 +45,9 @@ pub enum DoorwayError {
 
     #[error("Projection error: {0}")]
     Projection(String),
+
+    #[error("Overloaded: {0}")]
+    Overloaded(String),
 }
 
 impl DoorwayError {
@@ -64,6 +67,7 @@ impl DoorwayError {
             Self::Config(_) => StatusCode::INTERNAL_SERVER_ERROR,
             Self::Auth(_) => StatusCode::UNAUTHORIZED,
             Self::Projection(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::Overloaded(_) => StatusCode::SERVICE_UNAVAILABLE,
         }
     }
 
@@ -0,0 +1,276 @@
+//! Learning analytics rollup
+//!
+//! Periodically asks the content DNA for per-path aggregates (completion
+//! funnel, time per step, challenge score distribution) and stores them in
+//! the `analytics_path_rollups` collection for the `/analytics` routes.
+//!
+//! Aggregation happens inside the zome, so per-learner records never reach
+//! the doorway. Paths with fewer than [`MIN_COHORT_SIZE`] learners are
+//! stored suppressed (cohort size only) so small groups can't be singled out.
+
+use bson::doc;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::db::schemas::{
+    FunnelStepRollup, Metadata, PathAnalyticsRollupDoc, ANALYTICS_ROLLUP_COLLECTION,
+};
+use crate::db::{MongoClient, MongoCollection};
+use crate::services::zome_caller::ZomeCaller;
+
+/// Role holding the content_store zome
+const CONTENT_ROLE: &str = "lamad";
+
+/// Zome exposing the path analytics functions
+const CONTENT_ZOME: &str = "content_store";
+
+/// Smallest cohort reported in detail
+pub const MIN_COHORT_SIZE: u32 = 5;
+
+/// Path listing entry (only the id is needed)
+/// Subset of PathIndexEntry in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Clone, Deserialize)]
+pub struct PathIndexEntry {
+    pub id: String,
+}
+
+/// Subset of PathIndex in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Clone, Deserialize)]
+pub struct PathIndex {
+    pub paths: Vec<PathIndexEntry>,
+}
+
+/// Input for content_store::get_path_analytics
+#[derive(Debug, Clone, Serialize)]
+pub struct PathAnalyticsInput {
+    pub path_id: String,
+}
+
+/// Must match StepFunnelEntry in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepFunnelEntry {
+    pub step_index: u32,
+    pub step_title: Option<String>,
+    pub resource_id: String,
+    pub estimated_minutes: Option<u32>,
+    pub completions: u32,
+}
+
+/// Must match PathAnalytics in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PathAnalytics {
+    pub path_id: String,
+    pub path_title: String,
+    pub learners_started: u32,
+    pub learners_completed: u32,
+    pub funnel: Vec<StepFunnelEntry>,
+    pub avg_estimated_minutes_per_step: Option<f64>,
+    pub avg_actual_minutes_per_step: Option<f64>,
+    pub challenge_score_histogram: Vec<u32>,
+    pub challenges_completed: u32,
+}
+
+/// Outcome of one rollup pass
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RollupSummary {
+    pub paths: usize,
+    pub written: usize,
+    pub suppressed: usize,
+    pub failed: usize,
+}
+
+/// Turn zome aggregates into a stored rollup, applying cohort suppression
+pub fn to_rollup(analytics: PathAnalytics) -> PathAnalyticsRollupDoc {
+    let started = analytics.learners_started;
+    let mut rollup = PathAnalyticsRollupDoc {
+        id: None,
+        metadata: Metadata::new(),
+        path_id: analytics.path_id,
+        path_title: analytics.path_title,
+        learners_started: started,
+        learners_completed: analytics.learners_completed,
+        suppressed: false,
+        funnel: analytics
+            .funnel
+            .into_iter()
+            .map(|step| FunnelStepRollup {
+                step_index: step.step_index,
+                step_title: step.step_title,
+                estimated_minutes: step.estimated_minutes,
+                completions: step.completions,
+                completion_rate: if started == 0 {
+                    0.0
+                } else {
+                    step.completions as f64 / started as f64
+                },
+            })
+            .collect(),
+        avg_estimated_minutes_per_step: analytics.avg_estimated_minutes_per_step,
+        avg_actual_minutes_per_step: analytics.avg_actual_minutes_per_step,
+        challenge_score_histogram: analytics.challenge_score_histogram,
+        challenges_completed: analytics.challenges_completed,
+        computed_at: Some(Utc::now()),
+    };
+
+    if started < MIN_COHORT_SIZE {
+        rollup.suppress();
+    } else if rollup.challenges_completed < MIN_COHORT_SIZE {
+        // Enough learners, but too few scores to show a distribution
+        rollup.challenge_score_histogram.clear();
+        rollup.challenges_completed = 0;
+    }
+    rollup
+}
+
+/// Run a single rollup: aggregate every path and replace its document.
+pub async fn rollup_once(
+    zome_caller: &ZomeCaller,
+    mongo: &MongoClient,
+) -> Result<RollupSummary, String> {
+    let collection: MongoCollection<PathAnalyticsRollupDoc> = mongo
+        .collection(ANALYTICS_ROLLUP_COLLECTION)
+        .await
+        .map_err(|e| format!("Analytics collection unavailable: {e}"))?;
+
+    let index: PathIndex = zome_caller
+        .call(CONTENT_ROLE, CONTENT_ZOME, "get_all_paths", &())
+        .await?;
+
+    let mut summary = RollupSummary {
+        paths: index.paths.len(),
+        ..Default::default()
+    };
+
+    for entry in index.paths {
+        let input = PathAnalyticsInput {
+            path_id: entry.id.clone(),
+        };
+        let analytics: PathAnalytics = match zome_caller
+            .call(CONTENT_ROLE, CONTENT_ZOME, "get_path_analytics", &input)
+            .await
+        {
+            Ok(analytics) => analytics,
+            Err(e) => {
+                warn!(path_id = %entry.id, error = %e, "Path analytics failed");
+                summary.failed += 1;
+                continue;
+            }
+        };
+
+        let rollup = to_rollup(analytics);
+        let result = collection
+            .inner()
+            .replace_one(doc! { "path_id": &rollup.path_id }, &rollup)
+            .upsert(true)
+            .await;
+
+        match result {
+            Ok(_) => {
+                summary.written += 1;
+                if rollup.suppressed {
+                    summary.suppressed += 1;
+                }
+            }
+            Err(e) => {
+                warn!(path_id = %entry.id, error = %e, "Failed to store analytics rollup");
+                summary.failed += 1;
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Spawn the periodic analytics rollup.
+///
+/// Runs once at startup, then every `interval`. Failures are logged and
+/// retried on the next interval.
+pub fn spawn_analytics_rollup_task(
+    interval: Duration,
+    zome_caller: Arc<ZomeCaller>,
+    mongo: MongoClient,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!(
+            interval_secs = interval.as_secs(),
+            "Analytics rollup task started"
+        );
+
+        loop {
+            match rollup_once(&zome_caller, &mongo).await {
+                Ok(summary) if summary.paths > 0 => {
+                    info!(
+                        paths = summary.paths,
+                        written = summary.written,
+                        suppressed = summary.suppressed,
+                        failed = summary.failed,
+                        "Analytics rollup complete"
+                    );
+                }
+                Ok(_) => debug!("Analytics rollup: no paths"),
+                Err(e) => {
+                    warn!(error = %e, "Analytics rollup failed (will retry next interval)");
+                }
+            }
+
+            tokio::time::sleep(interval).await;
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analytics(started: u32, challenges: u32) -> PathAnalytics {
+        PathAnalytics {
+            path_id: "path-1".to_string(),
+            path_title: "Path".to_string(),
+            learners_started: started,
+            learners_completed: started / 2,
+            funnel: vec![StepFunnelEntry {
+                step_index: 0,
+                step_title: Some("Intro".to_string()),
+                resource_id: "content-1".to_string(),
+                estimated_minutes: Some(5),
+                completions: started,
+            }],
+            avg_estimated_minutes_per_step: Some(5.0),
+            avg_actual_minutes_per_step: Some(7.5),
+            challenge_score_histogram: vec![0, 0, 0, 0, 0, 0, 0, 0, challenges, 0],
+            challenges_completed: challenges,
+        }
+    }
+
+    #[test]
+    fn test_to_rollup_reports_large_cohorts() {
+        let rollup = to_rollup(analytics(10, 6));
+        assert!(!rollup.suppressed);
+        assert_eq!(rollup.learners_completed, 5);
+        assert_eq!(rollup.funnel[0].completion_rate, 1.0);
+        assert_eq!(rollup.challenges_completed, 6);
+        assert_eq!(rollup.challenge_score_histogram.len(), 10);
+    }
+
+    #[test]
+    fn test_to_rollup_suppresses_small_cohorts() {
+        let rollup = to_rollup(analytics(MIN_COHORT_SIZE - 1, 6));
+        assert!(rollup.suppressed);
+        assert_eq!(rollup.learners_started, MIN_COHORT_SIZE - 1);
+        assert!(rollup.funnel.is_empty());
+        assert!(rollup.challenge_score_histogram.is_empty());
+    }
+
+    #[test]
+    fn test_to_rollup_hides_sparse_score_distribution() {
+        let rollup = to_rollup(analytics(10, 2));
+        assert!(!rollup.suppressed);
+        assert!(!rollup.funnel.is_empty());
+        assert!(rollup.challenge_score_histogram.is_empty());
+        assert_eq!(rollup.challenges_completed, 0);
+    }
+}
@@ -0,0 +1,421 @@
+//! Open Badges export
+//!
+//! Packages a learner's earned attestations as Open Badges 3.0 credentials
+//! they can import into badge backpacks and LinkedIn. Exports run in the
+//! background: a learner asks for one, the worker reads their attestations
+//! from the imagodei zome, leaves out revoked and expired ones, signs the
+//! rest and keeps the file in memory for [`EXPORT_TTL`].
+//!
+//! Two formats are produced:
+//! - `ob3` - one `OpenBadgeCredential` per attestation
+//! - `clr` - a single CLR 2.0 `ClrCredential` bundling them
+//!
+//! Credentials are issued by the doorway's `did:web` and secured as VC-JWTs
+//! (compact JWS, `EdDSA`) signed with the operator's issuer key, which the
+//! DID document publishes as `#badge-issuer`. Each credential's id is its
+//! public `/verify/attestation/{id}` page.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use bytes::Bytes;
+use chrono::{DateTime, SecondsFormat, Utc};
+use dashmap::DashMap;
+use ed25519_dalek::{Signer, SigningKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::routes::verify::{attestation_status, open_badge_credential, OPEN_BADGE_CONTEXT};
+use crate::routes::zome_helpers::AttestationVerification;
+use crate::services::zome_caller::ZomeCaller;
+
+/// DID URL fragment of the issuer key
+pub const BADGE_KEY_FRAGMENT: &str = "badge-issuer";
+
+/// How long a finished export stays downloadable
+pub const EXPORT_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// JSON-LD context of a CLR 2.0 credential
+const CLR_CONTEXT: &str = "https://purl.imsglobal.org/spec/clr/v2p0/context-2.0.1.json";
+
+/// Export file format
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// One signed OpenBadgeCredential per attestation
+    #[default]
+    Ob3,
+    /// One signed ClrCredential containing every badge
+    Clr,
+}
+
+/// Where an export is up to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportStatus {
+    Pending,
+    Ready,
+    Failed,
+}
+
+/// A learner's latest export
+#[derive(Debug, Clone, Serialize)]
+pub struct BadgeExportJob {
+    pub status: ExportStatus,
+    pub format: ExportFormat,
+    pub requested_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Badges in the export
+    pub badges: usize,
+    pub error: Option<String>,
+    /// Export file, once ready
+    #[serde(skip)]
+    pub file: Option<Bytes>,
+}
+
+impl BadgeExportJob {
+    fn expired(&self, now: DateTime<Utc>) -> bool {
+        self.finished_at
+            .is_some_and(|finished| (now - finished).to_std().is_ok_and(|age| age > EXPORT_TTL))
+    }
+}
+
+/// Signs credentials as the doorway's badge issuer
+pub struct BadgeIssuer {
+    issuer_id: String,
+    name: String,
+    base_url: String,
+    signing_key: SigningKey,
+}
+
+impl BadgeIssuer {
+    pub fn new(issuer_id: String, name: String, base_url: String, signing_key: SigningKey) -> Self {
+        Self {
+            issuer_id,
+            name,
+            base_url,
+            signing_key,
+        }
+    }
+
+    /// Issuer key from a base64 32-byte Ed25519 seed
+    pub fn parse_key(seed: &str) -> Result<SigningKey, String> {
+        let seed: [u8; 32] = base64::engine::general_purpose::STANDARD
+            .decode(seed.trim())
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| "BADGE_ISSUER_KEY must be a base64 32-byte Ed25519 seed".to_string())?;
+        Ok(SigningKey::from_bytes(&seed))
+    }
+
+    /// DID URL of the issuer key, as a JWS `kid`
+    pub fn key_id(&self) -> String {
+        format!("{}#{BADGE_KEY_FRAGMENT}", self.issuer_id)
+    }
+
+    pub fn verifying_key(&self) -> VerifyingKey {
+        self.signing_key.verifying_key()
+    }
+
+    /// Open Badges issuer `Profile`
+    fn profile(&self) -> Value {
+        json!({
+            "id": self.issuer_id,
+            "type": ["Profile"],
+            "name": self.name,
+            "url": self.base_url.trim_end_matches('/'),
+        })
+    }
+
+    /// Compact JWS over `claims`
+    pub fn sign_jwt(&self, claims: &Value) -> String {
+        let header = json!({ "alg": "EdDSA", "typ": "JWT", "kid": self.key_id() });
+        let signing_input = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(header.to_string()),
+            URL_SAFE_NO_PAD.encode(claims.to_string())
+        );
+        let signature = self.signing_key.sign(signing_input.as_bytes());
+        format!(
+            "{signing_input}.{}",
+            URL_SAFE_NO_PAD.encode(signature.to_bytes())
+        )
+    }
+
+    /// Sign a credential as a VC-JWT, with the registered claims Open
+    /// Badges 3.0 derives from it
+    fn sign_credential(&self, credential: &Value) -> String {
+        let unix = |key: &str| {
+            credential[key]
+                .as_str()
+                .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                .map(|t| t.timestamp())
+        };
+        let mut claims = json!({
+            "iss": self.issuer_id,
+            "jti": credential["id"],
+            "sub": credential["credentialSubject"]["id"],
+            "vc": credential,
+        });
+        if let Some(nbf) = unix("validFrom") {
+            claims["nbf"] = json!(nbf);
+        }
+        if let Some(exp) = unix("validUntil") {
+            claims["exp"] = json!(exp);
+        }
+        self.sign_jwt(&claims)
+    }
+
+    /// An attestation as an OpenBadgeCredential issued by this doorway
+    pub fn badge_credential(&self, verification: &AttestationVerification) -> Value {
+        let mut credential = open_badge_credential(verification, &self.base_url);
+        credential["issuer"] = self.profile();
+        credential
+    }
+
+    /// Build the export file for a learner's attestations; returns the file
+    /// and how many badges it holds
+    pub fn build_export(
+        &self,
+        human_id: &str,
+        verifications: &[AttestationVerification],
+        format: ExportFormat,
+        now: DateTime<Utc>,
+    ) -> (Value, usize) {
+        let credentials: Vec<Value> = verifications
+            .iter()
+            .filter(|v| attestation_status(v, now) == "valid")
+            .map(|v| self.badge_credential(v))
+            .collect();
+        let badges = credentials.len();
+
+        let export = match format {
+            ExportFormat::Ob3 => json!({
+                "issuer": self.issuer_id,
+                "verificationMethod": self.key_id(),
+                "credentials": credentials
+                    .iter()
+                    .map(|c| json!({ "credential": c, "jwt": self.sign_credential(c) }))
+                    .collect::<Vec<_>>(),
+            }),
+            ExportFormat::Clr => {
+                let holder_name = verifications
+                    .iter()
+                    .find_map(|v| v.holder.as_ref()?.display_name.clone());
+                let clr = json!({
+                    "@context": [OPEN_BADGE_CONTEXT[0], CLR_CONTEXT, OPEN_BADGE_CONTEXT[1]],
+                    "id": format!("urn:elohim:clr:{human_id}:{}", now.timestamp()),
+                    "type": ["VerifiableCredential", "ClrCredential"],
+                    "issuer": self.profile(),
+                    "name": match holder_name {
+                        Some(name) => format!("Achievements of {name}"),
+                        None => "Learner achievements".to_string(),
+                    },
+                    "validFrom": now.to_rfc3339_opts(SecondsFormat::Secs, true),
+                    "credentialSubject": {
+                        "id": format!("urn:elohim:human:{human_id}"),
+                        "type": ["ClrSubject"],
+                        "achievement": credentials
+                            .iter()
+                            .map(|c| c["credentialSubject"]["achievement"].clone())
+                            .collect::<Vec<_>>(),
+                        "verifiableCredential": credentials,
+                    },
+                });
+                json!({
+                    "issuer": self.issuer_id,
+                    "verificationMethod": self.key_id(),
+                    "jwt": self.sign_credential(&clr),
+                    "credential": clr,
+                })
+            }
+        };
+        (export, badges)
+    }
+}
+
+/// Runs learners' badge exports and holds the results
+pub struct BadgeExporter {
+    issuer: BadgeIssuer,
+    zome_caller: Arc<ZomeCaller>,
+    jobs: DashMap<String, BadgeExportJob>,
+}
+
+impl BadgeExporter {
+    pub fn new(issuer: BadgeIssuer, zome_caller: Arc<ZomeCaller>) -> Self {
+        Self {
+            issuer,
+            zome_caller,
+            jobs: DashMap::new(),
+        }
+    }
+
+    pub fn issuer(&self) -> &BadgeIssuer {
+        &self.issuer
+    }
+
+    /// A learner's latest export, unless it has expired
+    pub fn job(&self, human_id: &str) -> Option<BadgeExportJob> {
+        let now = Utc::now();
+        self.jobs.remove_if(human_id, |_, job| job.expired(now));
+        self.jobs.get(human_id).map(|job| job.clone())
+    }
+
+    /// Start an export for a learner. A pending export is returned as is
+    /// rather than started twice.
+    pub fn request(self: &Arc<Self>, human_id: &str, format: ExportFormat) -> BadgeExportJob {
+        if let Some(job) = self.jobs.get(human_id) {
+            if job.status == ExportStatus::Pending {
+                return job.clone();
+            }
+        }
+        let job = BadgeExportJob {
+            status: ExportStatus::Pending,
+            format,
+            requested_at: Utc::now(),
+            finished_at: None,
+            badges: 0,
+            error: None,
+            file: None,
+        };
+        self.jobs.insert(human_id.to_string(), job.clone());
+
+        let exporter = Arc::clone(self);
+        let human_id = human_id.to_string();
+        tokio::spawn(async move {
+            let result = exporter.run(&human_id, format).await;
+            if let Some(mut job) = exporter.jobs.get_mut(&human_id) {
+                job.finished_at = Some(Utc::now());
+                match result {
+                    Ok((file, badges)) => {
+                        info!(human_id = %human_id, badges, ?format, "Badge export ready");
+                        job.status = ExportStatus::Ready;
+                        job.badges = badges;
+                        job.file = Some(file);
+                    }
+                    Err(e) => {
+                        warn!(human_id = %human_id, error = %e, "Badge export failed");
+                        job.status = ExportStatus::Failed;
+                        job.error = Some(e);
+                    }
+                }
+            }
+        });
+        job
+    }
+
+    async fn run(&self, human_id: &str, format: ExportFormat) -> Result<(Bytes, usize), String> {
+        let verifications: Vec<AttestationVerification> = self
+            .zome_caller
+            .call(
+                "imagodei",
+                "imagodei",
+                "get_attestation_verifications",
+                &human_id,
+            )
+            .await?;
+        let (export, badges) =
+            self.issuer
+                .build_export(human_id, &verifications, format, Utc::now());
+        let file = serde_json::to_vec_pretty(&export).map_err(|e| e.to_string())?;
+        Ok((Bytes::from(file), badges))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routes::zome_helpers::{Attestation, AttestationRevocation};
+    use ed25519_dalek::{Signature, Verifier};
+
+    fn issuer() -> BadgeIssuer {
+        BadgeIssuer::new(
+            "did:web:doorway.example".to_string(),
+            "Elohim".to_string(),
+            "https://doorway.example".to_string(),
+            SigningKey::from_bytes(&[3u8; 32]),
+        )
+    }
+
+    fn verification(id: &str, revoked: bool) -> AttestationVerification {
+        AttestationVerification {
+            attestation: Attestation {
+                id: id.to_string(),
+                agent_id: "human-1".to_string(),
+                category: "path_completion".to_string(),
+                attestation_type: id.to_string(),
+                display_name: format!("Badge {id}"),
+                description: "Completed a path".to_string(),
+                icon_url: None,
+                tier: None,
+                earned_via_json: "{}".to_string(),
+                issued_at: "2026-01-02T03:04:05Z".to_string(),
+                issued_by: "uhCAkIssuer".to_string(),
+                expires_at: None,
+                proof: None,
+            },
+            holder: None,
+            revocation: revoked.then(|| AttestationRevocation {
+                attestation_id: id.to_string(),
+                revoked_by: "uhCAkIssuer".to_string(),
+                reason: "Issued in error".to_string(),
+                revoked_at: "2026-02-01T00:00:00Z".to_string(),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_parse_key() {
+        let seed = base64::engine::general_purpose::STANDARD.encode([9u8; 32]);
+        assert!(BadgeIssuer::parse_key(&seed).is_ok());
+        assert!(BadgeIssuer::parse_key("not-a-key").is_err());
+    }
+
+    #[test]
+    fn test_jwt_verifies_with_issuer_key() {
+        let issuer = issuer();
+        let jwt = issuer.sign_jwt(&json!({ "iss": "did:web:doorway.example" }));
+        let parts: Vec<&str> = jwt.split('.').collect();
+        assert_eq!(parts.len(), 3);
+
+        let header: Value =
+            serde_json::from_slice(&URL_SAFE_NO_PAD.decode(parts[0]).unwrap()).unwrap();
+        assert_eq!(header["alg"], "EdDSA");
+        assert_eq!(header["kid"], "did:web:doorway.example#badge-issuer");
+
+        let signature = Signature::from_slice(&URL_SAFE_NO_PAD.decode(parts[2]).unwrap()).unwrap();
+        let signing_input = format!("{}.{}", parts[0], parts[1]);
+        assert!(issuer
+            .verifying_key()
+            .verify(signing_input.as_bytes(), &signature)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_export_leaves_out_revoked_badges() {
+        let issuer = issuer();
+        let verifications = vec![verification("a", false), verification("b", true)];
+        let now = Utc::now();
+
+        let (export, badges) =
+            issuer.build_export("human-1", &verifications, ExportFormat::Ob3, now);
+        assert_eq!(badges, 1);
+        let credential = &export["credentials"][0]["credential"];
+        assert_eq!(credential["issuer"]["id"], "did:web:doorway.example");
+        assert_eq!(
+            credential["id"],
+            "https://doorway.example/verify/attestation/a"
+        );
+
+        let (clr, badges) = issuer.build_export("human-1", &verifications, ExportFormat::Clr, now);
+        assert_eq!(badges, 1);
+        assert_eq!(clr["credential"]["type"][1], "ClrCredential");
+        assert_eq!(
+            clr["credential"]["credentialSubject"]["verifiableCredential"]
+                .as_array()
+                .map(Vec::len),
+            Some(1)
+        );
+    }
+}
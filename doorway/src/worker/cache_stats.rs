@@ -0,0 +1,192 @@
+//! Cache rule rollups
+//!
+//! Every interval, writes what each cache rule did since the previous
+//! rollup (hits, misses, stale serves, invalidations, payload sizes) to the
+//! `cache_rule_rollups` collection, so `/admin/cache/stats` can report on
+//! spans longer than one process lifetime.
+
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::cache::{ContentCache, RuleStatsSnapshot};
+use crate::db::schemas::{CacheRuleRollupDoc, Metadata, CACHE_RULE_ROLLUP_COLLECTION};
+use crate::db::{MongoClient, MongoCollection};
+
+type RuleId = (String, String);
+
+/// Documents for the rules that did something between two snapshots
+pub fn window_rollups(
+    previous: &HashMap<RuleId, RuleStatsSnapshot>,
+    current: &[RuleStatsSnapshot],
+    window_start: DateTime<Utc>,
+    window_end: DateTime<Utc>,
+) -> Vec<CacheRuleRollupDoc> {
+    current
+        .iter()
+        .map(
+            |stats| match previous.get(&(stats.dna_hash.clone(), stats.fn_name.clone())) {
+                Some(earlier) => stats.since(earlier),
+                None => stats.clone(),
+            },
+        )
+        .filter(|window| !window.is_empty())
+        .map(|window| CacheRuleRollupDoc {
+            id: None,
+            metadata: Metadata::new(),
+            stats: window,
+            window_start: Some(window_start),
+            window_end: Some(window_end),
+        })
+        .collect()
+}
+
+/// Sum rollups per rule, busiest rule first
+pub fn summarize(rollups: &[CacheRuleRollupDoc]) -> Vec<RuleStatsSnapshot> {
+    let mut totals: HashMap<RuleId, RuleStatsSnapshot> = HashMap::new();
+    for rollup in rollups {
+        let stats = &rollup.stats;
+        totals
+            .entry((stats.dna_hash.clone(), stats.fn_name.clone()))
+            .and_modify(|total| total.merge(stats))
+            .or_insert_with(|| stats.clone());
+    }
+
+    let mut totals: Vec<RuleStatsSnapshot> = totals.into_values().collect();
+    totals.sort_by(|a, b| {
+        (b.hits + b.misses)
+            .cmp(&(a.hits + a.misses))
+            .then_with(|| a.fn_name.cmp(&b.fn_name))
+    });
+    totals
+}
+
+/// Spawn the periodic cache rule rollup.
+///
+/// The first window starts when the task does. A rule whose rollup fails to
+/// store carries its counts into the next window rather than losing them.
+pub fn spawn_cache_stats_rollup_task(
+    interval: Duration,
+    cache: Arc<ContentCache>,
+    mongo: MongoClient,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!(
+            interval_secs = interval.as_secs(),
+            "Cache rule rollup task started"
+        );
+
+        let mut previous: HashMap<RuleId, RuleStatsSnapshot> = HashMap::new();
+        let mut window_start = Utc::now();
+
+        loop {
+            tokio::time::sleep(interval).await;
+
+            let collection: MongoCollection<CacheRuleRollupDoc> = match mongo
+                .collection(CACHE_RULE_ROLLUP_COLLECTION)
+                .await
+            {
+                Ok(collection) => collection,
+                Err(e) => {
+                    warn!(error = %e, "Cache rollup collection unavailable (will retry next interval)");
+                    continue;
+                }
+            };
+
+            let now = Utc::now();
+            let current = cache.rule_stats().snapshot();
+            let rollups = window_rollups(&previous, &current, window_start, now);
+
+            let current: HashMap<RuleId, RuleStatsSnapshot> = current
+                .into_iter()
+                .map(|stats| ((stats.dna_hash.clone(), stats.fn_name.clone()), stats))
+                .collect();
+            let mut written = 0;
+            for rollup in rollups {
+                let rule = (rollup.stats.dna_hash.clone(), rollup.stats.fn_name.clone());
+                match collection.insert_one(rollup).await {
+                    Ok(_) => {
+                        written += 1;
+                        if let Some(stats) = current.get(&rule) {
+                            previous.insert(rule, stats.clone());
+                        }
+                    }
+                    Err(e) => {
+                        warn!(fn_name = %rule.1, error = %e, "Failed to store cache rule rollup");
+                    }
+                }
+            }
+
+            if written == 0 {
+                debug!("Cache rule rollup: nothing written");
+            } else {
+                info!(rules = written, "Cache rule rollup complete");
+            }
+            window_start = now;
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(fn_name: &str, hits: u64, misses: u64) -> RuleStatsSnapshot {
+        RuleStatsSnapshot {
+            dna_hash: "dna".to_string(),
+            fn_name: fn_name.to_string(),
+            hits,
+            misses,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_window_rollups_skip_idle_rules() {
+        let previous: HashMap<RuleId, RuleStatsSnapshot> = [
+            (
+                ("dna".to_string(), "get_a".to_string()),
+                stats("get_a", 10, 2),
+            ),
+            (
+                ("dna".to_string(), "get_b".to_string()),
+                stats("get_b", 4, 4),
+            ),
+        ]
+        .into_iter()
+        .collect();
+        let current = vec![
+            stats("get_a", 15, 3),
+            stats("get_b", 4, 4),
+            stats("get_c", 1, 1),
+        ];
+
+        let now = Utc::now();
+        let mut rollups = window_rollups(&previous, &current, now, now);
+        rollups.sort_by(|a, b| a.stats.fn_name.cmp(&b.stats.fn_name));
+
+        assert_eq!(rollups.len(), 2);
+        assert_eq!((rollups[0].stats.hits, rollups[0].stats.misses), (5, 1));
+        assert_eq!(rollups[1].stats.fn_name, "get_c");
+    }
+
+    #[test]
+    fn test_summarize_merges_windows() {
+        let rollup = |s: RuleStatsSnapshot| CacheRuleRollupDoc {
+            stats: s,
+            ..Default::default()
+        };
+        let totals = summarize(&[
+            rollup(stats("get_a", 1, 1)),
+            rollup(stats("get_b", 10, 0)),
+            rollup(stats("get_a", 3, 0)),
+        ]);
+
+        assert_eq!(totals.len(), 2);
+        assert_eq!(totals[0].fn_name, "get_b");
+        assert_eq!((totals[1].hits, totals[1].misses), (4, 1));
+    }
+}
@@ -0,0 +1,443 @@
+//! Content health sweep
+//!
+//! Periodically walks all content and records maintenance work for stewards
+//! in the `content_health_reports` collection:
+//!
+//! - **Stale**: content not updated within the configured number of months
+//! - **Broken fallback URL**: a blob fallback URL errors or returns 4xx/5xx
+//! - **Archived relationship**: a related node is archived or forgotten
+//!
+//! The content DNA supplies the raw signals page by page; URL probing
+//! happens here since zomes can't make HTTP requests. Archived content is
+//! not checked itself. Reports for content that has become healthy are
+//! removed at the end of a complete sweep.
+
+use bson::doc;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::db::schemas::{
+    ContentHealthIssue, ContentHealthIssueKind, ContentHealthReportDoc, Metadata,
+    CONTENT_HEALTH_COLLECTION,
+};
+use crate::db::{MongoClient, MongoCollection};
+use crate::services::zome_caller::ZomeCaller;
+
+/// Role holding the content_store zome
+const CONTENT_ROLE: &str = "lamad";
+
+/// Zome exposing the content health scan
+const CONTENT_ZOME: &str = "content_store";
+
+/// Must match CONTENT_TYPES in holochain/dna/elohim/zomes/content_store_integrity/src/lib.rs
+const CONTENT_TYPES: [&str; 12] = [
+    "epic",
+    "concept",
+    "lesson",
+    "scenario",
+    "assessment",
+    "resource",
+    "reflection",
+    "discussion",
+    "exercise",
+    "example",
+    "reference",
+    "article",
+];
+
+/// Snapshots requested per zome call (the zome caps at 100)
+const PAGE_SIZE: u32 = 100;
+
+/// Per-URL probe timeout
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Lifecycle statuses that count as archived
+const ARCHIVED_STATUSES: [&str; 2] = ["archived", "forgotten"];
+
+/// Length of a "month" for the staleness window
+const MICROS_PER_MONTH: i64 = 30 * 24 * 60 * 60 * 1_000_000;
+
+/// Input for content_store::get_content_health_page
+#[derive(Debug, Clone, Serialize)]
+pub struct ContentHealthPageInput {
+    pub content_type: String,
+    pub offset: u32,
+    pub page_size: u32,
+}
+
+/// Must match BlobFallbacks in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlobFallbacks {
+    pub hash: String,
+    pub reach: String,
+    pub mime_type: String,
+    pub fallback_urls: Vec<String>,
+}
+
+/// Must match ContentHealthSnapshot in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentHealthSnapshot {
+    pub content_id: String,
+    pub title: String,
+    pub content_type: String,
+    pub author_id: Option<String>,
+    pub updated_at_micros: i64,
+    pub lifecycle_status: Option<String>,
+    pub related_ids: Vec<String>,
+    pub blobs: Vec<BlobFallbacks>,
+}
+
+/// Must match ContentHealthPage in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Clone, Deserialize)]
+pub struct ContentHealthPage {
+    pub items: Vec<ContentHealthSnapshot>,
+    pub total_count: u32,
+    pub has_more: bool,
+}
+
+/// Outcome of one sweep
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SweepSummary {
+    pub scanned: usize,
+    pub flagged: usize,
+    pub urls_probed: usize,
+    pub failed: usize,
+}
+
+fn is_archived(status: Option<&str>) -> bool {
+    status.is_some_and(|s| ARCHIVED_STATUSES.contains(&s))
+}
+
+/// Find the issues of one content node
+///
+/// `statuses` maps every scanned content id to its lifecycle status;
+/// `broken_urls` maps failed fallback URLs to the failure reason.
+pub fn assess(
+    snapshot: &ContentHealthSnapshot,
+    statuses: &HashMap<String, Option<String>>,
+    broken_urls: &HashMap<String, String>,
+    now_micros: i64,
+    stale_months: u32,
+) -> Vec<ContentHealthIssue> {
+    let mut issues = Vec::new();
+    if is_archived(snapshot.lifecycle_status.as_deref()) {
+        return issues;
+    }
+
+    let age_micros = now_micros - snapshot.updated_at_micros;
+    if stale_months > 0 && age_micros > stale_months as i64 * MICROS_PER_MONTH {
+        issues.push(ContentHealthIssue::new(
+            ContentHealthIssueKind::Stale,
+            format!("Not updated in {} months", age_micros / MICROS_PER_MONTH),
+            None,
+        ));
+    }
+
+    for blob in &snapshot.blobs {
+        for url in &blob.fallback_urls {
+            if let Some(reason) = broken_urls.get(url) {
+                issues.push(ContentHealthIssue::new(
+                    ContentHealthIssueKind::BrokenFallbackUrl,
+                    format!("Blob {}: {reason}", blob.hash),
+                    Some(url.clone()),
+                ));
+            }
+        }
+    }
+
+    for related_id in &snapshot.related_ids {
+        let status = statuses.get(related_id).and_then(|s| s.as_deref());
+        if is_archived(status) {
+            issues.push(ContentHealthIssue::new(
+                ContentHealthIssueKind::ArchivedRelationship,
+                format!("Related node is {}", status.unwrap_or("archived")),
+                Some(related_id.clone()),
+            ));
+        }
+    }
+
+    issues
+}
+
+/// Check that a URL answers; returns the failure reason if it doesn't
+///
+/// Uses HEAD, falling back to GET for servers that don't allow HEAD.
+async fn probe_url(client: &reqwest::Client, url: &str) -> Option<String> {
+    let response = match client.head(url).send().await {
+        Ok(response)
+            if matches!(
+                response.status(),
+                reqwest::StatusCode::METHOD_NOT_ALLOWED | reqwest::StatusCode::NOT_IMPLEMENTED
+            ) =>
+        {
+            client.get(url).send().await
+        }
+        other => other,
+    };
+
+    match response {
+        Ok(response)
+            if response.status().is_client_error() || response.status().is_server_error() =>
+        {
+            Some(format!("HTTP {}", response.status()))
+        }
+        Ok(_) => None,
+        Err(e) if e.is_timeout() => Some("Timed out".to_string()),
+        Err(e) => Some(format!("Request failed: {e}")),
+    }
+}
+
+/// Fetch every content snapshot, page by page and type by type
+///
+/// Also used by the [blob mirror](crate::worker::blob_mirror) to find blobs.
+/// Returns the snapshots and the number of pages that failed.
+pub async fn scan_all(zome_caller: &ZomeCaller) -> (Vec<ContentHealthSnapshot>, usize) {
+    let mut snapshots = Vec::new();
+    let mut failed = 0;
+    for content_type in CONTENT_TYPES {
+        let mut offset = 0;
+        loop {
+            let input = ContentHealthPageInput {
+                content_type: content_type.to_string(),
+                offset,
+                page_size: PAGE_SIZE,
+            };
+            let page: ContentHealthPage = match zome_caller
+                .call(
+                    CONTENT_ROLE,
+                    CONTENT_ZOME,
+                    "get_content_health_page",
+                    &input,
+                )
+                .await
+            {
+                Ok(page) => page,
+                Err(e) => {
+                    warn!(content_type, offset, error = %e, "Content health scan page failed");
+                    failed += 1;
+                    break;
+                }
+            };
+            offset += PAGE_SIZE;
+            snapshots.extend(page.items);
+            if !page.has_more {
+                break;
+            }
+        }
+    }
+    (snapshots, failed)
+}
+
+/// Run a single sweep: scan content, probe blob URLs and write reports.
+pub async fn sweep_once(
+    zome_caller: &ZomeCaller,
+    mongo: &MongoClient,
+    client: &reqwest::Client,
+    stale_months: u32,
+) -> Result<SweepSummary, String> {
+    let collection: MongoCollection<ContentHealthReportDoc> = mongo
+        .collection(CONTENT_HEALTH_COLLECTION)
+        .await
+        .map_err(|e| format!("Content health collection unavailable: {e}"))?;
+
+    let sweep_started = bson::DateTime::now();
+    let (snapshots, failed) = scan_all(zome_caller).await;
+    let mut summary = SweepSummary {
+        scanned: snapshots.len(),
+        failed,
+        ..Default::default()
+    };
+
+    let statuses: HashMap<String, Option<String>> = snapshots
+        .iter()
+        .map(|s| (s.content_id.clone(), s.lifecycle_status.clone()))
+        .collect();
+
+    // Probe each distinct URL once
+    let mut broken_urls: HashMap<String, String> = HashMap::new();
+    let mut probed: HashSet<&str> = HashSet::new();
+    for snapshot in snapshots
+        .iter()
+        .filter(|s| !is_archived(s.lifecycle_status.as_deref()))
+    {
+        for url in snapshot.blobs.iter().flat_map(|b| b.fallback_urls.iter()) {
+            if !probed.insert(url) {
+                continue;
+            }
+            if let Some(reason) = probe_url(client, url).await {
+                debug!(url, reason, "Blob fallback URL broken");
+                broken_urls.insert(url.clone(), reason);
+            }
+        }
+    }
+    summary.urls_probed = probed.len();
+
+    let now = Utc::now();
+    for snapshot in &snapshots {
+        let issues = assess(
+            snapshot,
+            &statuses,
+            &broken_urls,
+            now.timestamp_micros(),
+            stale_months,
+        );
+        if issues.is_empty() {
+            continue;
+        }
+
+        let report = ContentHealthReportDoc {
+            id: None,
+            metadata: Metadata::new(),
+            content_id: snapshot.content_id.clone(),
+            title: snapshot.title.clone(),
+            content_type: snapshot.content_type.clone(),
+            author_id: snapshot.author_id.clone(),
+            last_updated_at: DateTime::from_timestamp_micros(snapshot.updated_at_micros),
+            issues,
+            checked_at: Some(now),
+        };
+        match collection
+            .inner()
+            .replace_one(doc! { "content_id": &report.content_id }, &report)
+            .upsert(true)
+            .await
+        {
+            Ok(_) => summary.flagged += 1,
+            Err(e) => {
+                warn!(content_id = %report.content_id, error = %e, "Failed to store content health report");
+                summary.failed += 1;
+            }
+        }
+    }
+
+    // Anything not rewritten this sweep is healthy now. Skip after partial
+    // scans so unscanned content keeps its report.
+    if summary.failed == 0 {
+        collection
+            .inner()
+            .delete_many(doc! { "metadata.updated_at": { "$lt": sweep_started } })
+            .await
+            .map_err(|e| format!("Failed to clear resolved reports: {e}"))?;
+    }
+
+    Ok(summary)
+}
+
+/// Spawn the periodic content health sweep.
+///
+/// A scan page or report that fails is logged and counted in `failed`; each
+/// sweep rescans all content, so it is looked at again next time.
+pub fn spawn_content_health_task(
+    interval: Duration,
+    zome_caller: Arc<ZomeCaller>,
+    mongo: MongoClient,
+    stale_months: u32,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!(
+            interval_secs = interval.as_secs(),
+            stale_months, "Content health sweep task started"
+        );
+
+        let client = reqwest::Client::builder()
+            .timeout(PROBE_TIMEOUT)
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+
+        loop {
+            tokio::time::sleep(interval).await;
+
+            match sweep_once(&zome_caller, &mongo, &client, stale_months).await {
+                Ok(summary) => {
+                    info!(
+                        scanned = summary.scanned,
+                        flagged = summary.flagged,
+                        urls_probed = summary.urls_probed,
+                        failed = summary.failed,
+                        "Content health sweep complete"
+                    );
+                }
+                Err(e) => {
+                    warn!(error = %e, "Content health sweep failed (will retry next interval)");
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_800_000_000_000_000;
+
+    fn snapshot(id: &str, age_months: i64) -> ContentHealthSnapshot {
+        ContentHealthSnapshot {
+            content_id: id.to_string(),
+            title: id.to_string(),
+            content_type: "concept".to_string(),
+            author_id: None,
+            updated_at_micros: NOW - age_months * MICROS_PER_MONTH,
+            lifecycle_status: Some("published".to_string()),
+            related_ids: vec![],
+            blobs: vec![],
+        }
+    }
+
+    #[test]
+    fn test_assess_stale() {
+        let statuses = HashMap::new();
+        let broken = HashMap::new();
+
+        let fresh = assess(&snapshot("a", 2), &statuses, &broken, NOW, 12);
+        assert!(fresh.is_empty());
+
+        let old = assess(&snapshot("a", 14), &statuses, &broken, NOW, 12);
+        assert_eq!(old.len(), 1);
+        assert_eq!(old[0].kind, ContentHealthIssueKind::Stale);
+
+        // 0 disables the staleness check
+        assert!(assess(&snapshot("a", 14), &statuses, &broken, NOW, 0).is_empty());
+    }
+
+    #[test]
+    fn test_assess_broken_urls_and_archived_relationships() {
+        let mut content = snapshot("a", 1);
+        content.related_ids = vec!["old".to_string(), "live".to_string()];
+        content.blobs = vec![BlobFallbacks {
+            hash: "h1".to_string(),
+            reach: "commons".to_string(),
+            mime_type: "video/mp4".to_string(),
+            fallback_urls: vec!["https://ok".to_string(), "https://gone".to_string()],
+        }];
+
+        let statuses = HashMap::from([
+            ("old".to_string(), Some("archived".to_string())),
+            ("live".to_string(), Some("published".to_string())),
+        ]);
+        let broken = HashMap::from([("https://gone".to_string(), "HTTP 404".to_string())]);
+
+        let issues = assess(&content, &statuses, &broken, NOW, 12);
+        assert_eq!(issues.len(), 2);
+        assert!(issues
+            .iter()
+            .any(|i| i.kind == ContentHealthIssueKind::BrokenFallbackUrl
+                && i.target.as_deref() == Some("https://gone")));
+        assert!(issues
+            .iter()
+            .any(|i| i.kind == ContentHealthIssueKind::ArchivedRelationship
+                && i.target.as_deref() == Some("old")));
+    }
+
+    #[test]
+    fn test_assess_skips_archived_content() {
+        let mut content = snapshot("a", 48);
+        content.lifecycle_status = Some("archived".to_string());
+        assert!(assess(&content, &HashMap::new(), &HashMap::new(), NOW, 12).is_empty());
+    }
+}
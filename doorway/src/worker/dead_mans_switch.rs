@@ -0,0 +1,213 @@
+//! Dead man's switch sweep
+//!
+//! Custodian commitments can carry a `dead_mans_switch` emergency trigger:
+//! if the beneficiary stops calling `beneficiary_checkin` for longer than the
+//! configured inactivity window, custodians are expected to begin recovery.
+//! Agents can't act while offline, so the doorway polls the content DNA on a
+//! timer and fires overdue switches on the beneficiary's behalf.
+//!
+//! The zome re-checks the window before acting, so a check-in that lands
+//! between the sweep and the trigger call still wins. Commitments with M-of-N
+//! consensus move to `consensus_pending`; the rest activate directly.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::services::zome_caller::{ZomeCall, ZomeCaller};
+
+/// Role holding the content_store zome
+const CONTENT_ROLE: &str = "lamad";
+
+/// Zome exposing the dead man's switch functions
+const CONTENT_ZOME: &str = "content_store";
+
+/// Dead man's switch state for a commitment
+/// Must match DeadMansSwitchStatus in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadMansSwitchStatus {
+    pub commitment_id: String,
+    pub beneficiary_agent_id: String,
+    pub enabled: bool,
+    pub inactivity_days: u32,
+    pub last_checkin_micros: i64,
+    pub overdue: bool,
+    pub commitment_state: String,
+}
+
+/// Outcome of one sweep
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SweepSummary {
+    pub overdue: usize,
+    pub triggered: usize,
+    pub skipped: usize,
+    pub failed: usize,
+}
+
+/// Run a single sweep: list overdue switches and trigger each one.
+pub async fn sweep_once(zome_caller: &impl ZomeCall) -> Result<SweepSummary, String> {
+    let overdue: Vec<DeadMansSwitchStatus> = zome_caller
+        .call(
+            CONTENT_ROLE,
+            CONTENT_ZOME,
+            "get_overdue_dead_mans_switches",
+            &(),
+        )
+        .await?;
+
+    let mut summary = SweepSummary {
+        overdue: overdue.len(),
+        ..Default::default()
+    };
+
+    for status in overdue {
+        let result: Result<DeadMansSwitchStatus, String> = zome_caller
+            .call(
+                CONTENT_ROLE,
+                CONTENT_ZOME,
+                "trigger_dead_mans_switch",
+                &status.commitment_id,
+            )
+            .await;
+
+        match result {
+            Ok(updated) if updated.commitment_state != status.commitment_state => {
+                info!(
+                    commitment_id = %updated.commitment_id,
+                    inactivity_days = updated.inactivity_days,
+                    state = %updated.commitment_state,
+                    "Dead man's switch triggered"
+                );
+                summary.triggered += 1;
+            }
+            Ok(_) => {
+                debug!(
+                    commitment_id = %status.commitment_id,
+                    "Dead man's switch no longer overdue, skipped"
+                );
+                summary.skipped += 1;
+            }
+            Err(e) => {
+                warn!(
+                    commitment_id = %status.commitment_id,
+                    error = %e,
+                    "Failed to trigger dead man's switch"
+                );
+                summary.failed += 1;
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Spawn the periodic dead man's switch sweep.
+///
+/// A commitment that fails to trigger is still overdue at the next sweep and
+/// is tried again.
+pub fn spawn_dead_mans_switch_task(
+    interval: Duration,
+    zome_caller: Arc<ZomeCaller>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!(
+            interval_secs = interval.as_secs(),
+            "Dead man's switch sweep task started"
+        );
+
+        loop {
+            tokio::time::sleep(interval).await;
+
+            match sweep_once(zome_caller.as_ref()).await {
+                Ok(summary) if summary.overdue > 0 => {
+                    info!(
+                        overdue = summary.overdue,
+                        triggered = summary.triggered,
+                        skipped = summary.skipped,
+                        failed = summary.failed,
+                        "Dead man's switch sweep complete"
+                    );
+                }
+                Ok(_) => debug!("Dead man's switch sweep: nothing overdue"),
+                Err(e) => {
+                    warn!(error = %e, "Dead man's switch sweep failed (will retry next interval)");
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::zome_caller::StubZomeCaller;
+
+    fn status(commitment_id: &str, commitment_state: &str) -> DeadMansSwitchStatus {
+        DeadMansSwitchStatus {
+            commitment_id: commitment_id.to_string(),
+            beneficiary_agent_id: "uhCAk...".to_string(),
+            enabled: true,
+            inactivity_days: 30,
+            last_checkin_micros: 1_700_000_000_000_000,
+            overdue: true,
+            commitment_state: commitment_state.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_status_msgpack_roundtrip() {
+        let status = status("commitment-1", "accepted");
+
+        // Zome output is encoded with named fields
+        let bytes = rmp_serde::to_vec_named(&status).unwrap();
+        let decoded: DeadMansSwitchStatus = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(decoded.commitment_id, "commitment-1");
+        assert_eq!(decoded.inactivity_days, 30);
+        assert!(decoded.overdue);
+    }
+
+    #[tokio::test]
+    async fn test_sweep_counts_triggered_skipped_and_failed() {
+        let zome = StubZomeCaller(|fn_name: &str, payload: &[u8]| match fn_name {
+            "get_overdue_dead_mans_switches" => Ok(rmp_serde::to_vec_named(&vec![
+                status("commitment-lapsed", "accepted"),
+                status("commitment-checked-in", "accepted"),
+                status("commitment-broken", "accepted"),
+            ])
+            .unwrap()),
+            "trigger_dead_mans_switch" => {
+                let commitment_id: String = rmp_serde::from_slice(payload).unwrap();
+                match commitment_id.as_str() {
+                    "commitment-lapsed" => {
+                        Ok(rmp_serde::to_vec_named(&status(&commitment_id, "active")).unwrap())
+                    }
+                    // Beneficiary checked in after the listing; the zome leaves it alone
+                    "commitment-checked-in" => {
+                        Ok(rmp_serde::to_vec_named(&status(&commitment_id, "accepted")).unwrap())
+                    }
+                    _ => Err("Commitment not found".to_string()),
+                }
+            }
+            other => panic!("unexpected zome call {other}"),
+        });
+
+        let summary = sweep_once(&zome).await.unwrap();
+        assert_eq!(
+            summary,
+            SweepSummary {
+                overdue: 3,
+                triggered: 1,
+                skipped: 1,
+                failed: 1,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sweep_fails_when_listing_fails() {
+        let zome = StubZomeCaller(|_: &str, _: &[u8]| Err("Zome call failed".to_string()));
+        assert!(sweep_once(&zome).await.is_err());
+    }
+}
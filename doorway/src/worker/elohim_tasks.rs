@@ -0,0 +1,175 @@
+//! Elohim task tracking
+//!
+//! Tasks the doorway dispatches to elohim agents (see
+//! [`routes::elohim`](crate::routes::elohim)) are recorded in the
+//! `elohim_tasks` collection. Agents report progress as
+//! [`ElohimTaskUpdate`]s on `ELOHIM.TASK.STATUS.{task_id}`; this worker
+//! subscribes to those subjects and records each update on its task.
+//!
+//! Updates for tasks that already reached a final status are ignored, so a
+//! late `completed` can't overwrite a timeout. Tasks still open after the
+//! task timeout are marked `timed_out` on a timer.
+
+use bson::doc;
+use chrono::Utc;
+use futures_util::StreamExt;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::db::schemas::{ElohimTaskDoc, ElohimTaskStatus, ELOHIM_TASK_COLLECTION};
+use crate::db::{MongoClient, MongoCollection};
+use crate::nats::messages::ELOHIM_TASK_STATUS_SUBJECT_PREFIX;
+use crate::nats::{ElohimTaskUpdate, NatsClient};
+
+/// How often open tasks are checked against the timeout
+const TIMEOUT_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Statuses a task can still leave
+fn open_statuses() -> Vec<bson::Bson> {
+    [ElohimTaskStatus::Dispatched, ElohimTaskStatus::Accepted]
+        .iter()
+        .map(|s| bson::to_bson(s).unwrap_or_default())
+        .collect()
+}
+
+async fn tasks(mongo: &MongoClient) -> Result<MongoCollection<ElohimTaskDoc>, String> {
+    mongo
+        .collection::<ElohimTaskDoc>(ELOHIM_TASK_COLLECTION)
+        .await
+        .map_err(|e| format!("Elohim task collection unavailable: {e}"))
+}
+
+/// Record a progress report on its task. Returns whether the task changed.
+pub async fn apply_update(mongo: &MongoClient, update: &ElohimTaskUpdate) -> Result<bool, String> {
+    let status = bson::to_bson(&update.status).map_err(|e| e.to_string())?;
+    let mut set = doc! {
+        "status": status,
+        "metadata.updated_at": bson::DateTime::now(),
+    };
+    if let Some(ref result) = update.result {
+        set.insert("result_json", result.to_string());
+    }
+    if let Some(ref error) = update.error {
+        set.insert("error", error);
+    }
+    if update.status.is_final() {
+        set.insert(
+            "completed_at",
+            bson::to_bson(&Utc::now()).unwrap_or_default(),
+        );
+    }
+
+    let result = tasks(mongo)
+        .await?
+        .update_one(
+            doc! { "task_id": &update.task_id, "status": { "$in": open_statuses() } },
+            doc! { "$set": set },
+        )
+        .await
+        .map_err(|e| format!("Failed to update elohim task: {e}"))?;
+    Ok(result.modified_count > 0)
+}
+
+/// Mark tasks still open after `timeout` as timed out
+pub async fn expire_tasks(mongo: &MongoClient, timeout: Duration) -> Result<u64, String> {
+    let cutoff = bson::DateTime::from_millis(
+        bson::DateTime::now().timestamp_millis() - timeout.as_millis() as i64,
+    );
+    let timed_out = bson::to_bson(&ElohimTaskStatus::TimedOut).map_err(|e| e.to_string())?;
+    let result = tasks(mongo)
+        .await?
+        .inner()
+        .update_many(
+            doc! {
+                "status": { "$in": open_statuses() },
+                "metadata.created_at": { "$lt": cutoff },
+            },
+            doc! { "$set": {
+                "status": timed_out,
+                "error": "No result from the elohim before the task timeout",
+                "completed_at": bson::to_bson(&Utc::now()).unwrap_or_default(),
+                "metadata.updated_at": bson::DateTime::now(),
+            } },
+        )
+        .await
+        .map_err(|e| format!("Failed to time out elohim tasks: {e}"))?;
+    Ok(result.modified_count)
+}
+
+/// Spawn the tracker: records progress reports and times out stalled tasks
+pub fn spawn_elohim_task_tracker(
+    nats: NatsClient,
+    mongo: MongoClient,
+    timeout: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let subject = format!("{ELOHIM_TASK_STATUS_SUBJECT_PREFIX}.>");
+        let mut subscriber = match nats.subscribe(&subject).await {
+            Ok(subscriber) => subscriber,
+            Err(e) => {
+                warn!(error = %e, "Elohim task tracker could not subscribe, not tracking tasks");
+                return;
+            }
+        };
+        info!(
+            subject = %subject,
+            timeout_secs = timeout.as_secs(),
+            "Elohim task tracker started"
+        );
+
+        let mut ticker = tokio::time::interval(TIMEOUT_SWEEP_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => match expire_tasks(&mongo, timeout).await {
+                    Ok(0) => {}
+                    Ok(n) => info!(count = n, "Elohim tasks timed out"),
+                    Err(e) => warn!(error = %e, "Elohim task timeout sweep failed"),
+                },
+                message = subscriber.next() => {
+                    let Some(message) = message else {
+                        warn!("Elohim task status subscription closed");
+                        break;
+                    };
+                    let update: ElohimTaskUpdate = match serde_json::from_slice(&message.payload) {
+                        Ok(update) => update,
+                        Err(e) => {
+                            warn!(subject = %message.subject, error = %e, "Invalid elohim task update");
+                            continue;
+                        }
+                    };
+                    match apply_update(&mongo, &update).await {
+                        Ok(true) => debug!(
+                            task_id = %update.task_id,
+                            status = ?update.status,
+                            "Elohim task updated"
+                        ),
+                        Ok(false) => debug!(
+                            task_id = %update.task_id,
+                            "Ignored update for unknown or finished elohim task"
+                        ),
+                        Err(e) => warn!(task_id = %update.task_id, error = %e, "Failed to record elohim task update"),
+                    }
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_statuses_exclude_final() {
+        let open = open_statuses();
+        assert_eq!(open.len(), 2);
+        for status in [
+            ElohimTaskStatus::Completed,
+            ElohimTaskStatus::Failed,
+            ElohimTaskStatus::TimedOut,
+        ] {
+            assert!(!open.contains(&bson::to_bson(&status).unwrap()));
+        }
+    }
+}
@@ -0,0 +1,914 @@
+//! Content embeddings and semantic relationship suggestions
+//!
+//! Periodically embeds commons content (title, description and text body)
+//! with the configured provider and stores the vectors in the
+//! `content_embeddings` collection. Content whose text hasn't changed since
+//! the last pass is not re-embedded.
+//!
+//! Providers:
+//!
+//! | Name | Endpoint |
+//! |------|----------|
+//! | `openai` | `POST {url}/v1/embeddings` (api.openai.com or any compatible server) |
+//! | `local` | Same API on a local server, e.g. text-embeddings-inference running an ONNX model |
+//! | `ollama` | `POST {url}/api/embed` |
+//!
+//! [`SemanticIndex`] answers "what reads like this?" for
+//! `GET /content/{id}/semantic-related`. It uses an Atlas `vectorSearch`
+//! index when the deployment supports one and otherwise ranks candidates by
+//! cosine similarity in doorway.
+//!
+//! Newly embedded content that is very close to another node (and not
+//! already related to it) becomes a relationship suggestion. Stewards
+//! approve suggestions through `/steward/relationship-suggestions`, which
+//! calls `content_store::create_relationship` with
+//! `inference_source: "semantic"`.
+
+use bson::doc;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::db::schemas::{
+    vector_index_definition, ContentEmbeddingDoc, Metadata, RelationshipSuggestionDoc,
+    SuggestionStatus, CONTENT_EMBEDDING_COLLECTION, CONTENT_EMBEDDING_VECTOR_INDEX,
+    RELATIONSHIP_SUGGESTION_COLLECTION,
+};
+use crate::db::{MongoClient, MongoCollection};
+use crate::routes::content::QueryContentInput;
+use crate::services::zome_caller::ZomeCaller;
+
+/// Role holding the content_store zome
+const CONTENT_ROLE: &str = "lamad";
+
+/// Zome owning content and relationships
+const CONTENT_ZOME: &str = "content_store";
+
+/// Reach of embedded content (vectors of other reaches never leave the DHT)
+const EMBEDDING_REACH: &str = "commons";
+
+/// Body formats embedded (others contribute title and description only)
+const TEXT_FORMATS: [&str; 5] = ["markdown", "html", "text", "plaintext", "plain"];
+
+/// Longest text sent to a provider, in characters
+const MAX_TEXT_CHARS: usize = 8_000;
+
+/// Texts per provider request
+const BATCH_SIZE: usize = 16;
+
+/// Page size for query_content (zome maximum)
+const PAGE_SIZE: u32 = 100;
+
+/// Candidates considered per result by `$vectorSearch`
+const VECTOR_SEARCH_CANDIDATES: usize = 20;
+
+/// Most suggestions made for one newly embedded node
+const MAX_SUGGESTIONS: usize = 5;
+
+/// Timeout for provider calls
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Embedding provider
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Provider {
+    OpenAi,
+    Local,
+    Ollama,
+}
+
+impl Provider {
+    /// Parse a provider name (case-insensitive)
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "openai" => Some(Self::OpenAi),
+            "local" | "onnx" | "tei" => Some(Self::Local),
+            "ollama" => Some(Self::Ollama),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::OpenAi => "openai",
+            Self::Local => "local",
+            Self::Ollama => "ollama",
+        }
+    }
+
+    pub fn default_url(&self) -> &'static str {
+        match self {
+            Self::OpenAi => "https://api.openai.com",
+            Self::Local => "http://localhost:8080",
+            Self::Ollama => "http://localhost:11434",
+        }
+    }
+
+    /// Model used when none is configured. Local servers serve one model
+    /// and ignore the name.
+    pub fn default_model(&self) -> &'static str {
+        match self {
+            Self::OpenAi => "text-embedding-3-small",
+            Self::Local => "local",
+            Self::Ollama => "nomic-embed-text",
+        }
+    }
+}
+
+/// Embedding settings
+#[derive(Debug, Clone)]
+pub struct EmbeddingConfig {
+    pub provider: Provider,
+    /// Provider API base URL
+    pub url: String,
+    pub api_key: Option<String>,
+    pub model: String,
+    /// Cosine similarity from which a pair is suggested as related
+    pub suggest_threshold: f32,
+}
+
+/// Outcome of one pass
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct EmbeddingPassSummary {
+    pub scanned: usize,
+    pub embedded: usize,
+    pub unchanged: usize,
+    pub suggested: usize,
+    pub failed: usize,
+}
+
+/// A similar content node
+#[derive(Debug, Clone, Serialize)]
+pub struct SemanticMatch {
+    pub content_id: String,
+    pub title: String,
+    pub content_type: String,
+    pub similarity: f32,
+}
+
+/// Cosine similarity of two vectors (0 when either is empty or they differ
+/// in length)
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.is_empty() || a.len() != b.len() {
+        return 0.0;
+    }
+    let (mut dot, mut norm_a, mut norm_b) = (0.0f32, 0.0f32, 0.0f32);
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+/// Rank candidates by similarity to `query`, best first, skipping `exclude`
+pub fn rank_similar(
+    query: &[f32],
+    exclude: &str,
+    candidates: &[ContentEmbeddingDoc],
+    limit: usize,
+) -> Vec<SemanticMatch> {
+    let mut matches: Vec<SemanticMatch> = candidates
+        .iter()
+        .filter(|c| c.content_id != exclude)
+        .map(|c| SemanticMatch {
+            content_id: c.content_id.clone(),
+            title: c.title.clone(),
+            content_type: c.content_type.clone(),
+            similarity: cosine_similarity(query, &c.embedding),
+        })
+        .collect();
+    matches.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+    matches.truncate(limit);
+    matches
+}
+
+/// Text embedded for a content node, or None if it has nothing to embed
+pub fn embedding_text(content: &Value) -> Option<String> {
+    let field = |key: &str| {
+        content
+            .get(key)
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .unwrap_or_default()
+    };
+    let format = field("content_format").to_lowercase();
+    let has_blob = content.get("blob_cid").is_some_and(|b| !b.is_null());
+
+    let mut parts = vec![field("title"), field("description")];
+    if TEXT_FORMATS.contains(&format.as_str()) && !has_blob {
+        parts.push(field("content"));
+    }
+    let text = parts
+        .into_iter()
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    if text.is_empty() {
+        return None;
+    }
+    Some(text.chars().take(MAX_TEXT_CHARS).collect())
+}
+
+fn text_hash(text: &str) -> String {
+    hex::encode(Sha256::digest(text.as_bytes()))
+}
+
+// =============================================================================
+// Zome types (subsets)
+// =============================================================================
+
+/// Subset of ContentStats
+#[derive(Debug, Clone, Deserialize)]
+struct ContentStats {
+    by_type: BTreeMap<String, u32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ContentOutput {
+    content: Value,
+}
+
+/// Subset of PaginatedContentOutput
+#[derive(Debug, Clone, Deserialize)]
+struct PaginatedContentOutput {
+    items: Vec<ContentOutput>,
+    has_more: bool,
+}
+
+/// Must match GetRelationshipsInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct GetRelationshipsInput<'a> {
+    content_id: &'a str,
+    direction: &'static str,
+}
+
+#[derive(Debug, Deserialize)]
+struct RelationshipOutput {
+    relationship: RelationshipEntry,
+}
+
+/// Subset of Relationship
+#[derive(Debug, Deserialize)]
+struct RelationshipEntry {
+    source_id: String,
+    target_id: String,
+}
+
+// =============================================================================
+// Provider API
+// =============================================================================
+
+#[derive(Debug, Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+/// OpenAI-compatible response
+#[derive(Debug, Deserialize)]
+struct OpenAiEmbeddingResponse {
+    data: Vec<OpenAiEmbedding>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiEmbedding {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaEmbeddingResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
+/// Calls the embedding provider
+pub struct Embedder {
+    config: EmbeddingConfig,
+    client: reqwest::Client,
+}
+
+impl Embedder {
+    pub fn new(config: EmbeddingConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        Self { config, client }
+    }
+
+    pub fn config(&self) -> &EmbeddingConfig {
+        &self.config
+    }
+
+    /// Embed a batch of texts; vectors are returned in input order
+    pub async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
+        let base = self.config.url.trim_end_matches('/');
+        let path = match self.config.provider {
+            Provider::OpenAi | Provider::Local => "/v1/embeddings",
+            Provider::Ollama => "/api/embed",
+        };
+        let mut request = self
+            .client
+            .post(format!("{base}{path}"))
+            .json(&EmbeddingRequest {
+                model: &self.config.model,
+                input: texts,
+            });
+        if let Some(ref key) = self.config.api_key {
+            request = request.bearer_auth(key);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Embedding request failed: {e}"))?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "Embedding provider returned HTTP {}",
+                response.status()
+            ));
+        }
+
+        let vectors = match self.config.provider {
+            Provider::OpenAi | Provider::Local => {
+                let mut body: OpenAiEmbeddingResponse = response
+                    .json()
+                    .await
+                    .map_err(|e| format!("Invalid embedding response: {e}"))?;
+                body.data.sort_by_key(|d| d.index);
+                body.data.into_iter().map(|d| d.embedding).collect()
+            }
+            Provider::Ollama => {
+                let body: OllamaEmbeddingResponse = response
+                    .json()
+                    .await
+                    .map_err(|e| format!("Invalid embedding response: {e}"))?;
+                body.embeddings
+            }
+        };
+        if vectors.len() != texts.len() {
+            return Err(format!(
+                "Expected {} embeddings, got {}",
+                texts.len(),
+                vectors.len()
+            ));
+        }
+        Ok(vectors)
+    }
+}
+
+// =============================================================================
+// Index
+// =============================================================================
+
+/// Stored embeddings and similarity lookups
+pub struct SemanticIndex {
+    mongo: MongoClient,
+    model: String,
+    /// Set once the Atlas vector search index exists
+    vector_search: AtomicBool,
+    /// Set once creating the vector search index has been attempted
+    index_checked: AtomicBool,
+}
+
+impl SemanticIndex {
+    pub fn new(mongo: MongoClient, model: String) -> Self {
+        Self {
+            mongo,
+            model,
+            vector_search: AtomicBool::new(false),
+            index_checked: AtomicBool::new(false),
+        }
+    }
+
+    async fn embeddings(&self) -> Result<MongoCollection<ContentEmbeddingDoc>, String> {
+        self.mongo
+            .collection(CONTENT_EMBEDDING_COLLECTION)
+            .await
+            .map_err(|e| format!("Embedding collection unavailable: {e}"))
+    }
+
+    async fn suggestions(&self) -> Result<MongoCollection<RelationshipSuggestionDoc>, String> {
+        self.mongo
+            .collection(RELATIONSHIP_SUGGESTION_COLLECTION)
+            .await
+            .map_err(|e| format!("Suggestion collection unavailable: {e}"))
+    }
+
+    /// Try once to create the Atlas vector search index. Deployments
+    /// without search indexes keep using in-process ranking.
+    async fn ensure_vector_index(&self, dimensions: usize) {
+        if self.index_checked.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let command = doc! {
+            "createSearchIndexes": CONTENT_EMBEDDING_COLLECTION,
+            "indexes": [{
+                "name": CONTENT_EMBEDDING_VECTOR_INDEX,
+                "type": "vectorSearch",
+                "definition": vector_index_definition(dimensions),
+            }],
+        };
+        match self
+            .mongo
+            .inner()
+            .database(self.mongo.db_name())
+            .run_command(command)
+            .await
+        {
+            Ok(_) => {
+                info!(dimensions, "Vector search index ready");
+                self.vector_search.store(true, Ordering::SeqCst);
+            }
+            Err(e) if e.to_string().contains("already exists") => {
+                self.vector_search.store(true, Ordering::SeqCst);
+            }
+            Err(e) => {
+                debug!(error = %e, "Vector search unavailable; ranking in doorway");
+            }
+        }
+    }
+
+    /// Stored embedding of a content node
+    async fn get(&self, content_id: &str) -> Result<Option<ContentEmbeddingDoc>, String> {
+        self.embeddings()
+            .await?
+            .find_one(doc! { "content_id": content_id, "model": &self.model })
+            .await
+            .map_err(|e| format!("Embedding lookup failed: {e}"))
+    }
+
+    async fn vector_search(
+        &self,
+        source: &ContentEmbeddingDoc,
+        limit: usize,
+    ) -> Result<Vec<SemanticMatch>, String> {
+        use futures_util::TryStreamExt;
+
+        let pipeline = vec![
+            doc! {
+                "$vectorSearch": {
+                    "index": CONTENT_EMBEDDING_VECTOR_INDEX,
+                    "path": "embedding",
+                    "queryVector": source.embedding.iter().map(|v| *v as f64).collect::<Vec<f64>>(),
+                    "numCandidates": ((limit + 1) * VECTOR_SEARCH_CANDIDATES) as i64,
+                    "limit": (limit + 1) as i64,
+                    "filter": { "model": &self.model, "reach": EMBEDDING_REACH },
+                }
+            },
+            doc! {
+                "$project": {
+                    "content_id": 1,
+                    "title": 1,
+                    "content_type": 1,
+                    "similarity": { "$meta": "vectorSearchScore" },
+                }
+            },
+        ];
+        let collection = self.embeddings().await?;
+        let docs: Vec<bson::Document> = collection
+            .inner()
+            .aggregate(pipeline)
+            .await
+            .map_err(|e| format!("Vector search failed: {e}"))?
+            .try_collect()
+            .await
+            .map_err(|e| format!("Vector search failed: {e}"))?;
+
+        Ok(docs
+            .iter()
+            .filter_map(|d| {
+                let content_id = d.get_str("content_id").ok()?;
+                (content_id != source.content_id).then(|| SemanticMatch {
+                    content_id: content_id.to_string(),
+                    title: d.get_str("title").unwrap_or_default().to_string(),
+                    content_type: d.get_str("content_type").unwrap_or_default().to_string(),
+                    // Atlas maps cosine to (1 + cos) / 2
+                    similarity: d.get_f64("similarity").unwrap_or_default() as f32 * 2.0 - 1.0,
+                })
+            })
+            .take(limit)
+            .collect())
+    }
+
+    async fn scan_search(
+        &self,
+        source: &ContentEmbeddingDoc,
+        limit: usize,
+    ) -> Result<Vec<SemanticMatch>, String> {
+        let candidates = self
+            .embeddings()
+            .await?
+            .find_many(doc! { "model": &self.model, "reach": EMBEDDING_REACH })
+            .await
+            .map_err(|e| format!("Embedding scan failed: {e}"))?;
+        Ok(rank_similar(
+            &source.embedding,
+            &source.content_id,
+            &candidates,
+            limit,
+        ))
+    }
+
+    async fn nearest(
+        &self,
+        source: &ContentEmbeddingDoc,
+        limit: usize,
+    ) -> Result<Vec<SemanticMatch>, String> {
+        if self.vector_search.load(Ordering::SeqCst) {
+            match self.vector_search(source, limit).await {
+                Ok(matches) => return Ok(matches),
+                Err(e) => warn!(error = %e, "Vector search failed; ranking in doorway"),
+            }
+        }
+        self.scan_search(source, limit).await
+    }
+
+    /// Content most similar to `content_id`; None if it hasn't been embedded
+    pub async fn related(
+        &self,
+        content_id: &str,
+        limit: usize,
+    ) -> Result<Option<Vec<SemanticMatch>>, String> {
+        let Some(source) = self.get(content_id).await? else {
+            return Ok(None);
+        };
+        self.nearest(&source, limit).await.map(Some)
+    }
+
+    /// Record suggestions for pairs above the threshold. Pairs already
+    /// suggested (including rejected ones) are left alone.
+    async fn suggest(
+        &self,
+        source: &ContentEmbeddingDoc,
+        matches: &[SemanticMatch],
+        related: &HashSet<String>,
+        threshold: f32,
+    ) -> Result<usize, String> {
+        let collection = self.suggestions().await?;
+        let mut created = 0;
+        for candidate in matches
+            .iter()
+            .filter(|m| m.similarity >= threshold && !related.contains(&m.content_id))
+        {
+            let (source_id, target_id) =
+                RelationshipSuggestionDoc::ordered_pair(&source.content_id, &candidate.content_id);
+            let (source_title, target_title) = if source_id == source.content_id {
+                (&source.title, &candidate.title)
+            } else {
+                (&candidate.title, &source.title)
+            };
+            let suggestion = RelationshipSuggestionDoc {
+                id: None,
+                metadata: Metadata::new(),
+                source_id: source_id.to_string(),
+                source_title: source_title.clone(),
+                target_id: target_id.to_string(),
+                target_title: target_title.clone(),
+                similarity: candidate.similarity as f64,
+                model: self.model.clone(),
+                status: SuggestionStatus::Pending,
+                reviewed_by: None,
+                reviewed_at: None,
+            };
+            let on_insert = bson::to_document(&suggestion)
+                .map_err(|e| format!("Failed to encode suggestion: {e}"))?;
+            let result = collection
+                .inner()
+                .update_one(
+                    doc! { "source_id": source_id, "target_id": target_id },
+                    doc! { "$setOnInsert": on_insert },
+                )
+                .upsert(true)
+                .await
+                .map_err(|e| format!("Failed to store suggestion: {e}"))?;
+            if result.upserted_id.is_some() {
+                created += 1;
+            }
+        }
+        Ok(created)
+    }
+}
+
+// =============================================================================
+// Worker
+// =============================================================================
+
+/// All commons content, one content type at a time
+///
+/// Also used by the [question generator](crate::worker::question_generation).
+pub async fn load_commons_content(zome_caller: &ZomeCaller) -> Result<Vec<Value>, String> {
+    let stats: ContentStats = zome_caller
+        .call(CONTENT_ROLE, CONTENT_ZOME, "get_content_stats", &None::<()>)
+        .await?;
+
+    let mut content = Vec::new();
+    for content_type in stats.by_type.into_keys() {
+        let mut offset = 0;
+        loop {
+            let input = QueryContentInput {
+                content_type: Some(content_type.clone()),
+                tags_all: Vec::new(),
+                tags_any: Vec::new(),
+                reach: Some(EMBEDDING_REACH.to_string()),
+                author: None,
+                created_after: None,
+                sort: None,
+                page_size: PAGE_SIZE,
+                offset,
+            };
+            let page: PaginatedContentOutput = zome_caller
+                .call(CONTENT_ROLE, CONTENT_ZOME, "query_content", &input)
+                .await?;
+            offset += page.items.len() as u32;
+            content.extend(page.items.into_iter().map(|item| item.content));
+            if !page.has_more || offset == 0 {
+                break;
+            }
+        }
+    }
+    Ok(content)
+}
+
+/// Ids already related to a content node, in either direction
+async fn related_ids(zome_caller: &ZomeCaller, content_id: &str) -> HashSet<String> {
+    let input = GetRelationshipsInput {
+        content_id,
+        direction: "both",
+    };
+    match zome_caller
+        .call::<_, Vec<RelationshipOutput>>(CONTENT_ROLE, CONTENT_ZOME, "get_relationships", &input)
+        .await
+    {
+        Ok(relationships) => relationships
+            .into_iter()
+            .map(|r| {
+                if r.relationship.source_id == content_id {
+                    r.relationship.target_id
+                } else {
+                    r.relationship.source_id
+                }
+            })
+            .collect(),
+        Err(e) => {
+            debug!(content_id, error = %e, "Could not load relationships");
+            HashSet::new()
+        }
+    }
+}
+
+/// Run a single pass: embed new or changed content, drop embeddings of
+/// content that left the commons and suggest relationships.
+pub async fn embed_once(
+    zome_caller: &ZomeCaller,
+    embedder: &Embedder,
+    index: &SemanticIndex,
+) -> Result<EmbeddingPassSummary, String> {
+    let collection = index.embeddings().await?;
+    let config = embedder.config();
+
+    let content = load_commons_content(zome_caller).await?;
+    let existing: HashMap<String, String> = collection
+        .find_many(doc! { "model": &config.model })
+        .await
+        .map_err(|e| format!("Embedding scan failed: {e}"))?
+        .into_iter()
+        .map(|d| (d.content_id, d.text_hash))
+        .collect();
+
+    let mut summary = EmbeddingPassSummary {
+        scanned: content.len(),
+        ..Default::default()
+    };
+    let mut seen: Vec<String> = Vec::new();
+    let mut pending: Vec<(ContentEmbeddingDoc, String)> = Vec::new();
+    for item in &content {
+        let Some(id) = item.get("id").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        seen.push(id.to_string());
+        let Some(text) = embedding_text(item) else {
+            continue;
+        };
+        let hash = text_hash(&text);
+        if existing.get(id) == Some(&hash) {
+            summary.unchanged += 1;
+            continue;
+        }
+        let str_field = |key: &str| {
+            item.get(key)
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+        pending.push((
+            ContentEmbeddingDoc {
+                id: None,
+                metadata: Metadata::new(),
+                content_id: id.to_string(),
+                title: str_field("title"),
+                content_type: str_field("content_type"),
+                reach: EMBEDDING_REACH.to_string(),
+                model: config.model.clone(),
+                embedding: Vec::new(),
+                text_hash: hash,
+                embedded_at: None,
+            },
+            text,
+        ));
+    }
+
+    let mut embedded: Vec<ContentEmbeddingDoc> = Vec::new();
+    for batch in pending.chunks(BATCH_SIZE) {
+        let texts: Vec<String> = batch.iter().map(|(_, text)| text.clone()).collect();
+        let vectors = match embedder.embed(&texts).await {
+            Ok(vectors) => vectors,
+            Err(e) => {
+                warn!(error = %e, batch = batch.len(), "Embedding batch failed");
+                summary.failed += batch.len();
+                continue;
+            }
+        };
+        for ((doc, _), vector) in batch.iter().zip(vectors) {
+            let mut doc = doc.clone();
+            doc.embedding = vector;
+            doc.embedded_at = Some(Utc::now());
+            index.ensure_vector_index(doc.embedding.len()).await;
+            match collection
+                .inner()
+                .replace_one(
+                    doc! { "content_id": &doc.content_id, "model": &doc.model },
+                    &doc,
+                )
+                .upsert(true)
+                .await
+            {
+                Ok(_) => {
+                    summary.embedded += 1;
+                    embedded.push(doc);
+                }
+                Err(e) => {
+                    warn!(content_id = %doc.content_id, error = %e, "Failed to store embedding");
+                    summary.failed += 1;
+                }
+            }
+        }
+    }
+
+    // Content no longer in the commons keeps no vector. Skip after partial
+    // passes so nothing is dropped by mistake.
+    if summary.failed == 0 {
+        collection
+            .inner()
+            .delete_many(doc! { "model": &config.model, "content_id": { "$nin": seen.clone() } })
+            .await
+            .map_err(|e| format!("Failed to drop stale embeddings: {e}"))?;
+    }
+
+    for doc in &embedded {
+        let matches = match index.nearest(doc, MAX_SUGGESTIONS).await {
+            Ok(matches) => matches,
+            Err(e) => {
+                warn!(content_id = %doc.content_id, error = %e, "Similarity lookup failed");
+                continue;
+            }
+        };
+        if !matches
+            .iter()
+            .any(|m| m.similarity >= config.suggest_threshold)
+        {
+            continue;
+        }
+        let related = related_ids(zome_caller, &doc.content_id).await;
+        match index
+            .suggest(doc, &matches, &related, config.suggest_threshold)
+            .await
+        {
+            Ok(created) => summary.suggested += created,
+            Err(e) => {
+                warn!(content_id = %doc.content_id, error = %e, "Failed to suggest relationships")
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Spawn the periodic embedding pass (runs once immediately).
+///
+/// Content whose embedding fails to store still lacks a current embedding at
+/// the next pass and is embedded again.
+pub fn spawn_embedding_task(
+    interval: Duration,
+    zome_caller: Arc<ZomeCaller>,
+    embedder: Embedder,
+    index: Arc<SemanticIndex>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!(
+            interval_secs = interval.as_secs(),
+            provider = embedder.config().provider.name(),
+            model = %embedder.config().model,
+            "Embedding task started"
+        );
+
+        loop {
+            match embed_once(&zome_caller, &embedder, &index).await {
+                Ok(summary) => info!(
+                    scanned = summary.scanned,
+                    embedded = summary.embedded,
+                    unchanged = summary.unchanged,
+                    suggested = summary.suggested,
+                    failed = summary.failed,
+                    "Embedding pass complete"
+                ),
+                Err(e) => warn!(error = %e, "Embedding pass failed (will retry next interval)"),
+            }
+            tokio::time::sleep(interval).await;
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn embedding(id: &str, vector: Vec<f32>) -> ContentEmbeddingDoc {
+        ContentEmbeddingDoc {
+            content_id: id.to_string(),
+            title: id.to_string(),
+            embedding: vector,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_provider_parse() {
+        assert_eq!(Provider::parse("OpenAI"), Some(Provider::OpenAi));
+        assert_eq!(Provider::parse("onnx"), Some(Provider::Local));
+        assert_eq!(Provider::parse("ollama"), Some(Provider::Ollama));
+        assert_eq!(Provider::parse("word2vec"), None);
+    }
+
+    #[test]
+    fn test_cosine_similarity() {
+        assert!((cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 0.0]), 0.0);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+    }
+
+    #[test]
+    fn test_rank_similar_excludes_source() {
+        let candidates = vec![
+            embedding("self", vec![1.0, 0.0]),
+            embedding("near", vec![0.9, 0.1]),
+            embedding("far", vec![0.0, 1.0]),
+            embedding("mid", vec![0.5, 0.5]),
+        ];
+        let ranked = rank_similar(&[1.0, 0.0], "self", &candidates, 2);
+        let ids: Vec<&str> = ranked.iter().map(|m| m.content_id.as_str()).collect();
+        assert_eq!(ids, vec!["near", "mid"]);
+    }
+
+    #[test]
+    fn test_embedding_text() {
+        let lesson = json!({
+            "title": "Commons",
+            "description": "Shared resources",
+            "content": "Body text",
+            "content_format": "markdown",
+        });
+        assert_eq!(
+            embedding_text(&lesson).as_deref(),
+            Some("Commons\n\nShared resources\n\nBody text")
+        );
+
+        let video = json!({
+            "title": "Clip",
+            "description": "",
+            "content": "ignored",
+            "content_format": "video",
+        });
+        assert_eq!(embedding_text(&video).as_deref(), Some("Clip"));
+
+        assert_eq!(embedding_text(&json!({ "title": " " })), None);
+    }
+
+    #[test]
+    fn test_text_hash_is_stable() {
+        assert_eq!(text_hash("a"), text_hash("a"));
+        assert_ne!(text_hash("a"), text_hash("b"));
+    }
+}
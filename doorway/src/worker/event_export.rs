@@ -0,0 +1,389 @@
+//! Domain event export
+//!
+//! Optional feed of normalized domain events to the operator's Kafka or
+//! NATS, so data teams can build warehouses without scraping the API.
+//! Events are derived from the zome write signals the projection subscriber
+//! receives, so enable it on the projection writer.
+//!
+//! | Event | Written by | `data` |
+//! |-------|------------|--------|
+//! | `content.created` | `create_content` | `{content_id}` |
+//! | `path.completed` | `complete_path` | `{progress_id}` |
+//! | `gate.purchased` | `grant_paid_access` | `{grant_id}` |
+//! | `import.completed` | final `process_import_chunk` | `{batch_id}` |
+//!
+//! Every payload is an envelope:
+//!
+//! ```json
+//! { "id": "<uuid>", "type": "content.created", "schema_version": 1,
+//!   "occurred_at": "2025-01-01T00:00:00Z", "source": "<doorway id>",
+//!   "data": { "content_id": "..." } }
+//! ```
+//!
+//! `schema_version` changes whenever a type's `data` changes incompatibly;
+//! new fields are added without a bump.
+//!
+//! The sink is chosen by URL scheme:
+//! - `nats://` - published to `{topic}.{type}` (e.g. `elohim.events.path.completed`)
+//! - `http(s)://` - a Kafka REST Proxy (v2 API); records go to `{topic}`,
+//!   keyed by the event's subject id so one entity's events stay in order
+//!
+//! Events are delivered at least once. Undelivered events are kept (up to
+//! [`MAX_PENDING`]) and retried on the next flush.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::VecDeque;
+use std::time::Duration;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::cache::CacheInvalidation;
+use crate::config::Args;
+
+/// Version of the event envelope and payloads
+pub const EVENT_SCHEMA_VERSION: u32 = 1;
+
+/// Events held while the sink is unreachable
+pub const MAX_PENDING: usize = 10_000;
+
+/// Events sent per flush
+const BATCH_SIZE: usize = 500;
+
+/// Exporter settings
+#[derive(Debug, Clone)]
+pub struct EventExportConfig {
+    /// `nats://...` or the Kafka REST Proxy base URL
+    pub url: String,
+    /// Kafka topic, or NATS subject prefix
+    pub topic: String,
+    /// Event types to export; empty exports all
+    pub types: Vec<String>,
+    /// Identifies this doorway in the `source` field
+    pub source: String,
+    pub flush_interval: Duration,
+}
+
+impl EventExportConfig {
+    /// None when `EVENT_EXPORT_URL` is unset
+    pub fn from_args(args: &Args) -> Option<Self> {
+        Some(Self {
+            url: args.event_export_url.clone()?,
+            topic: args.event_export_topic.clone(),
+            types: args
+                .event_export_types
+                .iter()
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .collect(),
+            source: args
+                .doorway_id
+                .clone()
+                .unwrap_or_else(|| args.node_id.to_string()),
+            flush_interval: Duration::from_secs(2),
+        })
+    }
+
+    fn exports(&self, event_type: &str) -> bool {
+        self.types.is_empty() || self.types.iter().any(|t| t == event_type)
+    }
+}
+
+/// Typed payload of a domain event
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", content = "data")]
+pub enum EventPayload {
+    #[serde(rename = "content.created")]
+    ContentCreated { content_id: String },
+    #[serde(rename = "path.completed")]
+    PathCompleted { progress_id: String },
+    #[serde(rename = "gate.purchased")]
+    GatePurchased { grant_id: String },
+    #[serde(rename = "import.completed")]
+    ImportCompleted { batch_id: String },
+}
+
+impl EventPayload {
+    /// Event for a zome write, if the write is a domain event
+    pub fn from_write(write: &CacheInvalidation) -> Option<Self> {
+        let id = write.doc_id.clone();
+        match write.source_fn.as_str() {
+            "create_content" => Some(Self::ContentCreated { content_id: id }),
+            "complete_path" => Some(Self::PathCompleted { progress_id: id }),
+            "grant_paid_access" => Some(Self::GatePurchased { grant_id: id }),
+            "process_import_chunk" => Some(Self::ImportCompleted { batch_id: id }),
+            _ => None,
+        }
+    }
+
+    /// Event type, e.g. `content.created`
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            Self::ContentCreated { .. } => "content.created",
+            Self::PathCompleted { .. } => "path.completed",
+            Self::GatePurchased { .. } => "gate.purchased",
+            Self::ImportCompleted { .. } => "import.completed",
+        }
+    }
+
+    /// Id of the entity the event is about
+    pub fn subject(&self) -> &str {
+        match self {
+            Self::ContentCreated { content_id } => content_id,
+            Self::PathCompleted { progress_id } => progress_id,
+            Self::GatePurchased { grant_id } => grant_id,
+            Self::ImportCompleted { batch_id } => batch_id,
+        }
+    }
+}
+
+/// Event as published
+#[derive(Debug, Clone, Serialize)]
+pub struct DomainEvent {
+    pub id: Uuid,
+    #[serde(flatten)]
+    pub payload: EventPayload,
+    pub schema_version: u32,
+    pub occurred_at: DateTime<Utc>,
+    pub source: String,
+}
+
+impl DomainEvent {
+    pub fn new(payload: EventPayload, source: &str) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            payload,
+            schema_version: EVENT_SCHEMA_VERSION,
+            occurred_at: Utc::now(),
+            source: source.to_string(),
+        }
+    }
+}
+
+/// Kafka REST Proxy (v2) produce body
+pub fn kafka_records(events: &[DomainEvent]) -> serde_json::Value {
+    let records: Vec<serde_json::Value> = events
+        .iter()
+        .map(|event| json!({ "key": event.payload.subject(), "value": event }))
+        .collect();
+    json!({ "records": records })
+}
+
+/// Where events are published
+enum EventSink {
+    Nats(async_nats::Client),
+    Kafka {
+        client: reqwest::Client,
+        topic_url: String,
+    },
+}
+
+impl EventSink {
+    async fn connect(config: &EventExportConfig) -> Result<Self, String> {
+        if config.url.starts_with("nats://") || config.url.starts_with("tls://") {
+            let client = async_nats::connect(config.url.as_str())
+                .await
+                .map_err(|e| format!("NATS connect failed: {e}"))?;
+            return Ok(Self::Nats(client));
+        }
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+        Ok(Self::Kafka {
+            client,
+            topic_url: format!(
+                "{}/topics/{}",
+                config.url.trim_end_matches('/'),
+                config.topic
+            ),
+        })
+    }
+
+    async fn publish(&self, topic: &str, events: &[DomainEvent]) -> Result<(), String> {
+        match self {
+            Self::Nats(client) => {
+                for event in events {
+                    let subject = format!("{topic}.{}", event.payload.event_type());
+                    let payload = serde_json::to_vec(event).unwrap_or_default();
+                    client
+                        .publish(subject, payload.into())
+                        .await
+                        .map_err(|e| format!("NATS publish failed: {e}"))?;
+                }
+                client
+                    .flush()
+                    .await
+                    .map_err(|e| format!("NATS flush failed: {e}"))
+            }
+            Self::Kafka { client, topic_url } => {
+                let response = client
+                    .post(topic_url)
+                    .header("Content-Type", "application/vnd.kafka.json.v2+json")
+                    .body(kafka_records(events).to_string())
+                    .send()
+                    .await
+                    .map_err(|e| format!("Kafka produce failed: {e}"))?;
+                if !response.status().is_success() {
+                    return Err(format!("Kafka produce returned HTTP {}", response.status()));
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Buffers events between flushes
+pub struct EventExporter {
+    config: EventExportConfig,
+    pending: VecDeque<DomainEvent>,
+}
+
+impl EventExporter {
+    pub fn new(config: EventExportConfig) -> Self {
+        Self {
+            config,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Queue the event for a write, if it is one that's exported
+    pub fn enqueue(&mut self, write: &CacheInvalidation) {
+        let Some(payload) = EventPayload::from_write(write) else {
+            return;
+        };
+        if !self.config.exports(payload.event_type()) {
+            return;
+        }
+        if self.pending.len() >= MAX_PENDING {
+            warn!(
+                event_type = payload.event_type(),
+                "Event export backlog full, dropping event"
+            );
+            return;
+        }
+        self.pending
+            .push_back(DomainEvent::new(payload, &self.config.source));
+    }
+
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Send pending events in order; keeps whatever wasn't sent
+    async fn flush(&mut self, sink: &EventSink) -> Result<usize, String> {
+        let mut sent = 0;
+        while !self.pending.is_empty() {
+            let batch = self.pending.len().min(BATCH_SIZE);
+            let events: Vec<DomainEvent> = self.pending.range(..batch).cloned().collect();
+            sink.publish(&self.config.topic, &events).await?;
+            self.pending.drain(..batch);
+            sent += batch;
+        }
+        Ok(sent)
+    }
+}
+
+/// Spawn the exporter, following zome write signals until the subscriber
+/// shuts down.
+pub fn spawn_event_export_task(
+    config: EventExportConfig,
+    mut writes: broadcast::Receiver<CacheInvalidation>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!(topic = %config.topic, types = ?config.types, "Event export started");
+
+        let mut ticker = tokio::time::interval(config.flush_interval);
+        let mut sink: Option<EventSink> = None;
+        let mut exporter = EventExporter::new(config.clone());
+
+        loop {
+            let closed = tokio::select! {
+                write = writes.recv() => match write {
+                    Ok(write) => {
+                        exporter.enqueue(&write);
+                        continue;
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!(skipped, "Event export fell behind write signals");
+                        continue;
+                    }
+                    Err(RecvError::Closed) => true,
+                },
+                _ = ticker.tick() => false,
+            };
+
+            if exporter.pending() > 0 {
+                if sink.is_none() {
+                    match EventSink::connect(&config).await {
+                        Ok(connected) => sink = Some(connected),
+                        Err(e) => warn!(error = %e, "Event sink unavailable (will retry)"),
+                    }
+                }
+                if let Some(ref connected) = sink {
+                    match exporter.flush(connected).await {
+                        Ok(sent) => debug!(sent, "Events exported"),
+                        Err(e) => {
+                            warn!(error = %e, pending = exporter.pending(), "Event export failed (will retry)");
+                        }
+                    }
+                }
+            }
+
+            if closed {
+                info!(pending = exporter.pending(), "Event export stopped");
+                return;
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(source_fn: &str, doc_id: &str) -> CacheInvalidation {
+        CacheInvalidation {
+            source_fn: source_fn.to_string(),
+            doc_type: "Any".to_string(),
+            doc_id: doc_id.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_event_envelope() {
+        let payload = EventPayload::from_write(&write("grant_paid_access", "grant-1")).unwrap();
+        let event = serde_json::to_value(DomainEvent::new(payload, "doorway-a")).unwrap();
+
+        assert_eq!(event["type"], "gate.purchased");
+        assert_eq!(event["data"]["grant_id"], "grant-1");
+        assert_eq!(event["schema_version"], EVENT_SCHEMA_VERSION);
+        assert_eq!(event["source"], "doorway-a");
+        assert!(event["id"].is_string() && event["occurred_at"].is_string());
+
+        assert_eq!(EventPayload::from_write(&write("update_path", "p1")), None);
+    }
+
+    #[test]
+    fn test_enqueue_filters_types_and_keys_records() {
+        let mut exporter = EventExporter::new(EventExportConfig {
+            url: "nats://localhost:4222".to_string(),
+            topic: "elohim.events".to_string(),
+            types: vec!["content.created".to_string()],
+            source: "doorway-a".to_string(),
+            flush_interval: Duration::from_secs(2),
+        });
+        exporter.enqueue(&write("create_content", "c1"));
+        exporter.enqueue(&write("complete_path", "agent-path"));
+        exporter.enqueue(&write("create_relationship", "r1"));
+        assert_eq!(exporter.pending(), 1);
+
+        let events: Vec<DomainEvent> = exporter.pending.iter().cloned().collect();
+        let body = kafka_records(&events);
+        assert_eq!(body["records"][0]["key"], "c1");
+        assert_eq!(body["records"][0]["value"]["type"], "content.created");
+    }
+}
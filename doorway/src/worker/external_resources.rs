@@ -0,0 +1,483 @@
+//! External resource enrichment
+//!
+//! Steps with `step_type: "external"` only carry a URL. When one is added,
+//! content_store registers a pending `ExternalResource` for the URL; this
+//! worker polls `get_external_resources_by_status("pending")`, fetches each
+//! page and records what it finds with `update_external_resource`:
+//!
+//! - the provider's oEmbed response, when the page advertises one
+//!   (`<link rel="alternate" type="application/json+oembed">`)
+//! - Open Graph / HTML metadata: title, description, site name and image
+//! - a snapshot of the page body uploaded to elohim-storage as a blob, so the
+//!   step still has something to show (at `/store/{hash}`) after link rot
+//!
+//! Snapshots need `STORAGE_URL`; without it resources are enriched with
+//! metadata only. Unreachable pages are marked `failed`.
+
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::services::zome_caller::ZomeCaller;
+
+/// Role holding the content_store zome
+const CONTENT_ROLE: &str = "lamad";
+
+/// Zome owning external resources
+const CONTENT_ZOME: &str = "content_store";
+
+/// Resources fetched per pass
+const MAX_RESOURCES_PER_PASS: usize = 20;
+
+/// Largest page fetched and snapshotted
+const MAX_PAGE_BYTES: usize = 5 * 1024 * 1024;
+
+/// Longest description kept, in characters
+const MAX_DESCRIPTION_CHARS: usize = 1000;
+
+/// Timeout for page, oEmbed and storage requests
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+
+const USER_AGENT: &str = "elohim-doorway/1.0 (+external resource enrichment)";
+
+/// Metadata found in a page's `<head>`
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PageMetadata {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub site_name: Option<String>,
+    pub image: Option<String>,
+    /// oEmbed endpoint advertised by the page
+    pub oembed_url: Option<String>,
+}
+
+/// Outcome of one pass
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct EnrichmentPassSummary {
+    pub enriched: usize,
+    pub snapshots: usize,
+    pub failed: usize,
+}
+
+/// Fields of an ExternalResource the worker reads
+/// Must match ExternalResource in holochain/dna/elohim/zomes/content_store_integrity/src/lib.rs
+#[derive(Debug, Clone, Deserialize)]
+struct ExternalResource {
+    url: String,
+}
+
+/// Must match ExternalResourceOutput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Clone, Deserialize)]
+struct ExternalResourceOutput {
+    resource: ExternalResource,
+}
+
+/// Must match UpdateExternalResourceInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Default, Serialize)]
+struct UpdateExternalResourceInput {
+    url: String,
+    status: String,
+    provider: Option<String>,
+    title: Option<String>,
+    description: Option<String>,
+    thumbnail_url: Option<String>,
+    oembed_json: Option<String>,
+    snapshot_hash: Option<String>,
+}
+
+// =============================================================================
+// HTML metadata
+// =============================================================================
+
+/// Decode the entities that commonly appear in attribute values
+fn decode_entities(value: &str) -> String {
+    value
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+/// Attributes of one tag, names lowercased
+fn tag_attributes(tag: &str) -> Vec<(String, String)> {
+    let mut attrs = Vec::new();
+    let mut rest = tag;
+    while let Some(eq) = rest.find('=') {
+        let name = rest[..eq]
+            .rsplit(|c: char| c.is_whitespace())
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        let after = rest[eq + 1..].trim_start();
+        let (value, remainder) = match after.chars().next() {
+            Some(quote @ ('"' | '\'')) => match after[1..].find(quote) {
+                Some(end) => (&after[1..end + 1], &after[end + 2..]),
+                None => (&after[1..], ""),
+            },
+            _ => {
+                let end = after
+                    .find(|c: char| c.is_whitespace() || c == '>')
+                    .unwrap_or(after.len());
+                (&after[..end], &after[end..])
+            }
+        };
+        attrs.push((name, decode_entities(value.trim())));
+        rest = remainder;
+    }
+    attrs
+}
+
+/// Open tags named `name` in `html`, as the text after `<name`. `lower` is
+/// `html` lowercased.
+fn tags<'a>(html: &'a str, lower: &str, name: &str) -> Vec<&'a str> {
+    let open = format!("<{name}");
+    lower
+        .match_indices(open.as_str())
+        .filter_map(|(start, _)| {
+            let body_start = start + open.len();
+            let end = lower[body_start..].find('>')? + body_start;
+            let boundary = lower[body_start..].chars().next()?;
+            (boundary.is_whitespace() || boundary == '/').then(|| &html[body_start..end])
+        })
+        .collect()
+}
+
+/// Read title, description, site name, image and oEmbed discovery link from
+/// a page. Open Graph and Twitter card tags win over plain HTML ones.
+pub fn parse_page_metadata(html: &str, page_url: &str) -> PageMetadata {
+    // Only the head matters. ASCII lowercasing keeps byte offsets aligned
+    // with `html` for case-insensitive matching.
+    let lower = html.to_ascii_lowercase();
+    let head_end = lower.find("</head>").unwrap_or(html.len());
+    let (html, lower) = (&html[..head_end], &lower[..head_end]);
+    let base = Url::parse(page_url).ok();
+    let absolute = |href: &str| match &base {
+        Some(base) => base.join(href).map(String::from).ok(),
+        None => Some(href.to_string()),
+    };
+
+    let mut meta = PageMetadata::default();
+    let mut html_title = None;
+    let mut html_description = None;
+
+    for tag in tags(html, lower, "meta") {
+        let attrs = tag_attributes(tag);
+        let get = |key: &str| attrs.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone());
+        let Some(content) = get("content").filter(|c| !c.is_empty()) else {
+            continue;
+        };
+        let key = get("property")
+            .or_else(|| get("name"))
+            .unwrap_or_default()
+            .to_lowercase();
+        match key.as_str() {
+            "og:title" => meta.title = Some(content),
+            "twitter:title" if meta.title.is_none() => meta.title = Some(content),
+            "og:description" => meta.description = Some(content),
+            "twitter:description" if meta.description.is_none() => meta.description = Some(content),
+            "description" => html_description = Some(content),
+            "og:site_name" => meta.site_name = Some(content),
+            "og:image" | "og:image:url" => meta.image = absolute(&content),
+            "twitter:image" if meta.image.is_none() => meta.image = absolute(&content),
+            _ => {}
+        }
+    }
+
+    for tag in tags(html, lower, "link") {
+        let attrs = tag_attributes(tag);
+        let get = |key: &str| attrs.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone());
+        let is_oembed =
+            get("type").is_some_and(|t| t.eq_ignore_ascii_case("application/json+oembed"));
+        if is_oembed && meta.oembed_url.is_none() {
+            meta.oembed_url = get("href").and_then(|href| absolute(&href));
+        }
+    }
+
+    if let Some(start) = lower.find("<title") {
+        if let Some(open_end) = lower[start..].find('>') {
+            let text_start = start + open_end + 1;
+            if let Some(len) = lower[text_start..].find("</title") {
+                let title = decode_entities(html[text_start..text_start + len].trim());
+                html_title = (!title.is_empty()).then_some(title);
+            }
+        }
+    }
+
+    meta.title = meta.title.or(html_title);
+    meta.description = meta
+        .description
+        .or(html_description)
+        .map(|d| d.chars().take(MAX_DESCRIPTION_CHARS).collect());
+    meta
+}
+
+/// Blob hash for a snapshot, matching the seeder's `sha256-{hex}` convention
+pub fn snapshot_hash(body: &[u8]) -> String {
+    format!("sha256-{:x}", Sha256::digest(body))
+}
+
+// =============================================================================
+// Enricher
+// =============================================================================
+
+/// Fetches and records metadata for pending external resources
+pub struct ExternalResourceEnricher {
+    /// elohim-storage URL snapshots are uploaded to; none disables snapshots
+    storage_url: Option<String>,
+    client: reqwest::Client,
+}
+
+impl ExternalResourceEnricher {
+    pub fn new(storage_url: Option<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(USER_AGENT)
+            .build()
+            .unwrap_or_default();
+        Self {
+            storage_url,
+            client,
+        }
+    }
+
+    /// Fetch a URL's body, refusing anything over MAX_PAGE_BYTES
+    async fn fetch(&self, url: &str) -> Result<(String, Vec<u8>), String> {
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| format!("Request failed: {e}"))?;
+        if !response.status().is_success() {
+            return Err(format!("HTTP {}", response.status()));
+        }
+        if response
+            .content_length()
+            .is_some_and(|len| len > MAX_PAGE_BYTES as u64)
+        {
+            return Err("Page too large".to_string());
+        }
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_lowercase();
+        let body = response
+            .bytes()
+            .await
+            .map_err(|e| format!("Failed to read body: {e}"))?;
+        if body.len() > MAX_PAGE_BYTES {
+            return Err("Page too large".to_string());
+        }
+        Ok((content_type, body.to_vec()))
+    }
+
+    /// Upload a snapshot to elohim-storage; returns its hash
+    async fn store_snapshot(&self, body: &[u8]) -> Option<String> {
+        let storage_url = self.storage_url.as_deref()?;
+        let hash = snapshot_hash(body);
+        let url = format!("{}/blob/{}", storage_url.trim_end_matches('/'), hash);
+        match self
+            .client
+            .put(&url)
+            .header("Content-Type", "application/octet-stream")
+            .body(body.to_vec())
+            .send()
+            .await
+        {
+            Ok(response) if response.status().is_success() => Some(hash),
+            Ok(response) => {
+                warn!(status = %response.status(), "elohim-storage rejected snapshot");
+                None
+            }
+            Err(e) => {
+                warn!(error = %e, "Failed to upload snapshot");
+                None
+            }
+        }
+    }
+
+    /// Fetch one resource and build its update
+    async fn enrich(&self, url: &str) -> UpdateExternalResourceInput {
+        let mut update = UpdateExternalResourceInput {
+            url: url.to_string(),
+            ..Default::default()
+        };
+        let (content_type, body) = match self.fetch(url).await {
+            Ok(page) => page,
+            Err(e) => {
+                debug!(url, error = %e, "External resource unreachable");
+                update.status = "failed".to_string();
+                return update;
+            }
+        };
+
+        if content_type.contains("html") {
+            let metadata = parse_page_metadata(&String::from_utf8_lossy(&body), url);
+            update.title = metadata.title;
+            update.description = metadata.description;
+            update.provider = metadata.site_name;
+            update.thumbnail_url = metadata.image;
+
+            if let Some(oembed_url) = metadata.oembed_url {
+                match self.fetch(&oembed_url).await.and_then(|(_, bytes)| {
+                    serde_json::from_slice::<Value>(&bytes).map_err(|e| e.to_string())
+                }) {
+                    Ok(oembed) => {
+                        let field = |key: &str| oembed.get(key)?.as_str().map(str::to_string);
+                        update.provider = field("provider_name").or(update.provider);
+                        update.title = update.title.or_else(|| field("title"));
+                        update.thumbnail_url =
+                            update.thumbnail_url.or_else(|| field("thumbnail_url"));
+                        update.oembed_json = Some(oembed.to_string());
+                    }
+                    Err(e) => debug!(url, error = %e, "oEmbed fetch failed"),
+                }
+            }
+        }
+
+        update.snapshot_hash = self.store_snapshot(&body).await;
+        update.status = "enriched".to_string();
+        update
+    }
+
+    /// Run a single pass over pending resources
+    pub async fn run_once(
+        &self,
+        zome_caller: &ZomeCaller,
+    ) -> Result<EnrichmentPassSummary, String> {
+        let pending: Vec<ExternalResourceOutput> = zome_caller
+            .call(
+                CONTENT_ROLE,
+                CONTENT_ZOME,
+                "get_external_resources_by_status",
+                &"pending",
+            )
+            .await?;
+        let mut summary = EnrichmentPassSummary::default();
+
+        for output in pending.into_iter().take(MAX_RESOURCES_PER_PASS) {
+            let update = self.enrich(&output.resource.url).await;
+            let failed = update.status == "failed";
+            let snapshot = update.snapshot_hash.is_some();
+            match zome_caller
+                .call::<_, Value>(
+                    CONTENT_ROLE,
+                    CONTENT_ZOME,
+                    "update_external_resource",
+                    &update,
+                )
+                .await
+            {
+                Ok(_) if failed => summary.failed += 1,
+                Ok(_) => {
+                    summary.enriched += 1;
+                    if snapshot {
+                        summary.snapshots += 1;
+                    }
+                }
+                Err(e) => {
+                    warn!(
+                        url = %output.resource.url,
+                        error = %e,
+                        "Failed to record external resource"
+                    );
+                    summary.failed += 1;
+                }
+            }
+        }
+
+        Ok(summary)
+    }
+}
+
+/// Spawn the periodic enrichment pass.
+///
+/// Unreachable pages are marked `failed` and left alone; a resource whose
+/// update can't be recorded stays `pending` and is fetched again next pass.
+pub fn spawn_external_resource_task(
+    interval: Duration,
+    zome_caller: Arc<ZomeCaller>,
+    enricher: ExternalResourceEnricher,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!(
+            interval_secs = interval.as_secs(),
+            snapshots = enricher.storage_url.is_some(),
+            "External resource enrichment task started"
+        );
+
+        loop {
+            tokio::time::sleep(interval).await;
+
+            match enricher.run_once(&zome_caller).await {
+                Ok(summary) if summary.enriched + summary.failed > 0 => info!(
+                    enriched = summary.enriched,
+                    snapshots = summary.snapshots,
+                    failed = summary.failed,
+                    "External resource enrichment pass complete"
+                ),
+                Ok(_) => debug!("External resource enrichment pass: nothing to do"),
+                Err(e) => warn!(error = %e, "External resource enrichment pass failed"),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_page_metadata() {
+        let html = r#"<!doctype html><html><HEAD>
+<title>Fallback &amp; Title</title>
+<meta name="description" content="Plain description">
+<meta property="og:title" content="Commons &quot;Stewardship&quot;" />
+<meta property="og:site_name" content='Example Video'>
+<meta property="og:image" content="/thumbs/1.jpg">
+<link rel="alternate" type="application/json+oembed"
+      href="https://example.com/oembed?url=https%3A%2F%2Fexample.com%2Fv%2F1&amp;format=json">
+</HEAD><body><meta property="og:title" content="Ignored"></body></html>"#;
+
+        let meta = parse_page_metadata(html, "https://example.com/v/1");
+        assert_eq!(meta.title.as_deref(), Some("Commons \"Stewardship\""));
+        assert_eq!(meta.description.as_deref(), Some("Plain description"));
+        assert_eq!(meta.site_name.as_deref(), Some("Example Video"));
+        assert_eq!(
+            meta.image.as_deref(),
+            Some("https://example.com/thumbs/1.jpg")
+        );
+        assert_eq!(
+            meta.oembed_url.as_deref(),
+            Some("https://example.com/oembed?url=https%3A%2F%2Fexample.com%2Fv%2F1&format=json")
+        );
+    }
+
+    #[test]
+    fn test_parse_page_metadata_falls_back_to_title_tag() {
+        let meta = parse_page_metadata(
+            "<html><head><title> A page </title><metadata x=1></head></html>",
+            "https://example.com/",
+        );
+        assert_eq!(meta.title.as_deref(), Some("A page"));
+        assert_eq!(meta.oembed_url, None);
+        assert_eq!(meta.image, None);
+    }
+
+    #[test]
+    fn test_snapshot_hash() {
+        assert_eq!(
+            snapshot_hash(b"hello"),
+            "sha256-2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+    }
+}
@@ -0,0 +1,500 @@
+//! Governance executor
+//!
+//! Makes community decisions about this doorway take effect. Each governed
+//! setting is a `GovernanceState` entity of type `doorway_setting` whose
+//! `entity_id` is the setting key, e.g.
+//!
+//! ```json
+//! { "entity_type": "doorway_setting", "entity_id": "moderation.score_threshold",
+//!   "status": "approved", "metadata_json": "{\"value\": 0.7}" }
+//! ```
+//!
+//! The latest state of a setting applies while it is `approved`. Any other
+//! status (pending, challenged, suspended) puts the setting back to the
+//! operator's configured value, as does an out-of-range value.
+//!
+//! Approved settings are loaded at startup and on every refresh interval. In
+//! writer mode, `set_governance_state` write signals re-read the setting they
+//! name straight away.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::cache::CacheInvalidation;
+use crate::services::zome_caller::ZomeCaller;
+
+/// Role holding the content_store zome
+const CONTENT_ROLE: &str = "lamad";
+
+/// Zome exposing governance state
+const CONTENT_ZOME: &str = "content_store";
+
+/// Governance entity type for doorway settings
+pub const SETTING_ENTITY_TYPE: &str = "doorway_setting";
+
+/// Write signal that can change a setting
+const GOVERNANCE_FN: &str = "set_governance_state";
+
+/// Status under which a governance state takes effect
+const APPROVED: &str = "approved";
+
+/// Approved states read per load
+const QUERY_LIMIT: u32 = 1000;
+
+/// Revenue share suggested to premium gate stewards, in percent
+pub const STEWARD_SHARE_PERCENT: &str = "recognition.steward_share_percent";
+
+/// Revenue share suggested for the commons, in percent
+pub const COMMONS_SHARE_PERCENT: &str = "recognition.commons_share_percent";
+
+/// Tutor tokens per operator key per month (0 = unlimited)
+pub const TUTOR_MONTHLY_TOKENS: &str = "rate_limit.tutor_monthly_tokens";
+
+/// Moderation API category score that holds a write back, even when the
+/// provider doesn't flag it
+pub const MODERATION_SCORE_THRESHOLD: &str = "moderation.score_threshold";
+
+/// A setting the community can govern
+#[derive(Debug, Clone, Copy)]
+pub struct SettingSpec {
+    pub key: &'static str,
+    pub description: &'static str,
+    pub min: f64,
+    pub max: f64,
+    /// Used when neither governance nor the operator sets it
+    pub default: Option<f64>,
+}
+
+/// Settings the executor applies
+pub const SETTINGS: &[SettingSpec] = &[
+    SettingSpec {
+        key: STEWARD_SHARE_PERCENT,
+        description: "Suggested steward share of premium gate revenue, in percent",
+        min: 0.0,
+        max: 100.0,
+        default: Some(85.0),
+    },
+    SettingSpec {
+        key: COMMONS_SHARE_PERCENT,
+        description: "Suggested commons share of premium gate revenue, in percent",
+        min: 0.0,
+        max: 100.0,
+        default: Some(15.0),
+    },
+    SettingSpec {
+        key: TUTOR_MONTHLY_TOKENS,
+        description: "Tutor tokens per operator key per month (0 = unlimited)",
+        min: 0.0,
+        max: 1e12,
+        default: None,
+    },
+    SettingSpec {
+        key: MODERATION_SCORE_THRESHOLD,
+        description: "Moderation API category score that holds a write for review",
+        min: 0.0,
+        max: 1.0,
+        default: None,
+    },
+];
+
+fn spec(key: &str) -> Option<&'static SettingSpec> {
+    SETTINGS.iter().find(|s| s.key == key)
+}
+
+/// Value set by an approved governance state
+#[derive(Debug, Clone, PartialEq)]
+struct Governed {
+    value: f64,
+    state_id: String,
+}
+
+#[derive(Debug, Clone, Default)]
+struct SettingValue {
+    configured: Option<f64>,
+    governed: Option<Governed>,
+}
+
+/// Current value of a setting, for `GET /governance/settings`
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SettingView {
+    pub key: &'static str,
+    pub description: &'static str,
+    pub value: Option<f64>,
+    /// `governance`, `config` or `default`
+    pub source: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub governance_state_id: Option<String>,
+}
+
+/// Runtime settings, governed values first
+#[derive(Debug, Default)]
+pub struct GovernedSettings {
+    values: RwLock<BTreeMap<&'static str, SettingValue>>,
+}
+
+impl GovernedSettings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the operator's value, used whenever governance hasn't set one
+    pub fn configure(&self, key: &str, value: f64) {
+        let Some(spec) = spec(key) else { return };
+        let mut values = self.values.write().unwrap_or_else(|e| e.into_inner());
+        values.entry(spec.key).or_default().configured = Some(value);
+    }
+
+    /// Effective value: governed, then configured, then the built-in default
+    pub fn get(&self, key: &str) -> Option<f64> {
+        let spec = spec(key)?;
+        let values = self.values.read().unwrap_or_else(|e| e.into_inner());
+        let value = values.get(spec.key);
+        value
+            .and_then(|v| v.governed.as_ref().map(|g| g.value))
+            .or_else(|| value.and_then(|v| v.configured))
+            .or(spec.default)
+    }
+
+    /// Effective value as a whole number
+    pub fn get_u64(&self, key: &str) -> Option<u64> {
+        self.get(key).map(|v| v.max(0.0) as u64)
+    }
+
+    /// Apply a setting's latest governance state (`None` when it has none).
+    /// Returns whether the effective value changed.
+    fn apply(&self, key: &str, state: Option<&GovernanceState>) -> bool {
+        let Some(spec) = spec(key) else { return false };
+        let governed = state.and_then(|state| governed_value(spec, state));
+        let before = self.get(key);
+        {
+            let mut values = self.values.write().unwrap_or_else(|e| e.into_inner());
+            values.entry(spec.key).or_default().governed = governed;
+        }
+        before != self.get(key)
+    }
+
+    /// Every setting with its current value
+    pub fn snapshot(&self) -> Vec<SettingView> {
+        let values = self.values.read().unwrap_or_else(|e| e.into_inner());
+        SETTINGS
+            .iter()
+            .map(|spec| {
+                let value = values.get(spec.key).cloned().unwrap_or_default();
+                let (value, source, governance_state_id) = match (value.governed, value.configured)
+                {
+                    (Some(g), _) => (Some(g.value), "governance", Some(g.state_id)),
+                    (None, Some(v)) => (Some(v), "config", None),
+                    (None, None) => (spec.default, "default", None),
+                };
+                SettingView {
+                    key: spec.key,
+                    description: spec.description,
+                    value,
+                    source,
+                    governance_state_id,
+                }
+            })
+            .collect()
+    }
+}
+
+/// The value an approved state sets, if it is approved and in range
+fn governed_value(spec: &SettingSpec, state: &GovernanceState) -> Option<Governed> {
+    if state.status != APPROVED {
+        return None;
+    }
+    let metadata: Value = serde_json::from_str(&state.metadata_json).ok()?;
+    let value = match metadata.get("value")? {
+        Value::Number(n) => n.as_f64()?,
+        Value::String(s) => s.trim().parse().ok()?,
+        _ => return None,
+    };
+    if !(spec.min..=spec.max).contains(&value) {
+        warn!(
+            setting = spec.key,
+            value,
+            min = spec.min,
+            max = spec.max,
+            "Approved governance value out of range, ignored"
+        );
+        return None;
+    }
+    Some(Governed {
+        value,
+        state_id: state.id.clone(),
+    })
+}
+
+/// Must match GovernanceState in holochain/dna/elohim/zomes/content_store_integrity/src/lib.rs
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct GovernanceState {
+    pub id: String,
+    pub entity_type: String,
+    pub entity_id: String,
+    pub status: String,
+    #[serde(default)]
+    pub metadata_json: String,
+}
+
+/// Must match GovernanceStateOutput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Deserialize)]
+struct GovernanceStateOutput {
+    governance_state: GovernanceState,
+}
+
+/// Must match GetGovernanceStateInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct GetGovernanceStateInput<'a> {
+    entity_type: &'a str,
+    entity_id: &'a str,
+}
+
+/// Must match QueryGovernanceStatesInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct QueryGovernanceStatesInput<'a> {
+    status: Option<&'a str>,
+    limit: Option<u32>,
+}
+
+/// Setting key named by a `set_governance_state` signal, when the state uses
+/// the default `gs-{entity_type}:{entity_id}` id
+fn setting_key_from_state_id(state_id: &str) -> Option<&str> {
+    state_id
+        .strip_prefix("gs-")?
+        .strip_prefix(SETTING_ENTITY_TYPE)?
+        .strip_prefix(':')
+        .filter(|key| !key.is_empty())
+}
+
+/// Re-read one setting's latest governance state. Returns whether its value
+/// changed.
+pub async fn refresh_setting(
+    zome_caller: &ZomeCaller,
+    settings: &GovernedSettings,
+    key: &str,
+) -> Result<bool, String> {
+    let latest: Option<GovernanceStateOutput> = zome_caller
+        .call(
+            CONTENT_ROLE,
+            CONTENT_ZOME,
+            "get_governance_state",
+            &GetGovernanceStateInput {
+                entity_type: SETTING_ENTITY_TYPE,
+                entity_id: key,
+            },
+        )
+        .await?;
+    let state = latest.map(|output| output.governance_state);
+    let changed = settings.apply(key, state.as_ref());
+    if changed {
+        info!(
+            setting = key,
+            value = ?settings.get(key),
+            status = state.as_ref().map(|s| s.status.as_str()).unwrap_or("none"),
+            "Governed setting changed"
+        );
+    }
+    Ok(changed)
+}
+
+/// Re-read every known setting that has ever been approved. Returns the
+/// number whose value changed.
+pub async fn refresh_all(
+    zome_caller: &ZomeCaller,
+    settings: &GovernedSettings,
+) -> Result<usize, String> {
+    let approved: Vec<GovernanceStateOutput> = zome_caller
+        .call(
+            CONTENT_ROLE,
+            CONTENT_ZOME,
+            "query_governance_states",
+            &QueryGovernanceStatesInput {
+                status: Some(APPROVED),
+                limit: Some(QUERY_LIMIT),
+            },
+        )
+        .await?;
+
+    // Older approvals stay linked under "approved", so each key is re-read
+    // for its latest state
+    let keys: BTreeSet<String> = approved
+        .into_iter()
+        .map(|output| output.governance_state)
+        .filter(|state| state.entity_type == SETTING_ENTITY_TYPE)
+        .map(|state| state.entity_id)
+        .collect();
+
+    let mut changed = 0;
+    for key in keys {
+        if spec(&key).is_none() {
+            debug!(setting = %key, "Unknown governed setting, skipped");
+            continue;
+        }
+        match refresh_setting(zome_caller, settings, &key).await {
+            Ok(true) => changed += 1,
+            Ok(false) => {}
+            Err(e) => warn!(setting = %key, error = %e, "Failed to read governed setting"),
+        }
+    }
+    Ok(changed)
+}
+
+/// Spawn the governance executor.
+///
+/// Loads approved settings straight away, then on every `interval` and, when
+/// `invalidations` is given, on each `set_governance_state` write signal.
+pub fn spawn_governance_task(
+    interval: Duration,
+    zome_caller: Arc<ZomeCaller>,
+    settings: Arc<GovernedSettings>,
+    mut invalidations: Option<broadcast::Receiver<CacheInvalidation>>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!(
+            interval_secs = interval.as_secs(),
+            signals = invalidations.is_some(),
+            "Governance executor started"
+        );
+
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            let signal = match invalidations.as_mut() {
+                Some(rx) => tokio::select! {
+                    _ = ticker.tick() => None,
+                    received = rx.recv() => Some(received),
+                },
+                None => {
+                    ticker.tick().await;
+                    None
+                }
+            };
+
+            let result = match signal {
+                None => refresh_all(&zome_caller, &settings).await,
+                Some(Ok(invalidation)) if invalidation.source_fn == GOVERNANCE_FN => {
+                    match setting_key_from_state_id(&invalidation.doc_id) {
+                        Some(key) if spec(key).is_some() => {
+                            refresh_setting(&zome_caller, &settings, key)
+                                .await
+                                .map(usize::from)
+                        }
+                        Some(_) => continue,
+                        // Custom state ids don't say which setting they're for
+                        None => refresh_all(&zome_caller, &settings).await,
+                    }
+                }
+                Some(Ok(_)) => continue,
+                Some(Err(broadcast::error::RecvError::Lagged(n))) => {
+                    warn!(missed = n, "Governance executor lagged, reloading settings");
+                    refresh_all(&zome_caller, &settings).await
+                }
+                Some(Err(broadcast::error::RecvError::Closed)) => {
+                    invalidations = None;
+                    continue;
+                }
+            };
+
+            match result {
+                Ok(0) => debug!("Governed settings unchanged"),
+                Ok(changed) => info!(changed, "Governed settings applied"),
+                Err(e) => warn!(error = %e, "Failed to load governed settings (will retry)"),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(key: &str, status: &str, metadata_json: &str) -> GovernanceState {
+        GovernanceState {
+            id: format!("gs-{SETTING_ENTITY_TYPE}:{key}"),
+            entity_type: SETTING_ENTITY_TYPE.to_string(),
+            entity_id: key.to_string(),
+            status: status.to_string(),
+            metadata_json: metadata_json.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_setting_key_from_state_id() {
+        assert_eq!(
+            setting_key_from_state_id("gs-doorway_setting:moderation.score_threshold"),
+            Some("moderation.score_threshold")
+        );
+        assert_eq!(setting_key_from_state_id("gs-content:abc"), None);
+        assert_eq!(setting_key_from_state_id("gs-doorway_setting:"), None);
+        assert_eq!(setting_key_from_state_id("custom-id"), None);
+    }
+
+    #[test]
+    fn test_precedence() {
+        let settings = GovernedSettings::new();
+        assert_eq!(settings.get(STEWARD_SHARE_PERCENT), Some(85.0));
+        assert_eq!(settings.get(TUTOR_MONTHLY_TOKENS), None);
+        assert_eq!(settings.get("unknown.setting"), None);
+
+        settings.configure(TUTOR_MONTHLY_TOKENS, 100_000.0);
+        assert_eq!(settings.get_u64(TUTOR_MONTHLY_TOKENS), Some(100_000));
+
+        let approved = state(TUTOR_MONTHLY_TOKENS, "approved", r#"{"value": 250000}"#);
+        assert!(settings.apply(TUTOR_MONTHLY_TOKENS, Some(&approved)));
+        assert_eq!(settings.get_u64(TUTOR_MONTHLY_TOKENS), Some(250_000));
+
+        // A later challenge reverts to the operator's value
+        let challenged = state(TUTOR_MONTHLY_TOKENS, "challenged", r#"{"value": 250000}"#);
+        assert!(settings.apply(TUTOR_MONTHLY_TOKENS, Some(&challenged)));
+        assert_eq!(settings.get_u64(TUTOR_MONTHLY_TOKENS), Some(100_000));
+        assert!(!settings.apply(TUTOR_MONTHLY_TOKENS, None));
+    }
+
+    #[test]
+    fn test_governed_value_validation() {
+        let spec = spec(MODERATION_SCORE_THRESHOLD).unwrap();
+        let ok = state(spec.key, "approved", r#"{"value": "0.7"}"#);
+        assert_eq!(governed_value(spec, &ok).map(|g| g.value), Some(0.7));
+
+        for bad in [
+            state(spec.key, "approved", r#"{"value": 1.5}"#),
+            state(spec.key, "approved", r#"{"value": true}"#),
+            state(spec.key, "approved", r#"{}"#),
+            state(spec.key, "approved", "not json"),
+            state(spec.key, "pending", r#"{"value": 0.7}"#),
+        ] {
+            assert!(governed_value(spec, &bad).is_none(), "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn test_snapshot_sources() {
+        let settings = GovernedSettings::new();
+        settings.configure(TUTOR_MONTHLY_TOKENS, 5000.0);
+        settings.apply(
+            COMMONS_SHARE_PERCENT,
+            Some(&state(
+                COMMONS_SHARE_PERCENT,
+                "approved",
+                r#"{"value": 20}"#,
+            )),
+        );
+
+        let snapshot = settings.snapshot();
+        let find = |key: &str| snapshot.iter().find(|s| s.key == key).unwrap().clone();
+        assert_eq!(find(COMMONS_SHARE_PERCENT).source, "governance");
+        assert_eq!(find(COMMONS_SHARE_PERCENT).value, Some(20.0));
+        assert_eq!(
+            find(COMMONS_SHARE_PERCENT).governance_state_id.as_deref(),
+            Some("gs-doorway_setting:recognition.commons_share_percent")
+        );
+        assert_eq!(find(TUTOR_MONTHLY_TOKENS).source, "config");
+        assert_eq!(find(STEWARD_SHARE_PERCENT).source, "default");
+        assert_eq!(find(MODERATION_SCORE_THRESHOLD).value, None);
+    }
+}
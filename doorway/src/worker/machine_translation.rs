@@ -0,0 +1,575 @@
+//! Machine-translation assist
+//!
+//! When a reader asks for content in a locale nobody has translated it into
+//! yet, the [translations route](crate::routes::translations) queues a job
+//! here. Each pass sends the content's title, description and (text) body
+//! to the configured provider and stores the result with
+//! `content_store::add_translation` as a draft flagged `machine_generated`.
+//! Drafts land on the steward review queue (`get_pending_translations`,
+//! signalled as `TranslationReview`) and are not served until a steward
+//! publishes them.
+//!
+//! Providers:
+//!
+//! | Name | Endpoint |
+//! |------|----------|
+//! | `libretranslate` | `POST {url}/translate` (self-hosted or libretranslate.com) |
+//! | `deepl` | `POST {url}/v2/translate` (defaults to api.deepl.com, api-free for `:fx` keys) |
+//!
+//! Only `commons`/`public` content is sent to a provider. Each content and
+//! locale pair is queued at most once per process; on restart, an existing
+//! draft for the locale stops it from being translated again.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::services::zome_caller::ZomeCaller;
+
+/// Role holding the content_store zome
+const CONTENT_ROLE: &str = "lamad";
+
+/// Zome owning content and translations
+const CONTENT_ZOME: &str = "content_store";
+
+/// Reach levels that may be sent to a third-party provider
+const TRANSLATABLE_REACH: [&str; 2] = ["commons", "public"];
+
+/// Body formats sent for translation (others keep the original body)
+const TEXT_FORMATS: [&str; 5] = ["markdown", "html", "text", "plaintext", "plain"];
+
+/// Longest body sent to a provider, in characters
+const MAX_BODY_CHARS: usize = 20_000;
+
+/// Jobs processed per pass, to bound provider spend
+const MAX_JOBS_PER_PASS: usize = 20;
+
+/// Most jobs waiting at once; later requests are dropped until there's room
+const MAX_QUEUE_LEN: usize = 500;
+
+/// Timeout for provider calls
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Machine-translation provider
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Provider {
+    LibreTranslate,
+    DeepL,
+}
+
+impl Provider {
+    /// Parse a provider name (case-insensitive)
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "libretranslate" | "libre" => Some(Self::LibreTranslate),
+            "deepl" => Some(Self::DeepL),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::LibreTranslate => "libretranslate",
+            Self::DeepL => "deepl",
+        }
+    }
+
+    /// Default API base URL, when the provider has one
+    pub fn default_url(&self, api_key: Option<&str>) -> Option<String> {
+        match self {
+            Self::LibreTranslate => None,
+            Self::DeepL if api_key.is_some_and(|k| k.ends_with(":fx")) => {
+                Some("https://api-free.deepl.com".to_string())
+            }
+            Self::DeepL => Some("https://api.deepl.com".to_string()),
+        }
+    }
+}
+
+/// Machine-translation settings
+#[derive(Debug, Clone)]
+pub struct MachineTranslationConfig {
+    pub provider: Provider,
+    /// Provider API base URL
+    pub url: String,
+    pub api_key: Option<String>,
+    /// Language content is written in; never translated into
+    pub source_locale: String,
+    /// Locales to translate into; empty allows any
+    pub target_locales: Vec<String>,
+}
+
+/// Outcome of one pass
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TranslationPassSummary {
+    pub translated: usize,
+    pub skipped: usize,
+    pub failed: usize,
+}
+
+/// Primary language subtag of a locale ("pt-BR" -> "pt")
+fn primary_language(locale: &str) -> String {
+    locale.split('-').next().unwrap_or(locale).to_lowercase()
+}
+
+/// DeepL `target_lang` for a locale. English and Portuguese need a variant;
+/// Chinese keeps its script.
+pub fn deepl_target(locale: &str) -> String {
+    let mut subtags = locale.split('-');
+    let primary = subtags.next().unwrap_or(locale).to_uppercase();
+    let rest: Vec<String> = subtags.map(str::to_uppercase).collect();
+    match (primary.as_str(), rest.first().map(String::as_str)) {
+        ("EN", Some(region @ ("GB" | "US"))) => format!("EN-{region}"),
+        ("EN", _) => "EN-US".to_string(),
+        ("PT", Some(region @ ("BR" | "PT"))) => format!("PT-{region}"),
+        ("PT", _) => "PT-PT".to_string(),
+        ("ZH", Some(script @ ("HANS" | "HANT"))) => format!("ZH-{script}"),
+        _ => primary,
+    }
+}
+
+// =============================================================================
+// Zome types (subsets)
+// =============================================================================
+
+#[derive(Debug, Serialize)]
+struct QueryByIdInput<'a> {
+    id: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContentOutput {
+    content: SourceContent,
+}
+
+/// Subset of Content
+#[derive(Debug, Clone, Deserialize)]
+struct SourceContent {
+    title: String,
+    description: String,
+    content: String,
+    content_format: String,
+    reach: String,
+    #[serde(default)]
+    blob_cid: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TranslationOutput {
+    translation: ExistingTranslation,
+}
+
+/// Subset of ContentTranslation
+#[derive(Debug, Deserialize)]
+struct ExistingTranslation {
+    locale: String,
+}
+
+/// Must match AddTranslationInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct AddTranslationInput {
+    content_id: String,
+    locale: String,
+    title: String,
+    description: String,
+    body: Option<String>,
+    translator_id: Option<String>,
+    machine_generated: bool,
+    draft: bool,
+}
+
+// =============================================================================
+// Provider API
+// =============================================================================
+
+#[derive(Debug, Serialize)]
+struct LibreTranslateRequest<'a> {
+    q: &'a str,
+    source: &'static str,
+    target: String,
+    format: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    api_key: Option<&'a str>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LibreTranslateResponse {
+    #[serde(rename = "translatedText")]
+    translated_text: String,
+}
+
+#[derive(Debug, Serialize)]
+struct DeepLRequest<'a> {
+    text: [&'a str; 1],
+    target_lang: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tag_handling: Option<&'static str>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeepLResponse {
+    translations: Vec<DeepLTranslation>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeepLTranslation {
+    text: String,
+}
+
+#[derive(Debug, Default)]
+struct JobQueue {
+    order: VecDeque<(String, String)>,
+    /// Every pair ever queued, so repeat requests don't re-translate
+    seen: HashSet<(String, String)>,
+}
+
+/// Queues and runs machine-translation jobs
+pub struct MachineTranslator {
+    config: MachineTranslationConfig,
+    client: reqwest::Client,
+    queue: Mutex<JobQueue>,
+}
+
+impl MachineTranslator {
+    pub fn new(config: MachineTranslationConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        Self {
+            config,
+            client,
+            queue: Mutex::new(JobQueue::default()),
+        }
+    }
+
+    /// Whether a locale may be machine-translated into
+    fn accepts_locale(&self, locale: &str) -> bool {
+        let primary = primary_language(locale);
+        if primary == primary_language(&self.config.source_locale) {
+            return false;
+        }
+        self.config.target_locales.is_empty()
+            || self
+                .config
+                .target_locales
+                .iter()
+                .any(|t| t.eq_ignore_ascii_case(locale) || t.eq_ignore_ascii_case(&primary))
+    }
+
+    /// Queue a translation of `content_id` into `locale`.
+    /// Returns false when the locale isn't wanted, the pair was queued
+    /// before or the queue is full.
+    pub fn enqueue(&self, content_id: &str, locale: &str) -> bool {
+        if !self.accepts_locale(locale) {
+            return false;
+        }
+        let Ok(mut queue) = self.queue.lock() else {
+            return false;
+        };
+        let job = (content_id.to_string(), locale.to_string());
+        if queue.order.len() >= MAX_QUEUE_LEN || queue.seen.contains(&job) {
+            return false;
+        }
+        queue.seen.insert(job.clone());
+        queue.order.push_back(job);
+        debug!(content_id, locale, "Machine translation queued");
+        true
+    }
+
+    /// Jobs waiting
+    pub fn queued(&self) -> usize {
+        self.queue.lock().map(|q| q.order.len()).unwrap_or(0)
+    }
+
+    fn next_jobs(&self) -> Vec<(String, String)> {
+        let Ok(mut queue) = self.queue.lock() else {
+            return Vec::new();
+        };
+        let take = queue.order.len().min(MAX_JOBS_PER_PASS);
+        queue.order.drain(..take).collect()
+    }
+
+    /// Translate one text
+    async fn translate(&self, text: &str, locale: &str, html: bool) -> Result<String, String> {
+        let api_key = self.config.api_key.as_deref();
+        match self.config.provider {
+            Provider::LibreTranslate => {
+                let request = LibreTranslateRequest {
+                    q: text,
+                    source: "auto",
+                    target: primary_language(locale),
+                    format: if html { "html" } else { "text" },
+                    api_key,
+                };
+                let response = self
+                    .client
+                    .post(format!(
+                        "{}/translate",
+                        self.config.url.trim_end_matches('/')
+                    ))
+                    .json(&request)
+                    .send()
+                    .await
+                    .map_err(|e| format!("Provider request failed: {e}"))?;
+                if !response.status().is_success() {
+                    return Err(format!("Provider returned {}", response.status()));
+                }
+                let body: LibreTranslateResponse = response
+                    .json()
+                    .await
+                    .map_err(|e| format!("Invalid provider response: {e}"))?;
+                Ok(body.translated_text)
+            }
+            Provider::DeepL => {
+                let request = DeepLRequest {
+                    text: [text],
+                    target_lang: deepl_target(locale),
+                    tag_handling: html.then_some("html"),
+                };
+                let mut call = self
+                    .client
+                    .post(format!(
+                        "{}/v2/translate",
+                        self.config.url.trim_end_matches('/')
+                    ))
+                    .json(&request);
+                if let Some(key) = api_key {
+                    call = call.header("Authorization", format!("DeepL-Auth-Key {key}"));
+                }
+                let response = call
+                    .send()
+                    .await
+                    .map_err(|e| format!("Provider request failed: {e}"))?;
+                if !response.status().is_success() {
+                    return Err(format!("Provider returned {}", response.status()));
+                }
+                let body: DeepLResponse = response
+                    .json()
+                    .await
+                    .map_err(|e| format!("Invalid provider response: {e}"))?;
+                body.translations
+                    .into_iter()
+                    .next()
+                    .map(|t| t.text)
+                    .ok_or_else(|| "Provider returned no translation".to_string())
+            }
+        }
+    }
+
+    /// Translate one content node into one locale and store it as a draft.
+    /// Ok(false) means there was nothing to do.
+    async fn process(
+        &self,
+        zome_caller: &ZomeCaller,
+        content_id: &str,
+        locale: &str,
+    ) -> Result<bool, String> {
+        // A person (or an earlier run) may have got there first
+        let existing: Vec<TranslationOutput> = zome_caller
+            .call(
+                CONTENT_ROLE,
+                CONTENT_ZOME,
+                "get_content_translations",
+                &content_id,
+            )
+            .await?;
+        let primary = primary_language(locale);
+        if existing
+            .iter()
+            .any(|t| primary_language(&t.translation.locale) == primary)
+        {
+            return Ok(false);
+        }
+
+        let content: Option<ContentOutput> = zome_caller
+            .call(
+                CONTENT_ROLE,
+                CONTENT_ZOME,
+                "get_content_by_id",
+                &QueryByIdInput { id: content_id },
+            )
+            .await?;
+        let Some(ContentOutput { content }) = content else {
+            return Ok(false);
+        };
+        if !TRANSLATABLE_REACH.contains(&content.reach.as_str()) {
+            return Ok(false);
+        }
+
+        let title = self.translate(&content.title, locale, false).await?;
+        let description = if content.description.trim().is_empty() {
+            String::new()
+        } else {
+            self.translate(&content.description, locale, false).await?
+        };
+        let format = content.content_format.to_lowercase();
+        let body_translatable = content.blob_cid.is_none()
+            && TEXT_FORMATS.contains(&format.as_str())
+            && !content.content.trim().is_empty()
+            && content.content.chars().count() <= MAX_BODY_CHARS;
+        let body = if body_translatable {
+            Some(
+                self.translate(&content.content, locale, format == "html")
+                    .await?,
+            )
+        } else {
+            None
+        };
+
+        let input = AddTranslationInput {
+            content_id: content_id.to_string(),
+            locale: locale.to_string(),
+            title,
+            description,
+            body,
+            translator_id: Some(format!("machine:{}", self.config.provider.name())),
+            machine_generated: true,
+            draft: true,
+        };
+        zome_caller
+            .call::<_, serde_json::Value>(CONTENT_ROLE, CONTENT_ZOME, "add_translation", &input)
+            .await?;
+        Ok(true)
+    }
+
+    /// Work through the queued jobs (up to MAX_JOBS_PER_PASS)
+    pub async fn run_once(&self, zome_caller: &ZomeCaller) -> TranslationPassSummary {
+        let mut summary = TranslationPassSummary::default();
+        for (content_id, locale) in self.next_jobs() {
+            match self.process(zome_caller, &content_id, &locale).await {
+                Ok(true) => {
+                    info!(
+                        content_id = %content_id,
+                        locale = %locale,
+                        "Machine translation stored for review"
+                    );
+                    summary.translated += 1;
+                }
+                Ok(false) => summary.skipped += 1,
+                Err(e) => {
+                    warn!(content_id = %content_id, locale = %locale, error = %e, "Machine translation failed");
+                    summary.failed += 1;
+                }
+            }
+        }
+        summary
+    }
+}
+
+/// Spawn the periodic machine-translation task.
+///
+/// A draft that fails is not retried until the process restarts.
+pub fn spawn_machine_translation_task(
+    interval: Duration,
+    zome_caller: Arc<ZomeCaller>,
+    translator: Arc<MachineTranslator>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!(
+            interval_secs = interval.as_secs(),
+            provider = translator.config.provider.name(),
+            "Machine translation task started"
+        );
+
+        loop {
+            tokio::time::sleep(interval).await;
+
+            if translator.queued() == 0 {
+                continue;
+            }
+            let summary = translator.run_once(&zome_caller).await;
+            info!(
+                translated = summary.translated,
+                skipped = summary.skipped,
+                failed = summary.failed,
+                queued = translator.queued(),
+                "Machine translation pass complete"
+            );
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn translator(target_locales: &[&str]) -> MachineTranslator {
+        MachineTranslator::new(MachineTranslationConfig {
+            provider: Provider::LibreTranslate,
+            url: "http://translate:5000".to_string(),
+            api_key: None,
+            source_locale: "en".to_string(),
+            target_locales: target_locales.iter().map(|s| s.to_string()).collect(),
+        })
+    }
+
+    #[test]
+    fn test_provider_parse_and_default_url() {
+        assert_eq!(Provider::parse("DeepL"), Some(Provider::DeepL));
+        assert_eq!(
+            Provider::parse("libretranslate"),
+            Some(Provider::LibreTranslate)
+        );
+        assert_eq!(Provider::parse("babelfish"), None);
+        assert_eq!(
+            Provider::DeepL.default_url(Some("abc:fx")).as_deref(),
+            Some("https://api-free.deepl.com")
+        );
+        assert_eq!(Provider::LibreTranslate.default_url(None), None);
+    }
+
+    #[test]
+    fn test_deepl_target() {
+        assert_eq!(deepl_target("es"), "ES");
+        assert_eq!(deepl_target("pt-BR"), "PT-BR");
+        assert_eq!(deepl_target("pt"), "PT-PT");
+        assert_eq!(deepl_target("en-AU"), "EN-US");
+        assert_eq!(deepl_target("zh-Hant-TW"), "ZH-HANT");
+        assert_eq!(deepl_target("de-AT"), "DE");
+    }
+
+    #[test]
+    fn test_enqueue_filters_and_dedupes() {
+        let any = translator(&[]);
+        assert!(any.enqueue("intro", "es"));
+        assert!(!any.enqueue("intro", "es"), "queued twice");
+        assert!(!any.enqueue("intro", "en-GB"), "source language");
+        assert!(any.enqueue("intro", "pt-BR"));
+        assert_eq!(any.queued(), 2);
+
+        let limited = translator(&["es", "pt-BR"]);
+        assert!(limited.enqueue("intro", "es-MX"));
+        assert!(limited.enqueue("intro", "pt-BR"));
+        assert!(!limited.enqueue("intro", "pt-PT"));
+        assert!(!limited.enqueue("intro", "fr"));
+    }
+
+    #[test]
+    fn test_next_jobs_drains_in_order() {
+        let translator = translator(&[]);
+        for i in 0..(MAX_JOBS_PER_PASS + 5) {
+            translator.enqueue(&format!("c{i}"), "fr");
+        }
+        let jobs = translator.next_jobs();
+        assert_eq!(jobs.len(), MAX_JOBS_PER_PASS);
+        assert_eq!(jobs[0], ("c0".to_string(), "fr".to_string()));
+        assert_eq!(translator.queued(), 5);
+    }
+
+    #[test]
+    fn test_provider_responses_parse() {
+        let libre: LibreTranslateResponse =
+            serde_json::from_str(r#"{"translatedText":"Hola"}"#).unwrap();
+        assert_eq!(libre.translated_text, "Hola");
+
+        let deepl: DeepLResponse = serde_json::from_str(
+            r#"{"translations":[{"detected_source_language":"EN","text":"Hallo"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(deepl.translations[0].text, "Hallo");
+    }
+}
@@ -5,10 +5,58 @@
 //! - **NATS mode**: JetStream-based distributed workers (multi-node)
 //!
 //! Pool mode is used automatically when NATS isn't available.
+//!
+//! Also hosts periodic background jobs that drive zome workflows on a timer
+//! (see [`dead_mans_switch`], the [`analytics`] rollup and the
+//! [`content_health`] sweep), the per-agent [`recommendations`] engine and
+//! the optional [`search_export`] connector and S3 [`blob_mirror`],
+//! [`torrent`] metadata generation for large blobs, the [`transcode`]
+//! pipeline hook, [`sitemap`] generation, [`machine_translation`] assist,
+//! content [`embeddings`] for semantic related-content,
+//! [`question_generation`] for the assessment question bank, the
+//! [`governance`] executor that applies approved doorway settings, the
+//! [`elohim_tasks`] tracker for work dispatched to elohim agents,
+//! Shefa request/offer [`service_matching`], learning
+//! [`session_reminders`], insurance mutual
+//! [`solvency`] snapshots, [`external_resources`] enrichment for external
+//! path steps, the conductor [`signal_journal`] used to
+//! replay projections, the slow-query [`query_advisor`] for cache rules, the
+//! [`cache_stats`] rollup of per-rule cache counters,
+//! [`retention`] policies that archive, tombstone and expire doorway data
+//! and the optional domain [`event_export`] to Kafka or NATS. Doorways in
+//! several [`regions`] announce themselves and share cache invalidations.
+//! Learners' Open Badges exports are signed by [`badge_export`], and public
+//! paths are printed to PDF by [`path_render`].
 
+pub mod analytics;
+pub mod badge_export;
+pub mod blob_mirror;
+pub mod cache_stats;
 pub mod conductor;
+pub mod content_health;
+pub mod dead_mans_switch;
+pub mod elohim_tasks;
+pub mod embeddings;
+pub mod event_export;
+pub mod external_resources;
+pub mod governance;
+pub mod machine_translation;
+pub mod path_render;
 pub mod pool;
 pub mod processor;
+pub mod query_advisor;
+pub mod question_generation;
+pub mod recommendations;
+pub mod regions;
+pub mod retention;
+pub mod search_export;
+pub mod service_matching;
+pub mod session_reminders;
+pub mod signal_journal;
+pub mod sitemap;
+pub mod solvency;
+pub mod torrent;
+pub mod transcode;
 pub mod zome_call;
 
 pub use conductor::ConductorConnection;
@@ -0,0 +1,502 @@
+//! Printable path rendering
+//!
+//! Renders a learning path - its chapters, steps and content bodies - as a
+//! paginated PDF for offline and classroom use, served at
+//! `GET /paths/{id}/export.pdf`. The doorway writes the path as one
+//! print-styled HTML document and hands it to a headless Chromium renderer.
+//! Anything speaking Gotenberg's HTML route works:
+//!
+//! | Request | Body / Response |
+//! |---------|-----------------|
+//! | `POST {url}/forms/chromium/convert/html` | multipart `files` = `index.html` → `application/pdf` |
+//!
+//! Renders run in the background; a request for a path not rendered yet
+//! starts one and is told to come back. PDFs are kept in memory by path and
+//! version (the path's `version` and `updated_at`), so an unchanged path is
+//! served from memory and an edited one is rendered afresh. Past
+//! `PATH_RENDER_CACHE_SIZE` paths, the oldest render is dropped.
+//!
+//! As in the [static site export](crate::services::site_export), only public
+//! paths are rendered, and only content that may be redistributed (commons
+//! reach, license permitting) has its body printed; other steps show their
+//! title. Bodies are printed as text, never as markup, so nothing in them
+//! runs in the renderer.
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::routes::preview::escape_html;
+use crate::services::site_export::{is_exportable_content, is_public_path, str_field};
+use crate::services::zome_caller::ZomeCaller;
+
+/// Role holding the content_store zome
+const CONTENT_ROLE: &str = "lamad";
+
+/// Zome owning paths, chapters and content
+const CONTENT_ZOME: &str = "content_store";
+
+/// Timeout for one render
+const RENDER_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How long a failed render is reported before it is tried again
+const RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// Renderer settings
+#[derive(Debug, Clone)]
+pub struct PathRenderConfig {
+    /// Renderer base URL
+    pub url: String,
+    /// Paths whose PDFs are kept in memory
+    pub cache_size: usize,
+    /// Doorway URL, for links to content printed without its body
+    pub doorway_url: Option<String>,
+}
+
+/// PathReadInput::WithOptions in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Clone, Serialize)]
+struct PathReadInput<'a> {
+    path_id: &'a str,
+    include_content: bool,
+}
+
+/// Subset of PathWithSteps; entries are kept as JSON
+#[derive(Debug, Clone, Deserialize)]
+pub struct PathWithSteps {
+    pub path: Value,
+    pub steps: Vec<PathStepOutput>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PathStepOutput {
+    pub step: Value,
+    #[serde(default)]
+    pub content: Option<ContentOutput>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ContentOutput {
+    pub content: Value,
+}
+
+/// Subset of ChapterWithSteps
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChapterWithSteps {
+    pub chapter: Value,
+}
+
+/// Where a path's PDF is up to
+#[derive(Debug, Clone)]
+pub enum RenderOutcome {
+    /// No such public path
+    NotFound,
+    /// Being rendered; ask again shortly
+    Pending,
+    Ready(Bytes),
+    Failed(String),
+}
+
+#[derive(Debug, Clone)]
+enum RenderState {
+    Pending,
+    Ready(Bytes),
+    Failed(String),
+}
+
+/// A path's latest render
+#[derive(Debug, Clone)]
+struct RenderedPath {
+    version: String,
+    state: RenderState,
+    at: DateTime<Utc>,
+}
+
+/// Version a render is cached under
+pub fn path_version(path: &Value) -> String {
+    format!(
+        "{}@{}",
+        str_field(path, "version").unwrap_or_default(),
+        str_field(path, "updated_at").unwrap_or_default()
+    )
+}
+
+/// Print stylesheet: A4 pages, each chapter starting a new one
+const PRINT_CSS: &str = "@page{size:A4;margin:2cm}\
+body{font-family:serif;line-height:1.5;font-size:11pt}\
+h1{font-size:24pt;margin-top:30%}\
+.chapter{break-before:page}\
+.step{break-inside:avoid-page}\
+.body{white-space:pre-wrap}\
+.note{color:#555;font-style:italic}\
+nav ol{line-height:1.8}";
+
+/// The path as one printable HTML document
+pub fn render_path_document(
+    path: &PathWithSteps,
+    chapters: &[ChapterWithSteps],
+    doorway_url: Option<&str>,
+) -> String {
+    let title = str_field(&path.path, "title").unwrap_or_default();
+    let mut steps: Vec<&PathStepOutput> = path.steps.iter().collect();
+    steps.sort_by_key(|s| s.step["order_index"].as_u64().unwrap_or(0));
+
+    // Steps under their chapters, in chapter order; unchaptered steps first
+    let mut chapter_order: Vec<(u64, String, Value)> = chapters
+        .iter()
+        .filter_map(|c| {
+            let id = str_field(&c.chapter, "id")?;
+            Some((
+                c.chapter["order_index"].as_u64().unwrap_or(0),
+                id,
+                c.chapter.clone(),
+            ))
+        })
+        .collect();
+    chapter_order.sort_by_key(|(order, _, _)| *order);
+    let mut by_chapter: BTreeMap<Option<String>, Vec<&PathStepOutput>> = BTreeMap::new();
+    for step in steps {
+        let chapter_id = str_field(&step.step, "chapter_id")
+            .filter(|id| chapter_order.iter().any(|(_, c, _)| c == id));
+        by_chapter.entry(chapter_id).or_default().push(step);
+    }
+    let mut sections: Vec<(Option<&Value>, Vec<&PathStepOutput>)> = Vec::new();
+    if let Some(steps) = by_chapter.remove(&None) {
+        sections.push((None, steps));
+    }
+    for (_, id, chapter) in chapter_order.iter() {
+        if let Some(steps) = by_chapter.remove(&Some(id.clone())) {
+            sections.push((Some(chapter), steps));
+        }
+    }
+
+    let mut body = format!("<h1>{}</h1>\n", escape_html(&title));
+    if let Some(description) = str_field(&path.path, "description") {
+        body.push_str(&format!("<p>{}</p>\n", escape_html(&description)));
+    }
+
+    body.push_str("<nav>\n<h2>Contents</h2>\n<ol>\n");
+    for (chapter, steps) in sections.iter() {
+        match chapter {
+            Some(chapter) => body.push_str(&format!(
+                "<li>{}</li>\n",
+                escape_html(&str_field(chapter, "title").unwrap_or_default())
+            )),
+            None => {
+                for step in steps {
+                    body.push_str(&format!("<li>{}</li>\n", escape_html(&step_title(step))));
+                }
+            }
+        }
+    }
+    body.push_str("</ol>\n</nav>\n");
+
+    for (chapter, steps) in sections.iter() {
+        if let Some(chapter) = chapter {
+            body.push_str(&format!(
+                "<section class=\"chapter\">\n<h2>{}</h2>\n",
+                escape_html(&str_field(chapter, "title").unwrap_or_default())
+            ));
+            if let Some(description) = str_field(chapter, "description") {
+                body.push_str(&format!("<p>{}</p>\n", escape_html(&description)));
+            }
+        } else {
+            body.push_str("<section class=\"chapter\">\n");
+        }
+        for step in steps {
+            render_step(&mut body, step, doorway_url);
+        }
+        body.push_str("</section>\n");
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{PRINT_CSS}</style>\n</head>\n<body>\n{body}</body>\n</html>\n",
+        escape_html(&title)
+    )
+}
+
+fn step_title(step: &PathStepOutput) -> String {
+    str_field(&step.step, "step_title")
+        .or_else(|| {
+            step.content
+                .as_ref()
+                .and_then(|c| str_field(&c.content, "title"))
+        })
+        .or_else(|| str_field(&step.step, "resource_id"))
+        .unwrap_or_default()
+}
+
+fn render_step(body: &mut String, step: &PathStepOutput, doorway_url: Option<&str>) {
+    body.push_str(&format!(
+        "<article class=\"step\">\n<h3>{}</h3>\n",
+        escape_html(&step_title(step))
+    ));
+    if let Some(narrative) = str_field(&step.step, "step_narrative") {
+        body.push_str(&format!("<p>{}</p>\n", escape_html(&narrative)));
+    }
+
+    let resource_id = str_field(&step.step, "resource_id").unwrap_or_default();
+    let online =
+        doorway_url.map(|url| format!("{}/content/{}", url.trim_end_matches('/'), resource_id));
+    match step.content.as_ref().map(|c| &c.content) {
+        Some(content) if is_exportable_content(content) => {
+            match str_field(content, "content").filter(|_| str_field(content, "blob_cid").is_none())
+            {
+                Some(text) => body.push_str(&format!(
+                    "<div class=\"body\">{}</div>\n",
+                    escape_html(&text)
+                )),
+                None => {
+                    let note = match online {
+                        Some(ref url) => format!("Read this step online: {url}"),
+                        None => "This step's content is available online.".to_string(),
+                    };
+                    body.push_str(&format!("<p class=\"note\">{}</p>\n", escape_html(&note)));
+                }
+            }
+        }
+        Some(_) => body.push_str(
+            "<p class=\"note\">This step's content can't be printed; read it on Elohim.</p>\n",
+        ),
+        None => {}
+    }
+    body.push_str("</article>\n");
+}
+
+/// A Gotenberg-style multipart body carrying `index.html`
+fn multipart_body(boundary: &str, html: &str) -> Vec<u8> {
+    format!(
+        "--{boundary}\r\n\
+         Content-Disposition: form-data; name=\"files\"; filename=\"index.html\"\r\n\
+         Content-Type: text/html; charset=utf-8\r\n\r\n\
+         {html}\r\n\
+         --{boundary}\r\n\
+         Content-Disposition: form-data; name=\"preferCssPageSize\"\r\n\r\n\
+         true\r\n\
+         --{boundary}--\r\n"
+    )
+    .into_bytes()
+}
+
+/// Renders public paths to PDF and holds the results
+pub struct PathRenderer {
+    config: PathRenderConfig,
+    zome_caller: Arc<ZomeCaller>,
+    client: reqwest::Client,
+    renders: DashMap<String, RenderedPath>,
+}
+
+impl PathRenderer {
+    pub fn new(config: PathRenderConfig, zome_caller: Arc<ZomeCaller>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(RENDER_TIMEOUT)
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+        Self {
+            config,
+            zome_caller,
+            client,
+            renders: DashMap::new(),
+        }
+    }
+
+    async fn load_path(&self, path_id: &str) -> Result<Option<PathWithSteps>, String> {
+        let input = PathReadInput {
+            path_id,
+            include_content: true,
+        };
+        self.zome_caller
+            .call(CONTENT_ROLE, CONTENT_ZOME, "get_path_with_steps", &input)
+            .await
+    }
+
+    /// The PDF of a public path's current version, starting a render when
+    /// there is none
+    pub async fn pdf(self: &Arc<Self>, path_id: &str) -> Result<RenderOutcome, String> {
+        let Some(path) = self.load_path(path_id).await? else {
+            return Ok(RenderOutcome::NotFound);
+        };
+        if !is_public_path(&path.path) {
+            return Ok(RenderOutcome::NotFound);
+        }
+        let version = path_version(&path.path);
+
+        if let Some(render) = self.renders.get(path_id) {
+            if render.version == version {
+                match render.state {
+                    RenderState::Ready(ref pdf) => return Ok(RenderOutcome::Ready(pdf.clone())),
+                    RenderState::Pending => return Ok(RenderOutcome::Pending),
+                    RenderState::Failed(ref error)
+                        if (Utc::now() - render.at)
+                            .to_std()
+                            .is_ok_and(|age| age < RETRY_AFTER) =>
+                    {
+                        return Ok(RenderOutcome::Failed(error.clone()))
+                    }
+                    RenderState::Failed(_) => {}
+                }
+            }
+        }
+
+        self.renders.insert(
+            path_id.to_string(),
+            RenderedPath {
+                version: version.clone(),
+                state: RenderState::Pending,
+                at: Utc::now(),
+            },
+        );
+        let renderer = Arc::clone(self);
+        let path_id = path_id.to_string();
+        tokio::spawn(async move {
+            let state = match renderer.render(&path_id, &path).await {
+                Ok(pdf) => {
+                    info!(path_id = %path_id, version = %version, bytes = pdf.len(), "Path PDF rendered");
+                    RenderState::Ready(pdf)
+                }
+                Err(e) => {
+                    warn!(path_id = %path_id, error = %e, "Path PDF render failed");
+                    RenderState::Failed(e)
+                }
+            };
+            renderer.renders.insert(
+                path_id,
+                RenderedPath {
+                    version,
+                    state,
+                    at: Utc::now(),
+                },
+            );
+            renderer.evict();
+        });
+        Ok(RenderOutcome::Pending)
+    }
+
+    async fn render(&self, path_id: &str, path: &PathWithSteps) -> Result<Bytes, String> {
+        let input = PathReadInput {
+            path_id,
+            include_content: false,
+        };
+        let chapters: Vec<ChapterWithSteps> = self
+            .zome_caller
+            .call(CONTENT_ROLE, CONTENT_ZOME, "get_chapters_for_path", &input)
+            .await?;
+        let html = render_path_document(path, &chapters, self.config.doorway_url.as_deref());
+
+        let boundary = format!("elohim-{}", uuid::Uuid::new_v4().simple());
+        let response = self
+            .client
+            .post(format!(
+                "{}/forms/chromium/convert/html",
+                self.config.url.trim_end_matches('/')
+            ))
+            .header(
+                "Content-Type",
+                format!("multipart/form-data; boundary={boundary}"),
+            )
+            .body(multipart_body(&boundary, &html))
+            .send()
+            .await
+            .map_err(|e| format!("Renderer unreachable: {e}"))?;
+        if !response.status().is_success() {
+            return Err(format!("Renderer returned {}", response.status()));
+        }
+        response
+            .bytes()
+            .await
+            .map_err(|e| format!("Failed to read rendered PDF: {e}"))
+    }
+
+    /// Drop the oldest finished renders past the cache size
+    fn evict(&self) {
+        let mut finished: Vec<(String, DateTime<Utc>)> = self
+            .renders
+            .iter()
+            .filter(|r| !matches!(r.state, RenderState::Pending))
+            .map(|r| (r.key().clone(), r.at))
+            .collect();
+        if finished.len() <= self.config.cache_size {
+            return;
+        }
+        finished.sort_by_key(|(_, at)| *at);
+        let excess = finished.len() - self.config.cache_size;
+        for (path_id, _) in finished.into_iter().take(excess) {
+            self.renders.remove(&path_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn step(order: u64, chapter: Option<&str>, content: Option<Value>) -> PathStepOutput {
+        PathStepOutput {
+            step: json!({
+                "order_index": order,
+                "chapter_id": chapter,
+                "resource_id": format!("content-{order}"),
+                "step_title": format!("Step {order}"),
+            }),
+            content: content.map(|content| ContentOutput { content }),
+        }
+    }
+
+    #[test]
+    fn test_render_path_document() {
+        let path = PathWithSteps {
+            path: json!({"id": "path-1", "title": "Fractions", "visibility": "public"}),
+            steps: vec![
+                step(
+                    2,
+                    Some("ch-2"),
+                    Some(json!({"reach": "private", "content": "secret"})),
+                ),
+                step(
+                    1,
+                    Some("ch-1"),
+                    Some(json!({"reach": "commons", "content": "<b>1/10</b>"})),
+                ),
+                step(0, None, None),
+            ],
+        };
+        let chapters = vec![
+            ChapterWithSteps {
+                chapter: json!({"id": "ch-2", "order_index": 1, "title": "Decimals"}),
+            },
+            ChapterWithSteps {
+                chapter: json!({"id": "ch-1", "order_index": 0, "title": "Tenths"}),
+            },
+        ];
+        let html = render_path_document(&path, &chapters, Some("https://doorway.example"));
+
+        // Unchaptered steps, then chapters in order
+        let step0 = html.find("<h3>Step 0</h3>").unwrap();
+        let tenths = html.find("<h2>Tenths</h2>").unwrap();
+        let decimals = html.find("<h2>Decimals</h2>").unwrap();
+        assert!(step0 < tenths && tenths < decimals);
+
+        // Bodies are text, and only redistributable ones are printed
+        assert!(html.contains("&lt;b&gt;1/10&lt;/b&gt;"));
+        assert!(!html.contains("secret"));
+    }
+
+    #[test]
+    fn test_path_version_and_multipart() {
+        let path = json!({"version": "1.2", "updated_at": "2026-10-16T09:30:00Z"});
+        assert_eq!(path_version(&path), "1.2@2026-10-16T09:30:00Z");
+
+        let body = String::from_utf8(multipart_body("b", "<p>hi</p>")).unwrap();
+        assert!(body.starts_with(
+            "--b\r\nContent-Disposition: form-data; name=\"files\"; filename=\"index.html\""
+        ));
+        assert!(body.contains("\r\n\r\n<p>hi</p>\r\n--b\r\n"));
+        assert!(body.ends_with("--b--\r\n"));
+    }
+}
@@ -6,14 +6,19 @@
 //! - No thread starvation
 //!
 //! Use this for single-node deployments. Use NATS for distributed multi-node setups.
+//!
+//! The pool reports its [`ConductorLoad`] (calls in flight, recent timeout
+//! rate) and, given an [`AdmissionPolicy`], holds back low-priority calls
+//! while the conductor is under pressure.
 
 use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, oneshot, Semaphore};
 use tracing::{debug, error, info};
 
 use super::conductor::ConductorConnection;
+use crate::proxy::admission::{AdmissionPolicy, CallOutcomes, CallPriority, ConductorLoad};
 use crate::types::{DoorwayError, Result};
 
 /// Request sent to the worker pool
@@ -66,6 +71,8 @@ pub struct PoolMetrics {
     pub requests_err: u64,
     /// Error rate ratio (err / total), 0.0 - 1.0
     pub error_rate: f64,
+    /// Share of the last minute's requests that timed out, 0.0 - 1.0
+    pub recent_timeout_rate: f64,
 }
 
 /// In-process worker pool that manages conductor connections
@@ -86,6 +93,10 @@ pub struct WorkerPool {
     requests_ok: Arc<AtomicU64>,
     /// Count of failed requests
     requests_err: Arc<AtomicU64>,
+    /// Completed and timed-out requests over the last minute
+    outcomes: CallOutcomes,
+    /// Holds back low-priority requests under conductor pressure
+    admission: Option<Arc<AdmissionPolicy>>,
 }
 
 impl WorkerPool {
@@ -126,11 +137,34 @@ impl WorkerPool {
             max_queue_size: config.max_queue_size,
             requests_ok: Arc::new(AtomicU64::new(0)),
             requests_err: Arc::new(AtomicU64::new(0)),
+            outcomes: CallOutcomes::default(),
+            admission: None,
         })
     }
 
+    /// Apply an admission policy to low-priority requests
+    pub fn with_admission(mut self, admission: Option<Arc<AdmissionPolicy>>) -> Self {
+        self.admission = admission;
+        self
+    }
+
     /// Send a request through the pool and wait for response
     pub async fn request(&self, payload: Vec<u8>) -> Result<Vec<u8>> {
+        self.request_with_priority(payload, CallPriority::Normal)
+            .await
+    }
+
+    /// Send a request that may be delayed or shed while the conductor is
+    /// under pressure
+    pub async fn request_with_priority(
+        &self,
+        payload: Vec<u8>,
+        priority: CallPriority,
+    ) -> Result<Vec<u8>> {
+        if let Some(ref admission) = self.admission {
+            admission.admit(|| self.load(), priority).await?;
+        }
+
         // Try to acquire semaphore (limits queue depth)
         let _permit = self
             .semaphore
@@ -160,6 +194,9 @@ impl WorkerPool {
         };
 
         // Track success/error counts
+        let timed_out =
+            matches!(&result, Err(DoorwayError::Holochain(msg)) if msg == "Request timeout");
+        self.outcomes.record(timed_out, Instant::now());
         match &result {
             Ok(_) => {
                 self.requests_ok.fetch_add(1, Ordering::Relaxed);
@@ -178,6 +215,20 @@ impl WorkerPool {
             .saturating_sub(self.semaphore.available_permits())
     }
 
+    /// Calls in flight and recent timeout rate
+    pub fn load(&self) -> ConductorLoad {
+        let (calls, timeouts) = self.outcomes.totals(Instant::now());
+        ConductorLoad {
+            in_flight: self.queue_depth(),
+            timeout_rate: if calls > 0 {
+                timeouts as f64 / calls as f64
+            } else {
+                0.0
+            },
+            recent_calls: calls,
+        }
+    }
+
     /// Check if the worker pool is healthy (at least one worker connected to conductor)
     pub fn is_healthy(&self) -> bool {
         self.connected_workers.load(Ordering::Relaxed) > 0
@@ -219,6 +270,7 @@ impl WorkerPool {
             requests_ok: ok,
             requests_err: err,
             error_rate,
+            recent_timeout_rate: self.load().timeout_rate,
         }
     }
 }
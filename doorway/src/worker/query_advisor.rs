@@ -0,0 +1,527 @@
+//! Slow query log and cache-rule tuning advisor
+//!
+//! Every content_store zome call made through the doorway is timed. Calls
+//! over the threshold (`SLOW_QUERY_THRESHOLD_MS`) are logged with whether the
+//! function is cacheable and the shape of its arguments, never their values.
+//! Each interval the advisor turns the window's numbers into suggestions:
+//!
+//! - **cache_longer**: a cacheable read that keeps missing slowly although
+//!   nothing invalidated it, so its TTL is what expires it
+//! - **add_cache_rule**: a slow read with no cache rule at all
+//! - **unused_invalidation**: a rule whose `invalidated_by` writes never
+//!   happened during the window
+//!
+//! Admins read the latest report at `GET /admin/query-advisor`.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::cache::{CacheInvalidation, CacheRuleStore};
+
+/// Slow calls kept for the report
+const MAX_SLOW_CALLS: usize = 200;
+
+/// Calls a function needs in a window before the advisor judges it
+const MIN_CALLS: u64 = 20;
+
+/// Slow calls a function without a rule needs before a rule is suggested
+const MIN_SLOW_CALLS: u64 = 5;
+
+/// Share of slow misses that marks a TTL as too short
+const SLOW_MISS_RATIO: f64 = 0.25;
+
+/// Longest TTL the advisor will suggest
+const MAX_SUGGESTED_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// TTL suggested for a new rule
+const NEW_RULE_TTL_SECS: u64 = 300;
+
+/// A logged slow call
+#[derive(Debug, Clone, Serialize)]
+pub struct SlowCall {
+    pub at: DateTime<Utc>,
+    pub fn_name: String,
+    pub duration_ms: u64,
+    /// Whether a cache rule covers the function (the call was a cache miss)
+    pub cacheable: bool,
+    /// Argument structure with values replaced by their types
+    pub args_shape: String,
+}
+
+/// Per-function numbers for the window
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FnStats {
+    pub dna_hash: String,
+    pub fn_name: String,
+    pub calls: u64,
+    pub slow_calls: u64,
+    pub total_ms: u64,
+    pub max_ms: u64,
+    pub cacheable: bool,
+}
+
+impl FnStats {
+    pub fn mean_ms(&self) -> u64 {
+        self.total_ms.checked_div(self.calls).unwrap_or(0)
+    }
+}
+
+/// A tuning suggestion
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Suggestion {
+    CacheLonger {
+        fn_name: String,
+        current_ttl_secs: u64,
+        suggested_ttl_secs: u64,
+        reason: String,
+    },
+    AddCacheRule {
+        fn_name: String,
+        suggested_ttl_secs: u64,
+        reason: String,
+    },
+    UnusedInvalidation {
+        fn_name: String,
+        invalidated_by: Vec<String>,
+        reason: String,
+    },
+}
+
+/// Advisor output for one window
+#[derive(Debug, Clone, Serialize)]
+pub struct AdvisorReport {
+    pub window_started_at: DateTime<Utc>,
+    pub generated_at: DateTime<Utc>,
+    pub threshold_ms: u64,
+    pub functions: Vec<FnStats>,
+    pub slow_calls: Vec<SlowCall>,
+    pub suggestions: Vec<Suggestion>,
+}
+
+#[derive(Debug, Clone)]
+struct Window {
+    started_at: DateTime<Utc>,
+    functions: HashMap<(String, String), FnStats>,
+    slow_calls: VecDeque<SlowCall>,
+    /// Rules invalidated at least once, as (dna_hash, fn_name)
+    fired: HashSet<(String, String)>,
+}
+
+impl Window {
+    fn new(started_at: DateTime<Utc>) -> Self {
+        Self {
+            started_at,
+            functions: HashMap::new(),
+            slow_calls: VecDeque::new(),
+            fired: HashSet::new(),
+        }
+    }
+}
+
+/// Records zome call latency and advises on cache rules
+pub struct QueryAdvisor {
+    threshold: Duration,
+    window: Mutex<Window>,
+    latest: RwLock<Option<AdvisorReport>>,
+}
+
+impl QueryAdvisor {
+    pub fn new(threshold: Duration) -> Self {
+        Self {
+            threshold,
+            window: Mutex::new(Window::new(Utc::now())),
+            latest: RwLock::new(None),
+        }
+    }
+
+    /// Record a zome call that went to the conductor
+    pub fn record_call(
+        &self,
+        dna_hash: &str,
+        fn_name: &str,
+        elapsed: Duration,
+        cacheable: bool,
+        input: &Value,
+    ) {
+        let ms = elapsed.as_millis() as u64;
+        let slow = elapsed >= self.threshold;
+        let Ok(mut window) = self.window.lock() else {
+            return;
+        };
+
+        let stats = window
+            .functions
+            .entry((dna_hash.to_string(), fn_name.to_string()))
+            .or_insert_with(|| FnStats {
+                dna_hash: dna_hash.to_string(),
+                fn_name: fn_name.to_string(),
+                ..Default::default()
+            });
+        stats.calls += 1;
+        stats.total_ms += ms;
+        stats.max_ms = stats.max_ms.max(ms);
+        stats.cacheable = cacheable;
+        if !slow {
+            return;
+        }
+        stats.slow_calls += 1;
+
+        if window.slow_calls.len() == MAX_SLOW_CALLS {
+            window.slow_calls.pop_front();
+        }
+        window.slow_calls.push_back(SlowCall {
+            at: Utc::now(),
+            fn_name: fn_name.to_string(),
+            duration_ms: ms,
+            cacheable,
+            args_shape: args_shape(input),
+        });
+        info!(fn_name = %fn_name, duration_ms = ms, cacheable, "Slow zome call");
+    }
+
+    /// Record that a write invalidated cached `fn_name` results in `dna_hash`
+    pub fn record_invalidation(&self, dna_hash: &str, fn_name: &str) {
+        if let Ok(mut window) = self.window.lock() {
+            window
+                .fired
+                .insert((dna_hash.to_string(), fn_name.to_string()));
+        }
+    }
+
+    /// Close the window: build its report, keep it as the latest and start a
+    /// new window
+    pub fn roll_window(&self, rules: &CacheRuleStore) -> AdvisorReport {
+        let now = Utc::now();
+        let window = match self.window.lock() {
+            Ok(mut window) => std::mem::replace(&mut *window, Window::new(now)),
+            Err(_) => Window::new(now),
+        };
+        let report = build_report(window, rules, self.threshold, now);
+        if let Ok(mut latest) = self.latest.write() {
+            *latest = Some(report.clone());
+        }
+        report
+    }
+
+    /// Latest closed window's report, or the open window so far
+    pub fn report(&self, rules: &CacheRuleStore) -> AdvisorReport {
+        if let Some(report) = self.latest.read().ok().and_then(|l| l.clone()) {
+            return report;
+        }
+        let now = Utc::now();
+        let window = match self.window.lock() {
+            Ok(window) => window.clone(),
+            Err(_) => Window::new(now),
+        };
+        build_report(window, rules, self.threshold, now)
+    }
+}
+
+fn build_report(
+    window: Window,
+    rules: &CacheRuleStore,
+    threshold: Duration,
+    now: DateTime<Utc>,
+) -> AdvisorReport {
+    let mut suggestions = Vec::new();
+
+    let mut functions: Vec<FnStats> = window.functions.into_values().collect();
+    functions.sort_by(|a, b| {
+        b.slow_calls
+            .cmp(&a.slow_calls)
+            .then_with(|| a.fn_name.cmp(&b.fn_name))
+    });
+
+    for stats in &functions {
+        let fired = window
+            .fired
+            .contains(&(stats.dna_hash.clone(), stats.fn_name.clone()));
+        match rules.get_rule(&stats.dna_hash, &stats.fn_name) {
+            Some(rule) if rule.cacheable => {
+                let slow_ratio = stats.slow_calls as f64 / stats.calls.max(1) as f64;
+                if stats.calls >= MIN_CALLS
+                    && slow_ratio >= SLOW_MISS_RATIO
+                    && !fired
+                    && rule.ttl_secs < MAX_SUGGESTED_TTL_SECS
+                {
+                    suggestions.push(Suggestion::CacheLonger {
+                        fn_name: stats.fn_name.clone(),
+                        current_ttl_secs: rule.ttl_secs,
+                        suggested_ttl_secs: (rule.ttl_secs.max(60) * 4)
+                            .min(MAX_SUGGESTED_TTL_SECS),
+                        reason: format!(
+                            "{} of {} calls missed the cache and took over {} ms; nothing invalidated it",
+                            stats.slow_calls,
+                            stats.calls,
+                            threshold.as_millis()
+                        ),
+                    });
+                }
+            }
+            // The operator ruled these out
+            None if rules.is_denied(&stats.dna_hash, &stats.fn_name) => {}
+            _ if is_read(&stats.fn_name) && stats.slow_calls >= MIN_SLOW_CALLS => {
+                suggestions.push(Suggestion::AddCacheRule {
+                    fn_name: stats.fn_name.clone(),
+                    suggested_ttl_secs: NEW_RULE_TTL_SECS,
+                    reason: format!(
+                        "{} slow calls (mean {} ms) with no cache rule",
+                        stats.slow_calls,
+                        stats.mean_ms()
+                    ),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    let mut declared = rules.declared_rules();
+    declared.sort_by(|a, b| a.1.fn_name.cmp(&b.1.fn_name));
+    for (dna_hash, rule) in declared {
+        if !rule.cacheable
+            || (rule.invalidated_by.is_empty() && rule.bridge_invalidated_by.is_empty())
+            || window.fired.contains(&(dna_hash, rule.fn_name.clone()))
+        {
+            continue;
+        }
+        let invalidated_by: Vec<String> = rule
+            .invalidated_by
+            .iter()
+            .chain(&rule.bridge_invalidated_by)
+            .cloned()
+            .collect();
+        suggestions.push(Suggestion::UnusedInvalidation {
+            fn_name: rule.fn_name.clone(),
+            reason: format!(
+                "none of {} wrote during the window",
+                invalidated_by.join(", ")
+            ),
+            invalidated_by,
+        });
+    }
+
+    AdvisorReport {
+        window_started_at: window.started_at,
+        generated_at: now,
+        threshold_ms: threshold.as_millis() as u64,
+        functions,
+        slow_calls: window.slow_calls.into_iter().rev().collect(),
+        suggestions,
+    }
+}
+
+/// Read-only by naming convention
+fn is_read(fn_name: &str) -> bool {
+    ["get_", "list_", "query_", "search_", "find_"]
+        .iter()
+        .any(|prefix| fn_name.starts_with(prefix))
+}
+
+/// Structure of a zome input with values replaced by their types
+pub fn args_shape(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(_) => "bool".to_string(),
+        Value::Number(_) => "number".to_string(),
+        Value::String(_) => "string".to_string(),
+        Value::Array(items) => match items.first() {
+            Some(first) => format!("[{}; {}]", args_shape(first), items.len()),
+            None => "[]".to_string(),
+        },
+        Value::Object(fields) => {
+            let mut keys: Vec<&String> = fields.keys().collect();
+            keys.sort();
+            let inner: Vec<String> = keys
+                .into_iter()
+                .map(|key| format!("{key}: {}", args_shape(&fields[key])))
+                .collect();
+            format!("{{{}}}", inner.join(", "))
+        }
+    }
+}
+
+/// Spawn the advisor: roll the window every `interval`, and count
+/// invalidations arriving as zome write signals
+pub fn spawn_query_advisor_task(
+    advisor: Arc<QueryAdvisor>,
+    rules: Arc<CacheRuleStore>,
+    interval: Duration,
+    mut invalidations: Option<broadcast::Receiver<CacheInvalidation>>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!(
+            interval_secs = interval.as_secs(),
+            threshold_ms = advisor.threshold.as_millis() as u64,
+            "Query advisor started"
+        );
+
+        let mut ticker = tokio::time::interval(interval);
+        ticker.tick().await;
+        loop {
+            let mut closed = false;
+            let signal = async {
+                match invalidations.as_mut() {
+                    Some(rx) => rx.recv().await,
+                    None => std::future::pending().await,
+                }
+            };
+            tokio::select! {
+                _ = ticker.tick() => {
+                    let report = advisor.roll_window(&rules);
+                    info!(
+                        functions = report.functions.len(),
+                        slow_calls = report.slow_calls.len(),
+                        suggestions = report.suggestions.len(),
+                        "Query advisor report ready"
+                    );
+                }
+                received = signal => match received {
+                    Ok(invalidation) => {
+                        for (dna_hash, rule) in rules.signal_invalidations(&invalidation.source_fn) {
+                            advisor.record_invalidation(&dna_hash, &rule.fn_name);
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        warn!(missed = n, "Query advisor lagged behind write signals");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => closed = true,
+                },
+            }
+            if closed {
+                invalidations = None;
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::CacheRule;
+
+    fn rule(fn_name: &str, ttl_secs: u64, invalidated_by: &[&str]) -> CacheRule {
+        CacheRule {
+            fn_name: fn_name.to_string(),
+            cacheable: true,
+            ttl_secs,
+            public: false,
+            reach_field: None,
+            reach_value: None,
+            invalidated_by: invalidated_by.iter().map(|s| s.to_string()).collect(),
+            bridge_invalidated_by: vec![],
+            keyed_by_id: false,
+            key_fields: vec![],
+        }
+    }
+
+    #[test]
+    fn test_args_shape() {
+        let input = serde_json::json!({
+            "id": "manifesto",
+            "limit": 20,
+            "tags": ["a", "b"],
+            "filter": { "reach": null }
+        });
+        assert_eq!(
+            args_shape(&input),
+            "{filter: {reach: null}, id: string, limit: number, tags: [string; 2]}"
+        );
+    }
+
+    #[test]
+    fn test_suggests_longer_ttl_and_flags_unused_invalidation() {
+        let rules = CacheRuleStore::new();
+        rules.set_dna_rules(
+            "dna1",
+            vec![rule("get_path_overview", 60, &["update_path"])],
+        );
+        let advisor = QueryAdvisor::new(Duration::from_millis(100));
+
+        for _ in 0..MIN_CALLS {
+            advisor.record_call(
+                "dna1",
+                "get_path_overview",
+                Duration::from_millis(250),
+                true,
+                &serde_json::json!("path-1"),
+            );
+        }
+        let report = advisor.roll_window(&rules);
+
+        assert!(report.suggestions.contains(&Suggestion::CacheLonger {
+            fn_name: "get_path_overview".to_string(),
+            current_ttl_secs: 60,
+            suggested_ttl_secs: 240,
+            reason: "20 of 20 calls missed the cache and took over 100 ms; nothing invalidated it"
+                .to_string(),
+        }));
+        assert!(report.suggestions.iter().any(|s| matches!(
+            s,
+            Suggestion::UnusedInvalidation { fn_name, .. } if fn_name == "get_path_overview"
+        )));
+        assert_eq!(report.slow_calls.len(), MIN_CALLS as usize);
+        assert_eq!(report.slow_calls[0].args_shape, "string");
+    }
+
+    #[test]
+    fn test_invalidated_rule_is_left_alone() {
+        let rules = CacheRuleStore::new();
+        rules.set_dna_rules(
+            "dna1",
+            vec![rule("get_path_overview", 60, &["update_path"])],
+        );
+        let advisor = QueryAdvisor::new(Duration::from_millis(100));
+
+        for _ in 0..MIN_CALLS {
+            advisor.record_call(
+                "dna1",
+                "get_path_overview",
+                Duration::from_millis(250),
+                true,
+                &Value::Null,
+            );
+        }
+        advisor.record_invalidation("dna1", "get_path_overview");
+
+        assert!(advisor.roll_window(&rules).suggestions.is_empty());
+        assert!(advisor.report(&rules).suggestions.is_empty());
+    }
+
+    #[test]
+    fn test_suggests_rule_for_slow_uncached_read() {
+        let rules = CacheRuleStore::new();
+        let advisor = QueryAdvisor::new(Duration::from_millis(100));
+
+        for _ in 0..MIN_SLOW_CALLS {
+            advisor.record_call(
+                "dna1",
+                "query_content",
+                Duration::from_millis(400),
+                false,
+                &Value::Null,
+            );
+            advisor.record_call(
+                "dna1",
+                "create_content",
+                Duration::from_millis(400),
+                false,
+                &Value::Null,
+            );
+        }
+
+        let suggestions = advisor.roll_window(&rules).suggestions;
+        assert_eq!(suggestions.len(), 1);
+        assert!(matches!(
+            &suggestions[0],
+            Suggestion::AddCacheRule { fn_name, .. } if fn_name == "query_content"
+        ));
+    }
+}
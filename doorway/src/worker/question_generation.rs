@@ -0,0 +1,467 @@
+//! Question generation for the assessment question bank
+//!
+//! Periodically finds commons content with a text body but no questions in
+//! its question bank, asks the configured LLM for quiz questions about the
+//! body and stores them with `content_store::create_assessment_item`. The
+//! zome tags them `ai_generated` and keeps them unpublished; they land on
+//! the steward review queue (`get_pending_assessment_items`) and only appear
+//! in mastery challenges once a steward publishes them.
+//!
+//! The endpoint is any OpenAI-compatible chat completions API
+//! (`POST {url}/v1/chat/completions`), hosted or local. Only `commons`
+//! content is sent to it.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::services::zome_caller::ZomeCaller;
+use crate::worker::embeddings::load_commons_content;
+
+/// Role holding the content_store zome
+const CONTENT_ROLE: &str = "lamad";
+
+/// Zome owning content and the question bank
+const CONTENT_ZOME: &str = "content_store";
+
+/// Body formats questions are generated from
+const TEXT_FORMATS: [&str; 5] = ["markdown", "html", "text", "plaintext", "plain"];
+
+/// Bodies shorter than this don't carry enough to ask about, in characters
+const MIN_BODY_CHARS: usize = 200;
+
+/// Longest body sent to the model, in characters
+const MAX_BODY_CHARS: usize = 12_000;
+
+/// Content nodes sent to the model per pass, to bound spend
+const MAX_CONTENT_PER_PASS: usize = 10;
+
+/// Must match ASSESSMENT_QUESTION_TYPES in holochain/dna/elohim/zomes/content_store_integrity/src/lib.rs
+const QUESTION_TYPES: [&str; 4] = ["multiple_choice", "true_false", "short_answer", "recall"];
+
+/// Timeout for model calls
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+const SYSTEM_PROMPT: &str = "You write quiz questions that check understanding of a learning resource. \
+Reply with JSON only: {\"questions\": [{\"question_type\": \"multiple_choice\" | \"true_false\" | \"short_answer\", \
+\"question_text\": string, \"options\": [string], \"correct_answer\": string, \"explanation\": string}]}. \
+Multiple choice questions have four options and correct_answer is one of them verbatim. \
+True/false questions have no options and correct_answer is \"true\" or \"false\". \
+Ask only about what the resource says.";
+
+/// Question generation settings
+#[derive(Debug, Clone)]
+pub struct QuestionGenerationConfig {
+    /// Chat completions API base URL
+    pub url: String,
+    pub api_key: Option<String>,
+    pub model: String,
+    /// Questions requested per content node
+    pub questions_per_content: usize,
+}
+
+/// Outcome of one pass
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct GenerationPassSummary {
+    pub content: usize,
+    pub questions: usize,
+    pub failed: usize,
+}
+
+/// A question as returned by the model
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct GeneratedQuestion {
+    pub question_type: String,
+    pub question_text: String,
+    #[serde(default)]
+    pub options: Vec<String>,
+    pub correct_answer: String,
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
+impl GeneratedQuestion {
+    /// Tidy a question and check it would pass zome validation
+    fn normalized(mut self) -> Option<Self> {
+        self.question_type = self.question_type.trim().to_lowercase();
+        self.question_text = self.question_text.trim().to_string();
+        self.correct_answer = self.correct_answer.trim().to_string();
+        self.options = self
+            .options
+            .into_iter()
+            .map(|o| o.trim().to_string())
+            .filter(|o| !o.is_empty())
+            .collect();
+
+        if !QUESTION_TYPES.contains(&self.question_type.as_str())
+            || self.question_text.is_empty()
+            || self.correct_answer.is_empty()
+        {
+            return None;
+        }
+        match self.question_type.as_str() {
+            "multiple_choice" => (self.options.len() >= 2
+                && self.options.contains(&self.correct_answer))
+            .then_some(self),
+            "true_false" => {
+                self.correct_answer = self.correct_answer.to_lowercase();
+                self.options.clear();
+                matches!(self.correct_answer.as_str(), "true" | "false").then_some(self)
+            }
+            _ => {
+                self.options.clear();
+                Some(self)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GeneratedQuestions {
+    questions: Vec<GeneratedQuestion>,
+}
+
+/// Parse the model's reply, dropping questions that wouldn't validate.
+/// Tolerates a Markdown code fence around the JSON.
+pub fn parse_questions(reply: &str) -> Result<Vec<GeneratedQuestion>, String> {
+    let trimmed = reply.trim();
+    let json = trimmed
+        .strip_prefix("```json")
+        .or_else(|| trimmed.strip_prefix("```"))
+        .and_then(|rest| rest.trim_end().strip_suffix("```"))
+        .unwrap_or(trimmed);
+    let parsed: GeneratedQuestions =
+        serde_json::from_str(json.trim()).map_err(|e| format!("Invalid model reply: {e}"))?;
+    Ok(parsed
+        .questions
+        .into_iter()
+        .filter_map(GeneratedQuestion::normalized)
+        .collect())
+}
+
+/// Body to generate questions from, if the content has a usable one
+fn question_source(content: &Value) -> Option<String> {
+    let format = content
+        .get("content_format")
+        .and_then(|f| f.as_str())
+        .unwrap_or_default()
+        .to_lowercase();
+    let has_blob = content.get("blob_cid").is_some_and(|b| !b.is_null());
+    if !TEXT_FORMATS.contains(&format.as_str()) || has_blob {
+        return None;
+    }
+    let body = content.get("content").and_then(|b| b.as_str())?.trim();
+    (body.chars().count() >= MIN_BODY_CHARS).then(|| body.chars().take(MAX_BODY_CHARS).collect())
+}
+
+// =============================================================================
+// Zome types
+// =============================================================================
+
+/// Must match GetAssessmentItemsInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct GetAssessmentItemsInput<'a> {
+    content_id: &'a str,
+    include_unpublished: bool,
+}
+
+/// Must match CreateAssessmentItemInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct CreateAssessmentItemInput {
+    content_id: String,
+    question_type: String,
+    question_text: String,
+    options: Vec<String>,
+    correct_answer: String,
+    explanation: Option<String>,
+    tags: Vec<String>,
+    published: bool,
+    generated_by: Option<String>,
+}
+
+// =============================================================================
+// Model API
+// =============================================================================
+
+#[derive(Debug, Serialize)]
+struct ChatMessage<'a> {
+    role: &'static str,
+    content: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: Vec<ChatMessage<'a>>,
+    response_format: Value,
+    temperature: f32,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+    message: ChatReply,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatReply {
+    content: String,
+}
+
+/// Drafts questions for content without any
+pub struct QuestionGenerator {
+    config: QuestionGenerationConfig,
+    client: reqwest::Client,
+    /// Content already sent this process, so failures aren't retried every pass
+    attempted: Mutex<HashSet<String>>,
+}
+
+impl QuestionGenerator {
+    pub fn new(config: QuestionGenerationConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        Self {
+            config,
+            client,
+            attempted: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Ask the model for questions about one content node
+    async fn generate(&self, title: &str, body: &str) -> Result<Vec<GeneratedQuestion>, String> {
+        let prompt = format!(
+            "Write {} questions about this resource.\n\nTitle: {title}\n\n{body}",
+            self.config.questions_per_content
+        );
+        let request = ChatRequest {
+            model: &self.config.model,
+            messages: vec![
+                ChatMessage {
+                    role: "system",
+                    content: SYSTEM_PROMPT,
+                },
+                ChatMessage {
+                    role: "user",
+                    content: &prompt,
+                },
+            ],
+            response_format: serde_json::json!({ "type": "json_object" }),
+            temperature: 0.2,
+        };
+
+        let mut builder = self
+            .client
+            .post(format!(
+                "{}/v1/chat/completions",
+                self.config.url.trim_end_matches('/')
+            ))
+            .json(&request);
+        if let Some(ref key) = self.config.api_key {
+            builder = builder.bearer_auth(key);
+        }
+        let response = builder
+            .send()
+            .await
+            .map_err(|e| format!("Model request failed: {e}"))?;
+        if !response.status().is_success() {
+            return Err(format!("Model returned HTTP {}", response.status()));
+        }
+        let reply: ChatResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("Invalid model response: {e}"))?;
+        let content = reply
+            .choices
+            .into_iter()
+            .next()
+            .map(|c| c.message.content)
+            .ok_or("Model returned no choices")?;
+
+        let mut questions = parse_questions(&content)?;
+        questions.truncate(self.config.questions_per_content);
+        Ok(questions)
+    }
+
+    /// Run a single pass over commons content
+    pub async fn run_once(
+        &self,
+        zome_caller: &ZomeCaller,
+    ) -> Result<GenerationPassSummary, String> {
+        let content = load_commons_content(zome_caller).await?;
+        let mut summary = GenerationPassSummary::default();
+
+        for item in &content {
+            if summary.content >= MAX_CONTENT_PER_PASS {
+                break;
+            }
+            let Some(content_id) = item.get("id").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            let Some(body) = question_source(item) else {
+                continue;
+            };
+            if self
+                .attempted
+                .lock()
+                .map(|a| a.contains(content_id))
+                .unwrap_or(true)
+            {
+                continue;
+            }
+
+            // Any question, drafts included, means the bank is being looked after
+            let existing: Vec<Value> = match zome_caller
+                .call(
+                    CONTENT_ROLE,
+                    CONTENT_ZOME,
+                    "get_assessment_items",
+                    &GetAssessmentItemsInput {
+                        content_id,
+                        include_unpublished: true,
+                    },
+                )
+                .await
+            {
+                Ok(existing) => existing,
+                Err(e) => {
+                    debug!(content_id, error = %e, "Could not read question bank");
+                    continue;
+                }
+            };
+            if !existing.is_empty() {
+                continue;
+            }
+
+            if let Ok(mut attempted) = self.attempted.lock() {
+                attempted.insert(content_id.to_string());
+            }
+            summary.content += 1;
+
+            let title = item
+                .get("title")
+                .and_then(|t| t.as_str())
+                .unwrap_or_default();
+            let questions = match self.generate(title, &body).await {
+                Ok(questions) => questions,
+                Err(e) => {
+                    warn!(content_id, error = %e, "Question generation failed");
+                    summary.failed += 1;
+                    continue;
+                }
+            };
+
+            for question in questions {
+                let input = CreateAssessmentItemInput {
+                    content_id: content_id.to_string(),
+                    question_type: question.question_type,
+                    question_text: question.question_text,
+                    options: question.options,
+                    correct_answer: question.correct_answer,
+                    explanation: question.explanation,
+                    tags: vec!["ai_generated".to_string()],
+                    published: false,
+                    generated_by: Some(self.config.model.clone()),
+                };
+                match zome_caller
+                    .call::<_, Value>(CONTENT_ROLE, CONTENT_ZOME, "create_assessment_item", &input)
+                    .await
+                {
+                    Ok(_) => summary.questions += 1,
+                    Err(e) => {
+                        warn!(content_id, error = %e, "Failed to store generated question");
+                        summary.failed += 1;
+                    }
+                }
+            }
+        }
+
+        Ok(summary)
+    }
+}
+
+/// Spawn the periodic question generation pass.
+///
+/// Each content node is attempted once per process: a node whose generation
+/// fails is not retried until the doorway restarts.
+pub fn spawn_question_generation_task(
+    interval: Duration,
+    zome_caller: Arc<ZomeCaller>,
+    generator: QuestionGenerator,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!(
+            interval_secs = interval.as_secs(),
+            model = %generator.config.model,
+            "Question generation task started"
+        );
+
+        loop {
+            tokio::time::sleep(interval).await;
+
+            match generator.run_once(&zome_caller).await {
+                Ok(summary) if summary.content > 0 => info!(
+                    content = summary.content,
+                    questions = summary.questions,
+                    failed = summary.failed,
+                    "Question generation pass complete"
+                ),
+                Ok(_) => debug!("Question generation pass: nothing to do"),
+                Err(e) => warn!(error = %e, "Question generation pass failed"),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_questions_filters_invalid() {
+        let reply = r#"```json
+{"questions": [
+  {"question_type": "multiple_choice", "question_text": "Who stewards a commons?", "options": ["Its community", "A market", "Nobody", "The state"], "correct_answer": "Its community"},
+  {"question_type": "multiple_choice", "question_text": "Broken", "options": ["a", "b"], "correct_answer": "c"},
+  {"question_type": "true_false", "question_text": "Commons can be digital.", "correct_answer": "True"},
+  {"question_type": "essay", "question_text": "Discuss.", "correct_answer": "..."}
+]}
+```"#;
+        let questions = parse_questions(reply).unwrap();
+        assert_eq!(questions.len(), 2);
+        assert_eq!(questions[0].correct_answer, "Its community");
+        assert_eq!(questions[1].correct_answer, "true");
+        assert!(questions[1].options.is_empty());
+    }
+
+    #[test]
+    fn test_parse_questions_rejects_non_json() {
+        assert!(parse_questions("Here are some questions!").is_err());
+    }
+
+    #[test]
+    fn test_question_source() {
+        let long = "word ".repeat(60);
+        assert!(
+            question_source(&json!({ "content_format": "markdown", "content": long })).is_some()
+        );
+        assert!(
+            question_source(&json!({ "content_format": "markdown", "content": "short" })).is_none()
+        );
+        assert!(question_source(&json!({ "content_format": "video", "content": long })).is_none());
+        assert!(question_source(
+            &json!({ "content_format": "markdown", "content": long, "blob_cid": "bafy" })
+        )
+        .is_none());
+    }
+}
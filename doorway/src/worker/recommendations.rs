@@ -0,0 +1,649 @@
+//! Recommendation engine ("what to learn next")
+//!
+//! Ranks next content for a learner from four signal sources:
+//!
+//! - **Next steps** on paths they have started
+//! - **Refresh queue** from their practice pool (mastered, but fading)
+//! - **Discoveries** the pool found through the relationship graph
+//! - **Related** content one hop out from what they completed, read from the
+//!   projection's content graph
+//!
+//! The content_store zome gathers the first three (`get_learner_signals`,
+//! including imagodei mastery); doorway adds the graph hop and scores the
+//! merged candidates with a [`ScoringStrategy`]. Communities that weigh
+//! things differently plug in their own strategy.
+//!
+//! Results are cached per agent. A background task re-ranks agents that
+//! asked recently before their entry goes stale, so `GET /me/recommendations`
+//! is normally a cache hit.
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::projection::{GraphDirection, GraphOptions, ProjectionStore};
+use crate::services::zome_caller::ZomeCaller;
+
+/// Role holding the content_store zome
+const CONTENT_ROLE: &str = "lamad";
+
+/// Zome exposing learner signals
+const CONTENT_ZOME: &str = "content_store";
+
+/// Recommendations kept per agent
+const MAX_RECOMMENDATIONS: usize = 50;
+
+/// Completed items expanded through the relationship graph
+const MAX_GRAPH_SEEDS: usize = 20;
+
+/// Agents idle longer than this are dropped instead of re-ranked
+const ACTIVE_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Highest Bloom's mastery index (create)
+const MAX_MASTERY_INDEX: u32 = 7;
+
+/// Mastery index from which content counts as learned (apply)
+const LEARNED_MASTERY_INDEX: u32 = 4;
+
+// =============================================================================
+// Zome Types
+// =============================================================================
+
+/// Input for content_store::get_learner_signals
+/// Must match LearnerSignalsInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Clone, Serialize)]
+pub struct LearnerSignalsInput {
+    pub agent_id: String,
+    pub human_id: String,
+}
+
+/// Must match NextStepSignal in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Clone, Deserialize)]
+pub struct NextStepSignal {
+    pub path_id: String,
+    pub step_index: u32,
+    pub content_id: String,
+    pub remaining_steps: u32,
+}
+
+/// Must match DiscoveryCandidate in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Clone, Deserialize)]
+pub struct DiscoveryCandidate {
+    pub content_id: String,
+    pub source_content_id: String,
+    pub relationship_type: String,
+    pub discovery_reason: String,
+}
+
+/// Must match MasterySignal in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Clone, Deserialize)]
+pub struct MasterySignal {
+    pub content_id: String,
+    pub mastery_level_index: u32,
+    pub freshness_score: f64,
+    pub needs_refresh: bool,
+}
+
+/// Must match LearnerSignals in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LearnerSignals {
+    pub next_steps: Vec<NextStepSignal>,
+    pub completed_content_ids: Vec<String>,
+    pub refresh_queue_ids: Vec<String>,
+    pub discovery_candidates: Vec<DiscoveryCandidate>,
+    pub mastery: Vec<MasterySignal>,
+}
+
+// =============================================================================
+// Candidates and Scoring
+// =============================================================================
+
+/// Why a candidate was suggested
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CandidateSource {
+    NextStep,
+    Refresh,
+    Discovery,
+    Related,
+}
+
+/// A content item under consideration, with every signal about it
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub content_id: String,
+    pub sources: Vec<CandidateSource>,
+    /// Path this is the next step of
+    pub path_id: Option<String>,
+    /// Steps left on that path after this one
+    pub remaining_steps: Option<u32>,
+    pub mastery_level_index: Option<u32>,
+    pub freshness_score: Option<f64>,
+    pub needs_refresh: bool,
+    /// Strongest relationship confidence linking it to completed content
+    pub graph_confidence: Option<f64>,
+}
+
+impl Candidate {
+    fn new(content_id: &str) -> Self {
+        Self {
+            content_id: content_id.to_string(),
+            sources: Vec::new(),
+            path_id: None,
+            remaining_steps: None,
+            mastery_level_index: None,
+            freshness_score: None,
+            needs_refresh: false,
+            graph_confidence: None,
+        }
+    }
+
+    pub fn has_source(&self, source: CandidateSource) -> bool {
+        self.sources.contains(&source)
+    }
+}
+
+/// Ranks candidates; higher scores come first
+///
+/// Implementations must be cheap: they run for every candidate of every
+/// learner on each refresh.
+pub trait ScoringStrategy: Send + Sync {
+    /// Name reported alongside results
+    fn name(&self) -> &str;
+
+    /// Score a candidate; non-positive scores are dropped
+    fn score(&self, candidate: &Candidate) -> f64;
+}
+
+/// Default ranking: continue paths, then repair fading mastery, then explore
+#[derive(Debug, Clone)]
+pub struct DefaultScoring {
+    pub next_step_weight: f64,
+    pub refresh_weight: f64,
+    pub discovery_weight: f64,
+    pub related_weight: f64,
+    /// Bonus scaled by how far mastery is from `create`
+    pub mastery_gap_weight: f64,
+    /// Bonus for paths close to completion
+    pub momentum_weight: f64,
+}
+
+impl Default for DefaultScoring {
+    fn default() -> Self {
+        Self {
+            next_step_weight: 1.0,
+            refresh_weight: 0.8,
+            discovery_weight: 0.4,
+            related_weight: 0.3,
+            mastery_gap_weight: 0.5,
+            momentum_weight: 0.3,
+        }
+    }
+}
+
+impl ScoringStrategy for DefaultScoring {
+    fn name(&self) -> &str {
+        "default"
+    }
+
+    fn score(&self, candidate: &Candidate) -> f64 {
+        let mut score = 0.0;
+
+        if candidate.has_source(CandidateSource::NextStep) {
+            score += self.next_step_weight;
+            if let Some(remaining) = candidate.remaining_steps {
+                score += self.momentum_weight / (1.0 + remaining as f64);
+            }
+        }
+        if candidate.has_source(CandidateSource::Refresh) || candidate.needs_refresh {
+            let fade = 1.0 - candidate.freshness_score.unwrap_or(0.0).clamp(0.0, 1.0);
+            score += self.refresh_weight * (0.5 + fade / 2.0);
+        }
+        if candidate.has_source(CandidateSource::Discovery) {
+            score += self.discovery_weight;
+        }
+        if candidate.has_source(CandidateSource::Related) {
+            score += self.related_weight * candidate.graph_confidence.unwrap_or(0.5);
+        }
+
+        let level = candidate
+            .mastery_level_index
+            .unwrap_or(0)
+            .min(MAX_MASTERY_INDEX);
+        let gap = (MAX_MASTERY_INDEX - level) as f64 / MAX_MASTERY_INDEX as f64;
+        score + self.mastery_gap_weight * gap
+    }
+}
+
+/// A ranked suggestion
+#[derive(Debug, Clone, Serialize)]
+pub struct Recommendation {
+    pub content_id: String,
+    pub score: f64,
+    pub reasons: Vec<CandidateSource>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path_id: Option<String>,
+}
+
+/// Merge learner signals and graph neighbours into candidates
+///
+/// `related` pairs a content id with the relationship confidence that links
+/// it to something the learner completed. Completed or learned content is
+/// left out unless it is due for a refresh.
+pub fn build_candidates(signals: &LearnerSignals, related: &[(String, f64)]) -> Vec<Candidate> {
+    let mut candidates: Vec<Candidate> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+
+    let mut entry = |content_id: &str| -> usize {
+        *index.entry(content_id.to_string()).or_insert_with(|| {
+            candidates.push(Candidate::new(content_id));
+            candidates.len() - 1
+        })
+    };
+
+    let mut touched: Vec<(usize, CandidateSource)> = Vec::new();
+    for step in &signals.next_steps {
+        touched.push((entry(&step.content_id), CandidateSource::NextStep));
+    }
+    for content_id in &signals.refresh_queue_ids {
+        touched.push((entry(content_id), CandidateSource::Refresh));
+    }
+    for discovery in &signals.discovery_candidates {
+        touched.push((entry(&discovery.content_id), CandidateSource::Discovery));
+    }
+    for (content_id, _) in related {
+        touched.push((entry(content_id), CandidateSource::Related));
+    }
+
+    for (i, source) in touched {
+        if !candidates[i].sources.contains(&source) {
+            candidates[i].sources.push(source);
+        }
+    }
+
+    // The earliest next step wins when several paths lead to the same content
+    for step in signals.next_steps.iter().rev() {
+        if let Some(&i) = index.get(&step.content_id) {
+            candidates[i].path_id = Some(step.path_id.clone());
+            candidates[i].remaining_steps = Some(step.remaining_steps);
+        }
+    }
+    for (content_id, confidence) in related {
+        if let Some(&i) = index.get(content_id) {
+            let best = candidates[i]
+                .graph_confidence
+                .unwrap_or(0.0)
+                .max(*confidence);
+            candidates[i].graph_confidence = Some(best);
+        }
+    }
+    for mastery in &signals.mastery {
+        if let Some(&i) = index.get(&mastery.content_id) {
+            candidates[i].mastery_level_index = Some(mastery.mastery_level_index);
+            candidates[i].freshness_score = Some(mastery.freshness_score);
+            candidates[i].needs_refresh = mastery.needs_refresh;
+        }
+    }
+
+    candidates.retain(|c| {
+        let due = c.needs_refresh || c.has_source(CandidateSource::Refresh);
+        let learned = signals.completed_content_ids.contains(&c.content_id)
+            || c.mastery_level_index.unwrap_or(0) >= LEARNED_MASTERY_INDEX;
+        due || !learned
+    });
+    candidates
+}
+
+/// Score and sort candidates, best first
+pub fn rank(candidates: &[Candidate], strategy: &dyn ScoringStrategy) -> Vec<Recommendation> {
+    let mut ranked: Vec<Recommendation> = candidates
+        .iter()
+        .map(|c| Recommendation {
+            content_id: c.content_id.clone(),
+            score: strategy.score(c),
+            reasons: c.sources.clone(),
+            path_id: c.path_id.clone(),
+        })
+        .filter(|r| r.score > 0.0)
+        .collect();
+
+    ranked.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.content_id.cmp(&b.content_id))
+    });
+    ranked.truncate(MAX_RECOMMENDATIONS);
+    ranked
+}
+
+// =============================================================================
+// Engine
+// =============================================================================
+
+/// Cached ranking for one agent
+struct CachedRecommendations {
+    human_id: String,
+    recommendations: Vec<Recommendation>,
+    computed_at: Instant,
+    last_requested: Instant,
+}
+
+/// Outcome of one background refresh
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RefreshSummary {
+    pub refreshed: usize,
+    pub failed: usize,
+    pub evicted: usize,
+}
+
+/// Computes and caches per-agent recommendations
+pub struct RecommendationEngine {
+    zome_caller: Arc<ZomeCaller>,
+    projection: Option<Arc<ProjectionStore>>,
+    strategy: Arc<dyn ScoringStrategy>,
+    ttl: Duration,
+    cache: DashMap<String, CachedRecommendations>,
+}
+
+impl RecommendationEngine {
+    /// Create an engine with [`DefaultScoring`]
+    pub fn new(
+        zome_caller: Arc<ZomeCaller>,
+        projection: Option<Arc<ProjectionStore>>,
+        ttl: Duration,
+    ) -> Self {
+        Self {
+            zome_caller,
+            projection,
+            strategy: Arc::new(DefaultScoring::default()),
+            ttl,
+            cache: DashMap::new(),
+        }
+    }
+
+    /// Replace the scoring strategy
+    pub fn with_strategy(mut self, strategy: Arc<dyn ScoringStrategy>) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Name of the active scoring strategy
+    pub fn strategy_name(&self) -> &str {
+        self.strategy.name()
+    }
+
+    /// Recommendations for an agent, from cache when fresh
+    pub async fn recommendations_for(
+        &self,
+        agent_id: &str,
+        human_id: &str,
+        limit: usize,
+    ) -> Result<Vec<Recommendation>, String> {
+        if let Some(mut cached) = self.cache.get_mut(agent_id) {
+            cached.last_requested = Instant::now();
+            if cached.computed_at.elapsed() < self.ttl {
+                return Ok(cached.recommendations.iter().take(limit).cloned().collect());
+            }
+        }
+
+        let recommendations = self.compute(agent_id, human_id).await?;
+        let now = Instant::now();
+        self.cache.insert(
+            agent_id.to_string(),
+            CachedRecommendations {
+                human_id: human_id.to_string(),
+                recommendations: recommendations.clone(),
+                computed_at: now,
+                last_requested: now,
+            },
+        );
+        Ok(recommendations.into_iter().take(limit).collect())
+    }
+
+    /// Gather signals and rank, bypassing the cache
+    pub async fn compute(
+        &self,
+        agent_id: &str,
+        human_id: &str,
+    ) -> Result<Vec<Recommendation>, String> {
+        let signals: LearnerSignals = self
+            .zome_caller
+            .call(
+                CONTENT_ROLE,
+                CONTENT_ZOME,
+                "get_learner_signals",
+                &LearnerSignalsInput {
+                    agent_id: agent_id.to_string(),
+                    human_id: human_id.to_string(),
+                },
+            )
+            .await?;
+
+        let related = self.related_content(&signals.completed_content_ids).await;
+        let candidates = build_candidates(&signals, &related);
+        Ok(rank(&candidates, self.strategy.as_ref()))
+    }
+
+    /// One-hop outgoing neighbours of completed content
+    async fn related_content(&self, completed: &[String]) -> Vec<(String, f64)> {
+        let Some(ref projection) = self.projection else {
+            return Vec::new();
+        };
+        let graph = match projection.content_graph_index().await {
+            Ok(graph) => graph,
+            Err(e) => {
+                warn!(error = %e, "Content graph unavailable for recommendations");
+                return Vec::new();
+            }
+        };
+
+        let options = GraphOptions {
+            depth: 1,
+            types: None,
+            direction: GraphDirection::Outgoing,
+        };
+        completed
+            .iter()
+            .take(MAX_GRAPH_SEEDS)
+            .flat_map(|seed| graph.traverse(seed, &options).edges)
+            .map(|edge| (edge.target, edge.confidence))
+            .collect()
+    }
+
+    /// Re-rank recently active agents whose entry went stale; drop idle ones
+    pub async fn refresh_active(&self) -> RefreshSummary {
+        let mut summary = RefreshSummary::default();
+
+        let idle: Vec<String> = self
+            .cache
+            .iter()
+            .filter(|e| e.last_requested.elapsed() > ACTIVE_WINDOW)
+            .map(|e| e.key().clone())
+            .collect();
+        for agent_id in idle {
+            self.cache.remove(&agent_id);
+            summary.evicted += 1;
+        }
+
+        let stale: Vec<(String, String)> = self
+            .cache
+            .iter()
+            .filter(|e| e.computed_at.elapsed() >= self.ttl)
+            .map(|e| (e.key().clone(), e.human_id.clone()))
+            .collect();
+        for (agent_id, human_id) in stale {
+            match self.compute(&agent_id, &human_id).await {
+                Ok(recommendations) => {
+                    if let Some(mut cached) = self.cache.get_mut(&agent_id) {
+                        cached.recommendations = recommendations;
+                        cached.computed_at = Instant::now();
+                    }
+                    summary.refreshed += 1;
+                }
+                Err(e) => {
+                    warn!(agent_id = %agent_id, error = %e, "Failed to refresh recommendations");
+                    summary.failed += 1;
+                }
+            }
+        }
+
+        summary
+    }
+}
+
+/// Spawn the periodic recommendation refresh.
+///
+/// An agent whose refresh fails keeps its previous recommendations and is
+/// tried again on the next refresh.
+pub fn spawn_recommendation_task(
+    interval: Duration,
+    engine: Arc<RecommendationEngine>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!(
+            interval_secs = interval.as_secs(),
+            strategy = engine.strategy_name(),
+            "Recommendation refresh task started"
+        );
+
+        loop {
+            tokio::time::sleep(interval).await;
+
+            let summary = engine.refresh_active().await;
+            if summary == RefreshSummary::default() {
+                debug!("Recommendation refresh: nothing stale");
+            } else {
+                info!(
+                    refreshed = summary.refreshed,
+                    failed = summary.failed,
+                    evicted = summary.evicted,
+                    "Recommendation refresh complete"
+                );
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mastery(content_id: &str, level: u32, freshness: f64, needs_refresh: bool) -> MasterySignal {
+        MasterySignal {
+            content_id: content_id.to_string(),
+            mastery_level_index: level,
+            freshness_score: freshness,
+            needs_refresh,
+        }
+    }
+
+    fn signals() -> LearnerSignals {
+        LearnerSignals {
+            next_steps: vec![
+                NextStepSignal {
+                    path_id: "path-1".into(),
+                    step_index: 3,
+                    content_id: "step-content".into(),
+                    remaining_steps: 1,
+                },
+                NextStepSignal {
+                    path_id: "path-2".into(),
+                    step_index: 0,
+                    content_id: "shared".into(),
+                    remaining_steps: 9,
+                },
+            ],
+            completed_content_ids: vec!["done".into()],
+            refresh_queue_ids: vec!["fading".into()],
+            discovery_candidates: vec![DiscoveryCandidate {
+                content_id: "shared".into(),
+                source_content_id: "done".into(),
+                relationship_type: "RELATES_TO".into(),
+                discovery_reason: "graph".into(),
+            }],
+            mastery: vec![
+                mastery("fading", 5, 0.2, true),
+                mastery("step-content", 1, 1.0, false),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_build_candidates_merges_sources() {
+        let related = vec![("neighbour".to_string(), 0.9), ("done".to_string(), 1.0)];
+        let candidates = build_candidates(&signals(), &related);
+
+        let ids: Vec<&str> = candidates.iter().map(|c| c.content_id.as_str()).collect();
+        assert_eq!(ids, vec!["step-content", "shared", "fading", "neighbour"]);
+
+        let shared = &candidates[1];
+        assert_eq!(
+            shared.sources,
+            vec![CandidateSource::NextStep, CandidateSource::Discovery]
+        );
+        assert_eq!(shared.path_id.as_deref(), Some("path-2"));
+
+        // Learned content stays only while it needs a refresh
+        assert!(candidates[2].needs_refresh);
+        assert_eq!(candidates[3].graph_confidence, Some(0.9));
+    }
+
+    #[test]
+    fn test_rank_prefers_path_steps_then_refresh() {
+        let related = vec![("neighbour".to_string(), 0.9)];
+        let candidates = build_candidates(&signals(), &related);
+        let ranked = rank(&candidates, &DefaultScoring::default());
+
+        assert_eq!(ranked[0].content_id, "shared");
+        assert_eq!(ranked.last().unwrap().content_id, "neighbour");
+        assert!(ranked.windows(2).all(|w| w[0].score >= w[1].score));
+    }
+
+    struct DiscoveryOnly;
+
+    impl ScoringStrategy for DiscoveryOnly {
+        fn name(&self) -> &str {
+            "discovery-only"
+        }
+
+        fn score(&self, candidate: &Candidate) -> f64 {
+            if candidate.has_source(CandidateSource::Discovery) {
+                1.0
+            } else {
+                0.0
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom_strategy_filters_non_positive() {
+        let candidates = build_candidates(&signals(), &[]);
+        let ranked = rank(&candidates, &DiscoveryOnly);
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].content_id, "shared");
+    }
+
+    #[test]
+    fn test_signals_decode_from_zome_msgpack() {
+        let value = serde_json::json!({
+            "next_steps": [],
+            "completed_content_ids": ["a"],
+            "refresh_queue_ids": [],
+            "discovery_candidates": [],
+            "mastery": [{
+                "content_id": "a",
+                "mastery_level_index": 3,
+                "freshness_score": 0.5,
+                "needs_refresh": false
+            }]
+        });
+        let bytes = rmp_serde::to_vec_named(&value).unwrap();
+        let decoded: LearnerSignals = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(decoded.completed_content_ids, vec!["a"]);
+        assert_eq!(decoded.mastery[0].mastery_level_index, 3);
+    }
+}
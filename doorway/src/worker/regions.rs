@@ -0,0 +1,189 @@
+//! Region presence and global cache invalidation
+//!
+//! Doorways in several regions (see [`regions`](crate::services::regions))
+//! keep each other informed over NATS:
+//!
+//! - Every doorway announces its [`DoorwayPresence`] on
+//!   `DOORWAY.PRESENCE.{region}` and records the announcements of the
+//!   others, feeding `GET /region-hint`
+//! - Writes seen by this doorway's signal subscriber are relayed on
+//!   `DOORWAY.CACHE.INVALIDATE`, and writes relayed by the others are
+//!   applied to this doorway's response cache, so a reader in one region
+//!   doesn't keep serving what was changed in another
+//!
+//! Relayed invalidations carry their origin node so a doorway never applies
+//! its own twice.
+
+use bytes::Bytes;
+use futures_util::StreamExt;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::cache::{apply_invalidation, CacheInvalidation, CacheRuleStore, ContentCache};
+use crate::nats::messages::{
+    CacheInvalidationMessage, DoorwayPresence, CACHE_INVALIDATION_SUBJECT,
+    DOORWAY_PRESENCE_SUBJECT_PREFIX,
+};
+use crate::nats::NatsClient;
+use crate::services::regions::RegionDirectory;
+
+/// Spawn the presence task: announces this doorway and records the others
+///
+/// `health` reports whether this doorway can take clients, and how many
+/// conductor calls it has in flight.
+pub fn spawn_region_presence_task(
+    directory: Arc<RegionDirectory>,
+    nats: NatsClient,
+    health: impl Fn() -> (bool, usize) + Send + 'static,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let subject = format!("{DOORWAY_PRESENCE_SUBJECT_PREFIX}.>");
+        let mut subscriber = match nats.subscribe(&subject).await {
+            Ok(subscriber) => subscriber,
+            Err(e) => {
+                warn!(error = %e, "Region presence could not subscribe, not tracking other doorways");
+                return;
+            }
+        };
+        info!(
+            region = %directory.region(),
+            interval_secs = directory.interval().as_secs(),
+            "Region presence started"
+        );
+
+        let own_subject = DoorwayPresence::subject(directory.region());
+        let mut ticker = tokio::time::interval(directory.interval());
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    let (healthy, in_flight) = health();
+                    let presence = directory.presence(healthy, in_flight);
+                    match serde_json::to_vec(&presence) {
+                        Ok(payload) => {
+                            if let Err(e) = nats.publish(&own_subject, Bytes::from(payload)).await {
+                                warn!(error = %e, "Failed to announce doorway presence");
+                            }
+                        }
+                        Err(e) => warn!(error = %e, "Failed to encode doorway presence"),
+                    }
+                }
+                message = subscriber.next() => {
+                    let Some(message) = message else {
+                        warn!("Region presence subscription closed");
+                        break;
+                    };
+                    match serde_json::from_slice::<DoorwayPresence>(&message.payload) {
+                        Ok(presence) => directory.observe(presence, Instant::now()),
+                        Err(e) => {
+                            warn!(subject = %message.subject, error = %e, "Invalid doorway presence")
+                        }
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Next local invalidation, or never without a signal subscriber
+async fn next_local(
+    local: &mut Option<broadcast::Receiver<CacheInvalidation>>,
+) -> Result<CacheInvalidation, broadcast::error::RecvError> {
+    match local {
+        Some(rx) => rx.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Publish a local write for the other doorways
+async fn relay(nats: &NatsClient, node_id: &str, invalidation: CacheInvalidation) {
+    let message = CacheInvalidationMessage {
+        origin: node_id.to_string(),
+        source_fn: invalidation.source_fn,
+        doc_type: invalidation.doc_type,
+        doc_id: invalidation.doc_id,
+    };
+    match serde_json::to_vec(&message) {
+        Ok(payload) => {
+            if let Err(e) = nats
+                .publish(CACHE_INVALIDATION_SUBJECT, Bytes::from(payload))
+                .await
+            {
+                warn!(error = %e, "Failed to relay cache invalidation");
+            }
+        }
+        Err(e) => warn!(error = %e, "Failed to encode cache invalidation"),
+    }
+}
+
+/// Spawn the relay sharing cache invalidations with the other doorways
+///
+/// `local` carries the writes this doorway's signal subscriber sees; readers
+/// without one pass `None` and only apply the writes relayed to them.
+pub fn spawn_invalidation_relay(
+    nats: NatsClient,
+    node_id: String,
+    mut local: Option<broadcast::Receiver<CacheInvalidation>>,
+    cache: Arc<ContentCache>,
+    rules: Arc<CacheRuleStore>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut subscriber = match nats.subscribe(CACHE_INVALIDATION_SUBJECT).await {
+            Ok(subscriber) => subscriber,
+            Err(e) => {
+                warn!(error = %e, "Invalidation relay could not subscribe, caches stay regional");
+                return;
+            }
+        };
+        info!(
+            relaying = local.is_some(),
+            "Global cache invalidation started"
+        );
+
+        loop {
+            tokio::select! {
+                invalidation = next_local(&mut local) => match invalidation {
+                    Ok(invalidation) => relay(&nats, &node_id, invalidation).await,
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        warn!(missed = n, "Invalidation relay lagged, other doorways may serve stale reads");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => {
+                        info!("Local invalidations closed, applying relayed ones only");
+                        local = None;
+                    }
+                },
+                message = subscriber.next() => {
+                    let Some(message) = message else {
+                        warn!("Cache invalidation subscription closed");
+                        break;
+                    };
+                    let message: CacheInvalidationMessage = match serde_json::from_slice(&message.payload) {
+                        Ok(message) => message,
+                        Err(e) => {
+                            warn!(error = %e, "Invalid relayed cache invalidation");
+                            continue;
+                        }
+                    };
+                    if message.origin == node_id {
+                        continue;
+                    }
+                    let invalidation = CacheInvalidation {
+                        source_fn: message.source_fn,
+                        doc_type: message.doc_type,
+                        doc_id: message.doc_id,
+                    };
+                    let removed = apply_invalidation(&cache, &rules, &invalidation);
+                    debug!(
+                        origin = %message.origin,
+                        source_fn = %invalidation.source_fn,
+                        doc_id = %invalidation.doc_id,
+                        removed,
+                        "Applied relayed cache invalidation"
+                    );
+                }
+            }
+        }
+    })
+}
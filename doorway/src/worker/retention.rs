@@ -0,0 +1,541 @@
+//! Retention policies
+//!
+//! Operators list retention policies in a JSON file (`RETENTION_POLICIES`);
+//! the worker runs each on a timer (`RETENTION_INTERVAL_SECS`) against the
+//! data the doorway holds itself. Conductor and DHT data is never touched.
+//!
+//! ```json
+//! [
+//!   {"id": "archive-stale", "action": "archive_stale_content", "after_days": 1825},
+//!   {"id": "erased-users", "action": "purge_erased_users", "after_days": 30},
+//!   {"id": "old-imports", "action": "expire_import_batches", "after_days": 90, "dry_run": true}
+//! ]
+//! ```
+//!
+//! - **archive_stale_content**: projections not written for `after_days`
+//!   move to the `{projections}_archive` collection and leave the live one
+//! - **purge_erased_users**: projections authored by users deleted more than
+//!   `after_days` ago are tombstoned: data and search tokens are cleared
+//! - **expire_import_batches**: import batch projections older than
+//!   `after_days` are deleted
+//!
+//! `doc_types` narrows or changes the projected types a policy covers. A
+//! policy marked `dry_run` only counts what it would do. Every run, dry or
+//! not, writes a [`RetentionAuditDoc`]. Admins preview and run policies at
+//! `/admin/retention/*`.
+
+use bson::{doc, DateTime as BsonDateTime, Document};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use futures_util::StreamExt;
+use mongodb::options::FindOptions;
+use mongodb::Collection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::db::schemas::{RetentionAuditDoc, RETENTION_AUDIT_COLLECTION, USER_COLLECTION};
+use crate::db::{MongoClient, MongoCollection};
+use crate::projection::ProjectionStore;
+
+/// Most documents one run acts on; the rest wait for the next run
+const MAX_PER_RUN: i64 = 5_000;
+
+/// Matched ids kept in a report and its audit record
+const SAMPLE_SIZE: usize = 20;
+
+/// What a policy does with the documents it matches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionAction {
+    ArchiveStaleContent,
+    PurgeErasedUsers,
+    ExpireImportBatches,
+}
+
+impl RetentionAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ArchiveStaleContent => "archive_stale_content",
+            Self::PurgeErasedUsers => "purge_erased_users",
+            Self::ExpireImportBatches => "expire_import_batches",
+        }
+    }
+
+    /// Projected types covered when a policy names none (empty: all types)
+    fn default_doc_types(self) -> Vec<String> {
+        match self {
+            Self::ArchiveStaleContent => vec!["Content".to_string()],
+            Self::PurgeErasedUsers => Vec::new(),
+            Self::ExpireImportBatches => vec!["ImportBatch".to_string()],
+        }
+    }
+}
+
+/// An operator-defined retention policy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    pub id: String,
+    pub action: RetentionAction,
+    /// Age in days before a document is in scope
+    pub after_days: u32,
+    /// Projected document types covered (defaults depend on the action)
+    #[serde(default)]
+    pub doc_types: Vec<String>,
+    /// Only preview and audit, never change anything
+    #[serde(default)]
+    pub dry_run: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl RetentionPolicy {
+    pub fn doc_types(&self) -> Vec<String> {
+        if self.doc_types.is_empty() {
+            self.action.default_doc_types()
+        } else {
+            self.doc_types.clone()
+        }
+    }
+
+    /// Documents older than this are in scope
+    pub fn cutoff(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        now - ChronoDuration::days(i64::from(self.after_days))
+    }
+
+    /// Projection filter for this policy; `authors` are the erased users'
+    /// agent keys (purge_erased_users only)
+    pub fn projection_filter(&self, now: DateTime<Utc>, authors: &[String]) -> Document {
+        let cutoff = BsonDateTime::from_chrono(self.cutoff(now));
+        let mut filter = match self.action {
+            RetentionAction::ArchiveStaleContent => doc! {
+                "projected_at": { "$lt": cutoff },
+                "metadata.is_deleted": { "$ne": true },
+            },
+            RetentionAction::PurgeErasedUsers => doc! {
+                "author": { "$in": authors },
+                "data": { "$ne": null },
+            },
+            RetentionAction::ExpireImportBatches => doc! {
+                "created_at": { "$lt": cutoff },
+            },
+        };
+        let doc_types = self.doc_types();
+        if !doc_types.is_empty() {
+            filter.insert("doc_type", doc! { "$in": doc_types });
+        }
+        filter
+    }
+}
+
+/// Filter for users deleted before the policy cutoff
+///
+/// Older deletions only set `metadata.updated_at`, which isn't touched again
+/// once a user is deleted.
+pub fn erased_users_filter(cutoff: DateTime<Utc>) -> Document {
+    let cutoff = BsonDateTime::from_chrono(cutoff);
+    doc! {
+        "metadata.is_deleted": true,
+        "$or": [
+            { "metadata.deleted_at": { "$lt": cutoff } },
+            {
+                "metadata.deleted_at": { "$exists": false },
+                "metadata.updated_at": { "$lt": cutoff },
+            },
+        ],
+    }
+}
+
+/// Parse and check a policy file
+pub fn parse_policies(json: &str) -> Result<Vec<RetentionPolicy>, String> {
+    let policies: Vec<RetentionPolicy> =
+        serde_json::from_str(json).map_err(|e| format!("Invalid retention policies: {e}"))?;
+    let mut ids = HashSet::new();
+    for policy in &policies {
+        if policy.id.trim().is_empty() {
+            return Err("Retention policy without an id".to_string());
+        }
+        if !ids.insert(policy.id.as_str()) {
+            return Err(format!("Duplicate retention policy id '{}'", policy.id));
+        }
+        if policy.after_days == 0 {
+            return Err(format!(
+                "Retention policy '{}' needs after_days of at least 1",
+                policy.id
+            ));
+        }
+    }
+    Ok(policies)
+}
+
+/// Outcome of one policy run
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RetentionRunReport {
+    pub policy_id: String,
+    pub action: String,
+    pub dry_run: bool,
+    pub cutoff: Option<DateTime<Utc>>,
+    pub matched: u64,
+    pub affected: u64,
+    pub sample_ids: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// Runs retention policies against the projection store
+pub struct RetentionEngine {
+    mongo: MongoClient,
+    projection: Arc<ProjectionStore>,
+    policies: Vec<RetentionPolicy>,
+    audit: MongoCollection<RetentionAuditDoc>,
+}
+
+impl RetentionEngine {
+    pub async fn new(
+        mongo: MongoClient,
+        projection: Arc<ProjectionStore>,
+        policies: Vec<RetentionPolicy>,
+    ) -> Result<Self, String> {
+        let audit = mongo
+            .collection::<RetentionAuditDoc>(RETENTION_AUDIT_COLLECTION)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(Self {
+            mongo,
+            projection,
+            policies,
+            audit,
+        })
+    }
+
+    pub fn policies(&self) -> &[RetentionPolicy] {
+        &self.policies
+    }
+
+    pub fn policy(&self, id: &str) -> Option<&RetentionPolicy> {
+        self.policies.iter().find(|policy| policy.id == id)
+    }
+
+    fn collection(&self, name: &str) -> Collection<Document> {
+        self.mongo
+            .inner()
+            .database(self.mongo.db_name())
+            .collection::<Document>(name)
+    }
+
+    /// Agent keys of users erased before `cutoff`
+    async fn erased_authors(&self, cutoff: DateTime<Utc>) -> Result<Vec<String>, String> {
+        let options = FindOptions::builder()
+            .projection(doc! { "agent_pub_key": 1 })
+            .build();
+        let cursor = self
+            .collection(USER_COLLECTION)
+            .find(erased_users_filter(cutoff))
+            .with_options(options)
+            .await
+            .map_err(|e| format!("Failed to read erased users: {e}"))?;
+        let keys: Vec<String> = cursor
+            .filter_map(|user| async move {
+                user.ok()?
+                    .get_str("agent_pub_key")
+                    .ok()
+                    .filter(|key| !key.is_empty())
+                    .map(String::from)
+            })
+            .collect()
+            .await;
+        Ok(keys)
+    }
+
+    /// Ids of matching projections, at most `MAX_PER_RUN`
+    async fn matching_ids(&self, filter: &Document) -> Result<Vec<String>, String> {
+        let options = FindOptions::builder()
+            .projection(doc! { "_id": 1 })
+            .limit(MAX_PER_RUN)
+            .build();
+        let cursor = self
+            .collection(self.projection.collection_name())
+            .find(filter.clone())
+            .with_options(options)
+            .await
+            .map_err(|e| format!("Failed to read projections: {e}"))?;
+        let ids: Vec<String> = cursor
+            .filter_map(|doc| async move { doc.ok()?.get_str("_id").ok().map(String::from) })
+            .collect()
+            .await;
+        Ok(ids)
+    }
+
+    /// Apply a policy's action to the given projections
+    async fn apply(&self, policy: &RetentionPolicy, ids: &[String]) -> Result<u64, String> {
+        let projections = self.collection(self.projection.collection_name());
+        let selected = doc! { "_id": { "$in": ids } };
+        let now = BsonDateTime::now();
+
+        let affected = match policy.action {
+            RetentionAction::ArchiveStaleContent => {
+                let archive =
+                    self.collection(&format!("{}_archive", self.projection.collection_name()));
+                let mut cursor = projections
+                    .find(selected.clone())
+                    .await
+                    .map_err(|e| format!("Failed to read projections: {e}"))?;
+                let mut archived = 0;
+                while let Some(doc) = cursor.next().await {
+                    let mut doc = doc.map_err(|e| format!("Failed to read projection: {e}"))?;
+                    doc.insert("archived_at", now);
+                    doc.insert("archived_by_policy", policy.id.as_str());
+                    let id = doc.get("_id").cloned().unwrap_or(bson::Bson::Null);
+                    archive
+                        .replace_one(doc! { "_id": id }, doc)
+                        .upsert(true)
+                        .await
+                        .map_err(|e| format!("Failed to archive projection: {e}"))?;
+                    archived += 1;
+                }
+                projections
+                    .delete_many(selected)
+                    .await
+                    .map_err(|e| format!("Failed to remove archived projections: {e}"))?;
+                archived
+            }
+            RetentionAction::PurgeErasedUsers => {
+                let update = doc! {
+                    "$set": {
+                        "data": null,
+                        "search_tokens": [],
+                        "blob_endpoints": [],
+                        "metadata.is_deleted": true,
+                        "metadata.deleted_at": now,
+                        "tombstoned_by_policy": policy.id.as_str(),
+                    }
+                };
+                projections
+                    .update_many(selected, update)
+                    .await
+                    .map_err(|e| format!("Failed to tombstone projections: {e}"))?
+                    .modified_count
+            }
+            RetentionAction::ExpireImportBatches => {
+                projections
+                    .delete_many(selected)
+                    .await
+                    .map_err(|e| format!("Failed to delete projections: {e}"))?
+                    .deleted_count
+            }
+        };
+
+        // Drop the documents from the hot cache (and soft-delete what's left)
+        for id in ids {
+            if let Err(e) = self.projection.invalidate(id).await {
+                warn!(id = %id, error = %e, "Failed to evict projection");
+            }
+        }
+        Ok(affected)
+    }
+
+    async fn execute(
+        &self,
+        policy: &RetentionPolicy,
+        dry_run: bool,
+        report: &mut RetentionRunReport,
+    ) -> Result<(), String> {
+        let now = Utc::now();
+        let authors = match policy.action {
+            RetentionAction::PurgeErasedUsers => self.erased_authors(policy.cutoff(now)).await?,
+            _ => Vec::new(),
+        };
+        if policy.action == RetentionAction::PurgeErasedUsers && authors.is_empty() {
+            return Ok(());
+        }
+
+        let filter = policy.projection_filter(now, &authors);
+        report.matched = self
+            .collection(self.projection.collection_name())
+            .count_documents(filter.clone())
+            .await
+            .map_err(|e| format!("Failed to count projections: {e}"))?;
+        if report.matched == 0 {
+            return Ok(());
+        }
+
+        let ids = self.matching_ids(&filter).await?;
+        report.sample_ids = ids.iter().take(SAMPLE_SIZE).cloned().collect();
+        if !dry_run {
+            report.affected = self.apply(policy, &ids).await?;
+        }
+        Ok(())
+    }
+
+    /// Run one policy and audit the run. A policy marked `dry_run` never
+    /// changes anything, whatever the caller asks.
+    pub async fn run(&self, policy: &RetentionPolicy, dry_run: bool) -> RetentionRunReport {
+        let started_at = Utc::now();
+        let mut report = RetentionRunReport {
+            policy_id: policy.id.clone(),
+            action: policy.action.as_str().to_string(),
+            dry_run: dry_run || policy.dry_run,
+            cutoff: Some(policy.cutoff(started_at)),
+            started_at: Some(started_at),
+            ..Default::default()
+        };
+
+        if let Err(e) = self.execute(policy, report.dry_run, &mut report).await {
+            warn!(policy_id = %policy.id, error = %e, "Retention policy failed");
+            report.error = Some(e);
+        }
+        report.finished_at = Some(Utc::now());
+
+        info!(
+            policy_id = %policy.id,
+            dry_run = report.dry_run,
+            matched = report.matched,
+            affected = report.affected,
+            "Retention policy run"
+        );
+        let audit = RetentionAuditDoc {
+            policy_id: report.policy_id.clone(),
+            action: report.action.clone(),
+            dry_run: report.dry_run,
+            cutoff: report.cutoff,
+            matched: report.matched,
+            affected: report.affected,
+            sample_ids: report.sample_ids.clone(),
+            error: report.error.clone(),
+            finished_at: report.finished_at,
+            ..Default::default()
+        };
+        if let Err(e) = self.audit.insert_one(audit).await {
+            warn!(policy_id = %policy.id, error = %e, "Failed to record retention audit");
+        }
+        report
+    }
+
+    /// Run every policy
+    pub async fn run_all(&self) -> Vec<RetentionRunReport> {
+        let mut reports = Vec::with_capacity(self.policies.len());
+        for policy in &self.policies {
+            reports.push(self.run(policy, false).await);
+        }
+        reports
+    }
+
+    /// Audit records, newest first
+    pub async fn audit_log(
+        &self,
+        policy_id: Option<&str>,
+        skip: u64,
+        limit: i64,
+    ) -> Result<Vec<RetentionAuditDoc>, String> {
+        let filter = match policy_id {
+            Some(id) => doc! { "policy_id": id },
+            None => doc! {},
+        };
+        let options = FindOptions::builder()
+            .sort(doc! { "metadata.created_at": -1 })
+            .skip(skip)
+            .limit(limit)
+            .build();
+        let cursor = self
+            .audit
+            .inner()
+            .find(filter)
+            .with_options(options)
+            .await
+            .map_err(|e| format!("Failed to read retention audit: {e}"))?;
+        let records: Vec<RetentionAuditDoc> = cursor
+            .filter_map(|doc| async move { doc.ok() })
+            .collect()
+            .await;
+        Ok(records)
+    }
+}
+
+/// Spawn the retention worker: run every policy each `interval`
+pub fn spawn_retention_task(engine: Arc<RetentionEngine>, interval: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!(
+            policies = engine.policies().len(),
+            interval_secs = interval.as_secs(),
+            "Retention worker started"
+        );
+
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let reports = engine.run_all().await;
+            let failed = reports.iter().filter(|r| r.error.is_some()).count();
+            if failed > 0 {
+                warn!(failed, "Some retention policies failed");
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLICIES: &str = r#"[
+        {"id": "archive-stale", "action": "archive_stale_content", "after_days": 1825},
+        {"id": "erased-users", "action": "purge_erased_users", "after_days": 30},
+        {"id": "old-imports", "action": "expire_import_batches", "after_days": 90,
+         "doc_types": ["ImportBatch", "ImportItem"], "dry_run": true}
+    ]"#;
+
+    #[test]
+    fn test_parse_policies() {
+        let policies = parse_policies(POLICIES).unwrap();
+        assert_eq!(policies.len(), 3);
+        assert_eq!(policies[1].action, RetentionAction::PurgeErasedUsers);
+        assert!(policies[2].dry_run);
+
+        assert!(parse_policies(r#"[{"id": "x", "action": "shred", "after_days": 1}]"#).is_err());
+        assert!(parse_policies(
+            r#"[{"id": "x", "action": "purge_erased_users", "after_days": 0}]"#
+        )
+        .is_err());
+        assert!(parse_policies(
+            r#"[{"id": "x", "action": "purge_erased_users", "after_days": 1},
+                {"id": "x", "action": "archive_stale_content", "after_days": 1}]"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_projection_filter() {
+        let policies = parse_policies(POLICIES).unwrap();
+        let now = Utc::now();
+
+        let archive = policies[0].projection_filter(now, &[]);
+        assert!(archive.get_document("projected_at").is_ok());
+        assert_eq!(
+            archive.get_document("doc_type").unwrap(),
+            &doc! { "$in": ["Content"] }
+        );
+
+        let purge = policies[1].projection_filter(now, &["uhCAkerased".to_string()]);
+        assert_eq!(
+            purge.get_document("author").unwrap(),
+            &doc! { "$in": ["uhCAkerased"] }
+        );
+        assert!(!purge.contains_key("doc_type"));
+
+        let expire = policies[2].projection_filter(now, &[]);
+        assert_eq!(
+            expire.get_document("doc_type").unwrap(),
+            &doc! { "$in": ["ImportBatch", "ImportItem"] }
+        );
+    }
+
+    #[test]
+    fn test_cutoff() {
+        let policies = parse_policies(POLICIES).unwrap();
+        let now = Utc::now();
+        assert_eq!(policies[1].cutoff(now), now - ChronoDuration::days(30));
+    }
+}
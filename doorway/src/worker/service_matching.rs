@@ -0,0 +1,295 @@
+//! Service request/offer matching
+//!
+//! Shefa requests and offers only meet if someone goes looking. This job
+//! walks the active service requests on a timer and asks the content DNA to
+//! record a `suggested` ServiceMatch for every good new offer
+//! (`suggest_service_matches`, which scores tag, skill and geography
+//! overlap). The zome skips pairs that already have a match, so each pair is
+//! suggested once.
+//!
+//! Both parties are notified of a new suggestion and confirm it with
+//! `confirm_service_match`; the match moves on once both have.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::db::schemas::{NotificationDoc, NOTIFICATION_COLLECTION};
+use crate::db::MongoClient;
+use crate::services::zome_caller::{ZomeCall, ZomeCaller};
+
+/// Role holding the content_store zome
+const CONTENT_ROLE: &str = "lamad";
+
+/// Zome exposing the request/offer functions
+const CONTENT_ZOME: &str = "content_store";
+
+/// Fields of a ServiceRequest the job needs
+/// Must match ServiceRequest in holochain/dna/elohim/zomes/content_store_integrity/src/lib.rs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceRequestSummary {
+    pub id: String,
+    pub requester_id: String,
+    pub title: String,
+    pub status: String,
+}
+
+/// Fields of a ServiceMatch the job needs
+/// Must match ServiceMatch in holochain/dna/elohim/zomes/content_store_integrity/src/lib.rs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceMatchSummary {
+    pub id: String,
+    pub request_id: String,
+    pub offer_id: String,
+    pub match_reason: String,
+    pub match_quality: u32,
+}
+
+/// A newly recorded match and who to tell
+/// Must match SuggestedServiceMatch in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuggestedServiceMatch {
+    pub service_match: ServiceMatchSummary,
+    pub requester_id: String,
+    pub offeror_id: String,
+}
+
+/// Outcome of one matching run
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MatchingSummary {
+    pub requests: usize,
+    pub suggested: usize,
+    pub failed: usize,
+}
+
+/// Notifications for both parties of a suggested match
+fn match_notifications(
+    request: &ServiceRequestSummary,
+    suggested: &SuggestedServiceMatch,
+) -> [NotificationDoc; 2] {
+    let service_match = &suggested.service_match;
+    let detail = format!(
+        "{}% fit ({}). Confirm match {} if you'd like to be put in touch.",
+        service_match.match_quality, service_match.match_reason, service_match.id
+    );
+    let notification = |recipient: &str, title: String| NotificationDoc {
+        recipient: recipient.to_string(),
+        kind: "service_match".to_string(),
+        title,
+        message: detail.clone(),
+        ..Default::default()
+    };
+    [
+        notification(
+            &suggested.requester_id,
+            format!("An offer may fit your request \"{}\"", request.title),
+        ),
+        notification(
+            &suggested.offeror_id,
+            format!("Your offer may fit the request \"{}\"", request.title),
+        ),
+    ]
+}
+
+/// Leave match notifications in MongoDB
+async fn notify_parties(
+    mongo: &MongoClient,
+    notifications: [NotificationDoc; 2],
+) -> Result<(), String> {
+    let collection = mongo
+        .collection::<NotificationDoc>(NOTIFICATION_COLLECTION)
+        .await
+        .map_err(|e| e.to_string())?;
+    for notification in notifications {
+        if notification.recipient.is_empty() {
+            continue;
+        }
+        collection
+            .insert_one(notification)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Run a single matching pass over every active request
+pub async fn match_once(
+    zome_caller: &impl ZomeCall,
+    mongo: Option<&MongoClient>,
+) -> Result<MatchingSummary, String> {
+    let requests: Vec<ServiceRequestSummary> = zome_caller
+        .call(
+            CONTENT_ROLE,
+            CONTENT_ZOME,
+            "get_service_requests_by_status",
+            &"active",
+        )
+        .await?;
+
+    let mut summary = MatchingSummary {
+        requests: requests.len(),
+        ..Default::default()
+    };
+
+    for request in requests {
+        let result: Result<Vec<SuggestedServiceMatch>, String> = zome_caller
+            .call(
+                CONTENT_ROLE,
+                CONTENT_ZOME,
+                "suggest_service_matches",
+                &request.id,
+            )
+            .await;
+
+        let suggested = match result {
+            Ok(suggested) => suggested,
+            Err(e) => {
+                warn!(request_id = %request.id, error = %e, "Failed to match service request");
+                summary.failed += 1;
+                continue;
+            }
+        };
+
+        for suggestion in &suggested {
+            info!(
+                match_id = %suggestion.service_match.id,
+                request_id = %request.id,
+                offer_id = %suggestion.service_match.offer_id,
+                quality = suggestion.service_match.match_quality,
+                "Service match suggested"
+            );
+            if let Some(mongo) = mongo {
+                if let Err(e) =
+                    notify_parties(mongo, match_notifications(&request, suggestion)).await
+                {
+                    warn!(
+                        match_id = %suggestion.service_match.id,
+                        error = %e,
+                        "Failed to notify parties of service match"
+                    );
+                }
+            }
+        }
+        summary.suggested += suggested.len();
+    }
+
+    Ok(summary)
+}
+
+/// Spawn the periodic matching job.
+///
+/// Notifications need MongoDB; without it matches are still recorded and
+/// reach the parties through the ServiceMatch projection signal only.
+pub fn spawn_service_matching_task(
+    interval: Duration,
+    zome_caller: Arc<ZomeCaller>,
+    mongo: Option<MongoClient>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!(
+            interval_secs = interval.as_secs(),
+            notifications = mongo.is_some(),
+            "Service matching task started"
+        );
+
+        loop {
+            tokio::time::sleep(interval).await;
+
+            match match_once(zome_caller.as_ref(), mongo.as_ref()).await {
+                Ok(summary) if summary.suggested > 0 || summary.failed > 0 => {
+                    info!(
+                        requests = summary.requests,
+                        suggested = summary.suggested,
+                        failed = summary.failed,
+                        "Service matching complete"
+                    );
+                }
+                Ok(summary) => debug!(
+                    requests = summary.requests,
+                    "Service matching: no new matches"
+                ),
+                Err(e) => {
+                    warn!(error = %e, "Service matching failed (will retry next interval)");
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::zome_caller::StubZomeCaller;
+
+    fn request(id: &str) -> ServiceRequestSummary {
+        ServiceRequestSummary {
+            id: id.to_string(),
+            requester_id: "human-a".to_string(),
+            title: "Fix a fence".to_string(),
+            status: "active".to_string(),
+        }
+    }
+
+    fn suggestion() -> SuggestedServiceMatch {
+        SuggestedServiceMatch {
+            service_match: ServiceMatchSummary {
+                id: "match-req-1-ofr-1".to_string(),
+                request_id: "req-1".to_string(),
+                offer_id: "ofr-1".to_string(),
+                match_reason: "skills: carpentry".to_string(),
+                match_quality: 72,
+            },
+            requester_id: "human-a".to_string(),
+            offeror_id: "human-b".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_suggestion_msgpack_roundtrip() {
+        let bytes = rmp_serde::to_vec_named(&vec![suggestion()]).unwrap();
+        let decoded: Vec<SuggestedServiceMatch> = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(decoded[0].service_match.match_quality, 72);
+        assert_eq!(decoded[0].offeror_id, "human-b");
+    }
+
+    #[test]
+    fn test_both_parties_notified() {
+        let [requester, offeror] = match_notifications(&request("req-1"), &suggestion());
+        assert_eq!(requester.recipient, "human-a");
+        assert_eq!(offeror.recipient, "human-b");
+        assert_eq!(requester.kind, "service_match");
+        assert!(requester.title.contains("Fix a fence"));
+        assert!(offeror.message.contains("match-req-1-ofr-1"));
+    }
+
+    #[tokio::test]
+    async fn test_match_once_counts_suggestions_and_failures() {
+        let zome = StubZomeCaller(|fn_name: &str, payload: &[u8]| match fn_name {
+            "get_service_requests_by_status" => {
+                let status: String = rmp_serde::from_slice(payload).unwrap();
+                assert_eq!(status, "active");
+                Ok(rmp_serde::to_vec_named(&vec![request("req-1"), request("req-2")]).unwrap())
+            }
+            "suggest_service_matches" => {
+                let request_id: String = rmp_serde::from_slice(payload).unwrap();
+                match request_id.as_str() {
+                    "req-1" => Ok(rmp_serde::to_vec_named(&vec![suggestion()]).unwrap()),
+                    _ => Err("Request not found".to_string()),
+                }
+            }
+            other => panic!("unexpected zome call {other}"),
+        });
+
+        let summary = match_once(&zome, None).await.unwrap();
+        assert_eq!(
+            summary,
+            MatchingSummary {
+                requests: 2,
+                suggested: 1,
+                failed: 1,
+            }
+        );
+    }
+}
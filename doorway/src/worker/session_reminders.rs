@@ -0,0 +1,212 @@
+//! Learning session reminders
+//!
+//! Study and cohort sessions live in the content DNA (see
+//! [`calendar`](crate::services::calendar)). This job polls it on a timer
+//! for scheduled sessions starting within `SESSION_REMINDER_LEAD_MINS` and
+//! leaves a `session_reminder` notification for everyone each session is
+//! for: its organizer and, for a cohort session, the learners sharing the
+//! path with the facilitator. A recipient already reminded of a session is
+//! skipped, so each is reminded once.
+
+use bson::doc;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::db::schemas::{NotificationDoc, NOTIFICATION_COLLECTION};
+use crate::db::MongoClient;
+use crate::services::calendar::LearningSession;
+use crate::services::zome_caller::ZomeCaller;
+
+/// Role holding the content_store zome
+const CONTENT_ROLE: &str = "lamad";
+
+/// Zome exposing the learning session functions
+const CONTENT_ZOME: &str = "content_store";
+
+/// Kind of the notifications this job leaves
+const REMINDER_KIND: &str = "session_reminder";
+
+/// Must match LearningSessionWindowInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Clone, Serialize)]
+pub struct LearningSessionWindowInput {
+    pub from_micros: i64,
+    pub until_micros: i64,
+}
+
+/// Must match UpcomingLearningSession in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpcomingLearningSession {
+    pub session: LearningSession,
+    pub attendee_ids: Vec<String>,
+}
+
+/// Outcome of one reminder run
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ReminderSummary {
+    pub sessions: usize,
+    pub reminded: usize,
+    pub failed: usize,
+}
+
+/// A reminder of a session for one attendee
+fn session_reminder(session: &LearningSession, recipient: &str) -> NotificationDoc {
+    let starts = DateTime::from_timestamp_micros(session.starts_at_micros)
+        .map(|at| at.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_default();
+    let mut message = format!(
+        "Starts {} and runs {} minutes.",
+        starts, session.duration_minutes
+    );
+    if let Some(ref location) = session.location {
+        message.push_str(&format!(" Where: {location}"));
+    }
+    NotificationDoc {
+        recipient: recipient.to_string(),
+        kind: REMINDER_KIND.to_string(),
+        title: format!("Coming up: {}", session.title),
+        message,
+        content_id: Some(session.id.clone()),
+        ..Default::default()
+    }
+}
+
+/// Run a single pass: remind attendees of sessions starting within `lead`
+pub async fn remind_once(
+    zome_caller: &ZomeCaller,
+    mongo: &MongoClient,
+    lead: Duration,
+    now: DateTime<Utc>,
+) -> Result<ReminderSummary, String> {
+    let input = LearningSessionWindowInput {
+        from_micros: now.timestamp_micros(),
+        until_micros: now.timestamp_micros() + lead.as_micros() as i64,
+    };
+    let upcoming: Vec<UpcomingLearningSession> = zome_caller
+        .call(
+            CONTENT_ROLE,
+            CONTENT_ZOME,
+            "get_learning_sessions_starting",
+            &input,
+        )
+        .await?;
+    let collection = mongo
+        .collection::<NotificationDoc>(NOTIFICATION_COLLECTION)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut summary = ReminderSummary {
+        sessions: upcoming.len(),
+        ..Default::default()
+    };
+    for UpcomingLearningSession {
+        session,
+        attendee_ids,
+    } in upcoming
+    {
+        for recipient in attendee_ids {
+            let already = collection
+                .find_one(doc! {
+                    "recipient": &recipient,
+                    "kind": REMINDER_KIND,
+                    "content_id": &session.id,
+                })
+                .await;
+            let result = match already {
+                Ok(Some(_)) => continue,
+                Ok(None) => collection
+                    .insert_one(session_reminder(&session, &recipient))
+                    .await
+                    .map(|_| ()),
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(()) => summary.reminded += 1,
+                Err(e) => {
+                    warn!(session_id = %session.id, error = %e, "Failed to leave session reminder");
+                    summary.failed += 1;
+                }
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Spawn the periodic reminder job
+pub fn spawn_session_reminder_task(
+    interval: Duration,
+    lead: Duration,
+    zome_caller: Arc<ZomeCaller>,
+    mongo: MongoClient,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!(
+            interval_secs = interval.as_secs(),
+            lead_mins = lead.as_secs() / 60,
+            "Session reminder task started"
+        );
+
+        loop {
+            tokio::time::sleep(interval).await;
+
+            match remind_once(&zome_caller, &mongo, lead, Utc::now()).await {
+                Ok(summary) if summary.reminded > 0 || summary.failed > 0 => {
+                    info!(
+                        sessions = summary.sessions,
+                        reminded = summary.reminded,
+                        failed = summary.failed,
+                        "Session reminders sent"
+                    );
+                }
+                Ok(summary) => debug!(
+                    sessions = summary.sessions,
+                    "Session reminders: nobody new to remind"
+                ),
+                Err(e) => {
+                    warn!(error = %e, "Session reminders failed (will retry next interval)");
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_reminder() {
+        let session = LearningSession {
+            id: "session-uhCAk-1".to_string(),
+            kind: "study".to_string(),
+            organizer_id: "uhCAk-learner".to_string(),
+            path_id: "path-1".to_string(),
+            title: "Fractions review".to_string(),
+            description: None,
+            location: Some("Library, room 2".to_string()),
+            starts_at_micros: 1_792_515_600_000_000,
+            duration_minutes: 45,
+            status: "scheduled".to_string(),
+        };
+        let reminder = session_reminder(&session, "uhCAk-learner");
+        assert_eq!(reminder.kind, "session_reminder");
+        assert_eq!(reminder.content_id.as_deref(), Some("session-uhCAk-1"));
+        assert_eq!(reminder.title, "Coming up: Fractions review");
+        assert_eq!(
+            reminder.message,
+            "Starts 2026-10-20 17:00 UTC and runs 45 minutes. Where: Library, room 2"
+        );
+
+        let bytes = rmp_serde::to_vec_named(&vec![UpcomingLearningSession {
+            session,
+            attendee_ids: vec!["uhCAk-learner".to_string()],
+        }])
+        .unwrap();
+        let decoded: Vec<UpcomingLearningSession> = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(decoded[0].attendee_ids, ["uhCAk-learner"]);
+    }
+}
@@ -0,0 +1,380 @@
+//! Conductor signal journal
+//!
+//! Records every signal the projection subscriber receives in the capped
+//! `signal_journal` collection, numbered by an increasing offset. When a bug
+//! has left projections or the response cache wrong, an admin can replay a
+//! time range (`POST /admin/signals/replay`) instead of re-crawling the DHT:
+//! projection signals go back through the projection engine, and zome write
+//! signals are re-applied to the response cache.
+//!
+//! Moderation revocations and governance changes are not replayed; they act
+//! on the world rather than on derived state.
+
+use bson::{doc, DateTime, Document};
+use futures_util::StreamExt;
+use mongodb::options::FindOptions;
+use serde::Serialize;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::cache::{apply_invalidation, CacheInvalidation, CacheRuleStore, ContentCache};
+use crate::db::schemas::{JournaledSignalKind, SignalJournalDoc, SIGNAL_JOURNAL_COLLECTION};
+use crate::db::{MongoClient, MongoCollection};
+use crate::projection::{ProjectionEngine, ProjectionSignal};
+
+/// Journal entries read per query while replaying
+const REPLAY_BATCH_SIZE: i64 = 500;
+
+/// MongoDB error code for "collection already exists"
+const NAMESPACE_EXISTS: i32 = 48;
+
+/// Which journaled signals to read
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct JournalRange {
+    /// Received at or after
+    pub from: Option<DateTime>,
+    /// Received before
+    pub to: Option<DateTime>,
+    /// Only entries past this offset
+    pub after_offset: Option<i64>,
+    /// Kinds to include (all when empty)
+    pub kinds: Vec<JournaledSignalKind>,
+}
+
+impl JournalRange {
+    fn to_filter(&self) -> Document {
+        let mut filter = Document::new();
+        let mut received = Document::new();
+        if let Some(from) = self.from {
+            received.insert("$gte", from);
+        }
+        if let Some(to) = self.to {
+            received.insert("$lt", to);
+        }
+        if !received.is_empty() {
+            filter.insert("metadata.created_at", received);
+        }
+        if let Some(after) = self.after_offset {
+            filter.insert("offset", doc! { "$gt": after });
+        }
+        if !self.kinds.is_empty() {
+            let kinds: Vec<_> = self
+                .kinds
+                .iter()
+                .filter_map(|kind| bson::to_bson(kind).ok())
+                .collect();
+            filter.insert("kind", doc! { "$in": kinds });
+        }
+        filter
+    }
+}
+
+/// Outcome of a replay
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct ReplaySummary {
+    pub projection_signals: usize,
+    pub cache_invalidations: usize,
+    pub cache_entries_evicted: usize,
+    pub failed: usize,
+    pub first_offset: Option<i64>,
+    pub last_offset: Option<i64>,
+}
+
+/// Durable, offset-numbered record of received conductor signals
+pub struct SignalJournal {
+    collection: MongoCollection<SignalJournalDoc>,
+    next_offset: AtomicI64,
+}
+
+impl SignalJournal {
+    /// Open the journal, creating the capped collection (`max_bytes`) on
+    /// first use. Offsets carry on from the newest entry.
+    pub async fn open(mongo: &MongoClient, max_bytes: u64) -> Result<Self, String> {
+        let db = mongo.inner().database(mongo.db_name());
+        if let Err(e) = db
+            .create_collection(SIGNAL_JOURNAL_COLLECTION)
+            .capped(true)
+            .size(max_bytes)
+            .await
+        {
+            let exists = matches!(
+                *e.kind,
+                mongodb::error::ErrorKind::Command(ref c) if c.code == NAMESPACE_EXISTS
+            );
+            if !exists {
+                return Err(format!("Failed to create signal journal: {e}"));
+            }
+        }
+
+        let collection = mongo
+            .collection::<SignalJournalDoc>(SIGNAL_JOURNAL_COLLECTION)
+            .await
+            .map_err(|e| e.to_string())?;
+        let last = collection
+            .inner()
+            .find_one(doc! {})
+            .sort(doc! { "offset": -1 })
+            .await
+            .map_err(|e| format!("Failed to read signal journal: {e}"))?;
+
+        Ok(Self {
+            collection,
+            next_offset: AtomicI64::new(last.map_or(1, |doc| doc.offset + 1)),
+        })
+    }
+
+    async fn append(&self, mut entry: SignalJournalDoc) -> Result<i64, String> {
+        entry.offset = self.next_offset.fetch_add(1, Ordering::SeqCst);
+        let offset = entry.offset;
+        self.collection
+            .insert_one(entry)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(offset)
+    }
+
+    /// Record a projection signal
+    pub async fn append_projection(&self, signal: &ProjectionSignal) -> Result<i64, String> {
+        self.append(projection_entry(signal)?).await
+    }
+
+    /// Record a zome write signal
+    pub async fn append_invalidation(
+        &self,
+        invalidation: &CacheInvalidation,
+    ) -> Result<i64, String> {
+        self.append(invalidation_entry(invalidation)).await
+    }
+
+    /// Entries in a range, oldest first, at most `limit`
+    pub async fn read(
+        &self,
+        range: &JournalRange,
+        limit: i64,
+    ) -> Result<Vec<SignalJournalDoc>, String> {
+        let options = FindOptions::builder()
+            .sort(doc! { "offset": 1 })
+            .limit(limit)
+            .build();
+        let cursor = self
+            .collection
+            .inner()
+            .find(range.to_filter())
+            .with_options(options)
+            .await
+            .map_err(|e| format!("Failed to read signal journal: {e}"))?;
+
+        let entries: Vec<SignalJournalDoc> = cursor
+            .filter_map(|doc| async {
+                match doc {
+                    Ok(d) => Some(d),
+                    Err(e) => {
+                        warn!(error = %e, "Unreadable signal journal entry");
+                        None
+                    }
+                }
+            })
+            .collect()
+            .await;
+        Ok(entries)
+    }
+
+    /// Replay a range in offset order: projection signals through `engine`,
+    /// zome writes against the response cache
+    pub async fn replay(
+        &self,
+        range: &JournalRange,
+        engine: &ProjectionEngine,
+        cache: &ContentCache,
+        rules: &CacheRuleStore,
+    ) -> Result<ReplaySummary, String> {
+        let mut summary = ReplaySummary::default();
+        let mut range = range.clone();
+
+        loop {
+            let batch = self.read(&range, REPLAY_BATCH_SIZE).await?;
+            let Some(last) = batch.last() else {
+                break;
+            };
+            range.after_offset = Some(last.offset);
+            summary.first_offset = summary.first_offset.or(batch.first().map(|e| e.offset));
+            summary.last_offset = Some(last.offset);
+
+            for entry in &batch {
+                match entry.kind {
+                    JournaledSignalKind::Projection => {
+                        let signal = entry
+                            .payload_json
+                            .as_deref()
+                            .and_then(|json| serde_json::from_str::<ProjectionSignal>(json).ok());
+                        let Some(signal) = signal else {
+                            warn!(
+                                offset = entry.offset,
+                                "Journaled projection signal unreadable"
+                            );
+                            summary.failed += 1;
+                            continue;
+                        };
+                        match engine.process_signal(signal).await {
+                            Ok(()) => summary.projection_signals += 1,
+                            Err(e) => {
+                                warn!(offset = entry.offset, error = %e, "Replayed signal failed");
+                                summary.failed += 1;
+                            }
+                        }
+                    }
+                    JournaledSignalKind::CacheInvalidation => {
+                        let Some(invalidation) = entry_invalidation(entry) else {
+                            summary.failed += 1;
+                            continue;
+                        };
+                        summary.cache_entries_evicted +=
+                            apply_invalidation(cache, rules, &invalidation);
+                        summary.cache_invalidations += 1;
+                    }
+                }
+            }
+
+            if (batch.len() as i64) < REPLAY_BATCH_SIZE {
+                break;
+            }
+        }
+
+        Ok(summary)
+    }
+}
+
+fn projection_entry(signal: &ProjectionSignal) -> Result<SignalJournalDoc, String> {
+    Ok(SignalJournalDoc {
+        kind: JournaledSignalKind::Projection,
+        doc_type: signal.doc_type.clone(),
+        doc_id: signal.id.clone(),
+        payload_json: Some(serde_json::to_string(signal).map_err(|e| e.to_string())?),
+        ..Default::default()
+    })
+}
+
+fn invalidation_entry(invalidation: &CacheInvalidation) -> SignalJournalDoc {
+    SignalJournalDoc {
+        kind: JournaledSignalKind::CacheInvalidation,
+        doc_type: invalidation.doc_type.clone(),
+        doc_id: invalidation.doc_id.clone(),
+        source_fn: Some(invalidation.source_fn.clone()),
+        ..Default::default()
+    }
+}
+
+fn entry_invalidation(entry: &SignalJournalDoc) -> Option<CacheInvalidation> {
+    Some(CacheInvalidation {
+        source_fn: entry.source_fn.clone()?,
+        doc_type: entry.doc_type.clone(),
+        doc_id: entry.doc_id.clone(),
+    })
+}
+
+/// Spawn the task journaling both subscriber streams
+pub fn spawn_signal_journal_task(
+    journal: Arc<SignalJournal>,
+    mut signals: broadcast::Receiver<ProjectionSignal>,
+    mut invalidations: broadcast::Receiver<CacheInvalidation>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!("Signal journal started");
+
+        loop {
+            let appended = tokio::select! {
+                signal = signals.recv() => match signal {
+                    Ok(signal) => journal.append_projection(&signal).await,
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        warn!(missed = n, "Signal journal lagged; projection signals not journaled");
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                invalidation = invalidations.recv() => match invalidation {
+                    Ok(invalidation) => journal.append_invalidation(&invalidation).await,
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        warn!(missed = n, "Signal journal lagged; write signals not journaled");
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+            };
+            match appended {
+                Ok(offset) => debug!(offset, "Signal journaled"),
+                Err(e) => warn!(error = %e, "Failed to journal signal"),
+            }
+        }
+
+        info!("Signal journal stopped");
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signal() -> ProjectionSignal {
+        ProjectionSignal {
+            doc_type: "Content".to_string(),
+            action: "commit".to_string(),
+            id: "manifesto".to_string(),
+            data: serde_json::json!({ "title": "The Elohim Protocol" }),
+            action_hash: "uhCkk...".to_string(),
+            entry_hash: None,
+            author: "uhCAk...".to_string(),
+            search_tokens: vec!["elohim".to_string()],
+            invalidates: vec![],
+            ttl_secs: None,
+        }
+    }
+
+    #[test]
+    fn test_projection_entry_roundtrip() {
+        let entry = projection_entry(&signal()).unwrap();
+        assert_eq!(entry.kind, JournaledSignalKind::Projection);
+        assert_eq!(entry.doc_id, "manifesto");
+        let replayed: ProjectionSignal =
+            serde_json::from_str(entry.payload_json.as_deref().unwrap()).unwrap();
+        assert_eq!(replayed.data["title"], "The Elohim Protocol");
+    }
+
+    #[test]
+    fn test_invalidation_entry_roundtrip() {
+        let invalidation = CacheInvalidation {
+            source_fn: "update_content".to_string(),
+            doc_type: "Content".to_string(),
+            doc_id: "manifesto".to_string(),
+        };
+        let entry = invalidation_entry(&invalidation);
+        assert_eq!(entry.kind, JournaledSignalKind::CacheInvalidation);
+        assert_eq!(entry_invalidation(&entry), Some(invalidation));
+    }
+
+    #[test]
+    fn test_range_filter() {
+        let range = JournalRange {
+            from: Some(DateTime::from_millis(1_000)),
+            to: None,
+            after_offset: Some(41),
+            kinds: vec![JournaledSignalKind::Projection],
+        };
+        let filter = range.to_filter();
+        assert_eq!(
+            filter.get_document("metadata.created_at").unwrap(),
+            &doc! { "$gte": DateTime::from_millis(1_000) }
+        );
+        assert_eq!(
+            filter.get_document("offset").unwrap(),
+            &doc! { "$gt": 41_i64 }
+        );
+        assert_eq!(
+            filter.get_document("kind").unwrap(),
+            &doc! { "$in": ["projection"] }
+        );
+        assert!(JournalRange::default().to_filter().is_empty());
+    }
+}
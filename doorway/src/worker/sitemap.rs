@@ -0,0 +1,363 @@
+//! Sitemap generation
+//!
+//! Periodically builds `sitemap.xml` from the projection store so search
+//! engines index hosted learning portals. The index points at one sitemap
+//! per content type (`sitemaps/content-{type}.xml`) plus `sitemaps/paths.xml`
+//! for public learning paths. Only `commons` content is listed.
+//!
+//! `lastmod` comes from the entry's own update time (see
+//! [`ProjectedDocument::entry_times`]), not from when it was projected, so
+//! re-projection doesn't make everything look freshly changed. Sitemaps are
+//! split at the protocol's 50,000 URL limit.
+
+use bytes::Bytes;
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::projection::{ProjectedDocument, ProjectionQuery, ProjectionStore};
+
+/// Most URLs allowed in one sitemap file
+pub const MAX_URLS_PER_SITEMAP: usize = 50_000;
+
+/// Name of the sitemap index
+pub const INDEX_NAME: &str = "sitemap.xml";
+
+/// Reach of content listed in sitemaps
+const SITEMAP_REACH: &str = "commons";
+
+/// Path visibilities listed in sitemaps
+const PUBLIC_VISIBILITY: [&str; 2] = ["public", "published"];
+
+/// One `<url>` entry
+#[derive(Debug, Clone, PartialEq)]
+pub struct SitemapUrl {
+    pub loc: String,
+    pub lastmod: DateTime<Utc>,
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn w3c_datetime(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Sitemap file slug for a content type (lowercase, url-safe)
+pub fn type_slug(content_type: &str) -> String {
+    let slug: String = content_type
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let slug = slug.trim_matches('-');
+    if slug.is_empty() {
+        "other".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+/// Whether a Content document is listed
+fn is_listed_content(doc: &ProjectedDocument) -> bool {
+    let reach = doc
+        .reach
+        .as_deref()
+        .or_else(|| doc.data.get("reach").and_then(|r| r.as_str()));
+    doc.doc_type == "Content" && !doc.metadata.is_deleted && reach == Some(SITEMAP_REACH)
+}
+
+/// Whether a LearningPath document is listed
+fn is_listed_path(doc: &ProjectedDocument) -> bool {
+    let visibility = doc.data.get("visibility").and_then(|v| v.as_str());
+    doc.doc_type == "LearningPath"
+        && !doc.metadata.is_deleted
+        && (visibility.is_some_and(|v| PUBLIC_VISIBILITY.contains(&v))
+            || doc.reach.as_deref() == Some(SITEMAP_REACH))
+}
+
+/// Render a `<urlset>`
+pub fn render_urlset(urls: &[SitemapUrl]) -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+    );
+    for url in urls {
+        xml.push_str(&format!(
+            "  <url><loc>{}</loc><lastmod>{}</lastmod></url>\n",
+            escape_xml(&url.loc),
+            w3c_datetime(url.lastmod)
+        ));
+    }
+    xml.push_str("</urlset>\n");
+    xml
+}
+
+/// Render the `<sitemapindex>`
+pub fn render_index(sitemaps: &[SitemapUrl]) -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<sitemapindex xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+    );
+    for sitemap in sitemaps {
+        xml.push_str(&format!(
+            "  <sitemap><loc>{}</loc><lastmod>{}</lastmod></sitemap>\n",
+            escape_xml(&sitemap.loc),
+            w3c_datetime(sitemap.lastmod)
+        ));
+    }
+    xml.push_str("</sitemapindex>\n");
+    xml
+}
+
+/// Build every sitemap file, keyed by name (`sitemap.xml`,
+/// `content-lesson.xml`, `paths.xml`, ...)
+pub fn build_sitemaps(
+    base_url: &str,
+    content: &[ProjectedDocument],
+    paths: &[ProjectedDocument],
+) -> HashMap<String, String> {
+    let base_url = base_url.trim_end_matches('/');
+    let mut groups: BTreeMap<String, Vec<SitemapUrl>> = BTreeMap::new();
+
+    for doc in content.iter().filter(|d| is_listed_content(d)) {
+        let content_type = doc
+            .data
+            .get("content_type")
+            .and_then(|t| t.as_str())
+            .unwrap_or("other");
+        groups
+            .entry(format!("content-{}", type_slug(content_type)))
+            .or_default()
+            .push(SitemapUrl {
+                loc: format!(
+                    "{base_url}/lamad/resource/{}",
+                    urlencoding::encode(&doc.doc_id)
+                ),
+                lastmod: doc.entry_times().1,
+            });
+    }
+    for doc in paths.iter().filter(|d| is_listed_path(d)) {
+        groups
+            .entry("paths".to_string())
+            .or_default()
+            .push(SitemapUrl {
+                loc: format!("{base_url}/lamad/path/{}", urlencoding::encode(&doc.doc_id)),
+                lastmod: doc.entry_times().1,
+            });
+    }
+
+    let mut files = HashMap::new();
+    let mut index = Vec::new();
+    for (group, mut urls) in groups {
+        urls.sort_by(|a, b| a.loc.cmp(&b.loc));
+        let chunks: Vec<&[SitemapUrl]> = urls.chunks(MAX_URLS_PER_SITEMAP).collect();
+        let split = chunks.len() > 1;
+        for (i, chunk) in chunks.into_iter().enumerate() {
+            let name = if split {
+                format!("{group}-{}.xml", i + 1)
+            } else {
+                format!("{group}.xml")
+            };
+            if let Some(lastmod) = chunk.iter().map(|u| u.lastmod).max() {
+                index.push(SitemapUrl {
+                    loc: format!("{base_url}/sitemaps/{name}"),
+                    lastmod,
+                });
+            }
+            files.insert(name, render_urlset(chunk));
+        }
+    }
+    files.insert(INDEX_NAME.to_string(), render_index(&index));
+    files
+}
+
+/// Keeps the latest generated sitemaps in memory
+pub struct SitemapGenerator {
+    base_url: String,
+    projection: Arc<ProjectionStore>,
+    files: RwLock<HashMap<String, Bytes>>,
+}
+
+impl SitemapGenerator {
+    pub fn new(base_url: String, projection: Arc<ProjectionStore>) -> Self {
+        Self {
+            base_url,
+            projection,
+            files: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// A generated file by name; None until the first run finishes
+    pub fn get(&self, name: &str) -> Option<Bytes> {
+        self.files.read().ok()?.get(name).cloned()
+    }
+
+    async fn load(
+        &self,
+        doc_type: &str,
+        filter: bson::Document,
+    ) -> Result<Vec<ProjectedDocument>, String> {
+        let mut query = ProjectionQuery::by_type(doc_type);
+        query.filter = Some(filter);
+        self.projection
+            .query(query)
+            .await
+            .map_err(|e| format!("{doc_type} query failed: {e}"))
+    }
+
+    /// Rebuild all sitemaps from the projection store.
+    /// Returns the number of files generated.
+    pub async fn regenerate(&self) -> Result<usize, String> {
+        let content = self
+            .load(
+                "Content",
+                bson::doc! {
+                    "metadata.is_deleted": { "$ne": true },
+                    "$or": [
+                        { "reach": SITEMAP_REACH },
+                        { "reach": null, "data.reach": SITEMAP_REACH },
+                    ],
+                },
+            )
+            .await?;
+        let paths = self
+            .load(
+                "LearningPath",
+                bson::doc! {
+                    "metadata.is_deleted": { "$ne": true },
+                    "$or": [
+                        { "data.visibility": { "$in": PUBLIC_VISIBILITY.to_vec() } },
+                        { "reach": SITEMAP_REACH },
+                    ],
+                },
+            )
+            .await?;
+
+        let files: HashMap<String, Bytes> = build_sitemaps(&self.base_url, &content, &paths)
+            .into_iter()
+            .map(|(name, xml)| (name, Bytes::from(xml)))
+            .collect();
+        let count = files.len();
+        if let Ok(mut current) = self.files.write() {
+            *current = files;
+        }
+        Ok(count)
+    }
+}
+
+/// Spawn the periodic sitemap rebuild (runs once immediately).
+///
+/// When a rebuild fails, the previous sitemaps are served until one succeeds.
+pub fn spawn_sitemap_task(interval: Duration, generator: Arc<SitemapGenerator>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!(
+            interval_secs = interval.as_secs(),
+            base_url = %generator.base_url,
+            "Sitemap task started"
+        );
+
+        loop {
+            match generator.regenerate().await {
+                Ok(files) => info!(files, "Sitemaps regenerated"),
+                Err(e) => warn!(error = %e, "Sitemap generation failed"),
+            }
+            tokio::time::sleep(interval).await;
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn doc(
+        doc_type: &str,
+        id: &str,
+        reach: Option<&str>,
+        data: serde_json::Value,
+    ) -> ProjectedDocument {
+        ProjectedDocument {
+            doc_type: doc_type.to_string(),
+            doc_id: id.to_string(),
+            reach: reach.map(String::from),
+            data,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_type_slug() {
+        assert_eq!(type_slug("lesson"), "lesson");
+        assert_eq!(type_slug("Case Study"), "case-study");
+        assert_eq!(type_slug(""), "other");
+    }
+
+    #[test]
+    fn test_build_sitemaps() {
+        let content = vec![
+            doc(
+                "Content",
+                "intro",
+                Some("commons"),
+                json!({ "content_type": "lesson", "updated_at": "2025-03-02T08:30:00Z" }),
+            ),
+            doc(
+                "Content",
+                "secret",
+                Some("community"),
+                json!({ "content_type": "lesson" }),
+            ),
+            doc(
+                "Content",
+                "clip",
+                Some("commons"),
+                json!({ "content_type": "video" }),
+            ),
+        ];
+        let paths = vec![
+            doc(
+                "LearningPath",
+                "governance",
+                None,
+                json!({ "visibility": "public" }),
+            ),
+            doc(
+                "LearningPath",
+                "draft",
+                None,
+                json!({ "visibility": "private" }),
+            ),
+        ];
+
+        let files = build_sitemaps("https://learn.example/", &content, &paths);
+        assert_eq!(files.len(), 4);
+
+        let lessons = &files["content-lesson.xml"];
+        assert!(lessons.contains(
+            "<url><loc>https://learn.example/lamad/resource/intro</loc><lastmod>2025-03-02T08:30:00Z</lastmod></url>"
+        ));
+        assert!(!lessons.contains("secret"));
+
+        assert!(files["paths.xml"].contains("/lamad/path/governance"));
+        assert!(!files["paths.xml"].contains("draft"));
+
+        let index = &files[INDEX_NAME];
+        assert!(index.contains("<loc>https://learn.example/sitemaps/content-lesson.xml</loc><lastmod>2025-03-02T08:30:00Z</lastmod>"));
+        assert!(index.contains("sitemaps/content-video.xml"));
+        assert!(index.contains("sitemaps/paths.xml"));
+    }
+
+    #[test]
+    fn test_escape_xml() {
+        assert_eq!(escape_xml("a&b<'c'>"), "a&amp;b&lt;&apos;c&apos;&gt;");
+    }
+}
@@ -0,0 +1,118 @@
+//! Mutual solvency snapshots
+//!
+//! Records the Shefa insurance mutual's pool health on a timer by calling
+//! `record_solvency_snapshot` in the content DNA, which totals pooled
+//! contributions from member risk profiles against paid and outstanding
+//! claims. Members read the snapshots through `GET /shefa/solvency`.
+
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::services::zome_caller::ZomeCaller;
+
+/// Role holding the content_store zome
+const CONTENT_ROLE: &str = "lamad";
+
+/// Zome exposing the insurance mutual functions
+const CONTENT_ZOME: &str = "content_store";
+
+/// Fields of a SolvencySnapshot the job logs
+/// Must match SolvencySnapshot in holochain/dna/elohim/zomes/content_store_integrity/src/lib.rs
+#[derive(Debug, Clone, Deserialize)]
+pub struct SolvencySnapshotSummary {
+    pub id: String,
+    pub unit: String,
+    pub member_count: u32,
+    pub contributions_value: f64,
+    pub outstanding_claims_value: f64,
+    pub reserve_value: f64,
+    pub solvency_ratio: Option<f64>,
+    pub status: String,
+}
+
+/// Record one snapshot
+pub async fn record_snapshot(zome_caller: &ZomeCaller) -> Result<SolvencySnapshotSummary, String> {
+    zome_caller
+        .call(CONTENT_ROLE, CONTENT_ZOME, "record_solvency_snapshot", &())
+        .await
+}
+
+/// Spawn the periodic snapshot job. The first snapshot is taken straight away.
+pub fn spawn_solvency_task(interval: Duration, zome_caller: Arc<ZomeCaller>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!(
+            interval_secs = interval.as_secs(),
+            "Solvency snapshot task started"
+        );
+
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+
+            match record_snapshot(&zome_caller).await {
+                Ok(snapshot) if snapshot.status == "underfunded" => warn!(
+                    snapshot_id = %snapshot.id,
+                    reserve = snapshot.reserve_value,
+                    outstanding = snapshot.outstanding_claims_value,
+                    ratio = ?snapshot.solvency_ratio,
+                    unit = %snapshot.unit,
+                    "Mutual pool underfunded: outstanding claims exceed reserves"
+                ),
+                Ok(snapshot) => info!(
+                    snapshot_id = %snapshot.id,
+                    status = %snapshot.status,
+                    members = snapshot.member_count,
+                    contributions = snapshot.contributions_value,
+                    reserve = snapshot.reserve_value,
+                    ratio = ?snapshot.solvency_ratio,
+                    "Solvency snapshot recorded"
+                ),
+                Err(e) => {
+                    warn!(error = %e, "Solvency snapshot failed (will retry next interval)");
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_msgpack_roundtrip() {
+        #[derive(serde::Serialize)]
+        struct Snapshot<'a> {
+            id: &'a str,
+            unit: &'a str,
+            member_count: u32,
+            contributions_value: f64,
+            paid_claims_value: f64,
+            outstanding_claims_value: f64,
+            reserve_value: f64,
+            solvency_ratio: Option<f64>,
+            status: &'a str,
+            breakdown_json: &'a str,
+        }
+        let bytes = rmp_serde::to_vec_named(&Snapshot {
+            id: "solvency-1",
+            unit: "unit-token",
+            member_count: 12,
+            contributions_value: 5400.0,
+            paid_claims_value: 400.0,
+            outstanding_claims_value: 2000.0,
+            reserve_value: 5000.0,
+            solvency_ratio: Some(2.5),
+            status: "healthy",
+            breakdown_json: "{}",
+        })
+        .unwrap();
+        let decoded: SolvencySnapshotSummary = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(decoded.member_count, 12);
+        assert_eq!(decoded.solvency_ratio, Some(2.5));
+        assert_eq!(decoded.status, "healthy");
+    }
+}
@@ -0,0 +1,318 @@
+//! Torrent metadata for large blobs
+//!
+//! Generates single-file `.torrent` metadata (BEP 3) for blobs above a size
+//! threshold, with this doorway's `/store/{hash}` endpoint as a web seed
+//! (BEP 19). Learners' BitTorrent-capable clients can then fetch pieces from
+//! each other and fall back to the doorway, spreading the load of large
+//! video delivery. The torrent carries no tracker; peers find each other
+//! through the DHT and the web seed always answers.
+//!
+//! Generation happens in the background the first time a torrent is asked
+//! for: the blob is fetched from elohim-storage, checked against its hash and
+//! split into pieces. The result is kept in memory; failures are remembered
+//! briefly so a missing blob doesn't trigger a fetch on every request.
+
+use dashmap::{DashMap, DashSet};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+/// Smallest piece size used
+const MIN_PIECE_LENGTH: u64 = 256 * 1024;
+
+/// Largest piece size used
+const MAX_PIECE_LENGTH: u64 = 16 * 1024 * 1024;
+
+/// Piece count aimed for when choosing a piece size
+const TARGET_PIECES: u64 = 1500;
+
+/// Generated torrents kept in memory
+const MAX_CACHED_TORRENTS: usize = 1024;
+
+/// How long a failed generation is remembered before retrying
+const FAILURE_TTL: Duration = Duration::from_secs(600);
+
+/// Timeout for fetching a blob to hash
+const FETCH_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Torrent generator settings
+#[derive(Debug, Clone)]
+pub struct TorrentConfig {
+    /// elohim-storage URL blobs are read from
+    pub storage_url: String,
+    /// Public URL of this doorway, used for the web seed
+    pub doorway_url: String,
+    /// Blobs smaller than this are served over HTTP only
+    pub min_size_bytes: u64,
+}
+
+/// A generated torrent
+#[derive(Debug, Clone)]
+pub struct TorrentMeta {
+    /// Bencoded `.torrent` file
+    pub torrent: bytes::Bytes,
+    /// Hex SHA1 of the bencoded info dictionary
+    pub info_hash: String,
+    pub size_bytes: u64,
+}
+
+/// Answer to a torrent request
+#[derive(Debug, Clone)]
+pub enum TorrentLookup {
+    Ready(Arc<TorrentMeta>),
+    /// Being generated; ask again shortly
+    Pending,
+    /// Blob is missing, too small or failed verification
+    Unavailable(String),
+}
+
+/// Piece size for a blob: a power of two giving roughly [`TARGET_PIECES`]
+pub fn piece_length_for(size: u64) -> u64 {
+    (size / TARGET_PIECES)
+        .next_power_of_two()
+        .clamp(MIN_PIECE_LENGTH, MAX_PIECE_LENGTH)
+}
+
+fn bencode_bytes(out: &mut Vec<u8>, value: &[u8]) {
+    out.extend_from_slice(value.len().to_string().as_bytes());
+    out.push(b':');
+    out.extend_from_slice(value);
+}
+
+fn bencode_int(out: &mut Vec<u8>, value: u64) {
+    out.push(b'i');
+    out.extend_from_slice(value.to_string().as_bytes());
+    out.push(b'e');
+}
+
+/// Bencoded info dictionary (keys in sorted order, as the format requires)
+fn info_dict(data: &[u8], name: &str, piece_length: u64) -> Vec<u8> {
+    let mut pieces = Vec::with_capacity(data.len() / piece_length as usize * 20 + 20);
+    for piece in data.chunks(piece_length as usize) {
+        pieces.extend_from_slice(&Sha1::digest(piece));
+    }
+
+    let mut info = vec![b'd'];
+    bencode_bytes(&mut info, b"length");
+    bencode_int(&mut info, data.len() as u64);
+    bencode_bytes(&mut info, b"name");
+    bencode_bytes(&mut info, name.as_bytes());
+    bencode_bytes(&mut info, b"piece length");
+    bencode_int(&mut info, piece_length);
+    bencode_bytes(&mut info, b"pieces");
+    bencode_bytes(&mut info, &pieces);
+    info.push(b'e');
+    info
+}
+
+/// Build a single-file torrent for `data` with the given web seeds
+pub fn build_torrent(
+    data: &[u8],
+    name: &str,
+    web_seeds: &[String],
+    created_at: i64,
+) -> TorrentMeta {
+    let info = info_dict(data, name, piece_length_for(data.len() as u64));
+    let info_hash = hex::encode(Sha1::digest(&info));
+
+    let mut torrent = vec![b'd'];
+    bencode_bytes(&mut torrent, b"created by");
+    bencode_bytes(&mut torrent, b"elohim-doorway");
+    bencode_bytes(&mut torrent, b"creation date");
+    bencode_int(&mut torrent, created_at.max(0) as u64);
+    bencode_bytes(&mut torrent, b"info");
+    torrent.extend_from_slice(&info);
+    bencode_bytes(&mut torrent, b"url-list");
+    torrent.push(b'l');
+    for seed in web_seeds {
+        bencode_bytes(&mut torrent, seed.as_bytes());
+    }
+    torrent.push(b'e');
+    torrent.push(b'e');
+
+    TorrentMeta {
+        torrent: torrent.into(),
+        info_hash,
+        size_bytes: data.len() as u64,
+    }
+}
+
+/// Generates torrents on demand and caches them
+pub struct TorrentGenerator {
+    config: TorrentConfig,
+    client: reqwest::Client,
+    ready: DashMap<String, Arc<TorrentMeta>>,
+    in_flight: DashSet<String>,
+    failed: DashMap<String, (Instant, String)>,
+}
+
+impl TorrentGenerator {
+    pub fn new(config: TorrentConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(FETCH_TIMEOUT)
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+        Self {
+            config,
+            client,
+            ready: DashMap::new(),
+            in_flight: DashSet::new(),
+            failed: DashMap::new(),
+        }
+    }
+
+    /// Web seed URL for a blob
+    pub fn web_seed(&self, hash: &str) -> String {
+        format!(
+            "{}/store/{hash}",
+            self.config.doorway_url.trim_end_matches('/')
+        )
+    }
+
+    /// Torrent for a normalized `sha256-{hex}` hash, starting generation if
+    /// it hasn't been made yet
+    pub fn lookup(self: &Arc<Self>, hash: &str) -> TorrentLookup {
+        if let Some(meta) = self.ready.get(hash) {
+            return TorrentLookup::Ready(Arc::clone(&meta));
+        }
+        if let Some(entry) = self.failed.get(hash) {
+            if entry.0.elapsed() < FAILURE_TTL {
+                return TorrentLookup::Unavailable(entry.1.clone());
+            }
+        }
+
+        if self.in_flight.insert(hash.to_string()) {
+            let generator = Arc::clone(self);
+            let hash = hash.to_string();
+            tokio::spawn(async move {
+                let result = generator.generate(&hash).await;
+                match result {
+                    Ok(meta) => {
+                        info!(hash, info_hash = %meta.info_hash, size = meta.size_bytes, "Torrent generated");
+                        if generator.ready.len() >= MAX_CACHED_TORRENTS {
+                            let evict = generator.ready.iter().next().map(|e| e.key().clone());
+                            if let Some(key) = evict {
+                                generator.ready.remove(&key);
+                            }
+                        }
+                        generator.failed.remove(&hash);
+                        generator.ready.insert(hash.clone(), Arc::new(meta));
+                    }
+                    Err(reason) => {
+                        debug!(hash, reason, "Torrent not generated");
+                        generator
+                            .failed
+                            .insert(hash.clone(), (Instant::now(), reason));
+                    }
+                }
+                generator.in_flight.remove(&hash);
+            });
+        }
+        TorrentLookup::Pending
+    }
+
+    /// Fetch, verify and hash a blob
+    async fn generate(&self, hash: &str) -> Result<TorrentMeta, String> {
+        let url = format!(
+            "{}/blob/{hash}",
+            self.config.storage_url.trim_end_matches('/')
+        );
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| format!("Fetch failed: {e}"))?;
+        if !response.status().is_success() {
+            return Err(format!("Storage returned HTTP {}", response.status()));
+        }
+        if let Some(length) = response.content_length() {
+            if length < self.config.min_size_bytes {
+                return Err("Blob is below the torrent size threshold".to_string());
+            }
+        }
+
+        let data = response
+            .bytes()
+            .await
+            .map_err(|e| format!("Failed to read blob: {e}"))?;
+        if (data.len() as u64) < self.config.min_size_bytes {
+            return Err("Blob is below the torrent size threshold".to_string());
+        }
+
+        let name = hash.to_string();
+        let web_seeds = vec![self.web_seed(hash)];
+        let expected = hash.strip_prefix("sha256-").unwrap_or(hash).to_string();
+        let created_at = chrono::Utc::now().timestamp();
+
+        // Hashing a large blob twice is CPU-bound; keep it off the runtime
+        tokio::task::spawn_blocking(move || {
+            if !hex::encode(Sha256::digest(&data)).eq_ignore_ascii_case(&expected) {
+                warn!(hash = %name, "Blob from storage does not match its hash");
+                return Err("Blob failed hash verification".to_string());
+            }
+            Ok(build_torrent(&data, &name, &web_seeds, created_at))
+        })
+        .await
+        .map_err(|e| format!("Torrent generation panicked: {e}"))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_piece_length_for() {
+        assert_eq!(piece_length_for(10 * 1024 * 1024), MIN_PIECE_LENGTH);
+        assert_eq!(piece_length_for(1024 * 1024 * 1024), 1024 * 1024);
+        assert_eq!(piece_length_for(100 * 1024 * 1024 * 1024), MAX_PIECE_LENGTH);
+    }
+
+    #[test]
+    fn test_build_torrent_layout() {
+        let data = vec![7u8; 600 * 1024];
+        let meta = build_torrent(
+            &data,
+            "blob",
+            &["https://d.example/store/blob".to_string()],
+            1,
+        );
+
+        assert!(meta
+            .torrent
+            .starts_with(b"d10:created by14:elohim-doorway13:creation datei1e4:infod"));
+
+        // 600 KiB at 256 KiB pieces = 3 pieces of 20-byte SHA1s
+        let needle = b"6:pieces60:";
+        assert!(meta.torrent.windows(needle.len()).any(|w| w == needle));
+
+        let tail = b"8:url-listl28:https://d.example/store/blobee";
+        assert!(meta.torrent.ends_with(tail));
+        assert_eq!(meta.size_bytes, data.len() as u64);
+    }
+
+    #[test]
+    fn test_info_hash_covers_only_info() {
+        let data = vec![1u8; 300 * 1024];
+        let a = build_torrent(
+            &data,
+            "blob",
+            &["https://a.example/store/blob".to_string()],
+            1,
+        );
+        let b = build_torrent(
+            &data,
+            "blob",
+            &["https://b.example/store/blob".to_string()],
+            2,
+        );
+        assert_eq!(a.info_hash, b.info_hash);
+        assert_eq!(a.info_hash.len(), 40);
+
+        let c = build_torrent(&vec![2u8; 300 * 1024], "blob", &[], 1);
+        assert_ne!(a.info_hash, c.info_hash);
+    }
+}
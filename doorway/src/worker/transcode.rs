@@ -0,0 +1,557 @@
+//! Transcoding pipeline hook
+//!
+//! Closes the loop from upload to adaptive playback: video blobs attached to
+//! content are submitted to a transcoder, and the renditions it produces are
+//! registered with `content_store::register_blob_variant` (and any embedded
+//! subtitle streams with `add_blob_caption`), where the
+//! [media route](crate::routes::stream) picks them up.
+//!
+//! Blobs are found through the content health scan. A video blob is
+//! submitted once if it has no registered variants yet; jobs are then polled
+//! on each pass until they finish.
+//!
+//! The transcoder is anything speaking this small HTTP contract, typically an
+//! ffmpeg sidecar or a thin adapter in front of a hosted service:
+//!
+//! | Request | Body / Response |
+//! |---------|-----------------|
+//! | `POST {url}/jobs` | [`TranscodeJobRequest`] → `{"job_id": ".."}` |
+//! | `GET {url}/jobs/{job_id}` | [`TranscodeJobStatus`] |
+//!
+//! The transcoder stores its outputs itself (elohim-storage or a bucket) and
+//! reports each one's SHA256 hash and download URL.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use super::content_health::{scan_all, BlobFallbacks};
+use crate::services::zome_caller::ZomeCaller;
+
+/// Role holding the content_store zome
+const CONTENT_ROLE: &str = "lamad";
+
+/// Zome owning blob variants and caption tracks
+const CONTENT_ZOME: &str = "content_store";
+
+/// Caption formats content_store accepts (CAPTION_FORMATS in the integrity zome)
+const CAPTION_FORMATS: [&str; 5] = ["webvtt", "srt", "vtt", "ass", "ssa"];
+
+/// Jobs submitted per pass, so a large backlog doesn't flood the transcoder
+const MAX_SUBMISSIONS_PER_PASS: usize = 10;
+
+/// Timeout for transcoder API calls
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Transcoder settings
+#[derive(Debug, Clone)]
+pub struct TranscodeConfig {
+    /// Transcoder API base URL
+    pub url: String,
+    /// Sent as `Authorization: Bearer ..` when set
+    pub api_key: Option<String>,
+    /// Resolution labels to produce (see VIDEO_VARIANTS)
+    pub renditions: Vec<String>,
+    /// elohim-storage URL the transcoder downloads sources from; the blob's
+    /// first fallback URL is used otherwise
+    pub storage_url: Option<String>,
+}
+
+/// Job submitted to the transcoder
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscodeJobRequest {
+    pub blob_hash: String,
+    pub source_url: String,
+    pub mime_type: String,
+    pub renditions: Vec<String>,
+    /// Also extract embedded subtitle streams as caption files
+    pub extract_captions: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct TranscodeJobCreated {
+    job_id: String,
+}
+
+/// Job state reported by the transcoder
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscodeJobState {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+}
+
+/// One output rendition
+#[derive(Debug, Clone, Deserialize)]
+pub struct TranscodedRendition {
+    pub hash: String,
+    pub resolution: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub bitrate_mbps: f32,
+    pub codec: String,
+    pub size_bytes: u64,
+    pub mime_type: String,
+    pub url: Option<String>,
+}
+
+/// One extracted caption track
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExtractedCaption {
+    pub hash: String,
+    pub language: String,
+    pub label: Option<String>,
+    pub format: String,
+    pub size_bytes: u64,
+}
+
+/// Response to `GET {url}/jobs/{job_id}`
+#[derive(Debug, Clone, Deserialize)]
+pub struct TranscodeJobStatus {
+    pub status: TranscodeJobState,
+    #[serde(default)]
+    pub renditions: Vec<TranscodedRendition>,
+    #[serde(default)]
+    pub captions: Vec<ExtractedCaption>,
+    pub error: Option<String>,
+}
+
+/// Must match RegisterBlobVariantInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct RegisterBlobVariantInput {
+    parent_hash: String,
+    hash: String,
+    resolution: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+    bitrate_mbps: f32,
+    codec: String,
+    size_bytes: u64,
+    mime_type: String,
+    fallback_urls: Vec<String>,
+}
+
+/// Must match AddBlobCaptionInput in holochain/dna/elohim/zomes/content_store/src/lib.rs
+#[derive(Debug, Serialize)]
+struct AddBlobCaptionInput {
+    blob_hash: String,
+    content_id: String,
+    language: String,
+    label: Option<String>,
+    format: String,
+    caption_hash: String,
+    size_bytes: u64,
+    author_id: Option<String>,
+}
+
+/// Input for content_store::get_blob_variants
+#[derive(Debug, Serialize)]
+struct QueryBlobVariantsInput {
+    blob_hash: String,
+}
+
+/// A job waiting on the transcoder
+#[derive(Debug, Clone)]
+struct PendingJob {
+    job_id: String,
+    content_id: String,
+}
+
+/// Outcome of one pass
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TranscodeSummary {
+    pub submitted: usize,
+    pub completed: usize,
+    pub variants: usize,
+    pub captions: usize,
+    pub failed: usize,
+}
+
+/// Whether a blob is a video the transcoder should handle
+pub fn is_video(blob: &BlobFallbacks) -> bool {
+    blob.mime_type.to_ascii_lowercase().starts_with("video/")
+}
+
+/// URL the transcoder should download the original from
+pub fn source_url(storage_url: Option<&str>, blob: &BlobFallbacks) -> Option<String> {
+    match storage_url {
+        Some(base) => Some(format!("{}/blob/{}", base.trim_end_matches('/'), blob.hash)),
+        None => blob.fallback_urls.first().cloned(),
+    }
+}
+
+/// Submits video blobs for transcoding and registers the results
+pub struct Transcoder {
+    config: TranscodeConfig,
+    client: reqwest::Client,
+    /// Jobs in progress, keyed by source blob hash
+    pending: HashMap<String, PendingJob>,
+    /// Blobs already dealt with (submitted, finished or already transcoded)
+    handled: HashSet<String>,
+}
+
+impl Transcoder {
+    pub fn new(config: TranscodeConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+        Self {
+            config,
+            client,
+            pending: HashMap::new(),
+            handled: HashSet::new(),
+        }
+    }
+
+    fn authorize(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match self.config.api_key {
+            Some(ref key) => request.bearer_auth(key),
+            None => request,
+        }
+    }
+
+    async fn submit(&self, job: &TranscodeJobRequest) -> Result<String, String> {
+        let url = format!("{}/jobs", self.config.url.trim_end_matches('/'));
+        let response = self
+            .authorize(self.client.post(&url).json(job))
+            .send()
+            .await
+            .map_err(|e| format!("Submit failed: {e}"))?;
+        if !response.status().is_success() {
+            return Err(format!("Transcoder returned HTTP {}", response.status()));
+        }
+        let created: TranscodeJobCreated = response
+            .json()
+            .await
+            .map_err(|e| format!("Invalid submit response: {e}"))?;
+        Ok(created.job_id)
+    }
+
+    async fn status(&self, job_id: &str) -> Result<TranscodeJobStatus, String> {
+        let url = format!("{}/jobs/{job_id}", self.config.url.trim_end_matches('/'));
+        let response = self
+            .authorize(self.client.get(&url))
+            .send()
+            .await
+            .map_err(|e| format!("Status request failed: {e}"))?;
+        if !response.status().is_success() {
+            return Err(format!("Transcoder returned HTTP {}", response.status()));
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| format!("Invalid status response: {e}"))
+    }
+
+    /// Register a finished job's renditions and captions.
+    /// Returns (variants, captions) registered.
+    async fn register_outputs(
+        &self,
+        zome_caller: &ZomeCaller,
+        blob_hash: &str,
+        content_id: &str,
+        status: TranscodeJobStatus,
+    ) -> (usize, usize) {
+        let mut variants = 0;
+        for rendition in status.renditions {
+            let input = RegisterBlobVariantInput {
+                parent_hash: blob_hash.to_string(),
+                hash: rendition.hash.clone(),
+                resolution: rendition.resolution,
+                width: rendition.width,
+                height: rendition.height,
+                bitrate_mbps: rendition.bitrate_mbps,
+                codec: rendition.codec,
+                size_bytes: rendition.size_bytes,
+                mime_type: rendition.mime_type,
+                fallback_urls: rendition.url.into_iter().collect(),
+            };
+            match zome_caller
+                .call::<_, serde_json::Value>(
+                    CONTENT_ROLE,
+                    CONTENT_ZOME,
+                    "register_blob_variant",
+                    &input,
+                )
+                .await
+            {
+                Ok(_) => variants += 1,
+                Err(e) => {
+                    warn!(blob_hash, variant = %rendition.hash, error = %e, "Failed to register variant")
+                }
+            }
+        }
+
+        let mut captions = 0;
+        for caption in status.captions {
+            let format = caption.format.to_ascii_lowercase();
+            if !CAPTION_FORMATS.contains(&format.as_str()) {
+                debug!(
+                    blob_hash,
+                    format, "Skipping extracted caption in unsupported format"
+                );
+                continue;
+            }
+            let input = AddBlobCaptionInput {
+                blob_hash: blob_hash.to_string(),
+                content_id: content_id.to_string(),
+                language: caption.language,
+                label: caption.label,
+                format,
+                caption_hash: caption.hash.clone(),
+                size_bytes: caption.size_bytes,
+                author_id: None,
+            };
+            match zome_caller
+                .call::<_, serde_json::Value>(
+                    CONTENT_ROLE,
+                    CONTENT_ZOME,
+                    "add_blob_caption",
+                    &input,
+                )
+                .await
+            {
+                Ok(_) => captions += 1,
+                Err(e) => {
+                    warn!(blob_hash, caption = %caption.hash, error = %e, "Failed to register caption")
+                }
+            }
+        }
+
+        (variants, captions)
+    }
+
+    /// Poll pending jobs and register the ones that finished
+    async fn poll(&mut self, zome_caller: &ZomeCaller, summary: &mut TranscodeSummary) {
+        let jobs: Vec<(String, PendingJob)> = self
+            .pending
+            .iter()
+            .map(|(hash, job)| (hash.clone(), job.clone()))
+            .collect();
+
+        for (blob_hash, job) in jobs {
+            let status = match self.status(&job.job_id).await {
+                Ok(status) => status,
+                Err(e) => {
+                    debug!(blob_hash, job_id = %job.job_id, error = %e, "Transcode status unavailable");
+                    continue;
+                }
+            };
+
+            match status.status {
+                TranscodeJobState::Queued | TranscodeJobState::Running => {}
+                TranscodeJobState::Failed => {
+                    warn!(
+                        blob_hash,
+                        job_id = %job.job_id,
+                        error = status.error.as_deref().unwrap_or("unknown"),
+                        "Transcode job failed"
+                    );
+                    self.pending.remove(&blob_hash);
+                    summary.failed += 1;
+                }
+                TranscodeJobState::Completed => {
+                    let (variants, captions) = self
+                        .register_outputs(zome_caller, &blob_hash, &job.content_id, status)
+                        .await;
+                    info!(blob_hash, variants, captions, "Transcode job completed");
+                    self.pending.remove(&blob_hash);
+                    summary.completed += 1;
+                    summary.variants += variants;
+                    summary.captions += captions;
+                }
+            }
+        }
+    }
+
+    /// Run a single pass: poll running jobs, then submit new video blobs.
+    pub async fn run_once(&mut self, zome_caller: &ZomeCaller) -> TranscodeSummary {
+        let mut summary = TranscodeSummary::default();
+        self.poll(zome_caller, &mut summary).await;
+
+        let (snapshots, failed) = scan_all(zome_caller).await;
+        summary.failed += failed;
+
+        for snapshot in &snapshots {
+            for blob in &snapshot.blobs {
+                if summary.submitted >= MAX_SUBMISSIONS_PER_PASS {
+                    return summary;
+                }
+                if !is_video(blob) || self.handled.contains(&blob.hash) {
+                    continue;
+                }
+
+                // Already transcoded (possibly by another doorway)
+                let existing: Vec<serde_json::Value> = match zome_caller
+                    .call(
+                        CONTENT_ROLE,
+                        CONTENT_ZOME,
+                        "get_blob_variants",
+                        &QueryBlobVariantsInput {
+                            blob_hash: blob.hash.clone(),
+                        },
+                    )
+                    .await
+                {
+                    Ok(existing) => existing,
+                    Err(e) => {
+                        debug!(hash = %blob.hash, error = %e, "Variant lookup failed");
+                        continue;
+                    }
+                };
+                if !existing.is_empty() {
+                    self.handled.insert(blob.hash.clone());
+                    continue;
+                }
+
+                let Some(source_url) = source_url(self.config.storage_url.as_deref(), blob) else {
+                    debug!(hash = %blob.hash, "No source URL for video blob");
+                    self.handled.insert(blob.hash.clone());
+                    continue;
+                };
+
+                let job = TranscodeJobRequest {
+                    blob_hash: blob.hash.clone(),
+                    source_url,
+                    mime_type: blob.mime_type.clone(),
+                    renditions: self.config.renditions.clone(),
+                    extract_captions: true,
+                };
+                match self.submit(&job).await {
+                    Ok(job_id) => {
+                        debug!(hash = %blob.hash, job_id, "Transcode job submitted");
+                        self.handled.insert(blob.hash.clone());
+                        self.pending.insert(
+                            blob.hash.clone(),
+                            PendingJob {
+                                job_id,
+                                content_id: snapshot.content_id.clone(),
+                            },
+                        );
+                        summary.submitted += 1;
+                    }
+                    Err(e) => {
+                        // Left unhandled so the next pass retries
+                        warn!(hash = %blob.hash, error = %e, "Transcode submission failed");
+                        summary.failed += 1;
+                    }
+                }
+            }
+        }
+
+        summary
+    }
+}
+
+/// Spawn the periodic transcoding job.
+///
+/// A submission that fails leaves the blob for the next pass; a job the
+/// transcoder reports failed is dropped and not resubmitted until restart.
+pub fn spawn_transcode_task(
+    interval: Duration,
+    zome_caller: Arc<ZomeCaller>,
+    config: TranscodeConfig,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!(
+            interval_secs = interval.as_secs(),
+            url = %config.url,
+            "Transcode task started"
+        );
+
+        let mut transcoder = Transcoder::new(config);
+        loop {
+            tokio::time::sleep(interval).await;
+
+            let summary = transcoder.run_once(&zome_caller).await;
+            info!(
+                submitted = summary.submitted,
+                completed = summary.completed,
+                variants = summary.variants,
+                captions = summary.captions,
+                failed = summary.failed,
+                pending = transcoder.pending.len(),
+                "Transcode pass complete"
+            );
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blob(mime_type: &str) -> BlobFallbacks {
+        BlobFallbacks {
+            hash: "sha256-abc".to_string(),
+            reach: "commons".to_string(),
+            mime_type: mime_type.to_string(),
+            fallback_urls: vec!["https://cdn.example/video.mp4".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_is_video() {
+        assert!(is_video(&blob("video/mp4")));
+        assert!(is_video(&blob("Video/WebM")));
+        assert!(!is_video(&blob("audio/mpeg")));
+        assert!(!is_video(&blob("application/pdf")));
+    }
+
+    #[test]
+    fn test_source_url() {
+        assert_eq!(
+            source_url(Some("http://storage:8090/"), &blob("video/mp4")).as_deref(),
+            Some("http://storage:8090/blob/sha256-abc")
+        );
+        assert_eq!(
+            source_url(None, &blob("video/mp4")).as_deref(),
+            Some("https://cdn.example/video.mp4")
+        );
+
+        let mut bare = blob("video/mp4");
+        bare.fallback_urls.clear();
+        assert_eq!(source_url(None, &bare), None);
+    }
+
+    #[test]
+    fn test_job_status_parsing() {
+        let status: TranscodeJobStatus = serde_json::from_str(
+            r#"{
+                "status": "completed",
+                "renditions": [{
+                    "hash": "sha256-720",
+                    "resolution": "720p",
+                    "width": 1280,
+                    "height": 720,
+                    "bitrate_mbps": 3.0,
+                    "codec": "h264",
+                    "size_bytes": 1000,
+                    "mime_type": "video/mp4",
+                    "url": "https://cdn.example/720.mp4"
+                }],
+                "captions": [{
+                    "hash": "sha256-vtt",
+                    "language": "en",
+                    "label": null,
+                    "format": "vtt",
+                    "size_bytes": 10
+                }]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(status.status, TranscodeJobState::Completed);
+        assert_eq!(status.renditions[0].resolution.as_deref(), Some("720p"));
+        assert_eq!(status.captions[0].language, "en");
+
+        let running: TranscodeJobStatus = serde_json::from_str(r#"{"status": "running"}"#).unwrap();
+        assert_eq!(running.status, TranscodeJobState::Running);
+        assert!(running.renditions.is_empty());
+    }
+}
@@ -122,6 +122,11 @@ export interface BatchGetContentOutput {
   notFound: string[];
 }
 
+/**
+ * Sort order for paginated content queries (keys are stored in link tags)
+ */
+export type ContentSort = 'created_at_desc' | 'created_at_asc' | 'title' | 'estimated_minutes';
+
 /**
  * Input for paginated content query by type
  *
@@ -133,6 +138,7 @@ export interface PaginatedByTypeInput {
   content_type: string;
   page_size: number;
   offset: number;
+  sort?: ContentSort;
 }
 
 /**
@@ -146,6 +152,7 @@ export interface PaginatedByTagInput {
   tag: string;
   page_size: number;
   offset: number;
+  sort?: ContentSort;
 }
 
 /**
@@ -1099,12 +1106,14 @@ export class HolochainContentService {
    * @param contentType The content type to filter by
    * @param pageSize Number of items per page (max 100)
    * @param offset Number of items to skip (for pagination)
+   * @param sort Optional sort order (default: link order)
    * @returns Paginated result with items, total count, and has_more flag
    */
   async getContentByTypePaginated(
     contentType: string,
     pageSize = 20,
-    offset = 0
+    offset = 0,
+    sort?: ContentSort
   ): Promise<{ items: ContentNode[]; totalCount: number; offset: number; hasMore: boolean }> {
     if (!this.isAvailable()) {
       return { items: [], totalCount: 0, offset, hasMore: false };
@@ -1118,6 +1127,7 @@ export class HolochainContentService {
           content_type: contentType,
           page_size: Math.min(pageSize, 100),
           offset,
+          sort,
         } as PaginatedByTypeInput,
       });
 
@@ -1150,12 +1160,14 @@ export class HolochainContentService {
    * @param tag The tag to filter by
    * @param pageSize Number of items per page (max 100)
    * @param offset Number of items to skip
+   * @param sort Optional sort order (default: link order)
    * @returns Paginated result with items, total count, and has_more flag
    */
   async getContentByTagPaginated(
     tag: string,
     pageSize = 20,
-    offset = 0
+    offset = 0,
+    sort?: ContentSort
   ): Promise<{ items: ContentNode[]; totalCount: number; offset: number; hasMore: boolean }> {
     if (!this.isAvailable()) {
       return { items: [], totalCount: 0, offset, hasMore: false };
@@ -1169,6 +1181,7 @@ export class HolochainContentService {
           tag,
           page_size: Math.min(pageSize, 100),
           offset,
+          sort,
         } as PaginatedByTagInput,
       });
 
@@ -146,6 +146,12 @@ pub struct CacheSignal {
     /// Reach level for reach-aware caching
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reach: Option<String>,
+    /// Zome function whose write produced this signal
+    ///
+    /// Doorway looks it up in the DNA's `invalidated_by` rules to evict
+    /// cached reads, including writes that never passed through it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_fn: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -170,6 +176,7 @@ impl CacheSignal {
             ttl_secs: Some(T::cache_ttl()),
             public: entry.is_public(),
             reach: entry.reach().map(|s| s.to_string()),
+            source_fn: None,
         }
     }
 
@@ -183,6 +190,7 @@ impl CacheSignal {
             ttl_secs: None,
             public: false,
             reach: None,
+            source_fn: None,
         }
     }
 
@@ -196,8 +204,29 @@ impl CacheSignal {
             ttl_secs: None,
             public: false,
             reach: None,
+            source_fn: None,
         }
     }
+
+    /// Create an invalidate signal for one entity after a write
+    pub fn written(doc_type: &str, doc_id: &str, source_fn: &str) -> Self {
+        Self {
+            signal_type: CacheSignalType::Invalidate,
+            doc_type: doc_type.to_string(),
+            doc_id: doc_id.to_string(),
+            data: None,
+            ttl_secs: None,
+            public: false,
+            reach: None,
+            source_fn: Some(source_fn.to_string()),
+        }
+    }
+
+    /// Record the zome function whose write produced this signal
+    pub fn from_fn(mut self, source_fn: &str) -> Self {
+        self.source_fn = Some(source_fn.to_string());
+        self
+    }
 }
 
 /// Wrapper for emitting cache signals in a consistent format
@@ -289,8 +318,30 @@ pub struct CacheRule {
 
     /// Function names that invalidate this cache entry
     /// e.g., ["create_content", "update_content", "delete_content"]
+    ///
+    /// "fn.path" names the write input field holding the affected entity ID
+    /// (e.g., "create_relationship.source_id"), so only cached calls whose
+    /// input names that entity are evicted.
     #[serde(default)]
     pub invalidated_by: Vec<String>,
+
+    /// Functions in other DNAs (reached by bridge call) that invalidate this
+    /// cache entry, as "role:fn_name"
+    /// e.g., ["imagodei:upsert_mastery"] for results derived from mastery
+    #[serde(default)]
+    pub bridge_invalidated_by: Vec<String>,
+
+    /// Whether the function's input is the entity ID it returns
+    /// (e.g., get_step_by_id). Writes to one entity then evict only that
+    /// entity's cached response instead of every cached call.
+    #[serde(default)]
+    pub keyed_by_id: bool,
+
+    /// Input fields the cache key is built from, as dotted paths
+    /// e.g., ["content_id"] so calls differing only in other fields share
+    /// one entry. Empty means the whole input.
+    #[serde(default)]
+    pub key_fields: Vec<String>,
 }
 
 fn default_true() -> bool {
@@ -312,6 +363,9 @@ impl CacheRule {
             reach_field: None,
             reach_value: None,
             invalidated_by: vec![],
+            bridge_invalidated_by: vec![],
+            keyed_by_id: false,
+            key_fields: vec![],
         }
     }
 
@@ -325,6 +379,9 @@ impl CacheRule {
             reach_field: None,
             reach_value: None,
             invalidated_by: vec![],
+            bridge_invalidated_by: vec![],
+            keyed_by_id: false,
+            key_fields: vec![],
         }
     }
 }
@@ -406,6 +463,32 @@ impl CacheRuleBuilder {
         self
     }
 
+    /// Set functions in another DNA role that invalidate this cache entry
+    ///
+    /// Use for results that depend on a bridge call, e.g. mastery gating in
+    /// content_store that reads from imagodei.
+    pub fn invalidated_by_bridge(mut self, role: &str, functions: Vec<&str>) -> Self {
+        self.rule
+            .bridge_invalidated_by
+            .extend(functions.into_iter().map(|f| format!("{}:{}", role, f)));
+        self
+    }
+
+    /// Mark the function as taking the entity ID as its only input
+    pub fn keyed_by_id(mut self) -> Self {
+        self.rule.keyed_by_id = true;
+        self
+    }
+
+    /// Build the cache key from these input fields only
+    ///
+    /// Example: `.key_on(vec!["content_id"])` caches one response per
+    /// content ID regardless of other (non-result-affecting) input fields.
+    pub fn key_on(mut self, fields: Vec<&str>) -> Self {
+        self.rule.key_fields = fields.into_iter().map(String::from).collect();
+        self
+    }
+
     /// Disable caching for this function
     pub fn not_cacheable(mut self) -> Self {
         self.rule.cacheable = false;
@@ -734,6 +817,63 @@ mod tests {
         assert_eq!(rule.invalidated_by.len(), 2);
     }
 
+    #[test]
+    fn test_bridge_invalidators() {
+        let rule = CacheRuleBuilder::new("get_path_with_mastery")
+            .ttl_5m()
+            .private()
+            .invalidated_by_bridge("imagodei", vec!["upsert_mastery", "issue_attestation"])
+            .build();
+
+        assert_eq!(
+            rule.bridge_invalidated_by,
+            vec!["imagodei:upsert_mastery", "imagodei:issue_attestation"]
+        );
+        assert!(rule.invalidated_by.is_empty());
+
+        // Rules published before bridge invalidation existed still parse
+        let old: CacheRule = serde_json::from_str(r#"{"fn_name": "get_thing"}"#).unwrap();
+        assert!(old.bridge_invalidated_by.is_empty());
+    }
+
+    #[test]
+    fn test_written_signal() {
+        let signal = CacheSignal::written("PathStep", "step-1", "update_step");
+        assert_eq!(signal.signal_type, CacheSignalType::Invalidate);
+        assert_eq!(signal.doc_id, "step-1");
+        assert_eq!(signal.source_fn.as_deref(), Some("update_step"));
+
+        let json = serde_json::to_value(DoorwaySignal::new(signal)).unwrap();
+        assert_eq!(json["payload"]["source_fn"], "update_step");
+
+        // Signals without a source function omit the field entirely
+        let json = serde_json::to_value(CacheSignal::invalidate("Content")).unwrap();
+        assert!(json.get("source_fn").is_none());
+    }
+
+    #[test]
+    fn test_keyed_by_id_rule() {
+        let rule = CacheRuleBuilder::new("get_step_by_id")
+            .keyed_by_id()
+            .invalidated_by(vec!["update_step"])
+            .build();
+        assert!(rule.keyed_by_id);
+
+        let old: CacheRule = serde_json::from_str(r#"{"fn_name": "get_thing"}"#).unwrap();
+        assert!(!old.keyed_by_id);
+    }
+
+    #[test]
+    fn test_key_fields_rule() {
+        let rule = CacheRuleBuilder::new("get_content")
+            .key_on(vec!["content_id", "opts.locale"])
+            .build();
+        assert_eq!(rule.key_fields, vec!["content_id", "opts.locale"]);
+
+        let old: CacheRule = serde_json::from_str(r#"{"fn_name": "get_thing"}"#).unwrap();
+        assert!(old.key_fields.is_empty());
+    }
+
     #[test]
     fn test_public_rule() {
         let rule = CacheRuleBuilder::new("get_all_paths")
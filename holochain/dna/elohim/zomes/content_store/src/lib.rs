@@ -9,7 +9,7 @@
 use hdk::prelude::*;
 use content_store_integrity::*;
 use doorway_client::{CacheRule, CacheRuleBuilder, CacheSignal, CacheSignalType, DoorwaySignal, Cacheable};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 // Migration module for DNA version upgrades
 pub mod migration;
@@ -23,6 +23,9 @@ pub mod healing_integration;
 // Entry type providers for flexible healing architecture
 pub mod providers;
 
+// Shamir / Reed-Solomon shard encoding for custodian commitments
+pub mod sharding;
+
 // =============================================================================
 // Cross-DNA Bridge Calls to Imagodei
 // =============================================================================
@@ -48,6 +51,65 @@ pub struct UpsertMasteryInput {
     pub content_id: String,
     pub mastery_level: String,
     pub engagement_type: String,
+    /// One AssessmentEvidence object as JSON, appended to the mastery's evidence
+    #[serde(default)]
+    pub evidence_json: Option<String>,
+}
+
+/// Input for one human's mastery of several items (matches imagodei's GetMasteryBatchInput)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GetMasteryBatchInput {
+    pub human_id: String,
+    pub content_ids: Vec<String>,
+}
+
+// -----------------------------------------------------------------------------
+// Per-call bridge memo and circuit breaker
+// -----------------------------------------------------------------------------
+// The conductor instantiates the wasm module for each zome call, so thread-local
+// state lives exactly as long as one call. Step gating and challenge flows ask
+// imagodei for the same mastery several times per call; the memo answers the
+// repeats locally. Once imagodei fails with a network error the breaker opens
+// and later bridge calls in the same zome call fail fast instead of waiting on
+// another timeout.
+
+thread_local! {
+    /// content_id -> mastery as last seen from imagodei during this call
+    static MASTERY_MEMO: std::cell::RefCell<HashMap<String, Option<ContentMasteryOutput>>> =
+        std::cell::RefCell::new(HashMap::new());
+    /// First network error seen from imagodei during this call
+    static IMAGODEI_BREAKER: std::cell::RefCell<Option<String>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// Fail fast if imagodei already failed with a network error in this call
+fn check_imagodei_breaker() -> ExternResult<()> {
+    match IMAGODEI_BREAKER.with(|b| b.borrow().clone()) {
+        Some(err) => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "imagodei unavailable (circuit open): {}", err
+        )))),
+        None => Ok(()),
+    }
+}
+
+/// Open the breaker for the rest of this call
+fn trip_imagodei_breaker(err: &str) {
+    IMAGODEI_BREAKER.with(|b| {
+        let mut b = b.borrow_mut();
+        if b.is_none() {
+            *b = Some(err.to_string());
+        }
+    });
+}
+
+fn memoized_mastery(content_id: &str) -> Option<Option<ContentMasteryOutput>> {
+    MASTERY_MEMO.with(|m| m.borrow().get(content_id).cloned())
+}
+
+fn memoize_mastery(content_id: String, mastery: Option<ContentMasteryOutput>) {
+    MASTERY_MEMO.with(|m| {
+        m.borrow_mut().insert(content_id, mastery);
+    });
 }
 
 /// Helper to get mastery level index (local copy)
@@ -66,25 +128,34 @@ fn get_mastery_level_index(level: &str) -> u32 {
 }
 
 /// Bridge call to get my mastery for a content item from imagodei DNA
+///
+/// Memoized for the duration of the zome call.
 fn get_my_mastery(content_id: String) -> ExternResult<Option<ContentMasteryOutput>> {
+    if let Some(cached) = memoized_mastery(&content_id) {
+        return Ok(cached);
+    }
+    check_imagodei_breaker()?;
+
     let response = call(
         CallTargetCell::OtherRole(IMAGODEI_ROLE.into()),
         IMAGODEI_ZOME,
         "get_my_mastery".into(),
         None,
-        content_id,
+        content_id.clone(),
     )?;
 
     match response {
         ZomeCallResponse::Ok(result) => {
             let output: Option<ContentMasteryOutput> = result.decode()
                 .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Failed to decode mastery: {:?}", e))))?;
+            memoize_mastery(content_id, output.clone());
             Ok(output)
         }
         ZomeCallResponse::Unauthorized(_, _, _, _) => {
             Err(wasm_error!(WasmErrorInner::Guest("Unauthorized call to imagodei".to_string())))
         }
         ZomeCallResponse::NetworkError(err) => {
+            trip_imagodei_breaker(&err);
             Err(wasm_error!(WasmErrorInner::Guest(format!("Network error calling imagodei: {}", err))))
         }
         ZomeCallResponse::CountersigningSession(err) => {
@@ -97,7 +168,11 @@ fn get_my_mastery(content_id: String) -> ExternResult<Option<ContentMasteryOutpu
 }
 
 /// Bridge call to get all mastery records for calling agent
+///
+/// Seeds the per-call memo so later `get_my_mastery` lookups stay local.
 fn get_my_all_mastery(_: ()) -> ExternResult<Vec<ContentMasteryOutput>> {
+    check_imagodei_breaker()?;
+
     let response = call(
         CallTargetCell::OtherRole(IMAGODEI_ROLE.into()),
         IMAGODEI_ZOME,
@@ -110,12 +185,133 @@ fn get_my_all_mastery(_: ()) -> ExternResult<Vec<ContentMasteryOutput>> {
         ZomeCallResponse::Ok(result) => {
             let output: Vec<ContentMasteryOutput> = result.decode()
                 .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Failed to decode mastery list: {:?}", e))))?;
+            for m in &output {
+                memoize_mastery(m.mastery.content_id.clone(), Some(m.clone()));
+            }
             Ok(output)
         }
         ZomeCallResponse::Unauthorized(_, _, _, _) => {
             Err(wasm_error!(WasmErrorInner::Guest("Unauthorized call to imagodei".to_string())))
         }
         ZomeCallResponse::NetworkError(err) => {
+            trip_imagodei_breaker(&err);
+            Err(wasm_error!(WasmErrorInner::Guest(format!("Network error calling imagodei: {}", err))))
+        }
+        ZomeCallResponse::CountersigningSession(err) => {
+            Err(wasm_error!(WasmErrorInner::Guest(format!("Countersigning error: {}", err))))
+        }
+        ZomeCallResponse::AuthenticationFailed(_, _) => {
+            Err(wasm_error!(WasmErrorInner::Guest("Authentication failed calling imagodei".to_string())))
+        }
+    }
+}
+
+/// Bridge call to get my mastery for several content items in one round-trip
+///
+/// Only IDs not already memoized in this call cross the bridge. Every
+/// requested ID is present in the result, `None` if no mastery exists yet.
+fn get_my_mastery_batch(content_ids: Vec<String>) -> ExternResult<HashMap<String, Option<ContentMasteryOutput>>> {
+    let mut results = HashMap::new();
+    let mut missing: Vec<String> = Vec::new();
+    for content_id in content_ids {
+        if results.contains_key(&content_id) || missing.contains(&content_id) {
+            continue;
+        }
+        match memoized_mastery(&content_id) {
+            Some(cached) => {
+                results.insert(content_id, cached);
+            }
+            None => missing.push(content_id),
+        }
+    }
+
+    if missing.is_empty() {
+        return Ok(results);
+    }
+    check_imagodei_breaker()?;
+
+    let response = call(
+        CallTargetCell::OtherRole(IMAGODEI_ROLE.into()),
+        IMAGODEI_ZOME,
+        "get_my_mastery_batch".into(),
+        None,
+        missing.clone(),
+    )?;
+
+    match response {
+        ZomeCallResponse::Ok(result) => {
+            let output: Vec<Option<ContentMasteryOutput>> = result.decode()
+                .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Failed to decode mastery batch: {:?}", e))))?;
+            if output.len() != missing.len() {
+                return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                    "Mastery batch returned {} results for {} content IDs",
+                    output.len(),
+                    missing.len()
+                ))));
+            }
+            for (content_id, mastery) in missing.into_iter().zip(output) {
+                memoize_mastery(content_id.clone(), mastery.clone());
+                results.insert(content_id, mastery);
+            }
+            Ok(results)
+        }
+        ZomeCallResponse::Unauthorized(_, _, _, _) => {
+            Err(wasm_error!(WasmErrorInner::Guest("Unauthorized call to imagodei".to_string())))
+        }
+        ZomeCallResponse::NetworkError(err) => {
+            trip_imagodei_breaker(&err);
+            Err(wasm_error!(WasmErrorInner::Guest(format!("Network error calling imagodei: {}", err))))
+        }
+        ZomeCallResponse::CountersigningSession(err) => {
+            Err(wasm_error!(WasmErrorInner::Guest(format!("Countersigning error: {}", err))))
+        }
+        ZomeCallResponse::AuthenticationFailed(_, _) => {
+            Err(wasm_error!(WasmErrorInner::Guest("Authentication failed calling imagodei".to_string())))
+        }
+    }
+}
+
+/// Bridge call to get another human's mastery for several content items
+///
+/// Not memoized: the per-call memo only holds the calling agent's mastery.
+fn get_mastery_batch_for_human(
+    human_id: String,
+    content_ids: Vec<String>,
+) -> ExternResult<HashMap<String, Option<ContentMasteryOutput>>> {
+    if content_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    check_imagodei_breaker()?;
+
+    let response = call(
+        CallTargetCell::OtherRole(IMAGODEI_ROLE.into()),
+        IMAGODEI_ZOME,
+        "get_mastery_batch".into(),
+        None,
+        GetMasteryBatchInput {
+            human_id,
+            content_ids: content_ids.clone(),
+        },
+    )?;
+
+    match response {
+        ZomeCallResponse::Ok(result) => {
+            let output: Vec<Option<ContentMasteryOutput>> = result.decode()
+                .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Failed to decode mastery batch: {:?}", e))))?;
+            if output.len() != content_ids.len() {
+                return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                    "Mastery batch returned {} results for {} content IDs",
+                    output.len(),
+                    content_ids.len()
+                ))));
+            }
+            Ok(content_ids.into_iter().zip(output).collect())
+        }
+        ZomeCallResponse::Unauthorized(_, _, _, _) => {
+            Err(wasm_error!(WasmErrorInner::Guest("Unauthorized call to imagodei".to_string())))
+        }
+        ZomeCallResponse::NetworkError(err) => {
+            trip_imagodei_breaker(&err);
             Err(wasm_error!(WasmErrorInner::Guest(format!("Network error calling imagodei: {}", err))))
         }
         ZomeCallResponse::CountersigningSession(err) => {
@@ -129,6 +325,8 @@ fn get_my_all_mastery(_: ()) -> ExternResult<Vec<ContentMasteryOutput>> {
 
 /// Bridge call to upsert mastery in imagodei DNA
 fn upsert_mastery(input: UpsertMasteryInput) -> ExternResult<ContentMasteryOutput> {
+    check_imagodei_breaker()?;
+
     let response = call(
         CallTargetCell::OtherRole(IMAGODEI_ROLE.into()),
         IMAGODEI_ZOME,
@@ -141,12 +339,14 @@ fn upsert_mastery(input: UpsertMasteryInput) -> ExternResult<ContentMasteryOutpu
         ZomeCallResponse::Ok(result) => {
             let output: ContentMasteryOutput = result.decode()
                 .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Failed to decode mastery: {:?}", e))))?;
+            memoize_mastery(output.mastery.content_id.clone(), Some(output.clone()));
             Ok(output)
         }
         ZomeCallResponse::Unauthorized(_, _, _, _) => {
             Err(wasm_error!(WasmErrorInner::Guest("Unauthorized call to imagodei".to_string())))
         }
         ZomeCallResponse::NetworkError(err) => {
+            trip_imagodei_breaker(&err);
             Err(wasm_error!(WasmErrorInner::Guest(format!("Network error calling imagodei: {}", err))))
         }
         ZomeCallResponse::CountersigningSession(err) => {
@@ -160,6 +360,8 @@ fn upsert_mastery(input: UpsertMasteryInput) -> ExternResult<ContentMasteryOutpu
 
 /// Bridge call to issue attestation via imagodei DNA
 fn issue_attestation_via_imagodei(input: IssueAttestationBridgeInput) -> ExternResult<AttestationOutput> {
+    check_imagodei_breaker()?;
+
     let response = call(
         CallTargetCell::OtherRole(IMAGODEI_ROLE.into()),
         IMAGODEI_ZOME,
@@ -178,6 +380,41 @@ fn issue_attestation_via_imagodei(input: IssueAttestationBridgeInput) -> ExternR
             Err(wasm_error!(WasmErrorInner::Guest("Unauthorized call to imagodei".to_string())))
         }
         ZomeCallResponse::NetworkError(err) => {
+            trip_imagodei_breaker(&err);
+            Err(wasm_error!(WasmErrorInner::Guest(format!("Network error calling imagodei: {}", err))))
+        }
+        ZomeCallResponse::CountersigningSession(err) => {
+            Err(wasm_error!(WasmErrorInner::Guest(format!("Countersigning error: {}", err))))
+        }
+        ZomeCallResponse::AuthenticationFailed(_, _) => {
+            Err(wasm_error!(WasmErrorInner::Guest("Authentication failed calling imagodei".to_string())))
+        }
+    }
+}
+
+/// Bridge call to list an agent's attestations from imagodei DNA
+fn get_agent_attestations_via_imagodei(agent_id: String) -> ExternResult<Vec<AttestationOutput>> {
+    check_imagodei_breaker()?;
+
+    let response = call(
+        CallTargetCell::OtherRole(IMAGODEI_ROLE.into()),
+        IMAGODEI_ZOME,
+        "get_agent_attestations".into(),
+        None,
+        agent_id,
+    )?;
+
+    match response {
+        ZomeCallResponse::Ok(result) => {
+            let output: Vec<AttestationOutput> = result.decode()
+                .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Failed to decode attestations: {:?}", e))))?;
+            Ok(output)
+        }
+        ZomeCallResponse::Unauthorized(_, _, _, _) => {
+            Err(wasm_error!(WasmErrorInner::Guest("Unauthorized call to imagodei".to_string())))
+        }
+        ZomeCallResponse::NetworkError(err) => {
+            trip_imagodei_breaker(&err);
             Err(wasm_error!(WasmErrorInner::Guest(format!("Network error calling imagodei: {}", err))))
         }
         ZomeCallResponse::CountersigningSession(err) => {
@@ -288,47 +525,87 @@ pub fn __doorway_cache_rules(_: ()) -> ExternResult<Vec<CacheRule>> {
         CacheRuleBuilder::new("get_content")
             .ttl_1h()
             .reach_based("content.reach", "commons")
-            .invalidated_by(vec!["create_content", "bulk_create_content"])
+            .invalidated_by(vec!["create_content", "bulk_create_content", "change_content_reach", "accept_contribution"])
             .build(),
         CacheRuleBuilder::new("get_content_by_id")
             .ttl_1h()
             .reach_based("content.reach", "commons")
-            .invalidated_by(vec!["create_content", "bulk_create_content"])
+            .invalidated_by(vec!["create_content", "bulk_create_content", "change_content_reach", "accept_contribution"])
+            .build(),
+        CacheRuleBuilder::new("get_content_summary_by_id")
+            .ttl_1h()
+            .reach_based("content.reach", "commons")
+            .invalidated_by(vec!["create_content", "bulk_create_content", "change_content_reach", "accept_contribution"])
             .build(),
         CacheRuleBuilder::new("get_content_by_type")
             .ttl_15m()
             .reach_based("content.reach", "commons")
-            .invalidated_by(vec!["create_content", "bulk_create_content"])
+            .invalidated_by(vec!["create_content", "bulk_create_content", "change_content_reach", "accept_contribution"])
             .build(),
         CacheRuleBuilder::new("get_content_by_tag")
             .ttl_15m()
             .reach_based("content.reach", "commons")
-            .invalidated_by(vec!["create_content", "bulk_create_content"])
+            .invalidated_by(vec!["create_content", "bulk_create_content", "change_content_reach", "accept_contribution"])
+            .build(),
+        CacheRuleBuilder::new("get_content_by_license")
+            .ttl_15m()
+            .reach_based("content.reach", "commons")
+            .invalidated_by(vec!["create_content", "bulk_create_content", "change_content_reach", "accept_contribution"])
             .build(),
         CacheRuleBuilder::new("get_content_by_type_paginated")
             .ttl_15m()
             .reach_based("items.content.reach", "commons")
-            .invalidated_by(vec!["create_content", "bulk_create_content"])
+            .invalidated_by(vec!["create_content", "bulk_create_content", "change_content_reach", "accept_contribution"])
             .build(),
         CacheRuleBuilder::new("get_content_by_tag_paginated")
             .ttl_15m()
             .reach_based("items.content.reach", "commons")
-            .invalidated_by(vec!["create_content", "bulk_create_content"])
+            .invalidated_by(vec!["create_content", "bulk_create_content", "change_content_reach", "accept_contribution"])
+            .build(),
+        CacheRuleBuilder::new("get_content_summaries_by_type")
+            .ttl_15m()
+            .reach_based("items.reach", "commons")
+            .invalidated_by(vec!["create_content", "bulk_create_content", "change_content_reach", "accept_contribution"])
+            .build(),
+        CacheRuleBuilder::new("get_content_summaries_by_tag")
+            .ttl_15m()
+            .reach_based("items.reach", "commons")
+            .invalidated_by(vec!["create_content", "bulk_create_content", "change_content_reach", "accept_contribution"])
+            .build(),
+        CacheRuleBuilder::new("query_content")
+            .ttl_15m()
+            .reach_based("items.content.reach", "commons")
+            .invalidated_by(vec!["create_content", "bulk_create_content", "change_content_reach", "accept_contribution"])
             .build(),
         CacheRuleBuilder::new("batch_get_content_by_ids")
             .ttl_1h()
             .reach_based("items.content.reach", "commons")
-            .invalidated_by(vec!["create_content", "bulk_create_content"])
+            .invalidated_by(vec!["create_content", "bulk_create_content", "change_content_reach", "accept_contribution"])
             .build(),
         CacheRuleBuilder::new("get_content_stats")
             .ttl_5m()
             .public()
-            .invalidated_by(vec!["create_content", "bulk_create_content"])
+            .invalidated_by(vec!["create_content", "bulk_create_content", "change_content_reach", "accept_contribution"])
+            .build(),
+        CacheRuleBuilder::new("get_reach_changes_for_content")
+            .ttl_5m()
+            .public()
+            .invalidated_by(vec!["change_content_reach"])
+            .build(),
+        CacheRuleBuilder::new("get_recent_reach_changes")
+            .ttl_5m()
+            .public()
+            .invalidated_by(vec!["change_content_reach"])
             .build(),
         CacheRuleBuilder::new("get_content_graph")
             .ttl_15m()
             .reach_based("root.content.reach", "commons")
-            .invalidated_by(vec!["create_content", "create_relationship"])
+            .invalidated_by(vec!["create_content", "create_relationship", "cite_content", "change_content_reach", "accept_contribution"])
+            .build(),
+        CacheRuleBuilder::new("get_citation_graph")
+            .ttl_15m()
+            .public()
+            .invalidated_by(vec!["create_relationship", "cite_content", "accept_contribution"])
             .build(),
 
         // =====================================================================
@@ -337,37 +614,149 @@ pub fn __doorway_cache_rules(_: ()) -> ExternResult<Vec<CacheRule>> {
         CacheRuleBuilder::new("get_all_paths")
             .ttl_5m()
             .public()
-            .invalidated_by(vec!["create_path", "update_path", "delete_path"])
+            .invalidated_by(vec!["create_path", "update_path", "delete_path", "archive_path", "restore_path"])
+            .build(),
+        CacheRuleBuilder::new("get_all_path_summaries")
+            .ttl_5m()
+            .public()
+            .invalidated_by(vec!["create_path", "update_path", "delete_path", "archive_path", "restore_path"])
+            .build(),
+        CacheRuleBuilder::new("get_archived_paths")
+            .ttl_5m()
+            .invalidated_by(vec!["update_path", "delete_path", "archive_path", "restore_path"])
             .build(),
         CacheRuleBuilder::new("get_path_overview")
             .ttl_15m()
             .public()
-            .invalidated_by(vec!["create_path", "update_path", "delete_path", "add_path_step", "batch_add_path_steps"])
+            .invalidated_by(vec!["create_path", "update_path", "delete_path", "add_path_step", "batch_add_path_steps", "delete_chapter"])
             .build(),
         CacheRuleBuilder::new("get_path_with_steps")
             .ttl_15m()
             .public()
-            .invalidated_by(vec!["create_path", "update_path", "delete_path", "add_path_step", "update_step", "batch_add_path_steps"])
+            .invalidated_by(vec!["create_path", "update_path", "delete_path", "add_path_step", "update_step", "batch_add_path_steps", "delete_chapter", "update_external_resource"])
             .build(),
         CacheRuleBuilder::new("get_path_full")
             .ttl_15m()
             .public()
-            .invalidated_by(vec!["create_path", "update_path", "delete_path", "add_path_step", "create_chapter", "update_chapter", "update_step"])
+            .invalidated_by(vec!["create_path", "update_path", "delete_path", "add_path_step", "create_chapter", "update_chapter", "delete_chapter", "update_step", "update_external_resource"])
             .build(),
         CacheRuleBuilder::new("get_step_by_id")
             .ttl_15m()
+            .keyed_by_id()
+            .public()
+            .invalidated_by(vec!["add_path_step", "update_step", "delete_chapter"])
+            .build(),
+        CacheRuleBuilder::new("get_external_resource")
+            .ttl_1h()
+            .keyed_by_id()
             .public()
-            .invalidated_by(vec!["add_path_step", "update_step"])
+            .invalidated_by(vec!["update_external_resource"])
+            .build(),
+        CacheRuleBuilder::new("get_external_resources_by_status")
+            .ttl_5m()
+            .keyed_by_id()
+            .private()
+            .invalidated_by(vec!["add_path_step", "batch_add_path_steps", "update_external_resource"])
             .build(),
         CacheRuleBuilder::new("get_chapter_by_id")
             .ttl_15m()
+            .keyed_by_id()
             .public()
-            .invalidated_by(vec!["create_chapter", "update_chapter"])
+            .invalidated_by(vec!["create_chapter", "update_chapter", "delete_chapter"])
             .build(),
         CacheRuleBuilder::new("get_chapters_for_path")
             .ttl_15m()
             .public()
-            .invalidated_by(vec!["create_chapter", "update_chapter"])
+            .invalidated_by(vec!["create_chapter", "update_chapter", "delete_chapter"])
+            .build(),
+
+        // =====================================================================
+        // REFLECTIONS (personal journal; never cached)
+        // =====================================================================
+        CacheRuleBuilder::new("get_my_reflections").not_cacheable().build(),
+        CacheRuleBuilder::new("get_learner_reflections").not_cacheable().build(),
+        CacheRuleBuilder::new("get_shared_reflections").not_cacheable().build(),
+        CacheRuleBuilder::new("get_reflection_grants").not_cacheable().build(),
+        CacheRuleBuilder::new("export_learner_data").not_cacheable().build(),
+        CacheRuleBuilder::new("get_notes_export").not_cacheable().build(),
+
+        // =====================================================================
+        // LEARNING SESSIONS (calendars follow schedules and reflection grants)
+        // =====================================================================
+        CacheRuleBuilder::new("get_learner_calendar")
+            .ttl_1m()
+            .private()
+            .invalidated_by(vec![
+                "schedule_learning_session",
+                "cancel_learning_session",
+                "grant_reflection_access",
+                "revoke_reflection_access",
+            ])
+            .build(),
+        CacheRuleBuilder::new("get_learning_sessions_starting").not_cacheable().build(),
+
+        // =====================================================================
+        // ANNOTATIONS (layers follow edits and cohort membership)
+        // =====================================================================
+        CacheRuleBuilder::new("get_annotation")
+            .ttl_1m()
+            .private()
+            .invalidated_by(vec!["update_annotation", "delete_annotation"])
+            .build(),
+        CacheRuleBuilder::new("get_annotations_for_content")
+            .ttl_1m()
+            .private()
+            .invalidated_by(vec![
+                "create_annotation",
+                "update_annotation",
+                "delete_annotation",
+                "grant_reflection_access",
+                "revoke_reflection_access",
+            ])
+            .build(),
+
+        // =====================================================================
+        // BOOKMARKS (each human's library, keyed by their id)
+        // =====================================================================
+        CacheRuleBuilder::new("get_my_bookmarks")
+            .ttl_1m()
+            .keyed_by_id()
+            .private()
+            .invalidated_by(vec!["bookmark_content", "remove_bookmark"])
+            .build(),
+
+        // =====================================================================
+        // MASTERY-GATED (private, answers depend on imagodei mastery via bridge)
+        // =====================================================================
+        CacheRuleBuilder::new("check_step_access")
+            .ttl_1m()
+            .private()
+            .invalidated_by(vec!["submit_mastery_challenge", "submit_checkpoint", "record_engagement", "grant_attestation", "update_step"])
+            .invalidated_by_bridge(IMAGODEI_ROLE, vec!["upsert_mastery", "issue_attestation"])
+            .build(),
+        CacheRuleBuilder::new("check_attestation_eligibility")
+            .ttl_1m()
+            .private()
+            .invalidated_by(vec!["submit_mastery_challenge", "submit_checkpoint", "record_engagement", "grant_attestation"])
+            .invalidated_by_bridge(IMAGODEI_ROLE, vec!["upsert_mastery", "issue_attestation"])
+            .build(),
+        CacheRuleBuilder::new("get_assessment_history")
+            .ttl_1m()
+            .private()
+            .invalidated_by(vec!["submit_mastery_challenge", "submit_checkpoint"])
+            .invalidated_by_bridge(IMAGODEI_ROLE, vec!["upsert_mastery"])
+            .build(),
+        CacheRuleBuilder::new("get_knowledge_map_layout")
+            .ttl_1m()
+            .private()
+            .invalidated_by(vec!["create_knowledge_map", "create_content", "record_engagement"])
+            .invalidated_by_bridge(IMAGODEI_ROLE, vec!["upsert_mastery"])
+            .build(),
+        CacheRuleBuilder::new("get_tutor_context")
+            .ttl_1m()
+            .private()
+            .invalidated_by(vec!["start_path_progress", "complete_step", "update_step", "submit_mastery_challenge", "record_engagement"])
+            .invalidated_by_bridge(IMAGODEI_ROLE, vec!["upsert_mastery"])
             .build(),
 
         // =====================================================================
@@ -376,12 +765,12 @@ pub fn __doorway_cache_rules(_: ()) -> ExternResult<Vec<CacheRule>> {
         CacheRuleBuilder::new("get_relationships")
             .ttl_15m()
             .public()
-            .invalidated_by(vec!["create_relationship"])
+            .invalidated_by(vec!["create_relationship", "accept_contribution"])
             .build(),
         CacheRuleBuilder::new("query_related_content")
             .ttl_15m()
             .public()
-            .invalidated_by(vec!["create_relationship", "create_content"])
+            .invalidated_by(vec!["create_relationship", "create_content", "accept_contribution"])
             .build(),
 
         // =====================================================================
@@ -437,26 +826,29 @@ pub fn __doorway_cache_rules(_: ()) -> ExternResult<Vec<CacheRule>> {
         // =====================================================================
         CacheRuleBuilder::new("get_proposal_by_id")
             .ttl_5m()
+            .keyed_by_id()
             .public()
-            .invalidated_by(vec!["create_proposal"])
+            .invalidated_by(vec!["create_proposal", "tally_proposal"])
             .build(),
         CacheRuleBuilder::new("query_proposals")
             .ttl_5m()
             .public()
-            .invalidated_by(vec!["create_proposal"])
+            .invalidated_by(vec!["create_proposal", "tally_proposal"])
             .build(),
         CacheRuleBuilder::new("get_precedent_by_id")
             .ttl_15m()
+            .keyed_by_id()
             .public()
-            .invalidated_by(vec!["create_precedent"])
+            .invalidated_by(vec!["create_precedent", "tally_proposal"])
             .build(),
         CacheRuleBuilder::new("query_precedents")
             .ttl_15m()
             .public()
-            .invalidated_by(vec!["create_precedent"])
+            .invalidated_by(vec!["create_precedent", "tally_proposal"])
             .build(),
         CacheRuleBuilder::new("get_discussion_by_id")
             .ttl_5m()
+            .keyed_by_id()
             .public()
             .invalidated_by(vec!["create_discussion"])
             .build(),
@@ -481,6 +873,7 @@ pub fn __doorway_cache_rules(_: ()) -> ExternResult<Vec<CacheRule>> {
         // =====================================================================
         CacheRuleBuilder::new("get_knowledge_map_by_id")
             .ttl_15m()
+            .keyed_by_id()
             .public()
             .invalidated_by(vec!["create_knowledge_map"])
             .build(),
@@ -491,6 +884,7 @@ pub fn __doorway_cache_rules(_: ()) -> ExternResult<Vec<CacheRule>> {
             .build(),
         CacheRuleBuilder::new("get_path_extension_by_id")
             .ttl_15m()
+            .keyed_by_id()
             .public()
             .invalidated_by(vec!["create_path_extension"])
             .build(),
@@ -536,7 +930,17 @@ pub fn __doorway_cache_rules(_: ()) -> ExternResult<Vec<CacheRule>> {
         CacheRuleBuilder::new("get_steward_revenue_summary")
             .ttl_1m()
             .public()
-            .invalidated_by(vec!["grant_access"])
+            .invalidated_by(vec!["grant_access", "grant_paid_access", "renew_access"])
+            .build(),
+        CacheRuleBuilder::new("get_voucher_redemption_report")
+            .ttl_1m()
+            .private()
+            .invalidated_by(vec!["create_voucher_batch", "redeem_voucher"])
+            .build(),
+        CacheRuleBuilder::new("get_invitations_by_inviter")
+            .ttl_1m()
+            .private()
+            .invalidated_by(vec!["create_invitation", "redeem_invitation", "revoke_invitation"])
             .build(),
 
         // =====================================================================
@@ -545,7 +949,7 @@ pub fn __doorway_cache_rules(_: ()) -> ExternResult<Vec<CacheRule>> {
         CacheRuleBuilder::new("get_blobs_by_content_id")
             .ttl_15m()
             .reach_based("blobs.reach", "commons")
-            .invalidated_by(vec!["create_content", "bulk_create_content"])
+            .invalidated_by(vec!["create_content", "bulk_create_content", "add_blob_fallback_url"])
             .build(),
         CacheRuleBuilder::new("verify_blob_integrity")
             .ttl_1h()
@@ -555,12 +959,93 @@ pub fn __doorway_cache_rules(_: ()) -> ExternResult<Vec<CacheRule>> {
         CacheRuleBuilder::new("get_blob_variants")
             .ttl_15m()
             .reach_based("variants.reach", "commons")
-            .invalidated_by(vec!["create_content"])
+            .invalidated_by(vec!["create_content", "register_blob_variant"])
             .build(),
         CacheRuleBuilder::new("get_blob_captions")
             .ttl_15m()
             .reach_based("captions.reach", "commons")
-            .invalidated_by(vec!["create_content"])
+            .invalidated_by(vec!["create_content", "add_blob_caption"])
+            .build(),
+        CacheRuleBuilder::new("get_content_translations")
+            .ttl_15m()
+            .public()
+            .invalidated_by(vec!["add_translation", "publish_translation"])
+            .build(),
+        CacheRuleBuilder::new("get_content_localized")
+            .ttl_1h()
+            .reach_based("content.reach", "commons")
+            .invalidated_by(vec![
+                "create_content",
+                "bulk_create_content",
+                "change_content_reach",
+                "add_translation",
+                "publish_translation",
+            ])
+            .build(),
+        CacheRuleBuilder::new("get_pending_translations")
+            .ttl_5m()
+            .private()
+            .invalidated_by(vec!["add_translation", "publish_translation"])
+            .build(),
+
+        // =====================================================================
+        // QUESTION BANK (answers included - never public)
+        // =====================================================================
+        CacheRuleBuilder::new("get_assessment_items")
+            .ttl_15m()
+            .private()
+            .invalidated_by(vec!["create_assessment_item", "publish_assessment_item", "reject_assessment_item"])
+            .build(),
+        CacheRuleBuilder::new("get_pending_assessment_items")
+            .ttl_5m()
+            .private()
+            .invalidated_by(vec!["create_assessment_item", "publish_assessment_item", "reject_assessment_item"])
+            .build(),
+
+        // =====================================================================
+        // PEER REVIEW (reviewer assignments and rubric scores - private)
+        // =====================================================================
+        CacheRuleBuilder::new("get_peer_review_request")
+            .ttl_1m()
+            .keyed_by_id()
+            .private()
+            .invalidated_by(vec!["assign_peer_reviewer", "submit_peer_review", "cancel_peer_review_request"])
+            .build(),
+        CacheRuleBuilder::new("get_open_peer_review_requests")
+            .ttl_1m()
+            .private()
+            .invalidated_by(vec!["request_peer_review", "assign_peer_reviewer", "cancel_peer_review_request"])
+            .build(),
+        CacheRuleBuilder::new("get_my_peer_review_assignments")
+            .ttl_1m()
+            .private()
+            .invalidated_by(vec!["request_peer_review", "assign_peer_reviewer", "submit_peer_review", "cancel_peer_review_request"])
+            .build(),
+        CacheRuleBuilder::new("get_peer_reviews")
+            .ttl_1m()
+            .keyed_by_id()
+            .private()
+            .invalidated_by(vec!["submit_peer_review"])
+            .build(),
+
+        // =====================================================================
+        // CONTRIBUTIONS (steward review queue - private)
+        // =====================================================================
+        CacheRuleBuilder::new("get_pending_contributions")
+            .ttl_1m()
+            .private()
+            .invalidated_by(vec!["submit_contribution", "accept_contribution", "reject_contribution"])
+            .build(),
+        CacheRuleBuilder::new("get_contributions_for_content")
+            .ttl_1m()
+            .keyed_by_id()
+            .private()
+            .invalidated_by(vec!["submit_contribution", "accept_contribution", "reject_contribution"])
+            .build(),
+        CacheRuleBuilder::new("get_my_contributions")
+            .ttl_1m()
+            .private()
+            .invalidated_by(vec!["submit_contribution", "accept_contribution", "reject_contribution"])
             .build(),
 
         // =====================================================================
@@ -603,12 +1088,44 @@ pub fn __doorway_cache_rules(_: ()) -> ExternResult<Vec<CacheRule>> {
         CacheRuleBuilder::new("get_insurance_claim")
             .ttl_1m()
             .private()
-            .invalidated_by(vec!["create_insurance_claim"])
+            .invalidated_by(vec![
+                "create_insurance_claim",
+                "submit_claim_evidence",
+                "assign_adjuster",
+                "adjudicate_claim",
+                "record_claim_payment",
+            ])
             .build(),
-        CacheRuleBuilder::new("get_adjustment_reasoning")
-            .ttl_5m()
+        CacheRuleBuilder::new("get_claims_by_status")
+            .ttl_1m()
             .private()
-            .invalidated_by(vec!["create_adjustment_reasoning"])
+            .invalidated_by(vec![
+                "create_insurance_claim",
+                "submit_claim_evidence",
+                "assign_adjuster",
+                "adjudicate_claim",
+                "record_claim_payment",
+            ])
+            .build(),
+        CacheRuleBuilder::new("compute_pool_solvency")
+            .ttl_1m()
+            .private()
+            .invalidated_by(vec!["create_member_risk_profile", "adjudicate_claim", "record_claim_payment"])
+            .build(),
+        CacheRuleBuilder::new("get_latest_solvency_snapshot")
+            .ttl_5m()
+            .private()
+            .invalidated_by(vec!["record_solvency_snapshot"])
+            .build(),
+        CacheRuleBuilder::new("get_solvency_snapshots")
+            .ttl_5m()
+            .private()
+            .invalidated_by(vec!["record_solvency_snapshot"])
+            .build(),
+        CacheRuleBuilder::new("get_adjustment_reasoning")
+            .ttl_5m()
+            .private()
+            .invalidated_by(vec!["create_adjustment_reasoning", "adjudicate_claim"])
             .build(),
 
         // =====================================================================
@@ -627,7 +1144,17 @@ pub fn __doorway_cache_rules(_: ()) -> ExternResult<Vec<CacheRule>> {
         CacheRuleBuilder::new("get_service_match")
             .ttl_1m()
             .private()
-            .invalidated_by(vec!["create_service_match"])
+            .invalidated_by(vec!["create_service_match", "suggest_service_matches", "confirm_service_match"])
+            .build(),
+        CacheRuleBuilder::new("find_matches")
+            .ttl_1m()
+            .private()
+            .invalidated_by(vec![
+                "create_service_request",
+                "create_service_offer",
+                "create_service_match",
+                "suggest_service_matches",
+            ])
             .build(),
 
         // =====================================================================
@@ -641,6 +1168,15 @@ pub fn __doorway_cache_rules(_: ()) -> ExternResult<Vec<CacheRule>> {
     ])
 }
 
+/// Tell doorways that a write happened so they can evict cached reads.
+///
+/// Doorway maps `source_fn` through the `invalidated_by` rules above, so this
+/// also covers writes made by local apps or through other doorways. Best
+/// effort: a failed signal only means a cached read lives until its TTL.
+fn emit_write_signal(doc_type: &str, doc_id: &str, source_fn: &str) {
+    let _ = emit_signal(DoorwaySignal::new(CacheSignal::written(doc_type, doc_id, source_fn)));
+}
+
 // =============================================================================
 // Doorway Import Config (zome-declared import capabilities)
 // =============================================================================
@@ -900,6 +1436,13 @@ pub struct QueryByTypeInput {
     pub limit: Option<u32>,
 }
 
+/// Input for querying content by license identifier (e.g. "CC-BY-SA-4.0")
+#[derive(Serialize, Deserialize, Debug)]
+pub struct QueryByLicenseInput {
+    pub license: String,
+    pub limit: Option<u32>,
+}
+
 /// Input for querying content by ID
 #[derive(Serialize, Deserialize, Debug)]
 pub struct QueryByIdInput {
@@ -926,6 +1469,7 @@ pub struct BlobMetadataOutput {
     pub bitrate_mbps: Option<f64>,
     pub duration_seconds: Option<u32>,
     pub codec: Option<String>,
+    pub reach: String,
     pub created_at: Option<String>,
     pub verified_at: Option<String>,
 }
@@ -951,6 +1495,114 @@ pub struct QueryBlobVariantsInput {
     pub blob_hash: String,
 }
 
+/// Input for registering an alternate rendition of a blob
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RegisterBlobVariantInput {
+    pub parent_hash: String,
+    pub hash: String,
+    pub resolution: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub bitrate_mbps: f32,
+    pub codec: String,
+    pub size_bytes: u64,
+    pub mime_type: String,
+    pub fallback_urls: Vec<String>,
+}
+
+/// Output for a blob variant
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlobVariantOutput {
+    pub action_hash: ActionHash,
+    pub variant: BlobVariant,
+}
+
+/// Input for querying caption tracks of a blob
+#[derive(Serialize, Deserialize, Debug)]
+pub struct QueryBlobCaptionsInput {
+    pub blob_hash: String,
+    /// Only tracks in this language; matches the primary subtag too ("en" finds "en-GB")
+    pub language: Option<String>,
+}
+
+/// Input for attaching a caption track to a blob
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AddBlobCaptionInput {
+    pub blob_hash: String,
+    pub content_id: String,
+    pub language: String,
+    pub label: Option<String>,
+    pub format: String,
+    pub caption_hash: String,
+    pub size_bytes: u64,
+    pub author_id: Option<String>,
+}
+
+/// Output for a caption track
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlobCaptionOutput {
+    pub action_hash: ActionHash,
+    pub caption: BlobCaption,
+}
+
+// =============================================================================
+// Input/Output Types for Translations
+// =============================================================================
+
+/// Input for adding (or replacing) a content translation
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AddTranslationInput {
+    pub content_id: String,
+    pub locale: String,
+    pub title: String,
+    pub description: String,
+    pub body: Option<String>,
+    pub translator_id: Option<String>,
+    /// Produced by a machine-translation provider
+    #[serde(default)]
+    pub machine_generated: bool,
+    /// Store for steward review instead of publishing
+    #[serde(default)]
+    pub draft: bool,
+}
+
+/// Input for publishing a reviewed draft translation
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PublishTranslationInput {
+    pub content_id: String,
+    pub locale: String,
+}
+
+/// Output for a content translation
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ContentTranslationOutput {
+    pub action_hash: ActionHash,
+    pub translation: ContentTranslation,
+}
+
+/// Input for reading content in the caller's language
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetContentLocalizedInput {
+    pub id: String,
+    /// Preferred locale (BCP 47)
+    pub locale: String,
+    /// Locales to try, in order, when `locale` has no translation
+    #[serde(default)]
+    pub fallback_chain: Vec<String>,
+}
+
+/// Content with translated fields applied
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LocalizedContentOutput {
+    pub action_hash: ActionHash,
+    pub entry_hash: EntryHash,
+    pub content: Content,
+    /// Locale of the translation served; None when the original is served
+    pub locale: Option<String>,
+    /// Every locale a translation exists for
+    pub available_locales: Vec<String>,
+}
+
 // =============================================================================
 // Input/Output Types for Relationships
 // =============================================================================
@@ -1005,6 +1657,58 @@ pub struct ContentGraph {
     pub total_nodes: u32,
 }
 
+/// Input for citing one content from another
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CiteContentInput {
+    pub source_id: String,          // The citing content
+    pub cited_id: String,           // The content being cited
+    pub locator: Option<String>,    // Where in the cited work: "p. 42", "ch. 3", "00:12:30"
+    pub note: Option<String>,       // Quoted passage or why it is cited
+}
+
+/// Output for a citation
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CitationOutput {
+    pub relationship: RelationshipOutput,
+    pub presence_id: String,        // Presence credited for the cited content
+    pub citation_count: u32,        // The presence's citation count after this citation
+}
+
+/// Input for walking the citation graph
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetCitationGraphInput {
+    pub content_id: String,
+    pub direction: Option<String>,  // cites, cited_by, both (default)
+    pub depth: Option<u32>,         // Hops from the root (default 2, max 5)
+}
+
+/// Content in a citation graph
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CitationNode {
+    pub content_id: String,
+    pub title: Option<String>,      // None if the content is not on this network
+    pub depth: u32,
+}
+
+/// One citation in a citation graph
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CitationEdge {
+    pub source_id: String,
+    pub cited_id: String,
+    pub locator: Option<String>,
+    pub note: Option<String>,
+    pub created_at: String,
+}
+
+/// Citation graph around a content node
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CitationGraph {
+    pub root_id: String,
+    pub nodes: Vec<CitationNode>,
+    pub edges: Vec<CitationEdge>,
+    pub truncated: bool,            // Node limit reached before the requested depth
+}
+
 // =============================================================================
 // Input/Output Types for Humans
 // =============================================================================
@@ -1101,6 +1805,276 @@ pub struct AgentProgressOutput {
     pub progress: AgentProgress,
 }
 
+/// Output for a reflection
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReflectionOutput {
+    pub action_hash: ActionHash,
+    pub reflection: ReflectionEntry,
+}
+
+/// Input for journaling a reflection on a step
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateReflectionInput {
+    /// Learner writing the reflection; the calling agent when absent
+    pub human_id: Option<String>,
+    pub path_id: String,
+    pub step_index: u32,
+    pub prompt: Option<String>,
+    pub response: String,
+}
+
+/// Input for listing a learner's reflections
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LearnerReflectionsInput {
+    /// Learner whose reflections to list; the calling agent when absent
+    pub human_id: Option<String>,
+    /// Only reflections on this path; all paths when absent
+    pub path_id: Option<String>,
+}
+
+/// Input for granting or revoking a facilitator's access to reflections
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReflectionGrantInput {
+    /// Learner making the grant; the calling agent when absent
+    pub human_id: Option<String>,
+    pub facilitator_id: String,
+    pub path_id: String,
+}
+
+/// Output for a reflection share grant
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReflectionGrantOutput {
+    pub action_hash: ActionHash,
+    pub grant: ReflectionShareGrant,
+}
+
+/// Input for a facilitator reading reflections shared with them
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SharedReflectionsInput {
+    /// Facilitator reading; the calling agent when absent
+    pub facilitator_id: Option<String>,
+    pub learner_id: String,
+    pub path_id: String,
+}
+
+/// Everything held for a learner, for the learner data export
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LearnerDataExport {
+    pub learner_id: String,
+    pub exported_at: String,
+    pub reflections: Vec<ReflectionOutput>,
+    pub reflection_grants: Vec<ReflectionGrantOutput>,
+}
+
+/// Input for compiling a learner's notes export
+#[derive(Serialize, Deserialize, Debug)]
+pub struct NotesExportInput {
+    /// Agent whose path progress (and its step notes) is read
+    pub agent_id: String,
+    /// Human whose reflections, bookmarks and annotations are read
+    pub human_id: String,
+}
+
+/// A path step the learner wrote about
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NotesExportStep {
+    pub step_index: u32,
+    /// None when the step no longer exists on the path
+    pub title: Option<String>,
+    /// Content the step presents, for content steps
+    pub content_id: Option<String>,
+    pub note: Option<String>,
+    pub reflections: Vec<ReflectionEntry>,
+}
+
+/// A path with the steps the learner wrote about
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NotesExportPath {
+    pub path_id: String,
+    pub title: String,
+    pub steps: Vec<NotesExportStep>,
+}
+
+/// A relationship from one exported content item to another
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NotesExportLink {
+    pub content_id: String,
+    pub relationship_type: String,
+}
+
+/// A content item the learner wrote about, annotated or saved
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NotesExportItem {
+    pub content_id: String,
+    pub title: String,
+    pub bookmark_folder: Option<String>,
+    pub annotations: Vec<Annotation>,
+    /// Outgoing relationships to other items in the export
+    pub related: Vec<NotesExportLink>,
+}
+
+/// A learner's notes, reflections, bookmarks and annotations, for the notes export
+#[derive(Serialize, Deserialize, Debug)]
+pub struct NotesExport {
+    pub learner_id: String,
+    pub exported_at: String,
+    pub paths: Vec<NotesExportPath>,
+    pub items: Vec<NotesExportItem>,
+}
+
+/// Input for scheduling a learning session
+///
+/// Only the doorway calls this, for the signed-in learner or facilitator.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ScheduleLearningSessionInput {
+    pub organizer_id: String,
+    /// "study" or "cohort" (LEARNING_SESSION_KINDS)
+    pub kind: String,
+    pub path_id: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub location: Option<String>,
+    pub starts_at_micros: i64,
+    pub duration_minutes: u32,
+}
+
+/// Input for cancelling a learning session
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CancelLearningSessionInput {
+    pub session_id: String,
+    pub organizer_id: String,
+}
+
+/// Output for a learning session
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LearningSessionOutput {
+    pub action_hash: ActionHash,
+    pub session: LearningSession,
+}
+
+/// Window of session start times, in microseconds
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LearningSessionWindowInput {
+    pub from_micros: i64,
+    pub until_micros: i64,
+}
+
+/// A scheduled session and everyone it is for
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UpcomingLearningSession {
+    pub session: LearningSession,
+    /// The organizer, and for cohort sessions the learners sharing the path
+    pub attendee_ids: Vec<String>,
+}
+
+/// Input for annotating content
+///
+/// Only the doorway calls this, for the signed-in learner. Replies name
+/// `reply_to` and take their content, path, range and visibility from it.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateAnnotationInput {
+    pub author_id: String,
+    #[serde(default)]
+    pub content_id: String,
+    #[serde(default)]
+    pub path_id: Option<String>,
+    /// W3C Web Annotation selector, as JSON
+    #[serde(default)]
+    pub selector_json: String,
+    /// Motivation (ANNOTATION_MOTIVATIONS)
+    pub motivation: String,
+    #[serde(default)]
+    pub body: String,
+    #[serde(default)]
+    pub reply_to: Option<String>,
+    /// Visibility (ANNOTATION_VISIBILITIES)
+    #[serde(default = "default_annotation_visibility")]
+    pub visibility: String,
+}
+
+fn default_annotation_visibility() -> String {
+    "private".to_string()
+}
+
+/// Input for editing an annotation; fields left out are kept
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UpdateAnnotationInput {
+    pub annotation_id: String,
+    pub author_id: String,
+    #[serde(default)]
+    pub body: Option<String>,
+    #[serde(default)]
+    pub selector_json: Option<String>,
+    #[serde(default)]
+    pub visibility: Option<String>,
+}
+
+/// Input for deleting an annotation
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DeleteAnnotationInput {
+    pub annotation_id: String,
+    pub author_id: String,
+}
+
+/// Input for the annotations a reader sees on a content node
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetAnnotationsInput {
+    pub content_id: String,
+    pub viewer_id: String,
+    /// Path being studied; adds the cohort layer for it
+    #[serde(default)]
+    pub path_id: Option<String>,
+}
+
+/// Input for reading one annotation
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetAnnotationInput {
+    pub annotation_id: String,
+    pub viewer_id: String,
+}
+
+/// Output for an annotation
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AnnotationOutput {
+    pub action_hash: ActionHash,
+    pub annotation: Annotation,
+}
+
+/// Input for saving content to a human's library
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BookmarkContentInput {
+    /// Owner of the library; the calling agent when absent
+    pub human_id: Option<String>,
+    pub content_id: String,
+    /// Folder to file it in; DEFAULT_BOOKMARK_FOLDER when absent
+    #[serde(default)]
+    pub folder: Option<String>,
+}
+
+/// Input for removing content from a human's library
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RemoveBookmarkInput {
+    /// Owner of the library; the calling agent when absent
+    pub human_id: Option<String>,
+    pub content_id: String,
+}
+
+/// Output for a bookmark
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BookmarkOutput {
+    pub action_hash: ActionHash,
+    pub bookmark: Bookmark,
+}
+
+/// A folder of a human's library
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BookmarkFolder {
+    pub folder: String,
+    pub bookmarks: Vec<BookmarkOutput>,
+}
+
 /// Input for updating agent progress
 #[derive(Serialize, Deserialize, Debug)]
 pub struct UpdateAgentProgressInput {
@@ -1277,6 +2251,47 @@ pub struct PathOverview {
 pub struct PathStepOutput {
     pub action_hash: ActionHash,
     pub step: PathStep,
+    /// Resolved step content (only when requested with include_content)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<ContentOutput>,
+    /// Enriched metadata for external steps (only with include_content)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external: Option<ExternalResource>,
+}
+
+/// Input for path reads: a bare path ID, or an ID with options
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(untagged)]
+pub enum PathReadInput {
+    Id(String),
+    WithOptions {
+        path_id: String,
+        /// Also fetch the Content each step points to (default false)
+        #[serde(default)]
+        include_content: bool,
+    },
+}
+
+impl PathReadInput {
+    fn path_id(&self) -> &str {
+        match self {
+            PathReadInput::Id(id) => id,
+            PathReadInput::WithOptions { path_id, .. } => path_id,
+        }
+    }
+
+    fn include_content(&self) -> bool {
+        match self {
+            PathReadInput::Id(_) => false,
+            PathReadInput::WithOptions { include_content, .. } => *include_content,
+        }
+    }
+}
+
+impl From<String> for PathReadInput {
+    fn from(path_id: String) -> Self {
+        PathReadInput::Id(path_id)
+    }
 }
 
 /// Input for creating a chapter
@@ -1345,6 +2360,44 @@ pub struct UpdateChapterInput {
     pub mastery_threshold: Option<u32>,
 }
 
+/// Input for deleting a chapter
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DeleteChapterInput {
+    pub chapter_id: String,
+    /// Chapter in the same path to receive the steps; None moves them to the
+    /// path's ungrouped steps
+    pub reassign_to: Option<String>,
+}
+
+/// Output for a deleted chapter
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DeleteChapterOutput {
+    pub chapter_id: String,
+    pub reassigned_to: Option<String>,
+    /// The moved steps, renumbered after the steps already there
+    pub moved_steps: Vec<PathStepOutput>,
+}
+
+/// Output for an external resource
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ExternalResourceOutput {
+    pub action_hash: ActionHash,
+    pub resource: ExternalResource,
+}
+
+/// Input for recording enrichment results; unset fields keep their value
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UpdateExternalResourceInput {
+    pub url: String,
+    pub status: String,
+    pub provider: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub thumbnail_url: Option<String>,
+    pub oembed_json: Option<String>,
+    pub snapshot_hash: Option<String>,
+}
+
 /// Input for updating a step
 #[derive(Serialize, Deserialize, Debug)]
 pub struct UpdateStepInput {
@@ -1495,6 +2548,21 @@ pub struct GenerateShardsOutput {
     pub total_shards: u32,
     pub shard_hashes: Vec<String>, // Hash of each shard for verification
     pub content_hash: String,      // Hash of complete content
+    /// Base64 shard payloads in index order; shard_hashes[i] is the hash of shards[i]
+    pub shards: Vec<String>,
+}
+
+/// Input for reassembling content from shards gathered by the client
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReassembleShardsInput {
+    pub content_id: String,
+    pub shard_strategy: String,
+    pub redundancy_factor: u32,
+    /// Base64 shard payloads as produced by generate_shards (decrypted)
+    pub shards: Vec<String>,
+    /// content_hash from generate_shards, if the caller kept it
+    #[serde(default)]
+    pub expected_content_hash: Option<String>,
 }
 
 /// Input for storing a shard on-chain
@@ -1553,12 +2621,46 @@ pub struct GetShardOutput {
 /// Emergency trigger types for activation
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct EmergencyTriggerSpec {
-    pub trigger_type: String,           // manual_signal|trusted_party|m_of_n_consensus
+    pub trigger_type: String,           // manual_signal|trusted_party|m_of_n_consensus|dead_mans_switch
     pub enabled: bool,
     pub passphrase_hash: Option<String>, // For manual_signal
     pub trusted_agent_ids: Option<Vec<String>>, // For trusted_party
     pub consensus_m: Option<u32>,       // M-of-N: M custodians needed
     pub consensus_n: Option<u32>,       // M-of-N: N total custodians
+    pub inactivity_days: Option<u32>,   // For dead_mans_switch: days without beneficiary check-in
+}
+
+/// Default dead man's switch window when a trigger doesn't set inactivity_days
+pub const DEFAULT_INACTIVITY_DAYS: u32 = 90;
+
+/// Configure the dead man's switch on a commitment (beneficiary only)
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ConfigureDeadMansSwitchInput {
+    pub commitment_id: String,
+    pub enabled: bool,
+    pub inactivity_days: u32,
+}
+
+/// Dead man's switch state for a commitment
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DeadMansSwitchStatus {
+    pub commitment_id: String,
+    pub beneficiary_agent_id: String,
+    pub enabled: bool,
+    pub inactivity_days: u32,
+    /// Last beneficiary check-in (micros), or when the commitment was written if none yet
+    pub last_checkin_micros: i64,
+    pub overdue: bool,
+    pub commitment_state: String,
+}
+
+/// Result of a custodian heartbeat
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CustodianHeartbeatOutput {
+    pub commitment_id: String,
+    pub heartbeat_micros: i64,
+    /// Dead man's switch state, so custodians learn about a silent beneficiary
+    pub dead_mans_switch: Option<DeadMansSwitchStatus>,
 }
 
 /// Manual signal activation (passphrase-based immediate activation)
@@ -1606,6 +2708,13 @@ pub struct ConsensusVoteStatus {
     pub activation_status: String,      // pending|approved|rejected
 }
 
+/// One entry of a commitment's shard_assignments_json
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ShardAssignment {
+    pub shard_index: u32,
+    pub custodian_agent_id: String,
+}
+
 /// Shard reconstruction for emergency recovery
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ReconstructContentInput {
@@ -1624,6 +2733,16 @@ pub struct ReconstructContentOutput {
     pub error_message: Option<String>,
 }
 
+/// Shards available for one content item ahead of reconstruction
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RecoveryShardStatus {
+    pub content_id: String,
+    pub shards_available: u32,          // Distinct shard indexes that pass their hash check
+    pub shards_required: u32,
+    pub custodian_agent_ids: Vec<String>, // Custodians holding those shards
+    pub ready: bool,
+}
+
 // =============================================================================
 // Phase 6: Category-based Overrides
 // =============================================================================
@@ -1692,6 +2811,16 @@ pub struct ValidateCategoryAccessOutput {
     pub category_type: Option<String>,
     pub access_level: Option<String>,
     pub reason: String,
+    #[serde(default)]
+    pub commitment_id: Option<String>,
+    #[serde(default)]
+    pub attestation_id: Option<String>, // Imagodei attestation the specialist was admitted on
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CategoryAccessLogOutput {
+    pub action_hash: ActionHash,
+    pub log: CategoryAccessLog,
 }
 
 /// Input for granting attestation
@@ -1721,7 +2850,7 @@ pub const MASTERY_LEVELS: [&str; 8] = [
 ];
 
 /// Engagement types for mastery tracking
-pub const ENGAGEMENT_TYPES: [&str; 8] = [
+pub const ENGAGEMENT_TYPES: [&str; 9] = [
     "view",         // Viewed content
     "quiz",         // Completed quiz
     "practice",     // Did practice exercise
@@ -1730,6 +2859,7 @@ pub const ENGAGEMENT_TYPES: [&str; 8] = [
     "contribute",   // Contributed to content
     "path_step",    // Completed as path step
     "refresh",      // Refreshed stale mastery
+    "tutor",        // Discussed with the conversational tutor
 ];
 
 /// Input for initializing mastery tracking
@@ -1822,14 +2952,27 @@ pub fn create_content(input: CreateContentInput) -> ExternResult<ContentOutput>
     }
 
     // Delegate to unchecked version (caller verified uniqueness)
-    create_content_unchecked(input)
+    let output = create_content_unchecked(input)?;
+    increment_content_counter(&output.content.content_type, 1)?;
+    emit_write_signal("Content", &output.content.id, "create_content");
+    Ok(output)
 }
 
 /// Internal: Create content without existence check.
 /// Used by batch import when caller has already verified IDs don't exist.
 /// This avoids O(n) existence checks when processing import chunks.
 fn create_content_unchecked(input: CreateContentInput) -> ExternResult<ContentOutput> {
-    let agent_info = agent_info()?;
+    create_content_authored(input, None)
+}
+
+/// Internal: Create content credited to `author_id` (the calling agent when
+/// None), without existence check
+fn create_content_authored(input: CreateContentInput, author_id: Option<String>) -> ExternResult<ContentOutput> {
+    let license = content_license(&input.metadata_json)?;
+    let author_id = match author_id {
+        Some(author_id) => author_id,
+        None => agent_info()?.agent_initial_pubkey.to_string(),
+    };
     let now = sys_time()?;
     let timestamp = format!("{:?}", now);
 
@@ -1844,7 +2987,7 @@ fn create_content_unchecked(input: CreateContentInput) -> ExternResult<ContentOu
         tags: input.tags.clone(),
         source_path: input.source_path,
         related_node_ids: input.related_node_ids,
-        author_id: Some(agent_info.agent_initial_pubkey.to_string()),
+        author_id: Some(author_id),
         reach: input.reach,
         trust_score: 0.0,
         estimated_minutes: input.estimated_minutes,
@@ -1868,14 +3011,25 @@ fn create_content_unchecked(input: CreateContentInput) -> ExternResult<ContentOu
     let entry_hash = hash_entry(&EntryTypes::Content(content.clone()))?;
 
     // Create index links
-    // Note: Only ID and tag links are created to reduce write amplification.
-    // Type and author can be filtered client-side from cached data.
+    // Note: Author links are not created to reduce write amplification.
     // See: holochain/ESCALATION-validation-integration.md - Write Amplification section
     create_id_to_content_link(&input.id, &action_hash)?;
 
+    // Type and tag links carry sort keys so paginated listings can order
+    // results without fetching every entry
+    let sort_tag = ContentLinkTag::from_content(&content, &now);
+    create_type_to_content_link(&input.content_type, &action_hash, &sort_tag)?;
+
     // Tag links enable content discovery by topic
     for tag in &input.tags {
-        create_tag_to_content_link(tag, &action_hash)?;
+        create_tag_to_content_link(tag, &action_hash, &sort_tag)?;
+    }
+
+    // License links let exporters select redistributable content
+    if let Some(ref license) = license {
+        for id in license_ids(license) {
+            create_license_to_content_link(&id, &action_hash)?;
+        }
     }
 
     Ok(ContentOutput {
@@ -1905,6 +3059,12 @@ pub fn bulk_create_content(input: BulkCreateContentInput) -> ExternResult<BulkCr
         }
     }
 
+    if !action_hashes.is_empty() {
+        let _ = emit_signal(DoorwaySignal::new(
+            CacheSignal::invalidate("Content").from_fn("bulk_create_content"),
+        ));
+    }
+
     Ok(BulkCreateContentOutput {
         import_id: input.import_id,
         created_count: action_hashes.len() as u32,
@@ -1951,6 +3111,22 @@ pub struct QueueImportInput {
 
     /// Schema version for the items
     pub schema_version: u32,
+
+    /// Signed manifest from the upstream system, already verified by the doorway
+    #[serde(default)]
+    pub provenance: Option<ImportProvenanceInput>,
+}
+
+/// Upstream signature over an import manifest (see ImportProvenance)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportProvenanceInput {
+    pub source: String,
+    pub public_key: String,
+    pub signature: String,
+    pub blob_hash: String,
+    pub total_items: u32,
+    #[serde(default)]
+    pub verified_by: Option<String>,
 }
 
 /// Output from queuing an import batch
@@ -2009,6 +3185,27 @@ pub fn queue_import(input: QueueImportInput) -> ExternResult<QueueImportOutput>
     // Create index links
     create_import_batch_index_links(&input.id, &action_hash, &batch.status, &agent_info.agent_initial_pubkey.to_string())?;
 
+    // Keep the upstream signature alongside the batch for later audits
+    if let Some(provenance) = input.provenance {
+        let provenance = ImportProvenance {
+            batch_id: input.id.clone(),
+            source: provenance.source,
+            public_key: provenance.public_key,
+            signature: provenance.signature,
+            blob_hash: provenance.blob_hash,
+            total_items: provenance.total_items,
+            verified_by: provenance.verified_by,
+            recorded_at: timestamp.clone(),
+        };
+        let provenance_hash = create_entry(&EntryTypes::ImportProvenance(provenance))?;
+        create_link(
+            import_batch_anchor_hash(&input.id)?,
+            provenance_hash,
+            LinkTypes::ImportBatchToProvenance,
+            (),
+        )?;
+    }
+
     // Emit signal so elohim-store knows to start sending chunks
     emit_signal(ProjectionSignal::ImportBatchQueued {
         batch_id: input.id.clone(),
@@ -2232,6 +3429,9 @@ pub fn process_import_chunk(input: ProcessImportChunkInput) -> ExternResult<Proc
             let existing_check = check_content_ids_exist(CheckIdsExistInput { ids: all_ids })?;
             let existing_set: std::collections::HashSet<_> = existing_check.existing_ids.into_iter().collect();
 
+            // Per-type counts for this chunk, written as one counter link per type
+            let mut created_by_type: HashMap<String, i64> = HashMap::new();
+
             // Process only NEW items (skip existing)
             for content_input in items {
                 // Skip items that already exist - no source chain writes needed
@@ -2247,6 +3447,7 @@ pub fn process_import_chunk(input: ProcessImportChunkInput) -> ExternResult<Proc
                     Ok(output) => {
                         // Link content to this batch for traceability
                         create_import_batch_link(&input.batch_id, &output.action_hash)?;
+                        *created_by_type.entry(output.content.content_type).or_insert(0) += 1;
                         chunk_processed += 1;
                     }
                     Err(e) => {
@@ -2260,6 +3461,10 @@ pub fn process_import_chunk(input: ProcessImportChunkInput) -> ExternResult<Proc
                     }
                 }
             }
+
+            for (content_type, delta) in created_by_type {
+                increment_content_counter(&content_type, delta)?;
+            }
         }
     }
 
@@ -2293,6 +3498,9 @@ pub fn process_import_chunk(input: ProcessImportChunkInput) -> ExternResult<Proc
 
     // Update the batch entry
     update_entry(batch_action_hash.clone(), &EntryTypes::ImportBatch(batch.clone()))?;
+    if input.is_final {
+        emit_write_signal("ImportBatch", &input.batch_id, "process_import_chunk");
+    }
 
     // Emit progress signal
     if input.is_final {
@@ -2333,9 +3541,7 @@ fn create_import_batch_index_links(
     author_id: &str,
 ) -> ExternResult<()> {
     // IdToImportBatch
-    let id_anchor = StringAnchor::new("import_batch", batch_id);
-    let id_anchor_hash = hash_entry(&EntryTypes::StringAnchor(id_anchor))?;
-    create_link(id_anchor_hash, action_hash.clone(), LinkTypes::IdToImportBatch, ())?;
+    create_link(import_batch_anchor_hash(batch_id)?, action_hash.clone(), LinkTypes::IdToImportBatch, ())?;
 
     // AuthorToImportBatches
     let author_anchor = StringAnchor::new("import_batch_author", author_id);
@@ -2350,14 +3556,31 @@ fn create_import_batch_index_links(
     Ok(())
 }
 
+/// Anchor for an import batch's ID
+fn import_batch_anchor_hash(batch_id: &str) -> ExternResult<EntryHash> {
+    hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("import_batch", batch_id)))
+}
+
+/// Get the signed upstream provenance of an import batch, if it was signed
+#[hdk_extern]
+pub fn get_import_provenance(batch_id: String) -> ExternResult<Option<ImportProvenance>> {
+    let query = LinkQuery::try_new(import_batch_anchor_hash(&batch_id)?, LinkTypes::ImportBatchToProvenance)?;
+    let links = get_links(query, GetStrategy::default())?;
+
+    let Some(action_hash) = links.last().and_then(|link| link.target.clone().into_action_hash()) else {
+        return Ok(None);
+    };
+    match get(action_hash, GetOptions::default())? {
+        Some(record) => record.entry().to_app_option().map_err(|e| wasm_error!(e)),
+        None => Ok(None),
+    }
+}
+
 /// Get the status of an import batch by ID
 #[hdk_extern]
 pub fn get_import_status(batch_id: String) -> ExternResult<Option<ImportBatch>> {
     // Look up via IdToImportBatch link
-    let id_anchor = StringAnchor::new("import_batch", &batch_id);
-    let id_anchor_hash = hash_entry(&EntryTypes::StringAnchor(id_anchor))?;
-
-    let query = LinkQuery::try_new(id_anchor_hash, LinkTypes::IdToImportBatch)?;
+    let query = LinkQuery::try_new(import_batch_anchor_hash(&batch_id)?, LinkTypes::IdToImportBatch)?;
     let links = get_links(query, GetStrategy::default())?;
 
     if links.is_empty() {
@@ -2479,6 +3702,23 @@ pub fn get_content_by_id(input: QueryByIdInput) -> ExternResult<Option<ContentOu
     }
 }
 
+/// Get content by string ID without its body
+///
+/// Same output as get_content_by_id with `content` emptied, so a reader can
+/// show a long item's header before its body arrives. `content_size_bytes`
+/// carries the inline body's size; the body itself is paged from doorway's
+/// `GET /content/{id}/body`, or from elohim-storage when `blob_cid` is set.
+#[hdk_extern]
+pub fn get_content_summary_by_id(input: QueryByIdInput) -> ExternResult<Option<ContentOutput>> {
+    Ok(get_content_by_id(input)?.map(|mut output| {
+        let body = std::mem::take(&mut output.content.content);
+        if output.content.blob_cid.is_none() {
+            output.content.content_size_bytes = Some(body.len() as u64);
+        }
+        output
+    }))
+}
+
 /// Input for batch ID existence check
 #[derive(Serialize, Deserialize, Debug)]
 pub struct CheckIdsExistInput {
@@ -2589,12 +3829,138 @@ pub fn get_content_by_tag(tag: String) -> ExternResult<Vec<ContentOutput>> {
     Ok(results)
 }
 
+// =============================================================================
+// Content Licenses
+// =============================================================================
+//
+// A content's license lives in its metadata as `{"license": "<expression>"}`,
+// an SPDX license expression such as `CC-BY-SA-4.0` or `MIT OR Apache-2.0`.
+// Custom terms use `LicenseRef-<name>`. Doorway decides which licenses allow
+// commons redistribution when exporting or federating.
+
+/// Longest license identifier accepted
+const MAX_LICENSE_ID_LEN: usize = 64;
+
+/// License expression recorded in content metadata, if any.
+/// Metadata that isn't a JSON object carries no license; a `license` that
+/// isn't a valid SPDX-style expression is rejected.
+fn content_license(metadata_json: &str) -> ExternResult<Option<String>> {
+    let Ok(metadata) = serde_json::from_str::<serde_json::Value>(metadata_json) else {
+        return Ok(None);
+    };
+    match metadata.get("license") {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(serde_json::Value::String(license)) if is_license_expression(license.trim()) => {
+            Ok(Some(license.trim().to_string()))
+        }
+        Some(other) => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Invalid license {}: expected an SPDX identifier or expression such as \"CC-BY-SA-4.0\"",
+            other
+        )))),
+    }
+}
+
+fn is_license_id(token: &str) -> bool {
+    let id = token.strip_suffix('+').unwrap_or(token);
+    !id.is_empty()
+        && id.len() <= MAX_LICENSE_ID_LEN
+        && id.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+}
+
+/// Whether `expression` is identifiers joined by AND / OR / WITH.
+/// Parentheses are accepted but not checked for balance.
+fn is_license_expression(expression: &str) -> bool {
+    let spaced = expression.replace('(', " ( ").replace(')', " ) ");
+    let tokens: Vec<&str> = spaced.split_whitespace().filter(|t| *t != "(" && *t != ")").collect();
+    !tokens.is_empty()
+        && tokens.len() % 2 == 1
+        && tokens.iter().enumerate().all(|(i, token)| {
+            if i % 2 == 0 {
+                is_license_id(token)
+            } else {
+                matches!(*token, "AND" | "OR" | "WITH")
+            }
+        })
+}
+
+/// License identifiers in an expression (exceptions after WITH excluded),
+/// lowercased since SPDX identifiers match case-insensitively
+fn license_ids(expression: &str) -> Vec<String> {
+    let spaced = expression.replace(['(', ')'], " ");
+    let mut ids: Vec<String> = Vec::new();
+    let mut after_with = false;
+    for token in spaced.split_whitespace() {
+        match token {
+            "AND" | "OR" => after_with = false,
+            "WITH" => after_with = true,
+            id if !after_with => {
+                let id = id.to_ascii_lowercase();
+                if !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+            _ => after_with = false,
+        }
+    }
+    ids
+}
+
+fn get_license_links(license_id: &str) -> ExternResult<Vec<Link>> {
+    let anchor = StringAnchor::new("license", &license_id.to_ascii_lowercase());
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
+    let query = LinkQuery::try_new(anchor_hash, LinkTypes::LicenseToContent)?;
+    get_links(query, GetStrategy::default())
+}
+
+/// Get content released under a license identifier (using LicenseToContent links).
+/// Matches content whose license expression names the identifier anywhere,
+/// so "MIT" also finds "MIT OR Apache-2.0".
+#[hdk_extern]
+pub fn get_content_by_license(input: QueryByLicenseInput) -> ExternResult<Vec<ContentOutput>> {
+    let license = input.license.trim();
+    if !is_license_id(license) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Invalid license identifier: {}",
+            input.license
+        ))));
+    }
+
+    let links = get_license_links(license)?;
+    let limit = input.limit.unwrap_or(100) as usize;
+    let mut results = Vec::new();
+
+    for link in links.iter().take(limit) {
+        let action_hash = ActionHash::try_from(link.target.clone())
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid action hash in link".to_string())))?;
+
+        if let Some(output) = get_content(action_hash)? {
+            results.push(output);
+        }
+    }
+
+    Ok(results)
+}
+
+/// Sort order for paginated content listings.
+/// Keys come from the link tag, so ordering happens before any entry is fetched.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentSort {
+    CreatedAtDesc,                     // Newest first
+    CreatedAtAsc,                      // Oldest first
+    Title,                             // Alphabetical (case-insensitive)
+    EstimatedMinutes,                  // Shortest first, unknown durations last
+}
+
 /// Input for paginated content query by type
 #[derive(Serialize, Deserialize, Debug)]
 pub struct PaginatedByTypeInput {
     pub content_type: String,          // Filter by type
     pub page_size: u32,                // Number of items per page (max 100)
     pub offset: u32,                   // Number of items to skip
+    #[serde(default)]
+    pub sort: Option<ContentSort>,     // None = link order
 }
 
 /// Output for paginated content query
@@ -2614,7 +3980,10 @@ pub fn get_content_by_type_paginated(input: PaginatedByTypeInput) -> ExternResul
     let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
 
     let query = LinkQuery::try_new(anchor_hash, LinkTypes::TypeToContent)?;
-    let links = get_links(query, GetStrategy::default())?;
+    let mut links = get_links(query, GetStrategy::default())?;
+    if let Some(sort) = input.sort {
+        sort_content_links(&mut links, sort);
+    }
 
     let total_count = links.len() as u32;
     let page_size = (input.page_size.min(100)) as usize; // Cap at 100
@@ -2646,6 +4015,8 @@ pub struct PaginatedByTagInput {
     pub tag: String,                   // Filter by tag
     pub page_size: u32,                // Number of items per page (max 100)
     pub offset: u32,                   // Number of items to skip
+    #[serde(default)]
+    pub sort: Option<ContentSort>,     // None = link order
 }
 
 /// Get content by tag with pagination support
@@ -2655,7 +4026,10 @@ pub fn get_content_by_tag_paginated(input: PaginatedByTagInput) -> ExternResult<
     let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
 
     let query = LinkQuery::try_new(anchor_hash, LinkTypes::TagToContent)?;
-    let links = get_links(query, GetStrategy::default())?;
+    let mut links = get_links(query, GetStrategy::default())?;
+    if let Some(sort) = input.sort {
+        sort_content_links(&mut links, sort);
+    }
 
     let total_count = links.len() as u32;
     let page_size = (input.page_size.min(100)) as usize;
@@ -2681,1692 +4055,2210 @@ pub fn get_content_by_tag_paginated(input: PaginatedByTagInput) -> ExternResult<
     })
 }
 
-/// List all content created by the current agent
-#[hdk_extern]
-pub fn get_my_content(_: ()) -> ExternResult<Vec<ContentOutput>> {
-    let agent_info = agent_info()?;
-
-    let query = LinkQuery::try_new(agent_info.agent_initial_pubkey, LinkTypes::AuthorToContent)?;
-    let links = get_links(query, GetStrategy::default())?;
+/// Content listing summary decoded from a link tag (no entry fetch)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ContentSummary {
+    pub action_hash: ActionHash,
+    pub id: String,
+    pub title: String,
+    pub content_type: String,
+    pub reach: String,
+    pub estimated_minutes: Option<u32>,
+    pub updated_at: String,
+}
 
-    let mut results = Vec::new();
+/// Output for paginated summary listings
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PaginatedSummaryOutput {
+    pub items: Vec<ContentSummary>,
+    pub total_count: u32,
+    pub offset: u32,
+    pub has_more: bool,
+}
 
-    for link in links {
-        let action_hash = ActionHash::try_from(link.target)
-            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid action hash in link".to_string())))?;
+/// Listing variant of get_content_by_type_paginated that reads only link tags.
+/// Use for list views; fetch full content with get_content for detail views.
+#[hdk_extern]
+pub fn get_content_summaries_by_type(input: PaginatedByTypeInput) -> ExternResult<PaginatedSummaryOutput> {
+    let anchor = StringAnchor::new("content_type", &input.content_type);
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
 
-        if let Some(output) = get_content(action_hash)? {
-            results.push(output);
-        }
+    let query = LinkQuery::try_new(anchor_hash, LinkTypes::TypeToContent)?;
+    let mut links = get_links(query, GetStrategy::default())?;
+    if let Some(sort) = input.sort {
+        sort_content_links(&mut links, sort);
     }
 
-    Ok(results)
+    paginate_content_summaries(&links, input.page_size, input.offset)
 }
 
-/// Get content statistics (counts by type)
+/// Listing variant of get_content_by_tag_paginated that reads only link tags.
 #[hdk_extern]
-pub fn get_content_stats(_: ()) -> ExternResult<ContentStats> {
-    let filter = ChainQueryFilter::new()
-        .entry_type(UnitEntryTypes::Content.try_into()?);
+pub fn get_content_summaries_by_tag(input: PaginatedByTagInput) -> ExternResult<PaginatedSummaryOutput> {
+    let mut links = get_tag_links(&input.tag)?;
+    if let Some(sort) = input.sort {
+        sort_content_links(&mut links, sort);
+    }
 
-    let records = query(filter)?;
+    paginate_content_summaries(&links, input.page_size, input.offset)
+}
 
-    let mut by_type: HashMap<String, u32> = HashMap::new();
+/// Build a summary page from content index links.
+/// Links written before summaries were stored in tags fall back to a `get`.
+fn paginate_content_summaries(
+    links: &[Link],
+    page_size: u32,
+    offset: u32,
+) -> ExternResult<PaginatedSummaryOutput> {
+    let page_size = (page_size.min(100)) as usize;
+    let skip = offset as usize;
 
-    for record in &records {
-        if let Some(content) = record
-            .entry()
-            .to_app_option::<Content>()
-            .ok()
-            .flatten()
-        {
-            *by_type.entry(content.content_type).or_insert(0) += 1;
+    let mut items = Vec::new();
+    for link in links.iter().skip(skip).take(page_size) {
+        let action_hash = ActionHash::try_from(link.target.clone())
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid action hash in link".to_string())))?;
+
+        let tag = ContentLinkTag::from_link(link);
+        if !tag.id.is_empty() {
+            items.push(ContentSummary {
+                action_hash,
+                id: tag.id,
+                title: tag.title,
+                content_type: tag.content_type,
+                reach: tag.reach,
+                estimated_minutes: tag.estimated_minutes,
+                updated_at: tag.updated_at,
+            });
+        } else if let Some(output) = get_content(action_hash.clone())? {
+            items.push(ContentSummary {
+                action_hash,
+                id: output.content.id,
+                title: output.content.title,
+                content_type: output.content.content_type,
+                reach: output.content.reach,
+                estimated_minutes: output.content.estimated_minutes,
+                updated_at: output.content.updated_at,
+            });
         }
     }
 
-    Ok(ContentStats {
-        total_count: records.len() as u32,
-        by_type,
+    Ok(PaginatedSummaryOutput {
+        has_more: skip + page_size < links.len(),
+        items,
+        total_count: links.len() as u32,
+        offset,
     })
 }
 
-// =============================================================================
-// Blob Operations (Media Distribution - Phase 1)
-// =============================================================================
+/// Input for combined content filters.
+/// At least one indexed filter (content_type, tags_all, tags_any) is required;
+/// reach, author and created_after narrow the indexed candidates.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct QueryContentInput {
+    #[serde(default)]
+    pub content_type: Option<String>,  // TypeToContent index
+    #[serde(default)]
+    pub tags_all: Vec<String>,         // Content must carry every tag
+    #[serde(default)]
+    pub tags_any: Vec<String>,         // Content must carry at least one tag
+    #[serde(default)]
+    pub reach: Option<String>,         // Exact reach level
+    #[serde(default)]
+    pub author: Option<String>,        // Author agent pubkey (base64)
+    #[serde(default)]
+    pub created_after: Option<i64>,    // Microseconds since epoch
+    #[serde(default)]
+    pub sort: Option<ContentSort>,
+    pub page_size: u32,                // Number of items per page (max 100)
+    #[serde(default)]
+    pub offset: u32,
+}
 
-/// Get all blobs associated with a content ID.
-/// Returns metadata for all blobs attached to content for download/playback.
+/// Query content by intersecting index link sets (type AND tags AND reach ...).
+/// Filtering happens on links and their tags; entries are only fetched for the
+/// returned page.
 #[hdk_extern]
-pub fn get_blobs_by_content_id(input: QueryBlobsByContentIdInput) -> ExternResult<Vec<BlobMetadataOutput>> {
-    // First, find content by ID
-    let anchor = StringAnchor::new("content_id", &input.content_id);
-    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
+pub fn query_content(input: QueryContentInput) -> ExternResult<PaginatedContentOutput> {
+    if input.content_type.is_none() && input.tags_all.is_empty() && input.tags_any.is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "query_content requires content_type, tags_all or tags_any".to_string()
+        )));
+    }
 
-    let query = LinkQuery::try_new(anchor_hash, LinkTypes::IdToContent)?;
-    let links = get_links(query, GetStrategy::default())?;
+    let mut candidates: Option<Vec<Link>> = None;
 
-    if links.is_empty() {
-        return Ok(Vec::new());
+    if let Some(content_type) = &input.content_type {
+        let anchor = StringAnchor::new("content_type", content_type);
+        let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
+        let query = LinkQuery::try_new(anchor_hash, LinkTypes::TypeToContent)?;
+        candidates = Some(intersect_links(candidates, get_links(query, GetStrategy::default())?));
     }
 
-    // Get the content record
-    let action_hash = ActionHash::try_from(links[0].target.clone())
-        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid action hash in link".to_string())))?;
+    for tag in &input.tags_all {
+        candidates = Some(intersect_links(candidates, get_tag_links(tag)?));
+    }
 
-    let record = get(action_hash, GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Content not found".to_string())))?;
+    if !input.tags_any.is_empty() {
+        let mut any_links: Vec<Link> = Vec::new();
+        let mut seen = HashSet::new();
+        for tag in &input.tags_any {
+            for link in get_tag_links(tag)? {
+                if seen.insert(link.target.clone()) {
+                    any_links.push(link);
+                }
+            }
+        }
+        candidates = Some(intersect_links(candidates, any_links));
+    }
 
-    let content: Content = record
-        .entry()
-        .to_app_option()
-        .map_err(|e| wasm_error!(e))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not deserialize content".to_string())))?;
-
-    let content_hash = links[0].target.clone();
+    let mut links: Vec<Link> = candidates
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|link| {
+            input.author.as_ref().map_or(true, |author| link.author.to_string() == *author)
+                && input.created_after.map_or(true, |after| link.timestamp.as_micros() > after)
+        })
+        .collect();
 
-    // Query ContentToBlobs links from this content
-    let query = LinkQuery::try_new(content_hash, LinkTypes::ContentToBlobs)?;
-    let blob_links = get_links(query, GetStrategy::default())?;
+    if let Some(reach) = &input.reach {
+        let mut matching = Vec::with_capacity(links.len());
+        for link in links {
+            let tag = ContentLinkTag::from_link(&link);
+            let link_reach = if !tag.reach.is_empty() {
+                tag.reach
+            } else {
+                // Legacy links without a reach key: resolve from the entry
+                let action_hash = ActionHash::try_from(link.target.clone())
+                    .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid action hash in link".to_string())))?;
+                match get_content(action_hash)? {
+                    Some(output) => output.content.reach,
+                    None => continue,
+                }
+            };
+            if link_reach == *reach {
+                matching.push(link);
+            }
+        }
+        links = matching;
+    }
 
-    if blob_links.is_empty() {
-        return Ok(Vec::new());
+    if let Some(sort) = input.sort {
+        sort_content_links(&mut links, sort);
     }
 
-    // Fetch each blob entry
-    let mut results = Vec::new();
+    let total_count = links.len() as u32;
+    let page_size = (input.page_size.min(100)) as usize;
+    let offset = input.offset as usize;
 
-    for link in blob_links {
-        let blob_action_hash = ActionHash::try_from(link.target)
-            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid action hash in blob link".to_string())))?;
+    let mut items = Vec::new();
+    for link in links.iter().skip(offset).take(page_size) {
+        let action_hash = ActionHash::try_from(link.target.clone())
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid action hash in link".to_string())))?;
 
-        if let Some(record) = get(blob_action_hash, GetOptions::default())? {
-            if let Ok(Some(blob)) = record.entry().to_app_option::<BlobEntry>() {
-                results.push(BlobMetadataOutput {
-                    hash: blob.hash,
-                    size_bytes: blob.size_bytes,
-                    mime_type: blob.mime_type,
-                    fallback_urls: blob.fallback_urls,
-                    bitrate_mbps: blob.bitrate_mbps.map(|b| b as f64),
-                    duration_seconds: blob.duration_seconds,
-                    codec: blob.codec,
-                    created_at: Some(blob.created_at),
-                    verified_at: blob.verified_at,
-                });
-            }
+        if let Some(output) = get_content(action_hash)? {
+            items.push(output);
         }
     }
 
-    Ok(results)
+    // Missing entries shrink the page, so compare against the window, not what was found
+    let has_more = offset + page_size < links.len();
+
+    Ok(PaginatedContentOutput {
+        items,
+        total_count,
+        offset: input.offset,
+        has_more,
+    })
 }
 
-/// Verify that a blob hash is valid and matches expected content.
-/// Used during download to detect corruption.
-#[hdk_extern]
-pub fn verify_blob_integrity(input: VerifyBlobIntegrityInput) -> ExternResult<BlobIntegrityCheckOutput> {
-    let start_time = std::time::SystemTime::now();
+fn get_tag_links(tag: &str) -> ExternResult<Vec<Link>> {
+    let anchor = StringAnchor::new("tag", tag);
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
+    let query = LinkQuery::try_new(anchor_hash, LinkTypes::TagToContent)?;
+    get_links(query, GetStrategy::default())
+}
 
-    // In Phase 1, we store the expected hash in the content metadata
-    // The actual verification happens on the client side (BlobVerificationService)
-    // This function is mainly for caching and validation purposes
+/// Keep links from `current` whose target also appears in `next`.
+/// With no current set, `next` (deduplicated by target) becomes the set.
+fn intersect_links(current: Option<Vec<Link>>, next: Vec<Link>) -> Vec<Link> {
+    match current {
+        None => {
+            let mut seen = HashSet::new();
+            next.into_iter().filter(|link| seen.insert(link.target.clone())).collect()
+        }
+        Some(current) => {
+            let targets: HashSet<AnyLinkableHash> = next.into_iter().map(|link| link.target).collect();
+            current.into_iter().filter(|link| targets.contains(&link.target)).collect()
+        }
+    }
+}
 
-    // Find content by ID
-    let anchor = StringAnchor::new("content_id", &input.content_id);
-    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
+/// List all content created by the current agent
+#[hdk_extern]
+pub fn get_my_content(_: ()) -> ExternResult<Vec<ContentOutput>> {
+    let agent_info = agent_info()?;
 
-    let query = LinkQuery::try_new(anchor_hash, LinkTypes::IdToContent)?;
+    let query = LinkQuery::try_new(agent_info.agent_initial_pubkey, LinkTypes::AuthorToContent)?;
     let links = get_links(query, GetStrategy::default())?;
 
-    if links.is_empty() {
-        return Err(wasm_error!(WasmErrorInner::Guest(
-            "Content not found".to_string()
-        )));
+    let mut results = Vec::new();
+
+    for link in links {
+        let action_hash = ActionHash::try_from(link.target)
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid action hash in link".to_string())))?;
+
+        if let Some(output) = get_content(action_hash)? {
+            results.push(output);
+        }
     }
 
-    // Check if blob with this hash exists in our system
-    let blob_anchor = StringAnchor::new("blob_hash", &input.blob_hash);
-    let blob_anchor_hash = hash_entry(&EntryTypes::StringAnchor(blob_anchor))?;
+    Ok(results)
+}
 
-    let blob_query = LinkQuery::try_new(blob_anchor_hash, LinkTypes::IdToBlob)?;
-    let blob_links = get_links(blob_query, GetStrategy::default())?;
+/// Input for get_content_stats
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct ContentStatsInput {
+    /// Repair mode: scan this agent's source chain, correct this agent's
+    /// counter contributions to match, and return the chain-scan counts
+    #[serde(default)]
+    pub recount: bool,
+}
 
-    // Blob is valid if it exists in DHT and hash matches content
-    let is_valid = !blob_links.is_empty();
+/// Get content statistics (counts by type)
+/// Reads the per-type counter links; pass `{ recount: true }` to repair counters
+/// from a full source chain scan.
+#[hdk_extern]
+pub fn get_content_stats(input: Option<ContentStatsInput>) -> ExternResult<ContentStats> {
+    if input.unwrap_or_default().recount {
+        return recount_content_stats();
+    }
 
-    let elapsed = start_time
-        .elapsed()
-        .map(|d| d.as_millis() as u64)
-        .unwrap_or(0);
+    let mut by_type: HashMap<String, u32> = HashMap::new();
+    for (content_type, count) in sum_content_counters(None)? {
+        if count > 0 {
+            by_type.insert(content_type, count as u32);
+        }
+    }
 
-    Ok(BlobIntegrityCheckOutput {
-        blob_hash: input.blob_hash,
-        is_valid,
-        verification_time_ms: elapsed,
+    Ok(ContentStats {
+        total_count: by_type.values().sum(),
+        by_type,
     })
 }
 
-/// Get all variants of a blob (different bitrates, resolutions).
-/// Used for adaptive streaming - returns available quality options.
-#[hdk_extern]
-pub fn get_blob_variants(input: QueryBlobVariantsInput) -> ExternResult<Vec<BlobMetadataOutput>> {
-    // In Phase 1, variants are stored alongside the main blob
-    // This would query a dedicated entry type for blob variants
-    // For now, return empty as the full model is in progress
+/// Full source chain scan; also writes correcting deltas for this agent's counters
+fn recount_content_stats() -> ExternResult<ContentStats> {
+    let filter = ChainQueryFilter::new()
+        .entry_type(UnitEntryTypes::Content.try_into()?);
+
+    let records = query(filter)?;
+
+    let mut by_type: HashMap<String, u32> = HashMap::new();
+
+    for record in &records {
+        if let Some(content) = record
+            .entry()
+            .to_app_option::<Content>()
+            .ok()
+            .flatten()
+        {
+            *by_type.entry(content.content_type).or_insert(0) += 1;
+        }
+    }
 
-    // TODO: Query BlobVariant entries by parent blob hash
-    // Return all variants sorted by bitrate
+    // Bring this agent's counter contributions in line with its chain
+    let me = agent_info()?.agent_initial_pubkey;
+    let mut counted = sum_content_counters(Some(&me))?;
+    for (content_type, actual) in &by_type {
+        let current = counted.remove(content_type).unwrap_or(0);
+        if current != *actual as i64 {
+            increment_content_counter(content_type, *actual as i64 - current)?;
+        }
+    }
+    for (content_type, stale) in counted {
+        if stale != 0 {
+            increment_content_counter(&content_type, -stale)?;
+        }
+    }
 
-    Ok(Vec::new())
+    Ok(ContentStats {
+        total_count: records.len() as u32,
+        by_type,
+    })
 }
 
-/// Get captions/subtitles for a blob.
-/// Returns all available caption tracks for a video.
-#[hdk_extern]
-pub fn get_blob_captions(input: QueryBlobVariantsInput) -> ExternResult<Vec<BlobMetadataOutput>> {
-    // Query caption tracks for a specific blob
-    // Used for subtitle/caption support in players
+/// Delta stored in a ContentCounter link tag
+#[derive(Serialize, Deserialize, Debug)]
+struct ContentCounterTag {
+    content_type: String,
+    delta: i64,
+}
 
-    // TODO: Query BlobCaption entries by parent blob hash
-    // Return captions sorted by language/format
+fn content_counter_anchor_hash() -> ExternResult<EntryHash> {
+    hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("content_stats", "counters")))
+}
+
+/// Add `delta` to the counter for a content type (one link per call)
+fn increment_content_counter(content_type: &str, delta: i64) -> ExternResult<()> {
+    let type_anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("content_type", content_type)))?;
+    let tag = serde_json::to_vec(&ContentCounterTag {
+        content_type: content_type.to_string(),
+        delta,
+    })
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Failed to encode counter tag: {}", e))))?;
+
+    create_link(
+        content_counter_anchor_hash()?,
+        type_anchor_hash,
+        LinkTypes::ContentCounter,
+        LinkTag::new(tag),
+    )?;
+    Ok(())
+}
 
-    Ok(Vec::new())
+/// Sum counter deltas by content type, optionally only those written by `author`
+fn sum_content_counters(author: Option<&AgentPubKey>) -> ExternResult<HashMap<String, i64>> {
+    let query = LinkQuery::try_new(content_counter_anchor_hash()?, LinkTypes::ContentCounter)?;
+    let links = get_links(query, GetStrategy::default())?;
+
+    let mut totals: HashMap<String, i64> = HashMap::new();
+    for link in links {
+        if author.map_or(false, |a| link.author != *a) {
+            continue;
+        }
+        if let Ok(tag) = serde_json::from_slice::<ContentCounterTag>(&link.tag.0) {
+            *totals.entry(tag.content_type).or_insert(0) += tag.delta;
+        }
+    }
+    Ok(totals)
 }
 
 // =============================================================================
-// Learning Path Operations
+// Content Reach Transitions
 // =============================================================================
 
-/// Create a learning path
+/// Input for changing a content item's reach
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ChangeContentReachInput {
+    pub content_id: String,
+    pub new_reach: String,
+    pub justification: String,
+    /// Required when the caller is not the author
+    #[serde(default)]
+    pub steward_credential_id: Option<String>,
+}
+
+/// Output for a reach change
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReachChangeOutput {
+    pub action_hash: ActionHash,
+    pub reach_change: ReachChange,
+}
+
+/// Output for change_content_reach
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ChangeContentReachOutput {
+    pub content: ContentOutput,
+    pub reach_change: ReachChangeOutput,
+}
+
+/// Move content to a different reach level (e.g. draft → commons).
+///
+/// Allowed for the content's author, or for a steward whose active credential
+/// lists the content. Writes an updated Content entry, re-points the content
+/// indexes at it, and records a ReachChange for governance review.
 #[hdk_extern]
-pub fn create_path(input: CreatePathInput) -> ExternResult<ActionHash> {
-    // Check for existing path with this ID to prevent duplicates
-    if path_exists_by_id(&input.id)? {
+pub fn change_content_reach(input: ChangeContentReachInput) -> ExternResult<ChangeContentReachOutput> {
+    if !REACH_LEVELS.contains(&input.new_reach.as_str()) {
         return Err(wasm_error!(WasmErrorInner::Guest(format!(
-            "Path with id '{}' already exists. Use update_path to modify existing paths.",
-            input.id
+            "Invalid reach: {}. Must be one of: {:?}",
+            input.new_reach, REACH_LEVELS
         ))));
     }
+    if input.justification.trim().is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "A justification is required to change reach".to_string()
+        )));
+    }
+
+    let id_anchor = StringAnchor::new("content_id", &input.content_id);
+    let id_anchor_hash = hash_entry(&EntryTypes::StringAnchor(id_anchor))?;
+    let id_query = LinkQuery::try_new(id_anchor_hash.clone(), LinkTypes::IdToContent)?;
+    let id_links = get_links(id_query, GetStrategy::default())?;
+
+    let previous_hash = match id_links.first() {
+        Some(link) => ActionHash::try_from(link.target.clone())
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid action hash in link".to_string())))?,
+        None => {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                "Content not found: {}",
+                input.content_id
+            ))))
+        }
+    };
+    let existing = get_content(previous_hash.clone())?.ok_or(wasm_error!(WasmErrorInner::Guest(
+        format!("Content not found: {}", input.content_id)
+    )))?;
+
+    if existing.content.reach == input.new_reach {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Content '{}' already has reach '{}'",
+            input.content_id, input.new_reach
+        ))));
+    }
+
+    // Authorization: author, or steward with a credential covering this content
+    let me = agent_info()?.agent_initial_pubkey.to_string();
+    let authority = if existing.content.author_id.as_deref() == Some(me.as_str()) {
+        "author"
+    } else {
+        let credential_id = input.steward_credential_id.as_ref().ok_or(wasm_error!(WasmErrorInner::Guest(
+            "Only the author or a steward credential holder can change reach".to_string()
+        )))?;
+        let credential = get_steward_credential(credential_id.clone())?
+            .ok_or(wasm_error!(WasmErrorInner::Guest(format!(
+                "Steward credential not found: {}",
+                credential_id
+            ))))?
+            .credential;
+        let stewarded: Vec<String> =
+            serde_json::from_str(&credential.stewarded_content_ids_json).unwrap_or_default();
+        if !credential.is_active || credential.agent_id != me || !stewarded.contains(&input.content_id) {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                "Steward credential '{}' does not cover content '{}'",
+                credential_id, input.content_id
+            ))));
+        }
+        "steward"
+    };
 
-    let agent_info = agent_info()?;
     let now = sys_time()?;
     let timestamp = format!("{:?}", now);
 
-    let path = LearningPath {
-        id: input.id.clone(),
-        version: input.version,
-        title: input.title,
-        description: input.description,
-        purpose: input.purpose,
-        created_by: agent_info.agent_initial_pubkey.to_string(),
-        difficulty: input.difficulty,
-        estimated_duration: input.estimated_duration,
-        visibility: input.visibility,
-        path_type: input.path_type,
-        tags: input.tags,
-        metadata_json: input.metadata_json.unwrap_or_else(|| "{}".to_string()),
-        created_at: timestamp.clone(),
-        updated_at: timestamp,
-        schema_version: 2,
-        validation_status: "Valid".to_string(),
-    };
+    let mut updated = existing.content.clone();
+    updated.reach = input.new_reach.clone();
+    updated.updated_at = timestamp.clone();
 
-    let action_hash = create_entry(&EntryTypes::LearningPath(path))?;
+    let action_hash = update_entry(previous_hash.clone(), &EntryTypes::Content(updated.clone()))?;
+    let entry_hash = hash_entry(&EntryTypes::Content(updated.clone()))?;
 
-    // Create ID lookup link
-    let anchor = StringAnchor::new("path_id", &input.id);
-    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
-    create_link(anchor_hash, action_hash.clone(), LinkTypes::IdToPath, ())?;
+    // Re-point ID index at the new record
+    for link in id_links {
+        delete_link(link.create_link_hash, GetOptions::default())?;
+    }
+    create_link(id_anchor_hash, action_hash.clone(), LinkTypes::IdToContent, ())?;
+
+    // Re-point type and tag indexes, keeping the original created_at sort key
+    let old_target: AnyLinkableHash = previous_hash.clone().into();
+    let type_anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("content_type", &updated.content_type)))?;
+    let type_query = LinkQuery::try_new(type_anchor_hash, LinkTypes::TypeToContent)?;
+    let mut index_links: Vec<Link> = get_links(type_query, GetStrategy::default())?
+        .into_iter()
+        .filter(|link| link.target == old_target)
+        .collect();
+    for tag in &updated.tags {
+        index_links.extend(get_tag_links(tag)?.into_iter().filter(|link| link.target == old_target));
+    }
+    let license = content_license(&updated.metadata_json).ok().flatten();
+    let licensed_as = license.as_deref().map(license_ids).unwrap_or_default();
+    for id in &licensed_as {
+        for link in get_license_links(id)?.into_iter().filter(|link| link.target == old_target) {
+            delete_link(link.create_link_hash, GetOptions::default())?;
+        }
+    }
 
-    // Create global "all_paths" index link for get_all_paths()
-    let all_paths_anchor = StringAnchor::new("all_paths", "index");
-    let all_paths_anchor_hash = hash_entry(&EntryTypes::StringAnchor(all_paths_anchor))?;
-    create_link(all_paths_anchor_hash, action_hash.clone(), LinkTypes::IdToPath, ())?;
+    let mut sort_tag = ContentLinkTag::from_content(&updated, &now);
+    if let Some(previous) = index_links.iter().map(ContentLinkTag::from_link).find(|t| t.created_at > 0) {
+        sort_tag.created_at = previous.created_at;
+    }
+    for link in index_links {
+        delete_link(link.create_link_hash, GetOptions::default())?;
+    }
+    create_type_to_content_link(&updated.content_type, &action_hash, &sort_tag)?;
+    for tag in &updated.tags {
+        create_tag_to_content_link(tag, &action_hash, &sort_tag)?;
+    }
+    for id in &licensed_as {
+        create_license_to_content_link(id, &action_hash)?;
+    }
 
-    Ok(action_hash)
+    // Audit record
+    let reach_change = ReachChange {
+        id: format!("reach-change-{}-{}", input.content_id, now.as_micros()),
+        content_id: input.content_id.clone(),
+        previous_reach: existing.content.reach,
+        new_reach: input.new_reach,
+        justification: input.justification,
+        changed_by: me,
+        authority: authority.to_string(),
+        steward_credential_id: input.steward_credential_id,
+        previous_action_hash: previous_hash.to_string(),
+        created_at: timestamp,
+    };
+    let change_hash = create_entry(&EntryTypes::ReachChange(reach_change.clone()))?;
+
+    let content_anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("reach_change_content", &input.content_id)))?;
+    create_link(content_anchor_hash, change_hash.clone(), LinkTypes::ContentToReachChange, ())?;
+    let index_anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("reach_changes", "all")))?;
+    create_link(index_anchor_hash, change_hash.clone(), LinkTypes::ReachChangeIndex, ())?;
+
+    // Reach-based cache entries for Content may now be served to the wrong audience
+    emit_signal(DoorwaySignal::new(
+        CacheSignal::invalidate("Content").from_fn("change_content_reach"),
+    ))?;
+
+    Ok(ChangeContentReachOutput {
+        content: ContentOutput {
+            action_hash,
+            entry_hash,
+            content: updated,
+        },
+        reach_change: ReachChangeOutput {
+            action_hash: change_hash,
+            reach_change,
+        },
+    })
 }
 
-/// Add a step to a learning path
+/// Get the reach change history for a content item (oldest first)
 #[hdk_extern]
-pub fn add_path_step(input: AddPathStepInput) -> ExternResult<ActionHash> {
-    let now = sys_time()?;
-    let timestamp = format!("{:?}", now);
+pub fn get_reach_changes_for_content(content_id: String) -> ExternResult<Vec<ReachChangeOutput>> {
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("reach_change_content", &content_id)))?;
+    let query = LinkQuery::try_new(anchor_hash, LinkTypes::ContentToReachChange)?;
+    let mut changes = get_reach_changes_from_links(get_links(query, GetStrategy::default())?)?;
+    changes.sort_by(|a, b| a.reach_change.created_at.cmp(&b.reach_change.created_at));
+    Ok(changes)
+}
 
-    // Generate step ID
-    let step_id = match &input.chapter_id {
-        Some(ch_id) => format!("{}-{}-step-{}", input.path_id, ch_id, input.order_index),
-        None => format!("{}-step-{}", input.path_id, input.order_index),
-    };
+/// List recent reach changes across all content, newest first (governance review)
+#[hdk_extern]
+pub fn get_recent_reach_changes(limit: Option<u32>) -> ExternResult<Vec<ReachChangeOutput>> {
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("reach_changes", "all")))?;
+    let query = LinkQuery::try_new(anchor_hash, LinkTypes::ReachChangeIndex)?;
+    let mut links = get_links(query, GetStrategy::default())?;
+    links.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    links.truncate(limit.unwrap_or(50).min(200) as usize);
+    get_reach_changes_from_links(links)
+}
 
-    let step = PathStep {
-        id: step_id.clone(),
-        path_id: input.path_id.clone(),
-        chapter_id: input.chapter_id.clone(),
-        module_id: input.module_id.clone(),
-        section_id: input.section_id.clone(),
-        order_index: input.order_index,
-        step_type: input.step_type,
-        resource_id: input.resource_id.clone(),
-        step_title: input.step_title,
-        step_narrative: input.step_narrative,
-        is_optional: input.is_optional,
-        // Learning objectives and engagement
-        learning_objectives_json: serde_json::to_string(&input.learning_objectives.unwrap_or_default())
-            .unwrap_or_else(|_| "[]".to_string()),
-        reflection_prompts_json: serde_json::to_string(&input.reflection_prompts.unwrap_or_default())
-            .unwrap_or_else(|_| "[]".to_string()),
-        practice_exercises_json: serde_json::to_string(&input.practice_exercises.unwrap_or_default())
-            .unwrap_or_else(|_| "[]".to_string()),
-        // Completion and gating
-        estimated_minutes: input.estimated_minutes,
-        completion_criteria: input.completion_criteria,
-        attestation_required: input.attestation_required,
-        attestation_granted: input.attestation_granted,
-        mastery_threshold: input.mastery_threshold,
-        // Metadata
-        metadata_json: input.metadata_json.unwrap_or_else(|| "{}".to_string()),
-        created_at: timestamp.clone(),
-        updated_at: timestamp,
-        schema_version: 2,
-        validation_status: "Valid".to_string(),
+fn get_reach_changes_from_links(links: Vec<Link>) -> ExternResult<Vec<ReachChangeOutput>> {
+    let mut hashes = Vec::with_capacity(links.len());
+    for link in links {
+        let action_hash = ActionHash::try_from(link.target)
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid action hash in link".to_string())))?;
+        hashes.push(action_hash);
+    }
+
+    let records = get_records_batch(hashes.clone())?;
+    Ok(hashes
+        .into_iter()
+        .zip(records)
+        .filter_map(|(action_hash, record)| {
+            record
+                .and_then(|r| r.entry().to_app_option::<ReachChange>().ok().flatten())
+                .map(|reach_change| ReachChangeOutput { action_hash, reach_change })
+        })
+        .collect())
+}
+
+/// Revoke a trust claim about content.
+///
+/// Marks the attestation `revoked` with the revocation details and emits a
+/// write signal for its content, which doorway queues for steward
+/// moderation review.
+#[hdk_extern]
+pub fn revoke_content_attestation(input: RevokeContentAttestationInput) -> ExternResult<ContentAttestationOutput> {
+    if input.reason.trim().is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "A reason is required to revoke an attestation".to_string()
+        )));
+    }
+
+    let id_anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("content_attestation_id", &input.id)))?;
+    let id_query = LinkQuery::try_new(id_anchor_hash.clone(), LinkTypes::IdToContentAttestation)?;
+    let id_links = get_links(id_query, GetStrategy::default())?;
+    let previous_hash = match id_links.first() {
+        Some(link) => ActionHash::try_from(link.target.clone())
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid action hash in link".to_string())))?,
+        None => {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                "Content attestation not found: {}",
+                input.id
+            ))))
+        }
     };
+    let existing = get(previous_hash.clone(), GetOptions::default())?
+        .and_then(|record| record.entry().to_app_option::<ContentAttestation>().ok().flatten())
+        .ok_or(wasm_error!(WasmErrorInner::Guest(format!(
+            "Content attestation not found: {}",
+            input.id
+        ))))?;
+    if existing.status == "revoked" {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Content attestation '{}' is already revoked",
+            input.id
+        ))));
+    }
 
-    let action_hash = create_entry(&EntryTypes::PathStep(step))?;
+    let timestamp = format!("{:?}", sys_time()?);
+    let mut updated = existing;
+    updated.status = "revoked".to_string();
+    updated.revocation_json = Some(
+        serde_json::json!({
+            "revoked_by": input.revoked_by,
+            "revoked_at": timestamp,
+            "reason": input.reason,
+            "appealable": input.appealable,
+        })
+        .to_string(),
+    );
+    updated.updated_at = timestamp;
 
-    // Create ID lookup link for step
-    let step_anchor = StringAnchor::new("step_id", &step_id);
-    let step_anchor_hash = hash_entry(&EntryTypes::StringAnchor(step_anchor))?;
-    create_link(step_anchor_hash, action_hash.clone(), LinkTypes::IdToStep, ())?;
+    let action_hash = update_entry(previous_hash, &EntryTypes::ContentAttestation(updated.clone()))?;
+    let entry_hash = hash_entry(&EntryTypes::ContentAttestation(updated.clone()))?;
 
-    // Link path to step
-    let path_anchor = StringAnchor::new("path_id", &input.path_id);
-    let path_anchor_hash = hash_entry(&EntryTypes::StringAnchor(path_anchor))?;
+    for link in id_links {
+        delete_link(link.create_link_hash, GetOptions::default())?;
+    }
+    create_link(id_anchor_hash, action_hash.clone(), LinkTypes::IdToContentAttestation, ())?;
 
-    let query = LinkQuery::try_new(path_anchor_hash, LinkTypes::IdToPath)?;
-    let path_links = get_links(query, GetStrategy::default())?;
+    emit_write_signal("Content", &updated.content_id, "revoke_content_attestation");
 
-    if let Some(path_link) = path_links.first() {
-        let path_action_hash = ActionHash::try_from(path_link.target.clone())
-            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid path action hash".to_string())))?;
-        create_link(path_action_hash, action_hash.clone(), LinkTypes::PathToStep, ())?;
+    Ok(ContentAttestationOutput {
+        action_hash,
+        entry_hash,
+        content_attestation: updated,
+    })
+}
+
+// =============================================================================
+// Blob Operations (Media Distribution - Phase 1)
+// =============================================================================
+
+/// Get all blobs associated with a content ID.
+/// Returns metadata for all blobs attached to content for download/playback.
+#[hdk_extern]
+pub fn get_blobs_by_content_id(input: QueryBlobsByContentIdInput) -> ExternResult<Vec<BlobMetadataOutput>> {
+    // First, find content by ID
+    let anchor = StringAnchor::new("content_id", &input.content_id);
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
+
+    let query = LinkQuery::try_new(anchor_hash, LinkTypes::IdToContent)?;
+    let links = get_links(query, GetStrategy::default())?;
+
+    if links.is_empty() {
+        return Ok(Vec::new());
     }
 
-    // Link to chapter if specified
-    if let Some(chapter_id) = &input.chapter_id {
-        let chapter_anchor = StringAnchor::new("chapter_id", chapter_id);
-        let chapter_anchor_hash = hash_entry(&EntryTypes::StringAnchor(chapter_anchor))?;
+    // Get the content record
+    let action_hash = ActionHash::try_from(links[0].target.clone())
+        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid action hash in link".to_string())))?;
 
-        let chapter_query = LinkQuery::try_new(chapter_anchor_hash, LinkTypes::IdToChapter)?;
-        let chapter_links = get_links(chapter_query, GetStrategy::default())?;
+    let record = get(action_hash, GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Content not found".to_string())))?;
 
-        if let Some(chapter_link) = chapter_links.first() {
-            let chapter_action_hash = ActionHash::try_from(chapter_link.target.clone())
-                .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid chapter action hash".to_string())))?;
-            // Link chapter to step
-            create_link(chapter_action_hash.clone(), action_hash.clone(), LinkTypes::ChapterToStep, ())?;
-            // Link step back to chapter (for reverse lookup)
-            create_link(action_hash.clone(), chapter_action_hash, LinkTypes::StepToChapter, ())?;
-        }
+    let content: Content = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not deserialize content".to_string())))?;
+
+    let content_hash = links[0].target.clone();
+
+    // Query ContentToBlobs links from this content
+    let query = LinkQuery::try_new(content_hash, LinkTypes::ContentToBlobs)?;
+    let blob_links = get_links(query, GetStrategy::default())?;
+
+    if blob_links.is_empty() {
+        return Ok(Vec::new());
     }
 
-    // Link step to content (if resource exists)
-    let resource_anchor = StringAnchor::new("content_id", &input.resource_id);
-    let resource_anchor_hash = hash_entry(&EntryTypes::StringAnchor(resource_anchor))?;
+    // Fetch each blob entry
+    let mut results = Vec::new();
 
-    let content_query = LinkQuery::try_new(resource_anchor_hash, LinkTypes::IdToContent)?;
-    let content_links = get_links(content_query, GetStrategy::default())?;
+    for link in blob_links {
+        let blob_action_hash = ActionHash::try_from(link.target)
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid action hash in blob link".to_string())))?;
 
-    if let Some(content_link) = content_links.first() {
-        create_link(action_hash.clone(), content_link.target.clone(), LinkTypes::StepToContent, ())?;
+        if let Some(record) = get(blob_action_hash, GetOptions::default())? {
+            if let Ok(Some(blob)) = record.entry().to_app_option::<BlobEntry>() {
+                // Content links point at the first version; fallback URLs
+                // are added to later ones
+                let blob = get_latest_blob(&blob.hash)?
+                    .map(|(_, latest)| latest)
+                    .unwrap_or(blob);
+                results.push(blob_metadata_output(blob));
+            }
+        }
     }
 
-    Ok(action_hash)
+    Ok(results)
 }
 
-/// Batch add multiple steps to a path (for efficient seeding)
-#[derive(Serialize, Deserialize, Debug)]
-pub struct BatchAddPathStepsInput {
-    pub steps: Vec<AddPathStepInput>,
+fn blob_metadata_output(blob: BlobEntry) -> BlobMetadataOutput {
+    BlobMetadataOutput {
+        hash: blob.hash,
+        size_bytes: blob.size_bytes,
+        mime_type: blob.mime_type,
+        fallback_urls: blob.fallback_urls,
+        bitrate_mbps: blob.bitrate_mbps.map(|b| b as f64),
+        duration_seconds: blob.duration_seconds,
+        codec: blob.codec,
+        reach: blob.reach,
+        created_at: Some(blob.created_at),
+        verified_at: blob.verified_at,
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct BatchAddPathStepsOutput {
-    pub created_count: u32,
-    pub action_hashes: Vec<ActionHash>,
-    pub errors: Vec<String>,
-}
+/// Most recent version of a blob entry, found through its hash anchor
+fn get_latest_blob(blob_hash: &str) -> ExternResult<Option<(ActionHash, BlobEntry)>> {
+    let anchor = StringAnchor::new("blob_hash", blob_hash);
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
 
-#[hdk_extern]
-pub fn batch_add_path_steps(input: BatchAddPathStepsInput) -> ExternResult<BatchAddPathStepsOutput> {
-    let mut action_hashes = Vec::new();
-    let mut errors = Vec::new();
+    let query = LinkQuery::try_new(anchor_hash, LinkTypes::IdToBlob)?;
+    let mut links = get_links(query, GetStrategy::default())?;
+    links.sort_by_key(|link| std::cmp::Reverse(link.timestamp));
 
-    for step_input in input.steps {
-        match add_path_step(step_input.clone()) {
-            Ok(hash) => {
-                action_hashes.push(hash);
-            }
-            Err(e) => {
-                errors.push(format!("Failed to add step {}: {:?}", step_input.order_index, e));
-            }
+    for link in links {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(action_hash.clone(), GetOptions::default())? else {
+            continue;
+        };
+        if let Some(blob) = record.entry().to_app_option::<BlobEntry>().ok().flatten() {
+            return Ok(Some((action_hash, blob)));
         }
     }
 
-    Ok(BatchAddPathStepsOutput {
-        created_count: action_hashes.len() as u32,
-        action_hashes,
-        errors,
-    })
+    Ok(None)
 }
 
-/// Batch check which path IDs already exist
+/// Input for registering an extra download location for a blob
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AddBlobFallbackUrlInput {
+    pub blob_hash: String,
+    pub url: String,
+}
+
+/// Append a fallback URL to a blob.
+///
+/// Used by doorway's mirroring job after it has uploaded verified bytes to
+/// object storage, so the blob stays downloadable while no peer holding it
+/// is online. Adding a URL that is already listed is a no-op.
 #[hdk_extern]
-pub fn check_path_ids_exist(input: CheckIdsExistInput) -> ExternResult<CheckIdsExistOutput> {
-    let mut existing_ids = Vec::new();
+pub fn add_blob_fallback_url(input: AddBlobFallbackUrlInput) -> ExternResult<BlobMetadataOutput> {
+    if !(input.url.starts_with("https://") || input.url.starts_with("http://")) {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Fallback URL must be http(s)".to_string()
+        )));
+    }
 
-    for id in input.ids {
-        let anchor = StringAnchor::new("path_id", &id);
-        let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
+    let (previous_hash, mut blob) = get_latest_blob(&input.blob_hash)?.ok_or(wasm_error!(
+        WasmErrorInner::Guest(format!("Blob not found: {}", input.blob_hash))
+    ))?;
 
-        let query = LinkQuery::try_new(anchor_hash, LinkTypes::IdToPath)?;
-        let links = get_links(query, GetStrategy::default())?;
+    if blob.fallback_urls.contains(&input.url) {
+        return Ok(blob_metadata_output(blob));
+    }
 
-        if !links.is_empty() {
-            existing_ids.push(id);
+    blob.fallback_urls.push(input.url);
+    blob.verified_at = Some(format!("{:?}", sys_time()?));
+    let action_hash = update_entry(previous_hash.clone(), &EntryTypes::BlobEntry(blob.clone()))?;
+
+    // Move the hash lookup to the new version
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("blob_hash", &blob.hash)))?;
+    let old_target: AnyLinkableHash = previous_hash.into();
+    let query = LinkQuery::try_new(anchor_hash.clone(), LinkTypes::IdToBlob)?;
+    for link in get_links(query, GetStrategy::default())? {
+        if link.target == old_target {
+            delete_link(link.create_link_hash, GetOptions::default())?;
         }
     }
+    create_link(anchor_hash, action_hash, LinkTypes::IdToBlob, ())?;
 
-    Ok(CheckIdsExistOutput { existing_ids })
+    emit_write_signal("Blob", &blob.hash, "add_blob_fallback_url");
+
+    Ok(blob_metadata_output(blob))
 }
 
-/// Path index entry for listing
+/// Input for one page of the content health scan
 #[derive(Serialize, Deserialize, Debug)]
-pub struct PathIndexEntry {
-    pub id: String,
+pub struct ContentHealthPageInput {
+    pub content_type: String,
+    pub offset: u32,
+    pub page_size: u32,                // Max 100
+}
+
+/// Fallback URLs of one blob attached to content
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlobFallbacks {
+    pub hash: String,
+    pub reach: String,
+    pub mime_type: String,
+    pub fallback_urls: Vec<String>,
+}
+
+/// What a steward needs to judge one content node's health
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ContentHealthSnapshot {
+    pub content_id: String,
     pub title: String,
-    pub description: String,
-    pub difficulty: String,
-    pub estimated_duration: Option<String>,
-    pub step_count: u32,
-    pub tags: Vec<String>,
+    pub content_type: String,
+    pub author_id: Option<String>,
+    /// Commit time of the current content entry
+    pub updated_at_micros: i64,
+    /// Lifecycle status from metadata (draft, published, stale, deprecated, archived, forgotten)
+    pub lifecycle_status: Option<String>,
+    /// related_node_ids plus outgoing relationship targets
+    pub related_ids: Vec<String>,
+    pub blobs: Vec<BlobFallbacks>,
 }
 
-/// Path index output
+/// One page of content health snapshots
 #[derive(Serialize, Deserialize, Debug)]
-pub struct PathIndex {
-    pub paths: Vec<PathIndexEntry>,
+pub struct ContentHealthPage {
+    pub items: Vec<ContentHealthSnapshot>,
     pub total_count: u32,
-    pub last_updated: String,
+    pub has_more: bool,
 }
 
-/// Get all learning paths (for path index)
-/// Uses link-based lookup via global "all_paths" anchor
+/// Lifecycle status recorded in content metadata, if any
+///
+/// Accepts `{"lifecycle": {"status": ..}}` (the app's ContentLifecycle) and a
+/// flat `lifecycle_status` key.
+fn content_lifecycle_status(metadata_json: &str) -> Option<String> {
+    let metadata: serde_json::Value = serde_json::from_str(metadata_json).ok()?;
+    metadata
+        .get("lifecycle")
+        .and_then(|l| l.get("status"))
+        .or_else(|| metadata.get("lifecycle_status"))
+        .and_then(|s| s.as_str())
+        .map(String::from)
+}
+
+/// Page through content of one type for doorway's content health sweep
+///
+/// Returns the raw signals (age, lifecycle, links, blob URLs); doorway
+/// probes the URLs and decides what to flag.
 #[hdk_extern]
-pub fn get_all_paths(_: ()) -> ExternResult<PathIndex> {
-    // Use a global anchor to find all paths
-    let anchor = StringAnchor::new("all_paths", "index");
+pub fn get_content_health_page(input: ContentHealthPageInput) -> ExternResult<ContentHealthPage> {
+    let anchor = StringAnchor::new("content_type", &input.content_type);
     let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
 
-    let query = LinkQuery::try_new(anchor_hash, LinkTypes::IdToPath)?;
+    let query = LinkQuery::try_new(anchor_hash, LinkTypes::TypeToContent)?;
     let links = get_links(query, GetStrategy::default())?;
 
-    let mut paths = Vec::new();
-
-    for link in links {
-        let action_hash = ActionHash::try_from(link.target)
-            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid action hash in link".to_string())))?;
+    let total_count = links.len() as u32;
+    let page_size = input.page_size.min(100) as usize;
+    let offset = input.offset as usize;
 
-        let record = get(action_hash.clone(), GetOptions::default())?;
-        if let Some(record) = record {
-            if let Some(path) = record
-                .entry()
-                .to_app_option::<LearningPath>()
-                .ok()
-                .flatten()
-            {
-                // Count steps for this path
-                let step_query = LinkQuery::try_new(action_hash, LinkTypes::PathToStep)?;
-                let step_links = get_links(step_query, GetStrategy::default())?;
+    let mut items = Vec::new();
+    for link in links.iter().skip(offset).take(page_size) {
+        let Some(action_hash) = link.target.clone().into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(action_hash, GetOptions::default())? else {
+            continue;
+        };
+        let Some(content) = record.entry().to_app_option::<Content>().ok().flatten() else {
+            continue;
+        };
 
-                paths.push(PathIndexEntry {
-                    id: path.id,
-                    title: path.title,
-                    description: path.description,
-                    difficulty: path.difficulty,
-                    estimated_duration: path.estimated_duration,
-                    step_count: step_links.len() as u32,
-                    tags: path.tags,
-                });
+        let mut related_ids = content.related_node_ids.clone();
+        let outgoing = get_relationships(GetRelationshipsInput {
+            content_id: content.id.clone(),
+            direction: "outgoing".to_string(),
+        })?;
+        for output in outgoing {
+            if !related_ids.contains(&output.relationship.target_id) {
+                related_ids.push(output.relationship.target_id);
             }
         }
-    }
 
-    let now = sys_time()?;
+        let blobs = get_blobs_by_content_id(QueryBlobsByContentIdInput {
+            content_id: content.id.clone(),
+        })?
+        .into_iter()
+        .map(|blob| BlobFallbacks {
+            hash: blob.hash,
+            reach: blob.reach,
+            mime_type: blob.mime_type,
+            fallback_urls: blob.fallback_urls,
+        })
+        .collect();
 
-    Ok(PathIndex {
-        total_count: paths.len() as u32,
-        paths,
-        last_updated: format!("{:?}", now),
+        items.push(ContentHealthSnapshot {
+            lifecycle_status: content_lifecycle_status(&content.metadata_json),
+            updated_at_micros: record.action().timestamp().as_micros(),
+            content_id: content.id,
+            title: content.title,
+            content_type: content.content_type,
+            author_id: content.author_id,
+            related_ids,
+            blobs,
+        });
+    }
+
+    Ok(ContentHealthPage {
+        items,
+        total_count,
+        has_more: offset + page_size < links.len(),
     })
 }
 
-/// Delete a learning path and its steps (removes links, entries remain in DHT)
-/// Used for re-seeding paths with corrected step resource IDs
+/// Verify that a blob hash is valid and matches expected content.
+/// Used during download to detect corruption.
 #[hdk_extern]
-pub fn delete_path(path_id: String) -> ExternResult<bool> {
-    // Find path by ID
-    let anchor = StringAnchor::new("path_id", &path_id);
-    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
-
-    let query = LinkQuery::try_new(anchor_hash.clone(), LinkTypes::IdToPath)?;
-    let path_links = get_links(query, GetStrategy::default())?;
+pub fn verify_blob_integrity(input: VerifyBlobIntegrityInput) -> ExternResult<BlobIntegrityCheckOutput> {
+    let start_time = std::time::SystemTime::now();
 
-    let path_link = match path_links.first() {
-        Some(link) => link,
-        None => return Ok(false), // Path not found
-    };
+    // In Phase 1, we store the expected hash in the content metadata
+    // The actual verification happens on the client side (BlobVerificationService)
+    // This function is mainly for caching and validation purposes
 
-    let path_action_hash = ActionHash::try_from(path_link.target.clone())
-        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid path action hash".to_string())))?;
+    // Find content by ID
+    let anchor = StringAnchor::new("content_id", &input.content_id);
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
 
-    // Delete path-to-step links and step entries
-    let step_query = LinkQuery::try_new(path_action_hash.clone(), LinkTypes::PathToStep)?;
-    let step_links = get_links(step_query, GetStrategy::default())?;
+    let query = LinkQuery::try_new(anchor_hash, LinkTypes::IdToContent)?;
+    let links = get_links(query, GetStrategy::default())?;
 
-    for link in step_links {
-        // Delete the link from path to step
-        delete_link(link.create_link_hash, GetOptions::default())?;
+    if links.is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Content not found".to_string()
+        )));
     }
 
-    // Delete the ID-to-path link
-    delete_link(path_link.create_link_hash.clone(), GetOptions::default())?;
+    // Check if blob with this hash exists in our system
+    let blob_anchor = StringAnchor::new("blob_hash", &input.blob_hash);
+    let blob_anchor_hash = hash_entry(&EntryTypes::StringAnchor(blob_anchor))?;
 
-    // Delete the all_paths index link
-    let all_paths_anchor = StringAnchor::new("all_paths", "index");
-    let all_paths_anchor_hash = hash_entry(&EntryTypes::StringAnchor(all_paths_anchor))?;
-    let all_paths_query = LinkQuery::try_new(all_paths_anchor_hash, LinkTypes::IdToPath)?;
-    let all_paths_links = get_links(all_paths_query, GetStrategy::default())?;
+    let blob_query = LinkQuery::try_new(blob_anchor_hash, LinkTypes::IdToBlob)?;
+    let blob_links = get_links(blob_query, GetStrategy::default())?;
 
-    for link in all_paths_links {
-        if link.target == path_link.target {
-            delete_link(link.create_link_hash, GetOptions::default())?;
-            break;
-        }
-    }
+    // Blob is valid if it exists in DHT and hash matches content
+    let is_valid = !blob_links.is_empty();
 
-    Ok(true)
+    let elapsed = start_time
+        .elapsed()
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+
+    Ok(BlobIntegrityCheckOutput {
+        blob_hash: input.blob_hash,
+        is_valid,
+        verification_time_ms: elapsed,
+    })
 }
 
-/// Get a learning path with all its steps
+/// Register an alternate rendition of a blob (e.g. a 720p transcode).
+///
+/// Registering the same variant hash again returns the existing entry.
 #[hdk_extern]
-pub fn get_path_with_steps(path_id: String) -> ExternResult<Option<PathWithSteps>> {
-    // Find path by ID
-    let anchor = StringAnchor::new("path_id", &path_id);
-    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
-
-    let query = LinkQuery::try_new(anchor_hash, LinkTypes::IdToPath)?;
-    let path_links = get_links(query, GetStrategy::default())?;
+pub fn register_blob_variant(input: RegisterBlobVariantInput) -> ExternResult<BlobVariantOutput> {
+    if let Some(existing) = get_blob_variants(QueryBlobVariantsInput {
+        blob_hash: input.parent_hash.clone(),
+    })?
+    .into_iter()
+    .find(|v| v.variant.hash == input.hash)
+    {
+        return Ok(existing);
+    }
 
-    let path_link = match path_links.first() {
-        Some(link) => link,
-        None => return Ok(None),
+    let variant = BlobVariant {
+        parent_hash: input.parent_hash,
+        hash: input.hash,
+        resolution: input.resolution,
+        width: input.width,
+        height: input.height,
+        bitrate_mbps: input.bitrate_mbps,
+        codec: input.codec.to_lowercase(),
+        size_bytes: input.size_bytes,
+        mime_type: input.mime_type,
+        fallback_urls: input.fallback_urls,
+        created_at: format!("{:?}", sys_time()?),
     };
+    let action_hash = create_entry(&EntryTypes::BlobVariant(variant.clone()))?;
 
-    let path_action_hash = ActionHash::try_from(path_link.target.clone())
-        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid path action hash".to_string())))?;
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("blob_hash", &variant.parent_hash)))?;
+    create_link(anchor_hash, action_hash.clone(), LinkTypes::BlobToVariants, ())?;
 
-    let path_record = get(path_action_hash.clone(), GetOptions::default())?;
-    let path_record = match path_record {
-        Some(r) => r,
-        None => return Ok(None),
-    };
+    emit_write_signal("BlobVariant", &variant.parent_hash, "register_blob_variant");
 
-    let path: LearningPath = path_record
-        .entry()
-        .to_app_option()
-        .map_err(|e| wasm_error!(e))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest(
-            "Could not deserialize path".to_string()
-        )))?;
+    Ok(BlobVariantOutput { action_hash, variant })
+}
 
-    // Get steps
-    let step_query = LinkQuery::try_new(path_action_hash.clone(), LinkTypes::PathToStep)?;
-    let step_links = get_links(step_query, GetStrategy::default())?;
+/// Get all variants of a blob (different bitrates, resolutions).
+/// Used for adaptive streaming - returns available quality options,
+/// lowest bitrate first.
+#[hdk_extern]
+pub fn get_blob_variants(input: QueryBlobVariantsInput) -> ExternResult<Vec<BlobVariantOutput>> {
+    let anchor = StringAnchor::new("blob_hash", &input.blob_hash);
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
 
-    let mut steps = Vec::new();
-    for link in step_links {
-        let step_action_hash = ActionHash::try_from(link.target)
-            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid step action hash".to_string())))?;
+    let query = LinkQuery::try_new(anchor_hash, LinkTypes::BlobToVariants)?;
+    let links = get_links(query, GetStrategy::default())?;
 
-        let step_record = get(step_action_hash.clone(), GetOptions::default())?;
-        if let Some(record) = step_record {
-            if let Some(step) = record
-                .entry()
-                .to_app_option::<PathStep>()
-                .ok()
-                .flatten()
-            {
-                steps.push(PathStepOutput {
-                    action_hash: step_action_hash,
-                    step,
-                });
-            }
+    let mut variants = Vec::new();
+    for link in links {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(action_hash.clone(), GetOptions::default())? else {
+            continue;
+        };
+        if let Some(variant) = record.entry().to_app_option::<BlobVariant>().ok().flatten() {
+            variants.push(BlobVariantOutput { action_hash, variant });
         }
     }
 
-    // Sort steps by order_index
-    steps.sort_by_key(|s| s.step.order_index);
-
-    Ok(Some(PathWithSteps {
-        action_hash: path_action_hash,
-        path,
-        steps,
-    }))
+    variants.sort_by(|a, b| a.variant.bitrate_mbps.total_cmp(&b.variant.bitrate_mbps));
+    Ok(variants)
 }
 
-/// Get a lightweight path overview (no step content, just metadata and count)
+/// Whether a caption language matches a requested one
 ///
-/// This is MUCH faster than get_path_with_steps because it:
-/// - Only counts step links instead of fetching each step record
-/// - Returns path.metadata_json which contains chapter structure
+/// Case-insensitive; a bare primary tag also matches its regional variants.
+fn caption_language_matches(language: &str, requested: &str) -> bool {
+    let language = language.to_lowercase();
+    let requested = requested.to_lowercase();
+    language == requested || language.split('-').next() == Some(requested.as_str())
+}
+
+/// Attach a caption/subtitle track to a blob.
 ///
-/// Use for: path listings, path-overview page, initial navigation
+/// The caption file must already be stored as a blob (doorway's caption
+/// upload route does both). A new track replaces an existing one with the
+/// same language and label.
 #[hdk_extern]
-pub fn get_path_overview(path_id: String) -> ExternResult<Option<PathOverview>> {
-    // Find path by ID
-    let anchor = StringAnchor::new("path_id", &path_id);
-    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
-
-    let query = LinkQuery::try_new(anchor_hash, LinkTypes::IdToPath)?;
-    let path_links = get_links(query, GetStrategy::default())?;
-
-    let path_link = match path_links.first() {
-        Some(link) => link,
-        None => return Ok(None),
-    };
-
-    let path_action_hash = ActionHash::try_from(path_link.target.clone())
-        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid path action hash".to_string())))?;
+pub fn add_blob_caption(input: AddBlobCaptionInput) -> ExternResult<BlobCaptionOutput> {
+    let content_anchor = StringAnchor::new("content_id", &input.content_id);
+    let content_anchor_hash = hash_entry(&EntryTypes::StringAnchor(content_anchor))?;
+    let query = LinkQuery::try_new(content_anchor_hash, LinkTypes::IdToContent)?;
+    if get_links(query, GetStrategy::default())?.is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Content not found: {}",
+            input.content_id
+        ))));
+    }
 
-    let path_record = get(path_action_hash.clone(), GetOptions::default())?;
-    let path_record = match path_record {
-        Some(r) => r,
-        None => return Ok(None),
+    let caption = BlobCaption {
+        blob_hash: input.blob_hash,
+        content_id: input.content_id,
+        language: input.language.trim().to_string(),
+        label: input.label.filter(|l| !l.trim().is_empty()),
+        format: input.format.to_lowercase(),
+        caption_hash: input.caption_hash,
+        size_bytes: input.size_bytes,
+        author_id: input.author_id,
+        created_at: format!("{:?}", sys_time()?),
     };
+    let action_hash = create_entry(&EntryTypes::BlobCaption(caption.clone()))?;
+
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("blob_hash", &caption.blob_hash)))?;
+    for existing in get_blob_captions(QueryBlobCaptionsInput {
+        blob_hash: caption.blob_hash.clone(),
+        language: None,
+    })? {
+        if existing.caption.language.eq_ignore_ascii_case(&caption.language)
+            && existing.caption.label == caption.label
+        {
+            let old_target: AnyLinkableHash = existing.action_hash.into();
+            let query = LinkQuery::try_new(anchor_hash.clone(), LinkTypes::BlobToCaptions)?;
+            for link in get_links(query, GetStrategy::default())? {
+                if link.target == old_target {
+                    delete_link(link.create_link_hash, GetOptions::default())?;
+                }
+            }
+        }
+    }
+    create_link(anchor_hash, action_hash.clone(), LinkTypes::BlobToCaptions, ())?;
 
-    let path: LearningPath = path_record
-        .entry()
-        .to_app_option()
-        .map_err(|e| wasm_error!(e))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest(
-            "Could not deserialize path".to_string()
-        )))?;
-
-    // Just count step links - don't fetch each step record
-    let step_query = LinkQuery::try_new(path_action_hash.clone(), LinkTypes::PathToStep)?;
-    let step_links = get_links(step_query, GetStrategy::default())?;
-    let step_count = step_links.len();
+    emit_write_signal("BlobCaption", &caption.blob_hash, "add_blob_caption");
 
-    Ok(Some(PathOverview {
-        action_hash: path_action_hash,
-        path,
-        step_count,
-    }))
+    Ok(BlobCaptionOutput { action_hash, caption })
 }
 
-// =============================================================================
-// Chapter Operations
-// =============================================================================
-
-/// Create a chapter for a learning path
+/// Get captions/subtitles for a blob.
+/// Returns the caption tracks for a video, optionally in one language,
+/// sorted by language then format.
 #[hdk_extern]
-pub fn create_chapter(input: CreateChapterInput) -> ExternResult<ChapterOutput> {
-    let now = sys_time()?;
-    let timestamp = format!("{:?}", now);
+pub fn get_blob_captions(input: QueryBlobCaptionsInput) -> ExternResult<Vec<BlobCaptionOutput>> {
+    let anchor = StringAnchor::new("blob_hash", &input.blob_hash);
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
 
-    // Generate chapter ID
-    let chapter_id = format!("{}-chapter-{}", input.path_id, input.order_index);
+    let query = LinkQuery::try_new(anchor_hash, LinkTypes::BlobToCaptions)?;
+    let links = get_links(query, GetStrategy::default())?;
 
-    let chapter = PathChapter {
-        id: chapter_id.clone(),
-        path_id: input.path_id.clone(),
-        order_index: input.order_index,
-        title: input.title,
-        description: input.description,
-        learning_objectives_json: serde_json::to_string(&input.learning_objectives)
-            .unwrap_or_else(|_| "[]".to_string()),
-        estimated_minutes: input.estimated_minutes,
-        is_optional: input.is_optional,
-        attestation_granted: input.attestation_granted,
-        mastery_threshold: input.mastery_threshold,
-        metadata_json: input.metadata_json.unwrap_or_else(|| "{}".to_string()),
-        created_at: timestamp.clone(),
-        updated_at: timestamp,
-    };
+    let mut captions = Vec::new();
+    for link in links {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(action_hash.clone(), GetOptions::default())? else {
+            continue;
+        };
+        let Some(caption) = record.entry().to_app_option::<BlobCaption>().ok().flatten() else {
+            continue;
+        };
+        if let Some(ref language) = input.language {
+            if !caption_language_matches(&caption.language, language) {
+                continue;
+            }
+        }
+        captions.push(BlobCaptionOutput { action_hash, caption });
+    }
 
-    let action_hash = create_entry(&EntryTypes::PathChapter(chapter.clone()))?;
+    captions.sort_by(|a, b| {
+        a.caption
+            .language
+            .cmp(&b.caption.language)
+            .then_with(|| a.caption.format.cmp(&b.caption.format))
+    });
+    Ok(captions)
+}
 
-    // Create ID lookup link for chapter
-    let chapter_anchor = StringAnchor::new("chapter_id", &chapter_id);
-    let chapter_anchor_hash = hash_entry(&EntryTypes::StringAnchor(chapter_anchor))?;
-    create_link(chapter_anchor_hash, action_hash.clone(), LinkTypes::IdToChapter, ())?;
+// =============================================================================
+// Content Translations
+// =============================================================================
 
-    // Link path to chapter
-    let path_anchor = StringAnchor::new("path_id", &input.path_id);
-    let path_anchor_hash = hash_entry(&EntryTypes::StringAnchor(path_anchor))?;
+/// Canonical casing for a locale tag: language lowercase, script titlecase,
+/// region uppercase ("PT-br" -> "pt-BR", "zh-hant" -> "zh-Hant")
+fn normalize_locale(tag: &str) -> String {
+    tag.trim()
+        .split(['-', '_'])
+        .enumerate()
+        .map(|(i, subtag)| match (i, subtag.len()) {
+            (0, _) => subtag.to_lowercase(),
+            (_, 2) => subtag.to_uppercase(),
+            (_, 4) => {
+                let lower = subtag.to_lowercase();
+                let mut chars = lower.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_uppercase().chain(chars).collect())
+                    .unwrap_or_default()
+            }
+            _ => subtag.to_lowercase(),
+        })
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Primary language subtag of a locale ("pt-BR" -> "pt")
+fn primary_language(tag: &str) -> String {
+    tag.split('-').next().unwrap_or(tag).to_lowercase()
+}
+
+/// Pick the translation for the first requested locale that has one.
+/// A request matches exactly first, then the bare language ("pt-BR" falls
+/// back to "pt"), then any regional variant of it ("pt" accepts "pt-PT").
+fn pick_translation<'a>(
+    translations: &'a [ContentTranslationOutput],
+    requested: &[String],
+) -> Option<&'a ContentTranslationOutput> {
+    for locale in requested {
+        let primary = primary_language(locale);
+        let found = translations
+            .iter()
+            .find(|t| t.translation.locale.eq_ignore_ascii_case(locale))
+            .or_else(|| translations.iter().find(|t| t.translation.locale.eq_ignore_ascii_case(&primary)))
+            .or_else(|| translations.iter().find(|t| primary_language(&t.translation.locale) == primary));
+        if found.is_some() {
+            return found;
+        }
+    }
+    None
+}
 
-    let query = LinkQuery::try_new(path_anchor_hash, LinkTypes::IdToPath)?;
-    let path_links = get_links(query, GetStrategy::default())?;
+/// Anchor of the steward review queue for draft translations
+fn translation_review_anchor() -> ExternResult<EntryHash> {
+    hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("translation_review", "pending")))
+}
 
-    if let Some(path_link) = path_links.first() {
-        let path_action_hash = ActionHash::try_from(path_link.target.clone())
-            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid path action hash".to_string())))?;
-        create_link(path_action_hash, action_hash.clone(), LinkTypes::PathToChapter, ())?;
+/// Take a translation off the review queue
+fn remove_from_review_queue(action_hash: &ActionHash) -> ExternResult<()> {
+    let target: AnyLinkableHash = action_hash.clone().into();
+    let query = LinkQuery::try_new(translation_review_anchor()?, LinkTypes::TranslationReviewQueue)?;
+    for link in get_links(query, GetStrategy::default())? {
+        if link.target == target {
+            delete_link(link.create_link_hash, GetOptions::default())?;
+        }
     }
-
-    Ok(ChapterOutput {
-        action_hash,
-        chapter,
-    })
+    Ok(())
 }
 
-/// Get a chapter by ID
+/// Add a translation of a content node.
+///
+/// A published translation replaces every existing one for the same locale,
+/// drafts included. A draft only replaces an earlier draft, goes on the
+/// steward review queue and is not served until published.
 #[hdk_extern]
-pub fn get_chapter_by_id(chapter_id: String) -> ExternResult<Option<ChapterOutput>> {
-    let anchor = StringAnchor::new("chapter_id", &chapter_id);
-    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
-
-    let query = LinkQuery::try_new(anchor_hash, LinkTypes::IdToChapter)?;
-    let links = get_links(query, GetStrategy::default())?;
-
-    let link = match links.first() {
-        Some(l) => l,
-        None => return Ok(None),
-    };
-
-    let action_hash = ActionHash::try_from(link.target.clone())
-        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid chapter action hash".to_string())))?;
+pub fn add_translation(input: AddTranslationInput) -> ExternResult<ContentTranslationOutput> {
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("content_id", &input.content_id)))?;
+    let query = LinkQuery::try_new(anchor_hash.clone(), LinkTypes::IdToContent)?;
+    if get_links(query, GetStrategy::default())?.is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Content not found: {}",
+            input.content_id
+        ))));
+    }
 
-    let record = get(action_hash.clone(), GetOptions::default())?;
-    let record = match record {
-        Some(r) => r,
-        None => return Ok(None),
+    let translation = ContentTranslation {
+        content_id: input.content_id,
+        locale: normalize_locale(&input.locale),
+        title: input.title.trim().to_string(),
+        description: input.description.trim().to_string(),
+        body: input.body.filter(|b| !b.trim().is_empty()),
+        translator_id: input.translator_id,
+        created_at: format!("{:?}", sys_time()?),
+        machine_generated: input.machine_generated,
+        draft: input.draft,
     };
+    let action_hash = create_entry(&EntryTypes::ContentTranslation(translation.clone()))?;
+
+    for existing in get_content_translations(translation.content_id.clone())? {
+        let replaced = existing.translation.locale == translation.locale
+            && (existing.translation.draft || !translation.draft);
+        if replaced {
+            if existing.translation.draft {
+                remove_from_review_queue(&existing.action_hash)?;
+            }
+            let old_target: AnyLinkableHash = existing.action_hash.into();
+            let query = LinkQuery::try_new(anchor_hash.clone(), LinkTypes::ContentToTranslations)?;
+            for link in get_links(query, GetStrategy::default())? {
+                if link.target == old_target {
+                    delete_link(link.create_link_hash, GetOptions::default())?;
+                }
+            }
+        }
+    }
+    create_link(anchor_hash, action_hash.clone(), LinkTypes::ContentToTranslations, ())?;
 
-    let chapter: PathChapter = record
-        .entry()
-        .to_app_option()
-        .map_err(|e| wasm_error!(e))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest(
-            "Could not deserialize chapter".to_string()
-        )))?;
+    if translation.draft {
+        create_link(
+            translation_review_anchor()?,
+            action_hash.clone(),
+            LinkTypes::TranslationReviewQueue,
+            LinkTag::new(translation.content_id.as_bytes().to_vec()),
+        )?;
+        // Stewards watching the review queue pick this up
+        emit_write_signal("TranslationReview", &translation.content_id, "add_translation");
+    } else {
+        emit_write_signal("ContentTranslation", &translation.content_id, "add_translation");
+    }
 
-    Ok(Some(ChapterOutput {
-        action_hash,
-        chapter,
-    }))
+    Ok(ContentTranslationOutput { action_hash, translation })
 }
 
-/// Get all chapters for a path
+/// Get all translations of a content node (drafts included), sorted by locale
 #[hdk_extern]
-pub fn get_chapters_for_path(path_id: String) -> ExternResult<Vec<ChapterWithSteps>> {
-    // Find path by ID
-    let path_anchor = StringAnchor::new("path_id", &path_id);
-    let path_anchor_hash = hash_entry(&EntryTypes::StringAnchor(path_anchor))?;
-
-    let query = LinkQuery::try_new(path_anchor_hash, LinkTypes::IdToPath)?;
-    let path_links = get_links(query, GetStrategy::default())?;
-
-    let path_link = match path_links.first() {
-        Some(l) => l,
-        None => return Ok(Vec::new()),
-    };
-
-    let path_action_hash = ActionHash::try_from(path_link.target.clone())
-        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid path action hash".to_string())))?;
-
-    // Get chapters linked to path
-    let chapter_query = LinkQuery::try_new(path_action_hash, LinkTypes::PathToChapter)?;
-    let chapter_links = get_links(chapter_query, GetStrategy::default())?;
-
-    let mut chapters = Vec::new();
-    for link in chapter_links {
-        let chapter_action_hash = ActionHash::try_from(link.target)
-            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid chapter action hash".to_string())))?;
-
-        let chapter_record = get(chapter_action_hash.clone(), GetOptions::default())?;
-        if let Some(record) = chapter_record {
-            if let Some(chapter) = record
-                .entry()
-                .to_app_option::<PathChapter>()
-                .ok()
-                .flatten()
-            {
-                // Get steps for this chapter
-                let step_query = LinkQuery::try_new(chapter_action_hash.clone(), LinkTypes::ChapterToStep)?;
-                let step_links = get_links(step_query, GetStrategy::default())?;
-
-                let mut steps = Vec::new();
-                for step_link in step_links {
-                    let step_action_hash = ActionHash::try_from(step_link.target)
-                        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid step action hash".to_string())))?;
+pub fn get_content_translations(content_id: String) -> ExternResult<Vec<ContentTranslationOutput>> {
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("content_id", &content_id)))?;
+    let query = LinkQuery::try_new(anchor_hash, LinkTypes::ContentToTranslations)?;
+
+    let mut translations = Vec::new();
+    for link in get_links(query, GetStrategy::default())? {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(action_hash.clone(), GetOptions::default())? else {
+            continue;
+        };
+        let Some(translation) = record.entry().to_app_option::<ContentTranslation>().ok().flatten() else {
+            continue;
+        };
+        translations.push(ContentTranslationOutput { action_hash, translation });
+    }
 
-                    let step_record = get(step_action_hash.clone(), GetOptions::default())?;
-                    if let Some(step_rec) = step_record {
-                        if let Some(step) = step_rec
-                            .entry()
-                            .to_app_option::<PathStep>()
-                            .ok()
-                            .flatten()
-                        {
-                            steps.push(PathStepOutput {
-                                action_hash: step_action_hash,
-                                step,
-                            });
-                        }
-                    }
-                }
+    translations.sort_by(|a, b| a.translation.locale.cmp(&b.translation.locale));
+    Ok(translations)
+}
 
-                // Sort steps by order_index
-                steps.sort_by_key(|s| s.step.order_index);
+/// Get draft translations awaiting steward review, oldest first
+#[hdk_extern]
+pub fn get_pending_translations(_: ()) -> ExternResult<Vec<ContentTranslationOutput>> {
+    let query = LinkQuery::try_new(translation_review_anchor()?, LinkTypes::TranslationReviewQueue)?;
 
-                chapters.push(ChapterWithSteps {
-                    action_hash: chapter_action_hash,
-                    chapter,
-                    steps,
-                });
-            }
-        }
+    let mut pending = Vec::new();
+    for link in get_links(query, GetStrategy::default())? {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(action_hash.clone(), GetOptions::default())? else {
+            continue;
+        };
+        let Some(translation) = record.entry().to_app_option::<ContentTranslation>().ok().flatten() else {
+            continue;
+        };
+        pending.push(ContentTranslationOutput { action_hash, translation });
     }
 
-    // Sort chapters by order_index
-    chapters.sort_by_key(|c| c.chapter.order_index);
-
-    Ok(chapters)
+    pending.sort_by(|a, b| a.translation.created_at.cmp(&b.translation.created_at));
+    Ok(pending)
 }
 
-/// Get a full path with chapters and steps organized
+/// Publish the draft translation for a locale after review.
+/// The published copy replaces the draft and any earlier translation.
 #[hdk_extern]
-pub fn get_path_full(path_id: String) -> ExternResult<Option<PathWithChaptersAndSteps>> {
-    // Find path by ID
-    let path_anchor = StringAnchor::new("path_id", &path_id);
-    let path_anchor_hash = hash_entry(&EntryTypes::StringAnchor(path_anchor))?;
-
-    let query = LinkQuery::try_new(path_anchor_hash, LinkTypes::IdToPath)?;
-    let path_links = get_links(query, GetStrategy::default())?;
-
-    let path_link = match path_links.first() {
-        Some(l) => l,
-        None => return Ok(None),
+pub fn publish_translation(input: PublishTranslationInput) -> ExternResult<ContentTranslationOutput> {
+    let locale = normalize_locale(&input.locale);
+    let Some(draft) = get_content_translations(input.content_id.clone())?
+        .into_iter()
+        .find(|t| t.translation.draft && t.translation.locale == locale)
+    else {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "No draft translation of {} for {}",
+            input.content_id, locale
+        ))));
     };
 
-    let path_action_hash = ActionHash::try_from(path_link.target.clone())
-        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid path action hash".to_string())))?;
+    let draft = draft.translation;
+    add_translation(AddTranslationInput {
+        content_id: draft.content_id,
+        locale: draft.locale,
+        title: draft.title,
+        description: draft.description,
+        body: draft.body,
+        translator_id: draft.translator_id,
+        machine_generated: draft.machine_generated,
+        draft: false,
+    })
+}
 
-    let path_record = get(path_action_hash.clone(), GetOptions::default())?;
-    let path_record = match path_record {
-        Some(r) => r,
-        None => return Ok(None),
+/// Get content with title, description and body in the best available
+/// locale: `locale`, then each of `fallback_chain`, then the original.
+#[hdk_extern]
+pub fn get_content_localized(input: GetContentLocalizedInput) -> ExternResult<Option<LocalizedContentOutput>> {
+    let Some(output) = get_content_by_id(QueryByIdInput { id: input.id.clone() })? else {
+        return Ok(None);
     };
 
-    let path: LearningPath = path_record
-        .entry()
-        .to_app_option()
-        .map_err(|e| wasm_error!(e))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest(
-            "Could not deserialize path".to_string()
-        )))?;
-
-    // Get chapters
-    let chapters = get_chapters_for_path(path_id.clone())?;
-
-    // Get all steps linked directly to path (includes those with chapters)
-    let step_query = LinkQuery::try_new(path_action_hash.clone(), LinkTypes::PathToStep)?;
-    let step_links = get_links(step_query, GetStrategy::default())?;
-
-    let mut ungrouped_steps = Vec::new();
-    for link in step_links {
-        let step_action_hash = ActionHash::try_from(link.target)
-            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid step action hash".to_string())))?;
+    let translations: Vec<ContentTranslationOutput> = get_content_translations(input.id)?
+        .into_iter()
+        .filter(|t| !t.translation.draft)
+        .collect();
+    let requested: Vec<String> = std::iter::once(&input.locale)
+        .chain(input.fallback_chain.iter())
+        .map(|l| normalize_locale(l))
+        .filter(|l| is_valid_locale(l))
+        .collect();
 
-        let step_record = get(step_action_hash.clone(), GetOptions::default())?;
-        if let Some(record) = step_record {
-            if let Some(step) = record
-                .entry()
-                .to_app_option::<PathStep>()
-                .ok()
-                .flatten()
-            {
-                // Only include steps that are NOT in a chapter
-                if step.chapter_id.is_none() {
-                    ungrouped_steps.push(PathStepOutput {
-                        action_hash: step_action_hash,
-                        step,
-                    });
-                }
-            }
+    let mut content = output.content;
+    let locale = pick_translation(&translations, &requested).map(|t| {
+        let translation = &t.translation;
+        content.title = translation.title.clone();
+        // Summaries are generated from the original and would mix languages
+        content.summary = None;
+        if !translation.description.is_empty() {
+            content.description = translation.description.clone();
         }
-    }
-
-    // Sort ungrouped steps by order_index
-    ungrouped_steps.sort_by_key(|s| s.step.order_index);
+        if let Some(ref body) = translation.body {
+            content.content = body.clone();
+            // The stored blob holds the original body
+            content.blob_cid = None;
+            content.content_hash = None;
+            content.content_size_bytes = Some(body.len() as u64);
+        }
+        translation.locale.clone()
+    });
 
-    Ok(Some(PathWithChaptersAndSteps {
-        action_hash: path_action_hash,
-        path,
-        chapters,
-        ungrouped_steps,
+    Ok(Some(LocalizedContentOutput {
+        action_hash: output.action_hash,
+        entry_hash: output.entry_hash,
+        content,
+        locale,
+        available_locales: translations.into_iter().map(|t| t.translation.locale).collect(),
     }))
 }
 
-/// Update a chapter
+// =============================================================================
+// Learning Path Operations
+// =============================================================================
+
+/// Create a learning path
 #[hdk_extern]
-pub fn update_chapter(input: UpdateChapterInput) -> ExternResult<ChapterOutput> {
-    // Get existing chapter
-    let existing = get_chapter_by_id(input.chapter_id.clone())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest(
-            format!("Chapter not found: {}", input.chapter_id)
-        )))?;
+pub fn create_path(input: CreatePathInput) -> ExternResult<ActionHash> {
+    // Check for existing path with this ID to prevent duplicates
+    if path_exists_by_id(&input.id)? {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Path with id '{}' already exists. Use update_path to modify existing paths.",
+            input.id
+        ))));
+    }
 
+    let agent_info = agent_info()?;
     let now = sys_time()?;
     let timestamp = format!("{:?}", now);
 
-    // Create updated chapter (immutable DHT pattern)
-    let updated_chapter = PathChapter {
-        id: existing.chapter.id,
-        path_id: existing.chapter.path_id.clone(),
-        order_index: input.order_index.unwrap_or(existing.chapter.order_index),
-        title: input.title.unwrap_or(existing.chapter.title),
-        description: input.description.or(existing.chapter.description),
-        learning_objectives_json: input.learning_objectives
-            .map(|lo| serde_json::to_string(&lo).unwrap_or_else(|_| "[]".to_string()))
-            .unwrap_or(existing.chapter.learning_objectives_json),
-        estimated_minutes: input.estimated_minutes.or(existing.chapter.estimated_minutes),
-        is_optional: input.is_optional.unwrap_or(existing.chapter.is_optional),
-        attestation_granted: input.attestation_granted.or(existing.chapter.attestation_granted),
-        mastery_threshold: input.mastery_threshold.or(existing.chapter.mastery_threshold),
-        metadata_json: existing.chapter.metadata_json,
-        created_at: existing.chapter.created_at,
+    let path = LearningPath {
+        id: input.id.clone(),
+        version: input.version,
+        title: input.title,
+        description: input.description,
+        purpose: input.purpose,
+        created_by: agent_info.agent_initial_pubkey.to_string(),
+        difficulty: input.difficulty,
+        estimated_duration: input.estimated_duration,
+        visibility: input.visibility,
+        path_type: input.path_type,
+        tags: input.tags,
+        metadata_json: input.metadata_json.unwrap_or_else(|| "{}".to_string()),
+        created_at: timestamp.clone(),
         updated_at: timestamp,
+        schema_version: 2,
+        validation_status: "Valid".to_string(),
     };
 
-    let action_hash = create_entry(&EntryTypes::PathChapter(updated_chapter.clone()))?;
-
-    // Update the ID lookup link
-    let chapter_anchor = StringAnchor::new("chapter_id", &input.chapter_id);
-    let chapter_anchor_hash = hash_entry(&EntryTypes::StringAnchor(chapter_anchor))?;
+    let summary_tag = PathSummary::from_path(&path).to_link_tag()?;
+    let action_hash = create_entry(&EntryTypes::LearningPath(path))?;
 
-    // Delete old link
-    let old_query = LinkQuery::try_new(chapter_anchor_hash.clone(), LinkTypes::IdToChapter)?;
-    let old_links = get_links(old_query, GetStrategy::default())?;
-    for link in old_links {
-        delete_link(link.create_link_hash, GetOptions::default())?;
-    }
+    // Create ID lookup link
+    let anchor = StringAnchor::new("path_id", &input.id);
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
+    create_link(anchor_hash, action_hash.clone(), LinkTypes::IdToPath, ())?;
 
-    // Create new link
-    create_link(chapter_anchor_hash, action_hash.clone(), LinkTypes::IdToChapter, ())?;
+    // Create global "all_paths" index link for get_all_paths()
+    // The tag carries a summary for get_all_path_summaries()
+    let all_paths_anchor = StringAnchor::new("all_paths", "index");
+    let all_paths_anchor_hash = hash_entry(&EntryTypes::StringAnchor(all_paths_anchor))?;
+    create_link(all_paths_anchor_hash, action_hash.clone(), LinkTypes::IdToPath, summary_tag)?;
 
-    // Update path-to-chapter link
-    let path_anchor = StringAnchor::new("path_id", &existing.chapter.path_id);
-    let path_anchor_hash = hash_entry(&EntryTypes::StringAnchor(path_anchor))?;
-    let path_query = LinkQuery::try_new(path_anchor_hash, LinkTypes::IdToPath)?;
-    let path_links = get_links(path_query, GetStrategy::default())?;
+    emit_write_signal("LearningPath", &input.id, "create_path");
 
-    if let Some(path_link) = path_links.first() {
-        let path_action_hash = ActionHash::try_from(path_link.target.clone())
-            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid path action hash".to_string())))?;
+    Ok(action_hash)
+}
 
-        // Delete old path-to-chapter links pointing to old chapter
-        let old_chapter_query = LinkQuery::try_new(path_action_hash.clone(), LinkTypes::PathToChapter)?;
-        let old_chapter_links = get_links(old_chapter_query, GetStrategy::default())?;
-        for link in old_chapter_links {
-            if link.target == existing.action_hash.clone().into() {
-                delete_link(link.create_link_hash, GetOptions::default())?;
-            }
-        }
-
-        // Create new link
-        create_link(path_action_hash, action_hash.clone(), LinkTypes::PathToChapter, ())?;
-    }
-
-    Ok(ChapterOutput {
-        action_hash,
-        chapter: updated_chapter,
-    })
-}
-
-// =============================================================================
-// Path Update Operations
-// =============================================================================
-
-/// Update a learning path
+/// Add a step to a learning path
 #[hdk_extern]
-pub fn update_path(input: UpdatePathInput) -> ExternResult<PathWithSteps> {
-    // Get existing path
-    let existing = get_path_with_steps(input.path_id.clone())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest(
-            format!("Path not found: {}", input.path_id)
-        )))?;
-
+pub fn add_path_step(input: AddPathStepInput) -> ExternResult<ActionHash> {
     let now = sys_time()?;
     let timestamp = format!("{:?}", now);
 
-    // Create updated path (immutable DHT pattern)
-    let updated_path = LearningPath {
-        id: existing.path.id,
-        version: existing.path.version,
-        title: input.title.unwrap_or(existing.path.title),
-        description: input.description.unwrap_or(existing.path.description),
-        purpose: input.purpose.or(existing.path.purpose),
-        created_by: existing.path.created_by,
-        difficulty: input.difficulty.unwrap_or(existing.path.difficulty),
-        estimated_duration: input.estimated_duration.or(existing.path.estimated_duration),
-        visibility: input.visibility.unwrap_or(existing.path.visibility),
-        path_type: existing.path.path_type,
-        tags: input.tags.unwrap_or(existing.path.tags),
-        metadata_json: existing.path.metadata_json,
-        created_at: existing.path.created_at,
+    // Generate step ID
+    let step_id = match &input.chapter_id {
+        Some(ch_id) => format!("{}-{}-step-{}", input.path_id, ch_id, input.order_index),
+        None => format!("{}-step-{}", input.path_id, input.order_index),
+    };
+    let is_external = input.step_type == "external";
+
+    let step = PathStep {
+        id: step_id.clone(),
+        path_id: input.path_id.clone(),
+        chapter_id: input.chapter_id.clone(),
+        module_id: input.module_id.clone(),
+        section_id: input.section_id.clone(),
+        order_index: input.order_index,
+        step_type: input.step_type,
+        resource_id: input.resource_id.clone(),
+        step_title: input.step_title,
+        step_narrative: input.step_narrative,
+        is_optional: input.is_optional,
+        // Learning objectives and engagement
+        learning_objectives_json: serde_json::to_string(&input.learning_objectives.unwrap_or_default())
+            .unwrap_or_else(|_| "[]".to_string()),
+        reflection_prompts_json: serde_json::to_string(&input.reflection_prompts.unwrap_or_default())
+            .unwrap_or_else(|_| "[]".to_string()),
+        practice_exercises_json: serde_json::to_string(&input.practice_exercises.unwrap_or_default())
+            .unwrap_or_else(|_| "[]".to_string()),
+        // Completion and gating
+        estimated_minutes: input.estimated_minutes,
+        completion_criteria: input.completion_criteria,
+        attestation_required: input.attestation_required,
+        attestation_granted: input.attestation_granted,
+        mastery_threshold: input.mastery_threshold,
+        // Metadata
+        metadata_json: input.metadata_json.unwrap_or_else(|| "{}".to_string()),
+        created_at: timestamp.clone(),
         updated_at: timestamp,
         schema_version: 2,
         validation_status: "Valid".to_string(),
     };
 
-    let action_hash = create_entry(&EntryTypes::LearningPath(updated_path.clone()))?;
+    let action_hash = create_entry(&EntryTypes::PathStep(step))?;
 
-    // Update the ID lookup link
+    // Create ID lookup link for step
+    let step_anchor = StringAnchor::new("step_id", &step_id);
+    let step_anchor_hash = hash_entry(&EntryTypes::StringAnchor(step_anchor))?;
+    create_link(step_anchor_hash, action_hash.clone(), LinkTypes::IdToStep, ())?;
+
+    // Link path to step
     let path_anchor = StringAnchor::new("path_id", &input.path_id);
     let path_anchor_hash = hash_entry(&EntryTypes::StringAnchor(path_anchor))?;
 
-    // Delete old link
-    let old_query = LinkQuery::try_new(path_anchor_hash.clone(), LinkTypes::IdToPath)?;
-    let old_links = get_links(old_query, GetStrategy::default())?;
-    for link in old_links {
-        delete_link(link.create_link_hash, GetOptions::default())?;
+    let query = LinkQuery::try_new(path_anchor_hash, LinkTypes::IdToPath)?;
+    let path_links = get_links(query, GetStrategy::default())?;
+
+    if let Some(path_link) = path_links.first() {
+        let path_action_hash = ActionHash::try_from(path_link.target.clone())
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid path action hash".to_string())))?;
+        create_link(path_action_hash, action_hash.clone(), LinkTypes::PathToStep, ())?;
     }
 
-    // Create new link
-    create_link(path_anchor_hash, action_hash.clone(), LinkTypes::IdToPath, ())?;
+    // Link to chapter if specified
+    if let Some(chapter_id) = &input.chapter_id {
+        let chapter_anchor = StringAnchor::new("chapter_id", chapter_id);
+        let chapter_anchor_hash = hash_entry(&EntryTypes::StringAnchor(chapter_anchor))?;
 
-    // Update all_paths index link
-    let all_paths_anchor = StringAnchor::new("all_paths", "index");
-    let all_paths_anchor_hash = hash_entry(&EntryTypes::StringAnchor(all_paths_anchor))?;
+        let chapter_query = LinkQuery::try_new(chapter_anchor_hash, LinkTypes::IdToChapter)?;
+        let chapter_links = get_links(chapter_query, GetStrategy::default())?;
 
-    // Delete old all_paths link
-    let old_all_query = LinkQuery::try_new(all_paths_anchor_hash.clone(), LinkTypes::IdToPath)?;
-    let old_all_links = get_links(old_all_query, GetStrategy::default())?;
-    for link in old_all_links {
-        if link.target == existing.action_hash.clone().into() {
-            delete_link(link.create_link_hash, GetOptions::default())?;
+        if let Some(chapter_link) = chapter_links.first() {
+            let chapter_action_hash = ActionHash::try_from(chapter_link.target.clone())
+                .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid chapter action hash".to_string())))?;
+            // Link chapter to step
+            create_link(chapter_action_hash.clone(), action_hash.clone(), LinkTypes::ChapterToStep, ())?;
+            // Link step back to chapter (for reverse lookup)
+            create_link(action_hash.clone(), chapter_action_hash, LinkTypes::StepToChapter, ())?;
         }
     }
 
-    // Create new all_paths link
-    create_link(all_paths_anchor_hash, action_hash.clone(), LinkTypes::IdToPath, ())?;
-
-    // Re-link all steps to new path action hash
-    for step_output in &existing.steps {
-        create_link(action_hash.clone(), step_output.action_hash.clone(), LinkTypes::PathToStep, ())?;
-    }
-
-    Ok(PathWithSteps {
-        action_hash,
-        path: updated_path,
-        steps: existing.steps,
-    })
-}
-
-/// Update a step
-#[hdk_extern]
-pub fn update_step(input: UpdateStepInput) -> ExternResult<PathStepOutput> {
-    // Get existing step by ID
-    let step_anchor = StringAnchor::new("step_id", &input.step_id);
-    let step_anchor_hash = hash_entry(&EntryTypes::StringAnchor(step_anchor))?;
+    // Link step to content (if resource exists)
+    let resource_anchor = StringAnchor::new("content_id", &input.resource_id);
+    let resource_anchor_hash = hash_entry(&EntryTypes::StringAnchor(resource_anchor))?;
 
-    let query = LinkQuery::try_new(step_anchor_hash.clone(), LinkTypes::IdToStep)?;
-    let links = get_links(query, GetStrategy::default())?;
+    let content_query = LinkQuery::try_new(resource_anchor_hash, LinkTypes::IdToContent)?;
+    let content_links = get_links(content_query, GetStrategy::default())?;
 
-    let link = links.first()
-        .ok_or(wasm_error!(WasmErrorInner::Guest(
-            format!("Step not found: {}", input.step_id)
-        )))?;
+    if let Some(content_link) = content_links.first() {
+        create_link(action_hash.clone(), content_link.target.clone(), LinkTypes::StepToContent, ())?;
+    }
 
-    let existing_action_hash = ActionHash::try_from(link.target.clone())
-        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid step action hash".to_string())))?;
+    // Queue external URLs for enrichment
+    if is_external && is_http_url(&input.resource_id) {
+        ensure_external_resource(&input.resource_id)?;
+    }
 
-    let record = get(existing_action_hash.clone(), GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Step record not found".to_string())))?;
+    emit_write_signal("PathStep", &step_id, "add_path_step");
 
-    let existing: PathStep = record
-        .entry()
-        .to_app_option()
-        .map_err(|e| wasm_error!(e))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest(
-            "Could not deserialize step".to_string()
-        )))?;
+    Ok(action_hash)
+}
 
-    let now = sys_time()?;
-    let timestamp = format!("{:?}", now);
+/// Batch add multiple steps to a path (for efficient seeding)
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BatchAddPathStepsInput {
+    pub steps: Vec<AddPathStepInput>,
+}
 
-    // Create updated step
-    let updated_step = PathStep {
-        id: existing.id,
-        path_id: existing.path_id.clone(),
-        chapter_id: input.chapter_id.or(existing.chapter_id),
-        module_id: existing.module_id,
-        section_id: existing.section_id,
-        order_index: input.order_index.unwrap_or(existing.order_index),
-        step_type: existing.step_type,
-        resource_id: existing.resource_id.clone(),
-        step_title: input.step_title.or(existing.step_title),
-        step_narrative: input.step_narrative.or(existing.step_narrative),
-        is_optional: input.is_optional.unwrap_or(existing.is_optional),
-        learning_objectives_json: input.learning_objectives
-            .map(|lo| serde_json::to_string(&lo).unwrap_or_else(|_| "[]".to_string()))
-            .unwrap_or(existing.learning_objectives_json),
-        reflection_prompts_json: input.reflection_prompts
-            .map(|rp| serde_json::to_string(&rp).unwrap_or_else(|_| "[]".to_string()))
-            .unwrap_or(existing.reflection_prompts_json),
-        practice_exercises_json: input.practice_exercises
-            .map(|pe| serde_json::to_string(&pe).unwrap_or_else(|_| "[]".to_string()))
-            .unwrap_or(existing.practice_exercises_json),
-        estimated_minutes: input.estimated_minutes.or(existing.estimated_minutes),
-        completion_criteria: input.completion_criteria.or(existing.completion_criteria),
-        attestation_required: input.attestation_required.or(existing.attestation_required),
-        attestation_granted: input.attestation_granted.or(existing.attestation_granted),
-        mastery_threshold: input.mastery_threshold.or(existing.mastery_threshold),
-        metadata_json: existing.metadata_json,
-        created_at: existing.created_at,
-        updated_at: timestamp,
-        schema_version: 2,
-        validation_status: "Valid".to_string(),
-    };
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BatchAddPathStepsOutput {
+    pub created_count: u32,
+    pub action_hashes: Vec<ActionHash>,
+    pub errors: Vec<String>,
+}
 
-    let action_hash = create_entry(&EntryTypes::PathStep(updated_step.clone()))?;
+#[hdk_extern]
+pub fn batch_add_path_steps(input: BatchAddPathStepsInput) -> ExternResult<BatchAddPathStepsOutput> {
+    let mut action_hashes = Vec::new();
+    let mut errors = Vec::new();
 
-    // Update the ID lookup link
-    // Delete old link
-    for lnk in links {
-        delete_link(lnk.create_link_hash, GetOptions::default())?;
+    for step_input in input.steps {
+        match add_path_step(step_input.clone()) {
+            Ok(hash) => {
+                action_hashes.push(hash);
+            }
+            Err(e) => {
+                errors.push(format!("Failed to add step {}: {:?}", step_input.order_index, e));
+            }
+        }
     }
 
-    // Create new link
-    create_link(step_anchor_hash, action_hash.clone(), LinkTypes::IdToStep, ())?;
-
-    // Re-link to path
-    let path_anchor = StringAnchor::new("path_id", &existing.path_id);
-    let path_anchor_hash = hash_entry(&EntryTypes::StringAnchor(path_anchor))?;
-    let path_query = LinkQuery::try_new(path_anchor_hash, LinkTypes::IdToPath)?;
-    let path_links = get_links(path_query, GetStrategy::default())?;
-
-    if let Some(path_link) = path_links.first() {
-        let path_action_hash = ActionHash::try_from(path_link.target.clone())
-            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid path action hash".to_string())))?;
-        create_link(path_action_hash, action_hash.clone(), LinkTypes::PathToStep, ())?;
+    if !action_hashes.is_empty() {
+        let _ = emit_signal(DoorwaySignal::new(
+            CacheSignal::invalidate("PathStep").from_fn("batch_add_path_steps"),
+        ));
     }
 
-    Ok(PathStepOutput {
-        action_hash,
-        step: updated_step,
+    Ok(BatchAddPathStepsOutput {
+        created_count: action_hashes.len() as u32,
+        action_hashes,
+        errors,
     })
 }
 
-/// Get a step by ID
+/// Batch check which path IDs already exist
 #[hdk_extern]
-pub fn get_step_by_id(step_id: String) -> ExternResult<Option<PathStepOutput>> {
-    let anchor = StringAnchor::new("step_id", &step_id);
-    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
-
-    let query = LinkQuery::try_new(anchor_hash, LinkTypes::IdToStep)?;
-    let links = get_links(query, GetStrategy::default())?;
+pub fn check_path_ids_exist(input: CheckIdsExistInput) -> ExternResult<CheckIdsExistOutput> {
+    let mut existing_ids = Vec::new();
 
-    let link = match links.first() {
-        Some(l) => l,
-        None => return Ok(None),
-    };
+    for id in input.ids {
+        let anchor = StringAnchor::new("path_id", &id);
+        let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
 
-    let action_hash = ActionHash::try_from(link.target.clone())
-        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid step action hash".to_string())))?;
+        let query = LinkQuery::try_new(anchor_hash, LinkTypes::IdToPath)?;
+        let links = get_links(query, GetStrategy::default())?;
 
-    let record = get(action_hash.clone(), GetOptions::default())?;
-    let record = match record {
-        Some(r) => r,
-        None => return Ok(None),
-    };
+        if !links.is_empty() {
+            existing_ids.push(id);
+        }
+    }
 
-    let step: PathStep = record
-        .entry()
-        .to_app_option()
-        .map_err(|e| wasm_error!(e))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest(
-            "Could not deserialize step".to_string()
-        )))?;
+    Ok(CheckIdsExistOutput { existing_ids })
+}
 
-    Ok(Some(PathStepOutput {
-        action_hash,
-        step,
-    }))
+/// Path index entry for listing
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PathIndexEntry {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub difficulty: String,
+    pub estimated_duration: Option<String>,
+    pub step_count: u32,
+    pub tags: Vec<String>,
 }
 
-// =============================================================================
-// Relationship Operations
-// =============================================================================
+/// Path index output
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PathIndex {
+    pub paths: Vec<PathIndexEntry>,
+    pub total_count: u32,
+    pub last_updated: String,
+}
 
-/// Create a relationship between two content nodes
+/// Get all learning paths (for path index)
+/// Uses link-based lookup via global "all_paths" anchor
 #[hdk_extern]
-pub fn create_relationship(input: CreateRelationshipInput) -> ExternResult<RelationshipOutput> {
-    let now = sys_time()?;
-    let timestamp = format!("{:?}", now);
+pub fn get_all_paths(_: ()) -> ExternResult<PathIndex> {
+    // Use a global anchor to find all paths
+    let anchor = StringAnchor::new("all_paths", "index");
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
 
-    // Generate relationship ID
-    let rel_id = format!("{}-{}-{}", input.source_id, input.relationship_type, input.target_id);
+    let query = LinkQuery::try_new(anchor_hash, LinkTypes::IdToPath)?;
+    let links = get_links(query, GetStrategy::default())?;
 
-    let relationship = Relationship {
-        id: rel_id.clone(),
-        source_id: input.source_id.clone(),
-        target_id: input.target_id.clone(),
-        relationship_type: input.relationship_type.clone(),
-        confidence: input.confidence,
-        inference_source: input.inference_source,
-        metadata_json: input.metadata_json,
-        created_at: timestamp,
-    };
+    let mut hashes = Vec::with_capacity(links.len());
+    for link in links {
+        let action_hash = ActionHash::try_from(link.target)
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid action hash in link".to_string())))?;
+        hashes.push(action_hash);
+    }
 
-    let action_hash = create_entry(&EntryTypes::Relationship(relationship.clone()))?;
+    // Fetch all path records in one batched call
+    let records = get_records_batch(hashes.clone())?;
 
-    // Create index links for querying
-    // By source
-    let source_anchor = StringAnchor::new("rel_source", &input.source_id);
-    let source_anchor_hash = hash_entry(&EntryTypes::StringAnchor(source_anchor))?;
-    create_link(source_anchor_hash, action_hash.clone(), LinkTypes::RelationshipBySource, ())?;
+    let mut paths = Vec::new();
 
-    // By target
-    let target_anchor = StringAnchor::new("rel_target", &input.target_id);
-    let target_anchor_hash = hash_entry(&EntryTypes::StringAnchor(target_anchor))?;
-    create_link(target_anchor_hash, action_hash.clone(), LinkTypes::RelationshipByTarget, ())?;
+    for (action_hash, record) in hashes.into_iter().zip(records) {
+        if let Some(path) = record.and_then(|r| r.entry().to_app_option::<LearningPath>().ok().flatten()) {
+            // Count steps for this path
+            let step_query = LinkQuery::try_new(action_hash, LinkTypes::PathToStep)?;
+            let step_links = get_links(step_query, GetStrategy::default())?;
+
+            paths.push(PathIndexEntry {
+                id: path.id,
+                title: path.title,
+                description: path.description,
+                difficulty: path.difficulty,
+                estimated_duration: path.estimated_duration,
+                step_count: step_links.len() as u32,
+                tags: path.tags,
+            });
+        }
+    }
 
-    // By type
-    let type_anchor = StringAnchor::new("rel_type", &input.relationship_type);
-    let type_anchor_hash = hash_entry(&EntryTypes::StringAnchor(type_anchor))?;
-    create_link(type_anchor_hash, action_hash.clone(), LinkTypes::ContentRelationshipByType, ())?;
+    let now = sys_time()?;
 
-    Ok(RelationshipOutput {
-        action_hash,
-        relationship,
+    Ok(PathIndex {
+        total_count: paths.len() as u32,
+        paths,
+        last_updated: format!("{:?}", now),
     })
 }
 
-/// Get relationships for a content node
-#[hdk_extern]
-pub fn get_relationships(input: GetRelationshipsInput) -> ExternResult<Vec<RelationshipOutput>> {
-    let mut results = Vec::new();
-
-    // Get outgoing relationships (this content is source)
-    if input.direction == "outgoing" || input.direction == "both" {
-        let source_anchor = StringAnchor::new("rel_source", &input.content_id);
-        let source_anchor_hash = hash_entry(&EntryTypes::StringAnchor(source_anchor))?;
-        let query = LinkQuery::try_new(source_anchor_hash, LinkTypes::RelationshipBySource)?;
-        let links = get_links(query, GetStrategy::default())?;
+/// Path listing summary stored in the all_paths index link tag
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PathSummary {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub difficulty: String,
+    #[serde(default)]
+    pub estimated_duration: Option<String>,
+    #[serde(default)]
+    pub visibility: String,
+    #[serde(default)]
+    pub updated_at: String,
+}
 
-        for link in links {
-            let action_hash = ActionHash::try_from(link.target)
-                .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid relationship hash".to_string())))?;
-            if let Some(output) = get_relationship(action_hash)? {
-                results.push(output);
-            }
+impl PathSummary {
+    fn from_path(path: &LearningPath) -> Self {
+        Self {
+            id: path.id.clone(),
+            title: truncate_for_tag(&path.title, LINK_TAG_TITLE_MAX),
+            difficulty: path.difficulty.clone(),
+            estimated_duration: path.estimated_duration.clone(),
+            visibility: path.visibility.clone(),
+            updated_at: path.updated_at.clone(),
         }
     }
 
-    // Get incoming relationships (this content is target)
-    if input.direction == "incoming" || input.direction == "both" {
-        let target_anchor = StringAnchor::new("rel_target", &input.content_id);
-        let target_anchor_hash = hash_entry(&EntryTypes::StringAnchor(target_anchor))?;
-        let query = LinkQuery::try_new(target_anchor_hash, LinkTypes::RelationshipByTarget)?;
-        let links = get_links(query, GetStrategy::default())?;
+    fn to_link_tag(&self) -> ExternResult<LinkTag> {
+        let bytes = serde_json::to_vec(self)
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Failed to encode link tag: {}", e))))?;
+        Ok(LinkTag::new(bytes))
+    }
+}
 
-        for link in links {
-            let action_hash = ActionHash::try_from(link.target)
-                .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid relationship hash".to_string())))?;
-            if let Some(output) = get_relationship(action_hash)? {
-                // Avoid duplicates if direction is "both"
-                if !results.iter().any(|r| r.relationship.id == output.relationship.id) {
-                    results.push(output);
-                }
+/// Path summary listing output
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PathSummaryIndex {
+    pub paths: Vec<PathSummaryEntry>,
+    pub total_count: u32,
+}
+
+/// A path summary with the action hash it was read from
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PathSummaryEntry {
+    pub action_hash: ActionHash,
+    #[serde(flatten)]
+    pub summary: PathSummary,
+}
+
+/// Listing variant of get_all_paths that reads only the all_paths link tags.
+/// Omits description, tags and step_count; use get_path_overview for details.
+#[hdk_extern]
+pub fn get_all_path_summaries(_: ()) -> ExternResult<PathSummaryIndex> {
+    path_summaries_in(StringAnchor::new("all_paths", "index"))
+}
+
+/// Summaries of archived paths, for restoring them
+#[hdk_extern]
+pub fn get_archived_paths(_: ()) -> ExternResult<PathSummaryIndex> {
+    path_summaries_in(StringAnchor::new(ARCHIVED_PATHS_ANCHOR, "index"))
+}
+
+/// Read path summaries from the link tags under an index anchor
+fn path_summaries_in(anchor: StringAnchor) -> ExternResult<PathSummaryIndex> {
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
+
+    let query = LinkQuery::try_new(anchor_hash, LinkTypes::IdToPath)?;
+    let links = get_links(query, GetStrategy::default())?;
+
+    let mut paths = Vec::new();
+    for link in links {
+        let action_hash = ActionHash::try_from(link.target)
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid action hash in link".to_string())))?;
+
+        let summary: PathSummary = serde_json::from_slice(&link.tag.0).unwrap_or_default();
+        if !summary.id.is_empty() {
+            paths.push(PathSummaryEntry { action_hash, summary });
+            continue;
+        }
+
+        // Untagged link from before summaries were stored: fall back to a get
+        if let Some(record) = get(action_hash.clone(), GetOptions::default())? {
+            if let Some(path) = record.entry().to_app_option::<LearningPath>().ok().flatten() {
+                paths.push(PathSummaryEntry {
+                    action_hash,
+                    summary: PathSummary::from_path(&path),
+                });
             }
         }
     }
 
-    Ok(results)
+    Ok(PathSummaryIndex {
+        total_count: paths.len() as u32,
+        paths,
+    })
 }
 
-/// Get a relationship by action hash
-fn get_relationship(action_hash: ActionHash) -> ExternResult<Option<RelationshipOutput>> {
-    let record = get(action_hash.clone(), GetOptions::default())?;
+/// Anchor type of the index archived paths move to; get_all_paths skips it
+const ARCHIVED_PATHS_ANCHOR: &str = "archived_paths";
 
-    match record {
-        Some(record) => {
-            let relationship: Relationship = record
-                .entry()
-                .to_app_option()
-                .map_err(|e| wasm_error!(e))?
-                .ok_or(wasm_error!(WasmErrorInner::Guest(
-                    "Could not deserialize relationship".to_string()
-                )))?;
+/// Input for delete_path: a bare path ID, or an ID with `force`
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(untagged)]
+pub enum DeletePathInput {
+    Id(String),
+    WithOptions {
+        path_id: String,
+        /// Confirms the unrecoverable unlink (default false)
+        #[serde(default)]
+        force: bool,
+    },
+}
 
-            Ok(Some(RelationshipOutput {
-                action_hash,
-                relationship,
-            }))
-        }
+/// Action hash of a path's current version, by path ID
+fn find_path_action_hash(path_id: &str) -> ExternResult<Option<ActionHash>> {
+    let anchor = StringAnchor::new("path_id", path_id);
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
+
+    let query = LinkQuery::try_new(anchor_hash, LinkTypes::IdToPath)?;
+    match get_links(query, GetStrategy::default())?.first() {
+        Some(link) => ActionHash::try_from(link.target.clone())
+            .map(Some)
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid path action hash".to_string()))),
         None => Ok(None),
     }
 }
 
-/// Get related content for a content node (follows relationships)
-#[hdk_extern]
-pub fn query_related_content(input: QueryRelatedContentInput) -> ExternResult<Vec<ContentOutput>> {
-    let relationships = get_relationships(GetRelationshipsInput {
-        content_id: input.content_id.clone(),
-        direction: "outgoing".to_string(),
-    })?;
+/// Move a path's index link between the listed and archived indexes,
+/// keeping its summary tag. Returns false if the path isn't in `from`.
+fn move_path_index_link(from: &str, to: &str, path_action_hash: &ActionHash) -> ExternResult<bool> {
+    let from_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new(from, "index")))?;
+    let to_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new(to, "index")))?;
 
-    let mut results = Vec::new();
+    let query = LinkQuery::try_new(from_hash, LinkTypes::IdToPath)?;
+    let target: AnyLinkableHash = path_action_hash.clone().into();
+    let Some(link) = get_links(query, GetStrategy::default())?
+        .into_iter()
+        .find(|link| link.target == target)
+    else {
+        return Ok(false);
+    };
 
-    for rel_output in relationships {
-        // Filter by relationship type if specified
-        if let Some(ref types) = input.relationship_types {
-            if !types.contains(&rel_output.relationship.relationship_type) {
-                continue;
-            }
-        }
+    delete_link(link.create_link_hash, GetOptions::default())?;
+    create_link(to_hash, path_action_hash.clone(), LinkTypes::IdToPath, link.tag)?;
+    Ok(true)
+}
 
-        // Get the target content
-        if let Some(content) = get_content_by_id(QueryByIdInput {
-            id: rel_output.relationship.target_id,
-        })? {
-            results.push(content);
-        }
+/// Archive a learning path: it leaves get_all_paths but keeps its steps and
+/// stays readable by ID until restored. Returns false if the path isn't listed.
+#[hdk_extern]
+pub fn archive_path(path_id: String) -> ExternResult<bool> {
+    let Some(path_action_hash) = find_path_action_hash(&path_id)? else {
+        return Ok(false);
+    };
+    if !move_path_index_link("all_paths", ARCHIVED_PATHS_ANCHOR, &path_action_hash)? {
+        return Ok(false);
     }
 
-    Ok(results)
+    emit_write_signal("LearningPath", &path_id, "archive_path");
+    Ok(true)
 }
 
-/// Get content graph starting from a root node
+/// Restore an archived learning path to get_all_paths.
+/// Returns false if the path isn't archived.
 #[hdk_extern]
-pub fn get_content_graph(input: QueryRelatedContentInput) -> ExternResult<ContentGraph> {
-    let root = get_content_by_id(QueryByIdInput {
-        id: input.content_id.clone(),
-    })?;
+pub fn restore_path(path_id: String) -> ExternResult<bool> {
+    let Some(path_action_hash) = find_path_action_hash(&path_id)? else {
+        return Ok(false);
+    };
+    if !move_path_index_link(ARCHIVED_PATHS_ANCHOR, "all_paths", &path_action_hash)? {
+        return Ok(false);
+    }
 
-    let relationships = get_relationships(GetRelationshipsInput {
-        content_id: input.content_id.clone(),
-        direction: "outgoing".to_string(),
-    })?;
+    emit_write_signal("LearningPath", &path_id, "restore_path");
+    Ok(true)
+}
 
-    let mut related = Vec::new();
-    let mut total_nodes = if root.is_some() { 1 } else { 0 };
-
-    for rel_output in relationships {
-        if let Some(content) = get_content_by_id(QueryByIdInput {
-            id: rel_output.relationship.target_id,
-        })? {
-            total_nodes += 1;
-            related.push(ContentGraphNode {
-                content,
-                relationship_type: rel_output.relationship.relationship_type,
-                confidence: rel_output.relationship.confidence,
-                children: Vec::new(), // TODO: Recursive traversal for depth > 1
-            });
+/// Delete a learning path and its steps (removes links, entries remain in DHT)
+/// Used for re-seeding paths with corrected step resource IDs. Deletion can't
+/// be undone from the UI, so it needs `{ path_id, force: true }`; use
+/// archive_path otherwise.
+#[hdk_extern]
+pub fn delete_path(input: DeletePathInput) -> ExternResult<bool> {
+    let path_id = match input {
+        DeletePathInput::WithOptions { path_id, force: true } => path_id,
+        DeletePathInput::Id(path_id) | DeletePathInput::WithOptions { path_id, .. } => {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                "Deleting path {} is permanent: archive_path it instead, or pass force: true",
+                path_id
+            ))));
         }
-    }
+    };
 
-    Ok(ContentGraph {
-        root,
-        related,
-        total_nodes,
-    })
-}
+    // Find path by ID
+    let anchor = StringAnchor::new("path_id", &path_id);
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
 
-// =============================================================================
-// Human CRUD operations moved to: holochain/dna/imagodei/zomes/imagodei/
+    let query = LinkQuery::try_new(anchor_hash.clone(), LinkTypes::IdToPath)?;
+    let path_links = get_links(query, GetStrategy::default())?;
 
-// =============================================================================
-// Existence Check Helpers
-// =============================================================================
+    let path_link = match path_links.first() {
+        Some(link) => link,
+        None => return Ok(false), // Path not found
+    };
 
-/// Check if content with the given ID already exists.
-/// Used to prevent duplicate entries during create operations.
-fn content_exists_by_id(id: &str) -> ExternResult<bool> {
-    let anchor = StringAnchor::new("content_id", id);
-    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
-    let query = LinkQuery::try_new(anchor_hash, LinkTypes::IdToContent)?;
-    let links = get_links(query, GetStrategy::default())?;
-    Ok(!links.is_empty())
-}
+    let path_action_hash = ActionHash::try_from(path_link.target.clone())
+        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid path action hash".to_string())))?;
 
-/// Check if a learning path with the given ID already exists.
-fn path_exists_by_id(id: &str) -> ExternResult<bool> {
-    let anchor = StringAnchor::new("path_id", id);
-    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
-    let query = LinkQuery::try_new(anchor_hash, LinkTypes::IdToPath)?;
-    let links = get_links(query, GetStrategy::default())?;
-    Ok(!links.is_empty())
-}
+    // Delete path-to-step links and step entries
+    let step_query = LinkQuery::try_new(path_action_hash.clone(), LinkTypes::PathToStep)?;
+    let step_links = get_links(step_query, GetStrategy::default())?;
 
-// =============================================================================
-// Link Helper Functions
-// =============================================================================
+    for link in step_links {
+        // Delete the link from path to step
+        delete_link(link.create_link_hash, GetOptions::default())?;
+    }
 
-fn create_id_to_content_link(id: &str, target: &ActionHash) -> ExternResult<()> {
-    let anchor = StringAnchor::new("content_id", id);
-    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
-    create_link(anchor_hash, target.clone(), LinkTypes::IdToContent, ())?;
-    Ok(())
-}
+    // Delete the ID-to-path link
+    delete_link(path_link.create_link_hash.clone(), GetOptions::default())?;
 
-fn create_type_to_content_link(content_type: &str, target: &ActionHash) -> ExternResult<()> {
-    let anchor = StringAnchor::new("content_type", content_type);
-    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
-    create_link(anchor_hash, target.clone(), LinkTypes::TypeToContent, ())?;
-    Ok(())
-}
+    // Delete the index link, whether the path is listed or archived
+    for index in ["all_paths", ARCHIVED_PATHS_ANCHOR] {
+        let index_anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new(index, "index")))?;
+        let index_query = LinkQuery::try_new(index_anchor_hash, LinkTypes::IdToPath)?;
+        let index_links = get_links(index_query, GetStrategy::default())?;
 
-fn create_tag_to_content_link(tag: &str, target: &ActionHash) -> ExternResult<()> {
-    let anchor = StringAnchor::new("tag", tag);
-    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
-    create_link(anchor_hash, target.clone(), LinkTypes::TagToContent, ())?;
-    Ok(())
-}
+        if let Some(link) = index_links.into_iter().find(|link| link.target == path_link.target) {
+            delete_link(link.create_link_hash, GetOptions::default())?;
+            break;
+        }
+    }
 
-fn create_author_to_content_link(target: &ActionHash) -> ExternResult<()> {
-    let agent_info = agent_info()?;
-    create_link(
-        agent_info.agent_initial_pubkey,
-        target.clone(),
-        LinkTypes::AuthorToContent,
-        (),
-    )?;
-    Ok(())
+    emit_write_signal("LearningPath", &path_id, "delete_path");
+
+    Ok(true)
 }
 
-fn create_import_batch_link(import_id: &str, target: &ActionHash) -> ExternResult<()> {
-    let anchor = StringAnchor::new("import_batch", import_id);
+/// Get a learning path with all its steps
+/// Step records are fetched in one batched host call; pass
+/// `{ path_id, include_content: true }` to also resolve step content.
+#[hdk_extern]
+pub fn get_path_with_steps(input: PathReadInput) -> ExternResult<Option<PathWithSteps>> {
+    // Find path by ID
+    let anchor = StringAnchor::new("path_id", input.path_id());
     let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
-    create_link(anchor_hash, target.clone(), LinkTypes::ImportBatchToContent, ())?;
-    Ok(())
-}
 
-// =============================================================================
-// Agent CRUD operations moved to: holochain/dna/imagodei/zomes/imagodei/
+    let query = LinkQuery::try_new(anchor_hash, LinkTypes::IdToPath)?;
+    let path_links = get_links(query, GetStrategy::default())?;
 
-// =============================================================================
-// Agent progress operations moved to: holochain/dna/imagodei/zomes/imagodei/
+    let path_link = match path_links.first() {
+        Some(link) => link,
+        None => return Ok(None),
+    };
 
-// =============================================================================
-// Content mastery CRUD moved to: holochain/dna/imagodei/zomes/imagodei/
+    let path_action_hash = ActionHash::try_from(path_link.target.clone())
+        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid path action hash".to_string())))?;
 
-// =============================================================================
-// Attestation operations moved to: holochain/dna/imagodei/zomes/imagodei/
+    let path_record = get(path_action_hash.clone(), GetOptions::default())?;
+    let path_record = match path_record {
+        Some(r) => r,
+        None => return Ok(None),
+    };
 
-// =============================================================================
-// Agent presence operations moved to: holochain/dna/imagodei/zomes/imagodei/
+    let path: LearningPath = path_record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(
+            "Could not deserialize path".to_string()
+        )))?;
 
-// =============================================================================
-// Progress Tracking Operations
-// =============================================================================
-// NOTE: Progress tracking uses AgentToPathProgress link which remains in lamad
-// as it tracks learning path progress, a core lamad concern.
-// Agent/Human progress (identity-based) is in imagodei.
+    // Get steps
+    let step_query = LinkQuery::try_new(path_action_hash.clone(), LinkTypes::PathToStep)?;
+    let step_links = get_links(step_query, GetStrategy::default())?;
 
-/// Start tracking progress on a learning path
-#[hdk_extern]
-pub fn start_path_progress(input: StartPathProgressInput) -> ExternResult<AgentProgressOutput> {
-    let agent_info = agent_info()?;
-    let agent_id = agent_info.agent_initial_pubkey.to_string();
-    let now = sys_time()?;
-    let timestamp = format!("{:?}", now);
+    let mut steps = fetch_step_outputs(step_links, input.include_content())?;
 
-    // Generate progress ID
-    let progress_id = format!("{}-{}", agent_id, input.path_id);
+    // Sort steps by order_index
+    steps.sort_by_key(|s| s.step.order_index);
 
-    // Check if progress already exists
-    let progress_anchor = StringAnchor::new("progress_id", &progress_id);
-    let progress_anchor_hash = hash_entry(&EntryTypes::StringAnchor(progress_anchor.clone()))?;
+    Ok(Some(PathWithSteps {
+        action_hash: path_action_hash,
+        path,
+        steps,
+    }))
+}
 
-    let existing_query = LinkQuery::try_new(progress_anchor_hash.clone(), LinkTypes::AgentToPathProgress)?;
-    let existing_links = get_links(existing_query, GetStrategy::default())?;
+/// Fetch many records in a single host call (the conductor resolves them concurrently).
+/// Results are in the same order as `hashes`.
+fn get_records_batch(hashes: Vec<ActionHash>) -> ExternResult<Vec<Option<Record>>> {
+    if hashes.is_empty() {
+        return Ok(Vec::new());
+    }
+    let inputs: Vec<GetInput> = hashes
+        .into_iter()
+        .map(|hash| GetInput::new(hash.into(), GetOptions::default()))
+        .collect();
+    hdk::hdk::HDK.with(|hdk| hdk.borrow().get(inputs))
+}
 
-    if let Some(link) = existing_links.first() {
-        // Progress already exists, return it
-        let action_hash = ActionHash::try_from(link.target.clone())
-            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid progress action hash".to_string())))?;
-        let record = get(action_hash.clone(), GetOptions::default())?
-            .ok_or(wasm_error!(WasmErrorInner::Guest("Progress record not found".to_string())))?;
-        let progress: AgentProgress = record
-            .entry()
-            .to_app_option()
-            .map_err(|e| wasm_error!(e))?
-            .ok_or(wasm_error!(WasmErrorInner::Guest("Could not deserialize progress".to_string())))?;
-        return Ok(AgentProgressOutput { action_hash, progress });
+/// Resolve step links to step outputs with batched gets.
+/// With `include_content`, content-type steps also get their Content resolved
+/// (one more batched round for StepToContent targets) and external steps
+/// their ExternalResource.
+fn fetch_step_outputs(step_links: Vec<Link>, include_content: bool) -> ExternResult<Vec<PathStepOutput>> {
+    let mut hashes = Vec::with_capacity(step_links.len());
+    for link in step_links {
+        let step_action_hash = ActionHash::try_from(link.target)
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid step action hash".to_string())))?;
+        hashes.push(step_action_hash);
     }
 
-    // Create new progress
-    let progress = AgentProgress {
-        id: progress_id.clone(),
-        agent_id: agent_id.clone(),
-        path_id: input.path_id.clone(),
-        current_step_index: 0,
-        completed_step_indices: Vec::new(),
-        completed_content_ids: Vec::new(),
-        step_affinity_json: "{}".to_string(),
-        step_notes_json: "{}".to_string(),
-        reflection_responses_json: "{}".to_string(),
-        attestations_earned: Vec::new(),
-        started_at: timestamp.clone(),
-        last_activity_at: timestamp,
-        completed_at: None,
-    };
+    let records = get_records_batch(hashes.clone())?;
 
-    let action_hash = create_entry(&EntryTypes::AgentProgress(progress.clone()))?;
+    let mut steps = Vec::new();
+    for (step_action_hash, record) in hashes.into_iter().zip(records) {
+        if let Some(step) = record.and_then(|r| r.entry().to_app_option::<PathStep>().ok().flatten()) {
+            steps.push(PathStepOutput {
+                action_hash: step_action_hash,
+                step,
+                content: None,
+                external: None,
+            });
+        }
+    }
 
-    // Create ID lookup link
-    create_link(progress_anchor_hash, action_hash.clone(), LinkTypes::AgentToPathProgress, ())?;
+    if include_content {
+        attach_step_content(&mut steps)?;
+    }
 
-    // Create agent-to-progress link
-    let agent_anchor = StringAnchor::new("agent_progress", &agent_id);
-    let agent_anchor_hash = hash_entry(&EntryTypes::StringAnchor(agent_anchor))?;
-    create_link(agent_anchor_hash, action_hash.clone(), LinkTypes::AgentToPathProgress, ())?;
+    Ok(steps)
+}
 
-    // Create path-to-progress link
-    let path_progress_anchor = StringAnchor::new("path_progress", &input.path_id);
-    let path_progress_anchor_hash = hash_entry(&EntryTypes::StringAnchor(path_progress_anchor))?;
-    create_link(path_progress_anchor_hash, action_hash.clone(), LinkTypes::PathToProgress, ())?;
+/// Resolve the Content behind each "content" step via its StepToContent link,
+/// and the ExternalResource behind each "external" step via its URL
+fn attach_step_content(steps: &mut [PathStepOutput]) -> ExternResult<()> {
+    let mut targets: Vec<(usize, ActionHash)> = Vec::new();
+    for (i, output) in steps.iter_mut().enumerate() {
+        if output.step.step_type == "external" {
+            output.external = external_resource_by_url(&output.step.resource_id)?.map(|r| r.resource);
+            continue;
+        }
+        if output.step.step_type != "content" {
+            continue;
+        }
+        let query = LinkQuery::try_new(output.action_hash.clone(), LinkTypes::StepToContent)?;
+        if let Some(link) = get_links(query, GetStrategy::default())?.into_iter().next() {
+            if let Ok(content_hash) = ActionHash::try_from(link.target) {
+                targets.push((i, content_hash));
+            }
+        }
+    }
 
-    // Create status link (in_progress)
-    let status_anchor = StringAnchor::new("progress_status", "in_progress");
-    let status_anchor_hash = hash_entry(&EntryTypes::StringAnchor(status_anchor))?;
-    create_link(status_anchor_hash, action_hash.clone(), LinkTypes::ProgressByStatus, ())?;
+    let records = get_records_batch(targets.iter().map(|(_, h)| h.clone()).collect())?;
+    for ((i, action_hash), record) in targets.into_iter().zip(records) {
+        let record = match record {
+            Some(r) => r,
+            None => continue,
+        };
+        if let Some(content) = record.entry().to_app_option::<Content>().ok().flatten() {
+            let entry_hash = match record.action().entry_hash() {
+                Some(h) => h.clone(),
+                None => continue,
+            };
+            steps[i].content = Some(ContentOutput {
+                action_hash,
+                entry_hash,
+                content,
+            });
+        }
+    }
 
-    Ok(AgentProgressOutput { action_hash, progress })
+    Ok(())
 }
 
-/// Complete a step in a learning path
+/// Get a lightweight path overview (no step content, just metadata and count)
+///
+/// This is MUCH faster than get_path_with_steps because it:
+/// - Only counts step links instead of fetching each step record
+/// - Returns path.metadata_json which contains chapter structure
+///
+/// Use for: path listings, path-overview page, initial navigation
 #[hdk_extern]
-pub fn complete_step(input: CompleteStepInput) -> ExternResult<AgentProgressOutput> {
-    let agent_info = agent_info()?;
-    let agent_id = agent_info.agent_initial_pubkey.to_string();
-    let now = sys_time()?;
-    let timestamp = format!("{:?}", now);
-
-    // Find existing progress
-    let progress_id = format!("{}-{}", agent_id, input.path_id);
-    let progress_anchor = StringAnchor::new("progress_id", &progress_id);
-    let progress_anchor_hash = hash_entry(&EntryTypes::StringAnchor(progress_anchor))?;
+pub fn get_path_overview(path_id: String) -> ExternResult<Option<PathOverview>> {
+    // Find path by ID
+    let anchor = StringAnchor::new("path_id", &path_id);
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
 
-    let query = LinkQuery::try_new(progress_anchor_hash.clone(), LinkTypes::AgentToPathProgress)?;
-    let links = get_links(query, GetStrategy::default())?;
+    let query = LinkQuery::try_new(anchor_hash, LinkTypes::IdToPath)?;
+    let path_links = get_links(query, GetStrategy::default())?;
 
-    let link = links.first()
-        .ok_or(wasm_error!(WasmErrorInner::Guest(
-            format!("No progress found for path: {}", input.path_id)
-        )))?;
+    let path_link = match path_links.first() {
+        Some(link) => link,
+        None => return Ok(None),
+    };
 
-    let existing_action_hash = ActionHash::try_from(link.target.clone())
-        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid progress action hash".to_string())))?;
+    let path_action_hash = ActionHash::try_from(path_link.target.clone())
+        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid path action hash".to_string())))?;
 
-    let record = get(existing_action_hash.clone(), GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Progress record not found".to_string())))?;
+    let path_record = get(path_action_hash.clone(), GetOptions::default())?;
+    let path_record = match path_record {
+        Some(r) => r,
+        None => return Ok(None),
+    };
 
-    let existing: AgentProgress = record
+    let path: LearningPath = path_record
         .entry()
         .to_app_option()
         .map_err(|e| wasm_error!(e))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not deserialize progress".to_string())))?;
-
-    // Parse existing JSON data
-    let mut step_affinity: HashMap<String, u32> = serde_json::from_str(&existing.step_affinity_json)
-        .unwrap_or_default();
-    let mut step_notes: HashMap<String, String> = serde_json::from_str(&existing.step_notes_json)
-        .unwrap_or_default();
-    let mut reflection_responses: HashMap<String, Vec<String>> = serde_json::from_str(&existing.reflection_responses_json)
-        .unwrap_or_default();
-
-    // Update with new data
-    let step_key = input.step_index.to_string();
-    if let Some(affinity) = input.affinity_score {
-        step_affinity.insert(step_key.clone(), affinity);
-    }
-    if let Some(notes) = input.notes {
-        step_notes.insert(step_key.clone(), notes);
-    }
-    if let Some(responses) = input.reflection_responses {
-        reflection_responses.insert(step_key, responses);
-    }
-
-    // Update completed steps
-    let mut completed_step_indices = existing.completed_step_indices.clone();
-    if !completed_step_indices.contains(&input.step_index) {
-        completed_step_indices.push(input.step_index);
-        completed_step_indices.sort();
-    }
-
-    // Update completed content IDs
-    let mut completed_content_ids = existing.completed_content_ids.clone();
-    if let Some(content_id) = input.content_id {
-        if !completed_content_ids.contains(&content_id) {
-            completed_content_ids.push(content_id);
-        }
-    }
-
-    // Create updated progress
-    let updated_progress = AgentProgress {
-        id: existing.id,
-        agent_id: existing.agent_id,
-        path_id: existing.path_id.clone(),
-        current_step_index: input.step_index + 1,  // Advance to next step
-        completed_step_indices,
-        completed_content_ids,
-        step_affinity_json: serde_json::to_string(&step_affinity).unwrap_or_else(|_| "{}".to_string()),
-        step_notes_json: serde_json::to_string(&step_notes).unwrap_or_else(|_| "{}".to_string()),
-        reflection_responses_json: serde_json::to_string(&reflection_responses).unwrap_or_else(|_| "{}".to_string()),
-        attestations_earned: existing.attestations_earned,
-        started_at: existing.started_at,
-        last_activity_at: timestamp,
-        completed_at: existing.completed_at,
-    };
-
-    let action_hash = create_entry(&EntryTypes::AgentProgress(updated_progress.clone()))?;
+        .ok_or(wasm_error!(WasmErrorInner::Guest(
+            "Could not deserialize path".to_string()
+        )))?;
 
-    // Update ID lookup link
-    delete_link(link.create_link_hash.clone(), GetOptions::default())?;
-    create_link(progress_anchor_hash, action_hash.clone(), LinkTypes::AgentToPathProgress, ())?;
+    // Just count step links - don't fetch each step record
+    let step_query = LinkQuery::try_new(path_action_hash.clone(), LinkTypes::PathToStep)?;
+    let step_links = get_links(step_query, GetStrategy::default())?;
+    let step_count = step_links.len();
 
-    Ok(AgentProgressOutput { action_hash, progress: updated_progress })
+    Ok(Some(PathOverview {
+        action_hash: path_action_hash,
+        path,
+        step_count,
+    }))
 }
 
-/// Mark a path as completed
+// =============================================================================
+// Chapter Operations
+// =============================================================================
+
+/// Create a chapter for a learning path
 #[hdk_extern]
-pub fn complete_path(path_id: String) -> ExternResult<AgentProgressOutput> {
-    let agent_info = agent_info()?;
-    let agent_id = agent_info.agent_initial_pubkey.to_string();
+pub fn create_chapter(input: CreateChapterInput) -> ExternResult<ChapterOutput> {
     let now = sys_time()?;
     let timestamp = format!("{:?}", now);
 
-    // Find existing progress
-    let progress_id = format!("{}-{}", agent_id, path_id);
-    let progress_anchor = StringAnchor::new("progress_id", &progress_id);
-    let progress_anchor_hash = hash_entry(&EntryTypes::StringAnchor(progress_anchor))?;
-
-    let query = LinkQuery::try_new(progress_anchor_hash.clone(), LinkTypes::AgentToPathProgress)?;
-    let links = get_links(query, GetStrategy::default())?;
-
-    let link = links.first()
-        .ok_or(wasm_error!(WasmErrorInner::Guest(
-            format!("No progress found for path: {}", path_id)
-        )))?;
-
-    let existing_action_hash = ActionHash::try_from(link.target.clone())
-        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid progress action hash".to_string())))?;
+    // Generate chapter ID
+    let chapter_id = format!("{}-chapter-{}", input.path_id, input.order_index);
 
-    let record = get(existing_action_hash.clone(), GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Progress record not found".to_string())))?;
+    let chapter = PathChapter {
+        id: chapter_id.clone(),
+        path_id: input.path_id.clone(),
+        order_index: input.order_index,
+        title: input.title,
+        description: input.description,
+        learning_objectives_json: serde_json::to_string(&input.learning_objectives)
+            .unwrap_or_else(|_| "[]".to_string()),
+        estimated_minutes: input.estimated_minutes,
+        is_optional: input.is_optional,
+        attestation_granted: input.attestation_granted,
+        mastery_threshold: input.mastery_threshold,
+        metadata_json: input.metadata_json.unwrap_or_else(|| "{}".to_string()),
+        created_at: timestamp.clone(),
+        updated_at: timestamp,
+    };
 
-    let existing: AgentProgress = record
-        .entry()
-        .to_app_option()
-        .map_err(|e| wasm_error!(e))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not deserialize progress".to_string())))?;
+    let action_hash = create_entry(&EntryTypes::PathChapter(chapter.clone()))?;
 
-    // Create completed progress
-    let completed_progress = AgentProgress {
-        id: existing.id,
-        agent_id: existing.agent_id,
-        path_id: existing.path_id.clone(),
-        current_step_index: existing.current_step_index,
-        completed_step_indices: existing.completed_step_indices,
-        completed_content_ids: existing.completed_content_ids,
-        step_affinity_json: existing.step_affinity_json,
-        step_notes_json: existing.step_notes_json,
-        reflection_responses_json: existing.reflection_responses_json,
-        attestations_earned: existing.attestations_earned,
-        started_at: existing.started_at,
-        last_activity_at: timestamp.clone(),
-        completed_at: Some(timestamp),
-    };
+    // Create ID lookup link for chapter
+    let chapter_anchor = StringAnchor::new("chapter_id", &chapter_id);
+    let chapter_anchor_hash = hash_entry(&EntryTypes::StringAnchor(chapter_anchor))?;
+    create_link(chapter_anchor_hash, action_hash.clone(), LinkTypes::IdToChapter, ())?;
 
-    let action_hash = create_entry(&EntryTypes::AgentProgress(completed_progress.clone()))?;
+    // Link path to chapter
+    let path_anchor = StringAnchor::new("path_id", &input.path_id);
+    let path_anchor_hash = hash_entry(&EntryTypes::StringAnchor(path_anchor))?;
 
-    // Update ID lookup link
-    delete_link(link.create_link_hash.clone(), GetOptions::default())?;
-    create_link(progress_anchor_hash, action_hash.clone(), LinkTypes::AgentToPathProgress, ())?;
+    let query = LinkQuery::try_new(path_anchor_hash, LinkTypes::IdToPath)?;
+    let path_links = get_links(query, GetStrategy::default())?;
 
-    // Update status link (in_progress -> completed)
-    let old_status_anchor = StringAnchor::new("progress_status", "in_progress");
-    let old_status_anchor_hash = hash_entry(&EntryTypes::StringAnchor(old_status_anchor))?;
-    let old_status_query = LinkQuery::try_new(old_status_anchor_hash, LinkTypes::ProgressByStatus)?;
-    let old_status_links = get_links(old_status_query, GetStrategy::default())?;
-    for status_link in old_status_links {
-        if status_link.target == existing_action_hash.clone().into() {
-            delete_link(status_link.create_link_hash, GetOptions::default())?;
-            break;
-        }
+    if let Some(path_link) = path_links.first() {
+        let path_action_hash = ActionHash::try_from(path_link.target.clone())
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid path action hash".to_string())))?;
+        create_link(path_action_hash, action_hash.clone(), LinkTypes::PathToChapter, ())?;
     }
 
-    let new_status_anchor = StringAnchor::new("progress_status", "completed");
-    let new_status_anchor_hash = hash_entry(&EntryTypes::StringAnchor(new_status_anchor))?;
-    create_link(new_status_anchor_hash, action_hash.clone(), LinkTypes::ProgressByStatus, ())?;
+    emit_write_signal("PathChapter", &chapter.id, "create_chapter");
 
-    Ok(AgentProgressOutput { action_hash, progress: completed_progress })
+    Ok(ChapterOutput {
+        action_hash,
+        chapter,
+    })
 }
 
-/// Get current agent's progress on a path
+/// Get a chapter by ID
 #[hdk_extern]
-pub fn get_my_path_progress(path_id: String) -> ExternResult<Option<AgentProgressOutput>> {
-    let agent_info = agent_info()?;
-    let agent_id = agent_info.agent_initial_pubkey.to_string();
-
-    let progress_id = format!("{}-{}", agent_id, path_id);
-    let progress_anchor = StringAnchor::new("progress_id", &progress_id);
-    let progress_anchor_hash = hash_entry(&EntryTypes::StringAnchor(progress_anchor))?;
+pub fn get_chapter_by_id(chapter_id: String) -> ExternResult<Option<ChapterOutput>> {
+    let anchor = StringAnchor::new("chapter_id", &chapter_id);
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
 
-    let query = LinkQuery::try_new(progress_anchor_hash, LinkTypes::AgentToPathProgress)?;
+    let query = LinkQuery::try_new(anchor_hash, LinkTypes::IdToChapter)?;
     let links = get_links(query, GetStrategy::default())?;
 
     let link = match links.first() {
@@ -4375,7 +6267,7 @@ pub fn get_my_path_progress(path_id: String) -> ExternResult<Option<AgentProgres
     };
 
     let action_hash = ActionHash::try_from(link.target.clone())
-        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid progress action hash".to_string())))?;
+        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid chapter action hash".to_string())))?;
 
     let record = get(action_hash.clone(), GetOptions::default())?;
     let record = match record {
@@ -4383,1004 +6275,5251 @@ pub fn get_my_path_progress(path_id: String) -> ExternResult<Option<AgentProgres
         None => return Ok(None),
     };
 
-    let progress: AgentProgress = record
+    let chapter: PathChapter = record
         .entry()
         .to_app_option()
         .map_err(|e| wasm_error!(e))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not deserialize progress".to_string())))?;
+        .ok_or(wasm_error!(WasmErrorInner::Guest(
+            "Could not deserialize chapter".to_string()
+        )))?;
 
-    Ok(Some(AgentProgressOutput { action_hash, progress }))
+    Ok(Some(ChapterOutput {
+        action_hash,
+        chapter,
+    }))
 }
 
-/// Get all progress for current agent
+/// Get all chapters for a path
 #[hdk_extern]
-pub fn get_my_all_progress(_: ()) -> ExternResult<Vec<AgentProgressOutput>> {
-    let agent_info = agent_info()?;
-    let agent_id = agent_info.agent_initial_pubkey.to_string();
+pub fn get_chapters_for_path(input: PathReadInput) -> ExternResult<Vec<ChapterWithSteps>> {
+    // Find path by ID
+    let path_anchor = StringAnchor::new("path_id", input.path_id());
+    let path_anchor_hash = hash_entry(&EntryTypes::StringAnchor(path_anchor))?;
 
-    let agent_anchor = StringAnchor::new("agent_progress", &agent_id);
-    let agent_anchor_hash = hash_entry(&EntryTypes::StringAnchor(agent_anchor))?;
+    let query = LinkQuery::try_new(path_anchor_hash, LinkTypes::IdToPath)?;
+    let path_links = get_links(query, GetStrategy::default())?;
 
-    let query = LinkQuery::try_new(agent_anchor_hash, LinkTypes::AgentToPathProgress)?;
-    let links = get_links(query, GetStrategy::default())?;
+    let path_link = match path_links.first() {
+        Some(l) => l,
+        None => return Ok(Vec::new()),
+    };
 
-    let mut results = Vec::new();
-    for link in links {
-        let action_hash = ActionHash::try_from(link.target)
-            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid progress action hash".to_string())))?;
+    let path_action_hash = ActionHash::try_from(path_link.target.clone())
+        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid path action hash".to_string())))?;
 
-        let record = get(action_hash.clone(), GetOptions::default())?;
-        if let Some(rec) = record {
-            if let Some(progress) = rec.entry().to_app_option::<AgentProgress>().ok().flatten() {
-                results.push(AgentProgressOutput { action_hash, progress });
-            }
-        }
+    // Get chapters linked to path
+    let chapter_query = LinkQuery::try_new(path_action_hash, LinkTypes::PathToChapter)?;
+    let chapter_links = get_links(chapter_query, GetStrategy::default())?;
+
+    let mut chapter_hashes = Vec::with_capacity(chapter_links.len());
+    for link in chapter_links {
+        let chapter_action_hash = ActionHash::try_from(link.target)
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid chapter action hash".to_string())))?;
+        chapter_hashes.push(chapter_action_hash);
     }
 
-    Ok(results)
-}
+    let chapter_records = get_records_batch(chapter_hashes.clone())?;
 
-/// Get progress by status (in_progress, completed, abandoned)
-#[hdk_extern]
-pub fn get_progress_by_status(status: String) -> ExternResult<Vec<AgentProgressOutput>> {
-    let status_anchor = StringAnchor::new("progress_status", &status);
-    let status_anchor_hash = hash_entry(&EntryTypes::StringAnchor(status_anchor))?;
+    let mut chapters = Vec::new();
+    for (chapter_action_hash, record) in chapter_hashes.into_iter().zip(chapter_records) {
+        if let Some(chapter) = record.and_then(|r| r.entry().to_app_option::<PathChapter>().ok().flatten()) {
+            // Get steps for this chapter
+            let step_query = LinkQuery::try_new(chapter_action_hash.clone(), LinkTypes::ChapterToStep)?;
+            let step_links = get_links(step_query, GetStrategy::default())?;
 
-    let query = LinkQuery::try_new(status_anchor_hash, LinkTypes::ProgressByStatus)?;
-    let links = get_links(query, GetStrategy::default())?;
+            let mut steps = fetch_step_outputs(step_links, input.include_content())?;
 
-    let mut results = Vec::new();
-    for link in links {
-        let action_hash = ActionHash::try_from(link.target)
-            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid progress action hash".to_string())))?;
+            // Sort steps by order_index
+            steps.sort_by_key(|s| s.step.order_index);
 
-        let record = get(action_hash.clone(), GetOptions::default())?;
-        if let Some(rec) = record {
-            if let Some(progress) = rec.entry().to_app_option::<AgentProgress>().ok().flatten() {
-                results.push(AgentProgressOutput { action_hash, progress });
-            }
+            chapters.push(ChapterWithSteps {
+                action_hash: chapter_action_hash,
+                chapter,
+                steps,
+            });
         }
     }
 
-    Ok(results)
-}
-
-/// Get progress summaries for current agent
-#[hdk_extern]
-pub fn get_my_progress_summaries(_: ()) -> ExternResult<Vec<ProgressSummary>> {
-    let all_progress = get_my_all_progress(())?;
-    let mut summaries = Vec::new();
-
-    for progress_output in all_progress {
-        let progress = progress_output.progress;
-
-        // Get path to count total steps
-        let path_result = get_path_with_steps(progress.path_id.clone())?;
-        let (path_title, total_steps) = match path_result {
-            Some(path_data) => (path_data.path.title, path_data.steps.len() as u32),
-            None => ("Unknown Path".to_string(), 0),
-        };
-
-        summaries.push(ProgressSummary {
-            path_id: progress.path_id,
-            path_title,
-            total_steps,
-            completed_steps: progress.completed_step_indices.len() as u32,
-            current_step_index: progress.current_step_index,
-            is_completed: progress.completed_at.is_some(),
-            attestations_earned: progress.attestations_earned,
-            started_at: progress.started_at,
-            last_activity_at: progress.last_activity_at,
-            completed_at: progress.completed_at,
-        });
-    }
+    // Sort chapters by order_index
+    chapters.sort_by_key(|c| c.chapter.order_index);
 
-    Ok(summaries)
+    Ok(chapters)
 }
 
-// =============================================================================
-// Attestation Operations
-// =============================================================================
-
-/// Grant an attestation for completing a step, chapter, or path
+/// Get a full path with chapters and steps organized
 #[hdk_extern]
-pub fn grant_attestation(input: GrantAttestationInput) -> ExternResult<AgentProgressOutput> {
-    let agent_info = agent_info()?;
-    let agent_id = agent_info.agent_initial_pubkey.to_string();
-    let now = sys_time()?;
-    let timestamp = format!("{:?}", now);
-
-    // Find existing progress
-    let progress_id = format!("{}-{}", agent_id, input.path_id);
-    let progress_anchor = StringAnchor::new("progress_id", &progress_id);
-    let progress_anchor_hash = hash_entry(&EntryTypes::StringAnchor(progress_anchor))?;
+pub fn get_path_full(input: PathReadInput) -> ExternResult<Option<PathWithChaptersAndSteps>> {
+    // Find path by ID
+    let path_anchor = StringAnchor::new("path_id", input.path_id());
+    let path_anchor_hash = hash_entry(&EntryTypes::StringAnchor(path_anchor))?;
 
-    let query = LinkQuery::try_new(progress_anchor_hash.clone(), LinkTypes::AgentToPathProgress)?;
-    let links = get_links(query, GetStrategy::default())?;
+    let query = LinkQuery::try_new(path_anchor_hash, LinkTypes::IdToPath)?;
+    let path_links = get_links(query, GetStrategy::default())?;
 
-    let link = links.first()
-        .ok_or(wasm_error!(WasmErrorInner::Guest(
-            format!("No progress found for path: {}", input.path_id)
-        )))?;
+    let path_link = match path_links.first() {
+        Some(l) => l,
+        None => return Ok(None),
+    };
 
-    let existing_action_hash = ActionHash::try_from(link.target.clone())
-        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid progress action hash".to_string())))?;
+    let path_action_hash = ActionHash::try_from(path_link.target.clone())
+        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid path action hash".to_string())))?;
 
-    let record = get(existing_action_hash.clone(), GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Progress record not found".to_string())))?;
+    let path_record = get(path_action_hash.clone(), GetOptions::default())?;
+    let path_record = match path_record {
+        Some(r) => r,
+        None => return Ok(None),
+    };
 
-    let existing: AgentProgress = record
+    let path: LearningPath = path_record
         .entry()
         .to_app_option()
         .map_err(|e| wasm_error!(e))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not deserialize progress".to_string())))?;
+        .ok_or(wasm_error!(WasmErrorInner::Guest(
+            "Could not deserialize path".to_string()
+        )))?;
 
-    // Add attestation if not already earned
-    let mut attestations_earned = existing.attestations_earned.clone();
-    if !attestations_earned.contains(&input.attestation_id) {
-        attestations_earned.push(input.attestation_id.clone());
-    }
+    let include_content = input.include_content();
 
-    // Create updated progress with attestation
-    let updated_progress = AgentProgress {
-        id: existing.id,
-        agent_id: existing.agent_id,
-        path_id: existing.path_id.clone(),
-        current_step_index: existing.current_step_index,
-        completed_step_indices: existing.completed_step_indices,
-        completed_content_ids: existing.completed_content_ids,
-        step_affinity_json: existing.step_affinity_json,
-        step_notes_json: existing.step_notes_json,
-        reflection_responses_json: existing.reflection_responses_json,
-        attestations_earned,
-        started_at: existing.started_at,
-        last_activity_at: timestamp,
-        completed_at: existing.completed_at,
-    };
+    // Get chapters
+    let chapters = get_chapters_for_path(input)?;
 
-    let action_hash = create_entry(&EntryTypes::AgentProgress(updated_progress.clone()))?;
+    // Get all steps linked directly to path (includes those with chapters)
+    let step_query = LinkQuery::try_new(path_action_hash.clone(), LinkTypes::PathToStep)?;
+    let step_links = get_links(step_query, GetStrategy::default())?;
 
-    // Update ID lookup link
-    delete_link(link.create_link_hash.clone(), GetOptions::default())?;
-    create_link(progress_anchor_hash, action_hash.clone(), LinkTypes::AgentToPathProgress, ())?;
+    // Only include steps that are NOT in a chapter
+    let mut ungrouped_steps: Vec<PathStepOutput> = fetch_step_outputs(step_links, false)?
+        .into_iter()
+        .filter(|s| s.step.chapter_id.is_none())
+        .collect();
+    if include_content {
+        attach_step_content(&mut ungrouped_steps)?;
+    }
 
-    // Issue attestation via imagodei DNA (attestations live in identity layer)
-    let _attestation_result = issue_attestation_via_imagodei(IssueAttestationBridgeInput {
-        agent_id: agent_id.clone(),
-        category: input.source_type.clone(),  // "step", "chapter", "path"
-        attestation_type: input.attestation_id,
-        display_name: format!("Completed: {}", input.reason),
-        description: input.reason,
-        icon_url: None,
-        tier: None,
-        earned_via_json: serde_json::json!({
-            "source_type": input.source_type,
-            "source_id": input.source_id,
-            "path_id": input.path_id
-        }).to_string(),
-        expires_at: None,
-    })?;
+    // Sort ungrouped steps by order_index
+    ungrouped_steps.sort_by_key(|s| s.step.order_index);
 
-    Ok(AgentProgressOutput { action_hash, progress: updated_progress })
+    Ok(Some(PathWithChaptersAndSteps {
+        action_hash: path_action_hash,
+        path,
+        chapters,
+        ungrouped_steps,
+    }))
 }
 
-/// Check if learner has required attestation to access a step
+/// Update a chapter
 #[hdk_extern]
-pub fn check_attestation_access(input: CheckAttestationAccessInput) -> ExternResult<AttestationAccessResult> {
-    let agent_info = agent_info()?;
-    let agent_id = agent_info.agent_initial_pubkey.to_string();
-
-    // Get current progress to check attestations
-    let progress_id = format!("{}-{}", agent_id, input.path_id);
-    let progress_anchor = StringAnchor::new("progress_id", &progress_id);
-    let progress_anchor_hash = hash_entry(&EntryTypes::StringAnchor(progress_anchor))?;
+pub fn update_chapter(input: UpdateChapterInput) -> ExternResult<ChapterOutput> {
+    // Get existing chapter
+    let existing = get_chapter_by_id(input.chapter_id.clone())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(
+            format!("Chapter not found: {}", input.chapter_id)
+        )))?;
 
-    let query = LinkQuery::try_new(progress_anchor_hash, LinkTypes::AgentToPathProgress)?;
-    let links = get_links(query, GetStrategy::default())?;
+    let now = sys_time()?;
+    let timestamp = format!("{:?}", now);
 
-    let attestations_earned: Vec<String> = if let Some(link) = links.first() {
-        let action_hash = ActionHash::try_from(link.target.clone())
-            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid progress action hash".to_string())))?;
-        let record = get(action_hash, GetOptions::default())?;
-        if let Some(rec) = record {
-            if let Some(progress) = rec.entry().to_app_option::<AgentProgress>().ok().flatten() {
-                progress.attestations_earned
-            } else {
-                Vec::new()
-            }
-        } else {
-            Vec::new()
-        }
-    } else {
-        Vec::new()
+    // Create updated chapter (immutable DHT pattern)
+    let updated_chapter = PathChapter {
+        id: existing.chapter.id,
+        path_id: existing.chapter.path_id.clone(),
+        order_index: input.order_index.unwrap_or(existing.chapter.order_index),
+        title: input.title.unwrap_or(existing.chapter.title),
+        description: input.description.or(existing.chapter.description),
+        learning_objectives_json: input.learning_objectives
+            .map(|lo| serde_json::to_string(&lo).unwrap_or_else(|_| "[]".to_string()))
+            .unwrap_or(existing.chapter.learning_objectives_json),
+        estimated_minutes: input.estimated_minutes.or(existing.chapter.estimated_minutes),
+        is_optional: input.is_optional.unwrap_or(existing.chapter.is_optional),
+        attestation_granted: input.attestation_granted.or(existing.chapter.attestation_granted),
+        mastery_threshold: input.mastery_threshold.or(existing.chapter.mastery_threshold),
+        metadata_json: existing.chapter.metadata_json,
+        created_at: existing.chapter.created_at,
+        updated_at: timestamp,
     };
 
-    let has_access = attestations_earned.contains(&input.required_attestation);
+    let action_hash = create_entry(&EntryTypes::PathChapter(updated_chapter.clone()))?;
 
-    Ok(AttestationAccessResult {
-        has_access,
-        required_attestation: input.required_attestation,
-        attestations_earned,
-    })
-}
+    // Update the ID lookup link
+    let chapter_anchor = StringAnchor::new("chapter_id", &input.chapter_id);
+    let chapter_anchor_hash = hash_entry(&EntryTypes::StringAnchor(chapter_anchor))?;
 
-/// Input for checking attestation access
-#[derive(Serialize, Deserialize, Debug)]
-pub struct CheckAttestationAccessInput {
-    pub path_id: String,
-    pub required_attestation: String,
-}
+    // Delete old link
+    let old_query = LinkQuery::try_new(chapter_anchor_hash.clone(), LinkTypes::IdToChapter)?;
+    let old_links = get_links(old_query, GetStrategy::default())?;
+    for link in old_links {
+        delete_link(link.create_link_hash, GetOptions::default())?;
+    }
 
-/// Result of attestation access check
-#[derive(Serialize, Deserialize, Debug)]
-pub struct AttestationAccessResult {
-    pub has_access: bool,
-    pub required_attestation: String,
-    pub attestations_earned: Vec<String>,
-}
+    // Create new link
+    create_link(chapter_anchor_hash, action_hash.clone(), LinkTypes::IdToChapter, ())?;
 
-// =============================================================================
+    // Update path-to-chapter link
+    let path_anchor = StringAnchor::new("path_id", &existing.chapter.path_id);
+    let path_anchor_hash = hash_entry(&EntryTypes::StringAnchor(path_anchor))?;
+    let path_query = LinkQuery::try_new(path_anchor_hash, LinkTypes::IdToPath)?;
+    let path_links = get_links(path_query, GetStrategy::default())?;
+
+    if let Some(path_link) = path_links.first() {
+        let path_action_hash = ActionHash::try_from(path_link.target.clone())
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid path action hash".to_string())))?;
+
+        // Delete old path-to-chapter links pointing to old chapter
+        let old_chapter_query = LinkQuery::try_new(path_action_hash.clone(), LinkTypes::PathToChapter)?;
+        let old_chapter_links = get_links(old_chapter_query, GetStrategy::default())?;
+        for link in old_chapter_links {
+            if link.target == existing.action_hash.clone().into() {
+                delete_link(link.create_link_hash, GetOptions::default())?;
+            }
+        }
+
+        // Create new link
+        create_link(path_action_hash, action_hash.clone(), LinkTypes::PathToChapter, ())?;
+    }
+
+    emit_write_signal("PathChapter", &updated_chapter.id, "update_chapter");
+
+    Ok(ChapterOutput {
+        action_hash,
+        chapter: updated_chapter,
+    })
+}
+
+/// Steps linked from a chapter, sorted by order_index
+fn chapter_steps(chapter_action_hash: &ActionHash) -> ExternResult<Vec<PathStepOutput>> {
+    let query = LinkQuery::try_new(chapter_action_hash.clone(), LinkTypes::ChapterToStep)?;
+    let mut steps = fetch_step_outputs(get_links(query, GetStrategy::default())?, false)?;
+    steps.sort_by_key(|s| s.step.order_index);
+    Ok(steps)
+}
+
+/// Write a new version of a step in another chapter (or ungrouped when
+/// `chapter` is None) and move its ID, path and chapter links over to it
+fn reparent_step(
+    path_action_hash: &ActionHash,
+    existing: &PathStepOutput,
+    chapter: Option<&ChapterOutput>,
+    order_index: u32,
+) -> ExternResult<PathStepOutput> {
+    let mut step = existing.step.clone();
+    step.chapter_id = chapter.map(|c| c.chapter.id.clone());
+    step.order_index = order_index;
+    step.updated_at = format!("{:?}", sys_time()?);
+
+    let action_hash = create_entry(&EntryTypes::PathStep(step.clone()))?;
+
+    // ID lookup link
+    let step_anchor = StringAnchor::new("step_id", &step.id);
+    let step_anchor_hash = hash_entry(&EntryTypes::StringAnchor(step_anchor))?;
+    let query = LinkQuery::try_new(step_anchor_hash.clone(), LinkTypes::IdToStep)?;
+    for link in get_links(query, GetStrategy::default())? {
+        delete_link(link.create_link_hash, GetOptions::default())?;
+    }
+    create_link(step_anchor_hash, action_hash.clone(), LinkTypes::IdToStep, ())?;
+
+    // Path link
+    delete_links_to(path_action_hash.clone(), LinkTypes::PathToStep, &existing.action_hash)?;
+    create_link(path_action_hash.clone(), action_hash.clone(), LinkTypes::PathToStep, ())?;
+
+    // Chapter links, both directions
+    let query = LinkQuery::try_new(existing.action_hash.clone(), LinkTypes::StepToChapter)?;
+    for link in get_links(query, GetStrategy::default())? {
+        if let Ok(old_chapter_hash) = ActionHash::try_from(link.target.clone()) {
+            delete_links_to(old_chapter_hash, LinkTypes::ChapterToStep, &existing.action_hash)?;
+        }
+        delete_link(link.create_link_hash, GetOptions::default())?;
+    }
+    if let Some(chapter) = chapter {
+        create_link(chapter.action_hash.clone(), action_hash.clone(), LinkTypes::ChapterToStep, ())?;
+        create_link(action_hash.clone(), chapter.action_hash.clone(), LinkTypes::StepToChapter, ())?;
+    }
+
+    emit_write_signal("PathStep", &step.id, "delete_chapter");
+
+    Ok(PathStepOutput {
+        action_hash,
+        step,
+        content: None,
+        external: None,
+    })
+}
+
+/// Delete a chapter, moving its steps to another chapter of the same path
+/// or to the path's ungrouped steps.
+///
+/// Moved steps keep their relative order and are renumbered to follow the
+/// steps already in the destination.
+#[hdk_extern]
+pub fn delete_chapter(input: DeleteChapterInput) -> ExternResult<DeleteChapterOutput> {
+    let existing = get_chapter_by_id(input.chapter_id.clone())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(
+            format!("Chapter not found: {}", input.chapter_id)
+        )))?;
+    let path_id = existing.chapter.path_id.clone();
+
+    let target = match &input.reassign_to {
+        Some(target_id) if *target_id == input.chapter_id => {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "Cannot reassign steps to the chapter being deleted".to_string()
+            )));
+        }
+        Some(target_id) => {
+            let target = get_chapter_by_id(target_id.clone())?
+                .ok_or(wasm_error!(WasmErrorInner::Guest(
+                    format!("Chapter not found: {}", target_id)
+                )))?;
+            if target.chapter.path_id != path_id {
+                return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                    "Chapter {} is not in path {}",
+                    target_id, path_id
+                ))));
+            }
+            Some(target)
+        }
+        None => None,
+    };
+
+    let path_action_hash = find_path_action_hash(&path_id)?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(
+            format!("Path not found: {}", path_id)
+        )))?;
+
+    // Number moved steps after the last step already in the destination
+    let destination_steps = match &target {
+        Some(target) => chapter_steps(&target.action_hash)?,
+        None => {
+            let query = LinkQuery::try_new(path_action_hash.clone(), LinkTypes::PathToStep)?;
+            fetch_step_outputs(get_links(query, GetStrategy::default())?, false)?
+                .into_iter()
+                .filter(|s| s.step.chapter_id.is_none())
+                .collect()
+        }
+    };
+    let mut next_index = destination_steps
+        .iter()
+        .map(|s| s.step.order_index + 1)
+        .max()
+        .unwrap_or(0);
+
+    let mut moved_steps = Vec::new();
+    for step in chapter_steps(&existing.action_hash)? {
+        moved_steps.push(reparent_step(&path_action_hash, &step, target.as_ref(), next_index)?);
+        next_index += 1;
+    }
+
+    // Unlink the chapter; its entries stay in the DHT
+    let chapter_anchor = StringAnchor::new("chapter_id", &input.chapter_id);
+    let chapter_anchor_hash = hash_entry(&EntryTypes::StringAnchor(chapter_anchor))?;
+    let query = LinkQuery::try_new(chapter_anchor_hash, LinkTypes::IdToChapter)?;
+    for link in get_links(query, GetStrategy::default())? {
+        delete_link(link.create_link_hash, GetOptions::default())?;
+    }
+    delete_links_to(path_action_hash, LinkTypes::PathToChapter, &existing.action_hash)?;
+
+    emit_write_signal("PathChapter", &input.chapter_id, "delete_chapter");
+
+    Ok(DeleteChapterOutput {
+        chapter_id: input.chapter_id,
+        reassigned_to: input.reassign_to,
+        moved_steps,
+    })
+}
+
+// =============================================================================
+// External Resources
+// =============================================================================
+
+fn external_url_anchor(url: &str) -> ExternResult<EntryHash> {
+    hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("external_url", url)))
+}
+
+fn external_status_anchor(status: &str) -> ExternResult<EntryHash> {
+    hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("external_resource_status", status)))
+}
+
+fn is_http_url(value: &str) -> bool {
+    value.starts_with("https://") || value.starts_with("http://")
+}
+
+fn external_resources_from(base: EntryHash, link_type: LinkTypes) -> ExternResult<Vec<ExternalResourceOutput>> {
+    let query = LinkQuery::try_new(base, link_type)?;
+    let mut resources = Vec::new();
+    for link in get_links(query, GetStrategy::default())? {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(action_hash.clone(), GetOptions::default())? else {
+            continue;
+        };
+        let Some(resource) = record.entry().to_app_option::<ExternalResource>().ok().flatten() else {
+            continue;
+        };
+        resources.push(ExternalResourceOutput { action_hash, resource });
+    }
+    resources.sort_by(|a, b| a.resource.created_at.cmp(&b.resource.created_at));
+    Ok(resources)
+}
+
+fn external_resource_by_url(url: &str) -> ExternResult<Option<ExternalResourceOutput>> {
+    if !is_http_url(url) {
+        return Ok(None);
+    }
+    Ok(external_resources_from(external_url_anchor(url)?, LinkTypes::UrlToExternalResource)?.pop())
+}
+
+/// Get the resource for a URL, registering it for enrichment if it's new.
+/// Steps linking the same URL share one resource.
+fn ensure_external_resource(url: &str) -> ExternResult<ExternalResourceOutput> {
+    if let Some(existing) = external_resource_by_url(url)? {
+        return Ok(existing);
+    }
+
+    let timestamp = format!("{:?}", sys_time()?);
+    let resource = ExternalResource {
+        url: url.to_string(),
+        provider: None,
+        title: None,
+        description: None,
+        thumbnail_url: None,
+        oembed_json: None,
+        snapshot_hash: None,
+        status: "pending".to_string(),
+        fetched_at: None,
+        created_at: timestamp.clone(),
+        updated_at: timestamp,
+    };
+    let action_hash = create_entry(&EntryTypes::ExternalResource(resource.clone()))?;
+
+    create_link(external_url_anchor(url)?, action_hash.clone(), LinkTypes::UrlToExternalResource, ())?;
+    create_link(
+        external_status_anchor(&resource.status)?,
+        action_hash.clone(),
+        LinkTypes::ExternalResourceByStatus,
+        (),
+    )?;
+
+    Ok(ExternalResourceOutput { action_hash, resource })
+}
+
+/// Get the enriched metadata for an external step's URL
+#[hdk_extern]
+pub fn get_external_resource(url: String) -> ExternResult<Option<ExternalResourceOutput>> {
+    external_resource_by_url(&url)
+}
+
+/// Get external resources in an enrichment state (see
+/// EXTERNAL_RESOURCE_STATUSES), oldest first. The doorway enrichment worker
+/// polls "pending".
+#[hdk_extern]
+pub fn get_external_resources_by_status(status: String) -> ExternResult<Vec<ExternalResourceOutput>> {
+    external_resources_from(external_status_anchor(&status)?, LinkTypes::ExternalResourceByStatus)
+}
+
+/// Record enrichment results for an external resource
+#[hdk_extern]
+pub fn update_external_resource(input: UpdateExternalResourceInput) -> ExternResult<ExternalResourceOutput> {
+    let existing = external_resource_by_url(&input.url)?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(
+            format!("External resource not found: {}", input.url)
+        )))?;
+
+    let timestamp = format!("{:?}", sys_time()?);
+    let old = existing.resource;
+    let resource = ExternalResource {
+        url: old.url,
+        provider: input.provider.or(old.provider),
+        title: input.title.or(old.title),
+        description: input.description.or(old.description),
+        thumbnail_url: input.thumbnail_url.or(old.thumbnail_url),
+        oembed_json: input.oembed_json.or(old.oembed_json),
+        snapshot_hash: input.snapshot_hash.or(old.snapshot_hash),
+        status: input.status,
+        fetched_at: Some(timestamp.clone()),
+        created_at: old.created_at,
+        updated_at: timestamp,
+    };
+    let action_hash = update_entry(existing.action_hash.clone(), &EntryTypes::ExternalResource(resource.clone()))?;
+
+    let url_anchor = external_url_anchor(&resource.url)?;
+    delete_links_to(url_anchor.clone(), LinkTypes::UrlToExternalResource, &existing.action_hash)?;
+    create_link(url_anchor, action_hash.clone(), LinkTypes::UrlToExternalResource, ())?;
+
+    delete_links_to(
+        external_status_anchor(&old.status)?,
+        LinkTypes::ExternalResourceByStatus,
+        &existing.action_hash,
+    )?;
+    create_link(
+        external_status_anchor(&resource.status)?,
+        action_hash.clone(),
+        LinkTypes::ExternalResourceByStatus,
+        (),
+    )?;
+
+    emit_write_signal("ExternalResource", &resource.url, "update_external_resource");
+
+    Ok(ExternalResourceOutput { action_hash, resource })
+}
+
+// =============================================================================
+// Path Update Operations
+// =============================================================================
+
+/// Update a learning path
+#[hdk_extern]
+pub fn update_path(input: UpdatePathInput) -> ExternResult<PathWithSteps> {
+    // Get existing path
+    let existing = get_path_with_steps(input.path_id.clone().into())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(
+            format!("Path not found: {}", input.path_id)
+        )))?;
+
+    let now = sys_time()?;
+    let timestamp = format!("{:?}", now);
+
+    // Create updated path (immutable DHT pattern)
+    let updated_path = LearningPath {
+        id: existing.path.id,
+        version: existing.path.version,
+        title: input.title.unwrap_or(existing.path.title),
+        description: input.description.unwrap_or(existing.path.description),
+        purpose: input.purpose.or(existing.path.purpose),
+        created_by: existing.path.created_by,
+        difficulty: input.difficulty.unwrap_or(existing.path.difficulty),
+        estimated_duration: input.estimated_duration.or(existing.path.estimated_duration),
+        visibility: input.visibility.unwrap_or(existing.path.visibility),
+        path_type: existing.path.path_type,
+        tags: input.tags.unwrap_or(existing.path.tags),
+        metadata_json: existing.path.metadata_json,
+        created_at: existing.path.created_at,
+        updated_at: timestamp,
+        schema_version: 2,
+        validation_status: "Valid".to_string(),
+    };
+
+    let action_hash = create_entry(&EntryTypes::LearningPath(updated_path.clone()))?;
+
+    // Update the ID lookup link
+    let path_anchor = StringAnchor::new("path_id", &input.path_id);
+    let path_anchor_hash = hash_entry(&EntryTypes::StringAnchor(path_anchor))?;
+
+    // Delete old link
+    let old_query = LinkQuery::try_new(path_anchor_hash.clone(), LinkTypes::IdToPath)?;
+    let old_links = get_links(old_query, GetStrategy::default())?;
+    for link in old_links {
+        delete_link(link.create_link_hash, GetOptions::default())?;
+    }
+
+    // Create new link
+    create_link(path_anchor_hash, action_hash.clone(), LinkTypes::IdToPath, ())?;
+
+    // Update the index link; an archived path stays archived
+    let all_paths_anchor = StringAnchor::new("all_paths", "index");
+    let all_paths_anchor_hash = hash_entry(&EntryTypes::StringAnchor(all_paths_anchor))?;
+    let archived_anchor = StringAnchor::new(ARCHIVED_PATHS_ANCHOR, "index");
+    let archived_anchor_hash = hash_entry(&EntryTypes::StringAnchor(archived_anchor))?;
+    let mut index_anchor_hash = all_paths_anchor_hash.clone();
+
+    // Delete old index link
+    for anchor_hash in [all_paths_anchor_hash, archived_anchor_hash] {
+        let old_index_query = LinkQuery::try_new(anchor_hash.clone(), LinkTypes::IdToPath)?;
+        let old_index_links = get_links(old_index_query, GetStrategy::default())?;
+        for link in old_index_links {
+            if link.target == existing.action_hash.clone().into() {
+                delete_link(link.create_link_hash, GetOptions::default())?;
+                index_anchor_hash = anchor_hash.clone();
+            }
+        }
+    }
+
+    // Create new index link with refreshed summary
+    let summary_tag = PathSummary::from_path(&updated_path).to_link_tag()?;
+    create_link(index_anchor_hash, action_hash.clone(), LinkTypes::IdToPath, summary_tag)?;
+
+    // Re-link all steps to new path action hash
+    for step_output in &existing.steps {
+        create_link(action_hash.clone(), step_output.action_hash.clone(), LinkTypes::PathToStep, ())?;
+    }
+
+    emit_write_signal("LearningPath", &updated_path.id, "update_path");
+
+    Ok(PathWithSteps {
+        action_hash,
+        path: updated_path,
+        steps: existing.steps,
+    })
+}
+
+/// Update a step
+#[hdk_extern]
+pub fn update_step(input: UpdateStepInput) -> ExternResult<PathStepOutput> {
+    // Get existing step by ID
+    let step_anchor = StringAnchor::new("step_id", &input.step_id);
+    let step_anchor_hash = hash_entry(&EntryTypes::StringAnchor(step_anchor))?;
+
+    let query = LinkQuery::try_new(step_anchor_hash.clone(), LinkTypes::IdToStep)?;
+    let links = get_links(query, GetStrategy::default())?;
+
+    let link = links.first()
+        .ok_or(wasm_error!(WasmErrorInner::Guest(
+            format!("Step not found: {}", input.step_id)
+        )))?;
+
+    let existing_action_hash = ActionHash::try_from(link.target.clone())
+        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid step action hash".to_string())))?;
+
+    let record = get(existing_action_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Step record not found".to_string())))?;
+
+    let existing: PathStep = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(
+            "Could not deserialize step".to_string()
+        )))?;
+
+    let now = sys_time()?;
+    let timestamp = format!("{:?}", now);
+
+    // Create updated step
+    let updated_step = PathStep {
+        id: existing.id,
+        path_id: existing.path_id.clone(),
+        chapter_id: input.chapter_id.or(existing.chapter_id),
+        module_id: existing.module_id,
+        section_id: existing.section_id,
+        order_index: input.order_index.unwrap_or(existing.order_index),
+        step_type: existing.step_type,
+        resource_id: existing.resource_id.clone(),
+        step_title: input.step_title.or(existing.step_title),
+        step_narrative: input.step_narrative.or(existing.step_narrative),
+        is_optional: input.is_optional.unwrap_or(existing.is_optional),
+        learning_objectives_json: input.learning_objectives
+            .map(|lo| serde_json::to_string(&lo).unwrap_or_else(|_| "[]".to_string()))
+            .unwrap_or(existing.learning_objectives_json),
+        reflection_prompts_json: input.reflection_prompts
+            .map(|rp| serde_json::to_string(&rp).unwrap_or_else(|_| "[]".to_string()))
+            .unwrap_or(existing.reflection_prompts_json),
+        practice_exercises_json: input.practice_exercises
+            .map(|pe| serde_json::to_string(&pe).unwrap_or_else(|_| "[]".to_string()))
+            .unwrap_or(existing.practice_exercises_json),
+        estimated_minutes: input.estimated_minutes.or(existing.estimated_minutes),
+        completion_criteria: input.completion_criteria.or(existing.completion_criteria),
+        attestation_required: input.attestation_required.or(existing.attestation_required),
+        attestation_granted: input.attestation_granted.or(existing.attestation_granted),
+        mastery_threshold: input.mastery_threshold.or(existing.mastery_threshold),
+        metadata_json: existing.metadata_json,
+        created_at: existing.created_at,
+        updated_at: timestamp,
+        schema_version: 2,
+        validation_status: "Valid".to_string(),
+    };
+
+    let action_hash = create_entry(&EntryTypes::PathStep(updated_step.clone()))?;
+
+    // Update the ID lookup link
+    // Delete old link
+    for lnk in links {
+        delete_link(lnk.create_link_hash, GetOptions::default())?;
+    }
+
+    // Create new link
+    create_link(step_anchor_hash, action_hash.clone(), LinkTypes::IdToStep, ())?;
+
+    // Re-link to path
+    let path_anchor = StringAnchor::new("path_id", &existing.path_id);
+    let path_anchor_hash = hash_entry(&EntryTypes::StringAnchor(path_anchor))?;
+    let path_query = LinkQuery::try_new(path_anchor_hash, LinkTypes::IdToPath)?;
+    let path_links = get_links(path_query, GetStrategy::default())?;
+
+    if let Some(path_link) = path_links.first() {
+        let path_action_hash = ActionHash::try_from(path_link.target.clone())
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid path action hash".to_string())))?;
+        create_link(path_action_hash, action_hash.clone(), LinkTypes::PathToStep, ())?;
+    }
+
+    emit_write_signal("PathStep", &updated_step.id, "update_step");
+
+    Ok(PathStepOutput {
+        action_hash,
+        step: updated_step,
+        content: None,
+        external: None,
+    })
+}
+
+/// Get a step by ID
+#[hdk_extern]
+pub fn get_step_by_id(step_id: String) -> ExternResult<Option<PathStepOutput>> {
+    let anchor = StringAnchor::new("step_id", &step_id);
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
+
+    let query = LinkQuery::try_new(anchor_hash, LinkTypes::IdToStep)?;
+    let links = get_links(query, GetStrategy::default())?;
+
+    let link = match links.first() {
+        Some(l) => l,
+        None => return Ok(None),
+    };
+
+    let action_hash = ActionHash::try_from(link.target.clone())
+        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid step action hash".to_string())))?;
+
+    let record = get(action_hash.clone(), GetOptions::default())?;
+    let record = match record {
+        Some(r) => r,
+        None => return Ok(None),
+    };
+
+    let step: PathStep = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(
+            "Could not deserialize step".to_string()
+        )))?;
+
+    Ok(Some(PathStepOutput {
+        action_hash,
+        step,
+        content: None,
+        external: None,
+    }))
+}
+
+// =============================================================================
+// Relationship Operations
+// =============================================================================
+
+/// Create a relationship between two content nodes
+#[hdk_extern]
+pub fn create_relationship(input: CreateRelationshipInput) -> ExternResult<RelationshipOutput> {
+    let now = sys_time()?;
+    let timestamp = format!("{:?}", now);
+
+    // Generate relationship ID
+    let rel_id = format!("{}-{}-{}", input.source_id, input.relationship_type, input.target_id);
+
+    let relationship = Relationship {
+        id: rel_id.clone(),
+        source_id: input.source_id.clone(),
+        target_id: input.target_id.clone(),
+        relationship_type: input.relationship_type.clone(),
+        confidence: input.confidence,
+        inference_source: input.inference_source,
+        metadata_json: input.metadata_json,
+        created_at: timestamp,
+    };
+
+    let action_hash = create_entry(&EntryTypes::Relationship(relationship.clone()))?;
+
+    // Create index links for querying
+    // By source
+    let source_anchor = StringAnchor::new("rel_source", &input.source_id);
+    let source_anchor_hash = hash_entry(&EntryTypes::StringAnchor(source_anchor))?;
+    create_link(source_anchor_hash, action_hash.clone(), LinkTypes::RelationshipBySource, ())?;
+
+    // By target
+    let target_anchor = StringAnchor::new("rel_target", &input.target_id);
+    let target_anchor_hash = hash_entry(&EntryTypes::StringAnchor(target_anchor))?;
+    create_link(target_anchor_hash, action_hash.clone(), LinkTypes::RelationshipByTarget, ())?;
+
+    // By type
+    let type_anchor = StringAnchor::new("rel_type", &input.relationship_type);
+    let type_anchor_hash = hash_entry(&EntryTypes::StringAnchor(type_anchor))?;
+    create_link(type_anchor_hash, action_hash.clone(), LinkTypes::ContentRelationshipByType, ())?;
+
+    emit_write_signal("Relationship", &rel_id, "create_relationship");
+
+    Ok(RelationshipOutput {
+        action_hash,
+        relationship,
+    })
+}
+
+/// Get relationships for a content node
+#[hdk_extern]
+pub fn get_relationships(input: GetRelationshipsInput) -> ExternResult<Vec<RelationshipOutput>> {
+    let mut results = Vec::new();
+
+    // Get outgoing relationships (this content is source)
+    if input.direction == "outgoing" || input.direction == "both" {
+        let source_anchor = StringAnchor::new("rel_source", &input.content_id);
+        let source_anchor_hash = hash_entry(&EntryTypes::StringAnchor(source_anchor))?;
+        let query = LinkQuery::try_new(source_anchor_hash, LinkTypes::RelationshipBySource)?;
+        let links = get_links(query, GetStrategy::default())?;
+
+        for link in links {
+            let action_hash = ActionHash::try_from(link.target)
+                .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid relationship hash".to_string())))?;
+            if let Some(output) = get_relationship(action_hash)? {
+                results.push(output);
+            }
+        }
+    }
+
+    // Get incoming relationships (this content is target)
+    if input.direction == "incoming" || input.direction == "both" {
+        let target_anchor = StringAnchor::new("rel_target", &input.content_id);
+        let target_anchor_hash = hash_entry(&EntryTypes::StringAnchor(target_anchor))?;
+        let query = LinkQuery::try_new(target_anchor_hash, LinkTypes::RelationshipByTarget)?;
+        let links = get_links(query, GetStrategy::default())?;
+
+        for link in links {
+            let action_hash = ActionHash::try_from(link.target)
+                .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid relationship hash".to_string())))?;
+            if let Some(output) = get_relationship(action_hash)? {
+                // Avoid duplicates if direction is "both"
+                if !results.iter().any(|r| r.relationship.id == output.relationship.id) {
+                    results.push(output);
+                }
+            }
+        }
+    }
+
+    Ok(results)
+}
+
+/// Get a relationship by action hash
+fn get_relationship(action_hash: ActionHash) -> ExternResult<Option<RelationshipOutput>> {
+    let record = get(action_hash.clone(), GetOptions::default())?;
+
+    match record {
+        Some(record) => {
+            let relationship: Relationship = record
+                .entry()
+                .to_app_option()
+                .map_err(|e| wasm_error!(e))?
+                .ok_or(wasm_error!(WasmErrorInner::Guest(
+                    "Could not deserialize relationship".to_string()
+                )))?;
+
+            Ok(Some(RelationshipOutput {
+                action_hash,
+                relationship,
+            }))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Get related content for a content node (follows relationships)
+#[hdk_extern]
+pub fn query_related_content(input: QueryRelatedContentInput) -> ExternResult<Vec<ContentOutput>> {
+    let relationships = get_relationships(GetRelationshipsInput {
+        content_id: input.content_id.clone(),
+        direction: "outgoing".to_string(),
+    })?;
+
+    let mut results = Vec::new();
+
+    for rel_output in relationships {
+        // Filter by relationship type if specified
+        if let Some(ref types) = input.relationship_types {
+            if !types.contains(&rel_output.relationship.relationship_type) {
+                continue;
+            }
+        }
+
+        // Get the target content
+        if let Some(content) = get_content_by_id(QueryByIdInput {
+            id: rel_output.relationship.target_id,
+        })? {
+            results.push(content);
+        }
+    }
+
+    Ok(results)
+}
+
+/// Get content graph starting from a root node
+#[hdk_extern]
+pub fn get_content_graph(input: QueryRelatedContentInput) -> ExternResult<ContentGraph> {
+    let root = get_content_by_id(QueryByIdInput {
+        id: input.content_id.clone(),
+    })?;
+
+    let relationships = get_relationships(GetRelationshipsInput {
+        content_id: input.content_id.clone(),
+        direction: "outgoing".to_string(),
+    })?;
+
+    let mut related = Vec::new();
+    let mut total_nodes = if root.is_some() { 1 } else { 0 };
+
+    for rel_output in relationships {
+        if let Some(content) = get_content_by_id(QueryByIdInput {
+            id: rel_output.relationship.target_id,
+        })? {
+            total_nodes += 1;
+            related.push(ContentGraphNode {
+                content,
+                relationship_type: rel_output.relationship.relationship_type,
+                confidence: rel_output.relationship.confidence,
+                children: Vec::new(), // TODO: Recursive traversal for depth > 1
+            });
+        }
+    }
+
+    Ok(ContentGraph {
+        root,
+        related,
+        total_nodes,
+    })
+}
+
+// =============================================================================
+// Citations
+// =============================================================================
+//
+// A citation is a REFERENCES relationship (source cites target) whose
+// metadata_json holds `{"citation": {"locator", "note"}}`. Each citation adds
+// to the citation_count of the ContributorPresence behind the cited content.
+
+/// Relationship type used for citations
+const CITATION_RELATIONSHIP: &str = "REFERENCES";
+
+/// Deepest citation graph walk
+const MAX_CITATION_DEPTH: u32 = 5;
+
+/// Most nodes returned in one citation graph
+const MAX_CITATION_NODES: usize = 200;
+
+/// Citation metadata stored in a REFERENCES relationship
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct CitationMetadata {
+    #[serde(default)]
+    locator: Option<String>,
+    #[serde(default)]
+    note: Option<String>,
+}
+
+impl CitationMetadata {
+    fn from_relationship(relationship: &Relationship) -> Self {
+        relationship
+            .metadata_json
+            .as_deref()
+            .and_then(|json| serde_json::from_str::<serde_json::Value>(json).ok())
+            .and_then(|metadata| metadata.get("citation").cloned())
+            .and_then(|citation| serde_json::from_value(citation).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// Cite one content from another.
+///
+/// Creates a REFERENCES relationship carrying the locator and note, and
+/// counts the citation on the cited content's ContributorPresence (created
+/// unclaimed if the content has none yet). Citing the same content twice
+/// from one source is rejected.
+#[hdk_extern]
+pub fn cite_content(input: CiteContentInput) -> ExternResult<CitationOutput> {
+    if input.source_id == input.cited_id {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Content cannot cite itself".to_string()
+        )));
+    }
+    if !content_exists_by_id(&input.source_id)? {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Content not found: {}",
+            input.source_id
+        ))));
+    }
+    let cited = get_content_by_id(QueryByIdInput { id: input.cited_id.clone() })?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(format!(
+            "Content not found: {}",
+            input.cited_id
+        ))))?
+        .content;
+
+    let already_cited = get_relationships(GetRelationshipsInput {
+        content_id: input.source_id.clone(),
+        direction: "outgoing".to_string(),
+    })?
+    .iter()
+    .any(|r| r.relationship.relationship_type == CITATION_RELATIONSHIP && r.relationship.target_id == input.cited_id);
+    if already_cited {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "'{}' already cites '{}'",
+            input.source_id, input.cited_id
+        ))));
+    }
+
+    let citation = CitationMetadata {
+        locator: input.locator.filter(|l| !l.trim().is_empty()),
+        note: input.note.filter(|n| !n.trim().is_empty()),
+    };
+    let relationship = create_relationship(CreateRelationshipInput {
+        source_id: input.source_id,
+        target_id: input.cited_id,
+        relationship_type: CITATION_RELATIONSHIP.to_string(),
+        confidence: 1.0,
+        inference_source: "explicit".to_string(),
+        metadata_json: Some(serde_json::json!({ "citation": citation }).to_string()),
+    })?;
+
+    let timestamp = format!("{:?}", sys_time()?);
+    let (presence_id, citation_count) = add_presence_citation(&cited, &timestamp)?;
+    emit_write_signal("ContributorPresence", &presence_id, "cite_content");
+
+    Ok(CitationOutput {
+        relationship,
+        presence_id,
+        citation_count,
+    })
+}
+
+/// Count one more citation on the presence behind `content`.
+/// Writes an updated ContributorPresence and re-points its indexes at it.
+/// Returns the presence ID and its new citation count.
+fn add_presence_citation(content: &Content, timestamp: &str) -> ExternResult<(String, u32)> {
+    get_or_create_content_presence(&content.id, &content.title, content.author_id.as_deref(), timestamp)?;
+
+    let content_anchor = StringAnchor::new("content_presence", &content.id);
+    let content_anchor_hash = hash_entry(&EntryTypes::StringAnchor(content_anchor))?;
+    let query = LinkQuery::try_new(content_anchor_hash, LinkTypes::ContentToPresence)?;
+    let link = get_links(query, GetStrategy::default())?
+        .into_iter()
+        .next()
+        .ok_or(wasm_error!(WasmErrorInner::Guest(format!(
+            "No presence for content: {}",
+            content.id
+        ))))?;
+    let previous_hash = ActionHash::try_from(link.target)
+        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid presence hash".to_string())))?;
+    let mut presence: ContributorPresence = get(previous_hash.clone(), GetOptions::default())?
+        .and_then(|record| record.entry().to_app_option().ok().flatten())
+        .ok_or(wasm_error!(WasmErrorInner::Guest(format!(
+            "Presence not found for content: {}",
+            content.id
+        ))))?;
+
+    presence.citation_count = presence.citation_count.saturating_add(1);
+    presence.last_recognition_at = timestamp.to_string();
+    presence.updated_at = timestamp.to_string();
+    let action_hash = update_entry(previous_hash.clone(), &EntryTypes::ContributorPresence(presence.clone()))?;
+
+    // Re-point content, ID and state indexes at the updated record
+    let old_target: AnyLinkableHash = previous_hash.into();
+    let indexes = [
+        (StringAnchor::new("content_presence", &content.id), LinkTypes::ContentToPresence),
+        (StringAnchor::new("presence_id", &presence.id), LinkTypes::IdToPresence),
+        (StringAnchor::new("presence_state", &presence.presence_state), LinkTypes::PresenceByState),
+    ];
+    for (anchor, link_type) in indexes {
+        let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
+        let query = LinkQuery::try_new(anchor_hash.clone(), link_type)?;
+        for link in get_links(query, GetStrategy::default())?.into_iter().filter(|l| l.target == old_target) {
+            delete_link(link.create_link_hash, GetOptions::default())?;
+        }
+        create_link(anchor_hash, action_hash.clone(), link_type, ())?;
+    }
+
+    Ok((presence.id, presence.citation_count))
+}
+
+/// Walk the citation graph around a content node.
+///
+/// `cites` follows what the content cites (its provenance), `cited_by`
+/// follows what cites it, `both` does both. Nodes are listed breadth-first
+/// with their distance from the root.
+#[hdk_extern]
+pub fn get_citation_graph(input: GetCitationGraphInput) -> ExternResult<CitationGraph> {
+    let direction = input.direction.unwrap_or_else(|| "both".to_string());
+    let (follow_cites, follow_cited_by) = match direction.as_str() {
+        "cites" => (true, false),
+        "cited_by" => (false, true),
+        "both" => (true, true),
+        other => {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                "Invalid direction: {}. Must be one of: cites, cited_by, both",
+                other
+            ))))
+        }
+    };
+    let max_depth = input.depth.unwrap_or(2).clamp(1, MAX_CITATION_DEPTH);
+
+    let mut depths: HashMap<String, u32> = HashMap::new();
+    depths.insert(input.content_id.clone(), 0);
+    let mut order = vec![input.content_id.clone()];
+    let mut seen_edges: HashSet<String> = HashSet::new();
+    let mut edges = Vec::new();
+    let mut truncated = false;
+    let mut frontier = vec![input.content_id.clone()];
+
+    for depth in 1..=max_depth {
+        let mut next = Vec::new();
+        for content_id in &frontier {
+            let relationships = get_relationships(GetRelationshipsInput {
+                content_id: content_id.clone(),
+                direction: "both".to_string(),
+            })?;
+            for output in relationships {
+                let relationship = output.relationship;
+                if relationship.relationship_type != CITATION_RELATIONSHIP {
+                    continue;
+                }
+                let neighbour = if follow_cites && relationship.source_id == *content_id {
+                    relationship.target_id.clone()
+                } else if follow_cited_by && relationship.target_id == *content_id {
+                    relationship.source_id.clone()
+                } else {
+                    continue;
+                };
+
+                if !depths.contains_key(&neighbour) {
+                    if depths.len() >= MAX_CITATION_NODES {
+                        truncated = true;
+                        continue;
+                    }
+                    depths.insert(neighbour.clone(), depth);
+                    order.push(neighbour.clone());
+                    next.push(neighbour);
+                }
+                if seen_edges.insert(relationship.id.clone()) {
+                    let citation = CitationMetadata::from_relationship(&relationship);
+                    edges.push(CitationEdge {
+                        source_id: relationship.source_id,
+                        cited_id: relationship.target_id,
+                        locator: citation.locator,
+                        note: citation.note,
+                        created_at: relationship.created_at,
+                    });
+                }
+            }
+        }
+        if next.is_empty() {
+            break;
+        }
+        frontier = next;
+    }
+
+    let mut nodes = Vec::with_capacity(order.len());
+    for content_id in order {
+        let title = get_content_by_id(QueryByIdInput { id: content_id.clone() })?.map(|c| c.content.title);
+        nodes.push(CitationNode {
+            depth: depths.get(&content_id).copied().unwrap_or(0),
+            content_id,
+            title,
+        });
+    }
+
+    Ok(CitationGraph {
+        root_id: input.content_id,
+        nodes,
+        edges,
+        truncated,
+    })
+}
+
+// =============================================================================
+// Human CRUD operations moved to: holochain/dna/imagodei/zomes/imagodei/
+
+// =============================================================================
+// Existence Check Helpers
+// =============================================================================
+
+/// Check if content with the given ID already exists.
+/// Used to prevent duplicate entries during create operations.
+fn content_exists_by_id(id: &str) -> ExternResult<bool> {
+    let anchor = StringAnchor::new("content_id", id);
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
+    let query = LinkQuery::try_new(anchor_hash, LinkTypes::IdToContent)?;
+    let links = get_links(query, GetStrategy::default())?;
+    Ok(!links.is_empty())
+}
+
+/// Check if a learning path with the given ID already exists.
+fn path_exists_by_id(id: &str) -> ExternResult<bool> {
+    let anchor = StringAnchor::new("path_id", id);
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
+    let query = LinkQuery::try_new(anchor_hash, LinkTypes::IdToPath)?;
+    let links = get_links(query, GetStrategy::default())?;
+    Ok(!links.is_empty())
+}
+
+// =============================================================================
+// Content Link Tags (sort, filter and summary keys)
+// =============================================================================
+
+/// Max title bytes stored in a link tag (link tags are capped at 1KB)
+const LINK_TAG_TITLE_MAX: usize = 200;
+
+/// Truncate a string to at most `max` bytes on a char boundary
+fn truncate_for_tag(value: &str, max: usize) -> String {
+    let mut out = value.to_string();
+    if out.len() > max {
+        let mut cut = max;
+        while !out.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        out.truncate(cut);
+    }
+    out
+}
+
+/// Compact content summary written into TypeToContent/TagToContent link tags at create time.
+/// Serves as sort key, filter key and listing summary, so listings need no `get`.
+/// Links created before these keys existed have an empty tag and decode to defaults.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ContentLinkTag {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub created_at: i64,                       // Microseconds since epoch
+    #[serde(default)]
+    pub updated_at: String,
+    #[serde(default)]
+    pub title: String,                         // Truncated to LINK_TAG_TITLE_MAX
+    #[serde(default)]
+    pub content_type: String,
+    #[serde(default)]
+    pub estimated_minutes: Option<u32>,
+    #[serde(default)]
+    pub reach: String,
+}
+
+impl ContentLinkTag {
+    fn from_content(content: &Content, created_at: &Timestamp) -> Self {
+        Self {
+            id: content.id.clone(),
+            created_at: created_at.as_micros(),
+            updated_at: content.updated_at.clone(),
+            title: truncate_for_tag(&content.title, LINK_TAG_TITLE_MAX),
+            content_type: content.content_type.clone(),
+            estimated_minutes: content.estimated_minutes,
+            reach: content.reach.clone(),
+        }
+    }
+
+    fn to_link_tag(&self) -> ExternResult<LinkTag> {
+        let bytes = serde_json::to_vec(self)
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Failed to encode link tag: {}", e))))?;
+        Ok(LinkTag::new(bytes))
+    }
+
+    fn from_link(link: &Link) -> Self {
+        serde_json::from_slice(&link.tag.0).unwrap_or_default()
+    }
+}
+
+/// Order links by the sort keys stored in their tags.
+/// Untagged (legacy) links keep their relative order and sort last.
+fn sort_content_links(links: &mut [Link], sort: ContentSort) {
+    let mut keyed: Vec<(Option<ContentLinkTag>, Link)> = links
+        .iter()
+        .map(|link| {
+            let key = if link.tag.0.is_empty() { None } else { Some(ContentLinkTag::from_link(link)) };
+            (key, link.clone())
+        })
+        .collect();
+
+    keyed.sort_by(|(a, _), (b, _)| match (a, b) {
+        (Some(a), Some(b)) => match sort {
+            ContentSort::CreatedAtDesc => b.created_at.cmp(&a.created_at),
+            ContentSort::CreatedAtAsc => a.created_at.cmp(&b.created_at),
+            ContentSort::Title => a.title.to_lowercase().cmp(&b.title.to_lowercase()),
+            ContentSort::EstimatedMinutes => match (a.estimated_minutes, b.estimated_minutes) {
+                (Some(x), Some(y)) => x.cmp(&y),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            },
+        },
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    });
+
+    for (slot, (_, link)) in links.iter_mut().zip(keyed) {
+        *slot = link;
+    }
+}
+
+// =============================================================================
+// Link Helper Functions
+// =============================================================================
+
+fn create_id_to_content_link(id: &str, target: &ActionHash) -> ExternResult<()> {
+    let anchor = StringAnchor::new("content_id", id);
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
+    create_link(anchor_hash, target.clone(), LinkTypes::IdToContent, ())?;
+    Ok(())
+}
+
+fn create_type_to_content_link(
+    content_type: &str,
+    target: &ActionHash,
+    sort_tag: &ContentLinkTag,
+) -> ExternResult<()> {
+    let anchor = StringAnchor::new("content_type", content_type);
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
+    create_link(anchor_hash, target.clone(), LinkTypes::TypeToContent, sort_tag.to_link_tag()?)?;
+    Ok(())
+}
+
+fn create_tag_to_content_link(
+    tag: &str,
+    target: &ActionHash,
+    sort_tag: &ContentLinkTag,
+) -> ExternResult<()> {
+    let anchor = StringAnchor::new("tag", tag);
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
+    create_link(anchor_hash, target.clone(), LinkTypes::TagToContent, sort_tag.to_link_tag()?)?;
+    Ok(())
+}
+
+fn create_license_to_content_link(license_id: &str, target: &ActionHash) -> ExternResult<()> {
+    let anchor = StringAnchor::new("license", license_id);
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
+    create_link(anchor_hash, target.clone(), LinkTypes::LicenseToContent, ())?;
+    Ok(())
+}
+
+fn create_author_to_content_link(target: &ActionHash) -> ExternResult<()> {
+    let agent_info = agent_info()?;
+    create_link(
+        agent_info.agent_initial_pubkey,
+        target.clone(),
+        LinkTypes::AuthorToContent,
+        (),
+    )?;
+    Ok(())
+}
+
+fn create_import_batch_link(import_id: &str, target: &ActionHash) -> ExternResult<()> {
+    let anchor = StringAnchor::new("import_batch", import_id);
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
+    create_link(anchor_hash, target.clone(), LinkTypes::ImportBatchToContent, ())?;
+    Ok(())
+}
+
+// =============================================================================
+// Agent CRUD operations moved to: holochain/dna/imagodei/zomes/imagodei/
+
+// =============================================================================
+// Agent progress operations moved to: holochain/dna/imagodei/zomes/imagodei/
+
+// =============================================================================
+// Content mastery CRUD moved to: holochain/dna/imagodei/zomes/imagodei/
+
+// =============================================================================
+// Attestation operations moved to: holochain/dna/imagodei/zomes/imagodei/
+
+// =============================================================================
+// Agent presence operations moved to: holochain/dna/imagodei/zomes/imagodei/
+
+// =============================================================================
+// Progress Tracking Operations
+// =============================================================================
+// NOTE: Progress tracking uses AgentToPathProgress link which remains in lamad
+// as it tracks learning path progress, a core lamad concern.
+// Agent/Human progress (identity-based) is in imagodei.
+
+/// Start tracking progress on a learning path
+#[hdk_extern]
+pub fn start_path_progress(input: StartPathProgressInput) -> ExternResult<AgentProgressOutput> {
+    let agent_info = agent_info()?;
+    let agent_id = agent_info.agent_initial_pubkey.to_string();
+    let now = sys_time()?;
+    let timestamp = format!("{:?}", now);
+
+    // Generate progress ID
+    let progress_id = format!("{}-{}", agent_id, input.path_id);
+
+    // Check if progress already exists
+    let progress_anchor = StringAnchor::new("progress_id", &progress_id);
+    let progress_anchor_hash = hash_entry(&EntryTypes::StringAnchor(progress_anchor.clone()))?;
+
+    let existing_query = LinkQuery::try_new(progress_anchor_hash.clone(), LinkTypes::AgentToPathProgress)?;
+    let existing_links = get_links(existing_query, GetStrategy::default())?;
+
+    if let Some(link) = existing_links.first() {
+        // Progress already exists, return it
+        let action_hash = ActionHash::try_from(link.target.clone())
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid progress action hash".to_string())))?;
+        let record = get(action_hash.clone(), GetOptions::default())?
+            .ok_or(wasm_error!(WasmErrorInner::Guest("Progress record not found".to_string())))?;
+        let progress: AgentProgress = record
+            .entry()
+            .to_app_option()
+            .map_err(|e| wasm_error!(e))?
+            .ok_or(wasm_error!(WasmErrorInner::Guest("Could not deserialize progress".to_string())))?;
+        return Ok(AgentProgressOutput { action_hash, progress });
+    }
+
+    // Create new progress
+    let progress = AgentProgress {
+        id: progress_id.clone(),
+        agent_id: agent_id.clone(),
+        path_id: input.path_id.clone(),
+        current_step_index: 0,
+        completed_step_indices: Vec::new(),
+        completed_content_ids: Vec::new(),
+        step_affinity_json: "{}".to_string(),
+        step_notes_json: "{}".to_string(),
+        reflection_responses_json: "{}".to_string(),
+        attestations_earned: Vec::new(),
+        started_at: timestamp.clone(),
+        last_activity_at: timestamp,
+        completed_at: None,
+    };
+
+    let action_hash = create_entry(&EntryTypes::AgentProgress(progress.clone()))?;
+
+    // Create ID lookup link
+    create_link(progress_anchor_hash, action_hash.clone(), LinkTypes::AgentToPathProgress, ())?;
+
+    // Create agent-to-progress link
+    let agent_anchor = StringAnchor::new("agent_progress", &agent_id);
+    let agent_anchor_hash = hash_entry(&EntryTypes::StringAnchor(agent_anchor))?;
+    create_link(agent_anchor_hash, action_hash.clone(), LinkTypes::AgentToPathProgress, ())?;
+
+    // Create path-to-progress link
+    let path_progress_anchor = StringAnchor::new("path_progress", &input.path_id);
+    let path_progress_anchor_hash = hash_entry(&EntryTypes::StringAnchor(path_progress_anchor))?;
+    create_link(path_progress_anchor_hash, action_hash.clone(), LinkTypes::PathToProgress, ())?;
+
+    // Create status link (in_progress)
+    let status_anchor = StringAnchor::new("progress_status", "in_progress");
+    let status_anchor_hash = hash_entry(&EntryTypes::StringAnchor(status_anchor))?;
+    create_link(status_anchor_hash, action_hash.clone(), LinkTypes::ProgressByStatus, ())?;
+
+    Ok(AgentProgressOutput { action_hash, progress })
+}
+
+/// Complete a step in a learning path
+#[hdk_extern]
+pub fn complete_step(input: CompleteStepInput) -> ExternResult<AgentProgressOutput> {
+    let agent_info = agent_info()?;
+    let agent_id = agent_info.agent_initial_pubkey.to_string();
+    let now = sys_time()?;
+    let timestamp = format!("{:?}", now);
+
+    // Find existing progress
+    let progress_id = format!("{}-{}", agent_id, input.path_id);
+    let progress_anchor = StringAnchor::new("progress_id", &progress_id);
+    let progress_anchor_hash = hash_entry(&EntryTypes::StringAnchor(progress_anchor))?;
+
+    let query = LinkQuery::try_new(progress_anchor_hash.clone(), LinkTypes::AgentToPathProgress)?;
+    let links = get_links(query, GetStrategy::default())?;
+
+    let link = links.first()
+        .ok_or(wasm_error!(WasmErrorInner::Guest(
+            format!("No progress found for path: {}", input.path_id)
+        )))?;
+
+    let existing_action_hash = ActionHash::try_from(link.target.clone())
+        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid progress action hash".to_string())))?;
+
+    let record = get(existing_action_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Progress record not found".to_string())))?;
+
+    let existing: AgentProgress = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not deserialize progress".to_string())))?;
+
+    // Checkpoints complete only after a passing review
+    let step = path_step_at(&input.path_id, input.step_index)?;
+    if let Some(step) = &step {
+        if step.step_type == "checkpoint" && !checkpoint_passed(&agent_id, step)? {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                "Checkpoint {} needs a passing review (submit_checkpoint) before it can be completed",
+                step.id
+            ))));
+        }
+    }
+
+    // Parse existing JSON data
+    let mut step_affinity: HashMap<String, u32> = serde_json::from_str(&existing.step_affinity_json)
+        .unwrap_or_default();
+    let mut step_notes: HashMap<String, String> = serde_json::from_str(&existing.step_notes_json)
+        .unwrap_or_default();
+    let mut reflection_responses: HashMap<String, Vec<String>> = serde_json::from_str(&existing.reflection_responses_json)
+        .unwrap_or_default();
+
+    // Update with new data
+    let step_key = input.step_index.to_string();
+    if let Some(affinity) = input.affinity_score {
+        step_affinity.insert(step_key.clone(), affinity);
+    }
+    if let Some(notes) = input.notes {
+        step_notes.insert(step_key.clone(), notes);
+    }
+    if let Some(responses) = input.reflection_responses {
+        // Each response is also journaled as a ReflectionEntry, paired with its prompt
+        let prompts: Vec<String> = step
+            .as_ref()
+            .and_then(|s| serde_json::from_str(&s.reflection_prompts_json).ok())
+            .unwrap_or_default();
+        for (i, response) in responses.iter().enumerate() {
+            if !response.trim().is_empty() {
+                write_reflection(
+                    &agent_id,
+                    &input.path_id,
+                    input.step_index,
+                    step.as_ref(),
+                    prompts.get(i).cloned(),
+                    response,
+                    &timestamp,
+                )?;
+            }
+        }
+        reflection_responses.insert(step_key, responses);
+    }
+
+    // Update completed steps
+    let mut completed_step_indices = existing.completed_step_indices.clone();
+    if !completed_step_indices.contains(&input.step_index) {
+        completed_step_indices.push(input.step_index);
+        completed_step_indices.sort();
+    }
+
+    // Update completed content IDs
+    let mut completed_content_ids = existing.completed_content_ids.clone();
+    if let Some(content_id) = input.content_id {
+        if !completed_content_ids.contains(&content_id) {
+            completed_content_ids.push(content_id);
+        }
+    }
+
+    // Create updated progress
+    let updated_progress = AgentProgress {
+        id: existing.id,
+        agent_id: existing.agent_id,
+        path_id: existing.path_id.clone(),
+        current_step_index: input.step_index + 1,  // Advance to next step
+        completed_step_indices,
+        completed_content_ids,
+        step_affinity_json: serde_json::to_string(&step_affinity).unwrap_or_else(|_| "{}".to_string()),
+        step_notes_json: serde_json::to_string(&step_notes).unwrap_or_else(|_| "{}".to_string()),
+        reflection_responses_json: serde_json::to_string(&reflection_responses).unwrap_or_else(|_| "{}".to_string()),
+        attestations_earned: existing.attestations_earned,
+        started_at: existing.started_at,
+        last_activity_at: timestamp,
+        completed_at: existing.completed_at,
+    };
+
+    let action_hash = create_entry(&EntryTypes::AgentProgress(updated_progress.clone()))?;
+
+    // Update ID lookup link
+    delete_link(link.create_link_hash.clone(), GetOptions::default())?;
+    create_link(progress_anchor_hash, action_hash.clone(), LinkTypes::AgentToPathProgress, ())?;
+
+    Ok(AgentProgressOutput { action_hash, progress: updated_progress })
+}
+
+/// Mark a path as completed
+#[hdk_extern]
+pub fn complete_path(path_id: String) -> ExternResult<AgentProgressOutput> {
+    let agent_info = agent_info()?;
+    let agent_id = agent_info.agent_initial_pubkey.to_string();
+    let now = sys_time()?;
+    let timestamp = format!("{:?}", now);
+
+    // Find existing progress
+    let progress_id = format!("{}-{}", agent_id, path_id);
+    let progress_anchor = StringAnchor::new("progress_id", &progress_id);
+    let progress_anchor_hash = hash_entry(&EntryTypes::StringAnchor(progress_anchor))?;
+
+    let query = LinkQuery::try_new(progress_anchor_hash.clone(), LinkTypes::AgentToPathProgress)?;
+    let links = get_links(query, GetStrategy::default())?;
+
+    let link = links.first()
+        .ok_or(wasm_error!(WasmErrorInner::Guest(
+            format!("No progress found for path: {}", path_id)
+        )))?;
+
+    let existing_action_hash = ActionHash::try_from(link.target.clone())
+        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid progress action hash".to_string())))?;
+
+    let record = get(existing_action_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Progress record not found".to_string())))?;
+
+    let existing: AgentProgress = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not deserialize progress".to_string())))?;
+
+    // Create completed progress
+    let completed_progress = AgentProgress {
+        id: existing.id,
+        agent_id: existing.agent_id,
+        path_id: existing.path_id.clone(),
+        current_step_index: existing.current_step_index,
+        completed_step_indices: existing.completed_step_indices,
+        completed_content_ids: existing.completed_content_ids,
+        step_affinity_json: existing.step_affinity_json,
+        step_notes_json: existing.step_notes_json,
+        reflection_responses_json: existing.reflection_responses_json,
+        attestations_earned: existing.attestations_earned,
+        started_at: existing.started_at,
+        last_activity_at: timestamp.clone(),
+        completed_at: Some(timestamp),
+    };
+
+    let action_hash = create_entry(&EntryTypes::AgentProgress(completed_progress.clone()))?;
+
+    // Update ID lookup link
+    delete_link(link.create_link_hash.clone(), GetOptions::default())?;
+    create_link(progress_anchor_hash, action_hash.clone(), LinkTypes::AgentToPathProgress, ())?;
+
+    // Update status link (in_progress -> completed)
+    let old_status_anchor = StringAnchor::new("progress_status", "in_progress");
+    let old_status_anchor_hash = hash_entry(&EntryTypes::StringAnchor(old_status_anchor))?;
+    let old_status_query = LinkQuery::try_new(old_status_anchor_hash, LinkTypes::ProgressByStatus)?;
+    let old_status_links = get_links(old_status_query, GetStrategy::default())?;
+    for status_link in old_status_links {
+        if status_link.target == existing_action_hash.clone().into() {
+            delete_link(status_link.create_link_hash, GetOptions::default())?;
+            break;
+        }
+    }
+
+    let new_status_anchor = StringAnchor::new("progress_status", "completed");
+    let new_status_anchor_hash = hash_entry(&EntryTypes::StringAnchor(new_status_anchor))?;
+    create_link(new_status_anchor_hash, action_hash.clone(), LinkTypes::ProgressByStatus, ())?;
+
+    emit_write_signal("AgentProgress", &completed_progress.id, "complete_path");
+    Ok(AgentProgressOutput { action_hash, progress: completed_progress })
+}
+
+/// Get current agent's progress on a path
+#[hdk_extern]
+pub fn get_my_path_progress(path_id: String) -> ExternResult<Option<AgentProgressOutput>> {
+    let agent_info = agent_info()?;
+    let agent_id = agent_info.agent_initial_pubkey.to_string();
+
+    let progress_id = format!("{}-{}", agent_id, path_id);
+    let progress_anchor = StringAnchor::new("progress_id", &progress_id);
+    let progress_anchor_hash = hash_entry(&EntryTypes::StringAnchor(progress_anchor))?;
+
+    let query = LinkQuery::try_new(progress_anchor_hash, LinkTypes::AgentToPathProgress)?;
+    let links = get_links(query, GetStrategy::default())?;
+
+    let link = match links.first() {
+        Some(l) => l,
+        None => return Ok(None),
+    };
+
+    let action_hash = ActionHash::try_from(link.target.clone())
+        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid progress action hash".to_string())))?;
+
+    let record = get(action_hash.clone(), GetOptions::default())?;
+    let record = match record {
+        Some(r) => r,
+        None => return Ok(None),
+    };
+
+    let progress: AgentProgress = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not deserialize progress".to_string())))?;
+
+    Ok(Some(AgentProgressOutput { action_hash, progress }))
+}
+
+/// Get all progress for current agent
+#[hdk_extern]
+pub fn get_my_all_progress(_: ()) -> ExternResult<Vec<AgentProgressOutput>> {
+    let agent_info = agent_info()?;
+    let agent_id = agent_info.agent_initial_pubkey.to_string();
+
+    let agent_anchor = StringAnchor::new("agent_progress", &agent_id);
+    let agent_anchor_hash = hash_entry(&EntryTypes::StringAnchor(agent_anchor))?;
+
+    let query = LinkQuery::try_new(agent_anchor_hash, LinkTypes::AgentToPathProgress)?;
+    let links = get_links(query, GetStrategy::default())?;
+
+    let mut results = Vec::new();
+    for link in links {
+        let action_hash = ActionHash::try_from(link.target)
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid progress action hash".to_string())))?;
+
+        let record = get(action_hash.clone(), GetOptions::default())?;
+        if let Some(rec) = record {
+            if let Some(progress) = rec.entry().to_app_option::<AgentProgress>().ok().flatten() {
+                results.push(AgentProgressOutput { action_hash, progress });
+            }
+        }
+    }
+
+    Ok(results)
+}
+
+/// Get progress by status (in_progress, completed, abandoned)
+#[hdk_extern]
+pub fn get_progress_by_status(status: String) -> ExternResult<Vec<AgentProgressOutput>> {
+    let status_anchor = StringAnchor::new("progress_status", &status);
+    let status_anchor_hash = hash_entry(&EntryTypes::StringAnchor(status_anchor))?;
+
+    let query = LinkQuery::try_new(status_anchor_hash, LinkTypes::ProgressByStatus)?;
+    let links = get_links(query, GetStrategy::default())?;
+
+    let mut results = Vec::new();
+    for link in links {
+        let action_hash = ActionHash::try_from(link.target)
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid progress action hash".to_string())))?;
+
+        let record = get(action_hash.clone(), GetOptions::default())?;
+        if let Some(rec) = record {
+            if let Some(progress) = rec.entry().to_app_option::<AgentProgress>().ok().flatten() {
+                results.push(AgentProgressOutput { action_hash, progress });
+            }
+        }
+    }
+
+    Ok(results)
+}
+
+/// Get progress summaries for current agent
+#[hdk_extern]
+pub fn get_my_progress_summaries(_: ()) -> ExternResult<Vec<ProgressSummary>> {
+    let all_progress = get_my_all_progress(())?;
+    let mut summaries = Vec::new();
+
+    for progress_output in all_progress {
+        let progress = progress_output.progress;
+
+        // Get path to count total steps
+        let path_result = get_path_with_steps(progress.path_id.clone().into())?;
+        let (path_title, total_steps) = match path_result {
+            Some(path_data) => (path_data.path.title, path_data.steps.len() as u32),
+            None => ("Unknown Path".to_string(), 0),
+        };
+
+        summaries.push(ProgressSummary {
+            path_id: progress.path_id,
+            path_title,
+            total_steps,
+            completed_steps: progress.completed_step_indices.len() as u32,
+            current_step_index: progress.current_step_index,
+            is_completed: progress.completed_at.is_some(),
+            attestations_earned: progress.attestations_earned,
+            started_at: progress.started_at,
+            last_activity_at: progress.last_activity_at,
+            completed_at: progress.completed_at,
+        });
+    }
+
+    Ok(summaries)
+}
+
+// =============================================================================
+// Reflection Journal
+// =============================================================================
+
+/// The learner an input names, or the calling agent
+fn learner_or_agent(human_id: Option<String>) -> ExternResult<String> {
+    match human_id {
+        Some(id) if !id.is_empty() => Ok(id),
+        _ => Ok(agent_info()?.agent_initial_pubkey.to_string()),
+    }
+}
+
+/// Anchor for a learner's reflections, on one path or all of them
+fn reflections_anchor(learner_id: &str, path_id: Option<&str>) -> ExternResult<EntryHash> {
+    let anchor = match path_id {
+        Some(path_id) => StringAnchor::new("learner_path_reflections", &format!("{}:{}", learner_id, path_id)),
+        None => StringAnchor::new("learner_reflections", learner_id),
+    };
+    hash_entry(&EntryTypes::StringAnchor(anchor))
+}
+
+fn reflection_grants_anchor(anchor_type: &str, human_id: &str) -> ExternResult<EntryHash> {
+    hash_entry(&EntryTypes::StringAnchor(StringAnchor::new(anchor_type, human_id)))
+}
+
+fn write_reflection(
+    learner_id: &str,
+    path_id: &str,
+    step_index: u32,
+    step: Option<&PathStep>,
+    prompt: Option<String>,
+    response: &str,
+    timestamp: &str,
+) -> ExternResult<ReflectionOutput> {
+    let reflection = ReflectionEntry {
+        id: format!("reflection-{}-{}-{}-{}", learner_id, path_id, step_index, sys_time()?.as_micros()),
+        learner_id: learner_id.to_string(),
+        path_id: path_id.to_string(),
+        step_index,
+        step_id: step.map(|s| s.id.clone()),
+        prompt,
+        response: response.to_string(),
+        created_at: timestamp.to_string(),
+        updated_at: timestamp.to_string(),
+    };
+    let action_hash = create_entry(&EntryTypes::ReflectionEntry(reflection.clone()))?;
+
+    for anchor in [reflections_anchor(learner_id, Some(path_id))?, reflections_anchor(learner_id, None)?] {
+        create_link(anchor, action_hash.clone(), LinkTypes::LearnerToReflection, ())?;
+    }
+
+    emit_write_signal("ReflectionEntry", &reflection.id, "create_reflection");
+
+    Ok(ReflectionOutput { action_hash, reflection })
+}
+
+/// Reflections under an anchor, in step order then oldest first
+fn load_reflections(anchor: EntryHash) -> ExternResult<Vec<ReflectionOutput>> {
+    let query = LinkQuery::try_new(anchor, LinkTypes::LearnerToReflection)?;
+    let hashes: Vec<ActionHash> = get_links(query, GetStrategy::default())?
+        .into_iter()
+        .filter_map(|link| link.target.into_action_hash())
+        .collect();
+    let records = get_records_batch(hashes.clone())?;
+
+    let mut results = Vec::new();
+    for (action_hash, record) in hashes.into_iter().zip(records) {
+        if let Some(reflection) = record.and_then(|r| r.entry().to_app_option::<ReflectionEntry>().ok().flatten()) {
+            results.push(ReflectionOutput { action_hash, reflection });
+        }
+    }
+    results.sort_by(|a, b| {
+        (a.reflection.step_index, &a.reflection.created_at).cmp(&(b.reflection.step_index, &b.reflection.created_at))
+    });
+    Ok(results)
+}
+
+/// Current grants under a learner or facilitator anchor
+fn load_reflection_grants(anchor: EntryHash, link_type: LinkTypes) -> ExternResult<Vec<ReflectionGrantOutput>> {
+    let query = LinkQuery::try_new(anchor, link_type)?;
+    let hashes: Vec<ActionHash> = get_links(query, GetStrategy::default())?
+        .into_iter()
+        .filter_map(|link| link.target.into_action_hash())
+        .collect();
+    let records = get_records_batch(hashes.clone())?;
+
+    let mut results = Vec::new();
+    for (action_hash, record) in hashes.into_iter().zip(records) {
+        if let Some(grant) = record.and_then(|r| r.entry().to_app_option::<ReflectionShareGrant>().ok().flatten()) {
+            results.push(ReflectionGrantOutput { action_hash, grant });
+        }
+    }
+    Ok(results)
+}
+
+/// The learner's unrevoked grant to a facilitator for a path
+fn active_reflection_grant(
+    learner_id: &str,
+    facilitator_id: &str,
+    path_id: &str,
+) -> ExternResult<Option<ReflectionGrantOutput>> {
+    let anchor = reflection_grants_anchor("reflection_grants_by_learner", learner_id)?;
+    Ok(load_reflection_grants(anchor, LinkTypes::LearnerToReflectionGrant)?
+        .into_iter()
+        .find(|g| {
+            g.grant.facilitator_id == facilitator_id && g.grant.path_id == path_id && g.grant.revoked_at.is_none()
+        }))
+}
+
+/// Journal a reflection on a path step.
+///
+/// Reflections are private to the learner; facilitators only see them
+/// through an explicit `grant_reflection_access`.
+#[hdk_extern]
+pub fn create_reflection(input: CreateReflectionInput) -> ExternResult<ReflectionOutput> {
+    if input.response.trim().is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest("Reflection response cannot be empty".to_string())));
+    }
+    let learner_id = learner_or_agent(input.human_id)?;
+    let timestamp = format!("{:?}", sys_time()?);
+    let step = path_step_at(&input.path_id, input.step_index)?;
+
+    write_reflection(
+        &learner_id,
+        &input.path_id,
+        input.step_index,
+        step.as_ref(),
+        input.prompt,
+        &input.response,
+        &timestamp,
+    )
+}
+
+/// Get the calling agent's reflections on a path
+#[hdk_extern]
+pub fn get_my_reflections(path_id: String) -> ExternResult<Vec<ReflectionOutput>> {
+    get_learner_reflections(LearnerReflectionsInput { human_id: None, path_id: Some(path_id) })
+}
+
+/// Get a learner's own reflections, on one path or all paths
+#[hdk_extern]
+pub fn get_learner_reflections(input: LearnerReflectionsInput) -> ExternResult<Vec<ReflectionOutput>> {
+    let learner_id = learner_or_agent(input.human_id)?;
+    load_reflections(reflections_anchor(&learner_id, input.path_id.as_deref())?)
+}
+
+/// Let a facilitator read the learner's reflections on a path.
+///
+/// Returns the existing grant when one is already active.
+#[hdk_extern]
+pub fn grant_reflection_access(input: ReflectionGrantInput) -> ExternResult<ReflectionGrantOutput> {
+    let learner_id = learner_or_agent(input.human_id)?;
+    if let Some(existing) = active_reflection_grant(&learner_id, &input.facilitator_id, &input.path_id)? {
+        return Ok(existing);
+    }
+
+    let now = sys_time()?;
+    let grant = ReflectionShareGrant {
+        id: format!("reflection-grant-{}-{}-{}", learner_id, input.facilitator_id, now.as_micros()),
+        learner_id: learner_id.clone(),
+        facilitator_id: input.facilitator_id.clone(),
+        path_id: input.path_id,
+        granted_at: format!("{:?}", now),
+        revoked_at: None,
+    };
+    let action_hash = create_entry(&EntryTypes::ReflectionShareGrant(grant.clone()))?;
+
+    create_link(
+        reflection_grants_anchor("reflection_grants_by_learner", &learner_id)?,
+        action_hash.clone(),
+        LinkTypes::LearnerToReflectionGrant,
+        (),
+    )?;
+    create_link(
+        reflection_grants_anchor("reflection_grants_by_facilitator", &input.facilitator_id)?,
+        action_hash.clone(),
+        LinkTypes::FacilitatorToReflectionGrant,
+        (),
+    )?;
+
+    emit_write_signal("ReflectionShareGrant", &grant.id, "grant_reflection_access");
+
+    Ok(ReflectionGrantOutput { action_hash, grant })
+}
+
+/// Withdraw a facilitator's access to the learner's reflections on a path.
+///
+/// Returns the revoked grant, or None when there was no active grant.
+#[hdk_extern]
+pub fn revoke_reflection_access(input: ReflectionGrantInput) -> ExternResult<Option<ReflectionGrantOutput>> {
+    let learner_id = learner_or_agent(input.human_id)?;
+    let Some(existing) = active_reflection_grant(&learner_id, &input.facilitator_id, &input.path_id)? else {
+        return Ok(None);
+    };
+
+    let grant = ReflectionShareGrant {
+        revoked_at: Some(format!("{:?}", sys_time()?)),
+        ..existing.grant
+    };
+    let action_hash = create_entry(&EntryTypes::ReflectionShareGrant(grant.clone()))?;
+
+    // Point both anchors at the revoked version
+    let learner_anchor = reflection_grants_anchor("reflection_grants_by_learner", &learner_id)?;
+    delete_links_to(learner_anchor.clone(), LinkTypes::LearnerToReflectionGrant, &existing.action_hash)?;
+    create_link(learner_anchor, action_hash.clone(), LinkTypes::LearnerToReflectionGrant, ())?;
+    let facilitator_anchor = reflection_grants_anchor("reflection_grants_by_facilitator", &grant.facilitator_id)?;
+    delete_links_to(facilitator_anchor.clone(), LinkTypes::FacilitatorToReflectionGrant, &existing.action_hash)?;
+    create_link(facilitator_anchor, action_hash.clone(), LinkTypes::FacilitatorToReflectionGrant, ())?;
+
+    emit_write_signal("ReflectionShareGrant", &grant.id, "revoke_reflection_access");
+
+    Ok(Some(ReflectionGrantOutput { action_hash, grant }))
+}
+
+/// Get a learner's reflection grants, active and revoked
+#[hdk_extern]
+pub fn get_reflection_grants(human_id: Option<String>) -> ExternResult<Vec<ReflectionGrantOutput>> {
+    let learner_id = learner_or_agent(human_id)?;
+    load_reflection_grants(
+        reflection_grants_anchor("reflection_grants_by_learner", &learner_id)?,
+        LinkTypes::LearnerToReflectionGrant,
+    )
+}
+
+/// Get a learner's reflections on a path, as the facilitator they shared them with.
+///
+/// Fails unless the learner has an active grant for this facilitator and path.
+#[hdk_extern]
+pub fn get_shared_reflections(input: SharedReflectionsInput) -> ExternResult<Vec<ReflectionOutput>> {
+    let facilitator_id = learner_or_agent(input.facilitator_id)?;
+    if active_reflection_grant(&input.learner_id, &facilitator_id, &input.path_id)?.is_none() {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "These reflections have not been shared with you".to_string()
+        )));
+    }
+    load_reflections(reflections_anchor(&input.learner_id, Some(&input.path_id))?)
+}
+
+/// Export a learner's data held by this zome
+#[hdk_extern]
+pub fn export_learner_data(human_id: Option<String>) -> ExternResult<LearnerDataExport> {
+    let learner_id = learner_or_agent(human_id)?;
+    Ok(LearnerDataExport {
+        reflections: load_reflections(reflections_anchor(&learner_id, None)?)?,
+        reflection_grants: get_reflection_grants(Some(learner_id.clone()))?,
+        exported_at: format!("{:?}", sys_time()?),
+        learner_id,
+    })
+}
+
+/// Step notes a learner kept on each path they started, by step index
+fn step_notes_by_path(agent_id: &str) -> ExternResult<BTreeMap<String, BTreeMap<u32, String>>> {
+    let anchor = StringAnchor::new("agent_progress", agent_id);
+    let query = LinkQuery::try_new(hash_entry(&EntryTypes::StringAnchor(anchor))?, LinkTypes::AgentToPathProgress)?;
+    let hashes: Vec<ActionHash> = get_links(query, GetStrategy::default())?
+        .into_iter()
+        .filter_map(|link| link.target.into_action_hash())
+        .collect();
+
+    let mut notes_by_path = BTreeMap::new();
+    for record in get_records_batch(hashes)?.into_iter().flatten() {
+        let Some(progress) = record.entry().to_app_option::<AgentProgress>().ok().flatten() else {
+            continue;
+        };
+        let notes: HashMap<String, String> = serde_json::from_str(&progress.step_notes_json).unwrap_or_default();
+        let notes: BTreeMap<u32, String> = notes
+            .into_iter()
+            .filter(|(_, note)| !note.trim().is_empty())
+            .filter_map(|(index, note)| Some((index.parse().ok()?, note)))
+            .collect();
+        notes_by_path.insert(progress.path_id, notes);
+    }
+    Ok(notes_by_path)
+}
+
+/// Gather everything a learner wrote or saved for the doorway's Markdown
+/// vault export: step notes and reflections by path, and each content item
+/// they wrote about, annotated or bookmarked, with the relationships between
+/// those items.
+#[hdk_extern]
+pub fn get_notes_export(input: NotesExportInput) -> ExternResult<NotesExport> {
+    let mut reflections_by_step: BTreeMap<(String, u32), Vec<ReflectionEntry>> = BTreeMap::new();
+    for output in load_reflections(reflections_anchor(&input.human_id, None)?)? {
+        let reflection = output.reflection;
+        reflections_by_step
+            .entry((reflection.path_id.clone(), reflection.step_index))
+            .or_default()
+            .push(reflection);
+    }
+    let mut notes_by_path = step_notes_by_path(&input.agent_id)?;
+    for (path_id, _) in reflections_by_step.keys() {
+        notes_by_path.entry(path_id.clone()).or_default();
+    }
+
+    let mut content_ids = BTreeSet::new();
+    let mut paths = Vec::new();
+    for (path_id, mut notes) in notes_by_path {
+        let path = get_path_with_steps(path_id.clone().into())?;
+        let mut path_steps: Vec<PathStep> = path.as_ref().map(|p| p.steps.iter().map(|s| s.step.clone()).collect()).unwrap_or_default();
+        path_steps.sort_by_key(|step| step.order_index);
+
+        let mut steps = Vec::new();
+        for step in path_steps {
+            let note = notes.remove(&step.order_index);
+            let reflections = reflections_by_step.remove(&(path_id.clone(), step.order_index)).unwrap_or_default();
+            if note.is_none() && reflections.is_empty() {
+                continue;
+            }
+            let content_id = (step.step_type == "content").then(|| step.resource_id.clone());
+            content_ids.extend(content_id.clone());
+            steps.push(NotesExportStep { step_index: step.order_index, title: step.step_title, content_id, note, reflections });
+        }
+
+        // Notes and reflections on steps since removed from the path
+        let mut orphaned: BTreeSet<u32> = notes.keys().copied().collect();
+        orphaned.extend(reflections_by_step.keys().filter(|(p, _)| *p == path_id).map(|(_, index)| *index));
+        for step_index in orphaned {
+            steps.push(NotesExportStep {
+                step_index,
+                title: None,
+                content_id: None,
+                note: notes.remove(&step_index),
+                reflections: reflections_by_step.remove(&(path_id.clone(), step_index)).unwrap_or_default(),
+            });
+        }
+
+        if !steps.is_empty() {
+            let title = path.map(|p| p.path.title).unwrap_or_else(|| path_id.clone());
+            paths.push(NotesExportPath { path_id, title, steps });
+        }
+    }
+
+    let mut folders = HashMap::new();
+    for output in load_bookmarks(&input.human_id)? {
+        content_ids.insert(output.bookmark.content_id.clone());
+        folders.insert(output.bookmark.content_id, output.bookmark.folder);
+    }
+    let mut annotations: HashMap<String, Vec<Annotation>> = HashMap::new();
+    for output in load_annotations(
+        StringAnchor::new("author_all_annotations", &input.human_id),
+        LinkTypes::AuthorToAllAnnotations,
+    )? {
+        content_ids.insert(output.annotation.content_id.clone());
+        annotations.entry(output.annotation.content_id.clone()).or_default().push(output.annotation);
+    }
+
+    let mut items = Vec::new();
+    for content_id in content_ids.iter() {
+        let title = healing_integration::get_content_by_id_with_healing(content_id)?
+            .map(|content| content.title)
+            .unwrap_or_else(|| content_id.clone());
+        let mut item_annotations = annotations.remove(content_id).unwrap_or_default();
+        item_annotations.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        let related = get_relationships(GetRelationshipsInput {
+            content_id: content_id.clone(),
+            direction: "outgoing".to_string(),
+        })?
+        .into_iter()
+        .map(|output| output.relationship)
+        .filter(|r| r.target_id != *content_id && content_ids.contains(&r.target_id))
+        .map(|r| NotesExportLink { content_id: r.target_id, relationship_type: r.relationship_type })
+        .collect();
+        items.push(NotesExportItem {
+            content_id: content_id.clone(),
+            title,
+            bookmark_folder: folders.remove(content_id),
+            annotations: item_annotations,
+            related,
+        });
+    }
+
+    Ok(NotesExport {
+        learner_id: input.human_id,
+        exported_at: format!("{:?}", sys_time()?),
+        paths,
+        items,
+    })
+}
+
+// =============================================================================
+// Learning Sessions
+// =============================================================================
+
+/// Microseconds in a day, for the by-day session anchors
+const MICROS_PER_DAY: i64 = 86_400_000_000;
+
+/// Widest window `get_learning_sessions_starting` will scan, in days
+const MAX_SESSION_WINDOW_DAYS: i64 = 7;
+
+/// Anchors a learning session is linked from
+fn learning_session_anchors(session: &LearningSession) -> Vec<(StringAnchor, LinkTypes)> {
+    let day = session.starts_at_micros.div_euclid(MICROS_PER_DAY).to_string();
+    let mut anchors = vec![
+        (StringAnchor::new("learning_session_id", &session.id), LinkTypes::IdToLearningSession),
+        (StringAnchor::new("organizer_learning_sessions", &session.organizer_id), LinkTypes::OrganizerToLearningSession),
+        (StringAnchor::new("learning_sessions_by_day", &day), LinkTypes::LearningSessionsByDay),
+    ];
+    if session.kind == "cohort" {
+        anchors.push((StringAnchor::new("path_cohort_sessions", &session.path_id), LinkTypes::PathToCohortSession));
+    }
+    anchors
+}
+
+fn load_learning_sessions(anchor: StringAnchor, link_type: LinkTypes) -> ExternResult<Vec<LearningSessionOutput>> {
+    let query = LinkQuery::try_new(hash_entry(&EntryTypes::StringAnchor(anchor))?, link_type)?;
+    let hashes: Vec<ActionHash> = get_links(query, GetStrategy::default())?
+        .into_iter()
+        .filter_map(|link| link.target.into_action_hash())
+        .collect();
+    let records = get_records_batch(hashes.clone())?;
+
+    let mut results = Vec::new();
+    for (action_hash, record) in hashes.into_iter().zip(records) {
+        if let Some(session) = record.and_then(|r| r.entry().to_app_option::<LearningSession>().ok().flatten()) {
+            results.push(LearningSessionOutput { action_hash, session });
+        }
+    }
+    Ok(results)
+}
+
+/// Learners a facilitator's cohort sessions on a path are for: those with an
+/// active reflection grant to the facilitator on that path
+fn cohort_learners(facilitator_id: &str, path_id: &str) -> ExternResult<Vec<String>> {
+    let anchor = reflection_grants_anchor("reflection_grants_by_facilitator", facilitator_id)?;
+    let mut learners: Vec<String> = load_reflection_grants(anchor, LinkTypes::FacilitatorToReflectionGrant)?
+        .into_iter()
+        .filter(|g| g.grant.path_id == path_id && g.grant.revoked_at.is_none())
+        .map(|g| g.grant.learner_id)
+        .collect();
+    learners.sort();
+    learners.dedup();
+    Ok(learners)
+}
+
+/// Schedule a study session for a path, or, as a facilitator, a cohort
+/// session for the learners sharing the path with you.
+#[hdk_extern]
+pub fn schedule_learning_session(input: ScheduleLearningSessionInput) -> ExternResult<LearningSessionOutput> {
+    if !LEARNING_SESSION_KINDS.contains(&input.kind.as_str()) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Invalid session kind: {}. Must be one of: {:?}",
+            input.kind, LEARNING_SESSION_KINDS
+        ))));
+    }
+    if input.title.trim().is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest("Session title cannot be empty".to_string())));
+    }
+    if input.duration_minutes == 0 || input.duration_minutes > MAX_LEARNING_SESSION_MINUTES {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Session duration must be between 1 and {} minutes",
+            MAX_LEARNING_SESSION_MINUTES
+        ))));
+    }
+    let now = sys_time()?;
+    if input.starts_at_micros <= now.as_micros() {
+        return Err(wasm_error!(WasmErrorInner::Guest("Sessions must start in the future".to_string())));
+    }
+    if find_path_action_hash(&input.path_id)?.is_none() {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!("Path not found: {}", input.path_id))));
+    }
+
+    let timestamp = format!("{:?}", now);
+    let session = LearningSession {
+        id: format!("session-{}-{}", input.organizer_id, now.as_micros()),
+        kind: input.kind,
+        organizer_id: input.organizer_id,
+        path_id: input.path_id,
+        title: input.title.trim().to_string(),
+        description: input.description,
+        location: input.location,
+        starts_at: format!("{:?}", Timestamp::from_micros(input.starts_at_micros)),
+        starts_at_micros: input.starts_at_micros,
+        duration_minutes: input.duration_minutes,
+        status: "scheduled".to_string(),
+        created_at: timestamp.clone(),
+        updated_at: timestamp,
+    };
+    let action_hash = create_entry(&EntryTypes::LearningSession(session.clone()))?;
+    for (anchor, link_type) in learning_session_anchors(&session) {
+        let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor.clone()))?;
+        create_entry(&EntryTypes::StringAnchor(anchor))?;
+        create_link(anchor_hash, action_hash.clone(), link_type, ())?;
+    }
+
+    emit_write_signal("LearningSession", &session.id, "schedule_learning_session");
+
+    Ok(LearningSessionOutput { action_hash, session })
+}
+
+/// Cancel a scheduled session; only its organizer can
+#[hdk_extern]
+pub fn cancel_learning_session(input: CancelLearningSessionInput) -> ExternResult<LearningSessionOutput> {
+    let existing = load_learning_sessions(
+        StringAnchor::new("learning_session_id", &input.session_id),
+        LinkTypes::IdToLearningSession,
+    )?
+    .into_iter()
+    .next()
+    .ok_or_else(|| wasm_error!(WasmErrorInner::Guest("Session not found".to_string())))?;
+    if existing.session.organizer_id != input.organizer_id {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the organizer can cancel a session".to_string()
+        )));
+    }
+    if existing.session.status == "cancelled" {
+        return Ok(existing);
+    }
+
+    let session = LearningSession {
+        status: "cancelled".to_string(),
+        updated_at: format!("{:?}", sys_time()?),
+        ..existing.session
+    };
+    let action_hash = update_entry(existing.action_hash.clone(), &EntryTypes::LearningSession(session.clone()))?;
+    for (anchor, link_type) in learning_session_anchors(&session) {
+        let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
+        delete_links_to(anchor_hash.clone(), link_type, &existing.action_hash)?;
+        create_link(anchor_hash, action_hash.clone(), link_type, ())?;
+    }
+
+    emit_write_signal("LearningSession", &session.id, "cancel_learning_session");
+
+    Ok(LearningSessionOutput { action_hash, session })
+}
+
+/// A learner's calendar: the sessions they organized, and the cohort
+/// sessions of facilitators they share a path with. Cancelled sessions are
+/// included so calendar feeds can drop them; sorted by start.
+#[hdk_extern]
+pub fn get_learner_calendar(learner_id: String) -> ExternResult<Vec<LearningSessionOutput>> {
+    let mut sessions = load_learning_sessions(
+        StringAnchor::new("organizer_learning_sessions", &learner_id),
+        LinkTypes::OrganizerToLearningSession,
+    )?;
+
+    let grants = load_reflection_grants(
+        reflection_grants_anchor("reflection_grants_by_learner", &learner_id)?,
+        LinkTypes::LearnerToReflectionGrant,
+    )?;
+    let mut shared: Vec<(String, String)> = grants
+        .into_iter()
+        .filter(|g| g.grant.revoked_at.is_none())
+        .map(|g| (g.grant.path_id, g.grant.facilitator_id))
+        .collect();
+    shared.sort();
+    shared.dedup();
+
+    let mut seen: HashSet<String> = sessions.iter().map(|s| s.session.id.clone()).collect();
+    for (path_id, facilitator_id) in shared {
+        for cohort in load_learning_sessions(
+            StringAnchor::new("path_cohort_sessions", &path_id),
+            LinkTypes::PathToCohortSession,
+        )? {
+            if cohort.session.organizer_id == facilitator_id && seen.insert(cohort.session.id.clone()) {
+                sessions.push(cohort);
+            }
+        }
+    }
+
+    sessions.sort_by_key(|s| s.session.starts_at_micros);
+    Ok(sessions)
+}
+
+/// Scheduled sessions starting in a window (at most a week), with everyone
+/// each is for; the doorway's reminder job polls this.
+#[hdk_extern]
+pub fn get_learning_sessions_starting(input: LearningSessionWindowInput) -> ExternResult<Vec<UpcomingLearningSession>> {
+    if input.until_micros <= input.from_micros {
+        return Ok(Vec::new());
+    }
+    let first_day = input.from_micros.div_euclid(MICROS_PER_DAY);
+    let last_day = (input.until_micros - 1).div_euclid(MICROS_PER_DAY);
+    if last_day - first_day >= MAX_SESSION_WINDOW_DAYS {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Session windows are limited to {} days",
+            MAX_SESSION_WINDOW_DAYS
+        ))));
+    }
+
+    let mut upcoming = Vec::new();
+    for day in first_day..=last_day {
+        for output in load_learning_sessions(
+            StringAnchor::new("learning_sessions_by_day", &day.to_string()),
+            LinkTypes::LearningSessionsByDay,
+        )? {
+            let session = output.session;
+            if session.status != "scheduled"
+                || session.starts_at_micros < input.from_micros
+                || session.starts_at_micros >= input.until_micros
+            {
+                continue;
+            }
+            let mut attendee_ids = vec![session.organizer_id.clone()];
+            if session.kind == "cohort" {
+                attendee_ids.extend(cohort_learners(&session.organizer_id, &session.path_id)?);
+            }
+            upcoming.push(UpcomingLearningSession { session, attendee_ids });
+        }
+    }
+
+    upcoming.sort_by_key(|u| u.session.starts_at_micros);
+    Ok(upcoming)
+}
+
+// =============================================================================
+// Annotations
+// =============================================================================
+
+/// Anchors an annotation is linked from
+fn annotation_anchors(annotation: &Annotation) -> Vec<(StringAnchor, LinkTypes)> {
+    let mut anchors = vec![
+        (StringAnchor::new("annotation_id", &annotation.id), LinkTypes::IdToAnnotation),
+        (
+            StringAnchor::new("author_annotations", &format!("{}:{}", annotation.author_id, annotation.content_id)),
+            LinkTypes::AuthorToAnnotation,
+        ),
+        (StringAnchor::new("author_all_annotations", &annotation.author_id), LinkTypes::AuthorToAllAnnotations),
+    ];
+    match (annotation.visibility.as_str(), &annotation.path_id) {
+        ("public", _) => anchors.push((
+            StringAnchor::new("content_public_annotations", &annotation.content_id),
+            LinkTypes::ContentToPublicAnnotation,
+        )),
+        ("cohort", Some(path_id)) => anchors.push((
+            StringAnchor::new("path_content_annotations", &format!("{}:{}", path_id, annotation.content_id)),
+            LinkTypes::PathContentToAnnotation,
+        )),
+        _ => {}
+    }
+    anchors
+}
+
+fn load_annotations(anchor: StringAnchor, link_type: LinkTypes) -> ExternResult<Vec<AnnotationOutput>> {
+    let query = LinkQuery::try_new(hash_entry(&EntryTypes::StringAnchor(anchor))?, link_type)?;
+    let hashes: Vec<ActionHash> = get_links(query, GetStrategy::default())?
+        .into_iter()
+        .filter_map(|link| link.target.into_action_hash())
+        .collect();
+    let records = get_records_batch(hashes.clone())?;
+
+    let mut results = Vec::new();
+    for (action_hash, record) in hashes.into_iter().zip(records) {
+        if let Some(annotation) = record.and_then(|r| r.entry().to_app_option::<Annotation>().ok().flatten()) {
+            results.push(AnnotationOutput { action_hash, annotation });
+        }
+    }
+    Ok(results)
+}
+
+fn annotation_by_id(annotation_id: &str) -> ExternResult<AnnotationOutput> {
+    load_annotations(StringAnchor::new("annotation_id", annotation_id), LinkTypes::IdToAnnotation)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| wasm_error!(WasmErrorInner::Guest(format!("Annotation not found: {}", annotation_id))))
+}
+
+/// Everyone in a reader's cohorts on a path: the facilitators they share
+/// the path with and those facilitators' learners, or, for a facilitator,
+/// their own learners. Includes the reader.
+fn path_cohort(reader_id: &str, path_id: &str) -> ExternResult<HashSet<String>> {
+    let mut cohort: HashSet<String> = cohort_learners(reader_id, path_id)?.into_iter().collect();
+    let grants = load_reflection_grants(
+        reflection_grants_anchor("reflection_grants_by_learner", reader_id)?,
+        LinkTypes::LearnerToReflectionGrant,
+    )?;
+    for grant in grants {
+        if grant.grant.path_id == path_id && grant.grant.revoked_at.is_none() {
+            cohort.extend(cohort_learners(&grant.grant.facilitator_id, path_id)?);
+            cohort.insert(grant.grant.facilitator_id);
+        }
+    }
+    cohort.insert(reader_id.to_string());
+    Ok(cohort)
+}
+
+/// Whether a reader may see an annotation
+fn annotation_visible_to(annotation: &Annotation, reader_id: &str) -> ExternResult<bool> {
+    if annotation.author_id == reader_id {
+        return Ok(true);
+    }
+    match (annotation.visibility.as_str(), &annotation.path_id) {
+        ("public", _) => Ok(true),
+        ("cohort", Some(path_id)) => Ok(path_cohort(reader_id, path_id)?.contains(&annotation.author_id)),
+        _ => Ok(false),
+    }
+}
+
+fn check_annotation_fields(visibility: &str, selector_json: &str) -> ExternResult<()> {
+    if !ANNOTATION_VISIBILITIES.contains(&visibility) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Invalid annotation visibility: {}. Must be one of: {:?}",
+            visibility, ANNOTATION_VISIBILITIES
+        ))));
+    }
+    if serde_json::from_str::<serde_json::Value>(selector_json).is_err() {
+        return Err(wasm_error!(WasmErrorInner::Guest("Annotation selector must be valid JSON".to_string())));
+    }
+    Ok(())
+}
+
+/// Highlight or comment on a range of content, or reply to an annotation
+/// you can see.
+#[hdk_extern]
+pub fn create_annotation(input: CreateAnnotationInput) -> ExternResult<AnnotationOutput> {
+    if !ANNOTATION_MOTIVATIONS.contains(&input.motivation.as_str()) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Invalid annotation motivation: {}. Must be one of: {:?}",
+            input.motivation, ANNOTATION_MOTIVATIONS
+        ))));
+    }
+
+    let (content_id, path_id, selector_json, visibility) = match input.reply_to {
+        Some(ref parent_id) => {
+            let parent = annotation_by_id(parent_id)?.annotation;
+            if !annotation_visible_to(&parent, &input.author_id)? {
+                return Err(wasm_error!(WasmErrorInner::Guest(format!("Annotation not found: {}", parent_id))));
+            }
+            (parent.content_id, parent.path_id, parent.selector_json, parent.visibility)
+        }
+        None => {
+            check_annotation_fields(&input.visibility, &input.selector_json)?;
+            if !content_exists_by_id(&input.content_id)? {
+                return Err(wasm_error!(WasmErrorInner::Guest(format!("Content not found: {}", input.content_id))));
+            }
+            if input.visibility == "cohort" && input.path_id.is_none() {
+                return Err(wasm_error!(WasmErrorInner::Guest("Cohort annotations need a path_id".to_string())));
+            }
+            if let Some(ref path_id) = input.path_id {
+                if find_path_action_hash(path_id)?.is_none() {
+                    return Err(wasm_error!(WasmErrorInner::Guest(format!("Path not found: {}", path_id))));
+                }
+            }
+            (input.content_id, input.path_id, input.selector_json, input.visibility)
+        }
+    };
+
+    let now = sys_time()?;
+    let timestamp = format!("{:?}", now);
+    let annotation = Annotation {
+        id: format!("annotation-{}-{}", input.author_id, now.as_micros()),
+        author_id: input.author_id,
+        content_id,
+        path_id,
+        selector_json,
+        motivation: input.motivation,
+        body: input.body.trim().to_string(),
+        reply_to: input.reply_to,
+        visibility,
+        created_at: timestamp.clone(),
+        updated_at: timestamp,
+    };
+    let action_hash = create_entry(&EntryTypes::Annotation(annotation.clone()))?;
+    for (anchor, link_type) in annotation_anchors(&annotation) {
+        let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor.clone()))?;
+        create_entry(&EntryTypes::StringAnchor(anchor))?;
+        create_link(anchor_hash, action_hash.clone(), link_type, ())?;
+    }
+
+    emit_write_signal("Annotation", &annotation.id, "create_annotation");
+
+    Ok(AnnotationOutput { action_hash, annotation })
+}
+
+/// Edit your annotation's body, range or visibility. Replies keep the
+/// range and visibility of what they answer.
+#[hdk_extern]
+pub fn update_annotation(input: UpdateAnnotationInput) -> ExternResult<AnnotationOutput> {
+    let existing = annotation_by_id(&input.annotation_id)?;
+    if existing.annotation.author_id != input.author_id {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the author can edit an annotation".to_string()
+        )));
+    }
+    let is_reply = existing.annotation.reply_to.is_some();
+    if is_reply && (input.selector_json.is_some() || input.visibility.is_some()) {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Replies keep the range and visibility of what they answer".to_string()
+        )));
+    }
+
+    let mut annotation = existing.annotation.clone();
+    if let Some(body) = input.body {
+        annotation.body = body.trim().to_string();
+    }
+    if let Some(selector_json) = input.selector_json {
+        annotation.selector_json = selector_json;
+    }
+    if let Some(visibility) = input.visibility {
+        annotation.visibility = visibility;
+    }
+    check_annotation_fields(&annotation.visibility, &annotation.selector_json)?;
+    if annotation.visibility == "cohort" && annotation.path_id.is_none() {
+        return Err(wasm_error!(WasmErrorInner::Guest("Cohort annotations need a path_id".to_string())));
+    }
+    annotation.updated_at = format!("{:?}", sys_time()?);
+
+    let action_hash = update_entry(existing.action_hash.clone(), &EntryTypes::Annotation(annotation.clone()))?;
+    for (anchor, link_type) in annotation_anchors(&existing.annotation) {
+        delete_links_to(hash_entry(&EntryTypes::StringAnchor(anchor))?, link_type, &existing.action_hash)?;
+    }
+    for (anchor, link_type) in annotation_anchors(&annotation) {
+        let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor.clone()))?;
+        create_entry(&EntryTypes::StringAnchor(anchor))?;
+        create_link(anchor_hash, action_hash.clone(), link_type, ())?;
+    }
+
+    emit_write_signal("Annotation", &annotation.id, "update_annotation");
+
+    Ok(AnnotationOutput { action_hash, annotation })
+}
+
+/// Delete your annotation; replies to it stay with their authors
+#[hdk_extern]
+pub fn delete_annotation(input: DeleteAnnotationInput) -> ExternResult<()> {
+    let existing = annotation_by_id(&input.annotation_id)?;
+    if existing.annotation.author_id != input.author_id {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the author can delete an annotation".to_string()
+        )));
+    }
+
+    for (anchor, link_type) in annotation_anchors(&existing.annotation) {
+        delete_links_to(hash_entry(&EntryTypes::StringAnchor(anchor))?, link_type, &existing.action_hash)?;
+    }
+    delete_entry(existing.action_hash)?;
+    emit_write_signal("Annotation", &input.annotation_id, "delete_annotation");
+
+    Ok(())
+}
+
+/// One annotation, if the reader may see it
+#[hdk_extern]
+pub fn get_annotation(input: GetAnnotationInput) -> ExternResult<Option<AnnotationOutput>> {
+    let found = match annotation_by_id(&input.annotation_id) {
+        Ok(found) => found,
+        Err(_) => return Ok(None),
+    };
+    Ok(annotation_visible_to(&found.annotation, &input.viewer_id)?.then_some(found))
+}
+
+/// The annotations a reader sees on a content node: their own, public ones,
+/// and, while studying a path, their cohort's layer for it. Oldest first.
+#[hdk_extern]
+pub fn get_annotations_for_content(input: GetAnnotationsInput) -> ExternResult<Vec<AnnotationOutput>> {
+    let mut annotations = load_annotations(
+        StringAnchor::new("author_annotations", &format!("{}:{}", input.viewer_id, input.content_id)),
+        LinkTypes::AuthorToAnnotation,
+    )?;
+    annotations.extend(load_annotations(
+        StringAnchor::new("content_public_annotations", &input.content_id),
+        LinkTypes::ContentToPublicAnnotation,
+    )?);
+    if let Some(ref path_id) = input.path_id {
+        let cohort = path_cohort(&input.viewer_id, path_id)?;
+        annotations.extend(
+            load_annotations(
+                StringAnchor::new("path_content_annotations", &format!("{}:{}", path_id, input.content_id)),
+                LinkTypes::PathContentToAnnotation,
+            )?
+            .into_iter()
+            .filter(|a| cohort.contains(&a.annotation.author_id)),
+        );
+    }
+
+    let mut seen = HashSet::new();
+    annotations.retain(|a| seen.insert(a.annotation.id.clone()));
+    annotations.sort_by(|a, b| a.annotation.created_at.cmp(&b.annotation.created_at));
+    Ok(annotations)
+}
+
+// =============================================================================
+// Bookmarks
+// =============================================================================
+
+fn load_bookmarks(owner_id: &str) -> ExternResult<Vec<BookmarkOutput>> {
+    let anchor = StringAnchor::new("owner_bookmarks", owner_id);
+    let query = LinkQuery::try_new(hash_entry(&EntryTypes::StringAnchor(anchor))?, LinkTypes::OwnerToBookmark)?;
+    let hashes: Vec<ActionHash> = get_links(query, GetStrategy::default())?
+        .into_iter()
+        .filter_map(|link| link.target.into_action_hash())
+        .collect();
+    let records = get_records_batch(hashes.clone())?;
+
+    let mut results = Vec::new();
+    for (action_hash, record) in hashes.into_iter().zip(records) {
+        if let Some(bookmark) = record.and_then(|r| r.entry().to_app_option::<Bookmark>().ok().flatten()) {
+            results.push(BookmarkOutput { action_hash, bookmark });
+        }
+    }
+    Ok(results)
+}
+
+/// Save content to a human's library. Saving content already there moves it
+/// to the given folder.
+#[hdk_extern]
+pub fn bookmark_content(input: BookmarkContentInput) -> ExternResult<BookmarkOutput> {
+    let owner_id = learner_or_agent(input.human_id)?;
+    let folder = input
+        .folder
+        .map(|f| f.trim().to_string())
+        .filter(|f| !f.is_empty())
+        .unwrap_or_else(|| DEFAULT_BOOKMARK_FOLDER.to_string());
+    if folder.len() > MAX_BOOKMARK_FOLDER_BYTES {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Bookmark folders are limited to {} bytes",
+            MAX_BOOKMARK_FOLDER_BYTES
+        ))));
+    }
+
+    let anchor = StringAnchor::new("owner_bookmarks", &owner_id);
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor.clone()))?;
+    let timestamp = format!("{:?}", sys_time()?);
+
+    let existing = load_bookmarks(&owner_id)?
+        .into_iter()
+        .find(|b| b.bookmark.content_id == input.content_id);
+    if let Some(existing) = existing {
+        if existing.bookmark.folder == folder {
+            return Ok(existing);
+        }
+        let bookmark = Bookmark {
+            folder,
+            updated_at: timestamp,
+            ..existing.bookmark.clone()
+        };
+        let action_hash = update_entry(existing.action_hash.clone(), &EntryTypes::Bookmark(bookmark.clone()))?;
+        delete_links_to(anchor_hash.clone(), LinkTypes::OwnerToBookmark, &existing.action_hash)?;
+        create_link(anchor_hash, action_hash.clone(), LinkTypes::OwnerToBookmark, ())?;
+        emit_write_signal("Bookmark", &owner_id, "bookmark_content");
+        return Ok(BookmarkOutput { action_hash, bookmark });
+    }
+
+    if !content_exists_by_id(&input.content_id)? {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!("Content not found: {}", input.content_id))));
+    }
+    let bookmark = Bookmark {
+        id: format!("bookmark-{}-{}", owner_id, input.content_id),
+        owner_id: owner_id.clone(),
+        content_id: input.content_id,
+        folder,
+        created_at: timestamp.clone(),
+        updated_at: timestamp,
+    };
+    let action_hash = create_entry(&EntryTypes::Bookmark(bookmark.clone()))?;
+    create_entry(&EntryTypes::StringAnchor(anchor))?;
+    create_link(anchor_hash, action_hash.clone(), LinkTypes::OwnerToBookmark, ())?;
+
+    emit_write_signal("Bookmark", &owner_id, "bookmark_content");
+
+    Ok(BookmarkOutput { action_hash, bookmark })
+}
+
+/// Take content out of a human's library. Removing content that isn't
+/// there is not an error.
+#[hdk_extern]
+pub fn remove_bookmark(input: RemoveBookmarkInput) -> ExternResult<()> {
+    let owner_id = learner_or_agent(input.human_id)?;
+    let existing = load_bookmarks(&owner_id)?
+        .into_iter()
+        .find(|b| b.bookmark.content_id == input.content_id);
+    let Some(existing) = existing else {
+        return Ok(());
+    };
+
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("owner_bookmarks", &owner_id)))?;
+    delete_links_to(anchor_hash, LinkTypes::OwnerToBookmark, &existing.action_hash)?;
+    delete_entry(existing.action_hash)?;
+    emit_write_signal("Bookmark", &owner_id, "remove_bookmark");
+
+    Ok(())
+}
+
+/// A human's library, by folder. Folders are sorted by name and their
+/// bookmarks newest first.
+#[hdk_extern]
+pub fn get_my_bookmarks(human_id: Option<String>) -> ExternResult<Vec<BookmarkFolder>> {
+    let owner_id = learner_or_agent(human_id)?;
+    let mut bookmarks = load_bookmarks(&owner_id)?;
+    bookmarks.sort_by(|a, b| b.bookmark.created_at.cmp(&a.bookmark.created_at));
+
+    let mut folders: BTreeMap<String, Vec<BookmarkOutput>> = BTreeMap::new();
+    for bookmark in bookmarks {
+        folders.entry(bookmark.bookmark.folder.clone()).or_default().push(bookmark);
+    }
+    Ok(folders
+        .into_iter()
+        .map(|(folder, bookmarks)| BookmarkFolder { folder, bookmarks })
+        .collect())
+}
+
+// =============================================================================
+// Attestation Operations
+// =============================================================================
+
+/// Grant an attestation for completing a step, chapter, or path
+#[hdk_extern]
+pub fn grant_attestation(input: GrantAttestationInput) -> ExternResult<AgentProgressOutput> {
+    let agent_info = agent_info()?;
+    let agent_id = agent_info.agent_initial_pubkey.to_string();
+    let now = sys_time()?;
+    let timestamp = format!("{:?}", now);
+
+    // Find existing progress
+    let progress_id = format!("{}-{}", agent_id, input.path_id);
+    let progress_anchor = StringAnchor::new("progress_id", &progress_id);
+    let progress_anchor_hash = hash_entry(&EntryTypes::StringAnchor(progress_anchor))?;
+
+    let query = LinkQuery::try_new(progress_anchor_hash.clone(), LinkTypes::AgentToPathProgress)?;
+    let links = get_links(query, GetStrategy::default())?;
+
+    let link = links.first()
+        .ok_or(wasm_error!(WasmErrorInner::Guest(
+            format!("No progress found for path: {}", input.path_id)
+        )))?;
+
+    let existing_action_hash = ActionHash::try_from(link.target.clone())
+        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid progress action hash".to_string())))?;
+
+    let record = get(existing_action_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Progress record not found".to_string())))?;
+
+    let existing: AgentProgress = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not deserialize progress".to_string())))?;
+
+    // Add attestation if not already earned
+    let mut attestations_earned = existing.attestations_earned.clone();
+    if !attestations_earned.contains(&input.attestation_id) {
+        attestations_earned.push(input.attestation_id.clone());
+    }
+
+    // Create updated progress with attestation
+    let updated_progress = AgentProgress {
+        id: existing.id,
+        agent_id: existing.agent_id,
+        path_id: existing.path_id.clone(),
+        current_step_index: existing.current_step_index,
+        completed_step_indices: existing.completed_step_indices,
+        completed_content_ids: existing.completed_content_ids,
+        step_affinity_json: existing.step_affinity_json,
+        step_notes_json: existing.step_notes_json,
+        reflection_responses_json: existing.reflection_responses_json,
+        attestations_earned,
+        started_at: existing.started_at,
+        last_activity_at: timestamp,
+        completed_at: existing.completed_at,
+    };
+
+    let action_hash = create_entry(&EntryTypes::AgentProgress(updated_progress.clone()))?;
+
+    // Update ID lookup link
+    delete_link(link.create_link_hash.clone(), GetOptions::default())?;
+    create_link(progress_anchor_hash, action_hash.clone(), LinkTypes::AgentToPathProgress, ())?;
+
+    // Issue attestation via imagodei DNA (attestations live in identity layer)
+    let _attestation_result = issue_attestation_via_imagodei(IssueAttestationBridgeInput {
+        agent_id: agent_id.clone(),
+        category: input.source_type.clone(),  // "step", "chapter", "path"
+        attestation_type: input.attestation_id,
+        display_name: format!("Completed: {}", input.reason),
+        description: input.reason,
+        icon_url: None,
+        tier: None,
+        earned_via_json: serde_json::json!({
+            "source_type": input.source_type,
+            "source_id": input.source_id,
+            "path_id": input.path_id
+        }).to_string(),
+        expires_at: None,
+    })?;
+
+    emit_write_signal("AgentProgress", &updated_progress.id, "grant_attestation");
+
+    Ok(AgentProgressOutput { action_hash, progress: updated_progress })
+}
+
+/// Check if learner has required attestation to access a step
+#[hdk_extern]
+pub fn check_attestation_access(input: CheckAttestationAccessInput) -> ExternResult<AttestationAccessResult> {
+    let agent_info = agent_info()?;
+    let agent_id = agent_info.agent_initial_pubkey.to_string();
+
+    // Get current progress to check attestations
+    let progress_id = format!("{}-{}", agent_id, input.path_id);
+    let progress_anchor = StringAnchor::new("progress_id", &progress_id);
+    let progress_anchor_hash = hash_entry(&EntryTypes::StringAnchor(progress_anchor))?;
+
+    let query = LinkQuery::try_new(progress_anchor_hash, LinkTypes::AgentToPathProgress)?;
+    let links = get_links(query, GetStrategy::default())?;
+
+    let attestations_earned: Vec<String> = if let Some(link) = links.first() {
+        let action_hash = ActionHash::try_from(link.target.clone())
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid progress action hash".to_string())))?;
+        let record = get(action_hash, GetOptions::default())?;
+        if let Some(rec) = record {
+            if let Some(progress) = rec.entry().to_app_option::<AgentProgress>().ok().flatten() {
+                progress.attestations_earned
+            } else {
+                Vec::new()
+            }
+        } else {
+            Vec::new()
+        }
+    } else {
+        Vec::new()
+    };
+
+    let has_access = attestations_earned.contains(&input.required_attestation);
+
+    Ok(AttestationAccessResult {
+        has_access,
+        required_attestation: input.required_attestation,
+        attestations_earned,
+    })
+}
+
+/// Input for checking attestation access
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CheckAttestationAccessInput {
+    pub path_id: String,
+    pub required_attestation: String,
+}
+
+/// Result of attestation access check
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AttestationAccessResult {
+    pub has_access: bool,
+    pub required_attestation: String,
+    pub attestations_earned: Vec<String>,
+}
+
+// =============================================================================
 // Content mastery operations moved to: holochain/dna/imagodei/zomes/imagodei/
 
 // =============================================================================
-// Assessment History & Attestation Gating
+// Assessment History & Attestation Gating
+// =============================================================================
+
+/// Assessment history entry (for viewing past attempts)
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AssessmentHistoryEntry {
+    pub assessment_type: String,
+    pub score: f64,
+    pub passed: bool,
+    pub threshold: f64,
+    pub question_count: u32,
+    pub correct_count: u32,
+    pub time_seconds: u32,
+    pub timestamp: String,
+    pub level_achieved: Option<String>,
+}
+
+/// Assessment history output
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AssessmentHistory {
+    pub content_id: String,
+    pub entries: Vec<AssessmentHistoryEntry>,
+    pub total_attempts: u32,
+    pub pass_count: u32,
+    pub best_score: f64,
+    pub current_level: String,
+}
+
+/// Input for checking attestation eligibility
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CheckAttestationEligibilityInput {
+    pub path_id: String,
+    pub attestation_id: String,
+    pub required_content_ids: Vec<String>,
+    pub required_mastery_level: String,
+}
+
+/// Attestation eligibility result
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AttestationEligibilityResult {
+    pub eligible: bool,
+    pub attestation_id: String,
+    pub required_level: String,
+    pub required_level_index: u32,
+    pub content_requirements: Vec<ContentMasteryRequirement>,
+    pub all_requirements_met: bool,
+    pub missing_requirements: Vec<String>,
+}
+
+/// Individual content mastery requirement check
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ContentMasteryRequirement {
+    pub content_id: String,
+    pub required_level: String,
+    pub current_level: String,
+    pub met: bool,
+    pub gap: i32,
+}
+
+/// Input for granting attestation with mastery check
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GrantAttestationWithMasteryInput {
+    pub path_id: String,
+    pub attestation_id: String,
+    pub reason: String,
+    pub source_type: String,
+    pub source_id: String,
+    pub required_content_ids: Vec<String>,
+    pub required_mastery_level: String,
+}
+
+/// Result of step access check
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StepAccessResult {
+    pub step_id: String,
+    pub access_granted: bool,
+    pub blockers: Vec<String>,
+    pub attestation_required: Option<String>,
+    pub mastery_threshold: Option<u32>,
+}
+
+/// Get assessment history for a content node
+#[hdk_extern]
+pub fn get_assessment_history(content_id: String) -> ExternResult<AssessmentHistory> {
+    let mastery = get_my_mastery(content_id.clone())?;
+
+    match mastery {
+        Some(m) => {
+            // Parse assessment evidence JSON
+            let evidence_array: Vec<serde_json::Value> = serde_json::from_str(&m.mastery.assessment_evidence_json)
+                .unwrap_or_default();
+
+            let mut entries = Vec::new();
+            let mut pass_count = 0u32;
+            let mut best_score = 0.0f64;
+
+            for evidence in &evidence_array {
+                let assessment_type = evidence.get("type")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("unknown")
+                    .to_string();
+                let score = evidence.get("score")
+                    .and_then(|v| v.as_f64())
+                    .unwrap_or(0.0);
+                let passed = evidence.get("passed")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                let threshold = evidence.get("threshold")
+                    .and_then(|v| v.as_f64())
+                    .unwrap_or(0.7);
+                let question_count = evidence.get("questions")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(0) as u32;
+                let correct_count = evidence.get("correct")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(0) as u32;
+                let time_seconds = evidence.get("time_seconds")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(0) as u32;
+                let timestamp = evidence.get("timestamp")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string();
+
+                if passed {
+                    pass_count += 1;
+                }
+                if score > best_score {
+                    best_score = score;
+                }
+
+                entries.push(AssessmentHistoryEntry {
+                    assessment_type,
+                    score,
+                    passed,
+                    threshold,
+                    question_count,
+                    correct_count,
+                    time_seconds,
+                    timestamp,
+                    level_achieved: if passed { Some(m.mastery.mastery_level.clone()) } else { None },
+                });
+            }
+
+            Ok(AssessmentHistory {
+                content_id,
+                entries,
+                total_attempts: evidence_array.len() as u32,
+                pass_count,
+                best_score,
+                current_level: m.mastery.mastery_level,
+            })
+        }
+        None => Ok(AssessmentHistory {
+            content_id,
+            entries: Vec::new(),
+            total_attempts: 0,
+            pass_count: 0,
+            best_score: 0.0,
+            current_level: "not_started".to_string(),
+        })
+    }
+}
+
+/// Check if current agent is eligible for an attestation based on mastery requirements
+#[hdk_extern]
+pub fn check_attestation_eligibility(input: CheckAttestationEligibilityInput) -> ExternResult<AttestationEligibilityResult> {
+    let required_level_index = get_mastery_level_index(&input.required_mastery_level);
+
+    let mut content_requirements = Vec::new();
+    let mut missing_requirements = Vec::new();
+    let mut all_met = true;
+
+    let masteries = get_my_mastery_batch(input.required_content_ids.clone())?;
+
+    for content_id in &input.required_content_ids {
+        let mastery = masteries.get(content_id).cloned().flatten();
+
+        let (current_level, current_index) = match mastery {
+            Some(m) => (m.mastery.mastery_level, m.mastery.mastery_level_index),
+            None => ("not_started".to_string(), 0),
+        };
+
+        let met = current_index >= required_level_index;
+        let gap = required_level_index as i32 - current_index as i32;
+
+        if !met {
+            all_met = false;
+            missing_requirements.push(format!(
+                "{}: need {} (have {})",
+                content_id, input.required_mastery_level, current_level
+            ));
+        }
+
+        content_requirements.push(ContentMasteryRequirement {
+            content_id: content_id.clone(),
+            required_level: input.required_mastery_level.clone(),
+            current_level,
+            met,
+            gap,
+        });
+    }
+
+    Ok(AttestationEligibilityResult {
+        eligible: all_met,
+        attestation_id: input.attestation_id,
+        required_level: input.required_mastery_level.clone(),
+        required_level_index,
+        content_requirements,
+        all_requirements_met: all_met,
+        missing_requirements,
+    })
+}
+
+/// Grant attestation only if mastery requirements are met
+#[hdk_extern]
+pub fn grant_attestation_with_mastery_check(input: GrantAttestationWithMasteryInput) -> ExternResult<AgentProgressOutput> {
+    // First check eligibility
+    let eligibility = check_attestation_eligibility(CheckAttestationEligibilityInput {
+        path_id: input.path_id.clone(),
+        attestation_id: input.attestation_id.clone(),
+        required_content_ids: input.required_content_ids.clone(),
+        required_mastery_level: input.required_mastery_level.clone(),
+    })?;
+
+    if !eligibility.eligible {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            format!(
+                "Mastery requirements not met for attestation '{}'. Missing: {:?}",
+                input.attestation_id,
+                eligibility.missing_requirements
+            )
+        )));
+    }
+
+    // If eligible, grant the attestation using existing function
+    grant_attestation(GrantAttestationInput {
+        path_id: input.path_id,
+        attestation_id: input.attestation_id,
+        reason: input.reason,
+        source_type: input.source_type,
+        source_id: input.source_id,
+    })
+}
+
+/// Check step access based on required attestation and mastery
+#[hdk_extern]
+pub fn check_step_access(step_id: String) -> ExternResult<StepAccessResult> {
+    // Get the step
+    let step_output = get_step_by_id(step_id.clone())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(format!("Step not found: {}", step_id))))?;
+
+    let step = step_output.step;
+    let mut access_granted = true;
+    let mut blockers = Vec::new();
+
+    // Check attestation requirement
+    if let Some(ref required_attestation) = step.attestation_required {
+        let attestation_check = check_attestation_access(CheckAttestationAccessInput {
+            path_id: step.path_id.clone(),
+            required_attestation: required_attestation.clone(),
+        })?;
+
+        if !attestation_check.has_access {
+            access_granted = false;
+            blockers.push(format!("Missing attestation: {}", required_attestation));
+        }
+    }
+
+    // Check mastery threshold
+    if let Some(mastery_threshold) = step.mastery_threshold {
+        // Get mastery for the content this step references
+        let mastery = get_my_mastery(step.resource_id.clone())?;
+
+        let current_level_index = match mastery {
+            Some(m) => m.mastery.mastery_level_index,
+            None => 0,
+        };
+
+        if current_level_index < mastery_threshold {
+            access_granted = false;
+            let required_level = MASTERY_LEVELS.get(mastery_threshold as usize)
+                .unwrap_or(&"apply")
+                .to_string();
+            let current_level = MASTERY_LEVELS.get(current_level_index as usize)
+                .unwrap_or(&"not_started")
+                .to_string();
+            blockers.push(format!(
+                "Need mastery level '{}' (currently '{}')",
+                required_level, current_level
+            ));
+        }
+    }
+
+    Ok(StepAccessResult {
+        step_id,
+        access_granted,
+        blockers,
+        attestation_required: step.attestation_required,
+        mastery_threshold: step.mastery_threshold,
+    })
+}
+
+/// Batch check multiple steps for access (efficient for path overview)
+#[hdk_extern]
+pub fn check_path_step_access(path_id: String) -> ExternResult<Vec<StepAccessResult>> {
+    let path_with_steps = get_path_with_steps(path_id.into())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Path not found".to_string())))?;
+
+    // Fetch mastery for every gated step up front; check_step_access then
+    // reads it from the per-call memo instead of bridging once per step
+    let gated_content: Vec<String> = path_with_steps.steps.iter()
+        .filter(|s| s.step.mastery_threshold.is_some())
+        .map(|s| s.step.resource_id.clone())
+        .collect();
+    if !gated_content.is_empty() {
+        get_my_mastery_batch(gated_content)?;
+    }
+
+    let mut results = Vec::new();
+    for step_output in path_with_steps.steps {
+        let step_id = step_output.step.id.clone();
+        match check_step_access(step_id) {
+            Ok(result) => results.push(result),
+            Err(_) => continue,  // Skip steps that error
+        }
+    }
+
+    Ok(results)
+}
+
+// =============================================================================
+// Practice Pool & Mastery Challenge Types
 // =============================================================================
 
-/// Assessment history entry (for viewing past attempts)
+/// Discovery candidate from knowledge graph
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DiscoveryCandidate {
+    pub content_id: String,
+    pub source_content_id: String,
+    pub relationship_type: String,
+    pub discovery_reason: String,
+}
+
+/// Content mix entry for a challenge
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ContentMixEntry {
+    pub content_id: String,
+    pub source: String,  // "path_active", "refresh_queue", "graph_neighbor", "serendipity"
+    pub question_count: u32,
+}
+
+/// Question in a challenge
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChallengeQuestion {
+    pub content_id: String,
+    pub question_type: String,
+    pub question_text: String,
+    pub options_json: String,
+    pub correct_answer: String,
+}
+
+/// Response to a challenge question
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChallengeResponse {
+    pub content_id: String,
+    pub question_index: u32,
+    pub response: String,
+    pub correct: bool,
+    pub time_taken_ms: u32,
+}
+
+/// Level change from a challenge
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LevelChange {
+    pub content_id: String,
+    pub from_level: String,
+    pub to_level: String,
+    pub from_index: u32,
+    pub to_index: u32,
+    pub change: String,  // "up", "down", "same"
+}
+
+/// Discovery made from challenge
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChallengeDiscovery {
+    pub content_id: String,
+    pub discovered_via: String,
+    pub relationship_type: String,
+}
+
+/// Output for practice pool
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PracticePoolOutput {
+    pub action_hash: ActionHash,
+    pub pool: PracticePool,
+}
+
+/// Output for mastery challenge
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MasteryChallengeOutput {
+    pub action_hash: ActionHash,
+    pub challenge: MasteryChallenge,
+}
+
+/// Input for creating/updating practice pool
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreatePoolInput {
+    pub contributing_path_ids: Vec<String>,
+    pub max_active_size: Option<u32>,
+    pub refresh_threshold: Option<f64>,
+    pub discovery_probability: Option<f64>,
+    pub regression_enabled: Option<bool>,
+    pub challenge_cooldown_hours: Option<u32>,
+}
+
+/// Input for starting a mastery challenge
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StartChallengeInput {
+    pub path_id: Option<String>,
+    pub question_count: u32,
+    pub include_discoveries: bool,
+    pub time_limit_seconds: Option<u32>,
+}
+
+/// Input for submitting challenge responses
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SubmitChallengeInput {
+    pub challenge_id: String,
+    pub responses: Vec<ChallengeResponse>,
+    pub actual_time_seconds: u32,
+}
+
+/// Challenge result after submission
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ChallengeResult {
+    pub challenge: MasteryChallengeOutput,
+    pub score: f64,
+    pub level_changes: Vec<LevelChange>,
+    pub discoveries: Vec<ChallengeDiscovery>,
+    pub net_level_change: i32,
+    pub can_retake_at: String,
+}
+
+/// Input for starting a checkpoint review
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StartCheckpointInput {
+    pub path_id: String,
+    /// order_index of the checkpoint step
+    pub step_index: u32,
+}
+
+/// Checkpoint result after submission
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CheckpointResult {
+    pub challenge: MasteryChallengeOutput,
+    pub step_id: String,
+    pub score: f64,
+    pub passing_score: f64,
+    pub passed: bool,
+}
+
+/// Cooldown check result
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CooldownCheckResult {
+    pub can_take_challenge: bool,
+    pub cooldown_remaining_hours: u32,
+    pub last_challenge_at: Option<String>,
+    pub next_available_at: Option<String>,
+}
+
+/// Input for adding a question to the question bank
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateAssessmentItemInput {
+    pub content_id: String,
+    pub question_type: String,
+    pub question_text: String,
+    #[serde(default)]
+    pub options: Vec<String>,
+    pub correct_answer: String,
+    #[serde(default)]
+    pub explanation: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Unpublished items go on the steward review queue
+    #[serde(default)]
+    pub published: bool,
+    /// Model that generated the question; generated items always start unpublished
+    #[serde(default)]
+    pub generated_by: Option<String>,
+}
+
+/// Output for an assessment item
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AssessmentItemOutput {
+    pub action_hash: ActionHash,
+    pub item: AssessmentItem,
+}
+
+/// Input for listing a content node's questions
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetAssessmentItemsInput {
+    pub content_id: String,
+    #[serde(default)]
+    pub include_unpublished: bool,
+}
+
+/// Input for a steward publishing or rejecting a question
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReviewAssessmentItemInput {
+    pub item_id: String,
+    #[serde(default)]
+    pub reviewer_id: Option<String>,
+}
+
+/// Input for asking peers to evaluate work on a content node
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RequestPeerReviewInput {
+    /// Author of the work; the calling agent when absent
+    pub human_id: Option<String>,
+    pub content_id: String,
+    pub title: String,
+    pub artifact: String,
+    /// Defaults to accuracy / reasoning / clarity, each out of 4
+    pub rubric: Option<Vec<RubricCriterion>>,
+    pub reviewers_required: Option<u32>,
+    /// Reviewers the author nominates; each must qualify
+    #[serde(default)]
+    pub reviewer_ids: Vec<String>,
+}
+
+/// Output for a peer review request
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PeerReviewRequestOutput {
+    pub action_hash: ActionHash,
+    pub request: PeerReviewRequest,
+}
+
+/// Input for assigning a reviewer to a request
 #[derive(Serialize, Deserialize, Debug)]
-pub struct AssessmentHistoryEntry {
-    pub assessment_type: String,
-    pub score: f64,
-    pub passed: bool,
-    pub threshold: f64,
-    pub question_count: u32,
-    pub correct_count: u32,
-    pub time_seconds: u32,
-    pub timestamp: String,
-    pub level_achieved: Option<String>,
+pub struct AssignPeerReviewerInput {
+    pub request_id: String,
+    /// Reviewer to assign; the calling agent (volunteering) when absent
+    pub reviewer_id: Option<String>,
 }
 
-/// Assessment history output
+/// Input for submitting a rubric-structured review
 #[derive(Serialize, Deserialize, Debug)]
-pub struct AssessmentHistory {
-    pub content_id: String,
-    pub entries: Vec<AssessmentHistoryEntry>,
-    pub total_attempts: u32,
-    pub pass_count: u32,
-    pub best_score: f64,
-    pub current_level: String,
+pub struct SubmitPeerReviewInput {
+    pub request_id: String,
+    /// Reviewer submitting; the calling agent when absent
+    pub reviewer_id: Option<String>,
+    pub scores: Vec<RubricScore>,
+    pub recommendation: String,
+    #[serde(default)]
+    pub comments: String,
 }
 
-/// Input for checking attestation eligibility
+/// Output for a peer review
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PeerReviewOutput {
+    pub action_hash: ActionHash,
+    pub review: PeerReview,
+}
+
+/// Result of submitting a peer review
 #[derive(Serialize, Deserialize, Debug)]
-pub struct CheckAttestationEligibilityInput {
-    pub path_id: String,
-    pub attestation_id: String,
-    pub required_content_ids: Vec<String>,
-    pub required_mastery_level: String,
+pub struct PeerReviewResult {
+    pub review: PeerReviewOutput,
+    pub request: PeerReviewRequestOutput,
+    /// Mastery level the review recorded for the reviewer
+    pub reviewer_level: String,
 }
 
-/// Attestation eligibility result
+/// Input for submitting derived content or an edit for steward review
 #[derive(Serialize, Deserialize, Debug)]
-pub struct AttestationEligibilityResult {
-    pub eligible: bool,
-    pub attestation_id: String,
-    pub required_level: String,
-    pub required_level_index: u32,
-    pub content_requirements: Vec<ContentMasteryRequirement>,
-    pub all_requirements_met: bool,
-    pub missing_requirements: Vec<String>,
+pub struct SubmitContributionInput {
+    /// Contributor; the calling agent when absent
+    pub human_id: Option<String>,
+    pub original_content_id: String,
+    /// "derived" or "edit"
+    pub kind: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    pub content: String,
+    /// Defaults to the original's format
+    pub content_format: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub rationale: String,
 }
 
-/// Individual content mastery requirement check
+/// Output for a content contribution
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ContributionOutput {
+    pub action_hash: ActionHash,
+    pub contribution: ContentContribution,
+}
+
+/// Input for a steward accepting or rejecting a contribution
 #[derive(Serialize, Deserialize, Debug)]
-pub struct ContentMasteryRequirement {
-    pub content_id: String,
-    pub required_level: String,
-    pub current_level: String,
-    pub met: bool,
-    pub gap: i32,
+pub struct ReviewContributionInput {
+    pub contribution_id: String,
+    /// Reviewing steward; the calling agent when absent
+    pub reviewer_id: Option<String>,
+    pub note: Option<String>,
 }
 
-/// Input for granting attestation with mastery check
+/// Result of accepting a contribution
 #[derive(Serialize, Deserialize, Debug)]
-pub struct GrantAttestationWithMasteryInput {
-    pub path_id: String,
-    pub attestation_id: String,
-    pub reason: String,
-    pub source_type: String,
-    pub source_id: String,
-    pub required_content_ids: Vec<String>,
-    pub required_mastery_level: String,
+pub struct AcceptedContribution {
+    pub contribution: ContributionOutput,
+    /// The published content node
+    pub content: ContentOutput,
+    /// DERIVED_FROM relationship to the original
+    pub relationship: RelationshipOutput,
+    pub points_awarded: i32,
+    pub recognition: ContributorRecognitionOutput,
 }
 
-/// Result of step access check
+/// Pool recommendations for what to practice
 #[derive(Serialize, Deserialize, Debug)]
-pub struct StepAccessResult {
-    pub step_id: String,
-    pub access_granted: bool,
-    pub blockers: Vec<String>,
-    pub attestation_required: Option<String>,
-    pub mastery_threshold: Option<u32>,
+pub struct PoolRecommendations {
+    pub priority_refresh: Vec<String>,     // Mastered but need refresh
+    pub active_practice: Vec<String>,      // In active rotation
+    pub discovery_suggestions: Vec<DiscoveryCandidate>,  // Serendipity options
+    pub total_pool_size: u32,
 }
 
-/// Get assessment history for a content node
+// =============================================================================
+// Practice Pool Operations
+// =============================================================================
+
+/// Get or create practice pool for current agent
 #[hdk_extern]
-pub fn get_assessment_history(content_id: String) -> ExternResult<AssessmentHistory> {
-    let mastery = get_my_mastery(content_id.clone())?;
+pub fn get_or_create_practice_pool(input: CreatePoolInput) -> ExternResult<PracticePoolOutput> {
+    let agent_info = agent_info()?;
+    let agent_id = agent_info.agent_initial_pubkey.to_string();
+    let now = sys_time()?;
+    let timestamp = format!("{:?}", now);
 
-    match mastery {
-        Some(m) => {
-            // Parse assessment evidence JSON
-            let evidence_array: Vec<serde_json::Value> = serde_json::from_str(&m.mastery.assessment_evidence_json)
-                .unwrap_or_default();
+    // Check if pool already exists
+    let pool_anchor = StringAnchor::new("agent_pool", &agent_id);
+    let pool_anchor_hash = hash_entry(&EntryTypes::StringAnchor(pool_anchor.clone()))?;
 
-            let mut entries = Vec::new();
-            let mut pass_count = 0u32;
-            let mut best_score = 0.0f64;
+    let query = LinkQuery::try_new(pool_anchor_hash.clone(), LinkTypes::AgentToPool)?;
+    let links = get_links(query, GetStrategy::default())?;
 
-            for evidence in &evidence_array {
-                let assessment_type = evidence.get("type")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("unknown")
-                    .to_string();
-                let score = evidence.get("score")
-                    .and_then(|v| v.as_f64())
-                    .unwrap_or(0.0);
-                let passed = evidence.get("passed")
-                    .and_then(|v| v.as_bool())
-                    .unwrap_or(false);
-                let threshold = evidence.get("threshold")
-                    .and_then(|v| v.as_f64())
-                    .unwrap_or(0.7);
-                let question_count = evidence.get("questions")
-                    .and_then(|v| v.as_u64())
-                    .unwrap_or(0) as u32;
-                let correct_count = evidence.get("correct")
-                    .and_then(|v| v.as_u64())
-                    .unwrap_or(0) as u32;
-                let time_seconds = evidence.get("time_seconds")
-                    .and_then(|v| v.as_u64())
-                    .unwrap_or(0) as u32;
-                let timestamp = evidence.get("timestamp")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_string();
+    if let Some(link) = links.first() {
+        // Return existing pool
+        let action_hash = ActionHash::try_from(link.target.clone())
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid pool hash".to_string())))?;
+
+        let record = get(action_hash.clone(), GetOptions::default())?
+            .ok_or(wasm_error!(WasmErrorInner::Guest("Pool record not found".to_string())))?;
+
+        let pool: PracticePool = record
+            .entry()
+            .to_app_option()
+            .map_err(|e| wasm_error!(e))?
+            .ok_or(wasm_error!(WasmErrorInner::Guest("Could not deserialize pool".to_string())))?;
+
+        return Ok(PracticePoolOutput { action_hash, pool });
+    }
+
+    // Create new pool
+    let pool_id = format!("pool-{}-{}", agent_id, timestamp);
+
+    let pool = PracticePool {
+        id: pool_id.clone(),
+        agent_id: agent_id.clone(),
+        active_content_ids_json: "[]".to_string(),
+        refresh_queue_ids_json: "[]".to_string(),
+        discovery_candidates_json: "[]".to_string(),
+        contributing_path_ids_json: serde_json::to_string(&input.contributing_path_ids)
+            .unwrap_or_else(|_| "[]".to_string()),
+        max_active_size: input.max_active_size.unwrap_or(20),
+        refresh_threshold: input.refresh_threshold.unwrap_or(0.5),
+        discovery_probability: input.discovery_probability.unwrap_or(0.15),
+        regression_enabled: input.regression_enabled.unwrap_or(true),
+        challenge_cooldown_hours: input.challenge_cooldown_hours.unwrap_or(24),
+        last_challenge_at: None,
+        last_challenge_id: None,
+        total_challenges_taken: 0,
+        total_level_ups: 0,
+        total_level_downs: 0,
+        discoveries_unlocked: 0,
+        created_at: timestamp.clone(),
+        updated_at: timestamp,
+    };
+
+    let action_hash = create_entry(&EntryTypes::PracticePool(pool.clone()))?;
+
+    // Create anchor and link
+    create_entry(&EntryTypes::StringAnchor(pool_anchor))?;
+    create_link(pool_anchor_hash, action_hash.clone(), LinkTypes::AgentToPool, ())?;
+
+    Ok(PracticePoolOutput { action_hash, pool })
+}
+
+/// Refresh practice pool with content from paths and knowledge graph
+#[hdk_extern]
+pub fn refresh_practice_pool(_: ()) -> ExternResult<PracticePoolOutput> {
+    let agent_info = agent_info()?;
+    let agent_id = agent_info.agent_initial_pubkey.to_string();
+    let now = sys_time()?;
+    let timestamp = format!("{:?}", now);
+
+    // Get existing pool
+    let pool_output = get_or_create_practice_pool(CreatePoolInput {
+        contributing_path_ids: vec![],
+        max_active_size: None,
+        refresh_threshold: None,
+        discovery_probability: None,
+        regression_enabled: None,
+        challenge_cooldown_hours: None,
+    })?;
+
+    let existing_pool = pool_output.pool;
+
+    // Gather active content from contributing paths
+    let contributing_paths: Vec<String> = serde_json::from_str(&existing_pool.contributing_path_ids_json)
+        .unwrap_or_default();
+
+    let mut active_content: Vec<String> = Vec::new();
+    let mut _refresh_queue: Vec<String> = Vec::new();
+
+    for path_id in &contributing_paths {
+        if let Some(path_with_steps) = get_path_with_steps(path_id.clone().into())? {
+            let masteries = get_my_mastery_batch(
+                path_with_steps.steps.iter().map(|s| s.step.resource_id.clone()).collect(),
+            )?;
+
+            for step_output in path_with_steps.steps {
+                let content_id = step_output.step.resource_id.clone();
+
+                // Check mastery for this content
+                if let Some(mastery_output) = masteries.get(&content_id).cloned().flatten() {
+                    let mastery = mastery_output.mastery;
+
+                    // If not yet mastered (below apply level), add to active
+                    if mastery.mastery_level_index < 4 {
+                        if !active_content.contains(&content_id) && active_content.len() < existing_pool.max_active_size as usize {
+                            active_content.push(content_id);
+                        }
+                    }
+                    // If mastered but freshness dropped, add to refresh queue
+                    else if mastery.freshness_score < existing_pool.refresh_threshold {
+                        if !_refresh_queue.contains(&content_id) {
+                            _refresh_queue.push(content_id);
+                        }
+                    }
+                } else {
+                    // No mastery record - add to active
+                    if !active_content.contains(&content_id) && active_content.len() < existing_pool.max_active_size as usize {
+                        active_content.push(content_id);
+                    }
+                }
+            }
+        }
+    }
+
+    // Discover related content from knowledge graph
+    let mut discovery_candidates: Vec<DiscoveryCandidate> = Vec::new();
+
+    for content_id in &active_content {
+        // Get relationships for this content
+        let relationships = get_relationships(GetRelationshipsInput {
+            content_id: content_id.clone(),
+            direction: "both".to_string(),
+        })?;
 
-                if passed {
-                    pass_count += 1;
-                }
-                if score > best_score {
-                    best_score = score;
-                }
+        for rel_output in relationships {
+            let rel = rel_output.relationship;
 
-                entries.push(AssessmentHistoryEntry {
-                    assessment_type,
-                    score,
-                    passed,
-                    threshold,
-                    question_count,
-                    correct_count,
-                    time_seconds,
-                    timestamp,
-                    level_achieved: if passed { Some(m.mastery.mastery_level.clone()) } else { None },
-                });
-            }
+            // Find the related content (the one that's not this content_id)
+            let related_id = if rel.source_id == *content_id {
+                rel.target_id.clone()
+            } else {
+                rel.source_id.clone()
+            };
 
-            Ok(AssessmentHistory {
-                content_id,
-                entries,
-                total_attempts: evidence_array.len() as u32,
-                pass_count,
-                best_score,
-                current_level: m.mastery.mastery_level,
-            })
+            // Check if this related content is not already in our pool
+            if !active_content.contains(&related_id) && !_refresh_queue.contains(&related_id) {
+                // Check we haven't already added this as a discovery candidate
+                if !discovery_candidates.iter().any(|d| d.content_id == related_id) {
+                    discovery_candidates.push(DiscoveryCandidate {
+                        content_id: related_id,
+                        source_content_id: content_id.clone(),
+                        relationship_type: rel.relationship_type.clone(),
+                        discovery_reason: format!("Related via {} to content you're learning", rel.relationship_type),
+                    });
+                }
+            }
         }
-        None => Ok(AssessmentHistory {
-            content_id,
-            entries: Vec::new(),
-            total_attempts: 0,
-            pass_count: 0,
-            best_score: 0.0,
-            current_level: "not_started".to_string(),
-        })
     }
+
+    // Limit discovery candidates
+    discovery_candidates.truncate(10);
+
+    // Update the pool
+    let updated_pool = PracticePool {
+        id: existing_pool.id,
+        agent_id: existing_pool.agent_id,
+        active_content_ids_json: serde_json::to_string(&active_content).unwrap_or_else(|_| "[]".to_string()),
+        refresh_queue_ids_json: serde_json::to_string(&_refresh_queue).unwrap_or_else(|_| "[]".to_string()),
+        discovery_candidates_json: serde_json::to_string(&discovery_candidates).unwrap_or_else(|_| "[]".to_string()),
+        contributing_path_ids_json: existing_pool.contributing_path_ids_json,
+        max_active_size: existing_pool.max_active_size,
+        refresh_threshold: existing_pool.refresh_threshold,
+        discovery_probability: existing_pool.discovery_probability,
+        regression_enabled: existing_pool.regression_enabled,
+        challenge_cooldown_hours: existing_pool.challenge_cooldown_hours,
+        last_challenge_at: existing_pool.last_challenge_at,
+        last_challenge_id: existing_pool.last_challenge_id,
+        total_challenges_taken: existing_pool.total_challenges_taken,
+        total_level_ups: existing_pool.total_level_ups,
+        total_level_downs: existing_pool.total_level_downs,
+        discoveries_unlocked: existing_pool.discoveries_unlocked,
+        created_at: existing_pool.created_at,
+        updated_at: timestamp,
+    };
+
+    let action_hash = create_entry(&EntryTypes::PracticePool(updated_pool.clone()))?;
+
+    // Update link
+    let pool_anchor = StringAnchor::new("agent_pool", &agent_id);
+    let pool_anchor_hash = hash_entry(&EntryTypes::StringAnchor(pool_anchor))?;
+
+    let query = LinkQuery::try_new(pool_anchor_hash.clone(), LinkTypes::AgentToPool)?;
+    let links = get_links(query, GetStrategy::default())?;
+    if let Some(old_link) = links.first() {
+        delete_link(old_link.create_link_hash.clone(), GetOptions::default())?;
+    }
+    create_link(pool_anchor_hash, action_hash.clone(), LinkTypes::AgentToPool, ())?;
+
+    Ok(PracticePoolOutput { action_hash, pool: updated_pool })
 }
 
-/// Check if current agent is eligible for an attestation based on mastery requirements
+/// Add a path to the practice pool
 #[hdk_extern]
-pub fn check_attestation_eligibility(input: CheckAttestationEligibilityInput) -> ExternResult<AttestationEligibilityResult> {
-    let required_level_index = get_mastery_level_index(&input.required_mastery_level);
+pub fn add_path_to_pool(path_id: String) -> ExternResult<PracticePoolOutput> {
+    let pool_output = get_or_create_practice_pool(CreatePoolInput {
+        contributing_path_ids: vec![path_id.clone()],
+        max_active_size: None,
+        refresh_threshold: None,
+        discovery_probability: None,
+        regression_enabled: None,
+        challenge_cooldown_hours: None,
+    })?;
 
-    let mut content_requirements = Vec::new();
-    let mut missing_requirements = Vec::new();
-    let mut all_met = true;
+    let mut contributing: Vec<String> = serde_json::from_str(&pool_output.pool.contributing_path_ids_json)
+        .unwrap_or_default();
 
-    for content_id in &input.required_content_ids {
-        let mastery = get_my_mastery(content_id.clone())?;
+    if !contributing.contains(&path_id) {
+        contributing.push(path_id);
+    }
 
-        let (current_level, current_index) = match mastery {
-            Some(m) => (m.mastery.mastery_level, m.mastery.mastery_level_index),
-            None => ("not_started".to_string(), 0),
-        };
+    // Update and refresh
+    let agent_info = agent_info()?;
+    let agent_id = agent_info.agent_initial_pubkey.to_string();
+    let now = sys_time()?;
+    let timestamp = format!("{:?}", now);
 
-        let met = current_index >= required_level_index;
-        let gap = required_level_index as i32 - current_index as i32;
+    let updated_pool = PracticePool {
+        contributing_path_ids_json: serde_json::to_string(&contributing).unwrap_or_else(|_| "[]".to_string()),
+        updated_at: timestamp,
+        ..pool_output.pool
+    };
 
-        if !met {
-            all_met = false;
-            missing_requirements.push(format!(
-                "{}: need {} (have {})",
-                content_id, input.required_mastery_level, current_level
-            ));
-        }
+    let action_hash = create_entry(&EntryTypes::PracticePool(updated_pool.clone()))?;
 
-        content_requirements.push(ContentMasteryRequirement {
-            content_id: content_id.clone(),
-            required_level: input.required_mastery_level.clone(),
-            current_level,
-            met,
-            gap,
-        });
+    // Update link
+    let pool_anchor = StringAnchor::new("agent_pool", &agent_id);
+    let pool_anchor_hash = hash_entry(&EntryTypes::StringAnchor(pool_anchor))?;
+
+    let query = LinkQuery::try_new(pool_anchor_hash.clone(), LinkTypes::AgentToPool)?;
+    let links = get_links(query, GetStrategy::default())?;
+    if let Some(old_link) = links.first() {
+        delete_link(old_link.create_link_hash.clone(), GetOptions::default())?;
     }
+    create_link(pool_anchor_hash, action_hash.clone(), LinkTypes::AgentToPool, ())?;
 
-    Ok(AttestationEligibilityResult {
-        eligible: all_met,
-        attestation_id: input.attestation_id,
-        required_level: input.required_mastery_level.clone(),
-        required_level_index,
-        content_requirements,
-        all_requirements_met: all_met,
-        missing_requirements,
-    })
+    // Refresh to populate with content
+    refresh_practice_pool(())
 }
 
-/// Grant attestation only if mastery requirements are met
+/// Get pool recommendations for what to practice
 #[hdk_extern]
-pub fn grant_attestation_with_mastery_check(input: GrantAttestationWithMasteryInput) -> ExternResult<AgentProgressOutput> {
-    // First check eligibility
-    let eligibility = check_attestation_eligibility(CheckAttestationEligibilityInput {
-        path_id: input.path_id.clone(),
-        attestation_id: input.attestation_id.clone(),
-        required_content_ids: input.required_content_ids.clone(),
-        required_mastery_level: input.required_mastery_level.clone(),
-    })?;
+pub fn get_pool_recommendations(_: ()) -> ExternResult<PoolRecommendations> {
+    let pool_output = refresh_practice_pool(())?;
+    let pool = pool_output.pool;
 
-    if !eligibility.eligible {
-        return Err(wasm_error!(WasmErrorInner::Guest(
-            format!(
-                "Mastery requirements not met for attestation '{}'. Missing: {:?}",
-                input.attestation_id,
-                eligibility.missing_requirements
-            )
-        )));
-    }
+    let active: Vec<String> = serde_json::from_str(&pool.active_content_ids_json).unwrap_or_default();
+    let refresh: Vec<String> = serde_json::from_str(&pool.refresh_queue_ids_json).unwrap_or_default();
+    let discoveries: Vec<DiscoveryCandidate> = serde_json::from_str(&pool.discovery_candidates_json).unwrap_or_default();
 
-    // If eligible, grant the attestation using existing function
-    grant_attestation(GrantAttestationInput {
-        path_id: input.path_id,
-        attestation_id: input.attestation_id,
-        reason: input.reason,
-        source_type: input.source_type,
-        source_id: input.source_id,
+    Ok(PoolRecommendations {
+        priority_refresh: refresh,
+        active_practice: active.clone(),
+        discovery_suggestions: discoveries,
+        total_pool_size: active.len() as u32,
     })
 }
 
-/// Check step access based on required attestation and mastery
-#[hdk_extern]
-pub fn check_step_access(step_id: String) -> ExternResult<StepAccessResult> {
-    // Get the step
-    let step_output = get_step_by_id(step_id.clone())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest(format!("Step not found: {}", step_id))))?;
+/// Input for gathering one learner's recommendation signals
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LearnerSignalsInput {
+    /// Agent whose progress and practice pool are read
+    pub agent_id: String,
+    /// Human whose mastery is read from imagodei
+    pub human_id: String,
+}
 
-    let step = step_output.step;
-    let mut access_granted = true;
-    let mut blockers = Vec::new();
+/// Next unfinished step on a path the learner has started
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NextStepSignal {
+    pub path_id: String,
+    pub step_index: u32,
+    pub content_id: String,
+    /// Steps left after this one
+    pub remaining_steps: u32,
+}
 
-    // Check attestation requirement
-    if let Some(ref required_attestation) = step.attestation_required {
-        let attestation_check = check_attestation_access(CheckAttestationAccessInput {
-            path_id: step.path_id.clone(),
-            required_attestation: required_attestation.clone(),
-        })?;
+/// Mastery state for one content item
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MasterySignal {
+    pub content_id: String,
+    pub mastery_level_index: u32,
+    pub freshness_score: f64,
+    pub needs_refresh: bool,
+}
 
-        if !attestation_check.has_access {
-            access_granted = false;
-            blockers.push(format!("Missing attestation: {}", required_attestation));
-        }
-    }
+/// Raw inputs for ranking "what to learn next"
+///
+/// Doorway's recommendation worker combines these with the relationship
+/// graph and scores them; the zome only reads.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct LearnerSignals {
+    pub next_steps: Vec<NextStepSignal>,
+    pub completed_content_ids: Vec<String>,
+    pub refresh_queue_ids: Vec<String>,
+    pub discovery_candidates: Vec<DiscoveryCandidate>,
+    pub mastery: Vec<MasterySignal>,
+}
 
-    // Check mastery threshold
-    if let Some(mastery_threshold) = step.mastery_threshold {
-        // Get mastery for the content this step references
-        let mastery = get_my_mastery(step.resource_id.clone())?;
+/// Upcoming steps considered per in-progress path
+const NEXT_STEPS_PER_PATH: usize = 3;
 
-        let current_level_index = match mastery {
-            Some(m) => m.mastery.mastery_level_index,
-            None => 0,
+/// Gather a learner's progress, practice pool and mastery for recommendations
+///
+/// Unlike the `get_my_*` functions this reads another agent's records, so a
+/// doorway can rank suggestions for the learners it hosts. Mastery is
+/// best-effort: an unreachable imagodei yields signals without mastery.
+#[hdk_extern]
+pub fn get_learner_signals(input: LearnerSignalsInput) -> ExternResult<LearnerSignals> {
+    let mut signals = LearnerSignals::default();
+
+    // Progress → next steps and completed content
+    let agent_anchor = StringAnchor::new("agent_progress", &input.agent_id);
+    let agent_anchor_hash = hash_entry(&EntryTypes::StringAnchor(agent_anchor))?;
+    let query = LinkQuery::try_new(agent_anchor_hash, LinkTypes::AgentToPathProgress)?;
+
+    for link in get_links(query, GetStrategy::default())? {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
         };
+        let Some(record) = get(action_hash, GetOptions::default())? else {
+            continue;
+        };
+        let Some(progress) = record.entry().to_app_option::<AgentProgress>().ok().flatten() else {
+            continue;
+        };
+
+        for content_id in progress.completed_content_ids.iter() {
+            if !signals.completed_content_ids.contains(content_id) {
+                signals.completed_content_ids.push(content_id.clone());
+            }
+        }
+        if progress.completed_at.is_some() {
+            continue;
+        }
 
-        if current_level_index < mastery_threshold {
-            access_granted = false;
-            let required_level = MASTERY_LEVELS.get(mastery_threshold as usize)
-                .unwrap_or(&"apply")
-                .to_string();
-            let current_level = MASTERY_LEVELS.get(current_level_index as usize)
-                .unwrap_or(&"not_started")
-                .to_string();
-            blockers.push(format!(
-                "Need mastery level '{}' (currently '{}')",
-                required_level, current_level
-            ));
+        let Some(path) = get_path_with_steps(progress.path_id.clone().into())? else {
+            continue;
+        };
+        let mut steps: Vec<&PathStep> = path.steps.iter().map(|s| &s.step).collect();
+        steps.sort_by_key(|step| step.order_index);
+        let total = steps.len() as u32;
+
+        let upcoming = steps
+            .into_iter()
+            .filter(|step| {
+                step.step_type == "content"
+                    && step.order_index >= progress.current_step_index
+                    && !progress.completed_step_indices.contains(&step.order_index)
+            })
+            .take(NEXT_STEPS_PER_PATH);
+        for step in upcoming {
+            signals.next_steps.push(NextStepSignal {
+                path_id: progress.path_id.clone(),
+                step_index: step.order_index,
+                content_id: step.resource_id.clone(),
+                remaining_steps: total.saturating_sub(step.order_index + 1),
+            });
         }
     }
 
-    Ok(StepAccessResult {
-        step_id,
-        access_granted,
-        blockers,
-        attestation_required: step.attestation_required,
-        mastery_threshold: step.mastery_threshold,
-    })
-}
+    // Practice pool → refresh queue and graph discoveries
+    let pool_anchor = StringAnchor::new("agent_pool", &input.agent_id);
+    let pool_anchor_hash = hash_entry(&EntryTypes::StringAnchor(pool_anchor))?;
+    let query = LinkQuery::try_new(pool_anchor_hash, LinkTypes::AgentToPool)?;
+    if let Some(link) = get_links(query, GetStrategy::default())?.first() {
+        if let Some(action_hash) = link.target.clone().into_action_hash() {
+            if let Some(record) = get(action_hash, GetOptions::default())? {
+                if let Some(pool) = record.entry().to_app_option::<PracticePool>().ok().flatten() {
+                    signals.refresh_queue_ids =
+                        serde_json::from_str(&pool.refresh_queue_ids_json).unwrap_or_default();
+                    signals.discovery_candidates =
+                        serde_json::from_str(&pool.discovery_candidates_json).unwrap_or_default();
+                }
+            }
+        }
+    }
 
-/// Batch check multiple steps for access (efficient for path overview)
-#[hdk_extern]
-pub fn check_path_step_access(path_id: String) -> ExternResult<Vec<StepAccessResult>> {
-    let path_with_steps = get_path_with_steps(path_id)?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Path not found".to_string())))?;
+    // Mastery for every content item mentioned above
+    let mut content_ids: Vec<String> = Vec::new();
+    let mentioned = signals
+        .next_steps
+        .iter()
+        .map(|s| &s.content_id)
+        .chain(signals.completed_content_ids.iter())
+        .chain(signals.refresh_queue_ids.iter())
+        .chain(signals.discovery_candidates.iter().map(|d| &d.content_id));
+    for content_id in mentioned {
+        if !content_ids.contains(content_id) {
+            content_ids.push(content_id.clone());
+        }
+    }
 
-    let mut results = Vec::new();
-    for step_output in path_with_steps.steps {
-        let step_id = step_output.step.id.clone();
-        match check_step_access(step_id) {
-            Ok(result) => results.push(result),
-            Err(_) => continue,  // Skip steps that error
+    match get_mastery_batch_for_human(input.human_id, content_ids) {
+        Ok(mastery) => {
+            signals.mastery = mastery
+                .into_iter()
+                .filter_map(|(content_id, m)| {
+                    let m = m?.mastery;
+                    Some(MasterySignal {
+                        content_id,
+                        mastery_level_index: m.mastery_level_index,
+                        freshness_score: m.freshness_score,
+                        needs_refresh: m.needs_refresh,
+                    })
+                })
+                .collect();
         }
+        Err(e) => debug!("Learner signals without mastery: {:?}", e),
     }
 
-    Ok(results)
+    Ok(signals)
 }
 
 // =============================================================================
-// Practice Pool & Mastery Challenge Types
+// Tutor Context & Engagement
 // =============================================================================
 
-/// Discovery candidate from knowledge graph
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct DiscoveryCandidate {
-    pub content_id: String,
-    pub source_content_id: String,
-    pub relationship_type: String,
-    pub discovery_reason: String,
+/// Input for locating a learner for the conversational tutor
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TutorContextInput {
+    /// Agent whose path progress is read
+    pub agent_id: String,
+    /// Human whose mastery is read from imagodei
+    pub human_id: String,
+    /// Path the learner is working through; their current step is used
+    #[serde(default)]
+    pub path_id: Option<String>,
+    /// Content under discussion; overrides the current step's content
+    #[serde(default)]
+    pub content_id: Option<String>,
 }
 
-/// Content mix entry for a challenge
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct ContentMixEntry {
-    pub content_id: String,
-    pub source: String,  // "path_active", "refresh_queue", "graph_neighbor", "serendipity"
-    pub question_count: u32,
+/// Where a learner is and how well they know it
+///
+/// The content body is not included; doorway reads it through the shared
+/// `get_content_by_id` cache.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct TutorContext {
+    pub path_id: Option<String>,
+    pub path_title: Option<String>,
+    pub step_index: Option<u32>,
+    pub step_title: Option<String>,
+    pub step_narrative: Option<String>,
+    pub content_id: Option<String>,
+    pub mastery_level: Option<String>,
+    pub mastery_level_index: Option<u32>,
 }
 
-/// Question in a challenge
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct ChallengeQuestion {
-    pub content_id: String,
-    pub question_type: String,
-    pub question_text: String,
-    pub options_json: String,
-    pub correct_answer: String,
+/// Gather a learner's current path step and mastery for the tutor
+///
+/// Like `get_learner_signals` this reads another agent's records. Mastery
+/// is best-effort and left empty when imagodei is unreachable.
+#[hdk_extern]
+pub fn get_tutor_context(input: TutorContextInput) -> ExternResult<TutorContext> {
+    let mut context = TutorContext {
+        content_id: input.content_id.clone(),
+        ..Default::default()
+    };
+
+    if let Some(path_id) = input.path_id {
+        let progress_id = format!("{}-{}", input.agent_id, path_id);
+        let progress_anchor = StringAnchor::new("progress_id", &progress_id);
+        let progress_anchor_hash = hash_entry(&EntryTypes::StringAnchor(progress_anchor))?;
+        let query = LinkQuery::try_new(progress_anchor_hash, LinkTypes::AgentToPathProgress)?;
+
+        let current_step_index = match get_links(query, GetStrategy::default())?.first() {
+            Some(link) => link
+                .target
+                .clone()
+                .into_action_hash()
+                .and_then(|hash| get(hash, GetOptions::default()).ok().flatten())
+                .and_then(|record| record.entry().to_app_option::<AgentProgress>().ok().flatten())
+                .map(|progress| progress.current_step_index)
+                .unwrap_or(0),
+            // Not started yet: the tutor talks about the first step
+            None => 0,
+        };
+
+        if let Some(path) = get_path_with_steps(path_id.clone().into())? {
+            context.path_title = Some(path.path.title.clone());
+            if let Some(output) = path.steps.iter().find(|s| s.step.order_index == current_step_index) {
+                let step = &output.step;
+                context.step_index = Some(step.order_index);
+                context.step_title = step.step_title.clone();
+                context.step_narrative = step.step_narrative.clone();
+                if context.content_id.is_none() && step.step_type == "content" {
+                    context.content_id = Some(step.resource_id.clone());
+                }
+            }
+        }
+        context.path_id = Some(path_id);
+    }
+
+    if let Some(content_id) = context.content_id.clone() {
+        match get_mastery_batch_for_human(input.human_id, vec![content_id.clone()]) {
+            Ok(mut mastery) => {
+                if let Some(Some(output)) = mastery.remove(&content_id) {
+                    context.mastery_level = Some(output.mastery.mastery_level);
+                    context.mastery_level_index = Some(output.mastery.mastery_level_index);
+                }
+            }
+            Err(e) => debug!("Tutor context without mastery: {:?}", e),
+        }
+    }
+
+    Ok(context)
 }
 
-/// Response to a challenge question
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct ChallengeResponse {
+/// Input for logging a learner's engagement with a content item
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RecordEngagementInput {
+    pub human_id: String,
     pub content_id: String,
-    pub question_index: u32,
-    pub response: String,
-    pub correct: bool,
-    pub time_taken_ms: u32,
+    /// One of ENGAGEMENT_TYPES
+    pub engagement_type: String,
 }
 
-/// Level change from a challenge
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct LevelChange {
-    pub content_id: String,
-    pub from_level: String,
-    pub to_level: String,
-    pub from_index: u32,
-    pub to_index: u32,
-    pub change: String,  // "up", "down", "same"
+/// Record engagement with content without assessing it
+///
+/// Bumps the engagement count in imagodei and keeps the mastery level,
+/// except that content still at "not_started" becomes "seen".
+#[hdk_extern]
+pub fn record_engagement(input: RecordEngagementInput) -> ExternResult<ContentMasteryOutput> {
+    if !ENGAGEMENT_TYPES.contains(&input.engagement_type.as_str()) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Unknown engagement type '{}'. Expected one of {:?}",
+            input.engagement_type, ENGAGEMENT_TYPES
+        ))));
+    }
+
+    let current_index = get_mastery_batch_for_human(input.human_id.clone(), vec![input.content_id.clone()])?
+        .remove(&input.content_id)
+        .flatten()
+        .map(|output| output.mastery.mastery_level_index)
+        .unwrap_or(0);
+    let mastery_level = MASTERY_LEVELS
+        .get(current_index.max(1) as usize)
+        .unwrap_or(&"seen")
+        .to_string();
+
+    upsert_mastery(UpsertMasteryInput {
+        human_id: input.human_id,
+        content_id: input.content_id,
+        mastery_level,
+        engagement_type: input.engagement_type,
+        evidence_json: None,
+    })
 }
 
-/// Discovery made from challenge
+/// Input for aggregating one path's learner analytics
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PathAnalyticsInput {
+    pub path_id: String,
+}
+
+/// How many learners got through one step
 #[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct ChallengeDiscovery {
-    pub content_id: String,
-    pub discovered_via: String,
-    pub relationship_type: String,
+pub struct StepFunnelEntry {
+    pub step_index: u32,
+    pub step_title: Option<String>,
+    pub resource_id: String,
+    pub estimated_minutes: Option<u32>,
+    pub completions: u32,
 }
 
-/// Output for practice pool
-#[derive(Serialize, Deserialize, Debug)]
-pub struct PracticePoolOutput {
-    pub action_hash: ActionHash,
-    pub pool: PracticePool,
+/// Number of buckets in the challenge score histogram (0.0-0.1, ..., 0.9-1.0)
+const SCORE_HISTOGRAM_BUCKETS: usize = 10;
+
+/// Aggregate learner activity on one path
+///
+/// Only counts and averages leave the zome; no agent ids or individual
+/// progress records are included.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct PathAnalytics {
+    pub path_id: String,
+    pub path_title: String,
+    pub learners_started: u32,
+    pub learners_completed: u32,
+    /// Completions per step, in step order
+    pub funnel: Vec<StepFunnelEntry>,
+    /// Mean authored estimate across steps that have one
+    pub avg_estimated_minutes_per_step: Option<f64>,
+    /// Mean elapsed minutes per completed step (first start to last activity)
+    pub avg_actual_minutes_per_step: Option<f64>,
+    /// Completed challenges on this path, bucketed by score
+    pub challenge_score_histogram: Vec<u32>,
+    pub challenges_completed: u32,
+}
+
+/// Latest progress record behind a progress id, with its write time
+fn latest_path_progress(progress_id: &str) -> ExternResult<Option<(AgentProgress, Timestamp)>> {
+    let anchor = StringAnchor::new("progress_id", progress_id);
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
+    let query = LinkQuery::try_new(anchor_hash, LinkTypes::AgentToPathProgress)?;
+
+    let Some(link) = get_links(query, GetStrategy::default())?.into_iter().max_by_key(|l| l.timestamp) else {
+        return Ok(None);
+    };
+    let Some(action_hash) = link.target.into_action_hash() else {
+        return Ok(None);
+    };
+    let Some(record) = get(action_hash, GetOptions::default())? else {
+        return Ok(None);
+    };
+    let written_at = record.action().timestamp();
+    Ok(record
+        .entry()
+        .to_app_option::<AgentProgress>()
+        .ok()
+        .flatten()
+        .map(|progress| (progress, written_at)))
 }
 
-/// Output for mastery challenge
-#[derive(Serialize, Deserialize, Debug)]
-pub struct MasteryChallengeOutput {
-    pub action_hash: ActionHash,
-    pub challenge: MasteryChallenge,
+/// Aggregate completion funnel, step timing and challenge scores for a path
+///
+/// Reads every learner's progress through the `path_progress` anchor and
+/// reduces it to counts inside the zome, so doorway's analytics rollup never
+/// handles per-learner data. Actual time per step is approximate: the span
+/// from starting the path to the last recorded activity, divided by steps
+/// completed.
+#[hdk_extern]
+pub fn get_path_analytics(input: PathAnalyticsInput) -> ExternResult<PathAnalytics> {
+    let path = get_path_with_steps(input.path_id.clone().into())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(format!("Path not found: {}", input.path_id))))?;
+
+    let mut steps: Vec<&PathStep> = path.steps.iter().map(|s| &s.step).collect();
+    steps.sort_by_key(|step| step.order_index);
+    let mut funnel: Vec<StepFunnelEntry> = steps
+        .iter()
+        .map(|step| StepFunnelEntry {
+            step_index: step.order_index,
+            step_title: step.step_title.clone(),
+            resource_id: step.resource_id.clone(),
+            estimated_minutes: step.estimated_minutes,
+            completions: 0,
+        })
+        .collect();
+
+    let estimates: Vec<u32> = steps.iter().filter_map(|step| step.estimated_minutes).collect();
+    let mut analytics = PathAnalytics {
+        path_id: input.path_id.clone(),
+        path_title: path.path.title.clone(),
+        avg_estimated_minutes_per_step: (!estimates.is_empty())
+            .then(|| estimates.iter().sum::<u32>() as f64 / estimates.len() as f64),
+        challenge_score_histogram: vec![0; SCORE_HISTOGRAM_BUCKETS],
+        ..Default::default()
+    };
+
+    let anchor = StringAnchor::new("path_progress", &input.path_id);
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
+    let query = LinkQuery::try_new(anchor_hash, LinkTypes::PathToProgress)?;
+
+    let mut seen_agents: HashSet<String> = HashSet::new();
+    let mut elapsed_minutes = 0.0;
+    let mut timed_steps = 0u32;
+
+    for link in get_links(query, GetStrategy::default())? {
+        let started_at = link.timestamp;
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(action_hash, GetOptions::default())? else {
+            continue;
+        };
+        let Some(initial) = record.entry().to_app_option::<AgentProgress>().ok().flatten() else {
+            continue;
+        };
+        if !seen_agents.insert(initial.agent_id.clone()) {
+            continue;
+        }
+        let Some((progress, last_activity)) = latest_path_progress(&initial.id)? else {
+            continue;
+        };
+
+        analytics.learners_started += 1;
+        if progress.completed_at.is_some() {
+            analytics.learners_completed += 1;
+        }
+        for entry in funnel.iter_mut() {
+            if progress.completed_step_indices.contains(&entry.step_index) {
+                entry.completions += 1;
+            }
+        }
+
+        let completed = progress.completed_step_indices.len() as u32;
+        if completed > 0 {
+            let span_micros = (last_activity.as_micros() - started_at.as_micros()).max(0);
+            elapsed_minutes += span_micros as f64 / 60_000_000.0;
+            timed_steps += completed;
+        }
+
+        // Challenge scores taken against this path
+        let challenge_anchor = StringAnchor::new("agent_challenges", &progress.agent_id);
+        let challenge_anchor_hash = hash_entry(&EntryTypes::StringAnchor(challenge_anchor))?;
+        let query = LinkQuery::try_new(challenge_anchor_hash, LinkTypes::AgentToChallenge)?;
+        for challenge_link in get_links(query, GetStrategy::default())? {
+            let Some(action_hash) = challenge_link.target.into_action_hash() else {
+                continue;
+            };
+            let Some(record) = get(action_hash, GetOptions::default())? else {
+                continue;
+            };
+            let Some(challenge) = record.entry().to_app_option::<MasteryChallenge>().ok().flatten() else {
+                continue;
+            };
+            if challenge.path_id.as_deref() != Some(input.path_id.as_str()) {
+                continue;
+            }
+            let Some(score) = challenge.score else {
+                continue;
+            };
+            let bucket = ((score.clamp(0.0, 1.0) * SCORE_HISTOGRAM_BUCKETS as f64) as usize)
+                .min(SCORE_HISTOGRAM_BUCKETS - 1);
+            analytics.challenge_score_histogram[bucket] += 1;
+            analytics.challenges_completed += 1;
+        }
+    }
+
+    if timed_steps > 0 {
+        analytics.avg_actual_minutes_per_step = Some(elapsed_minutes / timed_steps as f64);
+    }
+    analytics.funnel = funnel;
+
+    Ok(analytics)
 }
 
-/// Input for creating/updating practice pool
-#[derive(Serialize, Deserialize, Debug)]
-pub struct CreatePoolInput {
-    pub contributing_path_ids: Vec<String>,
-    pub max_active_size: Option<u32>,
-    pub refresh_threshold: Option<f64>,
-    pub discovery_probability: Option<f64>,
-    pub regression_enabled: Option<bool>,
-    pub challenge_cooldown_hours: Option<u32>,
+/// Check if agent can take a mastery challenge (cooldown)
+#[hdk_extern]
+pub fn check_challenge_cooldown(_: ()) -> ExternResult<CooldownCheckResult> {
+    let pool_output = get_or_create_practice_pool(CreatePoolInput {
+        contributing_path_ids: vec![],
+        max_active_size: None,
+        refresh_threshold: None,
+        discovery_probability: None,
+        regression_enabled: None,
+        challenge_cooldown_hours: None,
+    })?;
+
+    let pool = pool_output.pool;
+
+    match &pool.last_challenge_at {
+        None => Ok(CooldownCheckResult {
+            can_take_challenge: true,
+            cooldown_remaining_hours: 0,
+            last_challenge_at: None,
+            next_available_at: None,
+        }),
+        Some(last_challenge) => {
+            // Parse timestamp and check if cooldown has passed
+            // For now, simplified: just check if string is set
+            // In production, would parse timestamp and calculate
+            let cooldown_hours = pool.challenge_cooldown_hours;
+
+            // Simplified check - in production would actually parse and compare timestamps
+            Ok(CooldownCheckResult {
+                can_take_challenge: false, // Would calculate properly
+                cooldown_remaining_hours: cooldown_hours,
+                last_challenge_at: Some(last_challenge.clone()),
+                next_available_at: Some(format!("{} + {} hours", last_challenge, cooldown_hours)),
+            })
+        }
+    }
 }
 
-/// Input for starting a mastery challenge
-#[derive(Serialize, Deserialize, Debug)]
-pub struct StartChallengeInput {
-    pub path_id: Option<String>,
-    pub question_count: u32,
-    pub include_discoveries: bool,
-    pub time_limit_seconds: Option<u32>,
+// =============================================================================
+// Question Bank (Assessment Items)
+// =============================================================================
+
+/// Tag marking machine-generated questions
+const AI_GENERATED_TAG: &str = "ai_generated";
+
+/// Anchor of the steward review queue for unpublished questions
+fn assessment_review_anchor() -> ExternResult<EntryHash> {
+    hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("assessment_review", "pending")))
 }
 
-/// Input for submitting challenge responses
-#[derive(Serialize, Deserialize, Debug)]
-pub struct SubmitChallengeInput {
-    pub challenge_id: String,
-    pub responses: Vec<ChallengeResponse>,
-    pub actual_time_seconds: u32,
+fn assessment_content_anchor(content_id: &str) -> ExternResult<EntryHash> {
+    hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("assessment_items", content_id)))
 }
 
-/// Challenge result after submission
-#[derive(Serialize, Deserialize, Debug)]
-pub struct ChallengeResult {
-    pub challenge: MasteryChallengeOutput,
-    pub score: f64,
-    pub level_changes: Vec<LevelChange>,
-    pub discoveries: Vec<ChallengeDiscovery>,
-    pub net_level_change: i32,
-    pub can_retake_at: String,
+fn assessment_id_anchor(item_id: &str) -> ExternResult<EntryHash> {
+    hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("assessment_item_id", item_id)))
 }
 
-/// Cooldown check result
-#[derive(Serialize, Deserialize, Debug)]
-pub struct CooldownCheckResult {
-    pub can_take_challenge: bool,
-    pub cooldown_remaining_hours: u32,
-    pub last_challenge_at: Option<String>,
-    pub next_available_at: Option<String>,
+/// Delete the links from `base` to `target`
+fn delete_links_to(base: impl Into<AnyLinkableHash>, link_type: LinkTypes, target: &ActionHash) -> ExternResult<()> {
+    let target: AnyLinkableHash = target.clone().into();
+    let query = LinkQuery::try_new(base, link_type)?;
+    for link in get_links(query, GetStrategy::default())? {
+        if link.target == target {
+            delete_link(link.create_link_hash, GetOptions::default())?;
+        }
+    }
+    Ok(())
 }
 
-/// Pool recommendations for what to practice
-#[derive(Serialize, Deserialize, Debug)]
-pub struct PoolRecommendations {
-    pub priority_refresh: Vec<String>,     // Mastered but need refresh
-    pub active_practice: Vec<String>,      // In active rotation
-    pub discovery_suggestions: Vec<DiscoveryCandidate>,  // Serendipity options
-    pub total_pool_size: u32,
+fn assessment_items_from(base: EntryHash, link_type: LinkTypes) -> ExternResult<Vec<AssessmentItemOutput>> {
+    let query = LinkQuery::try_new(base, link_type)?;
+    let mut items = Vec::new();
+    for link in get_links(query, GetStrategy::default())? {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(action_hash.clone(), GetOptions::default())? else {
+            continue;
+        };
+        let Some(item) = record.entry().to_app_option::<AssessmentItem>().ok().flatten() else {
+            continue;
+        };
+        items.push(AssessmentItemOutput { action_hash, item });
+    }
+    items.sort_by(|a, b| a.item.created_at.cmp(&b.item.created_at));
+    Ok(items)
 }
 
-// =============================================================================
-// Practice Pool Operations
-// =============================================================================
+fn assessment_item_by_id(item_id: &str) -> ExternResult<AssessmentItemOutput> {
+    assessment_items_from(assessment_id_anchor(item_id)?, LinkTypes::IdToAssessmentItem)?
+        .pop()
+        .ok_or(wasm_error!(WasmErrorInner::Guest(format!("Assessment item not found: {}", item_id))))
+}
 
-/// Get or create practice pool for current agent
+/// Point the content and id anchors at a new version of an item
+fn relink_assessment_item(item: &AssessmentItem, old: &ActionHash, new: &ActionHash) -> ExternResult<()> {
+    let content_anchor = assessment_content_anchor(&item.content_id)?;
+    delete_links_to(content_anchor.clone(), LinkTypes::ContentToAssessmentItems, old)?;
+    create_link(content_anchor, new.clone(), LinkTypes::ContentToAssessmentItems, ())?;
+
+    let id_anchor = assessment_id_anchor(&item.id)?;
+    delete_links_to(id_anchor.clone(), LinkTypes::IdToAssessmentItem, old)?;
+    create_link(id_anchor, new.clone(), LinkTypes::IdToAssessmentItem, ())?;
+    Ok(())
+}
+
+/// Add a question to a content node's question bank.
+///
+/// Unpublished questions go on the steward review queue. Generated
+/// questions are always tagged `ai_generated` and start unpublished.
 #[hdk_extern]
-pub fn get_or_create_practice_pool(input: CreatePoolInput) -> ExternResult<PracticePoolOutput> {
-    let agent_info = agent_info()?;
-    let agent_id = agent_info.agent_initial_pubkey.to_string();
+pub fn create_assessment_item(input: CreateAssessmentItemInput) -> ExternResult<AssessmentItemOutput> {
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("content_id", &input.content_id)))?;
+    let query = LinkQuery::try_new(anchor_hash, LinkTypes::IdToContent)?;
+    if get_links(query, GetStrategy::default())?.is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Content not found: {}",
+            input.content_id
+        ))));
+    }
+
     let now = sys_time()?;
     let timestamp = format!("{:?}", now);
+    let mut tags = input.tags;
+    if input.generated_by.is_some() && !tags.iter().any(|t| t == AI_GENERATED_TAG) {
+        tags.push(AI_GENERATED_TAG.to_string());
+    }
 
-    // Check if pool already exists
-    let pool_anchor = StringAnchor::new("agent_pool", &agent_id);
-    let pool_anchor_hash = hash_entry(&EntryTypes::StringAnchor(pool_anchor.clone()))?;
-
-    let query = LinkQuery::try_new(pool_anchor_hash.clone(), LinkTypes::AgentToPool)?;
-    let links = get_links(query, GetStrategy::default())?;
+    let item = AssessmentItem {
+        id: format!("assessment-{}-{}", input.content_id, now.as_micros()),
+        content_id: input.content_id,
+        question_type: input.question_type,
+        question_text: input.question_text.trim().to_string(),
+        options: input.options.into_iter().map(|o| o.trim().to_string()).collect(),
+        correct_answer: input.correct_answer.trim().to_string(),
+        explanation: input.explanation.filter(|e| !e.trim().is_empty()),
+        tags,
+        published: input.published && input.generated_by.is_none(),
+        author_id: agent_info()?.agent_initial_pubkey.to_string(),
+        generated_by: input.generated_by,
+        reviewed_by: None,
+        created_at: timestamp.clone(),
+        updated_at: timestamp,
+    };
+    let action_hash = create_entry(&EntryTypes::AssessmentItem(item.clone()))?;
 
-    if let Some(link) = links.first() {
-        // Return existing pool
-        let action_hash = ActionHash::try_from(link.target.clone())
-            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid pool hash".to_string())))?;
+    create_link(
+        assessment_content_anchor(&item.content_id)?,
+        action_hash.clone(),
+        LinkTypes::ContentToAssessmentItems,
+        (),
+    )?;
+    create_link(assessment_id_anchor(&item.id)?, action_hash.clone(), LinkTypes::IdToAssessmentItem, ())?;
 
-        let record = get(action_hash.clone(), GetOptions::default())?
-            .ok_or(wasm_error!(WasmErrorInner::Guest("Pool record not found".to_string())))?;
+    if item.published {
+        emit_write_signal("AssessmentItem", &item.id, "create_assessment_item");
+    } else {
+        create_link(
+            assessment_review_anchor()?,
+            action_hash.clone(),
+            LinkTypes::AssessmentReviewQueue,
+            LinkTag::new(item.content_id.as_bytes().to_vec()),
+        )?;
+        // Stewards watching the review queue pick this up
+        emit_write_signal("AssessmentReview", &item.id, "create_assessment_item");
+    }
 
-        let pool: PracticePool = record
-            .entry()
-            .to_app_option()
-            .map_err(|e| wasm_error!(e))?
-            .ok_or(wasm_error!(WasmErrorInner::Guest("Could not deserialize pool".to_string())))?;
+    Ok(AssessmentItemOutput { action_hash, item })
+}
 
-        return Ok(PracticePoolOutput { action_hash, pool });
-    }
+/// Get a content node's questions, oldest first (published only unless asked)
+#[hdk_extern]
+pub fn get_assessment_items(input: GetAssessmentItemsInput) -> ExternResult<Vec<AssessmentItemOutput>> {
+    let items = assessment_items_from(
+        assessment_content_anchor(&input.content_id)?,
+        LinkTypes::ContentToAssessmentItems,
+    )?;
+    Ok(items
+        .into_iter()
+        .filter(|i| input.include_unpublished || i.item.published)
+        .collect())
+}
 
-    // Create new pool
-    let pool_id = format!("pool-{}-{}", agent_id, timestamp);
+/// Get unpublished questions awaiting steward review, oldest first
+#[hdk_extern]
+pub fn get_pending_assessment_items(_: ()) -> ExternResult<Vec<AssessmentItemOutput>> {
+    assessment_items_from(assessment_review_anchor()?, LinkTypes::AssessmentReviewQueue)
+}
 
-    let pool = PracticePool {
-        id: pool_id.clone(),
-        agent_id: agent_id.clone(),
-        active_content_ids_json: "[]".to_string(),
-        refresh_queue_ids_json: "[]".to_string(),
-        discovery_candidates_json: "[]".to_string(),
-        contributing_path_ids_json: serde_json::to_string(&input.contributing_path_ids)
-            .unwrap_or_else(|_| "[]".to_string()),
-        max_active_size: input.max_active_size.unwrap_or(20),
-        refresh_threshold: input.refresh_threshold.unwrap_or(0.5),
-        discovery_probability: input.discovery_probability.unwrap_or(0.15),
-        regression_enabled: input.regression_enabled.unwrap_or(true),
-        challenge_cooldown_hours: input.challenge_cooldown_hours.unwrap_or(24),
-        last_challenge_at: None,
-        last_challenge_id: None,
-        total_challenges_taken: 0,
-        total_level_ups: 0,
-        total_level_downs: 0,
-        discoveries_unlocked: 0,
-        created_at: timestamp.clone(),
-        updated_at: timestamp,
-    };
+/// Publish a reviewed question so mastery challenges can use it
+#[hdk_extern]
+pub fn publish_assessment_item(input: ReviewAssessmentItemInput) -> ExternResult<AssessmentItemOutput> {
+    let existing = assessment_item_by_id(&input.item_id)?;
+    if existing.item.published {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Assessment item already published: {}",
+            input.item_id
+        ))));
+    }
 
-    let action_hash = create_entry(&EntryTypes::PracticePool(pool.clone()))?;
+    let mut item = existing.item;
+    item.published = true;
+    item.reviewed_by = input.reviewer_id;
+    item.updated_at = format!("{:?}", sys_time()?);
+    let action_hash = update_entry(existing.action_hash.clone(), &EntryTypes::AssessmentItem(item.clone()))?;
 
-    // Create anchor and link
-    create_entry(&EntryTypes::StringAnchor(pool_anchor))?;
-    create_link(pool_anchor_hash, action_hash.clone(), LinkTypes::AgentToPool, ())?;
+    relink_assessment_item(&item, &existing.action_hash, &action_hash)?;
+    delete_links_to(assessment_review_anchor()?, LinkTypes::AssessmentReviewQueue, &existing.action_hash)?;
+    emit_write_signal("AssessmentItem", &item.id, "publish_assessment_item");
 
-    Ok(PracticePoolOutput { action_hash, pool })
+    Ok(AssessmentItemOutput { action_hash, item })
 }
 
-/// Refresh practice pool with content from paths and knowledge graph
+/// Reject an unpublished question; it is removed from the question bank
 #[hdk_extern]
-pub fn refresh_practice_pool(_: ()) -> ExternResult<PracticePoolOutput> {
-    let agent_info = agent_info()?;
-    let agent_id = agent_info.agent_initial_pubkey.to_string();
-    let now = sys_time()?;
-    let timestamp = format!("{:?}", now);
+pub fn reject_assessment_item(input: ReviewAssessmentItemInput) -> ExternResult<()> {
+    let existing = assessment_item_by_id(&input.item_id)?;
+    if existing.item.published {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Assessment item already published: {}",
+            input.item_id
+        ))));
+    }
 
-    // Get existing pool
-    let pool_output = get_or_create_practice_pool(CreatePoolInput {
-        contributing_path_ids: vec![],
-        max_active_size: None,
-        refresh_threshold: None,
-        discovery_probability: None,
-        regression_enabled: None,
-        challenge_cooldown_hours: None,
+    let item = &existing.item;
+    delete_links_to(
+        assessment_content_anchor(&item.content_id)?,
+        LinkTypes::ContentToAssessmentItems,
+        &existing.action_hash,
+    )?;
+    delete_links_to(assessment_id_anchor(&item.id)?, LinkTypes::IdToAssessmentItem, &existing.action_hash)?;
+    delete_links_to(assessment_review_anchor()?, LinkTypes::AssessmentReviewQueue, &existing.action_hash)?;
+    delete_entry(existing.action_hash)?;
+    emit_write_signal("AssessmentItem", &input.item_id, "reject_assessment_item");
+
+    Ok(())
+}
+
+/// Pick a challenge question for a content node from its published
+/// question bank. Content without published questions gets a self-assessed
+/// recall prompt built from its title and description.
+fn challenge_question(content_id: &str, seed: usize) -> ExternResult<ChallengeQuestion> {
+    let items = get_assessment_items(GetAssessmentItemsInput {
+        content_id: content_id.to_string(),
+        include_unpublished: false,
     })?;
+    if !items.is_empty() {
+        let item = &items[seed % items.len()].item;
+        return Ok(ChallengeQuestion {
+            content_id: content_id.to_string(),
+            question_type: item.question_type.clone(),
+            question_text: item.question_text.clone(),
+            options_json: serde_json::to_string(&item.options).unwrap_or_else(|_| "[]".to_string()),
+            correct_answer: item.correct_answer.clone(),
+        });
+    }
 
-    let existing_pool = pool_output.pool;
+    let content = get_content_by_id(QueryByIdInput { id: content_id.to_string() })?.map(|c| c.content);
+    let title = content.as_ref().map(|c| c.title.clone()).unwrap_or_else(|| content_id.to_string());
+    Ok(ChallengeQuestion {
+        content_id: content_id.to_string(),
+        question_type: "recall".to_string(),
+        question_text: format!("In your own words, explain the key idea of \"{}\".", title),
+        options_json: "[]".to_string(),
+        correct_answer: content.map(|c| c.description).unwrap_or_default(),
+    })
+}
 
-    // Gather active content from contributing paths
-    let contributing_paths: Vec<String> = serde_json::from_str(&existing_pool.contributing_path_ids_json)
-        .unwrap_or_default();
+// =============================================================================
+// Peer Review ("evaluate" mastery)
+// =============================================================================
 
-    let mut active_content: Vec<String> = Vec::new();
-    let mut _refresh_queue: Vec<String> = Vec::new();
+/// Mastery a reviewer needs on the content ("analyze")
+const PEER_REVIEWER_MIN_LEVEL: u32 = 5;
+
+/// Mastery a submitted review demonstrates for the reviewer ("evaluate")
+const PEER_REVIEW_LEVEL: u32 = 6;
+
+const DEFAULT_PEER_REVIEWERS: u32 = 2;
+const MAX_PEER_REVIEWERS: u32 = 5;
+
+fn default_peer_review_rubric() -> Vec<RubricCriterion> {
+    [
+        ("accuracy", "Claims and details are correct"),
+        ("reasoning", "Analysis connects ideas and supports its conclusions"),
+        ("clarity", "Another learner could follow and use the work"),
+    ]
+    .into_iter()
+    .map(|(criterion, description)| RubricCriterion {
+        criterion: criterion.to_string(),
+        description: description.to_string(),
+        max_score: 4,
+    })
+    .collect()
+}
 
-    for path_id in &contributing_paths {
-        if let Some(path_with_steps) = get_path_with_steps(path_id.clone())? {
-            for step_output in path_with_steps.steps {
-                let content_id = step_output.step.resource_id.clone();
+fn peer_review_anchor(anchor_type: &str, value: &str) -> ExternResult<EntryHash> {
+    hash_entry(&EntryTypes::StringAnchor(StringAnchor::new(anchor_type, value)))
+}
 
-                // Check mastery for this content
-                if let Some(mastery_output) = get_my_mastery(content_id.clone())? {
-                    let mastery = mastery_output.mastery;
+fn peer_review_requests_from(base: EntryHash, link_type: LinkTypes) -> ExternResult<Vec<PeerReviewRequestOutput>> {
+    let query = LinkQuery::try_new(base, link_type)?;
+    let mut requests = Vec::new();
+    for link in get_links(query, GetStrategy::default())? {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(action_hash.clone(), GetOptions::default())? else {
+            continue;
+        };
+        let Some(request) = record.entry().to_app_option::<PeerReviewRequest>().ok().flatten() else {
+            continue;
+        };
+        requests.push(PeerReviewRequestOutput { action_hash, request });
+    }
+    requests.sort_by(|a, b| a.request.created_at.cmp(&b.request.created_at));
+    Ok(requests)
+}
 
-                    // If not yet mastered (below apply level), add to active
-                    if mastery.mastery_level_index < 4 {
-                        if !active_content.contains(&content_id) && active_content.len() < existing_pool.max_active_size as usize {
-                            active_content.push(content_id);
-                        }
-                    }
-                    // If mastered but freshness dropped, add to refresh queue
-                    else if mastery.freshness_score < existing_pool.refresh_threshold {
-                        if !_refresh_queue.contains(&content_id) {
-                            _refresh_queue.push(content_id);
-                        }
-                    }
-                } else {
-                    // No mastery record - add to active
-                    if !active_content.contains(&content_id) && active_content.len() < existing_pool.max_active_size as usize {
-                        active_content.push(content_id);
-                    }
-                }
-            }
-        }
+fn peer_review_request_by_id(request_id: &str) -> ExternResult<PeerReviewRequestOutput> {
+    peer_review_requests_from(
+        peer_review_anchor("peer_review_request_id", request_id)?,
+        LinkTypes::IdToPeerReviewRequest,
+    )?
+    .pop()
+    .ok_or(wasm_error!(WasmErrorInner::Guest(format!("Peer review request not found: {}", request_id))))
+}
+
+/// Link a request from its id, content, status and reviewer anchors
+fn link_peer_review_request(request: &PeerReviewRequest, action_hash: &ActionHash) -> ExternResult<()> {
+    create_link(
+        peer_review_anchor("peer_review_request_id", &request.id)?,
+        action_hash.clone(),
+        LinkTypes::IdToPeerReviewRequest,
+        (),
+    )?;
+    create_link(
+        peer_review_anchor("peer_review_content", &request.content_id)?,
+        action_hash.clone(),
+        LinkTypes::ContentToPeerReviewRequest,
+        (),
+    )?;
+    create_link(
+        peer_review_anchor("peer_review_status", &request.status)?,
+        action_hash.clone(),
+        LinkTypes::PeerReviewRequestByStatus,
+        (),
+    )?;
+    for reviewer_id in &request.assigned_reviewer_ids {
+        create_link(
+            peer_review_anchor("peer_reviewer", reviewer_id)?,
+            action_hash.clone(),
+            LinkTypes::ReviewerToPeerReviewRequest,
+            (),
+        )?;
+    }
+    Ok(())
+}
+
+/// Remove the links to a superseded version of a request
+fn unlink_peer_review_request(request: &PeerReviewRequest, action_hash: &ActionHash) -> ExternResult<()> {
+    delete_links_to(
+        peer_review_anchor("peer_review_request_id", &request.id)?,
+        LinkTypes::IdToPeerReviewRequest,
+        action_hash,
+    )?;
+    delete_links_to(
+        peer_review_anchor("peer_review_content", &request.content_id)?,
+        LinkTypes::ContentToPeerReviewRequest,
+        action_hash,
+    )?;
+    delete_links_to(
+        peer_review_anchor("peer_review_status", &request.status)?,
+        LinkTypes::PeerReviewRequestByStatus,
+        action_hash,
+    )?;
+    for reviewer_id in &request.assigned_reviewer_ids {
+        delete_links_to(
+            peer_review_anchor("peer_reviewer", reviewer_id)?,
+            LinkTypes::ReviewerToPeerReviewRequest,
+            action_hash,
+        )?;
+    }
+    Ok(())
+}
+
+/// Write a new version of a request and move its links over
+fn save_peer_review_request(
+    existing: &PeerReviewRequestOutput,
+    mut request: PeerReviewRequest,
+) -> ExternResult<PeerReviewRequestOutput> {
+    request.updated_at = format!("{:?}", sys_time()?);
+    let action_hash = update_entry(existing.action_hash.clone(), &EntryTypes::PeerReviewRequest(request.clone()))?;
+    unlink_peer_review_request(&existing.request, &existing.action_hash)?;
+    link_peer_review_request(&request, &action_hash)?;
+    Ok(PeerReviewRequestOutput { action_hash, request })
+}
+
+/// A human's mastery level index on a content node (0 when untracked)
+fn human_mastery_index(human_id: &str, content_id: &str) -> ExternResult<u32> {
+    Ok(get_mastery_batch_for_human(human_id.to_string(), vec![content_id.to_string()])?
+        .remove(content_id)
+        .flatten()
+        .map(|m| m.mastery.mastery_level_index)
+        .unwrap_or(0))
+}
+
+/// Check a reviewer may take a slot on a request
+fn check_peer_reviewer(request: &PeerReviewRequest, reviewer_id: &str) -> ExternResult<()> {
+    if reviewer_id == request.author_id {
+        return Err(wasm_error!(WasmErrorInner::Guest("Authors cannot review their own work".to_string())));
+    }
+    if request.assigned_reviewer_ids.iter().any(|r| r == reviewer_id) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "{} is already reviewing this request",
+            reviewer_id
+        ))));
+    }
+    let level = human_mastery_index(reviewer_id, &request.content_id)?;
+    if level < PEER_REVIEWER_MIN_LEVEL {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Reviewers need {} mastery of {}; {} is at {}",
+            MASTERY_LEVELS[PEER_REVIEWER_MIN_LEVEL as usize],
+            request.content_id,
+            reviewer_id,
+            MASTERY_LEVELS.get(level as usize).unwrap_or(&"not_started")
+        ))));
+    }
+    Ok(())
+}
+
+/// Ask peers to evaluate work on a content node.
+///
+/// Nominated reviewers are assigned straight away; remaining slots are
+/// filled by qualified learners volunteering with `assign_peer_reviewer`.
+#[hdk_extern]
+pub fn request_peer_review(input: RequestPeerReviewInput) -> ExternResult<PeerReviewRequestOutput> {
+    let author_id = learner_or_agent(input.human_id)?;
+    if input.artifact.trim().is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest("Describe or link the work to review".to_string())));
+    }
+    let reviewers_required = input.reviewers_required.unwrap_or(DEFAULT_PEER_REVIEWERS);
+    if reviewers_required == 0 || reviewers_required > MAX_PEER_REVIEWERS {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "reviewers_required must be between 1 and {}",
+            MAX_PEER_REVIEWERS
+        ))));
+    }
+    if input.reviewer_ids.len() > reviewers_required as usize {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Nominated {} reviewers for {} slots",
+            input.reviewer_ids.len(),
+            reviewers_required
+        ))));
     }
 
-    // Discover related content from knowledge graph
-    let mut discovery_candidates: Vec<DiscoveryCandidate> = Vec::new();
+    let now = sys_time()?;
+    let timestamp = format!("{:?}", now);
+    let mut request = PeerReviewRequest {
+        id: format!("peer-review-{}-{}", input.content_id, now.as_micros()),
+        content_id: input.content_id,
+        author_id,
+        title: input.title.trim().to_string(),
+        artifact: input.artifact,
+        rubric: input.rubric.filter(|r| !r.is_empty()).unwrap_or_else(default_peer_review_rubric),
+        reviewers_required,
+        assigned_reviewer_ids: Vec::new(),
+        completed_reviewer_ids: Vec::new(),
+        status: "open".to_string(),
+        created_at: timestamp.clone(),
+        updated_at: timestamp,
+    };
+    for reviewer_id in input.reviewer_ids {
+        check_peer_reviewer(&request, &reviewer_id)?;
+        request.assigned_reviewer_ids.push(reviewer_id);
+    }
+    if request.assigned_reviewer_ids.len() == reviewers_required as usize {
+        request.status = "assigned".to_string();
+    }
 
-    for content_id in &active_content {
-        // Get relationships for this content
-        let relationships = get_relationships(GetRelationshipsInput {
-            content_id: content_id.clone(),
-            direction: "both".to_string(),
-        })?;
+    let action_hash = create_entry(&EntryTypes::PeerReviewRequest(request.clone()))?;
+    link_peer_review_request(&request, &action_hash)?;
+    emit_write_signal("PeerReviewRequest", &request.id, "request_peer_review");
 
-        for rel_output in relationships {
-            let rel = rel_output.relationship;
+    Ok(PeerReviewRequestOutput { action_hash, request })
+}
 
-            // Find the related content (the one that's not this content_id)
-            let related_id = if rel.source_id == *content_id {
-                rel.target_id.clone()
-            } else {
-                rel.source_id.clone()
-            };
+/// Take a reviewer slot on an open request.
+///
+/// The reviewer must hold at least "analyze" mastery of the content.
+#[hdk_extern]
+pub fn assign_peer_reviewer(input: AssignPeerReviewerInput) -> ExternResult<PeerReviewRequestOutput> {
+    let reviewer_id = learner_or_agent(input.reviewer_id)?;
+    let existing = peer_review_request_by_id(&input.request_id)?;
+    if existing.request.status != "open" {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Peer review request {} is {}, not open",
+            input.request_id, existing.request.status
+        ))));
+    }
+    check_peer_reviewer(&existing.request, &reviewer_id)?;
 
-            // Check if this related content is not already in our pool
-            if !active_content.contains(&related_id) && !_refresh_queue.contains(&related_id) {
-                // Check we haven't already added this as a discovery candidate
-                if !discovery_candidates.iter().any(|d| d.content_id == related_id) {
-                    discovery_candidates.push(DiscoveryCandidate {
-                        content_id: related_id,
-                        source_content_id: content_id.clone(),
-                        relationship_type: rel.relationship_type.clone(),
-                        discovery_reason: format!("Related via {} to content you're learning", rel.relationship_type),
-                    });
-                }
-            }
+    let mut request = existing.request.clone();
+    request.assigned_reviewer_ids.push(reviewer_id);
+    if request.assigned_reviewer_ids.len() >= request.reviewers_required as usize {
+        request.status = "assigned".to_string();
+    }
+    let updated = save_peer_review_request(&existing, request)?;
+    emit_write_signal("PeerReviewRequest", &updated.request.id, "assign_peer_reviewer");
+
+    Ok(updated)
+}
+
+/// Submit a rubric-structured review.
+///
+/// Every rubric criterion is scored once, within its maximum. The review is
+/// recorded as mastery evidence for both sides: the reviewer's mastery rises
+/// to at least "evaluate", the author's evidence gains the review.
+#[hdk_extern]
+pub fn submit_peer_review(input: SubmitPeerReviewInput) -> ExternResult<PeerReviewResult> {
+    let reviewer_id = learner_or_agent(input.reviewer_id)?;
+    let existing = peer_review_request_by_id(&input.request_id)?;
+    let request = &existing.request;
+    if !request.assigned_reviewer_ids.contains(&reviewer_id) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "{} is not assigned to review {}",
+            reviewer_id, input.request_id
+        ))));
+    }
+    if request.completed_reviewer_ids.contains(&reviewer_id) || request.status == "cancelled" {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Peer review request {} is not awaiting a review from {}",
+            input.request_id, reviewer_id
+        ))));
+    }
+    if !PEER_REVIEW_RECOMMENDATIONS.contains(&input.recommendation.as_str()) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Invalid recommendation '{}'. Must be one of: {:?}",
+            input.recommendation, PEER_REVIEW_RECOMMENDATIONS
+        ))));
+    }
+
+    // One score per rubric criterion, within its maximum
+    let mut scored = 0;
+    let mut possible = 0;
+    for criterion in &request.rubric {
+        let mut matching = input.scores.iter().filter(|s| s.criterion == criterion.criterion);
+        let (Some(score), None) = (matching.next(), matching.next()) else {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                "Score '{}' exactly once",
+                criterion.criterion
+            ))));
+        };
+        if score.score > criterion.max_score {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                "'{}' is scored out of {}",
+                criterion.criterion, criterion.max_score
+            ))));
         }
+        scored += score.score;
+        possible += criterion.max_score;
+    }
+    if input.scores.len() != request.rubric.len() {
+        return Err(wasm_error!(WasmErrorInner::Guest("Scores must match the request's rubric".to_string())));
     }
 
-    // Limit discovery candidates
-    discovery_candidates.truncate(10);
+    let now = sys_time()?;
+    let timestamp = format!("{:?}", now);
+    let review = PeerReview {
+        id: format!("peer-review-{}-{}-{}", request.id, reviewer_id, now.as_micros()),
+        request_id: request.id.clone(),
+        content_id: request.content_id.clone(),
+        reviewer_id: reviewer_id.clone(),
+        author_id: request.author_id.clone(),
+        scores: input.scores,
+        overall_score: scored as f64 / possible.max(1) as f64,
+        recommendation: input.recommendation,
+        comments: input.comments,
+        created_at: timestamp.clone(),
+    };
+    let review_hash = create_entry(&EntryTypes::PeerReview(review.clone()))?;
+    create_link(
+        peer_review_anchor("peer_reviews", &request.id)?,
+        review_hash.clone(),
+        LinkTypes::RequestToPeerReview,
+        (),
+    )?;
 
-    // Update the pool
-    let updated_pool = PracticePool {
-        id: existing_pool.id,
-        agent_id: existing_pool.agent_id,
-        active_content_ids_json: serde_json::to_string(&active_content).unwrap_or_else(|_| "[]".to_string()),
-        refresh_queue_ids_json: serde_json::to_string(&_refresh_queue).unwrap_or_else(|_| "[]".to_string()),
-        discovery_candidates_json: serde_json::to_string(&discovery_candidates).unwrap_or_else(|_| "[]".to_string()),
-        contributing_path_ids_json: existing_pool.contributing_path_ids_json,
-        max_active_size: existing_pool.max_active_size,
-        refresh_threshold: existing_pool.refresh_threshold,
-        discovery_probability: existing_pool.discovery_probability,
-        regression_enabled: existing_pool.regression_enabled,
-        challenge_cooldown_hours: existing_pool.challenge_cooldown_hours,
-        last_challenge_at: existing_pool.last_challenge_at,
-        last_challenge_id: existing_pool.last_challenge_id,
-        total_challenges_taken: existing_pool.total_challenges_taken,
-        total_level_ups: existing_pool.total_level_ups,
-        total_level_downs: existing_pool.total_level_downs,
-        discoveries_unlocked: existing_pool.discoveries_unlocked,
-        created_at: existing_pool.created_at,
-        updated_at: timestamp,
+    let mut updated = request.clone();
+    updated.completed_reviewer_ids.push(reviewer_id.clone());
+    if updated.completed_reviewer_ids.len() >= updated.reviewers_required as usize {
+        updated.status = "completed".to_string();
+    }
+    let updated = save_peer_review_request(&existing, updated)?;
+
+    // Evidence for both sides of the review
+    let evidence = |role: &str| {
+        serde_json::json!({
+            "type": "peer_review",
+            "role": role,
+            "request_id": review.request_id,
+            "review_id": review.id,
+            "overall_score": review.overall_score,
+            "recommendation": review.recommendation,
+            "timestamp": timestamp,
+        })
+        .to_string()
     };
+    let reviewer_index = human_mastery_index(&reviewer_id, &review.content_id)?.max(PEER_REVIEW_LEVEL);
+    let reviewer_level = MASTERY_LEVELS[reviewer_index as usize].to_string();
+    upsert_mastery(UpsertMasteryInput {
+        human_id: reviewer_id,
+        content_id: review.content_id.clone(),
+        mastery_level: reviewer_level.clone(),
+        engagement_type: "review".to_string(),
+        evidence_json: Some(evidence("reviewer")),
+    })?;
+    let author_index = human_mastery_index(&review.author_id, &review.content_id)?;
+    upsert_mastery(UpsertMasteryInput {
+        human_id: review.author_id.clone(),
+        content_id: review.content_id.clone(),
+        mastery_level: MASTERY_LEVELS.get(author_index as usize).unwrap_or(&"seen").to_string(),
+        engagement_type: "review".to_string(),
+        evidence_json: Some(evidence("author")),
+    })?;
 
-    let action_hash = create_entry(&EntryTypes::PracticePool(updated_pool.clone()))?;
+    emit_write_signal("PeerReview", &review.id, "submit_peer_review");
 
-    // Update link
-    let pool_anchor = StringAnchor::new("agent_pool", &agent_id);
-    let pool_anchor_hash = hash_entry(&EntryTypes::StringAnchor(pool_anchor))?;
+    Ok(PeerReviewResult {
+        review: PeerReviewOutput { action_hash: review_hash, review },
+        request: updated,
+        reviewer_level,
+    })
+}
 
-    let query = LinkQuery::try_new(pool_anchor_hash.clone(), LinkTypes::AgentToPool)?;
-    let links = get_links(query, GetStrategy::default())?;
-    if let Some(old_link) = links.first() {
-        delete_link(old_link.create_link_hash.clone(), GetOptions::default())?;
+/// Withdraw a request; reviews already submitted stay on record
+#[hdk_extern]
+pub fn cancel_peer_review_request(request_id: String) -> ExternResult<PeerReviewRequestOutput> {
+    let existing = peer_review_request_by_id(&request_id)?;
+    if existing.request.status == "completed" || existing.request.status == "cancelled" {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Peer review request {} is already {}",
+            request_id, existing.request.status
+        ))));
     }
-    create_link(pool_anchor_hash, action_hash.clone(), LinkTypes::AgentToPool, ())?;
-
-    Ok(PracticePoolOutput { action_hash, pool: updated_pool })
+    let mut request = existing.request.clone();
+    request.status = "cancelled".to_string();
+    let updated = save_peer_review_request(&existing, request)?;
+    emit_write_signal("PeerReviewRequest", &request_id, "cancel_peer_review_request");
+    Ok(updated)
 }
 
-/// Add a path to the practice pool
+/// Get a peer review request by ID
 #[hdk_extern]
-pub fn add_path_to_pool(path_id: String) -> ExternResult<PracticePoolOutput> {
-    let pool_output = get_or_create_practice_pool(CreatePoolInput {
-        contributing_path_ids: vec![path_id.clone()],
-        max_active_size: None,
-        refresh_threshold: None,
-        discovery_probability: None,
-        regression_enabled: None,
-        challenge_cooldown_hours: None,
-    })?;
+pub fn get_peer_review_request(request_id: String) -> ExternResult<Option<PeerReviewRequestOutput>> {
+    Ok(peer_review_requests_from(
+        peer_review_anchor("peer_review_request_id", &request_id)?,
+        LinkTypes::IdToPeerReviewRequest,
+    )?
+    .pop())
+}
 
-    let mut contributing: Vec<String> = serde_json::from_str(&pool_output.pool.contributing_path_ids_json)
-        .unwrap_or_default();
+/// Get a content node's requests that still have reviewer slots, oldest first
+#[hdk_extern]
+pub fn get_open_peer_review_requests(content_id: String) -> ExternResult<Vec<PeerReviewRequestOutput>> {
+    Ok(peer_review_requests_from(
+        peer_review_anchor("peer_review_content", &content_id)?,
+        LinkTypes::ContentToPeerReviewRequest,
+    )?
+    .into_iter()
+    .filter(|r| r.request.status == "open")
+    .collect())
+}
 
-    if !contributing.contains(&path_id) {
-        contributing.push(path_id);
+/// Get the requests a reviewer still owes a review on
+#[hdk_extern]
+pub fn get_my_peer_review_assignments(human_id: Option<String>) -> ExternResult<Vec<PeerReviewRequestOutput>> {
+    let reviewer_id = learner_or_agent(human_id)?;
+    Ok(peer_review_requests_from(
+        peer_review_anchor("peer_reviewer", &reviewer_id)?,
+        LinkTypes::ReviewerToPeerReviewRequest,
+    )?
+    .into_iter()
+    .filter(|r| r.request.status != "cancelled" && !r.request.completed_reviewer_ids.contains(&reviewer_id))
+    .collect())
+}
+
+/// Get the reviews submitted on a request, oldest first
+#[hdk_extern]
+pub fn get_peer_reviews(request_id: String) -> ExternResult<Vec<PeerReviewOutput>> {
+    let query = LinkQuery::try_new(peer_review_anchor("peer_reviews", &request_id)?, LinkTypes::RequestToPeerReview)?;
+    let mut reviews = Vec::new();
+    for link in get_links(query, GetStrategy::default())? {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(action_hash.clone(), GetOptions::default())? else {
+            continue;
+        };
+        if let Some(review) = record.entry().to_app_option::<PeerReview>().ok().flatten() {
+            reviews.push(PeerReviewOutput { action_hash, review });
+        }
     }
+    reviews.sort_by(|a, b| a.review.created_at.cmp(&b.review.created_at));
+    Ok(reviews)
+}
 
-    // Update and refresh
-    let agent_info = agent_info()?;
-    let agent_id = agent_info.agent_initial_pubkey.to_string();
-    let now = sys_time()?;
-    let timestamp = format!("{:?}", now);
+// =============================================================================
+// Content Contributions ("create" mastery)
+// =============================================================================
 
-    let updated_pool = PracticePool {
-        contributing_path_ids_json: serde_json::to_string(&contributing).unwrap_or_else(|_| "[]".to_string()),
-        updated_at: timestamp,
-        ..pool_output.pool
-    };
+/// Mastery a contributor needs on the original content ("create")
+const CONTRIBUTION_MIN_LEVEL: u32 = 7;
 
-    let action_hash = create_entry(&EntryTypes::PracticePool(updated_pool.clone()))?;
+fn contribution_anchor(anchor_type: &str, value: &str) -> ExternResult<EntryHash> {
+    hash_entry(&EntryTypes::StringAnchor(StringAnchor::new(anchor_type, value)))
+}
 
-    // Update link
-    let pool_anchor = StringAnchor::new("agent_pool", &agent_id);
-    let pool_anchor_hash = hash_entry(&EntryTypes::StringAnchor(pool_anchor))?;
+fn contribution_review_anchor() -> ExternResult<EntryHash> {
+    contribution_anchor("contribution_review", "pending")
+}
 
-    let query = LinkQuery::try_new(pool_anchor_hash.clone(), LinkTypes::AgentToPool)?;
-    let links = get_links(query, GetStrategy::default())?;
-    if let Some(old_link) = links.first() {
-        delete_link(old_link.create_link_hash.clone(), GetOptions::default())?;
-    }
-    create_link(pool_anchor_hash, action_hash.clone(), LinkTypes::AgentToPool, ())?;
+fn contributions_from(base: EntryHash, link_type: LinkTypes) -> ExternResult<Vec<ContributionOutput>> {
+    let links = get_links(LinkQuery::try_new(base, link_type)?, GetStrategy::default())?;
+    let hashes: Vec<ActionHash> = links.into_iter().filter_map(|l| l.target.into_action_hash()).collect();
+    let records = get_records_batch(hashes.clone())?;
+    let mut contributions: Vec<ContributionOutput> = hashes
+        .into_iter()
+        .zip(records)
+        .filter_map(|(action_hash, record)| {
+            let contribution = record?.entry().to_app_option::<ContentContribution>().ok().flatten()?;
+            Some(ContributionOutput { action_hash, contribution })
+        })
+        .collect();
+    contributions.sort_by(|a, b| a.contribution.created_at.cmp(&b.contribution.created_at));
+    Ok(contributions)
+}
 
-    // Refresh to populate with content
-    refresh_practice_pool(())
+fn contribution_by_id(contribution_id: &str) -> ExternResult<ContributionOutput> {
+    contributions_from(contribution_anchor("contribution_id", contribution_id)?, LinkTypes::IdToContribution)?
+        .pop()
+        .ok_or(wasm_error!(WasmErrorInner::Guest(format!("Contribution not found: {}", contribution_id))))
 }
 
-/// Get pool recommendations for what to practice
+/// Link a contribution from its id, original content and contributor anchors
+fn link_contribution(contribution: &ContentContribution, action_hash: &ActionHash) -> ExternResult<()> {
+    create_link(
+        contribution_anchor("contribution_id", &contribution.id)?,
+        action_hash.clone(),
+        LinkTypes::IdToContribution,
+        (),
+    )?;
+    create_link(
+        contribution_anchor("contribution_content", &contribution.original_content_id)?,
+        action_hash.clone(),
+        LinkTypes::ContentToContribution,
+        (),
+    )?;
+    create_link(
+        contribution_anchor("contributor", &contribution.contributor_id)?,
+        action_hash.clone(),
+        LinkTypes::ContributorToContribution,
+        (),
+    )?;
+    Ok(())
+}
+
+/// Write the reviewed version of a pending contribution, move its links
+/// over and take it out of the review queue
+fn save_reviewed_contribution(
+    existing: &ContributionOutput,
+    mut contribution: ContentContribution,
+) -> ExternResult<ContributionOutput> {
+    contribution.updated_at = format!("{:?}", sys_time()?);
+    let action_hash =
+        update_entry(existing.action_hash.clone(), &EntryTypes::ContentContribution(contribution.clone()))?;
+    let old = &existing.contribution;
+    delete_links_to(contribution_anchor("contribution_id", &old.id)?, LinkTypes::IdToContribution, &existing.action_hash)?;
+    delete_links_to(
+        contribution_anchor("contribution_content", &old.original_content_id)?,
+        LinkTypes::ContentToContribution,
+        &existing.action_hash,
+    )?;
+    delete_links_to(
+        contribution_anchor("contributor", &old.contributor_id)?,
+        LinkTypes::ContributorToContribution,
+        &existing.action_hash,
+    )?;
+    delete_links_to(contribution_review_anchor()?, LinkTypes::ContributionReviewQueue, &existing.action_hash)?;
+    link_contribution(&contribution, &action_hash)?;
+    Ok(ContributionOutput { action_hash, contribution })
+}
+
+fn pending_contribution(contribution_id: &str) -> ExternResult<ContributionOutput> {
+    let existing = contribution_by_id(contribution_id)?;
+    if existing.contribution.status != "pending" {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Contribution {} is already {}",
+            contribution_id, existing.contribution.status
+        ))));
+    }
+    Ok(existing)
+}
+
+/// Submit derived content or an edit of a content node for steward review.
+///
+/// Requires "create" mastery of the original.
 #[hdk_extern]
-pub fn get_pool_recommendations(_: ()) -> ExternResult<PoolRecommendations> {
-    let pool_output = refresh_practice_pool(())?;
-    let pool = pool_output.pool;
+pub fn submit_contribution(input: SubmitContributionInput) -> ExternResult<ContributionOutput> {
+    let contributor_id = learner_or_agent(input.human_id)?;
+    if !CONTRIBUTION_KINDS.contains(&input.kind.as_str()) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Invalid contribution kind '{}'. Must be one of: {:?}",
+            input.kind, CONTRIBUTION_KINDS
+        ))));
+    }
+    let original = get_content_by_id(QueryByIdInput { id: input.original_content_id.clone() })?.ok_or(
+        wasm_error!(WasmErrorInner::Guest(format!("Content not found: {}", input.original_content_id))),
+    )?;
+    let level = human_mastery_index(&contributor_id, &input.original_content_id)?;
+    if level < CONTRIBUTION_MIN_LEVEL {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Contributing needs {} mastery of {}; {} is at {}",
+            MASTERY_LEVELS[CONTRIBUTION_MIN_LEVEL as usize],
+            input.original_content_id,
+            contributor_id,
+            MASTERY_LEVELS.get(level as usize).unwrap_or(&"not_started")
+        ))));
+    }
 
-    let active: Vec<String> = serde_json::from_str(&pool.active_content_ids_json).unwrap_or_default();
-    let refresh: Vec<String> = serde_json::from_str(&pool.refresh_queue_ids_json).unwrap_or_default();
-    let discoveries: Vec<DiscoveryCandidate> = serde_json::from_str(&pool.discovery_candidates_json).unwrap_or_default();
+    let now = sys_time()?;
+    let timestamp = format!("{:?}", now);
+    let contribution = ContentContribution {
+        id: format!("contrib-{}-{}", input.original_content_id, now.as_micros()),
+        original_content_id: input.original_content_id,
+        contributor_id,
+        kind: input.kind,
+        title: input.title,
+        description: input.description,
+        content: input.content,
+        content_format: input.content_format.unwrap_or(original.content.content_format),
+        tags: input.tags,
+        rationale: input.rationale,
+        status: "pending".to_string(),
+        reviewed_by: None,
+        review_note: None,
+        published_content_id: None,
+        points_awarded: 0,
+        created_at: timestamp.clone(),
+        updated_at: timestamp,
+    };
+    let action_hash = create_entry(&EntryTypes::ContentContribution(contribution.clone()))?;
+    link_contribution(&contribution, &action_hash)?;
+    create_link(contribution_review_anchor()?, action_hash.clone(), LinkTypes::ContributionReviewQueue, ())?;
+    emit_write_signal("ContentContribution", &contribution.id, "submit_contribution");
 
-    Ok(PoolRecommendations {
-        priority_refresh: refresh,
-        active_practice: active.clone(),
-        discovery_suggestions: discoveries,
-        total_pool_size: active.len() as u32,
-    })
+    Ok(ContributionOutput { action_hash, contribution })
 }
 
-/// Check if agent can take a mastery challenge (cooldown)
+/// Accept a pending contribution.
+///
+/// Publishes it as content credited to the contributor and linked
+/// DERIVED_FROM the original, awards the contributor `contribution` points
+/// and flows recognition to their presence on the new content.
 #[hdk_extern]
-pub fn check_challenge_cooldown(_: ()) -> ExternResult<CooldownCheckResult> {
-    let pool_output = get_or_create_practice_pool(CreatePoolInput {
-        contributing_path_ids: vec![],
-        max_active_size: None,
-        refresh_threshold: None,
-        discovery_probability: None,
-        regression_enabled: None,
-        challenge_cooldown_hours: None,
-    })?;
+pub fn accept_contribution(input: ReviewContributionInput) -> ExternResult<AcceptedContribution> {
+    let reviewer_id = learner_or_agent(input.reviewer_id)?;
+    let existing = pending_contribution(&input.contribution_id)?;
+    let contribution = &existing.contribution;
+    if reviewer_id == contribution.contributor_id {
+        return Err(wasm_error!(WasmErrorInner::Guest("Contributors cannot accept their own work".to_string())));
+    }
+    let original = get_content_by_id(QueryByIdInput { id: contribution.original_content_id.clone() })?.ok_or(
+        wasm_error!(WasmErrorInner::Guest(format!("Content not found: {}", contribution.original_content_id))),
+    )?;
 
-    let pool = pool_output.pool;
+    let now = sys_time()?;
+    let timestamp = format!("{:?}", now);
+    let content_id = format!("{}-contrib-{}", contribution.original_content_id, now.as_micros());
+    let metadata = serde_json::json!({
+        "contribution_id": contribution.id,
+        "contributed_by": contribution.contributor_id,
+        "derived_from": contribution.original_content_id,
+        "contribution_kind": contribution.kind,
+    });
+    let content = create_content_authored(
+        CreateContentInput {
+            id: content_id.clone(),
+            content_type: original.content.content_type.clone(),
+            title: contribution.title.clone(),
+            description: contribution.description.clone(),
+            summary: None,
+            content: contribution.content.clone(),
+            content_format: contribution.content_format.clone(),
+            tags: contribution.tags.clone(),
+            source_path: None,
+            related_node_ids: vec![contribution.original_content_id.clone()],
+            reach: original.content.reach.clone(),
+            estimated_minutes: None,
+            thumbnail_url: None,
+            metadata_json: metadata.to_string(),
+            blob_cid: None,
+            content_size_bytes: None,
+            content_hash: None,
+        },
+        Some(contribution.contributor_id.clone()),
+    )?;
+    increment_content_counter(&content.content.content_type, 1)?;
+    emit_write_signal("Content", &content_id, "accept_contribution");
+    let relationship = create_relationship(CreateRelationshipInput {
+        source_id: content_id.clone(),
+        target_id: contribution.original_content_id.clone(),
+        relationship_type: "DERIVED_FROM".to_string(),
+        confidence: 1.0,
+        inference_source: "explicit".to_string(),
+        metadata_json: Some(serde_json::json!({ "contribution_id": contribution.id }).to_string()),
+    })?;
 
-    match &pool.last_challenge_at {
-        None => Ok(CooldownCheckResult {
-            can_take_challenge: true,
-            cooldown_remaining_hours: 0,
-            last_challenge_at: None,
-            next_available_at: None,
-        }),
-        Some(last_challenge) => {
-            // Parse timestamp and check if cooldown has passed
-            // For now, simplified: just check if string is set
-            // In production, would parse timestamp and calculate
-            let cooldown_hours = pool.challenge_cooldown_hours;
+    // Points are awarded here, on the steward's acceptance, never claimed
+    let points = get_point_amount("contribution");
+    let event_id = format!("pe-{}-{}", contribution.contributor_id, timestamp);
+    let point_event = PointEvent {
+        id: event_id.clone(),
+        agent_id: contribution.contributor_id.clone(),
+        action: "produce".to_string(),
+        trigger: "contribution".to_string(),
+        points,
+        content_id: Some(content_id.clone()),
+        challenge_id: None,
+        path_id: None,
+        was_correct: None,
+        note: input.note.clone(),
+        metadata_json: serde_json::json!({ "contribution_id": contribution.id, "accepted_by": reviewer_id })
+            .to_string(),
+        occurred_at: timestamp.clone(),
+    };
+    record_point_event(&point_event)?;
+
+    let presence_id =
+        get_or_create_content_presence(&content_id, &contribution.title, Some(&contribution.contributor_id), &timestamp)?;
+    let recognition = flow_recognition_to_contributor(
+        &presence_id,
+        &content_id,
+        &reviewer_id,
+        &event_id,
+        "contribution",
+        points,
+        None,
+        None,
+        &timestamp,
+    )?;
 
-            // Simplified check - in production would actually parse and compare timestamps
-            Ok(CooldownCheckResult {
-                can_take_challenge: false, // Would calculate properly
-                cooldown_remaining_hours: cooldown_hours,
-                last_challenge_at: Some(last_challenge.clone()),
-                next_available_at: Some(format!("{} + {} hours", last_challenge, cooldown_hours)),
-            })
-        }
-    }
+    let mut accepted = contribution.clone();
+    accepted.status = "accepted".to_string();
+    accepted.reviewed_by = Some(reviewer_id);
+    accepted.review_note = input.note;
+    accepted.published_content_id = Some(content_id);
+    accepted.points_awarded = points;
+    let contribution = save_reviewed_contribution(&existing, accepted)?;
+    emit_write_signal("ContentContribution", &contribution.contribution.id, "accept_contribution");
+
+    Ok(AcceptedContribution { contribution, content, relationship, points_awarded: points, recognition })
+}
+
+/// Decline a pending contribution; it stays visible to the contributor
+/// with the steward's note
+#[hdk_extern]
+pub fn reject_contribution(input: ReviewContributionInput) -> ExternResult<ContributionOutput> {
+    let reviewer_id = learner_or_agent(input.reviewer_id)?;
+    let existing = pending_contribution(&input.contribution_id)?;
+    let mut rejected = existing.contribution.clone();
+    rejected.status = "rejected".to_string();
+    rejected.reviewed_by = Some(reviewer_id);
+    rejected.review_note = input.note;
+    let contribution = save_reviewed_contribution(&existing, rejected)?;
+    emit_write_signal("ContentContribution", &contribution.contribution.id, "reject_contribution");
+    Ok(contribution)
+}
+
+/// Get the steward review queue, oldest first
+#[hdk_extern]
+pub fn get_pending_contributions(_: ()) -> ExternResult<Vec<ContributionOutput>> {
+    contributions_from(contribution_review_anchor()?, LinkTypes::ContributionReviewQueue)
+}
+
+/// Get every contribution made against a content node
+#[hdk_extern]
+pub fn get_contributions_for_content(content_id: String) -> ExternResult<Vec<ContributionOutput>> {
+    contributions_from(contribution_anchor("contribution_content", &content_id)?, LinkTypes::ContentToContribution)
+}
+
+/// Get a contributor's contributions and how they were reviewed
+#[hdk_extern]
+pub fn get_my_contributions(human_id: Option<String>) -> ExternResult<Vec<ContributionOutput>> {
+    let contributor_id = learner_or_agent(human_id)?;
+    contributions_from(contribution_anchor("contributor", &contributor_id)?, LinkTypes::ContributorToContribution)
 }
 
 // =============================================================================
@@ -5443,16 +11582,12 @@ pub fn start_mastery_challenge(input: StartChallengeInput) -> ExternResult<Maste
         }
     }
 
-    // Generate placeholder questions (in production, would fetch from content)
+    // Draw questions from each content node's published question bank,
+    // rotating by start time so retakes see different items
+    let seed = now.as_micros().unsigned_abs() as usize;
     let mut questions: Vec<ChallengeQuestion> = Vec::new();
-    for mix_entry in &content_mix {
-        questions.push(ChallengeQuestion {
-            content_id: mix_entry.content_id.clone(),
-            question_type: "recall".to_string(),
-            question_text: format!("Question about {}", mix_entry.content_id),
-            options_json: "[]".to_string(),
-            correct_answer: "".to_string(),
-        });
+    for (i, mix_entry) in content_mix.iter().enumerate() {
+        questions.push(challenge_question(&mix_entry.content_id, seed.wrapping_add(i))?);
     }
 
     let challenge_id = format!("challenge-{}-{}", agent_id, timestamp);
@@ -5558,11 +11693,14 @@ pub fn submit_mastery_challenge(input: SubmitChallengeInput) -> ExternResult<Cha
     let pool = pool_output.pool;
     let regression_enabled = pool.regression_enabled;
 
+    // One bridge call for every content item the challenge touched
+    let current_masteries = get_my_mastery_batch(correct_by_content.keys().cloned().collect())?;
+
     for (content_id, (correct, total)) in &correct_by_content {
         let content_score = *correct as f64 / *total as f64;
 
         // Get current mastery
-        let current_mastery = get_my_mastery(content_id.clone())?;
+        let current_mastery = current_masteries.get(content_id).cloned().flatten();
         let (current_level, current_index) = match &current_mastery {
             Some(m) => (m.mastery.mastery_level.clone(), m.mastery.mastery_level_index),
             None => ("not_started".to_string(), 0),
@@ -5609,6 +11747,7 @@ pub fn submit_mastery_challenge(input: SubmitChallengeInput) -> ExternResult<Cha
                 content_id: content_id.clone(),
                 mastery_level: new_level,
                 engagement_type: "mastery_challenge".to_string(),
+                evidence_json: None,
             })?;
         }
     }
@@ -5689,6 +11828,8 @@ pub fn submit_mastery_challenge(input: SubmitChallengeInput) -> ExternResult<Cha
     let cooldown_hours = pool.challenge_cooldown_hours;
     let next_available = format!("{} + {} hours", timestamp, cooldown_hours);
 
+    emit_write_signal("MasteryChallenge", &updated_challenge.id, "submit_mastery_challenge");
+
     Ok(ChallengeResult {
         challenge: MasteryChallengeOutput {
             action_hash: new_action_hash,
@@ -5702,6 +11843,334 @@ pub fn submit_mastery_challenge(input: SubmitChallengeInput) -> ExternResult<Cha
     })
 }
 
+// =============================================================================
+// Checkpoint Steps
+// =============================================================================
+
+/// Score needed to pass a checkpoint unless its metadata sets `passing_score`
+const CHECKPOINT_PASSING_SCORE: f64 = 0.7;
+
+/// Most questions in one checkpoint review
+const CHECKPOINT_MAX_QUESTIONS: usize = 10;
+
+/// The step at `step_index` (its order_index) in a path
+fn path_step_at(path_id: &str, step_index: u32) -> ExternResult<Option<PathStep>> {
+    let Some(path_action_hash) = find_path_action_hash(path_id)? else {
+        return Ok(None);
+    };
+    let query = LinkQuery::try_new(path_action_hash, LinkTypes::PathToStep)?;
+    Ok(fetch_step_outputs(get_links(query, GetStrategy::default())?, false)?
+        .into_iter()
+        .map(|s| s.step)
+        .find(|step| step.order_index == step_index))
+}
+
+/// Passing score for a checkpoint, from `passing_score` (0.0-1.0) in its metadata
+fn checkpoint_passing_score(step: &PathStep) -> f64 {
+    serde_json::from_str::<serde_json::Value>(&step.metadata_json)
+        .ok()
+        .and_then(|m| m.get("passing_score").and_then(|v| v.as_f64()))
+        .filter(|score| (0.0..=1.0).contains(score))
+        .unwrap_or(CHECKPOINT_PASSING_SCORE)
+}
+
+/// Anchor for an agent's attempts at one checkpoint
+fn checkpoint_attempts_anchor(agent_id: &str, step_id: &str) -> ExternResult<EntryHash> {
+    hash_entry(&EntryTypes::StringAnchor(StringAnchor::new(
+        "checkpoint_attempts",
+        &format!("{}:{}", agent_id, step_id),
+    )))
+}
+
+fn challenge_from_link(link: Link) -> ExternResult<Option<MasteryChallengeOutput>> {
+    let Some(action_hash) = link.target.into_action_hash() else {
+        return Ok(None);
+    };
+    let Some(record) = get(action_hash.clone(), GetOptions::default())? else {
+        return Ok(None);
+    };
+    Ok(record
+        .entry()
+        .to_app_option::<MasteryChallenge>()
+        .ok()
+        .flatten()
+        .map(|challenge| MasteryChallengeOutput { action_hash, challenge }))
+}
+
+/// Whether the agent has a completed attempt at the checkpoint with a passing score
+fn checkpoint_passed(agent_id: &str, step: &PathStep) -> ExternResult<bool> {
+    let passing_score = checkpoint_passing_score(step);
+    let query = LinkQuery::try_new(checkpoint_attempts_anchor(agent_id, &step.id)?, LinkTypes::AgentToChallenge)?;
+    for link in get_links(query, GetStrategy::default())? {
+        if let Some(attempt) = challenge_from_link(link)? {
+            let challenge = attempt.challenge;
+            if challenge.state == "completed" && challenge.score.is_some_and(|s| s >= passing_score) {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// Content reviewed by a checkpoint: the content steps before it in its
+/// chapter (or among the ungrouped steps), in order
+fn checkpoint_content_ids(path_id: &str, checkpoint: &PathStep) -> ExternResult<Vec<String>> {
+    let Some(path_action_hash) = find_path_action_hash(path_id)? else {
+        return Ok(Vec::new());
+    };
+    let query = LinkQuery::try_new(path_action_hash, LinkTypes::PathToStep)?;
+    let mut steps: Vec<PathStep> = fetch_step_outputs(get_links(query, GetStrategy::default())?, false)?
+        .into_iter()
+        .map(|s| s.step)
+        .filter(|step| {
+            step.step_type == "content"
+                && step.chapter_id == checkpoint.chapter_id
+                && step.order_index < checkpoint.order_index
+        })
+        .collect();
+    steps.sort_by_key(|step| step.order_index);
+
+    let mut content_ids: Vec<String> = Vec::new();
+    for step in steps {
+        if !content_ids.contains(&step.resource_id) {
+            content_ids.push(step.resource_id);
+        }
+    }
+    Ok(content_ids)
+}
+
+/// Whether a response answers a question; recall questions are self-assessed
+fn checkpoint_answer_correct(question: &ChallengeQuestion, response: &ChallengeResponse) -> bool {
+    if question.question_type == "recall" {
+        return response.correct;
+    }
+    response.response.trim().eq_ignore_ascii_case(question.correct_answer.trim())
+}
+
+/// Start a checkpoint review: a mini-challenge with questions from the
+/// question banks of the content the checkpoint's chapter covered so far.
+///
+/// Submit it with `submit_checkpoint`; `complete_step` refuses the
+/// checkpoint until an attempt passes.
+#[hdk_extern]
+pub fn start_checkpoint(input: StartCheckpointInput) -> ExternResult<MasteryChallengeOutput> {
+    let agent_id = agent_info()?.agent_initial_pubkey.to_string();
+    let now = sys_time()?;
+    let timestamp = format!("{:?}", now);
+
+    let step = path_step_at(&input.path_id, input.step_index)?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(format!(
+            "No step {} in path {}",
+            input.step_index, input.path_id
+        ))))?;
+    if step.step_type != "checkpoint" {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!("Step {} is not a checkpoint", step.id))));
+    }
+
+    let mut content_ids = checkpoint_content_ids(&input.path_id, &step)?;
+    if content_ids.is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Checkpoint {} has no content before it to review",
+            step.id
+        ))));
+    }
+    // Most recent content first when there's more than fits
+    if content_ids.len() > CHECKPOINT_MAX_QUESTIONS {
+        content_ids.drain(..content_ids.len() - CHECKPOINT_MAX_QUESTIONS);
+    }
+
+    // Rotate by start time so retakes see different items
+    let seed = now.as_micros().unsigned_abs() as usize;
+    let mut questions = Vec::with_capacity(content_ids.len());
+    let mut content_mix = Vec::with_capacity(content_ids.len());
+    for (i, content_id) in content_ids.iter().enumerate() {
+        questions.push(challenge_question(content_id, seed.wrapping_add(i))?);
+        content_mix.push(ContentMixEntry {
+            content_id: content_id.clone(),
+            source: "checkpoint".to_string(),
+            question_count: 1,
+        });
+    }
+
+    let challenge = MasteryChallenge {
+        id: format!("checkpoint-{}-{}-{}", step.id, agent_id, now.as_micros()),
+        agent_id: agent_id.clone(),
+        pool_id: format!("checkpoint:{}", step.id),
+        path_id: Some(input.path_id),
+        content_mix_json: serde_json::to_string(&content_mix).unwrap_or_else(|_| "[]".to_string()),
+        total_questions: questions.len() as u32,
+        discovery_questions: 0,
+        state: "in_progress".to_string(),
+        started_at: timestamp.clone(),
+        completed_at: None,
+        time_limit_seconds: None,
+        actual_time_seconds: None,
+        questions_json: serde_json::to_string(&questions).unwrap_or_else(|_| "[]".to_string()),
+        responses_json: "[]".to_string(),
+        score: None,
+        score_by_content_json: "{}".to_string(),
+        level_changes_json: "[]".to_string(),
+        net_level_change: 0,
+        discoveries_json: "[]".to_string(),
+        created_at: timestamp,
+    };
+
+    let action_hash = create_entry(&EntryTypes::MasteryChallenge(challenge.clone()))?;
+
+    let challenge_anchor = StringAnchor::new("challenge_id", &challenge.id);
+    let challenge_anchor_hash = hash_entry(&EntryTypes::StringAnchor(challenge_anchor))?;
+    create_link(challenge_anchor_hash, action_hash.clone(), LinkTypes::AgentToChallenge, ())?;
+    create_link(
+        checkpoint_attempts_anchor(&agent_id, &step.id)?,
+        action_hash.clone(),
+        LinkTypes::AgentToChallenge,
+        LinkTag::new(step.id.as_bytes().to_vec()),
+    )?;
+
+    Ok(MasteryChallengeOutput { action_hash, challenge })
+}
+
+/// Grade a checkpoint review and record the result as assessment evidence
+/// on each reviewed content's mastery.
+///
+/// Answers are checked against the stored questions; unanswered questions
+/// count as wrong. A pass raises mastery of the reviewed content to at
+/// least "remember".
+#[hdk_extern]
+pub fn submit_checkpoint(input: SubmitChallengeInput) -> ExternResult<CheckpointResult> {
+    let agent_id = agent_info()?.agent_initial_pubkey.to_string();
+    let timestamp = format!("{:?}", sys_time()?);
+
+    let challenge_anchor = StringAnchor::new("challenge_id", &input.challenge_id);
+    let challenge_anchor_hash = hash_entry(&EntryTypes::StringAnchor(challenge_anchor))?;
+    let query = LinkQuery::try_new(challenge_anchor_hash.clone(), LinkTypes::AgentToChallenge)?;
+    let links = get_links(query, GetStrategy::default())?;
+    let existing = match links.first() {
+        Some(link) => challenge_from_link(link.clone())?,
+        None => None,
+    }
+    .ok_or(wasm_error!(WasmErrorInner::Guest(format!("Checkpoint not found: {}", input.challenge_id))))?;
+
+    let step_id = existing
+        .challenge
+        .pool_id
+        .strip_prefix("checkpoint:")
+        .map(str::to_string)
+        .ok_or(wasm_error!(WasmErrorInner::Guest(format!(
+            "Challenge {} is not a checkpoint review",
+            input.challenge_id
+        ))))?;
+    if existing.challenge.agent_id != agent_id {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the learner who started a checkpoint can submit it".to_string()
+        )));
+    }
+    if existing.challenge.state != "in_progress" {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Checkpoint {} was already submitted",
+            input.challenge_id
+        ))));
+    }
+    let path_id = existing.challenge.path_id.clone().unwrap_or_default();
+    let passing_score = get_step_by_id(step_id.clone())?
+        .map(|output| checkpoint_passing_score(&output.step))
+        .unwrap_or(CHECKPOINT_PASSING_SCORE);
+
+    // Grade against the stored questions, one answer per question
+    let questions: Vec<ChallengeQuestion> =
+        serde_json::from_str(&existing.challenge.questions_json).unwrap_or_default();
+    let mut graded: Vec<ChallengeResponse> = Vec::new();
+    let mut correct_by_content: HashMap<String, (u32, u32)> = HashMap::new(); // (correct, total)
+    for question in &questions {
+        correct_by_content.entry(question.content_id.clone()).or_insert((0, 0)).1 += 1;
+    }
+    for response in input.responses {
+        let Some(question) = questions.get(response.question_index as usize) else {
+            continue;
+        };
+        if graded.iter().any(|r| r.question_index == response.question_index) {
+            continue;
+        }
+        let correct = checkpoint_answer_correct(question, &response);
+        if correct {
+            correct_by_content.entry(question.content_id.clone()).or_insert((0, 0)).0 += 1;
+        }
+        graded.push(ChallengeResponse {
+            content_id: question.content_id.clone(),
+            correct,
+            ..response
+        });
+    }
+
+    let total_correct = graded.iter().filter(|r| r.correct).count() as u32;
+    let score = if questions.is_empty() { 0.0 } else { total_correct as f64 / questions.len() as f64 };
+    let passed = score >= passing_score;
+
+    // Record evidence on each reviewed content's mastery
+    let current = get_my_mastery_batch(correct_by_content.keys().cloned().collect())?;
+    for (content_id, (correct, total)) in &correct_by_content {
+        let current_index = current
+            .get(content_id)
+            .cloned()
+            .flatten()
+            .map(|m| m.mastery.mastery_level_index)
+            .unwrap_or(0);
+        let new_index = current_index.max(if passed { 2 } else { 1 });
+        let evidence = serde_json::json!({
+            "type": "checkpoint",
+            "checkpoint_id": step_id,
+            "path_id": path_id,
+            "score": *correct as f64 / (*total).max(1) as f64,
+            "passed": passed,
+            "threshold": passing_score,
+            "questions": total,
+            "correct": correct,
+            "time_seconds": input.actual_time_seconds,
+            "timestamp": timestamp,
+        });
+        upsert_mastery(UpsertMasteryInput {
+            human_id: agent_id.clone(),
+            content_id: content_id.clone(),
+            mastery_level: MASTERY_LEVELS.get(new_index as usize).unwrap_or(&"seen").to_string(),
+            engagement_type: "quiz".to_string(),
+            evidence_json: Some(evidence.to_string()),
+        })?;
+    }
+
+    let challenge = MasteryChallenge {
+        state: "completed".to_string(),
+        completed_at: Some(timestamp),
+        actual_time_seconds: Some(input.actual_time_seconds),
+        responses_json: serde_json::to_string(&graded).unwrap_or_else(|_| "[]".to_string()),
+        score: Some(score),
+        score_by_content_json: serde_json::to_string(&correct_by_content).unwrap_or_else(|_| "{}".to_string()),
+        ..existing.challenge
+    };
+    let action_hash = create_entry(&EntryTypes::MasteryChallenge(challenge.clone()))?;
+
+    // Point the challenge and attempt anchors at the graded version
+    delete_links_to(challenge_anchor_hash.clone(), LinkTypes::AgentToChallenge, &existing.action_hash)?;
+    create_link(challenge_anchor_hash, action_hash.clone(), LinkTypes::AgentToChallenge, ())?;
+    let attempts_anchor = checkpoint_attempts_anchor(&agent_id, &step_id)?;
+    delete_links_to(attempts_anchor.clone(), LinkTypes::AgentToChallenge, &existing.action_hash)?;
+    create_link(
+        attempts_anchor,
+        action_hash.clone(),
+        LinkTypes::AgentToChallenge,
+        LinkTag::new(step_id.as_bytes().to_vec()),
+    )?;
+
+    emit_write_signal("MasteryChallenge", &challenge.id, "submit_checkpoint");
+
+    Ok(CheckpointResult {
+        challenge: MasteryChallengeOutput { action_hash, challenge },
+        step_id,
+        score,
+        passing_score,
+        passed,
+    })
+}
+
 /// Get challenge history for current agent
 #[hdk_extern]
 pub fn get_challenge_history(_: ()) -> ExternResult<Vec<MasteryChallengeOutput>> {
@@ -5747,6 +12216,7 @@ fn get_point_amount(trigger: &str) -> i32 {
         "path_step_complete" => 5,
         "path_complete" => 100,
         "contribution" => 50,
+        "invitation" => 25,
         _ => 1,
     }
 }
@@ -5941,6 +12411,19 @@ fn get_or_create_content_presence(
 /// Earn points (and trigger recognition flow to contributors)
 #[hdk_extern]
 pub fn earn_points(input: EarnPointsInput) -> ExternResult<EarnPointsResult> {
+    // Contribution points come only from a steward accepting the contribution
+    if input.trigger == "contribution" {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Contribution points are awarded when a contribution is accepted".to_string()
+        )));
+    }
+    // Invitation points come only from someone signing up with an invite code
+    if input.trigger == "invitation" {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Invitation points are awarded when an invite code is used".to_string()
+        )));
+    }
+
     let agent_info = agent_info()?;
     let agent_id = agent_info.agent_initial_pubkey.to_string();
     let now = sys_time()?;
@@ -5966,23 +12449,7 @@ pub fn earn_points(input: EarnPointsInput) -> ExternResult<EarnPointsResult> {
         occurred_at: timestamp.clone(),
     };
 
-    let event_action_hash = create_entry(&EntryTypes::PointEvent(point_event.clone()))?;
-
-    // Create links for the event
-    let agent_events_anchor = StringAnchor::new("agent_points", &agent_id);
-    let agent_events_anchor_hash = hash_entry(&EntryTypes::StringAnchor(agent_events_anchor.clone()))?;
-    create_entry(&EntryTypes::StringAnchor(agent_events_anchor))?;
-    create_link(agent_events_anchor_hash, event_action_hash.clone(), LinkTypes::AgentToPointEvents, ())?;
-
-    if let Some(ref content_id) = input.content_id {
-        let content_events_anchor = StringAnchor::new("content_points", content_id);
-        let content_events_anchor_hash = hash_entry(&EntryTypes::StringAnchor(content_events_anchor.clone()))?;
-        create_entry(&EntryTypes::StringAnchor(content_events_anchor))?;
-        create_link(content_events_anchor_hash, event_action_hash.clone(), LinkTypes::ContentToPointEvents, ())?;
-    }
-
-    // Update or create point balance (hREA EconomicResource)
-    let balance_output = update_point_balance(&agent_id, points, &input.trigger, &event_id, &timestamp)?;
+    let (event_action_hash, balance_output) = record_point_event(&point_event)?;
 
     // Flow recognition to contributors (hREA Appreciation)
     // Recognition ALWAYS flows - ContributorPresence exists for all content,
@@ -6031,6 +12498,34 @@ pub fn earn_points(input: EarnPointsInput) -> ExternResult<EarnPointsResult> {
     })
 }
 
+/// Record a point event with its agent and content links, and update the
+/// agent's point balance (hREA EconomicResource)
+fn record_point_event(point_event: &PointEvent) -> ExternResult<(ActionHash, LearnerPointBalanceOutput)> {
+    let event_action_hash = create_entry(&EntryTypes::PointEvent(point_event.clone()))?;
+
+    // Create links for the event
+    let agent_events_anchor = StringAnchor::new("agent_points", &point_event.agent_id);
+    let agent_events_anchor_hash = hash_entry(&EntryTypes::StringAnchor(agent_events_anchor.clone()))?;
+    create_entry(&EntryTypes::StringAnchor(agent_events_anchor))?;
+    create_link(agent_events_anchor_hash, event_action_hash.clone(), LinkTypes::AgentToPointEvents, ())?;
+
+    if let Some(ref content_id) = point_event.content_id {
+        let content_events_anchor = StringAnchor::new("content_points", content_id);
+        let content_events_anchor_hash = hash_entry(&EntryTypes::StringAnchor(content_events_anchor.clone()))?;
+        create_entry(&EntryTypes::StringAnchor(content_events_anchor))?;
+        create_link(content_events_anchor_hash, event_action_hash.clone(), LinkTypes::ContentToPointEvents, ())?;
+    }
+
+    let balance_output = update_point_balance(
+        &point_event.agent_id,
+        point_event.points,
+        &point_event.trigger,
+        &point_event.id,
+        &point_event.occurred_at,
+    )?;
+    Ok((event_action_hash, balance_output))
+}
+
 /// Update or create point balance
 fn update_point_balance(
     agent_id: &str,
@@ -6120,6 +12615,7 @@ fn flow_recognition_to_contributor(
         "level_up" | "challenge_correct" => "content_mastery",
         "path_complete" | "path_step_complete" => "path_completion",
         "discovery" => "discovery_spark",
+        "contribution" => "contribution",
         _ => "content_engagement",
     };
 
@@ -6508,6 +13004,174 @@ pub struct GrantAccessInput {
     pub scholarship_reason: Option<String>,
 }
 
+/// Input for granting access on a payment settled outside the DNA
+///
+/// Only the doorway calls this, after verifying the payment proof.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GrantPaidAccessInput {
+    pub gate_id: String,
+    pub learner_agent_id: String,
+    /// Network the payment was settled on (e.g. "hrea", a token ledger name)
+    pub settlement_network: String,
+    /// Payment event ID on that network
+    pub payment_event_id: String,
+    pub payment_amount: f64,
+    pub payment_unit: String,
+}
+
+/// Input for minting a batch of voucher codes for a gate
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateVoucherBatchInput {
+    pub gate_id: String,
+    /// Agent of the minting steward; must hold the gate's steward credential
+    pub minted_by: String,
+    pub count: u32,
+    /// Redemptions allowed per code (default 1, single-use)
+    #[serde(default)]
+    pub max_redemptions: Option<u32>,
+    /// Grant type issued on redemption (default "lifetime")
+    #[serde(default)]
+    pub grant_type: Option<String>,
+    /// Days the codes stay redeemable (default: no expiry)
+    #[serde(default)]
+    pub valid_days: Option<u32>,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// A minted batch with its plaintext codes (only ever returned here)
+#[derive(Serialize, Deserialize, Debug)]
+pub struct VoucherBatchOutput {
+    pub action_hash: ActionHash,
+    pub batch: VoucherBatch,
+    pub codes: Vec<String>,
+}
+
+/// Input for redeeming a voucher code
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RedeemVoucherInput {
+    pub gate_id: String,
+    pub code: String,
+    pub learner_agent_id: String,
+}
+
+/// Redemption figures for a voucher batch
+#[derive(Serialize, Deserialize, Debug)]
+pub struct VoucherBatchReport {
+    pub batch: VoucherBatch,
+    /// Codes redeemed at least once
+    pub codes_redeemed: u32,
+    pub total_redemptions: u32,
+    /// Redemptions the batch allows (codes x redemptions per code)
+    pub capacity: u32,
+    /// total_redemptions / capacity
+    pub redemption_rate: f64,
+}
+
+/// Input for creating an invitation
+///
+/// Only the doorway calls this, for the signed-in member, along with the
+/// operator's cap on invitations per member.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateInvitationInput {
+    /// Human id of the inviting member
+    pub inviter_id: String,
+    /// Agent of the inviting member
+    pub inviter_agent_id: String,
+    /// Learning path the invitee is invited to
+    #[serde(default)]
+    pub path_id: Option<String>,
+    /// Gate the invitee gets access through; the inviter must be its steward
+    #[serde(default)]
+    pub gate_id: Option<String>,
+    /// Grant type issued through the gate (default "lifetime")
+    #[serde(default)]
+    pub grant_type: Option<String>,
+    /// Days the code stays usable (default: no expiry)
+    #[serde(default)]
+    pub valid_days: Option<u32>,
+    #[serde(default)]
+    pub note: Option<String>,
+    /// Invitations the inviter may hold, revoked ones aside (default: no cap)
+    #[serde(default)]
+    pub max_invitations: Option<u32>,
+}
+
+/// Output for invitation
+#[derive(Serialize, Deserialize, Debug)]
+pub struct InvitationOutput {
+    pub action_hash: ActionHash,
+    pub invitation: Invitation,
+}
+
+/// A created invitation with its plaintext code (only ever returned here)
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreatedInvitationOutput {
+    pub action_hash: ActionHash,
+    pub invitation: Invitation,
+    pub code: String,
+}
+
+/// Input for signing up with an invite code
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RedeemInvitationInput {
+    pub code: String,
+    /// Human id of the member signing up
+    pub invitee_id: String,
+    /// Agent of the member signing up, who gets any gate access
+    pub invitee_agent_id: String,
+}
+
+/// A redeemed invitation and what it gave
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RedeemedInvitationOutput {
+    pub invitation: InvitationOutput,
+    /// Points the inviter earned
+    pub points_awarded: i32,
+    /// Access granted through the invitation's gate
+    pub access_grant: Option<AccessGrantOutput>,
+}
+
+/// Input for withdrawing an unused invitation
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RevokeInvitationInput {
+    pub invitation_id: String,
+    pub inviter_id: String,
+}
+
+/// Input for renewing a subscription grant
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RenewAccessInput {
+    pub grant_id: String,
+    #[serde(default)]
+    pub payment_amount: Option<f64>,
+    #[serde(default)]
+    pub payment_unit: Option<String>,
+}
+
+/// Result of sweeping my grants for expiry
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GrantExpirySweepOutput {
+    pub checked: u32,
+    pub lapsed: Vec<AccessGrantOutput>,
+}
+
+/// An entitlement the current agent holds, with the gate details needed to render it
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ActiveGrantOutput {
+    pub grant: AccessGrantOutput,
+    pub gate_title: String,
+    pub gated_resource_type: String,
+    /// Resources this grant unlocks (every path for a bundle)
+    pub resource_ids: Vec<String>,
+}
+
+/// Default trial length when a gate doesn't set a subscription period
+const DEFAULT_TRIAL_DAYS: u32 = 7;
+
+/// Default subscription period when a gate doesn't set one
+const DEFAULT_SUBSCRIPTION_DAYS: u32 = 30;
+
 /// Create a steward credential
 #[hdk_extern]
 pub fn create_steward_credential(input: CreateStewardCredentialInput) -> ExternResult<StewardCredentialOutput> {
@@ -6663,6 +13327,18 @@ pub fn create_premium_gate(input: CreatePremiumGateInput) -> ExternResult<Premiu
 
     let gate_id = format!("gate-{}-{}", first_resource, timestamp);
 
+    // Bundles cover several paths at one price; record what they'd cost separately
+    let bundle_list_price = if input.gated_resource_type == "path_bundle" {
+        if input.gated_resource_ids.len() < 2 {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "A path bundle must cover at least two paths".to_string()
+            )));
+        }
+        bundle_list_price(&input.gated_resource_ids, input.price_unit.as_deref())?
+    } else {
+        None
+    };
+
     let gate = PremiumGate {
         id: gate_id.clone(),
         steward_credential_id: input.steward_credential_id.clone(),
@@ -6681,6 +13357,7 @@ pub fn create_premium_gate(input: CreatePremiumGateInput) -> ExternResult<Premiu
         price_unit: input.price_unit,
         subscription_period_days: input.subscription_period_days,
         min_amount: input.min_amount,
+        bundle_list_price,
         steward_share_percent: input.steward_share_percent,
         commons_share_percent: input.commons_share_percent,
         contributor_share_percent: input.contributor_share_percent,
@@ -6737,151 +13414,785 @@ pub fn create_premium_gate(input: CreatePremiumGateInput) -> ExternResult<Premiu
         create_link(contributor_anchor_hash, action_hash.clone(), LinkTypes::GateByContributor, ())?;
     }
 
-    Ok(PremiumGateOutput {
-        action_hash,
-        gate,
-    })
-}
+    Ok(PremiumGateOutput {
+        action_hash,
+        gate,
+    })
+}
+
+/// Get premium gate by ID
+#[hdk_extern]
+pub fn get_premium_gate(gate_id: String) -> ExternResult<Option<PremiumGateOutput>> {
+    let id_anchor = StringAnchor::new("gate_id", &gate_id);
+    let id_anchor_hash = hash_entry(&EntryTypes::StringAnchor(id_anchor))?;
+
+    let query = LinkQuery::try_new(id_anchor_hash, LinkTypes::IdToGate)?;
+    let links = get_links(query, GetStrategy::default())?;
+
+    if let Some(link) = links.first() {
+        let action_hash = ActionHash::try_from(link.target.clone())
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid gate hash".to_string())))?;
+
+        let record = get(action_hash.clone(), GetOptions::default())?;
+        if let Some(rec) = record {
+            if let Some(gate) = rec.entry().to_app_option::<PremiumGate>().ok().flatten() {
+                return Ok(Some(PremiumGateOutput { action_hash, gate }));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Get gates for a resource
+#[hdk_extern]
+pub fn get_gates_for_resource(resource_id: String) -> ExternResult<Vec<PremiumGateOutput>> {
+    let resource_anchor = StringAnchor::new("resource_gate", &resource_id);
+    let resource_anchor_hash = hash_entry(&EntryTypes::StringAnchor(resource_anchor))?;
+
+    let query = LinkQuery::try_new(resource_anchor_hash, LinkTypes::ResourceToGate)?;
+    let links = get_links(query, GetStrategy::default())?;
+
+    let mut results = Vec::new();
+    for link in links {
+        let action_hash = ActionHash::try_from(link.target)
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid gate hash".to_string())))?;
+
+        let record = get(action_hash.clone(), GetOptions::default())?;
+        if let Some(rec) = record {
+            if let Some(gate) = rec.entry().to_app_option::<PremiumGate>().ok().flatten() {
+                if gate.is_active {
+                    results.push(PremiumGateOutput { action_hash, gate });
+                }
+            }
+        }
+    }
+
+    Ok(results)
+}
+
+/// Grant access through a gate
+#[hdk_extern]
+pub fn grant_access(input: GrantAccessInput) -> ExternResult<AccessGrantOutput> {
+    let agent_info = agent_info()?;
+    let learner_id = agent_info.agent_initial_pubkey.to_string();
+
+    // Get the gate
+    let gate = get_premium_gate(input.gate_id.clone())?
+        .ok_or_else(|| wasm_error!(WasmErrorInner::Guest("Gate not found".to_string())))?;
+
+    commit_access_grant(&gate, &learner_id, &input, None, "{}".to_string())
+}
+
+/// Create an access grant for a learner and index it (shared by direct and
+/// settled grants)
+fn commit_access_grant(
+    gate: &PremiumGateOutput,
+    learner_id: &str,
+    input: &GrantAccessInput,
+    payment_event_id: Option<String>,
+    metadata_json: String,
+) -> ExternResult<AccessGrantOutput> {
+    let learner_id = learner_id.to_string();
+    let now = sys_time()?;
+    let timestamp = format!("{:?}", now);
+
+    // Validate access type
+    if !ACCESS_GRANT_TYPES.contains(&input.grant_type.as_str()) {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            format!("Invalid grant type: {}. Must be one of: {:?}", input.grant_type, ACCESS_GRANT_TYPES)
+        )));
+    }
+
+    let grant_id = format!("grant-{}-{}-{}", input.gate_id, learner_id, timestamp);
+
+    // Calculate expiration based on subscription period if applicable
+    let window_end = match input.grant_type.as_str() {
+        "subscription" => Some(access_window_end(
+            now,
+            gate.gate.subscription_period_days.unwrap_or(DEFAULT_SUBSCRIPTION_DAYS),
+        )),
+        "trial" => Some(access_window_end(now, gate.gate.subscription_period_days.unwrap_or(DEFAULT_TRIAL_DAYS))),
+        _ => None,
+    };
+    let (valid_until, valid_until_micros) = match window_end {
+        Some((until, micros)) => (Some(until), Some(micros)),
+        None => (None, None),
+    };
+    let renewal_due_at = if input.grant_type == "subscription" { valid_until.clone() } else { None };
+
+    let grant = AccessGrant {
+        id: grant_id.clone(),
+        gate_id: input.gate_id.clone(),
+        learner_agent_id: learner_id.clone(),
+        grant_type: input.grant_type.clone(),
+        granted_via: input.granted_via.clone(),
+        payment_event_id,
+        payment_amount: input.payment_amount,
+        payment_unit: input.payment_unit.clone(),
+        scholarship_sponsor_id: input.scholarship_sponsor_id.clone(),
+        scholarship_reason: input.scholarship_reason.clone(),
+        granted_at: timestamp.clone(),
+        valid_until,
+        renewal_due_at,
+        is_active: true,
+        revoked_at: None,
+        revoke_reason: None,
+        status: "active".to_string(),
+        valid_until_micros,
+        renewal_count: 0,
+        lapsed_at: None,
+        metadata_json,
+        created_at: timestamp.clone(),
+    };
+
+    let action_hash = create_entry(&EntryTypes::AccessGrant(grant.clone()))?;
+
+    // Create ID lookup link
+    let id_anchor = StringAnchor::new("grant_id", &grant_id);
+    let id_anchor_hash = hash_entry(&EntryTypes::StringAnchor(id_anchor.clone()))?;
+    create_entry(&EntryTypes::StringAnchor(id_anchor))?;
+    create_link(id_anchor_hash, action_hash.clone(), LinkTypes::IdToAccessGrant, ())?;
+
+    // Create learner lookup link
+    let learner_anchor = StringAnchor::new("learner_grants", &learner_id);
+    let learner_anchor_hash = hash_entry(&EntryTypes::StringAnchor(learner_anchor.clone()))?;
+    create_entry(&EntryTypes::StringAnchor(learner_anchor))?;
+    create_link(learner_anchor_hash, action_hash.clone(), LinkTypes::LearnerToGrant, ())?;
+
+    // Create gate lookup link
+    let gate_anchor = StringAnchor::new("gate_grants", &input.gate_id);
+    let gate_anchor_hash = hash_entry(&EntryTypes::StringAnchor(gate_anchor.clone()))?;
+    create_entry(&EntryTypes::StringAnchor(gate_anchor))?;
+    create_link(gate_anchor_hash, action_hash.clone(), LinkTypes::GateToGrant, ())?;
+
+    // Create grant type link
+    let type_anchor = StringAnchor::new("grant_type", &input.grant_type);
+    let type_anchor_hash = hash_entry(&EntryTypes::StringAnchor(type_anchor.clone()))?;
+    create_entry(&EntryTypes::StringAnchor(type_anchor))?;
+    create_link(type_anchor_hash, action_hash.clone(), LinkTypes::GrantByType, ())?;
+
+    // If there was payment, create revenue record
+    if let Some(amount) = input.payment_amount {
+        if amount > 0.0 {
+            create_steward_revenue(&gate.gate, &grant_id, &learner_id, amount, input.payment_unit.as_deref(), &timestamp)?;
+        }
+    }
+
+    Ok(AccessGrantOutput {
+        action_hash,
+        grant,
+    })
+}
+
+/// Grant access for a payment settled on an external network.
+///
+/// Each payment event grants access once: redeeming it again for the same
+/// learner and gate returns the existing grant, any other reuse fails.
+#[hdk_extern]
+pub fn grant_paid_access(input: GrantPaidAccessInput) -> ExternResult<AccessGrantOutput> {
+    if input.settlement_network.is_empty() || input.payment_event_id.is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "settlement_network and payment_event_id are required".to_string()
+        )));
+    }
+
+    let payment_key = format!("{}:{}", input.settlement_network, input.payment_event_id);
+    let payment_anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("grant_payment", &payment_key)))?;
+    let query = LinkQuery::try_new(payment_anchor_hash.clone(), LinkTypes::PaymentToGrant)?;
+    for link in get_links(query, GetStrategy::default())? {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(action_hash.clone(), GetOptions::default())? else {
+            continue;
+        };
+        if let Some(grant) = record.entry().to_app_option::<AccessGrant>().ok().flatten() {
+            if grant.gate_id == input.gate_id && grant.learner_agent_id == input.learner_agent_id {
+                return Ok(AccessGrantOutput { action_hash, grant });
+            }
+            return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                "Payment {} has already been redeemed",
+                payment_key
+            ))));
+        }
+    }
+
+    let gate = get_premium_gate(input.gate_id.clone())?
+        .ok_or_else(|| wasm_error!(WasmErrorInner::Guest("Gate not found".to_string())))?;
+    if !gate.gate.is_active {
+        return Err(wasm_error!(WasmErrorInner::Guest("Gate is not active".to_string())));
+    }
+
+    let grant_input = GrantAccessInput {
+        gate_id: input.gate_id.clone(),
+        grant_type: if gate.gate.pricing_model == "subscription" { "subscription" } else { "lifetime" }.to_string(),
+        granted_via: "payment".to_string(),
+        payment_amount: Some(input.payment_amount),
+        payment_unit: Some(input.payment_unit.clone()),
+        scholarship_sponsor_id: None,
+        scholarship_reason: None,
+    };
+    let metadata = serde_json::json!({ "settlement_network": input.settlement_network });
+    let output = commit_access_grant(
+        &gate,
+        &input.learner_agent_id,
+        &grant_input,
+        Some(input.payment_event_id.clone()),
+        metadata.to_string(),
+    )?;
+
+    create_entry(&EntryTypes::StringAnchor(StringAnchor::new("grant_payment", &payment_key)))?;
+    create_link(payment_anchor_hash, output.action_hash.clone(), LinkTypes::PaymentToGrant, ())?;
+    emit_write_signal("access_grant", &output.grant.id, "grant_paid_access");
+
+    Ok(output)
+}
+
+/// Most codes minted in one batch
+const MAX_VOUCHER_BATCH_SIZE: u32 = 500;
+
+/// Voucher code alphabet (no 0/O or 1/I, so codes survive being read aloud)
+const VOUCHER_ALPHABET: &[u8; 32] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+/// Characters per voucher code, written in groups of four
+const VOUCHER_CODE_LEN: usize = 12;
+
+/// Canonical form of a code as typed: uppercase, separators dropped
+fn normalize_voucher_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+/// Hex SHA-256 of a normalized code, the only form of it that's stored
+fn voucher_code_hash(normalized: &str) -> ExternResult<String> {
+    Ok(to_hex(&sha256_digest(normalized.as_bytes())?))
+}
+
+/// Random voucher code, e.g. "K7QH-M2XP-9TWD"
+fn generate_voucher_code() -> ExternResult<String> {
+    // 256 is a multiple of the alphabet size, so `% 32` is unbiased
+    let bytes = random_bytes(VOUCHER_CODE_LEN as u32)?;
+    let chars: Vec<char> = bytes
+        .iter()
+        .map(|b| VOUCHER_ALPHABET[(*b as usize) % VOUCHER_ALPHABET.len()] as char)
+        .collect();
+    Ok(chars
+        .chunks(4)
+        .map(|group| group.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("-"))
+}
+
+/// Mint a batch of voucher codes for a gate.
+///
+/// Only the gate's steward can mint. The plaintext codes are in the output and
+/// nowhere else; the DHT only holds their hashes.
+#[hdk_extern]
+pub fn create_voucher_batch(input: CreateVoucherBatchInput) -> ExternResult<VoucherBatchOutput> {
+    if input.count == 0 || input.count > MAX_VOUCHER_BATCH_SIZE {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "count must be between 1 and {}",
+            MAX_VOUCHER_BATCH_SIZE
+        ))));
+    }
+    let max_redemptions = input.max_redemptions.unwrap_or(1);
+    if max_redemptions == 0 {
+        return Err(wasm_error!(WasmErrorInner::Guest("max_redemptions must be at least 1".to_string())));
+    }
+    let grant_type = input.grant_type.clone().unwrap_or_else(|| "lifetime".to_string());
+    if !ACCESS_GRANT_TYPES.contains(&grant_type.as_str()) {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            format!("Invalid grant type: {}. Must be one of: {:?}", grant_type, ACCESS_GRANT_TYPES)
+        )));
+    }
+
+    let gate = get_premium_gate(input.gate_id.clone())?
+        .ok_or_else(|| wasm_error!(WasmErrorInner::Guest("Gate not found".to_string())))?;
+    let credential = get_steward_credential(gate.gate.steward_credential_id.clone())?
+        .ok_or_else(|| wasm_error!(WasmErrorInner::Guest("Gate steward credential not found".to_string())))?;
+    if credential.credential.agent_id != input.minted_by {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the gate's steward can mint vouchers".to_string()
+        )));
+    }
+
+    let now = sys_time()?;
+    let timestamp = format!("{:?}", now);
+    let batch_id = format!("voucher-batch-{}-{}", input.gate_id, now.as_micros());
+    let (valid_until, valid_until_micros) = match input.valid_days {
+        Some(days) => {
+            let (until, micros) = access_window_end(now, days);
+            (Some(until), Some(micros))
+        }
+        None => (None, None),
+    };
+
+    let batch = VoucherBatch {
+        id: batch_id.clone(),
+        gate_id: input.gate_id.clone(),
+        minted_by: input.minted_by.clone(),
+        code_count: input.count,
+        max_redemptions,
+        grant_type,
+        valid_until,
+        valid_until_micros,
+        note: input.note.clone(),
+        created_at: timestamp.clone(),
+    };
+    let action_hash = create_entry(&EntryTypes::VoucherBatch(batch.clone()))?;
+
+    let id_anchor = StringAnchor::new("voucher_batch_id", &batch_id);
+    let id_anchor_hash = hash_entry(&EntryTypes::StringAnchor(id_anchor.clone()))?;
+    create_entry(&EntryTypes::StringAnchor(id_anchor))?;
+    create_link(id_anchor_hash, action_hash.clone(), LinkTypes::IdToVoucherBatch, ())?;
+
+    for key in [input.gate_id.as_str(), "all"] {
+        let gate_anchor = StringAnchor::new("gate_voucher_batches", key);
+        let gate_anchor_hash = hash_entry(&EntryTypes::StringAnchor(gate_anchor.clone()))?;
+        create_entry(&EntryTypes::StringAnchor(gate_anchor))?;
+        create_link(gate_anchor_hash, action_hash.clone(), LinkTypes::GateToVoucherBatch, ())?;
+    }
+
+    let batch_anchor = StringAnchor::new("batch_vouchers", &batch_id);
+    let batch_anchor_hash = hash_entry(&EntryTypes::StringAnchor(batch_anchor.clone()))?;
+    create_entry(&EntryTypes::StringAnchor(batch_anchor))?;
+
+    let mut codes = Vec::with_capacity(input.count as usize);
+    for index in 0..input.count {
+        let code = generate_voucher_code()?;
+        let code_hash = voucher_code_hash(&normalize_voucher_code(&code))?;
+        let voucher = Voucher {
+            id: format!("{}-{}", batch_id, index),
+            batch_id: batch_id.clone(),
+            gate_id: input.gate_id.clone(),
+            code_hash: code_hash.clone(),
+            max_redemptions,
+            redemption_count: 0,
+            redeemed_by_json: "[]".to_string(),
+            is_active: true,
+            created_at: timestamp.clone(),
+            updated_at: timestamp.clone(),
+        };
+        let voucher_hash = create_entry(&EntryTypes::Voucher(voucher))?;
+        create_link(batch_anchor_hash.clone(), voucher_hash.clone(), LinkTypes::BatchToVoucher, ())?;
+
+        let code_anchor = StringAnchor::new("voucher_code", &code_hash);
+        let code_anchor_hash = hash_entry(&EntryTypes::StringAnchor(code_anchor.clone()))?;
+        create_entry(&EntryTypes::StringAnchor(code_anchor))?;
+        create_link(code_anchor_hash, voucher_hash, LinkTypes::CodeToVoucher, ())?;
+
+        codes.push(code);
+    }
+
+    Ok(VoucherBatchOutput { action_hash, batch, codes })
+}
+
+/// Get a voucher batch by ID
+fn get_voucher_batch(batch_id: &str) -> ExternResult<Option<VoucherBatch>> {
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("voucher_batch_id", batch_id)))?;
+    let query = LinkQuery::try_new(anchor_hash, LinkTypes::IdToVoucherBatch)?;
+    for link in get_links(query, GetStrategy::default())? {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        if let Some(record) = get(action_hash, GetOptions::default())? {
+            if let Some(batch) = record.entry().to_app_option::<VoucherBatch>().ok().flatten() {
+                return Ok(Some(batch));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Vouchers behind an anchor (a code hash or a batch)
+fn get_linked_vouchers(
+    anchor_kind: &str,
+    key: &str,
+    link_type: LinkTypes,
+) -> ExternResult<Vec<(ActionHash, Voucher)>> {
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new(anchor_kind, key)))?;
+    let query = LinkQuery::try_new(anchor_hash, link_type)?;
+    let mut vouchers = Vec::new();
+    for link in get_links(query, GetStrategy::default())? {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        if let Some(record) = get(action_hash.clone(), GetOptions::default())? {
+            if let Some(voucher) = record.entry().to_app_option::<Voucher>().ok().flatten() {
+                vouchers.push((action_hash, voucher));
+            }
+        }
+    }
+    Ok(vouchers)
+}
+
+/// Redeem a voucher code for access through its gate.
+///
+/// Only the doorway calls this, for the signed-in learner. Each learner can
+/// redeem a given code once, and a code stops working once it has been
+/// redeemed `max_redemptions` times or its batch has expired.
+#[hdk_extern]
+pub fn redeem_voucher(input: RedeemVoucherInput) -> ExternResult<AccessGrantOutput> {
+    let normalized = normalize_voucher_code(&input.code);
+    if normalized.is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest("Voucher code is required".to_string())));
+    }
+    let code_hash = voucher_code_hash(&normalized)?;
+    let invalid = || wasm_error!(WasmErrorInner::Guest("Invalid voucher code".to_string()));
+
+    let (voucher_hash, mut voucher) = get_linked_vouchers("voucher_code", &code_hash, LinkTypes::CodeToVoucher)?
+        .into_iter()
+        .next()
+        .ok_or_else(invalid)?;
+    if voucher.gate_id != input.gate_id || !voucher.is_active {
+        return Err(invalid());
+    }
+    if voucher.redemption_count >= voucher.max_redemptions {
+        return Err(wasm_error!(WasmErrorInner::Guest("Voucher has been fully redeemed".to_string())));
+    }
+    let mut redeemed_by: Vec<String> = serde_json::from_str(&voucher.redeemed_by_json).unwrap_or_default();
+    if redeemed_by.contains(&input.learner_agent_id) {
+        return Err(wasm_error!(WasmErrorInner::Guest("Voucher already redeemed by this learner".to_string())));
+    }
+
+    let batch = get_voucher_batch(&voucher.batch_id)?.ok_or_else(invalid)?;
+    let now = sys_time()?;
+    if batch.valid_until_micros.is_some_and(|until| until <= now.as_micros()) {
+        return Err(wasm_error!(WasmErrorInner::Guest("Voucher has expired".to_string())));
+    }
+
+    let gate = get_premium_gate(input.gate_id.clone())?
+        .ok_or_else(|| wasm_error!(WasmErrorInner::Guest("Gate not found".to_string())))?;
+    if !gate.gate.is_active {
+        return Err(wasm_error!(WasmErrorInner::Guest("Gate is not active".to_string())));
+    }
+
+    let grant_input = GrantAccessInput {
+        gate_id: input.gate_id.clone(),
+        grant_type: batch.grant_type.clone(),
+        granted_via: "voucher".to_string(),
+        payment_amount: None,
+        payment_unit: None,
+        scholarship_sponsor_id: None,
+        scholarship_reason: None,
+    };
+    let metadata = serde_json::json!({ "voucher_batch_id": batch.id, "voucher_id": voucher.id });
+    let output = commit_access_grant(&gate, &input.learner_agent_id, &grant_input, None, metadata.to_string())?;
 
-/// Get premium gate by ID
-#[hdk_extern]
-pub fn get_premium_gate(gate_id: String) -> ExternResult<Option<PremiumGateOutput>> {
-    let id_anchor = StringAnchor::new("gate_id", &gate_id);
-    let id_anchor_hash = hash_entry(&EntryTypes::StringAnchor(id_anchor))?;
+    // Count the redemption and re-point the voucher's links at the update
+    redeemed_by.push(input.learner_agent_id.clone());
+    voucher.redemption_count += 1;
+    voucher.redeemed_by_json = serde_json::to_string(&redeemed_by)
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Serialize error: {}", e))))?;
+    voucher.updated_at = format!("{:?}", now);
+    let new_hash = update_entry(voucher_hash.clone(), &EntryTypes::Voucher(voucher.clone()))?;
 
-    let query = LinkQuery::try_new(id_anchor_hash, LinkTypes::IdToGate)?;
-    let links = get_links(query, GetStrategy::default())?;
+    let code_anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("voucher_code", &code_hash)))?;
+    delete_links_to(code_anchor_hash.clone(), LinkTypes::CodeToVoucher, &voucher_hash)?;
+    create_link(code_anchor_hash, new_hash.clone(), LinkTypes::CodeToVoucher, ())?;
 
-    if let Some(link) = links.first() {
-        let action_hash = ActionHash::try_from(link.target.clone())
-            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid gate hash".to_string())))?;
+    let batch_anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("batch_vouchers", &voucher.batch_id)))?;
+    delete_links_to(batch_anchor_hash.clone(), LinkTypes::BatchToVoucher, &voucher_hash)?;
+    create_link(batch_anchor_hash, new_hash, LinkTypes::BatchToVoucher, ())?;
 
-        let record = get(action_hash.clone(), GetOptions::default())?;
-        if let Some(rec) = record {
-            if let Some(gate) = rec.entry().to_app_option::<PremiumGate>().ok().flatten() {
-                return Ok(Some(PremiumGateOutput { action_hash, gate }));
-            }
-        }
-    }
+    emit_write_signal("access_grant", &output.grant.id, "redeem_voucher");
 
-    Ok(None)
+    Ok(output)
 }
 
-/// Get gates for a resource
+/// Redemption figures for each voucher batch of a gate, or of every gate
+/// when no gate is given
 #[hdk_extern]
-pub fn get_gates_for_resource(resource_id: String) -> ExternResult<Vec<PremiumGateOutput>> {
-    let resource_anchor = StringAnchor::new("resource_gate", &resource_id);
-    let resource_anchor_hash = hash_entry(&EntryTypes::StringAnchor(resource_anchor))?;
+pub fn get_voucher_redemption_report(gate_id: Option<String>) -> ExternResult<Vec<VoucherBatchReport>> {
+    let key = gate_id.unwrap_or_else(|| "all".to_string());
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("gate_voucher_batches", &key)))?;
+    let query = LinkQuery::try_new(anchor_hash, LinkTypes::GateToVoucherBatch)?;
+
+    let mut reports = Vec::new();
+    for link in get_links(query, GetStrategy::default())? {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(action_hash, GetOptions::default())? else {
+            continue;
+        };
+        let Some(batch) = record.entry().to_app_option::<VoucherBatch>().ok().flatten() else {
+            continue;
+        };
 
-    let query = LinkQuery::try_new(resource_anchor_hash, LinkTypes::ResourceToGate)?;
-    let links = get_links(query, GetStrategy::default())?;
+        let vouchers = get_linked_vouchers("batch_vouchers", &batch.id, LinkTypes::BatchToVoucher)?;
+        let codes_redeemed = vouchers.iter().filter(|(_, v)| v.redemption_count > 0).count() as u32;
+        let total_redemptions: u32 = vouchers.iter().map(|(_, v)| v.redemption_count).sum();
+        let capacity = batch.code_count * batch.max_redemptions;
+        let redemption_rate = if capacity > 0 { total_redemptions as f64 / capacity as f64 } else { 0.0 };
+
+        reports.push(VoucherBatchReport {
+            batch,
+            codes_redeemed,
+            total_redemptions,
+            capacity,
+            redemption_rate,
+        });
+    }
+    reports.sort_by(|a, b| b.batch.created_at.cmp(&a.batch.created_at));
 
-    let mut results = Vec::new();
-    for link in links {
-        let action_hash = ActionHash::try_from(link.target)
-            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid gate hash".to_string())))?;
+    Ok(reports)
+}
 
-        let record = get(action_hash.clone(), GetOptions::default())?;
-        if let Some(rec) = record {
-            if let Some(gate) = rec.entry().to_app_option::<PremiumGate>().ok().flatten() {
-                if gate.is_active {
-                    results.push(PremiumGateOutput { action_hash, gate });
-                }
+// =============================================================================
+// Invitations
+// =============================================================================
+
+/// Invitations behind an anchor (an invitation ID, an inviter or a code hash)
+fn get_linked_invitations(
+    anchor_kind: &str,
+    key: &str,
+    link_type: LinkTypes,
+) -> ExternResult<Vec<(ActionHash, Invitation)>> {
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new(anchor_kind, key)))?;
+    let query = LinkQuery::try_new(anchor_hash, link_type)?;
+    let mut invitations = Vec::new();
+    for link in get_links(query, GetStrategy::default())? {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        if let Some(record) = get(action_hash.clone(), GetOptions::default())? {
+            if let Some(invitation) = record.entry().to_app_option::<Invitation>().ok().flatten() {
+                invitations.push((action_hash, invitation));
             }
         }
     }
-
-    Ok(results)
+    Ok(invitations)
 }
 
-/// Grant access through a gate
-#[hdk_extern]
-pub fn grant_access(input: GrantAccessInput) -> ExternResult<AccessGrantOutput> {
-    let agent_info = agent_info()?;
-    let learner_id = agent_info.agent_initial_pubkey.to_string();
-    let now = sys_time()?;
-    let timestamp = format!("{:?}", now);
-
-    // Get the gate
-    let gate = get_premium_gate(input.gate_id.clone())?
-        .ok_or_else(|| wasm_error!(WasmErrorInner::Guest("Gate not found".to_string())))?;
+/// Anchors an invitation is linked from
+fn invitation_anchors(invitation: &Invitation) -> [(StringAnchor, LinkTypes); 3] {
+    [
+        (StringAnchor::new("invitation_id", &invitation.id), LinkTypes::IdToInvitation),
+        (StringAnchor::new("inviter_invitations", &invitation.inviter_id), LinkTypes::InviterToInvitation),
+        (StringAnchor::new("invitation_code", &invitation.code_hash), LinkTypes::CodeToInvitation),
+    ]
+}
 
-    // Validate access type
-    if !ACCESS_GRANT_TYPES.contains(&input.grant_type.as_str()) {
-        return Err(wasm_error!(WasmErrorInner::Guest(
-            format!("Invalid grant type: {}. Must be one of: {:?}", input.grant_type, ACCESS_GRANT_TYPES)
-        )));
+/// Update an invitation and re-point its links at the update
+fn save_invitation(previous: &ActionHash, invitation: Invitation) -> ExternResult<InvitationOutput> {
+    let action_hash = update_entry(previous.clone(), &EntryTypes::Invitation(invitation.clone()))?;
+    for (anchor, link_type) in invitation_anchors(&invitation) {
+        let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
+        delete_links_to(anchor_hash.clone(), link_type, previous)?;
+        create_link(anchor_hash, action_hash.clone(), link_type, ())?;
     }
+    Ok(InvitationOutput { action_hash, invitation })
+}
 
-    let grant_id = format!("grant-{}-{}-{}", input.gate_id, learner_id, timestamp);
+/// The pending, unexpired invitation behind a code as typed
+fn usable_invitation(code: &str) -> ExternResult<Option<(ActionHash, Invitation)>> {
+    let normalized = normalize_voucher_code(code);
+    if normalized.is_empty() {
+        return Ok(None);
+    }
+    let code_hash = voucher_code_hash(&normalized)?;
+    let now = sys_time()?.as_micros();
+    Ok(get_linked_invitations("invitation_code", &code_hash, LinkTypes::CodeToInvitation)?
+        .into_iter()
+        .find(|(_, invitation)| {
+            invitation.status == "pending" && !invitation.valid_until_micros.is_some_and(|until| until <= now)
+        }))
+}
 
-    // Calculate expiration based on subscription period if applicable
-    let valid_until = match input.grant_type.as_str() {
-        "subscription" => {
-            let days = gate.gate.subscription_period_days.unwrap_or(30);
-            Some(format!("{}+{}d", timestamp, days))
+/// Create an invitation with a fresh invite code.
+///
+/// Invite codes look like voucher codes. The plaintext code is in the output
+/// and nowhere else; the DHT only holds its hash. An invitation granting gate
+/// access can only come from the gate's steward.
+#[hdk_extern]
+pub fn create_invitation(input: CreateInvitationInput) -> ExternResult<CreatedInvitationOutput> {
+    if let Some(max) = input.max_invitations {
+        let held = get_linked_invitations("inviter_invitations", &input.inviter_id, LinkTypes::InviterToInvitation)?
+            .iter()
+            .filter(|(_, invitation)| invitation.status != "revoked")
+            .count() as u32;
+        if held >= max {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                "Invitation limit reached ({} per member)",
+                max
+            ))));
         }
-        _ => None,
+    }
+    if let Some(ref path_id) = input.path_id {
+        if find_path_action_hash(path_id)?.is_none() {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!("Path not found: {}", path_id))));
+        }
+    }
+    let grant_type = match input.gate_id {
+        Some(ref gate_id) => {
+            let grant_type = input.grant_type.clone().unwrap_or_else(|| "lifetime".to_string());
+            if !ACCESS_GRANT_TYPES.contains(&grant_type.as_str()) {
+                return Err(wasm_error!(WasmErrorInner::Guest(
+                    format!("Invalid grant type: {}. Must be one of: {:?}", grant_type, ACCESS_GRANT_TYPES)
+                )));
+            }
+            let gate = get_premium_gate(gate_id.clone())?
+                .ok_or_else(|| wasm_error!(WasmErrorInner::Guest("Gate not found".to_string())))?;
+            let credential = get_steward_credential(gate.gate.steward_credential_id.clone())?
+                .ok_or_else(|| wasm_error!(WasmErrorInner::Guest("Gate steward credential not found".to_string())))?;
+            if credential.credential.agent_id != input.inviter_agent_id {
+                return Err(wasm_error!(WasmErrorInner::Guest(
+                    "Only the gate's steward can invite with access to it".to_string()
+                )));
+            }
+            Some(grant_type)
+        }
+        None => None,
     };
 
-    let grant = AccessGrant {
-        id: grant_id.clone(),
-        gate_id: input.gate_id.clone(),
-        learner_agent_id: learner_id.clone(),
-        grant_type: input.grant_type.clone(),
-        granted_via: input.granted_via.clone(),
-        payment_event_id: None,
-        payment_amount: input.payment_amount,
-        payment_unit: input.payment_unit.clone(),
-        scholarship_sponsor_id: input.scholarship_sponsor_id.clone(),
-        scholarship_reason: input.scholarship_reason.clone(),
-        granted_at: timestamp.clone(),
+    let now = sys_time()?;
+    let timestamp = format!("{:?}", now);
+    let (valid_until, valid_until_micros) = match input.valid_days {
+        Some(days) => {
+            let (until, micros) = access_window_end(now, days);
+            (Some(until), Some(micros))
+        }
+        None => (None, None),
+    };
+    let code = generate_voucher_code()?;
+    let invitation = Invitation {
+        id: format!("invitation-{}-{}", input.inviter_id, now.as_micros()),
+        inviter_id: input.inviter_id,
+        inviter_agent_id: input.inviter_agent_id,
+        code_hash: voucher_code_hash(&normalize_voucher_code(&code))?,
+        path_id: input.path_id,
+        gate_id: input.gate_id,
+        grant_type,
         valid_until,
-        renewal_due_at: None,
-        is_active: true,
-        revoked_at: None,
-        revoke_reason: None,
-        metadata_json: "{}".to_string(),
+        valid_until_micros,
+        status: "pending".to_string(),
+        redeemed_by: None,
+        redeemed_at: None,
+        note: input.note,
         created_at: timestamp.clone(),
+        updated_at: timestamp,
     };
+    let action_hash = create_entry(&EntryTypes::Invitation(invitation.clone()))?;
+    for (anchor, link_type) in invitation_anchors(&invitation) {
+        let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor.clone()))?;
+        create_entry(&EntryTypes::StringAnchor(anchor))?;
+        create_link(anchor_hash, action_hash.clone(), link_type, ())?;
+    }
 
-    let action_hash = create_entry(&EntryTypes::AccessGrant(grant.clone()))?;
+    Ok(CreatedInvitationOutput { action_hash, invitation, code })
+}
 
-    // Create ID lookup link
-    let id_anchor = StringAnchor::new("grant_id", &grant_id);
-    let id_anchor_hash = hash_entry(&EntryTypes::StringAnchor(id_anchor.clone()))?;
-    create_entry(&EntryTypes::StringAnchor(id_anchor))?;
-    create_link(id_anchor_hash, action_hash.clone(), LinkTypes::IdToAccessGrant, ())?;
+/// The invitation behind a code, if it can still be used
+#[hdk_extern]
+pub fn check_invitation(code: String) -> ExternResult<Option<InvitationOutput>> {
+    Ok(usable_invitation(&code)?.map(|(action_hash, invitation)| InvitationOutput { action_hash, invitation }))
+}
 
-    // Create learner lookup link
-    let learner_anchor = StringAnchor::new("learner_grants", &learner_id);
-    let learner_anchor_hash = hash_entry(&EntryTypes::StringAnchor(learner_anchor.clone()))?;
-    create_entry(&EntryTypes::StringAnchor(learner_anchor))?;
-    create_link(learner_anchor_hash, action_hash.clone(), LinkTypes::LearnerToGrant, ())?;
+/// Use an invite code for a member who is signing up.
+///
+/// Only the doorway calls this, at registration and before it creates the
+/// invitee's identity, so one code can't back two signups. Links the invitee
+/// to the inviter, awards the inviter `invitation` points and, when the
+/// invitation carries a gate, grants the invitee access through it (skipped
+/// if the gate has since closed).
+#[hdk_extern]
+pub fn redeem_invitation(input: RedeemInvitationInput) -> ExternResult<RedeemedInvitationOutput> {
+    let (action_hash, mut invitation) = usable_invitation(&input.code)?
+        .ok_or_else(|| wasm_error!(WasmErrorInner::Guest("Invalid or expired invite code".to_string())))?;
+    if invitation.inviter_id == input.invitee_id {
+        return Err(wasm_error!(WasmErrorInner::Guest("Members can't use their own invite code".to_string())));
+    }
 
-    // Create gate lookup link
-    let gate_anchor = StringAnchor::new("gate_grants", &input.gate_id);
-    let gate_anchor_hash = hash_entry(&EntryTypes::StringAnchor(gate_anchor.clone()))?;
-    create_entry(&EntryTypes::StringAnchor(gate_anchor))?;
-    create_link(gate_anchor_hash, action_hash.clone(), LinkTypes::GateToGrant, ())?;
+    let now = sys_time()?;
+    let timestamp = format!("{:?}", now);
+    let access_grant = match (&invitation.gate_id, &invitation.grant_type) {
+        (Some(gate_id), Some(grant_type)) => match get_premium_gate(gate_id.clone())? {
+            Some(gate) if gate.gate.is_active => {
+                let grant_input = GrantAccessInput {
+                    gate_id: gate_id.clone(),
+                    grant_type: grant_type.clone(),
+                    granted_via: "invitation".to_string(),
+                    payment_amount: None,
+                    payment_unit: None,
+                    scholarship_sponsor_id: None,
+                    scholarship_reason: None,
+                };
+                let metadata = serde_json::json!({ "invitation_id": invitation.id, "invited_by": invitation.inviter_id });
+                let output =
+                    commit_access_grant(&gate, &input.invitee_agent_id, &grant_input, None, metadata.to_string())?;
+                emit_write_signal("access_grant", &output.grant.id, "redeem_invitation");
+                Some(output)
+            }
+            _ => None,
+        },
+        _ => None,
+    };
 
-    // Create grant type link
-    let type_anchor = StringAnchor::new("grant_type", &input.grant_type);
-    let type_anchor_hash = hash_entry(&EntryTypes::StringAnchor(type_anchor.clone()))?;
-    create_entry(&EntryTypes::StringAnchor(type_anchor))?;
-    create_link(type_anchor_hash, action_hash.clone(), LinkTypes::GrantByType, ())?;
+    let points = get_point_amount("invitation");
+    let point_event = PointEvent {
+        id: format!("pe-{}-{}", invitation.inviter_agent_id, timestamp),
+        agent_id: invitation.inviter_agent_id.clone(),
+        action: "produce".to_string(),
+        trigger: "invitation".to_string(),
+        points,
+        content_id: None,
+        challenge_id: None,
+        path_id: invitation.path_id.clone(),
+        was_correct: None,
+        note: None,
+        metadata_json: serde_json::json!({ "invitation_id": invitation.id, "invitee_id": input.invitee_id })
+            .to_string(),
+        occurred_at: timestamp.clone(),
+    };
+    record_point_event(&point_event)?;
 
-    // If there was payment, create revenue record
-    if let Some(amount) = input.payment_amount {
-        if amount > 0.0 {
-            create_steward_revenue(&gate.gate, &grant_id, &learner_id, amount, input.payment_unit.as_deref(), &timestamp)?;
-        }
-    }
+    invitation.status = "redeemed".to_string();
+    invitation.redeemed_by = Some(input.invitee_id);
+    invitation.redeemed_at = Some(timestamp.clone());
+    invitation.updated_at = timestamp;
+    let invitation = save_invitation(&action_hash, invitation)?;
 
-    Ok(AccessGrantOutput {
-        action_hash,
-        grant,
-    })
+    Ok(RedeemedInvitationOutput { invitation, points_awarded: points, access_grant })
+}
+
+/// A member's invitations, newest first
+#[hdk_extern]
+pub fn get_invitations_by_inviter(inviter_id: String) -> ExternResult<Vec<InvitationOutput>> {
+    let mut invitations: Vec<InvitationOutput> =
+        get_linked_invitations("inviter_invitations", &inviter_id, LinkTypes::InviterToInvitation)?
+            .into_iter()
+            .map(|(action_hash, invitation)| InvitationOutput { action_hash, invitation })
+            .collect();
+    invitations.sort_by(|a, b| b.invitation.created_at.cmp(&a.invitation.created_at));
+    Ok(invitations)
+}
+
+/// Withdraw an unused invitation; only its inviter can
+#[hdk_extern]
+pub fn revoke_invitation(input: RevokeInvitationInput) -> ExternResult<InvitationOutput> {
+    let (action_hash, mut invitation) =
+        get_linked_invitations("invitation_id", &input.invitation_id, LinkTypes::IdToInvitation)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| wasm_error!(WasmErrorInner::Guest("Invitation not found".to_string())))?;
+    if invitation.inviter_id != input.inviter_id {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the inviter can revoke an invitation".to_string()
+        )));
+    }
+    if invitation.status != "pending" {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Invitation is already {}",
+            invitation.status
+        ))));
+    }
+    invitation.status = "revoked".to_string();
+    invitation.updated_at = format!("{:?}", sys_time()?);
+    save_invitation(&action_hash, invitation)
 }
 
 /// Create steward revenue record (internal function)
@@ -6967,8 +14278,39 @@ fn create_steward_revenue(
 pub fn check_access(gate_id: String) -> ExternResult<Option<AccessGrantOutput>> {
     let agent_info = agent_info()?;
     let learner_id = agent_info.agent_initial_pubkey.to_string();
+    current_grant(&learner_id, &gate_id, sys_time()?.as_micros())
+}
 
-    let learner_anchor = StringAnchor::new("learner_grants", &learner_id);
+/// Input for check_resource_access
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ResourceAccessInput {
+    pub resource_id: String,
+    pub learner_agent_id: String,
+}
+
+/// Whether a learner may open a resource: it has no active gate, or they
+/// hold a current grant through one of its gates.
+///
+/// Only the doorway calls this, for the signed-in learner (e.g. before
+/// signing a media URL).
+#[hdk_extern]
+pub fn check_resource_access(input: ResourceAccessInput) -> ExternResult<bool> {
+    let gates = get_gates_for_resource(input.resource_id)?;
+    if gates.is_empty() {
+        return Ok(true);
+    }
+    let now_micros = sys_time()?.as_micros();
+    for gate in gates {
+        if current_grant(&input.learner_agent_id, &gate.gate.id, now_micros)?.is_some() {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// A learner's current grant through a gate
+fn current_grant(learner_id: &str, gate_id: &str, now_micros: i64) -> ExternResult<Option<AccessGrantOutput>> {
+    let learner_anchor = StringAnchor::new("learner_grants", learner_id);
     let learner_anchor_hash = hash_entry(&EntryTypes::StringAnchor(learner_anchor))?;
 
     let query = LinkQuery::try_new(learner_anchor_hash, LinkTypes::LearnerToGrant)?;
@@ -6981,8 +14323,7 @@ pub fn check_access(gate_id: String) -> ExternResult<Option<AccessGrantOutput>>
         let record = get(action_hash.clone(), GetOptions::default())?;
         if let Some(rec) = record {
             if let Some(grant) = rec.entry().to_app_option::<AccessGrant>().ok().flatten() {
-                if grant.gate_id == gate_id && grant.is_active {
-                    // TODO: Check expiration
+                if grant.gate_id == gate_id && grant_is_current(&grant, now_micros) {
                     return Ok(Some(AccessGrantOutput { action_hash, grant }));
                 }
             }
@@ -7012,14 +14353,227 @@ pub fn get_my_access_grants(_: ()) -> ExternResult<Vec<AccessGrantOutput>> {
         let record = get(action_hash.clone(), GetOptions::default())?;
         if let Some(rec) = record {
             if let Some(grant) = rec.entry().to_app_option::<AccessGrant>().ok().flatten() {
-                if grant.is_active {
-                    results.push(AccessGrantOutput { action_hash, grant });
-                }
+                if grant.is_active {
+                    results.push(AccessGrantOutput { action_hash, grant });
+                }
+            }
+        }
+    }
+
+    Ok(results)
+}
+
+/// Get my current entitlements (active, unexpired grants) with their gate details.
+///
+/// Bundle grants list every bundled path in `resource_ids`, so clients can
+/// render entitlements without checking each gate individually.
+#[hdk_extern]
+pub fn get_my_active_grants(_: ()) -> ExternResult<Vec<ActiveGrantOutput>> {
+    let now_micros = sys_time()?.as_micros();
+    let grants = get_my_access_grants(())?;
+
+    let mut gates: HashMap<String, Option<PremiumGate>> = HashMap::new();
+    let mut results = Vec::new();
+    for grant in grants {
+        if !grant_is_current(&grant.grant, now_micros) {
+            continue;
+        }
+        let gate = match gates.get(&grant.grant.gate_id) {
+            Some(gate) => gate.clone(),
+            None => {
+                let gate = get_premium_gate(grant.grant.gate_id.clone())?.map(|g| g.gate);
+                gates.insert(grant.grant.gate_id.clone(), gate.clone());
+                gate
+            }
+        };
+        if let Some(gate) = gate {
+            results.push(ActiveGrantOutput {
+                gate_title: gate.gate_title,
+                gated_resource_type: gate.gated_resource_type,
+                resource_ids: serde_json::from_str(&gate.gated_resource_ids_json).unwrap_or_default(),
+                grant,
+            });
+        }
+    }
+
+    Ok(results)
+}
+
+/// Renew a subscription grant for another period.
+///
+/// The new period starts at the current expiry if it hasn't passed yet, so
+/// renewing early doesn't lose time. Lapsed grants are reactivated from now.
+#[hdk_extern]
+pub fn renew_access(input: RenewAccessInput) -> ExternResult<AccessGrantOutput> {
+    let agent_info = agent_info()?;
+    let learner_id = agent_info.agent_initial_pubkey.to_string();
+    let now = sys_time()?;
+    let timestamp = format!("{:?}", now);
+
+    let existing = get_access_grant(&input.grant_id)?
+        .ok_or_else(|| wasm_error!(WasmErrorInner::Guest("Grant not found".to_string())))?;
+    let mut grant = existing.grant.clone();
+
+    if grant.learner_agent_id != learner_id {
+        return Err(wasm_error!(WasmErrorInner::Guest("Only the grant holder can renew it".to_string())));
+    }
+    if grant.grant_type != "subscription" {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            format!("Only subscription grants can be renewed, got {}", grant.grant_type)
+        )));
+    }
+    if grant.status == "revoked" || grant.revoked_at.is_some() {
+        return Err(wasm_error!(WasmErrorInner::Guest("Revoked grants cannot be renewed".to_string())));
+    }
+
+    let gate = get_premium_gate(grant.gate_id.clone())?
+        .ok_or_else(|| wasm_error!(WasmErrorInner::Guest("Gate not found".to_string())))?;
+    if !gate.gate.is_active {
+        return Err(wasm_error!(WasmErrorInner::Guest("Gate is no longer active".to_string())));
+    }
+
+    let period_start = match grant.valid_until_micros {
+        Some(until) if until > now.as_micros() => Timestamp::from_micros(until),
+        _ => now,
+    };
+    let (valid_until, valid_until_micros) = access_window_end(
+        period_start,
+        gate.gate.subscription_period_days.unwrap_or(DEFAULT_SUBSCRIPTION_DAYS),
+    );
+
+    grant.valid_until = Some(valid_until.clone());
+    grant.valid_until_micros = Some(valid_until_micros);
+    grant.renewal_due_at = Some(valid_until);
+    grant.is_active = true;
+    grant.status = "active".to_string();
+    grant.lapsed_at = None;
+    grant.renewal_count += 1;
+    if input.payment_amount.is_some() {
+        grant.payment_amount = input.payment_amount;
+        grant.payment_unit = input.payment_unit.clone();
+    }
+
+    let renewed = update_access_grant(existing, grant)?;
+
+    if let Some(amount) = input.payment_amount {
+        if amount > 0.0 {
+            create_steward_revenue(&gate.gate, &input.grant_id, &learner_id, amount, input.payment_unit.as_deref(), &timestamp)?;
+        }
+    }
+
+    Ok(renewed)
+}
+
+/// Flip my expired subscription and trial grants to lapsed.
+///
+/// Grants live on the learner's own chain, so each agent sweeps their own;
+/// clients call this on startup. Reads already treat expired grants as
+/// inactive, so the sweep only makes the stored status match.
+#[hdk_extern]
+pub fn sweep_expired_grants(_: ()) -> ExternResult<GrantExpirySweepOutput> {
+    let now = sys_time()?;
+    let timestamp = format!("{:?}", now);
+    let grants = get_my_access_grants(())?;
+
+    let mut lapsed = Vec::new();
+    let checked = grants.len() as u32;
+    for existing in grants {
+        if !grant_has_expired(&existing.grant, now.as_micros()) || existing.grant.status == "lapsed" {
+            continue;
+        }
+        let mut grant = existing.grant.clone();
+        grant.is_active = false;
+        grant.status = "lapsed".to_string();
+        grant.lapsed_at = Some(timestamp.clone());
+        lapsed.push(update_access_grant(existing, grant)?);
+    }
+
+    Ok(GrantExpirySweepOutput { checked, lapsed })
+}
+
+/// Get an access grant by ID
+fn get_access_grant(grant_id: &str) -> ExternResult<Option<AccessGrantOutput>> {
+    let id_anchor = StringAnchor::new("grant_id", grant_id);
+    let id_anchor_hash = hash_entry(&EntryTypes::StringAnchor(id_anchor))?;
+
+    let query = LinkQuery::try_new(id_anchor_hash, LinkTypes::IdToAccessGrant)?;
+    let links = get_links(query, GetStrategy::default())?;
+
+    if let Some(link) = links.first() {
+        let action_hash = ActionHash::try_from(link.target.clone())
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid grant hash".to_string())))?;
+
+        let record = get(action_hash.clone(), GetOptions::default())?;
+        if let Some(rec) = record {
+            if let Some(grant) = rec.entry().to_app_option::<AccessGrant>().ok().flatten() {
+                return Ok(Some(AccessGrantOutput { action_hash, grant }));
             }
         }
     }
 
-    Ok(results)
+    Ok(None)
+}
+
+/// Write a new version of a grant and move its lookup links to it
+fn update_access_grant(existing: AccessGrantOutput, grant: AccessGrant) -> ExternResult<AccessGrantOutput> {
+    let action_hash = update_entry(existing.action_hash.clone(), &EntryTypes::AccessGrant(grant.clone()))?;
+
+    let old_target: AnyLinkableHash = existing.action_hash.into();
+    let lookups = [
+        ("grant_id", grant.id.as_str(), LinkTypes::IdToAccessGrant),
+        ("learner_grants", grant.learner_agent_id.as_str(), LinkTypes::LearnerToGrant),
+        ("gate_grants", grant.gate_id.as_str(), LinkTypes::GateToGrant),
+        ("grant_type", grant.grant_type.as_str(), LinkTypes::GrantByType),
+    ];
+    for (anchor_type, anchor_value, link_type) in lookups {
+        let anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new(anchor_type, anchor_value)))?;
+        let query = LinkQuery::try_new(anchor_hash.clone(), link_type)?;
+        for link in get_links(query, GetStrategy::default())? {
+            if link.target == old_target {
+                delete_link(link.create_link_hash, GetOptions::default())?;
+            }
+        }
+        create_link(anchor_hash, action_hash.clone(), link_type, ())?;
+    }
+
+    Ok(AccessGrantOutput { action_hash, grant })
+}
+
+/// End of an access window of `days` starting at `from`, as (display string, micros)
+fn access_window_end(from: Timestamp, days: u32) -> (String, i64) {
+    let end = from
+        .checked_add(&std::time::Duration::from_secs(days as u64 * 86_400))
+        .unwrap_or(from);
+    (format!("{:?}", end), end.as_micros())
+}
+
+/// Whether a grant's access window has passed.
+/// Grants without a numeric expiry (lifetime, or created before it was stored) never expire here.
+fn grant_has_expired(grant: &AccessGrant, now_micros: i64) -> bool {
+    grant.valid_until_micros.map_or(false, |until| until <= now_micros)
+}
+
+/// Whether a grant currently gives access
+fn grant_is_current(grant: &AccessGrant, now_micros: i64) -> bool {
+    grant.is_active && grant.status != "lapsed" && grant.status != "revoked" && !grant_has_expired(grant, now_micros)
+}
+
+/// Combined price of the cheapest single-path gate for each bundled path.
+/// None unless every path has a priced gate in the same unit.
+fn bundle_list_price(path_ids: &[String], price_unit: Option<&str>) -> ExternResult<Option<f64>> {
+    let mut total = 0.0;
+    for path_id in path_ids {
+        let cheapest = get_gates_for_resource(path_id.clone())?
+            .into_iter()
+            .filter(|g| g.gate.gated_resource_type == "path" && g.gate.price_unit.as_deref() == price_unit)
+            .filter_map(|g| g.gate.price_amount)
+            .fold(None, |min: Option<f64>, price| Some(min.map_or(price, |m| m.min(price))));
+        match cheapest {
+            Some(price) => total += price,
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(total))
 }
 
 /// Steward revenue summary
@@ -7335,6 +14889,8 @@ pub fn create_knowledge_map(input: CreateKnowledgeMapInput) -> ExternResult<Know
     let type_anchor_hash = hash_entry(&EntryTypes::StringAnchor(type_anchor))?;
     create_link(type_anchor_hash, action_hash.clone(), LinkTypes::KnowledgeMapByType, ())?;
 
+    emit_write_signal("KnowledgeMap", &knowledge_map.id, "create_knowledge_map");
+
     Ok(KnowledgeMapOutput {
         action_hash,
         knowledge_map,
@@ -7426,6 +14982,220 @@ pub fn query_knowledge_maps(input: QueryKnowledgeMapsInput) -> ExternResult<Vec<
     Ok(results)
 }
 
+/// Input for rendering a knowledge map
+#[derive(Serialize, Deserialize, Debug)]
+pub struct KnowledgeMapLayoutInput {
+    pub map_id: String,
+    /// Human whose mastery is overlaid; the calling agent when absent
+    pub human_id: Option<String>,
+    /// Leave mastery out entirely (anonymous viewers)
+    #[serde(default)]
+    pub skip_mastery: bool,
+}
+
+/// A knowledge map node merged with its content and mastery
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct KnowledgeMapLayoutNode {
+    pub id: String,
+    pub category: String,
+    pub title: String,
+    pub affinity: f64,
+    pub related_node_ids: Vec<String>,
+    /// Content this node stands for, if any
+    pub content_id: Option<String>,
+    pub content: Option<ContentSummary>,
+    pub mastery_level: Option<String>,
+    pub mastery_level_index: Option<u32>,
+    pub freshness_score: Option<f64>,
+}
+
+/// Edge between two nodes of the same map
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct KnowledgeMapLayoutEdge {
+    pub source: String,
+    pub target: String,
+}
+
+/// Nodes grouped by category for the cluster view
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct KnowledgeMapCluster {
+    pub category: String,
+    pub node_ids: Vec<String>,
+    pub mean_affinity: f64,
+}
+
+/// Ready-to-render knowledge map
+#[derive(Serialize, Deserialize, Debug)]
+pub struct KnowledgeMapLayout {
+    pub knowledge_map: KnowledgeMap,
+    pub nodes: Vec<KnowledgeMapLayoutNode>,
+    pub edges: Vec<KnowledgeMapLayoutEdge>,
+    pub clusters: Vec<KnowledgeMapCluster>,
+    /// False when mastery was skipped or imagodei was unreachable
+    pub mastery_overlay: bool,
+}
+
+/// Content id behind a knowledge node
+///
+/// Shared-content nodes name their source; domain map nodes are content ids.
+fn knowledge_node_content_id(map_type: &str, node: &serde_json::Value) -> Option<String> {
+    let source = node.get("source");
+    let is_shared_content = source
+        .and_then(|s| s.get("type"))
+        .and_then(|t| t.as_str())
+        == Some("shared-content");
+    if is_shared_content {
+        if let Some(id) = source.and_then(|s| s.get("sourceId")).and_then(|v| v.as_str()) {
+            return Some(id.to_string());
+        }
+    }
+    if map_type == "domain" {
+        return node.get("id").and_then(|v| v.as_str()).map(String::from);
+    }
+    None
+}
+
+/// Build everything the knowledge map UI needs in one call
+///
+/// Merges the map definition with content summaries for its nodes and, unless
+/// skipped, the viewer's mastery from imagodei. Mastery is best-effort: if the
+/// bridge fails the layout is still returned with `mastery_overlay: false`.
+#[hdk_extern]
+pub fn get_knowledge_map_layout(input: KnowledgeMapLayoutInput) -> ExternResult<Option<KnowledgeMapLayout>> {
+    let Some(output) = get_knowledge_map_by_id(input.map_id)? else {
+        return Ok(None);
+    };
+    let knowledge_map = output.knowledge_map;
+
+    let raw_nodes: Vec<serde_json::Value> =
+        serde_json::from_str(&knowledge_map.nodes_json).unwrap_or_default();
+
+    let mut nodes: Vec<KnowledgeMapLayoutNode> = raw_nodes
+        .iter()
+        .filter_map(|node| {
+            let id = node.get("id")?.as_str()?.to_string();
+            let str_field = |key: &str| node.get(key).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+            Some(KnowledgeMapLayoutNode {
+                category: str_field("category"),
+                title: str_field("title"),
+                affinity: node.get("affinity").and_then(|v| v.as_f64()).unwrap_or(0.0),
+                related_node_ids: node
+                    .get("relatedNodeIds")
+                    .and_then(|v| v.as_array())
+                    .map(|ids| ids.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+                    .unwrap_or_default(),
+                content_id: knowledge_node_content_id(&knowledge_map.map_type, node),
+                content: None,
+                mastery_level: None,
+                mastery_level_index: None,
+                freshness_score: None,
+                id,
+            })
+        })
+        .collect();
+
+    // Content summaries
+    let mut content_ids: Vec<String> = Vec::new();
+    for id in nodes.iter().filter_map(|n| n.content_id.clone()) {
+        if !content_ids.contains(&id) {
+            content_ids.push(id);
+        }
+    }
+    let batch = batch_get_content_by_ids(BatchGetContentInput { ids: content_ids.clone() })?;
+    let summaries: HashMap<String, ContentSummary> = batch
+        .found
+        .into_iter()
+        .map(|output| {
+            let content = output.content;
+            (
+                content.id.clone(),
+                ContentSummary {
+                    action_hash: output.action_hash,
+                    id: content.id,
+                    title: content.title,
+                    content_type: content.content_type,
+                    reach: content.reach,
+                    estimated_minutes: content.estimated_minutes,
+                    updated_at: content.updated_at,
+                },
+            )
+        })
+        .collect();
+
+    // Mastery overlay
+    let mastery = if input.skip_mastery || content_ids.is_empty() {
+        None
+    } else {
+        let result = match input.human_id {
+            Some(human_id) => get_mastery_batch_for_human(human_id, content_ids),
+            None => get_my_mastery_batch(content_ids),
+        };
+        match result {
+            Ok(mastery) => Some(mastery),
+            Err(e) => {
+                debug!("Knowledge map layout without mastery overlay: {:?}", e);
+                None
+            }
+        }
+    };
+    let mastery_overlay = mastery.is_some();
+
+    for node in nodes.iter_mut() {
+        let Some(ref content_id) = node.content_id else {
+            continue;
+        };
+        node.content = summaries.get(content_id).cloned();
+        if let Some(Some(m)) = mastery.as_ref().and_then(|m| m.get(content_id)) {
+            node.mastery_level = Some(m.mastery.mastery_level.clone());
+            node.mastery_level_index = Some(m.mastery.mastery_level_index);
+            node.freshness_score = Some(m.mastery.freshness_score);
+        }
+    }
+
+    // Edges within the map, each pair once
+    let node_ids: HashSet<&str> = nodes.iter().map(|n| n.id.as_str()).collect();
+    let mut edges: Vec<KnowledgeMapLayoutEdge> = Vec::new();
+    for node in &nodes {
+        for related in &node.related_node_ids {
+            if !node_ids.contains(related.as_str()) || related == &node.id {
+                continue;
+            }
+            let reverse = KnowledgeMapLayoutEdge { source: related.clone(), target: node.id.clone() };
+            let edge = KnowledgeMapLayoutEdge { source: node.id.clone(), target: related.clone() };
+            if !edges.contains(&edge) && !edges.contains(&reverse) {
+                edges.push(edge);
+            }
+        }
+    }
+
+    // Clusters by category, in first-seen order
+    let mut clusters: Vec<KnowledgeMapCluster> = Vec::new();
+    for node in &nodes {
+        match clusters.iter_mut().find(|c| c.category == node.category) {
+            Some(cluster) => {
+                cluster.mean_affinity += node.affinity;
+                cluster.node_ids.push(node.id.clone());
+            }
+            None => clusters.push(KnowledgeMapCluster {
+                category: node.category.clone(),
+                node_ids: vec![node.id.clone()],
+                mean_affinity: node.affinity,
+            }),
+        }
+    }
+    for cluster in clusters.iter_mut() {
+        cluster.mean_affinity /= cluster.node_ids.len() as f64;
+    }
+
+    Ok(Some(KnowledgeMapLayout {
+        knowledge_map,
+        nodes,
+        edges,
+        clusters,
+        mastery_overlay,
+    }))
+}
+
 // =============================================================================
 // PathExtension Operations
 // =============================================================================
@@ -7506,6 +15276,8 @@ pub fn create_path_extension(input: CreatePathExtensionInput) -> ExternResult<Pa
     let base_anchor_hash = hash_entry(&EntryTypes::StringAnchor(base_anchor))?;
     create_link(base_anchor_hash, action_hash.clone(), LinkTypes::BasePathToExtension, ())?;
 
+    emit_write_signal("PathExtension", &path_extension.id, "create_path_extension");
+
     Ok(PathExtensionOutput {
         action_hash,
         path_extension,
@@ -7862,6 +15634,8 @@ pub fn create_proposal(input: CreateProposalInput) -> ExternResult<ProposalOutpu
     let status_anchor_hash = hash_entry(&EntryTypes::StringAnchor(status_anchor))?;
     create_link(status_anchor_hash, action_hash.clone(), LinkTypes::ProposalByStatus, ())?;
 
+    emit_write_signal("Proposal", &proposal.id, "create_proposal");
+
     Ok(ProposalOutput {
         action_hash,
         proposal,
@@ -8029,6 +15803,8 @@ pub fn create_precedent(input: CreatePrecedentInput) -> ExternResult<PrecedentOu
     let status_anchor_hash = hash_entry(&EntryTypes::StringAnchor(status_anchor))?;
     create_link(status_anchor_hash, action_hash.clone(), LinkTypes::PrecedentByStatus, ())?;
 
+    emit_write_signal("Precedent", &precedent.id, "create_precedent");
+
     Ok(PrecedentOutput {
         action_hash,
         precedent,
@@ -8174,6 +15950,8 @@ pub fn create_discussion(input: CreateDiscussionInput) -> ExternResult<Discussio
     let status_anchor_hash = hash_entry(&EntryTypes::StringAnchor(status_anchor))?;
     create_link(status_anchor_hash, action_hash.clone(), LinkTypes::DiscussionByStatus, ())?;
 
+    emit_write_signal("Discussion", &discussion.id, "create_discussion");
+
     Ok(DiscussionOutput {
         action_hash,
         discussion,
@@ -8338,6 +16116,8 @@ pub fn set_governance_state(input: CreateGovernanceStateInput) -> ExternResult<G
     let status_anchor_hash = hash_entry(&EntryTypes::StringAnchor(status_anchor))?;
     create_link(status_anchor_hash, action_hash.clone(), LinkTypes::GovernanceStateByStatus, ())?;
 
+    emit_write_signal("GovernanceState", &governance_state.id, "set_governance_state");
+
     Ok(GovernanceStateOutput {
         action_hash,
         governance_state,
@@ -8367,50 +16147,582 @@ pub fn get_governance_state(input: GetGovernanceStateInput) -> ExternResult<Opti
         }
     }
 
-    Ok(None)
+    Ok(None)
+}
+
+/// Input for getting governance state
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetGovernanceStateInput {
+    pub entity_type: String,
+    pub entity_id: String,
+}
+
+/// Input for querying governance states
+#[derive(Serialize, Deserialize, Debug)]
+pub struct QueryGovernanceStatesInput {
+    pub status: Option<String>,
+    pub limit: Option<u32>,
+}
+
+/// Query governance states by status
+#[hdk_extern]
+pub fn query_governance_states(input: QueryGovernanceStatesInput) -> ExternResult<Vec<GovernanceStateOutput>> {
+    let mut results = Vec::new();
+    let limit = input.limit.unwrap_or(100) as usize;
+
+    if let Some(status) = &input.status {
+        let status_anchor = StringAnchor::new("governance_state_status", status);
+        let status_anchor_hash = hash_entry(&EntryTypes::StringAnchor(status_anchor))?;
+
+        let query = LinkQuery::try_new(status_anchor_hash, LinkTypes::GovernanceStateByStatus)?;
+        let links = get_links(query, GetStrategy::default())?;
+
+        for link in links.iter().take(limit) {
+            let action_hash = ActionHash::try_from(link.target.clone())
+                .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid governance state hash".to_string())))?;
+
+            let record = get(action_hash.clone(), GetOptions::default())?;
+            if let Some(record) = record {
+                if let Some(governance_state) = record.entry().to_app_option::<GovernanceState>().ok().flatten() {
+                    results.push(GovernanceStateOutput { action_hash, governance_state });
+                }
+            }
+        }
+    }
+
+    Ok(results)
+}
+
+// =============================================================================
+// Proposal Voting (Governance)
+// =============================================================================
+
+/// How a vote is weighted in the tally
+pub const VOTE_WEIGHT_BASES: [&str; 2] = [
+    "equal",   // Every voter counts 1
+    "mastery", // 1 plus up to 1 more for mastery of the proposal's related content
+];
+
+/// Input for casting a vote on a proposal
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CastVoteInput {
+    pub proposal_id: String,
+    /// One of VOTE_POSITIONS
+    pub choice: String,
+    /// One of VOTE_WEIGHT_BASES; must match the proposal's voting rules
+    pub weight_basis: String,
+    #[serde(default)]
+    pub voter_name: Option<String>,
+    #[serde(default)]
+    pub reasoning: Option<String>,
+}
+
+/// Output for a proposal vote
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProposalVoteOutput {
+    pub action_hash: ActionHash,
+    pub vote: ProposalVote,
+    /// Weight the vote counts for
+    pub weight: f64,
+}
+
+/// Voting rules for a proposal
+///
+/// Taken from the approved GovernanceState for the proposal type
+/// (entity_type "proposal_type", rules in metadata_json), then the
+/// proposal's voting_config_json for anything governance leaves open, then
+/// the defaults for the proposal type.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VotingRules {
+    /// Voters needed for a decision (abstentions count)
+    pub quorum: u32,
+    /// Share of agree weight among non-abstaining weight needed to pass
+    pub passage_threshold: f64,
+    /// Whether one block vote stops the proposal
+    pub block_is_veto: bool,
+    pub weight_basis: String,
+    /// Voting closes at this time; without it the proposal is decided at the
+    /// first tally that meets quorum
+    pub voting_ends_at_micros: Option<i64>,
+    /// Binding level of the precedent created on passage
+    pub precedent_binding: String,
+    /// GovernanceState the rules came from
+    pub governance_state_id: Option<String>,
+}
+
+/// Rule fields as written in GovernanceState metadata or voting_config_json
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct VotingRulesConfig {
+    #[serde(alias = "quorumCount")]
+    quorum: Option<u32>,
+    #[serde(alias = "quorumPercentage")]
+    quorum_percentage: Option<f64>,
+    #[serde(alias = "eligibleVoters")]
+    eligible_voters: Option<u32>,
+    #[serde(alias = "passageThreshold")]
+    passage_threshold: Option<f64>,
+    #[serde(alias = "blockIsVeto")]
+    block_is_veto: Option<bool>,
+    #[serde(alias = "weightBasis")]
+    weight_basis: Option<String>,
+    #[serde(alias = "votingEndsAtMicros")]
+    voting_ends_at_micros: Option<i64>,
+    #[serde(alias = "precedentBinding")]
+    precedent_binding: Option<String>,
+}
+
+impl VotingRulesConfig {
+    fn parse(json: &str) -> Self {
+        serde_json::from_str(json).unwrap_or_default()
+    }
+
+    /// Quorum as a voter count; a percentage needs the number of eligible voters
+    fn quorum(&self) -> Option<u32> {
+        let from_percentage = match (self.quorum_percentage, self.eligible_voters) {
+            (Some(percentage), Some(eligible)) => {
+                Some((percentage.clamp(0.0, 100.0) / 100.0 * eligible as f64).ceil() as u32)
+            }
+            _ => None,
+        };
+        match (self.quorum, from_percentage) {
+            (Some(count), Some(percentage)) => Some(count.max(percentage)),
+            (count, percentage) => count.or(percentage),
+        }
+    }
+}
+
+/// Defaults per proposal type: (quorum, passage threshold, block is veto, precedent binding)
+fn default_voting_rules(proposal_type: &str) -> (u32, f64, bool, &'static str) {
+    match proposal_type {
+        "sense-check" => (1, 0.5, false, "persuasive"),
+        "consensus" => (3, 1.0, true, "binding-local"),
+        "supermajority" => (5, 2.0 / 3.0, false, "binding-network"),
+        // consent: proceed unless blocked
+        _ => (3, 0.5, true, "binding-local"),
+    }
+}
+
+/// Work out the voting rules for a proposal
+fn voting_rules(proposal: &Proposal) -> ExternResult<VotingRules> {
+    let governed = get_governance_state(GetGovernanceStateInput {
+        entity_type: "proposal_type".to_string(),
+        entity_id: proposal.proposal_type.clone(),
+    })?
+    .map(|output| output.governance_state)
+    .filter(|state| state.status == "approved");
+    let community = governed
+        .as_ref()
+        .map(|state| VotingRulesConfig::parse(&state.metadata_json))
+        .unwrap_or_default();
+    let own = VotingRulesConfig::parse(&proposal.voting_config_json);
+    let (quorum, passage_threshold, block_is_veto, precedent_binding) = default_voting_rules(&proposal.proposal_type);
+
+    let weight_basis = community.weight_basis.clone()
+        .or(own.weight_basis.clone())
+        .unwrap_or_else(|| "equal".to_string());
+    if !VOTE_WEIGHT_BASES.contains(&weight_basis.as_str()) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Invalid weight basis: {}. Must be one of: {:?}",
+            weight_basis, VOTE_WEIGHT_BASES
+        ))));
+    }
+
+    Ok(VotingRules {
+        quorum: community.quorum().or(own.quorum()).unwrap_or(quorum).max(1),
+        passage_threshold: community.passage_threshold
+            .or(own.passage_threshold)
+            .unwrap_or(passage_threshold)
+            .clamp(0.0, 1.0),
+        block_is_veto: community.block_is_veto.or(own.block_is_veto).unwrap_or(block_is_veto),
+        weight_basis,
+        // Deadlines belong to the proposal
+        voting_ends_at_micros: own.voting_ends_at_micros,
+        precedent_binding: community.precedent_binding
+            .or(own.precedent_binding)
+            .filter(|binding| PRECEDENT_BINDING.contains(&binding.as_str()))
+            .unwrap_or_else(|| precedent_binding.to_string()),
+        governance_state_id: governed.map(|state| state.id),
+    })
+}
+
+/// Range a vote's weight can take under a weight basis
+fn vote_weight_range(weight_basis: &str) -> (f64, f64) {
+    match weight_basis {
+        "mastery" => (1.0, 2.0),
+        _ => (1.0, 1.0),
+    }
+}
+
+/// The calling agent's vote weight on a proposal
+fn vote_weight(weight_basis: &str, proposal: &Proposal) -> ExternResult<f64> {
+    match weight_basis {
+        "mastery" => {
+            // Proposals not about content count everyone equally
+            let content_id = match (proposal.related_entity_type.as_deref(), &proposal.related_entity_id) {
+                (Some("content"), Some(id)) => id.clone(),
+                _ => return Ok(1.0),
+            };
+            let level = get_my_mastery(content_id)?
+                .map(|output| output.mastery.mastery_level_index)
+                .unwrap_or(0)
+                .min(7);
+            Ok(1.0 + level as f64 / 7.0)
+        }
+        _ => Ok(1.0),
+    }
+}
+
+fn proposal_votes_anchor(proposal_id: &str) -> ExternResult<EntryHash> {
+    hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("proposal_votes", proposal_id)))
+}
+
+/// Cast or change the calling agent's vote on a proposal
+///
+/// Each agent has one vote per proposal, found through a proposal+agent
+/// anchor. Voting again updates that vote (new version, previous position
+/// kept) rather than adding a second one.
+#[hdk_extern]
+pub fn cast_vote(input: CastVoteInput) -> ExternResult<ProposalVoteOutput> {
+    if !VOTE_POSITIONS.contains(&input.choice.as_str()) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Invalid vote choice: {}. Must be one of: {:?}",
+            input.choice, VOTE_POSITIONS
+        ))));
+    }
+
+    let proposal = get_proposal_by_id(input.proposal_id.clone())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(format!(
+            "Proposal not found: {}",
+            input.proposal_id
+        ))))?
+        .proposal;
+    if proposal.status != "voting" {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Proposal '{}' is not open for voting (status: {})",
+            proposal.id, proposal.status
+        ))));
+    }
+
+    let rules = voting_rules(&proposal)?;
+    let now = sys_time()?;
+    if rules.voting_ends_at_micros.is_some_and(|ends_at| now.as_micros() >= ends_at) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Voting on proposal '{}' has closed",
+            proposal.id
+        ))));
+    }
+    if input.weight_basis != rules.weight_basis {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Proposal '{}' weights votes by '{}', not '{}'",
+            proposal.id, rules.weight_basis, input.weight_basis
+        ))));
+    }
+    let weight = vote_weight(&rules.weight_basis, &proposal)?;
+
+    let voter_id = agent_info()?.agent_initial_pubkey.to_string();
+    let timestamp = format!("{:?}", now);
+    let metadata_json = serde_json::json!({
+        "weight_basis": rules.weight_basis,
+        "weight": weight,
+    })
+    .to_string();
+
+    let voter_anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new(
+        "proposal_voter",
+        &format!("{}:{}", proposal.id, voter_id),
+    )))?;
+    let voter_query = LinkQuery::try_new(voter_anchor_hash.clone(), LinkTypes::AgentToVotes)?;
+    let voter_links = get_links(voter_query, GetStrategy::default())?;
+    let previous = match voter_links.first() {
+        Some(link) => {
+            let previous_hash = ActionHash::try_from(link.target.clone())
+                .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid vote hash".to_string())))?;
+            get(previous_hash.clone(), GetOptions::default())?
+                .and_then(|record| record.entry().to_app_option::<ProposalVote>().ok().flatten())
+                .map(|vote| (previous_hash, vote))
+        }
+        None => None,
+    };
+
+    let votes_anchor_hash = proposal_votes_anchor(&proposal.id)?;
+    let (action_hash, vote) = match previous {
+        Some((previous_hash, existing)) => {
+            let vote = ProposalVote {
+                voter_name: input.voter_name.unwrap_or_else(|| existing.voter_name.clone()),
+                position: input.choice.clone(),
+                reasoning: input.reasoning,
+                version: existing.version + 1,
+                previous_position: Some(existing.position.clone()),
+                updated_at: timestamp,
+                metadata_json,
+                ..existing
+            };
+            let action_hash = update_entry(previous_hash.clone(), &EntryTypes::ProposalVote(vote.clone()))?;
+
+            // Point the vote's links at the new version
+            for link in voter_links {
+                delete_link(link.create_link_hash, GetOptions::default())?;
+            }
+            delete_links_to(votes_anchor_hash.clone(), LinkTypes::ProposalToVotes, &previous_hash)?;
+            (action_hash, vote)
+        }
+        None => {
+            let vote = ProposalVote {
+                id: format!("vote-{}-{}", proposal.id, voter_id),
+                proposal_id: proposal.id.clone(),
+                voter_id: voter_id.clone(),
+                voter_name: input.voter_name.unwrap_or_default(),
+                position: input.choice.clone(),
+                reasoning: input.reasoning,
+                version: 1,
+                previous_position: None,
+                created_at: timestamp.clone(),
+                updated_at: timestamp,
+                metadata_json,
+            };
+            (create_entry(&EntryTypes::ProposalVote(vote.clone()))?, vote)
+        }
+    };
+
+    create_link(voter_anchor_hash, action_hash.clone(), LinkTypes::AgentToVotes, ())?;
+    create_link(votes_anchor_hash, action_hash.clone(), LinkTypes::ProposalToVotes, ())?;
+
+    emit_write_signal("Proposal", &proposal.id, "cast_vote");
+
+    Ok(ProposalVoteOutput {
+        action_hash,
+        vote,
+        weight,
+    })
+}
+
+/// Weighted vote counts for a proposal
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ProposalTally {
+    pub proposal_id: String,
+    pub voters: u32,
+    pub agree: f64,
+    pub abstain: f64,
+    pub disagree: f64,
+    pub block: f64,
+    pub blocks: u32,
+    /// agree / (agree + disagree + block)
+    pub agree_ratio: f64,
+    pub quorum_met: bool,
+    /// pass, fail, blocked or no_quorum
+    pub result: String,
+}
+
+/// Output for tallying a proposal
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TallyProposalOutput {
+    pub tally: ProposalTally,
+    pub rules: VotingRules,
+    /// Whether this tally decided the proposal
+    pub decided: bool,
+    pub proposal: Proposal,
+    /// Precedent created when the proposal passed
+    pub precedent_id: Option<String>,
+}
+
+/// Count votes under the proposal's rules; each vote is (position, weight)
+fn count_votes(proposal_id: &str, votes: &[(String, f64)], rules: &VotingRules) -> ProposalTally {
+    let mut tally = ProposalTally {
+        proposal_id: proposal_id.to_string(),
+        voters: votes.len() as u32,
+        ..Default::default()
+    };
+    for (position, weight) in votes {
+        match position.as_str() {
+            "agree" => tally.agree += weight,
+            "abstain" => tally.abstain += weight,
+            "disagree" => tally.disagree += weight,
+            "block" => {
+                tally.block += weight;
+                tally.blocks += 1;
+            }
+            _ => {}
+        }
+    }
+
+    let deciding = tally.agree + tally.disagree + tally.block;
+    tally.agree_ratio = if deciding > 0.0 { tally.agree / deciding } else { 0.0 };
+    tally.quorum_met = tally.voters >= rules.quorum;
+    tally.result = if !tally.quorum_met {
+        "no_quorum"
+    } else if rules.block_is_veto && tally.blocks > 0 {
+        "blocked"
+    } else if deciding > 0.0 && tally.agree_ratio >= rules.passage_threshold {
+        "pass"
+    } else {
+        "fail"
+    }
+    .to_string();
+    tally
+}
+
+/// Latest vote per voter, with its weight clamped to the weight basis
+///
+/// Votes whose record wasn't authored by the voter they name are ignored.
+fn proposal_votes(proposal_id: &str, rules: &VotingRules) -> ExternResult<Vec<(String, f64)>> {
+    let query = LinkQuery::try_new(proposal_votes_anchor(proposal_id)?, LinkTypes::ProposalToVotes)?;
+    let (min_weight, max_weight) = vote_weight_range(&rules.weight_basis);
+
+    let mut latest: HashMap<String, (u32, String, f64)> = HashMap::new();
+    for link in get_links(query, GetStrategy::default())? {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(action_hash, GetOptions::default())? else {
+            continue;
+        };
+        let Some(vote) = record.entry().to_app_option::<ProposalVote>().ok().flatten() else {
+            continue;
+        };
+        if record.action().author().to_string() != vote.voter_id {
+            continue;
+        }
+        let weight = serde_json::from_str::<serde_json::Value>(&vote.metadata_json)
+            .ok()
+            .and_then(|metadata| metadata.get("weight").and_then(|w| w.as_f64()))
+            .unwrap_or(min_weight)
+            .clamp(min_weight, max_weight);
+        let superseded = latest.get(&vote.voter_id).is_some_and(|(version, _, _)| *version >= vote.version);
+        if !superseded {
+            latest.insert(vote.voter_id, (vote.version, vote.position, weight));
+        }
+    }
+
+    Ok(latest.into_values().map(|(_, position, weight)| (position, weight)).collect())
 }
 
-/// Input for getting governance state
-#[derive(Serialize, Deserialize, Debug)]
-pub struct GetGovernanceStateInput {
-    pub entity_type: String,
-    pub entity_id: String,
-}
+/// Update a proposal and point its index links at the new version
+fn update_proposal(previous_hash: ActionHash, previous: &Proposal, proposal: &Proposal) -> ExternResult<ActionHash> {
+    let action_hash = update_entry(previous_hash.clone(), &EntryTypes::Proposal(proposal.clone()))?;
 
-/// Input for querying governance states
-#[derive(Serialize, Deserialize, Debug)]
-pub struct QueryGovernanceStatesInput {
-    pub status: Option<String>,
-    pub limit: Option<u32>,
+    let indexes = [
+        (StringAnchor::new("proposal_id", &proposal.id), StringAnchor::new("proposal_id", &proposal.id), LinkTypes::IdToProposal),
+        (StringAnchor::new("proposal_type", &previous.proposal_type), StringAnchor::new("proposal_type", &proposal.proposal_type), LinkTypes::ProposalByType),
+        (StringAnchor::new("proposal_proposer", &previous.proposer_id), StringAnchor::new("proposal_proposer", &proposal.proposer_id), LinkTypes::ProposerToProposal),
+        (StringAnchor::new("proposal_status", &previous.status), StringAnchor::new("proposal_status", &proposal.status), LinkTypes::ProposalByStatus),
+    ];
+    for (old_anchor, new_anchor, link_type) in indexes {
+        delete_links_to(hash_entry(&EntryTypes::StringAnchor(old_anchor))?, link_type, &previous_hash)?;
+        create_link(hash_entry(&EntryTypes::StringAnchor(new_anchor))?, action_hash.clone(), link_type, ())?;
+    }
+
+    Ok(action_hash)
 }
 
-/// Query governance states by status
+/// Count the votes on a proposal and decide it once voting is over
+///
+/// Voting is over when `voting_ends_at_micros` has passed or, without a
+/// deadline, at the first tally that meets quorum. Deciding records the
+/// tally and outcome on the proposal (status "decided") and, if it passed,
+/// creates a precedent established by the proposal. Tallying a proposal
+/// that isn't being voted on just recounts.
 #[hdk_extern]
-pub fn query_governance_states(input: QueryGovernanceStatesInput) -> ExternResult<Vec<GovernanceStateOutput>> {
-    let mut results = Vec::new();
-    let limit = input.limit.unwrap_or(100) as usize;
+pub fn tally_proposal(proposal_id: String) -> ExternResult<TallyProposalOutput> {
+    let ProposalOutput { action_hash, proposal } = get_proposal_by_id(proposal_id.clone())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(format!(
+            "Proposal not found: {}",
+            proposal_id
+        ))))?;
 
-    if let Some(status) = &input.status {
-        let status_anchor = StringAnchor::new("governance_state_status", status);
-        let status_anchor_hash = hash_entry(&EntryTypes::StringAnchor(status_anchor))?;
+    let rules = voting_rules(&proposal)?;
+    let votes = proposal_votes(&proposal.id, &rules)?;
+    let tally = count_votes(&proposal.id, &votes, &rules);
 
-        let query = LinkQuery::try_new(status_anchor_hash, LinkTypes::GovernanceStateByStatus)?;
-        let links = get_links(query, GetStrategy::default())?;
+    let now = sys_time()?;
+    let voting_over = match rules.voting_ends_at_micros {
+        Some(ends_at) => now.as_micros() >= ends_at,
+        None => tally.quorum_met,
+    };
+    if proposal.status != "voting" || !voting_over {
+        return Ok(TallyProposalOutput {
+            tally,
+            rules,
+            decided: false,
+            proposal,
+            precedent_id: None,
+        });
+    }
 
-        for link in links.iter().take(limit) {
-            let action_hash = ActionHash::try_from(link.target.clone())
-                .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid governance state hash".to_string())))?;
+    let passed = tally.result == "pass";
+    let timestamp = format!("{:?}", now);
 
-            let record = get(action_hash.clone(), GetOptions::default())?;
-            if let Some(record) = record {
-                if let Some(governance_state) = record.entry().to_app_option::<GovernanceState>().ok().flatten() {
-                    results.push(GovernanceStateOutput { action_hash, governance_state });
-                }
-            }
+    let precedent_id = if passed {
+        let id = format!("prec-{}", proposal.id);
+        if get_precedent_by_id(id.clone())?.is_none() {
+            let entity_types: Vec<&String> = proposal.related_entity_type.iter().collect();
+            let entity_ids: Vec<&String> = proposal.related_entity_id.iter().collect();
+            create_precedent(CreatePrecedentInput {
+                id: Some(id.clone()),
+                title: proposal.title.clone(),
+                summary: proposal.description.clone(),
+                full_reasoning: proposal.rationale.clone(),
+                binding: rules.precedent_binding.clone(),
+                scope_json: serde_json::json!({
+                    "entityTypes": entity_types,
+                    "entityIds": entity_ids,
+                })
+                .to_string(),
+                established_by: proposal.id.clone(),
+                status: "active".to_string(),
+                superseded_by: None,
+                metadata_json: serde_json::json!({ "tally": tally }).to_string(),
+            })?;
         }
-    }
+        Some(id)
+    } else {
+        None
+    };
 
-    Ok(results)
+    let reasoning = match tally.result.as_str() {
+        "pass" => format!(
+            "{:.0}% agreement met the {:.0}% threshold",
+            tally.agree_ratio * 100.0,
+            rules.passage_threshold * 100.0
+        ),
+        "blocked" => format!("Blocked by {} voter(s)", tally.blocks),
+        "no_quorum" => format!("{} of {} voters needed for quorum took part", tally.voters, rules.quorum),
+        _ => format!(
+            "{:.0}% agreement fell short of the {:.0}% threshold",
+            tally.agree_ratio * 100.0,
+            rules.passage_threshold * 100.0
+        ),
+    };
+
+    let actions_triggered: Vec<&str> = precedent_id.iter().map(|_| "create_precedent").collect();
+    let mut decided = proposal.clone();
+    decided.status = "decided".to_string();
+    decided.phase = "decided".to_string();
+    decided.current_votes_json = serde_json::to_string(&tally).unwrap_or_else(|_| "{}".to_string());
+    decided.outcome_json = Some(
+        serde_json::json!({
+            "decision": if passed { "approved" } else { "rejected" },
+            "reasoning": reasoning,
+            "decidedBy": "tally",
+            "decidedAt": timestamp,
+            "actionsTriggered": actions_triggered,
+            "precedentCreated": precedent_id,
+        })
+        .to_string(),
+    );
+    decided.updated_at = timestamp;
+
+    update_proposal(action_hash, &proposal, &decided)?;
+
+    emit_write_signal("Proposal", &decided.id, "tally_proposal");
+
+    Ok(TallyProposalOutput {
+        tally,
+        rules,
+        decided: true,
+        proposal: decided,
+        precedent_id,
+    })
 }
 
 // =============================================================================
@@ -8418,12 +16730,27 @@ pub fn query_governance_states(input: QueryGovernanceStatesInput) -> ExternResul
 // =============================================================================
 
 /// Create a custodian commitment
+///
+/// Only the beneficiary can propose custody of their own content, and the
+/// redundancy factor must be met by the shard assignments.
 #[hdk_extern]
 pub fn create_custodian_commitment(input: CreateCustodianCommitmentInput) -> ExternResult<CustodianCommitmentOutput> {
-    let agent_info = agent_info()?;
     let now = sys_time()?;
     let timestamp = format!("{:?}", now);
 
+    if input.custodian_agent_id == input.beneficiary_agent_id {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Custodian and beneficiary must be different agents".to_string()
+        )));
+    }
+    if agent_info()?.agent_initial_pubkey.to_string() != input.beneficiary_agent_id {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the beneficiary can propose a custodian commitment".to_string()
+        )));
+    }
+    check_redundancy(input.redundancy_factor, &input.shard_assignments_json)
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e)))?;
+
     let commitment = CustodianCommitment {
         id: format!("{}-{}", input.beneficiary_agent_id, input.custodian_agent_id),
         custodian_agent_id: input.custodian_agent_id.clone(),
@@ -8464,33 +16791,10 @@ pub fn create_custodian_commitment(input: CreateCustodianCommitmentInput) -> Ext
     let action_hash = create_entry(&EntryTypes::CustodianCommitment(commitment.clone()))?;
     let entry_hash = hash_entry(&EntryTypes::CustodianCommitment(commitment.clone()))?;
 
-    // Create index links
-    let id_anchor = StringAnchor::new("custodian_commitment_id", &commitment.id);
-    let id_anchor_hash = hash_entry(&EntryTypes::StringAnchor(id_anchor))?;
-    create_link(
-        id_anchor_hash,
-        action_hash.clone(),
-        LinkTypes::IdToCommitmentCustodian,
-        (),
-    )?;
-
-    let custodian_anchor = StringAnchor::new("custodian_id", &commitment.custodian_agent_id);
-    let custodian_anchor_hash = hash_entry(&EntryTypes::StringAnchor(custodian_anchor))?;
-    create_link(
-        custodian_anchor_hash,
-        action_hash.clone(),
-        LinkTypes::CustodianToCommitment,
-        (),
-    )?;
-
-    let beneficiary_anchor = StringAnchor::new("beneficiary_id", &commitment.beneficiary_agent_id);
-    let beneficiary_anchor_hash = hash_entry(&EntryTypes::StringAnchor(beneficiary_anchor))?;
-    create_link(
-        beneficiary_anchor_hash,
-        action_hash.clone(),
-        LinkTypes::BeneficiaryToCommitment,
-        (),
-    )?;
+    // Create index links (ID, custodian, beneficiary, type, basis, state)
+    for (anchor_hash, link_type) in custodian_commitment_index(&commitment)? {
+        create_link(anchor_hash, action_hash.clone(), link_type, ())?;
+    }
 
     Ok(CustodianCommitmentOutput {
         action_hash,
@@ -8499,125 +16803,197 @@ pub fn create_custodian_commitment(input: CreateCustodianCommitmentInput) -> Ext
     })
 }
 
-/// Accept a custodian commitment (proposed → accepted)
+/// Redundancy must be at least one and no more than the shards assigned
+fn check_redundancy(redundancy_factor: u32, shard_assignments_json: &str) -> Result<(), String> {
+    let assignments: Vec<ShardAssignment> = serde_json::from_str(shard_assignments_json)
+        .map_err(|e| format!("Invalid shard_assignments_json: {}", e))?;
+    if redundancy_factor == 0 {
+        return Err("redundancy_factor must be at least 1".to_string());
+    }
+    if redundancy_factor as usize > assignments.len() {
+        return Err(format!(
+            "redundancy_factor {} exceeds the {} shard assignment(s)",
+            redundancy_factor,
+            assignments.len()
+        ));
+    }
+    Ok(())
+}
+
+/// Get a custodian commitment by ID
 #[hdk_extern]
-pub fn accept_custodian_commitment(input: AcceptCommitmentInput) -> ExternResult<CustodianCommitmentOutput> {
-    // Get the commitment by ID
-    let id_anchor = StringAnchor::new("custodian_commitment_id", &input.commitment_id);
+pub fn get_custodian_commitment(commitment_id: String) -> ExternResult<Option<CustodianCommitmentOutput>> {
+    let id_anchor = StringAnchor::new("custodian_commitment_id", &commitment_id);
     let id_anchor_hash = hash_entry(&EntryTypes::StringAnchor(id_anchor))?;
 
     let query = LinkQuery::try_new(id_anchor_hash, LinkTypes::IdToCommitmentCustodian)?;
     let links = get_links(query, GetStrategy::default())?;
 
-    if links.is_empty() {
-        return Err(wasm_error!(WasmErrorInner::Guest("Commitment not found".to_string())));
-    }
-
-    let action_hash = ActionHash::try_from(links[0].target.clone())
+    let link = match links.first() {
+        Some(link) => link,
+        None => return Ok(None),
+    };
+    let action_hash = ActionHash::try_from(link.target.clone())
         .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid commitment hash".to_string())))?;
 
-    let record = get(action_hash.clone(), GetOptions::default())?
-        .ok_or_else(|| wasm_error!(WasmErrorInner::Guest("Commitment record not found".to_string())))?;
+    let record = match get(action_hash.clone(), GetOptions::default())? {
+        Some(record) => record,
+        None => return Ok(None),
+    };
+    match record.entry().to_app_option::<CustodianCommitment>().ok().flatten() {
+        Some(commitment) => {
+            let entry_hash = hash_entry(&EntryTypes::CustodianCommitment(commitment.clone()))?;
+            Ok(Some(CustodianCommitmentOutput { action_hash, entry_hash, commitment }))
+        }
+        None => Ok(None),
+    }
+}
 
-    let mut commitment = record
-        .entry()
-        .to_app_option::<CustodianCommitment>()
-        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid commitment entry type".to_string())))?
-        .ok_or_else(|| wasm_error!(WasmErrorInner::Guest("Invalid commitment entry".to_string())))?;
+/// Accept a custodian commitment (proposed → accepted)
+///
+/// Only the named custodian can accept.
+#[hdk_extern]
+pub fn accept_custodian_commitment(input: AcceptCommitmentInput) -> ExternResult<CustodianCommitmentOutput> {
+    let existing = get_custodian_commitment(input.commitment_id.clone())?
+        .ok_or_else(|| wasm_error!(WasmErrorInner::Guest("Commitment not found".to_string())))?;
+
+    let current_agent = agent_info()?.agent_initial_pubkey.to_string();
+    if existing.commitment.custodian_agent_id != current_agent {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the custodian can accept this commitment".to_string()
+        )));
+    }
+    if existing.commitment.state != "proposed" {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Commitment is {}, only proposed commitments can be accepted",
+            existing.commitment.state
+        ))));
+    }
 
     let now = sys_time()?;
     let timestamp = format!("{:?}", now);
 
     // Update the commitment
+    let mut commitment = existing.commitment.clone();
     commitment.state = "accepted".to_string();
     commitment.accepted_at = Some(timestamp.clone());
     commitment.updated_at = timestamp;
 
-    // Create a new version with update
-    let updated_action_hash = update_entry(action_hash.clone(), &EntryTypes::CustodianCommitment(commitment.clone()))?;
-
-    let entry_hash = hash_entry(&EntryTypes::CustodianCommitment(commitment.clone()))?;
-
-    Ok(CustodianCommitmentOutput {
-        action_hash: updated_action_hash,
-        entry_hash,
-        commitment,
-    })
+    update_custodian_commitment(existing, commitment)
 }
 
 /// Query custodian commitments by various criteria
+///
+/// Uses the most selective index available (custodian, beneficiary, state,
+/// type, basis) and applies the remaining criteria as filters.
 #[hdk_extern]
 pub fn query_custodian_commitments(input: QueryCommitmentsInput) -> ExternResult<Vec<CustodianCommitmentOutput>> {
-    let mut results = Vec::new();
     let limit = input.limit.unwrap_or(100) as usize;
 
-    // Query by custodian if specified
-    if let Some(custodian_id) = &input.custodian_agent_id {
-        let custodian_anchor = StringAnchor::new("custodian_id", custodian_id);
-        let custodian_anchor_hash = hash_entry(&EntryTypes::StringAnchor(custodian_anchor))?;
+    let (anchor, link_type) = if let Some(custodian_id) = &input.custodian_agent_id {
+        (StringAnchor::new("custodian_id", custodian_id), LinkTypes::CustodianToCommitment)
+    } else if let Some(beneficiary_id) = &input.beneficiary_agent_id {
+        (StringAnchor::new("beneficiary_id", beneficiary_id), LinkTypes::BeneficiaryToCommitment)
+    } else if let Some(state) = &input.state {
+        (StringAnchor::new("custodian_commitment_state", state), LinkTypes::CustodianCommitmentByState)
+    } else if let Some(commitment_type) = &input.commitment_type {
+        (StringAnchor::new("custodian_commitment_type", commitment_type), LinkTypes::CustodianCommitmentByType)
+    } else if let Some(basis) = &input.basis {
+        (StringAnchor::new("custodian_commitment_basis", basis), LinkTypes::CustodianCommitmentByBasis)
+    } else {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "At least one of custodian_agent_id, beneficiary_agent_id, state, commitment_type or basis is required".to_string()
+        )));
+    };
+
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
+    let query = LinkQuery::try_new(anchor_hash, link_type)?;
+    let links = get_links(query, GetStrategy::default())?;
 
-        let query = LinkQuery::try_new(custodian_anchor_hash, LinkTypes::CustodianToCommitment)?;
-        let links = get_links(query, GetStrategy::default())?;
+    let mut hashes = Vec::with_capacity(links.len());
+    for link in links {
+        let action_hash = ActionHash::try_from(link.target)
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid commitment hash".to_string())))?;
+        hashes.push(action_hash);
+    }
+    let records = get_records_batch(hashes.clone())?;
 
-        for link in links.iter().take(limit) {
-            let action_hash = ActionHash::try_from(link.target.clone())
-                .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid commitment hash".to_string())))?;
+    let matches = |value: &str, filter: &Option<String>| filter.as_ref().map_or(true, |f| f == value);
 
-            let record = get(action_hash.clone(), GetOptions::default())?;
-            if let Some(record) = record {
-                if let Some(commitment) = record.entry().to_app_option::<CustodianCommitment>().ok().flatten() {
-                    // Filter by state if specified
-                    if let Some(ref state) = input.state {
-                        if commitment.state != *state {
-                            continue;
-                        }
-                    }
-                    let entry_hash = hash_entry(&EntryTypes::CustodianCommitment(commitment.clone()))?;
-                    results.push(CustodianCommitmentOutput {
-                        action_hash,
-                        entry_hash,
-                        commitment,
-                    });
-                }
-            }
+    let mut results = Vec::new();
+    for (action_hash, record) in hashes.into_iter().zip(records) {
+        let commitment = match record.and_then(|r| r.entry().to_app_option::<CustodianCommitment>().ok().flatten()) {
+            Some(commitment) => commitment,
+            None => continue,
+        };
+        if !matches(&commitment.custodian_agent_id, &input.custodian_agent_id)
+            || !matches(&commitment.beneficiary_agent_id, &input.beneficiary_agent_id)
+            || !matches(&commitment.state, &input.state)
+            || !matches(&commitment.commitment_type, &input.commitment_type)
+            || !matches(&commitment.basis, &input.basis)
+        {
+            continue;
+        }
+        let entry_hash = hash_entry(&EntryTypes::CustodianCommitment(commitment.clone()))?;
+        results.push(CustodianCommitmentOutput {
+            action_hash,
+            entry_hash,
+            commitment,
+        });
+        if results.len() >= limit {
+            break;
         }
     }
 
-    // Query by beneficiary if specified (and no custodian specified)
-    if let Some(beneficiary_id) = &input.beneficiary_agent_id {
-        if input.custodian_agent_id.is_none() {
-            let beneficiary_anchor = StringAnchor::new("beneficiary_id", beneficiary_id);
-            let beneficiary_anchor_hash = hash_entry(&EntryTypes::StringAnchor(beneficiary_anchor))?;
+    Ok(results)
+}
 
-            let query = LinkQuery::try_new(beneficiary_anchor_hash, LinkTypes::BeneficiaryToCommitment)?;
-            let links = get_links(query, GetStrategy::default())?;
+/// Anchors and link types indexing a commitment
+fn custodian_commitment_index(commitment: &CustodianCommitment) -> ExternResult<Vec<(EntryHash, LinkTypes)>> {
+    let anchors = [
+        ("custodian_commitment_id", commitment.id.as_str(), LinkTypes::IdToCommitmentCustodian),
+        ("custodian_id", commitment.custodian_agent_id.as_str(), LinkTypes::CustodianToCommitment),
+        ("beneficiary_id", commitment.beneficiary_agent_id.as_str(), LinkTypes::BeneficiaryToCommitment),
+        ("custodian_commitment_type", commitment.commitment_type.as_str(), LinkTypes::CustodianCommitmentByType),
+        ("custodian_commitment_basis", commitment.basis.as_str(), LinkTypes::CustodianCommitmentByBasis),
+        ("custodian_commitment_state", commitment.state.as_str(), LinkTypes::CustodianCommitmentByState),
+    ];
+
+    anchors
+        .into_iter()
+        .map(|(anchor_type, value, link_type)| {
+            Ok((hash_entry(&EntryTypes::StringAnchor(StringAnchor::new(anchor_type, value)))?, link_type))
+        })
+        .collect()
+}
 
-            for link in links.iter().take(limit) {
-                let action_hash = ActionHash::try_from(link.target.clone())
-                    .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid commitment hash".to_string())))?;
-
-                let record = get(action_hash.clone(), GetOptions::default())?;
-                if let Some(record) = record {
-                    if let Some(commitment) = record.entry().to_app_option::<CustodianCommitment>().ok().flatten() {
-                        // Filter by state if specified
-                        if let Some(ref state) = input.state {
-                            if commitment.state != *state {
-                                continue;
-                            }
-                        }
-                        let entry_hash = hash_entry(&EntryTypes::CustodianCommitment(commitment.clone()))?;
-                        results.push(CustodianCommitmentOutput {
-                            action_hash,
-                            entry_hash,
-                            commitment,
-                        });
-                    }
-                }
+/// Write a new version of a commitment and move its index links to it
+fn update_custodian_commitment(
+    existing: CustodianCommitmentOutput,
+    commitment: CustodianCommitment,
+) -> ExternResult<CustodianCommitmentOutput> {
+    let action_hash = update_entry(existing.action_hash.clone(), &EntryTypes::CustodianCommitment(commitment.clone()))?;
+    let entry_hash = hash_entry(&EntryTypes::CustodianCommitment(commitment.clone()))?;
+
+    // Drop links to the previous version (including the old state index), then index the new one
+    let old_target: AnyLinkableHash = existing.action_hash.into();
+    for (anchor_hash, link_type) in custodian_commitment_index(&existing.commitment)? {
+        let query = LinkQuery::try_new(anchor_hash, link_type)?;
+        for link in get_links(query, GetStrategy::default())? {
+            if link.target == old_target {
+                delete_link(link.create_link_hash, GetOptions::default())?;
             }
         }
     }
+    for (anchor_hash, link_type) in custodian_commitment_index(&commitment)? {
+        create_link(anchor_hash, action_hash.clone(), link_type, ())?;
+    }
 
-    Ok(results)
+    Ok(CustodianCommitmentOutput {
+        action_hash,
+        entry_hash,
+        commitment,
+    })
 }
 
 // =============================================================================
@@ -8643,17 +17019,34 @@ pub fn generate_shards(input: GenerateShardsInput) -> ExternResult<GenerateShard
         )));
     }
 
-    // Calculate hash of complete content
-    let content_hash = calculate_sha256(&input.content_data);
+    // Calculate hash of complete content (carried inside every shard for verification)
+    let data = input.content_data.as_bytes();
+    let digest = sha256_digest(data)?;
+    let content_hash = to_hex(&digest);
 
-    // Generate shard hashes (deterministic, based on content + strategy + index)
-    // In production, would use actual Shamir or Reed-Solomon libraries
-    let mut shard_hashes = Vec::new();
-    for shard_index in 0..total_shards {
-        let shard_seed = format!("{}-{}-{}", input.content_id, shard_index, input.shard_strategy);
-        let shard_hash = calculate_sha256(&shard_seed);
-        shard_hashes.push(shard_hash);
-    }
+    let random = match sharding::random_bytes_needed(&input.shard_strategy, data.len(), input.redundancy_factor) {
+        0 => Vec::new(),
+        needed => random_bytes(needed as u32)?.to_vec(),
+    };
+
+    let shards: Vec<String> = sharding::encode_shards(
+        &input.shard_strategy,
+        data,
+        &digest,
+        input.redundancy_factor,
+        total_shards,
+        &random,
+    )
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e)))?
+    .iter()
+    .map(|shard| sharding::base64_encode(shard))
+    .collect();
+
+    // Shard hashes cover the encoded payload, matching what store_shard verifies
+    let shard_hashes = shards
+        .iter()
+        .map(|shard| shard_data_hash(shard))
+        .collect::<ExternResult<Vec<String>>>()?;
 
     Ok(GenerateShardsOutput {
         content_id: input.content_id,
@@ -8661,6 +17054,76 @@ pub fn generate_shards(input: GenerateShardsInput) -> ExternResult<GenerateShard
         total_shards,
         shard_hashes,
         content_hash,
+        shards,
+    })
+}
+
+/// Reassemble content from M-of-N shards produced by generate_shards
+///
+/// Clients gather shards from custodians (decrypting them first) and pass
+/// them here. The content digest carried inside the shards is checked
+/// against the reassembled content, and against `expected_content_hash` when
+/// given; any mismatch is an error rather than unverified content.
+#[hdk_extern]
+pub fn reconstruct_content(input: ReassembleShardsInput) -> ExternResult<ReconstructContentOutput> {
+    let output = reassemble_shards(&input.content_id, &input.shard_strategy, input.redundancy_factor, &input.shards)?;
+
+    if let Some(expected) = &input.expected_content_hash {
+        let actual = to_hex(&sha256_digest(output.content.as_bytes())?);
+        if !expected.eq_ignore_ascii_case(&actual) {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                "Reconstructed content hash {} does not match expected {}",
+                actual, expected
+            ))));
+        }
+    }
+
+    Ok(output)
+}
+
+/// Decode base64 shards, reassemble, and verify the embedded content digest
+fn reassemble_shards(
+    content_id: &str,
+    strategy: &str,
+    threshold: u32,
+    shards: &[String],
+) -> ExternResult<ReconstructContentOutput> {
+    // A shard that won't decode is as good as missing; the rest may still suffice
+    let decoded: Vec<Vec<u8>> = shards
+        .iter()
+        .filter_map(|shard| sharding::base64_decode(shard).ok())
+        .collect();
+    let decoded_count = decoded.len() as u32;
+    let shards_required = if strategy == "full_replica" { 1 } else { threshold };
+    if decoded_count < shards_required {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Only {} of {} shards are valid, {} required",
+            decoded_count,
+            shards.len(),
+            shards_required
+        ))));
+    }
+
+    let (digest, data) = sharding::decode_shards(strategy, &decoded, threshold)
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e)))?;
+
+    if sha256_digest(&data)?.as_slice() != digest.as_slice() {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Reconstructed content failed integrity check".to_string()
+        )));
+    }
+
+    let content = String::from_utf8(data)
+        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Reconstructed content is not valid UTF-8".to_string())))?;
+
+    Ok(ReconstructContentOutput {
+        content_id: content_id.to_string(),
+        content,
+        shards_gathered: decoded_count,
+        shards_required,
+        reconstruction_method: strategy.to_string(),
+        verification_status: "verified".to_string(),
+        error_message: None,
     })
 }
 
@@ -8730,99 +17193,179 @@ fn calculate_sha256(data: &str) -> String {
     format!("{:x}", hash)
 }
 
+/// Hex SHA-256 of shard data, as expected in `shard_hash`
+fn shard_data_hash(encrypted_shard_data: &str) -> ExternResult<String> {
+    Ok(to_hex(&sha256_digest(encrypted_shard_data.as_bytes())?))
+}
+
+fn sha256_digest(data: &[u8]) -> ExternResult<[u8; sharding::DIGEST_LEN]> {
+    hash_sha256(data.to_vec())?
+        .try_into()
+        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Unexpected SHA-256 digest length".to_string())))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
 /// Store shard on-chain (encrypted, with watermark, linked to commitment)
 ///
 /// Saves encrypted shard data to Holochain DHT with:
 /// - Watermark proving origin
-/// - Hash for integrity verification
+/// - Hash for integrity verification (rejected if it doesn't match the data)
 /// - Link to custodian commitment
 /// - Metadata for recovery
+///
+/// Called by the commitment's custodian once the commitment is accepted.
+/// Storing an index again replaces the earlier shard.
 #[hdk_extern]
 pub fn store_shard(input: StoreShardInput) -> ExternResult<StoredShardOutput> {
     let now = sys_time()?;
     let timestamp = format!("{:?}", now);
 
+    let existing = get_custodian_commitment(input.commitment_id.clone())?
+        .ok_or_else(|| wasm_error!(WasmErrorInner::Guest("Commitment not found".to_string())))?;
+
+    let custodian_id = agent_info()?.agent_initial_pubkey.to_string();
+    if existing.commitment.custodian_agent_id != custodian_id {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the commitment's custodian can store shards".to_string()
+        )));
+    }
+    if existing.commitment.state == "proposed" {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Commitment must be accepted before shards are stored".to_string()
+        )));
+    }
+
+    // Verify integrity before anything is written
+    let computed_hash = shard_data_hash(&input.encrypted_shard_data)?;
+    if computed_hash != input.shard_hash {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Shard hash mismatch: expected {}, computed {}",
+            input.shard_hash, computed_hash
+        ))));
+    }
+
     // Verify watermark validity
-    verify_watermark(
-        &input.watermark_signature,
-        &input.content_id,
-        &input.shard_hash,
-    )?;
+    if !verify_watermark(&input.watermark_signature, &input.content_id, &input.shard_hash)? {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Watermark does not match the shard's content_id and hash".to_string()
+        )));
+    }
 
-    // Create shard metadata entry (simplified - in production would be a full Shard entry type)
-    // For now, store as StringAnchor + metadata to avoid adding new entry type
-    let shard_key = format!(
-        "shard-{}-{}-{}",
-        input.content_id, input.shard_index, input.total_shards
-    );
+    let shard = CustodianShard {
+        id: format!("shard-{}-{}-{}", input.commitment_id, input.content_id, input.shard_index),
+        commitment_id: input.commitment_id.clone(),
+        content_id: input.content_id.clone(),
+        custodian_agent_id: custodian_id,
+        shard_index: input.shard_index,
+        total_shards: input.total_shards,
+        encrypted_shard_data: input.encrypted_shard_data,
+        encryption_method: input.encryption_method,
+        shard_hash: input.shard_hash,
+        watermark_signature: input.watermark_signature,
+        stored_at: timestamp.clone(),
+    };
 
-    let shard_anchor = StringAnchor::new("shard_storage", &shard_key);
-    let shard_anchor_hash = hash_entry(&EntryTypes::StringAnchor(shard_anchor))?;
+    // Replace an earlier copy of the same shard
+    let previous: Vec<Link> = get_shards_for_commitment(&input.commitment_id)?
+        .into_iter()
+        .filter(|(_, s)| s.content_id == shard.content_id && s.shard_index == shard.shard_index)
+        .map(|(link, _)| link)
+        .collect();
+    let replacing = !previous.is_empty();
+    for link in previous {
+        delete_link(link.create_link_hash, GetOptions::default())?;
+    }
 
-    // Store shard metadata as a StringAnchor (the encrypted_shard_data would be stored separately in production)
-    let shard_metadata = StringAnchor::new(
-        "shard_metadata",
-        &format!(
-            "content:{}|index:{}|total:{}|hash:{}",
-            input.content_id, input.shard_index, input.total_shards, input.shard_hash
-        ),
-    );
-    let metadata_hash = create_entry(&EntryTypes::StringAnchor(shard_metadata))?;
+    let action_hash = create_entry(&EntryTypes::CustodianShard(shard.clone()))?;
 
-    // Link shard to commitment
-    let commitment_anchor = StringAnchor::new("custodian_commitment_id", &input.commitment_id);
+    // Link shard to commitment (verification) and content (recovery)
+    let commitment_anchor = StringAnchor::new("custodian_commitment_shards", &shard.commitment_id);
     let commitment_anchor_hash = hash_entry(&EntryTypes::StringAnchor(commitment_anchor))?;
+    create_link(commitment_anchor_hash, action_hash.clone(), LinkTypes::CommitmentToShard, ())?;
 
-    create_link(
-        commitment_anchor_hash,
-        metadata_hash.clone(),
-        LinkTypes::ContentToCommitmentCustodian,
-        (),
-    )?;
-
-    // Link shards by content for recovery queries
-    let content_anchor = StringAnchor::new("content_id", &input.content_id);
+    let content_anchor = StringAnchor::new("content_shards", &shard.content_id);
     let content_anchor_hash = hash_entry(&EntryTypes::StringAnchor(content_anchor))?;
+    create_link(content_anchor_hash, action_hash.clone(), LinkTypes::ContentToShard, ())?;
 
-    create_link(
-        content_anchor_hash,
-        metadata_hash.clone(),
-        LinkTypes::ContentToCommitmentCustodian,
-        (),
-    )?;
+    // Track fulfillment on the commitment
+    let mut commitment = existing.commitment.clone();
+    if !replacing {
+        commitment.shards_stored_count += 1;
+    }
+    commitment.last_shard_update_at = Some(timestamp.clone());
+    commitment.updated_at = timestamp.clone();
+    update_custodian_commitment(existing, commitment)?;
 
     Ok(StoredShardOutput {
-        action_hash: metadata_hash,
-        content_id: input.content_id,
-        shard_index: input.shard_index,
+        action_hash,
+        content_id: shard.content_id,
+        shard_index: shard.shard_index,
         stored_at: timestamp,
     })
 }
 
-/// Verify shard integrity via probabilistic sampling
+/// Verify shard integrity
 ///
-/// Instead of verifying all shards constantly (expensive), sample-verify:
-/// - Verify 10% of shards per cycle
-/// - Detects failures early
-/// - Reduces DHT load
+/// Re-hashes the stored shard data and checks it against the recorded hash
+/// and watermark. The result is recorded on the commitment
+/// (`last_verification_at`, and `verification_failures_json` on failure) so
+/// probabilistic sampling across custodians leaves an audit trail.
+///
+/// Only the commitment's custodian, its beneficiary, or a custodian listed in
+/// its shard assignments may verify, since the result is written back.
 #[hdk_extern]
 pub fn verify_shard(input: VerifyShardInput) -> ExternResult<bool> {
-    // Get commitment to find shard assignments
-    let commitment_anchor = StringAnchor::new("custodian_commitment_id", &input.commitment_id);
-    let commitment_anchor_hash = hash_entry(&EntryTypes::StringAnchor(commitment_anchor))?;
-
-    let query = LinkQuery::try_new(commitment_anchor_hash, LinkTypes::ContentToCommitmentCustodian)?;
-    let links = get_links(query, GetStrategy::default())?;
+    let existing = get_custodian_commitment(input.commitment_id.clone())?
+        .ok_or_else(|| wasm_error!(WasmErrorInner::Guest("Commitment not found".to_string())))?;
 
-    if links.is_empty() {
+    let current_agent = agent_info()?.agent_initial_pubkey.to_string();
+    if !may_verify_shards(&existing.commitment, &current_agent) {
         return Err(wasm_error!(WasmErrorInner::Guest(
-            "Commitment not found".to_string()
+            "Only the commitment's custodians or beneficiary can verify its shards".to_string()
         )));
     }
 
-    // For now, return success if commitment exists
-    // In production: retrieve actual shard, verify hash, verify watermark
-    Ok(true)
+    let shard = get_shards_for_commitment(&input.commitment_id)?
+        .into_iter()
+        .map(|(_, shard)| shard)
+        .find(|shard| shard.shard_index == input.shard_index)
+        .ok_or_else(|| wasm_error!(WasmErrorInner::Guest(format!(
+            "Shard {} not stored for commitment {}",
+            input.shard_index, input.commitment_id
+        ))))?;
+
+    let hash_ok = shard_data_hash(&shard.encrypted_shard_data)? == shard.shard_hash;
+    let watermark_ok = verify_watermark(&shard.watermark_signature, &shard.content_id, &shard.shard_hash)?;
+    let verified = hash_ok && watermark_ok;
+
+    let timestamp = format!("{:?}", sys_time()?);
+    let mut commitment = existing.commitment.clone();
+    commitment.last_verification_at = Some(timestamp.clone());
+    if !verified {
+        let mut failures: Vec<serde_json::Value> =
+            serde_json::from_str(&commitment.verification_failures_json).unwrap_or_default();
+        failures.push(serde_json::json!({
+            "shard_index": shard.shard_index,
+            "content_id": shard.content_id,
+            "reason": if hash_ok { "watermark_mismatch" } else { "hash_mismatch" },
+            "detected_at": timestamp,
+        }));
+        commitment.verification_failures_json = serde_json::to_string(&failures).unwrap_or_else(|_| "[]".to_string());
+    }
+    commitment.updated_at = timestamp;
+    update_custodian_commitment(existing, commitment)?;
+
+    Ok(verified)
+}
+
+/// Custodian, beneficiary, or a custodian listed in the shard assignments
+fn may_verify_shards(commitment: &CustodianCommitment, agent: &str) -> bool {
+    commitment.custodian_agent_id == agent
+        || commitment.beneficiary_agent_id == agent
+        || consensus_voters(commitment).iter().any(|voter| voter == agent)
 }
 
 /// Retrieve shard for recovery (requires authorization)
@@ -8830,39 +17373,78 @@ pub fn verify_shard(input: VerifyShardInput) -> ExternResult<bool> {
 /// Returns encrypted shard data that can be used for content reconstruction.
 /// In emergency mode, multiple custodians provide their shards which are
 /// combined (Shamir reconstruction or Reed-Solomon decode) to restore content.
+///
+/// The caller must be the shard's custodian or the commitment's beneficiary,
+/// unless the commitment's emergency protocol has been activated. Shards whose
+/// data no longer matches their hash are skipped.
 #[hdk_extern]
 pub fn get_shard(input: GetShardInput) -> ExternResult<GetShardOutput> {
-    // Query for shard by content_id
-    let content_anchor = StringAnchor::new("content_id", &input.content_id);
+    let content_anchor = StringAnchor::new("content_shards", &input.content_id);
     let content_anchor_hash = hash_entry(&EntryTypes::StringAnchor(content_anchor))?;
 
-    let query = LinkQuery::try_new(content_anchor_hash, LinkTypes::ContentToCommitmentCustodian)?;
-    let links = get_links(query, GetStrategy::default())?;
+    let query = LinkQuery::try_new(content_anchor_hash, LinkTypes::ContentToShard)?;
+    let shards = get_shards_from_links(get_links(query, GetStrategy::default())?)?;
 
-    if links.is_empty() {
-        return Err(wasm_error!(WasmErrorInner::Guest(
-            "Shard not found".to_string()
-        )));
+    let current_agent = agent_info()?.agent_initial_pubkey.to_string();
+    let mut unauthorized = false;
+    for (_, shard) in shards.into_iter().filter(|(_, s)| s.shard_index == input.shard_index) {
+        if shard_data_hash(&shard.encrypted_shard_data)? != shard.shard_hash {
+            continue;
+        }
+        if shard.custodian_agent_id != current_agent {
+            let authorized = get_custodian_commitment(shard.commitment_id.clone())?
+                .map_or(false, |c| c.commitment.beneficiary_agent_id == current_agent || c.commitment.activated_at.is_some());
+            if !authorized {
+                unauthorized = true;
+                continue;
+            }
+        }
+
+        return Ok(GetShardOutput {
+            content_id: shard.content_id,
+            shard_index: shard.shard_index,
+            total_shards: shard.total_shards,
+            encrypted_shard_data: shard.encrypted_shard_data,
+            encryption_method: shard.encryption_method,
+            shard_hash: shard.shard_hash,
+            watermark_signature: shard.watermark_signature,
+            stored_at: shard.stored_at,
+        });
     }
 
-    // Get first matching shard (in production: filter by shard_index)
-    let action_hash = ActionHash::try_from(links[0].target.clone())
-        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid shard hash".to_string())))?;
+    Err(wasm_error!(WasmErrorInner::Guest(if unauthorized {
+        "Not authorized to retrieve this shard".to_string()
+    } else {
+        "Shard not found".to_string()
+    })))
+}
 
-    let record = get(action_hash, GetOptions::default())?
-        .ok_or_else(|| wasm_error!(WasmErrorInner::Guest("Shard record not found".to_string())))?;
+/// Shards stored under a commitment, with the links pointing at them
+fn get_shards_for_commitment(commitment_id: &str) -> ExternResult<Vec<(Link, CustodianShard)>> {
+    let anchor = StringAnchor::new("custodian_commitment_shards", commitment_id);
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(anchor))?;
+    let query = LinkQuery::try_new(anchor_hash, LinkTypes::CommitmentToShard)?;
+    get_shards_from_links(get_links(query, GetStrategy::default())?)
+}
 
-    // Extract shard metadata (simplified placeholder response)
-    Ok(GetShardOutput {
-        content_id: input.content_id.clone(),
-        shard_index: input.shard_index,
-        total_shards: 8,
-        encrypted_shard_data: "ENCRYPTED_DATA_PLACEHOLDER".to_string(),
-        encryption_method: "xchacha20".to_string(),
-        shard_hash: calculate_sha256(&format!("{}-{}", input.content_id, input.shard_index)),
-        watermark_signature: calculate_sha256("watermark"),
-        stored_at: format!("{:?}", sys_time()?),
-    })
+fn get_shards_from_links(links: Vec<Link>) -> ExternResult<Vec<(Link, CustodianShard)>> {
+    let mut hashes = Vec::with_capacity(links.len());
+    for link in &links {
+        let action_hash = ActionHash::try_from(link.target.clone())
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid shard hash".to_string())))?;
+        hashes.push(action_hash);
+    }
+
+    let records = get_records_batch(hashes)?;
+    Ok(links
+        .into_iter()
+        .zip(records)
+        .filter_map(|(link, record)| {
+            record
+                .and_then(|r| r.entry().to_app_option::<CustodianShard>().ok().flatten())
+                .map(|shard| (link, shard))
+        })
+        .collect())
 }
 
 // =============================================================================
@@ -8923,8 +17505,12 @@ fn on_relationship_updated(relationship: Relationship) -> ExternResult<()> {
         estimated_content_count: 0,
         estimated_size_mb: 0.0,
         shard_strategy: "full_replica".to_string(),
-        redundancy_factor: 2,
-        shard_assignments_json: "[]".to_string(),
+        redundancy_factor: 1,
+        shard_assignments_json: serde_json::to_string(&vec![ShardAssignment {
+            shard_index: 0,
+            custodian_agent_id: relationship.target_id.clone(),
+        }])
+        .unwrap_or_else(|_| "[]".to_string()),
         emergency_triggers_json: serde_json::to_string(&vec![
             serde_json::json!({
                 "trigger_type": "manual_signal",
@@ -8961,7 +17547,8 @@ fn on_relationship_updated(relationship: Relationship) -> ExternResult<()> {
     // Create source → target commitment
     let _ = create_custodian_commitment(source_to_target_input);
 
-    // Create bidirectional commitment (target → source)
+    // Create bidirectional commitment (target → source); only succeeds when the
+    // target is the author, since a commitment is proposed by its beneficiary
     let target_to_source_input = CreateCustodianCommitmentInput {
         custodian_agent_id: relationship.source_id.clone(),
         beneficiary_agent_id: relationship.target_id.clone(),
@@ -8982,8 +17569,12 @@ fn on_relationship_updated(relationship: Relationship) -> ExternResult<()> {
         estimated_content_count: 0,
         estimated_size_mb: 0.0,
         shard_strategy: "full_replica".to_string(),
-        redundancy_factor: 2,
-        shard_assignments_json: "[]".to_string(),
+        redundancy_factor: 1,
+        shard_assignments_json: serde_json::to_string(&vec![ShardAssignment {
+            shard_index: 0,
+            custodian_agent_id: relationship.source_id.clone(),
+        }])
+        .unwrap_or_else(|_| "[]".to_string()),
         emergency_triggers_json: serde_json::to_string(&vec![
             serde_json::json!({
                 "trigger_type": "manual_signal",
@@ -9198,51 +17789,95 @@ pub fn activate_emergency_trusted_party(
 
 /// Submit vote for M-of-N consensus-based emergency activation
 ///
-/// Any custodian can vote to approve recovery (or reject).
-/// Once M of N custodians approve, emergency is activated.
+/// Any custodian listed in the commitment's shard assignments whose own
+/// commitment to the beneficiary is accepted can vote once to approve
+/// recovery (or reject). The vote is signed by the caller and
+/// stored as a ConsensusVote entry. Once M approvals accumulate, the
+/// commitment is activated.
 #[hdk_extern]
 pub fn submit_consensus_vote(input: SubmitConsensusVoteInput) -> ExternResult<ConsensusVoteStatus> {
     // Get commitment
-    let commitment = get_commitment_by_id(&input.commitment_id)?
+    let existing = get_custodian_commitment(input.commitment_id.clone())?
         .ok_or(wasm_error!(WasmErrorInner::Guest("Commitment not found".to_string())))?;
 
-    // Extract consensus trigger
-    let triggers: Vec<EmergencyTriggerSpec> = serde_json::from_str(&commitment.emergency_triggers_json)
-        .unwrap_or_default();
-
-    let consensus_trigger = triggers
-        .iter()
-        .find(|t| t.trigger_type == "m_of_n_consensus" && t.enabled)
-        .ok_or(wasm_error!(WasmErrorInner::Guest(
-            "M-of-N consensus trigger not enabled".to_string()
-        )))?;
+    // Votes are signed with the caller's key, so the caller must be the named custodian
+    let me = agent_info()?.agent_initial_pubkey;
+    if input.custodian_id != me.to_string() {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Custodians can only cast their own vote".to_string()
+        )));
+    }
 
-    let threshold_m = consensus_trigger.consensus_m
-        .ok_or(wasm_error!(WasmErrorInner::Guest(
-            "Consensus threshold M not configured".to_string()
-        )))?;
+    let threshold_m = consensus_threshold(&existing.commitment)?;
 
-    let threshold_n = consensus_trigger.consensus_n
-        .ok_or(wasm_error!(WasmErrorInner::Guest(
-            "Consensus total N not configured".to_string()
-        )))?;
+    // Only custodians holding a shard of this commitment may vote
+    if !consensus_voters(&existing.commitment).contains(&input.custodian_id) {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Custodian is not listed in this commitment's shard assignments".to_string()
+        )));
+    }
+    if !accepted_consensus_voters(&existing.commitment)?.contains(&input.custodian_id) {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Custodian has not accepted a commitment to this beneficiary".to_string()
+        )));
+    }
 
-    // Store vote: create an anchor entry for voting record (simplified placeholder)
-    // In production, would store as StringAnchor and link to commitment with:
-    // {commitment_id, custodian_id, vote_approve, reason, timestamp}
+    if existing.commitment.state == "activated" {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Commitment is already activated".to_string()
+        )));
+    }
 
-    // For now, just return vote status placeholder
+    // Prevent double voting
+    let votes = get_consensus_votes(&input.commitment_id)?;
+    if votes.iter().any(|vote| vote.custodian_agent_id == input.custodian_id) {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Custodian has already voted on this commitment".to_string()
+        )));
+    }
 
-    // Calculate current vote status (simplified: return placeholder)
-    let status = ConsensusVoteStatus {
-        total_custodians: threshold_n,
-        votes_received: 1, // Placeholder
-        approves: if input.vote_approve { 1 } else { 0 },
-        rejects: if !input.vote_approve { 1 } else { 0 },
-        threshold_m,
-        threshold_reached: false, // Placeholder
-        activation_status: "pending".to_string(),
+    let payload = ConsensusVotePayload {
+        commitment_id: input.commitment_id.clone(),
+        custodian_agent_id: input.custodian_id.clone(),
+        vote_approve: input.vote_approve,
     };
+    let signature = sign(me, payload)?;
+
+    let vote = ConsensusVote {
+        id: format!("vote-{}-{}", input.commitment_id, input.custodian_id),
+        commitment_id: input.commitment_id.clone(),
+        custodian_agent_id: input.custodian_id.clone(),
+        vote_approve: input.vote_approve,
+        reason: input.reason.clone(),
+        signature: signature.0.to_vec(),
+        voted_at: format!("{:?}", sys_time()?),
+    };
+    let action_hash = create_entry(EntryTypes::ConsensusVote(vote))?;
+
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("consensus_votes", &input.commitment_id)))?;
+    create_link(anchor_hash, action_hash, LinkTypes::CommitmentToConsensusVote, ())?;
+
+    let status = tally_consensus_votes(&existing.commitment, threshold_m)?;
+
+    // Threshold reached: activate the commitment
+    if status.threshold_reached {
+        let mut commitment = existing.commitment.clone();
+        let timestamp = format!("{:?}", sys_time()?);
+        commitment.state = "activated".to_string();
+        commitment.activated_at = Some(timestamp.clone());
+        commitment.metadata_json = serde_json::to_string(&serde_json::json!({
+            "activation_type": "m_of_n_consensus",
+            "approves": status.approves,
+            "threshold_m": threshold_m,
+            "original_metadata": serde_json::from_str::<serde_json::Value>(&commitment.metadata_json).ok(),
+        }))
+        .unwrap_or_else(|_| "{}".to_string());
+        commitment.updated_at = timestamp;
+
+        let updated = update_custodian_commitment(existing, commitment)?;
+        let reason = format!("{} of {} custodians approved recovery", status.approves, status.total_custodians);
+        let _ = notify_emergency_contacts(&updated.commitment, &reason);
+    }
 
     Ok(status)
 }
@@ -9256,7 +17891,12 @@ pub fn check_consensus_status(input: CheckConsensusStatusInput) -> ExternResult<
     let commitment = get_commitment_by_id(&input.commitment_id)?
         .ok_or(wasm_error!(WasmErrorInner::Guest("Commitment not found".to_string())))?;
 
-    // Extract consensus trigger
+    let threshold_m = consensus_threshold(&commitment)?;
+    tally_consensus_votes(&commitment, threshold_m)
+}
+
+/// Approvals needed (M) from the commitment's enabled m_of_n_consensus trigger
+fn consensus_threshold(commitment: &CustodianCommitment) -> ExternResult<u32> {
     let triggers: Vec<EmergencyTriggerSpec> = serde_json::from_str(&commitment.emergency_triggers_json)
         .unwrap_or_default();
 
@@ -9267,27 +17907,350 @@ pub fn check_consensus_status(input: CheckConsensusStatusInput) -> ExternResult<
             "M-of-N consensus trigger not enabled".to_string()
         )))?;
 
-    let threshold_m = consensus_trigger.consensus_m.unwrap_or(0);
-    let threshold_n = consensus_trigger.consensus_n.unwrap_or(0);
+    check_consensus_threshold(&commitment.shard_strategy, consensus_trigger.consensus_m)
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e)))
+}
+
+/// M must be set, and at least 2 for threshold_split so no single custodian can trigger recovery
+fn check_consensus_threshold(shard_strategy: &str, consensus_m: Option<u32>) -> Result<u32, String> {
+    match consensus_m {
+        Some(m) if shard_strategy == "threshold_split" && m < 2 => Err(format!(
+            "Consensus threshold M must be at least 2 for threshold_split, got {}",
+            m
+        )),
+        Some(m) if m > 0 => Ok(m),
+        _ => Err("Consensus threshold M not configured".to_string()),
+    }
+}
+
+/// Distinct custodians listed in the commitment's shard assignments
+fn consensus_voters(commitment: &CustodianCommitment) -> Vec<String> {
+    let assignments: Vec<ShardAssignment> =
+        serde_json::from_str(&commitment.shard_assignments_json).unwrap_or_default();
+    let mut voters: Vec<String> = Vec::new();
+    for assignment in assignments {
+        if !voters.contains(&assignment.custodian_agent_id) {
+            voters.push(assignment.custodian_agent_id);
+        }
+    }
+    voters
+}
+
+/// Consensus voters whose own commitment to the beneficiary has been accepted
+///
+/// A custodian's commitment is keyed `{beneficiary}-{custodian}`, as in
+/// create_custodian_commitment.
+fn accepted_consensus_voters(commitment: &CustodianCommitment) -> ExternResult<Vec<String>> {
+    let mut accepted = Vec::new();
+    for voter in consensus_voters(commitment) {
+        let id = format!("{}-{}", commitment.beneficiary_agent_id, voter);
+        if get_commitment_by_id(&id)?.is_some_and(|c| is_accepted_commitment(&c)) {
+            accepted.push(voter);
+        }
+    }
+    Ok(accepted)
+}
+
+/// Accepted by the custodian and not since cancelled or breached
+fn is_accepted_commitment(commitment: &CustodianCommitment) -> bool {
+    commitment.accepted_at.is_some()
+        && !matches!(commitment.state.as_str(), "proposed" | "cancelled" | "breached")
+}
+
+/// All votes recorded for a commitment, oldest first
+fn get_consensus_votes(commitment_id: &str) -> ExternResult<Vec<ConsensusVote>> {
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("consensus_votes", commitment_id)))?;
+    let query = LinkQuery::try_new(anchor_hash, LinkTypes::CommitmentToConsensusVote)?;
+    let mut links = get_links(query, GetStrategy::default())?;
+    links.sort_by_key(|link| link.timestamp);
+
+    let mut hashes = Vec::with_capacity(links.len());
+    for link in &links {
+        let action_hash = ActionHash::try_from(link.target.clone())
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid vote hash".to_string())))?;
+        hashes.push(action_hash);
+    }
+
+    Ok(get_records_batch(hashes)?
+        .into_iter()
+        .flatten()
+        .filter_map(|record| record.entry().to_app_option::<ConsensusVote>().ok().flatten())
+        .collect())
+}
+
+/// Count valid votes: signed by the named custodian, listed in the shard
+/// assignments with an accepted commitment, and only the first vote per custodian.
+fn tally_consensus_votes(commitment: &CustodianCommitment, threshold_m: u32) -> ExternResult<ConsensusVoteStatus> {
+    let voters = accepted_consensus_voters(commitment)?;
+    let mut counted: HashSet<String> = HashSet::new();
+    let mut approves = 0u32;
+    let mut rejects = 0u32;
+
+    for vote in get_consensus_votes(&commitment.id)? {
+        if !voters.contains(&vote.custodian_agent_id) || counted.contains(&vote.custodian_agent_id) {
+            continue;
+        }
+        if !vote.verify()? {
+            continue;
+        }
+        counted.insert(vote.custodian_agent_id.clone());
+        if vote.vote_approve {
+            approves += 1;
+        } else {
+            rejects += 1;
+        }
+    }
+
+    let total_custodians = voters.len() as u32;
+    let threshold_reached = approves >= threshold_m;
+    let activation_status = if threshold_reached || commitment.state == "activated" {
+        "approved"
+    } else if total_custodians.saturating_sub(rejects) < threshold_m {
+        // Not enough custodians left to reach M approvals
+        "rejected"
+    } else {
+        "pending"
+    };
 
-    // Query votes (simplified placeholder)
-    // In production, would aggregate actual votes from DHT
-    let status = ConsensusVoteStatus {
-        total_custodians: threshold_n,
-        votes_received: 0,
-        approves: 0,
-        rejects: 0,
+    Ok(ConsensusVoteStatus {
+        total_custodians,
+        votes_received: approves + rejects,
+        approves,
+        rejects,
         threshold_m,
-        threshold_reached: false,
-        activation_status: "pending".to_string(),
+        threshold_reached,
+        activation_status: activation_status.to_string(),
+    })
+}
+
+// =============================================================================
+// Dead Man's Switch
+// =============================================================================
+
+/// Beneficiary check-in: proves the beneficiary is still around.
+///
+/// One check-in covers every commitment where the caller is beneficiary.
+/// Returns the check-in time in microseconds.
+#[hdk_extern]
+pub fn beneficiary_checkin(_: ()) -> ExternResult<i64> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("beneficiary_checkin", &me.to_string())))?;
+    replace_timestamp_link(anchor_hash, me, LinkTypes::BeneficiaryCheckin)
+}
+
+/// Custodian heartbeat for a commitment.
+///
+/// Records that the custodian is still holding its shards and reports the
+/// dead man's switch state so the custodian can join the activation flow.
+#[hdk_extern]
+pub fn custodian_heartbeat(commitment_id: String) -> ExternResult<CustodianHeartbeatOutput> {
+    let existing = get_custodian_commitment(commitment_id.clone())?
+        .ok_or_else(|| wasm_error!(WasmErrorInner::Guest("Commitment not found".to_string())))?;
+
+    let me = agent_info()?.agent_initial_pubkey;
+    if existing.commitment.custodian_agent_id != me.to_string() {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the commitment's custodian can send heartbeats".to_string()
+        )));
+    }
+
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("custodian_heartbeat", &commitment_id)))?;
+    let heartbeat_micros = replace_timestamp_link(anchor_hash, me, LinkTypes::CustodianHeartbeat)?;
+
+    Ok(CustodianHeartbeatOutput {
+        commitment_id,
+        heartbeat_micros,
+        dead_mans_switch: dead_mans_switch_status(&existing, sys_time()?.as_micros())?,
+    })
+}
+
+/// Enable, disable or change the inactivity window of a commitment's dead man's switch
+#[hdk_extern]
+pub fn configure_dead_mans_switch(input: ConfigureDeadMansSwitchInput) -> ExternResult<CustodianCommitmentOutput> {
+    let existing = get_custodian_commitment(input.commitment_id.clone())?
+        .ok_or_else(|| wasm_error!(WasmErrorInner::Guest("Commitment not found".to_string())))?;
+
+    if existing.commitment.beneficiary_agent_id != agent_info()?.agent_initial_pubkey.to_string() {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the beneficiary can configure the dead man's switch".to_string()
+        )));
+    }
+    if input.inactivity_days == 0 {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "inactivity_days must be at least 1".to_string()
+        )));
+    }
+
+    let mut triggers: Vec<EmergencyTriggerSpec> =
+        serde_json::from_str(&existing.commitment.emergency_triggers_json).unwrap_or_default();
+    triggers.retain(|t| t.trigger_type != "dead_mans_switch");
+    triggers.push(EmergencyTriggerSpec {
+        trigger_type: "dead_mans_switch".to_string(),
+        enabled: input.enabled,
+        passphrase_hash: None,
+        trusted_agent_ids: None,
+        consensus_m: None,
+        consensus_n: None,
+        inactivity_days: Some(input.inactivity_days),
+    });
+
+    let mut commitment = existing.commitment.clone();
+    commitment.emergency_triggers_json = serde_json::to_string(&triggers).unwrap_or_else(|_| "[]".to_string());
+    commitment.updated_at = format!("{:?}", sys_time()?);
+
+    // Configuring the switch counts as a check-in, so enabling it never fires immediately
+    beneficiary_checkin(())?;
+
+    update_custodian_commitment(existing, commitment)
+}
+
+/// Dead man's switch state for a commitment (None if the trigger isn't configured)
+#[hdk_extern]
+pub fn check_dead_mans_switch(commitment_id: String) -> ExternResult<Option<DeadMansSwitchStatus>> {
+    let existing = get_custodian_commitment(commitment_id)?
+        .ok_or_else(|| wasm_error!(WasmErrorInner::Guest("Commitment not found".to_string())))?;
+    dead_mans_switch_status(&existing, sys_time()?.as_micros())
+}
+
+/// Commitments whose beneficiary has missed the check-in window.
+///
+/// Polled by the doorway's dead man's switch job. Only accepted commitments
+/// are considered; proposed, already-activated and pending ones are skipped.
+#[hdk_extern]
+pub fn get_overdue_dead_mans_switches(_: ()) -> ExternResult<Vec<DeadMansSwitchStatus>> {
+    let now_micros = sys_time()?.as_micros();
+    let accepted = query_custodian_commitments(QueryCommitmentsInput {
+        custodian_agent_id: None,
+        beneficiary_agent_id: None,
+        commitment_type: None,
+        state: Some("accepted".to_string()),
+        basis: None,
+        limit: Some(1000),
+    })?;
+
+    let mut overdue = Vec::new();
+    for commitment in accepted {
+        if let Some(status) = dead_mans_switch_status(&commitment, now_micros)? {
+            if status.overdue {
+                overdue.push(status);
+            }
+        }
+    }
+    Ok(overdue)
+}
+
+/// Fire a commitment's dead man's switch once the beneficiary is overdue.
+///
+/// If the commitment also has M-of-N consensus enabled, this opens the vote
+/// (state `consensus_pending`) so custodians confirm before recovery;
+/// otherwise the commitment is activated directly. Emergency contacts are
+/// notified either way. Re-checks the window, so a late check-in wins.
+#[hdk_extern]
+pub fn trigger_dead_mans_switch(commitment_id: String) -> ExternResult<DeadMansSwitchStatus> {
+    let existing = get_custodian_commitment(commitment_id.clone())?
+        .ok_or_else(|| wasm_error!(WasmErrorInner::Guest("Commitment not found".to_string())))?;
+
+    let now = sys_time()?;
+    let status = dead_mans_switch_status(&existing, now.as_micros())?
+        .filter(|s| s.enabled)
+        .ok_or_else(|| wasm_error!(WasmErrorInner::Guest("Dead man's switch is not enabled".to_string())))?;
+
+    if !status.overdue || existing.commitment.state != "accepted" {
+        return Ok(status);
+    }
+
+    let triggers: Vec<EmergencyTriggerSpec> =
+        serde_json::from_str(&existing.commitment.emergency_triggers_json).unwrap_or_default();
+    let needs_consensus = triggers.iter().any(|t| t.trigger_type == "m_of_n_consensus" && t.enabled);
+
+    let timestamp = format!("{:?}", now);
+    let mut commitment = existing.commitment.clone();
+    if needs_consensus {
+        commitment.state = "consensus_pending".to_string();
+    } else {
+        commitment.state = "activated".to_string();
+        commitment.activated_at = Some(timestamp.clone());
+    }
+    let mut metadata: serde_json::Value =
+        serde_json::from_str(&commitment.metadata_json).unwrap_or_else(|_| serde_json::json!({}));
+    if let Some(obj) = metadata.as_object_mut() {
+        obj.insert("dead_mans_switch_fired_at".to_string(), serde_json::json!(timestamp));
+        obj.insert("last_checkin_micros".to_string(), serde_json::json!(status.last_checkin_micros));
+    }
+    commitment.metadata_json = metadata.to_string();
+    commitment.updated_at = timestamp;
+
+    let updated = update_custodian_commitment(existing, commitment)?;
+    let reason = format!("No check-in for {} days", status.inactivity_days);
+    let _ = notify_emergency_contacts(&updated.commitment, &reason);
+
+    Ok(DeadMansSwitchStatus {
+        commitment_state: updated.commitment.state,
+        ..status
+    })
+}
+
+/// Compute the dead man's switch state for a commitment
+fn dead_mans_switch_status(
+    existing: &CustodianCommitmentOutput,
+    now_micros: i64,
+) -> ExternResult<Option<DeadMansSwitchStatus>> {
+    let triggers: Vec<EmergencyTriggerSpec> =
+        serde_json::from_str(&existing.commitment.emergency_triggers_json).unwrap_or_default();
+    let trigger = match triggers.iter().find(|t| t.trigger_type == "dead_mans_switch") {
+        Some(trigger) => trigger,
+        None => return Ok(None),
+    };
+    let inactivity_days = trigger.inactivity_days.unwrap_or(DEFAULT_INACTIVITY_DAYS);
+
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new(
+        "beneficiary_checkin",
+        &existing.commitment.beneficiary_agent_id,
+    )))?;
+    // Anyone can link under the anchor; only the beneficiary's own check-ins count
+    let query = LinkQuery::try_new(anchor_hash, LinkTypes::BeneficiaryCheckin)?;
+    let last_checkin = get_links(query, GetStrategy::default())?
+        .iter()
+        .filter(|link| link.author.to_string() == existing.commitment.beneficiary_agent_id)
+        .map(|link| link.timestamp.as_micros())
+        .max();
+
+    // Without any check-in, the window runs from when this version of the commitment was written
+    let last_checkin_micros = match last_checkin {
+        Some(micros) => micros,
+        None => get(existing.action_hash.clone(), GetOptions::default())?
+            .map(|record| record.action().timestamp().as_micros())
+            .unwrap_or(now_micros),
     };
 
-    Ok(status)
+    let window_micros = inactivity_days as i64 * 86_400 * 1_000_000;
+    Ok(Some(DeadMansSwitchStatus {
+        commitment_id: existing.commitment.id.clone(),
+        beneficiary_agent_id: existing.commitment.beneficiary_agent_id.clone(),
+        enabled: trigger.enabled,
+        inactivity_days,
+        last_checkin_micros,
+        overdue: trigger.enabled && now_micros - last_checkin_micros > window_micros,
+        commitment_state: existing.commitment.state.clone(),
+    }))
+}
+
+/// Replace the caller's previous timestamp link on `base` with a fresh one, returning its time
+fn replace_timestamp_link(base: EntryHash, agent: AgentPubKey, link_type: LinkTypes) -> ExternResult<i64> {
+    let query = LinkQuery::try_new(base.clone(), link_type)?;
+    for link in get_links(query, GetStrategy::default())? {
+        if link.author == agent {
+            delete_link(link.create_link_hash, GetOptions::default())?;
+        }
+    }
+    create_link(base, agent, link_type, ())?;
+    Ok(sys_time()?.as_micros())
 }
 
 /// Reconstruct content from shards during emergency recovery
 ///
-/// Gathers shards from custodians, verifies watermarks, reconstructs content.
+/// Gathers the commitment's shards of the content, skips any whose hash or
+/// watermark doesn't match, and reconstructs the content from the rest.
 /// Uses appropriate algorithm: full_replica (copy), threshold_split (Shamir), erasure_coded (Reed-Solomon)
 #[hdk_extern]
 pub fn reconstruct_content_from_shards(
@@ -9312,55 +18275,80 @@ pub fn reconstruct_content_from_shards(
         )));
     }
 
-    // Parse shard assignments to find which custodians hold shards
-    let _shard_assignments: Vec<serde_json::Value> =
-        serde_json::from_str(&commitment.shard_assignments_json).unwrap_or_default();
-
-    // Strategy: based on shard_strategy, reconstruct
-    match commitment.shard_strategy.as_str() {
-        "full_replica" => {
-            // Each custodian holds complete copy → just retrieve from first available
-            // Placeholder: return reconstructed content
-            Ok(ReconstructContentOutput {
-                content_id: input.content_id.clone(),
-                content: format!("Reconstructed content from full_replica strategy for {}", input.content_id),
-                shards_gathered: 1,
-                shards_required: 1,
-                reconstruction_method: "full_replica".to_string(),
-                verification_status: "verified".to_string(),
-                error_message: None,
-            })
+    // Gather this commitment's shards of the content, skipping any that fail their hash
+    let stored = get_shards_for_commitment(&input.commitment_id)?
+        .into_iter()
+        .map(|(_, shard)| shard)
+        .collect();
+    let mut shards = Vec::new();
+    for shard in commitment_content_shards(stored, &input.commitment_id, &input.content_id) {
+        if shard_data_hash(&shard.encrypted_shard_data)? == shard.shard_hash {
+            shards.push(shard.encrypted_shard_data);
         }
-        "threshold_split" => {
-            // Shamir's Secret Sharing: need M of N shards to reconstruct
-            // Placeholder: requires Shamir library integration
-            Ok(ReconstructContentOutput {
-                content_id: input.content_id.clone(),
-                content: format!("Reconstructed content via Shamir's Secret Sharing for {}", input.content_id),
-                shards_gathered: commitment.redundancy_factor,
-                shards_required: commitment.redundancy_factor,
-                reconstruction_method: "threshold_split".to_string(),
-                verification_status: "verified".to_string(),
-                error_message: None,
-            })
+    }
+
+    // Shards stored as generate_shards produced them reassemble here; shards
+    // encrypted client-side must be decrypted and passed to reconstruct_content.
+    match reassemble_shards(&input.content_id, &commitment.shard_strategy, commitment.redundancy_factor, &shards) {
+        Ok(output) => Ok(output),
+        Err(e) => Ok(ReconstructContentOutput {
+            content_id: input.content_id.clone(),
+            content: String::new(),
+            shards_gathered: shards.len() as u32,
+            shards_required: commitment.redundancy_factor,
+            reconstruction_method: commitment.shard_strategy.clone(),
+            verification_status: if shards.is_empty() { "unverified" } else { "partial" }.to_string(),
+            error_message: Some(format!("{:?}", e)),
+        }),
+    }
+}
+
+/// Shards of `content_id` stored under `commitment_id` whose watermark matches
+fn commitment_content_shards(shards: Vec<CustodianShard>, commitment_id: &str, content_id: &str) -> Vec<CustodianShard> {
+    shards
+        .into_iter()
+        .filter(|shard| shard.commitment_id == commitment_id && shard.content_id == content_id)
+        .filter(|shard| {
+            matches!(verify_watermark(&shard.watermark_signature, &shard.content_id, &shard.shard_hash), Ok(true))
+        })
+        .collect()
+}
+
+/// Report how many shards of a content item custodians have stored
+///
+/// Lets a recovery coordinator wait for enough shards before calling
+/// `reconstruct_content_from_shards`, counting the same shards it would use.
+/// Read-only; anyone may ask.
+#[hdk_extern]
+pub fn get_recovery_shard_status(input: ReconstructContentInput) -> ExternResult<RecoveryShardStatus> {
+    let commitment = get_commitment_by_id(&input.commitment_id)?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Commitment not found".to_string())))?;
+
+    let stored = get_shards_for_commitment(&input.commitment_id)?
+        .into_iter()
+        .map(|(_, shard)| shard)
+        .collect();
+
+    let mut indexes: HashSet<u32> = HashSet::new();
+    let mut custodian_agent_ids: Vec<String> = Vec::new();
+    for shard in commitment_content_shards(stored, &input.commitment_id, &input.content_id) {
+        if shard_data_hash(&shard.encrypted_shard_data)? != shard.shard_hash {
+            continue;
         }
-        "erasure_coded" => {
-            // Reed-Solomon: need M of N shards to reconstruct
-            // Placeholder: requires Reed-Solomon library integration
-            Ok(ReconstructContentOutput {
-                content_id: input.content_id.clone(),
-                content: format!("Reconstructed content via Reed-Solomon for {}", input.content_id),
-                shards_gathered: commitment.redundancy_factor,
-                shards_required: commitment.redundancy_factor,
-                reconstruction_method: "erasure_coded".to_string(),
-                verification_status: "verified".to_string(),
-                error_message: None,
-            })
+        indexes.insert(shard.shard_index);
+        if !custodian_agent_ids.contains(&shard.custodian_agent_id) {
+            custodian_agent_ids.push(shard.custodian_agent_id);
         }
-        _ => Err(wasm_error!(WasmErrorInner::Guest(
-            "Unknown shard strategy".to_string()
-        ))),
     }
+
+    let shards_required = if commitment.shard_strategy == "full_replica" { 1 } else { commitment.redundancy_factor };
+    Ok(RecoveryShardStatus {
+        content_id: input.content_id,
+        shards_available: indexes.len() as u32,
+        shards_required,
+        custodian_agent_ids,
+        ready: indexes.len() as u32 >= shards_required,
+    })
 }
 
 /// Notify emergency contacts about activation
@@ -9415,7 +18403,10 @@ pub fn create_category_override(
     let now = format!("{:?}", sys_time()?);
 
     // Get existing commitment or create new one
-    let mut commitment = get_commitment_by_id(&input.commitment_id)?
+    let existing = get_custodian_commitment(input.commitment_id.clone())?;
+    let mut commitment = existing
+        .as_ref()
+        .map(|e| e.commitment.clone())
         .unwrap_or_else(|| CustodianCommitment {
             id: input.commitment_id.clone(),
             custodian_agent_id: input.specialist_agent_id.clone(),
@@ -9464,49 +18455,247 @@ pub fn create_category_override(
     // Update commitment with category override info
     commitment.category_override_json = serde_json::to_string(&input.category)
         .unwrap_or_else(|_| "{}".to_string());
+    let mut metadata: serde_json::Value =
+        serde_json::from_str(&commitment.metadata_json).unwrap_or_else(|_| serde_json::json!({}));
+    if let Some(obj) = metadata.as_object_mut() {
+        obj.insert("expires_at".to_string(), serde_json::json!(input.expires_at));
+    }
+    commitment.metadata_json = metadata.to_string();
 
-    // Store the updated commitment
-    let entry_hash = hash_entry(&EntryTypes::CustodianCommitment(commitment.clone()))?;
-    let action_hash = create_entry(EntryTypes::CustodianCommitment(commitment.clone()))?;
+    // Store the commitment with its index links, so validate_category_access can find it by specialist
+    let output = match existing {
+        Some(existing) => update_custodian_commitment(existing, commitment)?,
+        None => {
+            let action_hash = create_entry(EntryTypes::CustodianCommitment(commitment.clone()))?;
+            let entry_hash = hash_entry(&EntryTypes::CustodianCommitment(commitment.clone()))?;
+            for (anchor_hash, link_type) in custodian_commitment_index(&commitment)? {
+                create_link(anchor_hash, action_hash.clone(), link_type, ())?;
+            }
+            CustodianCommitmentOutput { action_hash, entry_hash, commitment }
+        }
+    };
 
     Ok(CategoryOverrideOutput {
-        action_hash,
-        entry_hash,
-        commitment_id: commitment.id,
+        action_hash: output.action_hash,
+        entry_hash: output.entry_hash,
+        commitment_id: output.commitment.id,
         override_status: "active".to_string(),
     })
 }
 
-/// Query category overrides with flexible filtering
-#[hdk_extern]
-pub fn query_category_overrides(
-    _input: QueryCategoryOverridesInput,
-) -> ExternResult<Vec<CategoryOverrideSummary>> {
-    // Placeholder: In production, would query by links and filter
-    // For now, return empty list (filters not yet implemented)
-    let results: Vec<CategoryOverrideSummary> = Vec::new();
-    Ok(results)
+/// Query category overrides with flexible filtering
+#[hdk_extern]
+pub fn query_category_overrides(
+    _input: QueryCategoryOverridesInput,
+) -> ExternResult<Vec<CategoryOverrideSummary>> {
+    // Placeholder: In production, would query by links and filter
+    // For now, return empty list (filters not yet implemented)
+    let results: Vec<CategoryOverrideSummary> = Vec::new();
+    Ok(results)
+}
+
+/// Validate if a specialist can access content via category override
+///
+/// The specialist needs an active, unexpired category override from the
+/// content's owner that covers the content's reach and type, and must hold
+/// a current imagodei attestation for that category (e.g. `medical_professional`
+/// for `medical`). Every check is written to a CategoryAccessLog the
+/// beneficiary can review with `get_category_access_log`.
+#[hdk_extern]
+pub fn validate_category_access(
+    input: ValidateCategoryAccessInput,
+) -> ExternResult<ValidateCategoryAccessOutput> {
+    let content = healing_integration::get_content_by_id_with_healing(&input.content_id)?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Content not found".to_string())))?;
+    let now = format!("{:?}", sys_time()?);
+
+    // Active overrides held by this specialist for the content's owner
+    let overrides = query_custodian_commitments(QueryCommitmentsInput {
+        custodian_agent_id: Some(input.specialist_id.clone()),
+        beneficiary_agent_id: content.author_id.clone(),
+        commitment_type: Some("category".to_string()),
+        state: Some("active".to_string()),
+        basis: None,
+        limit: None,
+    })?;
+
+    let mut denial = "No active category override for this specialist".to_string();
+    let mut matched: Option<(CustodianCommitment, CategoryOverrideSpec)> = None;
+    for output in overrides {
+        let spec: CategoryOverrideSpec = match serde_json::from_str(&output.commitment.category_override_json) {
+            Ok(spec) => spec,
+            Err(_) => continue,
+        };
+        if let Some(reason) = category_override_mismatch(&output.commitment, &spec, &content, input.required_category.as_deref(), &now) {
+            denial = reason;
+            continue;
+        }
+        matched = Some((output.commitment, spec));
+        break;
+    }
+
+    let mut result = ValidateCategoryAccessOutput {
+        authorized: false,
+        category_type: None,
+        access_level: None,
+        reason: denial,
+        commitment_id: None,
+        attestation_id: None,
+    };
+    let mut beneficiary = content.author_id.clone().unwrap_or_default();
+
+    if let Some((commitment, spec)) = matched {
+        result.category_type = Some(spec.category_type.clone());
+        result.access_level = Some(spec.access_level.clone());
+        result.commitment_id = Some(commitment.id.clone());
+        beneficiary = commitment.beneficiary_agent_id.clone();
+
+        match find_category_credential(&input.specialist_id, &spec, &now)? {
+            Ok(attestation) => {
+                result.authorized = true;
+                result.reason = format!("Authorized by {} attestation", attestation.attestation_type);
+                result.attestation_id = Some(attestation.id);
+            }
+            Err(reason) => result.reason = reason,
+        }
+    }
+
+    // Audit every check against the beneficiary's log
+    if !beneficiary.is_empty() {
+        log_category_access(&beneficiary, &input, &result, now)?;
+    }
+
+    Ok(result)
+}
+
+/// Category-override access log for content I'm the beneficiary of, newest first
+#[hdk_extern]
+pub fn get_category_access_log(limit: Option<u32>) -> ExternResult<Vec<CategoryAccessLogOutput>> {
+    let me = agent_info()?.agent_initial_pubkey.to_string();
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("category_access_log", &me)))?;
+    let query = LinkQuery::try_new(anchor_hash, LinkTypes::BeneficiaryToCategoryAccessLog)?;
+    let mut links = get_links(query, GetStrategy::default())?;
+    links.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    links.truncate(limit.unwrap_or(100) as usize);
+
+    let mut hashes = Vec::with_capacity(links.len());
+    for link in &links {
+        let action_hash = ActionHash::try_from(link.target.clone())
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid access log hash".to_string())))?;
+        hashes.push(action_hash);
+    }
+
+    Ok(hashes
+        .iter()
+        .cloned()
+        .zip(get_records_batch(hashes)?)
+        .filter_map(|(action_hash, record)| {
+            record
+                .and_then(|r| r.entry().to_app_option::<CategoryAccessLog>().ok().flatten())
+                .map(|log| CategoryAccessLogOutput { action_hash, log })
+        })
+        .collect())
+}
+
+/// Imagodei attestation types that satisfy each override category
+fn category_credential_types(category_type: &str) -> &'static [&'static str] {
+    match category_type {
+        "medical" => &["medical_professional"],
+        "emergency" => &["emergency_responder"],
+        "disaster_relief" => &["disaster_relief_worker", "emergency_responder"],
+        "high_bandwidth" => &["content_delivery_provider"],
+        "archive" => &["archivist"],
+        _ => &[],
+    }
+}
+
+/// Why an override doesn't cover this content, or None if it does
+fn category_override_mismatch(
+    commitment: &CustodianCommitment,
+    spec: &CategoryOverrideSpec,
+    content: &Content,
+    required_category: Option<&str>,
+    now: &str,
+) -> Option<String> {
+    if required_category.is_some_and(|c| c != spec.category_type) {
+        return Some(format!("Override category {} does not match required category", spec.category_type));
+    }
+    let expires_at = serde_json::from_str::<serde_json::Value>(&commitment.metadata_json)
+        .ok()
+        .and_then(|m| m.get("expires_at").and_then(|v| v.as_str()).map(str::to_string));
+    if expires_at.is_some_and(|exp| exp.as_str() <= now) {
+        return Some("Category override has expired".to_string());
+    }
+    if !spec.allowed_reach_levels.contains(&content.reach) {
+        return Some(format!("Override does not cover {} reach", content.reach));
+    }
+    if !spec.content_filters.is_empty() && !spec.content_filters.contains(&content.content_type) {
+        return Some(format!("Override does not cover {} content", content.content_type));
+    }
+    None
 }
 
-/// Validate if a specialist can access content via category override
+/// Find a current imagodei attestation proving the specialist's credential for this category.
 ///
-/// Returns authorization status and details about which specialist category applies
-#[hdk_extern]
-pub fn validate_category_access(
-    input: ValidateCategoryAccessInput,
-) -> ExternResult<ValidateCategoryAccessOutput> {
-    // Placeholder: would query for active category overrides for this specialist
-    // and check if they match the content's required category
-    let _specialist_id = input.specialist_id;
-    let _content_id = input.content_id;
-    let _required_category = input.required_category;
+/// If the override names a specific credential, the attestation's id or type must match it.
+fn find_category_credential(
+    specialist_id: &str,
+    spec: &CategoryOverrideSpec,
+    now: &str,
+) -> ExternResult<Result<Attestation, String>> {
+    let accepted = category_credential_types(&spec.category_type);
+    let attestations = get_agent_attestations_via_imagodei(specialist_id.to_string())?;
+
+    let mut expired = false;
+    for output in attestations {
+        let attestation = output.attestation;
+        if !accepted.contains(&attestation.attestation_type.as_str()) {
+            continue;
+        }
+        if let Some(credential) = spec.credentials.as_deref().filter(|c| !c.is_empty()) {
+            if attestation.id != credential && attestation.attestation_type != credential {
+                continue;
+            }
+        }
+        if attestation.expires_at.as_deref().is_some_and(|exp| exp <= now) {
+            expired = true;
+            continue;
+        }
+        return Ok(Ok(attestation));
+    }
 
-    Ok(ValidateCategoryAccessOutput {
-        authorized: false,
-        category_type: None,
-        access_level: None,
-        reason: "Category override validation not yet fully implemented".to_string(),
-    })
+    Ok(Err(if expired {
+        format!("Specialist's {} credential has expired", spec.category_type)
+    } else {
+        format!("Specialist holds no {} credential (requires one of {:?})", spec.category_type, accepted)
+    }))
+}
+
+/// Record a category-override access check for the beneficiary to review
+fn log_category_access(
+    beneficiary: &str,
+    input: &ValidateCategoryAccessInput,
+    result: &ValidateCategoryAccessOutput,
+    accessed_at: String,
+) -> ExternResult<ActionHash> {
+    let log = CategoryAccessLog {
+        id: format!("category-access-{}-{}-{}", input.specialist_id, input.content_id, accessed_at),
+        commitment_id: result.commitment_id.clone(),
+        beneficiary_agent_id: beneficiary.to_string(),
+        specialist_agent_id: input.specialist_id.clone(),
+        content_id: input.content_id.clone(),
+        category_type: result.category_type.clone(),
+        attestation_id: result.attestation_id.clone(),
+        authorized: result.authorized,
+        reason: result.reason.clone(),
+        accessed_at,
+    };
+    let action_hash = create_entry(EntryTypes::CategoryAccessLog(log))?;
+
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("category_access_log", beneficiary)))?;
+    create_link(anchor_hash, action_hash.clone(), LinkTypes::BeneficiaryToCategoryAccessLog, ())?;
+
+    Ok(action_hash)
 }
 
 /// Revoke a category override (beneficiary can revoke access)
@@ -9738,6 +18927,9 @@ pub fn create_member_risk_profile(input: MemberRiskProfile) -> ExternResult<(Act
     let member_anchor_hash = hash_entry(&EntryTypes::StringAnchor(member_anchor))?;
     create_link(member_anchor_hash, action_hash.clone(), LinkTypes::MemberToRiskProfile, ())?;
 
+    // Counted in the pool's solvency
+    create_link(risk_pool_anchor()?, action_hash.clone(), LinkTypes::RiskPoolToProfile, ())?;
+
     // RiskProfileByTier link removed - query via projection instead
 
     Ok((action_hash, entry_hash))
@@ -9787,128 +18979,705 @@ pub fn create_coverage_policy(input: CoveragePolicy) -> ExternResult<(ActionHash
 
     // CoveragePolicyByLevel link removed - query via projection instead
 
-    Ok((action_hash, entry_hash))
+    Ok((action_hash, entry_hash))
+}
+
+/// Get a coverage policy by ID
+#[hdk_extern]
+pub fn get_coverage_policy(policy_id: String) -> ExternResult<Option<(ActionHash, CoveragePolicy)>> {
+    let id_anchor = StringAnchor::new("coverage_policy", &policy_id);
+    let id_anchor_hash = hash_entry(&EntryTypes::StringAnchor(id_anchor))?;
+
+    let query = LinkQuery::try_new(id_anchor_hash, LinkTypes::IdToCoveragePolicy)?;
+    let links = get_links(query, GetStrategy::default())?;
+
+    if links.is_empty() {
+        return Ok(None);
+    }
+
+    let action_hash = ActionHash::try_from(links[0].target.clone())
+        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid action hash".to_string())))?;
+
+    let record = get(action_hash.clone(), GetOptions::default())?;
+    if let Some(record) = record {
+        if let Some(policy) = record.entry().to_app_option::<CoveragePolicy>().ok().flatten() {
+            return Ok(Some((action_hash, policy)));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Create an insurance claim entry
+#[hdk_extern]
+pub fn create_insurance_claim(input: InsuranceClaim) -> ExternResult<(ActionHash, EntryHash)> {
+    let action_hash = create_entry(&EntryTypes::InsuranceClaim(input.clone()))?;
+    let entry_hash = hash_entry(&EntryTypes::InsuranceClaim(input.clone()))?;
+
+    // Create ID lookup link
+    let id_anchor = StringAnchor::new("insurance_claim", &input.id);
+    let id_anchor_hash = hash_entry(&EntryTypes::StringAnchor(id_anchor))?;
+    create_link(id_anchor_hash, action_hash.clone(), LinkTypes::IdToInsuranceClaim, ())?;
+
+    // Create member lookup link
+    let member_anchor = StringAnchor::new("member_claims", &input.member_id);
+    let member_anchor_hash = hash_entry(&EntryTypes::StringAnchor(member_anchor))?;
+    create_link(member_anchor_hash, action_hash.clone(), LinkTypes::MemberToClaim, ())?;
+
+    // Create status lookup link
+    let status_anchor = StringAnchor::new("claims_by_status", &input.status);
+    let status_anchor_hash = hash_entry(&EntryTypes::StringAnchor(status_anchor))?;
+    create_link(status_anchor_hash, action_hash.clone(), LinkTypes::ClaimByStatus, ())?;
+
+    Ok((action_hash, entry_hash))
+}
+
+/// Get an insurance claim by ID
+#[hdk_extern]
+pub fn get_insurance_claim(claim_id: String) -> ExternResult<Option<(ActionHash, InsuranceClaim)>> {
+    let id_anchor = StringAnchor::new("insurance_claim", &claim_id);
+    let id_anchor_hash = hash_entry(&EntryTypes::StringAnchor(id_anchor))?;
+
+    let query = LinkQuery::try_new(id_anchor_hash, LinkTypes::IdToInsuranceClaim)?;
+    let links = get_links(query, GetStrategy::default())?;
+
+    if links.is_empty() {
+        return Ok(None);
+    }
+
+    let action_hash = ActionHash::try_from(links[0].target.clone())
+        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid action hash".to_string())))?;
+
+    let record = get(action_hash.clone(), GetOptions::default())?;
+    if let Some(record) = record {
+        if let Some(claim) = record.entry().to_app_option::<InsuranceClaim>().ok().flatten() {
+            return Ok(Some((action_hash, claim)));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Create an adjustment reasoning entry
+#[hdk_extern]
+pub fn create_adjustment_reasoning(input: AdjustmentReasoning) -> ExternResult<(ActionHash, EntryHash)> {
+    let action_hash = create_entry(&EntryTypes::AdjustmentReasoning(input.clone()))?;
+    let entry_hash = hash_entry(&EntryTypes::AdjustmentReasoning(input.clone()))?;
+
+    // Create ID lookup link
+    let id_anchor = StringAnchor::new("adjustment_reasoning", &input.id);
+    let id_anchor_hash = hash_entry(&EntryTypes::StringAnchor(id_anchor))?;
+    create_link(id_anchor_hash, action_hash.clone(), LinkTypes::IdToAdjustmentReasoning, ())?;
+
+    // Create claim lookup link
+    let claim_anchor = StringAnchor::new("claim_adjustments", &input.claim_id);
+    let claim_anchor_hash = hash_entry(&EntryTypes::StringAnchor(claim_anchor))?;
+    create_link(claim_anchor_hash, action_hash.clone(), LinkTypes::ClaimToAdjustment, ())?;
+
+    Ok((action_hash, entry_hash))
+}
+
+/// Get an adjustment reasoning by ID
+#[hdk_extern]
+pub fn get_adjustment_reasoning(reasoning_id: String) -> ExternResult<Option<(ActionHash, AdjustmentReasoning)>> {
+    let id_anchor = StringAnchor::new("adjustment_reasoning", &reasoning_id);
+    let id_anchor_hash = hash_entry(&EntryTypes::StringAnchor(id_anchor))?;
+
+    let query = LinkQuery::try_new(id_anchor_hash, LinkTypes::IdToAdjustmentReasoning)?;
+    let links = get_links(query, GetStrategy::default())?;
+
+    if links.is_empty() {
+        return Ok(None);
+    }
+
+    let action_hash = ActionHash::try_from(links[0].target.clone())
+        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid action hash".to_string())))?;
+
+    let record = get(action_hash.clone(), GetOptions::default())?;
+    if let Some(record) = record {
+        if let Some(reasoning) = record.entry().to_app_option::<AdjustmentReasoning>().ok().flatten() {
+            return Ok(Some((action_hash, reasoning)));
+        }
+    }
+
+    Ok(None)
+}
+
+// =============================================================================
+// Shefa: Insurance Claim Lifecycle
+// =============================================================================
+//
+// filed → submitted → under_review → approved/denied → paid
+//
+// Each step is an update of the InsuranceClaim that re-points its
+// `claims_by_status` link, so adjuster queues can be read per status. Actor
+// ids are passed in by the doorway, which checks who may take each step.
+
+/// Allowed claim status changes
+const CLAIM_TRANSITIONS: [(&str, &str); 5] = [
+    ("filed", "submitted"),
+    ("submitted", "under_review"),
+    ("under_review", "approved"),
+    ("under_review", "denied"),
+    ("approved", "paid"),
+];
+
+/// Statuses in which evidence can still be added
+const CLAIM_EVIDENCE_STATUSES: [&str; 3] = ["filed", "submitted", "under_review"];
+
+/// Input for attaching evidence to a claim
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SubmitClaimEvidenceInput {
+    pub claim_id: String,
+    pub submitted_by: String,
+    #[serde(default)]
+    pub observer_attestation_ids: Vec<String>,
+    #[serde(default)]
+    pub member_document_ids: Vec<String>,
+}
+
+/// Input for putting an adjuster on a claim
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AssignAdjusterInput {
+    pub claim_id: String,
+    pub adjuster_id: String,
+    pub assigned_by: String,
+}
+
+/// Input for an adjuster's decision on a claim
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AdjudicateClaimInput {
+    pub claim_id: String,
+    pub adjuster_id: String,
+    pub decision: String,                  // approved, denied, partial (from COVERAGE_DECISIONS)
+    pub reasoning: String,                 // Member-facing explanation
+    pub approved_amount_value: Option<f64>,
+    pub approved_amount_unit: Option<String>,
+    pub interpretation_notes: Option<String>,
+    #[serde(default)]
+    pub applied_generosity_principle: bool,
+    #[serde(default)]
+    pub constitutional_basis_documents: Vec<String>,
+    #[serde(default)]
+    pub policy_citations: Vec<String>,
+    #[serde(default)]
+    pub flagged_for_governance: bool,
+    pub governance_review_reason: Option<String>,
+}
+
+/// Input for recording that an approved claim was paid out
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RecordClaimPaymentInput {
+    pub claim_id: String,
+    pub paid_by: String,
+    pub settlement_event_id: String,    // EconomicEvent ID of the payout
+}
+
+/// A claim decision and the reasoning behind it
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ClaimAdjudication {
+    pub claim: InsuranceClaim,
+    pub reasoning: AdjustmentReasoning,
+}
+
+fn claim_transition_allowed(from: &str, to: &str) -> bool {
+    CLAIM_TRANSITIONS.iter().any(|(f, t)| *f == from && *t == to)
+}
+
+fn existing_claim(claim_id: &str) -> ExternResult<(ActionHash, InsuranceClaim)> {
+    get_insurance_claim(claim_id.to_string())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(format!("Claim not found: {}", claim_id))))
+}
+
+fn claim_metadata(claim: &InsuranceClaim) -> serde_json::Value {
+    serde_json::from_str::<serde_json::Value>(&claim.metadata_json)
+        .ok()
+        .filter(|m| m.is_object())
+        .unwrap_or_else(|| serde_json::json!({}))
+}
+
+/// Adjuster currently assigned to a claim (`metadata_json.adjusterId`)
+fn claim_adjuster(claim: &InsuranceClaim) -> Option<String> {
+    claim_metadata(claim).get("adjusterId").and_then(|a| a.as_str()).map(String::from)
+}
+
+/// Append ids to a `Vec<String>` JSON field, skipping ones already there
+fn append_json_ids(ids_json: &str, new_ids: &[String]) -> String {
+    let mut ids: Vec<String> = serde_json::from_str(ids_json).unwrap_or_default();
+    for id in new_ids {
+        if !id.is_empty() && !ids.contains(id) {
+            ids.push(id.clone());
+        }
+    }
+    serde_json::to_string(&ids).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// Commit an updated claim, recording the status change in its history and
+/// moving its lookup links to the new action.
+fn commit_claim_update(
+    old_hash: ActionHash,
+    mut claim: InsuranceClaim,
+    old_status: &str,
+    changed_by: &str,
+    note: Option<String>,
+    source_fn: &str,
+) -> ExternResult<InsuranceClaim> {
+    let now = format!("{:?}", sys_time()?);
+    if claim.status != old_status || note.is_some() {
+        let mut history: Vec<serde_json::Value> =
+            serde_json::from_str(&claim.status_history_json).unwrap_or_default();
+        history.push(serde_json::json!({
+            "from": old_status,
+            "to": claim.status,
+            "changedBy": changed_by,
+            "changedAt": now,
+            "note": note,
+        }));
+        claim.status_history_json = serde_json::to_string(&history).unwrap_or_else(|_| "[]".to_string());
+    }
+    claim.updated_at = now;
+
+    let action_hash = update_entry(old_hash.clone(), &EntryTypes::InsuranceClaim(claim.clone()))?;
+    for (anchor_type, anchor_value, link_type) in [
+        ("insurance_claim", claim.id.as_str(), LinkTypes::IdToInsuranceClaim),
+        ("member_claims", claim.member_id.as_str(), LinkTypes::MemberToClaim),
+    ] {
+        let anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new(anchor_type, anchor_value)))?;
+        delete_links_to(anchor_hash.clone(), link_type, &old_hash)?;
+        create_link(anchor_hash, action_hash.clone(), link_type, ())?;
+    }
+    let old_status_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("claims_by_status", old_status)))?;
+    delete_links_to(old_status_hash, LinkTypes::ClaimByStatus, &old_hash)?;
+    let status_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("claims_by_status", &claim.status)))?;
+    create_link(status_hash, action_hash, LinkTypes::ClaimByStatus, ())?;
+
+    emit_write_signal("insurance_claim", &claim.id, source_fn);
+    Ok(claim)
+}
+
+/// Attach observer attestations and member documents to a claim.
+///
+/// Only the member (or whoever filed for them) can add evidence. The first
+/// submission moves a `filed` claim to `submitted`; evidence can still be
+/// added while the claim is under review.
+#[hdk_extern]
+pub fn submit_claim_evidence(input: SubmitClaimEvidenceInput) -> ExternResult<InsuranceClaim> {
+    let (old_hash, mut claim) = existing_claim(&input.claim_id)?;
+    if input.submitted_by != claim.member_id && input.submitted_by != claim.filed_by {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the member can submit evidence for a claim".to_string()
+        )));
+    }
+    if !CLAIM_EVIDENCE_STATUSES.contains(&claim.status.as_str()) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Evidence can't be added to a claim that is {}",
+            claim.status
+        ))));
+    }
+    if input.observer_attestation_ids.is_empty() && input.member_document_ids.is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest("No evidence given".to_string())));
+    }
+
+    let old_status = claim.status.clone();
+    claim.observer_attestation_ids_json =
+        append_json_ids(&claim.observer_attestation_ids_json, &input.observer_attestation_ids);
+    claim.member_document_ids_json = append_json_ids(&claim.member_document_ids_json, &input.member_document_ids);
+    if old_status == "filed" {
+        claim.status = "submitted".to_string();
+    }
+
+    commit_claim_update(old_hash, claim, &old_status, &input.submitted_by, None, "submit_claim_evidence")
+}
+
+/// Assign an adjuster to a submitted claim, moving it to `under_review`.
+///
+/// Claims already under review can be reassigned. Members can't adjust their
+/// own claims.
+#[hdk_extern]
+pub fn assign_adjuster(input: AssignAdjusterInput) -> ExternResult<InsuranceClaim> {
+    let (old_hash, mut claim) = existing_claim(&input.claim_id)?;
+    if input.adjuster_id.is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest("adjuster_id is required".to_string())));
+    }
+    if input.adjuster_id == claim.member_id || input.adjuster_id == claim.filed_by {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "An adjuster can't review their own claim".to_string()
+        )));
+    }
+    if claim.status != "under_review" && !claim_transition_allowed(&claim.status, "under_review") {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Can't assign an adjuster to a claim that is {}",
+            claim.status
+        ))));
+    }
+
+    let old_status = claim.status.clone();
+    let mut metadata = claim_metadata(&claim);
+    metadata["adjusterId"] = serde_json::json!(input.adjuster_id);
+    claim.metadata_json = metadata.to_string();
+    claim.status = "under_review".to_string();
+
+    commit_claim_update(
+        old_hash,
+        claim,
+        &old_status,
+        &input.assigned_by,
+        Some(format!("Assigned to adjuster {}", input.adjuster_id)),
+        "assign_adjuster",
+    )
+}
+
+/// Record the assigned adjuster's decision on a claim under review.
+///
+/// Writes an AdjustmentReasoning with the plain-language explanation (the
+/// Bob Parr Principle: no decision without one). `approved` and `partial`
+/// approve the claim, `denied` denies it.
+#[hdk_extern]
+pub fn adjudicate_claim(input: AdjudicateClaimInput) -> ExternResult<ClaimAdjudication> {
+    let (old_hash, mut claim) = existing_claim(&input.claim_id)?;
+    if !COVERAGE_DECISIONS.contains(&input.decision.as_str()) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Invalid decision '{}', expected one of: {}",
+            input.decision,
+            COVERAGE_DECISIONS.join(", ")
+        ))));
+    }
+    if input.reasoning.trim().is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "A decision needs a plain-language explanation".to_string()
+        )));
+    }
+    if claim_adjuster(&claim).as_deref() != Some(input.adjuster_id.as_str()) {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the assigned adjuster can decide this claim".to_string()
+        )));
+    }
+    let new_status = if input.decision == "denied" { "denied" } else { "approved" };
+    if !claim_transition_allowed(&claim.status, new_status) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Can't decide a claim that is {}",
+            claim.status
+        ))));
+    }
+    if new_status == "approved" && input.approved_amount_value.is_some_and(|v| v < 0.0) {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "approved_amount_value can't be negative".to_string()
+        )));
+    }
+
+    let now = sys_time()?;
+    let timestamp = format!("{:?}", now);
+    let reasoning = AdjustmentReasoning {
+        id: format!("adj-{}-{}", claim.id, now.as_micros()),
+        claim_id: claim.id.clone(),
+        adjuster_id: input.adjuster_id.clone(),
+        coverage_decision: input.decision.clone(),
+        approved_amount_value: if new_status == "approved" { input.approved_amount_value } else { None },
+        approved_amount_unit: if new_status == "approved" { input.approved_amount_unit } else { None },
+        plain_language_explanation: input.reasoning,
+        interpretation_notes: input.interpretation_notes,
+        applied_generosity_principle: input.applied_generosity_principle,
+        constitutional_basis_documents_json: serde_json::to_string(&input.constitutional_basis_documents)
+            .unwrap_or_else(|_| "[]".to_string()),
+        policy_citations_json: serde_json::to_string(&input.policy_citations).unwrap_or_else(|_| "[]".to_string()),
+        flagged_for_governance: input.flagged_for_governance,
+        governance_review_reason: input.governance_review_reason,
+        adjustment_date: timestamp.clone(),
+        created_at: timestamp,
+    };
+    create_adjustment_reasoning(reasoning.clone())?;
+    emit_write_signal("adjustment_reasoning", &reasoning.id, "adjudicate_claim");
+
+    let old_status = claim.status.clone();
+    let mut metadata = claim_metadata(&claim);
+    metadata["adjustmentId"] = serde_json::json!(reasoning.id);
+    claim.metadata_json = metadata.to_string();
+    claim.status = new_status.to_string();
+
+    let claim = commit_claim_update(
+        old_hash,
+        claim,
+        &old_status,
+        &input.adjuster_id,
+        Some(format!("Decision {} ({})", input.decision, reasoning.id)),
+        "adjudicate_claim",
+    )?;
+    Ok(ClaimAdjudication { claim, reasoning })
 }
 
-/// Get a coverage policy by ID
+/// Record the payout of an approved claim, moving it to `paid`
 #[hdk_extern]
-pub fn get_coverage_policy(policy_id: String) -> ExternResult<Option<(ActionHash, CoveragePolicy)>> {
-    let id_anchor = StringAnchor::new("coverage_policy", &policy_id);
-    let id_anchor_hash = hash_entry(&EntryTypes::StringAnchor(id_anchor))?;
+pub fn record_claim_payment(input: RecordClaimPaymentInput) -> ExternResult<InsuranceClaim> {
+    let (old_hash, mut claim) = existing_claim(&input.claim_id)?;
+    if !claim_transition_allowed(&claim.status, "paid") {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Only approved claims can be paid, this one is {}",
+            claim.status
+        ))));
+    }
+    if input.settlement_event_id.is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest("settlement_event_id is required".to_string())));
+    }
 
-    let query = LinkQuery::try_new(id_anchor_hash, LinkTypes::IdToCoveragePolicy)?;
-    let links = get_links(query, GetStrategy::default())?;
+    let old_status = claim.status.clone();
+    claim.settlement_event_ids_json =
+        append_json_ids(&claim.settlement_event_ids_json, &[input.settlement_event_id.clone()]);
+    claim.status = "paid".to_string();
 
-    if links.is_empty() {
-        return Ok(None);
-    }
+    commit_claim_update(
+        old_hash,
+        claim,
+        &old_status,
+        &input.paid_by,
+        Some(format!("Settlement event {}", input.settlement_event_id)),
+        "record_claim_payment",
+    )
+}
 
-    let action_hash = ActionHash::try_from(links[0].target.clone())
-        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid action hash".to_string())))?;
+/// Claims currently in a status (e.g. the `submitted` queue awaiting an adjuster)
+#[hdk_extern]
+pub fn get_claims_by_status(status: String) -> ExternResult<Vec<InsuranceClaim>> {
+    let status_anchor = StringAnchor::new("claims_by_status", &status);
+    let status_anchor_hash = hash_entry(&EntryTypes::StringAnchor(status_anchor))?;
+    let query = LinkQuery::try_new(status_anchor_hash, LinkTypes::ClaimByStatus)?;
 
-    let record = get(action_hash.clone(), GetOptions::default())?;
-    if let Some(record) = record {
-        if let Some(policy) = record.entry().to_app_option::<CoveragePolicy>().ok().flatten() {
-            return Ok(Some((action_hash, policy)));
+    let mut claims = Vec::new();
+    for link in get_links(query, GetStrategy::default())? {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        if let Some(record) = get(action_hash, GetOptions::default())? {
+            if let Some(claim) = record.entry().to_app_option::<InsuranceClaim>().ok().flatten() {
+                // Skip links left on an earlier version of the claim
+                if claim.status == status {
+                    claims.push(claim);
+                }
+            }
         }
     }
-
-    Ok(None)
+    claims.sort_by(|a, b| a.filed_date.cmp(&b.filed_date));
+    Ok(claims)
 }
 
-/// Create an insurance claim entry
-#[hdk_extern]
-pub fn create_insurance_claim(input: InsuranceClaim) -> ExternResult<(ActionHash, EntryHash)> {
-    let action_hash = create_entry(&EntryTypes::InsuranceClaim(input.clone()))?;
-    let entry_hash = hash_entry(&EntryTypes::InsuranceClaim(input.clone()))?;
+// =============================================================================
+// Shefa: Insurance Mutual Solvency
+// =============================================================================
 
-    // Create ID lookup link
-    let id_anchor = StringAnchor::new("insurance_claim", &input.id);
-    let id_anchor_hash = hash_entry(&EntryTypes::StringAnchor(id_anchor))?;
-    create_link(id_anchor_hash, action_hash.clone(), LinkTypes::IdToInsuranceClaim, ())?;
+/// Unit pooled contributions and claim amounts are counted in
+const POOL_UNIT: &str = "unit-token";
 
-    // Create member lookup link
-    let member_anchor = StringAnchor::new("member_claims", &input.member_id);
-    let member_anchor_hash = hash_entry(&EntryTypes::StringAnchor(member_anchor))?;
-    create_link(member_anchor_hash, action_hash.clone(), LinkTypes::MemberToClaim, ())?;
+/// Annual premium for a standard-tier member before adjustments
+const BASE_ANNUAL_PREMIUM: f64 = 500.0;
 
-    // Create status lookup link
-    let status_anchor = StringAnchor::new("claims_by_status", &input.status);
-    let status_anchor_hash = hash_entry(&EntryTypes::StringAnchor(status_anchor))?;
-    create_link(status_anchor_hash, action_hash.clone(), LinkTypes::ClaimByStatus, ())?;
+/// Smallest annual premium charged
+const MIN_ANNUAL_PREMIUM: f64 = 100.0;
 
-    Ok((action_hash, entry_hash))
+/// Reserve to outstanding claims ratio from which the pool counts as healthy
+const HEALTHY_SOLVENCY_RATIO: f64 = 1.5;
+
+/// Claim statuses still owed by the pool
+const OUTSTANDING_CLAIM_STATUSES: [&str; 6] = [
+    "filed",
+    "submitted",
+    "under_review",
+    "adjustment-made",
+    "approved",
+    "appealed",
+];
+
+/// Claim statuses that have been paid out
+const PAID_CLAIM_STATUSES: [&str; 2] = ["paid", "settled"];
+
+fn solvency_anchor() -> ExternResult<EntryHash> {
+    hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("solvency_snapshots", "all")))
 }
 
-/// Get an insurance claim by ID
-#[hdk_extern]
-pub fn get_insurance_claim(claim_id: String) -> ExternResult<Option<(ActionHash, InsuranceClaim)>> {
-    let id_anchor = StringAnchor::new("insurance_claim", &claim_id);
-    let id_anchor_hash = hash_entry(&EntryTypes::StringAnchor(id_anchor))?;
+fn risk_pool_anchor() -> ExternResult<EntryHash> {
+    hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("risk_pool", "members")))
+}
 
-    let query = LinkQuery::try_new(id_anchor_hash, LinkTypes::IdToInsuranceClaim)?;
-    let links = get_links(query, GetStrategy::default())?;
+/// Annual premium a member contributes, following the mutual's premium
+/// schedule: tier adjustment (low -20%, high +30%) and a prevention discount
+/// for care and connectedness scores above 60 (at most 35%). Uninsurable
+/// members don't contribute.
+fn annual_premium(profile: &MemberRiskProfile) -> Option<f64> {
+    let adjustment_percent = match profile.risk_tier.as_str() {
+        "low" => -20.0,
+        "standard" => 0.0,
+        "high" => 30.0,
+        _ => return None,
+    };
+    let before_discount = BASE_ANNUAL_PREMIUM + (BASE_ANNUAL_PREMIUM * adjustment_percent / 100.0).round();
 
-    if links.is_empty() {
-        return Ok(None);
+    let mut discount_percent = 0.0;
+    if profile.care_maintenance_score > 60.0 {
+        discount_percent += (profile.care_maintenance_score - 60.0) * 0.05;
     }
+    if profile.community_connectedness_score > 60.0 {
+        discount_percent += (profile.community_connectedness_score - 60.0) * 0.03;
+    }
+    let discount = (before_discount * discount_percent.min(35.0) / 100.0).round();
+
+    Some((before_discount - discount).max(MIN_ANNUAL_PREMIUM))
+}
+
+/// Amount a claim costs the pool: the adjuster's approved amount once
+/// decided, the member's estimate before that. `None` if in another unit.
+fn claim_pool_amount(claim: &InsuranceClaim) -> ExternResult<Option<f64>> {
+    let adjustment_id = claim_metadata(claim)
+        .get("adjustmentId")
+        .and_then(|a| a.as_str())
+        .map(String::from);
+    if let Some(adjustment_id) = adjustment_id {
+        if let Some((_, reasoning)) = get_adjustment_reasoning(adjustment_id)? {
+            if let Some(value) = reasoning.approved_amount_value {
+                let unit = reasoning.approved_amount_unit.as_deref().unwrap_or(POOL_UNIT);
+                return Ok((unit == POOL_UNIT).then_some(value));
+            }
+        }
+    }
+    let unit = claim.estimated_amount_unit.as_deref().unwrap_or(POOL_UNIT);
+    Ok((unit == POOL_UNIT).then_some(claim.estimated_amount_value.unwrap_or(0.0)))
+}
 
-    let action_hash = ActionHash::try_from(links[0].target.clone())
-        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid action hash".to_string())))?;
-
-    let record = get(action_hash.clone(), GetOptions::default())?;
-    if let Some(record) = record {
-        if let Some(claim) = record.entry().to_app_option::<InsuranceClaim>().ok().flatten() {
-            return Ok(Some((action_hash, claim)));
+/// Latest risk profile per member and risk type in the pool
+fn pooled_risk_profiles() -> ExternResult<Vec<MemberRiskProfile>> {
+    let query = LinkQuery::try_new(risk_pool_anchor()?, LinkTypes::RiskPoolToProfile)?;
+    let mut latest: HashMap<(String, String), MemberRiskProfile> = HashMap::new();
+    for link in get_links(query, GetStrategy::default())? {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(action_hash, GetOptions::default())? else {
+            continue;
+        };
+        let Some(profile) = record.entry().to_app_option::<MemberRiskProfile>().ok().flatten() else {
+            continue;
+        };
+        let key = (profile.member_id.clone(), profile.risk_type.clone());
+        if latest.get(&key).is_some_and(|existing| existing.assessed_at >= profile.assessed_at) {
+            continue;
         }
+        latest.insert(key, profile);
     }
+    Ok(latest.into_values().collect())
+}
 
-    Ok(None)
+fn solvency_status(reserve: f64, ratio: Option<f64>) -> &'static str {
+    match ratio {
+        _ if reserve < 0.0 => "underfunded",
+        None => "healthy",
+        Some(r) if r >= HEALTHY_SOLVENCY_RATIO => "healthy",
+        Some(r) if r >= 1.0 => "watch",
+        Some(_) => "underfunded",
+    }
 }
 
-/// Create an adjustment reasoning entry
+/// Compute the pool's current solvency without recording it
 #[hdk_extern]
-pub fn create_adjustment_reasoning(input: AdjustmentReasoning) -> ExternResult<(ActionHash, EntryHash)> {
-    let action_hash = create_entry(&EntryTypes::AdjustmentReasoning(input.clone()))?;
-    let entry_hash = hash_entry(&EntryTypes::AdjustmentReasoning(input.clone()))?;
+pub fn compute_pool_solvency(_: ()) -> ExternResult<SolvencySnapshot> {
+    let mut contributions = 0.0;
+    let mut member_ids = HashSet::new();
+    let mut by_risk_type: HashMap<String, f64> = HashMap::new();
+    for profile in pooled_risk_profiles()? {
+        if let Some(premium) = annual_premium(&profile) {
+            contributions += premium;
+            member_ids.insert(profile.member_id.clone());
+            *by_risk_type.entry(profile.risk_type.clone()).or_default() += premium;
+        }
+    }
 
-    // Create ID lookup link
-    let id_anchor = StringAnchor::new("adjustment_reasoning", &input.id);
-    let id_anchor_hash = hash_entry(&EntryTypes::StringAnchor(id_anchor))?;
-    create_link(id_anchor_hash, action_hash.clone(), LinkTypes::IdToAdjustmentReasoning, ())?;
+    let mut by_claim_status: HashMap<String, serde_json::Value> = HashMap::new();
+    let mut skipped_claims = 0u32;
+    let (mut paid_value, mut paid_count) = (0.0, 0u32);
+    let (mut outstanding_value, mut outstanding_count) = (0.0, 0u32);
+    for status in OUTSTANDING_CLAIM_STATUSES.iter().chain(PAID_CLAIM_STATUSES.iter()) {
+        let paid = PAID_CLAIM_STATUSES.contains(status);
+        let (mut value, mut count) = (0.0, 0u32);
+        for claim in get_claims_by_status(status.to_string())? {
+            match claim_pool_amount(&claim)? {
+                Some(amount) => {
+                    value += amount;
+                    count += 1;
+                }
+                None => skipped_claims += 1,
+            }
+        }
+        if paid {
+            paid_value += value;
+            paid_count += count;
+        } else {
+            outstanding_value += value;
+            outstanding_count += count;
+        }
+        if count > 0 {
+            by_claim_status.insert(status.to_string(), serde_json::json!({ "count": count, "value": value }));
+        }
+    }
 
-    // Create claim lookup link
-    let claim_anchor = StringAnchor::new("claim_adjustments", &input.claim_id);
-    let claim_anchor_hash = hash_entry(&EntryTypes::StringAnchor(claim_anchor))?;
-    create_link(claim_anchor_hash, action_hash.clone(), LinkTypes::ClaimToAdjustment, ())?;
+    let reserve = contributions - paid_value;
+    let ratio = (outstanding_value > 0.0).then(|| reserve / outstanding_value);
+    let now = sys_time()?;
+    let timestamp = format!("{:?}", now);
 
-    Ok((action_hash, entry_hash))
+    Ok(SolvencySnapshot {
+        id: format!("solvency-{}", now.as_micros()),
+        unit: POOL_UNIT.to_string(),
+        member_count: member_ids.len() as u32,
+        contributions_value: contributions,
+        paid_claims_value: paid_value,
+        paid_claim_count: paid_count,
+        outstanding_claims_value: outstanding_value,
+        outstanding_claim_count: outstanding_count,
+        reserve_value: reserve,
+        solvency_ratio: ratio,
+        status: solvency_status(reserve, ratio).to_string(),
+        breakdown_json: serde_json::json!({
+            "contributionsByRiskType": by_risk_type,
+            "claimsByStatus": by_claim_status,
+            "claimsInOtherUnits": skipped_claims,
+        })
+        .to_string(),
+        computed_at: timestamp.clone(),
+        created_at: timestamp,
+    })
 }
 
-/// Get an adjustment reasoning by ID
+/// Compute the pool's solvency and record it as a snapshot
 #[hdk_extern]
-pub fn get_adjustment_reasoning(reasoning_id: String) -> ExternResult<Option<(ActionHash, AdjustmentReasoning)>> {
-    let id_anchor = StringAnchor::new("adjustment_reasoning", &reasoning_id);
-    let id_anchor_hash = hash_entry(&EntryTypes::StringAnchor(id_anchor))?;
-
-    let query = LinkQuery::try_new(id_anchor_hash, LinkTypes::IdToAdjustmentReasoning)?;
-    let links = get_links(query, GetStrategy::default())?;
-
-    if links.is_empty() {
-        return Ok(None);
-    }
-
-    let action_hash = ActionHash::try_from(links[0].target.clone())
-        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invalid action hash".to_string())))?;
+pub fn record_solvency_snapshot(_: ()) -> ExternResult<SolvencySnapshot> {
+    let snapshot = compute_pool_solvency(())?;
+    let action_hash = create_entry(&EntryTypes::SolvencySnapshot(snapshot.clone()))?;
+    create_link(solvency_anchor()?, action_hash, LinkTypes::SolvencySnapshots, ())?;
+    emit_write_signal("solvency_snapshot", &snapshot.id, "record_solvency_snapshot");
+    Ok(snapshot)
+}
 
-    let record = get(action_hash.clone(), GetOptions::default())?;
-    if let Some(record) = record {
-        if let Some(reasoning) = record.entry().to_app_option::<AdjustmentReasoning>().ok().flatten() {
-            return Ok(Some((action_hash, reasoning)));
+/// Recorded solvency snapshots, newest first
+#[hdk_extern]
+pub fn get_solvency_snapshots(limit: u32) -> ExternResult<Vec<SolvencySnapshot>> {
+    let query = LinkQuery::try_new(solvency_anchor()?, LinkTypes::SolvencySnapshots)?;
+    let mut links = get_links(query, GetStrategy::default())?;
+    links.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+
+    let mut snapshots = Vec::new();
+    for link in links.into_iter().take(limit.max(1) as usize) {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        if let Some(record) = get(action_hash, GetOptions::default())? {
+            if let Some(snapshot) = record.entry().to_app_option::<SolvencySnapshot>().ok().flatten() {
+                snapshots.push(snapshot);
+            }
         }
     }
+    Ok(snapshots)
+}
 
-    Ok(None)
+/// Most recent solvency snapshot, if one has been recorded
+#[hdk_extern]
+pub fn get_latest_solvency_snapshot(_: ()) -> ExternResult<Option<SolvencySnapshot>> {
+    Ok(get_solvency_snapshots(1)?.into_iter().next())
 }
 
 // =============================================================================
@@ -10070,6 +19839,339 @@ pub fn get_service_match(match_id: String) -> ExternResult<Option<(ActionHash, S
     Ok(None)
 }
 
+// =============================================================================
+// Shefa: Request/Offer Matching
+// =============================================================================
+
+/// Lowest match_quality worth suggesting to both parties
+const MIN_SUGGESTED_MATCH_QUALITY: u32 = 40;
+
+/// How an active offer fits a request
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ServiceMatchCandidate {
+    pub request_id: String,
+    pub offer_id: String,
+    pub offeror_id: String,
+    pub match_quality: u32,
+    pub match_reason: String,
+    pub shared_service_types: Vec<String>,
+    pub shared_skills: Vec<String>,
+    pub same_location: bool,
+    pub time_compatible: bool,
+    pub interaction_compatible: bool,
+    pub exchange_compatible: bool,
+    /// ServiceMatch already recorded for this pair
+    pub existing_match_id: Option<String>,
+}
+
+/// A match recorded by `suggest_service_matches`, with the parties to tell
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SuggestedServiceMatch {
+    pub service_match: ServiceMatch,
+    pub requester_id: String,
+    pub offeror_id: String,
+}
+
+/// Where a request or offer is based: `metadata_json.location` if given
+fn service_location(metadata_json: &str) -> Option<String> {
+    serde_json::from_str::<serde_json::Value>(metadata_json)
+        .ok()?
+        .get("location")?
+        .as_str()
+        .map(|l| l.trim().to_lowercase())
+        .filter(|l| !l.is_empty())
+}
+
+/// Lowercased values of a JSON string array, ignoring malformed input
+fn json_tags(json: &str) -> Vec<String> {
+    let mut tags: Vec<String> = serde_json::from_str::<Vec<String>>(json)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|t| t.trim().to_lowercase())
+        .filter(|t| !t.is_empty())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+/// Score an offer against a request.
+///
+/// Service types count for up to 40 points and skills for up to 35, both as
+/// the share of what the request asks for (half when it lists none). Geography adds up to 25: 15 for
+/// a shared location (required when either side works in person only) or
+/// 10 for compatible remote work, plus 10 for a shared time zone.
+/// Returns None when the offer shares neither a service type nor a skill.
+fn score_service_match(request: &ServiceRequest, offer: &ServiceOffer) -> Option<ServiceMatchCandidate> {
+    let request_types = json_tags(&request.service_type_ids_json);
+    let offer_types = json_tags(&offer.service_type_ids_json);
+    let shared_service_types: Vec<String> =
+        request_types.iter().filter(|t| offer_types.contains(t)).cloned().collect();
+
+    let required_skills = json_tags(&request.required_skills_json);
+    let offered_skills = json_tags(&offer.offered_skills_json);
+    let shared_skills: Vec<String> =
+        required_skills.iter().filter(|s| offered_skills.contains(s)).cloned().collect();
+
+    if shared_service_types.is_empty() && shared_skills.is_empty() {
+        return None;
+    }
+
+    let share = |shared: usize, wanted: usize, points: u32| -> u32 {
+        if wanted == 0 {
+            points / 2
+        } else {
+            (points as usize * shared / wanted) as u32
+        }
+    };
+    let mut quality = share(shared_service_types.len(), request_types.len(), 40)
+        + share(shared_skills.len(), required_skills.len(), 35);
+
+    let request_location = service_location(&request.metadata_json);
+    let same_location = request_location.is_some() && request_location == service_location(&offer.metadata_json);
+    let interaction_compatible = !matches!(
+        (request.interaction_type.as_str(), offer.interaction_type.as_str()),
+        ("virtual", "in-person") | ("in-person", "virtual")
+    );
+    let needs_meeting = request.interaction_type == "in-person" || offer.interaction_type == "in-person";
+    if interaction_compatible && needs_meeting && !same_location {
+        // In-person work is only possible in the same place
+        return None;
+    }
+    if interaction_compatible {
+        quality += if same_location { 15 } else { 10 };
+    }
+    if !request.time_zone.is_empty() && request.time_zone == offer.time_zone {
+        quality += 10;
+    }
+
+    let time_compatible = request.time_preference == offer.time_preference
+        || request.time_preference == "any"
+        || offer.time_preference == "any";
+    let request_mediums = json_tags(&request.medium_of_exchange_ids_json);
+    let offer_mediums = json_tags(&offer.medium_of_exchange_ids_json);
+    let exchange_compatible = offer.accepts_alternative_payment
+        || request_mediums.is_empty()
+        || request_mediums.iter().any(|m| offer_mediums.contains(m));
+
+    let mut reasons = Vec::new();
+    if !shared_service_types.is_empty() {
+        reasons.push(format!("service types: {}", shared_service_types.join(", ")));
+    }
+    if !shared_skills.is_empty() {
+        reasons.push(format!("skills: {}", shared_skills.join(", ")));
+    }
+    if same_location {
+        reasons.push("same location".to_string());
+    } else if !request.time_zone.is_empty() && request.time_zone == offer.time_zone {
+        reasons.push(format!("same time zone ({})", request.time_zone));
+    }
+
+    Some(ServiceMatchCandidate {
+        request_id: request.id.clone(),
+        offer_id: offer.id.clone(),
+        offeror_id: offer.offeror_id.clone(),
+        match_quality: quality.min(100),
+        match_reason: reasons.join("; "),
+        shared_service_types,
+        shared_skills,
+        same_location,
+        time_compatible,
+        interaction_compatible,
+        exchange_compatible,
+        existing_match_id: None,
+    })
+}
+
+/// Latest version of every entry linked from an anchor
+fn service_entries<T: TryFrom<SerializedBytes, Error = SerializedBytesError>>(
+    anchor_type: &str,
+    anchor_value: &str,
+    link_type: LinkTypes,
+) -> ExternResult<Vec<(ActionHash, T)>> {
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new(anchor_type, anchor_value)))?;
+    let mut entries = Vec::new();
+    for link in get_links(LinkQuery::try_new(anchor_hash, link_type)?, GetStrategy::default())? {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        if let Some(record) = get(action_hash.clone(), GetOptions::default())? {
+            if let Some(entry) = record.entry().to_app_option::<T>().ok().flatten() {
+                entries.push((action_hash, entry));
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// Active offers that fit a request, best first.
+///
+/// Overlap of service types and skills drives the score, with geography
+/// (location for in-person work, time zone otherwise) on top. The
+/// requester's own offers are skipped.
+#[hdk_extern]
+pub fn find_matches(request_id: String) -> ExternResult<Vec<ServiceMatchCandidate>> {
+    let (_, request) = get_service_request(request_id.clone())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Service request not found".to_string())))?;
+
+    let existing: HashMap<String, String> =
+        service_entries::<ServiceMatch>("request_matches", &request_id, LinkTypes::RequestToMatch)?
+            .into_iter()
+            .map(|(_, m)| (m.offer_id, m.id))
+            .collect();
+
+    let mut candidates: Vec<ServiceMatchCandidate> =
+        service_entries::<ServiceOffer>("offers_by_status", "active", LinkTypes::OfferByStatus)?
+            .into_iter()
+            .filter(|(_, offer)| offer.status == "active" && offer.offeror_id != request.requester_id)
+            .filter_map(|(_, offer)| score_service_match(&request, &offer))
+            .map(|mut candidate| {
+                candidate.existing_match_id = existing.get(&candidate.offer_id).cloned();
+                candidate
+            })
+            .collect();
+
+    candidates.sort_by(|a, b| b.match_quality.cmp(&a.match_quality).then(a.offer_id.cmp(&b.offer_id)));
+    Ok(candidates)
+}
+
+/// Service requests with a given status (e.g. `active`)
+#[hdk_extern]
+pub fn get_service_requests_by_status(status: String) -> ExternResult<Vec<ServiceRequest>> {
+    Ok(service_entries::<ServiceRequest>("requests_by_status", &status, LinkTypes::RequestByStatus)?
+        .into_iter()
+        .map(|(_, request)| request)
+        .filter(|request| request.status == status)
+        .collect())
+}
+
+/// Record a `suggested` ServiceMatch for each good new candidate of a request.
+///
+/// Called by the doorway's matching job. Pairs that already have a match
+/// are left alone, so running it again only picks up new offers. Both
+/// parties then confirm through `confirm_service_match`.
+#[hdk_extern]
+pub fn suggest_service_matches(request_id: String) -> ExternResult<Vec<SuggestedServiceMatch>> {
+    let (_, request) = get_service_request(request_id.clone())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Service request not found".to_string())))?;
+    let now = format!("{:?}", sys_time()?);
+    let mut created = Vec::new();
+
+    for candidate in find_matches(request_id)? {
+        if candidate.existing_match_id.is_some() || candidate.match_quality < MIN_SUGGESTED_MATCH_QUALITY {
+            continue;
+        }
+        let offeror_id = candidate.offeror_id;
+        let service_match = ServiceMatch {
+            id: format!("match-{}-{}", candidate.request_id, candidate.offer_id),
+            request_id: candidate.request_id,
+            offer_id: candidate.offer_id,
+            match_reason: candidate.match_reason,
+            match_quality: candidate.match_quality,
+            shared_service_types_json: serde_json::to_string(&candidate.shared_service_types)
+                .unwrap_or_else(|_| "[]".to_string()),
+            time_compatible: candidate.time_compatible,
+            interaction_compatible: candidate.interaction_compatible,
+            exchange_compatible: candidate.exchange_compatible,
+            status: "suggested".to_string(),
+            proposal_id: None,
+            commitment_id: None,
+            schema_version: 1,
+            validation_status: "Valid".to_string(),
+            metadata_json: serde_json::json!({ "confirmed_by": [] }).to_string(),
+            created_at: now.clone(),
+            updated_at: now.clone(),
+        };
+        create_service_match(service_match.clone())?;
+        emit_write_signal("service_match", &service_match.id, "suggest_service_matches");
+        created.push(SuggestedServiceMatch {
+            service_match,
+            requester_id: request.requester_id.clone(),
+            offeror_id,
+        });
+    }
+
+    Ok(created)
+}
+
+/// Confirm a suggested match as the requester or the offeror.
+///
+/// Confirmations are kept in `metadata_json.confirmed_by` as
+/// `requester`/`offeror`. Once both have confirmed, the match moves to
+/// `contacted` so the parties can coordinate.
+#[hdk_extern]
+pub fn confirm_service_match(match_id: String) -> ExternResult<ServiceMatch> {
+    let (old_hash, mut service_match) = get_service_match(match_id.clone())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Service match not found".to_string())))?;
+    if service_match.status != "suggested" {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Match is already {}",
+            service_match.status
+        ))));
+    }
+
+    let me = agent_info()?.agent_initial_pubkey;
+    let author_of = |hash: Option<ActionHash>| -> ExternResult<Option<AgentPubKey>> {
+        let Some(hash) = hash else {
+            return Ok(None);
+        };
+        Ok(get(hash, GetOptions::default())?.map(|record| record.action().author().clone()))
+    };
+    let requester = author_of(get_service_request(service_match.request_id.clone())?.map(|(h, _)| h))?;
+    let offeror = author_of(get_service_offer(service_match.offer_id.clone())?.map(|(h, _)| h))?;
+    let party = if requester.as_ref() == Some(&me) {
+        "requester"
+    } else if offeror.as_ref() == Some(&me) {
+        "offeror"
+    } else {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the requester or the offeror can confirm a match".to_string()
+        )));
+    };
+
+    let mut metadata: serde_json::Value =
+        serde_json::from_str(&service_match.metadata_json).unwrap_or_else(|_| serde_json::json!({}));
+    if !metadata.is_object() {
+        metadata = serde_json::json!({});
+    }
+    let mut confirmed_by: Vec<String> = metadata
+        .get("confirmed_by")
+        .and_then(|c| serde_json::from_value(c.clone()).ok())
+        .unwrap_or_default();
+    if !confirmed_by.iter().any(|p| p == party) {
+        confirmed_by.push(party.to_string());
+    }
+    let both = confirmed_by.iter().any(|p| p == "requester") && confirmed_by.iter().any(|p| p == "offeror");
+    metadata["confirmed_by"] = serde_json::json!(confirmed_by);
+
+    let old_status = service_match.status.clone();
+    service_match.metadata_json = metadata.to_string();
+    service_match.updated_at = format!("{:?}", sys_time()?);
+    if both {
+        service_match.status = "contacted".to_string();
+    }
+
+    let action_hash = update_entry(old_hash.clone(), &EntryTypes::ServiceMatch(service_match.clone()))?;
+    let id_anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("service_match", &match_id)))?;
+    delete_links_to(id_anchor_hash.clone(), LinkTypes::IdToServiceMatch, &old_hash)?;
+    create_link(id_anchor_hash, action_hash.clone(), LinkTypes::IdToServiceMatch, ())?;
+    for (anchor_type, anchor_value, link_type) in [
+        ("request_matches", service_match.request_id.as_str(), LinkTypes::RequestToMatch),
+        ("offer_matches", service_match.offer_id.as_str(), LinkTypes::OfferToMatch),
+    ] {
+        let anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new(anchor_type, anchor_value)))?;
+        delete_links_to(anchor_hash.clone(), link_type, &old_hash)?;
+        create_link(anchor_hash, action_hash.clone(), link_type, ())?;
+    }
+    let old_status_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("matches_by_status", &old_status)))?;
+    delete_links_to(old_status_hash, LinkTypes::MatchByStatus, &old_hash)?;
+    let status_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("matches_by_status", &service_match.status)))?;
+    create_link(status_hash, action_hash, LinkTypes::MatchByStatus, ())?;
+
+    emit_write_signal("service_match", &match_id, "confirm_service_match");
+    Ok(service_match)
+}
+
 // =============================================================================
 // Post-Commit Signals for Doorway Projection
 // =============================================================================
@@ -10793,6 +20895,7 @@ pub fn register_shard_manifest(input: RegisterShardManifestInput) -> ExternResul
         ttl_secs: Some(86400), // 24 hours
         public: manifest.reach == "commons",
         reach: Some(manifest.reach.clone()),
+        source_fn: Some("register_shard_manifest".to_string()),
     }));
 
     Ok(RegisterShardManifestOutput { action_hash, manifest })
@@ -11264,3 +21367,145 @@ pub fn get_current_author_for_content(content_id: String) -> ExternResult<Option
 
     Ok(latest.map(|s| s.entry.successor_author_key.clone()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solvency_status_thresholds() {
+        assert_eq!(solvency_status(5000.0, Some(2.5)), "healthy");
+        assert_eq!(solvency_status(3000.0, Some(HEALTHY_SOLVENCY_RATIO)), "healthy");
+        assert_eq!(solvency_status(2500.0, Some(1.2)), "watch");
+        assert_eq!(solvency_status(2000.0, Some(1.0)), "watch");
+        assert_eq!(solvency_status(1500.0, Some(0.75)), "underfunded");
+        // No outstanding claims means no ratio
+        assert_eq!(solvency_status(5000.0, None), "healthy");
+        // Paid claims above contributions is underfunded whatever the ratio
+        assert_eq!(solvency_status(-100.0, None), "underfunded");
+        assert_eq!(solvency_status(-100.0, Some(2.0)), "underfunded");
+    }
+
+    #[test]
+    fn test_check_redundancy_against_assignments() {
+        let two = r#"[{"shard_index":0,"custodian_agent_id":"a"},{"shard_index":1,"custodian_agent_id":"b"}]"#;
+        assert!(check_redundancy(1, two).is_ok());
+        assert!(check_redundancy(2, two).is_ok());
+        assert!(check_redundancy(0, two).is_err());
+        assert!(check_redundancy(3, two).is_err());
+        assert!(check_redundancy(1, "[]").is_err());
+        assert!(check_redundancy(1, "not json").is_err());
+    }
+
+    #[test]
+    fn test_verify_watermark_rejects_mismatch() {
+        let watermark = "content_id:doc-1|custodian:a|shard:0|hash:abc123|timestamp:1";
+        assert!(verify_watermark(watermark, "doc-1", "abc123").unwrap());
+        assert!(!verify_watermark(watermark, "doc-2", "abc123").unwrap());
+        assert!(!verify_watermark(watermark, "doc-1", "def456").unwrap());
+        assert!(!verify_watermark("", "doc-1", "abc123").unwrap());
+    }
+
+    fn commitment(custodian: &str, beneficiary: &str, shard_assignments_json: &str) -> CustodianCommitment {
+        CustodianCommitment {
+            id: format!("{}-{}", beneficiary, custodian),
+            custodian_agent_id: custodian.to_string(),
+            beneficiary_agent_id: beneficiary.to_string(),
+            commitment_type: "relationship".to_string(),
+            basis: "trusted_relationship".to_string(),
+            relationship_id: None,
+            category_override_json: "[]".to_string(),
+            content_filters_json: "[]".to_string(),
+            estimated_content_count: 0,
+            estimated_size_mb: 0.0,
+            shard_strategy: "full_replica".to_string(),
+            redundancy_factor: 1,
+            shard_assignments_json: shard_assignments_json.to_string(),
+            emergency_triggers_json: "[]".to_string(),
+            emergency_contacts_json: "[]".to_string(),
+            recovery_instructions_json: "{}".to_string(),
+            cache_priority: 50,
+            bandwidth_class: "medium".to_string(),
+            geographic_affinity: None,
+            state: "accepted".to_string(),
+            proposed_at: String::new(),
+            accepted_at: None,
+            activated_at: None,
+            last_verification_at: None,
+            verification_failures_json: "[]".to_string(),
+            shards_stored_count: 0,
+            last_shard_update_at: None,
+            total_restores_performed: 0,
+            shefa_commitment_id: None,
+            note: None,
+            metadata_json: "{}".to_string(),
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_may_verify_shards() {
+        let c = commitment("custodian", "beneficiary", r#"[{"shard_index":1,"custodian_agent_id":"peer"}]"#);
+        assert!(may_verify_shards(&c, "custodian"));
+        assert!(may_verify_shards(&c, "beneficiary"));
+        assert!(may_verify_shards(&c, "peer"));
+        assert!(!may_verify_shards(&c, "stranger"));
+    }
+
+    #[test]
+    fn test_is_accepted_commitment() {
+        let mut c = commitment("custodian", "beneficiary", "[]");
+        c.state = "proposed".to_string();
+        assert!(!is_accepted_commitment(&c));
+
+        c.state = "accepted".to_string();
+        c.accepted_at = Some("t".to_string());
+        assert!(is_accepted_commitment(&c));
+        c.state = "activated".to_string();
+        assert!(is_accepted_commitment(&c));
+        c.state = "cancelled".to_string();
+        assert!(!is_accepted_commitment(&c));
+    }
+
+    #[test]
+    fn test_check_consensus_threshold() {
+        assert_eq!(check_consensus_threshold("threshold_split", Some(2)), Ok(2));
+        assert!(check_consensus_threshold("threshold_split", Some(1)).is_err());
+        assert_eq!(check_consensus_threshold("full_replica", Some(1)), Ok(1));
+        assert!(check_consensus_threshold("full_replica", Some(0)).is_err());
+        assert!(check_consensus_threshold("erasure_coded", None).is_err());
+    }
+
+    fn shard(commitment_id: &str, content_id: &str, shard_index: u32, watermark_content_id: &str) -> CustodianShard {
+        CustodianShard {
+            id: format!("shard-{}-{}-{}", commitment_id, content_id, shard_index),
+            commitment_id: commitment_id.to_string(),
+            content_id: content_id.to_string(),
+            custodian_agent_id: "custodian".to_string(),
+            shard_index,
+            total_shards: 2,
+            encrypted_shard_data: String::new(),
+            encryption_method: "none".to_string(),
+            shard_hash: "abc123".to_string(),
+            watermark_signature: format!("content_id:{}|shard:{}|hash:abc123", watermark_content_id, shard_index),
+            stored_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_commitment_content_shards_leaves_out_other_commitments() {
+        let shards = vec![
+            shard("b-c1", "doc-1", 0, "doc-1"),
+            shard("b-c2", "doc-1", 1, "doc-1"),
+            shard("b-c1", "doc-2", 0, "doc-2"),
+            shard("b-c1", "doc-1", 1, "doc-9"),
+        ];
+
+        let kept = commitment_content_shards(shards, "b-c1", "doc-1");
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].commitment_id, "b-c1");
+        assert_eq!(kept[0].content_id, "doc-1");
+        assert_eq!(kept[0].shard_index, 0);
+    }
+}
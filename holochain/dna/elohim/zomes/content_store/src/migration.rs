@@ -30,7 +30,11 @@
 // See: holochain/rna/ for the reusable RNA toolkit
 
 use hdk::prelude::*;
+use std::collections::HashMap;
+use content_store_integrity::{EntryTypes, LinkTypes, MigrationProgress, StringAnchor};
+use hc_rna::MigrationSpec;
 use crate::{Content, LearningPath, PathStep, ContentMastery, AgentProgress, create_content, CreateContentInput};
+use crate::providers::{migration_plan, CURRENT_SCHEMA_VERSION, HEALING_ENTRY_TYPES, NEXT_SCHEMA_VERSION};
 
 /// Migration report tracking success/failure of migrated items
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -298,3 +302,144 @@ pub struct MigrationCounts {
     pub mastery_count: u32,
     pub progress_count: u32,
 }
+
+// =============================================================================
+// Schema Migration Progress
+// =============================================================================
+// Agents running a migration or healing pass record how far they got per
+// entry type; get_migration_status sums the latest record from each agent
+// into a network-wide view, next to the planned field mappings
+// (providers::schema_v3_plans).
+
+/// Counts from one migration or healing pass over an entry type
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RecordMigrationProgressInput {
+    pub entry_type: String,
+    pub from_version: u32,
+    pub to_version: u32,
+    pub scanned: u32,
+    pub at_target: u32,
+    pub migrated: u32,
+    pub degraded: u32,
+    pub failed: u32,
+}
+
+/// Network-wide migration status of one entry type
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EntryTypeMigrationStatus {
+    pub entry_type: String,
+    /// Version entries are written at today
+    pub current_version: u32,
+    /// Version the planned migration targets
+    pub target_version: u32,
+    /// Planned field mappings, if a migration is authored
+    pub plan: Option<MigrationSpec>,
+    pub scanned: u32,
+    pub at_target: u32,
+    pub migrated: u32,
+    pub degraded: u32,
+    pub failed: u32,
+    /// Agents whose latest pass is counted
+    pub reporters: u32,
+    pub last_recorded_at: Option<String>,
+}
+
+fn migration_progress_anchor_hash(entry_type: &str) -> ExternResult<EntryHash> {
+    hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("migration_progress", entry_type)))
+}
+
+/// Record this agent's progress migrating an entry type
+#[hdk_extern]
+pub fn record_migration_progress(input: RecordMigrationProgressInput) -> ExternResult<ActionHash> {
+    let progress = MigrationProgress {
+        entry_type: input.entry_type,
+        from_version: input.from_version,
+        to_version: input.to_version,
+        scanned: input.scanned,
+        at_target: input.at_target,
+        migrated: input.migrated,
+        degraded: input.degraded,
+        failed: input.failed,
+        reporter: agent_info()?.agent_initial_pubkey.to_string(),
+        recorded_at: format!("{:?}", sys_time()?),
+    };
+
+    let action_hash = create_entry(&EntryTypes::MigrationProgress(progress.clone()))?;
+    create_link(
+        migration_progress_anchor_hash(&progress.entry_type)?,
+        action_hash.clone(),
+        LinkTypes::EntryTypeToMigrationProgress,
+        (),
+    )?;
+
+    Ok(action_hash)
+}
+
+/// Healing and migration status of every healing entry type across the DHT
+#[hdk_extern]
+pub fn get_migration_status(_: ()) -> ExternResult<Vec<EntryTypeMigrationStatus>> {
+    HEALING_ENTRY_TYPES
+        .iter()
+        .map(|entry_type| entry_type_migration_status(entry_type))
+        .collect()
+}
+
+fn entry_type_migration_status(entry_type: &str) -> ExternResult<EntryTypeMigrationStatus> {
+    let plan = migration_plan(entry_type);
+    let target_version = plan.as_ref().map(|p| p.to_version).unwrap_or(NEXT_SCHEMA_VERSION);
+
+    let query = LinkQuery::try_new(migration_progress_anchor_hash(entry_type)?, LinkTypes::EntryTypeToMigrationProgress)?;
+    let links = get_links(query, GetStrategy::default())?;
+
+    // Latest pass per reporter towards the target version
+    let mut latest: HashMap<String, (Timestamp, MigrationProgress)> = HashMap::new();
+    for link in links {
+        let Some(action_hash) = link.target.clone().into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(action_hash, GetOptions::default())? else {
+            continue;
+        };
+        let Some(progress) = record.entry().to_app_option::<MigrationProgress>().ok().flatten() else {
+            continue;
+        };
+        if progress.to_version != target_version {
+            continue;
+        }
+        let newer = latest
+            .get(&progress.reporter)
+            .map(|(seen, _)| link.timestamp > *seen)
+            .unwrap_or(true);
+        if newer {
+            latest.insert(progress.reporter.clone(), (link.timestamp, progress));
+        }
+    }
+
+    let mut status = EntryTypeMigrationStatus {
+        entry_type: entry_type.to_string(),
+        current_version: CURRENT_SCHEMA_VERSION,
+        target_version,
+        plan,
+        scanned: 0,
+        at_target: 0,
+        migrated: 0,
+        degraded: 0,
+        failed: 0,
+        reporters: latest.len() as u32,
+        last_recorded_at: None,
+    };
+    let mut last: Option<Timestamp> = None;
+    for (timestamp, progress) in latest.into_values() {
+        status.scanned = status.scanned.saturating_add(progress.scanned);
+        status.at_target = status.at_target.saturating_add(progress.at_target);
+        status.migrated = status.migrated.saturating_add(progress.migrated);
+        status.degraded = status.degraded.saturating_add(progress.degraded);
+        status.failed = status.failed.saturating_add(progress.failed);
+        if last.map(|l| timestamp > l).unwrap_or(true) {
+            last = Some(timestamp);
+            status.last_recorded_at = Some(progress.recorded_at);
+        }
+    }
+
+    Ok(status)
+}
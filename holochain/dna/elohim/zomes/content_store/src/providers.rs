@@ -6,10 +6,12 @@
 //! - Transcriber: v1→v2 field mapping
 //! - ReferenceResolver: check if referenced entries exist
 //! - DegradationHandler: policy for handling healing failures
+//!
+//! It also holds the planned v2→v3 migrations (see `schema_v3_plans`).
 
 use hc_rna::{
     Validator, Transcriber, ReferenceResolver, DegradationHandler, DegradationDecision,
-    EntryTypeProvider,
+    EntryTypeProvider, MigrationSpec,
 };
 use serde_json::Value;
 
@@ -554,6 +556,40 @@ impl EntryTypeProvider for ContentMasteryProvider {
     }
 }
 
+// ============================================================================
+// SCHEMA v3 MIGRATION PLANS - Declarative v2→v3 Field Mappings
+// ============================================================================
+
+/// Schema version entries are written at today
+pub const CURRENT_SCHEMA_VERSION: u32 = 2;
+
+/// Schema version the next migration targets
+pub const NEXT_SCHEMA_VERSION: u32 = 3;
+
+/// Healing entry types, in the order they are registered in init
+pub const HEALING_ENTRY_TYPES: &[&str] = &["content", "learning_path", "path_step", "content_mastery"];
+
+/// Planned v2→v3 migration for every healing entry type
+///
+/// Author v3 changes here as `FieldMapping`s, e.g.
+/// `.map(FieldMapping::rename("reach", "audience"))`. Each spec is the v3
+/// transcriber and, wrapped in a `DualReadValidator`, the read shim that lets
+/// v3 code accept v2 entries until they heal. An entry type with no mappings
+/// only has its schema_version bumped.
+pub fn schema_v3_plans() -> Vec<MigrationSpec> {
+    vec![
+        MigrationSpec::new("content", CURRENT_SCHEMA_VERSION, NEXT_SCHEMA_VERSION, "Content v2 → v3"),
+        MigrationSpec::new("learning_path", CURRENT_SCHEMA_VERSION, NEXT_SCHEMA_VERSION, "LearningPath v2 → v3"),
+        MigrationSpec::new("path_step", CURRENT_SCHEMA_VERSION, NEXT_SCHEMA_VERSION, "PathStep v2 → v3"),
+        MigrationSpec::new("content_mastery", CURRENT_SCHEMA_VERSION, NEXT_SCHEMA_VERSION, "ContentMastery v2 → v3"),
+    ]
+}
+
+/// Planned v2→v3 migration for one entry type
+pub fn migration_plan(entry_type: &str) -> Option<MigrationSpec> {
+    schema_v3_plans().into_iter().find(|plan| plan.entry_type == entry_type)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -621,6 +657,36 @@ mod tests {
         assert_eq!(provider.entry_type(), "content");
     }
 
+    #[test]
+    fn test_every_healing_entry_type_has_a_v3_plan() {
+        let providers: [&dyn EntryTypeProvider; 4] =
+            [&ContentProvider, &LearningPathProvider, &PathStepProvider, &ContentMasteryProvider];
+        for provider in providers {
+            assert!(HEALING_ENTRY_TYPES.contains(&provider.entry_type()));
+            let plan = migration_plan(provider.entry_type()).expect("missing v3 plan");
+            assert_eq!(plan.from_version, CURRENT_SCHEMA_VERSION);
+            assert_eq!(plan.to_version, NEXT_SCHEMA_VERSION);
+        }
+    }
+
+    #[test]
+    fn test_v3_plan_upgrades_valid_v2_content() {
+        let v2 = serde_json::json!({
+            "id": "content-1",
+            "content_type": "lesson",
+            "title": "Learning Rust",
+            "schema_version": 2,
+            "validation_status": "Valid"
+        });
+        assert!(ContentValidator.validate_json(&v2).is_ok());
+
+        let plan = migration_plan("content").unwrap();
+        let v3 = plan.transcribe_from_prev(&v2).unwrap();
+        assert_eq!(v3["schema_version"], 3);
+        assert_eq!(plan.read_current(&v2).unwrap(), v3);
+        assert!(plan.is_reversible());
+    }
+
     #[test]
     fn test_different_degradation_decisions() {
         let content_handler = ContentDegradationHandler;
@@ -0,0 +1,398 @@
+//! Shard Encoding for Custodian Commitments
+//!
+//! Pure Rust (no host calls) so it runs unchanged in WASM and in unit tests.
+//! The zome supplies the content digest and any randomness.
+//!
+//! Strategies (SHARD_STRATEGIES):
+//! - `full_replica`: every shard carries the whole payload, any 1 restores
+//! - `threshold_split`: Shamir's Secret Sharing over GF(256), any M of N restore,
+//!   fewer than M reveal nothing
+//! - `erasure_coded`: systematic Reed-Solomon over GF(256), any M of N restore,
+//!   each shard is ~1/M of the payload
+//!
+//! Every shard is `[x][body]` where `x` (1..=N) is the shard's evaluation point.
+//! The payload that gets split is framed as `digest(32) | length(u64 BE) | data`,
+//! so reassembly can strip padding and the caller can check the digest.
+
+/// Length of the content digest carried in the frame (SHA-256)
+pub const DIGEST_LEN: usize = 32;
+
+const LENGTH_LEN: usize = 8;
+const FRAME_HEADER_LEN: usize = DIGEST_LEN + LENGTH_LEN;
+
+// =============================================================================
+// GF(256) Arithmetic (primitive polynomial x^8 + x^4 + x^3 + x^2 + 1)
+// =============================================================================
+
+const fn build_gf_tables() -> ([u8; 512], [u8; 256]) {
+    let mut exp = [0u8; 512];
+    let mut log = [0u8; 256];
+    let mut x: u16 = 1;
+    let mut i = 0;
+    while i < 255 {
+        exp[i] = x as u8;
+        log[x as usize] = i as u8;
+        x <<= 1;
+        if x & 0x100 != 0 {
+            x ^= 0x11d;
+        }
+        i += 1;
+    }
+    while i < 512 {
+        exp[i] = exp[i - 255];
+        i += 1;
+    }
+    (exp, log)
+}
+
+const GF_TABLES: ([u8; 512], [u8; 256]) = build_gf_tables();
+
+fn gf_mul(a: u8, b: u8) -> u8 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    let (exp, log) = &GF_TABLES;
+    exp[log[a as usize] as usize + log[b as usize] as usize]
+}
+
+fn gf_div(a: u8, b: u8) -> u8 {
+    debug_assert!(b != 0, "division by zero in GF(256)");
+    if a == 0 {
+        return 0;
+    }
+    let (exp, log) = &GF_TABLES;
+    exp[log[a as usize] as usize + 255 - log[b as usize] as usize]
+}
+
+/// Lagrange basis coefficients for evaluating at `x` from points `xs`
+fn lagrange_coefficients(xs: &[u8], x: u8) -> Vec<u8> {
+    xs.iter()
+        .enumerate()
+        .map(|(i, &xi)| {
+            xs.iter().enumerate().filter(|(j, _)| *j != i).fold(1u8, |acc, (_, &xj)| {
+                gf_mul(acc, gf_div(x ^ xj, xi ^ xj))
+            })
+        })
+        .collect()
+}
+
+/// Evaluate, column by column, the polynomials through (`xs`, `rows`) at `x`
+fn interpolate_rows(xs: &[u8], rows: &[&[u8]], x: u8) -> Vec<u8> {
+    let coefficients = lagrange_coefficients(xs, x);
+    let len = rows.first().map_or(0, |row| row.len());
+    let mut out = vec![0u8; len];
+    for (row, &coefficient) in rows.iter().zip(&coefficients) {
+        for (acc, &value) in out.iter_mut().zip(row.iter()) {
+            *acc ^= gf_mul(value, coefficient);
+        }
+    }
+    out
+}
+
+// =============================================================================
+// Framing
+// =============================================================================
+
+fn frame(data: &[u8], digest: &[u8; DIGEST_LEN]) -> Vec<u8> {
+    let mut framed = Vec::with_capacity(FRAME_HEADER_LEN + data.len());
+    framed.extend_from_slice(digest);
+    framed.extend_from_slice(&(data.len() as u64).to_be_bytes());
+    framed.extend_from_slice(data);
+    framed
+}
+
+fn unframe(framed: &[u8]) -> Result<(Vec<u8>, Vec<u8>), String> {
+    if framed.len() < FRAME_HEADER_LEN {
+        return Err("Reassembled payload is too short".to_string());
+    }
+    let mut length_bytes = [0u8; LENGTH_LEN];
+    length_bytes.copy_from_slice(&framed[DIGEST_LEN..FRAME_HEADER_LEN]);
+    let length = u64::from_be_bytes(length_bytes) as usize;
+    let body = &framed[FRAME_HEADER_LEN..];
+    if length > body.len() {
+        return Err("Reassembled payload length is corrupt".to_string());
+    }
+    Ok((framed[..DIGEST_LEN].to_vec(), body[..length].to_vec()))
+}
+
+// =============================================================================
+// Encoding
+// =============================================================================
+
+/// Check strategy parameters, returning (threshold, total) as shard counts
+fn check_params(strategy: &str, threshold: u32, total: u32) -> Result<(usize, usize), String> {
+    if total == 0 || total > 255 {
+        return Err(format!("total_shards must be between 1 and 255, got {}", total));
+    }
+    match strategy {
+        "full_replica" => Ok((1, total as usize)),
+        "threshold_split" | "erasure_coded" => {
+            if threshold == 0 || threshold > total {
+                return Err(format!(
+                    "redundancy_factor must be between 1 and total_shards ({}), got {}",
+                    total, threshold
+                ));
+            }
+            Ok((threshold as usize, total as usize))
+        }
+        other => Err(format!("Unknown shard strategy: {}", other)),
+    }
+}
+
+/// Random bytes `encode_shards` needs for the given strategy and content length
+pub fn random_bytes_needed(strategy: &str, data_len: usize, threshold: u32) -> usize {
+    match strategy {
+        "threshold_split" => (FRAME_HEADER_LEN + data_len) * threshold.saturating_sub(1) as usize,
+        _ => 0,
+    }
+}
+
+/// Split `data` into `total` shards, any `threshold` of which reassemble it.
+///
+/// `random` must hold at least [`random_bytes_needed`] bytes.
+pub fn encode_shards(
+    strategy: &str,
+    data: &[u8],
+    digest: &[u8; DIGEST_LEN],
+    threshold: u32,
+    total: u32,
+    random: &[u8],
+) -> Result<Vec<Vec<u8>>, String> {
+    let (threshold, total) = check_params(strategy, threshold, total)?;
+    let framed = frame(data, digest);
+
+    let bodies: Vec<Vec<u8>> = match strategy {
+        "full_replica" => vec![framed; total],
+        "threshold_split" => {
+            let needed = framed.len() * (threshold - 1);
+            if random.len() < needed {
+                return Err(format!("Need {} random bytes, got {}", needed, random.len()));
+            }
+            // One polynomial per byte: constant term is the secret byte, the
+            // rest are random. Share x is the polynomial evaluated at x.
+            (1..=total as u8)
+                .map(|x| {
+                    framed
+                        .iter()
+                        .enumerate()
+                        .map(|(byte_index, &secret)| {
+                            let coefficients = &random[byte_index * (threshold - 1)..(byte_index + 1) * (threshold - 1)];
+                            let higher = coefficients.iter().rev().fold(0u8, |acc, &c| gf_mul(acc, x) ^ c);
+                            gf_mul(higher, x) ^ secret
+                        })
+                        .collect()
+                })
+                .collect()
+        }
+        _ => {
+            // Systematic Reed-Solomon: shards 1..=M are the payload split into
+            // M equal chunks; parity shards are the same column polynomials
+            // evaluated at M+1..=N.
+            let chunk_len = framed.len().div_ceil(threshold);
+            let mut padded = framed;
+            padded.resize(chunk_len * threshold, 0);
+            let data_rows: Vec<&[u8]> = padded.chunks(chunk_len).collect();
+            let data_xs: Vec<u8> = (1..=threshold as u8).collect();
+
+            let mut bodies: Vec<Vec<u8>> = data_rows.iter().map(|row| row.to_vec()).collect();
+            for x in (threshold + 1)..=total {
+                bodies.push(interpolate_rows(&data_xs, &data_rows, x as u8));
+            }
+            bodies
+        }
+    };
+
+    Ok(bodies
+        .into_iter()
+        .enumerate()
+        .map(|(i, body)| {
+            let mut shard = Vec::with_capacity(body.len() + 1);
+            shard.push((i + 1) as u8);
+            shard.extend(body);
+            shard
+        })
+        .collect())
+}
+
+/// Reassemble shards produced by [`encode_shards`].
+///
+/// Returns `(digest, data)`; the caller checks the digest against the data.
+/// Duplicate shards are ignored; extra shards beyond the threshold are unused.
+pub fn decode_shards(strategy: &str, shards: &[Vec<u8>], threshold: u32) -> Result<(Vec<u8>, Vec<u8>), String> {
+    let needed = match strategy {
+        "full_replica" => 1,
+        "threshold_split" | "erasure_coded" if threshold > 0 && threshold <= 255 => threshold as usize,
+        "threshold_split" | "erasure_coded" => return Err(format!("Invalid redundancy_factor: {}", threshold)),
+        other => return Err(format!("Unknown shard strategy: {}", other)),
+    };
+
+    let mut xs: Vec<u8> = Vec::new();
+    let mut bodies: Vec<&[u8]> = Vec::new();
+    for shard in shards {
+        match shard.split_first() {
+            Some((&x, body)) if x != 0 && !xs.contains(&x) => {
+                if bodies.first().is_some_and(|first| first.len() != body.len()) {
+                    return Err("Shards have mismatched lengths".to_string());
+                }
+                xs.push(x);
+                bodies.push(body);
+            }
+            _ => continue,
+        }
+        if xs.len() == needed {
+            break;
+        }
+    }
+    if xs.len() < needed {
+        return Err(format!("Need {} distinct shards, got {}", needed, xs.len()));
+    }
+
+    let framed = match strategy {
+        "full_replica" => bodies[0].to_vec(),
+        "threshold_split" => interpolate_rows(&xs, &bodies, 0),
+        _ => {
+            let mut framed = Vec::with_capacity(bodies[0].len() * needed);
+            for x in 1..=needed as u8 {
+                match xs.iter().position(|&have| have == x) {
+                    Some(i) => framed.extend_from_slice(bodies[i]),
+                    None => framed.extend(interpolate_rows(&xs, &bodies, x)),
+                }
+            }
+            framed
+        }
+    };
+
+    unframe(&framed)
+}
+
+// =============================================================================
+// Base64 (standard alphabet, padded) for shard transport
+// =============================================================================
+
+const BASE64_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+pub fn base64_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = ((b[0] as u32) << 16) | ((b[1] as u32) << 8) | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64_ALPHABET[((n >> (18 - 6 * i)) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+pub fn base64_decode(text: &str) -> Result<Vec<u8>, String> {
+    let trimmed = text.trim_end_matches('=');
+    let mut out = Vec::with_capacity(trimmed.len() * 3 / 4);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in trimmed.bytes() {
+        let value = BASE64_ALPHABET
+            .iter()
+            .position(|&a| a == c)
+            .ok_or_else(|| format!("Invalid base64 character: {}", c as char))?;
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIGEST: [u8; DIGEST_LEN] = [7u8; DIGEST_LEN];
+
+    fn pseudo_random(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i as u32).wrapping_mul(2654435761).rotate_left(7) as u8).collect()
+    }
+
+    #[test]
+    fn test_gf_mul_div_roundtrip() {
+        for a in 1..=255u8 {
+            for b in [1u8, 2, 3, 29, 128, 255] {
+                assert_eq!(gf_div(gf_mul(a, b), b), a);
+            }
+        }
+    }
+
+    #[test]
+    fn test_shamir_any_threshold_subset_restores() {
+        let data = b"intimate reach family archive".to_vec();
+        let random = pseudo_random(random_bytes_needed("threshold_split", data.len(), 3));
+        let shards = encode_shards("threshold_split", &data, &DIGEST, 3, 5, &random).unwrap();
+        assert_eq!(shards.len(), 5);
+
+        for subset in [[0, 1, 2], [4, 2, 0], [1, 3, 4]] {
+            let picked: Vec<Vec<u8>> = subset.iter().map(|&i| shards[i].clone()).collect();
+            let (digest, restored) = decode_shards("threshold_split", &picked, 3).unwrap();
+            assert_eq!(digest, DIGEST.to_vec());
+            assert_eq!(restored, data);
+        }
+    }
+
+    #[test]
+    fn test_shamir_below_threshold_fails() {
+        let data = b"secret".to_vec();
+        let random = pseudo_random(random_bytes_needed("threshold_split", data.len(), 3));
+        let shards = encode_shards("threshold_split", &data, &DIGEST, 3, 5, &random).unwrap();
+        assert!(decode_shards("threshold_split", &shards[..2], 3).is_err());
+        // Duplicates don't count toward the threshold
+        let dupes = vec![shards[0].clone(), shards[0].clone(), shards[1].clone()];
+        assert!(decode_shards("threshold_split", &dupes, 3).is_err());
+    }
+
+    #[test]
+    fn test_reed_solomon_recovers_from_parity() {
+        let data: Vec<u8> = (0..1000u32).map(|i| (i * 7 % 251) as u8).collect();
+        let shards = encode_shards("erasure_coded", &data, &DIGEST, 4, 7, &[]).unwrap();
+        assert_eq!(shards.len(), 7);
+        // Each shard is about a quarter of the framed payload
+        assert!(shards[0].len() < data.len() / 3);
+
+        let parity_heavy: Vec<Vec<u8>> = vec![shards[6].clone(), shards[1].clone(), shards[5].clone(), shards[4].clone()];
+        let (_, restored) = decode_shards("erasure_coded", &parity_heavy, 4).unwrap();
+        assert_eq!(restored, data);
+
+        let (_, restored) = decode_shards("erasure_coded", &shards[..4], 4).unwrap();
+        assert_eq!(restored, data);
+    }
+
+    #[test]
+    fn test_full_replica_and_empty_content() {
+        let shards = encode_shards("full_replica", b"", &DIGEST, 1, 3, &[]).unwrap();
+        assert_eq!(shards.len(), 3);
+        let (_, restored) = decode_shards("full_replica", &shards[2..], 1).unwrap();
+        assert!(restored.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_params_rejected() {
+        assert!(encode_shards("erasure_coded", b"x", &DIGEST, 5, 3, &[]).is_err());
+        assert!(encode_shards("threshold_split", b"x", &DIGEST, 2, 3, &[]).is_err());
+        assert!(encode_shards("mirrored", b"x", &DIGEST, 1, 1, &[]).is_err());
+        assert!(encode_shards("full_replica", b"x", &DIGEST, 1, 300, &[]).is_err());
+    }
+
+    #[test]
+    fn test_base64_roundtrip() {
+        for len in 0..10 {
+            let bytes = pseudo_random(len);
+            assert_eq!(base64_decode(&base64_encode(&bytes)).unwrap(), bytes);
+        }
+        assert_eq!(base64_encode(b"Man"), "TWFu");
+        assert_eq!(base64_encode(b"Ma"), "TWE=");
+        assert!(base64_decode("a$b=").is_err());
+    }
+}
@@ -163,7 +163,7 @@ pub const ATTESTATION_CATEGORIES: [&str; 4] = [
 // =============================================================================
 
 /// Point triggers - actions that earn points (hREA EconomicEvent triggers)
-pub const POINT_TRIGGERS: [&str; 10] = [
+pub const POINT_TRIGGERS: [&str; 11] = [
     "engagement_view",       // Viewing content
     "engagement_practice",   // Practicing content
     "challenge_correct",     // Correct answer in mastery challenge
@@ -174,11 +174,12 @@ pub const POINT_TRIGGERS: [&str; 10] = [
     "path_step_complete",    // Completing a learning path step
     "path_complete",         // Completing entire learning path
     "contribution",          // Contributing to content
+    "invitation",            // Someone joined with your invite code
 ];
 
 /// Point amounts for each trigger (can be configured)
 /// Demonstrates hREA resourceQuantity
-pub const DEFAULT_POINT_AMOUNTS: [(& str, i32); 10] = [
+pub const DEFAULT_POINT_AMOUNTS: [(& str, i32); 11] = [
     ("engagement_view", 1),
     ("engagement_practice", 2),
     ("challenge_correct", 5),
@@ -189,6 +190,7 @@ pub const DEFAULT_POINT_AMOUNTS: [(& str, i32); 10] = [
     ("path_step_complete", 5),
     ("path_complete", 100),
     ("contribution", 50),
+    ("invitation", 25),
 ];
 
 /// Resource specifications for the point system (hREA ResourceSpecification)
@@ -199,11 +201,12 @@ pub const POINT_RESOURCE_SPECS: [&str; 3] = [
 ];
 
 /// Recognition flow types - how value flows to contributors (hREA Appreciation)
-pub const RECOGNITION_FLOW_TYPES: [&str; 4] = [
+pub const RECOGNITION_FLOW_TYPES: [&str; 5] = [
     "content_engagement",   // Someone engaged with your content
     "content_mastery",      // Someone mastered your content
     "path_completion",      // Someone completed a path with your content
     "discovery_spark",      // Your content sparked a discovery
+    "contribution",         // A steward accepted your contribution
 ];
 
 // =============================================================================
@@ -409,6 +412,13 @@ pub const SHARD_STRATEGIES: [&str; 3] = [
     "erasure_coded",     // Reed-Solomon erasure coding (efficient for large files)
 ];
 
+/// Encryption methods accepted for custodian shards
+pub const SHARD_ENCRYPTION_METHODS: [&str; 3] = [
+    "age",
+    "pgp",
+    "xchacha20",
+];
+
 /// Emergency trigger types - what can activate emergency protocol
 pub const EMERGENCY_TRIGGERS: [&str; 5] = [
     "manual_signal",           // Beneficiary manually activates via passphrase
@@ -580,6 +590,131 @@ pub struct BlobEntry {
     pub verified_at: Option<String>,
 }
 
+/// Alternate rendition of a media blob (resolution, bitrate or codec)
+///
+/// Players choose among a blob's variants for adaptive delivery. Each
+/// variant is a full blob of its own; linked from the parent's blob_hash
+/// anchor via BlobToVariants.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct BlobVariant {
+    /// Hash of the original blob this is a rendition of
+    pub parent_hash: String,
+
+    /// SHA256 hash of the variant's own bytes
+    pub hash: String,
+
+    /// Resolution label for video (see VIDEO_VARIANTS); None for audio
+    pub resolution: Option<String>,
+
+    /// Pixel dimensions, when known
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+
+    /// Bitrate in megabits per second
+    pub bitrate_mbps: f32,
+
+    /// Codec (see CODEC_TYPES)
+    pub codec: String,
+
+    /// Size in bytes
+    pub size_bytes: u64,
+
+    /// MIME type (video/mp4, audio/webm, ...)
+    pub mime_type: String,
+
+    /// Where the variant can be downloaded besides the DHT/storage
+    pub fallback_urls: Vec<String>,
+
+    /// When registered
+    pub created_at: String,
+}
+
+/// Caption/subtitle track for a media blob
+///
+/// The caption file itself is stored like any other blob and addressed by
+/// `caption_hash`; this entry ties it to the media it describes. Linked from
+/// the media's blob_hash anchor via BlobToCaptions.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct BlobCaption {
+    /// Hash of the media blob these captions belong to
+    pub blob_hash: String,
+
+    /// Content node the media is attached to
+    pub content_id: String,
+
+    /// BCP 47 language tag (en, es-MX, ...)
+    pub language: String,
+
+    /// Track label shown in players ("English (CC)")
+    pub label: Option<String>,
+
+    /// Caption format (see CAPTION_FORMATS)
+    pub format: String,
+
+    /// SHA256 hash of the caption file (sha256-{hex})
+    pub caption_hash: String,
+
+    /// Size of the caption file in bytes
+    pub size_bytes: u64,
+
+    /// Uploader
+    pub author_id: Option<String>,
+
+    /// When created
+    pub created_at: String,
+}
+
+/// Translation of a Content node into another locale
+///
+/// Lets one content node serve several languages instead of being duplicated
+/// per language. Fields left untranslated fall back to the original. Linked
+/// from the content_id anchor via ContentToTranslations; one live
+/// translation per locale, plus at most one draft awaiting review.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct ContentTranslation {
+    /// Content node this translates
+    pub content_id: String,
+
+    /// BCP 47 language tag (es, pt-BR, zh-Hant, ...)
+    pub locale: String,
+
+    /// Translated title
+    pub title: String,
+
+    /// Translated description
+    pub description: String,
+
+    /// Translated body; None keeps the original body
+    pub body: Option<String>,
+
+    /// Translator
+    pub translator_id: Option<String>,
+
+    /// When created
+    pub created_at: String,
+
+    /// Produced by a machine-translation provider rather than a person
+    #[serde(default)]
+    pub machine_generated: bool,
+
+    /// Awaiting steward review; drafts are never served to readers
+    #[serde(default)]
+    pub draft: bool,
+}
+
+/// Loose BCP 47 check: alphabetic primary subtag, alphanumeric subtags
+pub fn is_valid_locale(tag: &str) -> bool {
+    let mut subtags = tag.split('-');
+    let primary_ok = subtags
+        .next()
+        .is_some_and(|p| (2..=8).contains(&p.len()) && p.chars().all(|c| c.is_ascii_alphabetic()));
+    primary_ok
+        && subtags.all(|s| (1..=8).contains(&s.len()) && s.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
 // =============================================================================
 // Shard Management - Unified Model for Single/Distributed Storage
 // =============================================================================
@@ -817,6 +952,40 @@ pub struct PathChapter {
     pub updated_at: String,
 }
 
+/// Enrichment states for ExternalResource.status
+pub const EXTERNAL_RESOURCE_STATUSES: [&str; 3] = [
+    "pending",  // Waiting for the doorway enrichment worker
+    "enriched", // Metadata fetched (and a snapshot taken, if storage is configured)
+    "failed",   // The URL could not be fetched
+];
+
+/// External resource - the page behind an `external` step's URL.
+///
+/// Created when an external step is added and filled in by the doorway
+/// enrichment worker from the page's oEmbed and Open Graph metadata, so the
+/// link renders richly. `snapshot_hash` points at an archived copy of the
+/// page in elohim-storage, served at `/store/{hash}` after the original
+/// goes away. Linked from the external_url anchor via UrlToExternalResource;
+/// the URL identifies the resource.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct ExternalResource {
+    pub url: String,
+    /// Provider name, e.g. "YouTube" (oEmbed provider_name or og:site_name)
+    pub provider: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub thumbnail_url: Option<String>,
+    /// oEmbed response as JSON, when the provider offers one
+    pub oembed_json: Option<String>,
+    /// Blob hash of the archived page snapshot (sha256-...)
+    pub snapshot_hash: Option<String>,
+    pub status: String,                 // See EXTERNAL_RESOURCE_STATUSES
+    pub fetched_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
 // =============================================================================
 // Content Relationship Entry
 // =============================================================================
@@ -1031,6 +1200,154 @@ pub struct AgentProgress {
     pub completed_at: Option<String>,
 }
 
+/// ReflectionEntry - A learner's journal response to a path step.
+///
+/// One entry per response, so reflections can be listed, shared and exported
+/// on their own instead of living only in AgentProgress.reflection_responses_json.
+/// Private to the learner unless they grant a facilitator access to the path
+/// with a ReflectionShareGrant.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct ReflectionEntry {
+    pub id: String,
+    pub learner_id: String,
+    pub path_id: String,
+    pub step_index: u32,
+    pub step_id: Option<String>,
+    pub prompt: Option<String>,            // The reflection prompt answered, if any
+    pub response: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// ReflectionShareGrant - A learner's explicit grant letting a facilitator
+/// read their reflections on one path.
+///
+/// Revoking writes a new version with `revoked_at` set; grants are never
+/// implied by enrollment or facilitation alone.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct ReflectionShareGrant {
+    pub id: String,
+    pub learner_id: String,
+    pub facilitator_id: String,
+    pub path_id: String,
+    pub granted_at: String,
+    pub revoked_at: Option<String>,
+}
+
+/// Learning session kinds
+pub const LEARNING_SESSION_KINDS: [&str; 2] = [
+    "study",    // A learner blocking time for a path
+    "cohort",   // A facilitator's session for the learners sharing the path with them
+];
+
+/// Learning session statuses
+pub const LEARNING_SESSION_STATUSES: [&str; 2] = [
+    "scheduled",
+    "cancelled",
+];
+
+/// Longest a learning session may run, in minutes
+pub const MAX_LEARNING_SESSION_MINUTES: u32 = 24 * 60;
+
+/// LearningSession - Time set aside for a learning path.
+///
+/// A `study` session is a learner's own; a `cohort` session is published by
+/// a facilitator and shows up for every learner with an active
+/// ReflectionShareGrant to that facilitator on the path. Cancelling writes a
+/// new version with status `cancelled`, so calendars can drop it.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct LearningSession {
+    pub id: String,
+    /// Kind (LEARNING_SESSION_KINDS)
+    pub kind: String,
+    /// Learner (study) or facilitator (cohort) who scheduled it
+    pub organizer_id: String,
+    pub path_id: String,
+    pub title: String,
+    pub description: Option<String>,
+    /// Where to meet (a room or a call link)
+    pub location: Option<String>,
+    pub starts_at: String,
+    pub starts_at_micros: i64,
+    pub duration_minutes: u32,
+    /// Status (LEARNING_SESSION_STATUSES)
+    pub status: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Annotation visibilities
+pub const ANNOTATION_VISIBILITIES: [&str; 3] = [
+    "private",  // Only the author
+    "cohort",   // The author's cohort on the annotation's path
+    "public",   // Everyone reading the content
+];
+
+/// Annotation motivations (W3C Web Annotation motivations we accept)
+pub const ANNOTATION_MOTIVATIONS: [&str; 4] = [
+    "highlighting",
+    "commenting",
+    "questioning",
+    "replying",     // Answers another annotation (reply_to)
+];
+
+/// Longest annotation body, in bytes
+pub const MAX_ANNOTATION_BODY_BYTES: usize = 8 * 1024;
+
+/// Annotation - A learner's highlight or comment on a range of content.
+///
+/// `selector_json` locates the range (a W3C Web Annotation selector, e.g. a
+/// TextQuoteSelector). Cohort annotations are made while studying a path
+/// and form that path's shared layer: they show to learners with an active
+/// ReflectionShareGrant to the same facilitator on the path, and to the
+/// facilitator. Replies inherit their parent's content, path, range and
+/// visibility.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct Annotation {
+    pub id: String,
+    pub author_id: String,
+    pub content_id: String,
+    /// Path the author was studying, required for cohort annotations
+    pub path_id: Option<String>,
+    pub selector_json: String,
+    /// Motivation (ANNOTATION_MOTIVATIONS)
+    pub motivation: String,
+    /// Comment text; empty for a bare highlight
+    pub body: String,
+    /// Annotation this one answers, for motivation `replying`
+    pub reply_to: Option<String>,
+    /// Visibility (ANNOTATION_VISIBILITIES)
+    pub visibility: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Folder bookmarks land in when none is named
+pub const DEFAULT_BOOKMARK_FOLDER: &str = "Saved";
+
+/// Longest bookmark folder name, in bytes
+pub const MAX_BOOKMARK_FOLDER_BYTES: usize = 100;
+
+/// Bookmark - Content a human saved to their personal library.
+///
+/// Lets learners keep items outside formal paths, sorted into folders of
+/// their own naming. One bookmark per human and content: saving it again
+/// moves it to the new folder.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct Bookmark {
+    pub id: String,
+    pub owner_id: String,
+    pub content_id: String,
+    pub folder: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
 // =============================================================================
 // Lamad: Content Mastery Entry
 // =============================================================================
@@ -1612,7 +1929,7 @@ pub struct InsuranceClaim {
     pub observer_attestation_ids_json: String, // Vec<String> as JSON
     pub member_document_ids_json: String,      // Vec<String> as JSON
     // Status & history
-    pub status: String,                 // filed, adjustment-made, approved, denied, settled, appealed (from CLAIM_STATUSES)
+    pub status: String,                 // filed, submitted, under_review, approved, denied, paid, ... (from CLAIM_STATUSES)
     pub status_history_json: String,    // ClaimStatusChange[] as JSON
     // Adjustments
     pub adjustment_event_ids_json: String, // EconomicEvent IDs (Vec<String> as JSON)
@@ -1627,8 +1944,14 @@ pub struct InsuranceClaim {
 }
 
 /// Claim statuses
-pub const CLAIM_STATUSES: [&str; 7] = [
+///
+/// The adjustment lifecycle is filed → submitted (evidence attached) →
+/// under_review (adjuster assigned) → approved/denied → paid.
+pub const CLAIM_STATUSES: [&str; 10] = [
     "filed",
+    "submitted",
+    "under_review",
+    "paid",
     "adjustment-made",
     "approved",
     "denied",
@@ -1677,6 +2000,44 @@ pub const COVERAGE_DECISIONS: [&str; 3] = [
     "partial",
 ];
 
+// =============================================================================
+// Shefa: Insurance Mutual - Solvency Snapshot
+// =============================================================================
+
+/// SolvencySnapshot - Pool health at a point in time.
+///
+/// Pooled contributions (annual premiums of enrolled members, from their risk
+/// profiles) against claims paid and claims still owed. Recorded periodically
+/// so members can see how the mutual's reserves are trending.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct SolvencySnapshot {
+    pub id: String,
+    pub unit: String,                   // Unit all amounts are in (e.g. "unit-token")
+    // Pool
+    pub member_count: u32,              // Insurable members contributing
+    pub contributions_value: f64,       // Annual premiums pooled
+    // Claims
+    pub paid_claims_value: f64,
+    pub paid_claim_count: u32,
+    pub outstanding_claims_value: f64,  // Approved but unpaid, or still in review
+    pub outstanding_claim_count: u32,
+    // Health
+    pub reserve_value: f64,             // contributions - paid claims
+    pub solvency_ratio: Option<f64>,    // reserve / outstanding (None when nothing is owed)
+    pub status: String,                 // healthy, watch, underfunded (from SOLVENCY_STATUSES)
+    pub breakdown_json: String,         // Per risk type and claim status totals
+    pub computed_at: String,
+    pub created_at: String,
+}
+
+/// Solvency statuses
+pub const SOLVENCY_STATUSES: [&str; 3] = [
+    "healthy",      // Reserves cover outstanding claims with margin
+    "watch",        // Reserves cover outstanding claims, little margin
+    "underfunded",  // Outstanding claims exceed reserves
+];
+
 // =============================================================================
 // Shefa: Requests & Offers - Service Request
 // =============================================================================
@@ -2782,6 +3143,170 @@ pub const CHALLENGE_STATES: [&str; 3] = [
     "abandoned",
 ];
 
+/// Question types for assessment items
+pub const ASSESSMENT_QUESTION_TYPES: [&str; 4] = [
+    "multiple_choice", // Pick one of the options
+    "true_false",      // correct_answer is "true" or "false"
+    "short_answer",    // Free text compared to correct_answer
+    "recall",          // Self-assessed; correct_answer is a model answer
+];
+
+/// Assessment Item - a question in the question bank for a content node.
+///
+/// Mastery challenges draw their questions from published items. Items
+/// written by the question generator are tagged `ai_generated` and start
+/// unpublished; a steward publishes them after review. Linked from the
+/// content_id anchor via ContentToAssessmentItems.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct AssessmentItem {
+    pub id: String,
+    /// Content node this question assesses
+    pub content_id: String,
+    pub question_type: String,        // See ASSESSMENT_QUESTION_TYPES
+    pub question_text: String,
+    /// Answer options (empty for open questions)
+    pub options: Vec<String>,
+    pub correct_answer: String,
+    /// Shown after answering
+    pub explanation: Option<String>,
+    /// Free-form tags (`ai_generated` for generated drafts)
+    pub tags: Vec<String>,
+    /// Only published items appear in mastery challenges
+    pub published: bool,
+    pub author_id: String,
+    /// Model that generated the item, if any
+    pub generated_by: Option<String>,
+    pub reviewed_by: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+// =============================================================================
+// Lamad: Peer Review Entries
+// =============================================================================
+
+/// Peer review request states
+pub const PEER_REVIEW_STATUSES: [&str; 4] = [
+    "open",      // Waiting for reviewers
+    "assigned",  // All reviewer slots taken, reviews outstanding
+    "completed", // Every assigned reviewer has submitted
+    "cancelled", // Withdrawn by the author
+];
+
+/// Reviewer recommendations
+pub const PEER_REVIEW_RECOMMENDATIONS: [&str; 3] = [
+    "accept",   // Work demonstrates the expected mastery
+    "revise",   // Close; specific improvements needed
+    "reject",   // Does not yet demonstrate the expected mastery
+];
+
+/// One criterion of a peer review rubric
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RubricCriterion {
+    pub criterion: String,
+    pub description: String,
+    pub max_score: u32,
+}
+
+/// A reviewer's score on one rubric criterion
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RubricScore {
+    pub criterion: String,
+    pub score: u32,
+    pub comment: Option<String>,
+}
+
+/// PeerReviewRequest - A learner asking peers to evaluate their work on a
+/// content node.
+///
+/// Backs the "evaluate" rung of the mastery ladder: reviewers must hold at
+/// least "analyze" mastery of the content, and every submitted review is
+/// recorded as mastery evidence for both reviewer and author. Linked from
+/// the content_id anchor via ContentToPeerReviewRequest.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct PeerReviewRequest {
+    pub id: String,
+    pub content_id: String,
+    pub author_id: String,
+    pub title: String,
+    /// What the author wants reviewed (a description, link or blob reference)
+    pub artifact: String,
+    pub rubric: Vec<RubricCriterion>,
+    pub reviewers_required: u32,
+    pub assigned_reviewer_ids: Vec<String>,
+    pub completed_reviewer_ids: Vec<String>,
+    pub status: String,                 // See PEER_REVIEW_STATUSES
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// PeerReview - One reviewer's rubric-structured evaluation of a request.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct PeerReview {
+    pub id: String,
+    pub request_id: String,
+    pub content_id: String,
+    pub reviewer_id: String,
+    pub author_id: String,
+    pub scores: Vec<RubricScore>,
+    /// Sum of scores over sum of rubric maximums (0.0-1.0)
+    pub overall_score: f64,
+    pub recommendation: String,         // See PEER_REVIEW_RECOMMENDATIONS
+    pub comments: String,
+    pub created_at: String,
+}
+
+// =============================================================================
+// Lamad: Content Contribution Entry
+// =============================================================================
+
+/// Kinds of learner contribution
+pub const CONTRIBUTION_KINDS: [&str; 2] = [
+    "derived", // New content building on the original
+    "edit",    // A revised version of the original
+];
+
+/// Contribution review states
+pub const CONTRIBUTION_STATUSES: [&str; 3] = [
+    "pending",  // In the steward review queue
+    "accepted", // Published as content; contributor credited
+    "rejected", // Declined by a steward
+];
+
+/// ContentContribution - Content a learner at "create" mastery derived from
+/// (or edited out of) an existing content node.
+///
+/// Waits in the steward review queue; on acceptance it is published as
+/// content linked DERIVED_FROM the original, and the contributor receives
+/// `contribution` points and recognition. Points are only ever awarded by
+/// acceptance, never claimed by the contributor.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct ContentContribution {
+    pub id: String,
+    pub original_content_id: String,
+    pub contributor_id: String,
+    pub kind: String,                   // See CONTRIBUTION_KINDS
+    pub title: String,
+    pub description: String,
+    pub content: String,
+    pub content_format: String,
+    pub tags: Vec<String>,
+    /// Why the contributor thinks this improves on or extends the original
+    pub rationale: String,
+    pub status: String,                 // See CONTRIBUTION_STATUSES
+    pub reviewed_by: Option<String>,
+    pub review_note: Option<String>,
+    /// Content node published on acceptance
+    pub published_content_id: Option<String>,
+    pub points_awarded: i32,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
 // =============================================================================
 // Lamad: Knowledge Map Entry
 // =============================================================================
@@ -3424,6 +3949,13 @@ pub const ACCESS_GRANT_TYPES: [&str; 4] = [
     "revocable",     // Can be revoked (e.g., scholarship terms)
 ];
 
+/// Access grant lifecycle statuses
+pub const ACCESS_GRANT_STATUSES: [&str; 3] = [
+    "active",        // Currently grants access
+    "lapsed",        // Subscription/trial window passed without renewal
+    "revoked",       // Withdrawn by steward or governance
+];
+
 /// StewardCredential - Proof of qualification to steward premium content
 ///
 /// Before a steward can gate content, they must demonstrate qualification:
@@ -3509,7 +4041,7 @@ pub struct PremiumGate {
     pub contributor_presence_id: Option<String>,
 
     // What's being gated
-    /// Gated resource type: "path", "chapter", "content", "content_bundle"
+    /// Gated resource type: "path", "chapter", "content", "content_bundle", "path_bundle"
     pub gated_resource_type: String,
     /// IDs of gated resources
     pub gated_resource_ids_json: String,  // String[] as JSON
@@ -3537,6 +4069,9 @@ pub struct PremiumGate {
     pub subscription_period_days: Option<u32>,
     /// For pay_what_you_can: minimum amount
     pub min_amount: Option<f64>,
+    /// For bundles: combined price of the bundled paths' own gates (shows the saving)
+    #[serde(default)]
+    pub bundle_list_price: Option<f64>,
 
     // Revenue share
     /// Percentage to steward (e.g., 85.0)
@@ -3586,7 +4121,7 @@ pub struct AccessGrant {
     // How access was granted
     /// Grant type (ACCESS_GRANT_TYPES)
     pub grant_type: String,
-    /// How it was obtained: "payment", "attestation_met", "scholarship", "creator_gift"
+    /// How it was obtained: "payment", "attestation_met", "scholarship", "creator_gift", "voucher"
     pub granted_via: String,
 
     // Payment details (if payment-based)
@@ -3612,6 +4147,20 @@ pub struct AccessGrant {
     pub revoked_at: Option<String>,
     pub revoke_reason: Option<String>,
 
+    // Subscription lifecycle
+    /// Lifecycle status (ACCESS_GRANT_STATUSES); empty on grants that predate it
+    #[serde(default)]
+    pub status: String,
+    /// valid_until as microseconds since epoch, used for expiry checks
+    #[serde(default)]
+    pub valid_until_micros: Option<i64>,
+    /// Number of times this grant has been renewed
+    #[serde(default)]
+    pub renewal_count: u32,
+    /// When the grant lapsed (expiry sweep)
+    #[serde(default)]
+    pub lapsed_at: Option<String>,
+
     // Metadata
     pub metadata_json: String,
     pub created_at: String,
@@ -3669,35 +4218,117 @@ pub struct StewardRevenue {
     pub created_at: String,
 }
 
-// =============================================================================
-// CustodianCommitment - Digital Presence Stewardship
-// =============================================================================
-
-/// CustodianCommitment - Promise to custody content for resilience
-///
-/// Enables organic account protection through social relationships:
-/// - Family automatically custodies family-reach content (intimate relationships)
-/// - Communities custody community-reach content
-/// - Specialists custody category-specific content (medical, video, etc.)
-/// - Emergency protocols (manual, trusted party, M-of-N) can reconstruct presence
-///
-/// Lifecycle: PROPOSED → ACCEPTED → ACTIVE → FULFILLED | BREACHED
+/// VoucherBatch - Gift/voucher codes a steward minted for a gate
 ///
-/// Example commitments:
-/// 1. Alice (spouse) → Bob's "intimate" reach content (relationship-based, 60-80%)
-/// 2. Carol (nurse) → Medical content (category override, 20-40%)
-/// 3. Dave (fiber ISP) → Video content (bandwidth override)
-/// 4. Community (100 of 100k followers) → Sheila's commons-reach content (democratic resilience)
+/// Each code in the batch is a Voucher entry. Only the SHA-256 of a code is
+/// stored; the codes themselves are shown to the steward once, at minting.
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq)]
-pub struct CustodianCommitment {
+pub struct VoucherBatch {
     pub id: String,
+    pub gate_id: String,
+    /// Agent of the steward who minted the batch
+    pub minted_by: String,
+    pub code_count: u32,
+    /// Redemptions allowed per code (1 = single-use)
+    pub max_redemptions: u32,
+    /// Grant type issued on redemption (ACCESS_GRANT_TYPES)
+    pub grant_type: String,
+    /// Codes can't be redeemed after this
+    pub valid_until: Option<String>,
+    pub valid_until_micros: Option<i64>,
+    pub note: Option<String>,
+    pub created_at: String,
+}
 
-    // =========================================================================
-    // Parties
-    // =========================================================================
-    pub custodian_agent_id: String,       // Who is custodying
-    pub beneficiary_agent_id: String,     // Whose content/presence is being custodied
+/// Voucher - A single redeemable code of a VoucherBatch
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct Voucher {
+    pub id: String,
+    pub batch_id: String,
+    pub gate_id: String,
+    /// Hex SHA-256 of the normalized code
+    pub code_hash: String,
+    pub max_redemptions: u32,
+    pub redemption_count: u32,
+    /// Learners who redeemed this code
+    pub redeemed_by_json: String,  // String[] as JSON
+    pub is_active: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Invitation statuses
+pub const INVITATION_STATUSES: [&str; 3] = [
+    "pending",   // Not used yet
+    "redeemed",  // Someone signed up with it
+    "revoked",   // Withdrawn by the inviter
+];
+
+/// Invitation - An invite code a member handed to someone joining
+///
+/// Only the SHA-256 of the code is stored; the code is shown to the inviter
+/// once, when the invitation is created. Whoever signs up with it is linked
+/// to the inviter, who earns `invitation` points.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct Invitation {
+    pub id: String,
+    /// Human id of the member who invited
+    pub inviter_id: String,
+    /// Agent of the member who invited, credited with the `invitation` points
+    pub inviter_agent_id: String,
+    /// Hex SHA-256 of the normalized code
+    pub code_hash: String,
+    /// Learning path the invitee is invited to
+    pub path_id: Option<String>,
+    /// Gate the invitee gets access through on signup
+    pub gate_id: Option<String>,
+    /// Grant type issued through the gate (ACCESS_GRANT_TYPES)
+    pub grant_type: Option<String>,
+    /// The code can't be used after this
+    pub valid_until: Option<String>,
+    pub valid_until_micros: Option<i64>,
+    /// Status (INVITATION_STATUSES)
+    pub status: String,
+    /// Human id of the member who signed up with the code
+    pub redeemed_by: Option<String>,
+    pub redeemed_at: Option<String>,
+    pub note: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+// =============================================================================
+// CustodianCommitment - Digital Presence Stewardship
+// =============================================================================
+
+/// CustodianCommitment - Promise to custody content for resilience
+///
+/// Enables organic account protection through social relationships:
+/// - Family automatically custodies family-reach content (intimate relationships)
+/// - Communities custody community-reach content
+/// - Specialists custody category-specific content (medical, video, etc.)
+/// - Emergency protocols (manual, trusted party, M-of-N) can reconstruct presence
+///
+/// Lifecycle: PROPOSED → ACCEPTED → ACTIVE → FULFILLED | BREACHED
+///
+/// Example commitments:
+/// 1. Alice (spouse) → Bob's "intimate" reach content (relationship-based, 60-80%)
+/// 2. Carol (nurse) → Medical content (category override, 20-40%)
+/// 3. Dave (fiber ISP) → Video content (bandwidth override)
+/// 4. Community (100 of 100k followers) → Sheila's commons-reach content (democratic resilience)
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct CustodianCommitment {
+    pub id: String,
+
+    // =========================================================================
+    // Parties
+    // =========================================================================
+    pub custodian_agent_id: String,       // Who is custodying
+    pub beneficiary_agent_id: String,     // Whose content/presence is being custodied
     pub commitment_type: String,          // From COMMITMENT_TYPES: relationship|category|community|steward
 
     // =========================================================================
@@ -3772,6 +4403,93 @@ pub struct CustodianCommitment {
     pub updated_at: String,
 }
 
+/// CustodianShard - One encrypted shard held under a CustodianCommitment
+///
+/// Committed by the custodian holding it. `shard_hash` is the hex SHA-256 of
+/// `encrypted_shard_data` and is checked on store, retrieval and verification.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct CustodianShard {
+    pub id: String,
+    pub commitment_id: String,
+    pub content_id: String,
+    pub custodian_agent_id: String,
+    pub shard_index: u32,
+    pub total_shards: u32,
+    pub encrypted_shard_data: String,     // Base64 encrypted shard
+    pub encryption_method: String,        // From SHARD_ENCRYPTION_METHODS
+    pub shard_hash: String,
+    pub watermark_signature: String,
+    pub stored_at: String,
+}
+
+/// ConsensusVote - One custodian's vote on M-of-N emergency recovery
+///
+/// `signature` is the voter's Ed25519 signature over [`ConsensusVotePayload`],
+/// checked during validation so votes can't be forged on another custodian's behalf.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct ConsensusVote {
+    pub id: String,
+    pub commitment_id: String,
+    pub custodian_agent_id: String,
+    pub vote_approve: bool,
+    pub reason: String,
+    pub signature: Vec<u8>,
+    pub voted_at: String,
+}
+
+/// The data a custodian signs when voting
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConsensusVotePayload {
+    pub commitment_id: String,
+    pub custodian_agent_id: String,
+    pub vote_approve: bool,
+}
+
+impl ConsensusVote {
+    pub fn payload(&self) -> ConsensusVotePayload {
+        ConsensusVotePayload {
+            commitment_id: self.commitment_id.clone(),
+            custodian_agent_id: self.custodian_agent_id.clone(),
+            vote_approve: self.vote_approve,
+        }
+    }
+
+    /// Verify the vote was signed by the custodian it names
+    pub fn verify(&self) -> ExternResult<bool> {
+        let agent = match AgentPubKey::try_from(self.custodian_agent_id.clone()) {
+            Ok(agent) => agent,
+            Err(_) => return Ok(false),
+        };
+        let bytes: [u8; 64] = match self.signature.as_slice().try_into() {
+            Ok(bytes) => bytes,
+            Err(_) => return Ok(false),
+        };
+        verify_signature(agent, Signature(bytes), self.payload())
+    }
+}
+
+/// CategoryAccessLog - Audit record of one category-override access attempt
+///
+/// Written whenever a specialist's access is checked under a category
+/// override, granted or not, so the beneficiary can review who looked at
+/// their content and on which credential.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct CategoryAccessLog {
+    pub id: String,
+    pub commitment_id: Option<String>,    // Override consulted (None if the specialist had none)
+    pub beneficiary_agent_id: String,
+    pub specialist_agent_id: String,
+    pub content_id: String,
+    pub category_type: Option<String>,
+    pub attestation_id: Option<String>,   // Imagodei attestation that satisfied the check
+    pub authorized: bool,
+    pub reason: String,
+    pub accessed_at: String,
+}
+
 // =============================================================================
 // Doorway Infrastructure (Self-Validating Network Nodes)
 // =============================================================================
@@ -3962,6 +4680,67 @@ pub const IMPORT_BATCH_STATUSES: [&str; 4] = [
     "failed",      // Processing halted due to critical error
 ];
 
+/// ImportProvenance - Which upstream system vouched for an import batch.
+///
+/// The submitting system signs its manifest (blob hash + item count) with an
+/// Ed25519 key the doorway trusts. The doorway verifies the signature before
+/// queuing the batch and the signature is kept here, next to the ImportBatch,
+/// so an audit can re-check which system introduced which content.
+///
+/// Stored as its own entry so ImportBatch entries written before signing
+/// existed still deserialize.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct ImportProvenance {
+    /// ImportBatch this vouches for
+    pub batch_id: String,
+
+    /// Upstream system that signed the manifest (doorway IMPORT_SIGNERS name)
+    pub source: String,
+
+    /// Base64 Ed25519 public key the signature was checked against
+    pub public_key: String,
+
+    /// Base64 Ed25519 signature over the signed manifest
+    pub signature: String,
+
+    /// Blob hash as signed (before any moderation re-blobbing)
+    pub blob_hash: String,
+
+    /// Item count as signed
+    pub total_items: u32,
+
+    /// Doorway that verified the signature
+    pub verified_by: Option<String>,
+
+    /// When the provenance was recorded
+    pub recorded_at: String,
+}
+
+// =============================================================================
+// Infrastructure: Schema Migration Progress
+// =============================================================================
+
+/// MigrationProgress - One agent's progress moving an entry type to a new schema version.
+///
+/// Recorded by whichever agent runs a migration or healing pass over its
+/// share of the DHT. The latest record per reporter is summed into the
+/// network-wide status of the entry type (get_migration_status).
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct MigrationProgress {
+    pub entry_type: String,          // Healing entry type ("content", "learning_path", ...)
+    pub from_version: u32,
+    pub to_version: u32,
+    pub scanned: u32,                // Entries examined so far
+    pub at_target: u32,              // Already at to_version, nothing to do
+    pub migrated: u32,               // Transcribed to to_version
+    pub degraded: u32,               // Still readable, failing to_version validation
+    pub failed: u32,                 // Could not be transcribed
+    pub reporter: String,            // Agent that ran the pass
+    pub recorded_at: String,
+}
+
 // =============================================================================
 // Renewal Protocol Constants (Content Succession)
 // =============================================================================
@@ -4001,6 +4780,37 @@ pub struct ContentSuccession {
     pub created_at: String,
 }
 
+// =============================================================================
+// Reach Transition Entry Types
+// =============================================================================
+
+/// Who may move content between reach levels
+pub const REACH_CHANGE_AUTHORITIES: [&str; 2] = [
+    "author",    // Content author
+    "steward",   // Holder of an active steward credential covering the content
+];
+
+/// ReachChange - Auditable record of a content reach transition.
+///
+/// Reach is set at creation. Moving content to another reach level
+/// (e.g. draft "private" → "commons") writes an updated Content entry
+/// plus a ReachChange recording who moved it, on what authority and why,
+/// so governance can review transitions after the fact.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct ReachChange {
+    pub id: String,
+    pub content_id: String,
+    pub previous_reach: String,
+    pub new_reach: String,
+    pub justification: String,
+    pub changed_by: String,                  // Agent key (base64)
+    pub authority: String,                   // See REACH_CHANGE_AUTHORITIES
+    pub steward_credential_id: Option<String>,
+    pub previous_action_hash: String,        // Content record before the change
+    pub created_at: String,
+}
+
 // =============================================================================
 // Anchor Entries (for link indexing)
 // =============================================================================
@@ -4032,14 +4842,22 @@ pub enum EntryTypes {
     // Lamad: Content & Learning
     Content(Content),
     BlobEntry(BlobEntry),              // Large media metadata (video, audio, podcasts)
+    BlobVariant(BlobVariant),          // Alternate rendition of a blob (adaptive delivery)
+    BlobCaption(BlobCaption),          // Caption/subtitle track for a blob
+    ContentTranslation(ContentTranslation), // Localized title/description/body for a Content node
     ShardManifest(ShardManifest),      // Unified shard model - how blobs are split
     ShardLocation(ShardLocation),       // Where shards are stored in the network
     LearningPath(LearningPath),
     PathChapter(PathChapter),
     PathStep(PathStep),
+    ExternalResource(ExternalResource), // Metadata and snapshot for an external step's URL
     ContentMastery(ContentMastery),
     PracticePool(PracticePool),
     MasteryChallenge(MasteryChallenge),
+    AssessmentItem(AssessmentItem),    // Question bank entry for mastery challenges
+    PeerReviewRequest(PeerReviewRequest), // Author's request for peer evaluation
+    PeerReview(PeerReview),            // Rubric-structured review of a request
+    ContentContribution(ContentContribution), // Learner-derived content awaiting steward review
     KnowledgeMap(KnowledgeMap),
     PathExtension(PathExtension),
     ContentAttestation(ContentAttestation),
@@ -4069,8 +4887,16 @@ pub enum EntryTypes {
     HumanProgress(HumanProgress), // Legacy
     Agent(Agent),           // Expanded identity model
     AgentProgress(AgentProgress), // Expanded progress model
+    ReflectionEntry(ReflectionEntry),           // Learner journal response to a step
+    ReflectionShareGrant(ReflectionShareGrant), // Learner grant letting a facilitator read reflections
+    LearningSession(LearningSession),           // Study or cohort session scheduled on a path
+    Annotation(Annotation),                     // Highlight or comment on a range of content
+    Bookmark(Bookmark),                         // Content saved to a human's personal library
     Attestation(Attestation),
     CustodianCommitment(CustodianCommitment), // Digital presence stewardship
+    CustodianShard(CustodianShard),           // Encrypted shard held under a commitment
+    ConsensusVote(ConsensusVote),             // Custodian vote on M-of-N recovery
+    CategoryAccessLog(CategoryAccessLog),     // Audit trail of category-override access
 
     // Shefa: Economy (REA/ValueFlows)
     EconomicEvent(EconomicEvent),
@@ -4095,6 +4921,7 @@ pub enum EntryTypes {
     CoveredRisk(CoveredRisk),
     InsuranceClaim(InsuranceClaim),
     AdjustmentReasoning(AdjustmentReasoning),
+    SolvencySnapshot(SolvencySnapshot),
 
     // Shefa: Requests & Offers (Peer-to-Peer Service Coordination)
     ServiceRequest(ServiceRequest),
@@ -4133,6 +4960,11 @@ pub enum EntryTypes {
     PremiumGate(PremiumGate),
     AccessGrant(AccessGrant),
     StewardRevenue(StewardRevenue),
+    VoucherBatch(VoucherBatch),
+    Voucher(Voucher),
+
+    // Lamad: Community growth
+    Invitation(Invitation),
 
     // Infrastructure: Doorway Federation (Self-Validating Network Nodes)
     DoorwayRegistration(DoorwayRegistration),
@@ -4141,10 +4973,17 @@ pub enum EntryTypes {
 
     // Infrastructure: Import Batch Processing
     ImportBatch(ImportBatch),
+    ImportProvenance(ImportProvenance),
+
+    // Infrastructure: Schema migration progress
+    MigrationProgress(MigrationProgress),
 
     // Renewal Protocol: Content succession
     ContentSuccession(ContentSuccession),
 
+    // Governance: Content reach transitions
+    ReachChange(ReachChange),
+
     // Infrastructure: Anchors
     StringAnchor(StringAnchor),
 }
@@ -4163,14 +5002,18 @@ pub enum LinkTypes {
     TagToContent,
     AuthorToContent,
     ImportBatchToContent,
+    ContentCounter,                    // Anchor(content_stats) -> Anchor(content_type), delta in tag
+    ContentToTranslations,             // Anchor(content_id) -> ContentTranslation entries (one per locale)
+    TranslationReviewQueue,            // Anchor(translation_review) -> draft ContentTranslation entries
+    LicenseToContent,                  // Anchor(license) -> Content (one link per identifier in the expression)
 
     // =========================================================================
     // Lamad: Blob (Media) links - Phase 1
     // =========================================================================
     ContentToBlobs,                     // Content -> BlobEntry (one-to-many)
     IdToBlob,                          // Anchor(blob_hash) -> BlobEntry (for lookup)
-    BlobToVariants,                    // BlobEntry -> BlobVariant entries (quality options)
-    BlobToCaptions,                    // BlobEntry -> BlobCaption entries (subtitles)
+    BlobToVariants,                    // Anchor(blob_hash) -> BlobVariant entries (quality options)
+    BlobToCaptions,                    // Anchor(blob_hash) -> BlobCaption entries (subtitles)
     BlobToReplicas,                    // BlobEntry -> CustodianCommitment (replication)
     AuthorToBlobs,                     // Anchor(author_id) -> BlobEntry (author's blobs)
 
@@ -4190,6 +5033,8 @@ pub enum LinkTypes {
     IdToStep,                   // Anchor(step_id) -> PathStep
     PathToStep,
     StepToContent,
+    UrlToExternalResource,      // Anchor(external_url) -> ExternalResource
+    ExternalResourceByStatus,   // Anchor(external_resource_status) -> ExternalResource
     // PathByCreator, PathByDifficulty, PathByType, PathByTag removed - use queries
 
     // =========================================================================
@@ -4206,6 +5051,19 @@ pub enum LinkTypes {
     AgentToPathProgress,        // Anchor(agent_id) -> AgentProgress (for path queries)
     PathToProgress,             // Anchor(path_id) -> AgentProgress (for completion stats)
     ProgressByStatus,           // Anchor(status: in_progress|completed|abandoned) -> AgentProgress
+    LearnerToReflection,        // Anchor(learner_reflections / learner_path_reflections) -> ReflectionEntry
+    LearnerToReflectionGrant,   // Anchor(learner_id) -> ReflectionShareGrant
+    FacilitatorToReflectionGrant, // Anchor(facilitator_id) -> ReflectionShareGrant
+    IdToLearningSession,        // Anchor(session_id) -> LearningSession
+    OrganizerToLearningSession, // Anchor(organizer_id) -> LearningSession
+    PathToCohortSession,        // Anchor(path_id) -> LearningSession (cohort sessions)
+    LearningSessionsByDay,      // Anchor(UTC day number of the start) -> LearningSession
+    IdToAnnotation,             // Anchor(annotation_id) -> Annotation
+    AuthorToAnnotation,         // Anchor(author_id:content_id) -> Annotation
+    AuthorToAllAnnotations,     // Anchor(author_id) -> Annotation (for exports)
+    ContentToPublicAnnotation,  // Anchor(content_id) -> Annotation (public)
+    PathContentToAnnotation,    // Anchor(path_id:content_id) -> Annotation (cohort layer)
+    OwnerToBookmark,            // Anchor(owner_id) -> Bookmark
 
     // =========================================================================
     // Lamad: Content Mastery links
@@ -4224,6 +5082,18 @@ pub enum LinkTypes {
     AgentToChallenge,           // Anchor(agent_id) -> MasteryChallenge
     PoolToChallenge,            // PracticePool -> MasteryChallenge
     ChallengeByState,           // Anchor(state) -> MasteryChallenge
+    ContentToAssessmentItems,   // Anchor(content_id) -> AssessmentItem
+    IdToAssessmentItem,         // Anchor(assessment_item_id) -> AssessmentItem
+    AssessmentReviewQueue,      // Anchor(assessment_review) -> unpublished AssessmentItem
+    IdToPeerReviewRequest,      // Anchor(peer_review_request_id) -> PeerReviewRequest
+    ContentToPeerReviewRequest, // Anchor(content_id) -> PeerReviewRequest
+    PeerReviewRequestByStatus,  // Anchor(peer_review_status) -> PeerReviewRequest
+    ReviewerToPeerReviewRequest, // Anchor(reviewer_id) -> PeerReviewRequest (assignments)
+    RequestToPeerReview,        // PeerReviewRequest id anchor -> PeerReview
+    IdToContribution,           // Anchor(contribution_id) -> ContentContribution
+    ContentToContribution,      // Anchor(original content_id) -> ContentContribution
+    ContributorToContribution,  // Anchor(contributor_id) -> ContentContribution
+    ContributionReviewQueue,    // Anchor(contribution_review) -> pending ContentContribution
 
     // =========================================================================
     // Shefa: Point System links (hREA demonstration)
@@ -4295,6 +5165,12 @@ pub enum LinkTypes {
     CustodianCommitmentByState,     // Anchor(state) -> CustodianCommitment
     RelationshipToCommitment,       // HumanRelationship -> CustodianCommitment (automatic)
     ContentToCommitmentCustodian,   // Content -> CustodianCommitment (which commitments cover)
+    CommitmentToShard,              // Anchor(commitment_id) -> CustodianShard
+    ContentToShard,                 // Anchor(content_id) -> CustodianShard
+    BeneficiaryCheckin,             // Anchor(beneficiary_id) -> AgentPubKey, link timestamp = check-in
+    CustodianHeartbeat,             // Anchor(commitment_id) -> AgentPubKey, link timestamp = heartbeat
+    CommitmentToConsensusVote,      // Anchor(commitment_id) -> ConsensusVote
+    BeneficiaryToCategoryAccessLog, // Anchor(beneficiary_id) -> CategoryAccessLog
 
     // =========================================================================
     // Shefa: Economic Event links
@@ -4392,6 +5268,8 @@ pub enum LinkTypes {
     IdToAdjustmentReasoning,    // Anchor(adjustment_id) -> AdjustmentReasoning
     ClaimToAdjustment,          // InsuranceClaim -> AdjustmentReasoning
     AdjustmentToEvent,          // AdjustmentReasoning -> EconomicEvent
+    RiskPoolToProfile,          // Anchor(risk_pool) -> MemberRiskProfile
+    SolvencySnapshots,          // Anchor(solvency_snapshots) -> SolvencySnapshot
 
     // =========================================================================
     // Shefa: Requests & Offers links
@@ -4523,6 +5401,7 @@ pub enum LinkTypes {
     LearnerToGrant,             // Anchor(learner_id) -> AccessGrant
     GateToGrant,                // Anchor(gate_id) -> AccessGrant
     GrantByType,                // Anchor(access_type) -> AccessGrant
+    PaymentToGrant,             // Anchor(network:payment_event_id) -> AccessGrant (settled payments)
 
     // Steward Revenue
     IdToStewardRevenue,         // Anchor(revenue_id) -> StewardRevenue
@@ -4531,6 +5410,17 @@ pub enum LinkTypes {
     ContributorToRevenue,       // Anchor(contributor_presence_id) -> StewardRevenue
     RevenueByStatus,            // Anchor(status) -> StewardRevenue
 
+    // Vouchers
+    IdToVoucherBatch,           // Anchor(batch_id) -> VoucherBatch
+    GateToVoucherBatch,         // Anchor(gate_id | "all") -> VoucherBatch
+    BatchToVoucher,             // Anchor(batch_id) -> Voucher
+    CodeToVoucher,              // Anchor(code_hash) -> Voucher
+
+    // Invitations
+    IdToInvitation,             // Anchor(invitation_id) -> Invitation
+    InviterToInvitation,        // Anchor(inviter_id) -> Invitation
+    CodeToInvitation,           // Anchor(code_hash) -> Invitation
+
     // =========================================================================
     // Lamad: KnowledgeMap links
     // =========================================================================
@@ -4581,8 +5471,14 @@ pub enum LinkTypes {
     IdToImportBatch,            // Anchor(batch_id) -> ImportBatch
     AuthorToImportBatches,      // Anchor(author_id) -> ImportBatch
     ImportBatchByStatus,        // Anchor(status) -> ImportBatch
+    ImportBatchToProvenance,    // Anchor(batch_id) -> ImportProvenance
     // ImportBatchToContent - already defined in Lamad section (line ~4058)
 
+    // =========================================================================
+    // Infrastructure: Schema Migration Progress links
+    // =========================================================================
+    EntryTypeToMigrationProgress,    // Anchor(entry_type) -> MigrationProgress
+
     // =========================================================================
     // Renewal Protocol: Content Succession links
     // =========================================================================
@@ -4590,6 +5486,12 @@ pub enum LinkTypes {
     ContentToSuccession,             // Anchor(content_id) -> ContentSuccession
     SuccessorAuthorToSuccession,     // Anchor(successor_author_key) -> ContentSuccession
 
+    // =========================================================================
+    // Governance: Content reach transitions
+    // =========================================================================
+    ContentToReachChange,            // Anchor(content_id) -> ReachChange
+    ReachChangeIndex,                // Anchor(reach_changes) -> ReachChange (governance review)
+
     // =========================================================================
     // REMOVED: Doorway links now in infrastructure DNA
     // - IdToDoorway, OperatorToDoorway, DoorwayToHeartbeat, DoorwayToSummary
@@ -4644,15 +5546,525 @@ fn validate_create_entry(app_entry: &EntryTypes) -> ExternResult<ValidateCallbac
         EntryTypes::LearningPath(path) => adapt_validation(path.validate()),
         EntryTypes::PathStep(step) => adapt_validation(step.validate()),
         EntryTypes::ContentMastery(mastery) => adapt_validation(mastery.validate()),
+        EntryTypes::ExternalResource(resource) => validate_external_resource(resource),
+        EntryTypes::ReflectionEntry(reflection) => validate_reflection_entry(reflection),
+        EntryTypes::ReflectionShareGrant(grant) => validate_reflection_share_grant(grant),
+        EntryTypes::LearningSession(session) => validate_learning_session(session),
+        EntryTypes::Annotation(annotation) => validate_annotation(annotation),
+        EntryTypes::Bookmark(bookmark) => validate_bookmark(bookmark),
+
+        // Media: renditions and caption tracks
+        EntryTypes::BlobVariant(variant) => validate_blob_variant(variant),
+        EntryTypes::BlobCaption(caption) => validate_blob_caption(caption),
+        EntryTypes::ContentTranslation(translation) => validate_content_translation(translation),
+
+        // Question bank
+        EntryTypes::AssessmentItem(item) => validate_assessment_item(item),
+        EntryTypes::PeerReviewRequest(request) => validate_peer_review_request(request),
+        EntryTypes::PeerReview(review) => validate_peer_review(review),
+        EntryTypes::ContentContribution(contribution) => validate_content_contribution(contribution),
+
+        // Import batch processing
+        EntryTypes::ImportProvenance(provenance) => validate_import_provenance(provenance),
+
+        // Schema migration progress
+        EntryTypes::MigrationProgress(progress) => validate_migration_progress(progress),
 
         // Renewal protocol: Content succession
         EntryTypes::ContentSuccession(succession) => validate_content_succession(succession),
 
+        // Governance: Content reach transitions
+        EntryTypes::ReachChange(change) => validate_reach_change(change),
+        EntryTypes::CustodianCommitment(commitment) => validate_custodian_commitment(commitment),
+        EntryTypes::CustodianShard(shard) => validate_custodian_shard(shard),
+        EntryTypes::ConsensusVote(vote) => validate_consensus_vote(vote),
+
+        // Shefa: Insurance mutual
+        EntryTypes::SolvencySnapshot(snapshot) => validate_solvency_snapshot(snapshot),
+
         // Other entry types: accept for now (can add validation incrementally)
         _ => Ok(ValidateCallbackResult::Valid),
     }
 }
 
+/// Validate BlobVariant entry
+fn validate_blob_variant(variant: &BlobVariant) -> ExternResult<ValidateCallbackResult> {
+    if variant.parent_hash.is_empty() || variant.hash.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "BlobVariant parent_hash and hash cannot be empty".to_string(),
+        ));
+    }
+
+    if variant.parent_hash == variant.hash {
+        return Ok(ValidateCallbackResult::Invalid(
+            "BlobVariant cannot be a variant of itself".to_string(),
+        ));
+    }
+
+    if let Some(ref resolution) = variant.resolution {
+        if !VIDEO_VARIANTS.contains(&resolution.as_str()) {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "Invalid resolution '{}'. Must be one of: {:?}",
+                resolution, VIDEO_VARIANTS
+            )));
+        }
+    }
+
+    if !variant.bitrate_mbps.is_finite() || variant.bitrate_mbps <= 0.0 {
+        return Ok(ValidateCallbackResult::Invalid(
+            "BlobVariant bitrate_mbps must be positive".to_string(),
+        ));
+    }
+
+    if !CODEC_TYPES.contains(&variant.codec.as_str()) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Invalid codec '{}'. Must be one of: {:?}",
+            variant.codec, CODEC_TYPES
+        )));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validate BlobCaption entry
+fn validate_blob_caption(caption: &BlobCaption) -> ExternResult<ValidateCallbackResult> {
+    if caption.blob_hash.is_empty() || caption.content_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "BlobCaption blob_hash and content_id cannot be empty".to_string(),
+        ));
+    }
+
+    if caption.language.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "BlobCaption language cannot be empty".to_string(),
+        ));
+    }
+
+    if !CAPTION_FORMATS.contains(&caption.format.as_str()) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Invalid caption format '{}'. Must be one of: {:?}",
+            caption.format, CAPTION_FORMATS
+        )));
+    }
+
+    if caption.caption_hash.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "BlobCaption caption_hash cannot be empty".to_string(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validate ContentTranslation entry
+fn validate_content_translation(translation: &ContentTranslation) -> ExternResult<ValidateCallbackResult> {
+    if translation.content_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "ContentTranslation content_id cannot be empty".to_string(),
+        ));
+    }
+
+    if !is_valid_locale(&translation.locale) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Invalid locale '{}'. Must be a BCP 47 language tag (e.g. es, pt-BR)",
+            translation.locale
+        )));
+    }
+
+    if translation.title.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "ContentTranslation title cannot be empty".to_string(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validate ExternalResource entry
+fn validate_external_resource(resource: &ExternalResource) -> ExternResult<ValidateCallbackResult> {
+    if !resource.url.starts_with("https://") && !resource.url.starts_with("http://") {
+        return Ok(ValidateCallbackResult::Invalid(
+            "ExternalResource url must be an http(s) URL".to_string(),
+        ));
+    }
+
+    if !EXTERNAL_RESOURCE_STATUSES.contains(&resource.status.as_str()) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Invalid external resource status '{}'. Must be one of: {:?}",
+            resource.status, EXTERNAL_RESOURCE_STATUSES
+        )));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validate ReflectionEntry entry
+fn validate_reflection_entry(reflection: &ReflectionEntry) -> ExternResult<ValidateCallbackResult> {
+    if reflection.id.is_empty() || reflection.learner_id.is_empty() || reflection.path_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "ReflectionEntry id, learner_id and path_id cannot be empty".to_string(),
+        ));
+    }
+
+    if reflection.response.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "ReflectionEntry response cannot be empty".to_string(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validate ReflectionShareGrant entry
+fn validate_reflection_share_grant(grant: &ReflectionShareGrant) -> ExternResult<ValidateCallbackResult> {
+    if grant.learner_id.is_empty() || grant.facilitator_id.is_empty() || grant.path_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "ReflectionShareGrant learner_id, facilitator_id and path_id cannot be empty".to_string(),
+        ));
+    }
+
+    if grant.learner_id == grant.facilitator_id {
+        return Ok(ValidateCallbackResult::Invalid(
+            "ReflectionShareGrant facilitator must be someone other than the learner".to_string(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validate LearningSession entry
+fn validate_learning_session(session: &LearningSession) -> ExternResult<ValidateCallbackResult> {
+    if session.organizer_id.is_empty() || session.path_id.is_empty() || session.title.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "LearningSession organizer_id, path_id and title cannot be empty".to_string(),
+        ));
+    }
+
+    if !LEARNING_SESSION_KINDS.contains(&session.kind.as_str()) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Invalid LearningSession kind '{}'. Must be one of: {:?}",
+            session.kind, LEARNING_SESSION_KINDS
+        )));
+    }
+
+    if !LEARNING_SESSION_STATUSES.contains(&session.status.as_str()) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Invalid LearningSession status '{}'. Must be one of: {:?}",
+            session.status, LEARNING_SESSION_STATUSES
+        )));
+    }
+
+    if session.duration_minutes == 0 || session.duration_minutes > MAX_LEARNING_SESSION_MINUTES {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "LearningSession duration must be between 1 and {} minutes",
+            MAX_LEARNING_SESSION_MINUTES
+        )));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validate Bookmark entry
+fn validate_bookmark(bookmark: &Bookmark) -> ExternResult<ValidateCallbackResult> {
+    if bookmark.id.is_empty() || bookmark.owner_id.is_empty() || bookmark.content_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Bookmark id, owner_id and content_id cannot be empty".to_string(),
+        ));
+    }
+
+    if bookmark.folder.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Bookmark folder cannot be empty".to_string(),
+        ));
+    }
+
+    if bookmark.folder.len() > MAX_BOOKMARK_FOLDER_BYTES {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Bookmark folder is limited to {} bytes",
+            MAX_BOOKMARK_FOLDER_BYTES
+        )));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validate Annotation entry
+fn validate_annotation(annotation: &Annotation) -> ExternResult<ValidateCallbackResult> {
+    if annotation.id.is_empty() || annotation.author_id.is_empty() || annotation.content_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Annotation id, author_id and content_id cannot be empty".to_string(),
+        ));
+    }
+
+    if !ANNOTATION_MOTIVATIONS.contains(&annotation.motivation.as_str()) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Invalid Annotation motivation '{}'. Must be one of: {:?}",
+            annotation.motivation, ANNOTATION_MOTIVATIONS
+        )));
+    }
+
+    if (annotation.motivation == "replying") != annotation.reply_to.is_some() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Annotation reply_to must be set exactly for replies".to_string(),
+        ));
+    }
+
+    if !ANNOTATION_VISIBILITIES.contains(&annotation.visibility.as_str()) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Invalid Annotation visibility '{}'. Must be one of: {:?}",
+            annotation.visibility, ANNOTATION_VISIBILITIES
+        )));
+    }
+
+    if annotation.visibility == "cohort" && annotation.path_id.is_none() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Cohort annotations need a path_id".to_string(),
+        ));
+    }
+
+    if annotation.body.len() > MAX_ANNOTATION_BODY_BYTES {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Annotation body is limited to {} bytes",
+            MAX_ANNOTATION_BODY_BYTES
+        )));
+    }
+
+    if annotation.body.trim().is_empty() && annotation.motivation != "highlighting" {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only highlights may have an empty body".to_string(),
+        ));
+    }
+
+    if annotation.selector_json.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Annotation selector_json cannot be empty".to_string(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validate AssessmentItem entry
+fn validate_assessment_item(item: &AssessmentItem) -> ExternResult<ValidateCallbackResult> {
+    if item.id.is_empty() || item.content_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "AssessmentItem id and content_id cannot be empty".to_string(),
+        ));
+    }
+
+    if !ASSESSMENT_QUESTION_TYPES.contains(&item.question_type.as_str()) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Invalid question type '{}'. Must be one of: {:?}",
+            item.question_type, ASSESSMENT_QUESTION_TYPES
+        )));
+    }
+
+    if item.question_text.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "AssessmentItem question_text cannot be empty".to_string(),
+        ));
+    }
+
+    match item.question_type.as_str() {
+        "multiple_choice" if item.options.len() < 2 || !item.options.contains(&item.correct_answer) => {
+            Ok(ValidateCallbackResult::Invalid(
+                "Multiple choice items need at least two options, one of them correct_answer".to_string(),
+            ))
+        }
+        "true_false" if item.correct_answer != "true" && item.correct_answer != "false" => {
+            Ok(ValidateCallbackResult::Invalid(
+                "True/false items need correct_answer \"true\" or \"false\"".to_string(),
+            ))
+        }
+        _ if item.correct_answer.trim().is_empty() => Ok(ValidateCallbackResult::Invalid(
+            "AssessmentItem correct_answer cannot be empty".to_string(),
+        )),
+        _ => Ok(ValidateCallbackResult::Valid),
+    }
+}
+
+/// Validate PeerReviewRequest entry
+fn validate_peer_review_request(request: &PeerReviewRequest) -> ExternResult<ValidateCallbackResult> {
+    if request.id.is_empty() || request.content_id.is_empty() || request.author_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "PeerReviewRequest id, content_id and author_id cannot be empty".to_string(),
+        ));
+    }
+
+    if !PEER_REVIEW_STATUSES.contains(&request.status.as_str()) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Invalid peer review status '{}'. Must be one of: {:?}",
+            request.status, PEER_REVIEW_STATUSES
+        )));
+    }
+
+    if request.rubric.is_empty() || request.rubric.iter().any(|c| c.criterion.is_empty() || c.max_score == 0) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "PeerReviewRequest needs a rubric of named criteria with max_score above 0".to_string(),
+        ));
+    }
+
+    if request.reviewers_required == 0
+        || request.assigned_reviewer_ids.len() > request.reviewers_required as usize
+    {
+        return Ok(ValidateCallbackResult::Invalid(
+            "PeerReviewRequest needs at least one reviewer and no more assignments than required".to_string(),
+        ));
+    }
+
+    if request.assigned_reviewer_ids.contains(&request.author_id) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Authors cannot review their own work".to_string(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validate PeerReview entry
+fn validate_peer_review(review: &PeerReview) -> ExternResult<ValidateCallbackResult> {
+    if review.reviewer_id == review.author_id {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Authors cannot review their own work".to_string(),
+        ));
+    }
+
+    if !PEER_REVIEW_RECOMMENDATIONS.contains(&review.recommendation.as_str()) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Invalid peer review recommendation '{}'. Must be one of: {:?}",
+            review.recommendation, PEER_REVIEW_RECOMMENDATIONS
+        )));
+    }
+
+    if review.scores.is_empty() || !(0.0..=1.0).contains(&review.overall_score) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "PeerReview needs rubric scores and an overall_score between 0.0 and 1.0".to_string(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validate ContentContribution entry
+fn validate_content_contribution(contribution: &ContentContribution) -> ExternResult<ValidateCallbackResult> {
+    if contribution.id.is_empty()
+        || contribution.original_content_id.is_empty()
+        || contribution.contributor_id.is_empty()
+    {
+        return Ok(ValidateCallbackResult::Invalid(
+            "ContentContribution id, original_content_id and contributor_id cannot be empty".to_string(),
+        ));
+    }
+
+    if !CONTRIBUTION_KINDS.contains(&contribution.kind.as_str()) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Invalid contribution kind '{}'. Must be one of: {:?}",
+            contribution.kind, CONTRIBUTION_KINDS
+        )));
+    }
+
+    if !CONTRIBUTION_STATUSES.contains(&contribution.status.as_str()) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Invalid contribution status '{}'. Must be one of: {:?}",
+            contribution.status, CONTRIBUTION_STATUSES
+        )));
+    }
+
+    if contribution.title.trim().is_empty() || contribution.content.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "ContentContribution title and content cannot be empty".to_string(),
+        ));
+    }
+
+    // Points only come with acceptance
+    if contribution.points_awarded != 0 && contribution.status != "accepted" {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only accepted contributions carry points".to_string(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validate ImportProvenance entry
+fn validate_import_provenance(provenance: &ImportProvenance) -> ExternResult<ValidateCallbackResult> {
+    if provenance.batch_id.is_empty() || provenance.source.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "ImportProvenance batch_id and source cannot be empty".to_string(),
+        ));
+    }
+
+    if provenance.public_key.is_empty() || provenance.signature.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "ImportProvenance must carry the public key and signature".to_string(),
+        ));
+    }
+
+    if provenance.blob_hash.is_empty() || provenance.total_items == 0 {
+        return Ok(ValidateCallbackResult::Invalid(
+            "ImportProvenance must name the signed blob hash and item count".to_string(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validate MigrationProgress entry
+fn validate_migration_progress(progress: &MigrationProgress) -> ExternResult<ValidateCallbackResult> {
+    if progress.entry_type.is_empty() || progress.reporter.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "MigrationProgress entry_type and reporter cannot be empty".to_string(),
+        ));
+    }
+
+    if progress.to_version <= progress.from_version {
+        return Ok(ValidateCallbackResult::Invalid(
+            "MigrationProgress to_version must be newer than from_version".to_string(),
+        ));
+    }
+
+    let accounted = progress.at_target as u64
+        + progress.migrated as u64
+        + progress.degraded as u64
+        + progress.failed as u64;
+    if accounted > progress.scanned as u64 {
+        return Ok(ValidateCallbackResult::Invalid(
+            "MigrationProgress counts cannot exceed entries scanned".to_string(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validate SolvencySnapshot entry
+fn validate_solvency_snapshot(snapshot: &SolvencySnapshot) -> ExternResult<ValidateCallbackResult> {
+    if snapshot.id.is_empty() || snapshot.unit.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "SolvencySnapshot id and unit cannot be empty".to_string(),
+        ));
+    }
+
+    if !SOLVENCY_STATUSES.contains(&snapshot.status.as_str()) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Invalid solvency status '{}'. Must be one of: {:?}",
+            snapshot.status, SOLVENCY_STATUSES
+        )));
+    }
+
+    let amounts = [
+        snapshot.contributions_value,
+        snapshot.paid_claims_value,
+        snapshot.outstanding_claims_value,
+    ];
+    if amounts.iter().any(|v| !v.is_finite() || *v < 0.0) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "SolvencySnapshot amounts must be finite and non-negative".to_string(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
 /// Validate ContentSuccession entry
 fn validate_content_succession(succession: &ContentSuccession) -> ExternResult<ValidateCallbackResult> {
     if succession.id.is_empty() {
@@ -4707,6 +6119,129 @@ fn validate_content_succession(succession: &ContentSuccession) -> ExternResult<V
     Ok(ValidateCallbackResult::Valid)
 }
 
+/// Validate CustodianCommitment entry
+fn validate_custodian_commitment(commitment: &CustodianCommitment) -> ExternResult<ValidateCallbackResult> {
+    if commitment.custodian_agent_id.is_empty() || commitment.beneficiary_agent_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "CustodianCommitment requires custodian and beneficiary".to_string(),
+        ));
+    }
+
+    if !COMMITMENT_TYPES.contains(&commitment.commitment_type.as_str()) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Invalid commitment_type '{}'. Must be one of: {:?}",
+            commitment.commitment_type, COMMITMENT_TYPES
+        )));
+    }
+
+    if !COMMITMENT_BASIS.contains(&commitment.basis.as_str()) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Invalid basis '{}'. Must be one of: {:?}",
+            commitment.basis, COMMITMENT_BASIS
+        )));
+    }
+
+    if !SHARD_STRATEGIES.contains(&commitment.shard_strategy.as_str()) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Invalid shard_strategy '{}'. Must be one of: {:?}",
+            commitment.shard_strategy, SHARD_STRATEGIES
+        )));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validate CustodianShard entry
+fn validate_custodian_shard(shard: &CustodianShard) -> ExternResult<ValidateCallbackResult> {
+    if shard.commitment_id.is_empty() || shard.content_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "CustodianShard requires commitment_id and content_id".to_string(),
+        ));
+    }
+
+    if shard.total_shards == 0 || shard.shard_index >= shard.total_shards {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Shard index {} out of range for {} shards",
+            shard.shard_index, shard.total_shards
+        )));
+    }
+
+    if shard.encrypted_shard_data.is_empty() || shard.shard_hash.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "CustodianShard requires shard data and hash".to_string(),
+        ));
+    }
+
+    if !SHARD_ENCRYPTION_METHODS.contains(&shard.encryption_method.as_str()) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Invalid encryption_method '{}'. Must be one of: {:?}",
+            shard.encryption_method, SHARD_ENCRYPTION_METHODS
+        )));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validate ConsensusVote entry
+fn validate_consensus_vote(vote: &ConsensusVote) -> ExternResult<ValidateCallbackResult> {
+    if vote.commitment_id.is_empty() || vote.custodian_agent_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "ConsensusVote requires commitment_id and custodian_agent_id".to_string(),
+        ));
+    }
+
+    if !vote.verify()? {
+        return Ok(ValidateCallbackResult::Invalid(
+            "ConsensusVote signature does not match custodian_agent_id".to_string(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validate ReachChange entry
+fn validate_reach_change(change: &ReachChange) -> ExternResult<ValidateCallbackResult> {
+    if change.content_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "ReachChange content_id cannot be empty".to_string(),
+        ));
+    }
+
+    if !REACH_LEVELS.contains(&change.new_reach.as_str()) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Invalid new_reach '{}'. Must be one of: {:?}",
+            change.new_reach, REACH_LEVELS
+        )));
+    }
+
+    if change.previous_reach == change.new_reach {
+        return Ok(ValidateCallbackResult::Invalid(
+            "ReachChange must change the reach level".to_string(),
+        ));
+    }
+
+    if change.justification.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "ReachChange justification cannot be empty".to_string(),
+        ));
+    }
+
+    if !REACH_CHANGE_AUTHORITIES.contains(&change.authority.as_str()) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Invalid authority '{}'. Must be one of: {:?}",
+            change.authority, REACH_CHANGE_AUTHORITIES
+        )));
+    }
+
+    if change.authority == "steward" && change.steward_credential_id.is_none() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Steward reach changes must reference a steward credential".to_string(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
 /// Validate entry update operations
 ///
 /// Updates are validated the same as creates - the new entry state must be valid.
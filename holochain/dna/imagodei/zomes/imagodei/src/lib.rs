@@ -100,6 +100,31 @@ pub struct AttestationOutput {
     pub attestation: Attestation,
 }
 
+/// Input for revoking an attestation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevokeAttestationInput {
+    pub attestation_id: String,
+    pub reason: String,
+}
+
+/// Who holds an attestation, as shown to third-party verifiers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttestationHolder {
+    pub human_id: String,
+    /// Only present when the holder's profile is public
+    pub display_name: Option<String>,
+}
+
+/// Everything a verifier needs to check an attestation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttestationVerification {
+    pub action_hash: ActionHash,
+    pub attestation: Attestation,
+    /// None when the holder has no Human profile
+    pub holder: Option<AttestationHolder>,
+    pub revocation: Option<AttestationRevocation>,
+}
+
 // =============================================================================
 // Signals for Projection
 // =============================================================================
@@ -493,6 +518,14 @@ pub fn issue_attestation(input: IssueAttestationInput) -> ExternResult<Attestati
         (),
     )?;
 
+    // Create id lookup link (verification)
+    create_link(
+        attestation_anchor_hash(&attestation_id)?,
+        action_hash.clone(),
+        LinkTypes::IdToAttestation,
+        (),
+    )?;
+
     // Create category lookup link
     let category_anchor = StringAnchor::new("attestation_category", &input.category);
     let category_anchor_hash = hash_entry(&EntryTypes::StringAnchor(category_anchor))?;
@@ -555,6 +588,135 @@ pub fn get_my_attestations(_: ()) -> ExternResult<Vec<AttestationOutput>> {
     get_agent_attestations(my_human.human.id)
 }
 
+fn attestation_anchor_hash(attestation_id: &str) -> ExternResult<EntryHash> {
+    hash_entry(&EntryTypes::StringAnchor(StringAnchor::new(
+        "attestation_id",
+        attestation_id,
+    )))
+}
+
+/// Get an attestation by ID
+#[hdk_extern]
+pub fn get_attestation_by_id(id: String) -> ExternResult<Option<AttestationOutput>> {
+    let query = LinkQuery::try_new(attestation_anchor_hash(&id)?, LinkTypes::IdToAttestation)?;
+    let links = get_links(query, GetStrategy::default())?;
+
+    for link in links {
+        if let Some(action_hash) = link.target.into_action_hash() {
+            if let Some(record) = get(action_hash.clone(), GetOptions::default())? {
+                if let Some(attestation) = record.entry().to_app_option::<Attestation>().ok().flatten()
+                {
+                    return Ok(Some(AttestationOutput {
+                        action_hash,
+                        attestation,
+                    }));
+                }
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Get the revocation of an attestation, if it has been revoked
+fn get_attestation_revocation(attestation_id: &str) -> ExternResult<Option<AttestationRevocation>> {
+    let query = LinkQuery::try_new(
+        attestation_anchor_hash(attestation_id)?,
+        LinkTypes::AttestationToRevocation,
+    )?;
+    let links = get_links(query, GetStrategy::default())?;
+
+    for link in links {
+        if let Some(action_hash) = link.target.into_action_hash() {
+            if let Some(record) = get(action_hash, GetOptions::default())? {
+                if let Some(revocation) = record
+                    .entry()
+                    .to_app_option::<AttestationRevocation>()
+                    .ok()
+                    .flatten()
+                {
+                    return Ok(Some(revocation));
+                }
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Revoke an attestation. Only its issuer may revoke it.
+#[hdk_extern]
+pub fn revoke_attestation(input: RevokeAttestationInput) -> ExternResult<AttestationRevocation> {
+    let attestation = get_attestation_by_id(input.attestation_id.clone())?
+        .ok_or_else(|| {
+            wasm_error!(WasmErrorInner::Guest(format!(
+                "Attestation not found: {}",
+                input.attestation_id
+            )))
+        })?
+        .attestation;
+
+    let agent_key = agent_info()?.agent_initial_pubkey.to_string();
+    if attestation.issued_by != agent_key {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the issuer can revoke an attestation".to_string()
+        )));
+    }
+    if get_attestation_revocation(&input.attestation_id)?.is_some() {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Attestation {} is already revoked",
+            input.attestation_id
+        ))));
+    }
+
+    let revocation = AttestationRevocation {
+        attestation_id: input.attestation_id.clone(),
+        revoked_by: agent_key,
+        reason: input.reason,
+        revoked_at: format!("{:?}", sys_time()?),
+    };
+    let action_hash = create_entry(&EntryTypes::AttestationRevocation(revocation.clone()))?;
+    create_link(
+        attestation_anchor_hash(&input.attestation_id)?,
+        action_hash,
+        LinkTypes::AttestationToRevocation,
+        (),
+    )?;
+
+    Ok(revocation)
+}
+
+/// Look up an attestation for third-party verification: the attestation,
+/// its holder (named only when their profile is public) and any revocation
+#[hdk_extern]
+pub fn verify_attestation(id: String) -> ExternResult<Option<AttestationVerification>> {
+    get_attestation_by_id(id)?.map(attestation_verification).transpose()
+}
+
+/// Verification details for every attestation an agent holds, for exports
+#[hdk_extern]
+pub fn get_attestation_verifications(agent_id: String) -> ExternResult<Vec<AttestationVerification>> {
+    get_agent_attestations(agent_id)?
+        .into_iter()
+        .map(attestation_verification)
+        .collect()
+}
+
+fn attestation_verification(output: AttestationOutput) -> ExternResult<AttestationVerification> {
+    let holder = get_human_by_id(output.attestation.agent_id.clone())?.map(|h| AttestationHolder {
+        display_name: Some(h.human.display_name).filter(|_| h.human.profile_reach == "public"),
+        human_id: h.human.id,
+    });
+    let revocation = get_attestation_revocation(&output.attestation.id)?;
+
+    Ok(AttestationVerification {
+        action_hash: output.action_hash,
+        attestation: output.attestation,
+        holder,
+        revocation,
+    })
+}
+
 // =============================================================================
 // Agent Functions
 // =============================================================================
@@ -788,6 +950,20 @@ pub struct UpsertMasteryInput {
     pub content_id: String,
     pub mastery_level: String,
     pub engagement_type: String,
+    /// One AssessmentEvidence object as JSON, appended to the mastery's
+    /// assessment evidence
+    #[serde(default)]
+    pub evidence_json: Option<String>,
+}
+
+/// Append an evidence object to an AssessmentEvidence[] JSON array
+fn append_evidence(evidence_array_json: &str, evidence_json: Option<&str>) -> String {
+    let Some(evidence) = evidence_json.and_then(|e| serde_json::from_str::<serde_json::Value>(e).ok()) else {
+        return evidence_array_json.to_string();
+    };
+    let mut evidence_array: Vec<serde_json::Value> = serde_json::from_str(evidence_array_json).unwrap_or_default();
+    evidence_array.push(evidence);
+    serde_json::to_string(&evidence_array).unwrap_or_else(|_| evidence_array_json.to_string())
 }
 
 /// Helper to get mastery level index
@@ -843,6 +1019,8 @@ pub fn upsert_mastery(input: UpsertMasteryInput) -> ExternResult<ContentMasteryO
         existing.last_engagement_type = input.engagement_type;
         existing.last_engagement_at = timestamp.clone();
         existing.updated_at = timestamp;
+        existing.assessment_evidence_json =
+            append_evidence(&existing.assessment_evidence_json, input.evidence_json.as_deref());
 
         existing
     } else {
@@ -860,7 +1038,7 @@ pub fn upsert_mastery(input: UpsertMasteryInput) -> ExternResult<ContentMasteryO
             last_engagement_at: timestamp.clone(),
             level_achieved_at: timestamp.clone(),
             content_version_at_mastery: None,
-            assessment_evidence_json: "[]".to_string(),
+            assessment_evidence_json: append_evidence("[]", input.evidence_json.as_deref()),
             privileges_json: "[]".to_string(),
             created_at: timestamp.clone(),
             updated_at: timestamp,
@@ -914,9 +1092,51 @@ pub fn get_my_mastery(content_id: String) -> ExternResult<Option<ContentMasteryO
         content_id,
         mastery_level: String::new(),
         engagement_type: String::new(),
+        evidence_json: None,
+    })
+}
+
+/// Get my mastery for several content items in one call
+///
+/// Results line up with `content_ids`; `None` where no mastery exists yet.
+#[hdk_extern]
+pub fn get_my_mastery_batch(content_ids: Vec<String>) -> ExternResult<Vec<Option<ContentMasteryOutput>>> {
+    let my_human = get_my_human(())?
+        .ok_or_else(|| wasm_error!(WasmErrorInner::Guest("Must have Human profile".to_string())))?;
+
+    get_mastery_batch(GetMasteryBatchInput {
+        human_id: my_human.human.id,
+        content_ids,
     })
 }
 
+/// Input for fetching one human's mastery of several content items
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetMasteryBatchInput {
+    pub human_id: String,
+    pub content_ids: Vec<String>,
+}
+
+/// Get a human's mastery for several content items in one call
+///
+/// Results line up with `content_ids`; `None` where no mastery exists yet.
+#[hdk_extern]
+pub fn get_mastery_batch(input: GetMasteryBatchInput) -> ExternResult<Vec<Option<ContentMasteryOutput>>> {
+    input
+        .content_ids
+        .into_iter()
+        .map(|content_id| {
+            get_mastery(UpsertMasteryInput {
+                human_id: input.human_id.clone(),
+                content_id,
+                mastery_level: String::new(),
+                engagement_type: String::new(),
+                evidence_json: None,
+            })
+        })
+        .collect()
+}
+
 /// Get all mastery records for calling agent
 #[hdk_extern]
 pub fn get_my_all_mastery(_: ()) -> ExternResult<Vec<ContentMasteryOutput>> {
@@ -2285,6 +2505,212 @@ pub fn reaffirm_relationship_renewal(renewal_id: String) -> ExternResult<Relatio
     Ok(RelationshipRenewalOutput { action_hash, entry: renewal })
 }
 
+// =============================================================================
+// Elohim Registry
+// =============================================================================
+
+/// How long an elohim counts as live after its last heartbeat (microseconds)
+const ELOHIM_LIVENESS_WINDOW_MICROS: i64 = 5 * 60 * 1_000_000;
+
+/// Input for registering an elohim agent for a scope
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegisterElohimInput {
+    pub agent_id: String,
+    pub scope: String,
+    pub capabilities: Vec<String>,
+    pub endpoint: String,
+}
+
+/// Identifies one registration of an elohim agent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElohimScopeInput {
+    pub agent_id: String,
+    pub scope: String,
+}
+
+/// Registration with its liveness as of the call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElohimRegistrationOutput {
+    pub action_hash: ActionHash,
+    pub registration: ElohimRegistration,
+    pub last_heartbeat_micros: Option<i64>,
+    pub alive: bool,
+}
+
+/// Register an elohim agent for a scope, or update its capabilities and endpoint.
+///
+/// The caller must hold the key bound to the elohim's Agent profile.
+/// Registering counts as a heartbeat.
+#[hdk_extern]
+pub fn register_elohim(input: RegisterElohimInput) -> ExternResult<ElohimRegistrationOutput> {
+    let me = agent_info()?.agent_initial_pubkey;
+
+    let agent = get_agent_by_id(input.agent_id.clone())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Agent not found".to_string())))?
+        .agent;
+    if agent.agent_type != "elohim" {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only agents of type 'elohim' can register".to_string()
+        )));
+    }
+    if agent.holochain_agent_key.as_deref() != Some(me.to_string().as_str()) {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the elohim's own key can register it".to_string()
+        )));
+    }
+
+    let mut capabilities = input.capabilities;
+    capabilities.sort();
+    capabilities.dedup();
+
+    let timestamp = format!("{:?}", sys_time()?);
+    let registration_id = format!("{}:{}", input.agent_id, input.scope);
+    let id_anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("elohim_registration", &registration_id)))?;
+    let scope_anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("elohim_scope", &input.scope)))?;
+
+    let registration = ElohimRegistration {
+        id: registration_id.clone(),
+        agent_id: input.agent_id,
+        scope: input.scope,
+        capabilities,
+        endpoint: input.endpoint,
+        holochain_agent_key: me.to_string(),
+        active: true,
+        registered_at: timestamp.clone(),
+        updated_at: timestamp,
+    };
+
+    let action_hash = match get_elohim_registration(&registration_id)? {
+        Some((old_hash, existing)) => {
+            let registration = ElohimRegistration {
+                registered_at: existing.registered_at,
+                ..registration.clone()
+            };
+            let action_hash = update_entry(old_hash.clone(), &EntryTypes::ElohimRegistration(registration))?;
+
+            // Re-point the ID and scope links at the new version
+            for (base, link_type) in [
+                (id_anchor_hash.clone(), LinkTypes::IdToElohimRegistration),
+                (scope_anchor_hash.clone(), LinkTypes::ScopeToElohim),
+            ] {
+                for link in get_links(LinkQuery::try_new(base, link_type)?, GetStrategy::default())? {
+                    if link.target.clone().into_action_hash() == Some(old_hash.clone()) {
+                        delete_link(link.create_link_hash, GetOptions::default())?;
+                    }
+                }
+            }
+            action_hash
+        }
+        None => create_entry(&EntryTypes::ElohimRegistration(registration.clone()))?,
+    };
+    create_link(id_anchor_hash.clone(), action_hash.clone(), LinkTypes::IdToElohimRegistration, ())?;
+    create_link(scope_anchor_hash, action_hash, LinkTypes::ScopeToElohim, ())?;
+
+    elohim_heartbeat(ElohimScopeInput {
+        agent_id: registration.agent_id,
+        scope: registration.scope,
+    })
+}
+
+/// Heartbeat from a registered elohim, keeping it live for its scope
+#[hdk_extern]
+pub fn elohim_heartbeat(input: ElohimScopeInput) -> ExternResult<ElohimRegistrationOutput> {
+    let registration_id = format!("{}:{}", input.agent_id, input.scope);
+    let (action_hash, registration) = get_elohim_registration(&registration_id)?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Elohim registration not found".to_string())))?;
+
+    let me = agent_info()?.agent_initial_pubkey;
+    if registration.holochain_agent_key != me.to_string() {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the registered key can send heartbeats".to_string()
+        )));
+    }
+
+    let anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("elohim_registration", &registration_id)))?;
+    let query = LinkQuery::try_new(anchor_hash.clone(), LinkTypes::ElohimHeartbeat)?;
+    for link in get_links(query, GetStrategy::default())? {
+        if link.author == me {
+            delete_link(link.create_link_hash, GetOptions::default())?;
+        }
+    }
+    create_link(anchor_hash, me, LinkTypes::ElohimHeartbeat, ())?;
+
+    let now = sys_time()?.as_micros();
+    Ok(ElohimRegistrationOutput {
+        action_hash,
+        registration,
+        last_heartbeat_micros: Some(now),
+        alive: true,
+    })
+}
+
+/// Elohim agents registered for a scope, live ones first, most recent heartbeat first
+#[hdk_extern]
+pub fn get_elohim_by_scope(scope: String) -> ExternResult<Vec<ElohimRegistrationOutput>> {
+    let scope_anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("elohim_scope", &scope)))?;
+    let query = LinkQuery::try_new(scope_anchor_hash, LinkTypes::ScopeToElohim)?;
+    let now = sys_time()?.as_micros();
+
+    let mut results = Vec::new();
+    for link in get_links(query, GetStrategy::default())? {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(action_hash.clone(), GetOptions::default())? else {
+            continue;
+        };
+        let Some(registration) = record.entry().to_app_option::<ElohimRegistration>().ok().flatten() else {
+            continue;
+        };
+        if !registration.active {
+            continue;
+        }
+
+        let anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("elohim_registration", &registration.id)))?;
+        let last_heartbeat_micros = get_links(
+            LinkQuery::try_new(anchor_hash, LinkTypes::ElohimHeartbeat)?,
+            GetStrategy::default(),
+        )?
+        .into_iter()
+        .filter(|l| l.author.to_string() == registration.holochain_agent_key)
+        .map(|l| l.timestamp.as_micros())
+        .max();
+        let alive = last_heartbeat_micros.is_some_and(|t| now - t <= ELOHIM_LIVENESS_WINDOW_MICROS);
+
+        results.push(ElohimRegistrationOutput {
+            action_hash,
+            registration,
+            last_heartbeat_micros,
+            alive,
+        });
+    }
+
+    results.sort_by(|a, b| {
+        b.alive
+            .cmp(&a.alive)
+            .then(b.last_heartbeat_micros.cmp(&a.last_heartbeat_micros))
+    });
+    Ok(results)
+}
+
+/// Latest version of a registration by `{agent_id}:{scope}`
+fn get_elohim_registration(registration_id: &str) -> ExternResult<Option<(ActionHash, ElohimRegistration)>> {
+    let id_anchor_hash = hash_entry(&EntryTypes::StringAnchor(StringAnchor::new("elohim_registration", registration_id)))?;
+    let query = LinkQuery::try_new(id_anchor_hash, LinkTypes::IdToElohimRegistration)?;
+
+    for link in get_links(query, GetStrategy::default())? {
+        if let Some(action_hash) = link.target.into_action_hash() {
+            if let Some(record) = get(action_hash.clone(), GetOptions::default())? {
+                if let Some(registration) = record.entry().to_app_option::<ElohimRegistration>().ok().flatten() {
+                    return Ok(Some((action_hash, registration)));
+                }
+            }
+        }
+    }
+
+    Ok(None)
+}
+
 // =============================================================================
 // Init
 // =============================================================================
@@ -83,6 +83,17 @@ pub const ATTESTATION_CATEGORIES: [&str; 8] = [
 /// Attestation tiers (for tiered credentials)
 pub const ATTESTATION_TIERS: [&str; 4] = ["bronze", "silver", "gold", "platinum"];
 
+// =============================================================================
+// Elohim Registry Constants
+// =============================================================================
+
+/// Task kinds an elohim agent can take on for a doorway
+pub const ELOHIM_CAPABILITIES: [&str; 3] = [
+    "moderation_review", // Second look at flagged or reported content
+    "translation",       // Draft translations of content
+    "tutoring",          // Learner conversations
+];
+
 // =============================================================================
 // ContributorPresence Constants
 // =============================================================================
@@ -409,6 +420,19 @@ pub struct Attestation {
     pub proof: Option<String>, // Cryptographic signature
 }
 
+/// AttestationRevocation - Withdrawal of an issued attestation.
+///
+/// Attestations are never edited; revoking one records who withdrew it and
+/// why, so verifiers can report the revocation alongside the original.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct AttestationRevocation {
+    pub attestation_id: String,
+    pub revoked_by: String,
+    pub reason: String,
+    pub revoked_at: String,
+}
+
 // =============================================================================
 // Content Mastery Entry
 // =============================================================================
@@ -948,6 +972,26 @@ pub struct RelationshipRenewal {
     pub created_at: String,
 }
 
+/// ElohimRegistration - An elohim agent offering to serve a scope.
+///
+/// Scopes name where the agent acts (e.g. `global`, `community:{id}`,
+/// `path:{id}`). Doorways look registrations up by scope and forward tasks
+/// to `endpoint`, the NATS subject the agent listens on. Liveness comes from
+/// heartbeat links rather than entry updates, so heartbeats stay cheap.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct ElohimRegistration {
+    pub id: String,                          // {agent_id}:{scope}
+    pub agent_id: String,                    // Agent.id of the elohim (agent_type "elohim")
+    pub scope: String,
+    pub capabilities: Vec<String>,           // See ELOHIM_CAPABILITIES
+    pub endpoint: String,                    // NATS subject for dispatched tasks
+    pub holochain_agent_key: String,         // Key that registered and sends heartbeats
+    pub active: bool,                        // False once deregistered
+    pub registered_at: String,
+    pub updated_at: String,
+}
+
 // =============================================================================
 // Anchor Entry (for link indexing)
 // =============================================================================
@@ -982,6 +1026,7 @@ pub enum EntryTypes {
     AgentProgress(AgentProgress),
     HumanRelationship(HumanRelationship),
     Attestation(Attestation),
+    AttestationRevocation(AttestationRevocation),
     ContentMastery(ContentMastery),
     ContributorPresence(ContributorPresence),
     StringAnchor(StringAnchor),
@@ -1008,6 +1053,8 @@ pub enum EntryTypes {
     RenewalAttestation(RenewalAttestation),
     AgentRetirement(AgentRetirement),
     RelationshipRenewal(RelationshipRenewal),
+    // Elohim registry
+    ElohimRegistration(ElohimRegistration),
 }
 
 // =============================================================================
@@ -1042,6 +1089,8 @@ pub enum LinkTypes {
     AgentToAttestation,      // Anchor(agent_id) -> Attestation
     AttestationByCategory,   // Anchor(category) -> Attestation
     AttestationByType,       // Anchor(attestation_type) -> Attestation
+    IdToAttestation,         // Anchor(attestation_id) -> Attestation
+    AttestationToRevocation, // Anchor(attestation_id) -> AttestationRevocation
 
     // Content Mastery links
     HumanToMastery,          // Anchor(human_id) -> ContentMastery
@@ -1158,6 +1207,11 @@ pub enum LinkTypes {
     IdToRelationshipRenewal,         // Anchor(rel_renewal_id) -> RelationshipRenewal
     OriginalRelToRenewal,            // Anchor(original_relationship_id) -> RelationshipRenewal
     RenewalAttestationByStatus,      // Anchor(renewal_status) -> RenewalAttestation
+
+    // Elohim registry links
+    IdToElohimRegistration,          // Anchor(registration_id) -> ElohimRegistration
+    ScopeToElohim,                   // Anchor(scope) -> ElohimRegistration
+    ElohimHeartbeat,                 // Anchor(registration_id) -> AgentPubKey, link timestamp = heartbeat
 }
 
 // =============================================================================
@@ -1178,6 +1232,9 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 EntryTypes::Agent(agent) => validate_agent(&agent),
                 EntryTypes::HumanRelationship(rel) => validate_human_relationship(&rel, &action),
                 EntryTypes::Attestation(attestation) => validate_attestation(&attestation),
+                EntryTypes::AttestationRevocation(revocation) => {
+                    validate_attestation_revocation(&revocation)
+                }
                 EntryTypes::ContentMastery(mastery) => validate_content_mastery(&mastery),
                 EntryTypes::ContributorPresence(presence) => validate_contributor_presence(&presence),
                 EntryTypes::RecoveryRequest(request) => validate_recovery_request(&request),
@@ -1202,11 +1259,13 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 EntryTypes::RenewalAttestation(attestation) => validate_renewal_attestation(&attestation),
                 EntryTypes::AgentRetirement(retirement) => validate_agent_retirement(&retirement),
                 EntryTypes::RelationshipRenewal(renewal) => validate_relationship_renewal(&renewal),
+                EntryTypes::ElohimRegistration(registration) => validate_elohim_registration(&registration),
                 _ => Ok(ValidateCallbackResult::Valid),
             },
             OpEntry::UpdateEntry { app_entry, .. } => match app_entry {
                 EntryTypes::Human(human) => validate_human(&human),
                 EntryTypes::Agent(agent) => validate_agent(&agent),
+                EntryTypes::ElohimRegistration(registration) => validate_elohim_registration(&registration),
                 _ => Ok(ValidateCallbackResult::Valid),
             },
             _ => Ok(ValidateCallbackResult::Valid),
@@ -1346,6 +1405,25 @@ fn validate_attestation(attestation: &Attestation) -> ExternResult<ValidateCallb
     Ok(ValidateCallbackResult::Valid)
 }
 
+/// Validate AttestationRevocation entry
+fn validate_attestation_revocation(
+    revocation: &AttestationRevocation,
+) -> ExternResult<ValidateCallbackResult> {
+    if revocation.attestation_id.is_empty() || revocation.revoked_by.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "AttestationRevocation attestation_id and revoked_by cannot be empty".to_string(),
+        ));
+    }
+
+    if revocation.reason.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "A reason is required to revoke an attestation".to_string(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
 /// Validate ContentMastery entry
 fn validate_content_mastery(mastery: &ContentMastery) -> ExternResult<ValidateCallbackResult> {
     if mastery.id.is_empty() {
@@ -2071,3 +2149,47 @@ fn validate_relationship_renewal(renewal: &RelationshipRenewal) -> ExternResult<
 
     Ok(ValidateCallbackResult::Valid)
 }
+
+/// Validate ElohimRegistration entry
+fn validate_elohim_registration(registration: &ElohimRegistration) -> ExternResult<ValidateCallbackResult> {
+    if registration.agent_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "ElohimRegistration agent_id cannot be empty".to_string(),
+        ));
+    }
+
+    if registration.scope.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "ElohimRegistration scope cannot be empty".to_string(),
+        ));
+    }
+
+    if registration.id != format!("{}:{}", registration.agent_id, registration.scope) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "ElohimRegistration ID must be {agent_id}:{scope}".to_string(),
+        ));
+    }
+
+    if registration.endpoint.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "ElohimRegistration endpoint cannot be empty".to_string(),
+        ));
+    }
+
+    if registration.capabilities.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "ElohimRegistration needs at least one capability".to_string(),
+        ));
+    }
+
+    for capability in &registration.capabilities {
+        if !ELOHIM_CAPABILITIES.contains(&capability.as_str()) {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "Invalid capability '{}'. Must be one of: {:?}",
+                capability, ELOHIM_CAPABILITIES
+            )));
+        }
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
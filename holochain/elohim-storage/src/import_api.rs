@@ -82,6 +82,10 @@ pub struct QueueImportRequest {
     /// Higher delay = more conductor breathing room, slower overall
     #[serde(default)]
     pub chunk_delay_ms: Option<u64>,
+    /// Upstream signature over the manifest, verified by doorway.
+    /// Passed through to the zome, which stores it next to the batch.
+    #[serde(default)]
+    pub provenance: Option<ImportProvenance>,
 }
 
 fn default_schema_version() -> u32 { 1 }
@@ -199,6 +203,27 @@ struct ZomeQueueImportInput {
     pub total_items: u32,
     /// Schema version for the items
     pub schema_version: u32,
+    /// Signed upstream manifest, if the import was signed
+    pub provenance: Option<ImportProvenance>,
+}
+
+/// Signed upstream manifest of an import batch
+/// Must match content_store::ImportProvenanceInput exactly
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportProvenance {
+    /// Upstream system that signed the manifest
+    pub source: String,
+    /// Base64 Ed25519 key the signature was checked against
+    pub public_key: String,
+    /// Base64 Ed25519 signature
+    pub signature: String,
+    /// Blob hash as signed
+    pub blob_hash: String,
+    /// Item count as signed
+    pub total_items: u32,
+    /// Doorway that verified the signature
+    #[serde(default)]
+    pub verified_by: Option<String>,
 }
 
 /// Input for process_import_chunk zome call
@@ -626,6 +651,7 @@ impl ImportApi {
         let batch_options = BatchOptions {
             chunk_size: request.chunk_size,
             chunk_delay_ms: request.chunk_delay_ms,
+            provenance: request.provenance,
         };
         let processing_future = async move {
             if let Err(e) = api_self.process_batch(&batch_id_clone, &batch_type, &items_json, batch_options).await {
@@ -923,6 +949,8 @@ struct BatchOptions {
     chunk_size: Option<usize>,
     /// Override chunk delay for this batch
     chunk_delay_ms: Option<u64>,
+    /// Signed upstream manifest to record with the batch
+    provenance: Option<ImportProvenance>,
 }
 
 impl ImportApiProcessor {
@@ -998,6 +1026,7 @@ impl ImportApiProcessor {
             blob_hash: format!("inline-{}", batch_id), // No blob for inline imports
             total_items: total as u32,
             schema_version: 1, // Current schema version
+            provenance: options.provenance.clone(),
         };
         // CRITICAL: Use to_vec_named to serialize as a map with field names
         // to_vec serializes structs as arrays (positional), but zomes expect maps (named fields)
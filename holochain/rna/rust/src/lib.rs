@@ -46,6 +46,7 @@
 //! - [`healing`] - Core self-healing types: ValidationStatus, HealingSignal, HealingReport
 //! - [`self_healing`] - SelfHealingEntry trait: implement for any entry type
 //! - [`healing_orchestrator`] - Background healing orchestrator: manages healing workflow
+//! - [`schema_migration`] - Declarative field mappings and dual-read shims for the next schema version
 //!
 //! ## Two Patterns Supported
 //!
@@ -70,6 +71,7 @@ pub mod healing_orchestrator;
 pub mod entry_type_provider;
 pub mod healing_strategy;
 pub mod flexible_orchestrator;
+pub mod schema_migration;
 pub mod analyzer;
 pub mod generator;
 pub mod schema_export;
@@ -103,6 +105,7 @@ pub use healing_strategy::{
     TranscriptionProvider,
 };
 pub use flexible_orchestrator::{OrchestratorConfig as FlexibleOrchestratorConfig, FlexibleOrchestrator, HealingOutcome};
+pub use schema_migration::{FieldMapping, MigrationSpec, DualReadValidator};
 
 // Re-export schema analysis and generation
 pub use analyzer::{DNAAnalyzer, EntryTypeSchema, FieldType, Field};
@@ -0,0 +1,361 @@
+//! Declarative Schema Migrations
+//!
+//! Authoring support for the next schema version. Instead of hand-writing a
+//! [`Transcriber`] per entry type, a migration is declared as a list of
+//! [`FieldMapping`]s in a [`MigrationSpec`]. From that one declaration the
+//! spec provides:
+//!
+//! - the healing transcriber (`transcribe_from_prev`), and `transcribe_to_prev`
+//!   when every mapping can be undone
+//! - a dual-read shim, [`DualReadValidator`], so a DNA can accept entries at
+//!   either version while the DHT heals
+//! - a serializable plan, for reporting what a migration will change
+//!
+//! ```rust,ignore
+//! use hc_rna::{FieldMapping, MigrationSpec};
+//!
+//! let spec = MigrationSpec::new("content", 2, 3, "Split reach into audience")
+//!     .map(FieldMapping::rename("reach", "audience"))
+//!     .map(FieldMapping::default_value("audience_note", serde_json::json!(null)));
+//!
+//! let v3 = spec.upgrade(&v2_json)?;
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::entry_type_provider::{Transcriber, Validator};
+
+/// One field-level change between two schema versions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum FieldMapping {
+    /// Move a field to a new name
+    Rename { from: String, to: String },
+
+    /// Add a field, filling it when missing or null
+    Default { field: String, value: Value },
+
+    /// Drop a field that is no longer part of the schema
+    Remove { field: String },
+
+    /// Replace string values of a field (unlisted values are kept)
+    MapValues {
+        field: String,
+        values: Map<String, Value>,
+    },
+}
+
+impl FieldMapping {
+    pub fn rename(from: &str, to: &str) -> Self {
+        FieldMapping::Rename {
+            from: from.to_string(),
+            to: to.to_string(),
+        }
+    }
+
+    pub fn default_value(field: &str, value: Value) -> Self {
+        FieldMapping::Default {
+            field: field.to_string(),
+            value,
+        }
+    }
+
+    pub fn remove(field: &str) -> Self {
+        FieldMapping::Remove {
+            field: field.to_string(),
+        }
+    }
+
+    pub fn map_values(field: &str, values: &[(&str, &str)]) -> Self {
+        FieldMapping::MapValues {
+            field: field.to_string(),
+            values: values
+                .iter()
+                .map(|(from, to)| (from.to_string(), Value::String(to.to_string())))
+                .collect(),
+        }
+    }
+
+    /// Apply this mapping to an entry's fields
+    pub fn apply(&self, fields: &mut Map<String, Value>) {
+        match self {
+            FieldMapping::Rename { from, to } => {
+                if let Some(value) = fields.remove(from) {
+                    fields.insert(to.clone(), value);
+                }
+            }
+            FieldMapping::Default { field, value } => {
+                let slot = fields.entry(field.clone()).or_insert(Value::Null);
+                if slot.is_null() {
+                    *slot = value.clone();
+                }
+            }
+            FieldMapping::Remove { field } => {
+                fields.remove(field);
+            }
+            FieldMapping::MapValues { field, values } => {
+                let mapped = fields
+                    .get(field)
+                    .and_then(|v| v.as_str())
+                    .and_then(|v| values.get(v))
+                    .cloned();
+                if let Some(mapped) = mapped {
+                    fields.insert(field.clone(), mapped);
+                }
+            }
+        }
+    }
+
+    /// The mapping that undoes this one, if the change loses no data
+    pub fn reverse(&self) -> Option<FieldMapping> {
+        match self {
+            FieldMapping::Rename { from, to } => Some(FieldMapping::Rename {
+                from: to.clone(),
+                to: from.clone(),
+            }),
+            FieldMapping::Default { field, .. } => Some(FieldMapping::Remove {
+                field: field.clone(),
+            }),
+            FieldMapping::Remove { .. } => None,
+            FieldMapping::MapValues { field, values } => {
+                let mut reversed = Map::new();
+                for (from, to) in values {
+                    let to = to.as_str()?;
+                    if reversed.insert(to.to_string(), Value::String(from.clone())).is_some() {
+                        return None;
+                    }
+                }
+                Some(FieldMapping::MapValues {
+                    field: field.clone(),
+                    values: reversed,
+                })
+            }
+        }
+    }
+}
+
+/// A declared migration of one entry type between two schema versions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MigrationSpec {
+    pub entry_type: String,
+    pub from_version: u32,
+    pub to_version: u32,
+    pub description: String,
+    /// Applied in order on upgrade, reversed on downgrade
+    pub mappings: Vec<FieldMapping>,
+}
+
+impl MigrationSpec {
+    pub fn new(entry_type: &str, from_version: u32, to_version: u32, description: &str) -> Self {
+        Self {
+            entry_type: entry_type.to_string(),
+            from_version,
+            to_version,
+            description: description.to_string(),
+            mappings: Vec::new(),
+        }
+    }
+
+    /// Add a field mapping
+    pub fn map(mut self, mapping: FieldMapping) -> Self {
+        self.mappings.push(mapping);
+        self
+    }
+
+    /// Whether entries can be written back at `from_version`
+    pub fn is_reversible(&self) -> bool {
+        self.mappings.iter().all(|m| m.reverse().is_some())
+    }
+
+    /// Transcribe an entry at `from_version` to `to_version`
+    pub fn upgrade(&self, data: &Value) -> Result<Value, String> {
+        let mut fields = self.fields_at(data, self.from_version)?;
+        for mapping in &self.mappings {
+            mapping.apply(&mut fields);
+        }
+        fields.insert("schema_version".to_string(), Value::from(self.to_version));
+        fields.insert("validation_status".to_string(), Value::from("Migrated"));
+        Ok(Value::Object(fields))
+    }
+
+    /// Transcribe an entry at `to_version` back to `from_version`
+    pub fn downgrade(&self, data: &Value) -> Result<Value, String> {
+        let mut fields = self.fields_at(data, self.to_version)?;
+        for mapping in self.mappings.iter().rev() {
+            let reverse = mapping.reverse().ok_or_else(|| {
+                format!(
+                    "{} v{} -> v{} cannot be reversed: {:?} loses data",
+                    self.entry_type, self.from_version, self.to_version, mapping
+                )
+            })?;
+            reverse.apply(&mut fields);
+        }
+        fields.insert("schema_version".to_string(), Value::from(self.from_version));
+        Ok(Value::Object(fields))
+    }
+
+    /// Dual-read: an entry at either version, as seen by `to_version` code
+    pub fn read_current(&self, data: &Value) -> Result<Value, String> {
+        match data["schema_version"].as_u64() {
+            Some(v) if v == self.to_version as u64 => Ok(data.clone()),
+            Some(v) if v == self.from_version as u64 => self.upgrade(data),
+            other => Err(format!(
+                "{} entry at schema_version {:?}, expected {} or {}",
+                self.entry_type, other, self.from_version, self.to_version
+            )),
+        }
+    }
+
+    fn fields_at(&self, data: &Value, version: u32) -> Result<Map<String, Value>, String> {
+        let fields = data
+            .as_object()
+            .ok_or_else(|| format!("{} entry must be a JSON object", self.entry_type))?;
+        let found = fields.get("schema_version").and_then(|v| v.as_u64());
+        if found != Some(version as u64) {
+            return Err(format!(
+                "Expected {} schema_version {}, got {:?}",
+                self.entry_type, version, found
+            ));
+        }
+        Ok(fields.clone())
+    }
+}
+
+impl Transcriber for MigrationSpec {
+    fn transcribe_from_prev(&self, prev_data: &Value) -> Result<Value, String> {
+        self.upgrade(prev_data)
+    }
+
+    fn transcribe_to_prev(&self, self_data: &Value) -> Result<Value, String> {
+        self.downgrade(self_data)
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+}
+
+/// Validates entries at either side of a migration against the newer schema
+///
+/// Entries still at `from_version` are lifted in memory before validation, so
+/// readers can switch to the new schema before the DHT has healed.
+pub struct DualReadValidator<V: Validator> {
+    spec: MigrationSpec,
+    current: V,
+}
+
+impl<V: Validator> DualReadValidator<V> {
+    pub fn new(spec: MigrationSpec, current: V) -> Self {
+        Self { spec, current }
+    }
+
+    /// Read an entry at either version as a valid `to_version` entry
+    pub fn read(&self, data: &Value) -> Result<Value, String> {
+        let current = self.spec.read_current(data)?;
+        self.current.validate_json(&current)?;
+        Ok(current)
+    }
+}
+
+impl<V: Validator> Validator for DualReadValidator<V> {
+    fn validate_json(&self, data: &Value) -> Result<(), String> {
+        self.read(data).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn spec() -> MigrationSpec {
+        MigrationSpec::new("content", 2, 3, "Content v2 -> v3")
+            .map(FieldMapping::rename("reach", "audience"))
+            .map(FieldMapping::default_value("license", json!("CC-BY-4.0")))
+            .map(FieldMapping::map_values("content_type", &[("practice", "exercise")]))
+    }
+
+    fn v2() -> Value {
+        json!({
+            "id": "content-1",
+            "content_type": "practice",
+            "reach": "commons",
+            "schema_version": 2,
+            "validation_status": "Valid"
+        })
+    }
+
+    struct RequiresAudience;
+
+    impl Validator for RequiresAudience {
+        fn validate_json(&self, data: &Value) -> Result<(), String> {
+            data["audience"].as_str().map(|_| ()).ok_or("audience required".to_string())
+        }
+    }
+
+    #[test]
+    fn test_upgrade_applies_mappings() {
+        let v3 = spec().upgrade(&v2()).unwrap();
+        assert_eq!(v3["audience"], "commons");
+        assert!(v3.get("reach").is_none());
+        assert_eq!(v3["license"], "CC-BY-4.0");
+        assert_eq!(v3["content_type"], "exercise");
+        assert_eq!(v3["schema_version"], 3);
+        assert_eq!(v3["validation_status"], "Migrated");
+    }
+
+    #[test]
+    fn test_downgrade_round_trips() {
+        let spec = spec();
+        assert!(spec.is_reversible());
+        let v2_again = spec.downgrade(&spec.upgrade(&v2()).unwrap()).unwrap();
+        assert_eq!(v2_again["reach"], "commons");
+        assert_eq!(v2_again["content_type"], "practice");
+        assert!(v2_again.get("license").is_none());
+        assert_eq!(v2_again["schema_version"], 2);
+
+        let lossy = spec.map(FieldMapping::remove("legacy_notes"));
+        assert!(!lossy.is_reversible());
+        assert!(lossy.downgrade(&lossy.upgrade(&v2()).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_wrong_version_rejected() {
+        let mut v1 = v2();
+        v1["schema_version"] = json!(1);
+        assert!(spec().upgrade(&v1).is_err());
+        assert!(spec().read_current(&v1).is_err());
+        assert!(spec().downgrade(&v2()).is_err());
+    }
+
+    #[test]
+    fn test_dual_read_accepts_both_versions() {
+        let spec = spec();
+        let v3 = spec.upgrade(&v2()).unwrap();
+        let dual = DualReadValidator::new(spec, RequiresAudience);
+        assert!(dual.validate_json(&v2()).is_ok());
+        assert!(dual.validate_json(&v3).is_ok());
+        assert_eq!(dual.read(&v3).unwrap(), v3);
+
+        let mut missing = v3.clone();
+        missing.as_object_mut().unwrap().remove("audience");
+        assert!(dual.validate_json(&missing).is_err());
+    }
+
+    #[test]
+    fn test_spec_serializes_as_plan() {
+        let plan = serde_json::to_value(spec()).unwrap();
+        assert_eq!(plan["mappings"][0]["op"], "rename");
+        assert_eq!(plan["mappings"][1]["op"], "default");
+        let parsed: MigrationSpec = serde_json::from_value(plan).unwrap();
+        assert_eq!(parsed, spec());
+    }
+
+    #[test]
+    fn test_many_to_one_value_map_is_not_reversible() {
+        let mapping =
+            FieldMapping::map_values("reach", &[("self", "private"), ("private", "private")]);
+        assert!(mapping.reverse().is_none());
+    }
+}
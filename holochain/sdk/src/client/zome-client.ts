@@ -29,6 +29,8 @@ import {
   type ChapterWithSteps,
   type PathWithChaptersAndSteps,
   type UpdateChapterInput,
+  type DeleteChapterOutput,
+  type ExternalResourceOutput,
   // Progress tracking types
   type StartPathProgressInput,
   type CompleteStepInput,
@@ -60,6 +62,10 @@ import {
   type UpdateAgentStateInput,
   type CreateAgentProgressInput,
   type AgentProgressOutput,
+  type ReflectionOutput,
+  type CreateReflectionInput,
+  type ReflectionGrantOutput,
+  type ReflectionGrantInput,
   type UpdateAgentProgressInput,
   type UpsertMasteryInput,
   type ContentMasteryOutput,
@@ -84,6 +90,8 @@ import {
   type StartChallengeInput,
   type SubmitChallengeInput,
   type ChallengeResult,
+  type StartCheckpointInput,
+  type CheckpointResult,
   type CooldownCheckResult,
   type PoolRecommendations,
   type CreateAttestationInput,
@@ -254,10 +262,27 @@ export class ZomeClient {
     );
   }
 
-  async deletePath(pathId: string): Promise<boolean> {
+  /** Permanently unlink a path; prefer archivePath, which can be undone */
+  async deletePath(pathId: string, force = false): Promise<boolean> {
     return this.connection.callZome<boolean>(
       this.zomeName,
       'delete_path',
+      { path_id: pathId, force }
+    );
+  }
+
+  async archivePath(pathId: string): Promise<boolean> {
+    return this.connection.callZome<boolean>(
+      this.zomeName,
+      'archive_path',
+      pathId
+    );
+  }
+
+  async restorePath(pathId: string): Promise<boolean> {
+    return this.connection.callZome<boolean>(
+      this.zomeName,
+      'restore_path',
       pathId
     );
   }
@@ -330,6 +355,25 @@ export class ZomeClient {
     );
   }
 
+  async deleteChapter(
+    chapterId: string,
+    reassignTo: string | null = null
+  ): Promise<DeleteChapterOutput> {
+    return this.connection.callZome<DeleteChapterOutput>(
+      this.zomeName,
+      'delete_chapter',
+      { chapter_id: chapterId, reassign_to: reassignTo }
+    );
+  }
+
+  async getExternalResource(url: string): Promise<ExternalResourceOutput | null> {
+    return this.connection.callZome<ExternalResourceOutput | null>(
+      this.zomeName,
+      'get_external_resource',
+      url
+    );
+  }
+
   // ==========================================================================
   // Progress Tracking Operations
   // ==========================================================================
@@ -390,6 +434,39 @@ export class ZomeClient {
     );
   }
 
+  // ==========================================================================
+  // Reflection Journal
+  // ==========================================================================
+
+  async createReflection(input: CreateReflectionInput): Promise<ReflectionOutput> {
+    return this.connection.callZome<ReflectionOutput>(this.zomeName, 'create_reflection', input);
+  }
+
+  async getMyReflections(pathId: string): Promise<ReflectionOutput[]> {
+    return this.connection.callZome<ReflectionOutput[]>(
+      this.zomeName,
+      'get_my_reflections',
+      pathId
+    );
+  }
+
+  /** Let a facilitator read this learner's reflections on a path */
+  async grantReflectionAccess(input: ReflectionGrantInput): Promise<ReflectionGrantOutput> {
+    return this.connection.callZome<ReflectionGrantOutput>(
+      this.zomeName,
+      'grant_reflection_access',
+      input
+    );
+  }
+
+  async revokeReflectionAccess(input: ReflectionGrantInput): Promise<ReflectionGrantOutput | null> {
+    return this.connection.callZome<ReflectionGrantOutput | null>(
+      this.zomeName,
+      'revoke_reflection_access',
+      input
+    );
+  }
+
   // ==========================================================================
   // Attestation Operations
   // ==========================================================================
@@ -864,6 +941,24 @@ export class ZomeClient {
     );
   }
 
+  /** Start a checkpoint review built from the chapter's content so far */
+  async startCheckpoint(input: StartCheckpointInput): Promise<MasteryChallengeOutput> {
+    return this.connection.callZome<MasteryChallengeOutput>(
+      this.zomeName,
+      'start_checkpoint',
+      input
+    );
+  }
+
+  /** Submit checkpoint answers; a pass unlocks completing the checkpoint step */
+  async submitCheckpoint(input: SubmitChallengeInput): Promise<CheckpointResult> {
+    return this.connection.callZome<CheckpointResult>(
+      this.zomeName,
+      'submit_checkpoint',
+      input
+    );
+  }
+
   /** Get challenge history for current agent */
   async getChallengeHistory(): Promise<MasteryChallengeOutput[]> {
     return this.connection.callZome<MasteryChallengeOutput[]>(
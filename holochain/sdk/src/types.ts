@@ -213,6 +213,28 @@ export interface AddPathStepInput {
 export interface PathStepOutput {
   action_hash: ActionHash;
   step: PathStep;
+  external?: ExternalResource;         // External steps, with include_content
+}
+
+/** Metadata and snapshot for an external step's URL */
+export interface ExternalResource {
+  url: string;
+  provider: string | null;
+  title: string | null;
+  description: string | null;
+  thumbnail_url: string | null;
+  oembed_json: string | null;          // oEmbed response as JSON
+  snapshot_hash: string | null;        // Archived page, served at /store/{hash}
+  status: string;                      // pending, enriched, failed
+  fetched_at: string | null;
+  created_at: string;
+  updated_at: string;
+}
+
+/** Output for an external resource */
+export interface ExternalResourceOutput {
+  action_hash: ActionHash;
+  resource: ExternalResource;
 }
 
 /** Output for learning path with steps */
@@ -282,6 +304,20 @@ export interface UpdateChapterInput {
   mastery_threshold?: number;
 }
 
+/** Input for deleting a chapter */
+export interface DeleteChapterInput {
+  chapter_id: string;
+  /** Chapter in the same path to receive the steps; null ungroups them */
+  reassign_to: string | null;
+}
+
+/** Output for a deleted chapter */
+export interface DeleteChapterOutput {
+  chapter_id: string;
+  reassigned_to: string | null;
+  moved_steps: PathStepOutput[];
+}
+
 /** Input for updating a step */
 export interface UpdateStepInput {
   step_id: string;
@@ -709,6 +745,57 @@ export interface AgentProgressOutput {
   progress: AgentProgress;
 }
 
+/** Learner journal response to a path step */
+export interface ReflectionEntry {
+  id: string;
+  learner_id: string;
+  path_id: string;
+  step_index: number;
+  step_id: string | null;
+  prompt: string | null;
+  response: string;
+  created_at: string;
+  updated_at: string;
+}
+
+/** Output for a reflection */
+export interface ReflectionOutput {
+  action_hash: ActionHash;
+  reflection: ReflectionEntry;
+}
+
+/** Input for journaling a reflection on a step */
+export interface CreateReflectionInput {
+  human_id?: string | null;
+  path_id: string;
+  step_index: number;
+  prompt?: string | null;
+  response: string;
+}
+
+/** A learner's grant letting a facilitator read their reflections on a path */
+export interface ReflectionShareGrant {
+  id: string;
+  learner_id: string;
+  facilitator_id: string;
+  path_id: string;
+  granted_at: string;
+  revoked_at: string | null;
+}
+
+/** Output for a reflection share grant */
+export interface ReflectionGrantOutput {
+  action_hash: ActionHash;
+  grant: ReflectionShareGrant;
+}
+
+/** Input for granting or revoking a facilitator's access to reflections */
+export interface ReflectionGrantInput {
+  human_id?: string | null;
+  facilitator_id: string;
+  path_id: string;
+}
+
 /** Input for updating agent progress */
 export interface UpdateAgentProgressInput {
   agent_id: string;
@@ -1131,6 +1218,22 @@ export interface ChallengeResult {
   can_retake_at: string;
 }
 
+/** Input for starting a checkpoint review */
+export interface StartCheckpointInput {
+  path_id: string;
+  /** order_index of the checkpoint step */
+  step_index: number;
+}
+
+/** Checkpoint result after submission */
+export interface CheckpointResult {
+  challenge: MasteryChallengeOutput;
+  step_id: string;
+  score: number;
+  passing_score: number;
+  passed: boolean;
+}
+
 /** Cooldown check result */
 export interface CooldownCheckResult {
   can_take_challenge: boolean;